@@ -1,5 +1,7 @@
 //! Support for runtime configuration of the execution engine - as an integral property of the
 //! `EngineState` instance.
+use std::time::Duration;
+
 use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
 
 /// Default value for a maximum query depth configuration option.
@@ -12,6 +14,16 @@ pub const DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT: u32 = 12;
 pub const DEFAULT_MINIMUM_DELEGATION_AMOUNT: u64 = 500 * 1_000_000_000;
 /// Default value for strict argument checking.
 pub const DEFAULT_STRICT_ARGUMENT_CHECKING: bool = false;
+/// Default value for allowing accounts to designate authorization contracts.
+pub const DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS: bool = false;
+/// Default value for the maximum amount of gas an authorization contract can use.
+pub const DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT: u64 = 10_000_000;
+/// Default value for the maximum number of matured unbonding purses paid out per era-end step.
+pub const DEFAULT_MAX_UNBONDINGS_PER_STEP: u32 = 1_000;
+/// Default value for the maximum wall-clock duration of the Wasm execution of a deploy.
+pub const DEFAULT_MAX_EXECUTION_DURATION: Option<Duration> = None;
+/// Default value for the maximum amount of gas a call to a view entry point can use.
+pub const DEFAULT_MAX_VIEW_GAS: u64 = 1_000_000_000;
 /// 91 days / 7 days in a week = 13 weeks
 /// Length of total vesting schedule in days.
 const VESTING_SCHEDULE_LENGTH_DAYS: usize = 91;
@@ -35,6 +47,21 @@ pub struct EngineConfig {
     strict_argument_checking: bool,
     /// Vesting schedule period in milliseconds.
     vesting_schedule_period_millis: u64,
+    /// This flag indicates if accounts can delegate the authorization of their deploys to a
+    /// contract.
+    allow_authorization_contracts: bool,
+    /// Maximum amount of gas an authorization contract can use.  Authorization contracts are
+    /// called before payment code is executed, so their execution is not paid for.
+    authorization_contract_gas_limit: u64,
+    /// Maximum number of matured unbonding purses paid out in a single era-end step.  Any further
+    /// matured purses are carried over to the following steps.
+    max_unbondings_per_step: u32,
+    /// Maximum wall-clock duration of the Wasm execution of a single speculatively executed deploy
+    /// or view call, regardless of the gas it has left.  Unlimited if `None`.
+    max_execution_duration: Option<Duration>,
+    /// Maximum amount of gas a call to a view entry point can use.  Views are called without a
+    /// deploy, so their execution is not paid for.
+    max_view_gas: u64,
     wasm_config: WasmConfig,
     system_config: SystemConfig,
 }
@@ -48,6 +75,11 @@ impl Default for EngineConfig {
             minimum_delegation_amount: DEFAULT_MINIMUM_DELEGATION_AMOUNT,
             strict_argument_checking: DEFAULT_STRICT_ARGUMENT_CHECKING,
             vesting_schedule_period_millis: DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            allow_authorization_contracts: DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+            authorization_contract_gas_limit: DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            max_unbondings_per_step: DEFAULT_MAX_UNBONDINGS_PER_STEP,
+            max_execution_duration: DEFAULT_MAX_EXECUTION_DURATION,
+            max_view_gas: DEFAULT_MAX_VIEW_GAS,
             wasm_config: WasmConfig::default(),
             system_config: SystemConfig::default(),
         }
@@ -64,6 +96,9 @@ impl EngineConfig {
         minimum_delegation_amount: u64,
         strict_argument_checking: bool,
         vesting_schedule_period_millis: u64,
+        allow_authorization_contracts: bool,
+        authorization_contract_gas_limit: u64,
+        max_unbondings_per_step: u32,
         wasm_config: WasmConfig,
         system_config: SystemConfig,
     ) -> EngineConfig {
@@ -74,11 +109,41 @@ impl EngineConfig {
             minimum_delegation_amount,
             strict_argument_checking,
             vesting_schedule_period_millis,
+            allow_authorization_contracts,
+            authorization_contract_gas_limit,
+            max_unbondings_per_step,
+            max_execution_duration: DEFAULT_MAX_EXECUTION_DURATION,
+            max_view_gas: DEFAULT_MAX_VIEW_GAS,
             wasm_config,
             system_config,
         }
     }
 
+    /// Returns a copy of this config using the given maximum wall-clock duration of the Wasm
+    /// execution of a deploy.
+    ///
+    /// Whether a deploy exceeds the limit depends on the hardware and load of the node executing
+    /// it, so the limit only applies to speculative executions (see
+    /// [`ExecuteRequest::speculative`](super::ExecuteRequest::speculative)) and view calls, never
+    /// to deploys executed as part of a block.
+    pub fn with_max_execution_duration(
+        self,
+        max_execution_duration: Option<Duration>,
+    ) -> EngineConfig {
+        EngineConfig {
+            max_execution_duration,
+            ..self
+        }
+    }
+
+    /// Returns a copy of this config using the given maximum amount of gas a view call can use.
+    pub fn with_max_view_gas(self, max_view_gas: u64) -> EngineConfig {
+        EngineConfig {
+            max_view_gas,
+            ..self
+        }
+    }
+
     /// Returns the current max associated keys config.
     pub fn max_associated_keys(&self) -> u32 {
         self.max_associated_keys
@@ -94,6 +159,14 @@ impl EngineConfig {
         &self.wasm_config
     }
 
+    /// Returns a copy of this config using the given wasm config.
+    pub(crate) fn with_wasm_config(self, wasm_config: WasmConfig) -> EngineConfig {
+        EngineConfig {
+            wasm_config,
+            ..self
+        }
+    }
+
     /// Returns the current system config.
     pub fn system_config(&self) -> &SystemConfig {
         &self.system_config
@@ -113,4 +186,29 @@ impl EngineConfig {
     pub fn vesting_schedule_period_millis(&self) -> u64 {
         self.vesting_schedule_period_millis
     }
+
+    /// Get the engine config's flag allowing authorization contracts.
+    pub fn allow_authorization_contracts(&self) -> bool {
+        self.allow_authorization_contracts
+    }
+
+    /// Get the maximum amount of gas an authorization contract can use.
+    pub fn authorization_contract_gas_limit(&self) -> u64 {
+        self.authorization_contract_gas_limit
+    }
+
+    /// Get the maximum number of matured unbonding purses paid out per era-end step.
+    pub fn max_unbondings_per_step(&self) -> u32 {
+        self.max_unbondings_per_step
+    }
+
+    /// Get the maximum wall-clock duration of the Wasm execution of a deploy.
+    pub fn max_execution_duration(&self) -> Option<Duration> {
+        self.max_execution_duration
+    }
+
+    /// Get the maximum amount of gas a call to a view entry point can use.
+    pub fn max_view_gas(&self) -> u64 {
+        self.max_view_gas
+    }
 }
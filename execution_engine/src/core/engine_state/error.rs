@@ -7,7 +7,9 @@ use casper_types::{bytesrepr, system::mint, ApiError, ProtocolVersion};
 
 use crate::{
     core::{
-        engine_state::{genesis::GenesisError, upgrade::ProtocolUpgradeError},
+        engine_state::{
+            genesis::GenesisError, migration::MigrationError, upgrade::ProtocolUpgradeError,
+        },
         execution,
         runtime::stack,
     },
@@ -68,6 +70,9 @@ pub enum Error {
     /// Protocol upgrade error.
     #[error("Protocol upgrade error: {0}")]
     ProtocolUpgrade(ProtocolUpgradeError),
+    /// Global state migration error.
+    #[error("Migration error: {0}")]
+    Migration(MigrationError),
     /// Invalid deploy item variant.
     #[error("Unsupported deploy item variant: {0}")]
     InvalidDeployItemVariant(String),
@@ -2,7 +2,7 @@
 use std::mem;
 
 use casper_hashing::Digest;
-use casper_types::{ProtocolVersion, PublicKey, SecretKey};
+use casper_types::{BlockContext, BlockTime, EraId, ProtocolVersion, PublicKey, SecretKey};
 
 use super::deploy_item::DeployItem;
 
@@ -13,12 +13,19 @@ pub struct ExecuteRequest {
     pub parent_state_hash: Digest,
     /// Block time represented as a unix timestamp.
     pub block_time: u64,
+    /// Height of the block containing the deploys.
+    pub block_height: u64,
+    /// Era of the block containing the deploys.
+    pub era_id: EraId,
     /// List of deploys that will be executed as part of this request.
     pub deploys: Vec<DeployItem>,
     /// Protocol version used to execute deploys from the list.
     pub protocol_version: ProtocolVersion,
     /// The owner of the node that proposed the block containing this request.
     pub proposer: PublicKey,
+    /// Whether the deploys are executed speculatively, i.e. outside of a block.  Only speculative
+    /// executions are subject to the maximum execution duration of the engine config.
+    pub speculative: bool,
 }
 
 impl ExecuteRequest {
@@ -26,6 +33,8 @@ impl ExecuteRequest {
     pub fn new(
         parent_state_hash: Digest,
         block_time: u64,
+        block_height: u64,
+        era_id: EraId,
         deploys: Vec<DeployItem>,
         protocol_version: ProtocolVersion,
         proposer: PublicKey,
@@ -33,9 +42,20 @@ impl ExecuteRequest {
         Self {
             parent_state_hash,
             block_time,
+            block_height,
+            era_id,
             deploys,
             protocol_version,
             proposer,
+            speculative: false,
+        }
+    }
+
+    /// Returns this request marked as a speculative execution, i.e. one outside of a block.
+    pub fn into_speculative(self) -> Self {
+        Self {
+            speculative: true,
+            ..self
         }
     }
 
@@ -48,6 +68,16 @@ impl ExecuteRequest {
     pub fn deploys(&self) -> &Vec<DeployItem> {
         &self.deploys
     }
+
+    /// Returns the context of the block containing the deploys, as exposed to contracts.
+    pub fn block_context(&self) -> BlockContext {
+        BlockContext::new(
+            BlockTime::new(self.block_time),
+            self.block_height,
+            self.era_id,
+            self.protocol_version,
+        )
+    }
 }
 
 impl Default for ExecuteRequest {
@@ -58,9 +88,12 @@ impl Default for ExecuteRequest {
         Self {
             parent_state_hash: Digest::hash(&[]),
             block_time: 0,
+            block_height: 0,
+            era_id: EraId::default(),
             deploys: vec![],
             protocol_version: Default::default(),
             proposer,
+            speculative: false,
         }
     }
 }
@@ -16,26 +16,43 @@ pub struct ExecutionEffect {
     pub transforms: AdditiveMap<Key, Transform>,
 }
 
+impl ExecutionEffect {
+    /// Records a transform from an execution journal, along with the operation it represents.
+    fn record(&mut self, key: Key, transform: Transform) {
+        match transform {
+            Transform::Failure(_) => (),
+            Transform::Identity => self.ops.insert_add(key, Op::Read),
+            Transform::Write(_) | Transform::Prune => self.ops.insert_add(key, Op::Write),
+            Transform::AddInt32(_)
+            | Transform::AddUInt64(_)
+            | Transform::AddUInt128(_)
+            | Transform::AddUInt256(_)
+            | Transform::AddUInt512(_)
+            | Transform::AddKeys(_) => self.ops.insert_add(key, Op::Add),
+        };
+        self.transforms.insert_add(key, transform);
+    }
+}
+
 impl From<ExecutionJournal> for ExecutionEffect {
     fn from(journal: ExecutionJournal) -> Self {
-        let mut ops = AdditiveMap::new();
-        let mut transforms = AdditiveMap::new();
-        for (key, transform) in journal.into_iter() {
-            match transform {
-                Transform::Failure(_) => (),
-                Transform::Identity => ops.insert_add(key, Op::Read),
-                Transform::Write(_) => ops.insert_add(key, Op::Write),
-                Transform::AddInt32(_)
-                | Transform::AddUInt64(_)
-                | Transform::AddUInt128(_)
-                | Transform::AddUInt256(_)
-                | Transform::AddUInt512(_)
-                | Transform::AddKeys(_) => ops.insert_add(key, Op::Add),
-            };
-            transforms.insert_add(key, transform);
+        let mut effect = ExecutionEffect::default();
+        for (key, transform) in journal {
+            effect.record(key, transform);
         }
+        effect
+    }
+}
 
-        Self { ops, transforms }
+/// Builds the effect without cloning the journal as a whole, only the transforms as they are
+/// merged.
+impl From<&ExecutionJournal> for ExecutionEffect {
+    fn from(journal: &ExecutionJournal) -> Self {
+        let mut effect = ExecutionEffect::default();
+        for (key, transform) in journal.iter() {
+            effect.record(*key, transform.clone());
+        }
+        effect
     }
 }
 
@@ -24,7 +24,10 @@ use casper_types::{
             SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
         },
         handle_payment,
-        mint::{self, ARG_ROUND_SEIGNIORAGE_RATE, ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY},
+        mint::{
+            self, NativeTokens, ARG_ROUND_SEIGNIORAGE_RATE, NATIVE_TOKENS_KEY,
+            ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY,
+        },
         standard_payment, AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
     AccessRights, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash,
@@ -527,6 +530,7 @@ pub struct ExecConfig {
     round_seigniorage_rate: Ratio<u64>,
     unbonding_delay: u64,
     genesis_timestamp_millis: u64,
+    native_tokens: BTreeMap<String, AccountHash>,
 }
 
 impl ExecConfig {
@@ -542,6 +546,7 @@ impl ExecConfig {
         round_seigniorage_rate: Ratio<u64>,
         unbonding_delay: u64,
         genesis_timestamp_millis: u64,
+        native_tokens: BTreeMap<String, AccountHash>,
     ) -> ExecConfig {
         ExecConfig {
             accounts,
@@ -553,6 +558,7 @@ impl ExecConfig {
             round_seigniorage_rate,
             unbonding_delay,
             genesis_timestamp_millis,
+            native_tokens,
         }
     }
 
@@ -621,6 +627,11 @@ impl ExecConfig {
     pub fn genesis_timestamp_millis(&self) -> u64 {
         self.genesis_timestamp_millis
     }
+
+    /// Returns the native tokens held in the mint, mapping the symbol of each token to its issuer.
+    pub fn native_tokens(&self) -> &BTreeMap<String, AccountHash> {
+        &self.native_tokens
+    }
 }
 
 impl Distribution<ExecConfig> for Standard {
@@ -648,6 +659,12 @@ impl Distribution<ExecConfig> for Standard {
 
         let genesis_timestamp_millis = rng.gen();
 
+        let native_token_count = rng.gen_range(0..3);
+        let native_tokens = iter::repeat(())
+            .map(|_| (rng.gen::<u32>().to_string(), rng.gen()))
+            .take(native_token_count)
+            .collect();
+
         ExecConfig {
             accounts,
             wasm_config,
@@ -658,6 +675,7 @@ impl Distribution<ExecConfig> for Standard {
             round_seigniorage_rate,
             unbonding_delay,
             genesis_timestamp_millis,
+            native_tokens,
         }
     }
 }
@@ -823,6 +841,32 @@ where
             total_supply_uref
         };
 
+        // The registry of native tokens is only created if the network declares any, so the
+        // genesis state of other networks is unaffected.
+        let native_tokens_uref = if self.exec_config.native_tokens().is_empty() {
+            None
+        } else {
+            let native_tokens_uref = self
+                .address_generator
+                .borrow_mut()
+                .new_uref(AccessRights::READ_ADD_WRITE);
+
+            let native_tokens: NativeTokens = self
+                .exec_config
+                .native_tokens()
+                .iter()
+                .map(|(token, issuer)| (token.clone(), (*issuer, U512::zero())))
+                .collect();
+            self.tracking_copy.borrow_mut().write(
+                native_tokens_uref.into(),
+                StoredValue::CLValue(
+                    CLValue::from_t(native_tokens)
+                        .map_err(|_| GenesisError::CLValue(NATIVE_TOKENS_KEY.to_string()))?,
+                ),
+            );
+            Some(native_tokens_uref)
+        };
+
         let named_keys = {
             let mut named_keys = NamedKeys::new();
             named_keys.insert(
@@ -830,6 +874,9 @@ where
                 round_seigniorage_rate_uref.into(),
             );
             named_keys.insert(TOTAL_SUPPLY_KEY.to_string(), total_supply_uref.into());
+            if let Some(native_tokens_uref) = native_tokens_uref {
+                named_keys.insert(NATIVE_TOKENS_KEY.to_string(), native_tokens_uref.into());
+            }
             named_keys
         };
 
@@ -0,0 +1,353 @@
+//! Support for migrating global state at protocol upgrades.
+//!
+//! A migration plan consists of ordered steps, each introduced by a protocol version.  Steps
+//! migrate global state in batches, and every batch is committed on its own, so that large
+//! migrations can be interrupted and resumed from a [`MigrationCheckpoint`].
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{Key, ProtocolVersion};
+
+use crate::{
+    core::{
+        engine_state::{execution_effect::ExecutionEffect, Error},
+        tracking_copy::TrackingCopy,
+    },
+    shared::newtypes::CorrelationId,
+    storage::global_state::StateProvider,
+};
+
+/// Progress of a migration step after migrating a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepProgress {
+    /// Entries remain to be migrated; the next batch continues after the given key.
+    InProgress(Key),
+    /// All entries have been migrated.
+    Complete,
+}
+
+/// A single step of a global state migration.
+pub trait MigrationStep<S>
+where
+    S: StateProvider,
+{
+    /// Returns the name of the step, unique among the steps of its protocol version.
+    fn name(&self) -> &str;
+
+    /// Migrates the next batch of entries, writing the changes to `tracking_copy`.
+    ///
+    /// `cursor` is the key returned with the previous batch's progress, or `None` for the first
+    /// batch.  Batches should be small enough to be committed at once.
+    fn migrate_batch(
+        &self,
+        correlation_id: CorrelationId,
+        tracking_copy: &mut TrackingCopy<S::Reader>,
+        cursor: Option<&Key>,
+    ) -> Result<StepProgress, Error>;
+}
+
+/// The migration steps of all protocol versions, in the order they are run.
+pub struct MigrationPlan<S>
+where
+    S: StateProvider,
+{
+    steps: BTreeMap<ProtocolVersion, Vec<Box<dyn MigrationStep<S>>>>,
+}
+
+impl<S> MigrationPlan<S>
+where
+    S: StateProvider,
+{
+    /// Creates an empty migration plan.
+    pub fn new() -> Self {
+        MigrationPlan {
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a step introduced by `protocol_version`, run after all steps of earlier protocol
+    /// versions and after the steps already added for the same version.
+    pub fn add_step(
+        &mut self,
+        protocol_version: ProtocolVersion,
+        step: Box<dyn MigrationStep<S>>,
+    ) -> Result<(), MigrationError> {
+        let steps = self.steps.entry(protocol_version).or_default();
+        if steps.iter().any(|existing| existing.name() == step.name()) {
+            return Err(MigrationError::DuplicateStep {
+                protocol_version,
+                step_name: step.name().to_string(),
+            });
+        }
+        steps.push(step);
+        Ok(())
+    }
+
+    /// Returns the steps to run when upgrading from `current_protocol_version` to
+    /// `new_protocol_version`, i.e. those of the versions after the current one, up to and
+    /// including the new one.
+    pub(crate) fn steps_between(
+        &self,
+        current_protocol_version: ProtocolVersion,
+        new_protocol_version: ProtocolVersion,
+    ) -> Vec<(ProtocolVersion, &dyn MigrationStep<S>)> {
+        self.steps
+            .iter()
+            .filter(|(protocol_version, _)| {
+                **protocol_version > current_protocol_version
+                    && **protocol_version <= new_protocol_version
+            })
+            .flat_map(|(protocol_version, steps)| {
+                steps.iter().map(move |step| (*protocol_version, &**step))
+            })
+            .collect()
+    }
+}
+
+/// Returns the migration plan run by [`EngineState::commit_upgrade`] after upgrading the system
+/// contracts.
+///
+/// [`EngineState::commit_upgrade`]: crate::core::engine_state::EngineState::commit_upgrade
+pub fn upgrade_plan<S>() -> MigrationPlan<S>
+where
+    S: StateProvider,
+{
+    // Steps migrating global state are added here, under the protocol version introducing them.
+    MigrationPlan::new()
+}
+
+impl<S> Default for MigrationPlan<S>
+where
+    S: StateProvider,
+{
+    fn default() -> Self {
+        MigrationPlan::new()
+    }
+}
+
+/// The position from which an interrupted migration resumes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    /// The protocol version introducing the step in progress.
+    pub protocol_version: ProtocolVersion,
+    /// The name of the step in progress.
+    pub step_name: String,
+    /// The key after which the step continues, or `None` if it hasn't migrated any batch yet.
+    pub cursor: Option<Key>,
+}
+
+/// Representation of a request to run the migrations of a protocol upgrade.
+#[derive(Debug, Clone)]
+pub struct MigrationRequest {
+    /// State root hash to migrate.  When resuming, the post state hash returned with the
+    /// checkpoint.
+    pub pre_state_hash: Digest,
+    /// The protocol version before the upgrade.
+    pub current_protocol_version: ProtocolVersion,
+    /// The protocol version after the upgrade.
+    pub new_protocol_version: ProtocolVersion,
+    /// The checkpoint to resume from, or `None` to start from the first step.
+    pub checkpoint: Option<MigrationCheckpoint>,
+    /// The maximum number of batches to migrate before returning a checkpoint, if any.
+    pub batch_limit: Option<u64>,
+    /// Whether to only compute the effects of the migration, without committing them.
+    pub dry_run: bool,
+}
+
+impl MigrationRequest {
+    /// Creates a request running all migrations between the given protocol versions.
+    pub fn new(
+        pre_state_hash: Digest,
+        current_protocol_version: ProtocolVersion,
+        new_protocol_version: ProtocolVersion,
+    ) -> Self {
+        MigrationRequest {
+            pre_state_hash,
+            current_protocol_version,
+            new_protocol_version,
+            checkpoint: None,
+            batch_limit: None,
+            dry_run: false,
+        }
+    }
+}
+
+/// Represents a successfully executed migration request.
+#[derive(Debug, Clone)]
+pub struct MigrationSuccess {
+    /// New state root hash generated after the batches were committed.  Equals the pre state hash
+    /// in a dry run.
+    pub post_state_hash: Digest,
+    /// The number of batches migrated.
+    pub batch_count: u64,
+    /// Where to resume if the batch limit was reached, or `None` if the migration is complete.
+    pub checkpoint: Option<MigrationCheckpoint>,
+    /// The uncommitted effects of a dry run.
+    pub dry_run_effect: Option<ExecutionEffect>,
+}
+
+/// Represents outcomes of a failed migration.
+#[derive(Clone, Error, Debug)]
+pub enum MigrationError {
+    /// A step with the same name was already added for the protocol version.
+    #[error("Duplicate migration step {step_name} for protocol version {protocol_version}")]
+    DuplicateStep {
+        /// The protocol version of the step.
+        protocol_version: ProtocolVersion,
+        /// The name of the step.
+        step_name: String,
+    },
+    /// The checkpoint refers to a step that is not part of the migration.
+    #[error("Unknown migration step {step_name} for protocol version {protocol_version}")]
+    UnknownStep {
+        /// The protocol version of the step.
+        protocol_version: ProtocolVersion,
+        /// The name of the step.
+        step_name: String,
+    },
+    /// The step returned the same cursor twice, so it would never complete.
+    #[error("Migration step {step_name} made no progress after {cursor}")]
+    NoProgress {
+        /// The name of the step.
+        step_name: String,
+        /// The repeated cursor.
+        cursor: Key,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{CLValue, KeyTag, StoredValue};
+
+    use super::*;
+    use crate::{
+        core::{
+            engine_state::{EngineConfig, EngineState},
+            execution,
+        },
+        storage::global_state::in_memory::InMemoryGlobalState,
+    };
+
+    const VERSION_1_0_0: ProtocolVersion = ProtocolVersion::from_parts(1, 0, 0);
+    const VERSION_1_1_0: ProtocolVersion = ProtocolVersion::from_parts(1, 1, 0);
+
+    /// Doubles the `u64` values stored under hash keys, one key per batch.
+    struct DoubleValues;
+
+    impl MigrationStep<InMemoryGlobalState> for DoubleValues {
+        fn name(&self) -> &str {
+            "double values"
+        }
+
+        fn migrate_batch(
+            &self,
+            correlation_id: CorrelationId,
+            tracking_copy: &mut TrackingCopy<<InMemoryGlobalState as StateProvider>::Reader>,
+            cursor: Option<&Key>,
+        ) -> Result<StepProgress, Error> {
+            let keys = tracking_copy.get_keys(correlation_id, &KeyTag::Hash)?;
+            let mut remaining = keys
+                .into_iter()
+                .filter(|key| cursor.map_or(true, |cursor| key > cursor));
+            let key = match remaining.next() {
+                Some(key) => key,
+                None => return Ok(StepProgress::Complete),
+            };
+            let value: u64 = match tracking_copy.read(correlation_id, &key)? {
+                Some(StoredValue::CLValue(cl_value)) => {
+                    cl_value.into_t().map_err(execution::Error::from)?
+                }
+                _ => return Err(Error::InvalidKeyVariant),
+            };
+            tracking_copy.write(key, stored_u64(value * 2));
+            if remaining.next().is_some() {
+                Ok(StepProgress::InProgress(key))
+            } else {
+                Ok(StepProgress::Complete)
+            }
+        }
+    }
+
+    fn stored_u64(value: u64) -> StoredValue {
+        StoredValue::CLValue(CLValue::from_t(value).unwrap())
+    }
+
+    fn read_u64(
+        engine_state: &EngineState<InMemoryGlobalState>,
+        state_hash: Digest,
+        key: Key,
+    ) -> u64 {
+        let mut tracking_copy = engine_state.tracking_copy(state_hash).unwrap().unwrap();
+        match tracking_copy.read(CorrelationId::new(), &key).unwrap() {
+            Some(StoredValue::CLValue(cl_value)) => cl_value.into_t().unwrap(),
+            other => panic!("unexpected value {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_resume_migration_from_checkpoint() {
+        let correlation_id = CorrelationId::new();
+        let keys = [Key::Hash([1; 32]), Key::Hash([2; 32]), Key::Hash([3; 32])];
+        let pairs: Vec<_> = keys
+            .iter()
+            .zip(1..)
+            .map(|(key, value)| (*key, stored_u64(value)))
+            .collect();
+        let (state, pre_state_hash) =
+            InMemoryGlobalState::from_pairs(correlation_id, &pairs).unwrap();
+        let engine_state = EngineState::new(state, EngineConfig::default());
+
+        let mut plan = MigrationPlan::new();
+        plan.add_step(VERSION_1_1_0, Box::new(DoubleValues))
+            .unwrap();
+        assert!(matches!(
+            plan.add_step(VERSION_1_1_0, Box::new(DoubleValues)),
+            Err(MigrationError::DuplicateStep { .. })
+        ));
+
+        // A dry run computes the effects without committing them.
+        let mut request = MigrationRequest::new(pre_state_hash, VERSION_1_0_0, VERSION_1_1_0);
+        request.dry_run = true;
+        let dry_run = engine_state
+            .commit_migration(correlation_id, &plan, request.clone())
+            .unwrap();
+        assert_eq!(dry_run.post_state_hash, pre_state_hash);
+        assert_eq!(dry_run.batch_count, 3);
+        assert!(dry_run.dry_run_effect.is_some());
+        assert_eq!(read_u64(&engine_state, pre_state_hash, keys[0]), 1);
+
+        // Interrupt the migration after two batches, then resume it.
+        request.dry_run = false;
+        request.batch_limit = Some(2);
+        let interrupted = engine_state
+            .commit_migration(correlation_id, &plan, request.clone())
+            .unwrap();
+        let checkpoint = interrupted.checkpoint.expect("should be interrupted");
+        assert_eq!(checkpoint.cursor, Some(keys[1]));
+        assert_eq!(
+            read_u64(&engine_state, interrupted.post_state_hash, keys[1]),
+            4
+        );
+        assert_eq!(
+            read_u64(&engine_state, interrupted.post_state_hash, keys[2]),
+            3
+        );
+
+        request.pre_state_hash = interrupted.post_state_hash;
+        request.checkpoint = Some(checkpoint);
+        let resumed = engine_state
+            .commit_migration(correlation_id, &plan, request)
+            .unwrap();
+        assert!(resumed.checkpoint.is_none());
+        assert_eq!(resumed.batch_count, 1);
+        for (key, value) in keys.iter().zip(1..) {
+            assert_eq!(
+                read_u64(&engine_state, resumed.post_state_hash, *key),
+                value * 2
+            );
+        }
+    }
+}
@@ -11,6 +11,7 @@ pub mod execution_effect;
 pub mod execution_result;
 pub mod genesis;
 pub mod get_bids;
+pub mod migration;
 pub mod op;
 pub mod query;
 pub mod run_genesis_request;
@@ -18,12 +19,14 @@ pub mod step;
 pub mod system_contract_registry;
 mod transfer;
 pub mod upgrade;
+pub mod view;
 
 use std::{
     cell::RefCell,
     collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     rc::Rc,
+    sync::Arc,
 };
 
 use num::Zero;
@@ -38,17 +41,18 @@ use casper_types::{
     contracts::NamedKeys,
     system::{
         auction::{
-            EraValidators, ARG_ERA_END_TIMESTAMP_MILLIS, ARG_EVICTED_VALIDATORS,
+            EraValidators, SigningKeys, ARG_ERA_END_TIMESTAMP_MILLIS, ARG_EVICTED_VALIDATORS,
             ARG_REWARD_FACTORS, ARG_VALIDATOR_PUBLIC_KEYS, AUCTION_DELAY_KEY,
-            LOCKED_FUNDS_PERIOD_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY,
-            VALIDATOR_SLOTS_KEY,
+            LOCKED_FUNDS_PERIOD_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, SIGNING_KEYS_KEY,
+            UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
         },
         handle_payment,
-        mint::{self, ROUND_SEIGNIORAGE_RATE_KEY},
+        mint::{self, NativeTokens, NATIVE_TOKENS_KEY, ROUND_SEIGNIORAGE_RATE_KEY},
         AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
-    AccessRights, ApiError, BlockTime, CLValue, ContractHash, DeployHash, DeployInfo, Gas, Key,
-    KeyTag, Motes, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, URef, U512,
+    AccessRights, ApiError, BlockContext, CLValue, ContractHash, DeployHash, DeployInfo,
+    EntryPointType, Gas, Key, KeyTag, Motes, Phase, ProtocolVersion, PublicKey, RuntimeArgs,
+    StoredValue, URef, U512,
 };
 
 pub use self::{
@@ -64,12 +68,17 @@ pub use self::{
     execution_result::{ExecutionResult, ForcedTransferResult},
     genesis::{ExecConfig, GenesisAccount, GenesisConfig, GenesisSuccess},
     get_bids::{GetBidsRequest, GetBidsResult},
+    migration::{
+        MigrationCheckpoint, MigrationError, MigrationPlan, MigrationRequest, MigrationStep,
+        MigrationSuccess, StepProgress,
+    },
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_registry::SystemContractRegistry,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
     upgrade::{UpgradeConfig, UpgradeSuccess},
+    view::{ViewRequest, ViewResult},
 };
 use crate::{
     core::{
@@ -79,14 +88,20 @@ use crate::{
             genesis::GenesisInstaller,
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
-        execution::{self, DirectSystemContractCall, Executor},
+        execution::{self, AddressGenerator, DirectSystemContractCall, Executor},
         runtime::RuntimeStack,
-        tracking_copy::{TrackingCopy, TrackingCopyExt},
+        tracking_copy::{ReadCacheCounters, ReadCacheStats, TrackingCopy, TrackingCopyExt},
+    },
+    shared::{
+        additive_map::AdditiveMap,
+        newtypes::CorrelationId,
+        transform::Transform,
+        wasm_config::{WasmConfig, WasmConfigs},
     },
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
         global_state::{
             lmdb::LmdbGlobalState, scratch::ScratchGlobalState, CommitProvider, StateProvider,
+            StateReader,
         },
         trie::{TrieOrChunk, TrieOrChunkId},
     },
@@ -114,7 +129,11 @@ pub const WASMLESS_TRANSFER_FIXED_GAS_PRICE: u64 = 1;
 #[derive(Debug)]
 pub struct EngineState<S> {
     config: EngineConfig,
+    /// Wasm configurations activated at given protocol versions, overriding the one of `config`.
+    wasm_configs: WasmConfigs,
     state: S,
+    /// Counters of the reads served by all tracking copies created by this instance.
+    read_cache_counters: Arc<ReadCacheCounters>,
 }
 
 impl EngineState<ScratchGlobalState> {
@@ -142,7 +161,9 @@ impl EngineState<LmdbGlobalState> {
     pub fn get_scratch_engine_state(&self) -> EngineState<ScratchGlobalState> {
         EngineState {
             config: self.config,
+            wasm_configs: self.wasm_configs.clone(),
             state: self.state.create_scratch(),
+            read_cache_counters: Default::default(),
         }
     }
 
@@ -152,9 +173,14 @@ impl EngineState<LmdbGlobalState> {
         state_root_hash: Digest,
         scratch_global_state: ScratchGlobalState,
     ) -> Result<Digest, Error> {
-        let stored_values = scratch_global_state.into_inner();
+        let (stored_values, pruned_keys) = scratch_global_state.into_inner();
         self.state
-            .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)
+            .put_stored_values(
+                CorrelationId::new(),
+                state_root_hash,
+                stored_values,
+                pruned_keys,
+            )
             .map_err(Into::into)
     }
 }
@@ -166,7 +192,12 @@ where
 {
     /// Creates new engine state.
     pub fn new(state: S, config: EngineConfig) -> EngineState<S> {
-        EngineState { config, state }
+        EngineState {
+            config,
+            wasm_configs: WasmConfigs::new(),
+            state,
+            read_cache_counters: Default::default(),
+        }
     }
 
     /// Returns engine config.
@@ -179,6 +210,27 @@ where
         self.config = new_config
     }
 
+    /// Activates `wasm_config` for the requests of `activation_point` and later protocol versions.
+    pub fn add_wasm_config(&mut self, activation_point: ProtocolVersion, wasm_config: WasmConfig) {
+        self.wasm_configs.insert(activation_point, wasm_config);
+    }
+
+    /// Returns the wasm config applying to requests of the given protocol version.
+    ///
+    /// Falls back to the wasm config of the engine config if none was activated at or before
+    /// `protocol_version`.
+    pub fn wasm_config(&self, protocol_version: ProtocolVersion) -> &WasmConfig {
+        self.wasm_configs
+            .get(protocol_version)
+            .unwrap_or_else(|| self.config.wasm_config())
+    }
+
+    /// Returns the engine config with the wasm config applying to the given protocol version.
+    fn config_at(&self, protocol_version: ProtocolVersion) -> EngineConfig {
+        self.config
+            .with_wasm_config(*self.wasm_config(protocol_version))
+    }
+
     /// Commits genesis process.
     ///
     /// This process is run only once per network to initiate the system. By definition users are
@@ -240,7 +292,8 @@ where
 
     /// Commits upgrade.
     ///
-    /// This process applies changes to the global state.
+    /// This process applies changes to the global state, then runs the migrations of
+    /// [`migration::upgrade_plan`] introduced by the new protocol version.
     ///
     /// Returns [`UpgradeSuccess`].
     pub fn commit_upgrade(
@@ -423,6 +476,60 @@ where
                 .write(locked_funds_period_key, value);
         }
 
+        if let Some(new_native_tokens) = upgrade_config
+            .new_native_tokens()
+            .filter(|new_native_tokens| !new_native_tokens.is_empty())
+        {
+            let mut mint_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, *mint_hash)?;
+
+            let (native_tokens_key, mut native_tokens) =
+                match mint_contract.named_keys().get(NATIVE_TOKENS_KEY).copied() {
+                    Some(native_tokens_key) => {
+                        let native_tokens: NativeTokens = match tracking_copy
+                            .borrow_mut()
+                            .read(correlation_id, &native_tokens_key)
+                            .map_err(Into::into)?
+                        {
+                            Some(StoredValue::CLValue(cl_value)) => cl_value.into_t().ok(),
+                            _ => None,
+                        }
+                        .ok_or_else(|| Error::Bytesrepr(NATIVE_TOKENS_KEY.to_string()))?;
+                        (native_tokens_key, native_tokens)
+                    }
+                    None => {
+                        // the registry of native tokens is created when the first tokens are
+                        // declared
+                        let mut address_generator =
+                            AddressGenerator::new(pre_state_hash.as_ref(), Phase::System);
+                        let native_tokens_key =
+                            Key::URef(address_generator.new_uref(AccessRights::READ_ADD_WRITE));
+                        let mut named_keys = NamedKeys::new();
+                        named_keys.insert(NATIVE_TOKENS_KEY.to_string(), native_tokens_key);
+                        mint_contract.named_keys_append(&mut named_keys);
+                        tracking_copy
+                            .borrow_mut()
+                            .write(Key::from(*mint_hash), StoredValue::Contract(mint_contract));
+                        (native_tokens_key, BTreeMap::new())
+                    }
+                };
+
+            // tokens can't be removed once declared, as purses may still hold them, but their
+            // issuers can be replaced
+            for (token, issuer) in new_native_tokens {
+                native_tokens
+                    .entry(token.clone())
+                    .or_insert((*issuer, U512::zero()))
+                    .0 = *issuer;
+            }
+            let value = StoredValue::CLValue(
+                CLValue::from_t(native_tokens)
+                    .map_err(|_| Error::Bytesrepr(NATIVE_TOKENS_KEY.to_string()))?,
+            );
+            tracking_copy.borrow_mut().write(native_tokens_key, value);
+        }
+
         // apply the arbitrary modifications
         for (key, value) in upgrade_config.global_state_update() {
             tracking_copy.borrow_mut().write(*key, value.clone());
@@ -440,6 +547,20 @@ where
             )
             .map_err(Into::into)?;
 
+        // run the global state migrations introduced by the new protocol version
+        let migration_request = MigrationRequest::new(
+            post_state_hash,
+            current_protocol_version,
+            new_protocol_version,
+        );
+        let post_state_hash = self
+            .commit_migration(
+                correlation_id,
+                &migration::upgrade_plan(),
+                migration_request,
+            )?
+            .post_state_hash;
+
         // return result and effects
         Ok(UpgradeSuccess {
             post_state_hash,
@@ -447,14 +568,138 @@ where
         })
     }
 
+    /// Runs the global state migrations of `plan` introduced after the request's current protocol
+    /// version, up to and including its new protocol version.
+    ///
+    /// Every batch is committed on its own.  If the request's batch limit is reached before all
+    /// steps are complete, the returned checkpoint and post state hash can be passed in a later
+    /// request to resume the migration.  In a dry run, nothing is committed and the effects of all
+    /// migrated batches are returned instead.
+    pub fn commit_migration(
+        &self,
+        correlation_id: CorrelationId,
+        plan: &MigrationPlan<S>,
+        request: MigrationRequest,
+    ) -> Result<MigrationSuccess, Error> {
+        let MigrationRequest {
+            pre_state_hash,
+            current_protocol_version,
+            new_protocol_version,
+            checkpoint,
+            batch_limit,
+            dry_run,
+        } = request;
+
+        if current_protocol_version
+            .check_next_version(&new_protocol_version)
+            .is_invalid()
+        {
+            return Err(Error::InvalidProtocolVersion(new_protocol_version));
+        }
+
+        let steps = plan.steps_between(current_protocol_version, new_protocol_version);
+
+        // Skip the steps completed before the checkpoint.
+        let (mut step_index, mut cursor) = match checkpoint {
+            None => (0, None),
+            Some(checkpoint) => {
+                let step_index = steps
+                    .iter()
+                    .position(|(protocol_version, step)| {
+                        *protocol_version == checkpoint.protocol_version
+                            && step.name() == checkpoint.step_name
+                    })
+                    .ok_or(Error::Migration(MigrationError::UnknownStep {
+                        protocol_version: checkpoint.protocol_version,
+                        step_name: checkpoint.step_name,
+                    }))?;
+                (step_index, checkpoint.cursor)
+            }
+        };
+
+        let mut state_hash = pre_state_hash;
+        let mut tracking_copy = self
+            .tracking_copy(state_hash)?
+            .ok_or(Error::RootNotFound(state_hash))?;
+        let mut batch_count = 0;
+
+        while let Some((protocol_version, step)) = steps.get(step_index) {
+            if batch_limit.map_or(false, |limit| batch_count >= limit) {
+                return Ok(MigrationSuccess {
+                    post_state_hash: state_hash,
+                    batch_count,
+                    checkpoint: Some(MigrationCheckpoint {
+                        protocol_version: *protocol_version,
+                        step_name: step.name().to_string(),
+                        cursor,
+                    }),
+                    dry_run_effect: dry_run.then(|| tracking_copy.effect()),
+                });
+            }
+
+            let progress =
+                step.migrate_batch(correlation_id, &mut tracking_copy, cursor.as_ref())?;
+            batch_count += 1;
+
+            if !dry_run {
+                let execution_effect = tracking_copy.effect();
+                state_hash = self
+                    .state
+                    .commit(correlation_id, state_hash, execution_effect.transforms)
+                    .map_err(Into::into)?;
+                tracking_copy = self
+                    .tracking_copy(state_hash)?
+                    .ok_or(Error::RootNotFound(state_hash))?;
+            }
+
+            match progress {
+                StepProgress::InProgress(next_cursor) => {
+                    if cursor == Some(next_cursor) {
+                        return Err(Error::Migration(MigrationError::NoProgress {
+                            step_name: step.name().to_string(),
+                            cursor: next_cursor,
+                        }));
+                    }
+                    cursor = Some(next_cursor);
+                }
+                StepProgress::Complete => {
+                    debug!(
+                        %protocol_version,
+                        step = step.name(),
+                        batch_count,
+                        "completed global state migration step"
+                    );
+                    step_index += 1;
+                    cursor = None;
+                }
+            }
+        }
+
+        Ok(MigrationSuccess {
+            post_state_hash: state_hash,
+            batch_count,
+            checkpoint: None,
+            dry_run_effect: dry_run.then(|| tracking_copy.effect()),
+        })
+    }
+
     /// Creates a new tracking copy instance.
     pub fn tracking_copy(&self, hash: Digest) -> Result<Option<TrackingCopy<S::Reader>>, Error> {
         match self.state.checkout(hash).map_err(Into::into)? {
-            Some(tc) => Ok(Some(TrackingCopy::new(tc))),
+            Some(tc) => Ok(Some(TrackingCopy::with_read_cache_counters(
+                tc,
+                Arc::clone(&self.read_cache_counters),
+            ))),
             None => Ok(None),
         }
     }
 
+    /// Returns the statistics of the reads served by all tracking copies created by this instance,
+    /// e.g. while executing deploys.
+    pub fn read_cache_stats(&self) -> ReadCacheStats {
+        self.read_cache_counters.stats()
+    }
+
     /// Executes a query.
     ///
     /// For a given root [`Key`] it does a path lookup through the named keys.
@@ -483,6 +728,62 @@ where
             .into())
     }
 
+    /// Calls a view entry point.
+    ///
+    /// The view is called by the system account, without payment, and none of its effects are
+    /// committed.  Returns the value returned by the view wrapped in a [`ViewResult`].
+    pub fn call_view(
+        &self,
+        correlation_id: CorrelationId,
+        view_request: ViewRequest,
+    ) -> Result<ViewResult, Error> {
+        let tracking_copy = match self.tracking_copy(view_request.state_hash())? {
+            Some(tracking_copy) => Rc::new(RefCell::new(tracking_copy)),
+            None => return Ok(ViewResult::RootNotFound),
+        };
+
+        let contract = match tracking_copy
+            .borrow_mut()
+            .get_contract(correlation_id, view_request.contract_hash())
+        {
+            Ok(contract) => contract,
+            Err(execution::Error::KeyNotFound(_)) => return Ok(ViewResult::ContractNotFound),
+            Err(error) => return Err(error.into()),
+        };
+        let entry_point_name = view_request.entry_point_name();
+        match contract.entry_point(entry_point_name) {
+            Some(entry_point) if entry_point.entry_point_type() == EntryPointType::View => {}
+            Some(_) => return Ok(ViewResult::NotAView(entry_point_name.to_string())),
+            None => return Ok(ViewResult::EntryPointNotFound(entry_point_name.to_string())),
+        }
+
+        let virtual_system_account = {
+            let named_keys = NamedKeys::new();
+            let purse = URef::new(Default::default(), AccessRights::READ_ADD_WRITE);
+            Account::create(PublicKey::System.to_account_hash(), named_keys, purse)
+        };
+
+        let config = self.config_at(view_request.protocol_version());
+        let gas_limit = Gas::new(U512::from(config.max_view_gas()));
+        let executor = Executor::new(config);
+        let (result, cost) = executor.call_view(
+            view_request.contract_hash(),
+            entry_point_name,
+            view_request.args().clone(),
+            &virtual_system_account,
+            view_request.block_context(),
+            gas_limit,
+            view_request.protocol_version(),
+            correlation_id,
+            tracking_copy,
+            self.get_new_system_call_stack(),
+        );
+        Ok(match result {
+            Ok(value) => ViewResult::Success { value, cost },
+            Err(error) => ViewResult::Failure { error, cost },
+        })
+    }
+
     /// Runs a deploy execution request.
     ///
     /// For each deploy stored in the request it will execute it.
@@ -495,7 +796,13 @@ where
         correlation_id: CorrelationId,
         mut exec_request: ExecuteRequest,
     ) -> Result<ExecutionResults, Error> {
-        let executor = Executor::new(*self.config());
+        let mut config = self.config_at(exec_request.protocol_version);
+        if !exec_request.speculative {
+            // Whether a deploy exceeds a wall-clock limit depends on the executing node, so deploys
+            // in blocks must run to completion or until they run out of gas.
+            config = config.with_max_execution_duration(None);
+        }
+        let executor = Executor::new(config);
 
         let deploys = exec_request.take_deploys();
         let mut results = ExecutionResults::with_capacity(deploys.len());
@@ -507,7 +814,7 @@ where
                     &executor,
                     exec_request.protocol_version,
                     exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
+                    exec_request.block_context(),
                     deploy_item,
                     exec_request.proposer.clone(),
                 ),
@@ -516,7 +823,7 @@ where
                     &executor,
                     exec_request.protocol_version,
                     exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
+                    exec_request.block_context(),
                     deploy_item,
                     exec_request.proposer.clone(),
                 ),
@@ -532,13 +839,22 @@ where
         Ok(results)
     }
 
-    fn get_authorized_account(
+    #[allow(clippy::too_many_arguments)]
+    fn get_authorized_account<R>(
         &self,
         correlation_id: CorrelationId,
+        executor: &Executor,
         account_hash: AccountHash,
         authorization_keys: &BTreeSet<AccountHash>,
-        tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
-    ) -> Result<Account, Error> {
+        block_context: BlockContext,
+        deploy_hash: DeployHash,
+        protocol_version: ProtocolVersion,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<Account, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<ExecError>,
+    {
         let account: Account = match tracking_copy
             .borrow_mut()
             .get_account(correlation_id, account_hash)
@@ -554,6 +870,35 @@ where
             return Err(error::Error::Authorization);
         }
 
+        // Check the approvals using the account's authorization contract, if it designated one
+        if self.config.allow_authorization_contracts() {
+            if let Some(contract_addr) = account.authorization_contract() {
+                let stack = RuntimeStack::from_account_hash(
+                    account_hash,
+                    self.config.max_runtime_call_stack_height() as usize,
+                );
+                // The contract runs against a fork of the tracking copy so that none of its
+                // effects are persisted.
+                let tracking_copy = tracking_copy.borrow();
+                let is_authorized = executor.call_authorization_contract(
+                    ContractHash::new(contract_addr),
+                    &account,
+                    authorization_keys.clone(),
+                    block_context,
+                    deploy_hash,
+                    Gas::new(U512::from(self.config.authorization_contract_gas_limit())),
+                    protocol_version,
+                    correlation_id,
+                    Rc::new(RefCell::new(tracking_copy.fork())),
+                    stack,
+                )?;
+                if !is_authorized {
+                    return Err(execution::Error::DeploymentAuthorizationFailure.into());
+                }
+                return Ok(account);
+            }
+        }
+
         // Check total key weight against deploy threshold
         if !account.can_deploy_with(authorization_keys) {
             return Err(execution::Error::DeploymentAuthorizationFailure.into());
@@ -595,7 +940,7 @@ where
         executor: &Executor,
         protocol_version: ProtocolVersion,
         prestate_hash: Digest,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_item: DeployItem,
         proposer: PublicKey,
     ) -> Result<ExecutionResult, Error> {
@@ -605,6 +950,90 @@ where
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
 
+        self.transfer_with_tracking_copy(
+            correlation_id,
+            executor,
+            protocol_version,
+            block_context,
+            deploy_item,
+            proposer,
+            tracking_copy,
+        )
+    }
+
+    /// Executes the native transfers of an [`ExecuteRequest`] one after another, each on top of
+    /// the effects of the previous ones.
+    ///
+    /// Unlike [`EngineState::run_execute`], which checks out the parent state for every deploy,
+    /// this checks out the parent state once and shares the values read from it, such as the
+    /// system contracts and the proposer's account, between all transfers.  Each transfer runs on
+    /// a fork of the state left by the previous ones, so its execution result contains only its
+    /// own effects, exactly as if the previous results had been committed.
+    ///
+    /// Returns an error if the request contains deploys other than native transfers.
+    pub fn run_transfers(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<ExecutionResults, Error> {
+        let executor = Executor::new(self.config_at(exec_request.protocol_version));
+
+        let deploys = exec_request.take_deploys();
+        if let Some(deploy_item) = deploys
+            .iter()
+            .find(|deploy_item| !deploy_item.session.is_transfer())
+        {
+            return Err(Error::InvalidDeployItemVariant(format!(
+                "{:?} is not a native transfer",
+                deploy_item.deploy_hash
+            )));
+        }
+        let mut results = ExecutionResults::with_capacity(deploys.len());
+
+        let mut tracking_copy = match self.tracking_copy(exec_request.parent_state_hash) {
+            Err(error) => {
+                for _ in deploys {
+                    results.push_back(ExecutionResult::precondition_failure(error.clone()));
+                }
+                return Ok(results);
+            }
+            Ok(None) => return Err(Error::RootNotFound(exec_request.parent_state_hash)),
+            Ok(Some(tracking_copy)) => tracking_copy,
+        };
+
+        for deploy_item in deploys {
+            let result = self.transfer_with_tracking_copy(
+                correlation_id,
+                &executor,
+                exec_request.protocol_version,
+                exec_request.block_context(),
+                deploy_item,
+                exec_request.proposer.clone(),
+                Rc::new(RefCell::new(tracking_copy.fork())),
+            )?;
+            tracking_copy.apply_journal(correlation_id, result.execution_journal())?;
+            results.push_back(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Executes a native transfer on top of the given tracking copy.
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_with_tracking_copy<R>(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        protocol_version: ProtocolVersion,
+        block_context: BlockContext,
+        deploy_item: DeployItem,
+        proposer: PublicKey,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<ExecutionResult, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<ExecError>,
+    {
         let base_key = Key::Account(deploy_item.address);
 
         let account_hash = match base_key.into_account() {
@@ -620,8 +1049,12 @@ where
 
         let account = match self.get_authorized_account(
             correlation_id,
+            executor,
             account_hash,
             &authorization_keys,
+            block_context,
+            deploy_item.deploy_hash,
+            protocol_version,
             Rc::clone(&tracking_copy),
         ) {
             Ok(account) => account,
@@ -749,7 +1182,7 @@ where
                             RuntimeArgs::new(), // mint create takes no arguments
                             &account,
                             authorization_keys.clone(),
-                            blocktime,
+                            block_context,
                             deploy_item.deploy_hash,
                             gas_limit,
                             protocol_version,
@@ -838,7 +1271,7 @@ where
                     RuntimeArgs::default(),
                     &account,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_context,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -867,6 +1300,7 @@ where
                 payment_uref,
                 wasmless_transfer_motes.value(),
                 transfer_args.arg_id(),
+                transfer_args.memo().cloned(),
             );
 
             let runtime_args = match RuntimeArgs::try_from(new_transfer_args) {
@@ -881,7 +1315,7 @@ where
                     runtime_args,
                     &account,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_context,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -951,7 +1385,7 @@ where
             payment_result.with_cost(payment_gas)
         };
 
-        let runtime_args = match RuntimeArgs::try_from(transfer_args) {
+        let runtime_args = match RuntimeArgs::try_from(transfer_args.clone()) {
             Ok(runtime_args) => runtime_args,
             Err(error) => {
                 return Ok(make_charged_execution_failure(
@@ -967,7 +1401,7 @@ where
                 runtime_args,
                 &account,
                 authorization_keys.clone(),
-                blocktime,
+                block_context,
                 deploy_item.deploy_hash,
                 gas_limit,
                 protocol_version,
@@ -1031,7 +1465,7 @@ where
                     handle_payment_args,
                     &system_account,
                     authorization_keys,
-                    blocktime,
+                    block_context,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -1096,12 +1530,15 @@ where
         executor: &Executor,
         protocol_version: ProtocolVersion,
         prestate_hash: Digest,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_item: DeployItem,
         proposer: PublicKey,
     ) -> Result<ExecutionResult, Error> {
         // spec: https://casperlabs.atlassian.net/wiki/spaces/EN/pages/123404576/Payment+code+execution+specification
 
+        // The payment and session code share a single wall-clock budget.
+        let execution_deadline = executor.execution_deadline();
+
         // Create tracking copy (which functions as a deploy context)
         // validation_spec_2: prestate_hash check
         // do this second; as there is no reason to proceed if the prestate hash is invalid
@@ -1122,8 +1559,12 @@ where
             let account_hash = deploy_item.address;
             match self.get_authorized_account(
                 correlation_id,
+                executor,
                 account_hash,
                 &authorization_keys,
+                block_context,
+                deploy_item.deploy_hash,
+                protocol_version,
                 Rc::clone(&tracking_copy),
             ) {
                 Ok(account) => account,
@@ -1240,7 +1681,7 @@ where
                     &mut payment_named_keys,
                     payment_access_rights,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_context,
                     deploy_hash,
                     payment_gas_limit,
                     protocol_version,
@@ -1270,9 +1711,10 @@ where
                     &mut payment_named_keys,
                     payment_access_rights,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_context,
                     deploy_hash,
                     payment_gas_limit,
+                    execution_deadline,
                     protocol_version,
                     correlation_id,
                     Rc::clone(&tracking_copy),
@@ -1465,9 +1907,10 @@ where
                 &mut session_named_keys,
                 session_access_rights,
                 authorization_keys.clone(),
-                blocktime,
+                block_context,
                 deploy_hash,
                 session_gas_limit,
+                execution_deadline,
                 protocol_version,
                 correlation_id,
                 Rc::clone(&session_tracking_copy),
@@ -1607,7 +2050,7 @@ where
                     handle_payment_args,
                     &system_account,
                     authorization_keys,
-                    blocktime,
+                    block_context,
                     deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -1639,18 +2082,45 @@ where
     /// This is also referred to as "committing" the effects into the global state. This method has
     /// to be run after an execution has been made to persists the effects of it.
     ///
+    /// The transforms are streamed into the global state in order, so an execution journal can be
+    /// applied directly, without first merging it into an [`AdditiveMap`].
+    ///
     /// Returns new state root hash.
-    pub fn apply_effect(
+    pub fn apply_effect<I>(
         &self,
         correlation_id: CorrelationId,
         pre_state_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<Digest, Error> {
+        effects: I,
+    ) -> Result<Digest, Error>
+    where
+        I: IntoIterator<Item = (Key, Transform)>,
+    {
         self.state
             .commit(correlation_id, pre_state_hash, effects)
             .map_err(|err| Error::Exec(err.into()))
     }
 
+    /// Applies the effects of all deploys of an execute request in a single commit.
+    ///
+    /// The effects are merged in execution order, which is equivalent to applying them one deploy
+    /// at a time, but every trie affected by more than one deploy is only written once.
+    ///
+    /// Returns new state root hash.
+    pub fn apply_execution_results<'a>(
+        &self,
+        correlation_id: CorrelationId,
+        pre_state_hash: Digest,
+        execution_results: impl IntoIterator<Item = &'a ExecutionResult>,
+    ) -> Result<Digest, Error> {
+        let mut effects = AdditiveMap::new();
+        for execution_result in execution_results {
+            for (key, transform) in execution_result.execution_journal().iter() {
+                effects.insert_add(*key, transform.clone());
+            }
+        }
+        self.apply_effect(correlation_id, pre_state_hash, effects)
+    }
+
     /// Gets a trie (or chunk) object for given state root hash.
     pub fn get_trie(
         &self,
@@ -1770,7 +2240,43 @@ where
             }
         };
 
-        let era_validators_result = auction::detail::era_validators_from_snapshot(snapshot);
+        let signing_keys: SigningKeys = match self.run_query(
+            correlation_id,
+            QueryRequest::new(
+                state_root_hash,
+                auction_hash.into(),
+                vec![SIGNING_KEYS_KEY.to_string()],
+            ),
+        )? {
+            QueryResult::Success { value, proofs: _ } => {
+                let cl_value = match value.as_cl_value() {
+                    Some(signing_keys_cl_value) => signing_keys_cl_value.clone(),
+                    None => {
+                        error!("unexpected query failure; signing keys is not a CLValue");
+                        return Err(GetEraValidatorsError::UnexpectedQueryFailure);
+                    }
+                };
+
+                cl_value.into_t().map_err(|cl_value_error| {
+                    error!(%cl_value_error, "unexpected query failure; unable to parse signing keys");
+                    GetEraValidatorsError::CLValue
+                })?
+            }
+            // No validator has registered a successor key yet.
+            QueryResult::ValueNotFound(_) => SigningKeys::new(),
+            QueryResult::RootNotFound => return Err(GetEraValidatorsError::RootNotFound),
+            QueryResult::CircularReference(error) => {
+                error!(%error, "unexpected query failure; circular reference");
+                return Err(GetEraValidatorsError::UnexpectedQueryFailure);
+            }
+            QueryResult::DepthLimit { depth } => {
+                error!(%depth, "unexpected query failure; depth limit exceeded");
+                return Err(GetEraValidatorsError::UnexpectedQueryFailure);
+            }
+        };
+
+        let era_validators_result =
+            auction::detail::era_validators_from_snapshot(snapshot, &signing_keys);
         Ok(era_validators_result)
     }
 
@@ -1816,7 +2322,7 @@ where
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
 
-        let executor = Executor::new(*self.config());
+        let executor = Executor::new(self.config_at(step_request.protocol_version));
 
         let system_account_addr = PublicKey::System.to_account_hash();
 
@@ -1861,7 +2367,7 @@ where
             reward_args,
             &virtual_system_account,
             authorization_keys.clone(),
-            BlockTime::default(),
+            BlockContext::default(),
             deploy_hash,
             gas_limit,
             step_request.protocol_version,
@@ -1895,7 +2401,7 @@ where
                     slash_args,
                     &virtual_system_account,
                     authorization_keys.clone(),
-                    BlockTime::default(),
+                    BlockContext::default(),
                     deploy_hash,
                     gas_limit,
                     step_request.protocol_version,
@@ -1934,7 +2440,7 @@ where
             run_auction_args,
             &virtual_system_account,
             authorization_keys,
-            BlockTime::default(),
+            BlockContext::default(),
             deploy_hash,
             gas_limit,
             step_request.protocol_version,
@@ -2124,6 +2630,7 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
                 | ExecError::URefNotFound(_)
                 | ExecError::FunctionNotFound(_)
                 | ExecError::GasLimit
+                | ExecError::ExecutionTimeLimit
                 | ExecError::Ret(_)
                 | ExecError::Resolver(_)
                 | ExecError::Revert(_)
@@ -2133,6 +2640,7 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
                 | ExecError::SetThresholdFailure(_)
                 | ExecError::SystemContract(_)
                 | ExecError::DeploymentAuthorizationFailure
+                | ExecError::KeyManagementAuthorizationFailure
                 | ExecError::ExpectedReturnValue
                 | ExecError::UnexpectedReturnValue
                 | ExecError::InvalidContext
@@ -2154,7 +2662,8 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
                 | ExecError::RuntimeStackOverflow
                 | ExecError::ValueTooLarge
                 | ExecError::MissingRuntimeStack
-                | ExecError::DisabledContract(_) => false,
+                | ExecError::DisabledContract(_)
+                | ExecError::ReadOnlyView => false,
             },
             Error::WasmPreprocessing(_) => true,
             Error::WasmSerialization(_) => true,
@@ -2171,6 +2680,7 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
             | Error::Mint(_)
             | Error::InvalidKeyVariant
             | Error::ProtocolUpgrade(_)
+            | Error::Migration(_)
             | Error::InvalidDeployItemVariant(_)
             | Error::CommitError(_)
             | Error::MissingSystemContractRegistry
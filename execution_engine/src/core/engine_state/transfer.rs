@@ -3,8 +3,8 @@ use std::{cell::RefCell, convert::TryFrom, rc::Rc};
 use casper_types::{
     account::{Account, AccountHash},
     system::mint,
-    AccessRights, ApiError, CLType, CLValueError, Key, PublicKey, RuntimeArgs, StoredValue, URef,
-    U512,
+    AccessRights, ApiError, CLType, CLValueError, Key, PublicKey, RuntimeArgs, StoredValue,
+    TransferMemo, URef, U512,
 };
 
 use crate::{
@@ -32,13 +32,14 @@ pub enum TransferTargetMode {
 /// Mint's transfer arguments.
 ///
 /// A struct has a benefit of static typing, which is helpful while resolving the arguments.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct TransferArgs {
     to: Option<AccountHash>,
     source: URef,
     target: URef,
     amount: U512,
     arg_id: Option<u64>,
+    memo: Option<TransferMemo>,
 }
 
 impl TransferArgs {
@@ -49,6 +50,7 @@ impl TransferArgs {
         target: URef,
         amount: U512,
         arg_id: Option<u64>,
+        memo: Option<TransferMemo>,
     ) -> Self {
         Self {
             to,
@@ -56,6 +58,7 @@ impl TransferArgs {
             target,
             amount,
             arg_id,
+            memo,
         }
     }
 
@@ -78,6 +81,11 @@ impl TransferArgs {
     pub fn amount(&self) -> U512 {
         self.amount
     }
+
+    /// Returns `memo` field.
+    pub fn memo(&self) -> Option<&TransferMemo> {
+        self.memo.as_ref()
+    }
 }
 
 impl TryFrom<TransferArgs> for RuntimeArgs {
@@ -91,6 +99,7 @@ impl TryFrom<TransferArgs> for RuntimeArgs {
         runtime_args.insert(mint::ARG_TARGET, transfer_args.target)?;
         runtime_args.insert(mint::ARG_AMOUNT, transfer_args.amount)?;
         runtime_args.insert(mint::ARG_ID, transfer_args.arg_id)?;
+        runtime_args.insert(mint::ARG_MEMO, transfer_args.memo)?;
 
         Ok(runtime_args)
     }
@@ -314,6 +323,25 @@ impl TransferRuntimeArgsBuilder {
         Ok(id)
     }
 
+    /// Resolves the optional memo, given either as a `TransferMemo` or an
+    /// `Option<TransferMemo>`.
+    fn resolve_memo(&self) -> Result<Option<TransferMemo>, Error> {
+        let memo = match self.inner.get(mint::ARG_MEMO) {
+            None => None,
+            Some(memo_value) if matches!(memo_value.cl_type(), CLType::Option(_)) => memo_value
+                .clone()
+                .into_t::<Option<TransferMemo>>()
+                .map_err(Error::reverter)?,
+            Some(memo_value) => Some(
+                memo_value
+                    .clone()
+                    .into_t::<TransferMemo>()
+                    .map_err(Error::reverter)?,
+            ),
+        };
+        Ok(memo)
+    }
+
     /// Returns a resolved [`TransferTargetMode`].
     pub(crate) fn transfer_target_mode<R>(
         &mut self,
@@ -369,12 +397,15 @@ impl TransferRuntimeArgsBuilder {
 
         let id = self.resolve_id()?;
 
+        let memo = self.resolve_memo()?;
+
         Ok(TransferArgs {
             to,
             source: source_uref,
             target: target_uref,
             amount,
             arg_id: id,
+            memo,
         })
     }
 }
@@ -6,6 +6,7 @@ use thiserror::Error;
 
 use casper_hashing::Digest;
 use casper_types::{
+    account::AccountHash,
     bytesrepr::{self},
     system::SystemContractType,
     Contract, ContractHash, EraId, Key, ProtocolVersion, StoredValue,
@@ -51,6 +52,7 @@ pub struct UpgradeConfig {
     new_locked_funds_period_millis: Option<u64>,
     new_round_seigniorage_rate: Option<Ratio<u64>>,
     new_unbonding_delay: Option<u64>,
+    new_native_tokens: Option<BTreeMap<String, AccountHash>>,
     global_state_update: BTreeMap<Key, StoredValue>,
     chainspec_registry: ChainspecRegistry,
 }
@@ -68,6 +70,7 @@ impl UpgradeConfig {
         new_locked_funds_period_millis: Option<u64>,
         new_round_seigniorage_rate: Option<Ratio<u64>>,
         new_unbonding_delay: Option<u64>,
+        new_native_tokens: Option<BTreeMap<String, AccountHash>>,
         global_state_update: BTreeMap<Key, StoredValue>,
         chainspec_registry: ChainspecRegistry,
     ) -> Self {
@@ -81,6 +84,7 @@ impl UpgradeConfig {
             new_locked_funds_period_millis,
             new_round_seigniorage_rate,
             new_unbonding_delay,
+            new_native_tokens,
             global_state_update,
             chainspec_registry,
         }
@@ -131,6 +135,11 @@ impl UpgradeConfig {
         self.new_unbonding_delay
     }
 
+    /// Returns new map of native token issuers if specified.
+    pub fn new_native_tokens(&self) -> Option<&BTreeMap<String, AccountHash>> {
+        self.new_native_tokens.as_ref()
+    }
+
     /// Returns new map of emergency global state updates.
     pub fn global_state_update(&self) -> &BTreeMap<Key, StoredValue> {
         &self.global_state_update
@@ -0,0 +1,112 @@
+//! Support for calling view entry points.
+use casper_hashing::Digest;
+use casper_types::{
+    BlockContext, BlockTime, CLValue, ContractHash, EraId, Gas, ProtocolVersion, RuntimeArgs,
+};
+
+use crate::core::execution;
+
+/// Request to call a view entry point against a given state root hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewRequest {
+    state_hash: Digest,
+    protocol_version: ProtocolVersion,
+    block_time: u64,
+    block_height: u64,
+    era_id: EraId,
+    contract_hash: ContractHash,
+    entry_point_name: String,
+    args: RuntimeArgs,
+}
+
+impl ViewRequest {
+    /// Creates new request object.
+    pub fn new(
+        state_hash: Digest,
+        protocol_version: ProtocolVersion,
+        block_time: u64,
+        block_height: u64,
+        era_id: EraId,
+        contract_hash: ContractHash,
+        entry_point_name: String,
+        args: RuntimeArgs,
+    ) -> Self {
+        ViewRequest {
+            state_hash,
+            protocol_version,
+            block_time,
+            block_height,
+            era_id,
+            contract_hash,
+            entry_point_name,
+            args,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns protocol version.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+
+    /// Returns the block time seen by the called contract.
+    pub fn block_time(&self) -> u64 {
+        self.block_time
+    }
+
+    /// Returns the block context seen by the called contract.
+    pub fn block_context(&self) -> BlockContext {
+        BlockContext::new(
+            BlockTime::new(self.block_time),
+            self.block_height,
+            self.era_id,
+            self.protocol_version,
+        )
+    }
+
+    /// Returns the hash of the called contract.
+    pub fn contract_hash(&self) -> ContractHash {
+        self.contract_hash
+    }
+
+    /// Returns the name of the called entry point.
+    pub fn entry_point_name(&self) -> &str {
+        &self.entry_point_name
+    }
+
+    /// Returns the runtime arguments of the call.
+    pub fn args(&self) -> &RuntimeArgs {
+        &self.args
+    }
+}
+
+/// Result of calling a view entry point.
+#[derive(Debug)]
+pub enum ViewResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// There is no contract under the given hash.
+    ContractNotFound,
+    /// The contract has no entry point with the given name.
+    EntryPointNotFound(String),
+    /// The entry point with the given name is not a view.
+    NotAView(String),
+    /// The view failed.
+    Failure {
+        /// Execution error.
+        error: execution::Error,
+        /// Gas used until the view failed.
+        cost: Gas,
+    },
+    /// The view returned a value.
+    Success {
+        /// The returned value.
+        value: CLValue,
+        /// Gas used by the view.
+        cost: Gas,
+    },
+}
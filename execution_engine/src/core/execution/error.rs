@@ -63,6 +63,9 @@ pub enum Error {
     /// Execution exceeded the gas limit.
     #[error("Out of gas error")]
     GasLimit,
+    /// Execution exceeded the maximum wall-clock duration configured for the node.
+    #[error("Execution time limit exceeded")]
+    ExecutionTimeLimit,
     /// A stored smart contract incorrectly called a ret function.
     #[error("Return")]
     Ret(Vec<URef>),
@@ -90,6 +93,9 @@ pub enum Error {
     /// Weight of all used associated keys does not meet account's deploy threshold.
     #[error("Deployment authorization failure")]
     DeploymentAuthorizationFailure,
+    /// Weight of all used associated keys does not meet account's key management threshold.
+    #[error("Key management authorization failure")]
+    KeyManagementAuthorizationFailure,
     /// Host buffer expected a value to be present.
     #[error("Expected return value")]
     ExpectedReturnValue,
@@ -152,6 +158,17 @@ pub enum Error {
         /// Name of the required argument.
         name: String,
     },
+    /// Error calling a smart contract with an argument of a different type than declared by the
+    /// entry point.
+    #[error("Invalid argument {name}: expected {expected:?}, found {found:?}")]
+    InvalidArgument {
+        /// Name of the argument.
+        name: String,
+        /// Type of the argument declared by the entry point.
+        expected: CLType,
+        /// Type of the provided argument.
+        found: CLType,
+    },
     /// Error writing a dictionary item key which exceeded maximum allowed length.
     #[error("Dictionary item key exceeded maximum length")]
     DictionaryItemKeyExceedsLength,
@@ -173,6 +190,9 @@ pub enum Error {
     /// Contract is disabled.
     #[error("Contract is disabled")]
     DisabledContract(ContractHash),
+    /// An attempt to modify global state from within a view entry point.
+    #[error("Global state is read-only within a view")]
+    ReadOnlyView,
 }
 
 impl From<wasm_prep::PreprocessingError> for Error {
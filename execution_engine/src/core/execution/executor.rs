@@ -1,12 +1,15 @@
-use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc, time::Instant};
 
 use casper_types::{
-    account::{Account, AccountHash},
+    account::{
+        Account, AccountHash, ARG_AUTHORIZATION_KEYS, ARG_DEPLOY_HASH, AUTHORIZATION_ENTRY_POINT,
+    },
     bytesrepr::FromBytes,
     contracts::NamedKeys,
+    runtime_args,
     system::{auction, handle_payment, mint, AUCTION, HANDLE_PAYMENT, MINT},
-    BlockTime, CLTyped, ContextAccessRights, DeployHash, EntryPointType, Gas, Key, Phase,
-    ProtocolVersion, RuntimeArgs, StoredValue, U512,
+    BlockContext, CLTyped, CLValue, ContextAccessRights, ContractHash, DeployHash, EntryPointType,
+    Gas, Key, Phase, ProtocolVersion, RuntimeArgs, StoredValue, U512,
 };
 
 use crate::{
@@ -43,6 +46,14 @@ impl Executor {
         Executor { config }
     }
 
+    /// Returns the instant by which the Wasm execution of a deploy started now has to finish, or
+    /// `None` if its duration is unlimited.
+    pub(crate) fn execution_deadline(&self) -> Option<Instant> {
+        self.config
+            .max_execution_duration()
+            .and_then(|max_execution_duration| Instant::now().checked_add(max_execution_duration))
+    }
+
     /// Executes a WASM module.
     ///
     /// This method checks if a given contract hash is a system contract, and then short circuits to
@@ -56,9 +67,10 @@ impl Executor {
         named_keys: &mut NamedKeys,
         access_rights: ContextAccessRights,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_hash: DeployHash,
         gas_limit: Gas,
+        execution_deadline: Option<Instant>,
         protocol_version: ProtocolVersion,
         correlation_id: CorrelationId,
         tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
@@ -81,7 +93,7 @@ impl Executor {
             Rc::new(RefCell::new(generator))
         };
 
-        let context = self.create_runtime_context(
+        let mut context = self.create_runtime_context(
             EntryPointType::Session,
             args.clone(),
             named_keys,
@@ -89,7 +101,7 @@ impl Executor {
             Key::from(account.account_hash()),
             account,
             authorization_keys,
-            blocktime,
+            block_context,
             deploy_hash,
             gas_limit,
             address_generator,
@@ -99,6 +111,7 @@ impl Executor {
             phase,
             spending_limit,
         );
+        context.set_execution_deadline(execution_deadline);
 
         let mut runtime = Runtime::new(self.config, context);
 
@@ -142,7 +155,7 @@ impl Executor {
         payment_named_keys: &mut NamedKeys,
         access_rights: ContextAccessRights,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_hash: DeployHash,
         payment_gas_limit: Gas,
         protocol_version: ProtocolVersion,
@@ -175,7 +188,7 @@ impl Executor {
             payment_base_key,
             account,
             authorization_keys,
-            blocktime,
+            block_context,
             deploy_hash,
             payment_gas_limit,
             address_generator,
@@ -216,7 +229,7 @@ impl Executor {
         runtime_args: RuntimeArgs,
         account: &Account,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         protocol_version: ProtocolVersion,
@@ -294,7 +307,7 @@ impl Executor {
             base_key,
             account,
             authorization_keys,
-            blocktime,
+            block_context,
             deploy_hash,
             gas_limit,
             address_generator,
@@ -341,6 +354,141 @@ impl Executor {
         }
     }
 
+    /// Calls the authorization contract of `account` to check whether a deploy approved by
+    /// `authorization_keys` is authorized.
+    ///
+    /// The contract is called with a spending limit of zero, and is expected to return a `bool`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn call_authorization_contract<R>(
+        &self,
+        contract_hash: ContractHash,
+        account: &Account,
+        authorization_keys: BTreeSet<AccountHash>,
+        block_context: BlockContext,
+        deploy_hash: DeployHash,
+        gas_limit: Gas,
+        protocol_version: ProtocolVersion,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        stack: RuntimeStack,
+    ) -> Result<bool, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<Error>,
+    {
+        let phase = Phase::Session;
+        let address_generator = {
+            let generator = AddressGenerator::new(deploy_hash.as_bytes(), phase);
+            Rc::new(RefCell::new(generator))
+        };
+
+        let contract = tracking_copy
+            .borrow_mut()
+            .get_contract(correlation_id, contract_hash)?;
+        let mut named_keys = contract.named_keys().clone();
+        let access_rights = contract.extract_access_rights(contract_hash);
+
+        let runtime_args = runtime_args! {
+            ARG_AUTHORIZATION_KEYS => authorization_keys.iter().copied().collect::<Vec<_>>(),
+            ARG_DEPLOY_HASH => deploy_hash.value(),
+        };
+
+        let runtime_context = self.create_runtime_context(
+            EntryPointType::Contract,
+            runtime_args.clone(),
+            &mut named_keys,
+            access_rights,
+            Key::from(contract_hash),
+            account,
+            authorization_keys,
+            block_context,
+            deploy_hash,
+            gas_limit,
+            address_generator,
+            protocol_version,
+            correlation_id,
+            tracking_copy,
+            phase,
+            U512::zero(),
+        );
+
+        let mut runtime = Runtime::new(self.config, runtime_context);
+        runtime
+            .call_contract_with_stack(
+                contract_hash,
+                AUTHORIZATION_ENTRY_POINT,
+                runtime_args,
+                stack,
+            )?
+            .into_t()
+            .map_err(Error::CLValue)
+    }
+
+    /// Calls the view entry point `entry_point_name` of the given contract.
+    ///
+    /// The call is made from a view context, so neither the view nor anything it calls can modify
+    /// global state.  Returns the value returned by the view along with the gas it used.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn call_view<R>(
+        &self,
+        contract_hash: ContractHash,
+        entry_point_name: &str,
+        runtime_args: RuntimeArgs,
+        account: &Account,
+        block_context: BlockContext,
+        gas_limit: Gas,
+        protocol_version: ProtocolVersion,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        stack: RuntimeStack,
+    ) -> (Result<CLValue, Error>, Gas)
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<Error>,
+    {
+        let phase = Phase::Session;
+        let deploy_hash = DeployHash::default();
+        let address_generator = {
+            let generator = AddressGenerator::new(deploy_hash.as_bytes(), phase);
+            Rc::new(RefCell::new(generator))
+        };
+
+        let contract = match tracking_copy
+            .borrow_mut()
+            .get_contract(correlation_id, contract_hash)
+        {
+            Ok(contract) => contract,
+            Err(error) => return (Err(error), Gas::default()),
+        };
+        let mut named_keys = contract.named_keys().clone();
+        let access_rights = contract.extract_access_rights(contract_hash);
+
+        let mut runtime_context = self.create_runtime_context(
+            EntryPointType::View,
+            runtime_args.clone(),
+            &mut named_keys,
+            access_rights,
+            Key::from(contract_hash),
+            account,
+            BTreeSet::new(),
+            block_context,
+            deploy_hash,
+            gas_limit,
+            address_generator,
+            protocol_version,
+            correlation_id,
+            tracking_copy,
+            phase,
+            U512::zero(),
+        );
+        runtime_context.set_execution_deadline(self.execution_deadline());
+
+        let mut runtime = Runtime::new(self.config, runtime_context);
+        let result =
+            runtime.call_contract_with_stack(contract_hash, entry_point_name, runtime_args, stack);
+        (result, runtime.context().gas_counter())
+    }
+
     /// Creates new runtime context.
     #[allow(clippy::too_many_arguments)]
     fn create_runtime_context<'a, R>(
@@ -352,7 +500,7 @@ impl Executor {
         base_key: Key,
         account: &'a Account,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         address_generator: Rc<RefCell<AddressGenerator>>,
@@ -378,7 +526,7 @@ impl Executor {
             authorization_keys,
             account,
             base_key,
-            blocktime,
+            block_context,
             deploy_hash,
             gas_limit,
             gas_counter,
@@ -59,6 +59,9 @@ pub(crate) enum FunctionIndex {
     LoadAuthorizationKeys,
     RandomBytes,
     DictionaryReadFuncIndex,
+    RemoveContract,
+    GetBlockContextIndex,
+    NarrowURefIndex,
 }
 
 impl From<FunctionIndex> for usize {
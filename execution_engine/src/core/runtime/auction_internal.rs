@@ -61,6 +61,13 @@ where
             .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
     }
 
+    fn new_uref<T: ToBytes + CLTyped>(&mut self, init: T) -> Result<URef, Error> {
+        let cl_value = CLValue::from_t(init).map_err(|_| Error::CLValue)?;
+        self.context
+            .new_uref(StoredValue::CLValue(cl_value))
+            .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
+    }
+
     fn read_bid(&mut self, account_hash: &AccountHash) -> Result<Option<Bid>, Error> {
         match self.context.read_gs(&Key::Bid(*account_hash)) {
             Ok(Some(StoredValue::Bid(bid))) => Ok(Some(*bid)),
@@ -129,6 +136,12 @@ where
         self.context.named_keys_get(name).cloned()
     }
 
+    fn named_keys_put(&mut self, name: &str, key: Key) -> Result<(), Error> {
+        self.context
+            .put_key(name.to_string(), key)
+            .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
+    }
+
     fn get_keys(&mut self, key_tag: &KeyTag) -> Result<BTreeSet<Key>, Error> {
         self.context.get_keys(key_tag).map_err(|_| Error::Storage)
     }
@@ -140,6 +153,10 @@ where
     fn vesting_schedule_period_millis(&self) -> u64 {
         self.config.vesting_schedule_period_millis()
     }
+
+    fn max_unbondings_per_step(&self) -> u32 {
+        self.config.max_unbondings_per_step()
+    }
 }
 
 impl<'a, R> MintProvider for Runtime<'a, R>
@@ -13,7 +13,7 @@ use casper_types::{
     StoredValue, URef, U512, UREF_SERIALIZED_LENGTH,
 };
 
-use super::{args::Args, Error, Runtime};
+use super::{args::Args, host_call_trace, Error, Runtime};
 use crate::{
     core::resolvers::v1_function_index::FunctionIndex,
     shared::host_function_costs::{Cost, HostFunction, DEFAULT_HOST_FUNCTION_NEW_DICTIONARY},
@@ -31,6 +31,7 @@ where
         args: RuntimeArgs,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
+        host_call_trace::record(|| format!("{:?}", func));
 
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
 
@@ -185,6 +186,14 @@ where
                 Ok(None)
             }
 
+            FunctionIndex::GetBlockContextIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_block_context, [dest_ptr])?;
+                self.get_block_context(dest_ptr)?;
+                Ok(None)
+            }
+
             FunctionIndex::GasFuncIndex => {
                 let (gas_arg,): (u32,) = Args::parse(args)?;
                 // Gas is special cased internal host function and for accounting purposes it isn't
@@ -206,6 +215,20 @@ where
                 ))))
             }
 
+            FunctionIndex::NarrowURefIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref
+                // args(2) = access rights of the narrowed uref
+                // args(3) = pointer to Wasm memory where to write the narrowed uref
+                let (uref_ptr, uref_size, access_rights, dest_ptr) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.narrow_uref,
+                    [uref_ptr, uref_size, access_rights, dest_ptr],
+                )?;
+                let ret = self.narrow_uref(uref_ptr, uref_size, access_rights, dest_ptr)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
             FunctionIndex::RevertFuncIndex => {
                 // args(0) = status u32
                 let (status,) = Args::parse(args)?;
@@ -659,6 +682,45 @@ where
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
             }
 
+            FunctionIndex::RemoveContract => {
+                // args(0) = pointer to package hash in wasm memory
+                // args(1) = size of package hash in wasm memory
+                // args(2) = pointer to contract hash in wasm memory
+                // args(3) = size of contract hash in wasm memory
+                // args(4) = maximum number of created keys to remove
+                // args(5) = pointer to the number of remaining keys (output param)
+                let (
+                    package_key_ptr,
+                    package_key_size,
+                    contract_hash_ptr,
+                    contract_hash_size,
+                    max_keys,
+                    remaining_ptr,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.remove_contract,
+                    [
+                        package_key_ptr,
+                        package_key_size,
+                        contract_hash_ptr,
+                        contract_hash_size,
+                        max_keys,
+                        remaining_ptr,
+                    ],
+                )?;
+                let contract_package_hash = self.t_from_mem(package_key_ptr, package_key_size)?;
+                let contract_hash = self.t_from_mem(contract_hash_ptr, contract_hash_size)?;
+
+                let result = self.remove_contract(
+                    contract_package_hash,
+                    contract_hash,
+                    max_keys,
+                    remaining_ptr,
+                )?;
+
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
+            }
+
             FunctionIndex::CallContractFuncIndex => {
                 // args(0) = pointer to contract hash where contract is at in global state
                 // args(1) = size of contract hash
@@ -920,7 +982,7 @@ where
                 let target: URef = self.t_from_mem(target_ptr, target_size)?;
                 let amount: U512 = self.t_from_mem(amount_ptr, amount_size)?;
                 let id: Option<u64> = self.t_from_mem(id_ptr, id_size)?;
-                self.record_transfer(maybe_to, source, target, amount, id)?;
+                self.record_transfer(maybe_to, source, target, amount, id, None)?;
                 Ok(Some(RuntimeValue::I32(0)))
             }
 
@@ -0,0 +1,80 @@
+//! An opt-in trace of the most recent host function calls made by Wasm executed on this thread.
+//!
+//! Tracing is disabled by default and costs a single thread-local lookup per host function call
+//! while disabled. It is intended for diagnosing failed executions in tests.
+
+use std::{cell::RefCell, collections::VecDeque};
+
+thread_local! {
+    static TRACE: RefCell<Option<HostCallTrace>> = RefCell::new(None);
+}
+
+struct HostCallTrace {
+    capacity: usize,
+    calls: VecDeque<String>,
+}
+
+/// Enables tracing on the current thread, retaining the names of the latest `capacity` host
+/// function calls.  Any calls traced so far are discarded.
+///
+/// A `capacity` of zero disables tracing.
+pub fn enable(capacity: usize) {
+    TRACE.with(|trace| {
+        *trace.borrow_mut() = (capacity > 0).then(|| HostCallTrace {
+            capacity,
+            calls: VecDeque::with_capacity(capacity),
+        });
+    });
+}
+
+/// Returns whether tracing is enabled on the current thread.
+pub fn is_enabled() -> bool {
+    TRACE.with(|trace| trace.borrow().is_some())
+}
+
+/// Returns the traced host function calls, oldest first, and clears the trace.
+///
+/// Returns an empty list if tracing is disabled.
+pub fn take() -> Vec<String> {
+    TRACE.with(|trace| {
+        trace
+            .borrow_mut()
+            .as_mut()
+            .map(|trace| trace.calls.drain(..).collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Records a call to the host function with the given name, if tracing is enabled.
+pub(super) fn record<F: FnOnce() -> String>(name: F) {
+    TRACE.with(|trace| {
+        if let Some(trace) = trace.borrow_mut().as_mut() {
+            if trace.calls.len() == trace.capacity {
+                let _ = trace.calls.pop_front();
+            }
+            trace.calls.push_back(name());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_retain_latest_calls() {
+        record(|| "ignored".to_string());
+        assert!(!is_enabled());
+        assert!(take().is_empty());
+
+        enable(2);
+        for name in ["first", "second", "third"] {
+            record(|| name.to_string());
+        }
+        assert_eq!(take(), vec!["second".to_string(), "third".to_string()]);
+        assert!(take().is_empty());
+
+        enable(0);
+        assert!(!is_enabled());
+    }
+}
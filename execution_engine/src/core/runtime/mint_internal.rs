@@ -1,8 +1,11 @@
 use casper_types::{
     account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
-    system::{mint::Error, CallStackElement},
-    CLTyped, CLValue, Key, Phase, StoredValue, URef, U512,
+    system::{
+        mint::{self, Error},
+        CallStackElement,
+    },
+    CLTyped, CLValue, Key, Phase, StoredValue, TransferMemo, URef, U512,
 };
 
 use super::Runtime;
@@ -139,6 +142,31 @@ where
             .metered_add_gs_unsafe(Key::Balance(uref.addr()), StoredValue::CLValue(cl_value))
             .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
     }
+
+    fn read_token_balance(&mut self, token: &str, uref: URef) -> Result<Option<U512>, Error> {
+        let maybe_value = self
+            .context
+            .read_gs_direct(&mint::native_token_balance_key(token, uref))
+            .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))?;
+        match maybe_value {
+            Some(StoredValue::CLValue(value)) => {
+                let value = CLValue::into_t(value).map_err(|_| Error::CLValue)?;
+                Ok(Some(value))
+            }
+            Some(_cl_value) => Err(Error::CLValue),
+            None => Ok(None),
+        }
+    }
+
+    fn write_token_balance(&mut self, token: &str, uref: URef, balance: U512) -> Result<(), Error> {
+        let cl_value = CLValue::from_t(balance).map_err(|_| Error::CLValue)?;
+        self.context
+            .metered_write_gs_unsafe(
+                mint::native_token_balance_key(token, uref),
+                StoredValue::CLValue(cl_value),
+            )
+            .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
+    }
 }
 
 impl<'a, R> SystemProvider for Runtime<'a, R>
@@ -153,8 +181,9 @@ where
         target: URef,
         amount: U512,
         id: Option<u64>,
+        memo: Option<TransferMemo>,
     ) -> Result<(), Error> {
-        let result = Runtime::record_transfer(self, maybe_to, source, target, amount, id);
+        let result = Runtime::record_transfer(self, maybe_to, source, target, amount, id, memo);
         result.map_err(|exec_error| {
             <Option<Error>>::from(exec_error).unwrap_or(Error::RecordTransferFailure)
         })
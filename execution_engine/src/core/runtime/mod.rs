@@ -3,6 +3,7 @@ mod args;
 mod auction_internal;
 mod externals;
 mod handle_payment_internal;
+pub mod host_call_trace;
 mod host_function_flag;
 mod mint_internal;
 pub mod stack;
@@ -36,7 +37,7 @@ use casper_types::{
     },
     AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractHash,
     ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, EraId, Gas,
-    GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer,
+    GrantedAccess, Key, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer, TransferMemo,
     TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
 };
 
@@ -170,6 +171,19 @@ where
         self.context.set_gas_counter(new_gas_counter);
     }
 
+    /// Charges for the peak size of the Wasm memory used by the current instance.
+    ///
+    /// Wasm memory can only ever grow, so its current size is also its peak size.
+    fn charge_peak_memory(&mut self) -> Result<(), Error> {
+        let pages = self.try_get_memory()?.current_size();
+        let cost = self
+            .config
+            .wasm_config()
+            .memory_costs()
+            .calculate_peak_memory_cost(pages.0);
+        self.gas(cost)
+    }
+
     /// Charge for a system contract call.
     ///
     /// This method does not charge for system contract calls if the immediate caller is a system
@@ -260,6 +274,46 @@ where
         Ok(self.context.validate_uref(&uref).is_ok())
     }
 
+    /// Writes a copy of the given uref with its access rights narrowed to `access_rights` to
+    /// `dest_ptr` in Wasm memory.
+    ///
+    /// The uref must be valid in the current context, and `access_rights` must be a non-empty
+    /// subset of its access rights. The narrowed uref is valid wherever the uref is, so it can be
+    /// passed to other contracts without granting them the uref's other access rights.
+    fn narrow_uref(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        access_rights: u32,
+        dest_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        self.context.validate_uref(&uref)?;
+
+        let access_rights = match u8::try_from(access_rights)
+            .ok()
+            .and_then(AccessRights::from_bits)
+        {
+            Some(access_rights) => access_rights,
+            None => return Ok(Err(ApiError::InvalidArgument)),
+        };
+        if access_rights.is_none() {
+            return Ok(Err(ApiError::NoAccessRights));
+        }
+        if !uref.access_rights().contains(access_rights) {
+            return Ok(Err(ApiError::PermissionDenied));
+        }
+
+        let narrowed_uref = uref.with_access_rights(access_rights);
+        self.try_get_memory()?
+            .set(
+                dest_ptr,
+                &narrowed_uref.into_bytes().map_err(Error::BytesRepr)?,
+            )
+            .map_err(|e| Error::Interpreter(e.into()))?;
+        Ok(Ok(()))
+    }
+
     /// Load the uref known by the given name into the Wasm memory
     fn load_key(
         &mut self,
@@ -403,6 +457,18 @@ where
             .map_err(|e| Error::Interpreter(e.into()).into())
     }
 
+    /// Writes the context of the current block to dest_ptr in Wasm memory.
+    fn get_block_context(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let block_context = self
+            .context
+            .block_context()
+            .into_bytes()
+            .map_err(Error::BytesRepr)?;
+        self.try_get_memory()?
+            .set(dest_ptr, &block_context)
+            .map_err(|e| Error::Interpreter(e.into()).into())
+    }
+
     /// Load the uref known by the given name into the Wasm memory
     fn load_call_stack(
         &mut self,
@@ -646,7 +712,8 @@ where
                 CLValue::from_t(maybe_balance).map_err(Self::reverter)
             })(),
             // Type: `fn transfer(maybe_to: Option<AccountHash>, source: URef, target: URef, amount:
-            // U512, id: Option<u64>) -> Result<(), Error>`
+            // U512, id: Option<u64>, memo: Option<TransferMemo>) -> Result<(), Error>`, where
+            // `memo` may be omitted.
             mint::METHOD_TRANSFER => (|| {
                 mint_runtime.charge_system_contract_call(mint_costs.transfer)?;
 
@@ -656,8 +723,12 @@ where
                 let target: URef = Self::get_named_argument(runtime_args, mint::ARG_TARGET)?;
                 let amount: U512 = Self::get_named_argument(runtime_args, mint::ARG_AMOUNT)?;
                 let id: Option<u64> = Self::get_named_argument(runtime_args, mint::ARG_ID)?;
+                let memo: Option<TransferMemo> = match runtime_args.get(mint::ARG_MEMO) {
+                    Some(_) => Self::get_named_argument(runtime_args, mint::ARG_MEMO)?,
+                    None => None,
+                };
                 let result: Result<(), mint::Error> =
-                    mint_runtime.transfer(maybe_to, source, target, amount, id);
+                    mint_runtime.transfer(maybe_to, source, target, amount, id, memo);
                 CLValue::from_t(result).map_err(Self::reverter)
             })(),
             // Type: `fn read_base_round_reward() -> Result<U512, Error>`
@@ -679,6 +750,45 @@ where
                     mint_runtime.mint_into_existing_purse(existing_purse, amount);
                 CLValue::from_t(result).map_err(Self::reverter)
             })(),
+            // Type: `fn mint_token(token: String, target: URef, amount: U512) -> Result<(), Error>`
+            mint::METHOD_MINT_TOKEN => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.mint_token)?;
+
+                let token: String = Self::get_named_argument(runtime_args, mint::ARG_TOKEN)?;
+                let target: URef = Self::get_named_argument(runtime_args, mint::ARG_TARGET)?;
+                let amount: U512 = Self::get_named_argument(runtime_args, mint::ARG_AMOUNT)?;
+                let result: Result<(), mint::Error> =
+                    mint_runtime.mint_token(&token, target, amount);
+                if let Err(mint::Error::GasLimit) = result {
+                    return Err(execution::Error::GasLimit);
+                }
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
+            // Type: `fn transfer_token(token: String, source: URef, target: URef, amount: U512) ->
+            // Result<(), Error>`
+            mint::METHOD_TRANSFER_TOKEN => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.transfer_token)?;
+
+                let token: String = Self::get_named_argument(runtime_args, mint::ARG_TOKEN)?;
+                let source: URef = Self::get_named_argument(runtime_args, mint::ARG_SOURCE)?;
+                let target: URef = Self::get_named_argument(runtime_args, mint::ARG_TARGET)?;
+                let amount: U512 = Self::get_named_argument(runtime_args, mint::ARG_AMOUNT)?;
+                let result: Result<(), mint::Error> =
+                    mint_runtime.transfer_token(&token, source, target, amount);
+                if let Err(mint::Error::GasLimit) = result {
+                    return Err(execution::Error::GasLimit);
+                }
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
+            // Type: `fn token_balance(token: String, purse: URef) -> Result<U512, Error>`
+            mint::METHOD_TOKEN_BALANCE => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.token_balance)?;
+
+                let token: String = Self::get_named_argument(runtime_args, mint::ARG_TOKEN)?;
+                let purse: URef = Self::get_named_argument(runtime_args, mint::ARG_PURSE)?;
+                let result: Result<U512, mint::Error> = mint_runtime.token_balance(&token, purse);
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
 
             _ => CLValue::from_t(()).map_err(Self::reverter),
         };
@@ -802,8 +912,11 @@ where
         let amount: U512 =
             Self::get_named_argument(self.context.args(), standard_payment::ARG_AMOUNT)?;
         let result = self.pay(amount).map_err(Self::reverter);
+        // Only the configured cost of `pay` is charged, not the host functions used to pay.
         self.set_gas_counter(gas_counter);
-        result
+        result?;
+        let pay_cost = self.config.system_config().standard_payment_costs().pay;
+        self.charge_system_contract_call(pay_cost)
     }
 
     /// Calls host auction contract.
@@ -986,6 +1099,34 @@ where
                 CLValue::from_t(()).map_err(Self::reverter)
             })(),
 
+            auction::METHOD_REGISTER_SUCCESSOR_KEY => (|| {
+                runtime.charge_system_contract_call(auction_costs.register_successor_key)?;
+
+                let validator_public_key: PublicKey =
+                    Self::get_named_argument(runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
+                let successor_public_key: PublicKey =
+                    Self::get_named_argument(runtime_args, auction::ARG_SUCCESSOR_PUBLIC_KEY)?;
+
+                runtime
+                    .register_successor_key(validator_public_key, successor_public_key)
+                    .map_err(Self::reverter)?;
+
+                CLValue::from_t(()).map_err(Self::reverter)
+            })(),
+
+            auction::METHOD_CLAIM_DELEGATOR_REWARDS => (|| {
+                runtime.charge_system_contract_call(auction_costs.undelegate)?;
+
+                let delegator = Self::get_named_argument(runtime_args, auction::ARG_DELEGATOR)?;
+                let validator = Self::get_named_argument(runtime_args, auction::ARG_VALIDATOR)?;
+
+                let result = runtime
+                    .claim_delegator_rewards(delegator, validator)
+                    .map_err(Self::reverter)?;
+
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
+
             _ => CLValue::from_t(()).map_err(Self::reverter),
         };
 
@@ -1040,6 +1181,7 @@ where
         )?);
 
         let result = instance.invoke_export(DEFAULT_ENTRY_POINT_NAME, &[], self);
+        let peak_memory_charge = self.charge_peak_memory();
 
         let error = match result {
             Err(error) => error,
@@ -1048,6 +1190,7 @@ where
             // returned the unit type `()` as per Rust functions which don't specify a
             // return value.
             Ok(_) => {
+                peak_memory_charge?;
                 return Ok(self.take_host_buffer().unwrap_or(CLValue::from_t(())?));
             }
         };
@@ -1059,6 +1202,7 @@ where
             let downcasted_error = host_error.downcast_ref::<Error>();
             match downcasted_error {
                 Some(Error::Ret(ref _ret_urefs)) => {
+                    peak_memory_charge?;
                     return self.take_host_buffer().ok_or(Error::ExpectedReturnValue);
                 }
                 Some(error) => return Err(error.clone()),
@@ -1106,7 +1250,7 @@ where
         let current = self.context.entry_point_type();
         let next = entry_point.entry_point_type();
         match (current, next) {
-            (EntryPointType::Contract, EntryPointType::Session) => {
+            (EntryPointType::Contract | EntryPointType::View, EntryPointType::Session) => {
                 // Session code can't be called from Contract code for security reasons.
                 Err(Error::InvalidContext)
             }
@@ -1114,8 +1258,7 @@ where
                 // Session code called from session reuses current base key
                 Ok(self.context.base_key())
             }
-            (EntryPointType::Session, EntryPointType::Contract)
-            | (EntryPointType::Contract, EntryPointType::Contract) => Ok(contract_hash.into()),
+            (_, EntryPointType::Contract | EntryPointType::View) => Ok(contract_hash.into()),
         }
     }
 
@@ -1204,32 +1347,7 @@ where
         self.validate_group_membership(&contract_package, entry_point.access())?;
 
         if self.config.strict_argument_checking() {
-            let entry_point_args_lookup: BTreeMap<&str, &Parameter> = entry_point
-                .args()
-                .iter()
-                .map(|param| (param.name(), param))
-                .collect();
-
-            let args_lookup: BTreeMap<&str, &NamedArg> = args
-                .named_args()
-                .map(|named_arg| (named_arg.name(), named_arg))
-                .collect();
-
-            // variable ensure args type(s) match defined args of entry point
-            for (param_name, param) in entry_point_args_lookup {
-                if let Some(named_arg) = args_lookup.get(param_name) {
-                    if param.cl_type() != named_arg.cl_value().cl_type() {
-                        return Err(Error::type_mismatch(
-                            param.cl_type().clone(),
-                            named_arg.cl_value().cl_type().clone(),
-                        ));
-                    }
-                } else if !param.cl_type().is_option() {
-                    return Err(Error::MissingArgument {
-                        name: param.name().to_string(),
-                    });
-                }
-            }
+            utils::validate_entry_point_args(&entry_point, &args)?;
         }
         // if session the caller's context
         // else the called contract's context
@@ -1249,7 +1367,7 @@ where
                 self.context.account().named_keys().clone(),
                 self.context.account().extract_access_rights(),
             ),
-            EntryPointType::Contract => (
+            EntryPointType::Contract | EntryPointType::View => (
                 contract.named_keys().clone(),
                 contract.extract_access_rights(contract_hash),
             ),
@@ -1264,10 +1382,12 @@ where
                     contract.contract_package_hash(),
                     contract_hash,
                 ),
-                EntryPointType::Contract => CallStackElement::stored_contract(
-                    contract.contract_package_hash(),
-                    contract_hash,
-                ),
+                EntryPointType::Contract | EntryPointType::View => {
+                    CallStackElement::stored_contract(
+                        contract.contract_package_hash(),
+                        contract_hash,
+                    )
+                }
             };
             stack.push(call_stack_element)?;
 
@@ -1358,6 +1478,7 @@ where
         let runtime = &mut Runtime::new_invocation_runtime(self, context, module, memory, stack);
 
         let result = instance.invoke_export(entry_point.name(), &[], runtime);
+        let peak_memory_charge = runtime.charge_peak_memory();
 
         // The `runtime`'s context was initialized with our counter from before the call and any gas
         // charged by the sub-call was added to its counter - so let's copy the correct value of the
@@ -1375,6 +1496,7 @@ where
             // not explicitly call `runtime::ret()`.  Treat as though the execution returned the
             // unit type `()` as per Rust functions which don't specify a return value.
             Ok(_) => {
+                peak_memory_charge?;
                 if self.context.entry_point_type() == EntryPointType::Session
                     && runtime.context.entry_point_type() == EntryPointType::Session
                 {
@@ -1394,6 +1516,8 @@ where
             let downcasted_error = host_error.downcast_ref::<Error>();
             match downcasted_error {
                 Some(Error::Ret(ref ret_urefs)) => {
+                    peak_memory_charge?;
+
                     // Insert extra urefs returned from call.
                     // Those returned URef's are guaranteed to be valid as they were already
                     // validated in the `ret` call inside context we ret from.
@@ -1768,6 +1892,83 @@ where
         Ok(Ok(()))
     }
 
+    /// Removes the disabled contract `contract_hash` from the global state, along with at most
+    /// `max_keys` of the keys it created, and writes the number of keys still to be removed into
+    /// the Wasm memory (at `remaining_ptr` pointer).
+    ///
+    /// The contract itself, including its named keys and Wasm, is only removed along with its last
+    /// created key, hence this should be called until no keys remain. Its version stays disabled in
+    /// the package, so that the version can never be reused.
+    fn remove_contract(
+        &mut self,
+        contract_package_hash: ContractPackageHash,
+        contract_hash: ContractHash,
+        max_keys: u32,
+        remaining_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let contract_package_key = contract_package_hash.into();
+        self.context.validate_key(&contract_package_key)?;
+
+        let contract_package: ContractPackage = self
+            .context
+            .get_validated_contract_package(contract_package_hash)?;
+
+        if contract_package.is_locked() {
+            return Err(Error::LockedContract(contract_package_hash).into());
+        }
+
+        if !contract_package
+            .versions()
+            .values()
+            .any(|version_hash| *version_hash == contract_hash)
+        {
+            return Ok(Err(contracts::Error::ContractNotFound.into()));
+        }
+
+        if contract_package.is_contract_enabled(&contract_hash) {
+            return Ok(Err(contracts::Error::ContractNotDisabled.into()));
+        }
+
+        if self
+            .try_get_stack()?
+            .call_stack_elements()
+            .iter()
+            .any(|element| element.contract_hash() == Some(&contract_hash))
+        {
+            return Ok(Err(contracts::Error::ContractInUse.into()));
+        }
+
+        let contract_key = Key::from(contract_hash);
+        let contract = match self.context.read_gs(&contract_key)? {
+            Some(StoredValue::Contract(contract)) => contract,
+            Some(_) => return Err(Error::InvalidContract(contract_hash).into()),
+            // The contract has already been removed.
+            None => return Ok(Err(contracts::Error::ContractNotFound.into())),
+        };
+
+        let created_keys = self.context.read_contract_storage_index(contract_hash)?;
+        let remaining = created_keys.len().saturating_sub(max_keys as usize);
+        for (index_key, key) in created_keys.into_iter().take(max_keys as usize) {
+            self.context.prune_gs_unsafe(key);
+            self.context.prune_gs_unsafe(index_key);
+        }
+
+        let remaining = u32::try_from(remaining).unwrap_or(u32::MAX);
+        if remaining == 0 {
+            self.context
+                .prune_gs_unsafe(contract.contract_wasm_hash().into());
+            self.context.prune_gs_unsafe(contract_key);
+        }
+
+        if let Err(error) = self
+            .try_get_memory()?
+            .set(remaining_ptr, &remaining.to_le_bytes())
+        {
+            return Err(Error::Interpreter(error.into()).into());
+        }
+        Ok(Ok(()))
+    }
+
     /// Writes function address (`hash_bytes`) into the Wasm memory (at
     /// `dest_ptr` pointer).
     fn function_address(&mut self, hash_bytes: [u8; 32], dest_ptr: u32) -> Result<(), Trap> {
@@ -1809,6 +2010,7 @@ where
         target: URef,
         amount: U512,
         id: Option<u64>,
+        memo: Option<TransferMemo>,
     ) -> Result<(), Error> {
         if self.context.base_key() != Key::from(self.context.get_system_contract(MINT)?) {
             return Err(Error::InvalidContext);
@@ -1823,7 +2025,17 @@ where
             let deploy_hash: DeployHash = self.context.get_deploy_hash();
             let from: AccountHash = self.context.account().account_hash();
             let fee: U512 = U512::zero(); // TODO
-            Transfer::new(deploy_hash, from, maybe_to, source, target, amount, fee, id)
+            Transfer::new(
+                deploy_hash,
+                from,
+                maybe_to,
+                source,
+                target,
+                amount,
+                fee,
+                id,
+                memo,
+            )
         };
         {
             let transfers = self.context.transfers_mut();
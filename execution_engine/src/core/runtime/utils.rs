@@ -4,8 +4,8 @@ use parity_wasm::elements::Module;
 use wasmi::{ImportsBuilder, MemoryRef, ModuleInstance, ModuleRef};
 
 use casper_types::{
-    contracts::NamedKeys, AccessRights, CLType, CLValue, Key, ProtocolVersion, PublicKey,
-    RuntimeArgs, URef, URefAddr, U128, U256, U512,
+    contracts::NamedKeys, AccessRights, CLType, CLValue, EntryPoint, Key, ProtocolVersion,
+    PublicKey, RuntimeArgs, URef, URefAddr, U128, U256, U512,
 };
 
 use crate::{
@@ -43,6 +43,42 @@ pub(super) fn instance_and_memory(
     Ok((instance, memory))
 }
 
+/// Checks `args` against the parameters declared by `entry_point`.
+///
+/// Every declared parameter must be provided with its declared type, unless its type is an
+/// `Option`, in which case it may be omitted.  Arguments not declared by the entry point are
+/// ignored.  Parameters are checked in order of their names.
+pub(super) fn validate_entry_point_args(
+    entry_point: &EntryPoint,
+    args: &RuntimeArgs,
+) -> Result<(), Error> {
+    let params: BTreeMap<&str, &CLType> = entry_point
+        .args()
+        .iter()
+        .map(|param| (param.name(), param.cl_type()))
+        .collect();
+
+    for (name, expected) in params {
+        match args.get(name) {
+            Some(arg) if arg.cl_type() != expected => {
+                return Err(Error::InvalidArgument {
+                    name: name.to_string(),
+                    expected: expected.clone(),
+                    found: arg.cl_type().clone(),
+                });
+            }
+            Some(_) => {}
+            None if expected.is_option() => {}
+            None => {
+                return Err(Error::MissingArgument {
+                    name: name.to_string(),
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Removes `rights_to_disable` from all urefs in `args` matching the address `uref_addr`.
 pub(super) fn attenuate_uref_in_args(
     mut args: RuntimeArgs,
@@ -1184,8 +1220,8 @@ mod tests {
     };
 
     use casper_types::{
-        gens::*, runtime_args, AccessRights, CLType, CLValue, Key, PublicKey, RuntimeArgs,
-        SecretKey, URef,
+        gens::*, runtime_args, AccessRights, CLType, CLValue, EntryPointAccess, EntryPointType,
+        Key, Parameter, PublicKey, RuntimeArgs, SecretKey, URef,
     };
 
     use super::*;
@@ -1430,4 +1466,44 @@ mod tests {
         ];
         assert_eq!(lhs, rhs);
     }
+
+    #[test]
+    fn should_validate_entry_point_args() {
+        let entry_point = EntryPoint::new(
+            "entry_point",
+            vec![
+                Parameter::new("amount", CLType::U512),
+                Parameter::new("memo", CLType::Option(Box::new(CLType::U64))),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        );
+
+        let args = runtime_args! { "amount" => U512::one(), "memo" => Some(1u64) };
+        assert!(validate_entry_point_args(&entry_point, &args).is_ok());
+
+        // Optional arguments may be omitted, and undeclared ones are ignored.
+        let args = runtime_args! { "amount" => U512::one(), "other" => 1u8 };
+        assert!(validate_entry_point_args(&entry_point, &args).is_ok());
+
+        let args = runtime_args! { "memo" => Some(1u64) };
+        assert!(matches!(
+            validate_entry_point_args(&entry_point, &args),
+            Err(Error::MissingArgument { name }) if name == "amount"
+        ));
+
+        let args = runtime_args! { "amount" => 1u64 };
+        assert!(matches!(
+            validate_entry_point_args(&entry_point, &args),
+            Err(Error::InvalidArgument { name, expected: CLType::U512, found: CLType::U64 })
+                if name == "amount"
+        ));
+
+        let args = runtime_args! { "amount" => U512::one(), "memo" => 1u64 };
+        assert!(matches!(
+            validate_entry_point_args(&entry_point, &args),
+            Err(Error::InvalidArgument { name, found: CLType::U64, .. }) if name == "memo"
+        ));
+    }
 }
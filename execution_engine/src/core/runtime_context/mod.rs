@@ -1,10 +1,11 @@
 //! The context of execution of WASM code.
 use std::{
     cell::RefCell,
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     convert::{TryFrom, TryInto},
     fmt::Debug,
     rc::Rc,
+    time::Instant,
 };
 
 use tracing::error;
@@ -12,15 +13,16 @@ use tracing::error;
 use casper_types::{
     account::{
         Account, AccountHash, ActionType, AddKeyFailure, RemoveKeyFailure, SetThresholdFailure,
-        UpdateKeyFailure, Weight,
+        UpdateKeyFailure, Weight, AUTHORIZATION_CONTRACT_KEY,
     },
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::auction::EraInfo,
-    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
-    ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType,
-    Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue,
-    Transfer, TransferAddr, URef, URefAddr, DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
+    AccessRights, BlockContext, BlockTime, CLType, CLValue, ContextAccessRights, Contract,
+    ContractHash, ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess,
+    EntryPointType, Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey,
+    RuntimeArgs, StoredValue, StoredValueTypeMismatch, Transfer, TransferAddr, URef, URefAddr,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
 };
 
 use crate::{
@@ -92,10 +94,11 @@ pub struct RuntimeContext<'a, R> {
     // Key pointing to the entity we are currently running
     //(could point at an account or contract in the global state)
     base_key: Key,
-    blocktime: BlockTime,
+    block_context: BlockContext,
     deploy_hash: DeployHash,
     gas_limit: Gas,
     gas_counter: Gas,
+    execution_deadline: Option<Instant>,
     address_generator: Rc<RefCell<AddressGenerator>>,
     protocol_version: ProtocolVersion,
     correlation_id: CorrelationId,
@@ -124,7 +127,7 @@ where
         authorization_keys: BTreeSet<AccountHash>,
         account: &'a Account,
         base_key: Key,
-        blocktime: BlockTime,
+        block_context: BlockContext,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         gas_counter: Gas,
@@ -144,11 +147,12 @@ where
             args: runtime_args,
             account,
             authorization_keys,
-            blocktime,
+            block_context,
             deploy_hash,
             base_key,
             gas_limit,
             gas_counter,
+            execution_deadline: None,
             address_generator,
             protocol_version,
             correlation_id,
@@ -173,10 +177,11 @@ where
         let tracking_copy = self.state();
         let authorization_keys = self.authorization_keys.clone();
         let account = self.account;
-        let blocktime = self.blocktime;
+        let block_context = self.block_context;
         let deploy_hash = self.deploy_hash;
         let gas_limit = self.gas_limit;
         let gas_counter = self.gas_counter;
+        let execution_deadline = self.execution_deadline;
         let address_generator = self.address_generator.clone();
         let protocol_version = self.protocol_version;
         let correlation_id = self.correlation_id;
@@ -184,6 +189,11 @@ where
         let engine_config = self.engine_config;
         let transfers = self.transfers.clone();
         let remaining_spending_limit = self.remaining_spending_limit();
+        // Everything called from within a view is read-only as well.
+        let entry_point_type = match self.entry_point_type {
+            EntryPointType::View => EntryPointType::View,
+            _ => entry_point_type,
+        };
 
         RuntimeContext {
             tracking_copy,
@@ -193,11 +203,12 @@ where
             args: runtime_args,
             account,
             authorization_keys,
-            blocktime,
+            block_context,
             deploy_hash,
             base_key,
             gas_limit,
             gas_counter,
+            execution_deadline,
             address_generator,
             protocol_version,
             correlation_id,
@@ -252,6 +263,7 @@ where
     /// also persistable map (one that is found in the
     /// TrackingCopy/GlobalState).
     pub fn remove_key(&mut self, name: &str) -> Result<(), Error> {
+        self.check_authorization_contract_change(name)?;
         match self.base_key() {
             account_hash @ Key::Account(_) => {
                 let account: Account = {
@@ -338,6 +350,14 @@ where
                 error!("should not remove the deploy approvals root hash key");
                 Err(Error::RemoveKeyFailure(RemoveKeyFailure::PermissionDenied))
             }
+            Key::ContractStorageIndex { .. } => {
+                error!("should not remove the contract storage index key");
+                Err(Error::RemoveKeyFailure(RemoveKeyFailure::PermissionDenied))
+            }
+            Key::NativeTokenBalance(_) => {
+                error!("should not remove the native token balance key");
+                Err(Error::RemoveKeyFailure(RemoveKeyFailure::PermissionDenied))
+            }
         }
     }
 
@@ -348,7 +368,12 @@ where
 
     /// Returns the block time.
     pub fn get_blocktime(&self) -> BlockTime {
-        self.blocktime
+        self.block_context.block_time()
+    }
+
+    /// Returns the context of the block being executed.
+    pub fn block_context(&self) -> BlockContext {
+        self.block_context
     }
 
     /// Returns the deploy hash.
@@ -405,6 +430,11 @@ where
         self.gas_counter = new_gas_counter;
     }
 
+    /// Sets the instant after which charging gas fails, regardless of the gas left.
+    pub(crate) fn set_execution_deadline(&mut self, execution_deadline: Option<Instant>) {
+        self.execution_deadline = execution_deadline;
+    }
+
     /// Returns the base key.
     ///
     /// This could be either a [`Key::Account`] or a [`Key::Hash`] depending on the entry point
@@ -446,6 +476,9 @@ where
             .new_uref(AccessRights::READ_ADD_WRITE);
         self.insert_uref(uref);
         self.metered_write_gs(Key::URef(uref), value)?;
+        if let Some(contract_hash) = self.storage_index_owner()? {
+            self.track_created_key(contract_hash, Key::URef(uref.remove_access_rights()))?;
+        }
         Ok(uref)
     }
 
@@ -460,8 +493,23 @@ where
         Ok(TransferAddr::new(transfer_addr))
     }
 
+    /// Checks that a change of the named key `name` of the current context doesn't designate a new
+    /// authorization contract for an account without meeting its key management threshold.
+    fn check_authorization_contract_change(&self, name: &str) -> Result<(), Error> {
+        if name == AUTHORIZATION_CONTRACT_KEY
+            && self.base_key().into_account().is_some()
+            && !self
+                .account()
+                .can_manage_keys_with(&self.authorization_keys)
+        {
+            return Err(Error::KeyManagementAuthorizationFailure);
+        }
+        Ok(())
+    }
+
     /// Puts `key` to the map of named keys of current context.
     pub fn put_key(&mut self, name: String, key: Key) -> Result<(), Error> {
+        self.check_authorization_contract_change(&name)?;
         // No need to perform actual validation on the base key because an account or contract (i.e.
         // the element stored under `base_key`) is allowed to add new named keys to itself.
         let named_key_value = StoredValue::CLValue(CLValue::from_t((name.clone(), key))?);
@@ -796,6 +844,8 @@ where
             Key::ChainspecRegistry => true,
             Key::BlockEffectsRootHash { .. } => true,
             Key::DeployApprovalsRootHash { .. } => true,
+            Key::ContractStorageIndex { .. } => true,
+            Key::NativeTokenBalance(_) => false,
         }
     }
 
@@ -820,6 +870,8 @@ where
             Key::ChainspecRegistry => false,
             Key::BlockEffectsRootHash { .. } => false,
             Key::DeployApprovalsRootHash { .. } => false,
+            Key::ContractStorageIndex { .. } => false,
+            Key::NativeTokenBalance(_) => false,
         }
     }
 
@@ -844,15 +896,23 @@ where
             Key::ChainspecRegistry => false,
             Key::BlockEffectsRootHash { .. } => false,
             Key::DeployApprovalsRootHash { .. } => false,
+            Key::ContractStorageIndex { .. } => false,
+            Key::NativeTokenBalance(_) => false,
         }
     }
 
     /// Safely charge the specified amount of gas, up to the available gas limit.
     ///
-    /// Returns [`Error::GasLimit`] if gas limit exceeded and `()` if not.
+    /// Returns [`Error::GasLimit`] if gas limit exceeded, [`Error::ExecutionTimeLimit`] if the
+    /// execution deadline has passed and `()` if not.
     /// Intuition about the return value sense is to answer the question 'are we
     /// allowed to continue?'
     pub(crate) fn charge_gas(&mut self, amount: Gas) -> Result<(), Error> {
+        if let Some(execution_deadline) = self.execution_deadline {
+            if Instant::now() >= execution_deadline {
+                return Err(Error::ExecutionTimeLimit);
+            }
+        }
         let prev = self.gas_counter();
         let gas_limit = self.gas_limit();
         // gas charge overflow protection
@@ -911,6 +971,14 @@ where
         self.charge_gas(amount)
     }
 
+    /// Checks that the current context may modify global state, i.e. that it is not a view.
+    fn check_writable_context(&self) -> Result<(), Error> {
+        if self.entry_point_type == EntryPointType::View {
+            return Err(Error::ReadOnlyView);
+        }
+        Ok(())
+    }
+
     /// Writes data to global state with a measurement.
     ///
     /// Use with caution - there is no validation done as the key is assumed to be validated
@@ -920,6 +988,7 @@ where
         K: Into<Key>,
         V: Into<StoredValue>,
     {
+        self.check_writable_context()?;
         let stored_value = value.into();
 
         // Charge for amount as measured by serialized length
@@ -954,6 +1023,7 @@ where
         key: Key,
         value: StoredValue,
     ) -> Result<(), Error> {
+        self.check_writable_context()?;
         let value_bytes_count = value.serialized_length();
         self.charge_gas_storage(value_bytes_count)?;
 
@@ -1254,10 +1324,90 @@ where
         };
 
         let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
+        let owner = match self.storage_index_owner()? {
+            Some(contract_hash) if self.read_gs_direct(&dictionary_key)?.is_none() => {
+                Some(contract_hash)
+            }
+            _ => None,
+        };
         self.metered_write_gs_unsafe(dictionary_key, wrapped_cl_value)?;
+        if let Some(contract_hash) = owner {
+            self.track_created_key(contract_hash, dictionary_key)?;
+        }
         Ok(())
     }
 
+    /// Returns the contract whose storage index tracks the keys created in the current context.
+    ///
+    /// Only keys created by a non-system contract while executing in its own context are tracked,
+    /// so that they can be pruned once the contract is removed.
+    fn storage_index_owner(&self) -> Result<Option<ContractHash>, Error> {
+        if self.entry_point_type != EntryPointType::Contract {
+            return Ok(None);
+        }
+        let contract_hash = match self.base_key().into_hash() {
+            Some(hash_addr) => ContractHash::new(hash_addr),
+            None => return Ok(None),
+        };
+        if self.is_system_contract(&contract_hash)? {
+            return Ok(None);
+        }
+        Ok(Some(contract_hash))
+    }
+
+    /// Adds `key` to the storage index of the contract `contract_hash`.
+    ///
+    /// Each created key is indexed under its own entry, and storing that entry is charged for like
+    /// any other write.
+    fn track_created_key(&mut self, contract_hash: ContractHash, key: Key) -> Result<(), Error> {
+        let index_key = Key::contract_storage_index(contract_hash, &key);
+        if self.read_gs_direct(&index_key)?.is_some() {
+            return Ok(());
+        }
+        self.metered_write_gs_unsafe(index_key, CLValue::from_t(key)?)
+    }
+
+    /// Reads the keys created by the contract `contract_hash`, as tracked by its storage index.
+    ///
+    /// The returned map is keyed by the index entries, each holding one of the created keys.
+    pub(crate) fn read_contract_storage_index(
+        &mut self,
+        contract_hash: ContractHash,
+    ) -> Result<BTreeMap<Key, Key>, Error> {
+        let prefix = Key::contract_storage_index_prefix(contract_hash);
+        let index_keys = self
+            .tracking_copy
+            .borrow_mut()
+            .get_keys_with_prefix(self.correlation_id, &prefix)
+            .map_err(Into::into)?;
+        let mut created_keys = BTreeMap::new();
+        for index_key in index_keys {
+            match self.read_gs_direct(&index_key)? {
+                Some(StoredValue::CLValue(cl_value)) => {
+                    created_keys.insert(index_key, cl_value.into_t()?);
+                }
+                Some(other) => {
+                    return Err(Error::TypeMismatch(StoredValueTypeMismatch::new(
+                        "CLValue".to_string(),
+                        other.type_name(),
+                    )))
+                }
+                None => {}
+            }
+        }
+        Ok(created_keys)
+    }
+
+    /// Prunes the value stored under `key` from the global state.
+    ///
+    /// # Usage
+    ///
+    /// DO NOT EXPOSE THIS VIA THE FFI - This function bypasses security checks and should be used
+    /// with caution.
+    pub(crate) fn prune_gs_unsafe(&mut self, key: Key) {
+        self.tracking_copy.borrow_mut().prune(key);
+    }
+
     /// Gets system contract by name.
     pub(crate) fn get_system_contract(&self, name: &str) -> Result<ContractHash, Error> {
         let registry = self.system_contract_registry()?;
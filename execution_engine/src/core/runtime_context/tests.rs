@@ -1,8 +1,9 @@
 use std::{
     cell::RefCell,
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     iter::{self, FromIterator},
     rc::Rc,
+    time::{Duration, Instant},
 };
 
 use once_cell::sync::Lazy;
@@ -11,12 +12,12 @@ use rand::RngCore;
 use casper_types::{
     account::{
         Account, AccountHash, ActionType, AddKeyFailure, AssociatedKeys, RemoveKeyFailure,
-        SetThresholdFailure, Weight, ACCOUNT_HASH_LENGTH,
+        SetThresholdFailure, Weight, ACCOUNT_HASH_LENGTH, AUTHORIZATION_CONTRACT_KEY,
     },
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::{AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT},
-    AccessRights, BlockTime, CLValue, ContextAccessRights, Contract, ContractHash, DeployHash,
+    AccessRights, BlockContext, CLValue, ContextAccessRights, Contract, ContractHash, DeployHash,
     EntryPointType, EntryPoints, Gas, Key, Phase, ProtocolVersion, PublicKey, RuntimeArgs,
     SecretKey, StoredValue, URef, KEY_HASH_LENGTH, U256, U512,
 };
@@ -129,7 +130,7 @@ fn new_runtime_context<'a>(
         BTreeSet::from_iter(vec![account.account_hash()]),
         account,
         base_key,
-        BlockTime::new(0),
+        BlockContext::default(),
         DeployHash::new([1u8; 32]),
         Gas::new(U512::from(GAS_LIMIT)),
         Gas::default(),
@@ -390,7 +391,7 @@ fn contract_key_addable_valid() {
         authorization_keys,
         &account,
         contract_key,
-        BlockTime::new(0),
+        BlockContext::default(),
         DeployHash::new(DEPLOY_HASH),
         Gas::new(U512::from(GAS_LIMIT)),
         Gas::default(),
@@ -426,6 +427,97 @@ fn contract_key_addable_valid() {
     );
 }
 
+#[test]
+fn contract_created_urefs_are_tracked() {
+    let account_hash = AccountHash::new([0u8; 32]);
+    let (account_key, account) = new_account(account_hash, NamedKeys::new());
+    let authorization_keys = BTreeSet::from_iter(vec![account_hash]);
+    let address_generator = AddressGenerator::new(&DEPLOY_HASH, PHASE);
+
+    let mut rng = rand::thread_rng();
+    let contract_key = random_contract_key(&mut rng);
+    let contract_hash = ContractHash::new(contract_key.into_hash().unwrap());
+    let contract = StoredValue::Contract(Contract::default());
+    let access_rights = contract
+        .as_contract()
+        .unwrap()
+        .extract_access_rights(contract_hash);
+
+    let tracking_copy = Rc::new(RefCell::new(new_tracking_copy(
+        account_key,
+        account.clone(),
+    )));
+    tracking_copy.borrow_mut().write(contract_key, contract);
+
+    let default_system_registry = {
+        let mut registry = SystemContractRegistry::new();
+        registry.insert(MINT.to_string(), ContractHash::default());
+        StoredValue::CLValue(CLValue::from_t(registry).unwrap())
+    };
+    tracking_copy
+        .borrow_mut()
+        .write(Key::SystemContractRegistry, default_system_registry);
+
+    let mut named_keys = NamedKeys::new();
+    let mut runtime_context = RuntimeContext::new(
+        Rc::clone(&tracking_copy),
+        EntryPointType::Contract,
+        &mut named_keys,
+        access_rights,
+        RuntimeArgs::new(),
+        authorization_keys,
+        &account,
+        contract_key,
+        BlockContext::default(),
+        DeployHash::new(DEPLOY_HASH),
+        Gas::new(U512::from(GAS_LIMIT)),
+        Gas::default(),
+        Rc::new(RefCell::new(address_generator)),
+        ProtocolVersion::V1_0_0,
+        CorrelationId::new(),
+        PHASE,
+        EngineConfig::default(),
+        Vec::default(),
+        U512::zero(),
+    );
+
+    let uref = runtime_context
+        .new_uref(StoredValue::CLValue(CLValue::unit()))
+        .expect("should create uref");
+
+    let index_key = Key::contract_storage_index(contract_hash, &Key::URef(uref).normalize());
+    let created_keys = runtime_context
+        .read_contract_storage_index(contract_hash)
+        .expect("should read storage index");
+    assert_eq!(
+        created_keys,
+        BTreeMap::from_iter(vec![(index_key, Key::URef(uref).normalize())])
+    );
+    // Storing the index entry is charged for on top of the created value.
+    let storage_costs = EngineConfig::default().wasm_config().storage_costs();
+    let value_length = StoredValue::CLValue(CLValue::unit()).serialized_length();
+    let index_entry_length =
+        StoredValue::CLValue(CLValue::from_t(Key::URef(uref).normalize()).unwrap())
+            .serialized_length();
+    assert_eq!(
+        runtime_context.gas_counter(),
+        storage_costs.calculate_gas_cost(value_length)
+            + storage_costs.calculate_gas_cost(index_entry_length)
+    );
+
+    // Pruning the created key and its index entry removes both from the global state.
+    runtime_context.prune_gs_unsafe(Key::URef(uref));
+    runtime_context.prune_gs_unsafe(index_key);
+    assert_eq!(
+        runtime_context.read_gs_direct(&Key::URef(uref)).unwrap(),
+        None
+    );
+    assert!(runtime_context
+        .read_contract_storage_index(contract_hash)
+        .expect("should read storage index")
+        .is_empty());
+}
+
 #[test]
 fn contract_key_addable_invalid() {
     let account_hash = AccountHash::new([0u8; 32]);
@@ -466,7 +558,7 @@ fn contract_key_addable_invalid() {
         authorization_keys,
         &account,
         other_contract_key,
-        BlockTime::new(0),
+        BlockContext::default(),
         DeployHash::new(DEPLOY_HASH),
         Gas::default(),
         Gas::default(),
@@ -797,6 +889,44 @@ fn should_verify_ownership_before_setting_action_threshold() {
     let _ = build_runtime_context_and_execute(named_keys, query);
 }
 
+#[test]
+fn should_require_key_management_weight_to_change_authorization_contract() {
+    let named_keys = NamedKeys::new();
+    let query = |mut runtime_context: RuntimeContext<InMemoryGlobalStateView>| {
+        let authorization_contract = Key::Hash([42; 32]);
+        runtime_context
+            .put_key(
+                AUTHORIZATION_CONTRACT_KEY.to_string(),
+                authorization_contract,
+            )
+            .expect("should designate authorization contract with the account's key");
+
+        // A key which is not associated with the account has no weight.
+        runtime_context.authorization_keys =
+            BTreeSet::from_iter(iter::once(AccountHash::new([84; 32])));
+        let err = runtime_context
+            .remove_key(AUTHORIZATION_CONTRACT_KEY)
+            .expect_err("should not remove authorization contract");
+        assert!(matches!(err, Error::KeyManagementAuthorizationFailure));
+        let err = runtime_context
+            .put_key(AUTHORIZATION_CONTRACT_KEY.to_string(), Key::Hash([43; 32]))
+            .expect_err("should not replace authorization contract");
+        assert!(matches!(err, Error::KeyManagementAuthorizationFailure));
+        assert_eq!(
+            runtime_context.named_keys_get(AUTHORIZATION_CONTRACT_KEY),
+            Some(&authorization_contract)
+        );
+
+        // Other named keys are not affected.
+        runtime_context
+            .put_key("other".to_string(), Key::Hash([43; 32]))
+            .expect("should put other named key");
+
+        Ok(())
+    };
+    let _ = build_runtime_context_and_execute(named_keys, query);
+}
+
 #[test]
 fn can_roundtrip_key_value_pairs() {
     let named_keys = NamedKeys::new();
@@ -1009,6 +1139,52 @@ fn should_meter_for_gas_storage_add() {
     assert_eq!(gas_usage_after, gas_usage_before + expected_add_cost);
 }
 
+#[test]
+fn should_not_modify_global_state_from_view() {
+    let mut rng = AddressGenerator::new(&DEPLOY_HASH, PHASE);
+    let uref = rng.new_uref(AccessRights::READ_ADD_WRITE);
+    let uref_as_key = Key::URef(uref);
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert("entry".to_string(), uref_as_key);
+
+    let value = StoredValue::CLValue(CLValue::from_t(43_i32).unwrap());
+
+    let result = build_runtime_context_and_execute(named_keys.clone(), |mut rc| {
+        rc.metered_write_gs(uref_as_key, value.clone())
+            .expect("should write");
+
+        let base_key = rc.base_key();
+        let mut view_named_keys = named_keys.clone();
+        let mut view = rc.new_from_self(
+            base_key,
+            EntryPointType::View,
+            &mut view_named_keys,
+            ContextAccessRights::new(base_key, vec![uref]),
+            RuntimeArgs::new(),
+        );
+        assert_eq!(view.read_gs(&uref_as_key)?, Some(value.clone()));
+        assert!(matches!(
+            view.metered_write_gs(uref_as_key, value.clone()),
+            Err(Error::ReadOnlyView)
+        ));
+
+        // Contexts derived from a view are read-only as well.
+        let mut nested_named_keys = named_keys;
+        let mut nested = view.new_from_self(
+            base_key,
+            EntryPointType::Contract,
+            &mut nested_named_keys,
+            ContextAccessRights::new(base_key, vec![uref]),
+            RuntimeArgs::new(),
+        );
+        assert_eq!(nested.entry_point_type(), EntryPointType::View);
+        nested.metered_add_gs(uref_as_key, value)
+    });
+
+    assert!(matches!(result, Err(Error::ReadOnlyView)));
+}
+
 #[test]
 fn associated_keys_add_full() {
     let final_add_result = build_runtime_context_and_execute(Default::default(), |mut rc| {
@@ -1033,3 +1209,20 @@ fn associated_keys_add_full() {
         Error::AddKeyFailure(AddKeyFailure::MaxKeysLimit)
     ));
 }
+
+#[test]
+fn should_fail_charging_gas_after_execution_deadline() {
+    let result = build_runtime_context_and_execute(Default::default(), |mut rc| {
+        rc.set_execution_deadline(Instant::now().checked_add(Duration::from_secs(3600)));
+        rc.charge_gas(Gas::new(U512::one()))
+            .expect("should charge gas before the deadline");
+        let gas_counter = rc.gas_counter();
+
+        rc.set_execution_deadline(Some(Instant::now()));
+        let result = rc.charge_gas(Gas::new(U512::one()));
+        assert_eq!(rc.gas_counter(), gas_counter);
+        result
+    });
+
+    assert!(matches!(result, Err(Error::ExecutionTimeLimit)));
+}
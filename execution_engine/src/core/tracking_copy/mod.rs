@@ -10,7 +10,11 @@ mod tests;
 use std::{
     collections::{BTreeSet, HashMap, HashSet, VecDeque},
     convert::{From, TryInto},
-    iter,
+    iter, ops,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 use linked_hash_map::LinkedHashMap;
@@ -18,21 +22,26 @@ use thiserror::Error;
 
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::{self},
+    bytesrepr::{self, ToBytes},
     CLType, CLValue, CLValueError, Key, KeyTag, StoredValue, StoredValueTypeMismatch, Tagged, U512,
 };
 
 pub use self::ext::TrackingCopyExt;
 use self::meter::{heap_meter::HeapSize, Meter};
-use super::engine_state::EngineConfig;
+use super::engine_state::{self, EngineConfig};
 use crate::{
-    core::{engine_state::execution_effect::ExecutionEffect, runtime_context::dictionary},
+    core::{
+        engine_state::execution_effect::ExecutionEffect, execution, runtime_context::dictionary,
+    },
     shared::{
         execution_journal::ExecutionJournal,
         newtypes::CorrelationId,
         transform::{self, Transform},
     },
-    storage::{global_state::StateReader, trie::merkle_proof::TrieMerkleProof},
+    storage::{
+        global_state::{CommitError, StateReader},
+        trie::merkle_proof::TrieMerkleProof,
+    },
 };
 
 /// Result of a query on a `TrackingCopy`.
@@ -137,6 +146,7 @@ pub struct TrackingCopyCache<M> {
     muts_cached: HashMap<Key, StoredValue>,
     key_tag_reads_cached: LinkedHashMap<KeyTag, BTreeSet<Key>>,
     key_tag_muts_cached: HashMap<KeyTag, BTreeSet<Key>>,
+    prunes_cached: BTreeSet<Key>,
     meter: M,
 }
 
@@ -153,6 +163,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
             muts_cached: HashMap::new(),
             key_tag_reads_cached: LinkedHashMap::new(),
             key_tag_muts_cached: HashMap::new(),
+            prunes_cached: BTreeSet::new(),
             meter,
         }
     }
@@ -191,6 +202,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
 
     /// Inserts `key` and `value` pair to Write/Add cache.
     pub fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.prunes_cached.remove(&key);
         self.muts_cached.insert(key, value);
 
         let key_set = self
@@ -201,6 +213,24 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
         key_set.insert(key);
     }
 
+    /// Inserts `key` to Prune cache, invalidating any value cached under it.
+    pub fn insert_prune(&mut self, key: Key) {
+        self.muts_cached.remove(&key);
+        if let Some(key_set) = self.key_tag_muts_cached.get_mut(&key.tag()) {
+            key_set.remove(&key);
+        }
+        if let Some(value) = self.reads_cached.remove(&key) {
+            let element_size = Meter::measure(&self.meter, &key, &value);
+            self.current_cache_size -= element_size;
+        }
+        self.prunes_cached.insert(key);
+    }
+
+    /// Returns `true` if `key` has been pruned.
+    pub fn is_pruned(&self, key: &Key) -> bool {
+        self.prunes_cached.contains(key)
+    }
+
     /// Gets value from `key` in the cache.
     pub fn get(&mut self, key: &Key) -> Option<&StoredValue> {
         if let Some(value) = self.muts_cached.get(key) {
@@ -221,6 +251,53 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
     }
 }
 
+/// Statistics of the reads served by tracking copies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadCacheStats {
+    /// Number of reads served from the cache.
+    pub hits: u64,
+    /// Number of reads passed on to the underlying reader, e.g. descending the trie.
+    pub misses: u64,
+}
+
+impl ops::Sub for ReadCacheStats {
+    type Output = ReadCacheStats;
+
+    fn sub(self, other: ReadCacheStats) -> ReadCacheStats {
+        ReadCacheStats {
+            hits: self.hits.saturating_sub(other.hits),
+            misses: self.misses.saturating_sub(other.misses),
+        }
+    }
+}
+
+/// Counters of the reads served by a tracking copy and its forks.
+///
+/// The counters can be shared between several tracking copies to aggregate their statistics.
+#[derive(Debug, Default)]
+pub struct ReadCacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ReadCacheCounters {
+    /// Returns the statistics counted so far.
+    pub fn stats(&self) -> ReadCacheStats {
+        ReadCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 /// An interface for the global state that caches all operations (reads and writes) instead of
 /// applying them directly to the state. This way the state remains unmodified, while the user can
 /// interact with it as if it was being modified in real time.
@@ -228,6 +305,7 @@ pub struct TrackingCopy<R> {
     reader: R,
     cache: TrackingCopyCache<HeapSize>,
     journal: ExecutionJournal,
+    read_cache_counters: Arc<ReadCacheCounters>,
 }
 
 /// Result of executing an "add" operation on a value in the state.
@@ -259,6 +337,15 @@ impl From<CLValueError> for AddResult {
 impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
     /// Creates a new `TrackingCopy` using the `reader` as the interface to the state.
     pub fn new(reader: R) -> TrackingCopy<R> {
+        TrackingCopy::with_read_cache_counters(reader, Default::default())
+    }
+
+    /// Creates a new `TrackingCopy` using the `reader` as the interface to the state, counting its
+    /// cache hits and misses in `read_cache_counters`.
+    pub fn with_read_cache_counters(
+        reader: R,
+        read_cache_counters: Arc<ReadCacheCounters>,
+    ) -> TrackingCopy<R> {
         TrackingCopy {
             reader,
             cache: TrackingCopyCache::new(1024 * 16, HeapSize),
@@ -266,6 +353,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
              * be fraction of wasm memory
              * limit? */
             journal: Default::default(),
+            read_cache_counters,
         }
     }
 
@@ -286,8 +374,16 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
     /// `TrackingCopy`. this means the current usage requires repeated
     /// forking, however we recognize this is sub-optimal and will revisit
     /// in the future.
+    ///
+    /// The fork counts its reads in the same `ReadCacheCounters` as this one.
     pub fn fork(&self) -> TrackingCopy<&TrackingCopy<R>> {
-        TrackingCopy::new(self)
+        TrackingCopy::with_read_cache_counters(self, Arc::clone(&self.read_cache_counters))
+    }
+
+    /// Returns the statistics of the reads served by this tracking copy, its forks and any other
+    /// tracking copies sharing its `ReadCacheCounters`.
+    pub fn read_cache_stats(&self) -> ReadCacheStats {
+        self.read_cache_counters.stats()
     }
 
     pub(super) fn get(
@@ -295,9 +391,14 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, R::Error> {
+        if self.cache.is_pruned(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.get(key) {
+            self.read_cache_counters.record_hit();
             return Ok(Some(value.to_owned()));
         }
+        self.read_cache_counters.record_miss();
         if let Some(value) = self.reader.read(correlation_id, key)? {
             self.cache.insert_read(*key, value.to_owned());
             Ok(Some(value))
@@ -327,6 +428,34 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         if let Some(keys) = self.cache.get_key_tag_muts_cached(key_tag) {
             ret.extend(keys)
         }
+        ret.retain(|key| !self.cache.is_pruned(key));
+        Ok(ret)
+    }
+
+    /// Gets the set of keys in the state whose serialized form starts with `prefix`.
+    ///
+    /// The first byte of `prefix` is expected to be the tag of the keys being looked up.
+    pub fn get_keys_with_prefix(
+        &mut self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+    ) -> Result<BTreeSet<Key>, R::Error> {
+        let mut ret: BTreeSet<Key> = self
+            .reader
+            .keys_with_prefix(correlation_id, prefix)?
+            .into_iter()
+            .collect();
+        for (key_tag, keys) in self.cache.key_tag_muts_cached.iter() {
+            if prefix.first() != Some(&(*key_tag as u8)) {
+                continue;
+            }
+            ret.extend(keys.iter().filter(|key| {
+                key.to_bytes()
+                    .map(|bytes| bytes.starts_with(prefix))
+                    .unwrap_or(false)
+            }))
+        }
+        ret.retain(|key| !self.cache.is_pruned(key));
         Ok(ret)
     }
 
@@ -353,6 +482,14 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         self.journal.push((normalized_key, Transform::Write(value)));
     }
 
+    /// Prunes the value stored under `key`. Note that the prune is only cached, and the global
+    /// state itself remains unmodified.
+    pub fn prune(&mut self, key: Key) {
+        let normalized_key = key.normalize();
+        self.cache.insert_prune(normalized_key);
+        self.journal.push((normalized_key, Transform::Prune));
+    }
+
     /// Ok(None) represents missing key to which we want to "add" some value.
     /// Ok(Some(unit)) represents successful operation.
     /// Err(error) is reserved for unexpected errors when accessing global
@@ -426,12 +563,51 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
                 Ok(AddResult::TypeMismatch(type_mismatch))
             }
             Err(transform::Error::Serialization(error)) => Ok(AddResult::Serialization(error)),
+            Err(transform::Error::Prune) => unreachable!("add transforms never prune"),
+        }
+    }
+
+    /// Applies the operations of `journal`, e.g. the effects of an execution on a fork of this
+    /// instance, as if they were committed to the state.
+    ///
+    /// Reads recorded in the journal are skipped.
+    pub fn apply_journal(
+        &mut self,
+        correlation_id: CorrelationId,
+        journal: &ExecutionJournal,
+    ) -> Result<(), engine_state::Error>
+    where
+        R::Error: Into<execution::Error>,
+    {
+        for (key, transform) in journal.iter() {
+            let new_value = match transform {
+                Transform::Identity => continue,
+                Transform::Prune => {
+                    self.cache.insert_prune(*key);
+                    self.journal.push((*key, Transform::Prune));
+                    continue;
+                }
+                Transform::Write(value) => value.clone(),
+                transform => {
+                    let current_value = self
+                        .get(correlation_id, key)
+                        .map_err(|error| engine_state::Error::Exec(error.into()))?
+                        .ok_or(CommitError::KeyNotFound(*key))?;
+                    transform
+                        .clone()
+                        .apply(current_value)
+                        .map_err(CommitError::TransformError)?
+                }
+            };
+            self.cache.insert_write(*key, new_value);
+            self.journal.push((*key, transform.clone()));
         }
+        Ok(())
     }
 
     /// Returns the execution effects cached by this instance.
     pub fn effect(&self) -> ExecutionEffect {
-        ExecutionEffect::from(self.journal.clone())
+        ExecutionEffect::from(&self.journal)
     }
 
     /// Returns the journal of operations executed on this instance.
@@ -577,9 +753,17 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
+        if self.cache.prunes_cached.contains(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.muts_cached.get(key) {
             return Ok(Some(value.to_owned()));
         }
+        // Values read by this tracking copy are shared with its forks, so that reading the same
+        // key again during a later phase of the execution doesn't descend the trie.
+        if let Some(value) = self.cache.reads_cached.get(key) {
+            return Ok(Some(value.to_owned()));
+        }
         if let Some(value) = self.reader.read(correlation_id, key)? {
             Ok(Some(value))
         } else {
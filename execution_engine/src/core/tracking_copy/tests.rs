@@ -13,7 +13,8 @@ use casper_types::{
 };
 
 use super::{
-    meter::count_meter::Count, AddResult, TrackingCopy, TrackingCopyCache, TrackingCopyQueryResult,
+    meter::count_meter::Count, AddResult, ReadCacheStats, TrackingCopy, TrackingCopyCache,
+    TrackingCopyQueryResult,
 };
 use crate::{
     core::{engine_state::EngineConfig, runtime_context::dictionary, ValidationError},
@@ -108,6 +109,34 @@ fn tracking_copy_caching() {
     assert_eq!(db_value, 1);
 }
 
+#[test]
+fn tracking_copy_read_cache_stats() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(Rc::clone(&counter));
+    let mut tc = TrackingCopy::new(db);
+    let k = Key::Hash([0u8; 32]);
+
+    tc.read(correlation_id, &k).unwrap().unwrap();
+    tc.read(correlation_id, &k).unwrap().unwrap();
+    assert_eq!(tc.read_cache_stats(), ReadCacheStats { hits: 1, misses: 1 });
+
+    // A fork misses its own cache once, but is served from the cache of its parent rather than
+    // the DB.
+    let mut fork = tc.fork();
+    fork.read(correlation_id, &k).unwrap().unwrap();
+    fork.read(correlation_id, &k).unwrap().unwrap();
+    assert_eq!(counter.get(), 1);
+    let stats = fork.read_cache_stats();
+    assert_eq!(stats, ReadCacheStats { hits: 2, misses: 2 });
+    assert_eq!(
+        stats - ReadCacheStats { hits: 1, misses: 1 },
+        ReadCacheStats { hits: 1, misses: 1 }
+    );
+    drop(fork);
+    assert_eq!(tc.read_cache_stats(), stats);
+}
+
 #[test]
 fn tracking_copy_read() {
     let correlation_id = CorrelationId::new();
@@ -264,6 +293,30 @@ fn tracking_copy_rw() {
     );
 }
 
+#[test]
+fn tracking_copy_prune() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(counter);
+    let mut tc = TrackingCopy::new(db);
+    let k = Key::Hash([0u8; 32]);
+
+    // pruning a read value should hide it from subsequent reads
+    let _ = tc.read(correlation_id, &k);
+    tc.prune(k);
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), None);
+    assert_eq!(tc.fork().read(correlation_id, &k).unwrap(), None);
+    assert_eq!(
+        tc.journal,
+        ExecutionJournal::new(vec![(k, Transform::Identity), (k, Transform::Prune)])
+    );
+
+    // writing after pruning should make the value visible again
+    let value = StoredValue::CLValue(CLValue::from_t(3_i32).unwrap());
+    tc.write(k, value.clone());
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), Some(value));
+}
+
 #[test]
 fn tracking_copy_ra() {
     let correlation_id = CorrelationId::new();
@@ -304,6 +357,45 @@ fn tracking_copy_aw() {
     );
 }
 
+#[test]
+fn tracking_copy_apply_fork_journal() {
+    let correlation_id = CorrelationId::new();
+    let k = Key::Hash([0u8; 32]);
+    let (global_state, root_hash) = InMemoryGlobalState::from_pairs(
+        correlation_id,
+        &[(k, StoredValue::CLValue(CLValue::from_t(3_i32).unwrap()))],
+    )
+    .unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let mut tc = TrackingCopy::new(view);
+
+    // the changes of a fork are visible in the parent once its journal is applied
+    let journal = {
+        let mut fork = tc.fork();
+        let _ = fork.read(correlation_id, &k);
+        let _ = fork.add(
+            correlation_id,
+            k,
+            StoredValue::CLValue(CLValue::from_t(4_i32).unwrap()),
+        );
+        fork.execution_journal()
+    };
+    tc.apply_journal(correlation_id, &journal).unwrap();
+    assert_eq!(
+        tc.read(correlation_id, &k).unwrap(),
+        Some(StoredValue::CLValue(CLValue::from_t(7_i32).unwrap()))
+    );
+    assert_eq!(
+        tc.journal,
+        ExecutionJournal::new(vec![(k, Transform::AddInt32(4)), (k, Transform::Identity)])
+    );
+
+    // applying an addition to a missing key fails
+    let missing_key = Key::Hash([1u8; 32]);
+    let journal = ExecutionJournal::new(vec![(missing_key, Transform::AddInt32(1))]);
+    assert!(tc.apply_journal(correlation_id, &journal).is_err());
+}
+
 proptest! {
     #[test]
     fn query_empty_path(k in key_arb(), missing_key in key_arb(), v in stored_value_arb()) {
@@ -578,6 +670,57 @@ fn query_for_circular_references_should_fail() {
     }
 }
 
+#[test]
+fn query_for_mutually_referencing_named_keys_should_fail() {
+    // create two contracts, each holding the other in its named keys
+    let first_key = Key::Hash([1; 32]);
+    let second_key = Key::Hash([2; 32]);
+    let first_name = "first".to_string();
+    let second_name = "second".to_string();
+    let contract = |name: &String, key: Key, addr: u8| {
+        let mut named_keys = NamedKeys::new();
+        named_keys.insert(name.clone(), key);
+        StoredValue::Contract(Contract::new(
+            [addr; 32].into(),
+            [addr + 1; 32].into(),
+            named_keys,
+            EntryPoints::default(),
+            ProtocolVersion::V1_0_0,
+        ))
+    };
+
+    let correlation_id = CorrelationId::new();
+    let (global_state, root_hash) = InMemoryGlobalState::from_pairs(
+        correlation_id,
+        &[
+            (first_key, contract(&second_name, second_key, 10)),
+            (second_key, contract(&first_name, first_key, 20)),
+        ],
+    )
+    .unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let tracking_copy = TrackingCopy::new(view);
+
+    // the cycle is detected well before reaching the depth limit
+    let engine_config = EngineConfig::default();
+    let path: Vec<_> = [&second_name, &first_name]
+        .iter()
+        .cycle()
+        .take(2 * engine_config.max_query_depth as usize)
+        .map(|name| name.to_string())
+        .collect();
+    let result = tracking_copy.query(correlation_id, &engine_config, first_key, &path);
+    if let Ok(TrackingCopyQueryResult::CircularReference(msg)) = result {
+        let expected_path_msg = format!("at path: {:?}/{}/{}", first_key, second_name, first_name);
+        assert!(msg.contains(&expected_path_msg), "{}", msg);
+    } else {
+        panic!(
+            "Query didn't fail with a circular reference error: {:?}",
+            result
+        );
+    }
+}
+
 #[test]
 fn validate_query_proof_should_work() {
     // create account
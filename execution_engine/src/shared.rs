@@ -3,6 +3,8 @@ pub mod additive_map;
 pub mod execution_journal;
 pub mod host_function_costs;
 pub mod logging;
+pub mod memory_costs;
+pub mod module_limits;
 pub mod newtypes;
 pub mod opcode_costs;
 pub mod storage_costs;
@@ -1,29 +1,31 @@
 //! Support for additive maps that add values to existing items instead of overwriting them.
+//!
+//! Entries are kept sorted by key so that iterating an `AdditiveMap`, and anything derived from
+//! it, yields the same order in every process regardless of hasher seeding.
 use std::{
     borrow::Borrow,
     collections::{
-        hash_map::{IntoIter, Iter, IterMut, Keys, RandomState, Values},
-        HashMap,
+        btree_map::{IntoIter, Iter, IterMut, Keys, Values},
+        BTreeMap,
     },
     fmt::{self, Debug, Formatter},
-    hash::{BuildHasher, Hash},
     iter::{FromIterator, IntoIterator},
     ops::{AddAssign, Index},
 };
 
-/// An associative container that looks and works like a normal `HashMap` except that, instead of
+/// An associative container that looks and works like a normal `BTreeMap` except that, instead of
 /// overwriting existing items, it adds the new value to the existing value.
 #[derive(Clone)]
-pub struct AdditiveMap<K, V, S = RandomState>(HashMap<K, V, S>);
+pub struct AdditiveMap<K, V>(BTreeMap<K, V>);
 
-impl<K: Eq + Hash, V> AdditiveMap<K, V, RandomState> {
+impl<K: Ord, V> AdditiveMap<K, V> {
     /// Creates an empty `AdditiveMap`.
     pub fn new() -> Self {
         Self(Default::default())
     }
 }
 
-impl<K: Eq + Hash, V: AddAssign + Default, S: BuildHasher> AdditiveMap<K, V, S> {
+impl<K: Ord, V: AddAssign + Default> AdditiveMap<K, V> {
     /// Modifies the existing value stored under `key`, or the default value for `V` if none, by
     /// adding `value_to_add`.
     pub fn insert_add(&mut self, key: K, value_to_add: V) {
@@ -32,18 +34,18 @@ impl<K: Eq + Hash, V: AddAssign + Default, S: BuildHasher> AdditiveMap<K, V, S>
     }
 }
 
-impl<K, V, S> AdditiveMap<K, V, S> {
-    /// An iterator visiting all keys in arbitrary order. The iterator element type is `&'a K`.
+impl<K, V> AdditiveMap<K, V> {
+    /// An iterator visiting all keys in sorted order. The iterator element type is `&'a K`.
     pub fn keys(&self) -> Keys<'_, K, V> {
         self.0.keys()
     }
 
-    /// An iterator visiting all values in arbitrary order. The iterator element type is `&'a V`.
+    /// An iterator visiting all values in key order. The iterator element type is `&'a V`.
     pub fn values(&self) -> Values<'_, K, V> {
         self.0.values()
     }
 
-    /// An iterator visiting all key-value pairs in arbitrary order. The iterator element type is
+    /// An iterator visiting all key-value pairs in key order. The iterator element type is
     /// `(&'a K, &'a V)`.
     pub fn iter(&self) -> Iter<'_, K, V> {
         self.0.iter()
@@ -60,12 +62,12 @@ impl<K, V, S> AdditiveMap<K, V, S> {
     }
 }
 
-impl<K: Eq + Hash, V, S: BuildHasher> AdditiveMap<K, V, S> {
+impl<K: Ord, V> AdditiveMap<K, V> {
     /// Returns a reference to the value corresponding to a key.
     pub fn get<Q>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Ord + ?Sized,
     {
         self.0.get(key)
     }
@@ -83,7 +85,7 @@ impl<K: Eq + Hash, V, S: BuildHasher> AdditiveMap<K, V, S> {
     pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
     where
         K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Ord + ?Sized,
     {
         self.0.remove(key)
     }
@@ -93,19 +95,19 @@ impl<K: Eq + Hash, V, S: BuildHasher> AdditiveMap<K, V, S> {
     pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
     where
         K: Borrow<Q>,
-        Q: Eq + Hash + ?Sized,
+        Q: Ord + ?Sized,
     {
         self.0.remove_entry(key)
     }
 }
 
-impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for AdditiveMap<K, V, S> {
+impl<K: Ord, V> Default for AdditiveMap<K, V> {
     fn default() -> Self {
-        Self(HashMap::with_hasher(Default::default()))
+        Self(BTreeMap::new())
     }
 }
 
-impl<'a, K, V, S> IntoIterator for &'a AdditiveMap<K, V, S> {
+impl<'a, K, V> IntoIterator for &'a AdditiveMap<K, V> {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
 
@@ -114,7 +116,7 @@ impl<'a, K, V, S> IntoIterator for &'a AdditiveMap<K, V, S> {
     }
 }
 
-impl<'a, K, V, S> IntoIterator for &'a mut AdditiveMap<K, V, S> {
+impl<'a, K, V> IntoIterator for &'a mut AdditiveMap<K, V> {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
 
@@ -123,7 +125,7 @@ impl<'a, K, V, S> IntoIterator for &'a mut AdditiveMap<K, V, S> {
     }
 }
 
-impl<K, V, S> IntoIterator for AdditiveMap<K, V, S> {
+impl<K, V> IntoIterator for AdditiveMap<K, V> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
 
@@ -132,17 +134,16 @@ impl<K, V, S> IntoIterator for AdditiveMap<K, V, S> {
     }
 }
 
-impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for AdditiveMap<K, V, S> {
+impl<K: Ord, V> FromIterator<(K, V)> for AdditiveMap<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        Self(HashMap::from_iter(iter))
+        Self(BTreeMap::from_iter(iter))
     }
 }
 
-impl<K, Q, V, S> Index<&Q> for AdditiveMap<K, V, S>
+impl<K, Q, V> Index<&Q> for AdditiveMap<K, V>
 where
-    K: Eq + Hash + Borrow<Q>,
-    Q: Eq + Hash + ?Sized,
-    S: BuildHasher,
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
 {
     type Output = V;
 
@@ -151,15 +152,15 @@ where
     }
 }
 
-impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for AdditiveMap<K, V, S> {
-    fn eq(&self, other: &AdditiveMap<K, V, S>) -> bool {
+impl<K: PartialEq, V: PartialEq> PartialEq for AdditiveMap<K, V> {
+    fn eq(&self, other: &AdditiveMap<K, V>) -> bool {
         self.0 == other.0
     }
 }
 
-impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for AdditiveMap<K, V, S> {}
+impl<K: Eq, V: Eq> Eq for AdditiveMap<K, V> {}
 
-impl<K: Eq + Hash + Debug, V: Debug, S: BuildHasher> Debug for AdditiveMap<K, V, S> {
+impl<K: Debug, V: Debug> Debug for AdditiveMap<K, V> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
@@ -185,4 +186,20 @@ mod tests {
         transform_map.insert_add(key, Transform::AddInt32(2));
         assert_eq!(Transform::AddInt32(3), transform_map[key]);
     }
+
+    #[test]
+    fn iterates_in_key_order() {
+        let map: AdditiveMap<u8, u8> = vec![(3, 0), (1, 0), (2, 0)].into_iter().collect();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let mut reversed = AdditiveMap::new();
+        for key in (1..=3).rev() {
+            reversed.insert_add(key, 0u8);
+        }
+        assert_eq!(
+            format!("{:?}", map),
+            format!("{:?}", reversed),
+            "debug output must not depend on insertion order"
+        );
+    }
 }
@@ -28,6 +28,7 @@ const DEFAULT_CALL_CONTRACT_ARGS_SIZE_WEIGHT: u32 = 420;
 const DEFAULT_CREATE_PURSE_COST: u32 = 2_500_000_000;
 const DEFAULT_GET_BALANCE_COST: u32 = 3_800;
 const DEFAULT_GET_BLOCKTIME_COST: u32 = 330;
+const DEFAULT_GET_BLOCK_CONTEXT_COST: u32 = 330;
 const DEFAULT_GET_CALLER_COST: u32 = 380;
 const DEFAULT_GET_KEY_COST: u32 = 2_000;
 const DEFAULT_GET_KEY_NAME_SIZE_WEIGHT: u32 = 440;
@@ -38,6 +39,7 @@ const DEFAULT_HAS_KEY_COST: u32 = 1_500;
 const DEFAULT_HAS_KEY_NAME_SIZE_WEIGHT: u32 = 840;
 const DEFAULT_IS_VALID_UREF_COST: u32 = 760;
 const DEFAULT_LOAD_NAMED_KEYS_COST: u32 = 42_000;
+const DEFAULT_NARROW_UREF_COST: u32 = 760;
 const DEFAULT_NEW_UREF_COST: u32 = 17_000;
 const DEFAULT_NEW_UREF_VALUE_SIZE_WEIGHT: u32 = 590;
 
@@ -59,6 +61,9 @@ const DEFAULT_REMOVE_ASSOCIATED_KEY_COST: u32 = 4_200;
 const DEFAULT_REMOVE_KEY_COST: u32 = 61_000;
 const DEFAULT_REMOVE_KEY_NAME_SIZE_WEIGHT: u32 = 3_200;
 
+const DEFAULT_REMOVE_CONTRACT_COST: u32 = 61_000;
+const DEFAULT_REMOVE_CONTRACT_MAX_KEYS_WEIGHT: u32 = 61_000;
+
 const DEFAULT_RET_COST: u32 = 23_000;
 const DEFAULT_RET_VALUE_SIZE_WEIGHT: u32 = 420;
 
@@ -286,6 +291,12 @@ pub struct HostFunctionCosts {
     pub blake2b: HostFunction<[Cost; 4]>,
     /// Cost of calling the `next address` host function.
     pub random_bytes: HostFunction<[Cost; 2]>,
+    /// Cost of calling the `remove_contract` host function.
+    pub remove_contract: HostFunction<[Cost; 6]>,
+    /// Cost of calling the `get_block_context` host function.
+    pub get_block_context: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `narrow_uref` host function.
+    pub narrow_uref: HostFunction<[Cost; 4]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -404,6 +415,19 @@ impl Default for HostFunctionCosts {
             ),
             blake2b: HostFunction::default(),
             random_bytes: HostFunction::default(),
+            remove_contract: HostFunction::new(
+                DEFAULT_REMOVE_CONTRACT_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_REMOVE_CONTRACT_MAX_KEYS_WEIGHT,
+                    NOT_USED,
+                ],
+            ),
+            get_block_context: HostFunction::fixed(DEFAULT_GET_BLOCK_CONTEXT_COST),
+            narrow_uref: HostFunction::fixed(DEFAULT_NARROW_UREF_COST),
         }
     }
 }
@@ -454,6 +478,9 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.print.to_bytes()?);
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
+        ret.append(&mut self.remove_contract.to_bytes()?);
+        ret.append(&mut self.get_block_context.to_bytes()?);
+        ret.append(&mut self.narrow_uref.to_bytes()?);
         Ok(ret)
     }
 
@@ -501,6 +528,9 @@ impl ToBytes for HostFunctionCosts {
             + self.print.serialized_length()
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
+            + self.remove_contract.serialized_length()
+            + self.get_block_context.serialized_length()
+            + self.narrow_uref.serialized_length()
     }
 }
 
@@ -549,6 +579,9 @@ impl FromBytes for HostFunctionCosts {
         let (print, rem) = FromBytes::from_bytes(rem)?;
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
+        let (remove_contract, rem) = FromBytes::from_bytes(rem)?;
+        let (get_block_context, rem) = FromBytes::from_bytes(rem)?;
+        let (narrow_uref, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -594,6 +627,9 @@ impl FromBytes for HostFunctionCosts {
                 print,
                 blake2b,
                 random_bytes,
+                remove_contract,
+                get_block_context,
+                narrow_uref,
             },
             rem,
         ))
@@ -646,6 +682,9 @@ impl Distribution<HostFunctionCosts> for Standard {
             print: rng.gen(),
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
+            remove_contract: rng.gen(),
+            get_block_context: rng.gen(),
+            narrow_uref: rng.gen(),
         }
     }
 }
@@ -706,6 +745,9 @@ pub mod gens {
             print in host_function_cost_arb(),
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
+            remove_contract in host_function_cost_arb(),
+            get_block_context in host_function_cost_arb(),
+            narrow_uref in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -751,6 +793,9 @@ pub mod gens {
                 print,
                 blake2b,
                 random_bytes,
+                remove_contract,
+                get_block_context,
+                narrow_uref,
             }
         }
     }
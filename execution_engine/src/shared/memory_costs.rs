@@ -0,0 +1,161 @@
+//! Support for Wasm memory costs.
+use datasize::DataSize;
+use rand::{distributions::Standard, prelude::*, Rng};
+use serde::{Deserialize, Serialize};
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    Gas, U512,
+};
+
+/// Default gas cost per page (64kB) added to the Wasm memory by the `memory.grow` opcode.
+pub const DEFAULT_GROW_MEMORY_PER_PAGE_COST: u32 = 240_000;
+/// Default gas cost per page (64kB) of the peak size of the Wasm memory.
+pub const DEFAULT_PEAK_MEMORY_PER_PAGE_COST: u32 = 50_000;
+
+/// Represents a cost table for Wasm memory usage.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
+pub struct MemoryCosts {
+    /// Gas charged per page added by the `memory.grow` opcode.
+    grow_memory_per_page: u32,
+    /// Gas charged per page of the peak size of the memory of a Wasm instance, charged once the
+    /// instance has finished executing.
+    peak_memory_per_page: u32,
+}
+
+impl MemoryCosts {
+    /// Creates new `MemoryCosts`.
+    pub const fn new(grow_memory_per_page: u32, peak_memory_per_page: u32) -> Self {
+        Self {
+            grow_memory_per_page,
+            peak_memory_per_page,
+        }
+    }
+
+    /// Creates new `MemoryCosts` which don't charge for memory at all.
+    pub const fn zero() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Returns amount of gas per page added by the `memory.grow` opcode.
+    pub fn grow_memory_per_page(&self) -> u32 {
+        self.grow_memory_per_page
+    }
+
+    /// Returns amount of gas per page of the peak size of the memory.
+    pub fn peak_memory_per_page(&self) -> u32 {
+        self.peak_memory_per_page
+    }
+
+    /// Calculates gas cost for a Wasm instance whose memory peaked at `pages`.
+    pub fn calculate_peak_memory_cost(&self, pages: usize) -> Gas {
+        let value = U512::from(self.peak_memory_per_page) * U512::from(pages);
+        Gas::new(value)
+    }
+}
+
+impl Default for MemoryCosts {
+    fn default() -> Self {
+        Self {
+            grow_memory_per_page: DEFAULT_GROW_MEMORY_PER_PAGE_COST,
+            peak_memory_per_page: DEFAULT_PEAK_MEMORY_PER_PAGE_COST,
+        }
+    }
+}
+
+impl Distribution<MemoryCosts> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> MemoryCosts {
+        MemoryCosts {
+            grow_memory_per_page: rng.gen(),
+            peak_memory_per_page: rng.gen(),
+        }
+    }
+}
+
+impl ToBytes for MemoryCosts {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = bytesrepr::unchecked_allocate_buffer(self);
+
+        ret.append(&mut self.grow_memory_per_page.to_bytes()?);
+        ret.append(&mut self.peak_memory_per_page.to_bytes()?);
+
+        Ok(ret)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.grow_memory_per_page.serialized_length()
+            + self.peak_memory_per_page.serialized_length()
+    }
+}
+
+impl FromBytes for MemoryCosts {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (grow_memory_per_page, rem) = FromBytes::from_bytes(bytes)?;
+        let (peak_memory_per_page, rem) = FromBytes::from_bytes(rem)?;
+
+        Ok((
+            MemoryCosts {
+                grow_memory_per_page,
+                peak_memory_per_page,
+            },
+            rem,
+        ))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use casper_types::U512;
+
+    use super::*;
+
+    const SMALL_PAGES: usize = 17;
+    const LARGE_PAGES: usize = usize::max_value();
+
+    #[test]
+    fn should_calculate_peak_memory_cost() {
+        let memory_costs = MemoryCosts::default();
+
+        let cost = memory_costs.calculate_peak_memory_cost(SMALL_PAGES);
+
+        let expected_cost = U512::from(DEFAULT_PEAK_MEMORY_PER_PAGE_COST) * U512::from(SMALL_PAGES);
+        assert_eq!(cost, Gas::new(expected_cost));
+    }
+
+    #[test]
+    fn should_calculate_big_peak_memory_cost() {
+        let memory_costs = MemoryCosts::default();
+
+        let cost = memory_costs.calculate_peak_memory_cost(LARGE_PAGES);
+
+        let expected_cost = U512::from(DEFAULT_PEAK_MEMORY_PER_PAGE_COST) * U512::from(LARGE_PAGES);
+        assert_eq!(cost, Gas::new(expected_cost));
+    }
+
+    #[test]
+    fn should_not_charge_zero_pages() {
+        let memory_costs = MemoryCosts::default();
+
+        assert_eq!(memory_costs.calculate_peak_memory_cost(0), Gas::default());
+    }
+}
+
+#[doc(hidden)]
+#[cfg(any(feature = "gens", test))]
+pub mod gens {
+    use proptest::{num, prop_compose};
+
+    use super::MemoryCosts;
+
+    prop_compose! {
+        pub fn memory_costs_arb()(
+            grow_memory_per_page in num::u32::ANY,
+            peak_memory_per_page in num::u32::ANY,
+        ) -> MemoryCosts {
+            MemoryCosts {
+                grow_memory_per_page,
+                peak_memory_per_page,
+            }
+        }
+    }
+}
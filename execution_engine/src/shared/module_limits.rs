@@ -0,0 +1,168 @@
+//! Support for limits on the structure of Wasm modules.
+use datasize::DataSize;
+use rand::{distributions::Standard, prelude::*, Rng};
+use serde::{Deserialize, Serialize};
+
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+use super::wasm_prep::DEFAULT_MAX_GLOBALS;
+
+/// Default maximum number of custom sections a module can contain.
+pub const DEFAULT_MAX_CUSTOM_SECTIONS: u32 = 16;
+/// Default maximum combined size in bytes of the names and payloads of all custom sections of a
+/// module.
+pub const DEFAULT_MAX_CUSTOM_SECTIONS_SIZE: u32 = 64 * 1024;
+/// Default maximum number of functions, both imported and defined, a module can contain.
+pub const DEFAULT_MAX_FUNCTIONS: u32 = 16 * 1024;
+
+/// Limits on the structure of Wasm modules, enforced by the preprocessor.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
+pub struct ModuleLimits {
+    /// Whether modules can have a start section, i.e. a function run on instantiation.
+    allow_start_section: bool,
+    /// Maximum number of custom sections.
+    max_custom_sections: u32,
+    /// Maximum combined size in bytes of the names and payloads of all custom sections.
+    max_custom_sections_size: u32,
+    /// Maximum number of functions, both imported and defined.
+    max_functions: u32,
+    /// Maximum number of globals.
+    max_globals: u32,
+}
+
+impl ModuleLimits {
+    /// Creates new `ModuleLimits`.
+    pub const fn new(
+        allow_start_section: bool,
+        max_custom_sections: u32,
+        max_custom_sections_size: u32,
+        max_functions: u32,
+        max_globals: u32,
+    ) -> Self {
+        Self {
+            allow_start_section,
+            max_custom_sections,
+            max_custom_sections_size,
+            max_functions,
+            max_globals,
+        }
+    }
+
+    /// Returns whether modules can have a start section.
+    pub fn allow_start_section(&self) -> bool {
+        self.allow_start_section
+    }
+
+    /// Returns the maximum number of custom sections.
+    pub fn max_custom_sections(&self) -> u32 {
+        self.max_custom_sections
+    }
+
+    /// Returns the maximum combined size in bytes of the names and payloads of all custom sections.
+    pub fn max_custom_sections_size(&self) -> u32 {
+        self.max_custom_sections_size
+    }
+
+    /// Returns the maximum number of functions.
+    pub fn max_functions(&self) -> u32 {
+        self.max_functions
+    }
+
+    /// Returns the maximum number of globals.
+    pub fn max_globals(&self) -> u32 {
+        self.max_globals
+    }
+}
+
+impl Default for ModuleLimits {
+    fn default() -> Self {
+        Self {
+            allow_start_section: false,
+            max_custom_sections: DEFAULT_MAX_CUSTOM_SECTIONS,
+            max_custom_sections_size: DEFAULT_MAX_CUSTOM_SECTIONS_SIZE,
+            max_functions: DEFAULT_MAX_FUNCTIONS,
+            max_globals: DEFAULT_MAX_GLOBALS,
+        }
+    }
+}
+
+impl Distribution<ModuleLimits> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ModuleLimits {
+        ModuleLimits {
+            allow_start_section: rng.gen(),
+            max_custom_sections: rng.gen(),
+            max_custom_sections_size: rng.gen(),
+            max_functions: rng.gen(),
+            max_globals: rng.gen(),
+        }
+    }
+}
+
+impl ToBytes for ModuleLimits {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = bytesrepr::unchecked_allocate_buffer(self);
+
+        ret.append(&mut self.allow_start_section.to_bytes()?);
+        ret.append(&mut self.max_custom_sections.to_bytes()?);
+        ret.append(&mut self.max_custom_sections_size.to_bytes()?);
+        ret.append(&mut self.max_functions.to_bytes()?);
+        ret.append(&mut self.max_globals.to_bytes()?);
+
+        Ok(ret)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.allow_start_section.serialized_length()
+            + self.max_custom_sections.serialized_length()
+            + self.max_custom_sections_size.serialized_length()
+            + self.max_functions.serialized_length()
+            + self.max_globals.serialized_length()
+    }
+}
+
+impl FromBytes for ModuleLimits {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (allow_start_section, rem) = FromBytes::from_bytes(bytes)?;
+        let (max_custom_sections, rem) = FromBytes::from_bytes(rem)?;
+        let (max_custom_sections_size, rem) = FromBytes::from_bytes(rem)?;
+        let (max_functions, rem) = FromBytes::from_bytes(rem)?;
+        let (max_globals, rem) = FromBytes::from_bytes(rem)?;
+
+        Ok((
+            ModuleLimits {
+                allow_start_section,
+                max_custom_sections,
+                max_custom_sections_size,
+                max_functions,
+                max_globals,
+            },
+            rem,
+        ))
+    }
+}
+
+#[doc(hidden)]
+#[cfg(any(feature = "gens", test))]
+pub mod gens {
+    use proptest::{bool, num, prop_compose};
+
+    use super::ModuleLimits;
+
+    prop_compose! {
+        pub fn module_limits_arb()(
+            allow_start_section in bool::ANY,
+            max_custom_sections in num::u32::ANY,
+            max_custom_sections_size in num::u32::ANY,
+            max_functions in num::u32::ANY,
+            max_globals in num::u32::ANY,
+        ) -> ModuleLimits {
+            ModuleLimits {
+                allow_start_section,
+                max_custom_sections,
+                max_custom_sections_size,
+                max_functions,
+                max_globals,
+            }
+        }
+    }
+}
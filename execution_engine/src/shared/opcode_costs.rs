@@ -83,7 +83,10 @@ pub struct OpcodeCosts {
     pub nop: u32,
     /// Get current memory operation multiplier.
     pub current_memory: u32,
-    /// Grow memory cost, per page (64kb)
+    /// Grow memory operation multiplier.
+    ///
+    /// The cost per page (64kb) added to the memory is configured in
+    /// [`MemoryCosts`](super::memory_costs::MemoryCosts).
     pub grow_memory: u32,
     /// Regular opcode cost
     pub regular: u32,
@@ -134,9 +137,7 @@ impl OpcodeCosts {
 
             tmp
         };
-        Set::new(self.regular, meterings)
-            .with_grow_cost(self.grow_memory)
-            .with_forbidden_floats()
+        Set::new(self.regular, meterings).with_forbidden_floats()
     }
 }
 
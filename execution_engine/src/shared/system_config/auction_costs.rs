@@ -30,6 +30,8 @@ pub const DEFAULT_WITHDRAW_VALIDATOR_REWARD_COST: u32 = 10_000;
 pub const DEFAULT_READ_ERA_ID_COST: u32 = 10_000;
 /// Default cost of the `activate_bid` auction entry point.
 pub const DEFAULT_ACTIVATE_BID_COST: u32 = 10_000;
+/// Default cost of the `register_successor_key` auction entry point.
+pub const DEFAULT_REGISTER_SUCCESSOR_KEY_COST: u32 = 10_000;
 
 /// Description of the costs of calling auction entrypoints.
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
@@ -60,6 +62,8 @@ pub struct AuctionCosts {
     pub read_era_id: u32,
     /// Cost of calling the `activate_bid` entry point.
     pub activate_bid: u32,
+    /// Cost of calling the `register_successor_key` entry point.
+    pub register_successor_key: u32,
 }
 
 impl Default for AuctionCosts {
@@ -78,6 +82,7 @@ impl Default for AuctionCosts {
             withdraw_validator_reward: DEFAULT_WITHDRAW_VALIDATOR_REWARD_COST,
             read_era_id: DEFAULT_READ_ERA_ID_COST,
             activate_bid: DEFAULT_ACTIVATE_BID_COST,
+            register_successor_key: DEFAULT_REGISTER_SUCCESSOR_KEY_COST,
         }
     }
 }
@@ -99,6 +104,7 @@ impl ToBytes for AuctionCosts {
         ret.append(&mut self.withdraw_validator_reward.to_bytes()?);
         ret.append(&mut self.read_era_id.to_bytes()?);
         ret.append(&mut self.activate_bid.to_bytes()?);
+        ret.append(&mut self.register_successor_key.to_bytes()?);
 
         Ok(ret)
     }
@@ -117,6 +123,7 @@ impl ToBytes for AuctionCosts {
             + self.withdraw_validator_reward.serialized_length()
             + self.read_era_id.serialized_length()
             + self.activate_bid.serialized_length()
+            + self.register_successor_key.serialized_length()
     }
 }
 
@@ -135,6 +142,7 @@ impl FromBytes for AuctionCosts {
         let (withdraw_validator_reward, rem) = FromBytes::from_bytes(rem)?;
         let (read_era_id, rem) = FromBytes::from_bytes(rem)?;
         let (activate_bid, rem) = FromBytes::from_bytes(rem)?;
+        let (register_successor_key, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             Self {
                 get_era_validators,
@@ -150,6 +158,7 @@ impl FromBytes for AuctionCosts {
                 withdraw_validator_reward,
                 read_era_id,
                 activate_bid,
+                register_successor_key,
             },
             rem,
         ))
@@ -172,6 +181,7 @@ impl Distribution<AuctionCosts> for Standard {
             withdraw_validator_reward: rng.gen(),
             read_era_id: rng.gen(),
             activate_bid: rng.gen(),
+            register_successor_key: rng.gen(),
         }
     }
 }
@@ -198,6 +208,7 @@ pub mod gens {
             withdraw_validator_reward in num::u32::ANY,
             read_era_id in num::u32::ANY,
             activate_bid in num::u32::ANY,
+            register_successor_key in num::u32::ANY,
         ) -> AuctionCosts {
             AuctionCosts {
                 get_era_validators,
@@ -213,6 +224,7 @@ pub mod gens {
                 withdraw_validator_reward,
                 read_era_id,
                 activate_bid,
+                register_successor_key,
             }
         }
     }
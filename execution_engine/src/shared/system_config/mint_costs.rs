@@ -16,6 +16,12 @@ pub const DEFAULT_BALANCE_COST: u32 = 10_000;
 pub const DEFAULT_TRANSFER_COST: u32 = 10_000;
 /// Default cost of the `read_base_round_reward` mint entry point.
 pub const DEFAULT_READ_BASE_ROUND_REWARD_COST: u32 = 10_000;
+/// Default cost of the `mint_token` mint entry point.
+pub const DEFAULT_MINT_TOKEN_COST: u32 = 2_500_000_000;
+/// Default cost of the `transfer_token` mint entry point.
+pub const DEFAULT_TRANSFER_TOKEN_COST: u32 = 10_000;
+/// Default cost of the `token_balance` mint entry point.
+pub const DEFAULT_TOKEN_BALANCE_COST: u32 = 10_000;
 
 /// Description of the costs of calling mint entry points.
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
@@ -32,6 +38,12 @@ pub struct MintCosts {
     pub transfer: u32,
     /// Cost of calling the `read_base_round_reward` entry point.
     pub read_base_round_reward: u32,
+    /// Cost of calling the `mint_token` entry point.
+    pub mint_token: u32,
+    /// Cost of calling the `transfer_token` entry point.
+    pub transfer_token: u32,
+    /// Cost of calling the `token_balance` entry point.
+    pub token_balance: u32,
 }
 
 impl Default for MintCosts {
@@ -43,6 +55,9 @@ impl Default for MintCosts {
             balance: DEFAULT_BALANCE_COST,
             transfer: DEFAULT_TRANSFER_COST,
             read_base_round_reward: DEFAULT_READ_BASE_ROUND_REWARD_COST,
+            mint_token: DEFAULT_MINT_TOKEN_COST,
+            transfer_token: DEFAULT_TRANSFER_TOKEN_COST,
+            token_balance: DEFAULT_TOKEN_BALANCE_COST,
         }
     }
 }
@@ -57,6 +72,9 @@ impl ToBytes for MintCosts {
         ret.append(&mut self.balance.to_bytes()?);
         ret.append(&mut self.transfer.to_bytes()?);
         ret.append(&mut self.read_base_round_reward.to_bytes()?);
+        ret.append(&mut self.mint_token.to_bytes()?);
+        ret.append(&mut self.transfer_token.to_bytes()?);
+        ret.append(&mut self.token_balance.to_bytes()?);
 
         Ok(ret)
     }
@@ -68,6 +86,9 @@ impl ToBytes for MintCosts {
             + self.balance.serialized_length()
             + self.transfer.serialized_length()
             + self.read_base_round_reward.serialized_length()
+            + self.mint_token.serialized_length()
+            + self.transfer_token.serialized_length()
+            + self.token_balance.serialized_length()
     }
 }
 
@@ -79,6 +100,9 @@ impl FromBytes for MintCosts {
         let (balance, rem) = FromBytes::from_bytes(rem)?;
         let (transfer, rem) = FromBytes::from_bytes(rem)?;
         let (read_base_round_reward, rem) = FromBytes::from_bytes(rem)?;
+        let (mint_token, rem) = FromBytes::from_bytes(rem)?;
+        let (transfer_token, rem) = FromBytes::from_bytes(rem)?;
+        let (token_balance, rem) = FromBytes::from_bytes(rem)?;
 
         Ok((
             Self {
@@ -88,6 +112,9 @@ impl FromBytes for MintCosts {
                 balance,
                 transfer,
                 read_base_round_reward,
+                mint_token,
+                transfer_token,
+                token_balance,
             },
             rem,
         ))
@@ -103,6 +130,9 @@ impl Distribution<MintCosts> for Standard {
             balance: rng.gen(),
             transfer: rng.gen(),
             read_base_round_reward: rng.gen(),
+            mint_token: rng.gen(),
+            transfer_token: rng.gen(),
+            token_balance: rng.gen(),
         }
     }
 }
@@ -122,6 +152,9 @@ pub mod gens {
             balance in num::u32::ANY,
             transfer in num::u32::ANY,
             read_base_round_reward in num::u32::ANY,
+            mint_token in num::u32::ANY,
+            transfer_token in num::u32::ANY,
+            token_balance in num::u32::ANY,
         ) -> MintCosts {
             MintCosts {
                 mint,
@@ -130,6 +163,9 @@ pub mod gens {
                 balance,
                 transfer,
                 read_base_round_reward,
+                mint_token,
+                transfer_token,
+                token_balance,
             }
         }
     }
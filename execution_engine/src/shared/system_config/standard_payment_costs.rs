@@ -5,7 +5,10 @@ use rand::{distributions::Standard, prelude::*, Rng};
 use serde::{Deserialize, Serialize};
 
 /// Default cost of the `pay` standard payment entry point.
-const DEFAULT_PAY_COST: u32 = 10_000;
+///
+/// Standard payment is executed natively, without instantiating a wasm interpreter, so by default
+/// it is free.
+pub const DEFAULT_PAY_COST: u32 = 0;
 
 /// Description of the costs of calling standard payment entry points.
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
@@ -32,6 +32,9 @@ pub enum Error {
     /// Type mismatch error.
     #[error("{0}")]
     TypeMismatch(StoredValueTypeMismatch),
+    /// A prune cannot be applied to a stored value.
+    #[error("cannot apply a prune to a stored value")]
+    Prune,
 }
 
 impl From<StoredValueTypeMismatch> for Error {
@@ -86,6 +89,8 @@ pub enum Transform {
     ///
     /// This transform assumes that the existing stored value is either an Account or a Contract.
     AddKeys(NamedKeys),
+    /// Removes the value from the global state.
+    Prune,
     /// Represents the case where applying a transform would cause an error.
     #[data_size(skip)]
     Failure(Error),
@@ -232,6 +237,7 @@ impl Transform {
                     Err(StoredValueTypeMismatch::new(expected, found).into())
                 }
             },
+            Transform::Prune => Err(Error::Prune),
             Transform::Failure(error) => Err(error),
         }
     }
@@ -274,6 +280,7 @@ impl Add for Transform {
             (Transform::Identity, b) => b,
             (a @ Transform::Failure(_), _) => a,
             (_, b @ Transform::Failure(_)) => b,
+            (_, b @ Transform::Prune) => b,
             (_, b @ Transform::Write(_)) => b,
             (Transform::Write(v), b) => {
                 // second transform changes value being written
@@ -304,6 +311,9 @@ impl Add for Transform {
                         .into(),
                 ),
             },
+            (Transform::Prune, other) => Transform::Failure(
+                StoredValueTypeMismatch::new("Prune".to_owned(), format!("{:?}", other)).into(),
+            ),
             (Transform::AddUInt128(i), b) => wrapped_transform_addition(i, b, "U128"),
             (Transform::AddUInt256(i), b) => wrapped_transform_addition(i, b, "U256"),
             (Transform::AddUInt512(i), b) => wrapped_transform_addition(i, b, "U512"),
@@ -357,7 +367,7 @@ impl From<&Transform> for casper_types::Transform {
                 casper_types::Transform::WriteContractPackage
             }
             Transform::Write(StoredValue::Transfer(transfer)) => {
-                casper_types::Transform::WriteTransfer(*transfer)
+                casper_types::Transform::WriteTransfer(transfer.clone())
             }
             Transform::Write(StoredValue::DeployInfo(deploy_info)) => {
                 casper_types::Transform::WriteDeployInfo(deploy_info.clone())
@@ -388,6 +398,7 @@ impl From<&Transform> for casper_types::Transform {
                     })
                     .collect(),
             ),
+            Transform::Prune => casper_types::Transform::Prune,
             Transform::Failure(error) => casper_types::Transform::Failure(error.to_string()),
         }
     }
@@ -419,6 +430,7 @@ pub mod gens {
                 buf.copy_from_slice(&u);
                 Transform::AddUInt512(buf.into())
             }),
+            Just(Transform::Prune),
         ]
     }
 }
@@ -630,6 +642,28 @@ mod tests {
         assert_yields_type_mismatch_error(cl_tuple3);
     }
 
+    #[test]
+    fn prune_should_override_preceding_transforms() {
+        let value = StoredValue::CLValue(CLValue::from_t(1_u64).unwrap());
+
+        assert_eq!(
+            Transform::Write(value.clone()) + Transform::Prune,
+            Transform::Prune
+        );
+        assert_eq!(Transform::AddUInt64(1) + Transform::Prune, Transform::Prune);
+        assert_eq!(Transform::Identity + Transform::Prune, Transform::Prune);
+        assert_eq!(Transform::Prune + Transform::Identity, Transform::Prune);
+        assert_eq!(
+            Transform::Prune + Transform::Write(value.clone()),
+            Transform::Write(value.clone())
+        );
+        assert!(matches!(
+            Transform::Prune + Transform::AddUInt64(1),
+            Transform::Failure(_)
+        ));
+        assert_eq!(Transform::Prune.apply(value), Err(Error::Prune));
+    }
+
     #[test]
     #[allow(clippy::cognitive_complexity)]
     fn wrapping_addition_should_succeed() {
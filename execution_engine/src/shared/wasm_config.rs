@@ -1,12 +1,18 @@
 //! Configuration of the Wasm execution engine.
+use std::collections::BTreeMap;
+
 use datasize::DataSize;
 use rand::{distributions::Standard, prelude::*, Rng};
 use serde::{Deserialize, Serialize};
 
-use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    ProtocolVersion,
+};
 
 use super::{
-    host_function_costs::HostFunctionCosts, opcode_costs::OpcodeCosts, storage_costs::StorageCosts,
+    host_function_costs::HostFunctionCosts, memory_costs::MemoryCosts, module_limits::ModuleLimits,
+    opcode_costs::OpcodeCosts, storage_costs::StorageCosts,
 };
 
 /// Default maximum number of pages of the Wasm memory.
@@ -28,8 +34,12 @@ pub struct WasmConfig {
     opcode_costs: OpcodeCosts,
     /// Storage costs.
     storage_costs: StorageCosts,
+    /// Wasm memory costs.
+    memory_costs: MemoryCosts,
     /// Host function costs table.
     host_function_costs: HostFunctionCosts,
+    /// Limits on the structure of Wasm modules.
+    module_limits: ModuleLimits,
 }
 
 impl WasmConfig {
@@ -39,14 +49,18 @@ impl WasmConfig {
         max_stack_height: u32,
         opcode_costs: OpcodeCosts,
         storage_costs: StorageCosts,
+        memory_costs: MemoryCosts,
         host_function_costs: HostFunctionCosts,
+        module_limits: ModuleLimits,
     ) -> Self {
         Self {
             max_memory,
             max_stack_height,
             opcode_costs,
             storage_costs,
+            memory_costs,
             host_function_costs,
+            module_limits,
         }
     }
 
@@ -60,6 +74,16 @@ impl WasmConfig {
         self.storage_costs
     }
 
+    /// Returns Wasm memory costs.
+    pub fn memory_costs(&self) -> MemoryCosts {
+        self.memory_costs
+    }
+
+    /// Returns the limits on the structure of Wasm modules.
+    pub fn module_limits(&self) -> ModuleLimits {
+        self.module_limits
+    }
+
     /// Returns host function costs and consumes this object.
     pub fn take_host_function_costs(self) -> HostFunctionCosts {
         self.host_function_costs
@@ -73,7 +97,9 @@ impl Default for WasmConfig {
             max_stack_height: DEFAULT_MAX_STACK_HEIGHT,
             opcode_costs: OpcodeCosts::default(),
             storage_costs: StorageCosts::default(),
+            memory_costs: MemoryCosts::default(),
             host_function_costs: HostFunctionCosts::default(),
+            module_limits: ModuleLimits::default(),
         }
     }
 }
@@ -86,7 +112,9 @@ impl ToBytes for WasmConfig {
         ret.append(&mut self.max_stack_height.to_bytes()?);
         ret.append(&mut self.opcode_costs.to_bytes()?);
         ret.append(&mut self.storage_costs.to_bytes()?);
+        ret.append(&mut self.memory_costs.to_bytes()?);
         ret.append(&mut self.host_function_costs.to_bytes()?);
+        ret.append(&mut self.module_limits.to_bytes()?);
 
         Ok(ret)
     }
@@ -96,7 +124,9 @@ impl ToBytes for WasmConfig {
             + self.max_stack_height.serialized_length()
             + self.opcode_costs.serialized_length()
             + self.storage_costs.serialized_length()
+            + self.memory_costs.serialized_length()
             + self.host_function_costs.serialized_length()
+            + self.module_limits.serialized_length()
     }
 }
 
@@ -106,7 +136,9 @@ impl FromBytes for WasmConfig {
         let (max_stack_height, rem) = FromBytes::from_bytes(rem)?;
         let (opcode_costs, rem) = FromBytes::from_bytes(rem)?;
         let (storage_costs, rem) = FromBytes::from_bytes(rem)?;
+        let (memory_costs, rem) = FromBytes::from_bytes(rem)?;
         let (host_function_costs, rem) = FromBytes::from_bytes(rem)?;
+        let (module_limits, rem) = FromBytes::from_bytes(rem)?;
 
         Ok((
             WasmConfig {
@@ -114,7 +146,9 @@ impl FromBytes for WasmConfig {
                 max_stack_height,
                 opcode_costs,
                 storage_costs,
+                memory_costs,
                 host_function_costs,
+                module_limits,
             },
             rem,
         ))
@@ -128,11 +162,55 @@ impl Distribution<WasmConfig> for Standard {
             max_stack_height: rng.gen(),
             opcode_costs: rng.gen(),
             storage_costs: rng.gen(),
+            memory_costs: rng.gen(),
             host_function_costs: rng.gen(),
+            module_limits: rng.gen(),
         }
     }
 }
 
+/// Wasm configurations of several protocol versions, each keyed by the protocol version it is
+/// activated at.
+///
+/// The configuration applying to a protocol version is the one with the latest activation point
+/// not after it.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct WasmConfigs {
+    configs: BTreeMap<ProtocolVersion, WasmConfig>,
+}
+
+impl WasmConfigs {
+    /// Creates an empty set of Wasm configurations.
+    pub fn new() -> Self {
+        WasmConfigs::default()
+    }
+
+    /// Activates `wasm_config` at `activation_point`, returning the configuration previously
+    /// activated at the same protocol version, if any.
+    pub fn insert(
+        &mut self,
+        activation_point: ProtocolVersion,
+        wasm_config: WasmConfig,
+    ) -> Option<WasmConfig> {
+        self.configs.insert(activation_point, wasm_config)
+    }
+
+    /// Returns the Wasm configuration applying to `protocol_version`, or `None` if none was
+    /// activated at or before it.
+    pub fn get(&self, protocol_version: ProtocolVersion) -> Option<&WasmConfig> {
+        self.configs
+            .range(..=protocol_version)
+            .next_back()
+            .map(|(_, wasm_config)| wasm_config)
+    }
+
+    /// Returns an iterator over the activation points and their Wasm configurations, in ascending
+    /// order of protocol version.
+    pub fn iter(&self) -> impl Iterator<Item = (&ProtocolVersion, &WasmConfig)> {
+        self.configs.iter()
+    }
+}
+
 #[doc(hidden)]
 #[cfg(any(feature = "gens", test))]
 pub mod gens {
@@ -140,7 +218,8 @@ pub mod gens {
 
     use super::WasmConfig;
     use crate::shared::{
-        host_function_costs::gens::host_function_costs_arb, opcode_costs::gens::opcode_costs_arb,
+        host_function_costs::gens::host_function_costs_arb, memory_costs::gens::memory_costs_arb,
+        module_limits::gens::module_limits_arb, opcode_costs::gens::opcode_costs_arb,
         storage_costs::gens::storage_costs_arb,
     };
 
@@ -150,15 +229,51 @@ pub mod gens {
             max_stack_height in num::u32::ANY,
             opcode_costs in opcode_costs_arb(),
             storage_costs in storage_costs_arb(),
+            memory_costs in memory_costs_arb(),
             host_function_costs in host_function_costs_arb(),
+            module_limits in module_limits_arb(),
         ) -> WasmConfig {
             WasmConfig {
                 max_memory,
                 max_stack_height,
                 opcode_costs,
                 storage_costs,
+                memory_costs,
                 host_function_costs,
+                module_limits,
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_select_latest_activated_wasm_config() {
+        let v1_0_0 = ProtocolVersion::from_parts(1, 0, 0);
+        let v1_1_0 = ProtocolVersion::from_parts(1, 1, 0);
+        let v1_2_0 = ProtocolVersion::from_parts(1, 2, 0);
+
+        let mut rng = rand::thread_rng();
+        let first: WasmConfig = rng.gen();
+        let second: WasmConfig = rng.gen();
+
+        let mut wasm_configs = WasmConfigs::new();
+        assert!(wasm_configs.get(v1_0_0).is_none());
+        assert!(wasm_configs.insert(v1_0_0, first).is_none());
+        assert!(wasm_configs.insert(v1_2_0, second).is_none());
+
+        assert!(wasm_configs
+            .get(ProtocolVersion::from_parts(0, 9, 0))
+            .is_none());
+        assert_eq!(wasm_configs.get(v1_0_0), Some(&first));
+        assert_eq!(wasm_configs.get(v1_1_0), Some(&first));
+        assert_eq!(wasm_configs.get(v1_2_0), Some(&second));
+        assert_eq!(
+            wasm_configs.get(ProtocolVersion::from_parts(2, 0, 0)),
+            Some(&second)
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! Preprocessing of Wasm modules.
+use std::fmt::{self, Display, Formatter};
+
 use parity_wasm::elements::{
-    self, External, Instruction, Internal, MemorySection, Module, Section, TableType, Type,
+    self, External, FuncBody, ImportCountType, Instruction, Internal, MemorySection, Module,
+    Section, TableType, Type, VarUint32,
 };
-use pwasm_utils::{self, stack_height};
+use pwasm_utils::{self, rules::InstructionType, stack_height};
 use thiserror::Error;
 
 use super::wasm_config::WasmConfig;
@@ -15,7 +18,7 @@ const INTERNAL_GAS_FUNCTION_NAME: &str = "gas";
 pub const DEFAULT_MAX_TABLE_SIZE: u32 = 4096;
 /// Maximum number of elements that can appear as immediate value to the br_table instruction.
 pub const DEFAULT_BR_TABLE_MAX_SIZE: u32 = 256;
-/// Maximum number of global a module is allowed to declare.
+/// Default maximum number of globals a module is allowed to declare.
 pub const DEFAULT_MAX_GLOBALS: u32 = 256;
 /// Maximum number of parameters a function can have.
 pub const DEFAULT_MAX_PARAMETER_COUNT: u32 = 256;
@@ -88,6 +91,91 @@ pub enum WasmValidationError {
         /// Provided index.
         index: u32,
     },
+    /// Module has a start section, which is not allowed.
+    #[error("module has a start section")]
+    StartSectionNotAllowed,
+    /// Number of custom sections exceeds allowed limit.
+    #[error("number of custom sections ({actual}) exceeds allowed limit of {max}")]
+    TooManyCustomSections {
+        /// Maximum allowed custom sections.
+        max: u32,
+        /// Actual number of custom sections in the Wasm.
+        actual: usize,
+    },
+    /// Combined size of custom sections exceeds allowed limit.
+    #[error(
+        "combined size of custom sections ({actual} bytes) exceeds allowed limit of {max} bytes"
+    )]
+    CustomSectionsTooLarge {
+        /// Maximum allowed combined size in bytes.
+        max: u32,
+        /// Actual combined size in bytes of the custom sections in the Wasm.
+        actual: usize,
+    },
+    /// Number of functions exceeds allowed limit.
+    #[error("number of functions ({actual}) exceeds allowed limit of {max}")]
+    TooManyFunctions {
+        /// Maximum allowed functions.
+        max: u32,
+        /// Actual number of imported and defined functions in the Wasm.
+        actual: usize,
+    },
+    /// Module uses a floating point instruction, which is forbidden.
+    #[error("floating point instructions are forbidden, found {0}")]
+    FloatInstruction(FloatInstruction),
+}
+
+/// A floating point instruction found in a Wasm module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatInstruction {
+    function_index: u32,
+    offset: usize,
+    instruction: Instruction,
+}
+
+impl FloatInstruction {
+    /// Returns the index of the function containing the instruction, counting imported functions.
+    pub fn function_index(&self) -> u32 {
+        self.function_index
+    }
+
+    /// Returns the byte offset of the instruction from the start of its function body.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the instruction.
+    pub fn instruction(&self) -> &Instruction {
+        &self.instruction
+    }
+}
+
+impl Display for FloatInstruction {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} in function {} at offset {}",
+            self.instruction, self.function_index, self.offset
+        )
+    }
+}
+
+/// A report on a Wasm module, listing all the problems found in it rather than only the first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreprocessingReport {
+    float_instructions: Vec<FloatInstruction>,
+}
+
+impl PreprocessingReport {
+    /// Returns all floating point instructions found in the module, in order of appearance.
+    pub fn float_instructions(&self) -> &[FloatInstruction] {
+        &self.float_instructions
+    }
+
+    /// Returns `true` if the module can be preprocessed.
+    pub fn is_valid(&self) -> bool {
+        self.float_instructions.is_empty()
+    }
 }
 
 /// An error emitted by the Wasm preprocessor.
@@ -312,6 +400,59 @@ fn ensure_global_variable_limit(module: &Module, limit: u32) -> Result<(), WasmV
     Ok(())
 }
 
+/// Ensures that module doesn't have a start section, unless explicitly allowed.
+///
+/// A start function would run on instantiation, i.e. before the host gets to call the entry point.
+fn ensure_start_section_allowed(module: &Module, allowed: bool) -> Result<(), WasmValidationError> {
+    if !allowed && module.start_section().is_some() {
+        return Err(WasmValidationError::StartSectionNotAllowed);
+    }
+    Ok(())
+}
+
+/// Ensures that module doesn't contain too many or too large custom sections.
+///
+/// Custom sections are ignored during execution, but still need to be stored and deserialized.
+fn ensure_custom_section_limits(
+    module: &Module,
+    max_sections: u32,
+    max_size: u32,
+) -> Result<(), WasmValidationError> {
+    let mut count = 0_usize;
+    let mut size = 0_usize;
+    for section in module.sections() {
+        if let Section::Custom(custom_section) = section {
+            count = count.saturating_add(1);
+            size = size
+                .saturating_add(custom_section.name().len())
+                .saturating_add(custom_section.payload().len());
+        }
+    }
+
+    if count > max_sections as usize {
+        return Err(WasmValidationError::TooManyCustomSections {
+            max: max_sections,
+            actual: count,
+        });
+    }
+    if size > max_size as usize {
+        return Err(WasmValidationError::CustomSectionsTooLarge {
+            max: max_size,
+            actual: size,
+        });
+    }
+    Ok(())
+}
+
+/// Ensures that module doesn't contain too many functions, counting both imported and defined ones.
+fn ensure_function_limit(module: &Module, limit: u32) -> Result<(), WasmValidationError> {
+    let actual = module.functions_space();
+    if actual > limit as usize {
+        return Err(WasmValidationError::TooManyFunctions { max: limit, actual });
+    }
+    Ok(())
+}
+
 /// Ensure maximum numbers of parameters a function can have.
 ///
 /// Those need to be limited to prevent a potentially exploitable interaction with
@@ -359,23 +500,94 @@ fn ensure_valid_imports(module: &Module) -> Result<(), WasmValidationError> {
     Ok(())
 }
 
-/// Preprocesses Wasm bytes and returns a module.
+/// Returns floating point instructions in the code of the module, in order of appearance.
 ///
-/// This process consists of a few steps:
-/// - Validate that the given bytes contain a memory section, and check the memory page limit.
-/// - Inject gas counters into the code, which makes it possible for the executed Wasm to be charged
-///   for opcodes; this also validates opcodes and ensures that there are no forbidden opcodes in
-///   use, such as floating point opcodes.
-/// - Ensure that the code has a maximum stack height.
-///
-/// In case the preprocessing rules can't be applied, an error is returned.
-/// Otherwise, this method returns a valid module ready to be executed safely on the host.
-pub fn preprocess(
-    wasm_config: WasmConfig,
-    module_bytes: &[u8],
-) -> Result<Module, PreprocessingError> {
-    let module = deserialize(module_bytes)?;
+/// If `stop_at_first` is set, at most one instruction is returned.
+fn find_float_instructions(
+    module: &Module,
+    stop_at_first: bool,
+) -> Result<Vec<FloatInstruction>, PreprocessingError> {
+    let bodies = module
+        .code_section()
+        .map(|code_section| code_section.bodies())
+        .unwrap_or(&[]);
+    let imported_functions = module.import_count(ImportCountType::Function) as u32;
 
+    let mut float_instructions = Vec::new();
+    for (body_index, body) in bodies.iter().enumerate() {
+        let function_index = imported_functions.saturating_add(body_index as u32);
+        // Offsets are only calculated for instructions that are found, as this requires
+        // serializing all the instructions preceding them.
+        let mut offset = None;
+        let mut offset_index = 0;
+        for (index, instruction) in body.code().elements().iter().enumerate() {
+            if !is_float_instruction(instruction) {
+                continue;
+            }
+            let mut current_offset = match offset {
+                Some(current_offset) => current_offset,
+                None => locals_length(body)?,
+            };
+            for preceding in &body.code().elements()[offset_index..index] {
+                current_offset += serialized_length(preceding.clone())?;
+            }
+            offset = Some(current_offset);
+            offset_index = index;
+
+            float_instructions.push(FloatInstruction {
+                function_index,
+                offset: current_offset,
+                instruction: instruction.clone(),
+            });
+            if stop_at_first {
+                return Ok(float_instructions);
+            }
+        }
+    }
+    Ok(float_instructions)
+}
+
+/// Returns `true` if the instruction operates on floating point values.
+fn is_float_instruction(instruction: &Instruction) -> bool {
+    matches!(
+        InstructionType::op(instruction),
+        InstructionType::Float
+            | InstructionType::FloatComparison
+            | InstructionType::FloatConst
+            | InstructionType::FloatConversion
+    )
+}
+
+/// Returns the length of the serialized local declarations at the start of a function body.
+fn locals_length(body: &FuncBody) -> Result<usize, PreprocessingError> {
+    let mut length = serialized_length(VarUint32::from(body.locals().len()))?;
+    for local in body.locals() {
+        // Each local declaration is a count followed by a single byte value type.
+        length += serialized_length(VarUint32::from(local.count()))? + 1;
+    }
+    Ok(length)
+}
+
+/// Returns the length of the given value once serialized.
+fn serialized_length<T>(value: T) -> Result<usize, PreprocessingError>
+where
+    T: elements::Serialize<Error = elements::Error>,
+{
+    let bytes = parity_wasm::serialize(value)?;
+    Ok(bytes.len())
+}
+
+/// Validates the module against the preprocessing rules which don't involve its code.
+fn validate(wasm_config: WasmConfig, module: Module) -> Result<Module, PreprocessingError> {
+    let module_limits = wasm_config.module_limits();
+
+    ensure_start_section_allowed(&module, module_limits.allow_start_section())?;
+    ensure_custom_section_limits(
+        &module,
+        module_limits.max_custom_sections(),
+        module_limits.max_custom_sections_size(),
+    )?;
+    ensure_function_limit(&module, module_limits.max_functions())?;
     ensure_valid_access(&module)?;
 
     if memory_section(&module).is_none() {
@@ -386,22 +598,69 @@ pub fn preprocess(
 
     let module = ensure_table_size_limit(module, DEFAULT_MAX_TABLE_SIZE)?;
     ensure_br_table_size_limit(&module, DEFAULT_BR_TABLE_MAX_SIZE)?;
-    ensure_global_variable_limit(&module, DEFAULT_MAX_GLOBALS)?;
+    ensure_global_variable_limit(&module, module_limits.max_globals())?;
     ensure_parameter_limit(&module, DEFAULT_MAX_PARAMETER_COUNT)?;
     ensure_valid_imports(&module)?;
+    Ok(module)
+}
 
+/// Instruments the code of a validated module with gas counters and stack height limits.
+fn instrument(wasm_config: WasmConfig, module: Module) -> Result<Module, PreprocessingError> {
     let module = pwasm_utils::externalize_mem(module, None, wasm_config.max_memory);
-    let module = pwasm_utils::inject_gas_counter(
-        module,
-        &wasm_config.opcode_costs().to_set(),
-        DEFAULT_GAS_MODULE_NAME,
-    )
-    .map_err(|_| PreprocessingError::OperationForbiddenByGasRules)?;
+    let gas_rules = wasm_config
+        .opcode_costs()
+        .to_set()
+        .with_grow_cost(wasm_config.memory_costs().grow_memory_per_page());
+    let module = pwasm_utils::inject_gas_counter(module, &gas_rules, DEFAULT_GAS_MODULE_NAME)
+        .map_err(|_| PreprocessingError::OperationForbiddenByGasRules)?;
     let module = stack_height::inject_limiter(module, wasm_config.max_stack_height)
         .map_err(|_| PreprocessingError::StackLimiter)?;
     Ok(module)
 }
 
+/// Preprocesses Wasm bytes and returns a module.
+///
+/// This process consists of a few steps:
+/// - Validate the structure of the module against the configured
+///   [`ModuleLimits`](super::module_limits::ModuleLimits), i.e. whether
+///   it has a start section, and the number of its custom sections, functions and globals.
+/// - Validate that the given bytes contain a memory section, and check the memory page limit.
+/// - Ensure that there are no floating point opcodes in use, failing on the first one found.
+/// - Inject gas counters into the code, which makes it possible for the executed Wasm to be charged
+///   for opcodes; this also validates opcodes and ensures that there are no other forbidden
+///   opcodes in use.
+/// - Ensure that the code has a maximum stack height.
+///
+/// In case the preprocessing rules can't be applied, an error is returned.
+/// Otherwise, this method returns a valid module ready to be executed safely on the host.
+pub fn preprocess(
+    wasm_config: WasmConfig,
+    module_bytes: &[u8],
+) -> Result<Module, PreprocessingError> {
+    let module = validate(wasm_config, deserialize(module_bytes)?)?;
+    if let Some(float_instruction) = find_float_instructions(&module, true)?.pop() {
+        return Err(WasmValidationError::FloatInstruction(float_instruction).into());
+    }
+    instrument(wasm_config, module)
+}
+
+/// Audits Wasm bytes against the preprocessing rules and returns a report.
+///
+/// Unlike [`preprocess`], this doesn't fail on the first floating point instruction, but lists all
+/// of them in the report, which helps locating float usage introduced by a compiler. Any other
+/// preprocessing failure is returned as an error, so a valid report means [`preprocess`] succeeds.
+pub fn audit(
+    wasm_config: WasmConfig,
+    module_bytes: &[u8],
+) -> Result<PreprocessingReport, PreprocessingError> {
+    let module = validate(wasm_config, deserialize(module_bytes)?)?;
+    let float_instructions = find_float_instructions(&module, false)?;
+    if float_instructions.is_empty() {
+        instrument(wasm_config, module)?;
+    }
+    Ok(PreprocessingReport { float_instructions })
+}
+
 /// Returns a parity Module from the given bytes without making modifications or checking limits.
 pub fn deserialize(module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
     parity_wasm::deserialize_buffer::<Module>(module_bytes).map_err(Into::into)
@@ -412,10 +671,71 @@ mod tests {
     use casper_types::contracts::DEFAULT_ENTRY_POINT_NAME;
     use parity_wasm::{
         builder,
-        elements::{CodeSection, Instructions},
+        elements::{CodeSection, CustomSection, Instructions, Local, ValueType},
     };
 
     use super::*;
+    use crate::shared::{
+        host_function_costs::HostFunctionCosts,
+        memory_costs::MemoryCosts,
+        module_limits::{
+            ModuleLimits, DEFAULT_MAX_CUSTOM_SECTIONS, DEFAULT_MAX_CUSTOM_SECTIONS_SIZE,
+            DEFAULT_MAX_FUNCTIONS,
+        },
+        opcode_costs::OpcodeCosts,
+        storage_costs::StorageCosts,
+        wasm_config::{DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+    };
+
+    fn wasm_config_allowing_start_section() -> WasmConfig {
+        WasmConfig::new(
+            DEFAULT_WASM_MAX_MEMORY,
+            DEFAULT_MAX_STACK_HEIGHT,
+            OpcodeCosts::default(),
+            StorageCosts::default(),
+            MemoryCosts::default(),
+            HostFunctionCosts::default(),
+            ModuleLimits::new(
+                true,
+                DEFAULT_MAX_CUSTOM_SECTIONS,
+                DEFAULT_MAX_CUSTOM_SECTIONS_SIZE,
+                DEFAULT_MAX_FUNCTIONS,
+                DEFAULT_MAX_GLOBALS,
+            ),
+        )
+    }
+
+    /// Returns a module with a single exported function, optionally run as start function, and the
+    /// given custom sections.
+    fn build_module_bytes(start: bool, custom_sections: Vec<CustomSection>) -> Vec<u8> {
+        let mut module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::Nop, Instruction::End]))
+            .build()
+            .build()
+            .export()
+            .field(DEFAULT_ENTRY_POINT_NAME)
+            .internal()
+            .func(0)
+            .build()
+            .memory()
+            .build()
+            .build();
+        let sections = module.sections_mut();
+        if start {
+            // The start section has to precede the code section.
+            let code_section_index = sections
+                .iter()
+                .position(|section| matches!(section, Section::Code(_)))
+                .expect("should have code section");
+            sections.insert(code_section_index, Section::Start(0));
+        }
+        sections.extend(custom_sections.into_iter().map(Section::Custom));
+        parity_wasm::serialize(module).expect("should serialize")
+    }
 
     #[test]
     fn should_not_panic_on_empty_memory() {
@@ -552,7 +872,7 @@ mod tests {
             .build();
         let module_bytes = parity_wasm::serialize(module).expect("should serialize");
 
-        let error = preprocess(WasmConfig::default(), &module_bytes)
+        let error = preprocess(wasm_config_allowing_start_section(), &module_bytes)
             .expect_err("should fail with an error");
         assert!(
             matches!(
@@ -574,7 +894,7 @@ mod tests {
             .build()
             .build();
         let module_bytes = parity_wasm::serialize(module).expect("should serialize");
-        let error = preprocess(WasmConfig::default(), &module_bytes)
+        let error = preprocess(wasm_config_allowing_start_section(), &module_bytes)
             .expect_err("should fail with an error");
         assert!(
             matches!(
@@ -586,4 +906,184 @@ mod tests {
             error,
         );
     }
+
+    #[test]
+    fn should_reject_start_section_by_default() {
+        let module_bytes = build_module_bytes(true, vec![]);
+
+        let error = preprocess(WasmConfig::default(), &module_bytes)
+            .expect_err("should fail with an error");
+        assert!(
+            matches!(
+                &error,
+                PreprocessingError::WasmValidation(WasmValidationError::StartSectionNotAllowed)
+            ),
+            "{:?}",
+            error,
+        );
+
+        preprocess(wasm_config_allowing_start_section(), &module_bytes)
+            .expect("should accept start section if allowed");
+    }
+
+    #[test]
+    fn should_reject_too_many_custom_sections() {
+        let custom_section = CustomSection::new("custom".to_string(), vec![]);
+
+        let module_bytes = build_module_bytes(
+            false,
+            vec![custom_section.clone(); DEFAULT_MAX_CUSTOM_SECTIONS as usize],
+        );
+        preprocess(WasmConfig::default(), &module_bytes)
+            .expect("should accept custom sections up to the limit");
+
+        let module_bytes = build_module_bytes(
+            false,
+            vec![custom_section; DEFAULT_MAX_CUSTOM_SECTIONS as usize + 1],
+        );
+        let error = preprocess(WasmConfig::default(), &module_bytes)
+            .expect_err("should fail with an error");
+        assert!(
+            matches!(
+                &error,
+                PreprocessingError::WasmValidation(WasmValidationError::TooManyCustomSections { max, actual })
+                if *max == DEFAULT_MAX_CUSTOM_SECTIONS && *actual == DEFAULT_MAX_CUSTOM_SECTIONS as usize + 1
+            ),
+            "{:?}",
+            error,
+        );
+    }
+
+    #[test]
+    fn should_reject_too_large_custom_sections() {
+        let payload = vec![0; DEFAULT_MAX_CUSTOM_SECTIONS_SIZE as usize];
+        let module_bytes =
+            build_module_bytes(false, vec![CustomSection::new("a".to_string(), payload)]);
+
+        let error = preprocess(WasmConfig::default(), &module_bytes)
+            .expect_err("should fail with an error");
+        assert!(
+            matches!(
+                &error,
+                PreprocessingError::WasmValidation(WasmValidationError::CustomSectionsTooLarge { max, actual })
+                if *max == DEFAULT_MAX_CUSTOM_SECTIONS_SIZE && *actual == DEFAULT_MAX_CUSTOM_SECTIONS_SIZE as usize + 1
+            ),
+            "{:?}",
+            error,
+        );
+    }
+
+    #[test]
+    fn should_reject_too_many_functions() {
+        let mut module_builder = builder::module();
+        for _ in 0..=DEFAULT_MAX_FUNCTIONS {
+            module_builder = module_builder
+                .function()
+                .signature()
+                .build()
+                .body()
+                .with_instructions(Instructions::new(vec![Instruction::End]))
+                .build()
+                .build();
+        }
+        let module = module_builder.memory().build().build();
+        let module_bytes = parity_wasm::serialize(module).expect("should serialize");
+
+        let error = preprocess(WasmConfig::default(), &module_bytes)
+            .expect_err("should fail with an error");
+        assert!(
+            matches!(
+                &error,
+                PreprocessingError::WasmValidation(WasmValidationError::TooManyFunctions { max, actual })
+                if *max == DEFAULT_MAX_FUNCTIONS && *actual == DEFAULT_MAX_FUNCTIONS as usize + 1
+            ),
+            "{:?}",
+            error,
+        );
+    }
+
+    /// Returns a module whose second function uses floats, along with the expected float
+    /// instructions.
+    fn build_module_with_floats() -> (Vec<u8>, Vec<FloatInstruction>) {
+        let module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::End]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_locals(vec![Local::new(2, ValueType::I64)])
+            .with_instructions(Instructions::new(vec![
+                Instruction::F32Const(0),
+                Instruction::Drop,
+                Instruction::I32Const(0),
+                Instruction::Drop,
+                Instruction::F64Const(0),
+                Instruction::F64Neg,
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .memory()
+            .build()
+            .build();
+        let module_bytes = parity_wasm::serialize(module).expect("should serialize");
+
+        // Local declarations take 3 bytes, `f32.const` 5 bytes, `i32.const 0` 2 bytes, `f64.const`
+        // 9 bytes and `drop` 1 byte.
+        let expected = vec![
+            FloatInstruction {
+                function_index: 1,
+                offset: 3,
+                instruction: Instruction::F32Const(0),
+            },
+            FloatInstruction {
+                function_index: 1,
+                offset: 12,
+                instruction: Instruction::F64Const(0),
+            },
+            FloatInstruction {
+                function_index: 1,
+                offset: 21,
+                instruction: Instruction::F64Neg,
+            },
+        ];
+        (module_bytes, expected)
+    }
+
+    #[test]
+    fn should_reject_first_float_instruction() {
+        let (module_bytes, expected) = build_module_with_floats();
+
+        let error = preprocess(WasmConfig::default(), &module_bytes)
+            .expect_err("should fail with an error");
+        assert!(
+            matches!(
+                &error,
+                PreprocessingError::WasmValidation(WasmValidationError::FloatInstruction(float_instruction))
+                if *float_instruction == expected[0]
+            ),
+            "{:?}",
+            error,
+        );
+    }
+
+    #[test]
+    fn should_report_all_float_instructions() {
+        let (module_bytes, expected) = build_module_with_floats();
+
+        let report = audit(WasmConfig::default(), &module_bytes).expect("should audit");
+        assert!(!report.is_valid());
+        assert_eq!(report.float_instructions(), expected.as_slice());
+
+        let module_bytes = build_module_bytes(false, vec![]);
+        let report = audit(WasmConfig::default(), &module_bytes).expect("should audit");
+        assert!(report.is_valid());
+    }
 }
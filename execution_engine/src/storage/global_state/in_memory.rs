@@ -4,7 +4,7 @@ use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{bytesrepr::Bytes, Key, StoredValue};
 
 use crate::{
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    shared::{newtypes::CorrelationId, transform::Transform},
     storage::{
         error::{self, in_memory},
         global_state::{commit, CommitProvider, StateProvider, StateReader},
@@ -201,12 +201,15 @@ impl StateReader<Key, StoredValue> for InMemoryGlobalStateView {
 }
 
 impl CommitProvider for InMemoryGlobalState {
-    fn commit(
+    fn commit<I>(
         &self,
         correlation_id: CorrelationId,
         prestate_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<Digest, Self::Error> {
+        effects: I,
+    ) -> Result<Digest, Self::Error>
+    where
+        I: IntoIterator<Item = (Key, Transform)>,
+    {
         commit::<InMemoryEnvironment, InMemoryTrieStore, _, Self::Error>(
             &self.environment,
             &self.trie_store,
@@ -323,6 +326,7 @@ mod tests {
     use casper_types::{account::AccountHash, CLValue};
 
     use super::*;
+    use crate::shared::additive_map::AdditiveMap;
 
     #[derive(Debug, Clone)]
     struct TestPair {
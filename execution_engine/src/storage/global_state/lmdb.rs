@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     ops::Deref,
     sync::{Arc, RwLock},
 };
@@ -9,7 +9,7 @@ use casper_types::{bytesrepr::Bytes, Key, StoredValue};
 use tracing::trace;
 
 use crate::{
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    shared::{newtypes::CorrelationId, transform::Transform},
     storage::{
         error,
         global_state::{
@@ -95,12 +95,13 @@ impl LmdbGlobalState {
         )
     }
 
-    /// Write stored values to LMDB.
+    /// Write stored values to LMDB and prune the given keys from it.
     pub fn put_stored_values(
         &self,
         correlation_id: CorrelationId,
         prestate_hash: Digest,
         stored_values: HashMap<Key, StoredValue>,
+        pruned_keys: BTreeSet<Key>,
     ) -> Result<Digest, error::Error> {
         let scratch_trie = self.get_scratch_store();
         let new_state_root = put_stored_values::<_, _, error::Error>(
@@ -109,6 +110,7 @@ impl LmdbGlobalState {
             correlation_id,
             prestate_hash,
             stored_values,
+            pruned_keys,
         )?;
         scratch_trie.write_root_to_db(new_state_root)?;
         Ok(new_state_root)
@@ -209,20 +211,27 @@ impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
 }
 
 impl CommitProvider for LmdbGlobalState {
-    fn commit(
+    fn commit<I>(
         &self,
         correlation_id: CorrelationId,
         prestate_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<Digest, Self::Error> {
-        commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
-            &self.environment,
-            &self.trie_store,
+        effects: I,
+    ) -> Result<Digest, Self::Error>
+    where
+        I: IntoIterator<Item = (Key, Transform)>,
+    {
+        // Stage the new tries in a scratch trie store, so that tries superseded by later writes in
+        // the same commit are never persisted and shared tries are written only once.
+        let scratch_trie = self.get_scratch_store();
+        let new_state_root = commit::<_, _, _, Self::Error>(
+            &scratch_trie,
+            &scratch_trie,
             correlation_id,
             prestate_hash,
             effects,
-        )
-        .map_err(Into::into)
+        )?;
+        scratch_trie.write_root_to_db(new_state_root)?;
+        Ok(new_state_root)
     }
 }
 
@@ -374,6 +383,8 @@ impl StateProvider for LmdbGlobalState {
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+
     use lmdb::DatabaseFlags;
     use tempfile::tempdir;
 
@@ -381,9 +392,12 @@ mod tests {
     use casper_types::{account::AccountHash, bytesrepr, CLValue};
 
     use super::*;
-    use crate::storage::{
-        trie_store::operations::{write, WriteResult},
-        DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+    use crate::{
+        shared::additive_map::AdditiveMap,
+        storage::{
+            trie_store::operations::{write, WriteResult},
+            DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+        },
     };
 
     #[derive(Debug, Clone)]
@@ -570,6 +584,66 @@ mod tests {
         );
     }
 
+    /// Returns the hashes of all tries stored in `state`.
+    fn stored_trie_hashes(state: &LmdbGlobalState) -> HashSet<Digest> {
+        let txn = state.environment.create_read_txn().unwrap();
+        let hashes = {
+            let mut cursor =
+                lmdb::Transaction::open_ro_cursor(&txn, state.trie_store.get_db()).unwrap();
+            lmdb::Cursor::iter(&mut cursor)
+                .map(|(hash, _)| Digest::try_from(hash).unwrap())
+                .collect()
+        };
+        txn.commit().unwrap();
+        hashes
+    }
+
+    /// Returns the hashes of all tries reachable from `root_hash`.
+    fn reachable_trie_hashes(state: &LmdbGlobalState, root_hash: Digest) -> HashSet<Digest> {
+        let txn = state.environment.create_read_txn().unwrap();
+        let mut reachable = HashSet::new();
+        let mut to_visit = vec![root_hash];
+        while let Some(hash) = to_visit.pop() {
+            let trie: Trie<Key, StoredValue> = state.trie_store.get(&txn, &hash).unwrap().unwrap();
+            to_visit.extend(trie.iter_descendants());
+            reachable.insert(hash);
+        }
+        txn.commit().unwrap();
+        reachable
+    }
+
+    #[test]
+    fn commit_writes_only_tries_reachable_from_new_root() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let tries_before = stored_trie_hashes(&state);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        // Intermediate tries, created while writing all but the last of the updated values, must
+        // not have been persisted.
+        let new_tries: HashSet<_> = stored_trie_hashes(&state)
+            .difference(&tries_before)
+            .copied()
+            .collect();
+        let expected_new_tries: HashSet<_> = reachable_trie_hashes(&state, updated_hash)
+            .difference(&tries_before)
+            .copied()
+            .collect();
+        assert!(!new_tries.is_empty());
+        assert_eq!(new_tries, expected_new_tries);
+    }
+
     #[test]
     fn returns_trie_or_chunk() {
         let correlation_id = CorrelationId::new();
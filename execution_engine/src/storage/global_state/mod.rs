@@ -9,7 +9,7 @@ pub mod lmdb;
 /// Lmdb implementation of global state with cache.
 pub mod scratch;
 
-use std::{collections::HashMap, hash::BuildHasher};
+use std::collections::{BTreeSet, HashMap};
 
 use tracing::error;
 
@@ -18,15 +18,14 @@ use casper_types::{bytesrepr, bytesrepr::Bytes, Key, StoredValue};
 
 use crate::{
     shared::{
-        additive_map::AdditiveMap,
         newtypes::CorrelationId,
         transform::{self, Transform},
     },
     storage::{
-        transaction_source::{Transaction, TransactionSource},
+        transaction_source::{Readable, Transaction, TransactionSource, Writable},
         trie::{merkle_proof::TrieMerkleProof, Trie, TrieOrChunk, TrieOrChunkId},
         trie_store::{
-            operations::{read, write, ReadResult, WriteResult},
+            operations::{delete, read, write, DeleteResult, ReadResult, WriteResult},
             TrieStore,
         },
     },
@@ -82,12 +81,19 @@ pub enum CommitError {
 pub trait CommitProvider: StateProvider {
     /// Applies changes and returns a new post state hash.
     /// block_hash is used for computing a deterministic and unique keys.
-    fn commit(
+    ///
+    /// The transforms are applied in order as they are streamed from `effects`, so a key may occur
+    /// more than once, e.g. when committing an [`ExecutionJournal`] without merging it first.
+    ///
+    /// [`ExecutionJournal`]: crate::shared::execution_journal::ExecutionJournal
+    fn commit<I>(
         &self,
         correlation_id: CorrelationId,
         state_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<Digest, Self::Error>;
+        effects: I,
+    ) -> Result<Digest, Self::Error>
+    where
+        I: IntoIterator<Item = (Key, Transform)>;
 }
 
 /// A trait expressing operations over the trie.
@@ -129,13 +135,15 @@ pub trait StateProvider {
     ) -> Result<Vec<Digest>, Self::Error>;
 }
 
-/// Write multiple key/stored value pairs to the store in a single rw transaction.
+/// Write multiple key/stored value pairs to the store and prune the given keys from it in a single
+/// rw transaction.
 pub fn put_stored_values<'a, R, S, E>(
     environment: &'a R,
     store: &S,
     correlation_id: CorrelationId,
     prestate_hash: Digest,
     stored_values: HashMap<Key, StoredValue>,
+    pruned_keys: BTreeSet<Key>,
 ) -> Result<Digest, E>
 where
     R: TransactionSource<'a, Handle = S::Handle>,
@@ -163,24 +171,53 @@ where
             }
         }
     }
+    for key in pruned_keys.iter() {
+        state_root = prune::<_, _, E>(correlation_id, &mut txn, store, state_root, key)?;
+    }
     txn.commit()?;
     Ok(state_root)
 }
 
-/// Commit `effects` to the store.
-pub fn commit<'a, R, S, H, E>(
+/// Deletes `key` from the trie under `state_root`, returning the new state root.
+///
+/// Pruning a key which doesn't exist leaves the state root unchanged.
+fn prune<T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    state_root: Digest,
+    key: &Key,
+) -> Result<Digest, E>
+where
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error> + From<CommitError>,
+{
+    match delete::<Key, StoredValue, _, _, E>(correlation_id, txn, store, &state_root, key)? {
+        DeleteResult::Deleted(root_hash) => Ok(root_hash),
+        DeleteResult::DoesNotExist => Ok(state_root),
+        DeleteResult::RootNotFound => {
+            error!(?state_root, ?key, "Error pruning value");
+            Err(CommitError::WriteRootNotFound(state_root).into())
+        }
+    }
+}
+
+/// Commit `effects` to the store, applying the transforms in order.
+pub fn commit<'a, R, S, I, E>(
     environment: &'a R,
     store: &S,
     correlation_id: CorrelationId,
     prestate_hash: Digest,
-    effects: AdditiveMap<Key, Transform, H>,
+    effects: I,
 ) -> Result<Digest, E>
 where
     R: TransactionSource<'a, Handle = S::Handle>,
     S: TrieStore<Key, StoredValue>,
     S::Error: From<R::Error>,
     E: From<R::Error> + From<S::Error> + From<bytesrepr::Error> + From<CommitError>,
-    H: BuildHasher,
+    I: IntoIterator<Item = (Key, Transform)>,
 {
     let mut txn = environment.create_read_write_txn()?;
     let mut state_root = prestate_hash;
@@ -191,10 +228,14 @@ where
         return Err(CommitError::RootNotFound(prestate_hash).into());
     };
 
-    for (key, transform) in effects.into_iter() {
+    for (key, transform) in effects {
         let read_result = read::<_, _, _, _, E>(correlation_id, &txn, store, &state_root, &key)?;
 
         let value = match (read_result, transform) {
+            (_, Transform::Prune) => {
+                state_root = prune::<_, _, E>(correlation_id, &mut txn, store, state_root, &key)?;
+                continue;
+            }
             (ReadResult::NotFound, Transform::Write(new_value)) => new_value,
             (ReadResult::NotFound, transform) => {
                 error!(
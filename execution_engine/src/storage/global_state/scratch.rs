@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     mem,
     ops::Deref,
     sync::{Arc, RwLock},
@@ -11,7 +11,7 @@ use casper_hashing::{ChunkWithProof, Digest};
 use casper_types::{bytesrepr::Bytes, Key, StoredValue};
 
 use crate::{
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    shared::{newtypes::CorrelationId, transform::Transform},
     storage::{
         error,
         global_state::{CommitError, CommitProvider, StateProvider, StateReader},
@@ -31,19 +31,31 @@ type SharedCache = Arc<RwLock<Cache>>;
 
 struct Cache {
     cached_values: HashMap<Key, (bool, StoredValue)>,
+    pruned_keys: BTreeSet<Key>,
 }
 
 impl Cache {
     fn new() -> Self {
         Cache {
             cached_values: HashMap::new(),
+            pruned_keys: BTreeSet::new(),
         }
     }
 
     fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.pruned_keys.remove(&key);
         self.cached_values.insert(key, (true, value));
     }
 
+    fn insert_prune(&mut self, key: Key) {
+        self.cached_values.remove(&key);
+        self.pruned_keys.insert(key);
+    }
+
+    fn is_pruned(&self, key: &Key) -> bool {
+        self.pruned_keys.contains(key)
+    }
+
     fn insert_read(&mut self, key: Key, value: StoredValue) {
         self.cached_values.entry(key).or_insert((false, value));
     }
@@ -53,12 +65,14 @@ impl Cache {
     }
 
     /// Consumes self and returns only written values as values that were only read must be filtered
-    /// out to prevent unnecessary writes.
-    fn into_dirty_writes(self) -> HashMap<Key, StoredValue> {
-        self.cached_values
+    /// out to prevent unnecessary writes, along with the pruned keys.
+    fn into_dirty_writes(self) -> (HashMap<Key, StoredValue>, BTreeSet<Key>) {
+        let writes = self
+            .cached_values
             .into_iter()
             .filter_map(|(key, (dirty, value))| if dirty { Some((key, value)) } else { None })
-            .collect()
+            .collect();
+        (writes, self.pruned_keys)
     }
 }
 
@@ -102,8 +116,8 @@ impl ScratchGlobalState {
         }
     }
 
-    /// Consume self and return inner cache.
-    pub fn into_inner(self) -> HashMap<Key, StoredValue> {
+    /// Consume self and return inner cache, i.e. the written values and the pruned keys.
+    pub fn into_inner(self) -> (HashMap<Key, StoredValue>, BTreeSet<Key>) {
         let cache = mem::replace(&mut *self.cache.write().unwrap(), Cache::new());
         cache.into_dirty_writes()
     }
@@ -117,8 +131,14 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
-        if let Some(value) = self.cache.read().unwrap().get(key) {
-            return Ok(Some(value.clone()));
+        {
+            let cache = self.cache.read().unwrap();
+            if cache.is_pruned(key) {
+                return Ok(None);
+            }
+            if let Some(value) = cache.get(key) {
+                return Ok(Some(value.clone()));
+            }
         }
         let txn = self.environment.create_read_txn()?;
         let ret = match read::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
@@ -194,16 +214,31 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
 impl CommitProvider for ScratchGlobalState {
     /// State hash returned is the one provided, as we do not write to lmdb with this kind of global
     /// state. Note that the state hash is NOT used, and simply passed back to the caller.
-    fn commit(
+    fn commit<I>(
         &self,
         correlation_id: CorrelationId,
         state_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<Digest, Self::Error> {
+        effects: I,
+    ) -> Result<Digest, Self::Error>
+    where
+        I: IntoIterator<Item = (Key, Transform)>,
+    {
         for (key, transform) in effects.into_iter() {
             let cached_value = self.cache.read().unwrap().get(&key).cloned();
             let value = match (cached_value, transform) {
+                (_, Transform::Prune) => {
+                    self.cache.write().unwrap().insert_prune(key);
+                    continue;
+                }
                 (None, Transform::Write(new_value)) => new_value,
+                (None, transform) if self.cache.read().unwrap().is_pruned(&key) => {
+                    error!(
+                        ?key,
+                        ?transform,
+                        "Key pruned while attempting to apply transform"
+                    );
+                    return Err(CommitError::KeyNotFound(key).into());
+                }
                 (None, transform) => {
                     // It might be the case that for `Add*` operations we don't have the previous
                     // value in cache yet.
@@ -359,6 +394,8 @@ impl StateProvider for ScratchGlobalState {
 
 #[cfg(test)]
 mod tests {
+    use std::iter;
+
     use lmdb::DatabaseFlags;
     use tempfile::tempdir;
 
@@ -366,10 +403,13 @@ mod tests {
     use casper_types::{account::AccountHash, CLValue};
 
     use super::*;
-    use crate::storage::{
-        global_state::{lmdb::LmdbGlobalState, CommitProvider},
-        trie_store::operations::{write, WriteResult},
-        DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+    use crate::{
+        shared::{additive_map::AdditiveMap, execution_journal::ExecutionJournal},
+        storage::{
+            global_state::{lmdb::LmdbGlobalState, CommitProvider},
+            trie_store::operations::{write, WriteResult},
+            DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+        },
     };
 
     #[derive(Debug, Clone)]
@@ -512,7 +552,8 @@ mod tests {
             .keys_with_prefix(correlation_id, &[])
             .unwrap();
 
-        let stored_values = scratch.into_inner();
+        let (stored_values, pruned_keys) = scratch.into_inner();
+        assert!(pruned_keys.is_empty());
         assert_eq!(all_keys.len(), stored_values.len());
 
         for key in all_keys {
@@ -594,6 +635,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn commit_streamed_journal_matches_merged_effects() {
+        let correlation_id = CorrelationId::new();
+        let key = create_test_pairs()[0].key;
+        let journal = ExecutionJournal::new(vec![
+            (key, Transform::Identity),
+            (key, Transform::AddInt32(2)),
+            (create_test_pairs()[1].key, Transform::Identity),
+            (key, Transform::AddInt32(3)),
+        ]);
+
+        let TestState { state, root_hash } = create_test_state();
+        let scratch = state.create_scratch();
+        scratch
+            .commit(correlation_id, root_hash, journal.clone())
+            .unwrap();
+
+        let merged_effects = AdditiveMap::from(journal);
+        assert_eq!(merged_effects.len(), 2);
+        let lmdb_hash = state
+            .commit(correlation_id, root_hash, merged_effects)
+            .unwrap();
+
+        let scratch_checkout = scratch.checkout(root_hash).unwrap().unwrap();
+        let lmdb_checkout = state.checkout(lmdb_hash).unwrap().unwrap();
+        let expected = StoredValue::CLValue(CLValue::from_t(6_i32).unwrap());
+        assert_eq!(
+            scratch_checkout.read(correlation_id, &key).unwrap(),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            lmdb_checkout.read(correlation_id, &key).unwrap(),
+            Some(expected)
+        );
+
+        let (stored_values, pruned_keys) = scratch.into_inner();
+        let scratch_hash = state
+            .put_stored_values(correlation_id, root_hash, stored_values, pruned_keys)
+            .unwrap();
+        assert_eq!(scratch_hash, lmdb_hash);
+    }
+
+    #[test]
+    fn commit_prunes_state() {
+        let correlation_id = CorrelationId::new();
+        let pruned_key = create_test_pairs()[0].key;
+
+        let TestState { state, root_hash } = create_test_state();
+
+        let scratch = state.create_scratch();
+
+        let mut effects = AdditiveMap::new();
+        effects.insert(pruned_key, Transform::Prune);
+
+        scratch
+            .commit(correlation_id, root_hash, effects.clone())
+            .unwrap();
+        let scratch_checkout = scratch.checkout(root_hash).unwrap().unwrap();
+        assert_eq!(
+            None,
+            scratch_checkout.read(correlation_id, &pruned_key).unwrap()
+        );
+
+        let lmdb_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+        let lmdb_checkout = state.checkout(lmdb_hash).unwrap().unwrap();
+        assert_eq!(
+            None,
+            lmdb_checkout.read(correlation_id, &pruned_key).unwrap()
+        );
+
+        let (stored_values, pruned_keys) = scratch.into_inner();
+        assert!(stored_values.is_empty());
+        assert_eq!(pruned_keys, iter::once(pruned_key).collect());
+
+        let scratch_hash = state
+            .put_stored_values(correlation_id, root_hash, stored_values, pruned_keys)
+            .unwrap();
+        assert_eq!(scratch_hash, lmdb_hash);
+    }
+
     #[test]
     fn commit_updates_state_and_original_state_stays_intact() {
         let correlation_id = CorrelationId::new();
@@ -1,7 +1,7 @@
 pub(crate) mod detail;
 pub(crate) mod providers;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use num_rational::Ratio;
 use num_traits::{CheckedMul, CheckedSub};
@@ -23,9 +23,13 @@ pub trait Auction:
 {
     /// Returns active validators and auction winners for a number of future eras determined by the
     /// configured auction_delay.
+    ///
+    /// Validators which rotated their signing key are identified by the key they currently sign
+    /// with.
     fn get_era_validators(&mut self) -> Result<EraValidators, Error> {
         let snapshot = detail::get_seigniorage_recipients_snapshot(self)?;
-        let era_validators = detail::era_validators_from_snapshot(snapshot);
+        let signing_keys = detail::get_signing_keys(self)?;
+        let era_validators = detail::era_validators_from_snapshot(snapshot, &signing_keys);
         Ok(era_validators)
     }
 
@@ -55,7 +59,8 @@ pub trait Auction:
     /// its stake.
     ///
     /// Validators cannot create a bid with 0 amount, and the delegation rate can't exceed
-    /// [`DELEGATION_RATE_DENOMINATOR`].
+    /// [`DELEGATION_RATE_DENOMINATOR`].  A new bid can't be created for a key which another
+    /// validator signs with, or has registered as its successor key.
     ///
     /// Returns a [`U512`] value indicating total amount of tokens staked for given `public_key`.
     fn add_bid(
@@ -109,6 +114,11 @@ pub trait Auction:
                 updated_amount
             }
             None => {
+                // A new validator can't bid with a key another validator signs with, or is about
+                // to sign with.
+                if detail::is_signing_key_of_other_validator(self, &public_key, &public_key)? {
+                    return Err(Error::SigningKeyInUse.into());
+                }
                 let bonding_purse = self.create_purse()?;
                 self.mint_transfer_direct(
                     Some(PublicKey::System.to_account_hash()),
@@ -174,8 +184,9 @@ pub trait Auction:
         )?;
 
         if updated_stake.is_zero() {
-            // Automatically unbond delegators
-            for (delegator_public_key, delegator) in bid.delegators() {
+            // Automatically unbond delegators, along with their unclaimed rewards
+            let validator_bonding_purse = *bid.bonding_purse();
+            for (delegator_public_key, delegator) in bid.delegators_mut().iter_mut() {
                 detail::create_unbonding_purse(
                     self,
                     public_key.clone(),
@@ -184,6 +195,12 @@ pub trait Auction:
                     *delegator.staked_amount(),
                     None,
                 )?;
+                detail::claim_delegator_rewards(
+                    self,
+                    public_key.clone(),
+                    validator_bonding_purse,
+                    delegator,
+                )?;
             }
 
             *bid.delegators_mut() = BTreeMap::new();
@@ -267,13 +284,14 @@ pub trait Auction:
             None => return Err(Error::ValidatorNotFound),
         };
 
+        let validator_bonding_purse = *bid.bonding_purse();
         let delegators = bid.delegators_mut();
 
         let new_amount = match delegators.get_mut(&delegator_public_key) {
             Some(delegator) => {
                 detail::create_unbonding_purse(
                     self,
-                    validator_public_key,
+                    validator_public_key.clone(),
                     delegator_public_key.clone(),
                     *delegator.bonding_purse(),
                     amount,
@@ -283,6 +301,13 @@ pub trait Auction:
                 let era_end_timestamp_millis = detail::get_era_end_timestamp_millis(self)?;
                 let updated_stake = delegator.decrease_stake(amount, era_end_timestamp_millis)?;
                 if updated_stake == U512::zero() {
+                    // Rewards still owed to the delegator are paid out before its entry is gone.
+                    detail::claim_delegator_rewards(
+                        self,
+                        validator_public_key,
+                        validator_bonding_purse,
+                        delegator,
+                    )?;
                     delegators.remove(&delegator_public_key);
                 };
                 updated_stake
@@ -330,13 +355,14 @@ pub trait Auction:
             None => return Err(Error::ValidatorNotFound),
         };
 
+        let validator_bonding_purse = *bid.bonding_purse();
         let delegators = bid.delegators_mut();
 
         let new_amount = match delegators.get_mut(&delegator_public_key) {
             Some(delegator) => {
                 detail::create_unbonding_purse(
                     self,
-                    validator_public_key,
+                    validator_public_key.clone(),
                     delegator_public_key.clone(),
                     *delegator.bonding_purse(),
                     amount,
@@ -346,6 +372,13 @@ pub trait Auction:
                 let era_end_timestamp_millis = detail::get_era_end_timestamp_millis(self)?;
                 let updated_stake = delegator.decrease_stake(amount, era_end_timestamp_millis)?;
                 if updated_stake == U512::zero() {
+                    // Rewards still owed to the delegator are paid out before its entry is gone.
+                    detail::claim_delegator_rewards(
+                        self,
+                        validator_public_key,
+                        validator_bonding_purse,
+                        delegator,
+                    )?;
                     delegators.remove(&delegator_public_key);
                 };
                 updated_stake
@@ -358,7 +391,8 @@ pub trait Auction:
         Ok(new_amount)
     }
 
-    /// Slashes each validator.
+    /// Slashes each validator.  Validators can be given by the key they signed consensus messages
+    /// with.
     ///
     /// This can be only invoked through a system call.
     fn slash(&mut self, validator_public_keys: Vec<PublicKey>) -> Result<(), Error> {
@@ -366,18 +400,23 @@ pub trait Auction:
             return Err(Error::InvalidCaller);
         }
 
+        let signing_keys = detail::get_signing_keys(self)?;
         let mut burned_amount: U512 = U512::zero();
 
-        for validator_public_key in validator_public_keys {
+        for signing_key in validator_public_keys {
+            let validator_public_key =
+                detail::validator_for_signing_key(&signing_keys, signing_key);
             // Burn stake, deactivate
             let validator_account_hash = AccountHash::from(&validator_public_key);
             if let Some(mut bid) = self.read_bid(&validator_account_hash)? {
                 burned_amount += *bid.staked_amount();
                 *bid.staked_amount_mut() = U512::zero();
                 bid.deactivate();
-                // Reset delegator stakes when deactivating validator bid.
+                // Reset delegator stakes and burn their unclaimed rewards, which are held in the
+                // validator's bonding purse, when deactivating validator bid.
                 for delegator in bid.delegators_mut().values_mut() {
                     *delegator.staked_amount_mut() = U512::zero();
+                    burned_amount += delegator.take_unclaimed_rewards();
                 }
                 self.write_bid(validator_account_hash, bid)?;
             };
@@ -403,6 +442,9 @@ pub trait Auction:
     /// added to their delegators') ordered by size from largest to smallest, then takes the top N
     /// (number of auction slots) bidders and replaces era_validators with these.
     ///
+    /// Successor keys registered during the era that just ended become the validators' signing
+    /// keys.
+    ///
     /// Accessed by: node
     fn run_auction(
         &mut self,
@@ -419,6 +461,13 @@ pub trait Auction:
         let snapshot_size = auction_delay as usize + 1;
         let mut era_id: EraId = detail::get_era_id(self)?;
         let mut bids = detail::get_bids(self)?;
+        let mut signing_keys = detail::get_signing_keys(self)?;
+
+        // Evicted validators are reported by the key they signed with during the ended era.
+        let evicted_validators: BTreeSet<PublicKey> = evicted_validators
+            .into_iter()
+            .map(|signing_key| detail::validator_for_signing_key(&signing_keys, signing_key))
+            .collect();
 
         // Process unbond requests
         detail::process_unbond_requests(self)?;
@@ -516,6 +565,20 @@ pub trait Auction:
             detail::set_seigniorage_recipients_snapshot(self, snapshot)?;
         }
 
+        // Switch to successor signing keys at the era boundary
+        let successor_keys = detail::get_successor_keys(self)?;
+        if !successor_keys.is_empty() {
+            for (validator_public_key, successor_public_key) in successor_keys {
+                if validator_public_key == successor_public_key {
+                    signing_keys.remove(&validator_public_key);
+                } else {
+                    signing_keys.insert(validator_public_key, successor_public_key);
+                }
+            }
+            detail::set_signing_keys(self, signing_keys)?;
+            detail::set_successor_keys(self, BTreeMap::new())?;
+        }
+
         detail::set_era_id(self, era_id)?;
         detail::set_era_end_timestamp_millis(self, era_end_timestamp_millis)?;
 
@@ -536,11 +599,13 @@ pub trait Auction:
         let seigniorage_recipients = self.read_seigniorage_recipients()?;
         let base_round_reward = self.read_base_round_reward()?;
         let era_id = detail::get_era_id(self)?;
+        let signing_keys = detail::get_signing_keys(self)?;
 
         let mut era_info = EraInfo::new();
         let seigniorage_allocations = era_info.seigniorage_allocations_mut();
 
-        for (public_key, reward_factor) in reward_factors {
+        for (signing_key, reward_factor) in reward_factors {
+            let public_key = detail::validator_for_signing_key(&signing_keys, signing_key);
             let recipient = seigniorage_recipients
                 .get(&public_key)
                 .ok_or(Error::ValidatorNotFound)?;
@@ -588,16 +653,12 @@ pub trait Auction:
                         let reward = delegators_part * reward_multiplier;
                         (delegator_key.clone(), reward)
                     });
-            let delegator_payouts = detail::reinvest_delegator_rewards(
+            let total_delegator_payout = detail::accrue_delegator_rewards(
                 self,
                 seigniorage_allocations,
                 public_key.clone(),
                 delegator_rewards,
             )?;
-            let total_delegator_payout: U512 = delegator_payouts
-                .iter()
-                .map(|(_delegator_hash, amount, _bonding_purse)| *amount)
-                .sum();
 
             let validators_part: Ratio<U512> = total_reward - Ratio::from(total_delegator_payout);
             let validator_reward = validators_part.to_integer();
@@ -608,13 +669,12 @@ pub trait Auction:
                 validator_reward,
             )?;
 
-            self.mint_into_existing_purse(validator_reward, validator_bonding_purse)
+            // Delegator rewards are held in the validator's bonding purse until claimed.
+            let total_payout = validator_reward
+                .checked_add(total_delegator_payout)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.mint_into_existing_purse(total_payout, validator_bonding_purse)
                 .map_err(Error::from)?;
-
-            for (_delegator_account_hash, delegator_payout, bonding_purse) in delegator_payouts {
-                self.mint_into_existing_purse(delegator_payout, bonding_purse)
-                    .map_err(Error::from)?;
-            }
         }
 
         self.record_era_info(era_id, era_info)?;
@@ -648,4 +708,97 @@ pub trait Auction:
 
         Ok(())
     }
+
+    /// Registers a successor key the given validator will sign consensus messages with from the
+    /// next era onwards.  The validator keeps its bid, delegators and position in the auction.
+    ///
+    /// Registering the validator's own public key switches back to signing with it.
+    fn register_successor_key(
+        &mut self,
+        validator_public_key: PublicKey,
+        successor_public_key: PublicKey,
+    ) -> Result<(), Error> {
+        let provided_account_hash =
+            AccountHash::from_public_key(&validator_public_key, |x| self.blake2b(x));
+
+        if !self.is_allowed_session_caller(&provided_account_hash) {
+            return Err(Error::InvalidContext);
+        }
+
+        if self.read_bid(&provided_account_hash)?.is_none() {
+            return Err(Error::ValidatorNotFound);
+        }
+
+        if successor_public_key == PublicKey::System {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        if successor_public_key != validator_public_key {
+            // The successor key must not identify another validator, nor be used by one.
+            let successor_account_hash =
+                AccountHash::from_public_key(&successor_public_key, |x| self.blake2b(x));
+            let is_in_use = self.read_bid(&successor_account_hash)?.is_some()
+                || detail::is_signing_key_of_other_validator(
+                    self,
+                    &validator_public_key,
+                    &successor_public_key,
+                )?;
+            if is_in_use {
+                return Err(Error::SigningKeyInUse);
+            }
+        }
+
+        let mut successor_keys = detail::get_successor_keys(self)?;
+        successor_keys.insert(validator_public_key, successor_public_key);
+        detail::set_successor_keys(self, successor_keys)?;
+
+        Ok(())
+    }
+
+    /// Claims the rewards accrued to a delegator of the given validator.
+    ///
+    /// Delegator rewards are accrued on the validator's bid at the end of each era rather than
+    /// being paid out to every delegator.  Claiming them creates an unbonding request, so that
+    /// they are transferred to the delegator's main purse once the unbonding delay has passed.
+    ///
+    /// Returns the amount of motes claimed.
+    fn claim_delegator_rewards(
+        &mut self,
+        delegator_public_key: PublicKey,
+        validator_public_key: PublicKey,
+    ) -> Result<U512, Error> {
+        let provided_account_hash =
+            AccountHash::from_public_key(&delegator_public_key, |x| self.blake2b(x));
+
+        if !self.is_allowed_session_caller(&provided_account_hash) {
+            return Err(Error::InvalidContext);
+        }
+
+        let validator_account_hash = AccountHash::from(&validator_public_key);
+
+        let mut bid = match self.read_bid(&validator_account_hash)? {
+            Some(bid) => bid,
+            None => return Err(Error::ValidatorNotFound),
+        };
+
+        let validator_bonding_purse = *bid.bonding_purse();
+
+        let delegator = bid
+            .delegators_mut()
+            .get_mut(&delegator_public_key)
+            .ok_or(Error::DelegatorNotFound)?;
+
+        let claimed_amount = detail::claim_delegator_rewards(
+            self,
+            validator_public_key,
+            validator_bonding_purse,
+            delegator,
+        )?;
+
+        if !claimed_amount.is_zero() {
+            self.write_bid(validator_account_hash, bid)?;
+        }
+
+        Ok(claimed_amount)
+    }
 }
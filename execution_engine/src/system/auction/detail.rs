@@ -6,9 +6,10 @@ use casper_types::{
     account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
     system::auction::{
-        Bids, Delegator, Error, SeigniorageAllocation, SeigniorageRecipientsSnapshot,
+        Bids, Delegator, Error, SeigniorageAllocation, SeigniorageRecipientsSnapshot, SigningKeys,
         UnbondingPurse, UnbondingPurses, AUCTION_DELAY_KEY, ERA_END_TIMESTAMP_MILLIS_KEY,
-        ERA_ID_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
+        ERA_ID_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, SIGNING_KEYS_KEY, SUCCESSOR_KEYS_KEY,
+        UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
     },
     ApiError, CLTyped, EraId, Key, KeyTag, PublicKey, URef, U512,
 };
@@ -38,6 +39,41 @@ where
     provider.write(uref, value)
 }
 
+/// Reads a value stored under a named key which may not exist yet, returning the default value in
+/// that case.
+fn read_from_or_default<P, T>(provider: &mut P, name: &str) -> Result<T, Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+    T: FromBytes + CLTyped + Default,
+{
+    match provider.named_keys_get(name) {
+        Some(key) => {
+            let uref = key.into_uref().ok_or(Error::InvalidKeyVariant)?;
+            let value: T = provider.read(uref)?.ok_or(Error::MissingValue)?;
+            Ok(value)
+        }
+        None => Ok(T::default()),
+    }
+}
+
+/// Writes a value under a named key, creating the named key first if it doesn't exist yet.
+fn write_to_or_create<P, T>(provider: &mut P, name: &str, value: T) -> Result<(), Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+    T: ToBytes + CLTyped,
+{
+    match provider.named_keys_get(name) {
+        Some(key) => {
+            let uref = key.into_uref().ok_or(Error::InvalidKeyVariant)?;
+            provider.write(uref, value)
+        }
+        None => {
+            let uref = provider.new_uref(value)?;
+            provider.named_keys_put(name, uref.into())
+        }
+    }
+}
+
 pub fn get_bids<P>(provider: &mut P) -> Result<Bids, Error>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -92,19 +128,6 @@ where
     Ok(ret)
 }
 
-pub fn set_unbonding_purses<P>(
-    provider: &mut P,
-    unbonding_purses: UnbondingPurses,
-) -> Result<(), Error>
-where
-    P: StorageProvider + RuntimeProvider + ?Sized,
-{
-    for (account_hash, unbonding_purses) in unbonding_purses.into_iter() {
-        provider.write_unbond(account_hash, unbonding_purses)?;
-    }
-    Ok(())
-}
-
 pub fn get_era_id<P>(provider: &mut P) -> Result<EraId, Error>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -159,6 +182,70 @@ where
     write_to(provider, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, snapshot)
 }
 
+/// Returns the signing keys validators use in the current era.
+///
+/// Networks upgraded from a version without key rotation don't have the named key until the first
+/// successor key is registered, in which case no validator has rotated its key yet.
+pub fn get_signing_keys<P>(provider: &mut P) -> Result<SigningKeys, Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, SIGNING_KEYS_KEY)
+}
+
+pub fn set_signing_keys<P>(provider: &mut P, signing_keys: SigningKeys) -> Result<(), Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_to_or_create(provider, SIGNING_KEYS_KEY, signing_keys)
+}
+
+/// Returns the successor signing keys which take effect at the next era boundary.
+pub fn get_successor_keys<P>(provider: &mut P) -> Result<SigningKeys, Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, SUCCESSOR_KEYS_KEY)
+}
+
+pub fn set_successor_keys<P>(provider: &mut P, successor_keys: SigningKeys) -> Result<(), Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_to_or_create(provider, SUCCESSOR_KEYS_KEY, successor_keys)
+}
+
+/// Returns whether `signing_key` is the current or successor signing key of a validator other than
+/// `validator_public_key`.
+pub(crate) fn is_signing_key_of_other_validator<P>(
+    provider: &mut P,
+    validator_public_key: &PublicKey,
+    signing_key: &PublicKey,
+) -> Result<bool, Error>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    let signing_keys = get_signing_keys(provider)?;
+    let successor_keys = get_successor_keys(provider)?;
+    Ok(signing_keys
+        .iter()
+        .chain(successor_keys.iter())
+        .any(|(validator, key)| validator != validator_public_key && key == signing_key))
+}
+
+/// Maps a key used for signing consensus messages back to the public key identifying the
+/// validator.
+pub(crate) fn validator_for_signing_key(
+    signing_keys: &SigningKeys,
+    signing_key: PublicKey,
+) -> PublicKey {
+    signing_keys
+        .iter()
+        .find(|(_, key)| **key == signing_key)
+        .map(|(validator, _)| validator.clone())
+        .unwrap_or(signing_key)
+}
+
 pub fn get_validator_slots<P>(provider: &mut P) -> Result<usize, Error>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -188,6 +275,10 @@ where
 /// Iterates over unbonding entries and checks if a locked amount can be paid already if
 /// a specific era is reached.
 ///
+/// At most `max_unbondings_per_step` matured purses are paid out per call, bounding the cost of
+/// a single era-end step.  Matured purses exceeding that batch stay in their unbonding lists and
+/// are paid out by the following steps.
+///
 /// This function can be called by the system only.
 pub(crate) fn process_unbond_requests<P: Auction + ?Sized>(
     provider: &mut P,
@@ -197,19 +288,27 @@ pub(crate) fn process_unbond_requests<P: Auction + ?Sized>(
     }
 
     // Update `unbonding_purses` data
-    let mut unbonding_purses: UnbondingPurses = get_unbonding_purses(provider)?;
+    let unbonding_purses: UnbondingPurses = get_unbonding_purses(provider)?;
 
     let current_era_id = provider.read_era_id()?;
 
     let unbonding_delay = get_unbonding_delay(provider)?;
 
-    for unbonding_list in unbonding_purses.values_mut() {
+    let mut remaining_in_batch = provider.max_unbondings_per_step();
+
+    for (validator_account_hash, unbonding_list) in unbonding_purses {
+        if remaining_in_batch == 0 {
+            break;
+        }
         let mut new_unbonding_list = Vec::new();
         for unbonding_purse in unbonding_list.iter() {
             // Since `process_unbond_requests` is run before `run_auction`, we should check if
             // current era id + unbonding delay is equal or greater than the `era_of_creation` that
             // was calculated on `unbond` attempt.
-            if current_era_id >= unbonding_purse.era_of_creation() + unbonding_delay {
+            if remaining_in_batch > 0
+                && current_era_id >= unbonding_purse.era_of_creation() + unbonding_delay
+            {
+                remaining_in_batch -= 1;
                 match unbonding_purse.new_validator() {
                     Some(new_validator) => {
                         match provider.read_bid(&new_validator.to_account_hash()) {
@@ -252,10 +351,12 @@ pub(crate) fn process_unbond_requests<P: Auction + ?Sized>(
                 new_unbonding_list.push(unbonding_purse.clone());
             }
         }
-        *unbonding_list = new_unbonding_list;
+        // Only lists with paid out purses need to be written back.
+        if new_unbonding_list.len() < unbonding_list.len() {
+            provider.write_unbond(validator_account_hash, new_unbonding_list)?;
+        }
     }
 
-    set_unbonding_purses(provider, unbonding_purses)?;
     Ok(())
 }
 
@@ -290,17 +391,22 @@ pub(crate) fn create_unbonding_purse<P: Auction + ?Sized>(
     Ok(())
 }
 
-/// Reinvests delegator reward by increasing its stake.
-pub fn reinvest_delegator_rewards<P>(
+/// Accrues delegator rewards as unclaimed rewards on the validator's bid, to be claimed by each
+/// delegator later on.  Returns the total amount accrued, which is to be minted into the
+/// validator's bonding purse.
+///
+/// All delegators are credited through a single write of the validator's bid, keeping the size of
+/// the era end effects independent of the number of delegators.
+pub fn accrue_delegator_rewards<P>(
     provider: &mut P,
     seigniorage_allocations: &mut Vec<SeigniorageAllocation>,
     validator_public_key: PublicKey,
     rewards: impl Iterator<Item = (PublicKey, Ratio<U512>)>,
-) -> Result<Vec<(AccountHash, U512, URef)>, Error>
+) -> Result<U512, Error>
 where
     P: StorageProvider,
 {
-    let mut delegator_payouts = Vec::new();
+    let mut total_delegator_payout = U512::zero();
 
     let validator_account_hash = AccountHash::from(&validator_public_key);
 
@@ -319,13 +425,11 @@ where
 
         let delegator_reward_trunc = delegator_reward.to_integer();
 
-        delegator.increase_stake(delegator_reward_trunc)?;
+        delegator.add_unclaimed_rewards(delegator_reward_trunc)?;
 
-        delegator_payouts.push((
-            delegator_key.to_account_hash(),
-            delegator_reward_trunc,
-            *delegator.bonding_purse(),
-        ));
+        total_delegator_payout = total_delegator_payout
+            .checked_add(delegator_reward_trunc)
+            .ok_or(Error::ArithmeticOverflow)?;
 
         let allocation = SeigniorageAllocation::delegator(
             delegator_key,
@@ -338,7 +442,35 @@ where
 
     provider.write_bid(validator_account_hash, bid)?;
 
-    Ok(delegator_payouts)
+    Ok(total_delegator_payout)
+}
+
+/// Claims the unclaimed rewards of a delegator by creating an unbonding request which pays them
+/// out of the validator's bonding purse once the unbonding delay has passed.  Returns the claimed
+/// amount.
+///
+/// The caller is responsible for writing back the bid the delegator belongs to.
+pub(crate) fn claim_delegator_rewards<P: Auction + ?Sized>(
+    provider: &mut P,
+    validator_public_key: PublicKey,
+    validator_bonding_purse: URef,
+    delegator: &mut Delegator,
+) -> Result<U512, Error> {
+    let amount = delegator.take_unclaimed_rewards();
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+
+    create_unbonding_purse(
+        provider,
+        validator_public_key,
+        delegator.delegator_public_key().clone(),
+        validator_bonding_purse,
+        amount,
+        None,
+    )?;
+
+    Ok(amount)
 }
 
 /// Reinvests validator reward by increasing its stake and returns its bonding purse.
@@ -457,19 +589,24 @@ where
     Ok(bid)
 }
 
-/// Returns the era validators from a snapshot.
+/// Returns the era validators from a snapshot, each identified by the key it signs consensus
+/// messages with.
 ///
 /// This is `pub` as it is used not just in the relevant auction entry point, but also by the
 /// engine state while directly querying for the era validators.
 pub(crate) fn era_validators_from_snapshot(
     snapshot: SeigniorageRecipientsSnapshot,
+    signing_keys: &SigningKeys,
 ) -> EraValidators {
     snapshot
         .into_iter()
         .map(|(era_id, recipients)| {
             let validator_weights = recipients
                 .into_iter()
-                .filter_map(|(public_key, bid)| bid.total_stake().map(|stake| (public_key, stake)))
+                .filter_map(|(public_key, bid)| {
+                    let signing_key = signing_keys.get(&public_key).cloned().unwrap_or(public_key);
+                    bid.total_stake().map(|stake| (signing_key, stake))
+                })
                 .collect::<ValidatorWeights>();
             (era_id, validator_weights)
         })
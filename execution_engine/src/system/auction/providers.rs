@@ -21,6 +21,9 @@ pub trait RuntimeProvider {
     /// Gets named key under a `name`.
     fn named_keys_get(&self, name: &str) -> Option<Key>;
 
+    /// Puts a named key under a `name`.
+    fn named_keys_put(&mut self, name: &str, key: Key) -> Result<(), Error>;
+
     /// Gets keys in a given keyspace
     fn get_keys(&mut self, key_tag: &KeyTag) -> Result<BTreeSet<Key>, Error>;
 
@@ -29,6 +32,9 @@ pub trait RuntimeProvider {
 
     /// Returns vesting schedule period.
     fn vesting_schedule_period_millis(&self) -> u64;
+
+    /// Returns the maximum number of matured unbonding purses paid out per era-end step.
+    fn max_unbondings_per_step(&self) -> u32;
 }
 
 /// Provides functionality of a contract storage.
@@ -39,6 +45,9 @@ pub trait StorageProvider {
     /// Writes data to [`URef].
     fn write<T: ToBytes + CLTyped>(&mut self, uref: URef, value: T) -> Result<(), Error>;
 
+    /// Creates a new [`URef`] holding an initial value.
+    fn new_uref<T: ToBytes + CLTyped>(&mut self, init: T) -> Result<URef, Error>;
+
     /// Reads [`Bid`] at account hash derived from given public key
     fn read_bid(&mut self, account_hash: &AccountHash) -> Result<Option<Bid>, Error>;
 
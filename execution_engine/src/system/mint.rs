@@ -8,10 +8,12 @@ use num_traits::CheckedMul;
 use casper_types::{
     account::AccountHash,
     system::{
-        mint::{Error, ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY},
+        mint::{
+            Error, NativeTokens, NATIVE_TOKENS_KEY, ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY,
+        },
         CallStackElement,
     },
-    Key, Phase, PublicKey, URef, U512,
+    Key, Phase, PublicKey, TransferMemo, URef, U512,
 };
 
 use crate::system::mint::{
@@ -100,6 +102,7 @@ pub trait Mint: RuntimeProvider + StorageProvider + SystemProvider {
         target: URef,
         amount: U512,
         id: Option<u64>,
+        memo: Option<TransferMemo>,
     ) -> Result<(), Error> {
         if let (Phase::Session, Some(&CallStackElement::StoredSession { .. })) =
             (self.get_phase(), self.get_immediate_caller())
@@ -136,7 +139,7 @@ pub trait Mint: RuntimeProvider + StorageProvider + SystemProvider {
         self.write_balance(source, source_balance - amount)?;
         self.add_balance(target, amount)?;
 
-        self.record_transfer(maybe_to, source, target, amount, id)?;
+        self.record_transfer(maybe_to, source, target, amount, id, memo)?;
         Ok(())
     }
 
@@ -200,4 +203,116 @@ pub trait Mint: RuntimeProvider + StorageProvider + SystemProvider {
         self.add(total_supply_uref, amount)?;
         Ok(())
     }
+
+    /// Reads the registry of native tokens, which maps the symbol of each token to its issuer and
+    /// total supply.
+    fn read_native_tokens(&mut self) -> Result<(URef, NativeTokens), Error> {
+        let native_tokens_uref = match self.get_key(NATIVE_TOKENS_KEY) {
+            Some(Key::URef(uref)) => uref,
+            Some(_) => return Err(Error::MissingKey),
+            // no native tokens were declared on this network
+            None => return Err(Error::UnknownToken),
+        };
+        let native_tokens = self.read(native_tokens_uref)?.ok_or(Error::UnknownToken)?;
+        Ok((native_tokens_uref, native_tokens))
+    }
+
+    /// Mints `amount` of the native token `token` into the `target` purse.
+    /// Only the issuer of the token may mint it.
+    fn mint_token(&mut self, token: &str, target: URef, amount: U512) -> Result<(), Error> {
+        if let (Phase::Session, Some(&CallStackElement::StoredSession { .. })) =
+            (self.get_phase(), self.get_immediate_caller())
+        {
+            // stored session code is not allowed to call this method in the session phase
+            return Err(Error::InvalidContext);
+        }
+
+        let (native_tokens_uref, mut native_tokens) = self.read_native_tokens()?;
+        let caller = self.get_caller();
+        let (issuer, total_supply) = native_tokens.get_mut(token).ok_or(Error::UnknownToken)?;
+        if caller != *issuer {
+            return Err(Error::InvalidTokenIssuer);
+        }
+        if !target.is_addable() {
+            return Err(Error::InvalidAccessRights);
+        }
+        if self.read_balance(target)?.is_none() {
+            return Err(Error::PurseNotFound);
+        }
+        if amount.is_zero() {
+            // treat as noop
+            return Ok(());
+        }
+
+        *total_supply = total_supply
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let target_balance = self
+            .read_token_balance(token, target)?
+            .unwrap_or_default()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        self.write_token_balance(token, target, target_balance)?;
+        self.write(native_tokens_uref, native_tokens)?;
+        Ok(())
+    }
+
+    /// Transfers `amount` of the native token `token` from `source` purse to a `target` purse.
+    fn transfer_token(
+        &mut self,
+        token: &str,
+        source: URef,
+        target: URef,
+        amount: U512,
+    ) -> Result<(), Error> {
+        if let (Phase::Session, Some(&CallStackElement::StoredSession { .. })) =
+            (self.get_phase(), self.get_immediate_caller())
+        {
+            // stored session code is not allowed to call this method in the session phase
+            return Err(Error::InvalidContext);
+        }
+
+        let (_, native_tokens) = self.read_native_tokens()?;
+        if !native_tokens.contains_key(token) {
+            return Err(Error::UnknownToken);
+        }
+        if !source.is_readable() {
+            return Err(Error::InvalidAccessRights);
+        }
+        if !source.is_writeable() || !target.is_addable() {
+            return Err(Error::InvalidAccessRights);
+        }
+        if self.read_balance(source)?.is_none() {
+            return Err(Error::SourceNotFound);
+        }
+        if self.read_balance(target)?.is_none() {
+            return Err(Error::DestNotFound);
+        }
+        let source_balance = self.read_token_balance(token, source)?.unwrap_or_default();
+        if amount > source_balance {
+            return Err(Error::InsufficientFunds);
+        }
+
+        self.write_token_balance(token, source, source_balance - amount)?;
+        let target_balance = self
+            .read_token_balance(token, target)?
+            .unwrap_or_default()
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        self.write_token_balance(token, target, target_balance)?;
+        Ok(())
+    }
+
+    /// Read balance of the native token `token` held by `purse`.
+    fn token_balance(&mut self, token: &str, purse: URef) -> Result<U512, Error> {
+        let (_, native_tokens) = self.read_native_tokens()?;
+        if !native_tokens.contains_key(token) {
+            return Err(Error::UnknownToken);
+        }
+        if self.read_balance(purse)?.is_none() {
+            return Err(Error::PurseNotFound);
+        }
+        Ok(self.read_token_balance(token, purse)?.unwrap_or_default())
+    }
 }
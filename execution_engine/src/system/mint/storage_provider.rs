@@ -26,4 +26,10 @@ pub trait StorageProvider {
 
     /// Add amount to an existing balance.
     fn add_balance(&mut self, uref: URef, value: U512) -> Result<(), Error>;
+
+    /// Read balance of a native token.
+    fn read_token_balance(&mut self, token: &str, uref: URef) -> Result<Option<U512>, Error>;
+
+    /// Write balance of a native token.
+    fn write_token_balance(&mut self, token: &str, uref: URef, balance: U512) -> Result<(), Error>;
 }
@@ -0,0 +1,176 @@
+use crate::{DeployRecipe, Outcome};
+
+/// Payment attached to deploys calling the auction.
+const AUCTION_CALL_PAYMENT: u64 = 2_500_000_000;
+
+/// A version of the execution engine, running deploys against its own in-memory global state.
+pub trait Engine {
+    /// Executes the deploy described by `recipe` under the given deploy hash and commits its
+    /// effects.
+    fn execute(&mut self, deploy_hash: [u8; 32], recipe: &DeployRecipe) -> Outcome;
+}
+
+/// Implements an [`Engine`] in terms of the given versions of `casper-engine-test-support` and
+/// `casper-types`.
+///
+/// Both versions expose the same API, so the implementations only differ in the crates they are
+/// generated from.
+macro_rules! impl_engine {
+    ($(#[$attr:meta])* $name:ident, $test_support:ident, $types:ident) => {
+        $(#[$attr])*
+        pub struct $name {
+            builder: $test_support::InMemoryWasmTestBuilder,
+        }
+
+        impl $name {
+            /// Creates a new engine and runs genesis with the default test accounts.
+            pub fn new() -> Self {
+                let mut builder = $test_support::InMemoryWasmTestBuilder::default();
+                // Deprecated in the current version, but the previous version doesn't offer the
+                // replacement.
+                #[allow(deprecated)]
+                builder.run_genesis(&*$test_support::DEFAULT_RUN_GENESIS_REQUEST);
+                $name { builder }
+            }
+
+            fn public_key(account: u8) -> $types::PublicKey {
+                if account == crate::GENESIS_ACCOUNT {
+                    return $test_support::DEFAULT_ACCOUNT_PUBLIC_KEY.clone();
+                }
+                let secret_key = $types::SecretKey::ed25519_from_bytes(
+                    [account; $types::SecretKey::ED25519_LENGTH],
+                )
+                .expect("should create secret key");
+                $types::PublicKey::from(&secret_key)
+            }
+
+            fn account_hash(account: u8) -> $types::account::AccountHash {
+                $types::account::AccountHash::from(&Self::public_key(account))
+            }
+
+            fn run(&mut self, request_builder: $test_support::ExecuteRequestBuilder) -> Outcome {
+                self.builder.exec(request_builder.build()).commit();
+                let exec_results = self
+                    .builder
+                    .get_last_exec_results()
+                    .expect("should have exec results");
+                let exec_result = exec_results.first().expect("should have exec result");
+                // As above, `get_execution_journals` is not available in the previous version.
+                #[allow(deprecated)]
+                let effects = self.builder.get_transforms().pop().unwrap_or_default();
+                Outcome {
+                    cost: exec_result.cost().value().to_string(),
+                    error: exec_result.as_error().map(ToString::to_string),
+                    effects: effects
+                        .iter()
+                        .map(|(key, transform)| {
+                            (key.to_formatted_string(), format!("{:?}", transform))
+                        })
+                        .collect(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Engine for $name {
+            fn execute(&mut self, deploy_hash: [u8; 32], recipe: &DeployRecipe) -> Outcome {
+                use $test_support::{DeployItemBuilder, ExecuteRequestBuilder};
+                use $types::{runtime_args, system::auction, RuntimeArgs, U512};
+
+                let sender = Self::account_hash(recipe.sender());
+                let deploy_item_builder = DeployItemBuilder::new()
+                    .with_address(sender)
+                    .with_authorization_keys(&[sender])
+                    .with_deploy_hash(deploy_hash);
+
+                let (entry_point, args) = match *recipe {
+                    DeployRecipe::Transfer {
+                        target, amount, id, ..
+                    } => {
+                        let transfer_args = runtime_args! {
+                            "target" => Self::account_hash(target),
+                            "amount" => U512::from(amount),
+                            "id" => id,
+                        };
+                        let deploy_item = deploy_item_builder
+                            .with_empty_payment_bytes(runtime_args! {})
+                            .with_transfer_args(transfer_args)
+                            .build();
+                        return self.run(ExecuteRequestBuilder::from_deploy_item(deploy_item));
+                    }
+                    DeployRecipe::AddBid {
+                        validator,
+                        amount,
+                        delegation_rate,
+                    } => (
+                        auction::METHOD_ADD_BID,
+                        runtime_args! {
+                            auction::ARG_PUBLIC_KEY => Self::public_key(validator),
+                            auction::ARG_AMOUNT => U512::from(amount),
+                            auction::ARG_DELEGATION_RATE => delegation_rate,
+                        },
+                    ),
+                    DeployRecipe::WithdrawBid { validator, amount } => (
+                        auction::METHOD_WITHDRAW_BID,
+                        runtime_args! {
+                            auction::ARG_PUBLIC_KEY => Self::public_key(validator),
+                            auction::ARG_AMOUNT => U512::from(amount),
+                        },
+                    ),
+                    DeployRecipe::Delegate {
+                        delegator,
+                        validator,
+                        amount,
+                    } => (
+                        auction::METHOD_DELEGATE,
+                        runtime_args! {
+                            auction::ARG_DELEGATOR => Self::public_key(delegator),
+                            auction::ARG_VALIDATOR => Self::public_key(validator),
+                            auction::ARG_AMOUNT => U512::from(amount),
+                        },
+                    ),
+                    DeployRecipe::Undelegate {
+                        delegator,
+                        validator,
+                        amount,
+                    } => (
+                        auction::METHOD_UNDELEGATE,
+                        runtime_args! {
+                            auction::ARG_DELEGATOR => Self::public_key(delegator),
+                            auction::ARG_VALIDATOR => Self::public_key(validator),
+                            auction::ARG_AMOUNT => U512::from(amount),
+                        },
+                    ),
+                };
+
+                let auction_hash = self.builder.get_auction_contract_hash();
+                let deploy_item = deploy_item_builder
+                    .with_empty_payment_bytes(runtime_args! {
+                        "amount" => U512::from(AUCTION_CALL_PAYMENT),
+                    })
+                    .with_stored_session_hash(auction_hash, entry_point, args)
+                    .build();
+                self.run(ExecuteRequestBuilder::from_deploy_item(deploy_item))
+            }
+        }
+    };
+}
+
+impl_engine!(
+    /// The execution engine of this tree.
+    CurrentEngine,
+    casper_engine_test_support,
+    casper_types
+);
+
+impl_engine!(
+    /// The execution engine of the previous protocol version.
+    PreviousEngine,
+    casper_engine_test_support_previous,
+    casper_types_previous
+);
@@ -0,0 +1,154 @@
+//! Differential fuzzing of the execution engine against the engine of the previous protocol
+//! version.
+//!
+//! A seeded sequence of random deploys is executed by both versions of the engine, each against its
+//! own in-memory global state initialized by the same genesis.  After every deploy, the gas cost,
+//! the error (if any) and the effects on global state are compared, and the run stops at the first
+//! divergence, as the global states of both engines differ from then on.
+//!
+//! The deploys are limited to those which don't need any Wasm, i.e. native transfers and calls to
+//! the auction, so that no contracts have to be built for either version.
+//!
+//! The previous version of the engine is fetched via git; see the README for how to run it.
+
+#![warn(missing_docs)]
+
+mod engine;
+mod outcome;
+mod recipe;
+
+use std::fmt::{self, Display, Formatter};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub use engine::{CurrentEngine, Engine, PreviousEngine};
+pub use outcome::{compare, CompareOptions, Difference, Outcome};
+pub use recipe::{DeployRecipe, ACCOUNT_COUNT, GENESIS_ACCOUNT};
+
+/// The first deploy for which the two engines produced different outcomes.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// Index of the deploy in the run.
+    pub index: usize,
+    /// The deploy which was executed.
+    pub recipe: DeployRecipe,
+    /// The outcome of the previous engine.
+    pub previous: Outcome,
+    /// The outcome of the current engine.
+    pub current: Outcome,
+    /// The differences between the two outcomes.
+    pub differences: Vec<Difference>,
+}
+
+/// The result of a differential run.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The seed the deploys were generated from.
+    pub seed: u64,
+    /// The number of deploys executed by both engines.
+    pub executed: usize,
+    /// The number of deploys which failed in both engines.
+    pub failed: usize,
+    /// The first divergence, if any.
+    pub divergence: Option<Divergence>,
+}
+
+impl Display for Report {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "seed {}: executed {} deploys, {} of which failed in both engines",
+            self.seed, self.executed, self.failed
+        )?;
+        match &self.divergence {
+            None => writeln!(formatter, "no divergence found"),
+            Some(divergence) => {
+                writeln!(
+                    formatter,
+                    "divergence at deploy {}: {}",
+                    divergence.index, divergence.recipe
+                )?;
+                for difference in &divergence.differences {
+                    writeln!(formatter, "  {}", difference)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the deploy hash of the deploy at `index`, which is the same for both engines so that
+/// the addresses they generate match.
+fn deploy_hash(index: usize) -> [u8; 32] {
+    let mut deploy_hash = [0; 32];
+    deploy_hash[24..].copy_from_slice(&(index as u64).to_be_bytes());
+    deploy_hash
+}
+
+/// Executes `deploy_count` random deploys generated from `seed` with both versions of the engine,
+/// stopping at the first divergence.
+pub fn run(seed: u64, deploy_count: usize, options: &CompareOptions) -> Report {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut previous_engine = PreviousEngine::new();
+    let mut current_engine = CurrentEngine::new();
+    let mut report = Report {
+        seed,
+        executed: 0,
+        failed: 0,
+        divergence: None,
+    };
+
+    for index in 0..deploy_count {
+        let recipe: DeployRecipe = rng.gen();
+        let previous = previous_engine.execute(deploy_hash(index), &recipe);
+        let current = current_engine.execute(deploy_hash(index), &recipe);
+        report.executed += 1;
+
+        let differences = compare(&previous, &current, options);
+        if !differences.is_empty() {
+            report.divergence = Some(Divergence {
+                index,
+                recipe,
+                previous,
+                current,
+                differences,
+            });
+            break;
+        }
+        if current.error.is_some() {
+            report.failed += 1;
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_generate_reproducible_deploys() {
+        let recipes = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..100).map(|_| rng.gen()).collect::<Vec<DeployRecipe>>()
+        };
+        assert_eq!(recipes(42), recipes(42));
+    }
+
+    #[test]
+    fn should_not_diverge_on_random_deploys() {
+        // Gas costs are allowed to change between protocol versions.
+        let options = CompareOptions {
+            ignore_cost: true,
+            ..CompareOptions::default()
+        };
+        // The seed of the deploys is drawn from a `TestRng`, so a failing run can be reproduced via
+        // the `CL_TEST_SEED` printed on failure, or via the seed in the report.
+        let mut rng = TestRng::new();
+        let report = run(rng.gen(), 100, &options);
+        assert!(report.divergence.is_none(), "{}", report);
+    }
+}
@@ -0,0 +1,88 @@
+//! Runs random deploys with the current and the previous version of the execution engine and
+//! reports the first divergence between them.
+
+use std::process;
+
+use clap::{crate_version, App, Arg};
+
+use casper_engine_differential_tests::CompareOptions;
+
+const ABOUT: &str = "Executes random deploys with the current and the previous version of the \
+    execution engine, and reports the first difference in gas costs, errors or effects.";
+
+const SEED_ARG_NAME: &str = "seed";
+const SEED_ARG_SHORT: &str = "s";
+const SEED_ARG_VALUE_NAME: &str = "NUM";
+const SEED_ARG_HELP: &str = "Seed of the random deploys; if unspecified, a random seed is used";
+
+const DEPLOYS_ARG_NAME: &str = "deploys";
+const DEPLOYS_ARG_SHORT: &str = "d";
+const DEPLOYS_ARG_DEFAULT: &str = "1000";
+const DEPLOYS_ARG_VALUE_NAME: &str = "NUM";
+const DEPLOYS_ARG_HELP: &str = "Number of deploys to execute";
+
+const IGNORE_COST_ARG_NAME: &str = "ignore-cost";
+const IGNORE_COST_ARG_HELP: &str = "Don't report differences in gas costs";
+
+const IGNORE_KEY_ARG_NAME: &str = "ignore-key";
+const IGNORE_KEY_ARG_VALUE_NAME: &str = "PREFIX";
+const IGNORE_KEY_ARG_HELP: &str =
+    "Don't report differences in effects on keys starting with the given prefix, e.g. 'balance-'";
+
+fn main() {
+    let arg_matches = App::new("Differential fuzz")
+        .version(crate_version!())
+        .about(ABOUT)
+        .arg(
+            Arg::with_name(SEED_ARG_NAME)
+                .long(SEED_ARG_NAME)
+                .short(SEED_ARG_SHORT)
+                .value_name(SEED_ARG_VALUE_NAME)
+                .help(SEED_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(DEPLOYS_ARG_NAME)
+                .long(DEPLOYS_ARG_NAME)
+                .short(DEPLOYS_ARG_SHORT)
+                .default_value(DEPLOYS_ARG_DEFAULT)
+                .value_name(DEPLOYS_ARG_VALUE_NAME)
+                .help(DEPLOYS_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(IGNORE_COST_ARG_NAME)
+                .long(IGNORE_COST_ARG_NAME)
+                .help(IGNORE_COST_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(IGNORE_KEY_ARG_NAME)
+                .long(IGNORE_KEY_ARG_NAME)
+                .value_name(IGNORE_KEY_ARG_VALUE_NAME)
+                .multiple(true)
+                .number_of_values(1)
+                .help(IGNORE_KEY_ARG_HELP),
+        )
+        .get_matches();
+
+    let seed = arg_matches
+        .value_of(SEED_ARG_NAME)
+        .map(|value| value.parse().expect("seed should be an integer"))
+        .unwrap_or_else(rand::random);
+    let deploy_count = arg_matches
+        .value_of(DEPLOYS_ARG_NAME)
+        .expect("should have default")
+        .parse()
+        .expect("number of deploys should be an integer");
+    let options = CompareOptions {
+        ignore_cost: arg_matches.is_present(IGNORE_COST_ARG_NAME),
+        ignored_key_prefixes: arg_matches
+            .values_of(IGNORE_KEY_ARG_NAME)
+            .map(|values| values.map(ToString::to_string).collect())
+            .unwrap_or_default(),
+    };
+
+    let report = casper_engine_differential_tests::run(seed, deploy_count, &options);
+    print!("{}", report);
+    if report.divergence.is_some() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+/// The observable result of executing a deploy, rendered as strings so that the results of both
+/// versions of the engine can be compared.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The gas consumed by the deploy.
+    pub cost: String,
+    /// The error the deploy failed with, if any.
+    pub error: Option<String>,
+    /// The transforms written to global state, keyed by the formatted key they apply to.
+    pub effects: BTreeMap<String, String>,
+}
+
+/// Settings controlling which differences between outcomes are reported.
+#[derive(Clone, Debug, Default)]
+pub struct CompareOptions {
+    /// Whether differing gas costs are ignored, e.g. when the cost tables changed intentionally.
+    pub ignore_cost: bool,
+    /// Effects on keys starting with any of these prefixes are ignored.
+    pub ignored_key_prefixes: Vec<String>,
+}
+
+impl CompareOptions {
+    fn is_ignored(&self, key: &str) -> bool {
+        self.ignored_key_prefixes
+            .iter()
+            .any(|prefix| key.starts_with(prefix.as_str()))
+    }
+}
+
+/// A single difference between the outcomes of the previous and the current engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// The deploy consumed a different amount of gas.
+    Cost {
+        /// The cost in the previous engine.
+        previous: String,
+        /// The cost in the current engine.
+        current: String,
+    },
+    /// The deploy failed in one engine but not the other, or with a different error.
+    Error {
+        /// The error in the previous engine.
+        previous: Option<String>,
+        /// The error in the current engine.
+        current: Option<String>,
+    },
+    /// The deploy wrote different transforms to the given key.
+    Effect {
+        /// The formatted key.
+        key: String,
+        /// The transform written by the previous engine.
+        previous: Option<String>,
+        /// The transform written by the current engine.
+        current: Option<String>,
+    },
+}
+
+impl Display for Difference {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Difference::Cost { previous, current } => {
+                write!(formatter, "cost: {} -> {}", previous, current)
+            }
+            Difference::Error { previous, current } => {
+                write!(formatter, "error: {:?} -> {:?}", previous, current)
+            }
+            Difference::Effect {
+                key,
+                previous,
+                current,
+            } => write!(
+                formatter,
+                "effect on {}: {:?} -> {:?}",
+                key, previous, current
+            ),
+        }
+    }
+}
+
+/// Returns the differences between the outcomes of the previous and the current engine which are
+/// not ignored by `options`.
+pub fn compare(previous: &Outcome, current: &Outcome, options: &CompareOptions) -> Vec<Difference> {
+    let mut differences = vec![];
+
+    if !options.ignore_cost && previous.cost != current.cost {
+        differences.push(Difference::Cost {
+            previous: previous.cost.clone(),
+            current: current.cost.clone(),
+        });
+    }
+
+    if previous.error != current.error {
+        differences.push(Difference::Error {
+            previous: previous.error.clone(),
+            current: current.error.clone(),
+        });
+    }
+
+    let keys: BTreeSet<&String> = previous
+        .effects
+        .keys()
+        .chain(current.effects.keys())
+        .collect();
+    for key in keys {
+        if options.is_ignored(key) {
+            continue;
+        }
+        let previous_effect = previous.effects.get(key);
+        let current_effect = current.effects.get(key);
+        if previous_effect != current_effect {
+            differences.push(Difference::Effect {
+                key: key.clone(),
+                previous: previous_effect.cloned(),
+                current: current_effect.cloned(),
+            });
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(cost: &str, error: Option<&str>, effects: &[(&str, &str)]) -> Outcome {
+        Outcome {
+            cost: cost.to_string(),
+            error: error.map(ToString::to_string),
+            effects: effects
+                .iter()
+                .map(|(key, transform)| (key.to_string(), transform.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn should_report_all_differences() {
+        let previous = outcome(
+            "100",
+            None,
+            &[("balance-01", "AddUInt512(1)"), ("hash-02", "Identity")],
+        );
+        let current = outcome(
+            "200",
+            Some("Insufficient payment"),
+            &[("balance-01", "AddUInt512(2)"), ("uref-03", "Identity")],
+        );
+
+        let differences = compare(&previous, &current, &CompareOptions::default());
+        assert_eq!(
+            differences,
+            vec![
+                Difference::Cost {
+                    previous: "100".to_string(),
+                    current: "200".to_string()
+                },
+                Difference::Error {
+                    previous: None,
+                    current: Some("Insufficient payment".to_string())
+                },
+                Difference::Effect {
+                    key: "balance-01".to_string(),
+                    previous: Some("AddUInt512(1)".to_string()),
+                    current: Some("AddUInt512(2)".to_string())
+                },
+                Difference::Effect {
+                    key: "hash-02".to_string(),
+                    previous: Some("Identity".to_string()),
+                    current: None
+                },
+                Difference::Effect {
+                    key: "uref-03".to_string(),
+                    previous: None,
+                    current: Some("Identity".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_ignored_differences() {
+        let previous = outcome("100", None, &[("balance-01", "AddUInt512(1)")]);
+        let current = outcome("200", None, &[("balance-01", "AddUInt512(2)")]);
+        let options = CompareOptions {
+            ignore_cost: true,
+            ignored_key_prefixes: vec!["balance-".to_string()],
+        };
+
+        assert!(compare(&previous, &current, &options).is_empty());
+        assert!(compare(&previous, &previous, &CompareOptions::default()).is_empty());
+    }
+}
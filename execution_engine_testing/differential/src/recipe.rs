@@ -0,0 +1,188 @@
+use std::fmt::{self, Display, Formatter};
+
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+
+/// Number of accounts, including the genesis account, which deploys are sent from and to.
+pub const ACCOUNT_COUNT: u8 = 5;
+
+/// Index of the account funded at genesis.
+pub const GENESIS_ACCOUNT: u8 = 0;
+
+/// Amounts which are likely to hit edge cases in the mint and the auction.
+const INTERESTING_AMOUNTS: [u64; 7] = [
+    0,
+    1,
+    2_500_000_000,
+    2_500_000_001,
+    1_000_000_000_000,
+    100_000_000_000_000_000,
+    u64::MAX,
+];
+
+/// A deploy, described independently of the types of either version of the engine.
+///
+/// Accounts are referred to by their index; index [`GENESIS_ACCOUNT`] is the account funded at
+/// genesis, all others are derived from fixed secret keys and only exist once they have been sent
+/// a transfer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeployRecipe {
+    /// A native transfer to the main purse of an account.
+    Transfer {
+        /// Index of the account sending the transfer.
+        source: u8,
+        /// Index of the account receiving the transfer.
+        target: u8,
+        /// The amount in motes.
+        amount: u64,
+        /// The transfer ID.
+        id: Option<u64>,
+    },
+    /// A call to the auction's `add_bid` entry point.
+    AddBid {
+        /// Index of the validator account.
+        validator: u8,
+        /// The amount in motes.
+        amount: u64,
+        /// The delegation rate of the bid.
+        delegation_rate: u8,
+    },
+    /// A call to the auction's `withdraw_bid` entry point.
+    WithdrawBid {
+        /// Index of the validator account.
+        validator: u8,
+        /// The amount in motes.
+        amount: u64,
+    },
+    /// A call to the auction's `delegate` entry point.
+    Delegate {
+        /// Index of the delegating account.
+        delegator: u8,
+        /// Index of the validator account.
+        validator: u8,
+        /// The amount in motes.
+        amount: u64,
+    },
+    /// A call to the auction's `undelegate` entry point.
+    Undelegate {
+        /// Index of the delegating account.
+        delegator: u8,
+        /// Index of the validator account.
+        validator: u8,
+        /// The amount in motes.
+        amount: u64,
+    },
+}
+
+impl DeployRecipe {
+    /// Returns the index of the account sending the deploy.
+    pub fn sender(&self) -> u8 {
+        match self {
+            DeployRecipe::Transfer { source, .. } => *source,
+            DeployRecipe::AddBid { validator, .. }
+            | DeployRecipe::WithdrawBid { validator, .. } => *validator,
+            DeployRecipe::Delegate { delegator, .. }
+            | DeployRecipe::Undelegate { delegator, .. } => *delegator,
+        }
+    }
+}
+
+impl Display for DeployRecipe {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            DeployRecipe::Transfer {
+                source,
+                target,
+                amount,
+                id,
+            } => write!(
+                formatter,
+                "transfer {} from account {} to account {} (id {:?})",
+                amount, source, target, id
+            ),
+            DeployRecipe::AddBid {
+                validator,
+                amount,
+                delegation_rate,
+            } => write!(
+                formatter,
+                "add bid of {} by account {} at delegation rate {}",
+                amount, validator, delegation_rate
+            ),
+            DeployRecipe::WithdrawBid { validator, amount } => write!(
+                formatter,
+                "withdraw {} from bid of account {}",
+                amount, validator
+            ),
+            DeployRecipe::Delegate {
+                delegator,
+                validator,
+                amount,
+            } => write!(
+                formatter,
+                "delegate {} from account {} to account {}",
+                amount, delegator, validator
+            ),
+            DeployRecipe::Undelegate {
+                delegator,
+                validator,
+                amount,
+            } => write!(
+                formatter,
+                "undelegate {} from account {} to account {}",
+                amount, delegator, validator
+            ),
+        }
+    }
+}
+
+fn random_account<R: Rng + ?Sized>(rng: &mut R) -> u8 {
+    // Favor the genesis account, as most deploys from other accounts fail for lack of funds.
+    if rng.gen_bool(0.4) {
+        GENESIS_ACCOUNT
+    } else {
+        rng.gen_range(0..ACCOUNT_COUNT)
+    }
+}
+
+fn random_amount<R: Rng + ?Sized>(rng: &mut R) -> u64 {
+    if rng.gen() {
+        INTERESTING_AMOUNTS[rng.gen_range(0..INTERESTING_AMOUNTS.len())]
+    } else {
+        rng.gen_range(0..1_000_000_000_000_000)
+    }
+}
+
+impl Distribution<DeployRecipe> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DeployRecipe {
+        match rng.gen_range(0..10) {
+            0..=5 => DeployRecipe::Transfer {
+                source: random_account(rng),
+                target: random_account(rng),
+                amount: random_amount(rng),
+                id: rng.gen(),
+            },
+            6 => DeployRecipe::AddBid {
+                validator: random_account(rng),
+                amount: random_amount(rng),
+                delegation_rate: rng.gen_range(0..=101),
+            },
+            7 => DeployRecipe::WithdrawBid {
+                validator: random_account(rng),
+                amount: random_amount(rng),
+            },
+            8 => DeployRecipe::Delegate {
+                delegator: random_account(rng),
+                validator: random_account(rng),
+                amount: random_amount(rng),
+            },
+            _ => DeployRecipe::Undelegate {
+                delegator: random_account(rng),
+                validator: random_account(rng),
+                amount: random_amount(rng),
+            },
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use std::{collections::HashSet, convert::TryFrom, io::Write, time::Instant};
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+    io::Write,
+    time::Instant,
+};
 
 use lmdb::{Cursor, Transaction};
 use tempfile::TempDir;
@@ -337,6 +342,7 @@ fn create_run_genesis_request(
             DEFAULT_ROUND_SEIGNIORAGE_RATE,
             DEFAULT_UNBONDING_DELAY,
             DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+            BTreeMap::new(),
         )
     };
     RunGenesisRequest::new(
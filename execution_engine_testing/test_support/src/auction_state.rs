@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use casper_types::{
+    system::auction::{Bid, DelegationRate, Delegator, ValidatorWeights},
+    EraId, PublicKey, U512,
+};
+
+/// A validator's bid as read from global state, with assertion helpers.
+///
+/// Dereferences to the underlying [`Bid`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ValidatorBid(Bid);
+
+impl ValidatorBid {
+    /// Wraps a bid read from global state.
+    pub fn new(bid: Bid) -> Self {
+        ValidatorBid(bid)
+    }
+
+    /// Returns the wrapped bid.
+    pub fn into_inner(self) -> Bid {
+        self.0
+    }
+
+    /// Returns the delegation of `delegator` to this validator, if any.
+    pub fn delegation(&self, delegator: &PublicKey) -> Option<Delegation> {
+        self.0.delegators().get(delegator).cloned().map(Delegation)
+    }
+
+    /// Returns the public keys of all delegators of this validator.
+    pub fn delegator_public_keys(&self) -> BTreeSet<PublicKey> {
+        self.0.delegators().keys().cloned().collect()
+    }
+
+    /// Asserts that the validator's own stake equals `expected`.
+    #[track_caller]
+    pub fn assert_staked_amount<T: Into<U512>>(&self, expected: T) -> &Self {
+        assert_eq!(
+            *self.0.staked_amount(),
+            expected.into(),
+            "unexpected staked amount in bid of {}",
+            self.0.validator_public_key()
+        );
+        self
+    }
+
+    /// Asserts that the validator's delegation rate equals `expected`.
+    #[track_caller]
+    pub fn assert_delegation_rate(&self, expected: DelegationRate) -> &Self {
+        assert_eq!(
+            *self.0.delegation_rate(),
+            expected,
+            "unexpected delegation rate in bid of {}",
+            self.0.validator_public_key()
+        );
+        self
+    }
+
+    /// Asserts that the bid is active.
+    #[track_caller]
+    pub fn assert_active(&self) -> &Self {
+        assert!(
+            !self.0.inactive(),
+            "bid of {} should be active",
+            self.0.validator_public_key()
+        );
+        self
+    }
+
+    /// Asserts that the bid is inactive.
+    #[track_caller]
+    pub fn assert_inactive(&self) -> &Self {
+        assert!(
+            self.0.inactive(),
+            "bid of {} should be inactive",
+            self.0.validator_public_key()
+        );
+        self
+    }
+
+    /// Asserts that the validator's delegators are exactly `expected`.
+    #[track_caller]
+    pub fn assert_delegators<I>(&self, expected: I) -> &Self
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        assert_eq!(
+            self.delegator_public_keys(),
+            expected.into_iter().collect::<BTreeSet<_>>(),
+            "unexpected delegators in bid of {}",
+            self.0.validator_public_key()
+        );
+        self
+    }
+
+    /// Asserts that the validator has `expected` delegators.
+    #[track_caller]
+    pub fn assert_delegator_count(&self, expected: usize) -> &Self {
+        assert_eq!(
+            self.0.delegators().len(),
+            expected,
+            "unexpected number of delegators in bid of {}",
+            self.0.validator_public_key()
+        );
+        self
+    }
+}
+
+impl Deref for ValidatorBid {
+    type Target = Bid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Debug for ValidatorBid {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, formatter)
+    }
+}
+
+/// A delegation as read from global state, with assertion helpers.
+///
+/// Dereferences to the underlying [`Delegator`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Delegation(Delegator);
+
+impl Delegation {
+    /// Wraps a delegation read from global state.
+    pub fn new(delegator: Delegator) -> Self {
+        Delegation(delegator)
+    }
+
+    /// Returns the wrapped delegator.
+    pub fn into_inner(self) -> Delegator {
+        self.0
+    }
+
+    /// Asserts that the delegated amount equals `expected`.
+    #[track_caller]
+    pub fn assert_staked_amount<T: Into<U512>>(&self, expected: T) -> &Self {
+        assert_eq!(
+            *self.0.staked_amount(),
+            expected.into(),
+            "unexpected amount delegated by {} to {}",
+            self.0.delegator_public_key(),
+            self.0.validator_public_key()
+        );
+        self
+    }
+}
+
+impl Deref for Delegation {
+    type Target = Delegator;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Debug for Delegation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, formatter)
+    }
+}
+
+/// The validators and their weights for a single era, with assertion helpers.
+///
+/// Dereferences to the underlying [`ValidatorWeights`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EraValidatorSet {
+    era_id: EraId,
+    weights: ValidatorWeights,
+}
+
+impl EraValidatorSet {
+    /// Creates the validator set of `era_id`.
+    pub fn new(era_id: EraId, weights: ValidatorWeights) -> Self {
+        EraValidatorSet { era_id, weights }
+    }
+
+    /// Returns the era of this validator set.
+    pub fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    /// Returns the weight of `validator`, if it is part of the set.
+    pub fn weight(&self, validator: &PublicKey) -> Option<U512> {
+        self.weights.get(validator).copied()
+    }
+
+    /// Returns the sum of all validators' weights.
+    pub fn total_weight(&self) -> U512 {
+        self.weights
+            .values()
+            .copied()
+            .fold(U512::zero(), |a, b| a + b)
+    }
+
+    /// Returns the wrapped validator weights.
+    pub fn into_inner(self) -> ValidatorWeights {
+        self.weights
+    }
+
+    /// Asserts that the set consists of exactly the `expected` validators.
+    #[track_caller]
+    pub fn assert_validators<I>(&self, expected: I) -> &Self
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        assert_eq!(
+            self.weights.keys().cloned().collect::<BTreeSet<_>>(),
+            expected.into_iter().collect::<BTreeSet<_>>(),
+            "unexpected validators in {}",
+            self.era_id
+        );
+        self
+    }
+
+    /// Asserts that `validator` is part of the set.
+    #[track_caller]
+    pub fn assert_contains(&self, validator: &PublicKey) -> &Self {
+        assert!(
+            self.weights.contains_key(validator),
+            "{} should be a validator in {}",
+            validator,
+            self.era_id
+        );
+        self
+    }
+
+    /// Asserts that `validator` is not part of the set.
+    #[track_caller]
+    pub fn assert_not_contains(&self, validator: &PublicKey) -> &Self {
+        assert!(
+            !self.weights.contains_key(validator),
+            "{} should not be a validator in {}",
+            validator,
+            self.era_id
+        );
+        self
+    }
+
+    /// Asserts that the weight of `validator` equals `expected`.
+    #[track_caller]
+    pub fn assert_weight<T: Into<U512>>(&self, validator: &PublicKey, expected: T) -> &Self {
+        let weight = self
+            .weight(validator)
+            .unwrap_or_else(|| panic!("{} should be a validator in {}", validator, self.era_id));
+        assert_eq!(
+            weight,
+            expected.into(),
+            "unexpected weight of {} in {}",
+            validator,
+            self.era_id
+        );
+        self
+    }
+
+    /// Asserts that the sum of all validators' weights equals `expected`.
+    #[track_caller]
+    pub fn assert_total_weight<T: Into<U512>>(&self, expected: T) -> &Self {
+        assert_eq!(
+            self.total_weight(),
+            expected.into(),
+            "unexpected total weight in {}",
+            self.era_id
+        );
+        self
+    }
+}
+
+impl Deref for EraValidatorSet {
+    type Target = ValidatorWeights;
+
+    fn deref(&self) -> &Self::Target {
+        &self.weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{AccessRights, SecretKey, URef};
+
+    use super::*;
+
+    fn public_key(seed: u8) -> PublicKey {
+        let secret_key = SecretKey::ed25519_from_bytes([seed; SecretKey::ED25519_LENGTH]).unwrap();
+        PublicKey::from(&secret_key)
+    }
+
+    fn validator_bid() -> ValidatorBid {
+        let validator = public_key(1);
+        let purse = URef::new([1; 32], AccessRights::READ_ADD_WRITE);
+        let mut bid = Bid::unlocked(validator.clone(), purse, U512::from(1_000), 10);
+        for seed in [2, 3] {
+            let delegator = public_key(seed);
+            let delegation = Delegator::unlocked(
+                delegator.clone(),
+                U512::from(seed),
+                purse,
+                validator.clone(),
+            );
+            bid.delegators_mut().insert(delegator, delegation);
+        }
+        ValidatorBid::new(bid)
+    }
+
+    #[test]
+    fn should_assert_on_bid() {
+        let bid = validator_bid();
+        bid.assert_staked_amount(1_000)
+            .assert_delegation_rate(10)
+            .assert_active()
+            .assert_delegator_count(2)
+            .assert_delegators(vec![public_key(2), public_key(3)]);
+
+        bid.delegation(&public_key(3))
+            .expect("should have delegation")
+            .assert_staked_amount(3);
+        assert!(bid.delegation(&public_key(4)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected staked amount")]
+    fn should_fail_assertion_on_staked_amount() {
+        validator_bid().assert_staked_amount(999);
+    }
+
+    #[test]
+    #[should_panic(expected = "should be inactive")]
+    fn should_fail_assertion_on_inactive_bid() {
+        validator_bid().assert_inactive();
+    }
+
+    #[test]
+    fn should_assert_on_era_validators() {
+        let weights = vec![
+            (public_key(1), U512::from(10)),
+            (public_key(2), U512::from(5)),
+        ]
+        .into_iter()
+        .collect();
+        let validator_set = EraValidatorSet::new(EraId::new(3), weights);
+
+        validator_set
+            .assert_validators(vec![public_key(1), public_key(2)])
+            .assert_contains(&public_key(1))
+            .assert_not_contains(&public_key(3))
+            .assert_weight(&public_key(2), 5)
+            .assert_total_weight(15);
+    }
+
+    #[test]
+    #[should_panic(expected = "should be a validator in era 3")]
+    fn should_fail_assertion_on_missing_validator() {
+        EraValidatorSet::new(EraId::new(3), ValidatorWeights::new())
+            .assert_weight(&public_key(1), 1);
+    }
+}
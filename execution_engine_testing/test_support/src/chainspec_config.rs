@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     fs, io,
     path::{Path, PathBuf},
@@ -12,7 +13,7 @@ use casper_execution_engine::{
     core::engine_state::{run_genesis_request::RunGenesisRequest, ExecConfig, GenesisAccount},
     shared::{system_config::SystemConfig, wasm_config::WasmConfig},
 };
-use casper_types::ProtocolVersion;
+use casper_types::{account::AccountHash, ProtocolVersion, PublicKey};
 
 use crate::{
     DEFAULT_ACCOUNTS, DEFAULT_CHAINSPEC_REGISTRY, DEFAULT_GENESIS_CONFIG_HASH,
@@ -68,6 +69,23 @@ pub struct CoreConfig {
     pub(crate) minimum_delegation_amount: u64,
     /// Enables strict arguments checking when calling a contract.
     pub(crate) strict_argument_checking: bool,
+    /// Allows accounts to delegate the authorization of their deploys to a contract.
+    pub(crate) allow_authorization_contracts: bool,
+    /// Maximum amount of gas an authorization contract can use to authorize a deploy.
+    pub(crate) authorization_contract_gas_limit: u64,
+    /// Maximum number of matured unbonding purses paid out per era-end step.
+    pub(crate) max_unbondings_per_step: u32,
+    /// Native tokens held in the mint, mapping the symbol of each token to its issuer.
+    pub(crate) native_tokens: BTreeMap<String, PublicKey>,
+}
+
+impl CoreConfig {
+    fn native_token_issuers(&self) -> BTreeMap<String, AccountHash> {
+        self.native_tokens
+            .iter()
+            .map(|(token, issuer)| (token.clone(), issuer.to_account_hash()))
+            .collect()
+    }
 }
 
 /// This struct can be parsed from a TOML-encoded chainspec file.  It means that as the
@@ -121,6 +139,7 @@ impl ChainspecConfig {
             chainspec_config.core_config.round_seigniorage_rate,
             chainspec_config.core_config.unbonding_delay,
             DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+            chainspec_config.core_config.native_token_issuers(),
         );
         Ok(RunGenesisRequest::new(
             *DEFAULT_GENESIS_CONFIG_HASH,
@@ -161,6 +180,7 @@ impl TryFrom<ChainspecConfig> for ExecConfig {
             chainspec_config.core_config.round_seigniorage_rate,
             chainspec_config.core_config.unbonding_delay,
             DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+            chainspec_config.core_config.native_token_issuers(),
         ))
     }
 }
@@ -0,0 +1,631 @@
+//! Running a corpus of real-world contracts against the current execution engine.
+//!
+//! A corpus is a directory holding one TOML manifest per contract, each describing the Wasm file
+//! to install and the entry points to call afterwards, along with their args and expected
+//! outcomes.  Every contract is run in a fresh global state, and the results are collected into a
+//! [`CompatibilityReport`].
+//!
+//! An example manifest, `erc20.toml`:
+//!
+//! ```toml
+//! # Defaults to the manifest's file name with a `.wasm` extension.
+//! wasm = "erc20_token.wasm"
+//!
+//! [install]
+//! args = [
+//!     { name = "name", type = "String", value = "Token" },
+//!     { name = "total_supply", type = "U256", value = "1000000" },
+//! ]
+//!
+//! [[calls]]
+//! # The named key of the installing account under which the contract hash is stored.
+//! contract = "erc20_token_contract"
+//! entry_point = "transfer"
+//! args = [
+//!     { name = "recipient", type = "Key", value = "account-hash-2b2b...2b2b" },
+//!     { name = "amount", type = "U256", value = "10" },
+//! ]
+//!
+//! [[calls]]
+//! contract = "erc20_token_contract"
+//! entry_point = "transfer"
+//! args = [
+//!     { name = "recipient", type = "Key", value = "account-hash-2b2b...2b2b" },
+//!     { name = "amount", type = "U256", value = "2000000" },
+//! ]
+//! expect = "failure"
+//! error_contains = "User(65534)"
+//! ```
+//!
+//! All deploys are sent by [`DEFAULT_ACCOUNT_ADDR`].
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use casper_types::{
+    account::AccountHash, bytesrepr::Bytes, checksummed_hex, AsymmetricType, CLValue,
+    ContractVersion, Key, PublicKey, RuntimeArgs, URef, U128, U256, U512,
+};
+
+use crate::{
+    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+
+const MANIFEST_EXTENSION: &str = "toml";
+const WASM_EXTENSION: &str = "wasm";
+const ARG_AMOUNT: &str = "amount";
+
+/// An error preventing a corpus from being run.
+#[derive(Debug)]
+pub enum CorpusError {
+    /// Failed to read the corpus directory.
+    ReadDir {
+        /// The corpus directory.
+        path: PathBuf,
+        /// The underlying OS error.
+        error: io::Error,
+    },
+}
+
+impl Display for CorpusError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            CorpusError::ReadDir { path, error } => {
+                write!(formatter, "failed to read {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+/// The expected outcome of a deploy.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Expectation {
+    /// The deploy succeeds.
+    Success,
+    /// The deploy fails.
+    Failure,
+}
+
+impl Default for Expectation {
+    fn default() -> Self {
+        Expectation::Success
+    }
+}
+
+impl Display for Expectation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Expectation::Success => write!(formatter, "success"),
+            Expectation::Failure => write!(formatter, "failure"),
+        }
+    }
+}
+
+/// A runtime arg of a deploy described in a manifest.
+///
+/// Numbers wider than 64 bits are given as decimal strings, keys and URefs in their formatted form,
+/// and public keys and bytes hex-encoded.
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestArg {
+    /// The name of the arg.
+    pub name: String,
+    /// The type of the arg, e.g. `U512` or `Key`.
+    #[serde(rename = "type")]
+    pub cl_type: String,
+    /// The value of the arg; may be omitted for `Unit`.
+    #[serde(default)]
+    pub value: Option<toml::Value>,
+}
+
+impl ManifestArg {
+    /// Converts the arg into a `CLValue`.
+    pub fn to_cl_value(&self) -> Result<CLValue, String> {
+        let integer = || {
+            self.value
+                .as_ref()
+                .and_then(toml::Value::as_integer)
+                .ok_or_else(|| "expected an integer".to_string())
+        };
+        let string = || {
+            self.value
+                .as_ref()
+                .and_then(toml::Value::as_str)
+                .ok_or_else(|| "expected a string".to_string())
+        };
+        let out_of_range = |_| "out of range".to_string();
+
+        let cl_value = match self.cl_type.as_str() {
+            "Bool" => self
+                .value
+                .as_ref()
+                .and_then(toml::Value::as_bool)
+                .ok_or_else(|| "expected a boolean".to_string())
+                .map(CLValue::from_t)?,
+            "I32" => CLValue::from_t(i32::try_from(integer()?).map_err(out_of_range)?),
+            "I64" => CLValue::from_t(integer()?),
+            "U8" => CLValue::from_t(u8::try_from(integer()?).map_err(out_of_range)?),
+            "U32" => CLValue::from_t(u32::try_from(integer()?).map_err(out_of_range)?),
+            "U64" => CLValue::from_t(u64::try_from(integer()?).map_err(out_of_range)?),
+            "U128" => CLValue::from_t(
+                U128::from_dec_str(string()?).map_err(|error| format!("{:?}", error))?,
+            ),
+            "U256" => CLValue::from_t(
+                U256::from_dec_str(string()?).map_err(|error| format!("{:?}", error))?,
+            ),
+            "U512" => CLValue::from_t(
+                U512::from_dec_str(string()?).map_err(|error| format!("{:?}", error))?,
+            ),
+            "Unit" => CLValue::from_t(()),
+            "String" => CLValue::from_t(string()?.to_string()),
+            "Key" => CLValue::from_t(
+                Key::from_formatted_str(string()?).map_err(|error| error.to_string())?,
+            ),
+            "URef" => CLValue::from_t(
+                URef::from_formatted_str(string()?).map_err(|error| format!("{:?}", error))?,
+            ),
+            "AccountHash" => CLValue::from_t(
+                AccountHash::from_formatted_str(string()?)
+                    .map_err(|error| format!("{:?}", error))?,
+            ),
+            "PublicKey" => {
+                CLValue::from_t(PublicKey::from_hex(string()?).map_err(|error| error.to_string())?)
+            }
+            "Bytes" => CLValue::from_t(Bytes::from(
+                checksummed_hex::decode(string()?).map_err(|error| error.to_string())?,
+            )),
+            other => return Err(format!("unsupported type {}", other)),
+        };
+        cl_value.map_err(|error| format!("{:?}", error))
+    }
+}
+
+/// The installation of the contract described in a manifest.
+#[derive(Clone, Default, PartialEq, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct InstallStep {
+    /// The session args.
+    #[serde(default)]
+    pub args: Vec<ManifestArg>,
+    /// The expected outcome.
+    #[serde(default)]
+    pub expect: Expectation,
+    /// A substring of the expected error, if the installation is expected to fail.
+    #[serde(default)]
+    pub error_contains: Option<String>,
+}
+
+/// A call to an entry point of an installed contract described in a manifest.
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CallStep {
+    /// The named key under which the contract hash is stored.  Exactly one of `contract` and
+    /// `package` must be given.
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// The named key under which the contract package hash is stored.
+    #[serde(default)]
+    pub package: Option<String>,
+    /// The version to call if `package` is given; defaults to the latest version.
+    #[serde(default)]
+    pub version: Option<ContractVersion>,
+    /// The entry point to call.
+    pub entry_point: String,
+    /// The args of the entry point.
+    #[serde(default)]
+    pub args: Vec<ManifestArg>,
+    /// The expected outcome.
+    #[serde(default)]
+    pub expect: Expectation,
+    /// A substring of the expected error, if the call is expected to fail.
+    #[serde(default)]
+    pub error_contains: Option<String>,
+}
+
+impl Display for CallStep {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match (&self.contract, &self.package) {
+            (Some(contract), _) => write!(formatter, "call {} of {}", self.entry_point, contract),
+            (None, Some(package)) => {
+                write!(
+                    formatter,
+                    "call {} of package {}",
+                    self.entry_point, package
+                )?;
+                if let Some(version) = self.version {
+                    write!(formatter, " version {}", version)?;
+                }
+                Ok(())
+            }
+            (None, None) => write!(formatter, "call {}", self.entry_point),
+        }
+    }
+}
+
+/// The description of a contract in a corpus.
+#[derive(Clone, Default, PartialEq, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The Wasm file, relative to the corpus directory.
+    #[serde(default)]
+    pub wasm: Option<PathBuf>,
+    /// The installation of the contract.
+    #[serde(default)]
+    pub install: InstallStep,
+    /// The calls made after the installation, in order.
+    #[serde(default)]
+    pub calls: Vec<CallStep>,
+}
+
+/// The result of a single deploy of a contract's manifest.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StepReport {
+    /// Description of the deploy.
+    pub description: String,
+    /// The expected outcome.
+    pub expected: Expectation,
+    /// The error the deploy failed with, if any.
+    pub error: Option<String>,
+    /// The gas consumed by the deploy.
+    pub cost: U512,
+    /// Whether the deploy had the expected outcome.
+    pub passed: bool,
+}
+
+/// The result of running a contract of a corpus.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ContractOutcome {
+    /// All deploys had the expected outcome.
+    Compatible,
+    /// A deploy had an unexpected outcome; the remaining deploys were not run.
+    Incompatible,
+    /// The manifest or the Wasm file couldn't be loaded.
+    Invalid(String),
+}
+
+/// The result of running a single contract of a corpus.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContractReport {
+    /// The name of the manifest, without its extension.
+    pub name: String,
+    /// The overall outcome.
+    pub outcome: ContractOutcome,
+    /// The results of the deploys run.
+    pub steps: Vec<StepReport>,
+}
+
+impl Display for ContractReport {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match &self.outcome {
+            ContractOutcome::Compatible => {
+                writeln!(formatter, "{}: compatible", self.name)?;
+            }
+            ContractOutcome::Incompatible => {
+                writeln!(formatter, "{}: INCOMPATIBLE", self.name)?;
+            }
+            ContractOutcome::Invalid(reason) => {
+                return writeln!(formatter, "{}: invalid: {}", self.name, reason);
+            }
+        }
+        for step in &self.steps {
+            write!(
+                formatter,
+                "  [{}] {} (expected {}, cost {})",
+                if step.passed { "ok" } else { "FAILED" },
+                step.description,
+                step.expected,
+                step.cost
+            )?;
+            match &step.error {
+                Some(error) => writeln!(formatter, ": {}", error)?,
+                None => writeln!(formatter)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The results of running a corpus.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct CompatibilityReport {
+    /// The results per contract, ordered by name.
+    pub contracts: Vec<ContractReport>,
+}
+
+impl CompatibilityReport {
+    /// Returns `true` if all contracts of the corpus are compatible.
+    pub fn is_compatible(&self) -> bool {
+        self.contracts
+            .iter()
+            .all(|contract| contract.outcome == ContractOutcome::Compatible)
+    }
+}
+
+impl Display for CompatibilityReport {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for contract in &self.contracts {
+            write!(formatter, "{}", contract)?;
+        }
+        let compatible = self
+            .contracts
+            .iter()
+            .filter(|contract| contract.outcome == ContractOutcome::Compatible)
+            .count();
+        writeln!(
+            formatter,
+            "{} of {} contracts compatible",
+            compatible,
+            self.contracts.len()
+        )
+    }
+}
+
+/// Runs every contract of the corpus in `corpus_dir` and reports whether they behaved as their
+/// manifests expect.
+pub fn run_corpus<P: AsRef<Path>>(corpus_dir: P) -> Result<CompatibilityReport, CorpusError> {
+    let corpus_dir = corpus_dir.as_ref();
+    let read_dir_error = |error| CorpusError::ReadDir {
+        path: corpus_dir.to_path_buf(),
+        error,
+    };
+    let mut manifest_paths = vec![];
+    for entry in fs::read_dir(corpus_dir).map_err(read_dir_error)? {
+        let path = entry.map_err(read_dir_error)?.path();
+        if path.extension().and_then(|extension| extension.to_str()) == Some(MANIFEST_EXTENSION) {
+            manifest_paths.push(path);
+        }
+    }
+    manifest_paths.sort();
+
+    let contracts = manifest_paths
+        .iter()
+        .map(|manifest_path| run_contract(corpus_dir, manifest_path))
+        .collect();
+    Ok(CompatibilityReport { contracts })
+}
+
+fn run_contract(corpus_dir: &Path, manifest_path: &Path) -> ContractReport {
+    let name = manifest_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let invalid = |reason: String| ContractReport {
+        name: name.clone(),
+        outcome: ContractOutcome::Invalid(reason),
+        steps: vec![],
+    };
+
+    let manifest: Manifest = match fs::read(manifest_path)
+        .map_err(|error| error.to_string())
+        .and_then(|bytes| toml::from_slice(&bytes).map_err(|error| error.to_string()))
+    {
+        Ok(manifest) => manifest,
+        Err(error) => return invalid(format!("failed to load manifest: {}", error)),
+    };
+    let wasm_path = corpus_dir.join(
+        manifest
+            .wasm
+            .clone()
+            .unwrap_or_else(|| Path::new(&name).with_extension(WASM_EXTENSION)),
+    );
+    let module_bytes = match fs::read(&wasm_path) {
+        Ok(module_bytes) => module_bytes,
+        Err(error) => return invalid(format!("failed to read {}: {}", wasm_path.display(), error)),
+    };
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let mut steps = vec![];
+    let install_args = match to_runtime_args(&manifest.install.args) {
+        Ok(args) => args,
+        Err(error) => return invalid(format!("install: {}", error)),
+    };
+    let deploy_item = deploy_item_builder(steps.len())
+        .with_session_bytes(module_bytes, install_args)
+        .build();
+    steps.push(run_step(
+        &mut builder,
+        ExecuteRequestBuilder::from_deploy_item(deploy_item),
+        format!("install {}", wasm_path.display()),
+        manifest.install.expect,
+        manifest.install.error_contains.as_deref(),
+    ));
+
+    for call in &manifest.calls {
+        if !steps.iter().all(|step| step.passed) {
+            break;
+        }
+        let args = match to_runtime_args(&call.args) {
+            Ok(args) => args,
+            Err(error) => return invalid(format!("{}: {}", call, error)),
+        };
+        let deploy_item_builder = deploy_item_builder(steps.len());
+        let deploy_item = match (&call.contract, &call.package) {
+            (Some(contract), None) => deploy_item_builder
+                .with_stored_session_named_key(contract, &call.entry_point, args)
+                .build(),
+            (None, Some(package)) => deploy_item_builder
+                .with_stored_versioned_contract_by_name(
+                    package,
+                    call.version,
+                    &call.entry_point,
+                    args,
+                )
+                .build(),
+            _ => {
+                return invalid(format!(
+                    "{}: exactly one of contract and package must be given",
+                    call
+                ))
+            }
+        };
+        steps.push(run_step(
+            &mut builder,
+            ExecuteRequestBuilder::from_deploy_item(deploy_item),
+            call.to_string(),
+            call.expect,
+            call.error_contains.as_deref(),
+        ));
+    }
+
+    let outcome = if steps.iter().all(|step| step.passed) {
+        ContractOutcome::Compatible
+    } else {
+        ContractOutcome::Incompatible
+    };
+    ContractReport {
+        name,
+        outcome,
+        steps,
+    }
+}
+
+fn to_runtime_args(args: &[ManifestArg]) -> Result<RuntimeArgs, String> {
+    let mut runtime_args = RuntimeArgs::new();
+    for arg in args {
+        let cl_value = arg
+            .to_cl_value()
+            .map_err(|error| format!("invalid arg {}: {}", arg.name, error))?;
+        runtime_args.insert_cl_value(arg.name.clone(), cl_value);
+    }
+    Ok(runtime_args)
+}
+
+/// Returns a deploy item builder for the deploy at `index` of a contract's manifest, with a
+/// deterministic deploy hash so that runs are reproducible.
+fn deploy_item_builder(index: usize) -> DeployItemBuilder {
+    let mut deploy_hash = [0; 32];
+    deploy_hash[24..].copy_from_slice(&(index as u64).to_be_bytes());
+    let mut payment_args = RuntimeArgs::new();
+    payment_args
+        .insert(ARG_AMOUNT, *DEFAULT_PAYMENT)
+        .expect("should insert payment amount");
+    DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_empty_payment_bytes(payment_args)
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash(deploy_hash)
+}
+
+fn run_step(
+    builder: &mut InMemoryWasmTestBuilder,
+    request_builder: ExecuteRequestBuilder,
+    description: String,
+    expected: Expectation,
+    error_contains: Option<&str>,
+) -> StepReport {
+    builder.exec(request_builder.build()).commit();
+    let exec_result = builder.last_exec_result();
+    let error = exec_result.as_error().map(|error| format!("{:?}", error));
+    let passed = match (expected, &error) {
+        (Expectation::Success, None) => true,
+        (Expectation::Failure, Some(error)) => {
+            error_contains.map_or(true, |expected_error| error.contains(expected_error))
+        }
+        _ => false,
+    };
+    StepReport {
+        description,
+        expected,
+        error,
+        cost: exec_result.cost().value(),
+        passed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DO_NOTHING_WAT: &str = r#"(module (memory 1) (func (export "call")))"#;
+    const REVERT_WAT: &str = r#"(module
+        (import "env" "casper_revert" (func $revert (param i32)))
+        (memory 1)
+        (func (export "call") (call $revert (i32.const 65541))))"#;
+
+    fn write_contract(corpus_dir: &Path, name: &str, wat: &str, manifest: &str) {
+        let module_bytes = wat::parse_str(wat).unwrap();
+        fs::write(corpus_dir.join(format!("{}.wasm", name)), module_bytes).unwrap();
+        fs::write(corpus_dir.join(format!("{}.toml", name)), manifest).unwrap();
+    }
+
+    #[test]
+    fn should_convert_manifest_args() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [install]
+            args = [
+                { name = "amount", type = "U512", value = "1000000000000000000000" },
+                { name = "count", type = "U8", value = 7 },
+                { name = "flag", type = "Bool", value = true },
+                { name = "nothing", type = "Unit" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let args = to_runtime_args(&manifest.install.args).unwrap();
+        assert_eq!(
+            args.get("amount"),
+            Some(&CLValue::from_t(U512::from_dec_str("1000000000000000000000").unwrap()).unwrap())
+        );
+        assert_eq!(args.get("count"), Some(&CLValue::from_t(7u8).unwrap()));
+        assert_eq!(args.get("flag"), Some(&CLValue::from_t(true).unwrap()));
+        assert_eq!(args.get("nothing"), Some(&CLValue::from_t(()).unwrap()));
+
+        let out_of_range = ManifestArg {
+            name: "count".to_string(),
+            cl_type: "U8".to_string(),
+            value: Some(toml::Value::Integer(256)),
+        };
+        assert!(to_runtime_args(&[out_of_range]).is_err());
+    }
+
+    #[test]
+    fn should_report_compatibility_of_corpus() {
+        let corpus_dir = tempfile::tempdir().unwrap();
+        write_contract(corpus_dir.path(), "a_do_nothing", DO_NOTHING_WAT, "");
+        write_contract(
+            corpus_dir.path(),
+            "b_revert",
+            REVERT_WAT,
+            "[install]\nexpect = \"failure\"\nerror_contains = \"User(5)\"\n",
+        );
+        write_contract(
+            corpus_dir.path(),
+            "c_unexpected_revert",
+            REVERT_WAT,
+            "[[calls]]\ncontract = \"missing\"\nentry_point = \"call\"\n",
+        );
+        fs::write(corpus_dir.path().join("d_no_wasm.toml"), "").unwrap();
+
+        let report = run_corpus(corpus_dir.path()).unwrap();
+        let outcomes: Vec<_> = report
+            .contracts
+            .iter()
+            .map(|contract| (contract.name.as_str(), &contract.outcome))
+            .collect();
+        assert!(matches!(
+            outcomes.as_slice(),
+            [
+                ("a_do_nothing", ContractOutcome::Compatible),
+                ("b_revert", ContractOutcome::Compatible),
+                ("c_unexpected_revert", ContractOutcome::Incompatible),
+                ("d_no_wasm", ContractOutcome::Invalid(_)),
+            ]
+        ));
+        // The call is skipped, as the installation already failed.
+        assert_eq!(report.contracts[2].steps.len(), 1);
+        assert!(!report.is_compatible());
+    }
+}
@@ -7,11 +7,11 @@ use casper_execution_engine::core::engine_state::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::AccountHash, ContractHash, ContractPackageHash, ContractVersion, DeployHash, HashAddr,
-    RuntimeArgs,
+    account::AccountHash, runtime_args, system::mint, ContractHash, ContractPackageHash,
+    ContractVersion, DeployHash, HashAddr, RuntimeArgs, U512,
 };
 
-use crate::{utils, DEFAULT_GAS_PRICE};
+use crate::{utils, ARG_AMOUNT, DEFAULT_GAS_PRICE, DEFAULT_PAYMENT};
 
 #[derive(Default)]
 struct DeployItemData {
@@ -34,6 +34,79 @@ impl DeployItemBuilder {
         Default::default()
     }
 
+    /// Returns a builder for a native transfer of `amount` from the main purse of `from` to the
+    /// account `to`.
+    pub fn transfer<T: Into<U512>>(
+        from: AccountHash,
+        to: AccountHash,
+        amount: T,
+        id: Option<u64>,
+    ) -> Self {
+        DeployItemBuilder::new()
+            .with_address(from)
+            .with_authorization_keys(&[from])
+            .with_empty_payment_bytes(runtime_args! {})
+            .with_transfer_args(runtime_args! {
+                mint::ARG_TARGET => to,
+                mint::ARG_AMOUNT => amount.into(),
+                mint::ARG_ID => id,
+            })
+    }
+
+    /// Returns a builder for a deploy by `sender` calling `entry_point` of the contract stored
+    /// under the named key `contract_name`, paid for with the default payment.
+    pub fn call_contract_by_name(
+        sender: AccountHash,
+        contract_name: &str,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) -> Self {
+        DeployItemBuilder::for_sender(sender).with_stored_session_named_key(
+            contract_name,
+            entry_point,
+            args,
+        )
+    }
+
+    /// Returns a builder for a deploy by `sender` calling `entry_point` of the given version of
+    /// the contract package stored under the named key `package_name`, paid for with the default
+    /// payment.
+    ///
+    /// If `version` is `None`, the latest enabled version is called.
+    pub fn call_versioned_contract(
+        sender: AccountHash,
+        package_name: &str,
+        version: Option<ContractVersion>,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) -> Self {
+        DeployItemBuilder::for_sender(sender).with_stored_versioned_contract_by_name(
+            package_name,
+            version,
+            entry_point,
+            args,
+        )
+    }
+
+    /// Returns a builder for a deploy by `sender` running the session code in `wasm_file`,
+    /// typically installing a contract, paid for with the default payment.
+    pub fn install_contract<T: AsRef<Path>>(
+        sender: AccountHash,
+        wasm_file: T,
+        args: RuntimeArgs,
+    ) -> Self {
+        DeployItemBuilder::for_sender(sender).with_session_code(wasm_file, args)
+    }
+
+    /// Returns a builder for a deploy by `sender`, paying [`DEFAULT_PAYMENT`] using the standard
+    /// payment code.
+    fn for_sender(sender: AccountHash) -> Self {
+        DeployItemBuilder::new()
+            .with_address(sender)
+            .with_authorization_keys(&[sender])
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+    }
+
     /// Sets the address of the deploy.
     pub fn with_address(mut self, address: AccountHash) -> Self {
         self.deploy_item.address = Some(address);
@@ -311,4 +384,41 @@ mod tests {
             .build();
         assert_ne!(deploy.deploy_hash, DeployHash::default());
     }
+
+    #[test]
+    fn should_fill_in_presets() {
+        let sender = AccountHash::new([42; 32]);
+        let recipient = AccountHash::new([43; 32]);
+
+        let deploy = DeployItemBuilder::transfer(sender, recipient, 10, Some(1)).build();
+        assert_eq!(deploy.address, sender);
+        assert_eq!(deploy.authorization_keys, BTreeSet::from([sender]));
+        assert!(matches!(
+            deploy.session,
+            ExecutableDeployItem::Transfer { ref args }
+                if args.get(mint::ARG_TARGET).is_some() && args.get(mint::ARG_ID).is_some()
+        ));
+
+        let deploy = DeployItemBuilder::call_versioned_contract(
+            sender,
+            "package",
+            Some(2),
+            "entry_point",
+            RuntimeArgs::new(),
+        )
+        .build();
+        assert_eq!(deploy.authorization_keys, BTreeSet::from([sender]));
+        assert!(matches!(
+            deploy.session,
+            ExecutableDeployItem::StoredVersionedContractByName {
+                version: Some(2),
+                ..
+            }
+        ));
+        assert!(matches!(
+            deploy.payment,
+            ExecutableDeployItem::ModuleBytes { ref module_bytes, ref args }
+                if module_bytes.is_empty() && args.get(ARG_AMOUNT).is_some()
+        ));
+    }
 }
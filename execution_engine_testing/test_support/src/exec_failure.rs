@@ -0,0 +1,194 @@
+use std::{
+    env,
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+};
+
+use casper_execution_engine::core::{
+    engine_state::{self, execution_result::ExecutionResult, DeployItem, ExecutableDeployItem},
+    execution,
+    runtime::host_call_trace,
+};
+use casper_types::{contracts::NamedKeys, ContractHash, RuntimeArgs};
+
+/// Environment variable holding the number of host function calls to report when an execution
+/// fails unexpectedly.  Host function calls are not traced if unset or zero.
+pub(crate) const HOST_CALL_TRACE_ENV_VAR: &str = "CASPER_TRACE_HOST_CALLS";
+
+/// Starts tracing host function calls on the current thread, if enabled by
+/// [`HOST_CALL_TRACE_ENV_VAR`].
+pub(crate) fn start_host_call_trace() {
+    let capacity = env::var(HOST_CALL_TRACE_ENV_VAR)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+    host_call_trace::enable(capacity);
+}
+
+/// Returns the host function calls traced since [`start_host_call_trace`], if enabled.
+pub(crate) fn finish_host_call_trace() -> Option<Vec<String>> {
+    host_call_trace::is_enabled().then(host_call_trace::take)
+}
+
+/// A human-readable account of an execution which unexpectedly failed.
+pub(crate) struct ExecFailureReport<'a> {
+    /// The failed execution result.
+    pub(crate) exec_result: &'a ExecutionResult,
+    /// The executed deploy, if known.
+    pub(crate) deploy: Option<&'a DeployItem>,
+    /// The named keys of the deploy's account, as of the latest post-state.
+    pub(crate) account_named_keys: Option<&'a NamedKeys>,
+    /// The stored contract called by the session code and its named keys, if any.
+    pub(crate) contract: Option<(ContractHash, &'a NamedKeys)>,
+    /// The latest host function calls, if traced.
+    pub(crate) host_calls: Option<&'a [String]>,
+}
+
+impl<'a> Display for ExecFailureReport<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Expected successful execution result, but instead got a failure."
+        )?;
+
+        writeln!(f, "\nError:")?;
+        match self.exec_result.as_error() {
+            Some(error) => {
+                writeln!(f, "  {}", error)?;
+                if let Some(code) = api_error_code(error) {
+                    writeln!(f, "  api error code: {}", code)?;
+                }
+                let mut source = error.source();
+                while let Some(cause) = source {
+                    writeln!(f, "  caused by: {}", cause)?;
+                    source = cause.source();
+                }
+            }
+            None => writeln!(f, "  none")?,
+        }
+        writeln!(f, "  cost: {}", self.exec_result.cost())?;
+
+        if let Some(deploy) = self.deploy {
+            writeln!(
+                f,
+                "\nDeploy {:?} from account {}:",
+                deploy.deploy_hash, deploy.address
+            )?;
+            write_deploy_item(f, "session", &deploy.session)?;
+            write_deploy_item(f, "payment", &deploy.payment)?;
+        }
+
+        if let Some(named_keys) = self.account_named_keys {
+            writeln!(f, "\nAccount named keys:")?;
+            write_named_keys(f, named_keys)?;
+        }
+
+        if let Some((contract_hash, named_keys)) = self.contract {
+            writeln!(f, "\nNamed keys of called contract {}:", contract_hash)?;
+            write_named_keys(f, named_keys)?;
+        }
+
+        match self.host_calls {
+            Some(host_calls) => {
+                writeln!(f, "\nLatest {} host function calls:", host_calls.len())?;
+                for host_call in host_calls {
+                    writeln!(f, "  {}", host_call)?;
+                }
+            }
+            None => writeln!(
+                f,
+                "\nSet {}=<count> to list the latest host function calls.",
+                HOST_CALL_TRACE_ENV_VAR
+            )?,
+        }
+
+        writeln!(f, "\nFull execution result: {:#?}", self.exec_result)
+    }
+}
+
+/// Returns the numeric code of the API error the execution was reverted with, if any.
+fn api_error_code(error: &engine_state::Error) -> Option<u32> {
+    match error {
+        engine_state::Error::Exec(execution::Error::Revert(api_error)) => {
+            Some(u32::from(*api_error))
+        }
+        _ => None,
+    }
+}
+
+fn write_deploy_item(
+    f: &mut Formatter<'_>,
+    label: &str,
+    item: &ExecutableDeployItem,
+) -> fmt::Result {
+    let target = match item {
+        ExecutableDeployItem::ModuleBytes { module_bytes, .. } => {
+            format!("module bytes ({} bytes)", module_bytes.len())
+        }
+        ExecutableDeployItem::StoredContractByHash {
+            hash, entry_point, ..
+        } => format!("contract {}, entry point {:?}", hash, entry_point),
+        ExecutableDeployItem::StoredContractByName {
+            name, entry_point, ..
+        } => format!("contract named {:?}, entry point {:?}", name, entry_point),
+        ExecutableDeployItem::StoredVersionedContractByHash {
+            hash,
+            version,
+            entry_point,
+            ..
+        } => format!(
+            "contract package {}, version {:?}, entry point {:?}",
+            hash, version, entry_point
+        ),
+        ExecutableDeployItem::StoredVersionedContractByName {
+            name,
+            version,
+            entry_point,
+            ..
+        } => format!(
+            "contract package named {:?}, version {:?}, entry point {:?}",
+            name, version, entry_point
+        ),
+        ExecutableDeployItem::Transfer { .. } => "native transfer".to_string(),
+    };
+    writeln!(f, "  {}: {}", label, target)?;
+    write_args(f, item.args())
+}
+
+fn write_args(f: &mut Formatter<'_>, args: &RuntimeArgs) -> fmt::Result {
+    for named_arg in args.named_args() {
+        let cl_value = named_arg.cl_value();
+        writeln!(
+            f,
+            "    {}: {:?} = {:?}",
+            named_arg.name(),
+            cl_value.cl_type(),
+            cl_value.inner_bytes()
+        )?;
+    }
+    Ok(())
+}
+
+fn write_named_keys(f: &mut Formatter<'_>, named_keys: &NamedKeys) -> fmt::Result {
+    if named_keys.is_empty() {
+        return writeln!(f, "  none");
+    }
+    for (name, key) in named_keys {
+        writeln!(f, "  {}: {}", name, key)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::ApiError;
+
+    use super::*;
+
+    #[test]
+    fn should_report_api_error_code() {
+        let error = engine_state::Error::Exec(execution::Error::Revert(ApiError::User(7)));
+        assert_eq!(api_error_code(&error), Some(u32::from(ApiError::User(7))));
+        assert_eq!(api_error_code(&engine_state::Error::Deploy), None);
+    }
+}
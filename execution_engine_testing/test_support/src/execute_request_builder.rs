@@ -6,7 +6,7 @@ use casper_execution_engine::core::engine_state::{
     deploy_item::DeployItem, execute_request::ExecuteRequest,
 };
 use casper_types::{
-    account::AccountHash, runtime_args, ContractHash, ContractPackageHash, ContractVersion,
+    account::AccountHash, runtime_args, ContractHash, ContractPackageHash, ContractVersion, EraId,
     ProtocolVersion, RuntimeArgs,
 };
 
@@ -49,6 +49,18 @@ impl ExecuteRequestBuilder {
         self
     }
 
+    /// Sets the block height of the [`ExecuteRequest`].
+    pub fn with_block_height(mut self, block_height: u64) -> Self {
+        self.execute_request.block_height = block_height;
+        self
+    }
+
+    /// Sets the era ID of the [`ExecuteRequest`].
+    pub fn with_era_id(mut self, era_id: EraId) -> Self {
+        self.execute_request.era_id = era_id;
+        self
+    }
+
     /// Sets the protocol version of the [`ExecuteRequest`].
     pub fn with_protocol_version(mut self, protocol_version: ProtocolVersion) -> Self {
         self.execute_request.protocol_version = protocol_version;
@@ -72,18 +84,8 @@ impl ExecuteRequestBuilder {
         session_file: &str,
         session_args: RuntimeArgs,
     ) -> Self {
-        let mut rng = rand::thread_rng();
-        let deploy_hash: [u8; 32] = rng.gen();
-
-        let deploy = DeployItemBuilder::new()
-            .with_address(account_hash)
-            .with_session_code(session_file, session_args)
-            .with_empty_payment_bytes(runtime_args! {
-                ARG_AMOUNT => *DEFAULT_PAYMENT
-            })
-            .with_authorization_keys(&[account_hash])
-            .with_deploy_hash(deploy_hash)
-            .build();
+        let deploy =
+            DeployItemBuilder::install_contract(account_hash, session_file, session_args).build();
 
         ExecuteRequestBuilder::new().push_deploy(deploy)
     }
@@ -138,16 +140,9 @@ impl ExecuteRequestBuilder {
         entry_point: &str,
         args: RuntimeArgs,
     ) -> Self {
-        let mut rng = rand::thread_rng();
-        let deploy_hash = rng.gen();
-
-        let deploy = DeployItemBuilder::new()
-            .with_address(sender)
-            .with_stored_session_named_key(contract_name, entry_point, args)
-            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT, })
-            .with_authorization_keys(&[sender])
-            .with_deploy_hash(deploy_hash)
-            .build();
+        let deploy =
+            DeployItemBuilder::call_contract_by_name(sender, contract_name, entry_point, args)
+                .build();
 
         ExecuteRequestBuilder::new().push_deploy(deploy)
     }
@@ -187,16 +182,14 @@ impl ExecuteRequestBuilder {
         entry_point_name: &str,
         args: RuntimeArgs,
     ) -> Self {
-        let mut rng = rand::thread_rng();
-        let deploy_hash = rng.gen();
-
-        let deploy = DeployItemBuilder::new()
-            .with_address(sender)
-            .with_stored_versioned_contract_by_name(contract_name, version, entry_point_name, args)
-            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT, })
-            .with_authorization_keys(&[sender])
-            .with_deploy_hash(deploy_hash)
-            .build();
+        let deploy = DeployItemBuilder::call_versioned_contract(
+            sender,
+            contract_name,
+            version,
+            entry_point_name,
+            args,
+        )
+        .build();
 
         ExecuteRequestBuilder::new().push_deploy(deploy)
     }
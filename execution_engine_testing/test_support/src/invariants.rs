@@ -0,0 +1,569 @@
+//! Checks of system contract invariants, run by a [`WasmTestBuilder`](crate::WasmTestBuilder)
+//! after every commit if enabled.
+//!
+//! The checks catch global state corruption at the commit introducing it, rather than when a later
+//! assertion of the test happens to trip over it:
+//!
+//! * every balance key holds a `U512`, and the purses hold at least the total supply,
+//! * tokens are neither created nor destroyed outside of the mint, i.e. the amount by which the
+//!   purses' balances exceed the total supply only grows when validators are slashed,
+//! * every bonding purse holds the stakes, unclaimed delegator rewards and pending unbonds it backs,
+//!   and no more unless tokens were burned,
+//! * the seigniorage recipients snapshot covers a contiguous range of eras starting at the current
+//!   era, with a computable total stake for every recipient.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display, Formatter},
+};
+
+use casper_hashing::Digest;
+use casper_types::{
+    system::auction::{Bids, SeigniorageRecipientsSnapshot, UnbondingPurses},
+    EraId, Key, StoredValue, URefAddr, U512,
+};
+
+/// Environment variable enabling the invariant checks for all builders if set to anything other
+/// than `0`.
+pub(crate) const CHECK_INVARIANTS_ENV_VAR: &str = "CASPER_CHECK_INVARIANTS";
+
+/// Returns whether the invariant checks are enabled by [`CHECK_INVARIANTS_ENV_VAR`].
+pub(crate) fn enabled_by_env() -> bool {
+    env::var(CHECK_INVARIANTS_ENV_VAR)
+        .map(|value| !value.is_empty() && value != "0")
+        .unwrap_or_default()
+}
+
+/// The parts of a global state covered by the invariant checks.
+pub(crate) struct SystemState {
+    /// The state root hash of the global state.
+    pub(crate) state_root_hash: Digest,
+    /// The total supply tracked by the mint.
+    pub(crate) total_supply: U512,
+    /// The values stored under all balance keys.
+    pub(crate) balances: BTreeMap<URefAddr, StoredValue>,
+    /// All bids of the auction.
+    pub(crate) bids: Bids,
+    /// All pending unbonds.
+    pub(crate) unbonds: UnbondingPurses,
+    /// The current era of the auction.
+    pub(crate) era_id: EraId,
+    /// The seigniorage recipients snapshot of the auction.
+    pub(crate) seigniorage_recipients_snapshot: SeigniorageRecipientsSnapshot,
+}
+
+/// A violated invariant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum InvariantViolation {
+    /// A balance key holds something other than a `U512`.
+    MalformedBalance { key: Key, value: String },
+    /// The sum of all balances overflows.
+    BalanceOverflow,
+    /// The purses hold less than the total supply.
+    MissingTokens {
+        total_supply: U512,
+        total_balance: U512,
+    },
+    /// Tokens were created or destroyed without the mint accounting for them.
+    SupplyNotConserved { previous: U512, current: U512 },
+    /// A bonding purse holds less than the stakes, rewards and unbonds it backs.
+    UnderfundedBondingPurse {
+        purse: URefAddr,
+        balance: U512,
+        backed: U512,
+    },
+    /// A bonding purse holds more than the stakes, rewards and unbonds it backs, although no tokens
+    /// were burned.
+    OverfundedBondingPurse {
+        purse: URefAddr,
+        balance: U512,
+        backed: U512,
+    },
+    /// The seigniorage recipients snapshot does not start at the current era.
+    SnapshotStart {
+        era_id: EraId,
+        first_era_id: Option<EraId>,
+    },
+    /// The seigniorage recipients snapshot skips an era.
+    SnapshotGap { after: EraId, next: EraId },
+    /// The total stake of a seigniorage recipient overflows.
+    RecipientStakeOverflow { era_id: EraId, public_key: String },
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::MalformedBalance { key, value } => {
+                write!(f, "{} does not hold a U512 balance, but {}", key, value)
+            }
+            InvariantViolation::BalanceOverflow => write!(f, "sum of all balances overflows"),
+            InvariantViolation::MissingTokens {
+                total_supply,
+                total_balance,
+            } => write!(
+                f,
+                "purses hold {} motes, less than the total supply of {}",
+                total_balance, total_supply
+            ),
+            InvariantViolation::SupplyNotConserved { previous, current } => write!(
+                f,
+                "total supply not conserved: purses hold {} motes more than the total supply, but \
+                held {} more before the commit",
+                current, previous
+            ),
+            InvariantViolation::UnderfundedBondingPurse {
+                purse,
+                balance,
+                backed,
+            } => write!(
+                f,
+                "{} holds {} motes, but backs {} motes of stakes, rewards and unbonds",
+                Key::Balance(*purse),
+                balance,
+                backed
+            ),
+            InvariantViolation::OverfundedBondingPurse {
+                purse,
+                balance,
+                backed,
+            } => write!(
+                f,
+                "{} holds {} motes, but only backs {} motes of stakes, rewards and \
+                unbonds",
+                Key::Balance(*purse),
+                balance,
+                backed
+            ),
+            InvariantViolation::SnapshotStart {
+                era_id,
+                first_era_id: Some(first_era_id),
+            } => write!(
+                f,
+                "seigniorage recipients snapshot starts at era {} instead of current era {}",
+                first_era_id, era_id
+            ),
+            InvariantViolation::SnapshotStart {
+                era_id,
+                first_era_id: None,
+            } => write!(
+                f,
+                "seigniorage recipients snapshot is empty in era {}",
+                era_id
+            ),
+            InvariantViolation::SnapshotGap { after, next } => write!(
+                f,
+                "seigniorage recipients snapshot skips from era {} to era {}",
+                after, next
+            ),
+            InvariantViolation::RecipientStakeOverflow { era_id, public_key } => write!(
+                f,
+                "total stake of seigniorage recipient {} in era {} overflows",
+                public_key, era_id
+            ),
+        }
+    }
+}
+
+/// Runs the invariant checks, remembering what is needed to check the next commit.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InvariantChecker {
+    /// The latest checked state root hash, and the amount by which its purses' balances exceeded
+    /// the total supply.
+    last_checked: Option<(Digest, U512)>,
+}
+
+impl InvariantChecker {
+    /// Checks the state resulting from a commit on top of `pre_state_hash`.
+    ///
+    /// `may_burn` allows the commit to burn tokens, i.e. to reduce the total supply without
+    /// removing the tokens from their purses, as slashing does.  Supply conservation is only
+    /// checked if the previous check was of the pre-state, as commits not checked (like genesis or
+    /// upgrades) are not covered by it.
+    pub(crate) fn check(
+        &mut self,
+        pre_state_hash: Digest,
+        state: &SystemState,
+        may_burn: bool,
+    ) -> Vec<InvariantViolation> {
+        let mut violations = Vec::new();
+
+        let balances = decode_balances(&state.balances, &mut violations);
+        let total_balance = balances
+            .values()
+            .try_fold(U512::zero(), |total, balance| total.checked_add(*balance));
+        let burned = match total_balance {
+            Some(total_balance) if total_balance < state.total_supply => {
+                violations.push(InvariantViolation::MissingTokens {
+                    total_supply: state.total_supply,
+                    total_balance,
+                });
+                None
+            }
+            Some(total_balance) => Some(total_balance - state.total_supply),
+            None => {
+                violations.push(InvariantViolation::BalanceOverflow);
+                None
+            }
+        };
+
+        if let (Some((last_state_hash, previous)), Some(current)) = (self.last_checked, burned) {
+            let conserved = current == previous || (may_burn && current > previous);
+            if last_state_hash == pre_state_hash && !conserved {
+                violations.push(InvariantViolation::SupplyNotConserved { previous, current });
+            }
+        }
+
+        check_bonding_purses(
+            &balances,
+            &state.bids,
+            &state.unbonds,
+            burned == Some(U512::zero()),
+            &mut violations,
+        );
+        check_snapshot(
+            state.era_id,
+            &state.seigniorage_recipients_snapshot,
+            &mut violations,
+        );
+
+        self.last_checked = burned.map(|burned| (state.state_root_hash, burned));
+        violations
+    }
+}
+
+/// Decodes the balances, recording the balance keys not holding a `U512`.
+fn decode_balances(
+    values: &BTreeMap<URefAddr, StoredValue>,
+    violations: &mut Vec<InvariantViolation>,
+) -> BTreeMap<URefAddr, U512> {
+    let mut balances = BTreeMap::new();
+    for (addr, value) in values {
+        match value
+            .as_cl_value()
+            .and_then(|cl_value| cl_value.clone().into_t::<U512>().ok())
+        {
+            Some(balance) => {
+                balances.insert(*addr, balance);
+            }
+            None => violations.push(InvariantViolation::MalformedBalance {
+                key: Key::Balance(*addr),
+                value: format!("{:?}", value),
+            }),
+        }
+    }
+    balances
+}
+
+/// Checks every bonding purse holds the stakes, unclaimed delegator rewards and unbonds it backs,
+/// and exactly these if `exact` is set.
+fn check_bonding_purses(
+    balances: &BTreeMap<URefAddr, U512>,
+    bids: &Bids,
+    unbonds: &UnbondingPurses,
+    exact: bool,
+    violations: &mut Vec<InvariantViolation>,
+) {
+    let mut backed: BTreeMap<URefAddr, U512> = BTreeMap::new();
+    let mut back = |purse: URefAddr, amount: U512| {
+        let entry = backed.entry(purse).or_default();
+        *entry = entry.saturating_add(amount);
+    };
+    for bid in bids.values() {
+        back(bid.bonding_purse().addr(), *bid.staked_amount());
+        for delegator in bid.delegators().values() {
+            back(delegator.bonding_purse().addr(), *delegator.staked_amount());
+            back(bid.bonding_purse().addr(), *delegator.unclaimed_rewards());
+        }
+    }
+    for unbonding_purse in unbonds.values().flatten() {
+        back(
+            unbonding_purse.bonding_purse().addr(),
+            *unbonding_purse.amount(),
+        );
+    }
+
+    for (purse, backed) in backed {
+        let balance = balances.get(&purse).copied().unwrap_or_default();
+        if balance < backed {
+            violations.push(InvariantViolation::UnderfundedBondingPurse {
+                purse,
+                balance,
+                backed,
+            });
+        } else if exact && balance > backed {
+            violations.push(InvariantViolation::OverfundedBondingPurse {
+                purse,
+                balance,
+                backed,
+            });
+        }
+    }
+}
+
+/// Checks the seigniorage recipients snapshot covers contiguous eras starting at `era_id`.
+fn check_snapshot(
+    era_id: EraId,
+    snapshot: &SeigniorageRecipientsSnapshot,
+    violations: &mut Vec<InvariantViolation>,
+) {
+    let first_era_id = snapshot.keys().next().copied();
+    if first_era_id != Some(era_id) {
+        violations.push(InvariantViolation::SnapshotStart {
+            era_id,
+            first_era_id,
+        });
+    }
+    for (after, next) in snapshot.keys().zip(snapshot.keys().skip(1)) {
+        if after.successor() != *next {
+            violations.push(InvariantViolation::SnapshotGap {
+                after: *after,
+                next: *next,
+            });
+        }
+    }
+    for (era_id, recipients) in snapshot {
+        for (public_key, recipient) in recipients {
+            if recipient.total_stake().is_none() {
+                violations.push(InvariantViolation::RecipientStakeOverflow {
+                    era_id: *era_id,
+                    public_key: public_key.to_string(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{
+        system::auction::{Bid, Delegator, SeigniorageRecipient, UnbondingPurse},
+        AccessRights, CLValue, PublicKey, SecretKey, URef,
+    };
+
+    use super::*;
+
+    fn public_key(seed: u8) -> PublicKey {
+        let secret_key = SecretKey::ed25519_from_bytes([seed; 32]).unwrap();
+        PublicKey::from(&secret_key)
+    }
+
+    fn purse(seed: u8) -> URef {
+        URef::new([seed; 32], AccessRights::READ_ADD_WRITE)
+    }
+
+    fn balance(amount: u64) -> StoredValue {
+        StoredValue::CLValue(CLValue::from_t(U512::from(amount)).unwrap())
+    }
+
+    /// A state with a validator staking 100 motes, delegated 50 motes with 5 motes of unclaimed
+    /// rewards, and a pending unbond of 10 motes from the validator's bonding purse.
+    fn state() -> SystemState {
+        let validator = public_key(1);
+        let delegator_key = public_key(2);
+        let mut delegator = Delegator::unlocked(
+            delegator_key.clone(),
+            U512::from(50),
+            purse(2),
+            validator.clone(),
+        );
+        delegator.add_unclaimed_rewards(U512::from(5)).unwrap();
+        let mut bid = Bid::unlocked(validator.clone(), purse(1), U512::from(100), 10);
+        bid.delegators_mut()
+            .insert(delegator_key.clone(), delegator);
+
+        let unbond = UnbondingPurse::new(
+            purse(1),
+            validator.clone(),
+            validator.clone(),
+            EraId::new(1),
+            U512::from(10),
+            None,
+        );
+
+        let recipients: BTreeMap<_, _> = vec![(
+            validator.clone(),
+            SeigniorageRecipient::new(
+                U512::from(100),
+                10,
+                vec![(delegator_key, U512::from(50))].into_iter().collect(),
+            ),
+        )]
+        .into_iter()
+        .collect();
+
+        SystemState {
+            state_root_hash: Digest::hash([1]),
+            total_supply: U512::from(1_165),
+            balances: vec![
+                (purse(1).addr(), balance(115)),
+                (purse(2).addr(), balance(50)),
+                (purse(3).addr(), balance(1_000)),
+            ]
+            .into_iter()
+            .collect(),
+            bids: vec![(validator.clone(), bid)].into_iter().collect(),
+            unbonds: vec![(validator.to_account_hash(), vec![unbond])]
+                .into_iter()
+                .collect(),
+            era_id: EraId::new(2),
+            seigniorage_recipients_snapshot: (2..=5)
+                .map(|era_id| (EraId::new(era_id), recipients.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn should_accept_consistent_state() {
+        let mut checker = InvariantChecker::default();
+        assert_eq!(checker.check(Digest::hash([0]), &state(), false), vec![]);
+    }
+
+    #[test]
+    fn should_detect_malformed_balance() {
+        let mut state = state();
+        state
+            .balances
+            .insert(purse(4).addr(), StoredValue::CLValue(CLValue::unit()));
+        let violations = InvariantChecker::default().check(Digest::hash([0]), &state, false);
+        assert!(matches!(
+            violations.as_slice(),
+            [InvariantViolation::MalformedBalance { key, .. }] if *key == Key::Balance(purse(4).addr())
+        ));
+    }
+
+    #[test]
+    fn should_detect_missing_tokens() {
+        let mut state = state();
+        state.balances.insert(purse(3).addr(), balance(999));
+        let violations = InvariantChecker::default().check(Digest::hash([0]), &state, false);
+        assert_eq!(
+            violations,
+            vec![InvariantViolation::MissingTokens {
+                total_supply: U512::from(1_165),
+                total_balance: U512::from(1_164),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_detect_tokens_created_outside_of_mint() {
+        let mut checker = InvariantChecker::default();
+        let mut state = state();
+        assert_eq!(checker.check(Digest::hash([0]), &state, false), vec![]);
+
+        let pre_state_hash = state.state_root_hash;
+        state.state_root_hash = Digest::hash([2]);
+        state.balances.insert(purse(3).addr(), balance(1_001));
+        assert_eq!(
+            checker.check(pre_state_hash, &state, false),
+            vec![InvariantViolation::SupplyNotConserved {
+                previous: U512::zero(),
+                current: U512::one(),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_only_check_conservation_on_top_of_checked_state() {
+        let mut checker = InvariantChecker::default();
+        let mut state = state();
+        assert_eq!(checker.check(Digest::hash([0]), &state, false), vec![]);
+
+        // Minting credits the purse and raises the total supply.
+        state.state_root_hash = Digest::hash([2]);
+        state.balances.insert(purse(3).addr(), balance(1_001));
+        state.total_supply += U512::one();
+        assert_eq!(checker.check(Digest::hash([1]), &state, false), vec![]);
+
+        // Tokens appearing on top of an unchecked state are not attributed to the commit.
+        state.state_root_hash = Digest::hash([3]);
+        state.balances.insert(purse(3).addr(), balance(1_002));
+        assert!(checker.check(Digest::hash([9]), &state, false).is_empty());
+    }
+
+    #[test]
+    fn should_allow_burning_slashed_stake() {
+        let mut checker = InvariantChecker::default();
+        let mut state = state();
+        assert_eq!(checker.check(Digest::hash([0]), &state, false), vec![]);
+
+        // Slashing zeroes the stakes and rewards and reduces the total supply, leaving the tokens in
+        // the bonding purses.
+        let pre_state_hash = state.state_root_hash;
+        state.state_root_hash = Digest::hash([2]);
+        for bid in state.bids.values_mut() {
+            *bid.staked_amount_mut() = U512::zero();
+            for delegator in bid.delegators_mut().values_mut() {
+                *delegator.staked_amount_mut() = U512::zero();
+                delegator.take_unclaimed_rewards();
+            }
+        }
+        state.unbonds.clear();
+        state.total_supply = U512::from(1_000);
+        assert_eq!(
+            checker.check(pre_state_hash, &state, false),
+            vec![InvariantViolation::SupplyNotConserved {
+                previous: U512::zero(),
+                current: U512::from(165),
+            }]
+        );
+        assert_eq!(checker.check(pre_state_hash, &state, true), vec![]);
+    }
+
+    #[test]
+    fn should_detect_unbacked_bonding_purse() {
+        let mut state = state();
+        state.balances.insert(purse(1).addr(), balance(114));
+        state.balances.insert(purse(3).addr(), balance(1_001));
+        let violations = InvariantChecker::default().check(Digest::hash([0]), &state, false);
+        assert_eq!(
+            violations,
+            vec![InvariantViolation::UnderfundedBondingPurse {
+                purse: purse(1).addr(),
+                balance: U512::from(114),
+                backed: U512::from(115),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_detect_overfunded_bonding_purse() {
+        let mut state = state();
+        state.balances.insert(purse(2).addr(), balance(51));
+        state.balances.insert(purse(3).addr(), balance(999));
+        let violations = InvariantChecker::default().check(Digest::hash([0]), &state, false);
+        assert_eq!(
+            violations,
+            vec![InvariantViolation::OverfundedBondingPurse {
+                purse: purse(2).addr(),
+                balance: U512::from(51),
+                backed: U512::from(50),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_detect_inconsistent_snapshot() {
+        let mut state = state();
+        let recipients = state.seigniorage_recipients_snapshot[&EraId::new(2)].clone();
+        state.seigniorage_recipients_snapshot.remove(&EraId::new(4));
+        state
+            .seigniorage_recipients_snapshot
+            .insert(EraId::new(1), recipients);
+        let violations = InvariantChecker::default().check(Digest::hash([0]), &state, false);
+        assert_eq!(
+            violations,
+            vec![
+                InvariantViolation::SnapshotStart {
+                    era_id: EraId::new(2),
+                    first_era_id: Some(EraId::new(1)),
+                },
+                InvariantViolation::SnapshotGap {
+                    after: EraId::new(3),
+                    next: EraId::new(5),
+                },
+            ]
+        );
+    }
+}
@@ -10,16 +10,25 @@
 mod additive_map_diff;
 /// Utility methods for running the auction in a test or bench context.
 pub mod auction;
+mod auction_state;
 mod chainspec_config;
+/// Utilities for running a corpus of real-world contracts and reporting their compatibility.
+pub mod corpus;
 mod deploy_item_builder;
+mod exec_failure;
 mod execute_request_builder;
+mod invariants;
 mod step_request_builder;
+/// Utilities for stress testing an engine state shared between threads.
+pub mod stress;
 /// Utilities for running transfers in a test or bench context.
 pub mod transfer;
 mod upgrade_request_builder;
 pub mod utils;
 mod wasm_test_builder;
 
+use std::collections::BTreeMap;
+
 use num_rational::Ratio;
 use once_cell::sync::Lazy;
 
@@ -34,6 +43,7 @@ use casper_types::{account::AccountHash, Motes, ProtocolVersion, PublicKey, Secr
 
 use crate::chainspec_config::PRODUCTION_PATH;
 pub use additive_map_diff::AdditiveMapDiff;
+pub use auction_state::{Delegation, EraValidatorSet, ValidatorBid};
 pub use chainspec_config::ChainspecConfig;
 pub use deploy_item_builder::DeployItemBuilder;
 pub use execute_request_builder::ExecuteRequestBuilder;
@@ -148,6 +158,7 @@ pub static DEFAULT_EXEC_CONFIG: Lazy<ExecConfig> = Lazy::new(|| {
         DEFAULT_ROUND_SEIGNIORAGE_RATE,
         DEFAULT_UNBONDING_DELAY,
         DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+        BTreeMap::new(),
     )
 });
 /// Default [`GenesisConfig`].
@@ -0,0 +1,414 @@
+//! Stress testing an LMDB-backed engine state shared between threads.
+//!
+//! A [`StressTest`] runs many overlapping execute, commit and query requests against a single
+//! [`EngineState`] from several threads, the way the contract runtime of a node executes blocks
+//! while serving queries.  Every operation picks one of the state roots produced so far, so the
+//! threads keep branching off and revisiting the same parts of the trie store.
+//!
+//! The following invariants are checked, and every violation is collected into a
+//! [`StressReport`]:
+//!
+//! * executing and committing the same deploy on the same root always yields the same post state
+//!   hash, regardless of which thread did it and what ran concurrently;
+//! * every root produced by a commit can be queried for each of the configured keys, both right
+//!   after the commit and once all threads are done.
+//!
+//! The deploys are executed with a fixed block time, and are cloned for every execution, so they
+//! keep their deploy hashes.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use casper_execution_engine::{
+    core::engine_state::{DeployItem, EngineState, QueryRequest, QueryResult},
+    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    storage::global_state::lmdb::LmdbGlobalState,
+};
+use casper_hashing::Digest;
+use casper_types::Key;
+
+use crate::{ExecuteRequestBuilder, LmdbWasmTestBuilder, DEFAULT_ACCOUNT_ADDR, DEFAULT_BLOCK_TIME};
+
+/// The default number of threads issuing requests.
+pub const DEFAULT_THREADS: usize = 8;
+/// The default number of operations run by each thread.
+pub const DEFAULT_OPERATIONS_PER_THREAD: usize = 50;
+/// One in this many operations is a query of a random root, the rest execute and commit a deploy.
+const QUERY_ONE_IN: u32 = 3;
+
+/// A broken invariant found by a [`StressTest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// Committing the same deploy on the same root yielded different post state hashes.
+    NonDeterministicCommit {
+        /// The root the deploy was executed on.
+        pre_state_hash: Digest,
+        /// The index of the deploy in the stress test's workload.
+        deploy_index: usize,
+        /// The post state hash of the first commit.
+        expected: Digest,
+        /// The differing post state hash of a later commit.
+        actual: Digest,
+    },
+    /// Executing a deploy returned an engine error, rather than a failed execution result.
+    ExecutionFailed {
+        /// The root the deploy was executed on.
+        pre_state_hash: Digest,
+        /// The index of the deploy in the stress test's workload.
+        deploy_index: usize,
+        /// The engine error.
+        error: String,
+    },
+    /// Committing the effects of a deploy failed.
+    CommitFailed {
+        /// The root the effects were committed on.
+        pre_state_hash: Digest,
+        /// The index of the deploy in the stress test's workload.
+        deploy_index: usize,
+        /// The engine error.
+        error: String,
+    },
+    /// A key could not be queried under a committed root.
+    QueryFailed {
+        /// The queried root.
+        state_hash: Digest,
+        /// The queried key.
+        key: Key,
+        /// The engine error or unsuccessful query result.
+        error: String,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Violation::NonDeterministicCommit {
+                pre_state_hash,
+                deploy_index,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "deploy {} on {} committed to {} after previously committing to {}",
+                deploy_index, pre_state_hash, actual, expected
+            ),
+            Violation::ExecutionFailed {
+                pre_state_hash,
+                deploy_index,
+                error,
+            } => write!(
+                formatter,
+                "executing deploy {} on {} failed: {}",
+                deploy_index, pre_state_hash, error
+            ),
+            Violation::CommitFailed {
+                pre_state_hash,
+                deploy_index,
+                error,
+            } => write!(
+                formatter,
+                "committing deploy {} on {} failed: {}",
+                deploy_index, pre_state_hash, error
+            ),
+            Violation::QueryFailed {
+                state_hash,
+                key,
+                error,
+            } => write!(
+                formatter,
+                "querying {} under {} failed: {}",
+                key, state_hash, error
+            ),
+        }
+    }
+}
+
+/// The outcome of a [`StressTest`] run.
+#[derive(Clone, Debug, Default)]
+pub struct StressReport {
+    /// The number of deploys executed and committed.
+    pub executions: usize,
+    /// The number of queries run, including those checking newly committed roots.
+    pub queries: usize,
+    /// The number of distinct state roots produced by commits.
+    pub roots: usize,
+    /// All broken invariants, in the order they were found.
+    pub violations: Vec<Violation>,
+}
+
+impl StressReport {
+    /// Asserts that no invariant was broken.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every violation if there were any.
+    pub fn assert_no_violations(&self) {
+        assert!(self.violations.is_empty(), "{}", self);
+    }
+}
+
+impl Display for StressReport {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "{} executions, {} queries, {} roots, {} violations",
+            self.executions,
+            self.queries,
+            self.roots,
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            writeln!(formatter, "  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Issues overlapping requests against a shared engine state from multiple threads.
+pub struct StressTest {
+    engine_state: EngineState<LmdbGlobalState>,
+    initial_state_hash: Digest,
+    deploys: Vec<DeployItem>,
+    query_keys: Vec<Key>,
+    threads: usize,
+    operations_per_thread: usize,
+    seed: u64,
+}
+
+impl StressTest {
+    /// Returns a stress test running `deploys` on the engine state of `builder`, starting from its
+    /// current post state hash.
+    ///
+    /// By default, the account of [`DEFAULT_ACCOUNT_ADDR`] is queried under every root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deploys` is empty, or if `builder` has no post state hash, i.e. if genesis hasn't
+    /// been run.
+    pub fn new(builder: LmdbWasmTestBuilder, deploys: Vec<DeployItem>) -> Self {
+        assert!(!deploys.is_empty(), "stress test requires deploys");
+        let initial_state_hash = builder.get_post_state_hash();
+        StressTest {
+            engine_state: builder.into_engine_state(),
+            initial_state_hash,
+            deploys,
+            query_keys: vec![Key::Account(*DEFAULT_ACCOUNT_ADDR)],
+            threads: DEFAULT_THREADS,
+            operations_per_thread: DEFAULT_OPERATIONS_PER_THREAD,
+            seed: 0,
+        }
+    }
+
+    /// Sets the keys queried under every root.  These must exist in the starting state, and must
+    /// not be removed by any of the deploys.
+    pub fn with_query_keys(mut self, query_keys: Vec<Key>) -> Self {
+        self.query_keys = query_keys;
+        self
+    }
+
+    /// Sets the number of threads issuing requests.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the number of operations run by each thread.
+    pub fn with_operations_per_thread(mut self, operations_per_thread: usize) -> Self {
+        self.operations_per_thread = operations_per_thread;
+        self
+    }
+
+    /// Sets the seed from which the operations of each thread are generated.
+    ///
+    /// The operations are reproducible, but their interleaving across threads isn't.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Runs the stress test, then queries every root produced, and reports all violations.
+    pub fn run(&self) -> StressReport {
+        let run = Run {
+            test: self,
+            roots: Mutex::new(vec![self.initial_state_hash]),
+            post_state_hashes: Mutex::new(HashMap::new()),
+            violations: Mutex::new(Vec::new()),
+            executions: AtomicUsize::new(0),
+            queries: AtomicUsize::new(0),
+        };
+
+        thread::scope(|scope| {
+            for thread_index in 0..self.threads {
+                let run = &run;
+                let rng = StdRng::seed_from_u64(self.seed.wrapping_add(thread_index as u64));
+                scope.spawn(move || run.run_thread(rng));
+            }
+        });
+
+        let roots = run.roots.into_inner().expect("should lock roots");
+        let mut violations = run.violations.into_inner().expect("should lock violations");
+        let mut queries = run.queries.into_inner();
+        for root in &roots {
+            for key in &self.query_keys {
+                queries += 1;
+                if let Err(violation) = self.query(*root, *key) {
+                    violations.push(violation);
+                }
+            }
+        }
+
+        StressReport {
+            executions: run.executions.into_inner(),
+            queries,
+            roots: roots.len(),
+            violations,
+        }
+    }
+
+    /// Executes the deploy with the given index on `pre_state_hash` and commits its effects.
+    fn execute_and_commit(
+        &self,
+        pre_state_hash: Digest,
+        deploy_index: usize,
+    ) -> Result<Digest, Violation> {
+        let mut exec_request =
+            ExecuteRequestBuilder::from_deploy_item(self.deploys[deploy_index].clone())
+                .with_block_time(DEFAULT_BLOCK_TIME)
+                .build();
+        exec_request.parent_state_hash = pre_state_hash;
+
+        let execution_result = self
+            .engine_state
+            .run_execute(CorrelationId::new(), exec_request)
+            .map_err(|error| Violation::ExecutionFailed {
+                pre_state_hash,
+                deploy_index,
+                error: error.to_string(),
+            })?
+            .pop_front()
+            .ok_or_else(|| Violation::ExecutionFailed {
+                pre_state_hash,
+                deploy_index,
+                error: "no execution result".to_string(),
+            })?;
+
+        let effects: AdditiveMap<Key, Transform> =
+            execution_result.execution_journal().clone().into();
+        self.engine_state
+            .apply_effect(CorrelationId::new(), pre_state_hash, effects)
+            .map_err(|error| Violation::CommitFailed {
+                pre_state_hash,
+                deploy_index,
+                error: error.to_string(),
+            })
+    }
+
+    /// Queries `key` under `state_hash`, which must succeed.
+    fn query(&self, state_hash: Digest, key: Key) -> Result<(), Violation> {
+        let query_request = QueryRequest::new(state_hash, key, vec![]);
+        let error = match self
+            .engine_state
+            .run_query(CorrelationId::new(), query_request)
+        {
+            Ok(QueryResult::Success { .. }) => return Ok(()),
+            Ok(query_result) => format!("{:?}", query_result),
+            Err(error) => error.to_string(),
+        };
+        Err(Violation::QueryFailed {
+            state_hash,
+            key,
+            error,
+        })
+    }
+}
+
+/// The state shared by the threads of a single [`StressTest::run`].
+struct Run<'a> {
+    test: &'a StressTest,
+    /// All distinct roots produced so far, starting with the initial one.
+    roots: Mutex<Vec<Digest>>,
+    /// The post state hash of every deploy committed so far, by root and deploy index.
+    post_state_hashes: Mutex<HashMap<(Digest, usize), Digest>>,
+    violations: Mutex<Vec<Violation>>,
+    executions: AtomicUsize,
+    queries: AtomicUsize,
+}
+
+impl<'a> Run<'a> {
+    fn run_thread(&self, mut rng: StdRng) {
+        for _ in 0..self.test.operations_per_thread {
+            let root = {
+                let roots = self.roots.lock().expect("should lock roots");
+                roots[rng.gen_range(0..roots.len())]
+            };
+            if rng.gen_ratio(1, QUERY_ONE_IN) {
+                self.query_all(root);
+            } else {
+                let deploy_index = rng.gen_range(0..self.test.deploys.len());
+                self.execute_and_commit(root, deploy_index);
+            }
+        }
+    }
+
+    fn execute_and_commit(&self, pre_state_hash: Digest, deploy_index: usize) {
+        self.executions.fetch_add(1, Ordering::Relaxed);
+        let post_state_hash = match self.test.execute_and_commit(pre_state_hash, deploy_index) {
+            Ok(post_state_hash) => post_state_hash,
+            Err(violation) => return self.report(violation),
+        };
+
+        let previous = self
+            .post_state_hashes
+            .lock()
+            .expect("should lock post state hashes")
+            .insert((pre_state_hash, deploy_index), post_state_hash);
+        match previous {
+            Some(expected) if expected != post_state_hash => {
+                return self.report(Violation::NonDeterministicCommit {
+                    pre_state_hash,
+                    deploy_index,
+                    expected,
+                    actual: post_state_hash,
+                });
+            }
+            Some(_) => {}
+            None => {
+                // Different deploys or roots can commit to the same post state hash, e.g. if a
+                // transfer fails without effects beyond the payment, so the root may be known
+                // already.
+                let mut roots = self.roots.lock().expect("should lock roots");
+                if !roots.contains(&post_state_hash) {
+                    roots.push(post_state_hash);
+                }
+            }
+        }
+
+        // The new root must be readable right away, not just once the writes have settled.
+        self.query_all(post_state_hash);
+    }
+
+    fn query_all(&self, state_hash: Digest) {
+        for key in &self.test.query_keys {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            if let Err(violation) = self.test.query(state_hash, *key) {
+                self.report(violation);
+            }
+        }
+    }
+
+    fn report(&self, violation: Violation) {
+        self.violations
+            .lock()
+            .expect("should lock violations")
+            .push(violation);
+    }
+}
@@ -4,7 +4,7 @@ use num_rational::Ratio;
 
 use casper_execution_engine::core::engine_state::{ChainspecRegistry, UpgradeConfig};
 use casper_hashing::Digest;
-use casper_types::{EraId, Key, ProtocolVersion, StoredValue};
+use casper_types::{account::AccountHash, EraId, Key, ProtocolVersion, StoredValue};
 
 /// Builds an `UpgradeConfig`.
 pub struct UpgradeRequestBuilder {
@@ -17,6 +17,7 @@ pub struct UpgradeRequestBuilder {
     new_locked_funds_period_millis: Option<u64>,
     new_round_seigniorage_rate: Option<Ratio<u64>>,
     new_unbonding_delay: Option<u64>,
+    new_native_tokens: Option<BTreeMap<String, AccountHash>>,
     global_state_update: BTreeMap<Key, StoredValue>,
     chainspec_registry: ChainspecRegistry,
 }
@@ -78,6 +79,12 @@ impl UpgradeRequestBuilder {
         self
     }
 
+    /// Sets `new_native_tokens`.
+    pub fn with_new_native_tokens(mut self, native_tokens: BTreeMap<String, AccountHash>) -> Self {
+        self.new_native_tokens = Some(native_tokens);
+        self
+    }
+
     /// Sets `global_state_update`.
     pub fn with_global_state_update(
         mut self,
@@ -111,6 +118,7 @@ impl UpgradeRequestBuilder {
             self.new_locked_funds_period_millis,
             self.new_round_seigniorage_rate,
             self.new_unbonding_delay,
+            self.new_native_tokens,
             self.global_state_update,
             self.chainspec_registry,
         )
@@ -129,6 +137,7 @@ impl Default for UpgradeRequestBuilder {
             new_locked_funds_period_millis: None,
             new_round_seigniorage_rate: None,
             new_unbonding_delay: None,
+            new_native_tokens: None,
             global_state_update: Default::default(),
             chainspec_registry: ChainspecRegistry::new_with_optional_global_state(&[], None),
         }
@@ -1,6 +1,7 @@
 //! Utility types and functions for working with execution engine tests.
 
 use std::{
+    collections::BTreeMap,
     env, fs,
     path::{Path, PathBuf},
     rc::Rc,
@@ -152,6 +153,7 @@ pub fn create_exec_config(accounts: Vec<GenesisAccount>) -> ExecConfig {
         round_seigniorage_rate,
         unbonding_delay,
         genesis_timestamp_millis,
+        BTreeMap::new(),
     )
 }
 
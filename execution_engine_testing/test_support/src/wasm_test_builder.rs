@@ -24,11 +24,12 @@ use casper_execution_engine::{
             execution_result::ExecutionResult,
             run_genesis_request::RunGenesisRequest,
             step::{StepRequest, StepSuccess},
-            BalanceResult, EngineConfig, EngineState, Error, GenesisSuccess, GetBidsRequest,
-            QueryRequest, QueryResult, RewardItem, StepError, SystemContractRegistry,
-            UpgradeConfig, UpgradeSuccess, DEFAULT_MAX_QUERY_DEPTH,
+            BalanceResult, DeployItem, EngineConfig, EngineState, Error, ExecutableDeployItem,
+            GenesisSuccess, GetBidsRequest, QueryRequest, QueryResult, RewardItem, StepError,
+            SystemContractRegistry, UpgradeConfig, UpgradeSuccess, DEFAULT_MAX_QUERY_DEPTH,
         },
         execution,
+        tracking_copy::ReadCacheStats,
     },
     shared::{
         additive_map::AdditiveMap,
@@ -61,20 +62,22 @@ use casper_types::{
         auction::{
             Bids, EraValidators, UnbondingPurse, UnbondingPurses, ValidatorWeights, WithdrawPurses,
             ARG_ERA_END_TIMESTAMP_MILLIS, ARG_EVICTED_VALIDATORS, AUCTION_DELAY_KEY, ERA_ID_KEY,
-            METHOD_RUN_AUCTION, UNBONDING_DELAY_KEY,
+            METHOD_RUN_AUCTION, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY,
         },
         mint::{ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY},
         AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
-    CLTyped, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash, ContractWasm,
-    DeployHash, DeployInfo, EraId, Gas, Key, KeyTag, ProtocolVersion, PublicKey, RuntimeArgs,
-    StoredValue, Transfer, TransferAddr, URef, U512,
+    CLTyped, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash,
+    ContractVersion, ContractWasm, DeployHash, DeployInfo, EraId, Gas, Key, KeyTag,
+    ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, Transfer, TransferAddr, URef, U512,
 };
 
 use crate::{
     chainspec_config::{ChainspecConfig, PRODUCTION_PATH},
-    utils, ExecuteRequestBuilder, StepRequestBuilder, DEFAULT_PROPOSER_ADDR,
-    DEFAULT_PROTOCOL_VERSION, SYSTEM_ADDR,
+    exec_failure::{finish_host_call_trace, start_host_call_trace, ExecFailureReport},
+    invariants::{self, InvariantChecker, SystemState},
+    utils, Delegation, EraValidatorSet, ExecuteRequestBuilder, StepRequestBuilder, ValidatorBid,
+    DEFAULT_PROPOSER_ADDR, DEFAULT_PROTOCOL_VERSION, SYSTEM_ADDR,
 };
 
 /// LMDB initial map size is calculated based on DEFAULT_LMDB_PAGES and systems page size.
@@ -117,6 +120,12 @@ pub struct WasmTestBuilder<S> {
     system_contract_registry: Option<SystemContractRegistry>,
     /// Global state dir, for implementations that define one.
     global_state_dir: Option<PathBuf>,
+    /// Deploys of the latest exec call, reported if their execution unexpectedly fails.
+    last_exec_deploys: Vec<DeployItem>,
+    /// Host function calls traced during the latest exec call, if tracing is enabled.
+    last_host_calls: Option<Vec<String>>,
+    /// Checker of the system invariants after every commit, if enabled.
+    invariant_checker: Option<InvariantChecker>,
 }
 
 impl<S> WasmTestBuilder<S> {
@@ -148,6 +157,9 @@ impl<S> Clone for WasmTestBuilder<S> {
             scratch_engine_state: None,
             system_contract_registry: self.system_contract_registry.clone(),
             global_state_dir: self.global_state_dir.clone(),
+            last_exec_deploys: self.last_exec_deploys.clone(),
+            last_host_calls: self.last_host_calls.clone(),
+            invariant_checker: self.invariant_checker.clone(),
         }
     }
 }
@@ -175,6 +187,9 @@ impl InMemoryWasmTestBuilder {
             scratch_engine_state: None,
             system_contract_registry: None,
             global_state_dir: None,
+            last_exec_deploys: Vec::new(),
+            last_host_calls: None,
+            invariant_checker: invariants::enabled_by_env().then(InvariantChecker::default),
         }
     }
 
@@ -198,6 +213,9 @@ impl InMemoryWasmTestBuilder {
             scratch_engine_state: None,
             system_contract_registry: None,
             global_state_dir: None,
+            last_exec_deploys: Vec::new(),
+            last_host_calls: None,
+            invariant_checker: invariants::enabled_by_env().then(InvariantChecker::default),
         }
     }
 
@@ -221,6 +239,11 @@ impl InMemoryWasmTestBuilder {
             chainspec_config.core_config.minimum_delegation_amount,
             chainspec_config.core_config.strict_argument_checking,
             vesting_schedule_period_millis,
+            chainspec_config.core_config.allow_authorization_contracts,
+            chainspec_config
+                .core_config
+                .authorization_contract_gas_limit,
+            chainspec_config.core_config.max_unbondings_per_step,
             chainspec_config.wasm_config,
             chainspec_config.system_costs_config,
         );
@@ -270,6 +293,9 @@ impl LmdbWasmTestBuilder {
             scratch_engine_state: None,
             system_contract_registry: None,
             global_state_dir: Some(global_state_dir),
+            last_exec_deploys: Vec::new(),
+            last_host_calls: None,
+            invariant_checker: invariants::enabled_by_env().then(InvariantChecker::default),
         }
     }
 
@@ -293,6 +319,11 @@ impl LmdbWasmTestBuilder {
             chainspec_config.core_config.minimum_delegation_amount,
             chainspec_config.core_config.strict_argument_checking,
             vesting_schedule_period_millis,
+            chainspec_config.core_config.allow_authorization_contracts,
+            chainspec_config
+                .core_config
+                .authorization_contract_gas_limit,
+            chainspec_config.core_config.max_unbondings_per_step,
             chainspec_config.wasm_config,
             chainspec_config.system_costs_config,
         );
@@ -312,6 +343,16 @@ impl LmdbWasmTestBuilder {
         engine_state.flush_environment().unwrap();
     }
 
+    /// Consumes the builder and returns its engine state, e.g. to share it between threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the engine state is still shared with a clone of this builder.
+    pub fn into_engine_state(self) -> EngineState<LmdbGlobalState> {
+        Rc::try_unwrap(self.engine_state)
+            .unwrap_or_else(|_| panic!("engine state should not be shared with a cloned builder"))
+    }
+
     /// Returns a new [`LmdbWasmTestBuilder`].
     pub fn new<T: AsRef<OsStr> + ?Sized>(data_dir: &T) -> Self {
         Self::new_with_config(data_dir, Default::default())
@@ -366,6 +407,9 @@ impl LmdbWasmTestBuilder {
             scratch_engine_state: None,
             system_contract_registry: None,
             global_state_dir: Some(global_state_dir.as_ref().to_path_buf()),
+            last_exec_deploys: Vec::new(),
+            last_host_calls: None,
+            invariant_checker: invariants::enabled_by_env().then(InvariantChecker::default),
         }
     }
 
@@ -414,8 +458,11 @@ impl LmdbWasmTestBuilder {
         };
 
         let mut exec_results = Vec::new();
+        self.last_exec_deploys = exec_request.deploys.clone();
+        start_host_call_trace();
         // First execute the request against our scratch global state.
         let maybe_exec_results = cached_state.run_execute(CorrelationId::new(), exec_request);
+        self.last_host_calls = finish_host_call_trace();
         for execution_result in maybe_exec_results.unwrap() {
             let journal = execution_result.execution_journal().clone();
             let transforms: AdditiveMap<Key, Transform> = journal.clone().into();
@@ -659,9 +706,12 @@ where
             exec_request
         };
 
+        self.last_exec_deploys = exec_request.deploys.clone();
+        start_host_call_trace();
         let maybe_exec_results = self
             .engine_state
             .run_execute(CorrelationId::new(), exec_request);
+        self.last_host_calls = finish_host_call_trace();
         assert!(maybe_exec_results.is_ok());
         // Parse deploy results
         let execution_results = maybe_exec_results.as_ref().unwrap();
@@ -681,6 +731,38 @@ where
         self
     }
 
+    /// Runs an [`ExecuteRequest`] consisting only of native transfers as one batch.
+    pub fn exec_transfers(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
+        exec_request.parent_state_hash = self.post_state_hash.expect("expected post_state_hash");
+
+        self.last_exec_deploys = exec_request.deploys.clone();
+        start_host_call_trace();
+        let execution_results = self
+            .engine_state
+            .run_transfers(CorrelationId::new(), exec_request)
+            .expect("should run transfers");
+        self.last_host_calls = finish_host_call_trace();
+        self.transforms.extend(
+            execution_results
+                .iter()
+                .map(|res| res.execution_journal().clone()),
+        );
+        self.exec_results
+            .push(execution_results.into_iter().map(Rc::new).collect());
+        self
+    }
+
+    /// Enables checking the system invariants after every commit, panicking at the first commit
+    /// violating them.
+    ///
+    /// The checks can also be enabled for all builders by setting the `CASPER_CHECK_INVARIANTS`
+    /// environment variable.
+    pub fn with_invariant_checks(&mut self) -> &mut Self {
+        self.invariant_checker
+            .get_or_insert_with(InvariantChecker::default);
+        self
+    }
+
     /// Commit effects of previous exec call on the latest post-state hash.
     pub fn commit(&mut self) -> &mut Self {
         let prestate_hash = self.post_state_hash.expect("Should have genesis hash");
@@ -690,6 +772,26 @@ where
         self.commit_transforms(prestate_hash, effects.into())
     }
 
+    /// Commit effects of all deploys of previous exec call in a single batch on the latest
+    /// post-state hash.
+    pub fn commit_all(&mut self) -> &mut Self {
+        let prestate_hash = self.post_state_hash.expect("Should have genesis hash");
+
+        let exec_results = self.exec_results.last().cloned().unwrap_or_default();
+
+        let post_state_hash = self
+            .engine_state
+            .apply_execution_results(
+                CorrelationId::new(),
+                prestate_hash,
+                exec_results.iter().map(AsRef::as_ref),
+            )
+            .expect("should commit");
+        self.post_state_hash = Some(post_state_hash);
+        self.check_invariants(prestate_hash, false);
+        self
+    }
+
     /// Runs a commit request, expects a successful response, and
     /// overwrites existing cached post state hash with a new one.
     pub fn commit_transforms(
@@ -702,6 +804,7 @@ where
             .apply_effect(CorrelationId::new(), pre_state_hash, effects)
             .expect("should commit");
         self.post_state_hash = Some(post_state_hash);
+        self.check_invariants(pre_state_hash, false);
         self
     }
 
@@ -762,6 +865,8 @@ where
 
     /// Increments engine state.
     pub fn step(&mut self, step_request: StepRequest) -> Result<StepSuccess, StepError> {
+        let pre_state_hash = step_request.pre_state_hash;
+        let may_burn = !step_request.slash_items.is_empty();
         let step_result = self
             .engine_state
             .commit_step(CorrelationId::new(), step_request);
@@ -771,6 +876,7 @@ where
         }) = &step_result
         {
             self.post_state_hash = Some(*post_state_hash);
+            self.check_invariants(pre_state_hash, may_burn);
         }
 
         step_result
@@ -787,14 +893,83 @@ where
             .expect("Unable to get first deploy result");
 
         if exec_result.is_failure() {
-            panic!(
-                "Expected successful execution result, but instead got: {:#?}",
-                exec_result,
-            );
+            panic!("{}", self.describe_exec_failure(exec_result));
         }
         self
     }
 
+    /// Returns a human-readable report of the unexpectedly failed `exec_result`, including the
+    /// deploy, the named keys of its account and of the called contract, and the latest host
+    /// function calls if traced.
+    fn describe_exec_failure(&self, exec_result: &ExecutionResult) -> String {
+        let deploy = self.last_exec_deploys.first();
+        let account = deploy
+            .and_then(|deploy| self.query(None, Key::Account(deploy.address), &[]).ok())
+            .and_then(|value| value.as_account().cloned());
+        let contract = deploy
+            .zip(account.as_ref())
+            .and_then(|(deploy, account)| self.called_contract(&deploy.session, account));
+        ExecFailureReport {
+            exec_result,
+            deploy,
+            account_named_keys: account.as_ref().map(Account::named_keys),
+            contract: contract
+                .as_ref()
+                .map(|(contract_hash, contract)| (*contract_hash, contract.named_keys())),
+            host_calls: self.last_host_calls.as_deref(),
+        }
+        .to_string()
+    }
+
+    /// Returns the stored contract called by `session`, resolving names in `account`.
+    fn called_contract(
+        &self,
+        session: &ExecutableDeployItem,
+        account: &Account,
+    ) -> Option<(ContractHash, Contract)> {
+        let named_hash = |name: &String| account.named_keys().get(name)?.into_hash();
+        let contract_hash = match session {
+            ExecutableDeployItem::StoredContractByHash { hash, .. } => *hash,
+            ExecutableDeployItem::StoredContractByName { name, .. } => {
+                ContractHash::new(named_hash(name)?)
+            }
+            ExecutableDeployItem::StoredVersionedContractByHash { hash, version, .. } => {
+                self.package_contract_hash(*hash, *version)?
+            }
+            ExecutableDeployItem::StoredVersionedContractByName { name, version, .. } => {
+                self.package_contract_hash(ContractPackageHash::new(named_hash(name)?), *version)?
+            }
+            ExecutableDeployItem::ModuleBytes { .. } | ExecutableDeployItem::Transfer { .. } => {
+                return None
+            }
+        };
+        match self.query(None, contract_hash.into(), &[]) {
+            Ok(StoredValue::Contract(contract)) => Some((contract_hash, contract)),
+            _ => None,
+        }
+    }
+
+    /// Returns the hash of the given version of a contract package, or of its current version.
+    fn package_contract_hash(
+        &self,
+        package_hash: ContractPackageHash,
+        version: Option<ContractVersion>,
+    ) -> Option<ContractHash> {
+        let package = match self.query(None, package_hash.into(), &[]) {
+            Ok(StoredValue::ContractPackage(package)) => package,
+            _ => return None,
+        };
+        match version {
+            Some(version) => package
+                .versions()
+                .iter()
+                .filter(|(version_key, _)| version_key.contract_version() == version)
+                .map(|(_, contract_hash)| *contract_hash)
+                .last(),
+            None => package.current_contract_hash(),
+        }
+    }
+
     /// Expects a failed run
     pub fn expect_failure(&mut self) -> &mut Self {
         // Check first result, as only first result is interesting for a simple test
@@ -917,6 +1092,15 @@ where
         &self.engine_state
     }
 
+    /// Returns the statistics of the reads served by the read caches of the engine state's tracking
+    /// copies so far.
+    ///
+    /// Subtract the statistics taken before an `exec` call from those taken after it to get the
+    /// statistics of that execution.
+    pub fn read_cache_stats(&self) -> ReadCacheStats {
+        self.engine_state.read_cache_stats()
+    }
+
     /// Returns the last results execs.
     pub fn get_last_exec_results(&self) -> Option<Vec<Rc<ExecutionResult>>> {
         let exec_results = self.exec_results.last()?;
@@ -1164,6 +1348,12 @@ where
         result.remove(&era_id)
     }
 
+    /// Gets the [`EraValidatorSet`] for a given [`EraId`].
+    pub fn get_era_validator_set(&mut self, era_id: EraId) -> Option<EraValidatorSet> {
+        self.get_validator_weights(era_id)
+            .map(|weights| EraValidatorSet::new(era_id, weights))
+    }
+
     /// Gets [`Bids`].
     pub fn get_bids(&mut self) -> Bids {
         let get_bids_request = GetBidsRequest::new(self.get_post_state_hash());
@@ -1176,6 +1366,20 @@ where
         get_bids_result.into_success().unwrap()
     }
 
+    /// Gets the [`ValidatorBid`] of `validator`.
+    pub fn get_bid(&mut self, validator: &PublicKey) -> Option<ValidatorBid> {
+        self.get_bids().remove(validator).map(ValidatorBid::new)
+    }
+
+    /// Gets the [`Delegation`] of `delegator` to `validator`.
+    pub fn get_delegation(
+        &mut self,
+        validator: &PublicKey,
+        delegator: &PublicKey,
+    ) -> Option<Delegation> {
+        self.get_bid(validator)?.delegation(delegator)
+    }
+
     /// Gets [`UnbondingPurses`].
     pub fn get_unbonds(&mut self) -> UnbondingPurses {
         let correlation_id = CorrelationId::new();
@@ -1352,6 +1556,72 @@ where
             .expect("should have standard payment hash")
     }
 
+    /// Checks the system invariants of the latest post-state, resulting from a commit on top of
+    /// `pre_state_hash`, if enabled.
+    ///
+    /// # Panics
+    /// Panics if any invariant is violated.
+    fn check_invariants(&mut self, pre_state_hash: Digest, may_burn: bool) {
+        let mut invariant_checker = match self.invariant_checker.take() {
+            Some(invariant_checker) => invariant_checker,
+            None => return,
+        };
+        // Nothing to check before genesis.
+        if self.system_contract_registry.is_some() {
+            let state = self.get_system_state();
+            let violations = invariant_checker.check(pre_state_hash, &state, may_burn);
+            if !violations.is_empty() {
+                let violations: Vec<String> = violations
+                    .iter()
+                    .map(|violation| format!("  {}", violation))
+                    .collect();
+                panic!(
+                    "system invariants violated by commit on top of {}, resulting in {}:\n{}",
+                    pre_state_hash,
+                    state.state_root_hash,
+                    violations.join("\n")
+                );
+            }
+        }
+        self.invariant_checker = Some(invariant_checker);
+    }
+
+    /// Gets the parts of the latest post-state covered by the invariant checks.
+    fn get_system_state(&mut self) -> SystemState {
+        let correlation_id = CorrelationId::new();
+        let state_root_hash = self.get_post_state_hash();
+
+        let balances = {
+            let tracking_copy = self
+                .engine_state
+                .tracking_copy(state_root_hash)
+                .unwrap()
+                .unwrap();
+            let reader = tracking_copy.reader();
+            reader
+                .keys_with_prefix(correlation_id, &[KeyTag::Balance as u8])
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|key| match (key, reader.read(correlation_id, &key)) {
+                    (Key::Balance(addr), Ok(Some(value))) => Some((addr, value)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let auction_contract = self.get_auction_contract_hash();
+        SystemState {
+            state_root_hash,
+            total_supply: self.total_supply(None),
+            balances,
+            bids: self.get_bids(),
+            unbonds: self.get_unbonds(),
+            era_id: self.get_era(),
+            seigniorage_recipients_snapshot: self
+                .get_value(auction_contract, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY),
+        }
+    }
+
     /// Resets the `exec_results`, `upgrade_results` and `transform` fields.
     pub fn clear_results(&mut self) -> &mut Self {
         self.exec_results = Vec::new();
@@ -1,4 +1,4 @@
-use std::{path::Path, time::Duration};
+use std::{collections::BTreeMap, path::Path, time::Duration};
 
 use criterion::{
     criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion, Throughput,
@@ -121,6 +121,7 @@ fn create_run_genesis_request(
             DEFAULT_ROUND_SEIGNIORAGE_RATE,
             DEFAULT_UNBONDING_DELAY,
             DEFAULT_GENESIS_TIMESTAMP_MILLIS,
+            BTreeMap::new(),
         )
     };
     RunGenesisRequest::new(
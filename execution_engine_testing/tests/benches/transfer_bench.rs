@@ -338,6 +338,8 @@ fn transfer_to_account_multiple_native_transfers(
         let request = ExecuteRequest::new(
             exec_request.parent_state_hash,
             exec_request.block_time,
+            exec_request.block_height,
+            exec_request.era_id,
             exec_request.deploys.clone(),
             exec_request.protocol_version,
             exec_request.proposer.clone(),
@@ -0,0 +1,46 @@
+use tempfile::TempDir;
+
+use casper_engine_test_support::{
+    stress::StressTest, DeployItemBuilder, LmdbWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::DeployItem;
+use casper_types::{account::AccountHash, Key, U512};
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1; 32]);
+const ACCOUNT_2_ADDR: AccountHash = AccountHash::new([2; 32]);
+const TRANSFER_AMOUNT: u64 = 2_500_000_000;
+
+fn transfer_deploys() -> Vec<DeployItem> {
+    [ACCOUNT_1_ADDR, ACCOUNT_2_ADDR]
+        .iter()
+        .enumerate()
+        .map(|(index, target)| {
+            DeployItemBuilder::transfer(
+                *DEFAULT_ACCOUNT_ADDR,
+                *target,
+                U512::from(TRANSFER_AMOUNT),
+                Some(index as u64),
+            )
+            .build()
+        })
+        .collect()
+}
+
+#[ignore]
+#[test]
+fn should_commit_deterministically_from_concurrent_threads() {
+    let data_dir = TempDir::new().expect("should create temp dir");
+    let mut builder = LmdbWasmTestBuilder::new(data_dir.path());
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let report = StressTest::new(builder, transfer_deploys())
+        .with_query_keys(vec![Key::Account(*DEFAULT_ACCOUNT_ADDR)])
+        .with_threads(8)
+        .with_operations_per_thread(50)
+        .run();
+
+    report.assert_no_violations();
+    // The transfers must have changed the state, rather than all failing before any commit.
+    assert!(report.roots > 1, "{}", report);
+}
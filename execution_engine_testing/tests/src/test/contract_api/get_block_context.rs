@@ -0,0 +1,37 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_types::{runtime_args, EraId, RuntimeArgs};
+
+const CONTRACT_GET_BLOCK_CONTEXT: &str = "get_block_context.wasm";
+const ARG_KNOWN_BLOCK_TIME: &str = "known_block_time";
+const ARG_KNOWN_BLOCK_HEIGHT: &str = "known_block_height";
+const ARG_KNOWN_ERA_ID: &str = "known_era_id";
+
+#[ignore]
+#[test]
+fn should_run_get_block_context_contract() {
+    let block_time: u64 = 42;
+    let block_height: u64 = 7;
+    let era_id = EraId::new(3);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_GET_BLOCK_CONTEXT,
+        runtime_args! {
+            ARG_KNOWN_BLOCK_TIME => block_time,
+            ARG_KNOWN_BLOCK_HEIGHT => block_height,
+            ARG_KNOWN_ERA_ID => era_id,
+        },
+    )
+    .with_block_time(block_time)
+    .with_block_height(block_height)
+    .with_era_id(era_id)
+    .build();
+    InMemoryWasmTestBuilder::default()
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit()
+        .expect_success();
+}
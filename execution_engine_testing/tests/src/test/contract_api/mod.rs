@@ -2,6 +2,7 @@ mod account;
 mod create_purse;
 mod dictionary;
 mod get_arg;
+mod get_block_context;
 mod get_blocktime;
 mod get_call_stack;
 mod get_caller;
@@ -12,6 +13,7 @@ mod main_purse;
 mod mint_purse;
 mod multisig_authorization;
 mod named_dictionaries;
+mod narrow_uref;
 mod revert;
 mod runtime;
 mod subcall;
@@ -19,3 +21,4 @@ mod transfer;
 mod transfer_cached;
 mod transfer_purse_to_account;
 mod transfer_purse_to_purse;
+mod typed_dictionaries;
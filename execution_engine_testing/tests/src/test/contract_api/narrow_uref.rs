@@ -0,0 +1,73 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state::Error as EngineError, execution::Error};
+use casper_types::{runtime_args, AccessRights, CLValue, RuntimeArgs, StoredValue};
+
+const CONTRACT_NARROW_UREF: &str = "narrow_uref.wasm";
+const ARG_WRITE_THROUGH_ALIAS: &str = "write_through_alias";
+const READ_ONLY_KEY: &str = "read_only";
+const INITIAL_VALUE: u64 = 42;
+
+#[ignore]
+#[test]
+fn should_narrow_uref_to_read_only_alias() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_NARROW_UREF,
+        runtime_args! { ARG_WRITE_THROUGH_ALIAS => false },
+    )
+    .build();
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    let read_only = account
+        .named_keys()
+        .get(READ_ONLY_KEY)
+        .expect("should have read-only alias")
+        .into_uref()
+        .expect("should be a uref");
+    assert_eq!(read_only.access_rights(), AccessRights::READ);
+
+    let value = builder
+        .query(None, read_only.into(), &[])
+        .expect("should query alias");
+    assert_eq!(
+        value,
+        StoredValue::CLValue(CLValue::from_t(INITIAL_VALUE).unwrap())
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_write_through_read_only_alias() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_NARROW_UREF,
+        runtime_args! { ARG_WRITE_THROUGH_ALIAS => true },
+    )
+    .build();
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let error = builder.get_error().expect("should have error");
+    assert!(
+        matches!(
+            error,
+            EngineError::Exec(Error::InvalidAccess {
+                required: AccessRights::WRITE
+            })
+        ),
+        "Received error {:?}",
+        error
+    );
+}
@@ -0,0 +1,57 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_types::{account::AccountHash, runtime_args, Key, RuntimeArgs, StoredValue, U512};
+
+const TYPED_DICTIONARY_TEST_WASM: &str = "typed_dictionary_test.wasm";
+const ARG_CREDITS: &str = "credits";
+const BALANCES_DICTIONARY_NAME: &str = "balances";
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1u8; 32]);
+const ACCOUNT_2_ADDR: AccountHash = AccountHash::new([2u8; 32]);
+
+#[ignore]
+#[test]
+fn typed_dictionaries_should_store_values_under_derived_item_keys() {
+    let credits: Vec<(AccountHash, U512)> = vec![
+        (ACCOUNT_1_ADDR, U512::from(100)),
+        (ACCOUNT_2_ADDR, U512::from(5)),
+        (ACCOUNT_1_ADDR, U512::from(20)),
+    ];
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+    builder
+        .exec(
+            ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                TYPED_DICTIONARY_TEST_WASM,
+                runtime_args! { ARG_CREDITS => credits },
+            )
+            .build(),
+        )
+        .commit()
+        .expect_success();
+
+    let seed_uref = *builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .named_keys()
+        .get(BALANCES_DICTIONARY_NAME)
+        .and_then(Key::as_uref)
+        .expect("should have balances dictionary");
+
+    // Values keyed by an account hash are stored under its hex encoding.
+    for (account_hash, expected_balance) in [(ACCOUNT_1_ADDR, 120), (ACCOUNT_2_ADDR, 5)] {
+        let item_key = base16::encode_lower(&account_hash.value());
+        let stored_value = builder
+            .query(None, Key::dictionary(seed_uref, item_key.as_bytes()), &[])
+            .expect("should have balance");
+        let cl_value = match stored_value {
+            StoredValue::CLValue(cl_value) => cl_value,
+            other => panic!("unexpected stored value {:?}", other),
+        };
+        let balance: U512 = cl_value.into_t().expect("should be U512");
+        assert_eq!(balance, U512::from(expected_balance));
+    }
+}
@@ -8,8 +8,9 @@ use casper_engine_test_support::{
 };
 use casper_execution_engine::shared::system_config::DEFAULT_WASMLESS_TRANSFER_COST;
 use casper_types::{
-    account::AccountHash, runtime_args, system::mint, AccessRights, Contract, ContractHash,
-    DeployHash, PublicKey, RuntimeArgs, SecretKey, Transfer, TransferAddr, U512,
+    account::AccountHash, bytesrepr::Bytes, runtime_args, system::mint, AccessRights, Contract,
+    ContractHash, DeployHash, PublicKey, RuntimeArgs, SecretKey, Transfer, TransferAddr,
+    TransferMemo, TRANSFER_MEMO_MAX_LENGTH, U512,
 };
 
 const CONTRACT_TRANSFER_PURSE_TO_ACCOUNT: &str = "transfer_purse_to_account.wasm";
@@ -17,6 +18,7 @@ const CONTRACT_TRANSFER_PURSE_TO_ACCOUNT_WITH_ID: &str = "transfer_purse_to_acco
 const TRANSFER_ARG_TARGET: &str = "target";
 const TRANSFER_ARG_AMOUNT: &str = "amount";
 const TRANSFER_ARG_ID: &str = "id";
+const TRANSFER_ARG_MEMO: &str = "memo";
 
 const CONTRACT_TRANSFER_PURSE_TO_ACCOUNTS: &str = "transfer_purse_to_accounts.wasm";
 const TRANSFER_ARG_TARGETS: &str = "targets";
@@ -116,6 +118,55 @@ fn should_record_wasmless_transfer() {
     assert_eq!(transfer.id, id);
 }
 
+#[ignore]
+#[test]
+fn should_record_wasmless_transfer_memo() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let memo = TransferMemo::new(Bytes::from(b"deposit 42".to_vec())).unwrap();
+
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            TRANSFER_ARG_TARGET => *ALICE_ADDR,
+            TRANSFER_ARG_AMOUNT => *TRANSFER_AMOUNT_1,
+            TRANSFER_ARG_ID => Some(0u64),
+            TRANSFER_ARG_MEMO => Some(memo.clone())
+        },
+    )
+    .build();
+
+    let deploy_hash = transfer_request.deploys()[0].deploy_hash;
+
+    builder.exec(transfer_request).commit().expect_success();
+
+    let deploy_info = builder
+        .get_deploy_info(deploy_hash)
+        .expect("should have deploy info");
+    assert_eq!(deploy_info.transfers.len(), 1);
+
+    let transfer = builder
+        .get_transfer(deploy_info.transfers[0])
+        .expect("should have transfer");
+    assert_eq!(transfer.id, Some(0));
+    assert_eq!(transfer.memo, Some(memo));
+
+    let too_long_memo = Bytes::from(vec![0; TRANSFER_MEMO_MAX_LENGTH + 1]);
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            TRANSFER_ARG_TARGET => *ALICE_ADDR,
+            TRANSFER_ARG_AMOUNT => *TRANSFER_AMOUNT_1,
+            TRANSFER_ARG_ID => Some(1u64),
+            TRANSFER_ARG_MEMO => too_long_memo
+        },
+    )
+    .build();
+
+    builder.exec(transfer_request).commit().expect_failure();
+}
+
 #[ignore]
 #[test]
 fn should_record_wasm_transfer() {
@@ -356,6 +407,7 @@ fn should_record_wasm_transfers() {
         amount: *TRANSFER_AMOUNT_1,
         gas: U512::zero(),
         id: alice_id,
+        memo: None,
     }));
 
     assert!(transfers.contains(&Transfer {
@@ -367,6 +419,7 @@ fn should_record_wasm_transfers() {
         amount: *TRANSFER_AMOUNT_2,
         gas: U512::zero(),
         id: bob_id,
+        memo: None,
     }));
 
     assert!(transfers.contains(&Transfer {
@@ -378,6 +431,7 @@ fn should_record_wasm_transfers() {
         amount: *TRANSFER_AMOUNT_3,
         gas: U512::zero(),
         id: carol_id,
+        memo: None,
     }));
 }
 
@@ -514,6 +568,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_1,
         gas: U512::zero(),
         id: alice_id,
+        memo: None,
     };
 
     let session_expected_bob = Transfer {
@@ -525,6 +580,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_2,
         gas: U512::zero(),
         id: bob_id,
+        memo: None,
     };
 
     let session_expected_carol = Transfer {
@@ -536,6 +592,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_3,
         gas: U512::zero(),
         id: carol_id,
+        memo: None,
     };
 
     const SESSION_EXPECTED_COUNT: Option<usize> = Some(1);
@@ -564,6 +621,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_1,
         gas: U512::zero(),
         id: alice_id,
+        memo: None,
     };
 
     let stored_expected_bob = Transfer {
@@ -575,6 +633,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_2,
         gas: U512::zero(),
         id: bob_id,
+        memo: None,
     };
 
     let stored_expected_carol = Transfer {
@@ -586,6 +645,7 @@ fn should_record_wasm_transfers_with_subcall() {
         amount: *TRANSFER_AMOUNT_3,
         gas: U512::zero(),
         id: carol_id,
+        memo: None,
     };
 
     const STORED_EXPECTED_COUNT: Option<usize> = Some(1);
@@ -0,0 +1,123 @@
+//! Checks that executing the same block yields the same post state in separate processes.
+//!
+//! Each process seeds the standard library's hashers differently, so any `HashMap`-backed state
+//! whose iteration order leaks into execution results shows up here as diverging roots.
+use std::{env, process::Command};
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, StepRequestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_hashing::Digest;
+use casper_types::{
+    account::AccountHash, runtime_args, system::mint, EraId, ProtocolVersion, RuntimeArgs, U512,
+};
+
+const CONTRACT_NAMED_KEYS: &str = "named_keys.wasm";
+const ARG_COMMAND: &str = "command";
+const COMMANDS: [&str; 3] = ["create-uref1", "create-uref2", "increase-uref2"];
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42; 32]);
+const TRANSFER_AMOUNT: u64 = 1_000_000_000;
+
+/// Set in child processes so they print their fingerprint instead of spawning more children.
+const CHILD_ENV_VAR: &str = "CASPER_DETERMINISM_CHILD";
+const FINGERPRINT_PREFIX: &str = "determinism-fingerprint:";
+const CHILD_RUNS: usize = 2;
+
+/// Executes a fixed block of deploys followed by an era step and returns the post state hash
+/// together with a digest over every execution journal produced along the way.
+fn execute_block() -> (Digest, Digest) {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for command in COMMANDS {
+        let exec_request = ExecuteRequestBuilder::standard(
+            *DEFAULT_ACCOUNT_ADDR,
+            CONTRACT_NAMED_KEYS,
+            runtime_args! { ARG_COMMAND => command },
+        )
+        .build();
+        builder.exec(exec_request).commit().expect_success();
+    }
+
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            mint::ARG_TARGET => ACCOUNT_1_ADDR,
+            mint::ARG_AMOUNT => U512::from(TRANSFER_AMOUNT),
+            mint::ARG_ID => <Option<u64>>::None,
+        },
+    )
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
+
+    let step_request = StepRequestBuilder::new()
+        .with_parent_state_hash(builder.get_post_state_hash())
+        .with_protocol_version(ProtocolVersion::V1_0_0)
+        .with_next_era_id(EraId::from(1))
+        .build();
+    builder.step(step_request).expect("should step");
+
+    let journals = format!("{:?}", builder.get_execution_journals());
+    (builder.get_post_state_hash(), Digest::hash(journals))
+}
+
+fn fingerprint() -> String {
+    let (post_state_hash, journals_hash) = execute_block();
+    format!("{} {}", post_state_hash, journals_hash)
+}
+
+/// Re-runs this test in a fresh process and returns the fingerprint it reports.
+fn fingerprint_in_child_process() -> String {
+    let test_path = concat!(
+        module_path!(),
+        "::should_produce_same_root_across_processes"
+    );
+    let (_crate_name, test_name) = test_path
+        .split_once("::")
+        .expect("module path should include the crate name");
+
+    let output = Command::new(env::current_exe().expect("should get test executable"))
+        .args(&[test_name, "--exact", "--ignored", "--nocapture"])
+        .env(CHILD_ENV_VAR, "1")
+        .output()
+        .expect("should spawn child test process");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "child test process failed: {}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(FINGERPRINT_PREFIX))
+        .map(|fingerprint| fingerprint.trim().to_string())
+        .unwrap_or_else(|| panic!("child test process printed no fingerprint: {}", stdout))
+}
+
+#[ignore]
+#[test]
+fn should_produce_same_root_when_executed_twice() {
+    assert_eq!(execute_block(), execute_block());
+}
+
+#[ignore]
+#[test]
+fn should_produce_same_root_across_processes() {
+    if env::var_os(CHILD_ENV_VAR).is_some() {
+        println!("{} {}", FINGERPRINT_PREFIX, fingerprint());
+        return;
+    }
+
+    let expected = fingerprint();
+    for run in 0..CHILD_RUNS {
+        let actual = fingerprint_in_child_process();
+        assert_eq!(
+            expected, actual,
+            "child run {} diverged from the parent process",
+            run
+        );
+    }
+}
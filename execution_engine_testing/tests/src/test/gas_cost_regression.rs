@@ -0,0 +1,218 @@
+//! Golden gas-cost regression tests.
+//!
+//! A curated set of representative deploys is executed against a production genesis and the gas
+//! consumed by each of them is compared against the values committed in
+//! `fixtures/gas_costs.json`. Any difference, e.g. one introduced by a change to the wasm
+//! instrumentation or to a host function's charging, fails the test and lists every affected case.
+//!
+//! When a cost change is intentional the golden file should be regenerated and committed alongside
+//! the change:
+//!
+//! ```sh
+//! UPDATE_GAS_COST_GOLDEN=1 cargo test -p casper-engine-tests -- --ignored gas_cost_regression
+//! ```
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use once_cell::sync::Lazy;
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_ACCOUNT_PUBLIC_KEY, MINIMUM_ACCOUNT_CREATION_BALANCE, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::ExecuteRequest;
+use casper_types::{
+    account::AccountHash,
+    runtime_args,
+    system::{
+        auction::{self, DelegationRate},
+        mint,
+    },
+    RuntimeArgs, U512,
+};
+
+const GOLDEN_FILE_NAME: &str = "gas_costs.json";
+const FIXTURES_DIRECTORY: &str = "fixtures";
+/// Setting this environment variable to any value rewrites the golden file instead of asserting.
+const UPDATE_GOLDEN_ENV_VAR: &str = "UPDATE_GAS_COST_GOLDEN";
+
+const CONTRACT_DO_NOTHING: &str = "do_nothing.wasm";
+const CONTRACT_CREATE_PURSE_01: &str = "create_purse_01.wasm";
+const CONTRACT_NEW_NAMED_UREF: &str = "new_named_uref.wasm";
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const CONTRACT_ADD_BID: &str = "add_bid.wasm";
+
+const ARG_PURSE_NAME: &str = "purse_name";
+const ARG_UREF_NAME: &str = "uref_name";
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([201; 32]);
+const BID_AMOUNT: u64 = 1_000_000_000_000;
+const BID_DELEGATION_RATE: DelegationRate = 10;
+const TRANSFER_ID: u64 = 42;
+
+/// A named deploy whose gas cost is tracked by the golden file.
+struct GasCostCase {
+    name: &'static str,
+    make_request: fn() -> ExecuteRequest,
+}
+
+static GAS_COST_CASES: Lazy<Vec<GasCostCase>> = Lazy::new(|| {
+    vec![
+        GasCostCase {
+            name: "do_nothing",
+            make_request: || {
+                ExecuteRequestBuilder::standard(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    CONTRACT_DO_NOTHING,
+                    RuntimeArgs::default(),
+                )
+                .build()
+            },
+        },
+        GasCostCase {
+            name: "create_purse",
+            make_request: || {
+                ExecuteRequestBuilder::standard(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    CONTRACT_CREATE_PURSE_01,
+                    runtime_args! { ARG_PURSE_NAME => "purse_1" },
+                )
+                .build()
+            },
+        },
+        GasCostCase {
+            name: "new_named_uref",
+            make_request: || {
+                ExecuteRequestBuilder::standard(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    CONTRACT_NEW_NAMED_UREF,
+                    runtime_args! { ARG_UREF_NAME => "uref_1" },
+                )
+                .build()
+            },
+        },
+        GasCostCase {
+            name: "wasm_transfer_to_new_account",
+            make_request: || {
+                ExecuteRequestBuilder::standard(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    CONTRACT_TRANSFER_TO_ACCOUNT,
+                    runtime_args! {
+                        ARG_TARGET => ACCOUNT_1_ADDR,
+                        ARG_AMOUNT => U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE),
+                    },
+                )
+                .build()
+            },
+        },
+        GasCostCase {
+            name: "wasmless_transfer_to_new_account",
+            make_request: || {
+                ExecuteRequestBuilder::transfer(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    runtime_args! {
+                        mint::ARG_TARGET => ACCOUNT_1_ADDR,
+                        mint::ARG_AMOUNT => U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE),
+                        mint::ARG_ID => Some(TRANSFER_ID),
+                    },
+                )
+                .build()
+            },
+        },
+        GasCostCase {
+            name: "add_bid",
+            make_request: || {
+                ExecuteRequestBuilder::standard(
+                    *DEFAULT_ACCOUNT_ADDR,
+                    CONTRACT_ADD_BID,
+                    runtime_args! {
+                        auction::ARG_PUBLIC_KEY => DEFAULT_ACCOUNT_PUBLIC_KEY.clone(),
+                        auction::ARG_AMOUNT => U512::from(BID_AMOUNT),
+                        auction::ARG_DELEGATION_RATE => BID_DELEGATION_RATE,
+                    },
+                )
+                .build()
+            },
+        },
+    ]
+});
+
+fn path_to_golden_file() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(FIXTURES_DIRECTORY)
+        .join(GOLDEN_FILE_NAME)
+}
+
+/// Executes every case on a fresh production genesis and returns the gas consumed by each.
+fn measure_gas_costs() -> BTreeMap<String, U512> {
+    GAS_COST_CASES
+        .iter()
+        .map(|case| {
+            let mut builder = InMemoryWasmTestBuilder::default();
+            builder.run_genesis(&*PRODUCTION_RUN_GENESIS_REQUEST);
+
+            builder
+                .exec((case.make_request)())
+                .expect_success()
+                .commit();
+
+            (case.name.to_string(), builder.last_exec_gas_cost().value())
+        })
+        .collect()
+}
+
+#[ignore]
+#[test]
+fn gas_cost_regression() {
+    let measured = measure_gas_costs();
+    let path = path_to_golden_file();
+
+    if env::var_os(UPDATE_GOLDEN_ENV_VAR).is_some() {
+        let file = File::create(&path).expect("should create golden file");
+        serde_json::to_writer_pretty(file, &measured).expect("should write golden file");
+        eprintln!("Updated gas cost golden file at {}", path.display());
+        return;
+    }
+
+    let golden: BTreeMap<String, U512> = {
+        let file = File::open(&path).unwrap_or_else(|error| {
+            panic!(
+                "should open golden file at {}: {}; set {} to generate it",
+                path.display(),
+                error,
+                UPDATE_GOLDEN_ENV_VAR
+            )
+        });
+        serde_json::from_reader(file).expect("should parse golden file")
+    };
+
+    let mut mismatches = Vec::new();
+    for (name, actual) in &measured {
+        match golden.get(name) {
+            Some(expected) if expected == actual => {}
+            Some(expected) => mismatches.push(format!(
+                "{}: expected {} but consumed {}",
+                name, expected, actual
+            )),
+            None => mismatches.push(format!("{}: missing from golden file", name)),
+        }
+    }
+    for name in golden.keys().filter(|name| !measured.contains_key(*name)) {
+        mismatches.push(format!("{}: no longer measured", name));
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "gas costs differ from {}:\n{}\nif these changes are intended, rerun with {}=1 and commit \
+         the updated golden file",
+        path.display(),
+        mismatches.join("\n"),
+        UPDATE_GOLDEN_ENV_VAR
+    );
+}
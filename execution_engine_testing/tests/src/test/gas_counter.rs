@@ -76,6 +76,7 @@ fn should_fail_to_overflow_gas_counter() {
 #[test]
 fn should_correctly_measure_gas_for_opcodes() {
     let opcode_costs = DEFAULT_WASM_CONFIG.opcode_costs();
+    let memory_costs = DEFAULT_WASM_CONFIG.memory_costs();
 
     const GROW_PAGES: u32 = 1;
 
@@ -112,7 +113,7 @@ fn should_correctly_measure_gas_for_opcodes() {
         ),
         // memory.grow is metered by the number of pages
         (
-            Some(opcode_costs.grow_memory * (GROW_PAGES + 1)),
+            Some(opcode_costs.grow_memory + memory_costs.grow_memory_per_page() * GROW_PAGES),
             Instruction::GrowMemory(0),
         ),
         (Some(opcode_costs.op_const), Instruction::I32Const(0)),
@@ -169,10 +170,64 @@ fn should_correctly_measure_gas_for_opcodes() {
     builder.exec(exec_request).commit().expect_success();
 
     let gas_cost = builder.last_exec_gas_cost();
-    let expected_cost = accounted_opcodes.clone().into_iter().map(Gas::from).sum();
+    // The memory starts empty, so it peaks at the grown pages.
+    let peak_memory_cost = memory_costs.calculate_peak_memory_cost(GROW_PAGES as usize);
+    let expected_cost = accounted_opcodes
+        .clone()
+        .into_iter()
+        .map(Gas::from)
+        .sum::<Gas>()
+        + peak_memory_cost;
     assert_eq!(
         gas_cost, expected_cost,
         "accounted costs {:?}",
         accounted_opcodes
     );
 }
+
+#[ignore]
+#[test]
+fn should_charge_for_peak_memory() {
+    const INITIAL_PAGES: u32 = 7;
+
+    let module = builder::module()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![Instruction::End]))
+        .build()
+        .build()
+        .export()
+        .field(DEFAULT_ENTRY_POINT_NAME)
+        .build()
+        .memory()
+        .with_min(INITIAL_PAGES)
+        .build()
+        .build();
+    let session_bytes = parity_wasm::serialize(module).expect("should serialize");
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let exec_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_bytes(session_bytes, RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => *DEFAULT_PAYMENT
+            })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+
+    builder.exec(exec_request).commit().expect_success();
+
+    let expected_cost = DEFAULT_WASM_CONFIG
+        .memory_costs()
+        .calculate_peak_memory_cost(INITIAL_PAGES as usize);
+    assert_eq!(builder.last_exec_gas_cost(), expected_cost);
+}
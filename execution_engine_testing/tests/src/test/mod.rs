@@ -1,10 +1,13 @@
 mod bulk_update_with_scratch_trie;
 mod chainspec_registry;
 mod check_transfer_success;
+mod concurrent_engine_state;
 mod contract_api;
 mod contract_context;
 mod deploy;
+mod determinism;
 mod explorer;
+mod gas_cost_regression;
 mod gas_counter;
 mod get_balance;
 mod groups;
@@ -15,6 +18,7 @@ mod step;
 mod storage_costs;
 mod system_contracts;
 mod system_costs;
+mod tracking_copy_cache;
 mod tutorial;
 mod upgrade;
 mod wasmless_transfer;
@@ -11,8 +11,9 @@ use casper_execution_engine::{
     core::{
         engine_state::{
             engine_config::{
-                DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-                DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+                DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+                DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+                DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
             },
             EngineConfig, Error, ExecuteRequest, DEFAULT_MAX_QUERY_DEPTH,
             DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
@@ -21,6 +22,8 @@ use casper_execution_engine::{
     },
     shared::{
         host_function_costs::HostFunctionCosts,
+        memory_costs::MemoryCosts,
+        module_limits::ModuleLimits,
         opcode_costs::OpcodeCosts,
         storage_costs::StorageCosts,
         system_config::SystemConfig,
@@ -42,7 +45,9 @@ static DOUBLED_WASM_MEMORY_LIMIT: Lazy<WasmConfig> = Lazy::new(|| {
         DEFAULT_MAX_STACK_HEIGHT,
         OpcodeCosts::default(),
         StorageCosts::default(),
+        MemoryCosts::default(),
         HostFunctionCosts::default(),
+        ModuleLimits::default(),
     )
 });
 static NEW_PROTOCOL_VERSION: Lazy<ProtocolVersion> = Lazy::new(|| {
@@ -287,6 +292,9 @@ fn should_run_ee_966_regression_when_growing_mem_after_upgrade() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         *DOUBLED_WASM_MEMORY_LIMIT,
         SystemConfig::default(),
     );
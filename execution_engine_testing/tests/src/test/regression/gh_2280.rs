@@ -8,8 +8,9 @@ use casper_engine_test_support::{
 use casper_execution_engine::{
     core::engine_state::{
         engine_config::{
-            DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-            DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
         },
         EngineConfig, UpgradeConfig, DEFAULT_MAX_QUERY_DEPTH,
         DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
@@ -761,6 +762,9 @@ fn make_engine_config(
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         new_wasm_config,
         new_system_config,
     )
@@ -775,7 +779,9 @@ fn make_wasm_config(
         DEFAULT_MAX_STACK_HEIGHT,
         old_wasm_config.opcode_costs(),
         old_wasm_config.storage_costs(),
+        old_wasm_config.memory_costs(),
         new_host_function_costs,
+        old_wasm_config.module_limits(),
     )
 }
 
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use once_cell::sync::Lazy;
 
 use casper_engine_test_support::{
@@ -13,7 +15,11 @@ use casper_engine_test_support::{
 use casper_execution_engine::core::{
     engine_state::{
         self,
-        engine_config::{DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING},
+        engine_config::{
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING,
+        },
         genesis::GenesisValidator,
         EngineConfig, ExecConfig, GenesisAccount, RunGenesisRequest, DEFAULT_MAX_QUERY_DEPTH,
         DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
@@ -181,6 +187,7 @@ fn should_immediatelly_unbond_genesis_validator_with_zero_day_vesting_schedule()
             round_seigniorage_rate,
             unbonding_delay,
             genesis_timestamp_millis,
+            BTreeMap::new(),
         )
     };
 
@@ -198,6 +205,9 @@ fn should_immediatelly_unbond_genesis_validator_with_zero_day_vesting_schedule()
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         vesting_schedule_period_millis,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         Default::default(),
         Default::default(),
     );
@@ -329,6 +339,7 @@ fn should_immediatelly_unbond_genesis_validator_with_zero_day_vesting_schedule_a
             round_seigniorage_rate,
             unbonding_delay,
             genesis_timestamp_millis,
+            BTreeMap::new(),
         )
     };
 
@@ -346,6 +357,9 @@ fn should_immediatelly_unbond_genesis_validator_with_zero_day_vesting_schedule_a
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         vesting_schedule_period_millis,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         Default::default(),
         Default::default(),
     );
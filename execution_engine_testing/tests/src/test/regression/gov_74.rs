@@ -8,8 +8,9 @@ use casper_execution_engine::{
     core::{
         engine_state::{
             engine_config::{
-                DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-                DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+                DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+                DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+                DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
             },
             EngineConfig, Error, DEFAULT_MAX_QUERY_DEPTH, DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
         },
@@ -111,12 +112,17 @@ fn should_observe_stack_height_limit() {
             DEFAULT_MINIMUM_DELEGATION_AMOUNT,
             DEFAULT_STRICT_ARGUMENT_CHECKING,
             DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+            DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP,
             WasmConfig::new(
                 DEFAULT_WASM_MAX_MEMORY,
                 NEW_WASM_STACK_HEIGHT,
                 Default::default(),
                 Default::default(),
                 Default::default(),
+                Default::default(),
+                Default::default(),
             ),
             Default::default(),
         );
@@ -12,13 +12,16 @@ use casper_execution_engine::shared::system_config::auction_costs::DEFAULT_ADD_B
 use casper_execution_engine::{
     core::engine_state::{
         engine_config::{
-            DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-            DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
         },
         EngineConfig, DEFAULT_MAX_QUERY_DEPTH, DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
     },
     shared::{
         host_function_costs::{HostFunction, HostFunctionCosts},
+        memory_costs::MemoryCosts,
+        module_limits::ModuleLimits,
         opcode_costs::OpcodeCosts,
         storage_costs::StorageCosts,
         system_config::SystemConfig,
@@ -129,6 +132,9 @@ static NEW_HOST_FUNCTION_COSTS: Lazy<HostFunctionCosts> = Lazy::new(|| HostFunct
     print: HostFunction::fixed(0),
     blake2b: HostFunction::fixed(0),
     random_bytes: HostFunction::fixed(0),
+    remove_contract: HostFunction::fixed(0),
+    get_block_context: HostFunction::fixed(0),
+    narrow_uref: HostFunction::fixed(0),
 });
 static STORAGE_COSTS_ONLY: Lazy<WasmConfig> = Lazy::new(|| {
     WasmConfig::new(
@@ -136,7 +142,9 @@ static STORAGE_COSTS_ONLY: Lazy<WasmConfig> = Lazy::new(|| {
         DEFAULT_MAX_STACK_HEIGHT,
         NEW_OPCODE_COSTS,
         StorageCosts::default(),
+        MemoryCosts::zero(),
         *NEW_HOST_FUNCTION_COSTS,
+        ModuleLimits::default(),
     )
 });
 
@@ -170,6 +178,9 @@ fn initialize_isolated_storage_costs() -> InMemoryWasmTestBuilder {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         *STORAGE_COSTS_ONLY,
         SystemConfig::default(),
     );
@@ -15,9 +15,11 @@ use casper_execution_engine::{
         engine_state::{
             self,
             engine_config::{
+                DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
                 DEFAULT_MAX_ASSOCIATED_KEYS, DEFAULT_MAX_QUERY_DEPTH,
-                DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
-                DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+                DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT, DEFAULT_MAX_UNBONDINGS_PER_STEP,
+                DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
+                DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
             },
             genesis::{GenesisAccount, GenesisValidator},
             EngineConfig, RewardItem,
@@ -52,6 +54,7 @@ const CONTRACT_WITHDRAW_BID: &str = "withdraw_bid.wasm";
 const CONTRACT_DELEGATE: &str = "delegate.wasm";
 const CONTRACT_UNDELEGATE: &str = "undelegate.wasm";
 const CONTRACT_REDELEGATE: &str = "redelegate.wasm";
+const CONTRACT_REGISTER_SUCCESSOR_KEY: &str = "register_successor_key.wasm";
 
 const TRANSFER_AMOUNT: u64 = MINIMUM_ACCOUNT_CREATION_BALANCE + 1000;
 
@@ -114,6 +117,11 @@ static BID_ACCOUNT_2_PK: Lazy<PublicKey> = Lazy::new(|| {
 static BID_ACCOUNT_2_ADDR: Lazy<AccountHash> = Lazy::new(|| AccountHash::from(&*BID_ACCOUNT_2_PK));
 const BID_ACCOUNT_2_BALANCE: u64 = MINIMUM_ACCOUNT_CREATION_BALANCE;
 
+static SUCCESSOR_1_PK: Lazy<PublicKey> = Lazy::new(|| {
+    let secret_key = SecretKey::ed25519_from_bytes([208; SecretKey::ED25519_LENGTH]).unwrap();
+    PublicKey::from(&secret_key)
+});
+
 static VALIDATOR_1: Lazy<PublicKey> = Lazy::new(|| {
     let secret_key = SecretKey::ed25519_from_bytes([3; SecretKey::ED25519_LENGTH]).unwrap();
     PublicKey::from(&secret_key)
@@ -1279,6 +1287,159 @@ fn undelegated_funds_should_be_released() {
     )
 }
 
+#[ignore]
+#[test]
+fn matured_unbondings_exceeding_step_batch_should_be_carried_over() {
+    let system_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(SYSTEM_TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *NON_FOUNDER_VALIDATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let delegator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *BID_ACCOUNT_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_add_bid_request = ExecuteRequestBuilder::standard(
+        *NON_FOUNDER_VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+
+    let delegator_1_validator_1_delegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK.clone(),
+        },
+    )
+    .build();
+
+    let post_genesis_requests = vec![
+        system_fund_request,
+        delegator_1_fund_request,
+        validator_1_fund_request,
+        validator_1_add_bid_request,
+        delegator_1_validator_1_delegate_request,
+    ];
+
+    let mut timestamp_millis =
+        DEFAULT_GENESIS_TIMESTAMP_MILLIS + DEFAULT_LOCKED_FUNDS_PERIOD_MILLIS;
+
+    // Pay out only a single matured unbonding purse per era-end step.
+    let custom_engine_config = EngineConfig::new(
+        DEFAULT_MAX_QUERY_DEPTH,
+        DEFAULT_MAX_ASSOCIATED_KEYS,
+        DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
+        DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+        DEFAULT_STRICT_ARGUMENT_CHECKING,
+        DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        1,
+        WasmConfig::default(),
+        SystemConfig::default(),
+    );
+
+    let global_state = InMemoryGlobalState::empty().expect("should create global state");
+
+    let mut builder = InMemoryWasmTestBuilder::new(global_state, custom_engine_config, None);
+
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for request in post_genesis_requests {
+        builder.exec(request).commit().expect_success();
+    }
+
+    for _ in 0..5 {
+        builder.run_auction(timestamp_millis, Vec::new());
+        timestamp_millis += TIMESTAMP_MILLIS_INCREMENT;
+    }
+
+    let delegator_1_undelegate_purse = builder
+        .get_account(*BID_ACCOUNT_1_ADDR)
+        .expect("should have default account")
+        .main_purse();
+
+    // Two undelegations in the same era mature in the same era-end step.
+    for _ in 0..2 {
+        let delegator_1_undelegate_request = ExecuteRequestBuilder::standard(
+            *BID_ACCOUNT_1_ADDR,
+            CONTRACT_UNDELEGATE,
+            runtime_args! {
+                ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
+                ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+                ARG_DELEGATOR => BID_ACCOUNT_1_PK.clone(),
+            },
+        )
+        .build();
+
+        builder
+            .exec(delegator_1_undelegate_request)
+            .commit()
+            .expect_success();
+    }
+
+    let delegator_1_purse_balance_before = builder.get_purse_balance(delegator_1_undelegate_purse);
+
+    let unbonding_delay = builder.get_unbonding_delay();
+
+    for _ in 0..=unbonding_delay {
+        builder.run_auction(timestamp_millis, Vec::new());
+        timestamp_millis += TIMESTAMP_MILLIS_INCREMENT;
+    }
+
+    // Only the first matured purse is paid out, the second one is carried over.
+    assert_eq!(
+        builder.get_purse_balance(delegator_1_undelegate_purse),
+        delegator_1_purse_balance_before + U512::from(UNDELEGATE_AMOUNT_1)
+    );
+    let unbonding_purses: UnbondingPurses = builder.get_unbonds();
+    let unbond_list = unbonding_purses
+        .get(&NON_FOUNDER_VALIDATOR_1_ADDR)
+        .expect("should have carried over unbonding purse");
+    assert_eq!(unbond_list.len(), 1);
+
+    builder.run_auction(timestamp_millis, Vec::new());
+
+    assert_eq!(
+        builder.get_purse_balance(delegator_1_undelegate_purse),
+        delegator_1_purse_balance_before + U512::from(UNDELEGATE_AMOUNT_1 * 2)
+    );
+    let unbonding_purses: UnbondingPurses = builder.get_unbonds();
+    assert!(unbonding_purses
+        .get(&NON_FOUNDER_VALIDATOR_1_ADDR)
+        .expect("should have unbonding list")
+        .is_empty());
+}
+
 #[ignore]
 #[test]
 fn fully_undelegated_funds_should_be_released() {
@@ -2617,6 +2778,9 @@ fn should_release_vfta_holder_stake() {
         NEW_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         WasmConfig::default(),
         SystemConfig::default(),
     );
@@ -3458,3 +3622,352 @@ fn should_handle_redelegation_to_inactive_validator() {
         delegator_2_purse_balance_after
     );
 }
+
+#[ignore]
+#[test]
+fn should_rotate_signing_key_at_era_boundary() {
+    let register_successor_key =
+        |builder: &mut InMemoryWasmTestBuilder,
+         validator_public_key: PublicKey,
+         successor_public_key: PublicKey| {
+            let request = ExecuteRequestBuilder::standard(
+                AccountHash::from(&validator_public_key),
+                CONTRACT_REGISTER_SUCCESSOR_KEY,
+                runtime_args! {
+                    auction::ARG_VALIDATOR_PUBLIC_KEY => validator_public_key,
+                    auction::ARG_SUCCESSOR_PUBLIC_KEY => successor_public_key,
+                },
+            )
+            .build();
+            builder.exec(request).commit();
+        };
+
+    let latest_validators = |builder: &mut InMemoryWasmTestBuilder| {
+        let era_validators: EraValidators = builder.get_era_validators();
+        let validators = era_validators
+            .iter()
+            .rev()
+            .next()
+            .map(|(_era_id, validators)| validators)
+            .expect("should have validators");
+        validators.keys().cloned().collect::<BTreeSet<PublicKey>>()
+    };
+
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::account(
+            ACCOUNT_1_PK.clone(),
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Some(GenesisValidator::new(
+                Motes::new(ACCOUNT_1_BOND.into()),
+                DelegationRate::zero(),
+            )),
+        );
+        let account_2 = GenesisAccount::account(
+            ACCOUNT_2_PK.clone(),
+            Motes::new(ACCOUNT_2_BALANCE.into()),
+            Some(GenesisValidator::new(
+                Motes::new(ACCOUNT_2_BOND.into()),
+                DelegationRate::zero(),
+            )),
+        );
+        tmp.push(account_1);
+        tmp.push(account_2);
+        tmp
+    };
+
+    let system_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(SYSTEM_TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let mut timestamp = DEFAULT_GENESIS_TIMESTAMP_MILLIS;
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    builder.exec(system_fund_request).commit().expect_success();
+
+    // A key used by another validator can't be registered as a successor key.
+    register_successor_key(&mut builder, ACCOUNT_1_PK.clone(), ACCOUNT_2_PK.clone());
+    let error = builder.get_error().expect("should have error");
+    assert!(matches!(
+        error,
+        engine_state::Error::Exec(execution::Error::Revert(ApiError::AuctionError(auction_error)))
+        if auction_error == system::auction::Error::SigningKeyInUse as u8
+    ));
+
+    register_successor_key(&mut builder, ACCOUNT_1_PK.clone(), SUCCESSOR_1_PK.clone());
+    builder.expect_success();
+
+    // The successor key is only used from the next era onwards.
+    assert_eq!(
+        latest_validators(&mut builder),
+        BTreeSet::from_iter(vec![ACCOUNT_1_PK.clone(), ACCOUNT_2_PK.clone()])
+    );
+
+    builder.run_auction(timestamp, Vec::new());
+    timestamp += WEEK_MILLIS;
+
+    assert_eq!(
+        latest_validators(&mut builder),
+        BTreeSet::from_iter(vec![SUCCESSOR_1_PK.clone(), ACCOUNT_2_PK.clone()])
+    );
+
+    // The validator keeps its bid under its own public key.
+    let bids: Bids = builder.get_bids();
+    assert!(bids.contains_key(&*ACCOUNT_1_PK));
+    assert!(!bids.contains_key(&*SUCCESSOR_1_PK));
+
+    // Consensus reports the validator by its signing key.
+    builder.run_auction(timestamp, vec![SUCCESSOR_1_PK.clone()]);
+
+    let bids: Bids = builder.get_bids();
+    assert!(bids
+        .get(&*ACCOUNT_1_PK)
+        .expect("should have bid")
+        .inactive());
+    assert_eq!(
+        latest_validators(&mut builder),
+        BTreeSet::from_iter(vec![ACCOUNT_2_PK.clone()])
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_add_bid_for_registered_successor_key() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::account(
+            ACCOUNT_1_PK.clone(),
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Some(GenesisValidator::new(
+                Motes::new(ACCOUNT_1_BOND.into()),
+                DelegationRate::zero(),
+            )),
+        );
+        let successor_1 = GenesisAccount::account(
+            SUCCESSOR_1_PK.clone(),
+            Motes::new(DEFAULT_ACCOUNT_INITIAL_BALANCE.into()),
+            None,
+        );
+        tmp.push(account_1);
+        tmp.push(successor_1);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let register_successor_key_request = ExecuteRequestBuilder::standard(
+        AccountHash::from(&*ACCOUNT_1_PK),
+        CONTRACT_REGISTER_SUCCESSOR_KEY,
+        runtime_args! {
+            auction::ARG_VALIDATOR_PUBLIC_KEY => ACCOUNT_1_PK.clone(),
+            auction::ARG_SUCCESSOR_PUBLIC_KEY => SUCCESSOR_1_PK.clone(),
+        },
+    )
+    .build();
+
+    builder
+        .exec(register_successor_key_request)
+        .expect_success()
+        .commit();
+
+    // The successor key of another validator can't be used to create a new bid.
+    let add_bid_request = ExecuteRequestBuilder::standard(
+        AccountHash::from(&*SUCCESSOR_1_PK),
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => SUCCESSOR_1_PK.clone(),
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+
+    builder.exec(add_bid_request).commit();
+
+    let error = builder.get_error().expect("should have error");
+    assert!(matches!(
+        error,
+        engine_state::Error::Exec(execution::Error::Revert(ApiError::AuctionError(auction_error)))
+        if auction_error == system::auction::Error::SigningKeyInUse as u8
+    ));
+
+    let bids: Bids = builder.get_bids();
+    assert!(!bids.contains_key(&*SUCCESSOR_1_PK));
+}
+
+#[ignore]
+#[test]
+fn should_read_bids_and_delegations_through_auction_state_helpers() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::account(
+            BID_ACCOUNT_1_PK.clone(),
+            Motes::new(BID_ACCOUNT_1_BALANCE.into()),
+            None,
+        );
+        tmp.push(account_1);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let transfer_request_1 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let transfer_request_2 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *NON_FOUNDER_VALIDATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let add_bid_request_1 = ExecuteRequestBuilder::standard(
+        *NON_FOUNDER_VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+
+    builder.exec(transfer_request_1).commit().expect_success();
+    builder.exec(transfer_request_2).commit().expect_success();
+    builder.exec(add_bid_request_1).commit().expect_success();
+
+    builder
+        .get_bid(&NON_FOUNDER_VALIDATOR_1_PK)
+        .expect("should have bid")
+        .assert_staked_amount(ADD_BID_AMOUNT_1)
+        .assert_delegation_rate(ADD_BID_DELEGATION_RATE_1)
+        .assert_active()
+        .assert_delegator_count(0);
+    assert!(builder.get_bid(&BID_ACCOUNT_1_PK).is_none());
+
+    let delegate_request_1 = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK.clone(),
+        },
+    )
+    .build();
+
+    builder.exec(delegate_request_1).commit().expect_success();
+
+    let bid = builder
+        .get_bid(&NON_FOUNDER_VALIDATOR_1_PK)
+        .expect("should have bid");
+    bid.assert_staked_amount(ADD_BID_AMOUNT_1)
+        .assert_delegator_count(1)
+        .assert_delegators(vec![BID_ACCOUNT_1_PK.clone()]);
+    bid.delegation(&BID_ACCOUNT_1_PK)
+        .expect("should have delegation")
+        .assert_staked_amount(DELEGATE_AMOUNT_1);
+
+    let undelegate_request_1 = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_UNDELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK.clone(),
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK.clone(),
+        },
+    )
+    .build();
+
+    builder.exec(undelegate_request_1).commit().expect_success();
+
+    builder
+        .get_delegation(&NON_FOUNDER_VALIDATOR_1_PK, &BID_ACCOUNT_1_PK)
+        .expect("should have delegation")
+        .assert_staked_amount(DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1);
+    assert!(builder
+        .get_delegation(&NON_FOUNDER_VALIDATOR_1_PK, &NON_FOUNDER_VALIDATOR_1_PK)
+        .is_none());
+}
+
+#[ignore]
+#[test]
+fn should_read_era_validator_set_through_auction_state_helpers() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::account(
+            ACCOUNT_1_PK.clone(),
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Some(GenesisValidator::new(
+                Motes::new(ACCOUNT_1_BOND.into()),
+                DelegationRate::zero(),
+            )),
+        );
+        let account_2 = GenesisAccount::account(
+            ACCOUNT_2_PK.clone(),
+            Motes::new(ACCOUNT_2_BALANCE.into()),
+            Some(GenesisValidator::new(
+                Motes::new(ACCOUNT_2_BOND.into()),
+                DelegationRate::zero(),
+            )),
+        );
+        tmp.push(account_1);
+        tmp.push(account_2);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let era_validator_set = builder
+        .get_era_validator_set(INITIAL_ERA_ID)
+        .expect("should have first era validator set");
+
+    assert_eq!(era_validator_set.era_id(), INITIAL_ERA_ID);
+    era_validator_set
+        .assert_validators(vec![ACCOUNT_1_PK.clone(), ACCOUNT_2_PK.clone()])
+        .assert_contains(&ACCOUNT_1_PK)
+        .assert_not_contains(&BID_ACCOUNT_1_PK)
+        .assert_weight(&ACCOUNT_1_PK, ACCOUNT_1_BOND)
+        .assert_weight(&ACCOUNT_2_PK, ACCOUNT_2_BOND)
+        .assert_total_weight(ACCOUNT_1_BOND + ACCOUNT_2_BOND);
+    assert_eq!(era_validator_set.weight(&BID_ACCOUNT_1_PK), None);
+    assert_eq!(
+        era_validator_set.into_inner(),
+        builder
+            .get_validator_weights(INITIAL_ERA_ID)
+            .expect("should have first era validator weights")
+    );
+}
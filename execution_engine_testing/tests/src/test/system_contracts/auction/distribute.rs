@@ -132,6 +132,27 @@ fn undelegate(
     builder.exec(undelegate_request).expect_success().commit();
 }
 
+fn claim_delegator_rewards(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    delegator: PublicKey,
+    validator: PublicKey,
+) {
+    let auction = builder.get_auction_contract_hash();
+    let claim_args = runtime_args! {
+        auction::ARG_DELEGATOR => delegator,
+        auction::ARG_VALIDATOR => validator,
+    };
+    let claim_request = ExecuteRequestBuilder::contract_call_by_hash(
+        sender,
+        auction,
+        auction::METHOD_CLAIM_DELEGATOR_REWARDS,
+        claim_args,
+    )
+    .build();
+    builder.exec(claim_request).expect_success().commit();
+}
+
 fn get_delegator_staked_amount(
     builder: &mut InMemoryWasmTestBuilder,
     validator: PublicKey,
@@ -152,6 +173,34 @@ fn get_delegator_staked_amount(
     *delegator_entry.staked_amount()
 }
 
+fn get_delegator_unclaimed_rewards(
+    builder: &mut InMemoryWasmTestBuilder,
+    validator: PublicKey,
+    delegator: PublicKey,
+) -> U512 {
+    let delegator_entry =
+        get_delegator_bid(builder, validator, delegator).expect("should have delegator entry");
+    *delegator_entry.unclaimed_rewards()
+}
+
+fn get_delegator_unbonding_amount(
+    builder: &mut InMemoryWasmTestBuilder,
+    validator: PublicKey,
+    delegator: PublicKey,
+) -> U512 {
+    builder
+        .get_unbonds()
+        .get(&validator.to_account_hash())
+        .map(|unbonding_purses| {
+            unbonding_purses
+                .iter()
+                .filter(|unbonding_purse| *unbonding_purse.unbonder_public_key() == delegator)
+                .map(|unbonding_purse| *unbonding_purse.amount())
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
 #[ignore]
 #[test]
 fn should_distribute_delegation_rate_zero() {
@@ -344,22 +393,35 @@ fn should_distribute_delegation_rate_zero() {
         validator_1_expected_payout
     );
 
-    let delegator_1_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
     assert_eq!(delegator_1_actual_payout, delegator_1_expected_payout);
 
-    let delegator_2_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
     assert_eq!(delegator_2_actual_payout, delegator_2_expected_payout);
 
+    // Delegator rewards are held in the validator's bonding purse until claimed.
+    let delegator_1_bid = get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone())
+        .expect("should have delegator 1 bid");
+    assert_eq!(
+        *delegator_1_bid.staked_amount(),
+        U512::from(DELEGATOR_1_STAKE)
+    );
+    assert_eq!(
+        builder.get_purse_balance(*delegator_1_bid.bonding_purse()),
+        U512::from(DELEGATOR_1_STAKE)
+    );
+    let validator_1_bid =
+        get_validator_bid(&mut builder, VALIDATOR_1.clone()).expect("should have validator bid");
+    assert_eq!(
+        builder.get_purse_balance(*validator_1_bid.bonding_purse()),
+        U512::from(VALIDATOR_1_STAKE)
+            + validator_1_actual_payout
+            + delegator_1_actual_payout
+            + delegator_2_actual_payout
+    );
+
     // Subsequently, there should be no more rewards
     let validator_1_balance = {
         withdraw_bid(
@@ -521,7 +583,9 @@ fn should_withdraw_bids_after_distribute() {
 
     let mut builder = InMemoryWasmTestBuilder::default();
 
-    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .with_invariant_checks();
 
     // initial token supply
     let initial_supply = builder.total_supply(None);
@@ -616,21 +680,13 @@ fn should_withdraw_bids_after_distribute() {
         validator_1_expected_payout
     );
 
-    let delegator_1_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
 
     assert_eq!(delegator_1_actual_payout, delegator_1_expected_payout);
 
-    let delegator_2_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
 
     assert_eq!(delegator_2_actual_payout, delegator_2_expected_payout);
 
@@ -639,7 +695,8 @@ fn should_withdraw_bids_after_distribute() {
             get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone()).is_some(),
             "delegator 1 should have a stake"
         );
-        let undelegate_amount = U512::from(DELEGATOR_1_STAKE) + delegator_1_actual_payout;
+        // Unclaimed rewards are paid out along with the stake once the delegator fully unbonds.
+        let undelegate_amount = U512::from(DELEGATOR_1_STAKE);
         undelegate(
             &mut builder,
             *DELEGATOR_1_ADDR,
@@ -651,6 +708,11 @@ fn should_withdraw_bids_after_distribute() {
             get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone()).is_none(),
             "delegator 1 did not unstake full expected amount"
         );
+        assert_eq!(
+            get_delegator_unbonding_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone()),
+            undelegate_amount + delegator_1_actual_payout,
+            "delegator 1 should unbond its stake along with its rewards"
+        );
         delegator_1_actual_payout
     };
     assert!(
@@ -663,7 +725,8 @@ fn should_withdraw_bids_after_distribute() {
             get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone()).is_some(),
             "delegator 2 should have a stake"
         );
-        let undelegate_amount = U512::from(DELEGATOR_2_STAKE) + delegator_2_actual_payout;
+        // Unclaimed rewards are paid out along with the stake once the delegator fully unbonds.
+        let undelegate_amount = U512::from(DELEGATOR_2_STAKE);
         undelegate(
             &mut builder,
             *DELEGATOR_2_ADDR,
@@ -675,6 +738,11 @@ fn should_withdraw_bids_after_distribute() {
             get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone()).is_none(),
             "delegator 2 did not unstake full expected amount"
         );
+        assert_eq!(
+            get_delegator_unbonding_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone()),
+            undelegate_amount + delegator_2_actual_payout,
+            "delegator 2 should unbond its stake along with its rewards"
+        );
         delegator_2_actual_payout
     };
     assert!(
@@ -872,10 +940,10 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
     let validator_1_staked_amount_1 = *get_validator_bid(&mut builder, VALIDATOR_1.clone())
         .expect("should have validator bid")
         .staked_amount();
-    let delegator_1_staked_amount_1 =
-        get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-    let delegator_2_staked_amount_1 =
-        get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
+    let delegator_1_unclaimed_rewards_1 =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
+    let delegator_2_unclaimed_rewards_1 =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
 
     let delegators_share = {
         let commission_rate = Ratio::new(
@@ -929,20 +997,11 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
     };
     assert_eq!(validator_1_actual_payout_1, validator_1_expected_payout_1);
 
-    let delegator_1_actual_payout_1 = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after = delegator_1_staked_amount_1;
-
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout_1 = delegator_1_unclaimed_rewards_1;
 
     assert_eq!(delegator_1_actual_payout_1, delegator_1_expected_payout_1);
 
-    let delegator_2_actual_payout_1 = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after = delegator_2_staked_amount_1;
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout_1 = delegator_2_unclaimed_rewards_1;
 
     assert_eq!(delegator_2_actual_payout_1, delegator_2_expected_payout_1);
 
@@ -1002,10 +1061,10 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
     let validator_1_staked_amount_2 = *get_validator_bid(&mut builder, VALIDATOR_1.clone())
         .expect("should have validator bid")
         .staked_amount();
-    let delegator_1_staked_amount_2 =
-        get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-    let delegator_2_staked_amount_2 =
-        get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
+    let delegator_1_unclaimed_rewards_2 =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
+    let delegator_2_unclaimed_rewards_2 =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
 
     let delegators_share_2 = {
         let commission_rate = Ratio::new(
@@ -1058,20 +1117,13 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
     };
     assert_eq!(validator_1_actual_payout_2, validator_1_expected_payout_2);
 
-    let delegator_1_actual_payout_2 = {
-        let delegator_stake_before = delegator_1_staked_amount_1;
-        let delegator_stake_after = delegator_1_staked_amount_2;
-
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout_2 =
+        delegator_1_unclaimed_rewards_2 - delegator_1_unclaimed_rewards_1;
 
     assert_eq!(delegator_1_actual_payout_2, delegator_1_expected_payout_2);
 
-    let delegator_2_actual_payout_2 = {
-        let delegator_stake_before = delegator_2_staked_amount_1;
-        let delegator_stake_after = delegator_2_staked_amount_2;
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout_2 =
+        delegator_2_unclaimed_rewards_2 - delegator_2_unclaimed_rewards_1;
 
     assert_eq!(delegator_2_actual_payout_2, delegator_2_expected_payout_2);
 
@@ -1112,14 +1164,13 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
         if *delegator_public_key == *DELEGATOR_2 && *amount == delegator_2_expected_payout_2
     ));
 
-    // Withdraw delegator rewards
+    // Claim delegator rewards
     let delegator_1_rewards = delegator_1_actual_payout_1 + delegator_1_actual_payout_2;
-    undelegate(
+    claim_delegator_rewards(
         &mut builder,
         *DELEGATOR_1_ADDR,
         DELEGATOR_1.clone(),
         VALIDATOR_1.clone(),
-        delegator_1_rewards,
     );
     let remaining_delegator_1_bid =
         get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone())
@@ -1128,14 +1179,18 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
         *remaining_delegator_1_bid.staked_amount(),
         U512::from(DELEGATOR_1_STAKE)
     );
+    assert!(remaining_delegator_1_bid.unclaimed_rewards().is_zero());
+    assert_eq!(
+        get_delegator_unbonding_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone()),
+        delegator_1_rewards
+    );
 
     let delegator_2_rewards = delegator_2_actual_payout_1 + delegator_2_actual_payout_2;
-    undelegate(
+    claim_delegator_rewards(
         &mut builder,
         *DELEGATOR_2_ADDR,
         DELEGATOR_2.clone(),
         VALIDATOR_1.clone(),
-        delegator_2_rewards,
     );
     let remaining_delegator_2_bid =
         get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone())
@@ -1144,6 +1199,11 @@ fn should_distribute_rewards_after_restaking_delegated_funds() {
         *remaining_delegator_2_bid.staked_amount(),
         U512::from(DELEGATOR_2_STAKE)
     );
+    assert!(remaining_delegator_2_bid.unclaimed_rewards().is_zero());
+    assert_eq!(
+        get_delegator_unbonding_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone()),
+        delegator_2_rewards
+    );
 
     // Withdraw validator rewards
     let validator_1_rewards = validator_1_actual_payout_1 + validator_1_actual_payout_2;
@@ -1766,20 +1826,12 @@ fn should_distribute_delegation_rate_half() {
 
     assert_eq!(validator_1_actual_payout, validator_1_expected_payout);
 
-    let delegator_1_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
     assert_eq!(delegator_1_actual_payout, delegator_1_expected_payout);
 
-    let delegator_2_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
     assert_eq!(delegator_2_actual_payout, delegator_2_expected_payout);
 
     let era_info = {
@@ -2196,20 +2248,12 @@ fn should_distribute_uneven_delegation_rate_zero() {
     };
     assert_eq!(validator_1_updated_stake, validator_1_expected_payout);
 
-    let delegator_1_updated_stake = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_updated_stake =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
     assert_eq!(delegator_1_updated_stake, delegator_1_expected_payout);
 
-    let delegator_2_updated_stake = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_updated_stake =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
     assert_eq!(delegator_2_updated_stake, delegator_2_expected_payout);
 
     let era_info = {
@@ -3170,26 +3214,14 @@ fn should_distribute_with_multiple_validators_and_delegators() {
         validator_stake_after - validator_stake_before
     };
 
-    let delegator_1_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
 
-    let delegator_2_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
 
-    let delegator_3_actual_payout = {
-        let delegator_stake_before = U512::from(DELEGATOR_3_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_2.clone(), DELEGATOR_3.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_3_actual_payout =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_2.clone(), DELEGATOR_3.clone());
 
     let era_info = {
         let era = builder.get_era() - 1;
@@ -3603,35 +3635,23 @@ fn should_distribute_with_multiple_validators_and_shared_delegator() {
     };
     assert_eq!(validator_3_actual_payout, validator_3_expected_payout);
 
-    let delegator_1_validator_1_updated_stake = {
-        let delegator_balance_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_balance_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_balance_after - delegator_balance_before
-    };
+    let delegator_1_validator_1_updated_stake =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
 
     assert_eq!(
         delegator_1_validator_1_updated_stake,
         validator_1_delegator_1_share
     );
 
-    let delegator_1_validator_2_updated_stake = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_2.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_validator_2_updated_stake =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_2.clone(), DELEGATOR_1.clone());
     assert_eq!(
         delegator_1_validator_2_updated_stake,
         validator_2_delegator_1_share
     );
 
-    let delegator_1_validator_3_updated_stake = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_3.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_validator_3_updated_stake =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_3.clone(), DELEGATOR_1.clone());
     assert_eq!(
         delegator_1_validator_3_updated_stake,
         validator_3_delegator_1_share
@@ -4256,21 +4276,13 @@ fn should_distribute_delegation_rate_full_after_upgrading() {
         (expected_total_reward_before * Ratio::from(U512::one())).to_integer();
     assert_eq!(validator_1_stake_before, expected_validator_1_payout_before);
 
-    let delegator_1_stake_before = {
-        let delegator_stake_before = U512::from(DELEGATOR_1_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_1_stake_before =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
     let expected_delegator_1_payout_before = U512::zero();
     assert_eq!(delegator_1_stake_before, expected_delegator_1_payout_before);
 
-    let delegator_2_stake_before = {
-        let delegator_stake_before = U512::from(DELEGATOR_2_STAKE);
-        let delegator_stake_after =
-            get_delegator_staked_amount(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
-        delegator_stake_after - delegator_stake_before
-    };
+    let delegator_2_stake_before =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_2.clone());
     let expected_delegator_2_balance = U512::zero();
     assert_eq!(delegator_2_stake_before, expected_delegator_2_balance);
 
@@ -4659,3 +4671,133 @@ fn delegator_full_unbond_during_first_reward_era() {
     let delegator = get_delegator_bid(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
     assert!(delegator.is_none());
 }
+
+#[ignore]
+#[test]
+fn should_burn_unclaimed_delegator_rewards_when_slashing() {
+    const VALIDATOR_1_STAKE: u64 = DEFAULT_MINIMUM_DELEGATION_AMOUNT;
+    const DELEGATOR_1_STAKE: u64 = DEFAULT_MINIMUM_DELEGATION_AMOUNT;
+    const VALIDATOR_1_DELEGATION_RATE: DelegationRate = 0;
+
+    let system_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *VALIDATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let delegator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            ARG_TARGET => *DELEGATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_add_bid_request = ExecuteRequestBuilder::standard(
+        *VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(VALIDATOR_1_STAKE),
+            ARG_DELEGATION_RATE => VALIDATOR_1_DELEGATION_RATE,
+            ARG_PUBLIC_KEY => VALIDATOR_1.clone(),
+        },
+    )
+    .build();
+
+    let delegator_1_delegate_request = ExecuteRequestBuilder::standard(
+        *DELEGATOR_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATOR_1_STAKE),
+            ARG_VALIDATOR => VALIDATOR_1.clone(),
+            ARG_DELEGATOR => DELEGATOR_1.clone(),
+        },
+    )
+    .build();
+
+    let post_genesis_requests = vec![
+        system_fund_request,
+        validator_1_fund_request,
+        delegator_1_fund_request,
+        validator_1_add_bid_request,
+        delegator_1_delegate_request,
+    ];
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for request in post_genesis_requests {
+        builder.exec(request).commit().expect_success();
+    }
+
+    for _ in 0..=builder.get_auction_delay() {
+        let step_request = StepRequestBuilder::new()
+            .with_parent_state_hash(builder.get_post_state_hash())
+            .with_protocol_version(ProtocolVersion::V1_0_0)
+            .with_next_era_id(builder.get_era().successor())
+            .with_run_auction(true)
+            .build();
+        builder
+            .step(step_request)
+            .expect("must execute step successfully");
+    }
+
+    let step_request = StepRequestBuilder::new()
+        .with_parent_state_hash(builder.get_post_state_hash())
+        .with_protocol_version(ProtocolVersion::V1_0_0)
+        .with_reward_item(RewardItem::new(VALIDATOR_1.clone(), BLOCK_REWARD))
+        .with_next_era_id(builder.get_era().successor())
+        .with_run_auction(true)
+        .build();
+    builder
+        .step(step_request)
+        .expect("must execute step successfully");
+
+    let delegator_1_unclaimed_rewards =
+        get_delegator_unclaimed_rewards(&mut builder, VALIDATOR_1.clone(), DELEGATOR_1.clone());
+    assert!(!delegator_1_unclaimed_rewards.is_zero());
+    let validator_1_stake = *get_validator_bid(&mut builder, VALIDATOR_1.clone())
+        .expect("should have validator bid")
+        .staked_amount();
+    let total_supply_before = builder.total_supply(None);
+
+    let slash_request = ExecuteRequestBuilder::contract_call_by_hash(
+        *SYSTEM_ADDR,
+        builder.get_auction_contract_hash(),
+        auction::METHOD_SLASH,
+        runtime_args! {
+            auction::ARG_VALIDATOR_PUBLIC_KEYS => vec![VALIDATOR_1.clone()]
+        },
+    )
+    .build();
+    builder.exec(slash_request).expect_success().commit();
+
+    // The validator's stake and the rewards held for its delegators are both burned.
+    assert!(get_delegator_unclaimed_rewards(
+        &mut builder,
+        VALIDATOR_1.clone(),
+        DELEGATOR_1.clone()
+    )
+    .is_zero());
+    assert_eq!(
+        builder.total_supply(None),
+        total_supply_before - validator_1_stake - delegator_1_unclaimed_rewards
+    );
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use num_traits::Zero;
 use once_cell::sync::Lazy;
 
@@ -138,6 +140,7 @@ fn should_track_total_token_supply_in_mint() {
         round_seigniorage_rate,
         unbonding_delay,
         genesis_timestamp,
+        BTreeMap::new(),
     );
     let run_genesis_request = RunGenesisRequest::new(
         GENESIS_CONFIG_HASH.into(),
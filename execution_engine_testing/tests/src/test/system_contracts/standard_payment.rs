@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use assert_matches::assert_matches;
 
@@ -9,7 +9,7 @@ use casper_engine_test_support::{
 };
 use casper_execution_engine::{
     core::{
-        engine_state::{Error, MAX_PAYMENT},
+        engine_state::{EngineConfig, Error, MAX_PAYMENT},
         execution,
     },
     shared::transform::Transform,
@@ -220,6 +220,35 @@ fn should_forward_payment_execution_gas_limit_error() {
     );
 }
 
+#[ignore]
+#[test]
+fn should_limit_execution_duration_of_speculative_deploys_only() {
+    let engine_config =
+        EngineConfig::default().with_max_execution_duration(Some(Duration::from_nanos(1)));
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let endless_loop_request = |deploy_hash| {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_deploy_hash(deploy_hash)
+            .with_payment_code(ENDLESS_LOOP_WASM, RuntimeArgs::default())
+            .with_session_code(DO_NOTHING_WASM, RuntimeArgs::default())
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_KEY])
+            .build();
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    builder.exec(endless_loop_request([1; 32]));
+    let error = builder.get_error().expect("should have error");
+    assert_matches!(error, Error::Exec(execution::Error::GasLimit));
+
+    builder.exec(endless_loop_request([2; 32]).into_speculative());
+    let error = builder.get_error().expect("should have error");
+    assert_matches!(error, Error::Exec(execution::Error::ExecutionTimeLimit));
+}
+
 #[ignore]
 #[test]
 fn should_run_out_of_gas_when_session_code_exceeds_gas_limit() {
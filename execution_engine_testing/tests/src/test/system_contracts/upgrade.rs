@@ -11,13 +11,16 @@ use casper_engine_test_support::{
 use casper_execution_engine::{
     core::engine_state::{
         engine_config::{
-            DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-            DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
         },
         EngineConfig, DEFAULT_MAX_QUERY_DEPTH, DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
     },
     shared::{
         host_function_costs::HostFunctionCosts,
+        memory_costs::MemoryCosts,
+        module_limits::ModuleLimits,
         opcode_costs::{
             OpcodeCosts, DEFAULT_ADD_COST, DEFAULT_BIT_COST, DEFAULT_CONST_COST,
             DEFAULT_CONTROL_FLOW_COST, DEFAULT_CONVERSION_COST, DEFAULT_CURRENT_MEMORY_COST,
@@ -42,7 +45,7 @@ use casper_types::{
         auction::{
             AUCTION_DELAY_KEY, LOCKED_FUNDS_PERIOD_KEY, UNBONDING_DELAY_KEY, VALIDATOR_SLOTS_KEY,
         },
-        mint::ROUND_SEIGNIORAGE_RATE_KEY,
+        mint::{NativeTokens, NATIVE_TOKENS_KEY, ROUND_SEIGNIORAGE_RATE_KEY},
     },
     CLValue, EraId, ProtocolVersion, RuntimeArgs, StoredValue, U256, U512,
 };
@@ -78,7 +81,9 @@ fn get_upgraded_wasm_config() -> WasmConfig {
         DEFAULT_MAX_STACK_HEIGHT * 2,
         opcode_cost,
         storage_costs,
+        MemoryCosts::default(),
         host_function_costs,
+        ModuleLimits::default(),
     )
 }
 
@@ -144,6 +149,9 @@ fn should_allow_only_wasm_costs_patch_version() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         new_wasm_config,
         SystemConfig::default(),
     );
@@ -189,6 +197,9 @@ fn should_allow_only_wasm_costs_minor_version() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         new_wasm_config,
         SystemConfig::default(),
     );
@@ -541,6 +552,73 @@ fn should_upgrade_only_round_seigniorage_rate() {
     );
 }
 
+#[ignore]
+#[test]
+fn should_upgrade_native_tokens() {
+    const TOKEN: &str = "GOLD";
+    let new_issuer = AccountHash::new([42; ACCOUNT_HASH_LENGTH]);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let mint_contract_hash = builder.get_mint_contract_hash();
+    assert!(
+        !builder
+            .get_contract(mint_contract_hash)
+            .expect("mint should exist")
+            .named_keys()
+            .contains_key(NATIVE_TOKENS_KEY),
+        "should not create the registry of native tokens if none are declared"
+    );
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let mut current_protocol_version = PROTOCOL_VERSION;
+    for issuer in [*DEFAULT_ACCOUNT_ADDR, new_issuer] {
+        let new_protocol_version = ProtocolVersion::from_parts(
+            sem_ver.major,
+            sem_ver.minor,
+            current_protocol_version.value().patch + 1,
+        );
+        let mut native_tokens = BTreeMap::new();
+        native_tokens.insert(TOKEN.to_string(), issuer);
+
+        let mut upgrade_request = {
+            UpgradeRequestBuilder::new()
+                .with_current_protocol_version(current_protocol_version)
+                .with_new_protocol_version(new_protocol_version)
+                .with_activation_point(DEFAULT_ACTIVATION_POINT)
+                .with_new_native_tokens(native_tokens)
+                .build()
+        };
+
+        builder
+            .upgrade_with_upgrade_request(
+                *builder.get_engine_state().config(),
+                &mut upgrade_request,
+            )
+            .expect_upgrade_success();
+        current_protocol_version = new_protocol_version;
+
+        let native_tokens_key = builder
+            .get_contract(mint_contract_hash)
+            .expect("mint should exist")
+            .named_keys()[NATIVE_TOKENS_KEY];
+
+        let native_tokens: NativeTokens = builder
+            .query(None, native_tokens_key, &[])
+            .expect("should have native tokens")
+            .as_cl_value()
+            .expect("should be a CLValue")
+            .clone()
+            .into_t()
+            .expect("should be a map of native tokens");
+
+        assert_eq!(native_tokens.len(), 1);
+        assert_eq!(native_tokens[TOKEN], (issuer, U512::zero()));
+    }
+}
+
 #[ignore]
 #[test]
 fn should_upgrade_only_unbonding_delay() {
@@ -688,6 +766,9 @@ fn should_increase_max_associated_keys_after_upgrade() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         *DEFAULT_WASM_CONFIG,
         new_system_config,
     );
@@ -10,8 +10,9 @@ use casper_engine_test_support::{
 use casper_execution_engine::{
     core::engine_state::{
         engine_config::{
-            DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-            DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
         },
         genesis::GenesisValidator,
         EngineConfig, GenesisAccount, DEFAULT_MAX_QUERY_DEPTH,
@@ -19,6 +20,8 @@ use casper_execution_engine::{
     },
     shared::{
         host_function_costs::{Cost, HostFunction, HostFunctionCosts},
+        memory_costs::MemoryCosts,
+        module_limits::ModuleLimits,
         opcode_costs::OpcodeCosts,
         storage_costs::StorageCosts,
         system_config::{
@@ -65,6 +68,7 @@ const NEW_ADD_BID_COST: u32 = DEFAULT_ADD_BID_COST * 2;
 const NEW_WITHDRAW_BID_COST: u32 = DEFAULT_WITHDRAW_BID_COST * 3;
 const NEW_DELEGATE_COST: u32 = DEFAULT_DELEGATE_COST * 4;
 const NEW_UNDELEGATE_COST: u32 = DEFAULT_UNDELEGATE_COST * 5;
+const NEW_PAY_COST: u32 = 12_345;
 const DEFAULT_ACTIVATION_POINT: EraId = EraId::new(1);
 
 static OLD_PROTOCOL_VERSION: Lazy<ProtocolVersion> = Lazy::new(|| *DEFAULT_PROTOCOL_VERSION);
@@ -215,6 +219,9 @@ fn upgraded_add_bid_and_withdraw_bid_have_expected_costs() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         WasmConfig::default(),
         new_system_config,
     );
@@ -511,6 +518,9 @@ fn upgraded_delegate_and_undelegate_have_expected_costs() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         WasmConfig::default(),
         new_system_config,
     );
@@ -904,6 +914,85 @@ fn should_verify_do_nothing_charges_only_for_standard_payment() {
     assert_eq!(builder.last_exec_gas_cost(), Gas::new(U512::zero()));
 }
 
+#[ignore]
+#[test]
+fn upgraded_standard_payment_charges_only_configured_pay_cost() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&*PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let new_system_config = SystemConfig::new(
+        DEFAULT_WASMLESS_TRANSFER_COST,
+        AuctionCosts::default(),
+        MintCosts::default(),
+        HandlePaymentCosts::default(),
+        StandardPaymentCosts { pay: NEW_PAY_COST },
+    );
+
+    let new_engine_config = EngineConfig::new(
+        DEFAULT_MAX_QUERY_DEPTH,
+        DEFAULT_MAX_ASSOCIATED_KEYS,
+        DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
+        DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+        DEFAULT_STRICT_ARGUMENT_CHECKING,
+        DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
+        WasmConfig::default(),
+        new_system_config,
+    );
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(*OLD_PROTOCOL_VERSION)
+            .with_new_protocol_version(*NEW_PROTOCOL_VERSION)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(new_engine_config, &mut upgrade_request);
+
+    let default_account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account");
+
+    let do_nothing_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_bytes(wasm_utils::do_nothing_bytes(), RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => *DEFAULT_PAYMENT
+            })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+
+        ExecuteRequestBuilder::from_deploy_item(deploy_item)
+            .with_protocol_version(*NEW_PROTOCOL_VERSION)
+            .build()
+    };
+
+    let user_funds_before = builder.get_purse_balance(default_account.main_purse());
+
+    let proposer_reward_starting_balance = builder.get_proposer_purse_balance();
+
+    builder.exec(do_nothing_request).commit().expect_success();
+
+    let user_funds_after = builder.get_purse_balance(default_account.main_purse());
+
+    let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
+
+    assert_eq!(user_funds_after, user_funds_before - transaction_fee);
+
+    // The native standard payment costs exactly the configured `pay` cost, regardless of the
+    // host functions it uses under the hood.
+    assert_eq!(
+        builder.last_exec_gas_cost(),
+        Gas::new(U512::from(NEW_PAY_COST))
+    );
+}
+
 #[ignore]
 #[test]
 fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
@@ -979,6 +1068,9 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         print: HostFunction::fixed(0),
         blake2b: HostFunction::fixed(0),
         random_bytes: HostFunction::fixed(0),
+        remove_contract: HostFunction::fixed(0),
+        get_block_context: HostFunction::fixed(0),
+        narrow_uref: HostFunction::fixed(0),
     };
 
     let new_wasm_config = WasmConfig::new(
@@ -986,7 +1078,9 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         DEFAULT_MAX_STACK_HEIGHT,
         new_opcode_costs,
         new_storage_costs,
+        MemoryCosts::zero(),
         new_host_function_costs,
+        ModuleLimits::default(),
     );
 
     let new_wasmless_transfer_cost = 0;
@@ -1011,6 +1105,9 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         new_wasm_config,
         new_system_config,
     );
@@ -0,0 +1,40 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::ExecuteRequest;
+use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1; 32]);
+
+fn transfer_request() -> ExecuteRequest {
+    ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            "target" => ACCOUNT_1_ADDR,
+            "amount" => U512::from(2_500_000_000u64),
+            "id" => <Option<u64>>::None,
+        },
+    )
+    .build()
+}
+
+#[ignore]
+#[test]
+fn should_serve_repeated_reads_from_cache() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let before = builder.read_cache_stats();
+    builder.exec(transfer_request()).expect_success();
+    let first = builder.read_cache_stats() - before;
+    assert!(first.misses > 0, "{:?}", first);
+    assert!(first.hits > 0, "{:?}", first);
+
+    // The cache is scoped to a single execution, so executing the same deploy against the same
+    // state again reads exactly the same way.
+    let before = builder.read_cache_stats();
+    builder.exec(transfer_request()).expect_success();
+    let second = builder.read_cache_stats() - before;
+    assert_eq!(first, second);
+}
@@ -9,8 +9,9 @@ use casper_execution_engine::{
     core::{
         engine_state::{
             engine_config::{
-                DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-                DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+                DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+                DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+                DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
             },
             EngineConfig, Error as CoreError, DEFAULT_MAX_QUERY_DEPTH,
             DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT, WASMLESS_TRANSFER_FIXED_GAS_PRICE,
@@ -642,6 +643,68 @@ fn transfer_wasmless_should_create_target_if_it_doesnt_exist() {
     );
 }
 
+#[ignore]
+#[test]
+fn transfer_wasmless_should_run_batch_of_transfers() {
+    let wasmless_transfer_gas_cost = Gas::from(DEFAULT_WASMLESS_TRANSFER_COST);
+    let wasmless_transfer_cost = Motes::from_gas(
+        wasmless_transfer_gas_cost,
+        WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+    )
+    .expect("gas overflow");
+
+    let create_account_2: bool = false;
+    let mut builder = init_wasmless_transform_builder(create_account_2);
+    let transfer_amount: U512 = U512::from(1000);
+
+    let account_1_purse = builder
+        .get_account(*ACCOUNT_1_ADDR)
+        .expect("should get account 1")
+        .main_purse();
+
+    let account_1_starting_balance = builder.get_purse_balance(account_1_purse);
+
+    // The second transfer sees the account created by the first one.
+    let batch_request = [[42; 32], [43; 32]]
+        .iter()
+        .fold(ExecuteRequestBuilder::new(), |request, deploy_hash| {
+            let runtime_args = runtime_args! {
+               mint::ARG_TARGET => *ACCOUNT_2_ADDR,
+               mint::ARG_AMOUNT => transfer_amount,
+               mint::ARG_ID => <Option<u64>>::None
+            };
+            let deploy_item = DeployItemBuilder::new()
+                .with_address(*ACCOUNT_1_ADDR)
+                .with_empty_payment_bytes(runtime_args! {})
+                .with_transfer_args(runtime_args)
+                .with_authorization_keys(&[*ACCOUNT_1_ADDR])
+                .with_deploy_hash(*deploy_hash)
+                .build();
+            request.push_deploy(deploy_item)
+        })
+        .build();
+
+    builder
+        .exec_transfers(batch_request)
+        .expect_success()
+        .commit_all();
+
+    let account_2 = builder
+        .get_account(*ACCOUNT_2_ADDR)
+        .expect("account 2 should exist");
+
+    assert_eq!(
+        account_1_starting_balance - (transfer_amount + wasmless_transfer_cost.value()) * 2,
+        builder.get_purse_balance(account_1_purse),
+        "account 1 ending balance incorrect"
+    );
+    assert_eq!(
+        builder.get_purse_balance(account_2.main_purse()),
+        transfer_amount * 2,
+        "account 2 ending balance incorrect"
+    );
+}
+
 fn get_default_account_named_uref(builder: &mut InMemoryWasmTestBuilder, name: &str) -> URef {
     let default_account = builder
         .get_account(*DEFAULT_ACCOUNT_ADDR)
@@ -1003,6 +1066,9 @@ fn transfer_wasmless_should_observe_upgraded_cost() {
         DEFAULT_MINIMUM_DELEGATION_AMOUNT,
         DEFAULT_STRICT_ARGUMENT_CHECKING,
         DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+        DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+        DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+        DEFAULT_MAX_UNBONDINGS_PER_STEP,
         WasmConfig::default(),
         new_system_config,
     );
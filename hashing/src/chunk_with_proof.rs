@@ -61,6 +61,19 @@ impl ChunkWithProof {
     ///
     /// Empty data is always represented as single, empty chunk and not as zero chunks.
     pub fn new(data: &[u8], index: u64) -> Result<Self, MerkleConstructionError> {
+        Self::new_with_chunk_size(data, index, Self::CHUNK_SIZE_BYTES)
+    }
+
+    /// Constructs the [`ChunkWithProof`] like [`ChunkWithProof::new`], but splitting the data into
+    /// chunks of `chunk_size` bytes.
+    ///
+    /// Verifying a chunk doesn't depend on the chunk size, but all chunks of the same data need to
+    /// be constructed with the same size to share the proof's root hash.
+    pub fn new_with_chunk_size(
+        data: &[u8],
+        index: u64,
+        chunk_size: usize,
+    ) -> Result<Self, MerkleConstructionError> {
         Ok(if data.is_empty() {
             ChunkWithProof {
                 proof: IndexedMerkleProof::new([Digest::blake2b_hash(&[])], index)?,
@@ -69,18 +82,15 @@ impl ChunkWithProof {
         } else {
             ChunkWithProof {
                 proof: IndexedMerkleProof::new(
-                    data.chunks(Self::CHUNK_SIZE_BYTES)
-                        .map(Digest::blake2b_hash),
+                    data.chunks(chunk_size).map(Digest::blake2b_hash),
                     index,
                 )?,
-                chunk: Bytes::from(
-                    data.chunks(Self::CHUNK_SIZE_BYTES)
-                        .nth(index as usize)
-                        .ok_or_else(|| MerkleConstructionError::IndexOutOfBounds {
-                            count: data.chunks(Self::CHUNK_SIZE_BYTES).len() as u64,
-                            index,
-                        })?,
-                ),
+                chunk: Bytes::from(data.chunks(chunk_size).nth(index as usize).ok_or_else(
+                    || MerkleConstructionError::IndexOutOfBounds {
+                        count: data.chunks(chunk_size).len() as u64,
+                        index,
+                    },
+                )?),
             }
         })
     }
@@ -238,6 +248,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generates_valid_proof_with_custom_chunk_size() {
+        let chunk_size = 3;
+        let data = prepare_bytes(10);
+        let expected_root = Digest::hash_merkle_tree(data.chunks(chunk_size).map(Digest::hash));
+
+        for index in 0..4 {
+            let chunk_with_proof =
+                ChunkWithProof::new_with_chunk_size(data.as_slice(), index, chunk_size).unwrap();
+            assert!(chunk_with_proof.verify().is_ok());
+            assert_eq!(chunk_with_proof.proof.count(), 4);
+            assert_eq!(chunk_with_proof.proof.root_hash(), expected_root);
+        }
+        assert!(ChunkWithProof::new_with_chunk_size(data.as_slice(), 4, chunk_size).is_err());
+    }
+
     #[proptest]
     fn verifies_chunk_with_proofs(test_data: TestDataSize) {
         for data in [prepare_bytes(test_data.0), vec![0u8; test_data.0]] {
@@ -122,15 +122,32 @@ pub fn route<P: AsRef<str>>(
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+) -> BoxedFilter<(impl Reply,)> {
+    route_with_cors(
+        path,
+        max_body_bytes,
+        handlers,
+        allow_unknown_fields,
+        warp::cors().allow_any_origin(),
+    )
+}
+
+/// Constructs a set of warp filters suitable for use in a JSON-RPC server, using the given CORS
+/// policy.
+///
+/// This is the same as [`route`], except that the allowed origins and any additional allowed
+/// headers are taken from `cors`.  "content-type" as a header and the method "POST" are always
+/// allowed.
+pub fn route_with_cors<P: AsRef<str>>(
+    path: P,
+    max_body_bytes: u32,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    cors: warp::cors::Builder,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
         .and(filters::main_filter(handlers, allow_unknown_fields))
         .recover(filters::handle_rejection)
-        .with(
-            warp::cors()
-                .allow_any_origin()
-                .allow_header(CONTENT_TYPE)
-                .allow_method(Method::POST),
-        )
+        .with(cors.allow_header(CONTENT_TYPE).allow_method(Method::POST))
         .boxed()
 }
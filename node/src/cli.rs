@@ -3,10 +3,13 @@
 //! Most configuration is done via config files (see [`config`](../config/index.html) for details).
 
 pub mod arglang;
+mod backfill;
+mod local_net;
 
 use std::{
     alloc::System,
     env, fs,
+    net::SocketAddr,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -17,14 +20,16 @@ use regex::Regex;
 use stats_alloc::{StatsAlloc, INSTRUMENTED_SYSTEM};
 use structopt::StructOpt;
 use toml::{value::Table, Value};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+use casper_types::TimeDiff;
 
 use crate::{
     logging,
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     setup_signal_hooks,
-    types::ExitCode,
-    utils::WithDir,
+    types::{chainspec, Chainspec, ChainspecRawBytes, ExitCode, NodeMode},
+    utils::{preflight, Loadable, WithDir},
 };
 
 // We override the standard allocator to gather metrics and tune the allocator via th MALLOC_CONF
@@ -74,6 +79,81 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Check a chainspec for inconsistent settings before deploying it.
+    ///
+    /// Loads the chainspec and accompanying files from the given directory and validates them. If
+    /// the directory of the chainspec of the previous protocol version is given, also checks that
+    /// the upgrade from that version is allowed.
+    CheckChainspec {
+        /// Path to the directory containing the chainspec.toml to check.
+        chainspec_dir: PathBuf,
+        /// Path to the directory containing the chainspec.toml of the previous protocol version.
+        #[structopt(long)]
+        previous: Option<PathBuf>,
+    },
+    /// Launch a local test network.
+    ///
+    /// Generates keys, a chainspec, accounts and configs for the given number of validators, runs
+    /// each of them as a child process, and accepts line-based commands on the control address:
+    /// `status`, `start <ID>`, `stop <ID>`, `kill <ID>`, `restart <ID>`, `pause <ID>`,
+    /// `resume <ID>`, `stage-upgrade <VERSION> <ACTIVATION ERA>` and `shutdown`.
+    LocalNet {
+        /// Number of validators to launch.
+        #[structopt(long, default_value = "5")]
+        validators: u16,
+        /// Directory to generate the network in. Must not exist yet.
+        #[structopt(long, default_value = "local-net")]
+        base_dir: PathBuf,
+        /// Chainspec template, with `${TIMESTAMP}` in place of the genesis timestamp.
+        #[structopt(long, default_value = "resources/local/chainspec.toml.in")]
+        chainspec_template: PathBuf,
+        /// Node config template.
+        #[structopt(long, default_value = "resources/local/config.toml")]
+        config_template: PathBuf,
+        /// First port of the range used by the nodes. Node N listens on the ten ports starting at
+        /// the base port plus 10 * N.
+        #[structopt(long, default_value = "11000")]
+        base_port: u16,
+        /// Time from now until genesis.
+        #[structopt(long, default_value = "30sec")]
+        genesis_delay: TimeDiff,
+        /// Address to accept control commands on.
+        #[structopt(long, default_value = "127.0.0.1:11000")]
+        control_address: SocketAddr,
+    },
+    /// Inspect or repair the storage of a node which is not running.
+    Storage {
+        #[structopt(subcommand)]
+        command: StorageCommand,
+    },
+}
+
+/// Subcommands operating on the storage of a node which is not running.
+#[derive(Debug, StructOpt)]
+pub enum StorageCommand {
+    /// Regenerate missing deploy execution results.
+    ///
+    /// Reads the stored blocks in the given height range and reports the deploys for which no
+    /// execution result is stored.  With `--re-execute`, each affected block is executed again on
+    /// top of its parent's global state, and the missing execution results are stored if the
+    /// resulting block matches the stored one.
+    BackfillExecResults {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Overrides and extensions for configuration file entries in the form
+        /// <SECTION>.<KEY>=<VALUE>.
+        #[structopt(short = "C", long, use_delimiter(true), value_delimiter(";"))]
+        config_ext: Vec<ConfigExt>,
+        /// Height of the lowest block to check.
+        #[structopt(long, default_value = "0")]
+        from_height: u64,
+        /// Height of the highest block to check. Defaults to the highest stored block.
+        #[structopt(long)]
+        to_height: Option<u64>,
+        /// Re-execute blocks to regenerate their missing execution results.
+        #[structopt(long)]
+        re_execute: bool,
+    },
 }
 
 #[derive(Debug)]
@@ -108,6 +188,33 @@ impl ConfigExt {
     }
 }
 
+/// Applies the settings of the given node mode's profile to the TOML config table.
+///
+/// Returns the settings which were configured differently, along with their previous values.
+fn apply_node_mode_profile(
+    mode: NodeMode,
+    toml_value: &mut Value,
+) -> anyhow::Result<Vec<(&'static str, &'static str, Value, Value)>> {
+    let table = toml_value
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("configuration table is not a table"))?;
+
+    let mut overridden = Vec::new();
+    for (section, key, value) in mode.profile() {
+        let section_table = table
+            .entry(section)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("configuration section {} is not a table", section))?;
+        if let Some(previous) = section_table.insert(key.to_string(), value.clone()) {
+            if previous != value {
+                overridden.push((section, key, previous, value));
+            }
+        }
+    }
+    Ok(overridden)
+}
+
 impl FromStr for ConfigExt {
     type Err = anyhow::Error;
 
@@ -148,6 +255,31 @@ impl Cli {
                 let validator_config = Self::init(&config, config_ext)?;
                 info!(version = %crate::VERSION_STRING.as_str(), "node starting up");
 
+                let node_config = &validator_config.value().node;
+                if let Some(mode) = node_config.mode {
+                    if node_config.skip_preflight_checks {
+                        warn!(%mode, "skipping preflight checks");
+                    } else {
+                        let storage_path = validator_config
+                            .with_dir(validator_config.value().storage.path.clone());
+                        let failures = preflight::run_preflight_checks(mode, &storage_path);
+                        for failure in &failures {
+                            error!(%failure, "preflight check failed");
+                        }
+                        if !failures.is_empty() {
+                            anyhow::bail!(
+                                "{} preflight check(s) failed for {} mode",
+                                failures.len(),
+                                mode
+                            );
+                        }
+                        info!(%mode, "preflight checks passed");
+                    }
+                }
+
+                // The watchdog configuration is shared across all reactors.
+                let watchdog_config = validator_config.map_ref(|config| config.watchdog.clone());
+
                 // We use a `ChaCha20Rng` for the production node. For one, we want to completely
                 // eliminate any chance of runtime failures, regardless of how small (these
                 // exist with `OsRng`). Additionally, we want to limit the number of syscalls for
@@ -163,6 +295,7 @@ impl Cli {
                     &registry,
                 )
                 .await?;
+                initializer_runner.start_watchdog(&watchdog_config)?;
 
                 match initializer_runner.run(&mut rng).await {
                     ReactorExit::ProcessShouldExit(exit_code) => return Ok(exit_code as i32),
@@ -180,6 +313,8 @@ impl Cli {
                     &registry,
                 )
                 .await?;
+                joiner_runner.start_watchdog(&watchdog_config)?;
+
                 match joiner_runner.run(&mut rng).await {
                     ReactorExit::ProcessShouldExit(exit_code) => return Ok(exit_code as i32),
                     ReactorExit::ProcessShouldContinue => info!("finished joining"),
@@ -191,9 +326,16 @@ impl Cli {
                 let mut participating_runner =
                     Runner::<participating::Reactor>::with_metrics(config, &mut rng, &registry)
                         .await?;
+                participating_runner.start_watchdog(&watchdog_config)?;
 
                 match participating_runner.run(&mut rng).await {
-                    ReactorExit::ProcessShouldExit(exit_code) => Ok(exit_code as i32),
+                    ReactorExit::ProcessShouldExit(ExitCode::Abort) => Ok(ExitCode::Abort as i32),
+                    ReactorExit::ProcessShouldExit(exit_code) => {
+                        let participating_reactor = participating_runner.drain_into_inner().await;
+                        participating_reactor.shutdown_storage()?;
+                        info!(?exit_code, "storage flushed, shutting down");
+                        Ok(exit_code as i32)
+                    }
                     reactor_exit => {
                         error!("validator should not exit with {:?}", reactor_exit);
                         Ok(ExitCode::Abort as i32)
@@ -244,6 +386,78 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::CheckChainspec {
+                chainspec_dir,
+                previous,
+            } => {
+                logging::init_with_config(&Default::default())?;
+
+                let load = |dir: &Path| {
+                    <(Chainspec, ChainspecRawBytes)>::from_path(dir)
+                        .with_context(|| format!("could not load chainspec from {}", dir.display()))
+                };
+                let (chainspec, chainspec_raw_bytes) = load(&chainspec_dir)?;
+                let mut report = chainspec::check_chainspec(&chainspec, &chainspec_raw_bytes);
+                if let Some(previous_dir) = previous {
+                    let (previous_chainspec, _) = load(&previous_dir)?;
+                    report.extend(chainspec::check_upgrade(&previous_chainspec, &chainspec));
+                }
+
+                print!("{}", report);
+                if !report.is_ok() {
+                    anyhow::bail!("chainspec check found {} error(s)", report.errors.len());
+                }
+                println!("chainspec at {} is valid", chainspec_dir.display());
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::LocalNet {
+                validators,
+                base_dir,
+                chainspec_template,
+                config_template,
+                base_port,
+                genesis_delay,
+                control_address,
+            } => {
+                setup_signal_hooks();
+                logging::init_with_config(&Default::default())?;
+
+                local_net::run(local_net::LocalNetOptions {
+                    validators,
+                    base_dir,
+                    chainspec_template,
+                    config_template,
+                    base_port,
+                    genesis_delay,
+                    control_address,
+                })
+                .await?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::Storage {
+                command:
+                    StorageCommand::BackfillExecResults {
+                        config,
+                        config_ext,
+                        from_height,
+                        to_height,
+                        re_execute,
+                    },
+            } => {
+                let config = Self::init(&config, config_ext)?;
+
+                info!(%from_height, ?to_height, %re_execute, "backfilling execution results");
+                let summary = backfill::run(
+                    config,
+                    backfill::BackfillOptions {
+                        from_height,
+                        to_height,
+                        re_execute,
+                    },
+                )?;
+                print!("{}", summary);
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
@@ -269,14 +483,41 @@ impl Cli {
         let mut config_table: Value = toml::from_str(&encoded_config)?;
 
         // If any command line overrides to the config values are passed, apply them.
-        for item in config_ext {
+        for item in &config_ext {
             item.update_toml_table(&mut config_table)?;
         }
 
+        // If a node mode is selected, apply its profile. Command line overrides still take
+        // precedence, so they are applied again afterwards.
+        let mode: Option<NodeMode> = config_table
+            .get("node")
+            .and_then(|node| node.get("mode"))
+            .cloned()
+            .map(Value::try_into)
+            .transpose()
+            .context("invalid node mode")?;
+        let overridden = match mode {
+            Some(mode) => {
+                let overridden = apply_node_mode_profile(mode, &mut config_table)?;
+                for item in &config_ext {
+                    item.update_toml_table(&mut config_table)?;
+                }
+                overridden
+            }
+            None => Vec::new(),
+        };
+
         // Create participating config, including any overridden values.
         let participating_config: participating::Config = config_table.try_into()?;
         logging::init_with_config(&participating_config.logging)?;
 
+        for (section, key, previous, value) in overridden {
+            info!(
+                ?mode, %section, %key, %previous, %value,
+                "node mode overrode configured value"
+            );
+        }
+
         Ok(WithDir::new(root, participating_config))
     }
 }
@@ -0,0 +1,295 @@
+//! Regeneration of deploy execution results missing from storage, run by the
+//! `storage backfill-exec-results` subcommand.
+//!
+//! Databases populated by old versions of the node or by interrupted syncs can contain blocks for
+//! whose deploys no execution results are stored.  Such blocks are found by reading the stored
+//! blocks, and can optionally be executed again on top of the global state of their parent block to
+//! regenerate the missing results.  Regenerated results are only stored if executing the block
+//! reproduces the stored block, i.e. if they are the results the network agreed on.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use anyhow::Context;
+use prometheus::Registry;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{ExecutionResult, ProtocolVersion};
+
+use crate::{
+    components::{
+        contract_runtime::{self, BlockExecutionError, ContractRuntime, ExecutionPreState},
+        storage::Storage,
+    },
+    reactor::participating,
+    types::{Block, BlockHash, Chainspec, ChainspecRawBytes, DeployHash, FinalizedBlock},
+    utils::{Loadable, WithDir},
+};
+
+/// Settings of a run of the backfill.
+#[derive(Debug)]
+pub(super) struct BackfillOptions {
+    /// Lowest height of the blocks to check.
+    pub(super) from_height: u64,
+    /// Highest height of the blocks to check, or `None` to check up to the highest stored block.
+    pub(super) to_height: Option<u64>,
+    /// Whether to re-execute blocks with missing execution results to regenerate them.
+    pub(super) re_execute: bool,
+}
+
+/// Reason for not regenerating the missing execution results of a block.
+#[derive(Debug, Error)]
+enum SkipReason {
+    /// The block is not stored.
+    #[error("block not stored")]
+    MissingBlock,
+    /// The block was created with a different protocol version than the chainspec's.
+    #[error("block has protocol version {block}, but the chainspec is for {chainspec}")]
+    ProtocolVersion {
+        block: ProtocolVersion,
+        chainspec: ProtocolVersion,
+    },
+    /// The parent block is not stored.
+    #[error("parent block not stored")]
+    MissingParent,
+    /// The block is the first one after an upgrade, so it was not executed on top of its parent's
+    /// global state.
+    #[error("first block after an upgrade")]
+    UpgradeBoundary,
+    /// Some deploys of the block are not stored.
+    #[error("deploys not stored")]
+    MissingDeploys,
+    /// The global state of the parent block is missing.
+    #[error("global state root {0} of parent block missing")]
+    MissingGlobalState(Digest),
+    /// Executing the block failed.
+    #[error("execution failed: {0}")]
+    Execution(#[from] BlockExecutionError),
+    /// Executing the block produced a different block.
+    #[error("execution produced different block {0}")]
+    BlockMismatch(BlockHash),
+}
+
+/// Outcome of a run of the backfill.
+#[derive(Debug, Default)]
+pub(super) struct BackfillSummary {
+    /// Number of stored blocks checked for missing execution results.
+    blocks_checked: u64,
+    /// Number of blocks missing execution results of some of their deploys.
+    incomplete_blocks: u64,
+    /// Number of missing execution results found.
+    missing_results: usize,
+    /// Number of execution results regenerated and stored.
+    backfilled_results: usize,
+    /// Heights of the blocks whose missing execution results were not regenerated, and why.
+    skipped: Vec<(u64, String)>,
+}
+
+impl Display for BackfillSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "blocks checked: {}", self.blocks_checked)?;
+        writeln!(
+            f,
+            "blocks missing execution results: {}",
+            self.incomplete_blocks
+        )?;
+        writeln!(f, "missing execution results: {}", self.missing_results)?;
+        writeln!(
+            f,
+            "backfilled execution results: {}",
+            self.backfilled_results
+        )?;
+        for (height, reason) in &self.skipped {
+            writeln!(f, "skipped block at height {}: {}", height, reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Opens the storage of the node with the given config and regenerates missing execution results.
+///
+/// The node must not be running.
+pub(super) fn run(
+    config: WithDir<participating::Config>,
+    options: BackfillOptions,
+) -> anyhow::Result<BackfillSummary> {
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(config.dir())
+        .context("could not load chainspec")?;
+    let storage_config = config.map_ref(|config| config.storage.clone());
+    let mut storage = Storage::new(
+        &storage_config,
+        None,
+        chainspec.protocol_config.version,
+        &chainspec.network_config.name,
+    )?;
+
+    let result = if options.re_execute {
+        new_contract_runtime(&config, &chainspec, &storage).and_then(|contract_runtime| {
+            backfill(&mut storage, Some(&contract_runtime), &chainspec, &options)
+        })
+    } else {
+        backfill(&mut storage, None, &chainspec, &options)
+    };
+
+    // Leave the unclean shutdown marker of a previous run of the node in place, so that the node
+    // still checks the storage integrity on its next startup.
+    if storage.unclean_shutdown_detected() {
+        storage.flush()?;
+    } else {
+        storage.mark_clean_shutdown()?;
+    }
+    result
+}
+
+/// Creates a contract runtime operating on the global state in the storage folder.
+fn new_contract_runtime(
+    config: &WithDir<participating::Config>,
+    chainspec: &Chainspec,
+    storage: &Storage,
+) -> anyhow::Result<ContractRuntime> {
+    let core_config = &chainspec.core_config;
+    let contract_runtime = ContractRuntime::new(
+        chainspec.protocol_config.version,
+        storage.root_path(),
+        &config.value().contract_runtime,
+        chainspec.wasm_config,
+        chainspec.system_costs_config,
+        core_config.max_associated_keys,
+        core_config.max_runtime_call_stack_height,
+        core_config.minimum_delegation_amount,
+        core_config.strict_argument_checking,
+        core_config.vesting_schedule_period.millis(),
+        core_config.allow_authorization_contracts,
+        core_config.authorization_contract_gas_limit,
+        core_config.max_unbondings_per_step,
+        &Registry::new(),
+    )?;
+    Ok(contract_runtime)
+}
+
+/// Checks the stored blocks in the configured height range for missing execution results, and
+/// regenerates them if a contract runtime is given.
+fn backfill(
+    storage: &mut Storage,
+    contract_runtime: Option<&ContractRuntime>,
+    chainspec: &Chainspec,
+    options: &BackfillOptions,
+) -> anyhow::Result<BackfillSummary> {
+    let mut summary = BackfillSummary::default();
+    let highest_height = match storage.read_highest_block()? {
+        Some(block) => block.height(),
+        None => return Ok(summary),
+    };
+    let to_height = options
+        .to_height
+        .map_or(highest_height, |to_height| to_height.min(highest_height));
+
+    for height in options.from_height..=to_height {
+        let block = match storage.read_block_by_height(height)? {
+            Some(block) => block,
+            None => {
+                summary
+                    .skipped
+                    .push((height, SkipReason::MissingBlock.to_string()));
+                continue;
+            }
+        };
+        summary.blocks_checked += 1;
+
+        let missing = storage.read_missing_execution_results(&block)?;
+        if missing.is_empty() {
+            continue;
+        }
+        info!(
+            height,
+            missing = missing.len(),
+            "block is missing execution results"
+        );
+        summary.incomplete_blocks += 1;
+        summary.missing_results += missing.len();
+
+        let contract_runtime = match contract_runtime {
+            Some(contract_runtime) => contract_runtime,
+            None => continue,
+        };
+        match re_execute(storage, contract_runtime, chainspec, &block)? {
+            Ok(execution_results) => {
+                let written =
+                    storage.write_backfilled_execution_results(&block, execution_results)?;
+                info!(height, %written, "backfilled execution results");
+                summary.backfilled_results += written;
+            }
+            Err(reason) => {
+                warn!(height, %reason, "not backfilling execution results");
+                summary.skipped.push((height, reason.to_string()));
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Executes the given block again on top of the global state of its parent, returning the execution
+/// results of all of its deploys.
+///
+/// Returns the reason the block cannot be re-executed, or did not reproduce the stored block, as the
+/// inner error.
+fn re_execute(
+    storage: &Storage,
+    contract_runtime: &ContractRuntime,
+    chainspec: &Chainspec,
+    block: &Block,
+) -> anyhow::Result<Result<HashMap<DeployHash, ExecutionResult>, SkipReason>> {
+    let protocol_version = chainspec.protocol_config.version;
+    if block.protocol_version() != protocol_version {
+        return Ok(Err(SkipReason::ProtocolVersion {
+            block: block.protocol_version(),
+            chainspec: protocol_version,
+        }));
+    }
+    let parent = match block.height().checked_sub(1) {
+        Some(parent_height) => storage.read_block_by_height(parent_height)?,
+        None => None,
+    };
+    let parent = match parent {
+        Some(parent) if parent.hash() == block.header().parent_hash() => parent,
+        _ => return Ok(Err(SkipReason::MissingParent)),
+    };
+    if parent.protocol_version() != protocol_version {
+        return Ok(Err(SkipReason::UpgradeBoundary));
+    }
+    let (deploys, transfers) = match storage.read_block_deploys_with_finalized_approvals(block)? {
+        Some(deploys_and_transfers) => deploys_and_transfers,
+        None => return Ok(Err(SkipReason::MissingDeploys)),
+    };
+    let parent_state_root_hash = *parent.state_root_hash();
+    if !contract_runtime.state_root_exists(parent_state_root_hash)? {
+        return Ok(Err(SkipReason::MissingGlobalState(parent_state_root_hash)));
+    }
+
+    let block_and_execution_effects = match contract_runtime::execute_finalized_block(
+        contract_runtime.engine_state(),
+        None,
+        protocol_version,
+        ExecutionPreState::from_block_header(parent.header()),
+        FinalizedBlock::from(block.clone()),
+        deploys,
+        transfers,
+    ) {
+        Ok(block_and_execution_effects) => block_and_execution_effects,
+        Err(error) => return Ok(Err(error.into())),
+    };
+    if block_and_execution_effects.block.hash() != block.hash() {
+        return Ok(Err(SkipReason::BlockMismatch(
+            *block_and_execution_effects.block.hash(),
+        )));
+    }
+    Ok(Ok(block_and_execution_effects
+        .execution_results
+        .into_iter()
+        .map(|(deploy_hash, _, execution_result)| (deploy_hash, execution_result))
+        .collect()))
+}
@@ -0,0 +1,809 @@
+//! A local test network, launched and supervised by the `local-net` subcommand.
+//!
+//! Generates the keys, chainspec, accounts and configs of a network of validators under a base
+//! directory, runs each validator as a child process of the current executable, and listens for
+//! line-based commands on a control socket to stop, restart or stall nodes and to stage upgrades.
+//!
+//! Each node's directory mirrors the layout expected by the node launcher:
+//!
+//! ```text
+//! node-<ID>/
+//!     config/<VERSION>/{config.toml, chainspec.toml, accounts.toml, secret_key.pem}
+//!     storage/
+//!     logs/{stdout.log, stderr.log}
+//! ```
+//!
+//! A node exiting with `ExitCode::Success` has reached an upgrade point, and is restarted using the
+//! next installed version's config, just like the launcher would do.
+
+use std::{
+    convert::TryFrom,
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    str::FromStr,
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use anyhow::{self, bail, Context};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+};
+use toml::{value::Table, Value};
+use tracing::{debug, info, warn};
+
+use casper_types::{EraId, ProtocolVersion, PublicKey, SecretKey, TimeDiff, Timestamp};
+
+use crate::types::{chainspec::CHAINSPEC_FILENAME, ExitCode};
+
+/// Name of the config file in each version directory.
+const CONFIG_FILENAME: &str = "config.toml";
+/// Name of the accounts file in each version directory.
+const ACCOUNTS_FILENAME: &str = "accounts.toml";
+/// Name of the validator's secret key file in each version directory.
+const SECRET_KEY_FILENAME: &str = "secret_key.pem";
+/// Placeholder for the genesis timestamp in chainspec templates.
+const TIMESTAMP_PLACEHOLDER: &str = "${TIMESTAMP}";
+/// Number of ports reserved for each node, starting at `base_port + PORTS_PER_NODE * ID`.
+const PORTS_PER_NODE: u16 = 10;
+/// Balance of the faucet account.
+const FAUCET_BALANCE: &str = "1000000000000000000000000000";
+/// Balance of each validator's account.
+const VALIDATOR_BALANCE: &str = "1000000000000000000";
+/// Bonded amount of the first validator, decreasing for each following validator.
+const VALIDATOR_BASE_BOND: u64 = 1_000_000_000_000_000;
+/// Interval at which the node processes are checked for exits.
+const SUPERVISION_INTERVAL: Duration = Duration::from_secs(1);
+/// Interval at which a stopping node's process is checked for its exit.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Settings of a local test network.
+#[derive(Debug)]
+pub(super) struct LocalNetOptions {
+    /// Number of validators to generate and launch.
+    pub(super) validators: u16,
+    /// Directory to generate the network in.
+    pub(super) base_dir: PathBuf,
+    /// Chainspec template, with `${TIMESTAMP}` as the genesis timestamp.
+    pub(super) chainspec_template: PathBuf,
+    /// Node config template.
+    pub(super) config_template: PathBuf,
+    /// First port of the range used by the nodes.
+    pub(super) base_port: u16,
+    /// Time from now until genesis.
+    pub(super) genesis_delay: TimeDiff,
+    /// Address of the control API.
+    pub(super) control_address: SocketAddr,
+}
+
+/// The ports a single node listens on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct NodePorts {
+    network: u16,
+    rpc: u16,
+    speculative_exec: u16,
+    rest: u16,
+    event_stream: u16,
+}
+
+impl NodePorts {
+    fn new(base_port: u16, id: u16) -> anyhow::Result<Self> {
+        let first = id
+            .checked_mul(PORTS_PER_NODE)
+            .and_then(|offset| base_port.checked_add(offset))
+            .filter(|first| first.checked_add(PORTS_PER_NODE).is_some())
+            .with_context(|| format!("not enough ports above {} for node {}", base_port, id))?;
+        Ok(NodePorts {
+            network: first,
+            rpc: first + 1,
+            speculative_exec: first + 2,
+            rest: first + 3,
+            event_stream: first + 4,
+        })
+    }
+}
+
+/// A command received via the control API.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum ControlCommand {
+    /// Lists the nodes and their state.
+    Status,
+    /// Starts a stopped node.
+    Start(u16),
+    /// Stops a node gracefully.
+    Stop(u16),
+    /// Kills a node, as if it crashed.
+    Kill(u16),
+    /// Stops a node, then starts it again.
+    Restart(u16),
+    /// Stalls a node's process without stopping it.
+    Pause(u16),
+    /// Resumes a stalled node.
+    Resume(u16),
+    /// Installs the given protocol version on all nodes, activating at the given era.
+    StageUpgrade {
+        version: ProtocolVersion,
+        activation_era: EraId,
+    },
+    /// Stops all nodes and exits.
+    Shutdown,
+}
+
+impl FromStr for ControlCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let node_id = |words: &[&str]| -> anyhow::Result<u16> {
+            match words {
+                [_, id] => id
+                    .parse()
+                    .with_context(|| format!("invalid node ID '{}'", id)),
+                _ => bail!("expected a single node ID"),
+            }
+        };
+        let command = match words.first().copied() {
+            Some("status") => ControlCommand::Status,
+            Some("start") => ControlCommand::Start(node_id(&words)?),
+            Some("stop") => ControlCommand::Stop(node_id(&words)?),
+            Some("kill") => ControlCommand::Kill(node_id(&words)?),
+            Some("restart") => ControlCommand::Restart(node_id(&words)?),
+            Some("pause") => ControlCommand::Pause(node_id(&words)?),
+            Some("resume") => ControlCommand::Resume(node_id(&words)?),
+            Some("stage-upgrade") => match words.as_slice() {
+                [_, version, era] => ControlCommand::StageUpgrade {
+                    version: version
+                        .parse()
+                        .with_context(|| format!("invalid protocol version '{}'", version))?,
+                    activation_era: EraId::new(
+                        era.parse()
+                            .with_context(|| format!("invalid activation era '{}'", era))?,
+                    ),
+                },
+                _ => bail!("usage: stage-upgrade <VERSION> <ACTIVATION ERA>"),
+            },
+            Some("shutdown") => ControlCommand::Shutdown,
+            Some(other) => bail!(
+                "unknown command '{}', expected one of status, start, stop, kill, restart, pause, \
+                resume, stage-upgrade or shutdown",
+                other
+            ),
+            None => bail!("empty command"),
+        };
+        Ok(command)
+    }
+}
+
+/// A node of the local network.
+#[derive(Debug)]
+struct LocalNode {
+    id: u16,
+    dir: PathBuf,
+    version: ProtocolVersion,
+    child: Option<Child>,
+    paused: bool,
+}
+
+impl LocalNode {
+    fn config_dir(&self) -> PathBuf {
+        self.dir.join("config")
+    }
+
+    fn version_dir(&self, version: &ProtocolVersion) -> PathBuf {
+        self.config_dir().join(dir_name_from_version(version))
+    }
+
+    fn pid(&self) -> Option<u32> {
+        self.child.as_ref().map(Child::id)
+    }
+
+    fn state(&self) -> &'static str {
+        match (&self.child, self.paused) {
+            (None, _) => "stopped",
+            (Some(_), true) => "paused",
+            (Some(_), false) => "running",
+        }
+    }
+
+    /// Launches the node with the config of its current version.
+    fn start(&mut self, executable: &Path) -> anyhow::Result<()> {
+        if self.child.is_some() {
+            bail!("node {} is already running", self.id);
+        }
+        let logs_dir = self.dir.join("logs");
+        fs::create_dir_all(&logs_dir)?;
+        let open_log = |name: &str| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(logs_dir.join(name))
+        };
+        let config_path = self.version_dir(&self.version).join(CONFIG_FILENAME);
+        let child = Command::new(executable)
+            .arg("validator")
+            .arg(&config_path)
+            .stdin(Stdio::null())
+            .stdout(open_log("stdout.log")?)
+            .stderr(open_log("stderr.log")?)
+            .spawn()
+            .with_context(|| format!("could not launch node {}", self.id))?;
+        info!(id = self.id, pid = child.id(), version = %self.version, "started node");
+        self.child = Some(child);
+        self.paused = false;
+        Ok(())
+    }
+
+    /// Sends the given signal to the node's process.
+    fn signal(&self, signal: libc::c_int) -> anyhow::Result<()> {
+        let pid = self
+            .pid()
+            .with_context(|| format!("node {} is not running", self.id))?;
+        // Safe as `kill` has no memory safety preconditions.
+        if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+            bail!(
+                "could not signal node {}: {}",
+                self.id,
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(())
+    }
+
+    /// Stops the node, gracefully unless `kill` is set.
+    async fn stop(&mut self, kill: bool) -> anyhow::Result<()> {
+        if self.paused {
+            self.signal(libc::SIGCONT)?;
+        }
+        if kill {
+            self.signal(libc::SIGKILL)?;
+        } else {
+            self.signal(libc::SIGTERM)?;
+        }
+        let status = self.wait_for_exit().await?;
+        info!(id = self.id, %status, "stopped node");
+        self.child = None;
+        self.paused = false;
+        Ok(())
+    }
+
+    /// Waits for the node's process to exit, without blocking the runtime.
+    async fn wait_for_exit(&mut self) -> anyhow::Result<ExitStatus> {
+        let child = self
+            .child
+            .as_mut()
+            .with_context(|| format!("node {} is not running", self.id))?;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            tokio::time::sleep(STOP_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for LocalNode {
+    fn drop(&mut self) {
+        // Don't leave orphaned nodes behind if the local network exits early.
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// A local network of nodes running as child processes.
+#[derive(Debug)]
+struct LocalNet {
+    executable: PathBuf,
+    nodes: Vec<LocalNode>,
+}
+
+impl LocalNet {
+    fn node_mut(&mut self, id: u16) -> anyhow::Result<&mut LocalNode> {
+        self.nodes
+            .iter_mut()
+            .find(|node| node.id == id)
+            .with_context(|| format!("no node with ID {}", id))
+    }
+
+    /// Restarts nodes which exited at an upgrade point with their next installed version, and
+    /// records nodes which exited for any other reason as stopped.
+    fn supervise(&mut self) {
+        let executable = self.executable.clone();
+        for node in &mut self.nodes {
+            let status = match node.child.as_mut().map(Child::try_wait) {
+                Some(Ok(Some(status))) => status,
+                Some(Ok(None)) | None => continue,
+                Some(Err(error)) => {
+                    warn!(id = node.id, %error, "could not check node process");
+                    continue;
+                }
+            };
+            node.child = None;
+            node.paused = false;
+            if status.code() != Some(ExitCode::Success as i32) {
+                warn!(id = node.id, %status, "node exited");
+                continue;
+            }
+            match next_installed_version(&node.config_dir(), &node.version) {
+                Some(next_version) => {
+                    info!(id = node.id, from = %node.version, to = %next_version, "upgrading node");
+                    node.version = next_version;
+                    if let Err(error) = node.start(&executable) {
+                        warn!(id = node.id, %error, "could not restart upgraded node");
+                    }
+                }
+                None => warn!(id = node.id, "node exited without an upgrade installed"),
+            }
+        }
+    }
+
+    /// Executes a control command, returning the response to send to the client.
+    async fn execute(&mut self, command: ControlCommand) -> anyhow::Result<String> {
+        let executable = self.executable.clone();
+        match command {
+            ControlCommand::Status => {
+                let mut status = String::new();
+                for node in &self.nodes {
+                    let _ = write!(
+                        status,
+                        "node-{} {} version {}",
+                        node.id,
+                        node.state(),
+                        node.version
+                    );
+                    if let Some(pid) = node.pid() {
+                        let _ = write!(status, " pid {}", pid);
+                    }
+                    status.push('\n');
+                }
+                return Ok(status);
+            }
+            ControlCommand::Start(id) => self.node_mut(id)?.start(&executable)?,
+            ControlCommand::Stop(id) => self.node_mut(id)?.stop(false).await?,
+            ControlCommand::Kill(id) => self.node_mut(id)?.stop(true).await?,
+            ControlCommand::Restart(id) => {
+                let node = self.node_mut(id)?;
+                node.stop(false).await?;
+                node.start(&executable)?;
+            }
+            ControlCommand::Pause(id) => {
+                let node = self.node_mut(id)?;
+                node.signal(libc::SIGSTOP)?;
+                node.paused = true;
+            }
+            ControlCommand::Resume(id) => {
+                let node = self.node_mut(id)?;
+                node.signal(libc::SIGCONT)?;
+                node.paused = false;
+            }
+            ControlCommand::StageUpgrade {
+                version,
+                activation_era,
+            } => {
+                for node in &self.nodes {
+                    stage_upgrade(node, version, activation_era)?;
+                }
+                info!(%version, %activation_era, "staged upgrade");
+            }
+            ControlCommand::Shutdown => self.shutdown().await,
+        }
+        Ok("ok\n".to_string())
+    }
+
+    /// Stops all running nodes.
+    async fn shutdown(&mut self) {
+        for node in &mut self.nodes {
+            if node.child.is_some() {
+                if let Err(error) = node.stop(false).await {
+                    warn!(id = node.id, %error, "could not stop node");
+                }
+            }
+        }
+    }
+}
+
+/// Generates a local network as described by `options`, then runs it until shut down via the
+/// control API or interrupted.
+pub(super) async fn run(options: LocalNetOptions) -> anyhow::Result<()> {
+    if options.validators == 0 {
+        bail!("a local network needs at least one validator");
+    }
+    if options.base_dir.exists() {
+        bail!(
+            "{} already exists, remove it or choose another base directory",
+            options.base_dir.display()
+        );
+    }
+
+    let mut local_net = generate(&options)?;
+    for node in &mut local_net.nodes {
+        node.start(&local_net.executable)?;
+    }
+
+    let listener = TcpListener::bind(options.control_address)
+        .await
+        .with_context(|| format!("could not bind control API to {}", options.control_address))?;
+    info!(address = %options.control_address, "control API listening");
+    let (command_sender, mut command_receiver) = mpsc::channel(16);
+    tokio::spawn(accept_control_connections(listener, command_sender));
+
+    let mut supervision = tokio::time::interval(SUPERVISION_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = supervision.tick() => {
+                if crate::TERMINATION_REQUESTED.load(Ordering::SeqCst) != 0 {
+                    info!("termination requested, stopping local network");
+                    local_net.shutdown().await;
+                    return Ok(());
+                }
+                local_net.supervise();
+            }
+            Some((command, responder)) = command_receiver.recv() => {
+                let is_shutdown = command == ControlCommand::Shutdown;
+                let response = match local_net.execute(command).await {
+                    Ok(response) => response,
+                    Err(error) => format!("error: {:#}\n", error),
+                };
+                let _ = responder.send(response);
+                if is_shutdown {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Accepts connections to the control API, forwarding their commands to the supervisor.
+async fn accept_control_connections(
+    listener: TcpListener,
+    command_sender: mpsc::Sender<(ControlCommand, oneshot::Sender<String>)>,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                debug!(%peer, "control API connection");
+                tokio::spawn(handle_control_connection(stream, command_sender.clone()));
+            }
+            Err(error) => warn!(%error, "could not accept control API connection"),
+        }
+    }
+}
+
+/// Handles a connection to the control API, one command per line.
+async fn handle_control_connection(
+    stream: TcpStream,
+    command_sender: mpsc::Sender<(ControlCommand, oneshot::Sender<String>)>,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match line.parse::<ControlCommand>() {
+            Ok(command) => {
+                let (responder, response) = oneshot::channel();
+                command_sender
+                    .send((command, responder))
+                    .await
+                    .context("local network has shut down")?;
+                response.await?
+            }
+            Err(error) => format!("error: {:#}\n", error),
+        };
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Generates the keys, chainspec, accounts and configs of the local network.
+fn generate(options: &LocalNetOptions) -> anyhow::Result<LocalNet> {
+    let read_template = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))
+    };
+    let genesis_timestamp = Timestamp::now().saturating_add(options.genesis_delay);
+    let chainspec = chainspec_from_template(
+        &read_template(&options.chainspec_template)?,
+        genesis_timestamp,
+        options.validators,
+    )?;
+    let version: ProtocolVersion = chainspec
+        .get("protocol")
+        .and_then(|protocol| protocol.get("version"))
+        .and_then(Value::as_str)
+        .context("chainspec template has no protocol version")?
+        .parse()
+        .context("invalid protocol version in chainspec template")?;
+    let config_template: Value = toml::from_str(&read_template(&options.config_template)?)
+        .context("invalid config template")?;
+
+    fs::create_dir_all(&options.base_dir)?;
+    let faucet_key = SecretKey::generate_ed25519()?;
+    let faucet_dir = options.base_dir.join("faucet");
+    fs::create_dir_all(&faucet_dir)?;
+    faucet_key.to_file(faucet_dir.join(SECRET_KEY_FILENAME))?;
+
+    let validator_keys = (0..options.validators)
+        .map(|_| SecretKey::generate_ed25519())
+        .collect::<Result<Vec<_>, _>>()?;
+    let accounts = accounts_toml(
+        &PublicKey::from(&faucet_key),
+        validator_keys.iter().map(PublicKey::from),
+    );
+    let bootstrap_ports = NodePorts::new(options.base_port, 1)?;
+
+    let mut nodes = Vec::with_capacity(validator_keys.len());
+    for (id, secret_key) in (1..).zip(validator_keys) {
+        let node = LocalNode {
+            id,
+            dir: options.base_dir.join(format!("node-{}", id)),
+            version,
+            child: None,
+            paused: false,
+        };
+        let version_dir = node.version_dir(&version);
+        fs::create_dir_all(&version_dir)?;
+        fs::create_dir_all(node.dir.join("storage"))?;
+        secret_key.to_file(version_dir.join(SECRET_KEY_FILENAME))?;
+        write_toml(&version_dir.join(CHAINSPEC_FILENAME), &chainspec)?;
+        fs::write(version_dir.join(ACCOUNTS_FILENAME), &accounts)?;
+        let config = node_config(
+            &config_template,
+            NodePorts::new(options.base_port, id)?,
+            bootstrap_ports.network,
+        )?;
+        write_toml(&version_dir.join(CONFIG_FILENAME), &config)?;
+        nodes.push(node);
+    }
+    info!(
+        base_dir = %options.base_dir.display(),
+        validators = options.validators,
+        %genesis_timestamp,
+        "generated local network"
+    );
+
+    Ok(LocalNet {
+        executable: std::env::current_exe().context("could not determine node executable")?,
+        nodes,
+    })
+}
+
+/// Fills in the chainspec template, making room for all validators in the auction.
+fn chainspec_from_template(
+    template: &str,
+    genesis_timestamp: Timestamp,
+    validators: u16,
+) -> anyhow::Result<Value> {
+    let mut chainspec: Value =
+        toml::from_str(&template.replace(TIMESTAMP_PLACEHOLDER, &genesis_timestamp.to_string()))
+            .context("invalid chainspec template")?;
+    let core = table_mut(&mut chainspec, "core")?;
+    let validator_slots = core
+        .get("validator_slots")
+        .and_then(Value::as_integer)
+        .unwrap_or_default();
+    if validator_slots < i64::from(validators) {
+        let _ = core.insert(
+            "validator_slots".to_string(),
+            Value::Integer(validators.into()),
+        );
+    }
+    Ok(chainspec)
+}
+
+/// Returns the genesis accounts: a faucet, and a bonded account per validator.
+fn accounts_toml<I>(faucet: &PublicKey, validators: I) -> String
+where
+    I: IntoIterator<Item = PublicKey>,
+{
+    let mut accounts = format!(
+        "[[accounts]]\npublic_key = \"{}\"\nbalance = \"{}\"\n",
+        faucet.to_hex(),
+        FAUCET_BALANCE
+    );
+    for (index, validator) in validators.into_iter().enumerate() {
+        let bonded_amount = VALIDATOR_BASE_BOND - index as u64 * (VALIDATOR_BASE_BOND / 100);
+        let _ = write!(
+            accounts,
+            "\n[[accounts]]\npublic_key = \"{}\"\nbalance = \"{}\"\n\n[accounts.validator]\n\
+            bonded_amount = \"{}\"\n",
+            validator.to_hex(),
+            VALIDATOR_BALANCE,
+            bonded_amount
+        );
+    }
+    accounts
+}
+
+/// Returns the config template adjusted to the given node's ports, connecting to the bootstrap
+/// node and storing data outside of the versioned config directories.
+fn node_config(template: &Value, ports: NodePorts, bootstrap_port: u16) -> anyhow::Result<Value> {
+    let mut config = template.clone();
+    let mut set = |section: &str, key: &str, value: Value| -> anyhow::Result<()> {
+        let _ = table_mut(&mut config, section)?.insert(key.to_string(), value);
+        Ok(())
+    };
+    let address = |port: u16| Value::String(format!("127.0.0.1:{}", port));
+    set("network", "bind_address", address(ports.network))?;
+    set("network", "public_address", address(ports.network))?;
+    set(
+        "network",
+        "known_addresses",
+        Value::Array(vec![address(bootstrap_port)]),
+    )?;
+    set("rpc_server", "address", address(ports.rpc))?;
+    set(
+        "speculative_exec_server",
+        "address",
+        address(ports.speculative_exec),
+    )?;
+    set("rest_server", "address", address(ports.rest))?;
+    set(
+        "event_stream_server",
+        "address",
+        address(ports.event_stream),
+    )?;
+    set(
+        "consensus",
+        "secret_key_path",
+        Value::String(SECRET_KEY_FILENAME.to_string()),
+    )?;
+    set(
+        "storage",
+        "path",
+        Value::String("../../storage".to_string()),
+    )?;
+    Ok(config)
+}
+
+/// Installs `version` in the node's config directory, copying the files of its current version
+/// and setting the chainspec's protocol version and activation point.
+fn stage_upgrade(
+    node: &LocalNode,
+    version: ProtocolVersion,
+    activation_era: EraId,
+) -> anyhow::Result<()> {
+    if version <= node.version {
+        bail!(
+            "node {} already runs version {}, cannot upgrade to {}",
+            node.id,
+            node.version,
+            version
+        );
+    }
+    let current_dir = node.version_dir(&node.version);
+    let upgrade_dir = node.version_dir(&version);
+    fs::create_dir_all(&upgrade_dir)?;
+    for filename in [CONFIG_FILENAME, ACCOUNTS_FILENAME, SECRET_KEY_FILENAME] {
+        let _ = fs::copy(current_dir.join(filename), upgrade_dir.join(filename))
+            .with_context(|| format!("could not copy {} for node {}", filename, node.id))?;
+    }
+
+    let chainspec_path = current_dir.join(CHAINSPEC_FILENAME);
+    let mut chainspec: Value = toml::from_str(&fs::read_to_string(&chainspec_path)?)
+        .with_context(|| format!("invalid chainspec at {}", chainspec_path.display()))?;
+    let protocol = table_mut(&mut chainspec, "protocol")?;
+    let _ = protocol.insert("version".to_string(), Value::String(version.to_string()));
+    let _ = protocol.insert(
+        "activation_point".to_string(),
+        Value::Integer(i64::try_from(activation_era.value())?),
+    );
+    write_toml(&upgrade_dir.join(CHAINSPEC_FILENAME), &chainspec)
+}
+
+/// Returns the lowest version installed in `config_dir` above `current_version`, if any.
+fn next_installed_version(
+    config_dir: &Path,
+    current_version: &ProtocolVersion,
+) -> Option<ProtocolVersion> {
+    fs::read_dir(config_dir)
+        .ok()?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name();
+            ProtocolVersion::from_str(&name.to_string_lossy().replace('_', ".")).ok()
+        })
+        .filter(|version| version > current_version)
+        .min()
+}
+
+fn dir_name_from_version(version: &ProtocolVersion) -> String {
+    version.to_string().replace('.', "_")
+}
+
+/// Returns the given section of a TOML document, creating it if necessary.
+fn table_mut<'a>(document: &'a mut Value, section: &str) -> anyhow::Result<&'a mut Table> {
+    document
+        .as_table_mut()
+        .context("TOML document is not a table")?
+        .entry(section)
+        .or_insert_with(|| Value::Table(Table::new()))
+        .as_table_mut()
+        .with_context(|| format!("section {} is not a table", section))
+}
+
+fn write_toml(path: &Path, value: &Value) -> anyhow::Result<()> {
+    let encoded = toml::to_string_pretty(value)?;
+    fs::write(path, encoded).with_context(|| format!("could not write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_control_commands() {
+        assert_eq!(
+            "status".parse::<ControlCommand>().unwrap(),
+            ControlCommand::Status
+        );
+        assert_eq!(
+            " kill  3 ".parse::<ControlCommand>().unwrap(),
+            ControlCommand::Kill(3)
+        );
+        assert_eq!(
+            "stage-upgrade 1.1.0 5".parse::<ControlCommand>().unwrap(),
+            ControlCommand::StageUpgrade {
+                version: ProtocolVersion::from_parts(1, 1, 0),
+                activation_era: EraId::new(5),
+            }
+        );
+        assert!("stop".parse::<ControlCommand>().is_err());
+        assert!("stop one".parse::<ControlCommand>().is_err());
+        assert!("stage-upgrade 1.1.0".parse::<ControlCommand>().is_err());
+        assert!("partition 1 2".parse::<ControlCommand>().is_err());
+    }
+
+    #[test]
+    fn should_assign_distinct_ports() {
+        let first = NodePorts::new(11_000, 1).unwrap();
+        let second = NodePorts::new(11_000, 2).unwrap();
+        assert_eq!(first.network, 11_010);
+        assert_eq!(second.network, 11_020);
+        assert!(first.event_stream < second.network);
+        assert!(NodePorts::new(65_000, 100).is_err());
+    }
+
+    #[test]
+    fn should_adjust_config_template() {
+        let template: Value = toml::from_str(
+            "[network]\nbind_address = '0.0.0.0:34553'\n[storage]\npath = '../node-storage'\n",
+        )
+        .unwrap();
+        let ports = NodePorts::new(11_000, 2).unwrap();
+        let config = node_config(&template, ports, 11_010).unwrap();
+        assert_eq!(
+            config["network"]["bind_address"].as_str(),
+            Some("127.0.0.1:11020")
+        );
+        assert_eq!(
+            config["network"]["known_addresses"][0].as_str(),
+            Some("127.0.0.1:11010")
+        );
+        assert_eq!(
+            config["rest_server"]["address"].as_str(),
+            Some("127.0.0.1:11023")
+        );
+        assert_eq!(config["storage"]["path"].as_str(), Some("../../storage"));
+    }
+
+    #[test]
+    fn should_find_next_installed_version() {
+        let tempdir = tempfile::tempdir().unwrap();
+        for name in ["1_0_0", "1_2_0", "1_1_0", "not_a_version"] {
+            fs::create_dir(tempdir.path().join(name)).unwrap();
+        }
+        let current = ProtocolVersion::from_parts(1, 0, 0);
+        assert_eq!(
+            next_installed_version(tempdir.path(), &current),
+            Some(ProtocolVersion::from_parts(1, 1, 0))
+        );
+        let latest = ProtocolVersion::from_parts(1, 2, 0);
+        assert_eq!(next_installed_version(tempdir.path(), &latest), None);
+    }
+}
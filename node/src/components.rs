@@ -55,6 +55,7 @@ pub(crate) mod diagnostics_port;
 pub(crate) mod event_stream_server;
 pub(crate) mod fetcher;
 pub(crate) mod gossiper;
+pub(crate) mod indexer;
 pub(crate) mod linear_chain;
 pub(crate) mod rest_server;
 pub mod rpc_server;
@@ -64,6 +65,7 @@ pub mod in_memory_network;
 pub(crate) mod metrics;
 pub(crate) mod small_network;
 pub mod storage;
+pub(crate) mod telemetry;
 // TODO: this import is only required due to the usage of the `reactor!` macro in the fetcher tests;
 //       remove once the macro is deleted.
 #[cfg(test)]
@@ -71,6 +73,7 @@ pub(crate) use crate::testing::fake_deploy_acceptor;
 
 use crate::{
     effect::{EffectBuilder, Effects},
+    types::Health,
     NodeRng,
 };
 
@@ -116,3 +119,17 @@ pub(crate) trait Component<REv> {
         event: Self::Event,
     ) -> Effects<Self::Event>;
 }
+
+/// A component able to report its own health.
+///
+/// The reactor aggregates the health of its major components into the node's health, which is
+/// reported on the `/status` endpoint and as metrics.
+pub(crate) trait HealthCheck {
+    /// Name of the component in health reports.
+    const NAME: &'static str;
+
+    /// Returns the current health of the component.
+    ///
+    /// Like [`Component::handle_event`], this must return very quickly.
+    fn health(&self) -> Health;
+}
@@ -8,18 +8,21 @@ mod config;
 mod deploy_sets;
 mod event;
 mod metrics;
+mod packing;
 #[cfg(test)]
 mod tests;
 
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
     convert::Infallible,
+    mem,
     sync::Arc,
     time::Duration,
 };
 
 use datasize::DataSize;
 use futures::join;
+use itertools::Itertools;
 use prometheus::{self, Registry};
 use tracing::{debug, error, info, warn};
 
@@ -41,12 +44,13 @@ use crate::{
         Approval, BlockPayload, Chainspec, DeployHash, DeployHeader, DeployOrTransferHash,
         DeployWithApprovals, FinalizedBlock,
     },
+    utils::clock,
     NodeRng,
 };
 use cached_state::CachedState;
 pub use config::Config;
 use deploy_sets::{BlockProposerDeploySets, PendingDeployInfo, PruneResult};
-pub(crate) use event::{DeployInfo, Event};
+pub(crate) use event::{DeployInfo, Event, PendingDeployStatus};
 use metrics::Metrics;
 
 /// Block proposer component.
@@ -77,8 +81,18 @@ type FinalizationQueue = HashMap<BlockHeight, FinalizedBlock>;
 
 /// A queue of requests we can't respond to yet, because we aren't up to date on finalized blocks.
 /// The key is the height of the next block we will expect to be finalized at the point when we can
-/// fulfill the corresponding requests.
-type RequestQueue = HashMap<BlockHeight, Vec<BlockPayloadRequest>>;
+/// fulfill the corresponding requests. Each request is stored together with the time it was queued.
+type RequestQueue = HashMap<BlockHeight, Vec<(Timestamp, BlockPayloadRequest)>>;
+
+/// Numbers of eligible deploys chosen for and rejected from proposed blocks, not yet recorded in
+/// the metrics.
+#[derive(Clone, Copy, DataSize, Debug, Default)]
+struct PackingCounts {
+    /// Number of deploys included in a proposed block.
+    chosen: u64,
+    /// Number of eligible deploys left out of a proposed block.
+    rejected: u64,
+}
 
 /// Current operational state of a block proposer.
 #[derive(DataSize, Debug)]
@@ -186,6 +200,7 @@ where
                     deploy_config: *deploy_config,
                     request_queue: Default::default(),
                     local_config: local_config.clone(),
+                    packing_counts: Default::default(),
                 };
 
                 // Announce pruned hashes.
@@ -228,14 +243,20 @@ where
                 pending.push(event);
             }
 
+            (BlockProposerState::Ready(ref mut ready_state), Event::ProposalDeadline) => {
+                let (proposal_effects, expired_count) =
+                    ready_state.propose_expired_requests(clock::now());
+                effects.extend(proposal_effects);
+                self.metrics
+                    .deadline_empty_payloads
+                    .inc_by(expired_count as u64);
+                ready_state.update_metrics(&self.metrics);
+            }
             (BlockProposerState::Ready(ref mut ready_state), event) => {
                 effects.extend(ready_state.handle_event(effect_builder, event));
 
                 // Update metrics after the effects have been applied.
-                self.metrics.pending_deploys.set(
-                    ready_state.sets.pending_deploys.len() as i64
-                        + ready_state.sets.pending_transfers.len() as i64,
-                );
+                ready_state.update_metrics(&self.metrics);
             }
         };
 
@@ -255,6 +276,8 @@ struct BlockProposerReady {
     request_queue: RequestQueue,
     /// The block proposer configuration, containing local settings for selecting deploys.
     local_config: Config,
+    /// Deploys chosen for and rejected from proposed blocks since the metrics were last updated.
+    packing_counts: PackingCounts,
 }
 
 impl BlockProposerReady {
@@ -276,8 +299,11 @@ impl BlockProposerReady {
                     self.request_queue
                         .entry(request.next_finalized)
                         .or_default()
-                        .push(request);
-                    Effects::new()
+                        .push((clock::now(), request));
+                    // If we don't catch up in time, we propose an empty block instead.
+                    effect_builder
+                        .set_timeout(self.local_config.proposal_deadline.into())
+                        .event(|_| Event::ProposalDeadline)
                 } else {
                     info!(%request.next_finalized, "proposing a block payload");
                     request
@@ -291,12 +317,16 @@ impl BlockProposerReady {
                         .ignore()
                 }
             }
+            Event::ProposalDeadline => self.propose_expired_requests(clock::now()).0,
+            Event::Request(BlockProposerRequest::GetPendingDeploys(responder)) => {
+                responder.respond(self.pending_deploys()).ignore()
+            }
             Event::BufferDeploy {
                 hash,
                 approvals,
                 deploy_info,
             } => {
-                self.add_deploy(Timestamp::now(), hash, approvals, *deploy_info);
+                self.add_deploy(clock::now(), hash, approvals, *deploy_info);
                 Effects::new()
             }
             Event::Prune => {
@@ -306,7 +336,7 @@ impl BlockProposerReady {
                     .event(|_| Event::Prune);
 
                 // Announce pruned hashes.
-                let pruned_hashes = self.prune(Timestamp::now());
+                let pruned_hashes = self.prune(clock::now());
                 let pruned_count = pruned_hashes.total_pruned;
                 debug!(%pruned_count, "pruned deploys from buffer");
                 effects.extend(
@@ -425,7 +455,7 @@ impl BlockProposerReady {
             info!(height = %self.sets.next_finalized, "handling queued requests");
             requests
                 .into_iter()
-                .flat_map(|request| {
+                .flat_map(|(_, request)| {
                     request
                         .responder
                         .respond(self.propose_block_payload(
@@ -442,6 +472,87 @@ impl BlockProposerReady {
         }
     }
 
+    /// Responds with an empty block payload to every queued request that has waited at least
+    /// `proposal_deadline` for the finalized blocks it depends on.
+    ///
+    /// Without knowing all finalized deploys we can't safely include any, but proposing an empty
+    /// block is still better than missing the round. Returns the number of expired requests.
+    fn propose_expired_requests(&mut self, current_instant: Timestamp) -> (Effects<Event>, usize) {
+        let proposal_deadline = self.local_config.proposal_deadline;
+        let mut expired = Vec::new();
+        for queued in self.request_queue.values_mut() {
+            let (past_deadline, waiting): (Vec<_>, Vec<_>) =
+                queued.drain(..).partition(|(queued_at, _)| {
+                    current_instant.saturating_diff(*queued_at) >= proposal_deadline
+                });
+            *queued = waiting;
+            expired.extend(past_deadline);
+        }
+        self.request_queue.retain(|_, queued| !queued.is_empty());
+
+        let expired_count = expired.len();
+        let effects = expired
+            .into_iter()
+            .flat_map(|(_, request)| {
+                warn!(
+                    %request.next_finalized, next_finalized = %self.sets.next_finalized,
+                    "proposal deadline passed while waiting for finalized blocks; \
+                     proposing an empty block payload"
+                );
+                request
+                    .responder
+                    .respond(Arc::new(BlockPayload::new(
+                        vec![],
+                        vec![],
+                        request.accusations,
+                        request.random_bit,
+                    )))
+                    .ignore()
+            })
+            .collect();
+        (effects, expired_count)
+    }
+
+    /// Updates the gauges reflecting the state of the buffer and the request queue, and records
+    /// the deploys chosen for and rejected from blocks proposed since the last update.
+    fn update_metrics(&mut self, metrics: &Metrics) {
+        metrics
+            .pending_deploys
+            .set(self.sets.pending_deploys.len() as i64 + self.sets.pending_transfers.len() as i64);
+        metrics
+            .queued_payload_requests
+            .set(self.request_queue.values().map(Vec::len).sum::<usize>() as i64);
+        let packing_counts = mem::take(&mut self.packing_counts);
+        metrics.packed_deploys.inc_by(packing_counts.chosen);
+        metrics.rejected_deploys.inc_by(packing_counts.rejected);
+    }
+
+    /// Returns the status of all buffered deploys and transfers, oldest first.
+    fn pending_deploys(&self) -> Vec<PendingDeployStatus> {
+        let no_past_deploys = HashSet::new();
+        let deploys = self.sets.pending_deploys.iter().map(|entry| (false, entry));
+        let transfers = self
+            .sets
+            .pending_transfers
+            .iter()
+            .map(|entry| (true, entry));
+        deploys
+            .chain(transfers)
+            .map(|(is_transfer, (deploy_hash, pending))| {
+                let header = &pending.info.header;
+                PendingDeployStatus {
+                    deploy_hash: *deploy_hash,
+                    is_transfer,
+                    account: header.account().clone(),
+                    received: pending.timestamp,
+                    expires: header.expires(),
+                    dependencies_resolved: self.deps_resolved(header, &no_past_deploys),
+                }
+            })
+            .sorted_by_key(|status| (status.received, status.deploy_hash))
+            .collect()
+    }
+
     /// Checks if a deploy's dependencies are satisfied, so the deploy is eligible for inclusion.
     fn deps_resolved(&self, header: &DeployHeader, past_deploys: &HashSet<DeployHash>) -> bool {
         header
@@ -500,22 +611,38 @@ impl BlockProposerReady {
             }
         }
 
-        // Now we try to add other deploys to the block.
-        for (hash, pending_deploy_info) in &self.sets.pending_deploys {
-            if !self.deps_resolved(&pending_deploy_info.info.header, &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(pending_deploy_info.timestamp)
-                    < self.local_config.deploy_delay
-            {
+        // Now we try to add other deploys to the block, most profitable first.
+        let candidates = self
+            .sets
+            .pending_deploys
+            .iter()
+            .filter(|(hash, pending_deploy_info)| {
+                self.deps_resolved(&pending_deploy_info.info.header, &past_deploys)
+                    && !past_deploys.contains(hash)
+                    && !self.contains_finalized(hash)
+                    && block_timestamp.saturating_diff(pending_deploy_info.timestamp)
+                        >= self.local_config.deploy_delay
+            });
+        let candidates = packing::packing_order(candidates, &deploy_config);
+        let max_deploys_per_account = self.local_config.max_deploys_per_account;
+        let mut deploys_per_account: HashMap<&PublicKey, u32> = HashMap::new();
+        let mut chosen = 0;
+        for (hash, pending_deploy_info) in candidates.iter().copied() {
+            let account = pending_deploy_info.info.header.account();
+            let account_deploys = deploys_per_account.entry(account).or_default();
+            if max_deploys_per_account != 0 && *account_deploys >= max_deploys_per_account {
                 continue;
             }
 
-            if let Err(err) = appendable_block.add_deploy(
+            match appendable_block.add_deploy(
                 DeployWithApprovals::new(*hash, pending_deploy_info.approvals.clone()),
                 &pending_deploy_info.info,
             ) {
-                match err {
+                Ok(()) => {
+                    *account_deploys += 1;
+                    chosen += 1;
+                }
+                Err(err) => match err {
                     // We added the maximum number of deploys.
                     AddError::DeployCount => break,
                     AddError::BlockSize => {
@@ -529,8 +656,8 @@ impl BlockProposerReady {
                     // approvals might not.
                     AddError::ApprovalCount if pending_deploy_info.approvals.len() > 1 => (),
                     AddError::ApprovalCount => break,
-                    // The deploy is not valid in this block, but might be valid in another.
-                    // TODO: Do something similar to DEPLOY_APPROX_MIN_SIZE for gas.
+                    // The deploy is not valid in this block, but might be valid in another. A
+                    // deploy using less gas might still fit.
                     AddError::InvalidDeploy | AddError::GasLimit => (),
                     // These errors should never happen when adding a deploy.
                     AddError::TransferCount | AddError::Duplicate => {
@@ -539,9 +666,11 @@ impl BlockProposerReady {
                     AddError::InvalidGasAmount => {
                         error!("payment_amount couldn't be converted from motes to gas")
                     }
-                }
+                },
             }
         }
+        self.packing_counts.chosen += chosen;
+        self.packing_counts.rejected += candidates.len() as u64 - chosen;
 
         Arc::new(appendable_block.into_block_payload(accusations, random_bit))
     }
@@ -13,12 +13,22 @@ pub struct Config {
     /// other nodes, and don't have to be requested from the proposer afterwards.
     #[serde(default = "default_deploy_delay")]
     pub deploy_delay: TimeDiff,
+    /// If a block payload request can't be answered within this time because the proposer is
+    /// still waiting for finalized blocks, an empty payload is proposed instead, so that the round
+    /// is not missed.
+    #[serde(default = "default_proposal_deadline")]
+    pub proposal_deadline: TimeDiff,
+    /// Maximum number of deploys from a single account proposed in a new block. Unlimited if 0.
+    #[serde(default)]
+    pub max_deploys_per_account: u32,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             deploy_delay: default_deploy_delay(),
+            proposal_deadline: default_proposal_deadline(),
+            max_deploys_per_account: 0,
         }
     }
 }
@@ -26,3 +36,7 @@ impl Default for Config {
 fn default_deploy_delay() -> TimeDiff {
     "1min".parse().unwrap()
 }
+
+fn default_proposal_deadline() -> TimeDiff {
+    "10sec".parse().unwrap()
+}
@@ -11,7 +11,10 @@ use serde::{Deserialize, Serialize};
 use casper_types::{TimeDiff, Timestamp};
 
 use super::{BlockHeight, CachedState, DeployInfo, FinalizationQueue};
-use crate::types::{Approval, Block, DeployHash};
+use crate::{
+    types::{Approval, Block, DeployHash},
+    utils::clock,
+};
 
 pub(crate) struct PruneResult {
     pub(crate) total_pruned: usize,
@@ -96,7 +99,7 @@ impl BlockProposerDeploySets {
             next_finalized: next_finalized_height,
             ..Default::default()
         };
-        let prune_result = sets.prune(Timestamp::now());
+        let prune_result = sets.prune(clock::now());
         (sets, prune_result)
     }
 
@@ -8,12 +8,12 @@ use derive_more::From;
 use fmt::Display;
 use serde::{Deserialize, Serialize};
 
-use casper_types::Motes;
+use casper_types::{Motes, PublicKey, Timestamp};
 
 use super::{BlockHeight, CachedState};
 use crate::{
     effect::requests::BlockProposerRequest,
-    types::{Approval, Block, DeployHeader, DeployOrTransferHash, FinalizedBlock},
+    types::{Approval, Block, DeployHash, DeployHeader, DeployOrTransferHash, FinalizedBlock},
 };
 
 /// Information about a deploy.
@@ -24,6 +24,23 @@ pub struct DeployInfo {
     pub size: usize,
 }
 
+/// The status of a deploy or transfer pending in the block proposer's buffer.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq)]
+pub(crate) struct PendingDeployStatus {
+    /// The hash of the deploy.
+    pub(crate) deploy_hash: DeployHash,
+    /// Whether the deploy is a native transfer.
+    pub(crate) is_transfer: bool,
+    /// The account which created the deploy.
+    pub(crate) account: PublicKey,
+    /// When the block proposer received the deploy.
+    pub(crate) received: Timestamp,
+    /// When the deploy expires.
+    pub(crate) expires: Timestamp,
+    /// Whether all of the deploy's dependencies have been finalized.
+    pub(crate) dependencies_resolved: bool,
+}
+
 /// An event for when using the block proposer as a component.
 #[derive(DataSize, Debug, From)]
 pub(crate) enum Event {
@@ -48,6 +65,8 @@ pub(crate) enum Event {
     },
     /// The block proposer has been asked to prune stale deploys.
     Prune,
+    /// A queued block payload request may have passed its proposal deadline.
+    ProposalDeadline,
     /// A block has been finalized. We should never propose its deploys again.
     FinalizedBlock(Box<FinalizedBlock>),
 }
@@ -66,6 +85,7 @@ impl Display for Event {
             ),
             Event::BufferDeploy { hash, .. } => write!(f, "block-proposer add {}", hash),
             Event::Prune => write!(f, "block-proposer prune"),
+            Event::ProposalDeadline => write!(f, "block-proposer proposal deadline"),
             Event::FinalizedBlock(block) => {
                 write!(f, "block-proposer finalized block {}", block)
             }
@@ -1,5 +1,5 @@
 use datasize::DataSize;
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{self, IntCounter, IntGauge, Registry};
 
 use crate::unregister_metric;
 
@@ -9,6 +9,18 @@ pub(super) struct Metrics {
     /// Amount of pending deploys
     #[data_size(skip)]
     pub(super) pending_deploys: IntGauge,
+    /// Number of block payload requests waiting for finalized blocks.
+    #[data_size(skip)]
+    pub(super) queued_payload_requests: IntGauge,
+    /// Number of empty block payloads proposed because a request passed its deadline.
+    #[data_size(skip)]
+    pub(super) deadline_empty_payloads: IntCounter,
+    /// Number of deploys included in proposed blocks.
+    #[data_size(skip)]
+    pub(super) packed_deploys: IntCounter,
+    /// Number of eligible deploys left out of proposed blocks due to block limits or account caps.
+    #[data_size(skip)]
+    pub(super) rejected_deploys: IntCounter,
     /// Registry stored to allow deregistration later.
     #[data_size(skip)]
     registry: Registry,
@@ -18,9 +30,33 @@ impl Metrics {
     /// Creates a new instance of the block proposer metrics.
     pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
         let pending_deploys = IntGauge::new("pending_deploy", "the number of pending deploys")?;
+        let queued_payload_requests = IntGauge::new(
+            "block_proposer_queued_payload_requests",
+            "the number of block payload requests waiting for finalized blocks",
+        )?;
+        let deadline_empty_payloads = IntCounter::new(
+            "block_proposer_deadline_empty_payloads",
+            "the number of empty block payloads proposed because a request passed its deadline",
+        )?;
+        let packed_deploys = IntCounter::new(
+            "block_proposer_packed_deploys",
+            "the number of deploys included in proposed blocks",
+        )?;
+        let rejected_deploys = IntCounter::new(
+            "block_proposer_rejected_deploys",
+            "the number of eligible deploys left out of proposed blocks",
+        )?;
         registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(queued_payload_requests.clone()))?;
+        registry.register(Box::new(deadline_empty_payloads.clone()))?;
+        registry.register(Box::new(packed_deploys.clone()))?;
+        registry.register(Box::new(rejected_deploys.clone()))?;
         Ok(Metrics {
             pending_deploys,
+            queued_payload_requests,
+            deadline_empty_payloads,
+            packed_deploys,
+            rejected_deploys,
             registry,
         })
     }
@@ -29,5 +65,9 @@ impl Metrics {
 impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.pending_deploys);
+        unregister_metric!(self.registry, self.queued_payload_requests);
+        unregister_metric!(self.registry, self.deadline_empty_payloads);
+        unregister_metric!(self.registry, self.packed_deploys);
+        unregister_metric!(self.registry, self.rejected_deploys);
     }
 }
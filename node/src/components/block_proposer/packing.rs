@@ -0,0 +1,80 @@
+//! Ordering of pending deploys for packing them into a block.
+//!
+//! Choosing the deploys which maximize the fees of a block subject to its gas and size limits is a
+//! multi-dimensional knapsack problem. We approximate it greedily: each deploy is weighted by the
+//! shares of the block's gas and size limits it would take up, and deploys are proposed in order of
+//! their fee per unit of weight.
+
+use std::cmp::Ordering;
+
+use casper_types::U512;
+
+use super::{deploy_sets::PendingDeployInfo, DeployInfo};
+use crate::types::{chainspec::DeployConfig, DeployHash};
+
+/// Returns the given candidates in the order in which they should be added to a block, most
+/// profitable first.
+///
+/// Deploys of equal profitability are ordered by when they were received, oldest first.
+pub(super) fn packing_order<'a, I>(
+    candidates: I,
+    deploy_config: &DeployConfig,
+) -> Vec<(&'a DeployHash, &'a PendingDeployInfo)>
+where
+    I: IntoIterator<Item = (&'a DeployHash, &'a PendingDeployInfo)>,
+{
+    let mut weighted: Vec<_> = candidates
+        .into_iter()
+        .map(|(hash, pending)| (fee_density(&pending.info, deploy_config), hash, pending))
+        .collect();
+    weighted.sort_by(|(density1, hash1, pending1), (density2, hash2, pending2)| {
+        density2
+            .partial_cmp(density1)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| pending1.timestamp.cmp(&pending2.timestamp))
+            .then_with(|| hash1.cmp(hash2))
+    });
+    weighted
+        .into_iter()
+        .map(|(_, hash, pending)| (hash, pending))
+        .collect()
+}
+
+/// Returns the fee paid by a deploy per unit of the block capacity it takes up.
+///
+/// The capacity taken up is the sum of the deploy's shares of the block gas limit and the maximum
+/// block size.
+fn fee_density(deploy_info: &DeployInfo, deploy_config: &DeployConfig) -> f64 {
+    let fee = u512_to_f64(deploy_info.payment_amount.value());
+    let gas = fee / deploy_info.header.gas_price().max(1) as f64;
+    let gas_share = gas / deploy_config.block_gas_limit.max(1) as f64;
+    let size_share = deploy_info.size as f64 / deploy_config.max_block_size.max(1) as f64;
+    let weight = gas_share + size_share;
+    if weight > 0.0 {
+        fee / weight
+    } else {
+        0.0
+    }
+}
+
+/// Converts the given value to the nearest `f64`.
+fn u512_to_f64(value: U512) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_u512_to_f64() {
+        assert_eq!(u512_to_f64(U512::zero()), 0.0);
+        assert_eq!(u512_to_f64(U512::from(12_345u64)), 12_345.0);
+        assert_eq!(u512_to_f64(U512::from(u64::MAX) + 1), 2f64.powi(64));
+        assert_eq!(u512_to_f64(U512::MAX), 2f64.powi(512));
+    }
+}
@@ -1,5 +1,6 @@
 use std::{convert::TryInto, time::Duration};
 
+use futures::channel::oneshot;
 use itertools::Itertools;
 
 use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
@@ -9,7 +10,10 @@ use casper_types::{
 };
 
 use super::*;
-use crate::types::{BlockPayload, Deploy, DeployHash, FinalizedBlock};
+use crate::{
+    effect::Responder,
+    types::{BlockPayload, Deploy, DeployHash, FinalizedBlock},
+};
 
 const DEFAULT_TEST_GAS_PRICE: u64 = 1;
 
@@ -62,6 +66,24 @@ fn generate_deploy(
     gas_price: u64,
 ) -> Deploy {
     let secret_key = SecretKey::random(rng);
+    generate_deploy_signed_by(
+        &secret_key,
+        timestamp,
+        ttl,
+        dependencies,
+        payment_amount,
+        gas_price,
+    )
+}
+
+fn generate_deploy_signed_by(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+    payment_amount: Gas,
+    gas_price: u64,
+) -> Deploy {
     let chain_name = "chain".to_string();
     let args = runtime_args! {
         ARG_AMOUNT => payment_amount.value()
@@ -83,14 +105,17 @@ fn generate_deploy(
         chain_name,
         payment,
         session,
-        &secret_key,
+        secret_key,
         None,
     )
 }
 
 fn create_test_proposer(deploy_delay: TimeDiff) -> BlockProposerReady {
     BlockProposerReady {
-        local_config: Config { deploy_delay },
+        local_config: Config {
+            deploy_delay,
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
@@ -780,3 +805,236 @@ fn should_respect_deploy_delay() {
     );
     assert_eq!(vec![deploy.id()], block.deploy_hashes().collect::<Vec<_>>());
 }
+
+#[test]
+fn should_report_pending_deploy_status() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+    let pub_key = PublicKey::from(&SecretKey::random_secp256k1(&mut rng));
+
+    let deploy1 = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    // let transfer depend on deploy1
+    let transfer = generate_transfer(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![*deploy1.id()],
+        default_gas_payment(),
+    );
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy(
+        110.into(),
+        transfer.deploy_or_transfer_hash(),
+        BTreeSet::new(),
+        transfer.deploy_info().unwrap(),
+    );
+    proposer.add_deploy(
+        105.into(),
+        deploy1.deploy_or_transfer_hash(),
+        BTreeSet::new(),
+        deploy1.deploy_info().unwrap(),
+    );
+
+    // the statuses are ordered by the time the deploys were received
+    let statuses = proposer.pending_deploys();
+    assert_eq!(
+        statuses,
+        vec![
+            PendingDeployStatus {
+                deploy_hash: *deploy1.id(),
+                is_transfer: false,
+                account: deploy1.header().account().clone(),
+                received: 105.into(),
+                expires: deploy1.header().expires(),
+                dependencies_resolved: true,
+            },
+            PendingDeployStatus {
+                deploy_hash: *transfer.id(),
+                is_transfer: true,
+                account: transfer.header().account().clone(),
+                received: 110.into(),
+                expires: transfer.header().expires(),
+                dependencies_resolved: false,
+            },
+        ]
+    );
+
+    // once deploy1 is finalized, only the transfer remains, with its dependency resolved
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    let finalized_block = FinalizedBlock::new(
+        (*block).clone(),
+        None,
+        block_time,
+        EraId::from(1),
+        1,
+        pub_key,
+    );
+    proposer.handle_finalized_block(&finalized_block);
+
+    let statuses = proposer.pending_deploys();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].deploy_hash, *transfer.id());
+    assert!(statuses[0].dependencies_resolved);
+}
+
+#[test]
+fn should_propose_empty_payload_for_requests_past_deadline() {
+    let mut proposer = BlockProposerReady {
+        local_config: Config {
+            proposal_deadline: 100.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Queue two requests waiting for block 1 to be finalized, at times 0 and 80.
+    for queued_at in [0u64, 80] {
+        let (sender, _receiver) = oneshot::channel();
+        let request = BlockPayloadRequest {
+            context: BlockContext::new(queued_at.into(), vec![]),
+            next_finalized: 1,
+            accusations: vec![],
+            random_bit: true,
+            responder: Responder::without_shutdown(sender),
+        };
+        proposer
+            .request_queue
+            .entry(1)
+            .or_default()
+            .push((queued_at.into(), request));
+    }
+
+    // Neither request has waited long enough yet.
+    let (_, expired_count) = proposer.propose_expired_requests(99.into());
+    assert_eq!(expired_count, 0);
+    assert_eq!(proposer.request_queue[&1].len(), 2);
+
+    // Only the first request has passed its deadline.
+    let (_, expired_count) = proposer.propose_expired_requests(100.into());
+    assert_eq!(expired_count, 1);
+    assert_eq!(proposer.request_queue[&1].len(), 1);
+
+    // Once all requests are answered, the queue entry is removed.
+    let (_, expired_count) = proposer.propose_expired_requests(180.into());
+    assert_eq!(expired_count, 1);
+    assert!(proposer.request_queue.is_empty());
+}
+
+#[test]
+fn should_pack_deploys_by_fee_density() {
+    let mut rng = crate::new_rng();
+    let mut proposer = create_test_proposer(0.into());
+    let mut config = proposer.deploy_config;
+    config.block_gas_limit = 10;
+
+    let creation_time = Timestamp::from(100);
+    let block_time = Timestamp::from(120);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    // A deploy using all of the block's gas, paying less than three deploys using 3 gas each at a
+    // higher gas price.
+    let large_deploy = generate_deploy(&mut rng, creation_time, ttl, vec![], Gas::from(10u32), 1);
+    let small_deploys: Vec<_> = (0..3)
+        .map(|_| generate_deploy(&mut rng, creation_time, ttl, vec![], Gas::from(6u32), 2))
+        .collect();
+    for deploy in small_deploys.iter().chain(Some(&large_deploy)) {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            BTreeSet::new(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    let block =
+        proposer.propose_block_payload(config, BlockContext::new(block_time, vec![]), vec![], true);
+    let proposed: HashSet<_> = block.deploy_hashes().copied().collect();
+    let expected: HashSet<_> = small_deploys.iter().map(|deploy| *deploy.id()).collect();
+    assert_eq!(proposed, expected);
+    assert_eq!(proposer.packing_counts.chosen, 3);
+    assert_eq!(proposer.packing_counts.rejected, 1);
+
+    // The counts are recorded in the metrics once.
+    let metrics = Metrics::new(Registry::new()).unwrap();
+    proposer.update_metrics(&metrics);
+    proposer.update_metrics(&metrics);
+    assert_eq!(metrics.packed_deploys.get(), 3);
+    assert_eq!(metrics.rejected_deploys.get(), 1);
+}
+
+#[test]
+fn should_respect_max_deploys_per_account() {
+    let mut rng = crate::new_rng();
+    let mut proposer = BlockProposerReady {
+        local_config: Config {
+            deploy_delay: 0.into(),
+            max_deploys_per_account: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let creation_time = Timestamp::from(100);
+    let block_time = Timestamp::from(120);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let busy_account = SecretKey::random(&mut rng);
+    let busy_deploys: Vec<_> = (0..4)
+        .map(|_| {
+            generate_deploy_signed_by(
+                &busy_account,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect();
+    let other_deploy = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    for deploy in busy_deploys.iter().chain(Some(&other_deploy)) {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            BTreeSet::new(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    let config = proposer.deploy_config;
+    let block =
+        proposer.propose_block_payload(config, BlockContext::new(block_time, vec![]), vec![], true);
+    let proposed: HashSet<_> = block.deploy_hashes().copied().collect();
+    assert_eq!(proposed.len(), 3);
+    assert!(proposed.contains(other_deploy.id()));
+    assert_eq!(
+        busy_deploys
+            .iter()
+            .filter(|deploy| proposed.contains(deploy.id()))
+            .count(),
+        2
+    );
+    assert_eq!(proposer.packing_counts.rejected, 2);
+}
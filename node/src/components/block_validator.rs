@@ -1,7 +1,10 @@
 //! Block validator
 //!
 //! The block validator checks whether all the deploys included in the block payload exist, either
-//! locally or on the network.
+//! locally or on the network, and whether the approvals included in a proposed block are valid.
+//!
+//! Missing deploys are fetched concurrently from up to `MAX_CONCURRENT_SOURCES` of the peers that
+//! asked for the block to be validated, while the approvals are verified on rayon's thread pool.
 //!
 //! When multiple requests are made to validate the same block payload, they will eagerly return
 //! true if valid, but only fail if all sources have been exhausted. This is only relevant when
@@ -16,16 +19,18 @@ use std::{
     convert::Infallible,
     fmt::Debug,
     hash::Hash,
+    iter,
     sync::Arc,
 };
 
 use datasize::DataSize;
 use derive_more::{Display, From};
 use itertools::Itertools;
+use rayon::prelude::*;
 use smallvec::{smallvec, SmallVec};
 use tracing::{info, warn};
 
-use casper_types::Timestamp;
+use casper_types::{crypto, Timestamp};
 
 use crate::{
     components::{
@@ -47,6 +52,11 @@ use keyed_counter::KeyedCounter;
 
 use crate::components::fetcher::FetchedData;
 
+/// The maximum number of peers from which the missing deploys of a block are fetched concurrently.
+///
+/// Further peers are only asked once fetching a deploy from all of these has failed.
+const MAX_CONCURRENT_SOURCES: usize = 3;
+
 #[derive(DataSize, Debug, Display, Clone, Hash, Eq, PartialEq)]
 pub(crate) enum ValidatingBlock {
     #[display(fmt = "{}", _0.display())]
@@ -121,6 +131,18 @@ impl ValidatingBlock {
             }
         }
     }
+
+    /// Returns the approvals included in a proposed block, paired with the hash of their deploy.
+    fn approvals(&self) -> Vec<(DeployHash, Approval)> {
+        self.deploys_and_transfers_iter()
+            .flat_map(|(dt_hash, maybe_approvals)| {
+                maybe_approvals
+                    .into_iter()
+                    .flatten()
+                    .map(move |approval| (dt_hash.into(), approval))
+            })
+            .collect()
+    }
 }
 
 /// Block validator component event.
@@ -145,6 +167,10 @@ pub(crate) enum Event {
     /// Deploy was invalid. Unable to convert to a deploy type.
     #[display(fmt = "{} invalid", _0)]
     CannotConvertDeploy(DeployOrTransferHash),
+
+    /// The approvals included in a block have been verified.
+    #[display(fmt = "approvals of {} verified, valid: {}", block, valid)]
+    ApprovalsVerified { block: ValidatingBlock, valid: bool },
 }
 
 /// State of the current process of block validation.
@@ -160,15 +186,25 @@ pub(crate) struct BlockValidationState {
     missing_deploys: HashMap<DeployOrTransferHash, Option<BTreeSet<Approval>>>,
     /// A list of responders that are awaiting an answer.
     responders: SmallVec<[Responder<bool>; 2]>,
-    /// Peers that should have the data.
+    /// Peers the missing deploys are being fetched from concurrently.
+    fetching_from: BTreeSet<NodeId>,
+    /// Peers that should have the data, to be tried if fetching a deploy from all of
+    /// `fetching_from` fails.
     sources: VecDeque<NodeId>,
+    /// Whether the approvals included in the block have been verified.
+    approvals_verified: bool,
 }
 
 impl BlockValidationState {
     /// Adds alternative source of data.
-    /// Returns true if we already know about the peer.
+    ///
+    /// Returns true if the missing deploys should be fetched from the peer right away, i.e. if it
+    /// is new and fewer than `MAX_CONCURRENT_SOURCES` peers are being fetched from.
     fn add_source(&mut self, peer: NodeId) -> bool {
-        if self.sources.contains(&peer) {
+        if self.fetching_from.contains(&peer) || self.sources.contains(&peer) {
+            false
+        } else if self.fetching_from.len() < MAX_CONCURRENT_SOURCES {
+            self.fetching_from.insert(peer);
             true
         } else {
             self.sources.push_back(peer);
@@ -181,6 +217,11 @@ impl BlockValidationState {
         self.sources.pop_front()
     }
 
+    /// Returns whether all deploys have been found and the approvals have been verified.
+    fn is_complete(&self) -> bool {
+        self.missing_deploys.is_empty() && self.approvals_verified
+    }
+
     fn respond<REv>(&mut self, value: bool) -> Effects<REv> {
         self.responders
             .drain(..)
@@ -268,16 +309,24 @@ where
                 match self.validation_states.entry(block) {
                     Entry::Occupied(mut entry) => {
                         // The entry already exists.
-                        if entry.get().missing_deploys.is_empty() {
+                        let state = entry.get_mut();
+                        if state.is_complete() {
                             // Block has already been validated successfully, early return to
                             // caller.
                             effects.extend(responder.respond(true).ignore());
                         } else {
                             // We register ourselves as someone interested in the ultimate
                             // validation result.
-                            entry.get_mut().responders.push(responder);
-                            // And add an alternative source of data.
-                            entry.get_mut().add_source(sender);
+                            state.responders.push(responder);
+                            // And add an alternative source of data, fetching the missing deploys
+                            // from it concurrently if we aren't asking enough peers yet.
+                            if state.add_source(sender) {
+                                let in_flight = &mut self.in_flight;
+                                effects.extend(state.missing_deploys.keys().flat_map(|dt_hash| {
+                                    in_flight.inc(&(*dt_hash).into());
+                                    fetch_deploy(effect_builder, *dt_hash, sender)
+                                }));
+                            }
                         }
                     }
                     Entry::Vacant(entry) => {
@@ -291,14 +340,21 @@ where
                                 fetch_deploy(effect_builder, dt_hash, sender)
                             },
                         ));
+                        // Meanwhile, verify the approvals, if the block includes any.
+                        let approvals = entry.key().approvals();
+                        let approvals_verified = approvals.is_empty();
+                        if !approvals_verified {
+                            effects.extend(verify_approvals(entry.key().clone(), approvals));
+                        }
                         let block_timestamp = entry.key().timestamp();
                         let deploy_config = self.chainspec.deploy_config;
                         entry.insert(BlockValidationState {
                             appendable_block: AppendableBlock::new(deploy_config, block_timestamp),
                             missing_deploys: block_deploys,
                             responders: smallvec![responder],
-                            sources: VecDeque::new(), /* This is empty b/c we create the first
-                                                       * request using `sender`. */
+                            fetching_from: iter::once(sender).collect(),
+                            sources: VecDeque::new(),
+                            approvals_verified,
                         });
                     }
                 }
@@ -350,7 +406,7 @@ where
                         effects.extend(state.respond(false));
                         return false;
                     }
-                    if state.missing_deploys.is_empty() {
+                    if state.is_complete() {
                         // This one is done and valid.
                         effects.extend(state.respond(true));
                         return false;
@@ -425,11 +481,43 @@ where
                     }
                 });
             }
+            Event::ApprovalsVerified { block, valid } => {
+                if let Entry::Occupied(mut entry) = self.validation_states.entry(block) {
+                    if !valid {
+                        info!(block = ?entry.key(), "invalid approval. block is invalid");
+                        effects.extend(entry.get_mut().respond(false));
+                        entry.remove();
+                    } else {
+                        entry.get_mut().approvals_verified = true;
+                        if entry.get().is_complete() {
+                            effects.extend(entry.get_mut().respond(true));
+                            entry.remove();
+                        }
+                    }
+                }
+            }
         }
         effects
     }
 }
 
+/// Returns effects that verify the signatures of the given approvals on rayon's thread pool.
+fn verify_approvals(
+    block: ValidatingBlock,
+    approvals: Vec<(DeployHash, Approval)>,
+) -> Effects<Event> {
+    async move {
+        tokio::task::spawn_blocking(move || {
+            approvals.par_iter().all(|(deploy_hash, approval)| {
+                crypto::verify(deploy_hash, approval.signature(), approval.signer()).is_ok()
+            })
+        })
+        .await
+        .expect("task panicked")
+    }
+    .event(move |valid| Event::ApprovalsVerified { block, valid })
+}
+
 /// Returns effects that fetch the deploy and validate it.
 fn fetch_deploy<REv>(
     effect_builder: EffectBuilder<REv>,
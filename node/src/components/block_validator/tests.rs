@@ -56,6 +56,15 @@ impl MockReactor {
     async fn expect_fetch_deploy<T>(&self, deploy: T)
     where
         T: Into<Option<Deploy>>,
+    {
+        self.expect_fetch_deploy_from(|_| deploy.into()).await;
+    }
+
+    /// Answers the next fetch request with the deploy returned by `deploy_from` for the requested
+    /// peer, and returns that peer.
+    async fn expect_fetch_deploy_from<F>(&self, deploy_from: F) -> NodeId
+    where
+        F: FnOnce(NodeId) -> Option<Deploy>,
     {
         let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
         if let ReactorEvent::Fetcher(FetcherRequest {
@@ -64,7 +73,7 @@ impl MockReactor {
             responder,
         }) = reactor_event
         {
-            match deploy.into() {
+            match deploy_from(peer) {
                 None => {
                     responder
                         .respond(Err(FetcherError::Absent { id, peer }))
@@ -79,6 +88,7 @@ impl MockReactor {
                     responder.respond(Ok(response)).await;
                 }
             }
+            peer
         } else {
             panic!("unexpected event: {:?}", reactor_event);
         }
@@ -164,7 +174,17 @@ async fn validate_block(
         .map(DeployWithApprovals::from)
         .collect_vec();
     let proposed_block = new_proposed_block(timestamp, deploys_for_block, transfers_for_block);
+    let deploys_and_transfers = deploys.into_iter().chain(transfers).collect();
+    validate_proposed_block(rng, proposed_block, deploys_and_transfers).await
+}
 
+/// Validates a proposed block using a `BlockValidator` component, answering the requests to fetch
+/// its deploys with `deploys`, and returns the result.
+async fn validate_proposed_block(
+    rng: &mut TestRng,
+    proposed_block: ProposedBlock<ClContext>,
+    deploys: Vec<Deploy>,
+) -> bool {
     // Create the reactor and component.
     let reactor = MockReactor::new();
     let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(reactor.scheduler));
@@ -188,11 +208,12 @@ async fn validate_block(
         return validation_result.await.unwrap();
     }
 
-    // Otherwise the effects must be requests to fetch the block's deploys.
+    // Otherwise the effects must be requests to fetch the block's deploys and to verify their
+    // approvals.
     let fetch_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
 
     // We make our mock reactor answer with the expected deploys and transfers:
-    for deploy in deploys.into_iter().chain(transfers) {
+    for deploy in deploys {
         reactor.expect_fetch_deploy(deploy).await;
     }
 
@@ -289,3 +310,92 @@ async fn transfer_deploy_mixup_and_replay() {
     let transfers = vec![transfer1.clone(), transfer2.clone(), transfer2.clone()];
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
+
+/// Verifies that a proposed block is invalid if it contains an invalid approval, even if the deploy
+/// itself is valid.
+#[tokio::test]
+async fn invalid_approval() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploy = new_deploy(&mut rng, timestamp, ttl);
+    let other_deploy = new_deploy(&mut rng, timestamp, ttl);
+
+    // The approvals of the deploy itself are valid.
+    let proposed_block =
+        new_proposed_block(timestamp, vec![DeployWithApprovals::from(&deploy)], vec![]);
+    assert!(validate_proposed_block(&mut rng, proposed_block, vec![deploy.clone()]).await);
+
+    // An approval signing a different deploy's hash is not.
+    let approval = Approval::create(other_deploy.id(), &SecretKey::random(&mut rng));
+    let proposed_block = new_proposed_block(
+        timestamp,
+        vec![DeployWithApprovals::new(
+            *deploy.id(),
+            iter::once(approval).collect(),
+        )],
+        vec![],
+    );
+    assert!(!validate_proposed_block(&mut rng, proposed_block, vec![deploy]).await);
+}
+
+/// Verifies that missing deploys are fetched concurrently from further peers asking to validate the
+/// same block, so that one peer failing to provide them doesn't delay the validation.
+#[tokio::test]
+async fn fetch_from_multiple_peers() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploy = new_deploy(&mut rng, timestamp, ttl);
+    let proposed_block =
+        new_proposed_block(timestamp, vec![DeployWithApprovals::from(&deploy)], vec![]);
+
+    let reactor = MockReactor::new();
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(reactor.scheduler));
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut block_validator = BlockValidator::new(Arc::new(chainspec));
+
+    // Bob and Carol both ask for the block to be validated. Neither request has been answered yet,
+    // so the deploy is fetched from both of them.
+    let bob_node_id = NodeId::random(&mut rng);
+    let carol_node_id = NodeId::random(&mut rng);
+    let mut validation_results = Vec::new();
+    let mut effects = Effects::new();
+    for sender in [bob_node_id, carol_node_id] {
+        validation_results.push(tokio::spawn(
+            effect_builder.validate_block(sender, proposed_block.clone()),
+        ));
+        let event = reactor.expect_block_validator_event().await;
+        effects.extend(block_validator.handle_event(effect_builder, &mut rng, event));
+    }
+    let pending_effects: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+
+    // Bob doesn't have the deploy, but Carol does.
+    let mut peers = Vec::new();
+    for _ in 0..2 {
+        let peer = reactor
+            .expect_fetch_deploy_from(|peer| (peer == carol_node_id).then(|| deploy.clone()))
+            .await;
+        peers.push(peer);
+    }
+    peers.sort();
+    let mut expected_peers = vec![bob_node_id, carol_node_id];
+    expected_peers.sort();
+    assert_eq!(peers, expected_peers);
+
+    // Once all results are passed back into the component, both requests are answered.
+    let mut effects = Effects::new();
+    for pending_effect in pending_effects {
+        for event in pending_effect.await.unwrap() {
+            effects.extend(block_validator.handle_event(effect_builder, &mut rng, event));
+        }
+    }
+    assert_eq!(2, effects.len());
+    for effect in effects {
+        tokio::spawn(effect).await.unwrap(); // Response.
+    }
+    for validation_result in validation_results {
+        assert!(validation_result.await.unwrap());
+    }
+    assert!(block_validator.validation_states.is_empty());
+}
@@ -31,7 +31,7 @@ use crate::{
     fatal,
     storage::StorageRequest,
     types::{
-        Block, BlockAndDeploys, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
+        BlockAndDeploys, BlockBodyOrChunk, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
         BlockSignatures, BlockWithMetadata, Chainspec, Deploy, FinalizedApprovalsWithId,
         NodeConfig, NodeState,
     },
@@ -80,7 +80,7 @@ where
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
         + From<ChainspecLoaderRequest>
-        + From<FetcherRequest<Block>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<BlockAndDeploys>>
         + From<FetcherRequest<BlockWithMetadata>>
@@ -264,7 +264,7 @@ where
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
         + From<ChainspecLoaderRequest>
-        + From<FetcherRequest<Block>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<BlockHeadersBatch>>
         + From<FetcherRequest<BlockAndDeploys>>
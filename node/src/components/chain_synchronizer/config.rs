@@ -7,7 +7,7 @@ use casper_types::{EraId, ProtocolVersion, TimeDiff};
 
 use crate::{
     components::consensus::ChainspecConsensusExt,
-    types::{BlockHash, Chainspec, NodeConfig},
+    types::{chainspec::EmergencyRestart, BlockHash, Chainspec, NodeConfig},
     SmallNetworkConfig,
 };
 
@@ -147,4 +147,9 @@ impl Config {
     pub(super) fn chainspec(&self) -> Arc<Chainspec> {
         Arc::clone(&self.chainspec)
     }
+
+    /// Returns the emergency restart described by the chainspec, if any.
+    pub(super) fn emergency_restart(&self) -> Option<EmergencyRestart> {
+        EmergencyRestart::from_chainspec(&self.chainspec)
+    }
 }
@@ -9,7 +9,7 @@ use casper_execution_engine::{
     storage::trie::TrieOrChunk,
 };
 use casper_hashing::Digest;
-use casper_types::{EraId, ProtocolVersion};
+use casper_types::{bytesrepr, EraId, ProtocolVersion};
 
 use crate::{
     components::{
@@ -17,8 +17,9 @@ use crate::{
         linear_chain::BlockSignatureError,
     },
     types::{
-        Block, BlockAndDeploys, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockWithMetadata, Deploy, FinalizedApprovalsWithId, Item,
+        chainspec::EmergencyRestartError, Block, BlockAndDeploys, BlockBodyOrChunk, BlockHash,
+        BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch, BlockWithMetadata, Deploy,
+        FinalizedApprovalsWithId, Item,
     },
 };
 
@@ -62,6 +63,9 @@ pub(crate) enum Error {
         stored_header_at_same_height: Box<BlockHeader>,
     },
 
+    #[error("trusted header conflicts with the emergency restart: {0}")]
+    TrustedHeaderConflictsWithEmergencyRestart(#[serde(skip_serializing)] EmergencyRestartError),
+
     #[error(
         "current version is {current_version}, but retrieved block header with future version: \
          {block_header_with_future_version:?}"
@@ -95,12 +99,25 @@ pub(crate) enum Error {
     #[error(transparent)]
     BlockAndDeploysFetcher(#[from] FetcherError<BlockAndDeploys>),
 
+    #[error(transparent)]
+    BlockBodyOrChunkFetcher(#[from] FetcherError<BlockBodyOrChunk>),
+
+    #[error("received inconsistent chunks of the body of block {block_hash}")]
+    InconsistentBlockBodyChunks { block_hash: BlockHash },
+
+    #[error("received body doesn't match the header of block {block_hash}")]
+    InvalidBlockBody { block_hash: BlockHash },
+
     #[error(transparent)]
     DeployWithMetadataFetcher(#[from] FetcherError<Deploy>),
 
     #[error(transparent)]
     FinalizedApprovalsFetcher(#[from] FetcherError<FinalizedApprovalsWithId>),
 
+    /// Error serializing a set of approvals to compute its hash.
+    #[error("failed to hash approvals: {0}")]
+    ApprovalsHash(#[serde(skip_serializing)] bytesrepr::Error),
+
     #[error(transparent)]
     FinalitySignatures(
         #[from]
@@ -181,6 +198,12 @@ pub(crate) enum Error {
     AttemptsExhausted,
 }
 
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::ApprovalsHash(error)
+    }
+}
+
 #[derive(Error, Debug)]
 pub(crate) enum FetchTrieError {
     /// Fetcher error.
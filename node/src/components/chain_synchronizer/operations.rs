@@ -1,7 +1,7 @@
 use std::{
     cmp,
     collections::{BTreeMap, VecDeque},
-    mem,
+    iter, mem,
     sync::{
         atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
         Arc, RwLock,
@@ -24,7 +24,10 @@ use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::storage::trie::{TrieOrChunk, TrieOrChunkId};
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, EraId, PublicKey, TimeDiff, Timestamp, U512};
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    EraId, PublicKey, TimeDiff, Timestamp, U512,
+};
 
 use crate::{
     components::{
@@ -47,12 +50,12 @@ use crate::{
     },
     storage::StorageRequest,
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
-        BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockSignatures,
-        BlockWithMetadata, Deploy, DeployHash, FinalizedApprovals, FinalizedApprovalsWithId,
-        FinalizedBlock, Item, NodeId,
+        AvailableBlockRange, Block, BlockAndDeploys, BlockBody, BlockBodyOrChunk,
+        BlockBodyOrChunkId, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
+        BlockHeadersBatchId, BlockSignatures, BlockWithMetadata, Deploy, DeployHash,
+        FinalizedApprovals, FinalizedApprovalsWithId, FinalizedBlock, Item, NodeId,
     },
-    utils::work_queue::WorkQueue,
+    utils::{clock, work_queue::WorkQueue},
 };
 
 const FINALITY_SIGNATURE_FETCH_RETRY_COUNT: usize = 3;
@@ -177,6 +180,12 @@ where
             }
         };
 
+        if let Some(emergency_restart) = config.emergency_restart() {
+            emergency_restart
+                .check_block_header(&trusted_block_header)
+                .map_err(Error::TrustedHeaderConflictsWithEmergencyRestart)?;
+        }
+
         if trusted_block_header.protocol_version() != config.protocol_version() {
             return Err(Error::TrustedHeaderTooEarly {
                 trusted_header: Box::new(trusted_block_header),
@@ -329,6 +338,10 @@ impl<'a, REv> ChainSyncContext<'a, REv> {
 /// Restrict the fan-out for a trie being retrieved by chunks to query at most 10 peers at a time.
 const TRIE_CHUNK_FETCH_FAN_OUT: usize = 10;
 
+/// Restrict the fan-out for a block body being retrieved by chunks to query at most 10 peers at a
+/// time.
+const BLOCK_BODY_CHUNK_FETCH_FAN_OUT: usize = 10;
+
 // TODO[RC]: Replace this with a proper call to network component once implemented.
 const fn has_connected_to_network() -> bool {
     true
@@ -353,6 +366,7 @@ impl CanUseSyncingNodes for TrieOrChunk {
 /// backpressure implemented for these fetch requests.
 impl CanUseSyncingNodes for BlockHeader {}
 impl CanUseSyncingNodes for Block {}
+impl CanUseSyncingNodes for BlockBodyOrChunk {}
 impl CanUseSyncingNodes for Deploy {}
 impl CanUseSyncingNodes for BlockAndDeploys {}
 impl CanUseSyncingNodes for BlockHeadersBatch {}
@@ -909,6 +923,20 @@ where
                     continue;
                 }
 
+                if let Some(Err(error)) = ctx
+                    .config
+                    .emergency_restart()
+                    .map(|emergency_restart| emergency_restart.check_block_header(item.header()))
+                {
+                    warn!(
+                        %error,
+                        ?peer,
+                        "received block conflicting with the emergency restart from peer"
+                    );
+                    ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                    continue;
+                }
+
                 if key_block_info.era_id() != item.header().era_id() {
                     error!(
                         key_block_info_era_id = key_block_info.era_id().value(),
@@ -1021,21 +1049,102 @@ where
     }
 }
 
-/// Downloads and stores a block.
-async fn fetch_and_store_block_by_hash<REv>(
+/// Downloads the body of the block with the given header and stores the block.
+///
+/// Large bodies are downloaded in chunks, each fetched independently and possibly from different
+/// peers, so that a failed request only needs the affected chunk to be fetched again.  The
+/// reassembled body is verified against the header's body hash.
+async fn fetch_and_store_block_by_header<REv>(
+    block_header: BlockHeader,
+    ctx: &ChainSyncContext<'_, REv>,
+) -> Result<Box<Block>, Error>
+where
+    REv: From<StorageRequest> + From<FetcherRequest<BlockBodyOrChunk>> + From<NetworkInfoRequest>,
+{
+    let block_hash = block_header.hash();
+    let first_id = BlockBodyOrChunkId::new(block_hash, 0);
+    let first_chunk_with_proof =
+        match fetch_with_retries::<_, BlockBodyOrChunk>(ctx, first_id).await? {
+            FetchedData::FromStorage { .. } => return get_stored_block(block_hash, ctx).await,
+            FetchedData::FromPeer { item, .. } => match *item {
+                BlockBodyOrChunk::BlockBody { body, .. } => {
+                    return store_block(block_header, *body, ctx).await
+                }
+                BlockBodyOrChunk::ChunkWithProof {
+                    chunk_with_proof, ..
+                } => chunk_with_proof,
+            },
+        };
+
+    let root_hash = first_chunk_with_proof.proof().root_hash();
+    let count = first_chunk_with_proof.proof().count();
+    // Chunks fetched from storage mean the block was stored by a parallel task in the meantime.
+    let remaining_chunks: Vec<Option<Bytes>> = futures::stream::iter(1..count)
+        .map(|index| async move {
+            let id = BlockBodyOrChunkId::new(block_hash, index);
+            match fetch_with_retries::<_, BlockBodyOrChunk>(ctx, id).await? {
+                FetchedData::FromStorage { .. } => Ok(None),
+                FetchedData::FromPeer { item, .. } => match *item {
+                    BlockBodyOrChunk::ChunkWithProof {
+                        chunk_with_proof, ..
+                    } if chunk_with_proof.proof().root_hash() == root_hash => {
+                        Ok(Some(chunk_with_proof.into_chunk()))
+                    }
+                    _ => Err(Error::InconsistentBlockBodyChunks { block_hash }),
+                },
+            }
+        })
+        // Do not try to fetch all of the chunks at once; only fetch at most
+        // BLOCK_BODY_CHUNK_FETCH_FAN_OUT at a time, keeping them in order.
+        .buffered(BLOCK_BODY_CHUNK_FETCH_FAN_OUT)
+        .try_collect()
+        .await?;
+
+    let chunks = match iter::once(Some(first_chunk_with_proof.into_chunk()))
+        .chain(remaining_chunks)
+        .collect::<Option<Vec<Bytes>>>()
+    {
+        Some(chunks) => chunks,
+        None => return get_stored_block(block_hash, ctx).await,
+    };
+    let serialized_body: Vec<u8> = chunks.into_iter().flat_map(Vec::<u8>::from).collect();
+    let body = bytesrepr::deserialize(serialized_body)
+        .map_err(|_| Error::InvalidBlockBody { block_hash })?;
+    store_block(block_header, body, ctx).await
+}
+
+/// Stores the block assembled from the given header and body, if the body matches the header.
+async fn store_block<REv>(
+    block_header: BlockHeader,
+    body: BlockBody,
+    ctx: &ChainSyncContext<'_, REv>,
+) -> Result<Box<Block>, Error>
+where
+    REv: From<StorageRequest>,
+{
+    let block_hash = block_header.hash();
+    let block = Block::new_from_header_and_body(block_header, body)
+        .map_err(|_| Error::InvalidBlockBody { block_hash })?;
+    let block = Box::new(block);
+    ctx.effect_builder.put_block_to_storage(block.clone()).await;
+    Ok(block)
+}
+
+/// Gets a block which was stored while its body was being fetched.
+async fn get_stored_block<REv>(
     block_hash: BlockHash,
     ctx: &ChainSyncContext<'_, REv>,
-) -> Result<Box<Block>, FetchWithRetryError<Block>>
+) -> Result<Box<Block>, Error>
 where
-    REv: From<StorageRequest> + From<FetcherRequest<Block>> + From<NetworkInfoRequest>,
+    REv: From<StorageRequest>,
 {
-    match fetch_with_retries::<_, Block>(ctx, block_hash).await? {
-        FetchedData::FromStorage { item: block, .. } => Ok(block),
-        FetchedData::FromPeer { item: block, .. } => {
-            ctx.effect_builder.put_block_to_storage(block.clone()).await;
-            Ok(block)
-        }
-    }
+    ctx.effect_builder
+        .get_block_from_storage(block_hash)
+        .await
+        .map(Box::new)
+        .ok_or(Error::NoSuchBlockHash {
+            bogus_block_hash: block_hash,
+        })
 }
 
 /// Downloads and stores a block with all its deploys.
@@ -1204,7 +1313,7 @@ where
         + From<ContractRuntimeRequest>
         + From<StorageRequest>
         + From<BlocklistAnnouncement>
-        + From<FetcherRequest<Block>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<BlockHeaderWithMetadata>>
         + Send,
@@ -1347,7 +1456,10 @@ async fn fetch_blocks_for_deploy_replay_protection<REv>(
     ctx: &ChainSyncContext<'_, REv>,
 ) -> Result<(), Error>
 where
-    REv: From<StorageRequest> + From<FetcherRequest<Block>> + From<NetworkInfoRequest>,
+    REv: From<StorageRequest>
+        + From<FetcherRequest<BlockHeader>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
+        + From<NetworkInfoRequest>,
 {
     let _metric = ScopeTimer::new(&ctx.metrics.chain_sync_replay_protection_duration_seconds);
 
@@ -1358,7 +1470,9 @@ where
         < ctx.config.deploy_max_ttl()
         && current_header.height() != 0
     {
-        current_header = fetch_and_store_block_by_hash(*current_header.parent_hash(), ctx)
+        let parent_header =
+            fetch_and_store_block_header(ctx, *current_header.parent_hash()).await?;
+        current_header = fetch_and_store_block_by_header(*parent_header, ctx)
             .await?
             .take_header();
     }
@@ -1909,7 +2023,7 @@ where
     REv: From<StorageRequest>
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
-        + From<FetcherRequest<Block>>
+        + From<FetcherRequest<BlockBodyOrChunk>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<BlockAndDeploys>>
         + From<FetcherRequest<BlockWithMetadata>>
@@ -1965,7 +2079,7 @@ where
     info!(
         era_id = ?highest_block_header.era_id(),
         height = highest_block_header.height(),
-        now = %Timestamp::now(),
+        now = %clock::now(),
         block_timestamp = %highest_block_header.timestamp(),
         "finished initial chain sync",
     );
@@ -2009,7 +2123,7 @@ fn verify_trusted_block_header<REv>(ctx: &ChainSyncContext<'_, REv>) -> Result<(
                 .config
                 .unbonding_delay()
                 .saturating_sub(ctx.config.auction_delay())
-        < Timestamp::now()
+        < clock::now()
     {
         warn!(
             ?ctx.trusted_block_header,
@@ -2021,6 +2135,11 @@ fn verify_trusted_block_header<REv>(ctx: &ChainSyncContext<'_, REv>) -> Result<(
     Ok(())
 }
 
+/// Replaces the approvals of the given deploys with the finalized approvals held by `peer` and
+/// re-executes the block.
+///
+/// Returns `None` without re-executing if the peer's approvals hash the same as the ones already
+/// used for every deploy, as the outcome would be unchanged.
 async fn retry_execution_with_approvals_from_peer<REv>(
     deploys: &mut [Deploy],
     transfers: &mut [Deploy],
@@ -2028,24 +2147,32 @@ async fn retry_execution_with_approvals_from_peer<REv>(
     block: &Block,
     execution_pre_state: &ExecutionPreState,
     ctx: &ChainSyncContext<'_, REv>,
-) -> Result<BlockAndExecutionEffects, Error>
+) -> Result<Option<BlockAndExecutionEffects>, Error>
 where
     REv: From<FetcherRequest<FinalizedApprovalsWithId>> + From<ContractRuntimeRequest>,
 {
+    let mut approvals_changed = false;
     for deploy in deploys.iter_mut().chain(transfers.iter_mut()) {
         let new_approvals = fetch_finalized_approvals(*deploy.id(), peer, ctx).await?;
-        deploy.replace_approvals(new_approvals.into_inner());
+        if new_approvals.approvals_hash()? != deploy.approvals_hash()? {
+            deploy.replace_approvals(new_approvals.into_inner());
+            approvals_changed = true;
+        }
     }
-    Ok(ctx
-        .effect_builder
-        .execute_finalized_block(
-            block.protocol_version(),
-            execution_pre_state.clone(),
-            FinalizedBlock::from(block.clone()),
-            deploys.to_owned(),
-            transfers.to_owned(),
-        )
-        .await?)
+    if !approvals_changed {
+        return Ok(None);
+    }
+    Ok(Some(
+        ctx.effect_builder
+            .execute_finalized_block(
+                block.protocol_version(),
+                execution_pre_state.clone(),
+                FinalizedBlock::from(block.clone()),
+                deploys.to_owned(),
+                transfers.to_owned(),
+            )
+            .await?,
+    ))
 }
 
 /// Executes forwards from the block after `highest_synced_block_header` until we can get no higher
@@ -2074,7 +2201,7 @@ where
     info!(
         era_id = ?highest_synced_block_header.era_id(),
         height = highest_synced_block_header.height(),
-        now = %Timestamp::now(),
+        now = %clock::now(),
         block_timestamp = %highest_synced_block_header.timestamp(),
         "fetching and executing blocks to synchronize to current",
     );
@@ -2114,7 +2241,7 @@ where
         info!(
             era_id = ?block.header().era_id(),
             height = block.height(),
-            now = %Timestamp::now(),
+            now = %clock::now(),
             block_timestamp = %block.timestamp(),
             "executing block",
         );
@@ -2144,7 +2271,7 @@ where
                     "retrying execution due to deploy approvals mismatch"
                 );
                 ctx.progress.retry_executing_block(block.height(), attempts);
-                let block_and_execution_effects = retry_execution_with_approvals_from_peer(
+                let block_and_execution_effects = match retry_execution_with_approvals_from_peer(
                     &mut deploys,
                     &mut transfers,
                     peer,
@@ -2152,7 +2279,17 @@ where
                     &execution_pre_state,
                     ctx,
                 )
-                .await?;
+                .await?
+                {
+                    Some(block_and_execution_effects) => block_and_execution_effects,
+                    None => {
+                        debug!(
+                            %peer,
+                            "peer has the same approvals as already tried; not re-executing"
+                        );
+                        continue;
+                    }
+                };
                 debug!(block_hash=%block.hash(), "finish - re-executing finalized block");
                 blocks_match = block == *block_and_execution_effects.block();
                 if blocks_match {
@@ -2256,7 +2393,7 @@ pub(super) fn is_current_era(
         highest_synced_block,
         trusted_key_block_info,
         config,
-        Timestamp::now(),
+        clock::now(),
     )
 }
 
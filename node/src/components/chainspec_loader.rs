@@ -10,6 +10,8 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
+mod metrics;
+
 use std::{
     fmt::{self, Display, Formatter},
     fs,
@@ -21,15 +23,18 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
+use prometheus::Registry;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tokio::task;
 use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::core::engine_state::{
     self, ChainspecRegistry, GenesisSuccess, UpgradeConfig, UpgradeSuccess,
 };
-use casper_types::{bytesrepr, crypto::PublicKey, file_utils, EraId, ProtocolVersion, Timestamp};
+use casper_hashing::Digest;
+use casper_types::{bytesrepr, crypto::PublicKey, file_utils, EraId, ProtocolVersion, TimeDiff};
 
 #[cfg(test)]
 use crate::utils::RESOURCES_PATH;
@@ -50,16 +55,23 @@ use crate::{
     fatal,
     reactor::ReactorExit,
     types::{
-        chainspec::{ChainspecRawBytes, Error, ProtocolConfig, CHAINSPEC_FILENAME},
+        chainspec::{
+            self, ChainspecRawBytes, EmergencyRestart, EmergencyRestartError, Error,
+            ProtocolConfig, CHAINSPEC_FILENAME,
+        },
         ActivationPoint, BlockHeader, BlockPayload, Chainspec, ChainspecInfo, ExitCode,
         FinalizedBlock,
     },
-    utils::Loadable,
+    utils::{clock, Loadable},
     NodeRng,
 };
+use metrics::Metrics;
 
 const UPGRADE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
+/// The name of the node config file expected alongside a staged chainspec.
+const CONFIG_FILENAME: &str = "config.toml";
+
 /// `ChainspecHandler` events.
 #[derive(Debug, From, Serialize)]
 pub(crate) enum Event {
@@ -77,7 +89,7 @@ pub(crate) enum Event {
     },
     ExecuteImmediateSwitchBlockResult {
         #[serde(skip_serializing)]
-        result: Result<BlockAndExecutionEffects, BlockExecutionError>,
+        result: Result<BlockAndExecutionEffects, ImmediateSwitchBlockError>,
     },
     #[from]
     Request(ChainspecLoaderRequest),
@@ -85,6 +97,12 @@ pub(crate) enum Event {
     CheckForNextUpgrade,
     /// If the result of checking for an upgrade is successful, it is passed here.
     GotNextUpgrade(NextUpgrade),
+    /// The result of a dry run of the next upgrade, or `None` if it isn't due yet.
+    UpgradeDryRunResult {
+        protocol_version: ProtocolVersion,
+        #[serde(skip_serializing)]
+        result: Option<Result<Digest, UpgradeDryRunError>>,
+    },
 }
 
 impl Display for Event {
@@ -121,6 +139,16 @@ impl Display for Event {
             Event::GotNextUpgrade(next_upgrade) => {
                 write!(formatter, "got {}", next_upgrade)
             }
+            Event::UpgradeDryRunResult {
+                protocol_version,
+                result,
+            } => {
+                write!(
+                    formatter,
+                    "result of dry run of upgrade to {}: {:?}",
+                    protocol_version, result
+                )
+            }
         }
     }
 }
@@ -170,6 +198,54 @@ impl Display for NextUpgrade {
     }
 }
 
+/// An error creating the immediate switch block after genesis or an upgrade.
+#[derive(Debug, Error)]
+pub(crate) enum ImmediateSwitchBlockError {
+    /// Executing the block failed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The block doesn't match the emergency restart described by the chainspec.
+    #[error(transparent)]
+    EmergencyRestart(#[from] EmergencyRestartError),
+}
+
+/// An error found during a dry run of an upgrade.
+#[derive(Debug, Error)]
+pub(crate) enum UpgradeDryRunError {
+    /// The staged chainspec could not be loaded.
+    #[error("failed to load chainspec: {0}")]
+    LoadChainspec(#[from] Error),
+    /// The staged chainspec contains invalid settings or changes not allowed in an upgrade.
+    #[error("invalid chainspec: {}", .0.join("; "))]
+    InvalidChainspec(Vec<String>),
+    /// The staged config file could not be read or parsed.
+    #[error("failed to load {path}: {error}")]
+    LoadConfig { path: PathBuf, error: String },
+    /// The global state update in the staged chainspec could not be deserialized.
+    #[error("invalid global state update: {0}")]
+    GlobalStateUpdate(bytesrepr::Error),
+    /// The highest block is not known.
+    #[error("no highest block in storage")]
+    NoHighestBlock,
+    /// The task loading the staged files failed.
+    #[error("failed to join tokio task: {0}")]
+    Join(String),
+    /// Running the upgrade failed.
+    #[error("upgrade failed: {0}")]
+    Upgrade(#[from] engine_state::Error),
+}
+
+/// Settings and state of the dry runs of staged upgrades.
+#[derive(Clone, DataSize, Debug)]
+struct UpgradeDryRun {
+    /// How long before the estimated activation of an upgrade to run it.
+    lead_time: TimeDiff,
+    /// The version of the staged upgrade a dry run has been started for, if any.
+    started_for: Option<ProtocolVersion>,
+    #[data_size(skip)]
+    metrics: Arc<Metrics>,
+}
+
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct ChainspecLoader {
     chainspec: Arc<Chainspec>,
@@ -180,6 +256,7 @@ pub(crate) struct ChainspecLoader {
     reactor_exit: Option<ReactorExit>,
     next_upgrade: Option<NextUpgrade>,
     maybe_immediate_switch_block_data: Option<BlockAndExecutionEffects>,
+    upgrade_dry_run: Option<UpgradeDryRun>,
 }
 
 impl ChainspecLoader {
@@ -245,6 +322,7 @@ impl ChainspecLoader {
                 reactor_exit: Some(ReactorExit::ProcessShouldExit(ExitCode::Abort)),
                 next_upgrade: None,
                 maybe_immediate_switch_block_data: None,
+                upgrade_dry_run: None,
             };
             return (chainspec_loader, Effects::new());
         }
@@ -289,6 +367,7 @@ impl ChainspecLoader {
             reactor_exit,
             next_upgrade,
             maybe_immediate_switch_block_data: None,
+            upgrade_dry_run: None,
         };
 
         (chainspec_loader, effects)
@@ -312,6 +391,20 @@ impl ChainspecLoader {
             return Effects::new();
         }
 
+        // If this is an emergency restart, refuse to continue from a block conflicting with the
+        // agreed-upon checkpoint.
+        if let Some(emergency_restart) = EmergencyRestart::from_chainspec(&self.chainspec) {
+            emergency_restart.log();
+            if let Some(Err(error)) = maybe_highest_block_header
+                .as_deref()
+                .map(|header| emergency_restart.check_block_header(header))
+            {
+                error!(%error, "highest stored block conflicts with emergency restart");
+                self.reactor_exit = Some(ReactorExit::ProcessShouldExit(ExitCode::Abort));
+                return Effects::new();
+            }
+        }
+
         match maybe_highest_block_header {
             Some(header)
                 if self
@@ -323,7 +416,7 @@ impl ChainspecLoader {
                 // create an immediate switch block.
                 info!("valid run immediately after upgrade");
                 let upgrade_config_result =
-                    self.new_upgrade_config(&header, Arc::clone(&self.chainspec_raw_bytes));
+                    new_upgrade_config(&self.chainspec, &self.chainspec_raw_bytes, &header);
                 async move {
                     match upgrade_config_result {
                         Ok(upgrade_config) => {
@@ -343,7 +436,7 @@ impl ChainspecLoader {
                 // This is a valid initial run on a new network at genesis.
                 trace!("valid initial run at genesis");
                 // unwrap is safe as `chainspec.is_genesis()` is true
-                if Timestamp::now()
+                if clock::now()
                     < self
                         .chainspec
                         .protocol_config
@@ -373,32 +466,6 @@ impl ChainspecLoader {
         }
     }
 
-    fn new_upgrade_config(
-        &self,
-        upgrade_block_header: &BlockHeader,
-        chainspec_raw_bytes: Arc<ChainspecRawBytes>,
-    ) -> Result<Box<UpgradeConfig>, bytesrepr::Error> {
-        let global_state_update = self.chainspec.protocol_config.get_update_mapping()?;
-        let chainspec_registry = ChainspecRegistry::new_with_optional_global_state(
-            chainspec_raw_bytes.chainspec_bytes(),
-            chainspec_raw_bytes.maybe_global_state_bytes(),
-        );
-        let upgrade_config = UpgradeConfig::new(
-            *upgrade_block_header.state_root_hash(),
-            upgrade_block_header.protocol_version(),
-            self.chainspec.protocol_version(),
-            Some(self.chainspec.protocol_config.activation_point.era_id()),
-            Some(self.chainspec.core_config.validator_slots),
-            Some(self.chainspec.core_config.auction_delay),
-            Some(self.chainspec.core_config.locked_funds_period.millis()),
-            Some(self.chainspec.core_config.round_seigniorage_rate),
-            Some(self.chainspec.core_config.unbonding_delay),
-            global_state_update,
-            chainspec_registry,
-        );
-        Ok(Box::new(upgrade_config))
-    }
-
     fn should_exit_for_upgrade(
         maybe_highest_block_header: Option<&BlockHeader>,
         maybe_next_upgrade_activation_point: Option<ActivationPoint>,
@@ -541,6 +608,8 @@ impl ChainspecLoader {
     /// Creates a switch block after an upgrade or genesis. This block has the system public key as
     /// a proposer and doesn't contain any deploys or transfers. It is the only block in its era,
     /// and no consensus instance is run for era 0 or an upgrade point era.
+    ///
+    /// After an emergency restart, the block is checked against the chainspec before it is stored.
     fn execute_immediate_switch_block<REv>(
         &self,
         effect_builder: EffectBuilder<REv>,
@@ -554,6 +623,7 @@ impl ChainspecLoader {
             + Send,
     {
         let protocol_version = self.chainspec.protocol_version();
+        let maybe_emergency_restart = EmergencyRestart::from_chainspec(&self.chainspec);
         async move {
             let block_and_execution_effects = effect_builder
                 .execute_finalized_block(
@@ -564,6 +634,10 @@ impl ChainspecLoader {
                     vec![],
                 )
                 .await?;
+            if let Some(emergency_restart) = maybe_emergency_restart {
+                emergency_restart
+                    .check_immediate_switch_block(block_and_execution_effects.block.header())?;
+            }
             // We need to store the block now so that the era supervisor can be properly
             // initialized in the participating reactor's constructor.
             effect_builder
@@ -584,7 +658,7 @@ impl ChainspecLoader {
     fn handle_execute_immediate_switch_block_result<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        result: Result<BlockAndExecutionEffects, BlockExecutionError>,
+        result: Result<BlockAndExecutionEffects, ImmediateSwitchBlockError>,
     ) -> Effects<Event>
     where
         REv: From<ControlAnnouncement> + Send,
@@ -592,7 +666,7 @@ impl ChainspecLoader {
         let immediate_switch_block_and_exec_effects = match result {
             Ok(block_and_execution_effects) => block_and_execution_effects,
             Err(error) => {
-                error!(%error, "failed to execute block");
+                error!(%error, "failed to create immediate switch block");
                 return fatal!(effect_builder, "{}", error).ignore();
             }
         };
@@ -619,6 +693,23 @@ impl ChainspecLoader {
         self.check_for_next_upgrade(effect_builder)
     }
 
+    /// Enables dry runs of staged upgrades the given time before their estimated activation.
+    pub(crate) fn enable_upgrade_dry_run(
+        &mut self,
+        lead_time: Option<TimeDiff>,
+        registry: &Registry,
+    ) -> Result<(), prometheus::Error> {
+        self.upgrade_dry_run = match lead_time {
+            Some(lead_time) => Some(UpgradeDryRun {
+                lead_time,
+                started_for: None,
+                metrics: Arc::new(Metrics::new(registry)?),
+            }),
+            None => None,
+        };
+        Ok(())
+    }
+
     pub(crate) fn reactor_exit(&self) -> Option<ReactorExit> {
         self.reactor_exit
     }
@@ -684,7 +775,14 @@ impl ChainspecLoader {
         effects
     }
 
-    fn handle_got_next_upgrade(&mut self, next_upgrade: NextUpgrade) -> Effects<Event> {
+    fn handle_got_next_upgrade<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        next_upgrade: NextUpgrade,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + From<ContractRuntimeRequest> + Send,
+    {
         debug!("got {}", next_upgrade);
         if let Some(ref current_point) = self.next_upgrade {
             if next_upgrade != *current_point {
@@ -696,6 +794,83 @@ impl ChainspecLoader {
             }
         }
         self.next_upgrade = Some(next_upgrade);
+        self.maybe_dry_run_upgrade(effect_builder)
+    }
+
+    /// Starts a dry run of the next upgrade if dry runs are enabled, none has been started for its
+    /// version yet, and its activation is estimated to be within the configured lead time.
+    fn maybe_dry_run_upgrade<REv>(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event>
+    where
+        REv: From<StorageRequest> + From<ContractRuntimeRequest> + Send,
+    {
+        let (upgrade_dry_run, next_upgrade) =
+            match (self.upgrade_dry_run.as_mut(), self.next_upgrade.as_ref()) {
+                (Some(upgrade_dry_run), Some(next_upgrade)) => (upgrade_dry_run, next_upgrade),
+                _ => return Effects::new(),
+            };
+        let protocol_version = next_upgrade.protocol_version;
+        if upgrade_dry_run.started_for == Some(protocol_version) {
+            return Effects::new();
+        }
+        upgrade_dry_run.started_for = Some(protocol_version);
+
+        let lead_time = upgrade_dry_run.lead_time;
+        let activation_era = next_upgrade.activation_point.era_id();
+        let core_config = &self.chainspec.core_config;
+        let era_length = core_config.era_duration.millis().max(
+            self.chainspec
+                .highway_config
+                .min_round_length()
+                .millis()
+                .saturating_mul(core_config.minimum_era_height),
+        );
+        let dir = self.root_dir.join(dir_name_from_version(&protocol_version));
+        let current_chainspec = Arc::clone(&self.chainspec);
+        async move {
+            let block_header = match effect_builder.get_highest_block_header_from_storage().await {
+                Some(block_header) => block_header,
+                None => return Some(Err(UpgradeDryRunError::NoHighestBlock)),
+            };
+            let remaining_eras = activation_era
+                .value()
+                .saturating_sub(block_header.next_block_era_id().value());
+            if era_length.saturating_mul(remaining_eras) > lead_time.millis() {
+                return None;
+            }
+            info!(%protocol_version, "starting dry run of upgrade");
+            Some(dry_run_upgrade(effect_builder, dir, current_chainspec, block_header).await)
+        }
+        .event(move |result| Event::UpgradeDryRunResult {
+            protocol_version,
+            result,
+        })
+    }
+
+    fn handle_upgrade_dry_run_result(
+        &mut self,
+        protocol_version: ProtocolVersion,
+        result: Option<Result<Digest, UpgradeDryRunError>>,
+    ) -> Effects<Event> {
+        let upgrade_dry_run = match self.upgrade_dry_run.as_mut() {
+            Some(upgrade_dry_run) => upgrade_dry_run,
+            None => return Effects::new(),
+        };
+        match result {
+            None => {
+                // The upgrade is not due yet: try again on the next check.
+                if upgrade_dry_run.started_for == Some(protocol_version) {
+                    upgrade_dry_run.started_for = None;
+                }
+            }
+            Some(Ok(post_state_hash)) => {
+                info!(%protocol_version, %post_state_hash, "dry run of upgrade succeeded");
+                upgrade_dry_run.metrics.upgrade_dry_run_outcome.set(1);
+            }
+            Some(Err(error)) => {
+                error!(%protocol_version, %error, "dry run of upgrade failed");
+                upgrade_dry_run.metrics.upgrade_dry_run_outcome.set(-1);
+            }
+        }
         Effects::new()
     }
 }
@@ -740,8 +915,17 @@ where
             Event::Request(ChainspecLoaderRequest::GetChainspecRawBytes(responder)) => responder
                 .respond(Arc::clone(&self.chainspec_raw_bytes))
                 .ignore(),
+            Event::Request(ChainspecLoaderRequest::GetChainspec(responder)) => {
+                responder.respond(Arc::clone(&self.chainspec)).ignore()
+            }
             Event::CheckForNextUpgrade => self.check_for_next_upgrade(effect_builder),
-            Event::GotNextUpgrade(next_upgrade) => self.handle_got_next_upgrade(next_upgrade),
+            Event::GotNextUpgrade(next_upgrade) => {
+                self.handle_got_next_upgrade(effect_builder, next_upgrade)
+            }
+            Event::UpgradeDryRunResult {
+                protocol_version,
+                result,
+            } => self.handle_upgrade_dry_run_result(protocol_version, result),
         }
     }
 }
@@ -862,6 +1046,89 @@ fn next_upgrade(dir: PathBuf, current_version: ProtocolVersion) -> Option<NextUp
     Some(NextUpgrade::from(upgrade_point.protocol_config))
 }
 
+/// Creates the config to upgrade the global state as of `upgrade_block_header` to the protocol
+/// version of `chainspec`.
+fn new_upgrade_config(
+    chainspec: &Chainspec,
+    chainspec_raw_bytes: &ChainspecRawBytes,
+    upgrade_block_header: &BlockHeader,
+) -> Result<Box<UpgradeConfig>, bytesrepr::Error> {
+    let global_state_update = chainspec.protocol_config.get_update_mapping()?;
+    let chainspec_registry = ChainspecRegistry::new_with_optional_global_state(
+        chainspec_raw_bytes.chainspec_bytes(),
+        chainspec_raw_bytes.maybe_global_state_bytes(),
+    );
+    let upgrade_config = UpgradeConfig::new(
+        *upgrade_block_header.state_root_hash(),
+        upgrade_block_header.protocol_version(),
+        chainspec.protocol_version(),
+        Some(chainspec.protocol_config.activation_point.era_id()),
+        Some(chainspec.core_config.validator_slots),
+        Some(chainspec.core_config.auction_delay),
+        Some(chainspec.core_config.locked_funds_period.millis()),
+        Some(chainspec.core_config.round_seigniorage_rate),
+        Some(chainspec.core_config.unbonding_delay),
+        Some(chainspec.core_config.native_token_issuers()),
+        global_state_update,
+        chainspec_registry,
+    );
+    Ok(Box::new(upgrade_config))
+}
+
+/// Loads the chainspec and config staged in `dir`, and checks that the chainspec is valid and
+/// allowed as an upgrade from `current_chainspec`.
+fn load_staged_upgrade(
+    dir: &Path,
+    current_chainspec: &Chainspec,
+) -> Result<(Chainspec, ChainspecRawBytes), UpgradeDryRunError> {
+    let (chainspec, chainspec_raw_bytes) = <(Chainspec, ChainspecRawBytes)>::from_path(dir)?;
+    let mut report = chainspec::check_chainspec(&chainspec, &chainspec_raw_bytes);
+    report.extend(chainspec::check_upgrade(current_chainspec, &chainspec));
+    for warning in &report.warnings {
+        warn!(%warning, dir = %dir.display(), "staged chainspec");
+    }
+    if !report.is_ok() {
+        return Err(UpgradeDryRunError::InvalidChainspec(report.errors));
+    }
+
+    let config_path = dir.join(CONFIG_FILENAME);
+    if config_path.exists() {
+        let load_config_error = |error: String| UpgradeDryRunError::LoadConfig {
+            path: config_path.clone(),
+            error,
+        };
+        let config_bytes = file_utils::read_file(&config_path)
+            .map_err(|error| load_config_error(error.to_string()))?;
+        toml::from_slice::<toml::Value>(&config_bytes)
+            .map_err(|error| load_config_error(error.to_string()))?;
+    }
+
+    Ok((chainspec, chainspec_raw_bytes))
+}
+
+/// Runs the upgrade to the chainspec staged in `dir` against the global state as of
+/// `block_header`, without committing it, and returns the resulting state root hash.
+async fn dry_run_upgrade<REv>(
+    effect_builder: EffectBuilder<REv>,
+    dir: PathBuf,
+    current_chainspec: Arc<Chainspec>,
+    block_header: BlockHeader,
+) -> Result<Digest, UpgradeDryRunError>
+where
+    REv: From<ContractRuntimeRequest> + Send,
+{
+    let (chainspec, chainspec_raw_bytes) =
+        task::spawn_blocking(move || load_staged_upgrade(&dir, &current_chainspec))
+            .await
+            .map_err(|error| UpgradeDryRunError::Join(error.to_string()))??;
+    let upgrade_config = new_upgrade_config(&chainspec, &chainspec_raw_bytes, &block_header)
+        .map_err(UpgradeDryRunError::GlobalStateUpdate)?;
+    let UpgradeSuccess {
+        post_state_hash, ..
+    } = effect_builder.dry_run_upgrade(upgrade_config).await?;
+    Ok(post_state_hash)
+}
+
 #[cfg(test)]
 mod tests {
     use casper_types::testing::TestRng;
@@ -1146,4 +1413,43 @@ mod tests {
         fs::remove_file(&path_v1_0_0).unwrap();
         assert!(maybe_next_point(&current).is_none());
     }
+
+    #[test]
+    fn should_check_staged_upgrade() {
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let (current_chainspec, chainspec_raw_bytes) =
+            <(Chainspec, ChainspecRawBytes)>::from_resources("production");
+        let mut raw: toml::Value = toml::from_slice(chainspec_raw_bytes.chainspec_bytes()).unwrap();
+        let install = |raw: &toml::Value| {
+            fs::write(
+                tempdir.path().join(CHAINSPEC_FILENAME),
+                toml::to_string_pretty(raw).expect("should encode to toml"),
+            )
+            .expect("should install chainspec")
+        };
+
+        // Staging the current chainspec again is not a valid upgrade.
+        install(&raw);
+        assert!(matches!(
+            load_staged_upgrade(tempdir.path(), &current_chainspec),
+            Err(UpgradeDryRunError::InvalidChainspec(errors)) if errors.len() == 2
+        ));
+
+        raw["protocol"]["version"] = toml::Value::String("2.0.0".to_string());
+        raw["protocol"]["activation_point"] = toml::Value::Integer(4000);
+        install(&raw);
+        let (staged_chainspec, _) =
+            load_staged_upgrade(tempdir.path(), &current_chainspec).unwrap();
+        assert_eq!(
+            staged_chainspec.protocol_version(),
+            ProtocolVersion::from_parts(2, 0, 0)
+        );
+
+        // A corrupt config file staged alongside the chainspec is reported.
+        fs::write(tempdir.path().join(CONFIG_FILENAME), "bad data").unwrap();
+        assert!(matches!(
+            load_staged_upgrade(tempdir.path(), &current_chainspec),
+            Err(UpgradeDryRunError::LoadConfig { .. })
+        ));
+    }
 }
@@ -0,0 +1,34 @@
+use prometheus::{IntGauge, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the chainspec loader component.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Outcome of the dry run of the next staged upgrade.
+    pub(super) upgrade_dry_run_outcome: IntGauge,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+impl Metrics {
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let upgrade_dry_run_outcome = IntGauge::new(
+            "upgrade_dry_run_outcome",
+            "outcome of the dry run of the next staged upgrade: 0 if not run yet, 1 if it \
+            succeeded, -1 if it failed",
+        )?;
+        registry.register(Box::new(upgrade_dry_run_outcome.clone()))?;
+
+        Ok(Metrics {
+            upgrade_dry_run_outcome,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.upgrade_dry_run_outcome);
+    }
+}
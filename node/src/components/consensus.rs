@@ -11,6 +11,7 @@ mod highway_core;
 pub(crate) mod error;
 mod metrics;
 mod protocols;
+mod signer;
 #[cfg(test)]
 mod tests;
 mod traits;
@@ -30,8 +31,10 @@ use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use casper_types::{EraId, PublicKey, Timestamp};
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, Signature, Timestamp};
 
+use self::era_supervisor::PersistedEvidence;
 use crate::{
     components::Component,
     effect::{
@@ -40,7 +43,8 @@ use crate::{
         incoming::ConsensusMessageIncoming,
         requests::{
             BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
-            ContractRuntimeRequest, NetworkInfoRequest, NetworkRequest, StorageRequest,
+            ContractRuntimeRequest, NetworkInfoRequest, NetworkRequest, StateStoreRequest,
+            StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
@@ -52,9 +56,10 @@ use crate::{
 
 pub(crate) use cl_context::ClContext;
 pub(crate) use config::{ChainspecConsensusExt, Config};
-pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
+pub(crate) use consensus_protocol::{BlockContext, EraReport, LeaderSchedule, ProposedBlock};
 pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor};
 pub(crate) use protocols::highway::HighwayProtocol;
+pub(crate) use signer::Signer;
 pub(crate) use validator_change::ValidatorChange;
 
 #[derive(DataSize, Clone, Serialize, Deserialize)]
@@ -68,12 +73,12 @@ pub(crate) enum ConsensusMessage {
 
 /// An ID to distinguish different timers. What they are used for is specific to each consensus
 /// protocol implementation.
-#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct TimerId(pub u8);
 
 /// An ID to distinguish queued actions. What they are used for is specific to each consensus
 /// protocol implementation.
-#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct ActionId(pub u8);
 
 #[derive(DataSize, Debug, From)]
@@ -105,6 +110,12 @@ pub(crate) enum Event {
     },
     /// A queued action to be handled by a specific era.
     Action { era_id: EraId, action_id: ActionId },
+    /// The signature of a new vertex, or `None` if it couldn't be created.
+    Signature {
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    },
     /// We are receiving the data we require to propose a new block.
     NewBlockPayload(NewBlockPayload),
     #[from]
@@ -129,6 +140,8 @@ pub(crate) enum Event {
     },
     /// Got the result of checking for an upgrade activation point.
     GotUpgradeActivationPoint(ActivationPoint),
+    /// The equivocation evidence persisted before the last restart has been loaded.
+    PersistedEvidenceLoaded(Box<PersistedEvidence>),
     /// Dump state for debugging purposes.
     #[from]
     DumpState(DumpConsensusStateRequest),
@@ -182,6 +195,21 @@ impl Display for Event {
             Event::Action { era_id, action_id } => {
                 write!(f, "action (ID {}) for {}", action_id.0, era_id)
             }
+            Event::Signature {
+                era_id,
+                hash,
+                signature,
+            } => write!(
+                f,
+                "signature of {} for {}: {}",
+                hash,
+                era_id,
+                if signature.is_some() {
+                    "created"
+                } else {
+                    "failed"
+                }
+            ),
             Event::NewBlockPayload(NewBlockPayload {
                 era_id,
                 block_payload,
@@ -232,6 +260,7 @@ impl Display for Event {
             Event::GotUpgradeActivationPoint(activation_point) => {
                 write!(f, "new upgrade activation point: {:?}", activation_point)
             }
+            Event::PersistedEvidenceLoaded(_) => write!(f, "persisted evidence loaded"),
             Event::DumpState(req) => Display::fmt(req, f),
         }
     }
@@ -249,6 +278,7 @@ pub(crate) trait ReactorEventT:
     + From<ConsensusAnnouncement>
     + From<BlockValidationRequest>
     + From<StorageRequest>
+    + From<StateStoreRequest>
     + From<ContractRuntimeRequest>
     + From<ChainspecLoaderRequest>
     + From<BlocklistAnnouncement>
@@ -266,6 +296,7 @@ impl<REv> ReactorEventT for REv where
         + From<ConsensusAnnouncement>
         + From<BlockValidationRequest>
         + From<StorageRequest>
+        + From<StateStoreRequest>
         + From<ContractRuntimeRequest>
         + From<ChainspecLoaderRequest>
         + From<BlocklistAnnouncement>
@@ -294,6 +325,11 @@ where
             Event::Action { era_id, action_id } => {
                 self.handle_action(effect_builder, rng, era_id, action_id)
             }
+            Event::Signature {
+                era_id,
+                hash,
+                signature,
+            } => self.handle_signature(effect_builder, rng, era_id, hash, signature),
             Event::Incoming(ConsensusMessageIncoming { sender, message }) => {
                 self.handle_message(effect_builder, rng, sender, message)
             }
@@ -318,11 +354,20 @@ where
             Event::GotUpgradeActivationPoint(activation_point) => {
                 self.got_upgrade_activation_point(activation_point)
             }
+            Event::PersistedEvidenceLoaded(evidence) => {
+                self.handle_persisted_evidence_loaded(effect_builder, rng, *evidence)
+            }
             Event::ConsensusRequest(ConsensusRequest::Status(responder)) => self.status(responder),
             Event::ConsensusRequest(ConsensusRequest::ValidatorChanges(responder)) => {
                 let validator_changes = self.get_validator_changes();
                 responder.respond(validator_changes).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::Evidence(responder)) => {
+                responder.respond(self.get_evidence()).ignore()
+            }
+            Event::ConsensusRequest(ConsensusRequest::LeaderSchedule(responder)) => {
+                responder.respond(self.leader_schedule()).ignore()
+            }
             Event::DumpState(req @ DumpConsensusStateRequest { era_id, .. }) => {
                 let requested_era = era_id.unwrap_or_else(|| self.current_era());
 
@@ -8,29 +8,29 @@ use casper_hashing::Digest;
 use casper_types::{crypto, PublicKey, SecretKey, Signature};
 
 use crate::{
-    components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    components::consensus::{
+        signer::Signer,
+        traits::{ConsensusValueT, Context, ValidatorSecret},
+    },
     types::BlockPayload,
 };
 
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
+    signer: Signer,
     public_key: PublicKey,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(crate) fn new(signer: Signer, public_key: PublicKey) -> Self {
+        Self { signer, public_key }
     }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
     fn from(secret_key: Arc<SecretKey>) -> Self {
         let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(Signer::from(secret_key), public_key)
     }
 }
 
@@ -38,8 +38,8 @@ impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        self.signer.sign_locally(hash, &self.public_key)
     }
 }
 
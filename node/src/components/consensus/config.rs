@@ -7,7 +7,10 @@ use casper_types::{PublicKey, SecretKey};
 
 use crate::{
     components::consensus::{
-        era_supervisor::PAST_OPEN_ERAS, protocols::highway::config::Config as HighwayConfig, EraId,
+        era_supervisor::PAST_OPEN_ERAS,
+        protocols::highway::config::Config as HighwayConfig,
+        signer::{RemoteSigner, RemoteSignerConfig, Signer},
+        EraId,
     },
     types::Chainspec,
     utils::{External, LoadError, Loadable},
@@ -20,6 +23,17 @@ use crate::{
 pub(crate) struct Config {
     /// Path to secret key file.
     pub(crate) secret_key_path: External,
+    /// Path to the secret key file of a successor signing key registered with the auction.
+    ///
+    /// The node signs consensus messages with this key in eras whose validator set contains it.
+    #[serde(default)]
+    pub(crate) successor_secret_key_path: Option<External>,
+    /// External signer holding the validator key.
+    ///
+    /// If configured, consensus messages and finality signatures are signed by the external signer
+    /// and `secret_key_path` is ignored.
+    #[serde(default)]
+    pub(crate) remote_signer: Option<RemoteSignerConfig>,
     /// Highway-specific node configuration.
     pub(crate) highway: HighwayConfig,
 }
@@ -28,21 +42,51 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            successor_secret_key_path: None,
+            remote_signer: None,
             highway: HighwayConfig::default(),
         }
     }
 }
 
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    #[allow(clippy::type_complexity)]
+    /// Loads the validator's signer and public key.
+    ///
+    /// If an external signer is configured, its public key is loaded from the configured file.
+    /// Otherwise the secret key is loaded and the public key derived from it.
     pub(crate) fn load_keys<P: AsRef<Path>>(
         &self,
         root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadError<<Arc<SecretKey> as Loadable>::Error>> {
+    ) -> Result<(Signer, PublicKey), LoadError<<PublicKey as Loadable>::Error>> {
+        if let Some(remote_signer) = &self.remote_signer {
+            let public_key: PublicKey = remote_signer.public_key_path.clone().load(root)?;
+            let signer = Signer::Remote(Arc::new(RemoteSigner::new(
+                remote_signer.address,
+                remote_signer.timeout,
+            )));
+            return Ok((signer, public_key));
+        }
         let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
         let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+        Ok((Signer::Local(secret_signing_key), public_key))
+    }
+
+    /// Loads the successor secret key from the configuration file, if configured, and derives the
+    /// public key.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn load_successor_keys<P: AsRef<Path>>(
+        &self,
+        root: P,
+    ) -> Result<Option<(Signer, PublicKey)>, LoadError<<Arc<SecretKey> as Loadable>::Error>> {
+        match &self.successor_secret_key_path {
+            Some(successor_secret_key_path) => {
+                let secret_signing_key: Arc<SecretKey> =
+                    successor_secret_key_path.clone().load(root)?;
+                let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
+                Ok(Some((Signer::Local(secret_signing_key), public_key)))
+            }
+            None => Ok(None),
+        }
     }
 }
 
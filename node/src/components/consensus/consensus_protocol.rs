@@ -201,6 +201,14 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     QueueAction(ActionId),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock(BlockContext<C>),
+    /// Request a signature of the given hash of a new vertex, and pass it to
+    /// `ConsensusProtocol::handle_signature`. If the vertex is a unit, `unit_seq_number` is its
+    /// sequence number.
+    CreateSignature {
+        hash: C::Hash,
+        instance_id: C::InstanceId,
+        unit_seq_number: Option<u64>,
+    },
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
     /// Request validation of the consensus value, contained in a message received from the given
@@ -230,6 +238,18 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     Disconnect(NodeId),
 }
 
+/// The leaders of the rounds of an era, i.e. the validators expected to propose the blocks.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq)]
+pub(crate) struct LeaderSchedule<C>
+where
+    C: Context,
+{
+    /// The length of the rounds.
+    pub(crate) round_length: TimeDiff,
+    /// The start of each round and its leader, in chronological order.
+    pub(crate) leaders: Vec<(Timestamp, C::ValidatorId)>,
+}
+
 /// An API for a single instance of the consensus.
 pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Upcasts consensus protocol into `dyn Any`.
@@ -247,7 +267,7 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     ) -> ProtocolOutcomes<C>;
 
     /// Current instance of consensus protocol is latest era.
-    fn handle_is_current(&self, now: Timestamp) -> ProtocolOutcomes<C>;
+    fn handle_is_current(&mut self, now: Timestamp) -> ProtocolOutcomes<C>;
 
     /// Triggers consensus' timer.
     fn handle_timer(&mut self, timestamp: Timestamp, timer_id: TimerId) -> ProtocolOutcomes<C>;
@@ -258,6 +278,15 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Proposes a new value for consensus.
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C>;
 
+    /// Handles the signature requested via `ProtocolOutcome::CreateSignature`, or `None` if it
+    /// couldn't be created.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C>;
+
     /// Marks the `value` as valid or invalid, based on validation requested via
     /// `ProtocolOutcome::ValidateConsensusvalue`.
     fn resolve_validity(
@@ -291,6 +320,12 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Sends evidence for a faulty of validator `vid` to the `sender` of the request.
     fn request_evidence(&self, sender: NodeId, vid: &C::ValidatorId) -> ProtocolOutcomes<C>;
 
+    /// Returns the serialized evidence against the validator `vid`, if we have any.
+    fn evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>>;
+
+    /// Adds serialized evidence, as returned by `evidence`, that was persisted before a restart.
+    fn restore_evidence(&mut self, evidence: Vec<u8>, now: Timestamp) -> ProtocolOutcomes<C>;
+
     /// Sets the pause status: While paused we don't create consensus messages other than pings.
     fn set_paused(&mut self, paused: bool);
 
@@ -308,4 +343,8 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
 
     // TODO: Make this less Highway-specific.
     fn next_round_length(&self) -> Option<TimeDiff>;
+
+    /// Returns the leaders of this instance's rounds, from its start until its earliest possible
+    /// end, assuming the current round length. Returns `None` if the protocol has no leaders.
+    fn leader_schedule(&self) -> Option<LeaderSchedule<C>>;
 }
@@ -7,6 +7,7 @@
 
 pub(super) mod debug;
 mod era;
+mod persisted_evidence;
 
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
@@ -27,22 +28,24 @@ use rand::Rng;
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp, U512};
+use casper_types::{AsymmetricType, EraId, PublicKey, Signature, TimeDiff, Timestamp, U512};
 
 use crate::{
     components::{
         consensus::{
             cl_context::{ClContext, Keypair},
             consensus_protocol::{
-                ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
-                ProtocolOutcome,
+                ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, LeaderSchedule,
+                ProposedBlock, ProtocolOutcome,
             },
             metrics::Metrics,
+            signer::{Signer, UnitMetadata},
             validator_change::{ValidatorChange, ValidatorChanges},
             ActionId, ChainspecConsensusExt, Config, ConsensusMessage, Event, NewBlockPayload,
             ReactorEventT, ResolveValidity, TimerId,
         },
         storage::Storage,
+        HealthCheck,
     },
     effect::{
         announcements::ControlAnnouncement,
@@ -52,12 +55,15 @@ use crate::{
     fatal,
     types::{
         ActivationPoint, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
-        DeployOrTransferHash, FinalitySignature, FinalizedApprovals, FinalizedBlock, NodeId,
+        DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, Health, NodeId,
     },
+    utils::clock,
     NodeRng,
 };
 
 pub use self::era::Era;
+pub(crate) use self::persisted_evidence::PersistedEvidence;
+use self::persisted_evidence::EVIDENCE_STATE_KEY;
 use crate::components::consensus::error::CreateNewEraError;
 
 /// The delay in milliseconds before we shutdown after the number of faulty validators exceeded the
@@ -72,6 +78,9 @@ const PAST_EVIDENCE_ERAS: u64 = 1;
 /// The more recent half of these is active: it contains units and can still accept further units.
 /// The older half is in evidence-only state, and only used to validate cited evidence.
 pub(super) const PAST_OPEN_ERAS: u64 = 2 * PAST_EVIDENCE_ERAS;
+/// The number of times per era we send evidence against the same validator to the same peer.
+/// Further requests are ignored, so that peers cannot make us send the same evidence endlessly.
+const MAX_EVIDENCE_SENDS_PER_PEER: u8 = 2;
 
 type ConsensusConstructor = dyn Fn(
         Digest,                    // the era's unique instance ID
@@ -104,8 +113,11 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: HashMap<EraId, Era>,
-    secret_signing_key: Arc<SecretKey>,
+    signer: Signer,
     public_signing_key: PublicKey,
+    /// The successor signing key registered with the auction, if configured. It replaces our own
+    /// key in eras whose validator set contains it.
+    successor_signing_key: Option<(Signer, PublicKey)>,
     current_era: EraId,
     chainspec: Arc<Chainspec>,
     config: Config,
@@ -130,6 +142,16 @@ pub struct EraSupervisor {
     next_upgrade_activation_point: Option<ActivationPoint>,
     /// The era that was current when this node joined the network.
     era_where_we_joined: EraId,
+    /// If the node is draining, the height of the first block finalized after the drain was
+    /// requested. Once all blocks below it have been executed, the drain is complete.
+    drain_target_height: Option<u64>,
+    /// Evidence observed in the open eras, persisted so that it survives a restart.
+    persisted_evidence: PersistedEvidence,
+    /// Whether the evidence persisted by an earlier run has been loaded yet. Until then, new
+    /// evidence is not saved, so it doesn't overwrite the stored one.
+    persisted_evidence_loaded: bool,
+    /// How many times we sent evidence against a validator to a peer since the current era began.
+    evidence_sent: HashMap<(NodeId, PublicKey), u8>,
 }
 
 impl Debug for EraSupervisor {
@@ -145,8 +167,9 @@ impl EraSupervisor {
     pub(crate) fn new<REv: ReactorEventT>(
         current_era: EraId,
         storage_dir: &Path,
-        secret_signing_key: Arc<SecretKey>,
+        signer: Signer,
         public_signing_key: PublicKey,
+        successor_signing_key: Option<(Signer, PublicKey)>,
         config: Config,
         effect_builder: EffectBuilder<REv>,
         chainspec: Arc<Chainspec>,
@@ -174,8 +197,9 @@ impl EraSupervisor {
 
         let mut era_supervisor = Self {
             open_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
+            successor_signing_key,
             current_era,
             chainspec,
             config,
@@ -186,6 +210,10 @@ impl EraSupervisor {
             next_upgrade_activation_point,
             next_executed_height: next_height,
             era_where_we_joined: current_era,
+            drain_target_height: None,
+            persisted_evidence: PersistedEvidence::default(),
+            persisted_evidence_loaded: false,
+            evidence_sent: HashMap::new(),
         };
 
         // Collect the information needed to initialize all open eras.
@@ -233,6 +261,15 @@ impl EraSupervisor {
             ));
         }
 
+        // Load the evidence that was observed before the node was restarted.
+        effects.extend(
+            effect_builder
+                .load_state::<PersistedEvidence>(EVIDENCE_STATE_KEY.into())
+                .event(|maybe_evidence| {
+                    Event::PersistedEvidenceLoaded(Box::new(maybe_evidence.unwrap_or_default()))
+                }),
+        );
+
         Ok((era_supervisor, effects))
     }
 
@@ -299,6 +336,17 @@ impl EraSupervisor {
         self.open_eras.get(&era_id).map_or(false, has_validator)
     }
 
+    /// Returns the keys we sign with in the given era: the successor key if the era's validator
+    /// set contains it, and our own key otherwise.
+    fn signing_keys_in(&self, era_id: EraId) -> (Signer, PublicKey) {
+        match &self.successor_signing_key {
+            Some((signer, public_key)) if self.is_validator_in(public_key, era_id) => {
+                (signer.clone(), public_key.clone())
+            }
+            _ => (self.signer.clone(), self.public_signing_key.clone()),
+        }
+    }
+
     /// Updates `next_executed_height` based on the given block header, and unpauses consensus if
     /// block execution has caught up with finalization.
     #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
@@ -310,10 +358,11 @@ impl EraSupervisor {
     /// Pauses or unpauses consensus: Whenever the last executed block is too far behind the last
     /// finalized block, we suspend consensus.
     fn update_consensus_pause(&mut self) {
-        let paused = self
-            .next_block_height
-            .saturating_sub(self.next_executed_height)
-            > self.config.highway.max_execution_delay;
+        let paused = self.is_draining()
+            || self
+                .next_block_height
+                .saturating_sub(self.next_executed_height)
+                > self.config.highway.max_execution_delay;
         match self.open_eras.get_mut(&self.current_era) {
             Some(era) => era.set_paused(paused),
             None => error!(
@@ -425,7 +474,7 @@ impl EraSupervisor {
         let chainspec_hash = self.chainspec.hash();
         let key_block_hash = key_block.hash();
         let instance_id = instance_id(chainspec_hash, era_id, key_block_hash);
-        let now = Timestamp::now();
+        let now = clock::now();
 
         info!(
             ?validators,
@@ -474,7 +523,7 @@ impl EraSupervisor {
 
         // Activate the era if this node was already running when the era began, it is still
         // ongoing based on its minimum duration, and we are one of the validators.
-        let our_id = self.public_signing_key.clone();
+        let (our_signer, our_id) = self.signing_keys_in(era_id);
         if self.current_era > era_id {
             trace!(
                 era = era_id.value(),
@@ -484,13 +533,16 @@ impl EraSupervisor {
         } else {
             self.current_era = era_id;
             self.metrics.current_era.set(era_id.value() as i64);
+            self.evidence_sent.clear();
             self.next_block_height = self.next_block_height.max(start_height);
             outcomes.extend(self.era_mut(era_id).consensus.handle_is_current(now));
             if !self.era(era_id).validators().contains_key(&our_id) {
                 info!(era = era_id.value(), %our_id, "not voting; not a validator");
+            } else if self.is_draining() {
+                info!(era = era_id.value(), %our_id, "not voting; node is draining");
             } else {
                 info!(era = era_id.value(), %our_id, "start voting");
-                let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+                let secret = Keypair::new(our_signer, our_id.clone());
                 let unit_hash_file = self.unit_file(&instance_id);
                 outcomes.extend(self.era_mut(era_id).consensus.activate_validator(
                     our_id,
@@ -515,6 +567,17 @@ impl EraSupervisor {
             }
         }
 
+        // Add the evidence from this era that was persisted before a restart.
+        let persisted_evidence: Vec<Vec<u8>> =
+            self.persisted_evidence.in_era(era_id).cloned().collect();
+        for evidence in persisted_evidence {
+            outcomes.extend(
+                self.era_mut(era_id)
+                    .consensus
+                    .restore_evidence(evidence, now),
+            );
+        }
+
         // Clear the obsolete data from the era before the previous one. We only retain the
         // information necessary to validate evidence that units in the two most recent eras may
         // refer to for cross-era fault tracking.
@@ -525,6 +588,7 @@ impl EraSupervisor {
             }
 
             // Remove the era that has become obsolete now: We keep only three in memory.
+            self.persisted_evidence.prune(evidence_only_era_id);
             if let Some(obsolete_era_id) = evidence_only_era_id.checked_sub(1) {
                 if let Some(era) = self.open_eras.remove(&obsolete_era_id) {
                     trace!(era = obsolete_era_id.value(), "removing obsolete era");
@@ -602,7 +666,20 @@ impl EraSupervisor {
         action_id: ActionId,
     ) -> Effects<Event> {
         self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
-            consensus.handle_action(action_id, Timestamp::now())
+            consensus.handle_action(action_id, clock::now())
+        })
+    }
+
+    pub(super) fn handle_signature<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    ) -> Effects<Event> {
+        self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
+            consensus.handle_signature(hash, signature, clock::now())
         })
     }
 
@@ -617,9 +694,14 @@ impl EraSupervisor {
             ConsensusMessage::Protocol { era_id, payload } => {
                 // If the era is already unbonded, only accept new evidence, because still-bonded
                 // eras could depend on that.
-                trace!(era = era_id.value(), "received a consensus message");
+                trace!(
+                    era = era_id.value(),
+                    ?sender,
+                    payload = %base16::encode_lower(&payload),
+                    "received a consensus message"
+                );
                 self.delegate_to_era(effect_builder, rng, era_id, move |consensus, rng| {
-                    consensus.handle_message(rng, sender, payload, Timestamp::now())
+                    consensus.handle_message(rng, sender, payload, clock::now())
                 })
             }
             ConsensusMessage::EvidenceRequest { era_id, pub_key } => {
@@ -629,15 +711,125 @@ impl EraSupervisor {
                     trace!(era = era_id.value(), "not handling message; era too old");
                     return Effects::new();
                 }
-                self.iter_past(era_id, PAST_EVIDENCE_ERAS)
-                    .flat_map(|e_id| {
-                        self.delegate_to_era(effect_builder, rng, e_id, |consensus, _| {
-                            consensus.request_evidence(sender, &pub_key)
-                        })
-                    })
-                    .collect()
+                let era_ids = self.iter_past(era_id, PAST_EVIDENCE_ERAS).collect();
+                self.send_evidence(effect_builder, rng, sender, pub_key, era_ids)
+            }
+        }
+    }
+
+    /// Sends the evidence against `pub_key` from the given eras to `peer`, unless we have already
+    /// done so `MAX_EVIDENCE_SENDS_PER_PEER` times in the current era.
+    fn send_evidence<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        peer: NodeId,
+        pub_key: PublicKey,
+        era_ids: Vec<EraId>,
+    ) -> Effects<Event> {
+        let sent = self
+            .evidence_sent
+            .entry((peer, pub_key.clone()))
+            .or_default();
+        if *sent >= MAX_EVIDENCE_SENDS_PER_PEER {
+            debug!(%peer, %pub_key, "not sending evidence; already sent too often");
+            return Effects::new();
+        }
+        *sent = sent.saturating_add(1);
+        era_ids
+            .into_iter()
+            .flat_map(|e_id| {
+                self.delegate_to_era(effect_builder, rng, e_id, |consensus, _| {
+                    consensus.request_evidence(peer, &pub_key)
+                })
+            })
+            .collect()
+    }
+
+    /// Persists the evidence against `pub_key` from `era_id`, unless we already have evidence
+    /// against that validator.
+    fn persist_evidence<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        era_id: EraId,
+        pub_key: &PublicKey,
+    ) -> Effects<Event> {
+        if self.persisted_evidence.contains(pub_key) {
+            return Effects::new();
+        }
+        let evidence = match self
+            .open_eras
+            .get(&era_id)
+            .and_then(|era| era.consensus.evidence(pub_key))
+        {
+            Some(evidence) => evidence,
+            None => {
+                warn!(%pub_key, era = era_id.value(), "missing evidence for validator");
+                return Effects::new();
             }
+        };
+        self.persisted_evidence
+            .insert(pub_key.clone(), era_id, evidence);
+        self.save_persisted_evidence(effect_builder)
+    }
+
+    /// Saves the persisted evidence to storage, once the evidence from before a restart has been
+    /// loaded.
+    fn save_persisted_evidence<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event> {
+        if !self.persisted_evidence_loaded {
+            return Effects::new();
         }
+        effect_builder
+            .save_state(EVIDENCE_STATE_KEY.into(), self.persisted_evidence.clone())
+            .ignore()
+    }
+
+    /// Handles the evidence persisted before a restart: adds it to the open eras and merges it
+    /// with the evidence observed since.
+    pub(super) fn handle_persisted_evidence_loaded<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        mut loaded: PersistedEvidence,
+    ) -> Effects<Event> {
+        if let Some(evidence_only_era_id) = self.current_era.checked_sub(PAST_OPEN_ERAS) {
+            loaded.prune(evidence_only_era_id);
+        }
+        let mut era_ids: Vec<EraId> = self.open_eras.keys().copied().collect();
+        era_ids.sort();
+        let mut effects = Effects::new();
+        for era_id in era_ids {
+            for evidence in loaded.in_era(era_id).cloned().collect_vec() {
+                effects.extend(self.delegate_to_era(
+                    effect_builder,
+                    rng,
+                    era_id,
+                    |consensus, _| consensus.restore_evidence(evidence, clock::now()),
+                ));
+            }
+        }
+        info!(
+            count = loaded.eras().len(),
+            "loaded persisted equivocation evidence"
+        );
+        self.persisted_evidence.merge(loaded);
+        self.persisted_evidence_loaded = true;
+        effects.extend(self.save_persisted_evidence(effect_builder));
+        effects
+    }
+
+    /// Returns the leader schedule of the current era.
+    pub(super) fn leader_schedule(&self) -> Option<(EraId, LeaderSchedule<ClContext>)> {
+        let era = self.open_eras.get(&self.current_era)?;
+        Some((self.current_era, era.consensus.leader_schedule()?))
+    }
+
+    /// Returns the era of the persisted evidence, by faulty validator.
+    pub(super) fn get_evidence(&self) -> BTreeMap<PublicKey, EraId> {
+        self.persisted_evidence.eras()
     }
 
     pub(super) fn handle_new_block_payload<REv: ReactorEventT>(
@@ -659,7 +851,7 @@ impl EraSupervisor {
         }
         let proposed_block = ProposedBlock::new(block_payload, block_context);
         self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
-            consensus.propose(proposed_block, Timestamp::now())
+            consensus.propose(proposed_block, clock::now())
         })
     }
 
@@ -668,22 +860,29 @@ impl EraSupervisor {
         effect_builder: EffectBuilder<REv>,
         block_header: BlockHeader,
     ) -> Effects<Event> {
-        let our_pk = self.public_signing_key.clone();
-        let our_sk = self.secret_signing_key.clone();
         let era_id = block_header.era_id();
+        let (our_signer, our_pk) = self.signing_keys_in(era_id);
         self.executed_block(&block_header);
-        let mut effects = if self.is_validator_in(&our_pk, era_id) {
-            effect_builder
-                .announce_created_finality_signature(FinalitySignature::new(
-                    block_header.hash(),
-                    era_id,
-                    &our_sk,
-                    our_pk,
-                ))
-                .ignore()
-        } else {
-            Effects::new()
-        };
+        let mut effects = Effects::new();
+        if self.is_validator_in(&our_pk, era_id) {
+            let block_hash = block_header.hash();
+            effects.extend(
+                async move {
+                    match our_signer
+                        .finality_signature(block_hash, era_id, our_pk)
+                        .await
+                    {
+                        Ok(finality_signature) => {
+                            effect_builder
+                                .announce_created_finality_signature(finality_signature)
+                                .await
+                        }
+                        Err(error) => error!(%error, "failed to create finality signature"),
+                    }
+                }
+                .ignore(),
+            );
+        }
         if era_id < self.current_era {
             trace!(era = era_id.value(), "executed block in old era");
             return effects;
@@ -691,7 +890,7 @@ impl EraSupervisor {
         if block_header.is_switch_block() {
             if let Some(era) = self.open_eras.get_mut(&era_id) {
                 // This was the era's last block. Schedule deactivating this era.
-                let delay = Timestamp::now()
+                let delay = clock::now()
                     .saturating_diff(block_header.timestamp())
                     .into();
                 let faulty_num = era.consensus.validators_with_evidence().len();
@@ -772,7 +971,7 @@ impl EraSupervisor {
         {
             effects.extend(
                 self.delegate_to_era(effect_builder, rng, era_id, |consensus, _| {
-                    consensus.resolve_validity(proposed_block, valid, Timestamp::now())
+                    consensus.resolve_validity(proposed_block, valid, clock::now())
                 }),
             );
         }
@@ -857,7 +1056,7 @@ impl EraSupervisor {
                 .ignore()
             }
             ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => {
-                let timediff = timestamp.saturating_diff(Timestamp::now());
+                let timediff = timestamp.saturating_diff(clock::now());
                 effect_builder
                     .set_timeout(timediff.into())
                     .event(move |_| Event::Timer {
@@ -869,6 +1068,29 @@ impl EraSupervisor {
             ProtocolOutcome::QueueAction(action_id) => effect_builder
                 .immediately()
                 .event(move |()| Event::Action { era_id, action_id }),
+            ProtocolOutcome::CreateSignature {
+                hash,
+                instance_id,
+                unit_seq_number,
+            } => {
+                let (signer, public_key) = self.signing_keys_in(era_id);
+                let unit = unit_seq_number.map(|seq_number| UnitMetadata {
+                    instance_id,
+                    seq_number,
+                });
+                async move {
+                    signer
+                        .sign(hash.as_ref().to_vec(), public_key, unit)
+                        .await
+                        .map_err(|error| error!(%error, %hash, "failed to sign consensus message"))
+                        .ok()
+                }
+                .event(move |signature| Event::Signature {
+                    era_id,
+                    hash,
+                    signature,
+                })
+            }
             ProtocolOutcome::CreateNewBlock(block_context) => {
                 let accusations = self
                     .iter_past(era_id, PAST_EVIDENCE_ERAS)
@@ -1007,8 +1229,9 @@ impl EraSupervisor {
             ProtocolOutcome::NewEvidence(pub_key) => {
                 info!(%pub_key, era = era_id.value(), "validator equivocated");
                 let mut effects = effect_builder
-                    .announce_fault_event(era_id, pub_key.clone(), Timestamp::now())
+                    .announce_fault_event(era_id, pub_key.clone(), clock::now())
                     .ignore();
+                effects.extend(self.persist_evidence(effect_builder, era_id, &pub_key));
                 for e_id in self.iter_future(era_id, PAST_EVIDENCE_ERAS) {
                     let proposed_blocks = if let Some(era) = self.open_eras.get_mut(&e_id) {
                         era.resolve_evidence_and_mark_faulty(&pub_key)
@@ -1021,21 +1244,17 @@ impl EraSupervisor {
                             rng,
                             e_id,
                             |consensus, _| {
-                                consensus.resolve_validity(proposed_block, true, Timestamp::now())
+                                consensus.resolve_validity(proposed_block, true, clock::now())
                             },
                         ));
                     }
                 }
                 effects
             }
-            ProtocolOutcome::SendEvidence(sender, pub_key) => self
-                .iter_past_other(era_id, PAST_EVIDENCE_ERAS)
-                .flat_map(|e_id| {
-                    self.delegate_to_era(effect_builder, rng, e_id, |consensus, _| {
-                        consensus.request_evidence(sender, &pub_key)
-                    })
-                })
-                .collect(),
+            ProtocolOutcome::SendEvidence(sender, pub_key) => {
+                let era_ids = self.iter_past_other(era_id, PAST_EVIDENCE_ERAS).collect();
+                self.send_evidence(effect_builder, rng, sender, pub_key, era_ids)
+            }
             ProtocolOutcome::WeAreFaulty => Default::default(),
             ProtocolOutcome::DoppelgangerDetected => Default::default(),
             ProtocolOutcome::FttExceeded => effect_builder
@@ -1070,7 +1289,7 @@ impl EraSupervisor {
         &self,
         responder: Responder<Option<(PublicKey, Option<TimeDiff>)>>,
     ) -> Effects<Event> {
-        let public_key = self.public_signing_key.clone();
+        let (_, public_key) = self.signing_keys_in(self.current_era);
         let round_length = self
             .open_eras
             .get(&self.current_era)
@@ -1104,6 +1323,60 @@ impl EraSupervisor {
     pub(crate) fn current_era(&self) -> EraId {
         self.current_era
     }
+
+    /// Puts the era supervisor into drain mode.
+    ///
+    /// Consensus in the current era is paused, so no further blocks are proposed and no new units
+    /// are created, and validators in eras created later on are not activated. Blocks that have
+    /// already been finalized will still be executed.
+    pub(crate) fn begin_drain(&mut self) {
+        if self.is_draining() {
+            return;
+        }
+        info!(
+            next_block_height = self.next_block_height,
+            next_executed_height = self.next_executed_height,
+            "era supervisor draining"
+        );
+        self.drain_target_height = Some(self.next_block_height);
+        self.update_consensus_pause();
+    }
+
+    /// Returns whether the era supervisor is in drain mode.
+    pub(crate) fn is_draining(&self) -> bool {
+        self.drain_target_height.is_some()
+    }
+
+    /// Returns `true` if the era supervisor is draining and every block finalized before the drain
+    /// was requested has been executed.
+    pub(crate) fn is_drained(&self) -> bool {
+        self.drain_target_height
+            .map_or(false, |target| self.next_executed_height >= target)
+    }
+}
+
+impl HealthCheck for EraSupervisor {
+    const NAME: &'static str = "consensus";
+
+    fn health(&self) -> Health {
+        let mut health = Health::ready();
+        if !self.open_eras.contains_key(&self.current_era) {
+            health.fail(format!("current era {} not initialized", self.current_era));
+        }
+        if self.is_draining() {
+            health.degrade("draining");
+        }
+        let execution_delay = self
+            .next_block_height
+            .saturating_sub(self.next_executed_height);
+        if execution_delay > self.config.highway.max_execution_delay {
+            health.degrade(format!(
+                "paused while execution is {} blocks behind finalization",
+                execution_delay
+            ));
+        }
+        health
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{EraId, PublicKey};
+
+/// The key under which the evidence is kept in the state store.
+pub(super) const EVIDENCE_STATE_KEY: &[u8] = b"consensus evidence";
+
+/// Equivocation evidence that survives a restart of the node.
+///
+/// There is at most one piece of evidence per validator: Evidence from one era is enough to mark
+/// the validator as faulty in the following ones, so later evidence against the same validator is
+/// not stored.
+#[derive(DataSize, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PersistedEvidence {
+    /// The era and the serialized evidence, by the faulty validator's public key.
+    entries: BTreeMap<PublicKey, (EraId, Vec<u8>)>,
+}
+
+impl PersistedEvidence {
+    /// Returns whether there is evidence against the validator.
+    pub(super) fn contains(&self, pub_key: &PublicKey) -> bool {
+        self.entries.contains_key(pub_key)
+    }
+
+    /// Adds the evidence from `era_id`, unless there is already evidence against the validator.
+    ///
+    /// Returns `true` if the evidence was added.
+    pub(super) fn insert(&mut self, pub_key: PublicKey, era_id: EraId, evidence: Vec<u8>) -> bool {
+        if self.contains(&pub_key) {
+            return false;
+        }
+        self.entries.insert(pub_key, (era_id, evidence));
+        true
+    }
+
+    /// Adds all entries from `other` against validators we don't have evidence for yet.
+    pub(super) fn merge(&mut self, other: PersistedEvidence) {
+        for (pub_key, (era_id, evidence)) in other.entries {
+            self.insert(pub_key, era_id, evidence);
+        }
+    }
+
+    /// Removes the evidence from eras before `earliest_era_id`.
+    pub(super) fn prune(&mut self, earliest_era_id: EraId) {
+        self.entries
+            .retain(|_, (era_id, _)| *era_id >= earliest_era_id);
+    }
+
+    /// Returns the serialized evidence from the given era.
+    pub(super) fn in_era(&self, era_id: EraId) -> impl Iterator<Item = &Vec<u8>> {
+        self.entries
+            .values()
+            .filter(move |(e_id, _)| *e_id == era_id)
+            .map(|(_, evidence)| evidence)
+    }
+
+    /// Returns the era of the evidence, by faulty validator.
+    pub(super) fn eras(&self) -> BTreeMap<PublicKey, EraId> {
+        self.entries
+            .iter()
+            .map(|(pub_key, (era_id, _))| (pub_key.clone(), *era_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_keep_one_entry_per_validator() {
+        let mut rng = TestRng::new();
+        let alice = PublicKey::random(&mut rng);
+        let bob = PublicKey::random(&mut rng);
+
+        let mut evidence = PersistedEvidence::default();
+        assert!(evidence.insert(alice.clone(), EraId::new(3), vec![1]));
+        assert!(!evidence.insert(alice.clone(), EraId::new(4), vec![2]));
+
+        let mut loaded = PersistedEvidence::default();
+        loaded.insert(alice.clone(), EraId::new(2), vec![3]);
+        loaded.insert(bob.clone(), EraId::new(4), vec![4]);
+        evidence.merge(loaded);
+        assert_eq!(
+            evidence.in_era(EraId::new(3)).collect::<Vec<_>>(),
+            vec![&vec![1]]
+        );
+        assert_eq!(evidence.eras()[&bob], EraId::new(4));
+
+        evidence.prune(EraId::new(4));
+        assert!(!evidence.contains(&alice));
+        assert!(evidence.contains(&bob));
+    }
+}
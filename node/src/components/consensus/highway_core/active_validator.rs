@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -14,7 +15,7 @@ use casper_types::{TimeDiff, Timestamp};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit, Weight},
     validators::ValidatorIndex,
     ENABLE_ENDORSEMENTS,
@@ -36,12 +37,28 @@ pub(crate) enum Effect<C: Context> {
     /// `propose` needs to be called with a value for a new block with the specified block context
     /// and parent value.
     RequestNewBlock(BlockContext<C>),
+    /// The hash of a new vertex needs to be signed, because our secret can't sign it right away.
+    ///
+    /// `on_signature` needs to be called with the signature. If the vertex is a unit,
+    /// `unit_seq_number` is its sequence number.
+    RequestSignature {
+        hash: C::Hash,
+        unit_seq_number: Option<u64>,
+    },
     /// This validator is faulty.
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
 }
 
+/// A vertex created by us, waiting for its signature.
+#[derive(Clone, DataSize, Debug)]
+enum UnsignedVertex<C: Context> {
+    Unit(HashedWireUnit<C>),
+    Ping(Timestamp, C::InstanceId),
+    Endorsement(Endorsement<C>),
+}
+
 /// A validator that actively participates in consensus by creating new vertices.
 ///
 /// It implements the Highway schedule. The protocol proceeds in rounds, and in each round one
@@ -80,6 +97,8 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// Our new vertices waiting for their signatures, by hash.
+    pending_signatures: BTreeMap<C::Hash, UnsignedVertex<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -126,6 +145,7 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            pending_signatures: BTreeMap::new(),
         };
         let mut effects = av.schedule_timer(start_time, state);
         effects.push(av.send_ping(current_time, instance_id));
@@ -197,7 +217,7 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id + self.witness_offset(r_len) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
+                if let Some(witness_effect) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
                 {
                     if self
@@ -206,7 +226,7 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(witness_effect);
                     return effects;
                 }
             }
@@ -221,10 +241,14 @@ impl<C: Context> ActiveValidator<C> {
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, or requests its signature.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Effect<C> {
+        let hash = Ping::<C>::hash(self.vidx, timestamp, instance_id);
+        self.sign(hash, UnsignedVertex::Ping(timestamp, instance_id))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -258,17 +282,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.push(self.endorse(uhash));
         }
         effects
     }
@@ -292,7 +310,6 @@ impl<C: Context> ActiveValidator<C> {
                 unit.new_hash_obs(state, vidx)
             })
             .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
             .collect()
     }
 
@@ -314,9 +331,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -359,7 +374,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -408,7 +422,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns the effect creating a new unit with the given data, and the correct sequence
+    /// number, or requesting its signature.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -418,7 +433,7 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
@@ -426,6 +441,14 @@ impl<C: Context> ActiveValidator<C> {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        if self
+            .pending_signatures
+            .values()
+            .any(|unsigned| matches!(unsigned, UnsignedVertex::Unit(_)))
+        {
+            info!("not voting - waiting for the signature of our previous unit");
+            return None;
+        }
         if let Some((prop_context, _)) = self.next_proposal.take() {
             warn!(?prop_context, "canceling proposal due to unit");
         }
@@ -458,14 +481,93 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
-        write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
-            panic!(
-                "should successfully write unit's hash to {:?}, got {:?}",
-                self.unit_file, err
-            )
-        });
-        Some(swunit)
+        Some(self.sign(hwunit.hash(), UnsignedVertex::Unit(hwunit)))
+    }
+
+    /// Returns the effect creating our new vertex with the given hash if our secret can sign it
+    /// right away, or requesting its signature otherwise.
+    fn sign(&mut self, hash: C::Hash, unsigned: UnsignedVertex<C>) -> Effect<C> {
+        if let Some(signature) = self.secret.sign(&hash) {
+            return Effect::NewVertex(ValidVertex(self.signed_vertex(unsigned, signature)));
+        }
+        let unit_seq_number = match &unsigned {
+            UnsignedVertex::Unit(hwunit) => Some(hwunit.wire_unit().seq_number),
+            UnsignedVertex::Ping(..) | UnsignedVertex::Endorsement(_) => None,
+        };
+        self.pending_signatures.insert(hash, unsigned);
+        Effect::RequestSignature {
+            hash,
+            unit_seq_number,
+        }
+    }
+
+    /// Returns our new vertex with the given signature.
+    ///
+    /// Units are written to the unit file before they are returned.
+    fn signed_vertex(&self, unsigned: UnsignedVertex<C>, signature: C::Signature) -> Vertex<C> {
+        match unsigned {
+            UnsignedVertex::Unit(hashed_wire_unit) => {
+                let swunit = SignedWireUnit {
+                    hashed_wire_unit,
+                    signature,
+                };
+                write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
+                    panic!(
+                        "should successfully write unit's hash to {:?}, got {:?}",
+                        self.unit_file, err
+                    )
+                });
+                Vertex::Unit(swunit)
+            }
+            UnsignedVertex::Ping(timestamp, instance_id) => Vertex::Ping(Ping::with_signature(
+                self.vidx,
+                timestamp,
+                instance_id,
+                signature,
+            )),
+            UnsignedVertex::Endorsement(endorsement) => {
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        }
+    }
+
+    /// Returns actions a validator needs to take upon receiving the signature requested via
+    /// `Effect::RequestSignature`, or `None` if it couldn't be created.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let unsigned = match self.pending_signatures.remove(hash) {
+            Some(unsigned) => unsigned,
+            None => {
+                warn!(%hash, "received signature of unknown vertex");
+                return vec![];
+            }
+        };
+        let signature = match signature {
+            Some(signature) => signature,
+            None => {
+                warn!(%hash, "dropping our new vertex; it couldn't be signed");
+                return vec![];
+            }
+        };
+        if let UnsignedVertex::Unit(hwunit) = &unsigned {
+            if let Some(fault) = state.maybe_fault(self.vidx) {
+                return vec![Effect::WeAreFaulty(fault.clone())];
+            }
+            // We don't create any other units while waiting for the signature, so this can only
+            // happen if our previous unit was added after this one was created, e.g. from a
+            // doppelganger.
+            if hwunit.wire_unit().panorama[self.vidx] != state.panorama()[self.vidx] {
+                error!(%hash, "signed unit would be equivocation; dropping it");
+                return vec![];
+            }
+        }
+        vec![Effect::NewVertex(ValidVertex(
+            self.signed_vertex(unsigned, signature),
+        ))]
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -559,11 +661,10 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, or requests its signature.
+    fn endorse(&mut self, vhash: &C::Hash) -> Effect<C> {
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        self.sign(endorsement.hash(), UnsignedVertex::Endorsement(endorsement))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -988,7 +1089,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
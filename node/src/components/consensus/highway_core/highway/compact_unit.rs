@@ -0,0 +1,488 @@
+//! A compact wire encoding of units.
+//!
+//! A `WireUnit` carries one observation, usually a hash, for every validator, so its size grows
+//! linearly with the size of the validator set. A `CompactUnit` instead only lists the
+//! observations that differ from a base panorama, and refers to units the recipient is known to
+//! have by their sequence number instead of their hash. The consensus value, if any, is
+//! compressed.
+//!
+//! The base is the view the recipient reported in its latest state request, if we received one:
+//! the recipient remembers the views it recently sent, so it can resolve the base by the view's
+//! hash. Otherwise, e.g. when gossiping, the base is the panorama of the creator's previous unit.
+//!
+//! The recipient reconstructs the `SignedWireUnit` from its own protocol state and checks it
+//! against the unit hash that is sent along. If that fails, e.g. because it is missing a unit the
+//! base refers to or because an equivocation made a sequence number ambiguous, it can still
+//! request the full unit by its hash.
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    io::{self, Read},
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::Timestamp;
+
+use crate::components::consensus::{
+    highway_core::{
+        highway::{HashedWireUnit, SignedWireUnit, WireUnit},
+        state::{IndexObservation, IndexPanorama, Observation, Panorama, State},
+        validators::{ValidatorIndex, ValidatorMap},
+    },
+    traits::Context,
+};
+
+/// The zstd compression level used for consensus values.
+const VALUE_COMPRESSION_LEVEL: i32 = 3;
+
+/// Never decompress a consensus value to more than this many bytes.
+const MAX_DECOMPRESSED_VALUE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// An error reconstructing a unit from its compact encoding.
+#[derive(Debug, Error)]
+pub(crate) enum CompactUnitError {
+    /// The creator's previous unit, which the panorama delta is based on, is unknown.
+    #[error("the creator's unit with sequence number {0} is unknown")]
+    MissingPrevious(u64),
+    /// The view the panorama delta is based on is not among the ones we recently sent.
+    #[error("the view the panorama delta is based on is unknown")]
+    UnknownView,
+    /// A unit the base view refers to is unknown, and the delta doesn't override it.
+    #[error("the base observation of validator {0:?} is unknown")]
+    UnresolvedBase(ValidatorIndex),
+    /// A unit referred to by its sequence number is unknown.
+    #[error("no unit by {0:?} with sequence number {1} is known")]
+    MissingObservation(ValidatorIndex, u64),
+    /// The panorama delta refers to a validator that doesn't exist.
+    #[error("the panorama delta refers to unknown validator {0:?}")]
+    UnknownValidator(ValidatorIndex),
+    /// The consensus value could not be decompressed.
+    #[error("failed to decompress the consensus value: {0}")]
+    Decompress(#[source] io::Error),
+    /// The decompressed consensus value exceeds the size limit.
+    #[error("the decompressed consensus value exceeds the size limit")]
+    ValueTooLarge,
+    /// The consensus value could not be deserialized.
+    #[error("failed to deserialize the consensus value: {0}")]
+    Deserialize(#[source] bincode::Error),
+    /// The reconstructed unit has a different hash than the one that was sent.
+    #[error("the reconstructed unit's hash doesn't match")]
+    HashMismatch,
+}
+
+/// An observation in a panorama delta.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum CompactObservation<C>
+where
+    C: Context,
+{
+    /// No unit by that validator was observed yet.
+    None,
+    /// The validator's latest unit, identified by its hash.
+    Correct(C::Hash),
+    /// The validator's latest unit, identified by its sequence number. Only used for units the
+    /// recipient is known to have.
+    Seq(u64),
+    /// The validator has been seen to be faulty.
+    Faulty,
+}
+
+/// The panorama a `CompactUnit`'s panorama delta is based on.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum PanoramaBase<C>
+where
+    C: Context,
+{
+    /// The panorama of the creator's previous unit.
+    PreviousUnit,
+    /// The view with the given hash that the recipient reported in a latest state request: for
+    /// each validator, the unit with the highest sequence number the recipient had.
+    View(C::Hash),
+}
+
+/// Returns the hash identifying a view sent in a latest state request.
+pub(crate) fn view_hash<C: Context>(view: &IndexPanorama) -> C::Hash {
+    C::hash(&bincode::serialize(view).expect("should serialize index panorama"))
+}
+
+/// A signed unit with a compressed value and its panorama encoded as a delta against a base
+/// panorama.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct CompactUnit<C>
+where
+    C: Context,
+{
+    hash: C::Hash,
+    signature: C::Signature,
+    creator: ValidatorIndex,
+    seq_number: u64,
+    timestamp: Timestamp,
+    round_exp: u8,
+    endorsed: BTreeSet<C::Hash>,
+    /// The panorama the delta is based on.
+    base: PanoramaBase<C>,
+    /// The entries in which the unit's panorama differs from the base.
+    panorama_delta: Vec<(ValidatorIndex, CompactObservation<C>)>,
+    /// The zstd-compressed, serialized consensus value.
+    value: Option<Vec<u8>>,
+}
+
+impl<C: Context> CompactUnit<C> {
+    /// Encodes the unit for a recipient that reported `their_view` in its latest state request,
+    /// using that view as the base, or for any recipient if `their_view` is `None`, using the
+    /// creator's previous unit as the base.
+    ///
+    /// Returns `None` if we are missing the creator's previous unit ourselves.
+    pub(crate) fn encode(
+        swunit: &SignedWireUnit<C>,
+        state: &State<C>,
+        their_view: Option<&IndexPanorama>,
+    ) -> Option<Self> {
+        let wunit = swunit.wire_unit();
+        let (base, base_panorama) = match their_view {
+            Some(view) => (
+                PanoramaBase::View(view_hash::<C>(view)),
+                view_panorama(view, state),
+            ),
+            None => {
+                let panorama = match wunit.previous() {
+                    None => Panorama::new(wunit.panorama.len()),
+                    Some(prev_hash) => state.maybe_unit(prev_hash)?.panorama.clone(),
+                };
+                (
+                    PanoramaBase::PreviousUnit,
+                    panorama.iter().cloned().map(Some).collect(),
+                )
+            }
+        };
+        if base_panorama.len() != wunit.panorama.len() {
+            return None;
+        }
+
+        let known_seq = |vidx: ValidatorIndex, hash: &C::Hash| {
+            let unit = state.maybe_unit(hash)?;
+            match their_view?.get(vidx)? {
+                IndexObservation::NextSeq(next_seq) if unit.seq_number < *next_seq => {
+                    Some(unit.seq_number)
+                }
+                IndexObservation::NextSeq(_) | IndexObservation::Faulty => None,
+            }
+        };
+        // Entries whose base we can't resolve ourselves are always included.
+        let panorama_delta = wunit
+            .panorama
+            .enumerate()
+            .zip(base_panorama.iter())
+            .filter(|((_, obs), base_obs)| base_obs.as_ref() != Some(*obs))
+            .map(|((vidx, obs), _)| {
+                let compact_obs = match obs {
+                    Observation::None => CompactObservation::None,
+                    Observation::Faulty => CompactObservation::Faulty,
+                    Observation::Correct(hash) => known_seq(vidx, hash)
+                        .map_or(CompactObservation::Correct(*hash), CompactObservation::Seq),
+                };
+                (vidx, compact_obs)
+            })
+            .collect();
+
+        let value = match &wunit.value {
+            None => None,
+            Some(value) => {
+                let serialized = bincode::serialize(value).ok()?;
+                Some(
+                    zstd::stream::encode_all(serialized.as_slice(), VALUE_COMPRESSION_LEVEL)
+                        .ok()?,
+                )
+            }
+        };
+
+        Some(CompactUnit {
+            hash: swunit.hash(),
+            signature: swunit.signature,
+            creator: wunit.creator,
+            seq_number: wunit.seq_number,
+            timestamp: wunit.timestamp,
+            round_exp: wunit.round_exp,
+            endorsed: wunit.endorsed.clone(),
+            base,
+            panorama_delta,
+            value,
+        })
+    }
+
+    /// Returns the hash of the encoded unit.
+    pub(crate) fn hash(&self) -> C::Hash {
+        self.hash
+    }
+
+    /// Returns whether the unit contains a consensus value, i.e. is a proposal.
+    pub(crate) fn has_value(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Reconstructs the signed unit, using our protocol state and the views we recently sent in
+    /// latest state requests to resolve the panorama delta.
+    ///
+    /// The signature is not verified: the result still has to be validated like any other unit.
+    pub(crate) fn decode(
+        self,
+        state: &State<C>,
+        instance_id: C::InstanceId,
+        sent_views: &VecDeque<(C::Hash, IndexPanorama)>,
+    ) -> Result<SignedWireUnit<C>, CompactUnitError> {
+        let find_unit = |vidx: ValidatorIndex, seq_number: u64| match state.panorama().get(vidx) {
+            Some(Observation::Correct(latest)) => state.find_in_swimlane(latest, seq_number),
+            Some(Observation::None) | Some(Observation::Faulty) | None => None,
+        };
+
+        let mut panorama: ValidatorMap<Option<Observation<C>>> = match self.base {
+            PanoramaBase::View(hash) => {
+                let view = sent_views
+                    .iter()
+                    .find(|(view_hash, _)| *view_hash == hash)
+                    .map(|(_, view)| view)
+                    .ok_or(CompactUnitError::UnknownView)?;
+                view_panorama(view, state)
+            }
+            PanoramaBase::PreviousUnit => {
+                let panorama = match self.seq_number.checked_sub(1) {
+                    None => Panorama::new(state.panorama().len()),
+                    Some(prev_seq) => {
+                        let prev_hash = find_unit(self.creator, prev_seq)
+                            .ok_or(CompactUnitError::MissingPrevious(prev_seq))?;
+                        state.unit(prev_hash).panorama.clone()
+                    }
+                };
+                panorama.iter().cloned().map(Some).collect()
+            }
+        };
+        for (vidx, compact_obs) in self.panorama_delta {
+            let obs = match compact_obs {
+                CompactObservation::None => Observation::None,
+                CompactObservation::Faulty => Observation::Faulty,
+                CompactObservation::Correct(hash) => Observation::Correct(hash),
+                CompactObservation::Seq(seq_number) => Observation::Correct(
+                    *find_unit(vidx, seq_number)
+                        .ok_or(CompactUnitError::MissingObservation(vidx, seq_number))?,
+                ),
+            };
+            if !panorama.has(vidx) {
+                return Err(CompactUnitError::UnknownValidator(vidx));
+            }
+            panorama[vidx] = Some(obs);
+        }
+        let panorama = panorama
+            .enumerate()
+            .map(|(vidx, obs)| obs.clone().ok_or(CompactUnitError::UnresolvedBase(vidx)))
+            .collect::<Result<Panorama<C>, _>>()?;
+
+        let value = match self.value {
+            None => None,
+            Some(compressed) => {
+                let mut serialized = Vec::new();
+                zstd::stream::read::Decoder::new(compressed.as_slice())
+                    .and_then(|decoder| {
+                        decoder
+                            .take(MAX_DECOMPRESSED_VALUE_SIZE + 1)
+                            .read_to_end(&mut serialized)
+                    })
+                    .map_err(CompactUnitError::Decompress)?;
+                if serialized.len() as u64 > MAX_DECOMPRESSED_VALUE_SIZE {
+                    return Err(CompactUnitError::ValueTooLarge);
+                }
+                Some(bincode::deserialize(&serialized).map_err(CompactUnitError::Deserialize)?)
+            }
+        };
+
+        let wunit = WireUnit {
+            panorama,
+            creator: self.creator,
+            instance_id,
+            value,
+            seq_number: self.seq_number,
+            timestamp: self.timestamp,
+            round_exp: self.round_exp,
+            endorsed: self.endorsed,
+        };
+        let hashed_wire_unit = HashedWireUnit::new(wunit);
+        if hashed_wire_unit.hash() != self.hash {
+            return Err(CompactUnitError::HashMismatch);
+        }
+        Ok(SignedWireUnit {
+            hashed_wire_unit,
+            signature: self.signature,
+        })
+    }
+}
+
+/// Returns the panorama in which each validator's entry is the unit with the highest sequence
+/// number in `view`, according to our protocol state.
+///
+/// Entries referring to units we don't have ourselves are `None`.
+fn view_panorama<C: Context>(
+    view: &IndexPanorama,
+    state: &State<C>,
+) -> ValidatorMap<Option<Observation<C>>> {
+    view.enumerate()
+        .map(|(vidx, index_obs)| match *index_obs {
+            IndexObservation::Faulty => Some(Observation::Faulty),
+            IndexObservation::NextSeq(next_seq) => match next_seq.checked_sub(1) {
+                None => Some(Observation::None),
+                Some(seq_number) => match state.panorama().get(vidx)? {
+                    Observation::Correct(latest) => state
+                        .find_in_swimlane(latest, seq_number)
+                        .map(|hash| Observation::Correct(*hash)),
+                    Observation::None | Observation::Faulty => None,
+                },
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::consensus::highway_core::{
+        highway_testing::TEST_INSTANCE_ID,
+        state::tests::{AddUnitError, TestContext, ALICE, BOB, CAROL, N, WEIGHTS},
+    };
+
+    fn sent_views(views: &[&IndexPanorama]) -> VecDeque<(u64, IndexPanorama)> {
+        views
+            .iter()
+            .map(|view| (view_hash::<TestContext>(view), (*view).clone()))
+            .collect()
+    }
+
+    #[test]
+    fn compact_unit_round_trip() -> Result<(), AddUnitError<TestContext>> {
+        let mut state = State::new_test(WEIGHTS, 0);
+        let a0 = add_unit!(state, ALICE, 0xA; N, N, N)?;
+        let b0 = add_unit!(state, BOB, None; a0, N, N)?;
+        let c0 = add_unit!(state, CAROL, None; a0, b0, N)?;
+        let a1 = add_unit!(state, ALICE, None; a0, b0, c0)?;
+        let swunit = state.wire_unit(&a1, TEST_INSTANCE_ID).unwrap();
+
+        // Without knowing the recipient's view, only entries unchanged since the creator's
+        // previous unit are omitted.
+        let compact = CompactUnit::encode(&swunit, &state, None).expect("should encode");
+        assert_eq!(compact.base, PanoramaBase::PreviousUnit);
+        assert_eq!(
+            compact.panorama_delta,
+            vec![
+                (ALICE, CompactObservation::Correct(a0)),
+                (BOB, CompactObservation::Correct(b0)),
+                (CAROL, CompactObservation::Correct(c0)),
+            ]
+        );
+        assert_eq!(
+            compact
+                .decode(&state, TEST_INSTANCE_ID, &VecDeque::new())
+                .expect("should decode"),
+            swunit
+        );
+
+        // Entries the recipient's view already implies are omitted.
+        let their_view: IndexPanorama = vec![IndexObservation::NextSeq(1); WEIGHTS.len()].into();
+        let compact = CompactUnit::encode(&swunit, &state, Some(&their_view)).unwrap();
+        assert!(compact.panorama_delta.is_empty());
+        assert_eq!(
+            compact
+                .clone()
+                .decode(&state, TEST_INSTANCE_ID, &sent_views(&[&their_view]))
+                .unwrap(),
+            swunit
+        );
+        assert!(matches!(
+            compact.decode(&state, TEST_INSTANCE_ID, &VecDeque::new()),
+            Err(CompactUnitError::UnknownView)
+        ));
+
+        // Units the recipient has are referred to by sequence number.
+        let their_view = IndexPanorama::from_panorama(state.panorama(), &state);
+        let compact = CompactUnit::encode(&swunit, &state, Some(&their_view)).unwrap();
+        assert_eq!(
+            compact.panorama_delta,
+            vec![(ALICE, CompactObservation::Seq(0))]
+        );
+        assert_eq!(
+            compact
+                .decode(&state, TEST_INSTANCE_ID, &sent_views(&[&their_view]))
+                .unwrap(),
+            swunit
+        );
+
+        // A unit with a value survives compression.
+        let swunit = state.wire_unit(&a0, TEST_INSTANCE_ID).unwrap();
+        let compact = CompactUnit::encode(&swunit, &state, None).unwrap();
+        assert!(compact.panorama_delta.is_empty());
+        assert_eq!(
+            compact
+                .decode(&state, TEST_INSTANCE_ID, &VecDeque::new())
+                .unwrap(),
+            swunit
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compact_unit_requires_base() -> Result<(), AddUnitError<TestContext>> {
+        let mut state = State::new_test(WEIGHTS, 0);
+        let a0 = add_unit!(state, ALICE, 0xA; N, N, N)?;
+        let a1 = add_unit!(state, ALICE, None; a0, N, N)?;
+        let swunit = state.wire_unit(&a1, TEST_INSTANCE_ID).unwrap();
+        let empty_state = State::new_test(WEIGHTS, 0);
+
+        // A recipient that doesn't have a0 yet can decode a unit based on its view.
+        let empty_view = IndexPanorama::from_panorama(&Panorama::new(WEIGHTS.len()), &state);
+        let compact = CompactUnit::encode(&swunit, &state, Some(&empty_view)).unwrap();
+        assert_eq!(
+            compact.panorama_delta,
+            vec![(ALICE, CompactObservation::Correct(a0))]
+        );
+        assert_eq!(
+            compact
+                .decode(&empty_state, TEST_INSTANCE_ID, &sent_views(&[&empty_view]))
+                .unwrap(),
+            swunit
+        );
+
+        // But it fails to decode one based on the creator's previous unit.
+        let compact = CompactUnit::encode(&swunit, &state, None).unwrap();
+        assert!(matches!(
+            compact.decode(&empty_state, TEST_INSTANCE_ID, &VecDeque::new()),
+            Err(CompactUnitError::MissingPrevious(0))
+        ));
+
+        // Or one referring to units it doesn't have by sequence number.
+        let full_view = IndexPanorama::from_panorama(state.panorama(), &state);
+        let compact = CompactUnit::encode(&swunit, &state, Some(&full_view)).unwrap();
+        assert!(matches!(
+            compact.decode(&empty_state, TEST_INSTANCE_ID, &sent_views(&[&full_view])),
+            Err(CompactUnitError::MissingObservation(vidx, 0)) if vidx == ALICE
+        ));
+
+        // A different instance ID yields a different unit hash.
+        let compact = CompactUnit::encode(&swunit, &state, None).unwrap();
+        assert!(matches!(
+            compact.decode(&state, TEST_INSTANCE_ID + 1, &VecDeque::new()),
+            Err(CompactUnitError::HashMismatch)
+        ));
+        Ok(())
+    }
+}
@@ -13,7 +13,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tracing::{trace, warn};
 
-use casper_types::Timestamp;
+use casper_types::{TimeDiff, Timestamp};
 
 use super::{
     active_validator::Effect,
@@ -31,10 +31,12 @@ use crate::{
         consensus_protocol::FinalizedBlock,
         tests::{
             consensus_des_testing::{
-                DeliverySchedule, Fault as DesFault, Message, Node, Target, TargetedMessage,
+                ClassifyMessage, DeliverySchedule, Fault as DesFault, Honesty, Message,
+                MessageKind, MessageTraffic, Node, Target, TargetedMessage, TrafficStats,
                 ValidatorId, VirtualNet,
             },
             queue::QueueEntry,
+            utils::{vertex_kind, vertex_type},
         },
         traits::{ConsensusValueT, Context, ValidatorSecret},
         BlockContext,
@@ -107,6 +109,31 @@ impl HighwayMessage {
     }
 }
 
+impl MessageTraffic for HighwayMessage {
+    fn traffic(&self) -> Option<(&'static str, usize)> {
+        match self {
+            HighwayMessage::NewVertex(vertex) => {
+                let size = bincode::serialized_size(vertex).expect("should serialize vertex");
+                Some((vertex_type(vertex), size as usize))
+            }
+            HighwayMessage::Timer(_)
+            | HighwayMessage::RequestBlock(_)
+            | HighwayMessage::WeAreFaulty(_) => None,
+        }
+    }
+}
+
+impl ClassifyMessage for HighwayMessage {
+    fn kind(&self) -> MessageKind {
+        match self {
+            HighwayMessage::NewVertex(vertex) => vertex_kind(vertex),
+            HighwayMessage::Timer(_)
+            | HighwayMessage::RequestBlock(_)
+            | HighwayMessage::WeAreFaulty(_) => MessageKind::Local,
+        }
+    }
+}
+
 impl From<Effect<TestContext>> for HighwayMessage {
     fn from(eff: Effect<TestContext>) -> Self {
         match eff {
@@ -116,6 +143,9 @@ impl From<Effect<TestContext>> for HighwayMessage {
             Effect::ScheduleTimer(t) => HighwayMessage::Timer(t),
             Effect::RequestNewBlock(block_context) => HighwayMessage::RequestBlock(block_context),
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature { .. } => {
+                unreachable!("test validators sign their vertices right away")
+            }
         }
     }
 }
@@ -180,19 +210,39 @@ impl Distribution {
 }
 
 trait DeliveryStrategy {
+    /// Decides when the `message` created by `sender` is delivered, if at all.
+    ///
+    /// The decision can depend on the kind of the message, see `ClassifyMessage`.
     fn gen_delay(
         &mut self,
         rng: &mut NodeRng,
+        sender: ValidatorId,
         message: &HighwayMessage,
         distribution: &Distribution,
         base_delivery_timestamp: Timestamp,
     ) -> DeliverySchedule;
+
+    /// Returns the times at which copies of the `message` created by `sender` are delivered again,
+    /// after its first delivery at `delivery_timestamp`.
+    ///
+    /// Messages are not redelivered by default.
+    fn gen_replays(
+        &mut self,
+        _rng: &mut NodeRng,
+        _sender: ValidatorId,
+        _message: &HighwayMessage,
+        _delivery_timestamp: Timestamp,
+    ) -> Vec<Timestamp> {
+        Vec::new()
+    }
 }
 
 struct HighwayValidator {
     highway: Highway<TestContext>,
     finality_detector: FinalityDetector<TestContext>,
     fault: Option<DesFault>,
+    /// The time from which on the validator exhibits its fault. Before that, it is honest.
+    fault_activation: Timestamp,
 }
 
 impl HighwayValidator {
@@ -200,11 +250,13 @@ impl HighwayValidator {
         highway: Highway<TestContext>,
         finality_detector: FinalityDetector<TestContext>,
         fault: Option<DesFault>,
+        fault_activation: Timestamp,
     ) -> Self {
         HighwayValidator {
             highway,
             finality_detector,
             fault,
+            fault_activation,
         }
     }
 
@@ -221,7 +273,12 @@ impl HighwayValidator {
     }
 
     fn post_hook(&mut self, delivery_time: Timestamp, msg: HighwayMessage) -> Vec<HighwayMessage> {
-        match self.fault.as_ref() {
+        let fault = if delivery_time < self.fault_activation {
+            None
+        } else {
+            self.fault.as_ref()
+        };
+        match fault {
             Some(DesFault::TemporarilyMute { from, till })
                 if *from <= delivery_time && delivery_time <= *till =>
             {
@@ -277,7 +334,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -293,6 +350,12 @@ impl HighwayValidator {
     }
 }
 
+impl Honesty for HighwayValidator {
+    fn is_honest(&self) -> bool {
+        self.fault.is_none()
+    }
+}
+
 type HighwayNode = Node<ConsensusValue, HighwayMessage, HighwayValidator>;
 
 type HighwayNet = VirtualNet<ConsensusValue, HighwayMessage, HighwayValidator>;
@@ -355,9 +418,10 @@ where
 
         let targeted_messages = messages
             .into_iter()
-            .filter_map(|hwm| {
+            .flat_map(|hwm| {
                 let delivery = self.delivery_time_strategy.gen_delay(
                     rng,
+                    recipient,
                     &hwm,
                     &self.delivery_time_distribution,
                     delivery_time,
@@ -365,12 +429,22 @@ where
                 match delivery {
                     DeliverySchedule::Drop => {
                         trace!("{:?} message is dropped.", hwm);
-                        None
+                        vec![]
                     }
                     DeliverySchedule::AtInstant(timestamp) => {
                         trace!("{:?} scheduled for {:?}", hwm, timestamp);
+                        let replays = self
+                            .delivery_time_strategy
+                            .gen_replays(rng, recipient, &hwm, timestamp);
+                        let mut scheduled = Vec::with_capacity(1 + replays.len());
+                        for replay_timestamp in replays {
+                            trace!("{:?} replayed at {:?}", hwm, replay_timestamp);
+                            let targeted = hwm.clone().into_targeted(recipient);
+                            scheduled.push((targeted, replay_timestamp));
+                        }
                         let targeted = hwm.into_targeted(recipient);
-                        Some((targeted, timestamp))
+                        scheduled.push((targeted, timestamp));
+                        scheduled
                     }
                 }
             })
@@ -689,6 +763,28 @@ fn crank_until_time<DS: DeliveryStrategy>(
     })
 }
 
+/// Cranks until all validators have finalized `cv_count` values, and returns the time at which
+/// this happened, or `None` if it didn't happen by `horizon`.
+fn crank_until_finalized_by<DS: DeliveryStrategy>(
+    hth: &mut HighwayTestHarness<DS>,
+    rng: &mut NodeRng,
+    cv_count: usize,
+    horizon: Timestamp,
+) -> TestResult<Option<Timestamp>> {
+    let mut now = Timestamp::zero();
+    loop {
+        let has_all_finalized = |v: &HighwayNode| v.finalized_count() == cv_count;
+        if hth.virtual_net.validators().all(has_all_finalized) {
+            return Ok(Some(now));
+        }
+        match hth.virtual_net.peek_message() {
+            Some(qe) if qe.delivery_time <= horizon => now = qe.delivery_time,
+            _ => return Ok(None),
+        }
+        hth.crank(rng)?;
+    }
+}
+
 struct MutableHandle<'a, DS: DeliveryStrategy>(&'a mut HighwayTestHarness<DS>);
 
 impl<'a, DS: DeliveryStrategy> MutableHandle<'a, DS> {
@@ -701,11 +797,17 @@ impl<'a, DS: DeliveryStrategy> MutableHandle<'a, DS> {
         self.0.virtual_net.validators()
     }
 
+    /// Returns the network traffic of the test run so far.
+    fn traffic(&self) -> &TrafficStats {
+        self.0.virtual_net.traffic()
+    }
+
     fn correct_validators(&self) -> impl Iterator<Item = &HighwayNode> {
-        self.0
-            .virtual_net
-            .validators()
-            .filter(|v| v.validator().fault.is_none())
+        self.0.virtual_net.honest_validators()
+    }
+
+    fn virtual_net(&self) -> &HighwayNet {
+        &self.0.virtual_net
     }
 }
 
@@ -737,6 +839,9 @@ struct HighwayTestHarnessBuilder<DS: DeliveryStrategy> {
     /// Defaults to 0 (network is perfectly secure).
     faulty_percent: u64,
     fault_type: Option<DesFault>,
+    /// Time from which on faulty validators exhibit their fault; until then they behave honestly.
+    /// Defaults to 0, i.e. they are faulty from the start.
+    fault_activation: Timestamp,
     /// FTT value for the finality detector.
     /// If not given, defaults to 1/3 of total validators' weight.
     ftt: Option<u64>,
@@ -766,6 +871,7 @@ impl DeliveryStrategy for InstantDeliveryNoDropping {
     fn gen_delay(
         &mut self,
         _rng: &mut NodeRng,
+        _sender: ValidatorId,
         message: &HighwayMessage,
         _distribution: &Distribution,
         base_delivery_timestamp: Timestamp,
@@ -783,12 +889,97 @@ impl DeliveryStrategy for InstantDeliveryNoDropping {
     }
 }
 
+/// Drops the messages for which `filter` returns `true`, given their sender and kind, e.g. only
+/// the proposals of a particular validator, and delivers all others according to `inner`.
+struct DropMatching<DS, F> {
+    inner: DS,
+    filter: F,
+}
+
+impl<DS, F> DeliveryStrategy for DropMatching<DS, F>
+where
+    DS: DeliveryStrategy,
+    F: FnMut(ValidatorId, MessageKind) -> bool,
+{
+    fn gen_delay(
+        &mut self,
+        rng: &mut NodeRng,
+        sender: ValidatorId,
+        message: &HighwayMessage,
+        distribution: &Distribution,
+        base_delivery_timestamp: Timestamp,
+    ) -> DeliverySchedule {
+        if (self.filter)(sender, message.kind()) {
+            DeliverySchedule::Drop
+        } else {
+            self.inner
+                .gen_delay(rng, sender, message, distribution, base_delivery_timestamp)
+        }
+    }
+
+    fn gen_replays(
+        &mut self,
+        rng: &mut NodeRng,
+        sender: ValidatorId,
+        message: &HighwayMessage,
+        delivery_timestamp: Timestamp,
+    ) -> Vec<Timestamp> {
+        self.inner
+            .gen_replays(rng, sender, message, delivery_timestamp)
+    }
+}
+
+/// Simulates the senders for which `restarted` returns `true` rebroadcasting their vertices after
+/// a restart: every vertex they send is delivered again at a random time up to `max_delay` after
+/// its first delivery. Delivery is otherwise decided by `inner`.
+struct ReplayFrom<DS, F> {
+    inner: DS,
+    restarted: F,
+    max_delay: TimeDiff,
+}
+
+impl<DS, F> DeliveryStrategy for ReplayFrom<DS, F>
+where
+    DS: DeliveryStrategy,
+    F: FnMut(ValidatorId) -> bool,
+{
+    fn gen_delay(
+        &mut self,
+        rng: &mut NodeRng,
+        sender: ValidatorId,
+        message: &HighwayMessage,
+        distribution: &Distribution,
+        base_delivery_timestamp: Timestamp,
+    ) -> DeliverySchedule {
+        self.inner
+            .gen_delay(rng, sender, message, distribution, base_delivery_timestamp)
+    }
+
+    fn gen_replays(
+        &mut self,
+        rng: &mut NodeRng,
+        sender: ValidatorId,
+        message: &HighwayMessage,
+        delivery_timestamp: Timestamp,
+    ) -> Vec<Timestamp> {
+        let mut replays = self
+            .inner
+            .gen_replays(rng, sender, message, delivery_timestamp);
+        if matches!(message, HighwayMessage::NewVertex(_)) && (self.restarted)(sender) {
+            let delay = rng.gen_range(1..=self.max_delay.millis());
+            replays.push(delivery_timestamp + TimeDiff::from(delay));
+        }
+        replays
+    }
+}
+
 impl HighwayTestHarnessBuilder<InstantDeliveryNoDropping> {
     fn new() -> Self {
         HighwayTestHarnessBuilder {
             max_faulty_validators: 10,
             faulty_percent: 0,
             fault_type: None,
+            fault_activation: Timestamp::zero(),
             ftt: None,
             consensus_values_count: 10,
             delivery_distribution: Distribution::Uniform,
@@ -814,6 +1005,13 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
         self
     }
 
+    /// Makes the faulty validators behave honestly until `timestamp`, and only then switch to
+    /// the configured fault type, to simulate adaptive corruption.
+    fn fault_activation(mut self, timestamp: Timestamp) -> Self {
+        self.fault_activation = timestamp;
+        self
+    }
+
     pub(crate) fn consensus_values_count(mut self, count: u8) -> Self {
         assert!(count > 0);
         self.consensus_values_count = count;
@@ -829,6 +1027,27 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
         self
     }
 
+    /// Replaces the strategy deciding when messages are delivered.
+    fn delivery_strategy<DS2: DeliveryStrategy>(
+        self,
+        delivery_strategy: DS2,
+    ) -> HighwayTestHarnessBuilder<DS2> {
+        HighwayTestHarnessBuilder {
+            max_faulty_validators: self.max_faulty_validators,
+            faulty_percent: self.faulty_percent,
+            fault_type: self.fault_type,
+            fault_activation: self.fault_activation,
+            ftt: self.ftt,
+            consensus_values_count: self.consensus_values_count,
+            delivery_distribution: self.delivery_distribution,
+            delivery_strategy,
+            weight_limits: self.weight_limits,
+            start_time: self.start_time,
+            weight_distribution: self.weight_distribution,
+            params: self.params,
+        }
+    }
+
     fn max_faulty_validators(mut self, max_faulty_count: u8) -> Self {
         self.max_faulty_validators = max_faulty_count;
         self
@@ -846,6 +1065,7 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
 
         let instance_id = 0;
         let start_time = self.start_time;
+        let fault_activation = self.fault_activation;
 
         let (lower, upper) = {
             let (l, u) = self.weight_limits;
@@ -959,7 +1179,8 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
                     None
                 };
                 let (highway, finality_detector, msgs) = highway_consensus((vid, &mut secrets));
-                let highway_consensus = HighwayValidator::new(highway, finality_detector, fault);
+                let highway_consensus =
+                    HighwayValidator::new(highway, finality_detector, fault, fault_activation);
                 let validator = Node::new(vid, highway_consensus);
                 let qm: Vec<QueueEntry<HighwayMessage>> = msgs
                     .into_iter()
@@ -1031,8 +1252,8 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -1068,14 +1289,20 @@ mod test_harness {
     use casper_types::Timestamp;
 
     use super::{
-        crank_until, crank_until_finalized, crank_until_time, test_params, ConsensusValue,
-        HighwayTestHarness, HighwayTestHarnessBuilder, InstantDeliveryNoDropping, TestRunError,
-        TEST_MIN_ROUND_EXP,
+        crank_until, crank_until_finalized, crank_until_finalized_by, crank_until_time,
+        test_params, DropMatching, HighwayTestHarness, HighwayTestHarnessBuilder,
+        InstantDeliveryNoDropping, ReplayFrom, TestRunError, TEST_MIN_ROUND_EXP,
     };
     use crate::{
         components::consensus::{
             highway_core::state,
-            tests::consensus_des_testing::{Fault as DesFault, ValidatorId},
+            tests::{
+                consensus_des_testing::{
+                    assert_common_prefix, finalization_lag_histogram, ClassifyMessage,
+                    Fault as DesFault, MessageKind, ValidatorId,
+                },
+                model_checking::ModelChecker,
+            },
         },
         logging,
     };
@@ -1134,18 +1361,28 @@ mod test_harness {
 
         let handle = highway_test_harness.mutable_handle();
         let validators = handle.validators();
+        let validator_count = handle.validators().count();
 
-        let (finalized_values, units_produced): (Vec<Vec<ConsensusValue>>, Vec<usize>) = validators
+        let units_produced: Vec<usize> = validators
             .map(|v| {
-                (
-                    v.finalized_values().cloned().collect::<Vec<_>>(),
-                    v.messages_produced()
-                        .cloned()
-                        .filter(|hwm| hwm.is_new_unit())
-                        .count(),
-                )
+                v.messages_produced()
+                    .cloned()
+                    .filter(|hwm| hwm.is_new_unit())
+                    .count()
             })
-            .unzip();
+            .collect();
+
+        // Every unit is gossiped to all other validators.
+        let unit_traffic = handle.traffic().of_type("unit");
+        let total_units: usize = units_produced.iter().sum();
+        assert_eq!(
+            unit_traffic.messages,
+            (total_units * (validator_count - 1)) as u64
+        );
+        let (_, unit_bytes_per_value) = unit_traffic
+            .per_value(cv_count as usize)
+            .expect("values were finalized");
+        assert!(unit_bytes_per_value > 0.0);
 
         units_produced
             .into_iter()
@@ -1164,9 +1401,9 @@ mod test_harness {
                 )
             });
 
-        assert_eq_vectors(
-            finalized_values,
-            "Nodes finalized different consensus values.",
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
         );
     }
 
@@ -1198,15 +1435,9 @@ mod test_harness {
         .unwrap();
 
         let handle = highway_test_harness.mutable_handle();
-        let validators = handle.validators();
-
-        let finalized_values: Vec<Vec<ConsensusValue>> = validators
-            .map(|v| v.finalized_values().cloned().collect::<Vec<_>>())
-            .collect();
-
-        assert_eq_vectors(
-            finalized_values,
-            "Nodes finalized different consensus values.",
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
         );
     }
 
@@ -1240,26 +1471,223 @@ mod test_harness {
         let handle = highway_test_harness.mutable_handle();
         let validators = handle.validators();
 
-        let (finalized_values, equivocators_seen): (
-            Vec<Vec<ConsensusValue>>,
-            Vec<HashSet<ValidatorId>>,
-        ) = validators
+        let equivocators_seen: Vec<HashSet<ValidatorId>> = validators
             .map(|v| {
-                (
-                    v.finalized_values().cloned().collect::<Vec<_>>(),
-                    v.validator()
-                        .highway()
-                        .validators_with_evidence()
-                        .cloned()
-                        .collect::<HashSet<_>>(),
-                )
+                v.validator()
+                    .highway()
+                    .validators_with_evidence()
+                    .cloned()
+                    .collect::<HashSet<_>>()
             })
-            .unzip();
+            .collect();
 
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
+        );
         assert_eq_vectors(
-            finalized_values,
-            "Nodes finalized different consensus values.",
+            equivocators_seen,
+            "Nodes saw different set of equivocators.",
+        );
+    }
+
+    #[test]
+    fn finality_within_deadline_in_most_runs() {
+        let cv_count = 10u8;
+        let round_len = state::round_len(TEST_MIN_ROUND_EXP);
+        // Generous, given that a correct network finalizes about one value per round.
+        let deadline = Timestamp::zero() + round_len * 4 * cv_count as u64;
+        let horizon = deadline + round_len * 4 * cv_count as u64;
+
+        // Use `CL_MODEL_CHECK_RUNS` to run this for thousands of seeds.
+        let report = ModelChecker::new(100)
+            .assert_ratio(
+                "all values finalized by the deadline",
+                0.999,
+                move |finalized_at: &Option<Timestamp>| {
+                    finalized_at.map_or(false, |timestamp| timestamp <= deadline)
+                },
+            )
+            .metric(
+                "time to finality in ms",
+                |finalized_at: &Option<Timestamp>| {
+                    finalized_at.map_or(f64::INFINITY, |timestamp| timestamp.millis() as f64)
+                },
+            )
+            .run(|rng| {
+                let mut highway_test_harness = HighwayTestHarnessBuilder::new()
+                    .max_faulty_validators(3)
+                    .faulty_weight_perc(10)
+                    .fault_type(DesFault::PermanentlyMute)
+                    .consensus_values_count(cv_count)
+                    .weight_limits(100, 120)
+                    .build(rng)
+                    .expect("Construction was successful");
+                crank_until_finalized_by(&mut highway_test_harness, rng, cv_count as usize, horizon)
+            });
+        report.assert_passed();
+    }
+
+    #[test]
+    fn liveness_test_proposals_of_one_validator_dropped() {
+        let _ = logging::init_with_config(&LoggingConfig::new(LoggingFormat::Text, true, true));
+
+        let mut rng = crate::new_rng();
+        let cv_count = 10;
+        let attacked = ValidatorId(0);
+        let horizon =
+            Timestamp::zero() + state::round_len(TEST_MIN_ROUND_EXP) * 10 * cv_count as u64;
+
+        // Only the proposals of one validator are dropped; its other messages are delivered.
+        let drop_proposals = DropMatching {
+            inner: InstantDeliveryNoDropping,
+            filter: |sender: ValidatorId, kind: MessageKind| {
+                sender == attacked && kind == MessageKind::Proposal
+            },
+        };
+        let mut highway_test_harness = HighwayTestHarnessBuilder::new()
+            .max_faulty_validators(3)
+            .consensus_values_count(cv_count)
+            .weight_limits(100, 120)
+            .delivery_strategy(drop_proposals)
+            .build(&mut rng)
+            .expect("Construction was successful");
+
+        // The others still get the proposals by synchronizing the dependencies of later units.
+        let finalized_at = crank_until_finalized_by(
+            &mut highway_test_harness,
+            &mut rng,
+            cv_count as usize,
+            horizon,
+        )
+        .unwrap();
+        assert!(finalized_at.is_some(), "not all values were finalized");
+
+        let handle = highway_test_harness.mutable_handle();
+        for v in handle.validators().filter(|v| v.id != attacked) {
+            let received_from_attacked = v
+                .messages_received()
+                .filter(|msg| msg.sender == attacked)
+                .map(|msg| msg.payload().kind())
+                .collect::<HashSet<_>>();
+            assert!(!received_from_attacked.contains(&MessageKind::Proposal));
+            assert!(received_from_attacked.contains(&MessageKind::Vote));
+        }
+
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
         );
+    }
+
+    #[test]
+    fn stale_duplicates_from_restarted_validator_are_not_double_counted() {
+        let _ = logging::init_with_config(&LoggingConfig::new(LoggingFormat::Text, true, true));
+
+        let mut rng = crate::new_rng();
+        let cv_count = 10;
+        let restarted = ValidatorId(0);
+        let round_len = state::round_len(TEST_MIN_ROUND_EXP);
+        let horizon = Timestamp::zero() + round_len * 10 * cv_count as u64;
+
+        // Every vertex of one validator arrives a second time, up to three rounds late.
+        let replay_vertices = ReplayFrom {
+            inner: InstantDeliveryNoDropping,
+            restarted: |sender: ValidatorId| sender == restarted,
+            max_delay: round_len * 3,
+        };
+        let mut highway_test_harness = HighwayTestHarnessBuilder::new()
+            .max_faulty_validators(3)
+            .consensus_values_count(cv_count)
+            .weight_limits(100, 120)
+            .delivery_strategy(replay_vertices)
+            .build(&mut rng)
+            .expect("Construction was successful");
+
+        let finalized_at = crank_until_finalized_by(
+            &mut highway_test_harness,
+            &mut rng,
+            cv_count as usize,
+            horizon,
+        )
+        .unwrap();
+        assert!(finalized_at.is_some(), "not all values were finalized");
+
+        let handle = highway_test_harness.mutable_handle();
+        let units_produced = handle
+            .validators()
+            .flat_map(|v| v.messages_produced().filter(|hwm| hwm.is_new_unit()))
+            .collect::<HashSet<_>>();
+        for v in handle.validators() {
+            // Duplicates are neither mistaken for equivocations nor added to the state twice.
+            assert_eq!(
+                v.validator().highway().validators_with_evidence().count(),
+                0
+            );
+            assert!(v.unit_count() <= units_produced.len());
+
+            if v.id != restarted {
+                let units_from_restarted = v
+                    .messages_received()
+                    .filter(|msg| msg.sender == restarted && msg.payload().is_new_unit())
+                    .map(|msg| msg.payload())
+                    .collect_vec();
+                let distinct_units = units_from_restarted.iter().collect::<HashSet<_>>();
+                assert!(
+                    units_from_restarted.len() > distinct_units.len(),
+                    "no stale duplicates were delivered"
+                );
+            }
+        }
+
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
+        );
+    }
+
+    #[test]
+    fn equivocators_are_honest_until_fault_activation() {
+        let _ = logging::init_with_config(&LoggingConfig::new(LoggingFormat::Text, true, true));
+
+        let mut rng = crate::new_rng();
+        let cv_count = 10;
+        let fault_activation = Timestamp::zero() + state::round_len(TEST_MIN_ROUND_EXP) * 3;
+
+        let mut highway_test_harness = HighwayTestHarnessBuilder::new()
+            .max_faulty_validators(3)
+            .faulty_weight_perc(10)
+            .fault_type(DesFault::Equivocate)
+            .fault_activation(fault_activation)
+            .consensus_values_count(cv_count)
+            .weight_limits(100, 150)
+            .build(&mut rng)
+            .expect("Construction was successful");
+
+        // Until the fault is activated, nobody equivocates.
+        crank_until_time(&mut highway_test_harness, &mut rng, fault_activation).unwrap();
+        for v in highway_test_harness.mutable_handle().validators() {
+            assert_eq!(
+                v.validator().highway().validators_with_evidence().count(),
+                0
+            );
+        }
+
+        crank_until_finalized(&mut highway_test_harness, &mut rng, cv_count as usize).unwrap();
+
+        // Afterwards, every node has evidence against the same equivocators.
+        let equivocators_seen = highway_test_harness
+            .mutable_handle()
+            .validators()
+            .map(|v| {
+                v.validator()
+                    .highway()
+                    .validators_with_evidence()
+                    .cloned()
+                    .collect::<HashSet<_>>()
+            })
+            .collect_vec();
+        assert!(!equivocators_seen[0].is_empty(), "no equivocation detected");
         assert_eq_vectors(
             equivocators_seen,
             "Nodes saw different set of equivocators.",
@@ -1300,13 +1728,18 @@ mod test_harness {
 
         // They should all see the same number of finalized blocks.
         let handle = test_harness.mutable_handle();
-        let first_validator = handle.correct_validators().next().unwrap();
-        let finalized_before_pause = first_validator.finalized_count();
-        let unit_count_before_pause = first_validator.unit_count();
+        let honest_count = handle.correct_validators().count();
+        assert_eq!(
+            finalization_lag_histogram(handle.virtual_net())
+                .into_iter()
+                .collect_vec(),
+            vec![(0, honest_count)]
+        );
+        let finalized_before_pause = assert_common_prefix(handle.virtual_net());
+        let unit_count_before_pause = handle.correct_validators().next().unwrap().unit_count();
         assert_ne!(finalized_before_pause, 0);
         assert!(finalized_before_pause < cv_count as usize);
         for v in handle.correct_validators() {
-            assert_eq!(finalized_before_pause, v.finalized_count());
             assert_eq!(unit_count_before_pause, v.unit_count());
         }
 
@@ -1323,15 +1756,10 @@ mod test_harness {
         crank_until_finalized(&mut test_harness, &mut rng, cv_count as usize).unwrap();
 
         // ...and finalize the remaining blocks.
-        let finalized_values = test_harness
-            .mutable_handle()
-            .validators()
-            .map(|v| v.finalized_values().cloned().collect_vec())
-            .collect_vec();
-
-        assert_eq_vectors(
-            finalized_values,
-            "Nodes finalized different consensus values.",
+        let handle = test_harness.mutable_handle();
+        assert_eq!(
+            assert_common_prefix(handle.virtual_net()),
+            cv_count as usize
         );
     }
 }
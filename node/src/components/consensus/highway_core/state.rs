@@ -459,6 +459,22 @@ impl<C: Context> State<C> {
             .unwrap_or_else(panic_or_0)
     }
 
+    /// Returns the start and leader of each round with the given exponent, beginning with the
+    /// round that contains `from` and ending with the one that contains `to`.
+    pub(crate) fn leader_schedule(
+        &self,
+        from: Timestamp,
+        to: Timestamp,
+        round_exp: u8,
+    ) -> impl Iterator<Item = (Timestamp, ValidatorIndex)> + '_ {
+        let round_len = round_len(round_exp);
+        iter::successors(Some(round_id(from, round_exp)), move |r_id| {
+            Some(*r_id + round_len)
+        })
+        .take_while(move |r_id| *r_id <= to)
+        .map(move |r_id| (r_id, self.leader(r_id)))
+    }
+
     /// Adds the unit to the protocol state.
     ///
     /// The unit must be valid (see `validate_unit`), and its dependencies satisfied.
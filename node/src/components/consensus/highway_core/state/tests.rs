@@ -48,8 +48,8 @@ impl ValidatorSecret for TestSecret {
     type Hash = u64;
     type Signature = u64;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        data + u64::from(self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(data + u64::from(self.0))
     }
 }
 
@@ -187,12 +187,12 @@ fn add_unit() -> Result<(), AddUnitError<TestContext>> {
         round_exp: 4u8,
         endorsed: BTreeSet::new(),
     };
-    let unit = SignedWireUnit::new(wunit.clone().into_hashed(), &BOB_SEC);
+    let unit = SignedWireUnit::new(wunit.clone().into_hashed(), &BOB_SEC).unwrap();
     let maybe_err = state.add_unit(unit).err().map(unit_err);
     assert_eq!(Some(UnitError::SequenceNumber), maybe_err);
     // Still not valid: This would be the third unit in the first round.
     wunit.seq_number = 2;
-    let unit = SignedWireUnit::new(wunit.into_hashed(), &BOB_SEC);
+    let unit = SignedWireUnit::new(wunit.into_hashed(), &BOB_SEC).unwrap();
     let maybe_err = state.add_unit(unit).err().map(unit_err);
     assert_eq!(Some(UnitError::ThreeUnitsInRound), maybe_err);
 
@@ -903,6 +903,23 @@ fn test_leader() {
     );
 }
 
+#[test]
+fn test_leader_schedule() {
+    let weights = &[Weight(3), Weight(4), Weight(5), Weight(4), Weight(5)];
+    let state = State::<TestContext>::new(weights, test_params(0), vec![], vec![]);
+
+    // With round exponent 2, rounds start at multiples of 4.
+    let schedule = state
+        .leader_schedule(5.into(), 16.into(), 2)
+        .map(|(r_id, leader)| (r_id.millis(), leader))
+        .collect_vec();
+    let expected = [4, 8, 12, 16]
+        .iter()
+        .map(|r_id| (*r_id, state.leader((*r_id).into())))
+        .collect_vec();
+    assert_eq!(expected, schedule);
+}
+
 #[test]
 fn test_leader_prng() {
     let mut rng = crate::new_rng();
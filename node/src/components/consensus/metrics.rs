@@ -1,8 +1,6 @@
 use prometheus::{Gauge, IntGauge, Registry};
 
-use casper_types::Timestamp;
-
-use crate::{types::FinalizedBlock, unregister_metric};
+use crate::{types::FinalizedBlock, unregister_metric, utils::clock};
 
 /// Network metrics to track Consensus
 #[derive(Debug)]
@@ -66,7 +64,7 @@ impl Metrics {
     /// Updates the metrics and records a newly proposed block.
     pub(super) fn proposed_block(&mut self) {
         self.time_of_last_proposed_block
-            .set(Timestamp::now().millis() as i64);
+            .set(clock::now().millis() as i64);
     }
 }
 
@@ -6,7 +6,7 @@ mod tests;
 
 use std::{
     any::Any,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt::Debug,
     iter,
     path::PathBuf,
@@ -25,14 +25,15 @@ use crate::{
     components::consensus::{
         config::Config,
         consensus_protocol::{
-            BlockContext, ConsensusProtocol, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            BlockContext, ConsensusProtocol, LeaderSchedule, ProposedBlock, ProtocolOutcome,
+            ProtocolOutcomes,
         },
         highway_core::{
             active_validator::Effect as AvEffect,
             finality_detector::{FinalityDetector, FttExceeded},
             highway::{
-                Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, ValidVertex,
-                Vertex, VertexError,
+                view_hash, CompactUnit, Dependency, GetDepOutcome, Highway, Params,
+                PreValidatedVertex, ValidVertex, Vertex, VertexError,
             },
             state::{self, IndexObservation, IndexPanorama, Observation, Panorama},
             synchronizer::Synchronizer,
@@ -51,6 +52,9 @@ use self::round_success_meter::RoundSuccessMeter;
 /// even if eras are longer than this.
 const MAX_ENDORSEMENT_EVIDENCE_LIMIT: u64 = 10_000;
 
+/// Never compute the leaders of more than this many rounds, even if eras are longer than this.
+const MAX_LEADER_SCHEDULE_ROUNDS: usize = 10_000;
+
 /// The timer for creating new units, as a validator actively participating in consensus.
 const TIMER_ID_ACTIVE_VALIDATOR: TimerId = TimerId(0);
 /// The timer for adding a vertex with a future timestamp.
@@ -66,6 +70,10 @@ const TIMER_ID_SYNCHRONIZER_LOG: TimerId = TimerId(5);
 /// The timer to request the latest state from a random peer.
 const TIMER_ID_REQUEST_STATE: TimerId = TimerId(6);
 
+/// The number of views we sent in latest state requests that we remember, to decode compact units
+/// based on them.
+const MAX_SENT_VIEWS: usize = 10;
+
 /// The action of adding a vertex from the `vertices_to_be_added` queue.
 pub(crate) const ACTION_ID_VERTEX: ActionId = ActionId(0);
 
@@ -86,6 +94,12 @@ where
     /// The panorama snapshot. This is updated periodically, and if it does not change for too
     /// long, an alert is raised.
     last_panorama: Panorama<C>,
+    /// The latest state each peer reported in a latest state request, i.e. the units we can
+    /// assume they have when sending them compact units.
+    peer_views: HashMap<NodeId, IndexPanorama>,
+    /// The latest views we sent in latest state requests, by hash. Peers use them as the base of
+    /// the compact units they send us.
+    sent_views: VecDeque<(C::Hash, IndexPanorama)>,
     config: config::Config,
 }
 
@@ -202,6 +216,8 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             pvv_cache: Default::default(),
             evidence_only: false,
             last_panorama,
+            peer_views: HashMap::new(),
+            sent_views: VecDeque::new(),
             config: config.highway.clone(),
         });
 
@@ -264,6 +280,14 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             AvEffect::RequestNewBlock(block_context) => {
                 vec![ProtocolOutcome::CreateNewBlock(block_context)]
             }
+            AvEffect::RequestSignature {
+                hash,
+                unit_seq_number,
+            } => vec![ProtocolOutcome::CreateSignature {
+                hash,
+                instance_id: *self.highway.instance_id(),
+                unit_seq_number,
+            }],
             AvEffect::WeAreFaulty(fault) => {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
@@ -282,7 +306,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
                 .clone();
             outcomes.push(ProtocolOutcome::NewEvidence(v_id));
         }
-        let msg = HighwayMessage::NewVertex(vv.into());
+        let msg = self.vertex_message(vv.into(), None);
         outcomes.push(ProtocolOutcome::CreatedGossipMessage(msg.serialize()));
         outcomes.extend(self.detect_finality());
         outcomes
@@ -596,6 +620,68 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             Vertex::Ping(ping) => trace!(?ping, "received ping"),
         }
     }
+
+    /// Pre-validates an incoming vertex and schedules it to be added to the protocol state.
+    fn handle_new_vertex(
+        &mut self,
+        v: Vertex<C>,
+        sender: NodeId,
+        msg: Vec<u8>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let v_id = v.id();
+        // If we already have that vertex, do not process it.
+        if self.highway.has_dependency(&v_id) {
+            return vec![];
+        }
+        let pvv = match self.pre_validate_vertex(v) {
+            Ok(pvv) => pvv,
+            Err((_, err)) => {
+                trace!("received an invalid vertex");
+                // drop the vertices that might have depended on this one
+                let faulty_senders = self.synchronizer.invalid_vertices(vec![v_id]);
+                return iter::once(ProtocolOutcome::InvalidIncomingMessage(
+                    msg,
+                    sender,
+                    err.into(),
+                ))
+                .chain(faulty_senders.into_iter().map(ProtocolOutcome::Disconnect))
+                .collect();
+            }
+        };
+        // Keep track of whether the prevalidated vertex was from an equivocator
+        let is_faulty = match pvv.inner().creator() {
+            Some(creator) => self.highway.state().is_faulty(creator),
+            None => false,
+        };
+
+        if is_faulty && !self.synchronizer.is_dependency(&pvv.inner().id()) {
+            trace!("received a vertex from a faulty validator; dropping");
+            return vec![];
+        }
+
+        match pvv.timestamp() {
+            Some(timestamp) if timestamp > now + self.config.pending_vertex_timeout => {
+                trace!("received a vertex with a timestamp far in the future; dropping");
+                vec![]
+            }
+            Some(timestamp) if timestamp > now => {
+                // If it's not from an equivocator and from the future, add to queue
+                trace!("received a vertex from the future; storing for later");
+                self.synchronizer
+                    .store_vertex_for_addition_later(timestamp, now, sender, pvv);
+                let timer_id = TIMER_ID_VERTEX_WITH_FUTURE_TIMESTAMP;
+                vec![ProtocolOutcome::ScheduleTimer(timestamp, timer_id)]
+            }
+            _ => {
+                // If it's not from an equivocator or it is a transitive dependency, add the
+                // vertex
+                self.log_received_vertex(pvv.inner());
+                self.synchronizer.schedule_add_vertex(sender, pvv, now)
+            }
+        }
+    }
+
     /// Prevalidates the vertex but checks the cache for previously validated vertices.
     /// Avoids multiple validation of the same vertex.
     fn pre_validate_vertex(
@@ -611,11 +697,45 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         Ok(pvv)
     }
 
+    /// Creates a message containing the vertex, for the given recipient or for gossiping if `None`.
+    ///
+    /// If enabled, units are sent as compact units: A targeted message is encoded relative to the
+    /// view the recipient reported in its latest state request, while gossip assumes every peer
+    /// has the creator's previous unit.
+    fn vertex_message(&self, vertex: Vertex<C>, recipient: Option<&NodeId>) -> HighwayMessage<C> {
+        let their_view = match recipient {
+            None => None,
+            Some(peer) => match self.peer_views.get(peer) {
+                Some(view) => Some(view),
+                None => return HighwayMessage::NewVertex(vertex),
+            },
+        };
+        let compact_unit = match &vertex {
+            Vertex::Unit(swunit) if self.config.compact_units => {
+                CompactUnit::encode(swunit, self.highway.state(), their_view)
+            }
+            Vertex::Unit(_) | Vertex::Evidence(_) | Vertex::Endorsements(_) | Vertex::Ping(_) => {
+                None
+            }
+        };
+        compact_unit.map_or(
+            HighwayMessage::NewVertex(vertex),
+            HighwayMessage::NewCompactUnit,
+        )
+    }
+
     /// Creates a message to send our panorama to a random peer.
-    fn latest_state_request(&self) -> ProtocolOutcomes<C> {
-        let request: HighwayMessage<C> = HighwayMessage::LatestStateRequest(
-            IndexPanorama::from_panorama(self.highway.state().panorama(), self.highway.state()),
-        );
+    ///
+    /// The view is remembered, so that compact units based on it can be decoded.
+    fn latest_state_request(&mut self) -> ProtocolOutcomes<C> {
+        let view =
+            IndexPanorama::from_panorama(self.highway.state().panorama(), self.highway.state());
+        if self.sent_views.len() >= MAX_SENT_VIEWS {
+            self.sent_views.pop_front();
+        }
+        self.sent_views
+            .push_back((view_hash::<C>(&view), view.clone()));
+        let request: HighwayMessage<C> = HighwayMessage::LatestStateRequest(view);
         let payload = (&request).serialize();
         vec![ProtocolOutcome::CreatedMessageToRandomPeer(payload)]
     }
@@ -625,6 +745,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
     fn batch_request(
         &self,
         rng: &mut NodeRng,
+        sender: &NodeId,
         vid: ValidatorIndex,
         our_next_seq: u64,
         their_next_seq: u64,
@@ -684,7 +805,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
                             let unit = state.find_in_swimlane(hash, seq_num).unwrap();
                             state
                                 .wire_unit(unit, *self.highway.instance_id())
-                                .map(|swu| HighwayMessage::NewVertex(Vertex::Unit(swu)))
+                                .map(|swu| self.vertex_message(Vertex::Unit(swu), Some(sender)))
                         })
                         .into_iter()
                         .collect(),
@@ -715,6 +836,9 @@ pub(crate) enum HighwayMessage<C: Context> {
         unit_seq_number: u64,
     },
     LatestStateRequest(IndexPanorama),
+    /// A unit with a compressed value and a panorama relative to the recipient's view or the
+    /// creator's previous unit.
+    NewCompactUnit(CompactUnit<C>),
 }
 
 impl<C: Context> HighwayMessage<C> {
@@ -751,56 +875,33 @@ where
                 );
                 vec![]
             }
-            Ok(HighwayMessage::NewVertex(v)) => {
-                let v_id = v.id();
-                // If we already have that vertex, do not process it.
-                if self.highway.has_dependency(&v_id) {
+            Ok(HighwayMessage::NewVertex(v)) => self.handle_new_vertex(v, sender, msg, now),
+            Ok(HighwayMessage::NewCompactUnit(_)) if self.evidence_only => {
+                trace!("received an irrelevant compact unit");
+                vec![]
+            }
+            Ok(HighwayMessage::NewCompactUnit(compact_unit)) => {
+                let hash = compact_unit.hash();
+                if self.highway.has_dependency(&Dependency::Unit(hash)) {
                     return vec![];
                 }
-                let pvv = match self.pre_validate_vertex(v) {
-                    Ok(pvv) => pvv,
-                    Err((_, err)) => {
-                        trace!("received an invalid vertex");
-                        // drop the vertices that might have depended on this one
-                        let faulty_senders = self.synchronizer.invalid_vertices(vec![v_id]);
-                        return iter::once(ProtocolOutcome::InvalidIncomingMessage(
-                            msg,
+                match compact_unit.decode(
+                    self.highway.state(),
+                    *self.highway.instance_id(),
+                    &self.sent_views,
+                ) {
+                    Ok(swunit) => self.handle_new_vertex(Vertex::Unit(swunit), sender, msg, now),
+                    Err(err) => {
+                        // We might just be missing units the sender assumed we have, or an
+                        // equivocation made the delta ambiguous. Request the full unit instead.
+                        let uuid = rng.next_u64();
+                        debug!(%err, ?uuid, ?hash, "failed to decode compact unit");
+                        let request =
+                            HighwayMessage::RequestDependency(uuid, Dependency::Unit(hash));
+                        vec![ProtocolOutcome::CreatedTargetedMessage(
+                            request.serialize(),
                             sender,
-                            err.into(),
-                        ))
-                        .chain(faulty_senders.into_iter().map(ProtocolOutcome::Disconnect))
-                        .collect();
-                    }
-                };
-                // Keep track of whether the prevalidated vertex was from an equivocator
-                let is_faulty = match pvv.inner().creator() {
-                    Some(creator) => self.highway.state().is_faulty(creator),
-                    None => false,
-                };
-
-                if is_faulty && !self.synchronizer.is_dependency(&pvv.inner().id()) {
-                    trace!("received a vertex from a faulty validator; dropping");
-                    return vec![];
-                }
-
-                match pvv.timestamp() {
-                    Some(timestamp) if timestamp > now + self.config.pending_vertex_timeout => {
-                        trace!("received a vertex with a timestamp far in the future; dropping");
-                        vec![]
-                    }
-                    Some(timestamp) if timestamp > now => {
-                        // If it's not from an equivocator and from the future, add to queue
-                        trace!("received a vertex from the future; storing for later");
-                        self.synchronizer
-                            .store_vertex_for_addition_later(timestamp, now, sender, pvv);
-                        let timer_id = TIMER_ID_VERTEX_WITH_FUTURE_TIMESTAMP;
-                        vec![ProtocolOutcome::ScheduleTimer(timestamp, timer_id)]
-                    }
-                    _ => {
-                        // If it's not from an equivocator or it is a transitive dependency, add the
-                        // vertex
-                        self.log_received_vertex(pvv.inner());
-                        self.synchronizer.schedule_add_vertex(sender, pvv, now)
+                        )]
                     }
                 }
             }
@@ -816,7 +917,7 @@ where
                     }
                     // TODO: Should this be done via a gossip service?
                     GetDepOutcome::Vertex(vv) => vec![ProtocolOutcome::CreatedTargetedMessage(
-                        HighwayMessage::NewVertex(vv.into()).serialize(),
+                        self.vertex_message(vv.into(), Some(&sender)).serialize(),
                         sender,
                     )],
                 }
@@ -848,7 +949,7 @@ where
                     // TODO: Should this be done via a gossip service?
                     GetDepOutcome::Vertex(vv) => {
                         vec![ProtocolOutcome::CreatedTargetedMessage(
-                            HighwayMessage::NewVertex(vv.into()).serialize(),
+                            self.vertex_message(vv.into(), Some(&sender)).serialize(),
                             sender,
                         )]
                     }
@@ -856,6 +957,7 @@ where
             }
             Ok(HighwayMessage::LatestStateRequest(their_index_panorama)) => {
                 trace!("received a request for the latest state");
+                self.peer_views.insert(sender, their_index_panorama.clone());
                 let state = self.highway.state();
 
                 let create_message = |((vid, our_obs), their_obs): (
@@ -883,7 +985,7 @@ where
                         (
                             IndexObservation::NextSeq(our_next_seq),
                             IndexObservation::NextSeq(their_next_seq),
-                        ) => self.batch_request(rng, vid, our_next_seq, their_next_seq),
+                        ) => self.batch_request(rng, &sender, vid, our_next_seq, their_next_seq),
                     }
                 };
 
@@ -941,7 +1043,7 @@ where
         }
     }
 
-    fn handle_is_current(&self, now: Timestamp) -> ProtocolOutcomes<C> {
+    fn handle_is_current(&mut self, now: Timestamp) -> ProtocolOutcomes<C> {
         // Request latest protocol state of the current era.
         let mut outcomes = self.latest_state_request();
         // If configured, schedule periodic latest state requests.
@@ -967,6 +1069,16 @@ where
         self.process_av_effects(effects, now)
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let effects = self.highway.on_signature(&hash, signature, now);
+        self.process_av_effects(effects, now)
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
@@ -1028,6 +1140,8 @@ where
         // TODO: We could also drop the finality detector and round success meter here. Maybe make
         // HighwayProtocol an enum with an EvidenceOnly variant?
         self.pending_values.clear();
+        self.peer_views.clear();
+        self.sent_views.clear();
         self.synchronizer.retain_evidence_only();
         self.highway.retain_evidence_only();
         self.evidence_only = true;
@@ -1061,6 +1175,45 @@ where
             .collect()
     }
 
+    fn evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>> {
+        let vidx = self.highway.validators().get_index(vid)?;
+        match self.highway.get_dependency(&Dependency::Evidence(vidx)) {
+            GetDepOutcome::None | GetDepOutcome::Evidence(_) => None,
+            GetDepOutcome::Vertex(vv) => Some(HighwayMessage::NewVertex(vv.into()).serialize()),
+        }
+    }
+
+    fn restore_evidence(&mut self, evidence: Vec<u8>, now: Timestamp) -> ProtocolOutcomes<C> {
+        let vertex = match bincode::deserialize(evidence.as_slice()) {
+            Ok(HighwayMessage::NewVertex(vertex)) if vertex.is_evidence() => vertex,
+            Ok(_) => {
+                error!("persisted evidence is not an evidence vertex");
+                return vec![];
+            }
+            Err(err) => {
+                error!(%err, "failed to deserialize persisted evidence");
+                return vec![];
+            }
+        };
+        if self.highway.has_vertex(&vertex) {
+            return vec![];
+        }
+        let pvv = match self.pre_validate_vertex(vertex) {
+            Ok(pvv) => pvv,
+            Err((_, err)) => {
+                warn!(%err, "persisted evidence is invalid");
+                return vec![];
+            }
+        };
+        match self.highway.validate_vertex(pvv) {
+            Ok(vv) => self.add_valid_vertex(vv, now),
+            Err((_, err)) => {
+                warn!(%err, "persisted evidence is invalid");
+                vec![]
+            }
+        }
+    }
+
     /// Sets the pause status: While paused we don't create any new units, just pings.
     fn set_paused(&mut self, paused: bool) {
         self.highway.set_paused(paused);
@@ -1091,4 +1244,20 @@ where
     fn next_round_length(&self) -> Option<TimeDiff> {
         self.highway.next_round_length()
     }
+
+    fn leader_schedule(&self) -> Option<LeaderSchedule<C>> {
+        let params = self.highway.state().params();
+        let round_exp = self.round_success_meter.current_round_exp();
+        let leaders = self
+            .highway
+            .state()
+            .leader_schedule(params.start_timestamp(), params.end_timestamp(), round_exp)
+            .take(MAX_LEADER_SCHEDULE_ROUNDS)
+            .filter_map(|(r_id, vidx)| Some((r_id, self.highway.validators().id(vidx)?.clone())))
+            .collect();
+        Some(LeaderSchedule {
+            round_length: state::round_len(round_exp),
+            leaders,
+        })
+    }
 }
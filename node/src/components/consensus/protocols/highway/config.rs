@@ -32,6 +32,12 @@ pub struct Config {
     /// Limits requests per validator in panorama - in order to get a total number of
     /// requests, multiply by # of validators.
     pub max_request_batch_size: usize,
+    /// Send units with a compressed value and a panorama encoded relative to the recipient's view
+    /// or the creator's previous unit, instead of one full observation per validator.
+    ///
+    /// Nodes running an older version can't decode compact units, so this must only be enabled
+    /// once all peers have been upgraded.
+    pub compact_units: bool,
     pub round_success_meter: RSMConfig,
 }
 
@@ -47,6 +53,7 @@ impl Default for Config {
             max_execution_delay: 3,
             max_requests_for_vertex: 5,
             max_request_batch_size: 20,
+            compact_units: false,
             round_success_meter: RSMConfig::default(),
         }
     }
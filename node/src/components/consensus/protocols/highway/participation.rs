@@ -11,7 +11,7 @@ use crate::{
         },
         traits::Context,
     },
-    utils::div_round,
+    utils::{clock, div_round},
 };
 
 /// A validator's participation status: whether they are faulty or inactive.
@@ -67,7 +67,7 @@ impl<C: Context> Participation<C> {
     /// Highway instance.
     #[allow(clippy::integer_arithmetic)] // We use u128 to prevent overflows in weight calculation.
     pub(crate) fn new(highway: &Highway<C>) -> Self {
-        let now = Timestamp::now();
+        let now = clock::now();
         let state = highway.state();
         let mut inactive_w = 0;
         let mut faulty_w = 0;
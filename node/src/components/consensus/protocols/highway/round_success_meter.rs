@@ -5,9 +5,12 @@ use tracing::trace;
 
 use casper_types::Timestamp;
 
-use crate::components::consensus::{
-    highway_core::{finality_detector::FinalityDetector, state, State, Weight},
-    traits::Context,
+use crate::{
+    components::consensus::{
+        highway_core::{finality_detector::FinalityDetector, state, State, Weight},
+        traits::Context,
+    },
+    utils::clock,
 };
 
 pub(crate) mod config;
@@ -102,7 +105,7 @@ impl<C: Context> RoundSuccessMeter<C> {
     /// If the exponent shouldn't grow, and the round ID is divisible by a certain number, a lower
     /// round exponent is returned.
     pub fn calculate_new_exponent(&mut self, state: &State<C>) -> u8 {
-        let now = Timestamp::now();
+        let now = clock::now();
         // if the round hasn't finished, just return whatever we have now
         if state::round_id(now, self.current_round_exp) <= self.current_round_id {
             return self.new_exponent();
@@ -179,6 +182,11 @@ impl<C: Context> RoundSuccessMeter<C> {
         self.rounds.iter().filter(|&success| !success).count()
     }
 
+    /// Returns the round exponent currently in use.
+    pub(super) fn current_round_exp(&self) -> u8 {
+        self.current_round_exp
+    }
+
     /// Returns the round exponent to be used in the next round, based on the previously used round
     /// exponent and the current counts of successes and failures.
     pub(super) fn new_exponent(&self) -> u8 {
@@ -68,6 +68,8 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
+        successor_secret_key_path: None,
+        remote_signer: None,
         highway: HighwayConfig {
             pending_vertex_timeout: "1min".parse().unwrap(),
             standstill_timeout: Some(STANDSTILL_TIMEOUT.parse().unwrap()),
@@ -154,7 +156,7 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = *ALICE_NODE_ID;
@@ -208,7 +210,7 @@ fn send_a_valid_wire_unit() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
 
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
@@ -274,7 +276,7 @@ fn detect_doppelganger() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
@@ -297,3 +299,70 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn restore_persisted_evidence() {
+    let mut rng = TestRng::new();
+    let validators = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let now = Timestamp::zero();
+    // Two different units by Alice with the same sequence number: She equivocated.
+    let unit_msg = |random_bit| {
+        let wunit: WireUnit<ClContext> = WireUnit {
+            panorama: Panorama::from(vec![N, N]),
+            creator: ALICE,
+            instance_id: ClContext::hash(INSTANCE_ID_DATA),
+            value: Some(Arc::new(BlockPayload::new(
+                vec![],
+                vec![],
+                vec![],
+                random_bit,
+            ))),
+            seq_number: 0,
+            timestamp: now,
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        let swunit = SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap();
+        bincode::serialize(&HighwayMessage::NewVertex(Vertex::Unit(swunit))).unwrap()
+    };
+
+    let mut highway_protocol = new_test_highway_protocol(validators.clone(), vec![]);
+    assert!(highway_protocol.evidence(&ALICE_PUBLIC_KEY).is_none());
+    for random_bit in &[false, true] {
+        let mut outcomes =
+            highway_protocol.handle_message(&mut rng, *ALICE_NODE_ID, unit_msg(*random_bit), now);
+        while let Some(outcome) = outcomes.pop() {
+            if let ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) = outcome {
+                outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+            }
+        }
+    }
+    let evidence = highway_protocol
+        .evidence(&ALICE_PUBLIC_KEY)
+        .expect("should have evidence against Alice");
+
+    // A fresh instance, e.g. after a restart, accepts the evidence once.
+    let mut restarted_protocol = new_test_highway_protocol(validators, vec![]);
+    let outcomes = restarted_protocol.restore_evidence(evidence.clone(), now);
+    assert!(
+        outcomes.iter().any(|outcome| matches!(
+            outcome,
+            ProtocolOutcome::NewEvidence(pub_key) if *pub_key == *ALICE_PUBLIC_KEY
+        )),
+        "Unexpected outcomes: {:?}",
+        outcomes
+    );
+    assert!(restarted_protocol.has_evidence(&ALICE_PUBLIC_KEY));
+    assert!(restarted_protocol
+        .restore_evidence(evidence, now)
+        .is_empty());
+
+    // Anything else is rejected.
+    assert!(restarted_protocol
+        .restore_evidence(unit_msg(false), now)
+        .is_empty());
+}
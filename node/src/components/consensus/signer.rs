@@ -0,0 +1,452 @@
+//! Signing of consensus messages and finality signatures.
+//!
+//! A validator either signs with a secret key loaded from disk, or delegates signing to an external
+//! signer, e.g. a daemon with access to an HSM, so the key never has to be stored on the node.
+//!
+//! The external signer is expected to listen on a local address and accept HTTP requests of the
+//! form
+//!
+//! ```text
+//! POST /sign
+//! {"public_key": "<hex-encoded public key>", "data": "<hex-encoded data to sign>"}
+//! ```
+//!
+//! answering with status `200` and a body of the form `{"signature": "<hex-encoded signature>"}`.
+//! Every signature returned by the external signer is verified before it is used.
+//!
+//! If the data is the hash of a Highway unit, the request also contains a field
+//! `"unit": {"instance_id": "<hex-encoded era instance ID>", "seq_number": <sequence number>}`.
+//! Signing two different units with the same sequence number in the same instance, or a unit with
+//! a lower sequence number than one that was already signed, would be an equivocation, so the
+//! external signer should persistently keep track of the units it signed and refuse such requests.
+//!
+//! Requests to the external signer are sent from a blocking task, so they never stall the reactor.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{crypto, EraId, PublicKey, SecretKey, Signature, TimeDiff};
+
+use crate::{
+    types::{BlockHash, FinalitySignature},
+    utils::External,
+};
+
+/// The maximum size of a response accepted from an external signer.
+const MAX_RESPONSE_SIZE: usize = 64 * 1024;
+
+/// Configuration of an external signer.
+#[derive(DataSize, Debug, Deserialize, Clone)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub(crate) struct RemoteSignerConfig {
+    /// Address the external signer listens on.
+    pub(crate) address: SocketAddr,
+    /// Path to the public key file of the key held by the external signer.
+    pub(crate) public_key_path: External,
+    /// Time limit for a single signing request, after which it is abandoned.
+    pub(crate) timeout: TimeDiff,
+}
+
+/// An error while obtaining a signature.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Failed to communicate with the external signer.
+    #[error("failed to communicate with external signer at {address}: {error}")]
+    Io {
+        address: SocketAddr,
+        #[source]
+        error: io::Error,
+    },
+    /// The external signer didn't answer in time.
+    #[error("external signer at {address} did not respond within {timeout}")]
+    Timeout {
+        address: SocketAddr,
+        timeout: TimeDiff,
+    },
+    /// The external signer sent a response that could not be understood.
+    #[error("invalid response from external signer at {address}: {reason}")]
+    InvalidResponse { address: SocketAddr, reason: String },
+    /// The external signer returned a signature that is not valid for the requested data.
+    #[error("external signer at {address} returned an invalid signature: {error}")]
+    InvalidSignature {
+        address: SocketAddr,
+        #[source]
+        error: crypto::Error,
+    },
+    /// The blocking task sending the request panicked or was cancelled.
+    #[error("signing task for external signer at {address} failed: {error}")]
+    Task {
+        address: SocketAddr,
+        #[source]
+        error: tokio::task::JoinError,
+    },
+}
+
+/// Identifies a Highway unit whose hash is to be signed.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub(crate) struct UnitMetadata {
+    /// The instance ID of the era the unit belongs to.
+    pub(crate) instance_id: Digest,
+    /// The unit's sequence number.
+    pub(crate) seq_number: u64,
+}
+
+/// Produces signatures on behalf of this node's validator key.
+#[derive(Clone, DataSize)]
+pub(crate) enum Signer {
+    /// Signs with a secret key held in memory.
+    Local(Arc<SecretKey>),
+    /// Delegates signing to an external signer.
+    Remote(Arc<RemoteSigner>),
+}
+
+impl Debug for Signer {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Signer::Local(_) => write!(formatter, "Signer::Local"),
+            Signer::Remote(remote_signer) => {
+                write!(formatter, "Signer::Remote({})", remote_signer.address)
+            }
+        }
+    }
+}
+
+impl From<Arc<SecretKey>> for Signer {
+    fn from(secret_key: Arc<SecretKey>) -> Self {
+        Signer::Local(secret_key)
+    }
+}
+
+impl Signer {
+    /// Signs `data` with the secret key belonging to `public_key`, if it is held in memory.
+    ///
+    /// Returns `None` if signing is delegated to an external signer.
+    pub(crate) fn sign_locally<T: AsRef<[u8]>>(
+        &self,
+        data: T,
+        public_key: &PublicKey,
+    ) -> Option<Signature> {
+        match self {
+            Signer::Local(secret_key) => Some(crypto::sign(data, secret_key, public_key)),
+            Signer::Remote(_) => None,
+        }
+    }
+
+    /// Signs `data` with the secret key belonging to `public_key`.
+    ///
+    /// If `data` is the hash of a Highway unit, `unit` has to identify it, so that an external
+    /// signer can refuse to equivocate.
+    pub(crate) async fn sign(
+        &self,
+        data: Vec<u8>,
+        public_key: PublicKey,
+        unit: Option<UnitMetadata>,
+    ) -> Result<Signature, Error> {
+        match self {
+            Signer::Local(secret_key) => Ok(crypto::sign(data, secret_key, &public_key)),
+            Signer::Remote(remote_signer) => {
+                let remote_signer = Arc::clone(remote_signer);
+                let address = remote_signer.address;
+                tokio::task::spawn_blocking(move || remote_signer.sign(&data, &public_key, unit))
+                    .await
+                    .map_err(|error| Error::Task { address, error })?
+            }
+        }
+    }
+
+    /// Creates a finality signature for the given block by the validator with `public_key`.
+    pub(crate) async fn finality_signature(
+        &self,
+        block_hash: BlockHash,
+        era_id: EraId,
+        public_key: PublicKey,
+    ) -> Result<FinalitySignature, Error> {
+        let bytes = FinalitySignature::bytes_to_sign(&block_hash, era_id);
+        let signature = self.sign(bytes, public_key.clone(), None).await?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key,
+        })
+    }
+}
+
+/// A client of an external signer.
+#[derive(DataSize, Debug)]
+pub(crate) struct RemoteSigner {
+    address: SocketAddr,
+    timeout: TimeDiff,
+}
+
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    public_key: &'a PublicKey,
+    data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<UnitMetadata>,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: Signature,
+}
+
+impl RemoteSigner {
+    /// Creates a client of the external signer listening on `address`.
+    pub(crate) fn new(address: SocketAddr, timeout: TimeDiff) -> Self {
+        RemoteSigner { address, timeout }
+    }
+
+    /// Requests a signature over `data` from the external signer, and verifies it.
+    ///
+    /// The whole exchange is abandoned once the configured timeout has elapsed. This blocks the
+    /// current thread, so it must not be called from the reactor.
+    fn sign(
+        &self,
+        data: &[u8],
+        public_key: &PublicKey,
+        unit: Option<UnitMetadata>,
+    ) -> Result<Signature, Error> {
+        let request = SignRequest {
+            public_key,
+            data: base16::encode_lower(data),
+            unit,
+        };
+        let body = serde_json::to_vec(&request).map_err(|error| Error::InvalidResponse {
+            address: self.address,
+            reason: format!("failed to encode request: {}", error),
+        })?;
+        let response = self.exchange(&body)?;
+        let SignResponse { signature } =
+            serde_json::from_slice(&response).map_err(|error| Error::InvalidResponse {
+                address: self.address,
+                reason: error.to_string(),
+            })?;
+        crypto::verify(data, &signature, public_key).map_err(|error| Error::InvalidSignature {
+            address: self.address,
+            error,
+        })?;
+        Ok(signature)
+    }
+
+    /// Sends a signing request with the given body and returns the response body.
+    fn exchange(&self, body: &[u8]) -> Result<Vec<u8>, Error> {
+        let timeout: Duration = self.timeout.into();
+        let deadline = Instant::now() + timeout;
+        let remaining = || {
+            deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+                .ok_or(Error::Timeout {
+                    address: self.address,
+                    timeout: self.timeout,
+                })
+        };
+        let io_error = |error: io::Error| match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::Timeout {
+                address: self.address,
+                timeout: self.timeout,
+            },
+            _ => Error::Io {
+                address: self.address,
+                error,
+            },
+        };
+
+        let mut stream =
+            TcpStream::connect_timeout(&self.address, remaining()?).map_err(io_error)?;
+        stream
+            .set_write_timeout(Some(remaining()?))
+            .map_err(io_error)?;
+        let header = format!(
+            "POST /sign HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+            Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.address,
+            body.len()
+        );
+        stream.write_all(header.as_bytes()).map_err(io_error)?;
+        stream.write_all(body).map_err(io_error)?;
+
+        let mut response = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            stream
+                .set_read_timeout(Some(remaining()?))
+                .map_err(io_error)?;
+            match stream.read(&mut buffer).map_err(io_error)? {
+                0 => break,
+                read => response.extend_from_slice(&buffer[..read]),
+            }
+            if response.len() > MAX_RESPONSE_SIZE {
+                return Err(self.invalid_response("response too large"));
+            }
+        }
+
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or_else(|| self.invalid_response("incomplete response"))?;
+        let status_line = response[..header_end]
+            .split(|byte| *byte == b'\n')
+            .next()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(self.invalid_response(&format!("unexpected status: {}", status_line)));
+        }
+        Ok(response.split_off(header_end.saturating_add(4)))
+    }
+
+    fn invalid_response(&self, reason: &str) -> Error {
+        Error::InvalidResponse {
+            address: self.address,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread,
+    };
+
+    use casper_types::{testing::TestRng, SecretKey};
+
+    use super::*;
+
+    /// Spawns an external signer answering a single request with the response produced by
+    /// `respond` from the requested data and the whole request.
+    fn spawn_signer<F>(respond: F) -> SocketAddr
+    where
+        F: FnOnce(Vec<u8>, serde_json::Value) -> Option<String> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Content-Length: ") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let data = base16::decode(request["data"].as_str().unwrap()).unwrap();
+            if let Some(response_body) = respond(data, request) {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    response_body.len(),
+                    response_body
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            } else {
+                thread::sleep(Duration::from_secs(2));
+            }
+        });
+        address
+    }
+
+    fn signature_response(secret_key: &SecretKey, data: &[u8]) -> String {
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(data, secret_key, &public_key);
+        serde_json::json!({ "signature": signature }).to_string()
+    }
+
+    #[tokio::test]
+    async fn should_sign_via_external_signer() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let address = spawn_signer(move |data, _| Some(signature_response(&secret_key, &data)));
+
+        let signer = Signer::Remote(Arc::new(RemoteSigner::new(
+            address,
+            TimeDiff::from_seconds(5),
+        )));
+        let signature = signer
+            .sign(b"block hash".to_vec(), public_key.clone(), None)
+            .await
+            .unwrap();
+        crypto::verify(b"block hash", &signature, &public_key).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_identify_units_to_external_signer() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let instance_id = Digest::hash(b"era");
+        let address = spawn_signer(move |data, request| {
+            assert_eq!(request["unit"]["seq_number"], 7);
+            assert_eq!(
+                request["unit"]["instance_id"],
+                serde_json::to_value(instance_id).unwrap()
+            );
+            Some(signature_response(&secret_key, &data))
+        });
+
+        let signer = Signer::Remote(Arc::new(RemoteSigner::new(
+            address,
+            TimeDiff::from_seconds(5),
+        )));
+        let unit = UnitMetadata {
+            instance_id,
+            seq_number: 7,
+        };
+        let signature = signer
+            .sign(b"unit hash".to_vec(), public_key.clone(), Some(unit))
+            .await
+            .unwrap();
+        crypto::verify(b"unit hash", &signature, &public_key).unwrap();
+    }
+
+    #[test]
+    fn should_reject_signature_by_wrong_key() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let other_public_key = PublicKey::from(&SecretKey::random(&mut rng));
+        let address = spawn_signer(move |data, _| Some(signature_response(&secret_key, &data)));
+
+        let signer = RemoteSigner::new(address, TimeDiff::from_seconds(5));
+        assert!(matches!(
+            signer.sign(b"block hash", &other_public_key, None),
+            Err(Error::InvalidSignature { .. })
+        ));
+    }
+
+    #[test]
+    fn should_time_out_if_external_signer_does_not_respond() {
+        let mut rng = TestRng::new();
+        let public_key = PublicKey::from(&SecretKey::random(&mut rng));
+        let address = spawn_signer(|_, _| None);
+
+        let signer = RemoteSigner::new(address, "200ms".parse().unwrap());
+        assert!(matches!(
+            signer.sign(b"block hash", &public_key, None),
+            Err(Error::Timeout { .. })
+        ));
+    }
+}
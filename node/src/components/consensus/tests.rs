@@ -1,5 +1,11 @@
 /// Basic building blocks for the Discrete Event Simulator (DES).
 pub(crate) mod consensus_des_testing;
+/// Message schedules replayed in the DES.
+pub(crate) mod message_schedule;
+/// Running DES scenarios over many seeds with statistical assertions.
+pub(crate) mod model_checking;
+/// Adapter running production consensus protocols in the DES.
+pub(crate) mod protocol_adapter;
 /// Message queue.
 pub(crate) mod queue;
 /// Miscellaneous code shared among consensus tests
@@ -5,6 +5,7 @@ use std::{
 };
 
 use datasize::DataSize;
+use serde::{Deserialize, Serialize};
 
 use casper_types::Timestamp;
 
@@ -54,7 +55,9 @@ impl<M: Clone + Debug> TargetedMessage<M> {
     }
 }
 
-#[derive(Debug, Clone, DataSize, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(
+    Debug, Clone, DataSize, Copy, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
 pub(crate) struct ValidatorId(pub(crate) u64);
 
 impl Display for ValidatorId {
@@ -73,6 +76,14 @@ pub(crate) enum Fault {
     Equivocate,
 }
 
+/// A validator implementation that knows whether it follows the protocol.
+///
+/// Assertions about agreement and progress only consider honest validators.
+pub(crate) trait Honesty {
+    /// Returns whether the validator follows the protocol, i.e. is not configured to be faulty.
+    fn is_honest(&self) -> bool;
+}
+
 /// A validator in the test network.
 #[derive(Debug)]
 pub(crate) struct Node<C, M, V>
@@ -123,6 +134,11 @@ where
         self.finalized_values.iter()
     }
 
+    /// Iterator over the messages delivered to the validator.
+    pub(crate) fn messages_received(&self) -> impl Iterator<Item = &Message<M>> {
+        self.messages_received.iter()
+    }
+
     pub(crate) fn messages_produced(&self) -> impl Iterator<Item = &M> {
         self.messages_produced.iter()
     }
@@ -142,7 +158,6 @@ where
 
 pub(crate) enum DeliverySchedule {
     AtInstant(Timestamp),
-    #[allow(dead_code)] // Drop variant used in tests.
     Drop,
 }
 
@@ -164,6 +179,114 @@ impl From<Timestamp> for DeliverySchedule {
     }
 }
 
+/// A message whose network traffic can be accounted for.
+pub(crate) trait MessageTraffic {
+    /// Returns the message type and the size of the serialized message in bytes, or `None` if the
+    /// message never leaves its node, e.g. a timer.
+    fn traffic(&self) -> Option<(&'static str, usize)>;
+}
+
+/// The kind of a message, so that delivery strategies can single out e.g. proposals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum MessageKind {
+    /// A unit proposing a consensus value.
+    Proposal,
+    /// A unit without a consensus value, i.e. a vote for earlier units.
+    Vote,
+    /// Endorsements of units.
+    Endorsement,
+    /// Evidence of a validator's fault.
+    Evidence,
+    /// A ping, announcing that a validator is online.
+    Ping,
+    /// A request for missing dependencies or for the latest protocol state.
+    SyncRequest,
+    /// A message a node sends to itself, e.g. a timer.
+    Local,
+    /// A consensus message that could not be classified.
+    Other,
+}
+
+/// A message whose kind can be inspected, e.g. by delivery strategies.
+pub(crate) trait ClassifyMessage {
+    /// Returns the kind of the message.
+    fn kind(&self) -> MessageKind;
+}
+
+/// The number of messages and the number of serialized bytes sent over the network.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Traffic {
+    pub(crate) messages: u64,
+    pub(crate) bytes: u64,
+}
+
+impl Traffic {
+    /// Returns the average number of messages and bytes per finalized value, or `None` if no
+    /// value was finalized.
+    pub(crate) fn per_value(&self, finalized_count: usize) -> Option<(f64, f64)> {
+        if finalized_count == 0 {
+            return None;
+        }
+        let count = finalized_count as f64;
+        Some((self.messages as f64 / count, self.bytes as f64 / count))
+    }
+}
+
+/// Message and bit complexity of a test run: the network traffic, broken down by message type.
+///
+/// A message sent to several recipients is counted once per recipient, so that protocol variants
+/// using broadcasts and targeted messages can be compared.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct TrafficStats {
+    by_type: BTreeMap<&'static str, Traffic>,
+}
+
+impl TrafficStats {
+    /// Records one message of the given type and serialized size.
+    pub(crate) fn record(&mut self, message_type: &'static str, size: usize) {
+        let traffic = self.by_type.entry(message_type).or_default();
+        traffic.messages += 1;
+        traffic.bytes += size as u64;
+    }
+
+    /// Returns the traffic of the given message type.
+    pub(crate) fn of_type(&self, message_type: &str) -> Traffic {
+        self.by_type.get(message_type).copied().unwrap_or_default()
+    }
+
+    /// Returns the traffic by message type.
+    pub(crate) fn by_type(&self) -> impl Iterator<Item = (&'static str, Traffic)> + '_ {
+        self.by_type
+            .iter()
+            .map(|(message_type, traffic)| (*message_type, *traffic))
+    }
+
+    /// Returns the traffic of all message types combined.
+    pub(crate) fn total(&self) -> Traffic {
+        self.by_type
+            .values()
+            .fold(Traffic::default(), |total, traffic| Traffic {
+                messages: total.messages + traffic.messages,
+                bytes: total.bytes + traffic.bytes,
+            })
+    }
+}
+
+impl Display for TrafficStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let total = self.total();
+        write!(f, "{} messages, {} bytes", total.messages, total.bytes)?;
+        for (message_type, traffic) in self.by_type() {
+            write!(
+                f,
+                "; {}: {} messages, {} bytes",
+                message_type, traffic.messages, traffic.bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
 pub(crate) struct VirtualNet<C, M, V>
 where
     M: MessageT,
@@ -172,6 +295,8 @@ where
     validators_map: BTreeMap<ValidatorId, Node<C, M, V>>,
     /// A collection of all network messages queued up for delivery.
     msg_queue: Queue<M>,
+    /// The network traffic dispatched so far.
+    traffic: TrafficStats,
 }
 
 impl<C, M, V> VirtualNet<C, M, V>
@@ -195,21 +320,7 @@ where
         VirtualNet {
             validators_map,
             msg_queue: q,
-        }
-    }
-
-    /// Dispatches messages to their recipients.
-    pub(crate) fn dispatch_messages(&mut self, messages: Vec<(TargetedMessage<M>, Timestamp)>) {
-        for (TargetedMessage { message, target }, delivery_time) in messages {
-            let recipients = match target {
-                Target::AllExcept(creator) => self
-                    .validators_ids()
-                    .filter(|id| **id != creator)
-                    .cloned()
-                    .collect(),
-                Target::SingleValidator(recipient_id) => vec![recipient_id],
-            };
-            self.send_messages(recipients, message, delivery_time)
+            traffic: TrafficStats::default(),
         }
     }
 
@@ -241,18 +352,6 @@ where
         self.validators_map.values()
     }
 
-    // Utility function for dispatching message to multiple recipients.
-    fn send_messages<I: IntoIterator<Item = ValidatorId>>(
-        &mut self,
-        recipients: I,
-        message: Message<M>,
-        delivery_time: Timestamp,
-    ) {
-        for validator_id in recipients {
-            self.schedule_message(delivery_time, validator_id, message.clone())
-        }
-    }
-
     /// Schedules a message `message` to be delivered at `delivery_time` to `recipient` validator.
     fn schedule_message(
         &mut self,
@@ -264,6 +363,11 @@ where
         self.msg_queue.push(qe);
     }
 
+    /// Returns the network traffic dispatched so far.
+    pub(crate) fn traffic(&self) -> &TrafficStats {
+        &self.traffic
+    }
+
     /// Drops all messages from the queue.
     /// Should never be called during normal operation of the test.
     pub(crate) fn empty_queue(&mut self) {
@@ -271,14 +375,172 @@ where
     }
 }
 
+impl<C, M, V> VirtualNet<C, M, V>
+where
+    M: MessageT,
+    V: Honesty,
+{
+    /// Returns the validators that follow the protocol.
+    pub(crate) fn honest_validators(&self) -> impl Iterator<Item = &Node<C, M, V>> {
+        self.validators()
+            .filter(|node| node.validator().is_honest())
+    }
+}
+
+impl<C, M, V> VirtualNet<C, M, V>
+where
+    M: MessageT + MessageTraffic,
+{
+    /// Dispatches messages to their recipients.
+    pub(crate) fn dispatch_messages(&mut self, messages: Vec<(TargetedMessage<M>, Timestamp)>) {
+        for (TargetedMessage { message, target }, delivery_time) in messages {
+            let recipients = match target {
+                Target::AllExcept(creator) => self
+                    .validators_ids()
+                    .filter(|id| **id != creator)
+                    .cloned()
+                    .collect(),
+                Target::SingleValidator(recipient_id) => vec![recipient_id],
+            };
+            self.send_messages(recipients, message, delivery_time)
+        }
+    }
+
+    // Utility function for dispatching message to multiple recipients.
+    // Every copy of a network message is accounted for separately.
+    fn send_messages<I: IntoIterator<Item = ValidatorId>>(
+        &mut self,
+        recipients: I,
+        message: Message<M>,
+        delivery_time: Timestamp,
+    ) {
+        let traffic = message.payload.traffic();
+        for validator_id in recipients {
+            if let Some((message_type, size)) = traffic {
+                self.traffic.record(message_type, size);
+            }
+            self.schedule_message(delivery_time, validator_id, message.clone())
+        }
+    }
+}
+
+/// Asserts that the sequences of values finalized by the honest validators agree, i.e. that each
+/// of them is a prefix of the longest one.
+///
+/// Returns the length of the common prefix, i.e. the number of values finalized by every honest
+/// validator.
+pub(crate) fn assert_common_prefix<C, M, V>(virtual_net: &VirtualNet<C, M, V>) -> usize
+where
+    C: PartialEq + Debug,
+    M: MessageT,
+    V: Honesty,
+{
+    let longest = match virtual_net
+        .honest_validators()
+        .max_by_key(|node| node.finalized_count())
+    {
+        Some(longest) => longest,
+        None => return 0,
+    };
+    let mut common_prefix_len = longest.finalized_count();
+    for node in virtual_net.honest_validators() {
+        let divergence = longest
+            .finalized_values()
+            .zip(node.finalized_values())
+            .enumerate()
+            .find(|(_, (expected, actual))| expected != actual);
+        if let Some((index, (expected, actual))) = divergence {
+            panic!(
+                "validator {} finalized {:?} at position {}, but validator {} finalized {:?}",
+                node.id, actual, index, longest.id, expected
+            );
+        }
+        common_prefix_len = common_prefix_len.min(node.finalized_count());
+    }
+    common_prefix_len
+}
+
+/// Returns how many honest validators lag behind the honest validator with the most finalized
+/// values by how many values.
+///
+/// A network where every honest validator finalized the same number of values results in a single
+/// entry with lag 0.
+pub(crate) fn finalization_lag_histogram<C, M, V>(
+    virtual_net: &VirtualNet<C, M, V>,
+) -> BTreeMap<usize, usize>
+where
+    M: MessageT,
+    V: Honesty,
+{
+    let max_count = virtual_net
+        .honest_validators()
+        .map(Node::finalized_count)
+        .max()
+        .unwrap_or_default();
+    let mut histogram = BTreeMap::new();
+    for node in virtual_net.honest_validators() {
+        *histogram
+            .entry(max_count - node.finalized_count())
+            .or_default() += 1;
+    }
+    histogram
+}
+
 mod virtual_net_tests {
-    use super::{Message, Node, Target, TargetedMessage, Timestamp, ValidatorId, VirtualNet};
+    use std::collections::BTreeMap;
+
+    use super::{
+        assert_common_prefix, finalization_lag_histogram, Honesty, Message, MessageTraffic, Node,
+        Target, TargetedMessage, Timestamp, Traffic, ValidatorId, VirtualNet,
+    };
 
     type M = u64;
     type C = u64;
 
     struct NoOpValidator;
 
+    impl Honesty for NoOpValidator {
+        fn is_honest(&self) -> bool {
+            true
+        }
+    }
+
+    /// A validator that is honest or faulty, finalizing the given values.
+    struct FinalizingValidator {
+        honest: bool,
+    }
+
+    impl Honesty for FinalizingValidator {
+        fn is_honest(&self) -> bool {
+            self.honest
+        }
+    }
+
+    fn finalizing_net(finalized: Vec<(bool, Vec<C>)>) -> VirtualNet<C, M, FinalizingValidator> {
+        let nodes = finalized
+            .into_iter()
+            .enumerate()
+            .map(|(index, (honest, values))| {
+                let mut node = Node::new(ValidatorId(index as u64), FinalizingValidator { honest });
+                values
+                    .into_iter()
+                    .for_each(|value| node.push_finalized(value));
+                node
+            });
+        VirtualNet::new(nodes, vec![])
+    }
+
+    /// Even numbers are network messages of their own size, odd ones stay local.
+    impl MessageTraffic for u64 {
+        fn traffic(&self) -> Option<(&'static str, usize)> {
+            if self % 2 == 0 {
+                Some(("even", *self as usize))
+            } else {
+                None
+            }
+        }
+    }
+
     #[test]
     fn messages_are_enqueued_in_order() {
         let validator_id = ValidatorId(1u64);
@@ -335,4 +597,72 @@ mod virtual_net_tests {
             "A broadcast message should be delivered to every node but the creator."
         );
     }
+
+    #[test]
+    fn traffic_is_counted_per_recipient() {
+        let validator_id = ValidatorId(1u64);
+        let nodes: Vec<Node<C, M, NoOpValidator>> = (1..=4)
+            .map(|id| Node::new(ValidatorId(id), NoOpValidator))
+            .collect();
+        let mut virtual_net = VirtualNet::new(nodes, vec![]);
+
+        let broadcast = TargetedMessage::new(
+            Message::new(validator_id, 10u64),
+            Target::AllExcept(validator_id),
+        );
+        let targeted = TargetedMessage::new(
+            Message::new(validator_id, 4u64),
+            Target::SingleValidator(ValidatorId(2)),
+        );
+        let local = TargetedMessage::new(
+            Message::new(validator_id, 3u64),
+            Target::SingleValidator(validator_id),
+        );
+        virtual_net.dispatch_messages(vec![
+            (broadcast, 2.into()),
+            (targeted, 2.into()),
+            (local, 2.into()),
+        ]);
+
+        let traffic = virtual_net.traffic();
+        let expected = Traffic {
+            messages: 4,
+            bytes: 34,
+        };
+        assert_eq!(traffic.of_type("even"), expected);
+        assert_eq!(traffic.total(), expected);
+        assert_eq!(traffic.by_type().count(), 1);
+        assert_eq!(expected.per_value(2), Some((2.0, 17.0)));
+        assert_eq!(expected.per_value(0), None);
+    }
+
+    #[test]
+    fn common_prefix_ignores_faulty_validators() {
+        let virtual_net = finalizing_net(vec![
+            (true, vec![1, 2, 3]),
+            (true, vec![1, 2]),
+            (true, vec![1, 2, 3]),
+            (false, vec![1, 5]),
+        ]);
+        assert_eq!(assert_common_prefix(&virtual_net), 2);
+
+        let mut expected = BTreeMap::new();
+        expected.insert(0, 2);
+        expected.insert(1, 1);
+        assert_eq!(finalization_lag_histogram(&virtual_net), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "validator 1 finalized 5 at position 1")]
+    fn common_prefix_detects_diverging_honest_validators() {
+        let virtual_net = finalizing_net(vec![(true, vec![1, 2, 3]), (true, vec![1, 5])]);
+        assert_common_prefix(&virtual_net);
+    }
+
+    #[test]
+    fn empty_network_has_empty_common_prefix() {
+        let virtual_net = finalizing_net(vec![(false, vec![1])]);
+        assert_eq!(assert_common_prefix(&virtual_net), 0);
+        assert!(finalization_lag_histogram(&virtual_net).is_empty());
+    }
 }
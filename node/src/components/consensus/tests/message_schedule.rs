@@ -0,0 +1,234 @@
+//! Message schedules, e.g. recorded from a real network, to be replayed in simulations.
+//!
+//! A schedule is a JSON array of messages, each with its delivery time, sender, recipient and
+//! payload.  Loaded schedules can be passed as the `init_messages` of `VirtualNet::new`, so that an
+//! incident can be reproduced deterministically to verify a fix.
+//!
+//! Schedules of consensus messages can be extracted from the JSON logs of nodes running with the
+//! `casper_node::components::consensus=trace` log filter, which record every incoming consensus
+//! message.
+
+use std::{collections::BTreeMap, convert::TryFrom, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use casper_types::{EraId, Timestamp};
+
+use super::{
+    consensus_des_testing::{Message, ValidatorId},
+    protocol_adapter::ProtocolMessage,
+    queue::{MessageT, QueueEntry},
+};
+use crate::{tls::KeyFingerprint, types::NodeId};
+
+/// The message of the event logged for every incoming consensus message.
+const RECEIVED_MESSAGE: &str = "received a consensus message";
+
+/// A message in a schedule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ScheduledMessage<M> {
+    /// Time at which the message is delivered.
+    pub(crate) delivery_time: Timestamp,
+    pub(crate) sender: ValidatorId,
+    pub(crate) recipient: ValidatorId,
+    pub(crate) payload: M,
+}
+
+impl<M: MessageT> From<ScheduledMessage<M>> for QueueEntry<M> {
+    fn from(scheduled: ScheduledMessage<M>) -> Self {
+        QueueEntry::new(
+            scheduled.delivery_time,
+            scheduled.recipient,
+            Message::new(scheduled.sender, scheduled.payload),
+        )
+    }
+}
+
+/// Parses a JSON message schedule into queue entries for `VirtualNet::new`.
+pub(crate) fn from_json<M>(json: &str) -> serde_json::Result<Vec<QueueEntry<M>>>
+where
+    M: MessageT + DeserializeOwned,
+{
+    let schedule: Vec<ScheduledMessage<M>> = serde_json::from_str(json)?;
+    Ok(schedule.into_iter().map(QueueEntry::from).collect())
+}
+
+/// Loads a JSON message schedule fixture from `path`, panicking if it is missing or malformed.
+pub(crate) fn load<M>(path: &Path) -> Vec<QueueEntry<M>>
+where
+    M: MessageT + DeserializeOwned,
+{
+    let json = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("could not read {}: {}", path.display(), error));
+    from_json(&json).unwrap_or_else(|error| panic!("could not parse {}: {}", path.display(), error))
+}
+
+/// A line of a node's JSON log.
+#[derive(Deserialize)]
+struct LogLine {
+    timestamp: String,
+    fields: LogFields,
+}
+
+#[derive(Deserialize)]
+struct LogFields {
+    message: String,
+    era: Option<u64>,
+    sender: Option<String>,
+    payload: Option<String>,
+}
+
+/// Extracts the consensus messages of era `era_id` from the JSON log of the node simulated as
+/// `recipient`, ordered by delivery time.
+///
+/// `senders` maps the node IDs of the validators, as logged, to their IDs in the simulation.
+/// Messages from any other sender are dropped, as are all lines other than incoming consensus
+/// messages.
+pub(crate) fn from_node_log(
+    log: &str,
+    recipient: ValidatorId,
+    era_id: EraId,
+    senders: &BTreeMap<NodeId, ValidatorId>,
+) -> Vec<ScheduledMessage<ProtocolMessage>> {
+    let mut schedule: Vec<_> = log
+        .lines()
+        .filter_map(|line| serde_json::from_str::<LogLine>(line).ok())
+        .filter(|line| {
+            line.fields.message == RECEIVED_MESSAGE && line.fields.era == Some(era_id.value())
+        })
+        .filter_map(|line| {
+            let sender = parse_node_id(line.fields.sender.as_deref()?)?;
+            Some(ScheduledMessage {
+                delivery_time: line.timestamp.parse().ok()?,
+                sender: *senders.get(&sender)?,
+                recipient,
+                payload: ProtocolMessage::Consensus(
+                    base16::decode(line.fields.payload.as_deref()?).ok()?,
+                ),
+            })
+        })
+        .collect();
+    schedule.sort_by_key(|scheduled| scheduled.delivery_time);
+    schedule
+}
+
+/// Parses a node ID in its `Debug` representation, e.g. `NodeId(0a1b...)`.
+fn parse_node_id(node_id: &str) -> Option<NodeId> {
+    let hex = node_id.strip_prefix("NodeId(")?.strip_suffix(')')?;
+    let bytes = base16::decode(hex).ok()?;
+    let fingerprint = <[u8; KeyFingerprint::LENGTH]>::try_from(bytes.as_slice()).ok()?;
+    Some(NodeId::from(fingerprint))
+}
+
+mod message_schedule_tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+    use crate::components::consensus::tests::consensus_des_testing::{Node, VirtualNet};
+
+    struct NoOpValidator;
+
+    #[test]
+    fn should_load_schedule_into_virtual_net() {
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        let path = tempdir.path().join("schedule.json");
+        fs::write(
+            &path,
+            r#"[
+                {
+                    "delivery_time": "2022-07-01T10:00:02.000Z",
+                    "sender": 1,
+                    "recipient": 2,
+                    "payload": 7
+                },
+                {
+                    "delivery_time": "2022-07-01T10:00:01.000Z",
+                    "sender": 2,
+                    "recipient": 1,
+                    "payload": 3
+                }
+            ]"#,
+        )
+        .expect("should write schedule");
+
+        let nodes = vec![
+            Node::<u64, u64, _>::new(ValidatorId(1), NoOpValidator),
+            Node::new(ValidatorId(2), NoOpValidator),
+        ];
+        let mut virtual_net = VirtualNet::new(nodes, load(&path));
+
+        let first = virtual_net.pop_message().expect("should have a message");
+        assert_eq!(first.recipient, ValidatorId(1));
+        assert_eq!(first.message, Message::new(ValidatorId(2), 3));
+        let second = virtual_net.pop_message().expect("should have a message");
+        assert_eq!(second.recipient, ValidatorId(2));
+        assert_eq!(second.message, Message::new(ValidatorId(1), 7));
+        assert!(virtual_net.pop_message().is_none());
+    }
+
+    #[test]
+    fn should_reject_malformed_schedule() {
+        assert!(from_json::<u64>(r#"[{"sender": 1, "recipient": 2, "payload": 7}]"#).is_err());
+        assert!(from_json::<ProtocolMessage>(
+            r#"[{
+                "delivery_time": "2022-07-01T10:00:01.000Z",
+                "sender": 1,
+                "recipient": 2,
+                "payload": {"Consensus": "not hex"}
+            }]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn should_convert_node_log() {
+        let mut rng = TestRng::new();
+        let alice = NodeId::random(&mut rng);
+        let bob = NodeId::random(&mut rng);
+        let stranger = NodeId::random(&mut rng);
+        let senders = vec![(alice, ValidatorId(0)), (bob, ValidatorId(1))]
+            .into_iter()
+            .collect();
+        let log_line = |timestamp: &str, era: u64, sender: &NodeId, payload: &str| {
+            format!(
+                r#"{{"timestamp":"{}","level":"TRACE","fields":{{"message":"{}","era":{},"sender":"{:?}","payload":"{}"}},"target":"casper_node::components::consensus::era_supervisor"}}"#,
+                timestamp, RECEIVED_MESSAGE, era, sender, payload
+            )
+        };
+        let log = [
+            log_line("2022-07-01T10:00:02.5Z", 3, &bob, "0b0c"),
+            "not a JSON log line".to_string(),
+            r#"{"timestamp":"2022-07-01T10:00:00Z","level":"INFO","fields":{"message":"started"}}"#
+                .to_string(),
+            log_line("2022-07-01T10:00:01.25Z", 3, &alice, "0a"),
+            log_line("2022-07-01T10:00:01.5Z", 2, &alice, "0d"),
+            log_line("2022-07-01T10:00:01.75Z", 3, &stranger, "0e"),
+        ]
+        .join("\n");
+
+        let schedule = from_node_log(&log, ValidatorId(2), EraId::new(3), &senders);
+        let expected = vec![
+            ScheduledMessage {
+                delivery_time: "2022-07-01T10:00:01.25Z".parse().unwrap(),
+                sender: ValidatorId(0),
+                recipient: ValidatorId(2),
+                payload: ProtocolMessage::Consensus(vec![10]),
+            },
+            ScheduledMessage {
+                delivery_time: "2022-07-01T10:00:02.5Z".parse().unwrap(),
+                sender: ValidatorId(1),
+                recipient: ValidatorId(2),
+                payload: ProtocolMessage::Consensus(vec![11, 12]),
+            },
+        ];
+        assert_eq!(schedule, expected);
+
+        // The converted schedule can be stored as a fixture and loaded again.
+        let json = serde_json::to_string_pretty(&schedule).expect("should serialize schedule");
+        let entries: Vec<QueueEntry<ProtocolMessage>> =
+            from_json(&json).expect("should parse schedule");
+        let expected_entries: Vec<_> = expected.into_iter().map(QueueEntry::from).collect();
+        assert_eq!(entries, expected_entries);
+    }
+}
@@ -0,0 +1,473 @@
+//! Probabilistic model checking: running a DES scenario over many seeds.
+//!
+//! A single simulation only shows that a protocol behaves well for one particular random choice of
+//! validator weights, message delays and faults. The [`ModelChecker`] instead executes a scenario
+//! for a large number of seeds in parallel and evaluates statistical assertions over all outcomes,
+//! e.g. "all values are finalized within 10 rounds in at least 99.9% of runs". The resulting
+//! [`ModelCheckReport`] summarizes each assertion and metric, and lists the outlier seeds.
+//!
+//! Every run uses its own `TestRng`, so any reported seed can be replayed on its own, with full
+//! logging, by running the test again with the env var `CL_TEST_SEED` set to it. The number of
+//! runs can be raised, e.g. for a nightly job, with the env var `CL_MODEL_CHECK_RUNS`.
+
+#![allow(clippy::integer_arithmetic)] // In tests, overflows panic anyway.
+
+use std::{
+    any::Any,
+    env,
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use hex_fmt::HexFmt;
+use rand::{Rng, SeedableRng};
+use tracing::info;
+
+use crate::NodeRng;
+
+/// The env var which, if set, makes the model checker only replay the run with that seed.
+const CL_TEST_SEED: &str = "CL_TEST_SEED";
+/// The env var overriding the number of runs of every model check.
+const CL_MODEL_CHECK_RUNS: &str = "CL_MODEL_CHECK_RUNS";
+/// The default maximum number of outlier seeds listed per assertion or metric.
+const DEFAULT_MAX_REPORTED_OUTLIERS: usize = 10;
+
+/// The seed of a single run.
+pub(crate) type Seed = <NodeRng as SeedableRng>::Seed;
+
+/// A check that a predicate holds in at least a given share of the runs.
+struct StatisticalAssertion<O> {
+    description: String,
+    min_ratio: f64,
+    predicate: Box<dyn Fn(&O) -> bool + Sync>,
+}
+
+/// A value computed from every successful run, summarized by its distribution.
+struct Metric<O> {
+    name: String,
+    value: Box<dyn Fn(&O) -> f64 + Sync>,
+}
+
+/// Runs a scenario for many seeds and evaluates statistical assertions over the outcomes.
+pub(crate) struct ModelChecker<O> {
+    /// The number of seeds to run the scenario for.
+    runs: usize,
+    /// The number of runs executed in parallel.
+    /// Defaults to the number of CPUs.
+    parallelism: usize,
+    /// The maximum number of outlier seeds listed per assertion or metric.
+    max_reported_outliers: usize,
+    assertions: Vec<StatisticalAssertion<O>>,
+    metrics: Vec<Metric<O>>,
+}
+
+impl<O: Send> ModelChecker<O> {
+    /// Creates a model checker executing `runs` runs, unless overridden by `CL_MODEL_CHECK_RUNS`.
+    pub(crate) fn new(runs: usize) -> Self {
+        let runs = match env::var(CL_MODEL_CHECK_RUNS) {
+            Ok(runs) => runs
+                .parse()
+                .unwrap_or_else(|_| panic!("can't parse '{}' as a number of runs", runs)),
+            Err(_) => runs,
+        };
+        assert!(runs > 0, "a model check needs at least one run");
+        ModelChecker {
+            runs,
+            parallelism: num_cpus::get(),
+            max_reported_outliers: DEFAULT_MAX_REPORTED_OUTLIERS,
+            assertions: vec![],
+            metrics: vec![],
+        }
+    }
+
+    /// Sets the number of runs executed in parallel.
+    pub(crate) fn parallelism(mut self, parallelism: usize) -> Self {
+        assert!(parallelism > 0);
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Sets the maximum number of outlier seeds listed per assertion or metric.
+    pub(crate) fn max_reported_outliers(mut self, count: usize) -> Self {
+        self.max_reported_outliers = count;
+        self
+    }
+
+    /// Asserts that `predicate` holds for the outcomes of at least `min_ratio` of all runs.
+    ///
+    /// Runs that returned an error or panicked count as runs in which the predicate doesn't hold.
+    pub(crate) fn assert_ratio<F>(mut self, description: &str, min_ratio: f64, predicate: F) -> Self
+    where
+        F: Fn(&O) -> bool + Sync + 'static,
+    {
+        assert!((0.0..=1.0).contains(&min_ratio));
+        self.assertions.push(StatisticalAssertion {
+            description: description.to_string(),
+            min_ratio,
+            predicate: Box::new(predicate),
+        });
+        self
+    }
+
+    /// Adds a metric whose distribution is included in the report. Higher values are considered
+    /// worse, so the seeds of the runs with the highest values are listed as outliers.
+    pub(crate) fn metric<F>(mut self, name: &str, value: F) -> Self
+    where
+        F: Fn(&O) -> f64 + Sync + 'static,
+    {
+        self.metrics.push(Metric {
+            name: name.to_string(),
+            value: Box::new(value),
+        });
+        self
+    }
+
+    /// Executes `scenario` for every seed and evaluates the assertions and metrics.
+    ///
+    /// If `CL_TEST_SEED` is set, only the run with that seed is executed.
+    pub(crate) fn run<S, E>(&self, scenario: S) -> ModelCheckReport
+    where
+        S: Fn(&mut NodeRng) -> Result<O, E> + Sync,
+        E: Display,
+    {
+        let seeds = match env::var(CL_TEST_SEED) {
+            Ok(seed_as_hex) => {
+                let mut seed = Seed::default();
+                base16::decode_slice(&seed_as_hex, &mut seed).unwrap_or_else(|error| {
+                    panic!("can't parse '{}' as a TestRng seed: {}", seed_as_hex, error)
+                });
+                info!(seed = %seed_as_hex, "replaying a single model checking run");
+                vec![seed]
+            }
+            Err(_) => {
+                let mut seeds = vec![Seed::default(); self.runs];
+                let mut seed_rng = rand::thread_rng();
+                seeds.iter_mut().for_each(|seed| seed_rng.fill(seed));
+                seeds
+            }
+        };
+        self.run_seeds(&seeds, scenario)
+    }
+
+    /// Executes `scenario` for each of the given seeds and evaluates the assertions and metrics.
+    fn run_seeds<S, E>(&self, seeds: &[Seed], scenario: S) -> ModelCheckReport
+    where
+        S: Fn(&mut NodeRng) -> Result<O, E> + Sync,
+        E: Display,
+    {
+        let next_run = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(seeds.len()));
+        thread::scope(|scope| {
+            for _ in 0..self.parallelism.min(seeds.len()) {
+                scope.spawn(|| loop {
+                    let index = next_run.fetch_add(1, Ordering::SeqCst);
+                    let seed = match seeds.get(index) {
+                        Some(seed) => *seed,
+                        None => break,
+                    };
+                    let result = run_seed(&scenario, seed);
+                    results.lock().expect("lock poisoned").push((index, result));
+                });
+            }
+        });
+
+        let mut results = results.into_inner().expect("lock poisoned");
+        results.sort_by_key(|(index, _)| *index);
+        self.evaluate(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    fn evaluate(&self, results: Vec<RunResult<O>>) -> ModelCheckReport {
+        let runs = results.len();
+        let errors: Vec<(Seed, String)> = results
+            .iter()
+            .filter_map(|result| match &result.outcome {
+                Ok(_) => None,
+                Err(error) => Some((result.seed, error.clone())),
+            })
+            .collect();
+
+        let assertions = self
+            .assertions
+            .iter()
+            .map(|assertion| {
+                let failed_seeds: Vec<Seed> = results
+                    .iter()
+                    .filter(|result| {
+                        !result
+                            .outcome
+                            .as_ref()
+                            .map_or(false, |outcome| (assertion.predicate)(outcome))
+                    })
+                    .map(|result| result.seed)
+                    .collect();
+                AssertionSummary {
+                    description: assertion.description.clone(),
+                    min_ratio: assertion.min_ratio,
+                    ratio: (runs - failed_seeds.len()) as f64 / runs as f64,
+                    failed_runs: failed_seeds.len(),
+                    outlier_seeds: failed_seeds
+                        .into_iter()
+                        .take(self.max_reported_outliers)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let metrics = self
+            .metrics
+            .iter()
+            .filter_map(|metric| {
+                let mut values: Vec<(f64, Seed)> = results
+                    .iter()
+                    .filter_map(|result| {
+                        let outcome = result.outcome.as_ref().ok()?;
+                        Some(((metric.value)(outcome), result.seed))
+                    })
+                    .collect();
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+                let quantile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize].0;
+                Some(MetricSummary {
+                    name: metric.name.clone(),
+                    min: quantile(0.0),
+                    median: quantile(0.5),
+                    p99: quantile(0.99),
+                    max: quantile(1.0),
+                    outliers: values
+                        .iter()
+                        .rev()
+                        .take(self.max_reported_outliers)
+                        .copied()
+                        .collect(),
+                })
+            })
+            .collect();
+
+        ModelCheckReport {
+            runs,
+            errors,
+            assertions,
+            metrics,
+        }
+    }
+}
+
+/// The seed and outcome of a single run. The outcome is an error message if the run failed.
+struct RunResult<O> {
+    seed: Seed,
+    outcome: Result<O, String>,
+}
+
+/// Executes `scenario` with the given seed on a new thread, since `TestRng` allows only one
+/// instance per thread, and turns a panic into an error.
+fn run_seed<O, S, E>(scenario: &S, seed: Seed) -> RunResult<O>
+where
+    O: Send,
+    S: Fn(&mut NodeRng) -> Result<O, E> + Sync,
+    E: Display,
+{
+    let outcome = thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let mut rng = NodeRng::from_seed(seed);
+                scenario(&mut rng).map_err(|error| error.to_string())
+            })
+            .join()
+            .unwrap_or_else(|panic| Err(panic_message(panic)))
+    });
+    RunResult { seed, outcome }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload");
+    format!("panicked: {}", message)
+}
+
+/// The evaluation of a statistical assertion.
+#[derive(Debug)]
+pub(crate) struct AssertionSummary {
+    pub(crate) description: String,
+    pub(crate) min_ratio: f64,
+    /// The share of runs in which the predicate held.
+    pub(crate) ratio: f64,
+    /// The number of runs in which the predicate didn't hold.
+    pub(crate) failed_runs: usize,
+    /// The seeds of (some of) the runs in which the predicate didn't hold.
+    pub(crate) outlier_seeds: Vec<Seed>,
+}
+
+impl AssertionSummary {
+    pub(crate) fn passed(&self) -> bool {
+        self.ratio >= self.min_ratio
+    }
+}
+
+/// The distribution of a metric over all successful runs.
+#[derive(Debug)]
+pub(crate) struct MetricSummary {
+    pub(crate) name: String,
+    pub(crate) min: f64,
+    pub(crate) median: f64,
+    pub(crate) p99: f64,
+    pub(crate) max: f64,
+    /// The highest values and the seeds of their runs, in descending order.
+    pub(crate) outliers: Vec<(f64, Seed)>,
+}
+
+/// The summarized result of a model check.
+#[derive(Debug)]
+pub(crate) struct ModelCheckReport {
+    pub(crate) runs: usize,
+    /// The seeds and error messages of all runs that returned an error or panicked.
+    pub(crate) errors: Vec<(Seed, String)>,
+    pub(crate) assertions: Vec<AssertionSummary>,
+    pub(crate) metrics: Vec<MetricSummary>,
+}
+
+impl ModelCheckReport {
+    /// Returns whether all statistical assertions hold.
+    pub(crate) fn passed(&self) -> bool {
+        self.assertions.iter().all(AssertionSummary::passed)
+    }
+
+    /// Panics with the full report if any statistical assertion doesn't hold.
+    pub(crate) fn assert_passed(&self) {
+        assert!(self.passed(), "model check failed:\n{}", self);
+    }
+}
+
+impl Display for ModelCheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} runs, {} failed with an error",
+            self.runs,
+            self.errors.len()
+        )?;
+        for (seed, error) in &self.errors {
+            writeln!(f, "  seed {}: {}", HexFmt(seed), error)?;
+        }
+        for assertion in &self.assertions {
+            writeln!(
+                f,
+                "[{}] {}: held in {:.3}% of runs, required {:.3}%",
+                if assertion.passed() { "ok" } else { "FAILED" },
+                assertion.description,
+                assertion.ratio * 100.0,
+                assertion.min_ratio * 100.0
+            )?;
+            if assertion.failed_runs > 0 {
+                writeln!(
+                    f,
+                    "  violated in {} runs, e.g. seeds:",
+                    assertion.failed_runs
+                )?;
+                for seed in &assertion.outlier_seeds {
+                    writeln!(f, "    {}", HexFmt(seed))?;
+                }
+            }
+        }
+        for metric in &self.metrics {
+            writeln!(
+                f,
+                "{}: min {}, median {}, p99 {}, max {}; highest values:",
+                metric.name, metric.min, metric.median, metric.p99, metric.max
+            )?;
+            for (value, seed) in &metric.outliers {
+                writeln!(f, "    {} (seed {})", value, HexFmt(seed))?;
+            }
+        }
+        write!(
+            f,
+            "replay a seed by rerunning the test with the env var {}=<seed>",
+            CL_TEST_SEED
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns distinct fixed seeds, so that the tests don't depend on the env vars.
+    fn fixed_seeds(count: u64) -> Vec<Seed> {
+        (0..count)
+            .map(|i| {
+                let mut seed = Seed::default();
+                seed[..8].copy_from_slice(&i.to_le_bytes());
+                seed
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_evaluate_assertions_over_all_runs() {
+        let report = ModelChecker::new(1)
+            .parallelism(4)
+            .max_reported_outliers(3)
+            .assert_ratio("always below 100", 1.0, |value: &u32| *value < 100)
+            .assert_ratio("mostly below 90", 0.7, |value: &u32| *value < 90)
+            .assert_ratio("always below 50", 1.0, |value: &u32| *value < 50)
+            .metric("value", |value: &u32| *value as f64)
+            .run_seeds(&fixed_seeds(200), |rng| {
+                Ok::<_, String>(rng.gen_range(0..100))
+            });
+
+        assert_eq!(report.runs, 200);
+        assert!(report.errors.is_empty());
+        assert!(report.assertions[0].passed());
+        assert!(report.assertions[1].passed());
+        let failed = &report.assertions[2];
+        assert!(!failed.passed());
+        assert!(failed.failed_runs > 3);
+        assert_eq!(failed.outlier_seeds.len(), 3);
+        assert!(!report.passed());
+
+        let metric = &report.metrics[0];
+        assert!(metric.min <= metric.median && metric.median <= metric.p99);
+        assert!(metric.p99 <= metric.max && metric.max < 100.0);
+        assert_eq!(metric.outliers.len(), 3);
+        assert_eq!(metric.outliers[0].0, metric.max);
+    }
+
+    #[test]
+    fn should_count_errors_and_panics_as_failed_runs() {
+        let report = ModelChecker::new(1)
+            .assert_ratio("always ok", 1.0, |_: &()| true)
+            .run_seeds(&fixed_seeds(100), |rng| match rng.gen_range(0..3) {
+                0 => Ok(()),
+                1 => Err("scenario error"),
+                _ => panic!("scenario panic"),
+            });
+
+        let failed_runs = report.assertions[0].failed_runs;
+        assert_eq!(report.errors.len(), failed_runs);
+        assert!(report
+            .errors
+            .iter()
+            .any(|(_, error)| error == "scenario error"));
+        assert!(report
+            .errors
+            .iter()
+            .any(|(_, error)| error == "panicked: scenario panic"));
+    }
+
+    #[test]
+    fn should_reproduce_run_from_seed() {
+        let scenario = |rng: &mut NodeRng| Ok::<_, String>(rng.gen::<u64>());
+        let seed = [7; 16];
+        let first = run_seed(&scenario, seed).outcome;
+        let second = run_seed(&scenario, seed).outcome;
+        assert_eq!(first, second);
+    }
+}
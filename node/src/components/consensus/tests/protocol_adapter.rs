@@ -0,0 +1,468 @@
+//! Glue code running production consensus protocols, e.g. `HighwayProtocol`, as the validators of
+//! a `VirtualNet`.
+//!
+//! Every node wraps a boxed `ConsensusProtocol` instance, so simulations exercise the real message
+//! serialization, timers and synchronizer instead of simplified stand-ins.  The protocol outcomes
+//! are mapped to `TargetedMessage`s: consensus messages reach their recipients after the network
+//! delay, while timers and queued actions are addressed to the node itself.  Proposing and
+//! validating block payloads, which is the era supervisor's job in the node, is resolved
+//! immediately.
+
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+use hex_buffer_serde::{Hex, HexForm};
+use rand::{seq::IteratorRandom, Rng};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use casper_types::{PublicKey, SecretKey, TimeDiff, Timestamp, U512};
+
+use super::{
+    consensus_des_testing::{
+        ClassifyMessage, Honesty, Message, MessageKind, MessageTraffic, Node, Target,
+        TargetedMessage, TrafficStats, ValidatorId, VirtualNet,
+    },
+    queue::QueueEntry,
+    utils::{new_test_chainspec, vertex_kind, vertex_type},
+};
+use crate::{
+    components::consensus::{
+        cl_context::{ClContext, Keypair},
+        config::Config,
+        consensus_protocol::{
+            ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+        },
+        protocols::highway::{config::Config as HighwayConfig, HighwayMessage},
+        traits::Context,
+        ActionId, HighwayProtocol, TimerId,
+    },
+    types::{BlockPayload, NodeId},
+    NodeRng,
+};
+
+const INSTANCE_ID_DATA: &[u8; 1] = &[42u8; 1];
+
+/// An event delivered to a protocol instance.
+///
+/// Only consensus messages can be (de)serialized: message schedules replayed in simulations don't
+/// contain timers and actions, which every protocol instance schedules for itself.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) enum ProtocolMessage {
+    /// A serialized consensus message from a peer.
+    Consensus(#[serde(with = "HexForm")] Vec<u8>),
+    /// A timer the protocol scheduled for itself.
+    #[serde(skip)]
+    Timer(Timestamp, TimerId),
+    /// An action the protocol queued for itself.
+    #[serde(skip)]
+    Action(ActionId),
+}
+
+impl MessageTraffic for ProtocolMessage {
+    /// Consensus messages are broken down by their type if they are Highway messages.
+    fn traffic(&self) -> Option<(&'static str, usize)> {
+        let msg = match self {
+            ProtocolMessage::Consensus(msg) => msg,
+            ProtocolMessage::Timer(..) | ProtocolMessage::Action(_) => return None,
+        };
+        let message_type = match bincode::deserialize(msg) {
+            Ok(HighwayMessage::<ClContext>::NewVertex(vertex)) => vertex_type(&vertex),
+            Ok(HighwayMessage::RequestDependency(..)) => "request_dependency",
+            Ok(HighwayMessage::RequestDependencyByHeight { .. }) => "request_dependency_by_height",
+            Ok(HighwayMessage::LatestStateRequest(_)) => "latest_state_request",
+            Ok(HighwayMessage::NewCompactUnit(_)) => "compact_unit",
+            Err(_) => "consensus",
+        };
+        Some((message_type, msg.len()))
+    }
+}
+
+impl ClassifyMessage for ProtocolMessage {
+    fn kind(&self) -> MessageKind {
+        let msg = match self {
+            ProtocolMessage::Consensus(msg) => msg,
+            ProtocolMessage::Timer(..) | ProtocolMessage::Action(_) => return MessageKind::Local,
+        };
+        match bincode::deserialize(msg) {
+            Ok(HighwayMessage::<ClContext>::NewVertex(vertex)) => vertex_kind(&vertex),
+            Ok(HighwayMessage::RequestDependency(..))
+            | Ok(HighwayMessage::RequestDependencyByHeight { .. })
+            | Ok(HighwayMessage::LatestStateRequest(_)) => MessageKind::SyncRequest,
+            Ok(HighwayMessage::NewCompactUnit(compact_unit)) if compact_unit.has_value() => {
+                MessageKind::Proposal
+            }
+            Ok(HighwayMessage::NewCompactUnit(_)) => MessageKind::Vote,
+            Err(_) => MessageKind::Other,
+        }
+    }
+}
+
+/// Production protocol instances are run unmodified, so every node is honest.
+impl Honesty for Box<dyn ConsensusProtocol<ClContext>> {
+    fn is_honest(&self) -> bool {
+        true
+    }
+}
+
+pub(crate) type ProtocolNode =
+    Node<FinalizedBlock<ClContext>, ProtocolMessage, Box<dyn ConsensusProtocol<ClContext>>>;
+
+type ProtocolNet =
+    VirtualNet<FinalizedBlock<ClContext>, ProtocolMessage, Box<dyn ConsensusProtocol<ClContext>>>;
+
+/// A test network whose validators run production consensus protocol instances.
+pub(crate) struct ProtocolTestHarness {
+    virtual_net: ProtocolNet,
+    /// The node ID under which each validator is known to its peers' protocol instances.
+    node_ids: BTreeMap<ValidatorId, NodeId>,
+    /// The time it takes any message to reach its recipient.
+    network_delay: TimeDiff,
+}
+
+impl ProtocolTestHarness {
+    /// Creates a test network from protocol instances and the outcomes of their initialization,
+    /// with `init_messages` queued up for delivery.
+    pub(crate) fn new(
+        rng: &mut NodeRng,
+        protocols: Vec<(
+            Box<dyn ConsensusProtocol<ClContext>>,
+            ProtocolOutcomes<ClContext>,
+        )>,
+        init_messages: Vec<QueueEntry<ProtocolMessage>>,
+        network_delay: TimeDiff,
+        now: Timestamp,
+    ) -> Self {
+        let mut nodes = Vec::new();
+        let mut init_outcomes = Vec::new();
+        let mut node_ids = BTreeMap::new();
+        for (index, (protocol, outcomes)) in protocols.into_iter().enumerate() {
+            let validator_id = ValidatorId(index as u64);
+            nodes.push(Node::new(validator_id, protocol));
+            init_outcomes.push((validator_id, outcomes));
+            node_ids.insert(validator_id, NodeId::random(rng));
+        }
+
+        let mut harness = ProtocolTestHarness {
+            virtual_net: VirtualNet::new(nodes, init_messages),
+            node_ids,
+            network_delay,
+        };
+        for (validator_id, outcomes) in init_outcomes {
+            harness.handle_outcomes(rng, validator_id, now, outcomes);
+        }
+        harness
+    }
+
+    /// Advances the test by one message.
+    ///
+    /// Pops the earliest message from the queue, passes it to the recipient's protocol instance
+    /// and schedules the messages resulting from its outcomes.  Returns the delivery time of the
+    /// processed message, or `None` if the queue is empty.
+    pub(crate) fn crank(&mut self, rng: &mut NodeRng) -> Option<Timestamp> {
+        let QueueEntry {
+            delivery_time,
+            recipient,
+            message,
+        } = self.virtual_net.pop_message()?;
+        trace!(
+            "Processing: tick {}, sender validator={}, recipient validator={}, payload {:?}",
+            delivery_time,
+            message.sender,
+            recipient,
+            message.payload(),
+        );
+
+        let sender = self.node_ids[&message.sender];
+        let node = self.node_mut(recipient);
+        node.push_messages_received(vec![message.clone()]);
+        let protocol = node.validator_mut();
+        let outcomes = match message.payload {
+            ProtocolMessage::Consensus(msg) => {
+                protocol.handle_message(rng, sender, msg, delivery_time)
+            }
+            ProtocolMessage::Timer(timestamp, timer_id) => {
+                protocol.handle_timer(timestamp, timer_id)
+            }
+            ProtocolMessage::Action(action_id) => protocol.handle_action(action_id, delivery_time),
+        };
+        self.handle_outcomes(rng, recipient, delivery_time, outcomes);
+        Some(delivery_time)
+    }
+
+    /// Cranks until every node satisfies `done`, returning whether that happened before
+    /// `deadline`.
+    ///
+    /// Protocols usually schedule periodic timers, so their queue never runs empty.
+    pub(crate) fn crank_until<F>(&mut self, rng: &mut NodeRng, deadline: Timestamp, done: F) -> bool
+    where
+        F: Fn(&ProtocolNode) -> bool,
+    {
+        while !self.nodes().all(&done) {
+            match self.virtual_net.peek_message() {
+                Some(entry) if entry.delivery_time <= deadline => {
+                    self.crank(rng);
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Returns the nodes of the test network.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = &ProtocolNode> {
+        self.virtual_net.validators()
+    }
+
+    /// Returns the test network.
+    pub(crate) fn virtual_net(&self) -> &ProtocolNet {
+        &self.virtual_net
+    }
+
+    /// Returns the network traffic of the test run so far.
+    pub(crate) fn traffic(&self) -> &TrafficStats {
+        self.virtual_net.traffic()
+    }
+
+    /// Returns the highest number of values finalized by any node.
+    pub(crate) fn finalized_count(&self) -> usize {
+        self.nodes()
+            .map(|node| node.finalized_count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn node_mut(&mut self, validator_id: ValidatorId) -> &mut ProtocolNode {
+        self.virtual_net
+            .node_mut(&validator_id)
+            .unwrap_or_else(|| panic!("validator {} should exist", validator_id))
+    }
+
+    /// Handles the outcomes of `validator_id`'s protocol instance and dispatches the resulting
+    /// messages.
+    fn handle_outcomes(
+        &mut self,
+        rng: &mut NodeRng,
+        validator_id: ValidatorId,
+        now: Timestamp,
+        outcomes: ProtocolOutcomes<ClContext>,
+    ) {
+        let arrival = now + self.network_delay;
+        let node = self
+            .virtual_net
+            .node_mut(&validator_id)
+            .unwrap_or_else(|| panic!("validator {} should exist", validator_id));
+        let mut outcomes = VecDeque::from(outcomes);
+        let mut messages = Vec::new();
+        while let Some(outcome) = outcomes.pop_front() {
+            let (payload, target, delivery_time) = match outcome {
+                ProtocolOutcome::CreatedGossipMessage(msg) => (
+                    ProtocolMessage::Consensus(msg),
+                    Target::AllExcept(validator_id),
+                    arrival,
+                ),
+                ProtocolOutcome::CreatedTargetedMessage(msg, node_id) => {
+                    let (recipient, _) = self
+                        .node_ids
+                        .iter()
+                        .find(|(_, id)| **id == node_id)
+                        .unwrap_or_else(|| panic!("unknown recipient {}", node_id));
+                    (
+                        ProtocolMessage::Consensus(msg),
+                        Target::SingleValidator(*recipient),
+                        arrival,
+                    )
+                }
+                ProtocolOutcome::CreatedMessageToRandomPeer(msg) => {
+                    let peer = self
+                        .node_ids
+                        .keys()
+                        .filter(|id| **id != validator_id)
+                        .choose(rng);
+                    match peer {
+                        Some(recipient) => (
+                            ProtocolMessage::Consensus(msg),
+                            Target::SingleValidator(*recipient),
+                            arrival,
+                        ),
+                        None => continue,
+                    }
+                }
+                ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => (
+                    ProtocolMessage::Timer(timestamp, timer_id),
+                    Target::SingleValidator(validator_id),
+                    timestamp,
+                ),
+                ProtocolOutcome::QueueAction(action_id) => (
+                    ProtocolMessage::Action(action_id),
+                    Target::SingleValidator(validator_id),
+                    now,
+                ),
+                ProtocolOutcome::CreateNewBlock(block_context) => {
+                    let payload = BlockPayload::new(vec![], vec![], vec![], rng.gen());
+                    let proposed_block = ProposedBlock::new(Arc::new(payload), block_context);
+                    outcomes.extend(node.validator_mut().propose(proposed_block, now));
+                    continue;
+                }
+                ProtocolOutcome::ValidateConsensusValue { proposed_block, .. } => {
+                    let validity_outcomes =
+                        node.validator_mut()
+                            .resolve_validity(proposed_block, true, now);
+                    outcomes.extend(validity_outcomes);
+                    continue;
+                }
+                ProtocolOutcome::FinalizedBlock(finalized_block) => {
+                    node.push_finalized(finalized_block);
+                    continue;
+                }
+                ProtocolOutcome::CreateSignature { hash, .. } => {
+                    panic!(
+                        "validator {} requested a signature of {} but signs locally",
+                        validator_id, hash
+                    );
+                }
+                ProtocolOutcome::InvalidIncomingMessage(_, sender, error) => {
+                    panic!(
+                        "validator {} rejected a message from {}: {}",
+                        validator_id, sender, error
+                    );
+                }
+                outcome @ (ProtocolOutcome::WeAreFaulty
+                | ProtocolOutcome::DoppelgangerDetected
+                | ProtocolOutcome::FttExceeded) => {
+                    panic!("validator {} reported {:?}", validator_id, outcome);
+                }
+                outcome @ (ProtocolOutcome::NewEvidence(_)
+                | ProtocolOutcome::SendEvidence(..)
+                | ProtocolOutcome::StandstillAlert
+                | ProtocolOutcome::Disconnect(_)) => {
+                    trace!("validator {} ignores {:?}", validator_id, outcome);
+                    continue;
+                }
+            };
+            node.push_messages_produced(vec![payload.clone()]);
+            let message = Message::new(validator_id, payload);
+            messages.push((TargetedMessage::new(message, target), delivery_time));
+        }
+        self.virtual_net.dispatch_messages(messages);
+    }
+}
+
+/// Returns a test network of active `HighwayProtocol` validators, one for each of the given
+/// stakes, all in the same era of `era_height` blocks starting at `start_timestamp`.
+///
+/// The round success meter compares round IDs to the wall clock, so `start_timestamp` should be
+/// close to the current time.
+pub(crate) fn new_highway_harness(
+    rng: &mut NodeRng,
+    stakes: &[u64],
+    era_height: u64,
+    init_messages: Vec<QueueEntry<ProtocolMessage>>,
+    network_delay: TimeDiff,
+    start_timestamp: Timestamp,
+) -> ProtocolTestHarness {
+    let secret_keys: Vec<Arc<SecretKey>> = stakes
+        .iter()
+        .map(|_| Arc::new(SecretKey::random(rng)))
+        .collect();
+    let weights: BTreeMap<PublicKey, U512> = secret_keys
+        .iter()
+        .zip(stakes)
+        .map(|(secret_key, stake)| (PublicKey::from(&**secret_key), U512::from(*stake)))
+        .collect();
+    let mut chainspec = new_test_chainspec(weights.clone());
+    chainspec.core_config.minimum_era_height = era_height;
+    let config = Config {
+        secret_key_path: Default::default(),
+        successor_secret_key_path: None,
+        remote_signer: None,
+        highway: HighwayConfig::default(),
+    };
+
+    let now = start_timestamp;
+    let protocols = secret_keys
+        .into_iter()
+        .map(|secret_key| {
+            let public_key = PublicKey::from(&*secret_key);
+            let (mut protocol, mut outcomes) = HighwayProtocol::<ClContext>::new_boxed(
+                ClContext::hash(INSTANCE_ID_DATA),
+                weights.clone(),
+                &HashSet::new(),
+                &HashSet::new(),
+                &chainspec,
+                &config,
+                None,
+                now,
+                0,
+                now,
+            );
+            outcomes.extend(protocol.handle_is_current(now));
+            outcomes.extend(protocol.activate_validator(
+                public_key,
+                Keypair::from(secret_key),
+                now,
+                None,
+            ));
+            (protocol, outcomes)
+        })
+        .collect();
+    ProtocolTestHarness::new(rng, protocols, init_messages, network_delay, now)
+}
+
+mod highway_tests {
+    use itertools::Itertools;
+
+    use casper_types::testing::TestRng;
+
+    use super::{
+        super::consensus_des_testing::{assert_common_prefix, finalization_lag_histogram},
+        *,
+    };
+
+    #[test]
+    fn highway_validators_finalize_the_same_era() {
+        let mut rng = TestRng::new();
+        let start_timestamp = Timestamp::now();
+        let mut harness = new_highway_harness(
+            &mut rng,
+            &[10, 20, 30],
+            5,
+            vec![],
+            "100ms".parse().unwrap(),
+            start_timestamp,
+        );
+        // With the local chainspec's round length of about four seconds, five blocks are
+        // finalized well within five minutes.
+        let era_ended = harness.crank_until(
+            &mut rng,
+            start_timestamp + "5min".parse().unwrap(),
+            |node| {
+                node.finalized_values()
+                    .any(|block| block.terminal_block_data.is_some())
+            },
+        );
+        assert!(era_ended, "the era should have ended");
+
+        let finalized_count = assert_common_prefix(harness.virtual_net());
+        assert!(finalized_count >= 5, "too few blocks: {}", finalized_count);
+        assert_eq!(
+            finalization_lag_histogram(harness.virtual_net())
+                .into_iter()
+                .collect_vec(),
+            vec![(0, 3)]
+        );
+
+        let traffic = harness.traffic();
+        let unit_messages =
+            traffic.of_type("unit").messages + traffic.of_type("compact_unit").messages;
+        assert!(unit_messages > 0, "no units in {}", traffic);
+        let (messages_per_value, bytes_per_value) = traffic
+            .total()
+            .per_value(harness.finalized_count())
+            .expect("blocks were finalized");
+        assert!(messages_per_value >= 1.0);
+        assert!(bytes_per_value >= messages_per_value);
+    }
+}
@@ -5,7 +5,9 @@ use once_cell::sync::Lazy;
 
 use casper_types::{system::auction::DelegationRate, Motes, PublicKey, SecretKey, Timestamp, U512};
 
+use super::consensus_des_testing::MessageKind;
 use crate::{
+    components::consensus::{highway_core::highway::Vertex, traits::Context},
     tls::{KeyFingerprint, Sha512},
     types::{
         chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
@@ -53,3 +55,24 @@ where
     chainspec.core_config.era_duration = 0.into();
     chainspec
 }
+
+/// Returns the name of the vertex type, for breaking down network traffic in tests.
+pub(crate) fn vertex_type<C: Context>(vertex: &Vertex<C>) -> &'static str {
+    match vertex {
+        Vertex::Unit(_) => "unit",
+        Vertex::Evidence(_) => "evidence",
+        Vertex::Endorsements(_) => "endorsements",
+        Vertex::Ping(_) => "ping",
+    }
+}
+
+/// Returns the kind of message the vertex is sent as.
+pub(crate) fn vertex_kind<C: Context>(vertex: &Vertex<C>) -> MessageKind {
+    match vertex {
+        Vertex::Unit(_) if vertex.value().is_some() => MessageKind::Proposal,
+        Vertex::Unit(_) => MessageKind::Vote,
+        Vertex::Evidence(_) => MessageKind::Evidence,
+        Vertex::Endorsements(_) => MessageKind::Endorsement,
+        Vertex::Ping(_) => MessageKind::Ping,
+    }
+}
@@ -45,7 +45,10 @@ pub trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the given hash, or returns `None` if it can't be signed right away, e.g. because the
+    /// signing key is held by an external signer. The signature then has to be created
+    /// asynchronously.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
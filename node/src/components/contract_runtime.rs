@@ -10,7 +10,7 @@ use std::{
     collections::BTreeMap,
     fmt::{self, Debug, Display, Formatter},
     path::Path,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, TryLockError},
     time::Instant,
 };
 
@@ -38,10 +38,12 @@ use casper_execution_engine::{
     },
 };
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, ProtocolVersion, Timestamp};
+use casper_types::{bytesrepr::Bytes, EraId, ProtocolVersion, Timestamp};
 
 use crate::{
-    components::{contract_runtime::types::StepEffectAndUpcomingEraValidators, Component},
+    components::{
+        contract_runtime::types::StepEffectAndUpcomingEraValidators, Component, HealthCheck,
+    },
     effect::{
         announcements::{ContractRuntimeAnnouncement, ControlAnnouncement},
         incoming::{TrieDemand, TrieRequest, TrieRequestIncoming},
@@ -50,7 +52,7 @@ use crate::{
     },
     fatal,
     protocol::Message,
-    types::{BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, Deploy, FinalizedBlock},
+    types::{BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, Deploy, FinalizedBlock, Health},
     NodeRng,
 };
 pub(crate) use config::Config;
@@ -59,7 +61,7 @@ use metrics::Metrics;
 pub use operations::execute_finalized_block;
 pub(crate) use types::{BlockAndExecutionEffects, EraValidatorsRequest};
 
-use self::operations::execute_only;
+use self::{config::MAX_QUERY_DEPTH_LIMIT, operations::execute_only};
 
 use super::fetcher::FetchedOrNotFound;
 
@@ -107,6 +109,10 @@ pub struct SpeculativeExecutionState {
     pub state_root_hash: Digest,
     /// Block time.
     pub block_time: Timestamp,
+    /// Height of the block.
+    pub block_height: u64,
+    /// Era of the block.
+    pub era_id: EraId,
     /// Protocol version used when creating the original block.
     pub protocol_version: ProtocolVersion,
 }
@@ -162,6 +168,10 @@ impl ExecutionPreState {
 
 type ExecQueue = Arc<Mutex<BTreeMap<u64, (FinalizedBlock, Vec<Deploy>, Vec<Deploy>)>>>;
 
+/// Number of finalized blocks waiting for execution above which the contract runtime reports
+/// itself as degraded.
+const MAX_HEALTHY_EXEC_QUEUE_LENGTH: usize = 10;
+
 #[derive(Debug, From, Serialize)]
 pub(crate) enum Event {
     #[from]
@@ -236,6 +246,29 @@ where
     }
 }
 
+impl HealthCheck for ContractRuntime {
+    const NAME: &'static str = "contract_runtime";
+
+    fn health(&self) -> Health {
+        let mut health = Health::ready();
+        // Execution holds the lock only briefly, so rather skip the check than block the reactor.
+        match self.exec_queue.try_lock() {
+            Ok(exec_queue) if exec_queue.len() > MAX_HEALTHY_EXEC_QUEUE_LENGTH => {
+                health.degrade(format!(
+                    "{} finalized blocks waiting for execution",
+                    exec_queue.len()
+                ));
+            }
+            Ok(_) | Err(TryLockError::WouldBlock) => (),
+            Err(TryLockError::Poisoned(_)) => health.fail("execution queue lock poisoned"),
+        }
+        if self.execution_pre_state.is_poisoned() {
+            health.fail("execution pre-state lock poisoned");
+        }
+        health
+    }
+}
+
 impl ContractRuntime {
     /// Handles an incoming request to get a trie.
     fn handle_trie_request<REv>(
@@ -323,6 +356,23 @@ impl ContractRuntime {
             } => responder
                 .respond(self.commit_upgrade(*upgrade_config))
                 .ignore(),
+            ContractRuntimeRequest::DryRunUpgrade {
+                upgrade_config,
+                responder,
+            } => {
+                debug!(?upgrade_config, "dry run upgrade");
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    // The scratch state keeps all writes in memory, so they are discarded
+                    // afterwards.
+                    let result = engine_state
+                        .get_scratch_engine_state()
+                        .commit_upgrade(CorrelationId::new(), *upgrade_config);
+                    debug!(?result, "dry run upgrade result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::Query {
                 query_request,
                 responder,
@@ -340,6 +390,33 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::CallView {
+                view_request,
+                responder,
+            } => {
+                trace!(?view_request, "view");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let start = Instant::now();
+                    let result = run_intensive_task(move || {
+                        let correlation_id = CorrelationId::new();
+                        engine_state.call_view(correlation_id, *view_request)
+                    })
+                    .await;
+                    metrics.call_view.observe(start.elapsed().as_secs_f64());
+                    trace!(?result, "view result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GetWasmConfig {
+                protocol_version,
+                responder,
+            } => {
+                let wasm_config = *self.engine_state.wasm_config(protocol_version);
+                responder.respond(wasm_config).ignore()
+            }
             ContractRuntimeRequest::GetBalance {
                 balance_request,
                 responder,
@@ -615,8 +692,19 @@ impl ContractRuntime {
         minimum_delegation_amount: u64,
         strict_argument_checking: bool,
         vesting_schedule_period_millis: u64,
+        allow_authorization_contracts: bool,
+        authorization_contract_gas_limit: u64,
+        max_unbondings_per_step: u32,
         registry: &Registry,
     ) -> Result<Self, ConfigError> {
+        let max_query_depth = contract_runtime_config.max_query_depth();
+        if !(1..=MAX_QUERY_DEPTH_LIMIT).contains(&max_query_depth) {
+            return Err(ConfigError::InvalidMaxQueryDepth {
+                got: max_query_depth,
+                max: MAX_QUERY_DEPTH_LIMIT,
+            });
+        }
+
         // TODO: This is bogus, get rid of this
         let execution_pre_state = Arc::new(Mutex::new(ExecutionPreState {
             pre_state_root_hash: Default::default(),
@@ -640,17 +728,24 @@ impl ContractRuntime {
 
         let global_state = LmdbGlobalState::empty(environment, trie_store)?;
         let engine_config = EngineConfig::new(
-            contract_runtime_config.max_query_depth(),
+            max_query_depth,
             max_associated_keys,
             max_runtime_call_stack_height,
             minimum_delegation_amount,
             strict_argument_checking,
             vesting_schedule_period_millis,
+            allow_authorization_contracts,
+            authorization_contract_gas_limit,
+            max_unbondings_per_step,
             wasm_config,
             system_config,
-        );
+        )
+        .with_max_execution_duration(contract_runtime_config.max_execution_duration())
+        .with_max_view_gas(contract_runtime_config.max_view_gas());
 
-        let engine_state = Arc::new(EngineState::new(global_state, engine_config));
+        let mut engine_state = EngineState::new(global_state, engine_config);
+        engine_state.add_wasm_config(protocol_version, wasm_config);
+        let engine_state = Arc::new(engine_state);
 
         let metrics = Arc::new(Metrics::new(registry)?);
 
@@ -842,6 +937,15 @@ impl ContractRuntime {
         }
     }
 
+    /// Returns whether the root trie node of the given state root hash is present in global
+    /// state.
+    pub(crate) fn state_root_exists(
+        &self,
+        state_root_hash: Digest,
+    ) -> Result<bool, engine_state::Error> {
+        Ok(Self::get_trie_full(&self.engine_state, &self.metrics, state_root_hash)?.is_some())
+    }
+
     /// Reads the trie (or chunk of a trie) under the given key and index.
     pub(crate) fn get_trie(
         &self,
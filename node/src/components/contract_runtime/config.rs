@@ -1,12 +1,18 @@
+use std::time::Duration;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::shared::utils;
+use casper_types::TimeDiff;
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+/// The highest permitted value of `max_query_depth`.
+pub(crate) const MAX_QUERY_DEPTH_LIMIT: u64 = 64;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_MAX_VIEW_GAS: u64 = 1_000_000_000;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -23,7 +29,7 @@ pub struct Config {
     ///
     /// Defaults to 512.
     max_readers: Option<u32>,
-    /// The limit of depth of recursive global state queries.
+    /// The limit of depth of recursive global state queries.  Must be between 1 and 64.
     ///
     /// Defaults to 5.
     max_query_depth: Option<u64>,
@@ -31,6 +37,20 @@ pub struct Config {
     ///
     /// Defaults to `false`.
     enable_manual_sync: Option<bool>,
+    /// The maximum wall-clock duration of the Wasm execution of a single speculatively executed
+    /// deploy or view call, independent of its gas limit.  Executions running longer fail with an
+    /// "execution time limit exceeded" error.
+    ///
+    /// Whether a deploy exceeds this limit depends on the hardware and load of the node, so it
+    /// never applies to deploys executed as part of a block.
+    ///
+    /// Defaults to `None`, i.e. no limit.
+    max_execution_duration: Option<TimeDiff>,
+    /// The maximum amount of gas a call to a view entry point via the `state_call_view` JSON-RPC
+    /// can use.  View calls are not paid for.
+    ///
+    /// Defaults to 1,000,000,000.
+    max_view_gas: Option<u64>,
 }
 
 impl Config {
@@ -54,6 +74,14 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    pub(crate) fn max_execution_duration(&self) -> Option<Duration> {
+        self.max_execution_duration.map(Duration::from)
+    }
+
+    pub(crate) fn max_view_gas(&self) -> u64 {
+        self.max_view_gas.unwrap_or(DEFAULT_MAX_VIEW_GAS)
+    }
 }
 
 impl Default for Config {
@@ -63,6 +91,8 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            max_execution_duration: None,
+            max_view_gas: Some(DEFAULT_MAX_VIEW_GAS),
         }
     }
 }
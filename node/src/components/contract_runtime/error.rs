@@ -26,6 +26,14 @@ pub(crate) enum ConfigError {
     /// Error initializing execution engine.
     #[error("failed to initialize execution engine: {0}")]
     EngineState(#[from] EngineStateError),
+    /// The configured query depth limit is out of range.
+    #[error("max_query_depth must be between 1 and {max}, got {got}")]
+    InvalidMaxQueryDepth {
+        /// The configured query depth limit.
+        got: u64,
+        /// The highest permitted query depth limit.
+        max: u64,
+    },
 }
 
 /// An error during block execution.
@@ -35,6 +43,15 @@ pub enum BlockExecutionError {
     /// more than one execution result.
     #[error("more than one execution result")]
     MoreThanOneExecutionResult,
+    /// The execution engine returned a different number of execution results than the number of
+    /// native transfers executed as a batch.
+    #[error("expected {expected} execution results for native transfers, got {got}")]
+    TransferExecutionResultCount {
+        /// The number of transfers executed.
+        expected: usize,
+        /// The number of execution results returned.
+        got: usize,
+    },
     /// Both the block to be executed and the execution pre-state specify the height of the next
     /// block. These must agree and this error will be thrown if they do not.
     #[error(
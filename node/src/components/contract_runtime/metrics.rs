@@ -17,6 +17,10 @@ const EXPONENTIAL_BUCKET_COUNT: usize = 10;
 const RUN_EXECUTE_NAME: &str = "contract_runtime_run_execute";
 const RUN_EXECUTE_HELP: &str = "time in seconds to execute but not commit a contract";
 
+const RUN_TRANSFERS_NAME: &str = "contract_runtime_run_transfers";
+const RUN_TRANSFERS_HELP: &str =
+    "time in seconds to execute but not commit the native transfers of a block";
+
 const APPLY_EFFECT_NAME: &str = "contract_runtime_apply_commit";
 const APPLY_EFFECT_HELP: &str = "time in seconds to commit the execution effects of a contract";
 
@@ -26,6 +30,9 @@ const COMMIT_UPGRADE_HELP: &str = "time in seconds to commit an upgrade";
 const RUN_QUERY_NAME: &str = "contract_runtime_run_query";
 const RUN_QUERY_HELP: &str = "time in seconds to run a query in global state";
 
+const CALL_VIEW_NAME: &str = "contract_runtime_call_view";
+const CALL_VIEW_HELP: &str = "time in seconds to call a view entry point";
+
 const COMMIT_STEP_NAME: &str = "contract_runtime_commit_step";
 const COMMIT_STEP_HELP: &str = "time in seconds to commit the step at era end";
 
@@ -65,9 +72,11 @@ const LATEST_COMMIT_STEP_HELP: &str = "duration in seconds of latest commit step
 #[derive(Debug)]
 pub struct Metrics {
     pub(super) run_execute: Histogram,
+    pub(super) run_transfers: Histogram,
     pub(super) apply_effect: Histogram,
     pub(super) commit_upgrade: Histogram,
     pub(super) run_query: Histogram,
+    pub(super) call_view: Histogram,
     pub(super) commit_step: Histogram,
     pub(super) get_balance: Histogram,
     pub(super) get_validator_weights: Histogram,
@@ -110,6 +119,12 @@ impl Metrics {
                 RUN_EXECUTE_HELP,
                 common_buckets.clone(),
             )?,
+            run_transfers: utils::register_histogram_metric(
+                registry,
+                RUN_TRANSFERS_NAME,
+                RUN_TRANSFERS_HELP,
+                common_buckets.clone(),
+            )?,
             apply_effect: utils::register_histogram_metric(
                 registry,
                 APPLY_EFFECT_NAME,
@@ -122,6 +137,12 @@ impl Metrics {
                 RUN_QUERY_HELP,
                 common_buckets.clone(),
             )?,
+            call_view: utils::register_histogram_metric(
+                registry,
+                CALL_VIEW_NAME,
+                CALL_VIEW_HELP,
+                common_buckets.clone(),
+            )?,
             commit_step: utils::register_histogram_metric(
                 registry,
                 COMMIT_STEP_NAME,
@@ -192,9 +213,11 @@ impl Metrics {
 impl Drop for Metrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.run_execute);
+        unregister_metric!(self.registry, self.run_transfers);
         unregister_metric!(self.registry, self.apply_effect);
         unregister_metric!(self.registry, self.commit_upgrade);
         unregister_metric!(self.registry, self.run_query);
+        unregister_metric!(self.registry, self.call_view);
         unregister_metric!(self.registry, self.commit_step);
         unregister_metric!(self.registry, self.get_balance);
         unregister_metric!(self.registry, self.get_validator_weights);
@@ -9,7 +9,10 @@ use casper_execution_engine::{
         ExecutionResult as EngineExecutionResult, GetEraValidatorsRequest, RewardItem, StepError,
         StepRequest, StepSuccess,
     },
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    shared::{
+        additive_map::AdditiveMap, execution_journal::ExecutionJournal, newtypes::CorrelationId,
+        transform::Transform,
+    },
     storage::global_state::lmdb::LmdbGlobalState,
 };
 use casper_hashing::Digest;
@@ -63,18 +66,34 @@ pub fn execute_finalized_block(
         Vec::with_capacity(deploys.len() + transfers.len());
     // Run any deploys that must be executed
     let block_time = finalized_block.timestamp().millis();
+    let block_height = finalized_block.height();
+    let era_id = finalized_block.era_id();
     let start = Instant::now();
     let maybe_deploy_approvals_root_hash = compute_approvals_root_hash(&deploys, &transfers)?;
 
     // Create a new EngineState that reads from LMDB but only caches changes in memory.
     let scratch_state = engine_state.get_scratch_engine_state();
 
-    for deploy in deploys.into_iter().chain(transfers) {
+    // The transfers are only executed as a batch if they are all native transfers, which is
+    // guaranteed for blocks that passed validation.
+    let (batched_transfers, unbatched_transfers) = if transfers
+        .iter()
+        .all(|transfer| transfer.session().is_transfer())
+    {
+        (transfers, vec![])
+    } else {
+        warn!("executing transfers one by one, as not all of them are native transfers");
+        (vec![], transfers)
+    };
+
+    for deploy in deploys.into_iter().chain(unbatched_transfers) {
         let deploy_hash = *deploy.id();
         let deploy_header = deploy.header().clone();
         let execute_request = ExecuteRequest::new(
             state_root_hash,
             block_time,
+            block_height,
+            era_id,
             vec![DeployItem::from(deploy)],
             protocol_version,
             *finalized_block.proposer(),
@@ -85,7 +104,10 @@ pub fn execute_finalized_block(
         // mapping between deploy_hash and execution result, and this outer logic is
         // enriching it with the deploy hash. If we were passing multiple deploys per exec
         // the relation between the deploy and the execution results would be lost.
-        let result = execute(&scratch_state, metrics.clone(), execute_request)?;
+        let result = execute(&scratch_state, metrics.clone(), execute_request)?
+            .into_iter()
+            .exactly_one()
+            .map_err(|_| BlockExecutionError::MoreThanOneExecutionResult)?;
 
         trace!(?deploy_hash, ?result, "deploy execution result");
         // As for now a given state is expected to exist.
@@ -100,6 +122,49 @@ pub fn execute_finalized_block(
         state_root_hash = state_hash;
     }
 
+    // Native transfers are executed as one batch, sharing the values read from global state.  The
+    // engine returns their results in order, each containing only the effects of its transfer.
+    if !batched_transfers.is_empty() {
+        let (transfer_hashes_and_headers, transfer_items): (Vec<_>, Vec<_>) = batched_transfers
+            .into_iter()
+            .map(|transfer| {
+                let hash_and_header = (*transfer.id(), transfer.header().clone());
+                (hash_and_header, DeployItem::from(transfer))
+            })
+            .unzip();
+        let execute_request = ExecuteRequest::new(
+            state_root_hash,
+            block_time,
+            block_height,
+            era_id,
+            transfer_items,
+            protocol_version,
+            *finalized_block.proposer(),
+        );
+        let results = execute_transfers(&scratch_state, metrics.clone(), execute_request)?;
+        if results.len() != transfer_hashes_and_headers.len() {
+            return Err(BlockExecutionError::TransferExecutionResultCount {
+                expected: transfer_hashes_and_headers.len(),
+                got: results.len(),
+            });
+        }
+
+        for ((deploy_hash, deploy_header), result) in
+            transfer_hashes_and_headers.into_iter().zip(results)
+        {
+            trace!(?deploy_hash, ?result, "transfer execution result");
+            let (state_hash, execution_result) = commit_execution_effects(
+                &scratch_state,
+                metrics.clone(),
+                state_root_hash,
+                deploy_hash.into(),
+                result,
+            )?;
+            execution_results.push((deploy_hash, deploy_header, execution_result));
+            state_root_hash = state_hash;
+        }
+    }
+
     // Write the deploy approvals and execution results Merkle root hashes to global state if there
     // were any deploys.
     let block_height = finalized_block.height();
@@ -216,19 +281,17 @@ fn commit_execution_effects<S>(
     metrics: Option<Arc<Metrics>>,
     state_root_hash: Digest,
     deploy_hash: DeployHash,
-    execution_results: ExecutionResults,
+    ee_execution_result: EngineExecutionResult,
 ) -> Result<(Digest, ExecutionResult), BlockExecutionError>
 where
     S: StateProvider + CommitProvider,
     S::Error: Into<execution::Error>,
 {
-    let ee_execution_result = execution_results
-        .into_iter()
-        .exactly_one()
-        .map_err(|_| BlockExecutionError::MoreThanOneExecutionResult)?;
     let json_execution_result = ExecutionResult::from(&ee_execution_result);
 
-    let execution_effect: AdditiveMap<Key, Transform> = match ee_execution_result {
+    // The journal is streamed into the global state as is, rather than merged into a map of
+    // transforms first, as the scratch state applies repeated transforms of a key in its cache.
+    let execution_journal = match ee_execution_result {
         EngineExecutionResult::Success {
             execution_journal,
             cost,
@@ -251,10 +314,9 @@ where
             debug!(?deploy_hash, ?error, %cost, "execution failure");
             execution_journal
         }
-    }
-    .into();
+    };
     let new_state_root =
-        commit_transforms(engine_state, metrics, state_root_hash, execution_effect)?;
+        commit_transforms(engine_state, metrics, state_root_hash, execution_journal)?;
     Ok((new_state_root, json_execution_result))
 }
 
@@ -262,7 +324,7 @@ fn commit_transforms<S>(
     engine_state: &EngineState<S>,
     metrics: Option<Arc<Metrics>>,
     state_root_hash: Digest,
-    effects: AdditiveMap<Key, Transform>,
+    effects: ExecutionJournal,
 ) -> Result<Digest, engine_state::Error>
 where
     S: StateProvider + CommitProvider,
@@ -295,16 +357,21 @@ where
     let SpeculativeExecutionState {
         state_root_hash,
         block_time,
+        block_height,
+        era_id,
         protocol_version,
     } = execution_state;
     let deploy_hash = deploy.deploy_hash;
     let execute_request = ExecuteRequest::new(
         state_root_hash,
         block_time.millis(),
+        block_height,
+        era_id,
         vec![deploy],
         protocol_version,
         PublicKey::System,
-    );
+    )
+    .into_speculative();
     let results = execute(engine_state, None, execute_request);
     results.map(|mut execution_results| {
         let len = execution_results.len();
@@ -344,6 +411,27 @@ where
     result
 }
 
+/// Executes the native transfers of the request as one batch, without committing the effects.
+fn execute_transfers<S>(
+    engine_state: &EngineState<S>,
+    metrics: Option<Arc<Metrics>>,
+    execute_request: ExecuteRequest,
+) -> Result<ExecutionResults, engine_state::Error>
+where
+    S: StateProvider + CommitProvider,
+    S::Error: Into<execution::Error>,
+{
+    trace!(?execute_request, "execute transfers");
+    let correlation_id = CorrelationId::new();
+    let start = Instant::now();
+    let result = engine_state.run_transfers(correlation_id, execute_request);
+    if let Some(metrics) = metrics {
+        metrics.run_transfers.observe(start.elapsed().as_secs_f64());
+    }
+    trace!(?result, "execute transfers result");
+    result
+}
+
 fn commit_step<S>(
     engine_state: &EngineState<S>,
     maybe_metrics: Option<Arc<Metrics>>,
@@ -426,11 +514,10 @@ fn compute_approvals_root_hash(
 ) -> Result<Option<Digest>, BlockCreationError> {
     let mut approval_hashes = vec![];
     for deploy in deploys.iter().chain(transfers) {
-        let bytes = deploy
-            .approvals()
-            .to_bytes()
+        let approvals_hash = deploy
+            .approvals_hash()
             .map_err(BlockCreationError::BytesRepr)?;
-        approval_hashes.push(Digest::hash(bytes));
+        approval_hashes.push(approvals_hash.into());
     }
     Ok((!approval_hashes.is_empty()).then(|| Digest::hash_merkle_tree(approval_hashes)))
 }
@@ -2,7 +2,10 @@ mod event;
 mod metrics;
 mod tests;
 
-use std::fmt::Debug;
+use std::{
+    collections::{hash_map::Entry, BTreeSet, HashMap},
+    fmt::Debug,
+};
 
 use datasize::DataSize;
 use prometheus::Registry;
@@ -19,9 +22,10 @@ use casper_execution_engine::core::engine_state::{
 use casper_hashing::Digest;
 use casper_types::{
     account::{Account, AccountHash},
+    bytesrepr::ToBytes,
     system::auction::ARG_AMOUNT,
     Contract, ContractHash, ContractPackage, ContractPackageHash, ContractVersion,
-    ContractVersionKey, Key, ProtocolVersion, Timestamp, U512,
+    ContractVersionKey, Key, ProtocolVersion, PublicKey, Timestamp, U512,
 };
 
 use crate::{
@@ -31,8 +35,11 @@ use crate::{
         requests::{ContractRuntimeRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    types::{chainspec::DeployConfig, BlockHeader, Chainspec, Deploy, DeployConfigurationFailure},
-    utils::Source,
+    types::{
+        chainspec::DeployConfig, Approval, BlockHeader, Chainspec, Deploy, DeployHash,
+        DeployValidationReport,
+    },
+    utils::{clock, Source},
     NodeRng,
 };
 
@@ -40,6 +47,16 @@ pub(crate) use event::{Event, EventMetadata};
 
 const ARG_TARGET: &str = "target";
 
+/// The maximum number of deploys held while waiting for further approvals.
+const MAX_PENDING_DEPLOYS: usize = 1000;
+
+/// The maximum number of deploys of a single account held while waiting for further approvals.
+const MAX_PENDING_DEPLOYS_PER_ACCOUNT: usize = 10;
+
+/// The maximum combined serialized size in bytes of all deploys held while waiting for further
+/// approvals.
+const MAX_PENDING_DEPLOYS_SIZE: usize = 64 * 1024 * 1024;
+
 #[derive(Debug, Error, Serialize)]
 pub(crate) enum Error {
     /// The block chain has no blocks.
@@ -48,7 +65,7 @@ pub(crate) enum Error {
 
     /// The deploy is invalid due to failing to meet the deploy configuration.
     #[error("invalid deploy: {0}")]
-    InvalidDeployConfiguration(DeployConfigurationFailure),
+    InvalidDeployConfiguration(DeployValidationReport),
 
     /// The deploy is invalid due to missing or otherwise invalid parameters.
     #[error("deploy parameter failure: {failure} at prestate_hash: {prestate_hash}")]
@@ -65,6 +82,26 @@ pub(crate) enum Error {
         /// The timestamp when the node validated the expiry timestamp.
         current_node_timestamp: Timestamp,
     },
+
+    /// The node is draining and no longer accepts new deploys.
+    #[error("node is draining and no longer accepts new deploys")]
+    NodeDraining,
+
+    /// No deploy with the given hash is waiting for further approvals.
+    #[error("no pending deploy with hash {deploy_hash}")]
+    NoSuchPendingDeploy { deploy_hash: DeployHash },
+
+    /// Too many deploys, or too many bytes of deploys, are already waiting for further approvals.
+    #[error("too many deploys are pending further approvals")]
+    PendingDeployPoolFull,
+
+    /// Too many deploys of the same account are already waiting for further approvals.
+    #[error("too many deploys of account {account} are pending further approvals")]
+    TooManyPendingDeploysForAccount { account: PublicKey },
+
+    /// The deploy has already been executed, and would be rejected in any new block.
+    #[error("deploy {deploy_hash} has already been included in a block")]
+    ReplayedDeploy { deploy_hash: DeployHash },
 }
 
 /// A representation of the way in which a deploy failed validation checks.
@@ -146,6 +183,12 @@ pub struct DeployAcceptor {
     protocol_version: ProtocolVersion,
     deploy_config: DeployConfig,
     max_associated_keys: u32,
+    /// Whether accounts can delegate the authorization of their deploys to a contract.
+    allow_authorization_contracts: bool,
+    /// Whether the node is draining, in which case all new deploys are rejected.
+    is_draining: bool,
+    /// Deploys submitted by clients which are waiting for further approvals.
+    pending_deploys: HashMap<DeployHash, Box<Deploy>>,
     metrics: metrics::Metrics,
 }
 
@@ -159,10 +202,18 @@ impl DeployAcceptor {
             protocol_version: chainspec.protocol_version(),
             deploy_config: chainspec.deploy_config,
             max_associated_keys: chainspec.core_config.max_associated_keys,
+            allow_authorization_contracts: chainspec.core_config.allow_authorization_contracts,
+            is_draining: false,
+            pending_deploys: HashMap::new(),
             metrics: metrics::Metrics::new(registry)?,
         })
     }
 
+    /// Puts the deploy acceptor into drain mode, rejecting all deploys from then on.
+    pub(crate) fn begin_drain(&mut self) {
+        self.is_draining = true;
+    }
+
     /// Handles receiving a new `Deploy` from a peer or client.
     /// In the case of a peer, there should be no responder and the variant should be `None`
     /// In the case of a client, there should be a responder to communicate the validity of the
@@ -174,26 +225,34 @@ impl DeployAcceptor {
         source: Source,
         maybe_responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
+        if self.is_draining {
+            debug!(%deploy, %source, "rejecting deploy as node is draining");
+            return match maybe_responder {
+                Some(responder) => responder.respond(Err(Error::NodeDraining)).ignore(),
+                None => Effects::new(),
+            };
+        }
+
         let verification_start_timestamp = Timestamp::now();
-        let acceptable_result = deploy.is_config_compliant(
+        let report = deploy.check_config_compliance(
             &self.chain_name,
             &self.deploy_config,
             self.max_associated_keys,
         );
         // checks chainspec values
-        if let Err(error) = acceptable_result {
-            debug!(%deploy, %error, "deploy is incorrectly configured");
+        if !report.is_empty() {
+            debug!(%deploy, %report, "deploy is incorrectly configured");
             return self.handle_invalid_deploy_result(
                 effect_builder,
                 EventMetadata::new(deploy, source, maybe_responder),
-                Error::InvalidDeployConfiguration(error),
+                Error::InvalidDeployConfiguration(report),
                 verification_start_timestamp,
             );
         }
 
         // We only perform expiry checks on deploys received from the client.
         if source.is_client() {
-            let current_node_timestamp = Timestamp::now();
+            let current_node_timestamp = clock::now();
             if deploy.header().expired(current_node_timestamp) {
                 let time_of_expiry = deploy.header().expires();
                 debug!(%deploy, "deploy has expired");
@@ -207,17 +266,191 @@ impl DeployAcceptor {
                     verification_start_timestamp,
                 );
             }
+
+            // Deploys gossiped to us are checked for replays when proposing blocks; a client
+            // however should learn right away that their deploy will never be executed again.
+            let deploy_hash = *deploy.id();
+            return effect_builder
+                .is_replayed_deploy(deploy_hash)
+                .event(move |is_replay| Event::ReplayCheckResult {
+                    event_metadata: EventMetadata::new(deploy, source, maybe_responder),
+                    is_replay,
+                    verification_start_timestamp,
+                });
         }
 
+        self.get_highest_block_header(
+            effect_builder,
+            EventMetadata::new(deploy, source, maybe_responder),
+            verification_start_timestamp,
+        )
+    }
+
+    fn handle_replay_check_result<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: EventMetadata,
+        is_replay: bool,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        if is_replay {
+            let deploy_hash = *event_metadata.deploy.id();
+            debug!(%deploy_hash, "deploy is a replay");
+            return self.handle_invalid_deploy_result(
+                effect_builder,
+                event_metadata,
+                Error::ReplayedDeploy { deploy_hash },
+                verification_start_timestamp,
+            );
+        }
+
+        self.get_highest_block_header(effect_builder, event_metadata, verification_start_timestamp)
+    }
+
+    fn get_highest_block_header<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: EventMetadata,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
         effect_builder
             .get_highest_block_header_from_storage()
             .event(move |maybe_block_header| Event::GetBlockHeaderResult {
-                event_metadata: EventMetadata::new(deploy, source, maybe_responder),
+                event_metadata,
                 maybe_block_header: Box::new(maybe_block_header),
                 verification_start_timestamp,
             })
     }
 
+    /// Handles receiving a `Deploy` from a client which may not yet be approved by enough
+    /// authorization keys.  The deploy is held until further approvals are added via
+    /// `add_approvals`.
+    fn accept_partial(
+        &mut self,
+        deploy: Box<Deploy>,
+        responder: Responder<Result<(), Error>>,
+    ) -> Effects<Event> {
+        let current_node_timestamp = clock::now();
+        if let Err(error) = self.check_partial_deploy(&deploy, current_node_timestamp) {
+            debug!(%deploy, %error, "rejecting partial deploy");
+            return responder.respond(Err(error)).ignore();
+        }
+
+        self.purge_expired_pending_deploys(current_node_timestamp);
+        let pool_is_full = self.pending_deploys.len() >= MAX_PENDING_DEPLOYS
+            || self.pending_deploys_size() + deploy.serialized_length() > MAX_PENDING_DEPLOYS_SIZE;
+        let account = deploy.header().account();
+        let account_is_full = self
+            .pending_deploys
+            .values()
+            .filter(|pending_deploy| pending_deploy.header().account() == account)
+            .count()
+            >= MAX_PENDING_DEPLOYS_PER_ACCOUNT;
+        match self.pending_deploys.entry(*deploy.id()) {
+            Entry::Occupied(mut entry) => {
+                entry
+                    .get_mut()
+                    .add_approvals(deploy.approvals().iter().cloned());
+            }
+            Entry::Vacant(_) if pool_is_full => {
+                debug!(%deploy, "rejecting partial deploy as too many deploys are pending");
+                return responder
+                    .respond(Err(Error::PendingDeployPoolFull))
+                    .ignore();
+            }
+            Entry::Vacant(_) if account_is_full => {
+                debug!(
+                    %deploy,
+                    "rejecting partial deploy as too many deploys of its account are pending"
+                );
+                let account = account.clone();
+                return responder
+                    .respond(Err(Error::TooManyPendingDeploysForAccount { account }))
+                    .ignore();
+            }
+            Entry::Vacant(entry) => {
+                debug!(%deploy, "holding partial deploy until further approvals arrive");
+                entry.insert(deploy);
+            }
+        }
+        responder.respond(Ok(())).ignore()
+    }
+
+    /// Adds approvals to a pending `Deploy`, then runs it through the regular client deploy
+    /// checks.  If the deploy is still not sufficiently authorized, it remains pending.
+    fn add_approvals<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<(), Error>>,
+    ) -> Effects<Event> {
+        let current_node_timestamp = clock::now();
+        self.purge_expired_pending_deploys(current_node_timestamp);
+        let mut deploy = match self.pending_deploys.get(&deploy_hash) {
+            Some(deploy) => deploy.clone(),
+            None => {
+                return responder
+                    .respond(Err(Error::NoSuchPendingDeploy { deploy_hash }))
+                    .ignore();
+            }
+        };
+        deploy.add_approvals(approvals);
+        if let Err(error) = self.check_partial_deploy(&deploy, current_node_timestamp) {
+            debug!(%deploy, %error, "rejecting approvals for pending deploy");
+            return responder.respond(Err(error)).ignore();
+        }
+
+        let _ = self.pending_deploys.insert(deploy_hash, deploy.clone());
+        self.accept(effect_builder, deploy, Source::Client, Some(responder))
+    }
+
+    /// Performs the checks on a client's partially approved `Deploy` which do not depend on the
+    /// approvals being sufficient to authorize it.
+    fn check_partial_deploy(
+        &self,
+        deploy: &Deploy,
+        current_node_timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        if self.is_draining {
+            return Err(Error::NodeDraining);
+        }
+        let report = deploy.check_config_compliance(
+            &self.chain_name,
+            &self.deploy_config,
+            self.max_associated_keys,
+        );
+        if !report.is_empty() {
+            return Err(Error::InvalidDeployConfiguration(report));
+        }
+        if deploy.header().expired(current_node_timestamp) {
+            return Err(Error::ExpiredDeploy {
+                deploy_expiry_timestamp: deploy.header().expires(),
+                current_node_timestamp,
+            });
+        }
+        if deploy.is_valid().is_err() {
+            return Err(Error::InvalidDeployConfiguration(
+                deploy.check_cryptography(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the combined serialized size in bytes of all pending deploys.
+    fn pending_deploys_size(&self) -> usize {
+        self.pending_deploys
+            .values()
+            .map(|pending_deploy| pending_deploy.serialized_length())
+            .sum()
+    }
+
+    /// Drops all pending deploys which have expired.
+    fn purge_expired_pending_deploys(&mut self, current_node_timestamp: Timestamp) {
+        self.pending_deploys
+            .retain(|_, deploy| !deploy.header().expired(current_node_timestamp));
+    }
+
     fn handle_get_block_result<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -288,6 +521,11 @@ impl DeployAcceptor {
                 )
             }
             Some(account) => {
+                // If the account designated an authorization contract, the weight of the approvals
+                // is checked by executing it, which happens when the deploy is executed.  The
+                // approvals still have to be from associated keys of the account.
+                let is_authorized_by_contract = self.allow_authorization_contracts
+                    && account.authorization_contract().is_some();
                 let authorization_keys = event_metadata
                     .deploy
                     .approvals()
@@ -307,7 +545,7 @@ impl DeployAcceptor {
                         verification_start_timestamp,
                     );
                 }
-                if !account.can_deploy_with(&authorization_keys) {
+                if !is_authorized_by_contract && !account.can_deploy_with(&authorization_keys) {
                     let error = Error::InvalidDeployParameters {
                         prestate_hash,
                         failure: DeployParameterFailure::InsufficientDeploySignatureWeight,
@@ -755,7 +993,7 @@ impl DeployAcceptor {
     }
 
     fn handle_put_to_storage<REv: ReactorEventT>(
-        &self,
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: EventMetadata,
         is_new: bool,
@@ -767,6 +1005,8 @@ impl DeployAcceptor {
             maybe_responder,
         } = event_metadata;
         self.metrics.observe_accepted(verification_start_timestamp);
+        // A pending deploy which has now been accepted no longer needs to be held.
+        let _ = self.pending_deploys.remove(deploy.id());
         let mut effects = Effects::new();
         if is_new {
             effects.extend(
@@ -789,14 +1029,15 @@ impl DeployAcceptor {
         event_metadata: EventMetadata,
         verification_start_timestamp: Timestamp,
     ) -> Effects<Event> {
-        if let Err(deploy_configuration_failure) = event_metadata.deploy.is_valid() {
+        if event_metadata.deploy.is_valid().is_err() {
             // The client has submitted a deploy with one or more invalid signatures.
-            // Return an error to the RPC component via the responder.
+            // Return a report of all of them to the RPC component via the responder.
             debug!("deploy is cryptographically invalid");
+            let report = event_metadata.deploy.check_cryptography();
             return self.handle_invalid_deploy_result(
                 effect_builder,
                 event_metadata,
-                Error::InvalidDeployConfiguration(deploy_configuration_failure),
+                Error::InvalidDeployConfiguration(report),
                 verification_start_timestamp,
             );
         }
@@ -855,6 +1096,22 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 source,
                 maybe_responder: responder,
             } => self.accept(effect_builder, deploy, source, responder),
+            Event::AcceptPartial { deploy, responder } => self.accept_partial(deploy, responder),
+            Event::AddApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            } => self.add_approvals(effect_builder, deploy_hash, approvals, responder),
+            Event::ReplayCheckResult {
+                event_metadata,
+                is_replay,
+                verification_start_timestamp,
+            } => self.handle_replay_check_result(
+                effect_builder,
+                event_metadata,
+                is_replay,
+                verification_start_timestamp,
+            ),
             Event::GetBlockHeaderResult {
                 event_metadata,
                 maybe_block_header,
@@ -1,4 +1,7 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display, Formatter},
+};
 
 use serde::Serialize;
 
@@ -6,7 +9,7 @@ use super::Source;
 use crate::{
     components::deploy_acceptor::Error,
     effect::{announcements::RpcServerAnnouncement, Responder},
-    types::{BlockHeader, Deploy},
+    types::{Approval, BlockHeader, Deploy, DeployHash},
 };
 
 use casper_hashing::Digest;
@@ -46,12 +49,30 @@ pub(crate) enum Event {
         source: Source,
         maybe_responder: Option<Responder<Result<(), Error>>>,
     },
+    /// The initiating event to hold a client's `Deploy` until it has been approved by enough
+    /// authorization keys.
+    AcceptPartial {
+        deploy: Box<Deploy>,
+        responder: Responder<Result<(), Error>>,
+    },
+    /// Adds approvals to a pending `Deploy`, accepting it if it is then sufficiently authorized.
+    AddApprovals {
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<(), Error>>,
+    },
     /// The result of the `DeployAcceptor` putting a `Deploy` to the storage component.
     PutToStorageResult {
         event_metadata: EventMetadata,
         is_new: bool,
         verification_start_timestamp: Timestamp,
     },
+    /// The result of checking the replay index in the storage component for the `Deploy`.
+    ReplayCheckResult {
+        event_metadata: EventMetadata,
+        is_replay: bool,
+        verification_start_timestamp: Timestamp,
+    },
     /// The result of querying the highest available `BlockHeader` from the storage component.
     GetBlockHeaderResult {
         event_metadata: EventMetadata,
@@ -102,6 +123,18 @@ impl From<RpcServerAnnouncement> for Event {
                 source: Source::Client,
                 maybe_responder: responder,
             },
+            RpcServerAnnouncement::PartialDeployReceived { deploy, responder } => {
+                Event::AcceptPartial { deploy, responder }
+            }
+            RpcServerAnnouncement::DeployApprovalsReceived {
+                deploy_hash,
+                approvals,
+                responder,
+            } => Event::AddApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            },
         }
     }
 }
@@ -112,6 +145,19 @@ impl Display for Event {
             Event::Accept { deploy, source, .. } => {
                 write!(formatter, "accept {} from {}", deploy.id(), source)
             }
+            Event::AcceptPartial { deploy, .. } => {
+                write!(formatter, "accept partial {} from client", deploy.id())
+            }
+            Event::AddApprovals {
+                deploy_hash,
+                approvals,
+                ..
+            } => write!(
+                formatter,
+                "add {} approvals to pending {}",
+                approvals.len(),
+                deploy_hash
+            ),
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
@@ -131,6 +177,13 @@ impl Display for Event {
                     )
                 }
             }
+            Event::ReplayCheckResult { event_metadata, .. } => {
+                write!(
+                    formatter,
+                    "checked whether deploy with hash {} is a replay.",
+                    event_metadata.deploy.id()
+                )
+            }
             Event::GetBlockHeaderResult { event_metadata, .. } => {
                 write!(
                     formatter,
@@ -1,8 +1,9 @@
 #![cfg(test)]
 
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fmt::{self, Debug, Display, Formatter},
+    iter,
     time::Duration,
 };
 
@@ -21,7 +22,7 @@ use casper_execution_engine::{
 };
 use casper_types::{
     account::{Account, ActionThresholds, AssociatedKeys, Weight},
-    CLValue, StoredValue, URef, U512,
+    CLValue, PublicKey, SecretKey, StoredValue, URef, U512,
 };
 
 use super::*;
@@ -36,7 +37,7 @@ use crate::{
     protocol::Message,
     reactor::{self, EventQueueHandle, QueueKind, Runner},
     testing::ConditionCheckReactor,
-    types::{Block, Chainspec, ChainspecRawBytes, Deploy, NodeId},
+    types::{Block, Chainspec, ChainspecRawBytes, Deploy, DeployConfigurationFailure, NodeId},
     utils::{Loadable, WithDir},
     NodeRng,
 };
@@ -159,6 +160,7 @@ enum TestScenario {
     BalanceCheckForDeploySentByPeer,
     ShouldNotAcceptExpiredDeploySentByClient,
     ShouldAcceptExpiredDeploySentByPeer,
+    FromClientMultisigDeploy,
 }
 
 impl TestScenario {
@@ -197,7 +199,8 @@ impl TestScenario {
             | TestScenario::FromClientSessionContractPackage(_)
             | TestScenario::DeployWithEmptySessionModuleBytes
             | TestScenario::DeployWithNativeTransferInPayment
-            | TestScenario::ShouldNotAcceptExpiredDeploySentByClient => Source::Client,
+            | TestScenario::ShouldNotAcceptExpiredDeploySentByClient
+            | TestScenario::FromClientMultisigDeploy => Source::Client,
         }
     }
 
@@ -220,9 +223,8 @@ impl TestScenario {
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::AccountWithUnknownBalance
-            | TestScenario::BalanceCheckForDeploySentByPeer => {
-                Deploy::random_valid_native_transfer(rng)
-            }
+            | TestScenario::BalanceCheckForDeploySentByPeer
+            | TestScenario::FromClientMultisigDeploy => Deploy::random_valid_native_transfer(rng),
             TestScenario::DeployWithoutPaymentAmount => Deploy::random_without_payment_amount(rng),
             TestScenario::DeployWithMangledPaymentAmount => {
                 Deploy::random_with_mangled_payment_amount(rng)
@@ -316,7 +318,8 @@ impl TestScenario {
             | TestScenario::FromPeerAccountWithInvalidAssociatedKeys // account check skipped if from peer
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientValidDeploy
-            | TestScenario::ShouldAcceptExpiredDeploySentByPeer=> true,
+            | TestScenario::ShouldAcceptExpiredDeploySentByPeer
+            | TestScenario::FromClientMultisigDeploy => true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
             | TestScenario::FromClientMissingAccount
@@ -365,6 +368,15 @@ impl TestScenario {
     }
 }
 
+/// The secret key of the second associated key of the multisig account.
+fn cosigner_secret_key() -> SecretKey {
+    SecretKey::ed25519_from_bytes([42; SecretKey::ED25519_LENGTH]).unwrap()
+}
+
+fn cosigner_account_hash() -> AccountHash {
+    PublicKey::from(&cosigner_secret_key()).to_account_hash()
+}
+
 fn create_account(account_hash: AccountHash, test_scenario: TestScenario) -> Account {
     match test_scenario {
         TestScenario::FromPeerAccountWithInvalidAssociatedKeys
@@ -384,6 +396,21 @@ fn create_account(account_hash: AccountHash, test_scenario: TestScenario) -> Acc
                 invalid_action_threshold,
             )
         }
+        TestScenario::FromClientMultisigDeploy => {
+            let mut associated_keys = AssociatedKeys::new(account_hash, Weight::new(1));
+            associated_keys
+                .add_key(cosigner_account_hash(), Weight::new(1))
+                .expect("should add co-signer");
+            let action_thresholds = ActionThresholds::new(Weight::new(2), Weight::new(2))
+                .expect("should create action threshold");
+            Account::new(
+                account_hash,
+                BTreeMap::new(),
+                URef::default(),
+                associated_keys,
+                action_thresholds,
+            )
+        }
         _ => Account::create(account_hash, BTreeMap::new(), URef::default()),
     }
 }
@@ -656,6 +683,41 @@ fn schedule_accept_deploy(
     }
 }
 
+fn schedule_accept_partial_deploy(
+    deploy: Box<Deploy>,
+    responder: Responder<Result<(), super::Error>>,
+) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
+    |effect_builder: EffectBuilder<Event>| {
+        effect_builder
+            .into_inner()
+            .schedule(
+                super::Event::AcceptPartial { deploy, responder },
+                QueueKind::Regular,
+            )
+            .ignore()
+    }
+}
+
+fn schedule_add_approvals(
+    deploy_hash: DeployHash,
+    approvals: BTreeSet<Approval>,
+    responder: Responder<Result<(), super::Error>>,
+) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
+    move |effect_builder: EffectBuilder<Event>| {
+        effect_builder
+            .into_inner()
+            .schedule(
+                super::Event::AddApprovals {
+                    deploy_hash,
+                    approvals,
+                    responder,
+                },
+                QueueKind::Regular,
+            )
+            .ignore()
+    }
+}
+
 fn inject_balance_check_for_peer(
     deploy: Box<Deploy>,
     source: Source,
@@ -861,7 +923,7 @@ async fn run_deploy_acceptor_without_timeout(
             }
             // Check that a, new and valid, deploy sent by a client raises an `AcceptedNewDeploy`
             // announcement with the appropriate source.
-            TestScenario::FromClientValidDeploy => {
+            TestScenario::FromClientValidDeploy | TestScenario::FromClientMultisigDeploy => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(
@@ -1459,9 +1521,8 @@ async fn should_reject_deploy_without_transfer_amount() {
     let result = run_deploy_acceptor(test_scenario).await;
     assert!(matches!(
         result,
-        Err(super::Error::InvalidDeployConfiguration(
-            DeployConfigurationFailure::MissingTransferAmount
-        ))
+        Err(super::Error::InvalidDeployConfiguration(report))
+            if report.failures() == [DeployConfigurationFailure::MissingTransferAmount]
     ))
 }
 
@@ -1484,9 +1545,8 @@ async fn should_reject_deploy_with_mangled_transfer_amount() {
     let result = run_deploy_acceptor(test_scenario).await;
     assert!(matches!(
         result,
-        Err(super::Error::InvalidDeployConfiguration(
-            DeployConfigurationFailure::FailedToParseTransferAmount
-        ))
+        Err(super::Error::InvalidDeployConfiguration(report))
+            if report.failures() == [DeployConfigurationFailure::FailedToParseTransferAmount]
     ))
 }
 
@@ -1511,3 +1571,160 @@ async fn should_panic_when_balance_checking_for_deploy_sent_by_peer() {
     let result = run_deploy_acceptor(test_scenario).await;
     assert!(result.is_ok())
 }
+
+/// Adds the given approvals to the pending deploy, cranking the reactor until the deploy acceptor
+/// has responded.
+async fn add_approvals(
+    runner: &mut Runner<ConditionCheckReactor<Reactor>>,
+    rng: &mut NodeRng,
+    deploy_hash: DeployHash,
+    approvals: BTreeSet<Approval>,
+) -> Result<(), super::Error> {
+    let (sender, mut receiver) = oneshot::channel();
+    runner
+        .process_injected_effects(schedule_add_approvals(
+            deploy_hash,
+            approvals,
+            Responder::without_shutdown(sender),
+        ))
+        .await;
+    loop {
+        if let Ok(Some(result)) = receiver.try_recv() {
+            return result;
+        }
+        if runner.try_crank(rng).await.is_none() {
+            time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+async fn run_partial_deploy_without_timeout() {
+    let _ = logging::init();
+    let mut rng = crate::new_rng();
+
+    let mut runner: Runner<ConditionCheckReactor<Reactor>> =
+        Runner::new(TestScenario::FromClientMultisigDeploy, &mut rng)
+            .await
+            .unwrap();
+
+    let block = Box::new(Block::random(&mut rng));
+    let (block_sender, block_receiver) = oneshot::channel();
+    runner
+        .process_injected_effects(put_block_to_storage(
+            block,
+            Responder::without_shutdown(block_sender),
+        ))
+        .await;
+    while runner.try_crank(&mut rng).await.is_none() {
+        time::sleep(POLL_INTERVAL).await;
+    }
+    assert!(block_receiver.await.unwrap());
+
+    // The deploy is only signed by the account's main key, which is not enough to authorize it.
+    let deploy = TestScenario::FromClientMultisigDeploy.deploy(&mut rng);
+    let deploy_hash = *deploy.id();
+    let (sender, receiver) = oneshot::channel();
+    runner
+        .process_injected_effects(schedule_accept_partial_deploy(
+            Box::new(deploy),
+            Responder::without_shutdown(sender),
+        ))
+        .await;
+    while runner.try_crank(&mut rng).await.is_none() {
+        time::sleep(POLL_INTERVAL).await;
+    }
+    assert!(receiver.await.unwrap().is_ok());
+
+    // Adding no further approvals leaves the deploy pending.
+    let result = add_approvals(&mut runner, &mut rng, deploy_hash, BTreeSet::new()).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployParameters {
+            failure: DeployParameterFailure::InsufficientDeploySignatureWeight,
+            ..
+        })
+    ));
+    assert!(runner
+        .reactor()
+        .inner()
+        .deploy_acceptor
+        .pending_deploys
+        .contains_key(&deploy_hash));
+
+    // Once the co-signer approves the deploy, it is accepted and no longer pending.
+    let approvals = iter::once(Approval::create(&deploy_hash, &cosigner_secret_key())).collect();
+    let result = add_approvals(&mut runner, &mut rng, deploy_hash, approvals).await;
+    assert!(result.is_ok());
+    let reactor = runner.reactor().inner();
+    assert!(reactor.storage.get_deploy_by_hash(deploy_hash).is_some());
+    assert!(reactor.deploy_acceptor.pending_deploys.is_empty());
+}
+
+#[tokio::test]
+async fn should_accept_partial_deploy_once_sufficiently_approved() {
+    time::timeout(TIMEOUT, run_partial_deploy_without_timeout())
+        .await
+        .unwrap()
+}
+
+/// Creates a random native transfer deploy signed by `secret_key`.
+fn deploy_signed_by(secret_key: &SecretKey, rng: &mut NodeRng) -> Deploy {
+    let deploy = Deploy::random_valid_native_transfer(rng);
+    Deploy::new(
+        Timestamp::now(),
+        deploy.header().ttl(),
+        deploy.header().gas_price(),
+        deploy.header().dependencies().clone(),
+        deploy.header().chain_name().to_string(),
+        deploy.payment().clone(),
+        deploy.session().clone(),
+        secret_key,
+        None,
+    )
+}
+
+/// Hands the deploy to the deploy acceptor as a partially approved deploy and returns the result.
+async fn accept_partial(
+    deploy_acceptor: &mut DeployAcceptor,
+    deploy: Deploy,
+) -> Result<(), super::Error> {
+    let (sender, receiver) = oneshot::channel();
+    let effects =
+        deploy_acceptor.accept_partial(Box::new(deploy), Responder::without_shutdown(sender));
+    for effect in effects {
+        let _ = effect.await;
+    }
+    receiver.await.unwrap()
+}
+
+#[tokio::test]
+async fn should_limit_pending_deploys_per_account() {
+    let mut rng = crate::new_rng();
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let mut deploy_acceptor = DeployAcceptor::new(&chainspec, &Registry::new()).unwrap();
+
+    let secret_key = SecretKey::random(&mut rng);
+    for _ in 0..MAX_PENDING_DEPLOYS_PER_ACCOUNT {
+        let deploy = deploy_signed_by(&secret_key, &mut rng);
+        assert!(accept_partial(&mut deploy_acceptor, deploy).await.is_ok());
+    }
+
+    // Once the account has used up its share of the pool, its further deploys are rejected.
+    let deploy = deploy_signed_by(&secret_key, &mut rng);
+    let deploy_hash = *deploy.id();
+    let result = accept_partial(&mut deploy_acceptor, deploy).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::TooManyPendingDeploysForAccount { account })
+            if account == PublicKey::from(&secret_key)
+    ));
+    assert!(!deploy_acceptor.pending_deploys.contains_key(&deploy_hash));
+
+    // Other accounts can still add deploys to the pool.
+    let deploy = deploy_signed_by(&SecretKey::random(&mut rng), &mut rng);
+    assert!(accept_partial(&mut deploy_acceptor, deploy).await.is_ok());
+    assert_eq!(
+        deploy_acceptor.pending_deploys.len(),
+        MAX_PENDING_DEPLOYS_PER_ACCOUNT + 1
+    );
+}
@@ -86,6 +86,14 @@ pub(super) enum Action {
     },
     /// Dump the event queues.
     DumpQueues,
+    /// Show the network groups (autonomous systems or subnets) of outgoing connections, along
+    /// with the state of peer diversity enforcement.
+    NetworkDiversity,
+    /// Put the node into drain mode.
+    ///
+    /// The node stops proposing blocks and accepting deploys, finishes executing blocks that have
+    /// already been finalized and then exits with exit code 103.
+    Drain,
     /// Close connection server-side.
     Quit,
 }
@@ -117,5 +125,11 @@ mod tests {
 
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("network-diversity").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::NetworkDiversity));
+
+        let cmd = Command::from_line("drain").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::Drain));
     }
 }
@@ -32,6 +32,7 @@ use crate::{
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
+        requests::NetworkInfoRequest,
         EffectBuilder,
     },
     utils::display_error,
@@ -195,7 +196,10 @@ impl Session {
         line: &str,
     ) -> io::Result<bool>
     where
-        REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+        REv: From<DumpConsensusStateRequest>
+            + From<ControlAnnouncement>
+            + From<NetworkInfoRequest>
+            + Send,
     {
         debug!(%line, "line received");
         match Command::from_line(line) {
@@ -276,6 +280,17 @@ impl Session {
                             }
                         };
                     }
+                    Action::NetworkDiversity => {
+                        let stats = effect_builder.get_network_diversity_stats().await;
+                        self.send_outcome(writer, &Outcome::success("showing peer diversity"))
+                            .await?;
+                        self.send_to_client(writer, &stats).await?;
+                    }
+                    Action::Drain => {
+                        effect_builder.diagnostics_port_request_drain().await;
+                        self.send_outcome(writer, &Outcome::success("drain mode requested"))
+                            .await?;
+                    }
                     Action::Quit => {
                         self.send_outcome(writer, &Outcome::success("goodbye!"))
                             .await?;
@@ -393,7 +408,10 @@ async fn handler<REv>(
     mut shutdown_receiver: watch::Receiver<()>,
 ) -> io::Result<()>
 where
-    REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+    REv: From<DumpConsensusStateRequest>
+        + From<ControlAnnouncement>
+        + From<NetworkInfoRequest>
+        + Send,
 {
     debug!("accepted new connection on diagnostics port");
 
@@ -433,7 +451,10 @@ pub(super) async fn server<REv>(
     listener: UnixListener,
     mut shutdown_receiver: watch::Receiver<()>,
 ) where
-    REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+    REv: From<DumpConsensusStateRequest>
+        + From<ControlAnnouncement>
+        + From<NetworkInfoRequest>
+        + Send,
 {
     let handling_shutdown_receiver = shutdown_receiver.clone();
     let mut next_client_id: u64 = 0;
@@ -537,6 +558,7 @@ mod tests {
         type Config = TestReactorConfig;
 
         components: {
+            #[event_source]
             diagnostics_console = has_effects DiagnosticsPort(&WithDir::new(cfg.base_dir.clone(), cfg.diagnostics_port), event_queue);
         }
 
@@ -544,6 +566,7 @@ mod tests {
 
         requests: {
             DumpConsensusStateRequest -> !;
+            NetworkInfoRequest -> !;
         }
 
         announcements: {}
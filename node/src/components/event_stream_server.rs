@@ -25,12 +25,15 @@ mod event;
 mod event_indexer;
 mod http_server;
 mod sse_server;
+mod subscription_registry;
 #[cfg(test)]
 mod tests;
 
 use std::{convert::Infallible, fmt::Debug, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use datasize::DataSize;
+use futures::{future, TryFutureExt};
+use hyper::server::Server;
 use tokio::sync::{
     mpsc::{self, UnboundedSender},
     oneshot,
@@ -44,14 +47,15 @@ use super::Component;
 use crate::{
     effect::{EffectBuilder, Effects},
     types::JsonBlock,
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
 pub use config::Config;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
-use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
+use sse_server::{ChannelsAndFilter, ExecutionResultSummary};
+use subscription_registry::SubscriptionRegistry;
 
 /// This is used to define the number of events to buffer in the tokio broadcast channel to help
 /// slower clients to try to avoid missing events (See
@@ -77,6 +81,9 @@ struct InnerServer {
     sse_data_sender: UnboundedSender<(EventIndex, SseData)>,
     event_indexer: EventIndexer,
     listening_address: SocketAddr,
+    /// The accounts and contracts subscribed to via the finalized deploys event stream.
+    #[data_size(skip)]
+    subscription_registry: SubscriptionRegistry,
 }
 
 #[derive(DataSize, Debug)]
@@ -86,25 +93,24 @@ pub(crate) struct EventStreamServer {
 
 impl EventStreamServer {
     pub(crate) fn new(
-        config: Config,
+        config: WithDir<Config>,
         storage_path: PathBuf,
         api_version: ProtocolVersion,
     ) -> Result<Self, ListeningError> {
-        if !config.enable_server {
+        if !config.value().enable_server {
             return Ok(EventStreamServer { inner: None });
         }
 
-        let required_address = utils::resolve_address(&config.address).map_err(|error| {
-            warn!(
-                %error,
-                address=%config.address,
-                "failed to start event stream server, cannot parse address"
-            );
-            ListeningError::ResolveAddress(error)
-        })?;
+        let incoming = utils::start_listening(
+            &config.value().address,
+            WithDir::new(config.dir(), &config.value().tls),
+        )?;
+        let (_, config) = config.into_parts();
+        let cors = config.cors.to_builder()?;
 
         let event_indexer = EventIndexer::new(storage_path);
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
+        let subscription_registry = SubscriptionRegistry::default();
 
         // Event stream channels and filter.
         let broadcast_channel_size = config.event_stream_buffer_length
@@ -117,19 +123,21 @@ impl EventStreamServer {
         } = ChannelsAndFilter::new(
             broadcast_channel_size as usize,
             config.max_concurrent_subscribers,
+            subscription_registry.clone(),
         );
 
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-        let (listening_address, server_with_shutdown) =
-            warp::serve(sse_filter.with(warp::cors().allow_any_origin()))
-                .try_bind_with_graceful_shutdown(required_address, async {
-                    shutdown_receiver.await.ok();
-                })
-                .map_err(|error| ListeningError::Listen {
-                    address: required_address,
-                    error: Box::new(error),
-                })?;
+        let listening_address = incoming.local_addr();
+        let service = warp::service(sse_filter.with(cors));
+        let make_svc =
+            hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+        let server_with_shutdown = Server::builder(incoming)
+            .serve(make_svc)
+            .with_graceful_shutdown(async {
+                shutdown_receiver.await.ok();
+            })
+            .unwrap_or_else(|error| warn!(%error, "error running event stream server"));
         info!(address=%listening_address, "started event stream server");
 
         tokio::spawn(http_server::run(
@@ -147,6 +155,7 @@ impl EventStreamServer {
                 sse_data_sender,
                 event_indexer,
                 listening_address,
+                subscription_registry,
             }),
         })
     }
@@ -193,15 +202,37 @@ where
                 deploy_header,
                 block_hash,
                 execution_result,
-            } => self.broadcast(SseData::DeployProcessed {
-                deploy_hash: Box::new(deploy_hash),
-                account: Box::new(deploy_header.account().clone()),
-                timestamp: deploy_header.timestamp(),
-                ttl: deploy_header.ttl(),
-                dependencies: deploy_header.dependencies().clone(),
-                block_hash: Box::new(block_hash),
-                execution_result,
-            }),
+            } => {
+                // Only deploys of subscribed accounts or touching subscribed contracts are also
+                // announced on the finalized deploys event stream.
+                let subscribed_contracts = self.inner.as_ref().and_then(|server| {
+                    server
+                        .subscription_registry
+                        .subscribed_contracts(deploy_header.account(), &execution_result)
+                });
+                let deploy_finalized =
+                    subscribed_contracts.map(|contracts| SseData::DeployFinalized {
+                        deploy_hash: Box::new(deploy_hash),
+                        account: Box::new(deploy_header.account().clone()),
+                        block_hash: Box::new(block_hash),
+                        contracts,
+                        execution_result: ExecutionResultSummary::from(&*execution_result),
+                    });
+
+                let mut effects = self.broadcast(SseData::DeployProcessed {
+                    deploy_hash: Box::new(deploy_hash),
+                    account: Box::new(deploy_header.account().clone()),
+                    timestamp: deploy_header.timestamp(),
+                    ttl: deploy_header.ttl(),
+                    dependencies: deploy_header.dependencies().clone(),
+                    block_hash: Box::new(block_hash),
+                    execution_result,
+                });
+                if let Some(deploy_finalized) = deploy_finalized {
+                    effects.extend(self.broadcast(deploy_finalized));
+                }
+                effects
+            }
             Event::DeploysExpired(deploy_hashes) => deploy_hashes
                 .into_iter()
                 .flat_map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
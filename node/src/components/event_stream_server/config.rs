@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::http_server::{CorsConfig, TlsConfig};
+
 /// Default binding address for the SSE HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -28,6 +30,14 @@ pub struct Config {
 
     /// Default maximum number of subscribers across all event streams permitted at any one time.
     pub max_concurrent_subscribers: u32,
+
+    /// TLS settings.
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// CORS policy.
+    #[serde(default)]
+    pub cors: CorsConfig,
 }
 
 impl Config {
@@ -38,6 +48,8 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
+            tls: TlsConfig::default(),
+            cors: CorsConfig::default(),
         }
     }
 }
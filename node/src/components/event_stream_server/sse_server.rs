@@ -1,7 +1,7 @@
 //! Types and functions used by the http server to manage the event-stream.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     net::SocketAddr,
     sync::{Arc, RwLock},
 };
@@ -35,9 +35,13 @@ use warp::{
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    AsymmetricType, ContractHash, EraId, ExecutionEffect, ExecutionResult, ProtocolVersion,
+    PublicKey, TimeDiff, Timestamp, U512,
 };
 
+use super::subscription_registry::{
+    DeploySubscription, RegisteredSubscription, SubscriptionRegistry,
+};
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
 #[cfg(test)]
 use crate::{testing, types::Block};
@@ -51,8 +55,17 @@ pub const SSE_API_MAIN_PATH: &str = "main";
 pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 /// The URL path part to subscribe to only `FinalitySignature` events.
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
+/// The URL path part to subscribe to only `DeployFinalized` events of given accounts and
+/// contracts.
+pub const SSE_API_FINALIZED_DEPLOYS_PATH: &str = "finalized-deploys";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name listing the hex-encoded public keys of the accounts whose
+/// deploys are subscribed to, comma-separated.
+pub const ACCOUNTS_QUERY_FIELD: &str = "accounts";
+/// The URL query string field name listing the formatted hashes of the contracts whose deploys are
+/// subscribed to, comma-separated.
+pub const CONTRACTS_QUERY_FIELD: &str = "contracts";
 
 /// The filter associated with `/events/main` path.
 const MAIN_FILTER: [EventFilter; 5] = [
@@ -66,6 +79,8 @@ const MAIN_FILTER: [EventFilter; 5] = [
 const DEPLOYS_FILTER: [EventFilter; 1] = [EventFilter::DeployAccepted];
 /// The filter associated with `/events/sigs` path.
 const SIGNATURES_FILTER: [EventFilter; 1] = [EventFilter::FinalitySignature];
+/// The filter associated with `/events/finalized-deploys` path.
+const FINALIZED_DEPLOYS_FILTER: [EventFilter; 1] = [EventFilter::DeployFinalized];
 
 /// The "id" field of the events sent on the event stream to clients.
 pub type Id = u32;
@@ -99,6 +114,17 @@ pub enum SseData {
         #[data_size(skip)]
         execution_result: Box<ExecutionResult>,
     },
+    /// The given deploy, sent from an account or touching a contract subscribed to via
+    /// `/events/finalized-deploys`, has been executed, committed and forms part of the given block.
+    ///
+    /// `contracts` lists the subscribed contracts touched by the deploy.
+    DeployFinalized {
+        deploy_hash: Box<DeployHash>,
+        account: Box<PublicKey>,
+        block_hash: Box<BlockHash>,
+        contracts: Vec<ContractHash>,
+        execution_result: ExecutionResultSummary,
+    },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
     /// Generic representation of validator's fault in an era.
@@ -126,6 +152,7 @@ impl SseData {
             SseData::BlockAdded { .. } => filter.contains(&EventFilter::BlockAdded),
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
+            SseData::DeployFinalized { .. } => filter.contains(&EventFilter::DeployFinalized),
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
@@ -178,6 +205,17 @@ impl SseData {
         }
     }
 
+    /// Returns a random `SseData::DeployFinalized` sent from `account`.
+    pub(super) fn random_deploy_finalized(rng: &mut TestRng, account: PublicKey) -> Self {
+        SseData::DeployFinalized {
+            deploy_hash: Box::new(DeployHash::random(rng)),
+            account: Box::new(account),
+            block_hash: Box::new(BlockHash::random(rng)),
+            contracts: vec![],
+            execution_result: ExecutionResultSummary::from(&rng.gen::<ExecutionResult>()),
+        }
+    }
+
     /// Returns a random `SseData::DeployExpired`
     pub(super) fn random_deploy_expired(rng: &mut TestRng) -> Self {
         let deploy = testing::create_expired_deploy(Timestamp::now(), rng);
@@ -217,6 +255,34 @@ impl SseData {
     }
 }
 
+/// A summary of the result of executing a deploy.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize, JsonSchema)]
+pub struct ExecutionResultSummary {
+    /// The cost of executing the deploy.
+    cost: U512,
+    /// The error message associated with executing the deploy, or `None` if it succeeded.
+    error_message: Option<String>,
+}
+
+impl From<&ExecutionResult> for ExecutionResultSummary {
+    fn from(execution_result: &ExecutionResult) -> Self {
+        match execution_result {
+            ExecutionResult::Success { cost, .. } => ExecutionResultSummary {
+                cost: *cost,
+                error_message: None,
+            },
+            ExecutionResult::Failure {
+                cost,
+                error_message,
+                ..
+            } => ExecutionResultSummary {
+                cost: *cost,
+                error_message: Some(error_message.clone()),
+            },
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub(super) struct DeployAccepted {
@@ -270,6 +336,7 @@ pub(super) enum EventFilter {
     BlockAdded,
     DeployAccepted,
     DeployProcessed,
+    DeployFinalized,
     DeployExpired,
     Fault,
     FinalitySignature,
@@ -312,6 +379,7 @@ async fn filter_map_server_sent_event(
 
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
+        | &SseData::DeployFinalized { .. }
         | &SseData::DeployExpired { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
@@ -342,6 +410,7 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
         SSE_API_MAIN_PATH => Some(&MAIN_FILTER[..]),
         SSE_API_DEPLOYS_PATH => Some(&DEPLOYS_FILTER[..]),
         SSE_API_SIGNATURES_PATH => Some(&SIGNATURES_FILTER[..]),
+        SSE_API_FINALIZED_DEPLOYS_PATH => Some(&FINALIZED_DEPLOYS_FILTER[..]),
         _ => None,
     }
 }
@@ -368,14 +437,48 @@ fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
     }
 }
 
+/// Extracts the accounts and contracts subscribed to from the provided query, removing their
+/// fields from it.
+///
+/// Returns a 422 response if either field doesn't hold a comma-separated list of public keys or
+/// contract hashes respectively, or if neither lists anything.
+fn parse_deploy_subscription(
+    query: &mut HashMap<String, String>,
+) -> Result<DeploySubscription, Response> {
+    let accounts = match query.remove(ACCOUNTS_QUERY_FIELD) {
+        Some(accounts) => accounts
+            .split(',')
+            .map(PublicKey::from_hex)
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(|_| create_422_for_subscription())?,
+        None => BTreeSet::new(),
+    };
+    let contracts = match query.remove(CONTRACTS_QUERY_FIELD) {
+        Some(contracts) => contracts
+            .split(',')
+            .map(ContractHash::from_formatted_str)
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(|_| create_422_for_subscription())?,
+        None => BTreeSet::new(),
+    };
+
+    let subscription = DeploySubscription::new(accounts, contracts);
+    if subscription.is_empty() {
+        return Err(create_422_for_subscription());
+    }
+    Ok(subscription)
+}
+
 /// Creates a 404 response with a useful error message in the body.
 fn create_404() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}' or '/{root}/{sigs}'\n",
+        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}', '/{root}/{sigs}' or \
+        '/{root}/{finalized}'\n",
         root = SSE_API_ROOT_PATH,
         main = SSE_API_MAIN_PATH,
         deploys = SSE_API_DEPLOYS_PATH,
-        sigs = SSE_API_SIGNATURES_PATH
+        sigs = SSE_API_SIGNATURES_PATH,
+        finalized = SSE_API_FINALIZED_DEPLOYS_PATH
     )));
     *response.status_mut() = StatusCode::NOT_FOUND;
     response
@@ -392,6 +495,18 @@ fn create_422() -> Response {
     response
 }
 
+/// Creates a 422 response with a useful error message in the body for use in case of a bad
+/// subscription to finalized deploys.
+fn create_422_for_subscription() -> Response {
+    let mut response = Response::new(Body::from(format!(
+        "invalid query: expected '{}=<PUBLIC KEYS>' and/or '{}=<CONTRACT HASHES>', \
+        comma-separated\n",
+        ACCOUNTS_QUERY_FIELD, CONTRACTS_QUERY_FIELD
+    )));
+    *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+    response
+}
+
 /// Creates a 503 response (Service Unavailable) to be returned if the server has too many
 /// subscribers.
 fn create_503() -> Response {
@@ -409,7 +524,11 @@ pub(super) struct ChannelsAndFilter {
 impl ChannelsAndFilter {
     /// Creates the message-passing channels required to run the event-stream server and the warp
     /// filter for the event-stream server.
-    pub(super) fn new(broadcast_channel_size: usize, max_concurrent_subscribers: u32) -> Self {
+    pub(super) fn new(
+        broadcast_channel_size: usize,
+        max_concurrent_subscribers: u32,
+        subscription_registry: SubscriptionRegistry,
+    ) -> Self {
         // Create a channel to broadcast new events to all subscribed clients' streams.
         let (event_broadcaster, _) = broadcast::channel(broadcast_channel_size);
         let cloned_broadcaster = event_broadcaster.clone();
@@ -419,7 +538,7 @@ impl ChannelsAndFilter {
         let (new_subscriber_info_sender, new_subscriber_info_receiver) = mpsc::unbounded_channel();
 
         let serve = move |path_param: String,
-                          query: HashMap<String, String>,
+                          mut query: HashMap<String, String>,
                           maybe_remote_address: Option<SocketAddr>| {
             let remote_address = match maybe_remote_address {
                 Some(address) => address.to_string(),
@@ -442,6 +561,16 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
+            // Only subscriptions to finalized deploys name the accounts and contracts of interest.
+            let subscription = if path_param == SSE_API_FINALIZED_DEPLOYS_PATH {
+                match parse_deploy_subscription(&mut query) {
+                    Ok(subscription) => Some(subscription),
+                    Err(error_response) => return error_response,
+                }
+            } else {
+                None
+            };
+
             let start_from = match parse_query(query) {
                 Ok(maybe_id) => maybe_id,
                 Err(error_response) => return error_response,
@@ -466,10 +595,15 @@ impl ChannelsAndFilter {
             // Create a channel for the client's handler to receive the stream of ongoing events.
             let ongoing_events_receiver = cloned_broadcaster.subscribe();
 
+            // The subscription stays registered for as long as the client's stream is alive.
+            let registered_subscription =
+                subscription.map(|subscription| subscription_registry.register(subscription));
+
             sse::reply(sse::keep_alive().stream(stream_to_client(
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                registered_subscription,
                 remote_address,
             )))
             .into_response()
@@ -506,13 +640,17 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// skipped, and for clients of `/events/finalized-deploys` their `RegisteredSubscription`, which
+/// causes `DeployFinalized` events of accounts and contracts not subscribed to be skipped.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    subscription: Option<RegisteredSubscription>,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+    let subscription = subscription.map(Arc::new);
+
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
     let initial_stream_ids = Arc::new(RwLock::new(HashSet::new()));
     let cloned_initial_ids = Arc::clone(&initial_stream_ids);
@@ -558,14 +696,38 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| async move {
-            match result {
-                Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
-                Err(error) => Some(Err(error)),
+        .filter_map(move |result| {
+            let subscription = subscription.clone();
+            async move {
+                match result {
+                    Ok(event) => {
+                        if !is_subscribed(&event.data, subscription.as_deref()) {
+                            return None;
+                        }
+                        filter_map_server_sent_event(&event, event_filter).await
+                    }
+                    Err(error) => Some(Err(error)),
+                }
             }
         })
 }
 
+/// Returns `false` if `data` is a `DeployFinalized` event not covered by the client's
+/// subscription.
+fn is_subscribed(data: &SseData, subscription: Option<&RegisteredSubscription>) -> bool {
+    match (data, subscription) {
+        (
+            SseData::DeployFinalized {
+                account, contracts, ..
+            },
+            Some(registered_subscription),
+        ) => registered_subscription
+            .subscription()
+            .matches(account, contracts),
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
@@ -619,6 +781,10 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_deploy_processed(&mut rng),
         };
+        let deploy_finalized = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_deploy_finalized(&mut rng, PublicKey::random(&mut rng)),
+        };
         let deploy_expired = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_deploy_expired(&mut rng),
@@ -640,7 +806,8 @@ mod tests {
             data: SseData::Shutdown,
         };
 
-        // `EventFilter::Main` should only filter out `DeployAccepted`s and `FinalitySignature`s.
+        // `EventFilter::Main` should only filter out `DeployAccepted`s, `DeployFinalized`s and
+        // `FinalitySignature`s.
         should_not_filter_out(&api_version, &MAIN_FILTER[..]).await;
         should_not_filter_out(&block_added, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..]).await;
@@ -650,6 +817,7 @@ mod tests {
         should_not_filter_out(&shutdown, &MAIN_FILTER).await;
 
         should_filter_out(&deploy_accepted, &MAIN_FILTER[..]).await;
+        should_filter_out(&deploy_finalized, &MAIN_FILTER[..]).await;
         should_filter_out(&finality_signature, &MAIN_FILTER[..]).await;
 
         // `EventFilter::DeployAccepted` should filter out everything except `ApiVersion`s and
@@ -660,6 +828,7 @@ mod tests {
 
         should_filter_out(&block_added, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_processed, &DEPLOYS_FILTER[..]).await;
+        should_filter_out(&deploy_finalized, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_expired, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&fault, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&finality_signature, &DEPLOYS_FILTER[..]).await;
@@ -674,9 +843,24 @@ mod tests {
         should_filter_out(&block_added, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_accepted, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_processed, &SIGNATURES_FILTER[..]).await;
+        should_filter_out(&deploy_finalized, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_expired, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..]).await;
+
+        // `EventFilter::DeployFinalized` should filter out everything except `ApiVersion`s and
+        // `DeployFinalized`s.
+        should_not_filter_out(&api_version, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_not_filter_out(&deploy_finalized, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_not_filter_out(&shutdown, &FINALIZED_DEPLOYS_FILTER[..]).await;
+
+        should_filter_out(&block_added, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&deploy_accepted, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&deploy_processed, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&deploy_expired, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&fault, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&finality_signature, &FINALIZED_DEPLOYS_FILTER[..]).await;
+        should_filter_out(&step, &FINALIZED_DEPLOYS_FILTER[..]).await;
     }
 
     /// This test checks that events with incorrect IDs (i.e. no types have an ID except for
@@ -705,6 +889,10 @@ mod tests {
             id: None,
             data: SseData::random_deploy_processed(&mut rng),
         };
+        let malformed_deploy_finalized = ServerSentEvent {
+            id: None,
+            data: SseData::random_deploy_finalized(&mut rng, PublicKey::random(&mut rng)),
+        };
         let malformed_deploy_expired = ServerSentEvent {
             id: None,
             data: SseData::random_deploy_expired(&mut rng),
@@ -730,11 +918,13 @@ mod tests {
             &MAIN_FILTER[..],
             &DEPLOYS_FILTER[..],
             &SIGNATURES_FILTER[..],
+            &FINALIZED_DEPLOYS_FILTER[..],
         ] {
             should_filter_out(&malformed_api_version, filter).await;
             should_filter_out(&malformed_block_added, filter).await;
             should_filter_out(&malformed_deploy_accepted, filter).await;
             should_filter_out(&malformed_deploy_processed, filter).await;
+            should_filter_out(&malformed_deploy_finalized, filter).await;
             should_filter_out(&malformed_deploy_expired, filter).await;
             should_filter_out(&malformed_fault, filter).await;
             should_filter_out(&malformed_finality_signature, filter).await;
@@ -743,6 +933,48 @@ mod tests {
         }
     }
 
+    /// This test checks that `DeployFinalized` events are only passed to clients subscribed to the
+    /// deploy's account or to one of the contracts it touched.
+    #[test]
+    fn should_filter_unsubscribed_finalized_deploys() {
+        let mut rng = crate::new_rng();
+        let account = PublicKey::random(&mut rng);
+        let other_account = PublicKey::random(&mut rng);
+
+        let mut query = HashMap::new();
+        let _ = query.insert(ACCOUNTS_QUERY_FIELD.to_string(), account.to_hex());
+        let _ = query.insert(QUERY_FIELD.to_string(), "1".to_string());
+        let subscription = parse_deploy_subscription(&mut query).unwrap();
+        assert_eq!(parse_query(query).unwrap(), Some(1));
+
+        let registry = SubscriptionRegistry::default();
+        let registered_subscription = registry.register(subscription);
+
+        let subscribed = SseData::random_deploy_finalized(&mut rng, account);
+        let unsubscribed = SseData::random_deploy_finalized(&mut rng, other_account);
+        assert!(is_subscribed(&subscribed, Some(&registered_subscription)));
+        assert!(!is_subscribed(
+            &unsubscribed,
+            Some(&registered_subscription)
+        ));
+        assert!(is_subscribed(
+            &SseData::random_block_added(&mut rng),
+            Some(&registered_subscription)
+        ));
+
+        // A subscription must name at least one valid account or contract.
+        for (field, value) in &[
+            (QUERY_FIELD, "1"),
+            (ACCOUNTS_QUERY_FIELD, "not a public key"),
+            (CONTRACTS_QUERY_FIELD, "not a contract hash"),
+        ] {
+            let mut query = HashMap::new();
+            let _ = query.insert(field.to_string(), value.to_string());
+            let response = parse_deploy_subscription(&mut query).unwrap_err();
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
     async fn should_filter_duplicate_events(path_filter: &str) {
         // Returns `count` random SSE events, all of a single variant defined by `path_filter`.  The
         // events will have sequential IDs starting from `start_id`, and if the path filter
@@ -856,6 +1088,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                None,
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
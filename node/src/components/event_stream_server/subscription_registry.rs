@@ -0,0 +1,252 @@
+//! Registry of the accounts and contracts clients of the finalized deploys event stream have
+//! subscribed to.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use casper_types::{ContractHash, ExecutionResult, Key, PublicKey};
+
+/// The accounts and contracts a single client of the finalized deploys event stream has
+/// subscribed to.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub(super) struct DeploySubscription {
+    accounts: BTreeSet<PublicKey>,
+    contracts: BTreeSet<ContractHash>,
+}
+
+impl DeploySubscription {
+    pub(super) fn new(accounts: BTreeSet<PublicKey>, contracts: BTreeSet<ContractHash>) -> Self {
+        DeploySubscription {
+            accounts,
+            contracts,
+        }
+    }
+
+    /// Returns `true` if the subscription covers no account and no contract.
+    pub(super) fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.contracts.is_empty()
+    }
+
+    /// Returns `true` if a deploy sent from `account` and touching `contracts` is of interest to
+    /// the subscriber.
+    pub(super) fn matches(&self, account: &PublicKey, contracts: &[ContractHash]) -> bool {
+        self.accounts.contains(account)
+            || contracts
+                .iter()
+                .any(|contract_hash| self.contracts.contains(contract_hash))
+    }
+}
+
+/// The registry of all current subscriptions to the finalized deploys event stream.
+///
+/// Cloned instances share the same underlying subscriptions.
+#[derive(Clone, Default, Debug)]
+pub(super) struct SubscriptionRegistry {
+    subscriptions: Arc<RwLock<HashMap<u64, DeploySubscription>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl SubscriptionRegistry {
+    /// Registers the given subscription, which stays registered until the returned
+    /// `RegisteredSubscription` is dropped.
+    pub(super) fn register(&self, subscription: DeploySubscription) -> RegisteredSubscription {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .subscriptions
+            .write()
+            .unwrap()
+            .insert(id, subscription.clone());
+        RegisteredSubscription {
+            id,
+            subscription,
+            registry: self.clone(),
+        }
+    }
+
+    /// Returns the number of registered subscriptions.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.subscriptions.read().unwrap().len()
+    }
+
+    /// Returns the subscribed contracts touched by a deploy sent from `account` with the given
+    /// execution result, or `None` if no subscriber is interested in the deploy.
+    pub(super) fn subscribed_contracts(
+        &self,
+        account: &PublicKey,
+        execution_result: &ExecutionResult,
+    ) -> Option<Vec<ContractHash>> {
+        let subscriptions = self.subscriptions.read().unwrap();
+        if subscriptions.is_empty() {
+            return None;
+        }
+
+        let subscribed_contracts: Vec<ContractHash> = touched_contracts(execution_result)
+            .into_iter()
+            .filter(|contract_hash| {
+                subscriptions
+                    .values()
+                    .any(|subscription| subscription.contracts.contains(contract_hash))
+            })
+            .collect();
+
+        let is_subscribed = !subscribed_contracts.is_empty()
+            || subscriptions
+                .values()
+                .any(|subscription| subscription.accounts.contains(account));
+
+        if is_subscribed {
+            Some(subscribed_contracts)
+        } else {
+            None
+        }
+    }
+}
+
+/// A subscription held in the registry for as long as this value is alive.
+#[derive(Debug)]
+pub(super) struct RegisteredSubscription {
+    id: u64,
+    subscription: DeploySubscription,
+    registry: SubscriptionRegistry,
+}
+
+impl RegisteredSubscription {
+    pub(super) fn subscription(&self) -> &DeploySubscription {
+        &self.subscription
+    }
+}
+
+impl Drop for RegisteredSubscription {
+    fn drop(&mut self) {
+        let _ = self
+            .registry
+            .subscriptions
+            .write()
+            .unwrap()
+            .remove(&self.id);
+    }
+}
+
+/// Returns the contracts whose keys were read or written while executing a deploy.
+fn touched_contracts(execution_result: &ExecutionResult) -> BTreeSet<ContractHash> {
+    let effect = match execution_result {
+        ExecutionResult::Success { effect, .. } | ExecutionResult::Failure { effect, .. } => effect,
+    };
+    effect
+        .transforms
+        .iter()
+        .filter_map(
+            |transform_entry| match Key::from_formatted_str(&transform_entry.key) {
+                Ok(Key::Hash(hash_addr)) => Some(ContractHash::new(hash_addr)),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{ExecutionEffect, Transform, TransformEntry, U512};
+
+    use super::*;
+
+    fn execution_result_touching(contract_hashes: &[ContractHash]) -> ExecutionResult {
+        let transforms = contract_hashes
+            .iter()
+            .map(|contract_hash| TransformEntry {
+                key: Key::from(*contract_hash).to_formatted_string(),
+                transform: Transform::Identity,
+            })
+            .collect();
+        ExecutionResult::Success {
+            effect: ExecutionEffect {
+                operations: vec![],
+                transforms,
+            },
+            transfers: vec![],
+            cost: U512::one(),
+        }
+    }
+
+    #[test]
+    fn should_match_subscribed_accounts_and_contracts() {
+        let mut rng = crate::new_rng();
+        let account = PublicKey::random(&mut rng);
+        let other_account = PublicKey::random(&mut rng);
+        let contract_hash = ContractHash::new([1; 32]);
+        let other_contract_hash = ContractHash::new([2; 32]);
+
+        let registry = SubscriptionRegistry::default();
+        assert!(registry
+            .subscribed_contracts(&account, &execution_result_touching(&[contract_hash]))
+            .is_none());
+
+        let by_account = registry.register(DeploySubscription::new(
+            iter_set(account.clone()),
+            BTreeSet::new(),
+        ));
+        let by_contract = registry.register(DeploySubscription::new(
+            BTreeSet::new(),
+            iter_set(contract_hash),
+        ));
+        assert_eq!(registry.len(), 2);
+
+        assert_eq!(
+            registry.subscribed_contracts(&account, &execution_result_touching(&[])),
+            Some(vec![])
+        );
+        assert_eq!(
+            registry.subscribed_contracts(
+                &other_account,
+                &execution_result_touching(&[contract_hash, other_contract_hash])
+            ),
+            Some(vec![contract_hash])
+        );
+        assert!(registry
+            .subscribed_contracts(
+                &other_account,
+                &execution_result_touching(&[other_contract_hash])
+            )
+            .is_none());
+
+        assert!(by_account.subscription().matches(&account, &[]));
+        assert!(!by_account
+            .subscription()
+            .matches(&other_account, &[contract_hash]));
+        assert!(by_contract
+            .subscription()
+            .matches(&other_account, &[contract_hash]));
+        assert!(!by_contract.subscription().matches(&account, &[]));
+    }
+
+    #[test]
+    fn should_deregister_dropped_subscriptions() {
+        let mut rng = crate::new_rng();
+        let account = PublicKey::random(&mut rng);
+
+        let registry = SubscriptionRegistry::default();
+        let subscription = registry.register(DeploySubscription::new(
+            iter_set(account.clone()),
+            BTreeSet::new(),
+        ));
+        assert!(registry
+            .subscribed_contracts(&account, &execution_result_touching(&[]))
+            .is_some());
+
+        drop(subscription);
+        assert_eq!(registry.len(), 0);
+        assert!(registry
+            .subscribed_contracts(&account, &execution_result_touching(&[]))
+            .is_none());
+    }
+
+    fn iter_set<T: Ord>(item: T) -> BTreeSet<T> {
+        std::iter::once(item).collect()
+    }
+}
@@ -27,8 +27,8 @@ use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
     DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    SSE_API_FINALIZED_DEPLOYS_PATH as FINALIZED_DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -264,7 +264,7 @@ impl TestFixture {
             ..Default::default()
         };
         let mut server = EventStreamServer::new(
-            config,
+            WithDir::new(self.storage_dir.path(), config),
             self.storage_dir.path().to_path_buf(),
             self.protocol_version,
         )
@@ -899,8 +899,8 @@ async fn should_handle_bad_url_path() {
     ];
 
     let expected_body = format!(
-        "invalid path: expected '/{0}/{1}', '/{0}/{2}' or '/{0}/{3}'",
-        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH
+        "invalid path: expected '/{0}/{1}', '/{0}/{2}', '/{0}/{3}' or '/{0}/{4}'",
+        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH, FINALIZED_DEPLOYS_PATH
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
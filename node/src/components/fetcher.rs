@@ -25,9 +25,10 @@ use crate::{
     },
     protocol::Message,
     types::{
-        Block, BlockAndDeploys, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockHeadersBatchId, BlockSignatures, BlockWithMetadata, Deploy, DeployHash,
-        DeployWithFinalizedApprovals, FinalizedApprovals, FinalizedApprovalsWithId, Item, NodeId,
+        Block, BlockAndDeploys, BlockBodyOrChunk, BlockBodyOrChunkId, BlockHash, BlockHeader,
+        BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockSignatures,
+        BlockWithMetadata, Deploy, DeployHash, DeployWithFinalizedApprovals, FinalizedApprovals,
+        FinalizedApprovalsWithId, Item, NodeId,
     },
     utils::Source,
     FetcherConfig, NodeRng,
@@ -710,6 +711,49 @@ impl ItemFetcher<BlockHeadersBatch> for Fetcher<BlockHeadersBatch> {
     }
 }
 
+impl ItemFetcher<BlockBodyOrChunk> for Fetcher<BlockBodyOrChunk> {
+    // The chunks can only be verified against the reassembled body, so a peer's response must not
+    // be passed on to requests sent to other peers.
+    const SAFE_TO_RESPOND_TO_ALL: bool = false;
+
+    fn responders(
+        &mut self,
+    ) -> &mut HashMap<BlockBodyOrChunkId, HashMap<NodeId, Vec<FetchResponder<BlockBodyOrChunk>>>>
+    {
+        &mut self.responders
+    }
+
+    fn metrics(&mut self) -> &Metrics {
+        &self.metrics
+    }
+
+    fn peer_timeout(&self) -> Duration {
+        self.get_from_peer_timeout
+    }
+
+    fn get_from_storage<REv: ReactorEventT<BlockBodyOrChunk>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: BlockBodyOrChunkId,
+        peer: NodeId,
+        responder: FetchResponder<BlockBodyOrChunk>,
+    ) -> Effects<Event<BlockBodyOrChunk>> {
+        effect_builder
+            .get_block_from_storage(*id.block_hash())
+            .event(move |maybe_block| {
+                let maybe_item = maybe_block.and_then(|block| {
+                    BlockBodyOrChunk::new(*block.hash(), block.body(), id.index())
+                });
+                Event::GetFromStorageResult {
+                    id,
+                    peer,
+                    maybe_item: Box::new(maybe_item),
+                    responder,
+                }
+            })
+    }
+}
+
 impl<T, REv> Component<REv> for Fetcher<T>
 where
     Fetcher<T>: ItemFetcher<T>,
@@ -12,17 +12,20 @@ use thiserror::Error;
 use super::*;
 use crate::{
     components::{
-        deploy_acceptor, in_memory_network::NetworkController, small_network::GossipedAddress,
+        deploy_acceptor, in_memory_network::NetworkController, small_network::PeerRecord,
         storage,
     },
     effect::{
-        announcements::DeployAcceptorAnnouncement,
+        announcements::{ControlAnnouncement, DeployAcceptorAnnouncement},
         incoming::{NetResponse, NetResponseIncoming},
         Responder,
     },
     fatal,
     protocol::Message,
-    reactor::{Reactor as ReactorTrait, Runner},
+    reactor::{
+        OverflowPolicy, QueueKind, Reactor as ReactorTrait, ReactorEvent as ReactorEventTrait,
+        Runner,
+    },
     testing,
     testing::{
         network::{Network, NetworkedReactor},
@@ -49,7 +52,6 @@ impl Drop for Reactor {
 
 #[derive(Debug)]
 pub struct FetcherTestConfig {
-    fetcher_config: Config,
     storage_config: storage::Config,
     temp_dir: TempDir,
 }
@@ -58,7 +60,6 @@ impl Default for FetcherTestConfig {
     fn default() -> Self {
         let (storage_config, temp_dir) = storage::Config::default_for_tests();
         FetcherTestConfig {
-            fetcher_config: Default::default(),
             storage_config,
             temp_dir,
         }
@@ -79,13 +80,15 @@ reactor!(Reactor {
             chainspec_loader.hard_reset_to_start_of_era(),
             chainspec_loader.chainspec().protocol_config.version,
             &chainspec_loader.chainspec().network_config.name,
-        );
+        ) needs [chainspec_loader];
+        #[queue(NetworkIncoming)]
         fake_deploy_acceptor = infallible FakeDeployAcceptor();
+        #[config(Config, env = "CL_FETCHER_TEST_CONFIG")]
         deploy_fetcher = Fetcher::<Deploy>(
             "deploy",
-            cfg.fetcher_config,
+            config,
             chainspec_loader.chainspec().highway_config.finality_threshold_fraction,
-            registry);
+            registry) needs [chainspec_loader];
     }
 
     events: {
@@ -120,10 +123,11 @@ reactor!(Reactor {
         NetResponseIncoming -> [fn handle_net_response];
 
         // There is no deploy gossiping going on.
+        #[queue(NetworkLowPriority, drop_oldest)]
         GossiperIncoming<Deploy> -> [!];
 
         // We are using an in-memory network, so we do not expect any gossiping of addresses.
-        GossiperIncoming<GossipedAddress> -> [!];
+        GossiperIncoming<PeerRecord> -> [!];
 
         // We do not serve any other requests.
         TrieRequestIncoming -> [!];
@@ -510,3 +514,20 @@ async fn should_timeout_fetch_from_peer() {
 
     NetworkController::<Message>::remove_active();
 }
+
+#[test]
+fn should_map_events_to_declared_queues() {
+    let mut rng = TestRng::new();
+
+    let event = ReactorEvent::FakeDeployAcceptor(deploy_acceptor::Event::Accept {
+        deploy: Box::new(Deploy::random_valid_native_transfer(&mut rng)),
+        source: Source::Client,
+        maybe_responder: None,
+    });
+    assert_eq!(event.queue_kind(), QueueKind::NetworkIncoming);
+    assert_eq!(event.overflow_policy(), OverflowPolicy::NeverDrop);
+
+    let event = ReactorEvent::from(ControlAnnouncement::DrainRequested);
+    assert_eq!(event.queue_kind(), QueueKind::Control);
+    assert_eq!(event.overflow_policy(), OverflowPolicy::NeverDrop);
+}
@@ -10,19 +10,22 @@ mod tests;
 use datasize::DataSize;
 use prometheus::Registry;
 use std::{
+    borrow::Cow,
     collections::HashSet,
     convert::Infallible,
     fmt::{self, Debug, Formatter},
     time::Duration,
 };
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
+
+use casper_types::Timestamp;
 
 use crate::{
     components::{fetcher::FetchedOrNotFound, Component},
     effect::{
         announcements::GossiperAnnouncement,
         incoming::GossiperIncoming,
-        requests::{BeginGossipRequest, NetworkRequest, StorageRequest},
+        requests::{BeginGossipRequest, NetworkRequest, StateStoreRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message as NodeMessage,
@@ -32,10 +35,13 @@ use crate::{
 };
 pub(crate) use config::Config;
 pub(crate) use event::Event;
-use gossip_table::{GossipAction, GossipTable};
+use gossip_table::{GossipAction, GossipTable, PersistedGossipTable};
 pub(crate) use message::Message;
 use metrics::Metrics;
 
+/// Interval at which a persisted gossip table is saved to storage.
+const SAVE_TABLE_INTERVAL: Duration = Duration::from_secs(5);
+
 /// A helper trait whose bounds represent the requirements for a reactor event that `Gossiper` can
 /// work with.
 pub(crate) trait ReactorEventT<T>:
@@ -109,6 +115,16 @@ where
         Box<dyn Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static>,
     #[data_size(skip)]
     metrics: Metrics,
+    /// Saves the gossip table to storage, if it is to be persisted.
+    #[allow(clippy::type_complexity)]
+    #[data_size(skip)]
+    save_table: Option<
+        Box<
+            dyn Fn(EffectBuilder<REv>, PersistedGossipTable<T::Id>) -> Effects<Event<T>>
+                + Send
+                + 'static,
+        >,
+    >,
 }
 
 impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
@@ -142,6 +158,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             get_from_peer_timeout: config.get_remainder_timeout().into(),
             get_from_holder: Box::new(get_from_holder),
             metrics: Metrics::new(name, registry)?,
+            save_table: None,
         })
     }
 
@@ -167,9 +184,33 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                 panic!("gossiper should never try to get {}", item)
             }),
             metrics: Metrics::new(name, registry)?,
+            save_table: None,
         })
     }
 
+    /// Persists the gossip table in the state store, so that after a restart the node neither
+    /// gossips again nor gets again the items it handled shortly before.
+    ///
+    /// `name` must be the name the gossiper was constructed with.  Returns the effects restoring
+    /// the table persisted before the restart, after which the table is saved periodically.
+    pub(crate) fn persist_table(
+        &mut self,
+        name: &str,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<StateStoreRequest>,
+    {
+        let key: Cow<'static, [u8]> = Cow::Owned(format!("{} table", name).into_bytes());
+        let load_key = key.clone();
+        self.save_table = Some(Box::new(move |effect_builder, table| {
+            effect_builder.save_state(key.clone(), table).ignore()
+        }));
+        effect_builder
+            .load_state(load_key)
+            .event(|maybe_table| Event::TableLoaded(Box::new(maybe_table)))
+    }
+
     /// Handles a new item received from a peer or client for which we should begin gossiping.
     ///
     /// Note that this doesn't include items gossiped to us; those are handled in `handle_gossip()`.
@@ -201,6 +242,55 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         }
     }
 
+    /// Restores the gossip table persisted before a restart, resuming the gossip of its current
+    /// items, and starts saving the table periodically.
+    fn handle_table_loaded(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        maybe_table: Option<PersistedGossipTable<T::Id>>,
+    ) -> Effects<Event<T>> {
+        let mut effects = effect_builder
+            .set_timeout(SAVE_TABLE_INTERVAL)
+            .event(|_| Event::SaveTable);
+        let table = match maybe_table {
+            Some(table) => table,
+            None => return effects,
+        };
+        let actions = self.table.restore(table, Timestamp::now());
+        info!(resumed = actions.len(), "restored persisted gossip table");
+        for (item_id, action) in actions {
+            match action {
+                GossipAction::ShouldGossip(should_gossip) => effects.extend(self.gossip(
+                    effect_builder,
+                    item_id,
+                    should_gossip.count,
+                    should_gossip.exclude_peers,
+                )),
+                GossipAction::GetRemainder { holder } => {
+                    effects.extend(self.get_remainder(effect_builder, item_id, holder))
+                }
+                GossipAction::AwaitingRemainder
+                | GossipAction::Noop
+                | GossipAction::AnnounceFinished => (),
+            }
+        }
+        effects
+    }
+
+    /// Saves the gossip table to storage and schedules the next save.
+    fn save_table(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>> {
+        let mut effects = effect_builder
+            .set_timeout(SAVE_TABLE_INTERVAL)
+            .event(|_| Event::SaveTable);
+        if let Some(save_table) = &self.save_table {
+            effects.extend(save_table(
+                effect_builder,
+                self.table.to_persisted(Timestamp::now()),
+            ));
+        }
+        effects
+    }
+
     /// Gossips the given item ID to `count` random peers excluding the indicated ones.
     fn gossip(
         &mut self,
@@ -302,28 +392,9 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             ),
 
             GossipAction::GetRemainder { holder } => {
-                // The previous peer failed to provide the item, so we still need to get it.  Send
-                // a `GetRequest` to a different holder and set a timeout to check we got the
-                // response.
-                let request = match NodeMessage::new_get_request::<T>(&item_id) {
-                    Ok(request) => request,
-                    Err(error) => {
-                        error!("failed to create get-request: {}", error);
-                        // Treat this as if the holder didn't respond - i.e. try to get from a
-                        // different holder.
-                        return self.check_get_from_peer_timeout(effect_builder, item_id, holder);
-                    }
-                };
-                let mut effects = effect_builder.send_message(holder, request).ignore();
-                effects.extend(
-                    effect_builder
-                        .set_timeout(self.get_from_peer_timeout)
-                        .event(move |_| Event::CheckGetFromPeerTimeout {
-                            item_id,
-                            peer: holder,
-                        }),
-                );
-                effects
+                // The previous peer failed to provide the item, so we still need to get it from a
+                // different holder.
+                self.get_remainder(effect_builder, item_id, holder)
             }
 
             GossipAction::AnnounceFinished => {
@@ -334,6 +405,35 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         }
     }
 
+    /// Sends a `GetRequest` for the full item to `holder` and sets a timeout to check we got the
+    /// response.
+    fn get_remainder(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        holder: NodeId,
+    ) -> Effects<Event<T>> {
+        let request = match NodeMessage::new_get_request::<T>(&item_id) {
+            Ok(request) => request,
+            Err(error) => {
+                error!("failed to create get-request: {}", error);
+                // Treat this as if the holder didn't respond - i.e. try to get from a different
+                // holder.
+                return self.check_get_from_peer_timeout(effect_builder, item_id, holder);
+            }
+        };
+        let mut effects = effect_builder.send_message(holder, request).ignore();
+        effects.extend(
+            effect_builder
+                .set_timeout(self.get_from_peer_timeout)
+                .event(move |_| Event::CheckGetFromPeerTimeout {
+                    item_id,
+                    peer: holder,
+                }),
+        );
+        effects
+    }
+
     /// Handles an incoming gossip request from a peer on the network.
     fn handle_gossip(
         &mut self,
@@ -341,6 +441,14 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         item_id: T::Id,
         sender: NodeId,
     ) -> Effects<Event<T>> {
+        // Where the ID is the complete item, we must not pass on an invalid one.
+        if T::ID_IS_COMPLETE_ITEM {
+            if let Err(error) = T::validate_id(&item_id) {
+                warn!(item=%item_id, %sender, %error, "received invalid gossip item");
+                return Effects::new();
+            }
+        }
+
         let action = if T::ID_IS_COMPLETE_ITEM {
             self.table.new_complete_data(&item_id, Some(sender))
         } else {
@@ -568,6 +676,10 @@ where
                 Ok(item) => self.got_from_holder(effect_builder, item, requester),
                 Err(error) => self.failed_to_get_from_holder(effect_builder, item_id, error),
             },
+            Event::TableLoaded(maybe_table) => {
+                self.handle_table_loaded(effect_builder, *maybe_table)
+            }
+            Event::SaveTable => self.save_table(effect_builder),
         };
         self.update_gossip_table_metrics();
         effects
@@ -6,7 +6,7 @@ use std::{
 use derive_more::From;
 use serde::Serialize;
 
-use super::Item;
+use super::{gossip_table::PersistedGossipTable, Item};
 use crate::{
     effect::{incoming::GossiperIncoming, requests::BeginGossipRequest},
     types::NodeId,
@@ -43,6 +43,10 @@ pub(crate) enum Event<T: Item> {
         requester: NodeId,
         result: Box<Result<T, String>>,
     },
+    /// The gossip table persisted before a restart has been loaded from storage.
+    TableLoaded(Box<Option<PersistedGossipTable<T::Id>>>),
+    /// The gossip table should be persisted to storage.
+    SaveTable,
 }
 
 impl<T: Item> Display for Event<T> {
@@ -88,6 +92,14 @@ impl<T: Item> Display for Event<T> {
                     write!(formatter, "failed to get {} from holder component", item_id)
                 }
             }
+            Event::TableLoaded(maybe_table) => {
+                if maybe_table.is_some() {
+                    write!(formatter, "loaded persisted gossip table")
+                } else {
+                    write!(formatter, "no persisted gossip table")
+                }
+            }
+            Event::SaveTable => write!(formatter, "save gossip table"),
         }
     }
 }
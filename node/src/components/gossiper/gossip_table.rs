@@ -10,8 +10,11 @@ use std::{
 use datasize::DataSize;
 #[cfg(test)]
 use fake_instant::FakeClock as Instant;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, warn};
 
+use casper_types::Timestamp;
+
 use super::Config;
 use crate::{types::NodeId, utils::DisplayIter};
 
@@ -154,6 +157,14 @@ impl<T> Timeouts<T> {
         self.values.push((timeout, data_id));
     }
 
+    /// Inserts a timeout which may be earlier than the latest one, keeping the values sorted.
+    fn insert(&mut self, timeout: Instant, data_id: T) {
+        let index = self
+            .values
+            .partition_point(|(existing, _data_id)| *existing <= timeout);
+        self.values.insert(index, (timeout, data_id));
+    }
+
     fn purge(&mut self, now: &Instant) -> impl Iterator<Item = T> + '_ {
         // The values are sorted by timeout.  Locate the index of the first non-expired one.
         let split_index = match self
@@ -171,6 +182,29 @@ impl<T> Timeouts<T> {
     }
 }
 
+/// The state of gossiping an item as persisted in the state store.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedState {
+    /// The peers excluding us which hold the data.
+    holders: HashSet<NodeId>,
+    /// Whether we hold the full data locally.
+    held_by_us: bool,
+    /// The subset of `holders` we have infected.
+    infected_by_us: HashSet<NodeId>,
+}
+
+/// The entries of a gossip table as persisted in the state store, so that a restarted node neither
+/// gossips again nor gets again the data it handled shortly before.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PersistedGossipTable<T> {
+    /// The time the entries were persisted.
+    persisted_at: Timestamp,
+    /// Data IDs for which gossiping is still ongoing.
+    current: Vec<(T, PersistedState)>,
+    /// Data IDs for which gossiping is complete, with the time until which they are to be kept.
+    finished: Vec<(T, Timestamp)>,
+}
+
 #[derive(DataSize, Debug)]
 pub(crate) struct GossipTable<T> {
     /// Data IDs for which gossiping is still ongoing.
@@ -453,6 +487,102 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         Some(action)
     }
 
+    /// Returns the entries of the table to be persisted, given the current time `now`.
+    ///
+    /// In-flight gossip messages are not persisted, as their responses are lost on restart.
+    pub(crate) fn to_persisted(&self, now: Timestamp) -> PersistedGossipTable<T> {
+        let current = self
+            .current
+            .iter()
+            .map(|(data_id, state)| {
+                let persisted_state = PersistedState {
+                    holders: state.holders.clone(),
+                    held_by_us: state.held_by_us,
+                    infected_by_us: state.infected_by_us.clone(),
+                };
+                (*data_id, persisted_state)
+            })
+            .collect();
+
+        let instant_now = Instant::now();
+        let finished = self
+            .timeouts
+            .values
+            .iter()
+            .filter(|(timeout, data_id)| *timeout > instant_now && self.finished.contains(data_id))
+            .map(|(timeout, data_id)| {
+                let remaining = timeout.duration_since(instant_now);
+                (*data_id, now.saturating_add(remaining.into()))
+            })
+            .collect();
+
+        PersistedGossipTable {
+            persisted_at: now,
+            current,
+            finished,
+        }
+    }
+
+    /// Restores the entries persisted before a restart at time `now`, other than those we learned
+    /// of since.  Current entries are dropped if persisted longer ago than the finished entry
+    /// duration, as their gossip would have finished by now.
+    ///
+    /// Returns the actions to take to resume gossiping the restored current entries.
+    pub(crate) fn restore(
+        &mut self,
+        persisted: PersistedGossipTable<T>,
+        now: Timestamp,
+    ) -> Vec<(T, GossipAction)> {
+        self.purge_finished();
+
+        let instant_now = Instant::now();
+        for (data_id, keep_until) in persisted.finished {
+            if keep_until <= now || self.is_known(&data_id) {
+                continue;
+            }
+            let _ = self.finished.insert(data_id);
+            let remaining = keep_until.saturating_diff(now);
+            self.timeouts
+                .insert(instant_now + Duration::from(remaining), data_id);
+        }
+
+        let mut actions = Vec::new();
+        if Duration::from(now.saturating_diff(persisted.persisted_at))
+            >= self.finished_entry_duration
+        {
+            return actions;
+        }
+        for (data_id, persisted_state) in persisted.current {
+            if self.is_known(&data_id)
+                || (!persisted_state.held_by_us && persisted_state.holders.is_empty())
+            {
+                continue;
+            }
+            let mut state = State {
+                holders: persisted_state.holders,
+                held_by_us: persisted_state.held_by_us,
+                infected_by_us: persisted_state.infected_by_us,
+                in_flight_count: 0,
+            };
+            if state.is_finished(self.infection_target, self.holders_limit) {
+                self.insert_to_finished(&data_id);
+                continue;
+            }
+            // Without the data, we have to get it again as if we'd just learned of it.
+            let is_new = !state.held_by_us;
+            let action = state.action(self.infection_target, self.holders_limit, is_new);
+            let _ = self.current.insert(data_id, state);
+            debug!(item=%data_id, %action, "restored persisted item");
+            actions.push((data_id, action));
+        }
+        actions
+    }
+
+    /// Returns whether the data is being gossiped or finished being gossiped.
+    fn is_known(&self, data_id: &T) -> bool {
+        self.current.contains_key(data_id) || self.finished.contains(data_id)
+    }
+
     fn insert_to_finished(&mut self, data_id: &T) {
         let timeout = Instant::now() + self.finished_entry_duration;
         let _ = self.finished.insert(*data_id);
@@ -978,6 +1108,77 @@ mod tests {
         assert!(!gossip_table.finished.contains(&data_id));
     }
 
+    #[test]
+    fn should_restore_persisted_table() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let finished_id: u64 = 1;
+        let held_id: u64 = 2;
+        let partial_id: u64 = 3;
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Finish gossiping one item, and start gossiping one we hold and one we don't.
+        let _ = gossip_table.new_complete_data(&finished_id, None);
+        assert!(gossip_table.force_finish(&finished_id));
+        let _ = gossip_table.new_complete_data(&held_id, Some(node_ids[0]));
+        let _ = gossip_table.we_infected(&held_id, node_ids[1]);
+        let _ = gossip_table.new_partial_data(&partial_id, node_ids[2]);
+
+        // Persist the table as the state store does, and restore it after a restart taking 10
+        // seconds.
+        let persisted_at = Timestamp::from(1_000_000);
+        let restored_at = persisted_at + TimeDiff::from(Duration::from_secs(10));
+        let serialized = bincode::serialize(&gossip_table.to_persisted(persisted_at)).unwrap();
+        let persisted = bincode::deserialize(&serialized).unwrap();
+        let mut restored_table = GossipTable::new(Config::default());
+        let mut actions = restored_table.restore(persisted, restored_at);
+        actions.sort_by_key(|(data_id, _action)| *data_id);
+
+        // Gossiping the held item resumes, excluding its known holders, and the partial item is
+        // got from its holder again.
+        let expected_actions = vec![
+            (
+                held_id,
+                GossipAction::ShouldGossip(ShouldGossip {
+                    count: EXPECTED_DEFAULT_INFECTION_TARGET - 1,
+                    exclude_peers: node_ids[..2].iter().copied().collect(),
+                    is_already_held: true,
+                }),
+            ),
+            (
+                partial_id,
+                GossipAction::GetRemainder {
+                    holder: node_ids[2],
+                },
+            ),
+        ];
+        assert_eq!(expected_actions, actions);
+        check_holders(&node_ids[..2], &restored_table, &held_id);
+        check_holders(&node_ids[2..3], &restored_table, &partial_id);
+
+        // The finished item is kept for the remainder of its duration only.
+        assert_eq!(
+            GossipAction::Noop,
+            restored_table.new_complete_data(&finished_id, None)
+        );
+        let millis = TimeDiff::from_str(DEFAULT_FINISHED_ENTRY_DURATION)
+            .unwrap()
+            .millis();
+        Instant::advance_time(millis - 10_000 + 1);
+        restored_table.purge_finished();
+        assert!(!restored_table.finished.contains(&finished_id));
+
+        // No entries are restored if persisted longer ago than the finished entry duration.
+        let mut late_table = GossipTable::new(Config::default());
+        let restored_late_at = persisted_at + TimeDiff::from(millis);
+        let persisted = bincode::deserialize(&serialized).unwrap();
+        assert!(late_table.restore(persisted, restored_late_at).is_empty());
+        assert!(!late_table.finished.contains(&finished_id));
+        assert!(late_table.current.is_empty());
+    }
+
     #[test]
     fn timeouts_purge_in_order() {
         let mut timeouts = Timeouts::new();
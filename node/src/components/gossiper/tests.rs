@@ -20,8 +20,9 @@ use tracing::debug;
 use casper_execution_engine::{
     core::engine_state::{
         engine_config::{
-            DEFAULT_MINIMUM_DELEGATION_AMOUNT, DEFAULT_STRICT_ARGUMENT_CHECKING,
-            DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS, DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP, DEFAULT_MINIMUM_DELEGATION_AMOUNT,
+            DEFAULT_STRICT_ARGUMENT_CHECKING, DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
         },
         DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
     },
@@ -36,7 +37,7 @@ use crate::{
         deploy_acceptor,
         fake_deploy_acceptor::FakeDeployAcceptor,
         in_memory_network::{self, InMemoryNetwork, NetworkController},
-        small_network::GossipedAddress,
+        small_network::PeerRecord,
         storage::{self, Storage},
     },
     effect::{
@@ -101,7 +102,7 @@ enum Event {
     #[from]
     DeployGossiperIncoming(GossiperIncoming<Deploy>),
     #[from]
-    AddressGossiperIncoming(GossiperIncoming<GossipedAddress>),
+    AddressGossiperIncoming(GossiperIncoming<PeerRecord>),
     #[from]
     NetRequestIncoming(NetRequestIncoming),
     #[from]
@@ -248,6 +249,9 @@ impl reactor::Reactor for Reactor {
             DEFAULT_MINIMUM_DELEGATION_AMOUNT,
             DEFAULT_STRICT_ARGUMENT_CHECKING,
             DEFAULT_VESTING_SCHEDULE_LENGTH_MILLIS,
+            DEFAULT_ALLOW_AUTHORIZATION_CONTRACTS,
+            DEFAULT_AUTHORIZATION_CONTRACT_GAS_LIMIT,
+            DEFAULT_MAX_UNBONDINGS_PER_STEP,
             registry,
         )
         .unwrap();
@@ -322,6 +326,9 @@ impl reactor::Reactor for Reactor {
                 };
                 self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
             }
+            Event::RpcServerAnnouncement(ann) => {
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(ann.into()))
+            }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::AcceptedNewDeploy {
                 deploy,
                 source,
@@ -410,7 +417,8 @@ impl reactor::Reactor for Reactor {
                 | NetResponse::BlockHeaderByHash(_)
                 | NetResponse::BlockHeaderAndFinalitySignaturesByHeight(_)
                 | NetResponse::BlockHeadersBatch(_)
-                | NetResponse::FinalitySignatures(_)) => {
+                | NetResponse::FinalitySignatures(_)
+                | NetResponse::BlockBodyOrChunk(_)) => {
                     fatal!(effect_builder, "unexpected net response: {:?}", other).ignore()
                 }
             },
@@ -0,0 +1,330 @@
+//! Indexer component.
+//!
+//! When enabled, the indexer writes every executed block, together with its deploys, transfers
+//! and auction events, to a normalized SQLite database which can be queried while the node is
+//! running.  The node doesn't link against SQLite: statements are piped to an `sqlite3` shell by a
+//! background thread, so that slow disk writes never hold up the reactor.  At most
+//! [`MAX_QUEUED_BATCHES`] batches of statements are queued for that thread; further ones are
+//! dropped.
+//!
+//! The shell is run with `-bail`, so it exits on the first failing statement.  Its error output
+//! and exit status are logged and reported in the indexer's health, but don't otherwise affect
+//! the node.
+
+mod config;
+mod sql;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{Child, ChildStderr, ChildStdin, Command, ExitStatus, Stdio},
+    sync::{
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use datasize::DataSize;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, error, warn};
+
+use casper_types::{EraId, ExecutionEffect, ExecutionResult};
+
+use super::{Component, HealthCheck};
+use crate::{
+    effect::{EffectBuilder, Effects},
+    types::{Block, DeployHash, DeployHeader, Health, NodeRng},
+    WithDir,
+};
+pub(crate) use config::Config;
+
+/// Maximum number of batches of statements queued for the writer thread.
+const MAX_QUEUED_BATCHES: usize = 1_000;
+
+/// What is known about the `sqlite3` shell, shared with the threads driving it.
+#[derive(Debug, Default)]
+struct ShellStatus {
+    /// The last error reported by the shell on its standard error, or hit while writing to it.
+    last_error: Option<String>,
+    /// The exit status of the shell, once it has exited.
+    exit_status: Option<ExitStatus>,
+}
+
+/// Indexer component.
+#[derive(Debug, DataSize)]
+pub(crate) struct Indexer {
+    /// Whether the indexer is enabled.
+    enabled: bool,
+    /// Sender of the statements to write, `None` if the indexer is disabled or has stopped.
+    #[data_size(skip)]
+    sender: Option<SyncSender<String>>,
+    /// Handle of the writer thread, joined on drop so that pending statements are written.
+    #[data_size(skip)]
+    writer: Option<JoinHandle<()>>,
+    /// Status of the `sqlite3` shell.
+    #[data_size(skip)]
+    shell_status: Arc<Mutex<ShellStatus>>,
+    /// Number of batches of statements dropped because the queue was full.
+    dropped_batches: u64,
+}
+
+/// An indexer initialization error.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error starting the `sqlite3` shell.
+    #[error("could not start {command} for {}: {source}", database_path.display())]
+    StartSqlite3 {
+        command: String,
+        database_path: PathBuf,
+        source: io::Error,
+    },
+    /// Error starting the writer thread or the thread reading the shell's error output.
+    #[error("could not start indexer thread: {0}")]
+    StartWriter(io::Error),
+}
+
+/// Indexer event.
+#[derive(Debug, Serialize)]
+pub(crate) enum Event {
+    /// A block has been executed.
+    BlockExecuted {
+        block: Box<Block>,
+        execution_results: Vec<(DeployHash, DeployHeader, ExecutionResult)>,
+    },
+    /// A step has been committed to global state.
+    StepCommitted {
+        era_id: EraId,
+        execution_effect: ExecutionEffect,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::BlockExecuted { block, .. } => {
+                write!(formatter, "index executed block {}", block.hash())
+            }
+            Event::StepCommitted { era_id, .. } => {
+                write!(formatter, "index committed step for {}", era_id)
+            }
+        }
+    }
+}
+
+impl Indexer {
+    /// Creates a new indexer, starting an `sqlite3` shell on the configured database if enabled.
+    pub(crate) fn new(cfg: &WithDir<Config>) -> Result<Self, Error> {
+        let config = cfg.value();
+        let shell_status = Arc::new(Mutex::new(ShellStatus::default()));
+        if !config.enabled {
+            debug!("indexer disabled");
+            return Ok(Indexer {
+                enabled: false,
+                sender: None,
+                writer: None,
+                shell_status,
+                dropped_batches: 0,
+            });
+        }
+
+        let database_path = cfg.with_dir(config.database_path.clone());
+        let mut child = Command::new(&config.sqlite3_command)
+            .arg("-bail")
+            .arg(&database_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| Error::StartSqlite3 {
+                command: config.sqlite3_command.clone(),
+                database_path: database_path.clone(),
+                source,
+            })?;
+        let stdin = child.stdin.take().expect("stdin should be piped");
+        let stderr = child.stderr.take().expect("stderr should be piped");
+
+        let watcher = {
+            let shell_status = Arc::clone(&shell_status);
+            thread::Builder::new()
+                .name("indexer-sqlite3".to_string())
+                .spawn(move || watch_shell(child, stderr, &shell_status))
+                .map_err(Error::StartWriter)?
+        };
+        let (sender, receiver) = mpsc::sync_channel(MAX_QUEUED_BATCHES);
+        let writer = {
+            let shell_status = Arc::clone(&shell_status);
+            thread::Builder::new()
+                .name("indexer".to_string())
+                .spawn(move || write_statements(stdin, receiver, watcher, &shell_status))
+                .map_err(Error::StartWriter)?
+        };
+        // The writer thread is running, so the schema is always queued first.
+        let _ = sender.send(sql::SCHEMA.to_string());
+
+        debug!(database_path = %database_path.display(), "indexer started");
+        Ok(Indexer {
+            enabled: true,
+            sender: Some(sender),
+            writer: Some(writer),
+            shell_status,
+            dropped_batches: 0,
+        })
+    }
+
+    /// Queues `statements` for writing, unless the indexer is disabled or has stopped.
+    ///
+    /// The statements are dropped if the queue is full, rather than blocking the reactor.
+    fn write(&mut self, statements: impl FnOnce() -> String) {
+        if let Some(sender) = &self.sender {
+            match sender.try_send(statements()) {
+                Ok(()) => (),
+                Err(TrySendError::Full(_)) => {
+                    self.dropped_batches += 1;
+                    warn!(
+                        dropped_batches = self.dropped_batches,
+                        "indexer queue is full, dropping statements"
+                    );
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    warn!("indexer thread has stopped, no further data will be indexed");
+                    self.sender = None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Indexer {
+    fn drop(&mut self) {
+        // Closing the channel makes the writer thread close the shell's input and wait for it.
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!("indexer thread panicked");
+            }
+        }
+    }
+}
+
+/// Pipes every batch of statements received to the `sqlite3` shell, until the channel is closed or
+/// the shell stops accepting input, then waits for the shell to exit.
+fn write_statements(
+    mut stdin: ChildStdin,
+    receiver: Receiver<String>,
+    watcher: JoinHandle<()>,
+    shell_status: &Mutex<ShellStatus>,
+) {
+    for statements in receiver {
+        if let Err(error) = stdin
+            .write_all(statements.as_bytes())
+            .and_then(|()| stdin.flush())
+        {
+            error!(%error, "could not write to sqlite3, stopping indexer");
+            if let Ok(mut shell_status) = shell_status.lock() {
+                shell_status
+                    .last_error
+                    .get_or_insert_with(|| format!("could not write to sqlite3: {}", error));
+            }
+            break;
+        }
+    }
+    // Closing the shell's input makes it exit once all statements are executed.
+    drop(stdin);
+    if watcher.join().is_err() {
+        error!("sqlite3 watcher thread panicked");
+    }
+}
+
+/// Logs every line the `sqlite3` shell writes to its standard error, keeping the last one, then
+/// records the shell's exit status.
+fn watch_shell(mut child: Child, stderr: ChildStderr, shell_status: &Mutex<ShellStatus>) {
+    for line in BufReader::new(stderr).lines() {
+        match line {
+            Ok(line) => {
+                error!(%line, "sqlite3 reported an error");
+                if let Ok(mut shell_status) = shell_status.lock() {
+                    shell_status.last_error = Some(line);
+                }
+            }
+            Err(error) => {
+                warn!(%error, "could not read sqlite3 error output");
+                break;
+            }
+        }
+    }
+    match child.wait() {
+        Ok(status) => {
+            if status.success() {
+                debug!("sqlite3 exited");
+            } else {
+                error!(%status, "sqlite3 exited unsuccessfully");
+            }
+            if let Ok(mut shell_status) = shell_status.lock() {
+                shell_status.exit_status = Some(status);
+            }
+        }
+        Err(error) => warn!(%error, "could not wait for sqlite3 to exit"),
+    }
+}
+
+impl HealthCheck for Indexer {
+    const NAME: &'static str = "indexer";
+
+    fn health(&self) -> Health {
+        let mut health = Health::ready();
+        if !self.enabled {
+            return health;
+        }
+        if self.sender.is_none() {
+            health.degrade("indexer has stopped");
+        }
+        if self.dropped_batches > 0 {
+            health.degrade(format!(
+                "{} batches of statements dropped as the indexer fell behind",
+                self.dropped_batches
+            ));
+        }
+        match self.shell_status.lock() {
+            Ok(shell_status) => {
+                if let Some(error) = &shell_status.last_error {
+                    health.degrade(format!("sqlite3 error: {}", error));
+                }
+                if let Some(exit_status) = shell_status.exit_status {
+                    health.degrade(format!("sqlite3 exited: {}", exit_status));
+                }
+            }
+            Err(_) => health.degrade("indexer status lock poisoned"),
+        }
+        health
+    }
+}
+
+impl<REv> Component<REv> for Indexer {
+    type Event = Event;
+
+    type ConstructionError = Error;
+
+    fn handle_event(
+        &mut self,
+        _effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Event,
+    ) -> Effects<Event> {
+        match event {
+            Event::BlockExecuted {
+                block,
+                execution_results,
+            } => self.write(|| sql::block_statements(&block, &execution_results)),
+            Event::StepCommitted {
+                era_id,
+                execution_effect,
+            } => self.write(|| sql::step_statements(era_id, &execution_effect)),
+        }
+        Effects::new()
+    }
+}
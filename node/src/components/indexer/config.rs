@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Default path of the SQLite database, relative to the config file.
+const DEFAULT_DATABASE_PATH: &str = "index.sqlite3";
+
+/// Default `sqlite3` command-line shell.
+const DEFAULT_SQLITE3_COMMAND: &str = "sqlite3";
+
+/// Indexer configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields, default)]
+pub(crate) struct Config {
+    /// Whether or not the indexer is enabled.
+    pub(crate) enabled: bool,
+    /// Path of the SQLite database, relative to the config file.
+    pub(crate) database_path: PathBuf,
+    /// The `sqlite3` command-line shell used to write to the database.
+    pub(crate) sqlite3_command: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enabled: false,
+            database_path: DEFAULT_DATABASE_PATH.into(),
+            sqlite3_command: DEFAULT_SQLITE3_COMMAND.to_string(),
+        }
+    }
+}
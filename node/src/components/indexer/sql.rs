@@ -0,0 +1,394 @@
+//! Rendering of indexed data as SQLite statements.
+//!
+//! Every block and step is written in its own transaction.  Rows derived from a block are deleted
+//! before being re-inserted, so writing the same block twice leaves the database unchanged.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use casper_types::{
+    system::auction::{Bid, EraInfo, SeigniorageAllocation, UnbondingPurse},
+    AsymmetricType, EraId, ExecutionEffect, ExecutionResult, PublicKey, Transform, U512,
+};
+
+use crate::types::{Block, DeployHash, DeployHeader};
+
+/// The schema of the database, applied when the indexer starts.
+pub(super) const SCHEMA: &str = "\
+PRAGMA journal_mode = WAL;
+CREATE TABLE IF NOT EXISTS blocks (
+    hash TEXT PRIMARY KEY NOT NULL,
+    height INTEGER NOT NULL,
+    era_id INTEGER NOT NULL,
+    timestamp TEXT NOT NULL,
+    proposer TEXT NOT NULL,
+    state_root_hash TEXT NOT NULL,
+    is_switch_block INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS blocks_height ON blocks (height);
+CREATE TABLE IF NOT EXISTS deploys (
+    hash TEXT PRIMARY KEY NOT NULL,
+    block_hash TEXT NOT NULL REFERENCES blocks (hash),
+    account TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    is_transfer INTEGER NOT NULL,
+    cost TEXT NOT NULL,
+    error_message TEXT
+);
+CREATE INDEX IF NOT EXISTS deploys_block_hash ON deploys (block_hash);
+CREATE INDEX IF NOT EXISTS deploys_account ON deploys (account);
+CREATE TABLE IF NOT EXISTS transfers (
+    block_hash TEXT NOT NULL REFERENCES blocks (hash),
+    deploy_hash TEXT NOT NULL REFERENCES deploys (hash),
+    from_account TEXT NOT NULL,
+    to_account TEXT,
+    source_purse TEXT NOT NULL,
+    target_purse TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    transfer_id TEXT
+);
+CREATE INDEX IF NOT EXISTS transfers_block_hash ON transfers (block_hash);
+CREATE INDEX IF NOT EXISTS transfers_from_account ON transfers (from_account);
+CREATE INDEX IF NOT EXISTS transfers_to_account ON transfers (to_account);
+CREATE TABLE IF NOT EXISTS auction_events (
+    block_hash TEXT REFERENCES blocks (hash),
+    deploy_hash TEXT REFERENCES deploys (hash),
+    era_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    validator TEXT NOT NULL,
+    delegator TEXT,
+    amount TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS auction_events_block_hash ON auction_events (block_hash);
+CREATE INDEX IF NOT EXISTS auction_events_era_id ON auction_events (era_id);
+CREATE INDEX IF NOT EXISTS auction_events_validator ON auction_events (validator);
+CREATE TABLE IF NOT EXISTS value_writes (
+    block_hash TEXT NOT NULL REFERENCES blocks (hash),
+    deploy_hash TEXT NOT NULL REFERENCES deploys (hash),
+    key TEXT NOT NULL,
+    cl_type TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS value_writes_block_hash ON value_writes (block_hash);
+CREATE INDEX IF NOT EXISTS value_writes_key ON value_writes (key);
+";
+
+/// A value bound to a column.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Value {
+    Null,
+    Integer(u64),
+    Text(String),
+}
+
+impl Value {
+    fn text<T: ToString>(value: T) -> Self {
+        Value::Text(value.to_string())
+    }
+
+    fn hex<T: AsRef<[u8]>>(bytes: T) -> Self {
+        Value::Text(base16::encode_lower(&bytes))
+    }
+
+    fn public_key(public_key: &PublicKey) -> Self {
+        Value::Text(public_key.to_hex())
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => formatter.write_str("NULL"),
+            Value::Integer(value) => write!(formatter, "{}", value),
+            Value::Text(value) => write!(formatter, "'{}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Integer(value.into())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// A sequence of statements making up a single transaction.
+struct Transaction(String);
+
+impl Transaction {
+    fn begin() -> Self {
+        Transaction("BEGIN;\n".to_string())
+    }
+
+    fn push(&mut self, statement: fmt::Arguments) {
+        // Writing to a `String` cannot fail.
+        let _ = self.0.write_fmt(statement);
+        self.0.push_str(";\n");
+    }
+
+    fn insert(&mut self, verb: &str, table: &str, columns: &[&str], values: &[Value]) {
+        let values = values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.push(format_args!(
+            "{} INTO {} ({}) VALUES ({})",
+            verb,
+            table,
+            columns.join(", "),
+            values
+        ));
+    }
+
+    fn insert_auction_event(
+        &mut self,
+        block_hash: &Value,
+        deploy_hash: &Value,
+        era_id: EraId,
+        event: AuctionEvent,
+    ) {
+        self.insert(
+            "INSERT",
+            "auction_events",
+            &[
+                "block_hash",
+                "deploy_hash",
+                "era_id",
+                "kind",
+                "validator",
+                "delegator",
+                "amount",
+            ],
+            &[
+                block_hash.clone(),
+                deploy_hash.clone(),
+                Value::Integer(era_id.value()),
+                Value::text(event.kind),
+                Value::public_key(event.validator),
+                event.delegator.map(Value::public_key).into(),
+                Value::text(event.amount),
+            ],
+        );
+    }
+
+    fn commit(mut self) -> String {
+        self.0.push_str("COMMIT;\n");
+        self.0
+    }
+}
+
+/// A change to the auction state, derived from a single transform.
+struct AuctionEvent<'a> {
+    kind: &'static str,
+    validator: &'a PublicKey,
+    delegator: Option<&'a PublicKey>,
+    amount: &'a U512,
+}
+
+/// Returns the auction events recorded by `transform`.
+fn auction_events(transform: &Transform) -> Vec<AuctionEvent> {
+    match transform {
+        Transform::WriteBid(bid) => bid_events(bid),
+        Transform::WriteWithdraw(purses) => purses.iter().map(unbonding_event).collect(),
+        Transform::WriteEraInfo(era_info) => reward_events(era_info),
+        _ => Vec::new(),
+    }
+}
+
+fn bid_events(bid: &Bid) -> Vec<AuctionEvent> {
+    let validator = bid.validator_public_key();
+    let mut events = vec![AuctionEvent {
+        kind: "bid",
+        validator,
+        delegator: None,
+        amount: bid.staked_amount(),
+    }];
+    events.extend(bid.delegators().values().map(|delegator| AuctionEvent {
+        kind: "delegation",
+        validator,
+        delegator: Some(delegator.delegator_public_key()),
+        amount: delegator.staked_amount(),
+    }));
+    events
+}
+
+fn unbonding_event(purse: &UnbondingPurse) -> AuctionEvent {
+    AuctionEvent {
+        kind: "unbonding",
+        validator: purse.validator_public_key(),
+        delegator: (!purse.is_validator()).then(|| purse.unbonder_public_key()),
+        amount: purse.amount(),
+    }
+}
+
+fn reward_events(era_info: &EraInfo) -> Vec<AuctionEvent> {
+    era_info
+        .seigniorage_allocations()
+        .iter()
+        .map(|allocation| match allocation {
+            SeigniorageAllocation::Validator {
+                validator_public_key,
+                amount,
+            } => AuctionEvent {
+                kind: "reward",
+                validator: validator_public_key,
+                delegator: None,
+                amount,
+            },
+            SeigniorageAllocation::Delegator {
+                delegator_public_key,
+                validator_public_key,
+                amount,
+            } => AuctionEvent {
+                kind: "reward",
+                validator: validator_public_key,
+                delegator: Some(delegator_public_key),
+                amount,
+            },
+        })
+        .collect()
+}
+
+/// Renders the transaction recording `block` and the results of executing its deploys.
+pub(super) fn block_statements(
+    block: &Block,
+    execution_results: &[(DeployHash, DeployHeader, ExecutionResult)],
+) -> String {
+    let header = block.header();
+    let block_hash = Value::hex(block.hash().inner());
+    let era_id = header.era_id();
+
+    let mut transaction = Transaction::begin();
+    for table in ["value_writes", "auction_events", "transfers", "deploys"] {
+        transaction.push(format_args!(
+            "DELETE FROM {} WHERE block_hash = {}",
+            table, block_hash
+        ));
+    }
+    transaction.insert(
+        "INSERT OR REPLACE",
+        "blocks",
+        &[
+            "hash",
+            "height",
+            "era_id",
+            "timestamp",
+            "proposer",
+            "state_root_hash",
+            "is_switch_block",
+        ],
+        &[
+            block_hash.clone(),
+            Value::Integer(block.height()),
+            Value::Integer(era_id.value()),
+            Value::text(header.timestamp()),
+            Value::public_key(block.body().proposer()),
+            Value::hex(header.state_root_hash()),
+            header.is_switch_block().into(),
+        ],
+    );
+
+    for (deploy_hash, deploy_header, execution_result) in execution_results {
+        let (effect, cost, error_message) = match execution_result {
+            ExecutionResult::Failure {
+                effect,
+                cost,
+                error_message,
+                ..
+            } => (effect, cost, Some(Value::text(error_message))),
+            ExecutionResult::Success { effect, cost, .. } => (effect, cost, None),
+        };
+        let deploy_hash_value = Value::hex(deploy_hash.inner());
+        transaction.insert(
+            "INSERT OR REPLACE",
+            "deploys",
+            &[
+                "hash",
+                "block_hash",
+                "account",
+                "timestamp",
+                "is_transfer",
+                "cost",
+                "error_message",
+            ],
+            &[
+                deploy_hash_value.clone(),
+                block_hash.clone(),
+                Value::public_key(deploy_header.account()),
+                Value::text(deploy_header.timestamp()),
+                block.transfer_hashes().contains(deploy_hash).into(),
+                Value::text(cost),
+                error_message.into(),
+            ],
+        );
+
+        for entry in &effect.transforms {
+            if let Transform::WriteCLValue(cl_value) = &entry.transform {
+                let tree = cl_value.inspect();
+                transaction.insert(
+                    "INSERT",
+                    "value_writes",
+                    &["block_hash", "deploy_hash", "key", "cl_type", "value"],
+                    &[
+                        block_hash.clone(),
+                        deploy_hash_value.clone(),
+                        Value::text(&entry.key),
+                        Value::text(tree.cl_type()),
+                        Value::text(tree.to_compact_string()),
+                    ],
+                );
+            }
+            if let Transform::WriteTransfer(transfer) = &entry.transform {
+                transaction.insert(
+                    "INSERT",
+                    "transfers",
+                    &[
+                        "block_hash",
+                        "deploy_hash",
+                        "from_account",
+                        "to_account",
+                        "source_purse",
+                        "target_purse",
+                        "amount",
+                        "transfer_id",
+                    ],
+                    &[
+                        block_hash.clone(),
+                        Value::hex(transfer.deploy_hash.value()),
+                        Value::hex(transfer.from.value()),
+                        transfer.to.map(|to| Value::hex(to.value())).into(),
+                        Value::text(transfer.source.to_formatted_string()),
+                        Value::text(transfer.target.to_formatted_string()),
+                        Value::text(transfer.amount),
+                        transfer.id.map(Value::text).into(),
+                    ],
+                );
+            }
+            for event in auction_events(&entry.transform) {
+                transaction.insert_auction_event(&block_hash, &deploy_hash_value, era_id, event);
+            }
+        }
+    }
+
+    transaction.commit()
+}
+
+/// Renders the transaction recording the auction events of the step which ended `era_id`.
+pub(super) fn step_statements(era_id: EraId, execution_effect: &ExecutionEffect) -> String {
+    let mut transaction = Transaction::begin();
+    transaction.push(format_args!(
+        "DELETE FROM auction_events WHERE block_hash IS NULL AND era_id = {}",
+        era_id.value()
+    ));
+    for entry in &execution_effect.transforms {
+        for event in auction_events(&entry.transform) {
+            transaction.insert_auction_event(&Value::Null, &Value::Null, era_id, event);
+        }
+    }
+    transaction.commit()
+}
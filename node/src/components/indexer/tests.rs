@@ -0,0 +1,276 @@
+use std::{
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use casper_types::{
+    account::AccountHash,
+    system::auction::{Bid, Delegator, EraInfo, SeigniorageAllocation},
+    testing::TestRng,
+    AccessRights, AsymmetricType, CLValue, PublicKey, Transfer, Transform, TransformEntry, URef,
+    U512,
+};
+
+use super::{
+    sql::{self, Value},
+    *,
+};
+use crate::types::{Deploy, HealthStatus};
+
+/// Returns an execution result for `deploy_hash` which transfers 10 motes, writes a bid and writes
+/// a named value.
+fn execution_result(
+    deploy_hash: &DeployHash,
+    validator: &PublicKey,
+    delegator: &PublicKey,
+) -> ExecutionResult {
+    let purse = URef::new([1; 32], AccessRights::READ_ADD_WRITE);
+    let transfer = Transfer::new(
+        casper_types::DeployHash::new(deploy_hash.inner().value()),
+        AccountHash::new([2; 32]),
+        Some(AccountHash::new([3; 32])),
+        purse,
+        URef::new([4; 32], AccessRights::READ_ADD_WRITE),
+        U512::from(10),
+        U512::zero(),
+        Some(7),
+        None,
+    );
+    let mut bid = Bid::unlocked(validator.clone(), purse, U512::from(1_000), 5);
+    bid.delegators_mut().insert(
+        delegator.clone(),
+        Delegator::unlocked(delegator.clone(), U512::from(200), purse, validator.clone()),
+    );
+
+    ExecutionResult::Success {
+        effect: ExecutionEffect {
+            operations: Vec::new(),
+            transforms: vec![
+                TransformEntry {
+                    key: "transfer".to_string(),
+                    transform: Transform::WriteTransfer(transfer),
+                },
+                TransformEntry {
+                    key: "bid".to_string(),
+                    transform: Transform::WriteBid(Box::new(bid)),
+                },
+                TransformEntry {
+                    key: "value".to_string(),
+                    transform: Transform::WriteCLValue(
+                        CLValue::from_t((String::from("it's"), vec![1_u64, 2])).unwrap(),
+                    ),
+                },
+            ],
+        },
+        transfers: Vec::new(),
+        cost: U512::from(100),
+    }
+}
+
+/// Returns a step effect rewarding `validator` and `delegator`.
+fn step_effect(validator: &PublicKey, delegator: &PublicKey) -> ExecutionEffect {
+    let mut era_info = EraInfo::new();
+    era_info
+        .seigniorage_allocations_mut()
+        .push(SeigniorageAllocation::validator(
+            validator.clone(),
+            U512::from(30),
+        ));
+    era_info
+        .seigniorage_allocations_mut()
+        .push(SeigniorageAllocation::delegator(
+            delegator.clone(),
+            validator.clone(),
+            U512::from(3),
+        ));
+    ExecutionEffect {
+        operations: Vec::new(),
+        transforms: vec![TransformEntry {
+            key: "era-info".to_string(),
+            transform: Transform::WriteEraInfo(era_info),
+        }],
+    }
+}
+
+fn query(database_path: &Path, query: &str) -> String {
+    let output = Command::new("sqlite3")
+        .arg(database_path)
+        .arg(query)
+        .output()
+        .expect("should run sqlite3");
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).expect("should be utf-8")
+}
+
+#[test]
+fn should_escape_text_values() {
+    assert_eq!(Value::Text("it's".to_string()).to_string(), "'it''s'");
+    assert_eq!(Value::Integer(42).to_string(), "42");
+    assert_eq!(Value::Null.to_string(), "NULL");
+    assert_eq!(Value::from(None::<bool>).to_string(), "NULL");
+    assert_eq!(Value::from(true).to_string(), "1");
+}
+
+#[test]
+fn should_render_block_as_single_transaction() {
+    let mut rng = TestRng::new();
+    let block = Block::random(&mut rng);
+    let deploy = Deploy::random(&mut rng);
+    let validator = PublicKey::random(&mut rng);
+    let delegator = PublicKey::random(&mut rng);
+    let execution_results = vec![(
+        *deploy.id(),
+        deploy.header().clone(),
+        execution_result(deploy.id(), &validator, &delegator),
+    )];
+
+    let statements = sql::block_statements(&block, &execution_results);
+    assert!(statements.starts_with("BEGIN;\n"));
+    assert!(statements.ends_with("COMMIT;\n"));
+
+    let block_hash = base16::encode_lower(block.hash().inner());
+    assert!(statements.contains(&format!(
+        "DELETE FROM deploys WHERE block_hash = '{}'",
+        block_hash
+    )));
+    assert_eq!(
+        statements.matches("INSERT OR REPLACE INTO blocks").count(),
+        1
+    );
+    assert_eq!(
+        statements.matches("INSERT OR REPLACE INTO deploys").count(),
+        1
+    );
+    assert_eq!(statements.matches("INSERT INTO transfers").count(), 1);
+    assert_eq!(statements.matches("INSERT INTO value_writes").count(), 1);
+    // One event for the bid and one for its delegator.
+    assert_eq!(statements.matches("INSERT INTO auction_events").count(), 2);
+    assert!(statements.contains(&format!(
+        "'delegation', '{}', '{}'",
+        validator.to_hex(),
+        delegator.to_hex()
+    )));
+}
+
+#[test]
+fn should_write_to_database() {
+    if Command::new("sqlite3").arg("-version").output().is_err() {
+        // The indexer can't be exercised without an `sqlite3` shell on the path.
+        return;
+    }
+
+    let mut rng = TestRng::new();
+    let tempdir = tempfile::tempdir().expect("should create tempdir");
+    let config = Config {
+        enabled: true,
+        ..Config::default()
+    };
+    let database_path = tempdir.path().join(&config.database_path);
+    let mut indexer =
+        Indexer::new(&WithDir::new(tempdir.path(), config)).expect("should start indexer");
+
+    let block = Block::random(&mut rng);
+    let deploy = Deploy::random(&mut rng);
+    let validator = PublicKey::random(&mut rng);
+    let delegator = PublicKey::random(&mut rng);
+    let execution_results = vec![(
+        *deploy.id(),
+        deploy.header().clone(),
+        execution_result(deploy.id(), &validator, &delegator),
+    )];
+
+    // Writing the same block twice shouldn't duplicate any rows.
+    for _ in 0..2 {
+        indexer.write(|| sql::block_statements(&block, &execution_results));
+    }
+    indexer.write(|| {
+        sql::step_statements(
+            block.header().era_id(),
+            &step_effect(&validator, &delegator),
+        )
+    });
+    // Dropping the indexer waits for all statements to be written.
+    drop(indexer);
+
+    assert_eq!(
+        query(&database_path, "SELECT hash, height FROM blocks"),
+        format!(
+            "{}|{}\n",
+            base16::encode_lower(block.hash().inner()),
+            block.height()
+        )
+    );
+    assert_eq!(
+        query(&database_path, "SELECT hash, cost FROM deploys"),
+        format!("{}|100\n", base16::encode_lower(deploy.id().inner()))
+    );
+    assert_eq!(
+        query(&database_path, "SELECT amount, transfer_id FROM transfers"),
+        "10|7\n"
+    );
+    assert_eq!(
+        query(
+            &database_path,
+            "SELECT kind, amount FROM auction_events ORDER BY block_hash IS NULL, kind, amount"
+        ),
+        "bid|1000\ndelegation|200\nreward|3\nreward|30\n"
+    );
+    assert_eq!(
+        query(
+            &database_path,
+            "SELECT key, cl_type, value FROM value_writes"
+        ),
+        "value|(String, List<U64>)|(\"it's\", [1, 2])\n"
+    );
+}
+
+#[test]
+fn should_report_failing_statements_in_health() {
+    if Command::new("sqlite3").arg("-version").output().is_err() {
+        // The indexer can't be exercised without an `sqlite3` shell on the path.
+        return;
+    }
+
+    let tempdir = tempfile::tempdir().expect("should create tempdir");
+    let config = Config {
+        enabled: true,
+        ..Config::default()
+    };
+    let mut indexer =
+        Indexer::new(&WithDir::new(tempdir.path(), config)).expect("should start indexer");
+    assert_eq!(indexer.health().status, HealthStatus::Ready);
+
+    // The shell bails out on the failing statement, which is reported once it has exited.
+    indexer.write(|| "INSERT INTO no_such_table VALUES (1);\n".to_string());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while indexer
+        .shell_status
+        .lock()
+        .expect("should lock")
+        .exit_status
+        .is_none()
+    {
+        assert!(Instant::now() < deadline, "sqlite3 should have exited");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let health = indexer.health();
+    assert_eq!(health.status, HealthStatus::Degraded);
+    assert!(
+        health
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("no such table")),
+        "{:?}",
+        health
+    );
+    assert!(
+        health
+            .reasons
+            .iter()
+            .any(|reason| reason.starts_with("sqlite3 exited")),
+        "{:?}",
+        health
+    );
+}
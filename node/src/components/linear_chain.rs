@@ -31,12 +31,14 @@ use crate::{
     effect::{
         announcements::LinearChainAnnouncement,
         requests::{
-            ChainspecLoaderRequest, ContractRuntimeRequest, NetworkRequest, StorageRequest,
+            ChainspecLoaderRequest, ContractRuntimeRequest, LinearChainRequest, NetworkRequest,
+            StorageRequest,
         },
         EffectBuilder, EffectExt, EffectResultExt, Effects,
     },
     protocol::Message,
     types::{ActivationPoint, BlockHeader},
+    utils::clock,
     NodeRng,
 };
 pub(crate) use error::{BlockSignatureError, Error};
@@ -103,10 +105,8 @@ where
                 .put_signatures_to_storage(block_signatures)
                 .events(move |_| should_upgrade.then(|| Event::Upgrade).into_iter()),
             Outcome::StoreBlock(block, execution_results) => async move {
-                let block_hash = *block.hash();
-                effect_builder.put_block_to_storage(block.clone()).await;
                 effect_builder
-                    .put_execution_results_to_storage(block_hash, execution_results)
+                    .put_executed_block_to_storage(block.clone(), execution_results)
                     .await;
                 block
             }
@@ -212,6 +212,38 @@ where
                     .got_upgrade_activation_point(activation_point);
                 Effects::new()
             }
+            Event::Request(LinearChainRequest::RebroadcastFinalitySignatures {
+                block_hash,
+                responder,
+            }) => {
+                if !self
+                    .linear_chain_state
+                    .try_start_rebroadcast(block_hash, clock::now())
+                {
+                    return responder.respond(None).ignore();
+                }
+                effect_builder
+                    .get_signatures_from_storage(block_hash)
+                    .event(move |maybe_signatures| Event::GotSignaturesToRebroadcast {
+                        block_hash,
+                        maybe_signatures: maybe_signatures.map(Box::new),
+                        responder,
+                    })
+            }
+            Event::GotSignaturesToRebroadcast {
+                block_hash,
+                maybe_signatures,
+                responder,
+            } => {
+                let outcomes = self
+                    .linear_chain_state
+                    .handle_rebroadcast(block_hash, maybe_signatures);
+                let signature_count = outcomes.len();
+                info!(%block_hash, signature_count, "rebroadcasting finality signatures");
+                let mut effects = outcomes_to_effects(effect_builder, outcomes);
+                effects.extend(responder.respond(Some(signature_count)).ignore());
+                effects
+            }
         }
     }
 }
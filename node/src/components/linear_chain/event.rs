@@ -6,8 +6,8 @@ use std::{
 use casper_types::ExecutionResult;
 
 use crate::{
-    effect::incoming::FinalitySignatureIncoming,
-    types::{ActivationPoint, Block, BlockSignatures, DeployHash, FinalitySignature},
+    effect::{incoming::FinalitySignatureIncoming, requests::LinearChainRequest, Responder},
+    types::{ActivationPoint, Block, BlockHash, BlockSignatures, DeployHash, FinalitySignature},
 };
 
 #[derive(Debug)]
@@ -35,6 +35,24 @@ pub(crate) enum Event {
     Upgrade,
     /// Got the result of checking for an upgrade activation point.
     GotUpgradeActivationPoint(ActivationPoint),
+    /// An incoming request.
+    Request(LinearChainRequest),
+    /// The result of requesting the finality signatures of a block from storage to rebroadcast
+    /// them.
+    GotSignaturesToRebroadcast {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The stored signatures, if any.
+        maybe_signatures: Option<Box<BlockSignatures>>,
+        /// Responder to call with the number of signatures broadcast.
+        responder: Responder<Option<usize>>,
+    },
+}
+
+impl From<LinearChainRequest> for Event {
+    fn from(request: LinearChainRequest) -> Self {
+        Event::Request(request)
+    }
 }
 
 impl From<FinalitySignatureIncoming> for Event {
@@ -76,6 +94,17 @@ impl Display for Event {
                 "linear chain got upgrade activation point {}",
                 activation_point
             ),
+            Event::Request(request) => write!(f, "linear chain request: {}", request),
+            Event::GotSignaturesToRebroadcast {
+                block_hash,
+                maybe_signatures,
+                ..
+            } => write!(
+                f,
+                "linear chain got signatures to rebroadcast for {}, found: {}",
+                block_hash,
+                maybe_signatures.is_some()
+            ),
         }
     }
 }
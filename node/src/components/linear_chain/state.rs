@@ -6,7 +6,7 @@ use num::rational::Ratio;
 use tracing::{debug, warn};
 
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion};
+use casper_types::{EraId, ExecutionResult, ProtocolVersion, TimeDiff, Timestamp};
 
 use super::{
     pending_signatures::PendingSignatures, signature::Signature, signature_cache::SignatureCache,
@@ -19,6 +19,12 @@ use crate::{
     types::{ActivationPoint, Block, BlockHash, BlockSignatures, DeployHash, FinalitySignature},
 };
 
+/// The minimum time between two rebroadcasts of the finality signatures of the same block.
+const MIN_REBROADCAST_INTERVAL: TimeDiff = TimeDiff::from_seconds(30);
+/// The maximum number of blocks whose finality signatures are rebroadcast within
+/// `MIN_REBROADCAST_INTERVAL`.
+const MAX_REBROADCASTS_PER_INTERVAL: usize = 10;
+
 #[derive(DataSize, Debug)]
 pub(crate) struct LinearChain {
     /// The most recently added block.
@@ -38,6 +44,8 @@ pub(crate) struct LinearChain {
     /// The next upgrade activation point. When the key block for this era is fully signed, the
     /// linear chain component indicates that the node should restart for an upgrade.
     next_upgrade_activation_point: Option<ActivationPoint>,
+    /// The times at which the finality signatures of recent blocks were last rebroadcast.
+    last_rebroadcasts: HashMap<BlockHash, Timestamp>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -85,6 +93,7 @@ impl LinearChain {
             unbonding_delay,
             next_upgrade_activation_point,
             finality_threshold_fraction,
+            last_rebroadcasts: HashMap::new(),
         }
     }
 
@@ -329,6 +338,58 @@ impl LinearChain {
         }
     }
 
+    /// Returns whether the finality signatures of `block_hash` may be rebroadcast at `now`, and if
+    /// so, records the rebroadcast.
+    ///
+    /// Each block's signatures are rebroadcast at most once per `MIN_REBROADCAST_INTERVAL`, and
+    /// only for up to `MAX_REBROADCASTS_PER_INTERVAL` blocks within that interval.
+    pub(super) fn try_start_rebroadcast(&mut self, block_hash: BlockHash, now: Timestamp) -> bool {
+        self.last_rebroadcasts
+            .retain(|_, last| now.saturating_diff(*last) < MIN_REBROADCAST_INTERVAL);
+        if self.last_rebroadcasts.contains_key(&block_hash)
+            || self.last_rebroadcasts.len() >= MAX_REBROADCASTS_PER_INTERVAL
+        {
+            debug!(%block_hash, "rate limiting rebroadcast of finality signatures");
+            return false;
+        }
+        self.last_rebroadcasts.insert(block_hash, now);
+        true
+    }
+
+    /// Returns the outcomes to broadcast every known finality signature of `block_hash`, i.e. the
+    /// `stored` ones together with any cached ones not stored yet.
+    pub(super) fn handle_rebroadcast(
+        &self,
+        block_hash: BlockHash,
+        stored: Option<Box<BlockSignatures>>,
+    ) -> Outcomes {
+        let mut signatures = stored.map(|signatures| *signatures);
+        if let Some(cached) = self.get_signatures(&block_hash) {
+            match signatures.as_mut() {
+                Some(signatures) => signatures.proofs.extend(cached.proofs),
+                None => signatures = Some(cached),
+            }
+        }
+        signatures
+            .into_iter()
+            .flat_map(|signatures| {
+                let BlockSignatures {
+                    block_hash,
+                    era_id,
+                    proofs,
+                } = signatures;
+                proofs.into_iter().map(move |(public_key, signature)| {
+                    Outcome::Gossip(Box::new(FinalitySignature {
+                        block_hash,
+                        era_id,
+                        signature,
+                        public_key,
+                    }))
+                })
+            })
+            .collect()
+    }
+
     pub(super) fn handle_cached_signatures(
         &mut self,
         signatures: Option<Box<BlockSignatures>>,
@@ -981,4 +1042,67 @@ mod tests {
             outcomes,
         );
     }
+
+    #[test]
+    fn rate_limit_rebroadcasts() {
+        let mut rng = TestRng::new();
+        let protocol_version = ProtocolVersion::V1_0_0;
+        let mut lc = LinearChain::new(protocol_version, 1u64, 1u64, Ratio::new(1, 3), None);
+        let now = Timestamp::random(&mut rng);
+        let block_hash = BlockHash::random(&mut rng);
+
+        // The same block's signatures are only rebroadcast once per interval.
+        assert!(lc.try_start_rebroadcast(block_hash, now));
+        assert!(!lc.try_start_rebroadcast(block_hash, now));
+        let almost_expired = now + (MIN_REBROADCAST_INTERVAL - TimeDiff::from(1));
+        assert!(!lc.try_start_rebroadcast(block_hash, almost_expired));
+        let expired = now + MIN_REBROADCAST_INTERVAL;
+        assert!(lc.try_start_rebroadcast(block_hash, expired));
+
+        // Only a limited number of blocks' signatures are rebroadcast per interval.
+        for _ in 1..MAX_REBROADCASTS_PER_INTERVAL {
+            assert!(lc.try_start_rebroadcast(BlockHash::random(&mut rng), expired));
+        }
+        assert!(!lc.try_start_rebroadcast(BlockHash::random(&mut rng), expired));
+        assert!(lc.try_start_rebroadcast(
+            BlockHash::random(&mut rng),
+            expired + MIN_REBROADCAST_INTERVAL
+        ));
+    }
+
+    #[test]
+    fn rebroadcast_stored_and_cached_signatures() {
+        let mut rng = TestRng::new();
+        let protocol_version = ProtocolVersion::V1_0_0;
+        let mut lc = LinearChain::new(protocol_version, 1u64, 1u64, Ratio::new(1, 3), None);
+        let block = Block::random(&mut rng);
+        let block_hash = *block.hash();
+        let era_id = block.header().era_id();
+        let signatures: Vec<_> = iter::repeat_with(|| {
+            Box::new(FinalitySignature::random_for_block(
+                block_hash,
+                era_id.value(),
+            ))
+        })
+        .take(3)
+        .collect();
+
+        // Nothing is known about the block yet.
+        assert!(lc.handle_rebroadcast(block_hash, None).is_empty());
+
+        // The first signature is stored, the other two are cached.
+        let mut stored = BlockSignatures::new(block_hash, era_id);
+        stored.insert_proof(signatures[0].public_key.clone(), signatures[0].signature);
+        let mut cached = BlockSignatures::new(block_hash, era_id);
+        for fs in &signatures[1..] {
+            cached.insert_proof(fs.public_key.clone(), fs.signature);
+        }
+        lc.cache_signatures(cached);
+
+        let expected = signatures.into_iter().map(Outcome::Gossip).collect();
+        assert_equal(
+            expected,
+            lc.handle_rebroadcast(block_hash, Some(Box::new(stored))),
+        );
+    }
 }
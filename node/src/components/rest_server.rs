@@ -12,9 +12,11 @@
 //! reactor, and an external facing http server that exposes various uri routes and converts
 //! HTTP requests into the appropriate component events.
 //!
-//! Currently this component supports two endpoints, each of which takes no arguments:
+//! Currently this component supports the following endpoints, each of which takes no arguments:
 //! /status : a human readable JSON equivalent of the info-get-status rpc method.
 //!     example: curl -X GET 'http://<ip>:8888/status'
+//! /signed-status : the status, signed by the node's network identity and validator key.
+//!     example: curl -X GET 'http://<ip>:8888/signed-status'
 //! /metrics : time series data collected from the internals of the node being queried.
 //!     example: curl -X GET 'http://<ip>:8888/metrics'
 
@@ -37,14 +39,14 @@ use crate::{
     components::rpc_server::rpcs::docs::OPEN_RPC_SCHEMA,
     effect::{
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, MetricsRequest, NetworkInfoRequest,
-            NodeStateRequest, RestRequest, StorageRequest,
+            ChainspecLoaderRequest, ConsensusRequest, HealthRequest, MetricsRequest,
+            NetworkInfoRequest, NodeStateRequest, RestRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
     reactor::Finalize,
     types::StatusFeed,
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
 pub use config::Config;
@@ -60,6 +62,7 @@ pub(crate) trait ReactorEventT:
     + From<ConsensusRequest>
     + From<MetricsRequest>
     + From<NodeStateRequest>
+    + From<HealthRequest>
     + Send
 {
 }
@@ -73,6 +76,7 @@ impl<REv> ReactorEventT for REv where
         + From<ConsensusRequest>
         + From<MetricsRequest>
         + From<NodeStateRequest>
+        + From<HealthRequest>
         + Send
         + 'static
 {
@@ -98,7 +102,7 @@ pub(crate) struct RestServer {
 
 impl RestServer {
     pub(crate) fn new<REv>(
-        config: Config,
+        config: WithDir<Config>,
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
         node_startup_instant: Instant,
@@ -106,19 +110,24 @@ impl RestServer {
     where
         REv: ReactorEventT,
     {
-        if !config.enable_server {
+        if !config.value().enable_server {
             return Ok(RestServer { inner_rest: None });
         }
 
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-        let builder = utils::start_listening(&config.address)?;
+        let incoming = utils::start_listening(
+            &config.value().address,
+            WithDir::new(config.dir(), &config.value().tls),
+        )?;
+        let cors = config.value().cors.to_builder()?;
         let server_join_handle = Some(tokio::spawn(http_server::run(
-            builder,
+            incoming,
             effect_builder,
             api_version,
             shutdown_receiver,
-            config.qps_limit,
+            config.value().qps_limit,
+            cors,
         )));
 
         Ok(RestServer {
@@ -161,12 +170,14 @@ where
                         chainspec_info,
                         consensus_status,
                         node_state,
+                        health,
                     ) = join!(
                         effect_builder.get_highest_block_from_storage(),
                         effect_builder.network_peers(),
                         effect_builder.get_chainspec_info(),
                         effect_builder.consensus_status(),
-                        effect_builder.get_node_state()
+                        effect_builder.get_node_state(),
+                        effect_builder.get_health_report()
                     );
 
                     let status_feed = StatusFeed::new(
@@ -176,6 +187,7 @@ where
                         consensus_status,
                         node_uptime,
                         node_state,
+                        health,
                     );
                     responder.respond(status_feed).await;
                 }
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::http_server::{CorsConfig, TlsConfig};
+
 /// Default binding address for the REST HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -21,6 +23,14 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// TLS settings.
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// CORS policy.
+    #[serde(default)]
+    pub cors: CorsConfig,
 }
 
 impl Config {
@@ -30,6 +40,8 @@ impl Config {
             enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            tls: TlsConfig::default(),
+            cors: CorsConfig::default(),
         }
     }
 }
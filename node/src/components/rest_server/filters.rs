@@ -1,4 +1,4 @@
-use futures::FutureExt;
+use futures::{future, FutureExt};
 use http::Response;
 use hyper::Body;
 use tracing::warn;
@@ -10,19 +10,22 @@ use warp::{
     Filter,
 };
 
-use casper_types::ProtocolVersion;
+use casper_types::{ProtocolVersion, Timestamp};
 
 use super::ReactorEventT;
 use crate::{
     effect::{requests::RestRequest, EffectBuilder},
     reactor::QueueKind,
     rpcs::info::{GetChainspecResult, GetValidatorChangesResult},
-    types::GetStatusResult,
+    types::{GetSignedStatusResult, GetStatusResult, SignedStatusPayload},
 };
 
 /// The status URL path.
 pub const STATUS_API_PATH: &str = "status";
 
+/// The signed status URL path.
+pub const SIGNED_STATUS_API_PATH: &str = "signed-status";
+
 /// The metrics URL path.
 pub const METRICS_API_PATH: &str = "metrics";
 
@@ -55,6 +58,54 @@ pub(super) fn create_status_filter<REv: ReactorEventT>(
         .boxed()
 }
 
+pub(super) fn create_signed_status_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(SIGNED_STATUS_API_PATH))
+        .and_then(move || async move {
+            let status_feed = effect_builder
+                .make_request(
+                    |responder| RestRequest::Status { responder },
+                    QueueKind::Api,
+                )
+                .await;
+            let payload = SignedStatusPayload {
+                timestamp: Timestamp::now(),
+                status: GetStatusResult::new(status_feed, api_version),
+            };
+            let payload = match serde_json::to_string(&payload) {
+                Ok(payload) => payload,
+                Err(error) => {
+                    warn!(%error, "failed to encode status for signing");
+                    return Ok::<_, Rejection>(
+                        reply::with_status(
+                            "failed to encode status",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                        .into_response(),
+                    );
+                }
+            };
+            match effect_builder
+                .attest_with_node_identity(payload.as_bytes().to_vec())
+                .await
+            {
+                Some(attestation) => {
+                    let body = GetSignedStatusResult::new(payload, attestation);
+                    Ok(reply::json(&body).into_response())
+                }
+                None => Ok(reply::with_status(
+                    "failed to sign status",
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+                .into_response()),
+            }
+        })
+        .boxed()
+}
+
 pub(super) fn create_metrics_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
 ) -> BoxedFilter<(Response<Body>,)> {
@@ -125,12 +176,15 @@ pub(super) fn create_chainspec_filter<REv: ReactorEventT>(
     warp::get()
         .and(warp::path(CHAINSPEC_API_PATH))
         .and_then(move || {
-            effect_builder
-                .get_chainspec_raw_bytes()
-                .map(move |chainspec_bytes| {
-                    let result = GetChainspecResult::new(api_version, (*chainspec_bytes).clone());
-                    Ok::<_, Rejection>(reply::json(&result).into_response())
-                })
+            future::join(
+                effect_builder.get_chainspec_raw_bytes(),
+                effect_builder.get_chainspec(),
+            )
+            .map(move |(chainspec_bytes, chainspec)| {
+                let result =
+                    GetChainspecResult::new(api_version, (*chainspec_bytes).clone(), &chainspec);
+                Ok::<_, Rejection>(reply::json(&result).into_response())
+            })
         })
         .boxed()
 }
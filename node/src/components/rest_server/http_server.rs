@@ -1,7 +1,7 @@
 use std::{convert::Infallible, time::Duration};
 
 use futures::{future, TryFutureExt};
-use hyper::server::{conn::AddrIncoming, Builder};
+use hyper::server::Server;
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
 use tracing::{info, warn};
@@ -10,20 +10,22 @@ use warp::Filter;
 use casper_types::ProtocolVersion;
 
 use super::{filters, ReactorEventT};
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils::http_server::HttpIncoming};
 
 /// Run the REST HTTP server.
 ///
 /// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    cors: warp::cors::Builder,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
+    let rest_signed_status = filters::create_signed_status_filter(effect_builder, api_version);
     let rest_metrics = filters::create_metrics_filter(effect_builder);
     let rest_open_rpc = filters::create_rpc_schema_filter(effect_builder);
     let rest_validator_changes =
@@ -32,11 +34,12 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     let service = warp::service(
         rest_status
+            .or(rest_signed_status)
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
             .or(rest_chainspec_filter)
-            .with(warp::cors().allow_any_origin()),
+            .with(cors),
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
@@ -47,8 +50,8 @@ pub(super) async fn run<REv: ReactorEventT>(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(rate_limited_service);
-    info!(address = %server.local_addr(), "started REST server");
+    info!(address = %incoming.local_addr(), "started REST server");
+    let server = Server::builder(incoming).serve(rate_limited_service);
 
     // Shutdown the server gracefully.
     let _ = server
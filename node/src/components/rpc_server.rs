@@ -17,14 +17,18 @@
 mod config;
 mod event;
 mod http_server;
+mod metrics;
+mod request_limiter;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
 
-use std::{convert::Infallible, fmt::Debug, time::Instant};
+use std::{convert::Infallible, fmt::Debug, sync::Arc, time::Instant};
 
 use datasize::DataSize;
 use futures::join;
+use prometheus::Registry;
+use thiserror::Error;
 use tracing::error;
 
 use casper_execution_engine::core::engine_state::{
@@ -34,7 +38,7 @@ use casper_execution_engine::core::engine_state::{
 use casper_hashing::Digest;
 use casper_types::{system::auction::EraValidators, ExecutionResult, Key, ProtocolVersion, URef};
 
-use self::rpcs::chain::BlockIdentifier;
+use self::{request_limiter::RequestLimiter, rpcs::chain::BlockIdentifier};
 use super::Component;
 use crate::{
     components::contract_runtime::EraValidatorsRequest,
@@ -42,16 +46,17 @@ use crate::{
     effect::{
         announcements::RpcServerAnnouncement,
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, MetricsRequest,
-            NetworkInfoRequest, NodeStateRequest, RpcRequest, StorageRequest,
+            BlockProposerRequest, ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            HealthRequest, LinearChainRequest, MetricsRequest, NetworkInfoRequest,
+            NodeStateRequest, RpcRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{BlockHeader, Deploy, StatusFeed},
-    utils::{self, ListeningError},
+    utils::{self, ListeningError, WithDir},
     NodeRng,
 };
-pub use config::Config;
+pub use config::{Config, RequestLimitsConfig};
 pub(crate) use event::Event;
 pub use speculative_exec_config::Config as SpeculativeExecConfig;
 
@@ -63,10 +68,13 @@ pub(crate) trait ReactorEventT:
     + From<ChainspecLoaderRequest>
     + From<ContractRuntimeRequest>
     + From<ConsensusRequest>
+    + From<LinearChainRequest>
     + From<MetricsRequest>
     + From<NetworkInfoRequest>
     + From<StorageRequest>
     + From<NodeStateRequest>
+    + From<HealthRequest>
+    + From<BlockProposerRequest>
     + Send
 {
 }
@@ -78,15 +86,29 @@ impl<REv> ReactorEventT for REv where
         + From<ChainspecLoaderRequest>
         + From<ContractRuntimeRequest>
         + From<ConsensusRequest>
+        + From<LinearChainRequest>
         + From<MetricsRequest>
         + From<NetworkInfoRequest>
         + From<StorageRequest>
         + From<NodeStateRequest>
+        + From<HealthRequest>
+        + From<BlockProposerRequest>
         + Send
         + 'static
 {
 }
 
+/// An error constructing the JSON-RPC servers.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Failed to start listening on one of the servers' addresses.
+    #[error(transparent)]
+    Listening(#[from] ListeningError),
+    /// Failed to register the servers' metrics.
+    #[error("prometheus (metrics) error: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
 #[derive(DataSize, Debug)]
 pub(crate) struct InnerRpcServer {
     /// The instant at which the node has started.
@@ -114,24 +136,40 @@ pub(crate) struct RpcServer {
 
 impl RpcServer {
     pub(crate) fn new<REv>(
-        config: Config,
-        speculative_exec_config: SpeculativeExecConfig,
+        config: WithDir<Config>,
+        speculative_exec_config: WithDir<SpeculativeExecConfig>,
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
         node_startup_instant: Instant,
-    ) -> Result<Self, ListeningError>
+        registry: &Registry,
+    ) -> Result<Self, Error>
     where
         REv: ReactorEventT,
     {
         // Set the speculative execution HTTP server up first. The speculative
         // execution server can operate independently from the JSON-RPC server,
         // so we save its state before we construct the `RpcServer`.
-        let speculative_exec = if speculative_exec_config.enable_server {
-            let builder = utils::start_listening(&speculative_exec_config.address)?;
+        let speculative_exec = if speculative_exec_config.value().enable_server {
+            let incoming = utils::start_listening(
+                &speculative_exec_config.value().address,
+                WithDir::new(
+                    speculative_exec_config.dir(),
+                    &speculative_exec_config.value().tls,
+                ),
+            )?;
+            let (_, speculative_exec_config) = speculative_exec_config.into_parts();
+            let cors = speculative_exec_config.cors.to_builder()?;
+            let limiter = RequestLimiter::new(
+                &speculative_exec_config.request_limits,
+                "speculative_exec_server",
+                registry,
+            )?;
             tokio::spawn(speculative_exec_server::run(
-                builder,
+                incoming,
                 effect_builder,
                 api_version,
+                Arc::new(limiter),
+                cors,
                 speculative_exec_config.qps_limit,
                 speculative_exec_config.max_body_bytes,
             ));
@@ -140,20 +178,29 @@ impl RpcServer {
             None
         };
 
-        if !config.enable_server {
+        if !config.value().enable_server {
             return Ok(RpcServer {
                 inner_rpc: None,
                 speculative_exec,
             });
         }
 
-        let builder = utils::start_listening(&config.address)?;
+        let incoming = utils::start_listening(
+            &config.value().address,
+            WithDir::new(config.dir(), &config.value().tls),
+        )?;
+        let (_, config) = config.into_parts();
+        let cors = config.cors.to_builder()?;
+        let limiter = RequestLimiter::new(&config.request_limits, "rpc_server", registry)?;
         tokio::spawn(http_server::run(
-            builder,
+            incoming,
             effect_builder,
             api_version,
+            Arc::new(limiter),
+            cors,
             config.qps_limit,
             config.max_body_bytes,
+            config.enable_operator_rpcs,
         ));
 
         let inner_rpc = Some(InnerRpcServer {
@@ -228,6 +275,8 @@ impl RpcServer {
             let execution_prestate = SpeculativeExecutionState {
                 state_root_hash: *block_header.state_root_hash(),
                 block_time: block_header.timestamp(),
+                block_height: block_header.height(),
+                era_id: block_header.era_id(),
                 protocol_version: block_header.protocol_version(),
             };
             let result = effect_builder
@@ -252,15 +301,14 @@ where
         _rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
-        // Handle the special case where we need to route the request to
+        // Handle the special cases where we need to route the request to
         // the speculative execution JSON-RPC server.
-        if let Event::RpcRequest(RpcRequest::SpeculativeDeployExecute {
-            block_header,
-            deploy,
-            responder,
-        }) = event
-        {
-            match self.speculative_exec {
+        let event = match event {
+            Event::RpcRequest(RpcRequest::SpeculativeDeployExecute {
+                block_header,
+                deploy,
+                responder,
+            }) => match self.speculative_exec {
                 Some(_) => {
                     return self.handle_execute_deploy(
                         effect_builder,
@@ -272,10 +320,27 @@ where
                 None => {
                     return Effects::new();
                 }
-            }
-        }
+            },
+            Event::RpcRequest(RpcRequest::CallView {
+                view_request,
+                responder,
+            }) => match self.speculative_exec {
+                Some(_) => {
+                    return async move {
+                        responder
+                            .respond(effect_builder.call_view(*view_request).await)
+                            .await
+                    }
+                    .ignore();
+                }
+                None => {
+                    return Effects::new();
+                }
+            },
+            event => event,
+        };
 
-        // For all requests other than `SpeculativeDeployExecute`, we return
+        // For all requests other than `SpeculativeDeployExecute` and `CallView`, we return
         // empty effects if the JSON-RPC server is disabled.
         let rpc_server = match &self.inner_rpc {
             Some(rpc_server) => rpc_server,
@@ -288,6 +353,18 @@ where
             Event::RpcRequest(RpcRequest::SubmitDeploy { deploy, responder }) => effect_builder
                 .announce_deploy_received(deploy, Some(responder))
                 .ignore(),
+            Event::RpcRequest(RpcRequest::SubmitPartialDeploy { deploy, responder }) => {
+                effect_builder
+                    .announce_partial_deploy_received(deploy, responder)
+                    .ignore()
+            }
+            Event::RpcRequest(RpcRequest::AddDeployApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            }) => effect_builder
+                .announce_deploy_approvals_received(deploy_hash, approvals, responder)
+                .ignore(),
             Event::RpcRequest(RpcRequest::GetBlock {
                 maybe_id: Some(BlockIdentifier::Hash(hash)),
                 only_from_available_block_range,
@@ -399,12 +476,20 @@ where
             Event::RpcRequest(RpcRequest::GetStatus { responder }) => {
                 let node_uptime = rpc_server.node_startup_instant().elapsed();
                 async move {
-                    let (last_added_block, peers, chainspec_info, consensus_status, node_state) = join!(
+                    let (
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        node_state,
+                        health,
+                    ) = join!(
                         effect_builder.get_highest_block_from_storage(),
                         effect_builder.network_peers(),
                         effect_builder.get_chainspec_info(),
                         effect_builder.consensus_status(),
-                        effect_builder.get_node_state()
+                        effect_builder.get_node_state(),
+                        effect_builder.get_health_report()
                     );
                     let status_feed = StatusFeed::new(
                         last_added_block,
@@ -413,6 +498,7 @@ where
                         consensus_status,
                         node_uptime,
                         node_state,
+                        health,
                     );
                     responder.respond(status_feed).await;
                 }
@@ -428,7 +514,17 @@ where
                     .await
             }
             .ignore(),
-            Event::RpcRequest(RpcRequest::SpeculativeDeployExecute { .. }) => {
+            Event::RpcRequest(RpcRequest::GetWasmConfig {
+                protocol_version,
+                responder,
+            }) => async move {
+                responder
+                    .respond(effect_builder.get_wasm_config(protocol_version).await)
+                    .await
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::SpeculativeDeployExecute { .. })
+            | Event::RpcRequest(RpcRequest::CallView { .. }) => {
                 // Handled above by the speculative execution JSON-RPC server.
                 error!(
                     "Received spurious speculative exec event in JSON-RPC server, \
@@ -1,6 +1,10 @@
+use std::collections::BTreeMap;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::http_server::{CorsConfig, TlsConfig};
+
 /// Default binding address for the JSON-RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -24,6 +28,19 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Setting to enable RPCs exposing the node's internals to its operator, such as
+    /// `info_get_pending_deploys`.
+    #[serde(default)]
+    pub enable_operator_rpcs: bool,
+    /// Additional limits applied to incoming requests.
+    #[serde(default)]
+    pub request_limits: RequestLimitsConfig,
+    /// TLS settings.
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// CORS policy.
+    #[serde(default)]
+    pub cors: CorsConfig,
 }
 
 impl Config {
@@ -34,6 +51,10 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            enable_operator_rpcs: false,
+            request_limits: RequestLimitsConfig::default(),
+            tls: TlsConfig::default(),
+            cors: CorsConfig::default(),
         }
     }
 }
@@ -43,3 +64,18 @@ impl Default for Config {
         Config::new()
     }
 }
+
+/// Limits applied to requests on top of the server-wide `qps_limit`.
+///
+/// All limits are disabled by default.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(default, deny_unknown_fields)]
+pub struct RequestLimitsConfig {
+    /// Maximum rate limit in queries per second for a single client IP address.
+    pub qps_limit_per_ip: Option<u64>,
+    /// Maximum number of requests being processed concurrently.
+    pub max_in_flight: Option<usize>,
+    /// Maximum number of requests being processed concurrently, keyed by JSON-RPC method name.
+    pub max_in_flight_per_method: BTreeMap<String, usize>,
+}
@@ -1,23 +1,30 @@
-use hyper::server::{conn::AddrIncoming, Builder};
+use std::sync::Arc;
 
 use casper_json_rpc::RequestHandlersBuilder;
 use casper_types::ProtocolVersion;
 
 use super::{
+    request_limiter::RequestLimiter,
     rpcs::{
-        account::PutDeploy,
-        chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
+        account::{AddDeployApprovals, PutDeploy, PutPartialDeploy},
+        chain::{
+            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetLeaderSchedule,
+            GetLightClientProof, GetStateRootHash, GetWasmConfig, RebroadcastFinalitySignatures,
+        },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetChainspec, GetDeploy, GetEvidence, GetPeers, GetPendingDeploys, GetStatus,
+            GetValidatorChanges,
+        },
         state::{
             GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            QueryBalance, QueryBalanceDetails, QueryGlobalState,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
     ReactorEventT,
 };
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils::http_server::HttpIncoming};
 
 /// The URL path for all JSON-RPC requests.
 pub const RPC_API_PATH: &str = "rpc";
@@ -26,37 +33,69 @@ pub const RPC_API_SERVER_NAME: &str = "JSON RPC";
 
 /// Run the JSON-RPC server.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
+    limiter: Arc<RequestLimiter>,
+    cors: warp::cors::Builder,
     qps_limit: u64,
     max_body_bytes: u32,
+    enable_operator_rpcs: bool,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    PutDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    PutPartialDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    AddDeployApprovals::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBlock::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBlockTransfers::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStateRootHash::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetItem::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    QueryGlobalState::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBalance::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetAccountInfo::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetPeers::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStatus::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetEraInfoBySwitchBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        &limiter,
+        &mut handlers,
+    );
+    GetLightClientProof::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    RebroadcastFinalitySignatures::register_as_handler(
+        effect_builder,
+        api_version,
+        &limiter,
+        &mut handlers,
+    );
+    GetLeaderSchedule::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetWasmConfig::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetEraSummary::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetAuctionInfo::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetTrie::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetValidatorChanges::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetEvidence::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    ListRpcs::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetDictionaryItem::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetChainspec::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    QueryBalance::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    QueryBalanceDetails::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    if enable_operator_rpcs {
+        GetPendingDeploys::register_as_handler(
+            effect_builder,
+            api_version,
+            &limiter,
+            &mut handlers,
+        );
+    }
     let handlers = handlers.build();
 
     super::rpcs::run(
-        builder,
+        incoming,
         handlers,
+        limiter,
+        cors,
         qps_limit,
         max_body_bytes,
         RPC_API_PATH,
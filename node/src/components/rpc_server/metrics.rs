@@ -0,0 +1,63 @@
+use prometheus::{IntCounter, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics for the request limits of a JSON-RPC server.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of requests rejected due to the per-IP rate limit.
+    pub(super) throttled_by_ip: IntCounter,
+    /// Number of requests rejected due to the global in-flight limit.
+    pub(super) throttled_in_flight: IntCounter,
+    /// Number of requests rejected due to a per-method in-flight limit.
+    pub(super) throttled_by_method: IntCounter,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates a new instance of request limit metrics, using the given prefix.
+    pub(super) fn new(name: &str, registry: &Registry) -> Result<Self, prometheus::Error> {
+        let throttled_by_ip = IntCounter::new(
+            format!("{}_throttled_by_ip", name),
+            format!(
+                "number of requests to the {} rejected due to the per-ip rate limit",
+                name
+            ),
+        )?;
+        let throttled_in_flight = IntCounter::new(
+            format!("{}_throttled_in_flight", name),
+            format!(
+                "number of requests to the {} rejected due to too many requests in flight",
+                name
+            ),
+        )?;
+        let throttled_by_method = IntCounter::new(
+            format!("{}_throttled_by_method", name),
+            format!(
+                "number of requests to the {} rejected due to too many requests in flight for \
+                the same method",
+                name
+            ),
+        )?;
+
+        registry.register(Box::new(throttled_by_ip.clone()))?;
+        registry.register(Box::new(throttled_in_flight.clone()))?;
+        registry.register(Box::new(throttled_by_method.clone()))?;
+
+        Ok(Metrics {
+            throttled_by_ip,
+            throttled_in_flight,
+            throttled_by_method,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.throttled_by_ip);
+        unregister_metric!(self.registry, self.throttled_in_flight);
+        unregister_metric!(self.registry, self.throttled_by_method);
+    }
+}
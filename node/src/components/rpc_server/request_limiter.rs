@@ -0,0 +1,272 @@
+//! Limits on the requests accepted by a JSON-RPC server.
+//!
+//! Complements the server-wide `qps_limit` with a rate limit per client IP address, a cap on the
+//! number of requests processed concurrently, and caps on the number of concurrently processed
+//! requests per JSON-RPC method.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use prometheus::Registry;
+use thiserror::Error;
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+use warp::reject::Reject;
+
+use super::{config::RequestLimitsConfig, metrics::Metrics};
+
+/// The window over which requests per IP address are counted.
+const IP_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// The reason a request was rejected by the [`RequestLimiter`].
+#[derive(Debug, Error)]
+pub(super) enum Throttled {
+    /// The client exceeded its rate limit.
+    #[error("rate limit of {limit} requests per second exceeded for {ip}")]
+    IpRateLimit { ip: IpAddr, limit: u64 },
+    /// The server is processing too many requests already.
+    #[error("too many requests in flight (limit: {limit})")]
+    TooManyInFlight { limit: usize },
+    /// The server is processing too many requests for the same method already.
+    #[error("too many requests in flight for method '{method}' (limit: {limit})")]
+    MethodInFlight { method: &'static str, limit: usize },
+}
+
+impl Reject for Throttled {}
+
+/// Per-IP request counts within the current window.
+#[derive(Debug)]
+struct IpWindow {
+    /// The instant the current window started.
+    started: Instant,
+    /// Number of requests per IP address seen during the current window.
+    counts: HashMap<IpAddr, u64>,
+}
+
+/// An in-flight limit, enforced through a semaphore.
+#[derive(Debug)]
+struct InFlightLimit {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl InFlightLimit {
+    fn new(limit: usize) -> Self {
+        InFlightLimit {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+        }
+    }
+
+    /// Tries to acquire a permit, returning `None` if the limit has been reached.
+    fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.semaphore).try_acquire_owned().ok()
+    }
+}
+
+/// Enforces the request limits of a single JSON-RPC server.
+#[derive(Debug)]
+pub(super) struct RequestLimiter {
+    qps_limit_per_ip: Option<u64>,
+    ip_window: Mutex<IpWindow>,
+    in_flight: Option<InFlightLimit>,
+    in_flight_per_method: HashMap<String, InFlightLimit>,
+    metrics: Metrics,
+}
+
+impl RequestLimiter {
+    /// Creates a new limiter, registering its metrics with the given prefix.
+    pub(super) fn new(
+        config: &RequestLimitsConfig,
+        name: &str,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(RequestLimiter {
+            qps_limit_per_ip: config.qps_limit_per_ip,
+            ip_window: Mutex::new(IpWindow {
+                started: Instant::now(),
+                counts: HashMap::new(),
+            }),
+            in_flight: config.max_in_flight.map(InFlightLimit::new),
+            in_flight_per_method: config
+                .max_in_flight_per_method
+                .iter()
+                .map(|(method, limit)| (method.clone(), InFlightLimit::new(*limit)))
+                .collect(),
+            metrics: Metrics::new(name, registry)?,
+        })
+    }
+
+    /// Admits a request from `ip`, checking the per-IP rate limit and the global in-flight limit.
+    ///
+    /// The returned permit, if any, must be held until the request has been processed.
+    pub(super) fn try_admit(&self, ip: IpAddr) -> Result<Option<OwnedSemaphorePermit>, Throttled> {
+        if let Some(limit) = self.qps_limit_per_ip {
+            if !self.count_request(ip, limit) {
+                self.metrics.throttled_by_ip.inc();
+                return Err(Throttled::IpRateLimit { ip, limit });
+            }
+        }
+
+        match &self.in_flight {
+            None => Ok(None),
+            Some(in_flight) => match in_flight.try_acquire() {
+                Some(permit) => Ok(Some(permit)),
+                None => {
+                    self.metrics.throttled_in_flight.inc();
+                    Err(Throttled::TooManyInFlight {
+                        limit: in_flight.limit,
+                    })
+                }
+            },
+        }
+    }
+
+    /// Admits a request for `method`, checking the in-flight limit configured for it.
+    ///
+    /// The returned permit, if any, must be held until the request has been processed.
+    pub(super) fn try_admit_method(
+        &self,
+        method: &'static str,
+    ) -> Result<Option<OwnedSemaphorePermit>, Throttled> {
+        match self.in_flight_per_method.get(method) {
+            None => Ok(None),
+            Some(in_flight) => match in_flight.try_acquire() {
+                Some(permit) => Ok(Some(permit)),
+                None => {
+                    self.metrics.throttled_by_method.inc();
+                    Err(Throttled::MethodInFlight {
+                        method,
+                        limit: in_flight.limit,
+                    })
+                }
+            },
+        }
+    }
+
+    /// Counts a request from `ip` against the current window, returning `false` if `limit` has
+    /// been reached already.
+    fn count_request(&self, ip: IpAddr, limit: u64) -> bool {
+        let mut window = self.ip_window.lock().expect("lock poisoned");
+
+        let now = Instant::now();
+        if now.duration_since(window.started) >= IP_RATE_LIMIT_WINDOW {
+            window.started = now;
+            window.counts.clear();
+        }
+
+        let count = window.counts.entry(ip).or_default();
+        if *count >= limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::time;
+
+    use super::*;
+
+    const IP_1: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const IP_2: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+    fn new_limiter(config: RequestLimitsConfig) -> RequestLimiter {
+        RequestLimiter::new(&config, "test_server", &Registry::new()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_not_limit_by_default() {
+        let limiter = new_limiter(RequestLimitsConfig::default());
+
+        for _ in 0..1000 {
+            assert!(limiter.try_admit(IP_1).unwrap().is_none());
+            assert!(limiter
+                .try_admit_method("info_get_status")
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_rate_limit_per_ip() {
+        time::pause();
+        let limiter = new_limiter(RequestLimitsConfig {
+            qps_limit_per_ip: Some(2),
+            ..Default::default()
+        });
+
+        assert!(limiter.try_admit(IP_1).is_ok());
+        assert!(limiter.try_admit(IP_1).is_ok());
+        assert!(matches!(
+            limiter.try_admit(IP_1),
+            Err(Throttled::IpRateLimit { limit: 2, .. })
+        ));
+        // Other clients are unaffected.
+        assert!(limiter.try_admit(IP_2).is_ok());
+        assert_eq!(limiter.metrics.throttled_by_ip.get(), 1);
+
+        // The limit is lifted once the window has passed.
+        time::advance(IP_RATE_LIMIT_WINDOW).await;
+        assert!(limiter.try_admit(IP_1).is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_cap_requests_in_flight() {
+        let limiter = new_limiter(RequestLimitsConfig {
+            max_in_flight: Some(2),
+            ..Default::default()
+        });
+
+        let first = limiter.try_admit(IP_1).unwrap();
+        let _second = limiter.try_admit(IP_2).unwrap();
+        assert!(matches!(
+            limiter.try_admit(IP_1),
+            Err(Throttled::TooManyInFlight { limit: 2 })
+        ));
+        assert_eq!(limiter.metrics.throttled_in_flight.get(), 1);
+
+        // Finishing a request frees up a slot.
+        drop(first);
+        assert!(limiter.try_admit(IP_1).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn should_cap_requests_in_flight_per_method() {
+        let limiter = new_limiter(RequestLimitsConfig {
+            max_in_flight_per_method: vec![("state_get_trie".to_string(), 1)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        });
+
+        let permit = limiter.try_admit_method("state_get_trie").unwrap();
+        assert!(permit.is_some());
+        assert!(matches!(
+            limiter.try_admit_method("state_get_trie"),
+            Err(Throttled::MethodInFlight {
+                method: "state_get_trie",
+                limit: 1
+            })
+        ));
+        // Methods without a configured limit are unaffected.
+        assert!(limiter
+            .try_admit_method("info_get_status")
+            .unwrap()
+            .is_none());
+        assert_eq!(limiter.metrics.throttled_by_method.get(), 1);
+
+        drop(permit);
+        assert!(limiter.try_admit_method("state_get_trie").is_ok());
+    }
+}
@@ -14,21 +14,30 @@ pub mod state;
 use std::{convert::Infallible, str, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use http::header::ACCEPT_ENCODING;
-use hyper::server::{conn::AddrIncoming, Builder};
+use http::{header::ACCEPT_ENCODING, StatusCode};
+use hyper::server::Server;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use tokio::sync::oneshot;
+use serde_json::{json, Value};
+use tokio::sync::{oneshot, OwnedSemaphorePermit};
 use tower::ServiceBuilder;
-use tracing::info;
-use warp::Filter;
+use tracing::{debug, info};
+use warp::{
+    reply::{self, Json, WithStatus},
+    Filter, Rejection,
+};
 
 use casper_json_rpc::{Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode};
 use casper_types::ProtocolVersion;
 
-use super::{ReactorEventT, RpcRequest};
-use crate::effect::EffectBuilder;
+use super::{
+    request_limiter::{RequestLimiter, Throttled},
+    ReactorEventT, RpcRequest,
+};
+use crate::{
+    effect::EffectBuilder,
+    utils::http_server::{HttpConnection, HttpIncoming},
+};
 pub use common::ErrorData;
 use docs::DocExample;
 pub use error_code::ErrorCode;
@@ -86,11 +95,17 @@ pub(super) trait RpcWithParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &Arc<RequestLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let limiter = Arc::clone(limiter);
+        let handler = move |maybe_params| {
+            let limiter = Arc::clone(&limiter);
+            async move {
+                let _permit = try_admit_method(&limiter, Self::METHOD)?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -145,11 +160,17 @@ pub(super) trait RpcWithoutParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &Arc<RequestLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let limiter = Arc::clone(limiter);
+        let handler = move |maybe_params| {
+            let limiter = Arc::clone(&limiter);
+            async move {
+                let _permit = try_admit_method(&limiter, Self::METHOD)?;
+                Self::check_no_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -225,11 +246,17 @@ pub(super) trait RpcWithOptionalParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &Arc<RequestLimiter>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let limiter = Arc::clone(limiter);
+        let handler = move |maybe_params| {
+            let limiter = Arc::clone(&limiter);
+            async move {
+                let _permit = try_admit_method(&limiter, Self::METHOD)?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -252,21 +279,38 @@ pub(super) trait RpcWithOptionalParams {
     ) -> Result<Self::ResponseResult, Error>;
 }
 
+/// Acquires a permit to handle a request for `method`, or returns a JSON-RPC error if too many
+/// requests for it are being handled already.
+fn try_admit_method(
+    limiter: &RequestLimiter,
+    method: &'static str,
+) -> Result<Option<OwnedSemaphorePermit>, Error> {
+    limiter
+        .try_admit_method(method)
+        .map_err(|throttled| Error::new(ErrorCode::TooManyRequestsForMethod, throttled.to_string()))
+}
+
 /// Start JSON RPC server in a background.
 pub(super) async fn run(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     handlers: RequestHandlers,
+    limiter: Arc<RequestLimiter>,
+    cors: warp::cors::Builder,
     qps_limit: u64,
     max_body_bytes: u32,
     api_path: &'static str,
     server_name: &'static str,
 ) {
-    let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route(
+    let make_svc = hyper::service::make_service_fn(move |connection: &HttpConnection| {
+        let remote_ip = connection.remote_addr().ip();
+        let limiter = Arc::clone(&limiter);
+
+        let service_routes = casper_json_rpc::route_with_cors(
             api_path,
             max_body_bytes,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            cors.clone(),
         );
 
         // Supports content negotiation for gzip responses. This is an interim fix until
@@ -275,7 +319,19 @@ pub(super) async fn run(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        // Rejects the request if the client's rate limit or the in-flight limit is exceeded.  The
+        // permit is held until the reply has been produced.
+        let admission = warp::any().and_then(move || {
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.try_admit(remote_ip).map_err(warp::reject::custom) }
+        });
+
+        let service = warp::service(
+            admission
+                .and(service_routes_gzip.or(service_routes))
+                .map(|_permit: Option<OwnedSemaphorePermit>, reply| reply)
+                .recover(handle_throttled),
+        );
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
@@ -283,8 +339,8 @@ pub(super) async fn run(
         .rate_limit(qps_limit, Duration::from_secs(1))
         .service(make_svc);
 
-    let server = builder.serve(make_svc);
-    info!(address = %server.local_addr(), "started {} server", server_name);
+    info!(address = %incoming.local_addr(), "started {} server", server_name);
+    let server = Server::builder(incoming).serve(make_svc);
 
     let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
     let server_with_shutdown = server.with_graceful_shutdown(async {
@@ -296,12 +352,25 @@ pub(super) async fn run(
     info!("{} server shut down", server_name);
 }
 
+/// Replies with "429 Too Many Requests" to requests rejected by the [`RequestLimiter`].
+async fn handle_throttled(rejection: Rejection) -> Result<WithStatus<Json>, Rejection> {
+    match rejection.find::<Throttled>() {
+        Some(throttled) => {
+            debug!(%throttled, "throttled json-rpc request");
+            Ok(reply::with_status(
+                reply::json(&json!({ "message": throttled.to_string() })),
+                StatusCode::TOO_MANY_REQUESTS,
+            ))
+        }
+        None => Err(rejection),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Write;
 
-    use http::StatusCode;
-    use warp::{filters::BoxedFilter, Filter, Reply};
+    use warp::{filters::BoxedFilter, Reply};
 
     use casper_json_rpc::{filters, Response};
 
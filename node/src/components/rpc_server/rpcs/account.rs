@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{collections::BTreeSet, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -18,10 +18,13 @@ use super::{
     Error, ReactorEventT, RpcRequest, RpcWithParams,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{
+        deploy_acceptor::{DeployParameterFailure, Error as DeployAcceptorError},
+        rpc_server::rpcs::ErrorCode,
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{Approval, Deploy, DeployConfigurationFailure, DeployHash},
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -31,6 +34,26 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().id(),
 });
+static PUT_PARTIAL_DEPLOY_PARAMS: Lazy<PutPartialDeployParams> =
+    Lazy::new(|| PutPartialDeployParams {
+        deploy: Deploy::doc_example().clone(),
+    });
+static PUT_PARTIAL_DEPLOY_RESULT: Lazy<PutPartialDeployResult> =
+    Lazy::new(|| PutPartialDeployResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().id(),
+    });
+static ADD_DEPLOY_APPROVALS_PARAMS: Lazy<AddDeployApprovalsParams> =
+    Lazy::new(|| AddDeployApprovalsParams {
+        deploy_hash: *Deploy::doc_example().id(),
+        approvals: Deploy::doc_example().approvals().clone(),
+    });
+static ADD_DEPLOY_APPROVALS_RESULT: Lazy<AddDeployApprovalsResult> =
+    Lazy::new(|| AddDeployApprovalsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().id(),
+        is_fully_authorized: true,
+    });
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -105,8 +128,213 @@ impl RpcWithParams for PutDeploy {
                     %error,
                     "the deploy submitted by the client was invalid",
                 );
-                Err(Error::new(ErrorCode::InvalidDeploy, error.to_string()))
+                Err(invalid_deploy_error(error))
             }
         }
     }
 }
+
+/// Params for "account_put_partial_deploy" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutPartialDeployParams {
+    /// The `Deploy`, which may not yet be approved by enough authorization keys.
+    pub deploy: Deploy,
+}
+
+impl DocExample for PutPartialDeployParams {
+    fn doc_example() -> &'static Self {
+        &*PUT_PARTIAL_DEPLOY_PARAMS
+    }
+}
+
+/// Result for "account_put_partial_deploy" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PutPartialDeployResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for PutPartialDeployResult {
+    fn doc_example() -> &'static Self {
+        &*PUT_PARTIAL_DEPLOY_RESULT
+    }
+}
+
+/// "account_put_partial_deploy" RPC
+pub struct PutPartialDeploy {}
+
+#[async_trait]
+impl RpcWithParams for PutPartialDeploy {
+    const METHOD: &'static str = "account_put_partial_deploy";
+    type RequestParams = PutPartialDeployParams;
+    type ResponseResult = PutPartialDeployResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = *params.deploy.id();
+
+        // Submit the deploy to be held until it is sufficiently approved.
+        let put_partial_deploy_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::SubmitPartialDeploy {
+                    deploy: Box::new(params.deploy),
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        match put_partial_deploy_result {
+            Ok(_) => {
+                info!(%deploy_hash, "partial deploy is pending further approvals");
+                let result = Self::ResponseResult {
+                    api_version,
+                    deploy_hash,
+                };
+                Ok(result)
+            }
+            Err(error) => {
+                info!(
+                    %deploy_hash,
+                    %error,
+                    "the partial deploy submitted by the client was invalid",
+                );
+                Err(invalid_deploy_error(error))
+            }
+        }
+    }
+}
+
+/// Params for "account_add_deploy_approvals" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsParams {
+    /// The hash of a deploy previously submitted via "account_put_partial_deploy".
+    pub deploy_hash: DeployHash,
+    /// The approvals to add to the deploy.
+    pub approvals: BTreeSet<Approval>,
+}
+
+impl DocExample for AddDeployApprovalsParams {
+    fn doc_example() -> &'static Self {
+        &*ADD_DEPLOY_APPROVALS_PARAMS
+    }
+}
+
+/// Result for "account_add_deploy_approvals" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the deploy is now sufficiently approved and has been accepted for execution.  If
+    /// `false`, the deploy remains pending until further approvals are added.
+    pub is_fully_authorized: bool,
+}
+
+impl DocExample for AddDeployApprovalsResult {
+    fn doc_example() -> &'static Self {
+        &*ADD_DEPLOY_APPROVALS_RESULT
+    }
+}
+
+/// "account_add_deploy_approvals" RPC
+pub struct AddDeployApprovals {}
+
+#[async_trait]
+impl RpcWithParams for AddDeployApprovals {
+    const METHOD: &'static str = "account_add_deploy_approvals";
+    type RequestParams = AddDeployApprovalsParams;
+    type ResponseResult = AddDeployApprovalsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = params.deploy_hash;
+
+        let add_approvals_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::AddDeployApprovals {
+                    deploy_hash,
+                    approvals: params.approvals,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        let is_fully_authorized = match add_approvals_result {
+            Ok(()) => {
+                info!(%deploy_hash, "pending deploy is fully approved and was stored");
+                true
+            }
+            Err(DeployAcceptorError::InvalidDeployParameters {
+                failure: DeployParameterFailure::InsufficientDeploySignatureWeight,
+                ..
+            }) => {
+                info!(%deploy_hash, "pending deploy still requires further approvals");
+                false
+            }
+            Err(error @ DeployAcceptorError::NoSuchPendingDeploy { .. }) => {
+                return Err(Error::new(
+                    ErrorCode::NoSuchPendingDeploy,
+                    error.to_string(),
+                ));
+            }
+            Err(error) => {
+                info!(
+                    %deploy_hash,
+                    %error,
+                    "the approvals submitted by the client were invalid",
+                );
+                return Err(invalid_deploy_error(error));
+            }
+        };
+
+        Ok(Self::ResponseResult {
+            api_version,
+            deploy_hash,
+            is_fully_authorized,
+        })
+    }
+}
+
+/// Additional info of an "Invalid Deploy" error caused by the deploy failing validation.
+#[derive(Serialize)]
+struct DeployValidationErrorInfo<'a> {
+    /// Description of the error.
+    message: String,
+    /// The checks the deploy failed, in the order they were run.
+    failures: &'a [DeployConfigurationFailure],
+}
+
+/// Converts the error rejecting a deploy into an "Invalid Deploy" error.  If the deploy failed
+/// validation, the error carries the machine-readable list of failed checks.
+fn invalid_deploy_error(error: DeployAcceptorError) -> Error {
+    match &error {
+        DeployAcceptorError::InvalidDeployConfiguration(report) => Error::new(
+            ErrorCode::InvalidDeploy,
+            DeployValidationErrorInfo {
+                message: error.to_string(),
+                failures: report.failures(),
+            },
+        ),
+        DeployAcceptorError::ReplayedDeploy { .. } => {
+            Error::new(ErrorCode::ReplayedDeploy, error.to_string())
+        }
+        _ => Error::new(ErrorCode::InvalidDeploy, error.to_string()),
+    }
+}
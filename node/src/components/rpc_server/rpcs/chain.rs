@@ -5,28 +5,38 @@
 
 mod era_summary;
 
-use std::{num::ParseIntError, str};
+use std::{collections::BTreeMap, num::ParseIntError, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::info;
 
+use casper_execution_engine::shared::wasm_config::WasmConfig;
 use casper_hashing::Digest;
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{
+    system::auction::{EraInfo, SeigniorageAllocation},
+    CLValue, EraId, Key, ProtocolVersion, PublicKey, StoredValue as DomainStoredValue, TimeDiff,
+    Timestamp, Transfer, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    state, Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams, RpcWithoutParams,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
-    rpcs::common,
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    rpcs::common::{self, MERKLE_PROOF},
+    types::{
+        json_compatibility::StoredValue, Block, BlockHash, BlockHeader, BlockSignatures,
+        BlockWithMetadata, JsonBlock, JsonBlockHeader, JsonProof,
+    },
 };
 pub use era_summary::EraSummary;
-use era_summary::ERA_SUMMARY;
+use era_summary::{ERA_INFO, ERA_SUMMARY};
 
 static GET_BLOCK_PARAMS: Lazy<GetBlockParams> = Lazy::new(|| GetBlockParams {
     block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
@@ -61,6 +71,80 @@ static GET_ERA_INFO_RESULT: Lazy<GetEraInfoResult> = Lazy::new(|| GetEraInfoResu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: Some(ERA_SUMMARY.clone()),
 });
+static GET_LIGHT_CLIENT_PROOF_PARAMS: Lazy<GetLightClientProofParams> =
+    Lazy::new(|| GetLightClientProofParams {
+        block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+        trusted_era_id: Block::doc_example().header().era_id(),
+        keys: vec![
+            "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
+        ],
+    });
+static GET_LIGHT_CLIENT_PROOF_RESULT: Lazy<GetLightClientProofResult> = Lazy::new(|| {
+    let json_block = JsonBlock::doc_example();
+    let signed_block_header = SignedBlockHeader {
+        block_hash: json_block.hash,
+        header: json_block.header.clone(),
+        proofs: json_block.proofs.clone(),
+    };
+    GetLightClientProofResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block: signed_block_header.clone(),
+        switch_blocks: vec![signed_block_header],
+        stored_values: vec![StoredValueProof {
+            key: GET_LIGHT_CLIENT_PROOF_PARAMS.keys[0].clone(),
+            stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
+            merkle_proof: MERKLE_PROOF.clone(),
+        }],
+    }
+});
+static REBROADCAST_FINALITY_SIGNATURES_PARAMS: Lazy<RebroadcastFinalitySignaturesParams> =
+    Lazy::new(|| RebroadcastFinalitySignaturesParams {
+        block_hash: *Block::doc_example().hash(),
+    });
+static REBROADCAST_FINALITY_SIGNATURES_RESULT: Lazy<RebroadcastFinalitySignaturesResult> =
+    Lazy::new(|| RebroadcastFinalitySignaturesResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        signature_count: JsonBlock::doc_example().proofs.len() as u64,
+    });
+static GET_LEADER_SCHEDULE_RESULT: Lazy<GetLeaderScheduleResult> = Lazy::new(|| {
+    let header = Block::doc_example().header();
+    GetLeaderScheduleResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_id: header.era_id(),
+        round_length: TimeDiff::from(1 << 16),
+        leaders: vec![JsonRoundLeader {
+            round_start: header.timestamp(),
+            public_key: PublicKey::doc_example().clone(),
+        }],
+    }
+});
+static GET_WASM_CONFIG_PARAMS: Lazy<GetWasmConfigParams> = Lazy::new(|| GetWasmConfigParams {
+    block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+});
+static GET_WASM_CONFIG_RESULT: Lazy<GetWasmConfigResult> = Lazy::new(|| GetWasmConfigResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    protocol_version: Block::doc_example().header().protocol_version(),
+    wasm_config: WasmConfig::default(),
+});
+static GET_ERA_SUMMARY_PARAMS: Lazy<GetEraSummaryParams> = Lazy::new(|| GetEraSummaryParams {
+    era_id: Block::doc_example().header().era_id(),
+});
+static GET_ERA_SUMMARY_RESULT: Lazy<GetEraSummaryResult> = Lazy::new(|| {
+    let header = Block::doc_example().header();
+    let era_report = header
+        .era_end()
+        .expect("example block should be a switch block")
+        .era_report();
+    GetEraSummaryResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_id: header.era_id(),
+        switch_block: JsonSwitchBlockSummary::new(header),
+        total_rewards: total_rewards(&ERA_INFO),
+        validator_rewards: JsonValidatorRewards::from_era_info(&ERA_INFO),
+        equivocators: era_report.equivocators.clone(),
+        inactive_validators: era_report.inactive_validators.clone(),
+    }
+});
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -244,13 +328,26 @@ impl RpcWithOptionalParams for GetBlockTransfers {
 
         // Get the block.
         let maybe_block_id = maybe_params.map(|params| params.block_identifier);
-        let block_hash = common::get_block(
+        let block = common::get_block(
             maybe_block_id,
             only_from_available_block_range,
             effect_builder,
         )
-        .await
-        .map(|block| *block.hash())?;
+        .await?;
+        let block_hash = *block.hash();
+
+        let pruned_below = effect_builder
+            .get_execution_results_pruned_below_from_storage()
+            .await;
+        if block.height() < pruned_below {
+            let message = format!(
+                "transfers of block {} have been pruned, only those of blocks from height {} are \
+                 retained",
+                block_hash, pruned_below
+            );
+            info!("{}", message);
+            return Err(Error::new(ErrorCode::ExecutionResultsPruned, message));
+        }
 
         let transfers = effect_builder
             .make_request(
@@ -420,6 +517,649 @@ impl RpcWithOptionalParams for GetEraInfoBySwitchBlock {
     }
 }
 
+/// Params for "chain_get_light_client_proof" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetLightClientProofParams {
+    /// The block identifier.
+    pub block_identifier: BlockIdentifier,
+    /// The latest era whose validators are trusted by the light client.
+    pub trusted_era_id: EraId,
+    /// Formatted keys whose values are to be proven against the block's state root hash.
+    pub keys: Vec<String>,
+}
+
+impl DocExample for GetLightClientProofParams {
+    fn doc_example() -> &'static Self {
+        &*GET_LIGHT_CLIENT_PROOF_PARAMS
+    }
+}
+
+/// A block header together with the finality signatures of the block.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SignedBlockHeader {
+    /// The block hash.
+    pub block_hash: BlockHash,
+    /// The block header.
+    pub header: JsonBlockHeader,
+    /// The finality signatures of the block.
+    pub proofs: Vec<JsonProof>,
+}
+
+impl SignedBlockHeader {
+    fn new(header: BlockHeader, signatures: BlockSignatures) -> Self {
+        SignedBlockHeader {
+            block_hash: header.hash(),
+            header: JsonBlockHeader::from(header),
+            proofs: signatures.proofs.into_iter().map(JsonProof::from).collect(),
+        }
+    }
+}
+
+/// A stored value together with the merkle proof of it under a block's state root hash.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StoredValueProof {
+    /// The formatted key.
+    pub key: String,
+    /// The stored value.
+    pub stored_value: StoredValue,
+    /// The merkle proof.
+    pub merkle_proof: String,
+}
+
+/// Result for "chain_get_light_client_proof" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetLightClientProofResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The requested block's header and finality signatures.
+    pub block: SignedBlockHeader,
+    /// The switch blocks of every era from the trusted era up to the era preceding the requested
+    /// block, in ascending order.  Each one announces the validators who sign the next.
+    pub switch_blocks: Vec<SignedBlockHeader>,
+    /// The values stored under the requested keys, with their merkle proofs.
+    pub stored_values: Vec<StoredValueProof>,
+}
+
+impl DocExample for GetLightClientProofResult {
+    fn doc_example() -> &'static Self {
+        &*GET_LIGHT_CLIENT_PROOF_RESULT
+    }
+}
+
+/// "chain_get_light_client_proof" RPC.
+pub struct GetLightClientProof {}
+
+#[async_trait]
+impl RpcWithParams for GetLightClientProof {
+    const METHOD: &'static str = "chain_get_light_client_proof";
+    type RequestParams = GetLightClientProofParams;
+    type ResponseResult = GetLightClientProofResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        // Parse all keys up front, so that invalid requests don't cause any queries.
+        let keys = params
+            .keys
+            .iter()
+            .map(|key| {
+                Key::from_formatted_str(key).map_err(|error| {
+                    let error_msg = format!("failed to parse key {}: {}", key, error);
+                    info!("{}", error_msg);
+                    Error::new(ErrorCode::FailedToParseQueryKey, error_msg)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Get the block.
+        let BlockWithMetadata {
+            block,
+            block_signatures,
+        } = get_block_with_metadata(
+            Some(params.block_identifier),
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let era_id = block.header().era_id();
+        if params.trusted_era_id > era_id {
+            return Err(Error::new(
+                ErrorCode::InvalidTrustedEra,
+                format!(
+                    "trusted era {} is later than {} of block {}",
+                    params.trusted_era_id,
+                    era_id,
+                    block.hash()
+                ),
+            ));
+        }
+
+        // Get the chain of switch blocks from the trusted era.
+        let mut switch_blocks = Vec::new();
+        for switch_block_era_id in params
+            .trusted_era_id
+            .iter(era_id.value() - params.trusted_era_id.value())
+        {
+            switch_blocks.push(get_signed_switch_block(switch_block_era_id, effect_builder).await?);
+        }
+
+        // Prove the requested values.
+        let state_root_hash = *block.state_root_hash();
+        let mut stored_values = Vec::with_capacity(keys.len());
+        for (base_key, key) in keys.into_iter().zip(params.keys) {
+            let (stored_value, merkle_proof) =
+                common::run_query_and_encode(effect_builder, state_root_hash, base_key, Vec::new())
+                    .await?;
+            stored_values.push(StoredValueProof {
+                key,
+                stored_value,
+                merkle_proof,
+            });
+        }
+
+        let result = Self::ResponseResult {
+            api_version,
+            block: SignedBlockHeader::new(block.take_header(), block_signatures),
+            switch_blocks,
+            stored_values,
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "chain_rebroadcast_finality_signatures" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RebroadcastFinalitySignaturesParams {
+    /// The hash of the block whose finality signatures are to be broadcast.
+    pub block_hash: BlockHash,
+}
+
+impl DocExample for RebroadcastFinalitySignaturesParams {
+    fn doc_example() -> &'static Self {
+        &*REBROADCAST_FINALITY_SIGNATURES_PARAMS
+    }
+}
+
+/// Result for "chain_rebroadcast_finality_signatures" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RebroadcastFinalitySignaturesResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The number of finality signatures broadcast to the node's peers.
+    pub signature_count: u64,
+}
+
+impl DocExample for RebroadcastFinalitySignaturesResult {
+    fn doc_example() -> &'static Self {
+        &*REBROADCAST_FINALITY_SIGNATURES_RESULT
+    }
+}
+
+/// "chain_rebroadcast_finality_signatures" RPC.
+pub struct RebroadcastFinalitySignatures {}
+
+#[async_trait]
+impl RpcWithParams for RebroadcastFinalitySignatures {
+    const METHOD: &'static str = "chain_rebroadcast_finality_signatures";
+    type RequestParams = RebroadcastFinalitySignaturesParams;
+    type ResponseResult = RebroadcastFinalitySignaturesResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let signature_count = effect_builder
+            .rebroadcast_finality_signatures(params.block_hash)
+            .await
+            .ok_or_else(|| {
+                let error_msg = format!(
+                    "finality signatures of {} were rebroadcast too recently",
+                    params.block_hash
+                );
+                info!("{}", error_msg);
+                Error::new(ErrorCode::RebroadcastRateLimited, error_msg)
+            })?;
+        Ok(Self::ResponseResult {
+            api_version,
+            signature_count: signature_count as u64,
+        })
+    }
+}
+
+/// The leader of a round, i.e. the validator expected to propose a block in it.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonRoundLeader {
+    /// The start of the round.
+    pub round_start: Timestamp,
+    /// The public key of the round's leader.
+    pub public_key: PublicKey,
+}
+
+/// Result for "chain_get_leader_schedule" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetLeaderScheduleResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The current era.
+    pub era_id: EraId,
+    /// The round length the schedule is based on.
+    pub round_length: TimeDiff,
+    /// The leader of each round, from the start of the era until its earliest possible end.
+    pub leaders: Vec<JsonRoundLeader>,
+}
+
+impl DocExample for GetLeaderScheduleResult {
+    fn doc_example() -> &'static Self {
+        &*GET_LEADER_SCHEDULE_RESULT
+    }
+}
+
+/// "chain_get_leader_schedule" RPC.
+pub struct GetLeaderSchedule {}
+
+#[async_trait]
+impl RpcWithoutParams for GetLeaderSchedule {
+    const METHOD: &'static str = "chain_get_leader_schedule";
+    type ResponseResult = GetLeaderScheduleResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (era_id, schedule) = effect_builder
+            .get_consensus_leader_schedule()
+            .await
+            .ok_or_else(|| {
+                let error_msg = "the node is not running consensus".to_string();
+                info!("{}", error_msg);
+                Error::new(ErrorCode::NoLeaderSchedule, error_msg)
+            })?;
+        let leaders = schedule
+            .leaders
+            .into_iter()
+            .map(|(round_start, public_key)| JsonRoundLeader {
+                round_start,
+                public_key,
+            })
+            .collect();
+        Ok(Self::ResponseResult {
+            api_version,
+            era_id,
+            round_length: schedule.round_length,
+            leaders,
+        })
+    }
+}
+
+/// Params for "chain_get_wasm_config" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetWasmConfigParams {
+    /// The block whose protocol version the Wasm config applies to.
+    pub block_identifier: BlockIdentifier,
+}
+
+impl DocExample for GetWasmConfigParams {
+    fn doc_example() -> &'static Self {
+        &*GET_WASM_CONFIG_PARAMS
+    }
+}
+
+/// Result for "chain_get_wasm_config" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetWasmConfigResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The protocol version of the block.
+    #[schemars(with = "String")]
+    pub protocol_version: ProtocolVersion,
+    /// The Wasm config applying to the protocol version, including the opcode, storage and host
+    /// function costs.
+    #[schemars(with = "serde_json::Value")]
+    pub wasm_config: WasmConfig,
+}
+
+impl DocExample for GetWasmConfigResult {
+    fn doc_example() -> &'static Self {
+        &*GET_WASM_CONFIG_RESULT
+    }
+}
+
+/// "chain_get_wasm_config" RPC.
+pub struct GetWasmConfig {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetWasmConfig {
+    const METHOD: &'static str = "chain_get_wasm_config";
+    type OptionalRequestParams = GetWasmConfigParams;
+    type ResponseResult = GetWasmConfigResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        let maybe_block_id = maybe_params.map(|params| params.block_identifier);
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let protocol_version = block.header().protocol_version();
+
+        let wasm_config = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetWasmConfig {
+                    protocol_version,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        Ok(Self::ResponseResult {
+            api_version,
+            protocol_version,
+            wasm_config,
+        })
+    }
+}
+
+/// Params for "chain_get_era_summary" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraSummaryParams {
+    /// The era to summarize.
+    pub era_id: EraId,
+}
+
+impl DocExample for GetEraSummaryParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_SUMMARY_PARAMS
+    }
+}
+
+/// The switch block concluding an era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSwitchBlockSummary {
+    /// The hash of the switch block.
+    pub block_hash: BlockHash,
+    /// The height of the switch block.
+    pub height: u64,
+    /// The timestamp of the switch block.
+    pub timestamp: Timestamp,
+    /// The global state root hash after executing the switch block.
+    pub state_root_hash: Digest,
+}
+
+impl JsonSwitchBlockSummary {
+    fn new(header: &BlockHeader) -> Self {
+        JsonSwitchBlockSummary {
+            block_hash: header.hash(),
+            height: header.height(),
+            timestamp: header.timestamp(),
+            state_root_hash: *header.state_root_hash(),
+        }
+    }
+}
+
+/// The reward paid to a delegator at the end of an era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonDelegatorReward {
+    /// The public key of the delegator.
+    pub public_key: PublicKey,
+    /// The amount paid to the delegator.
+    pub amount: U512,
+}
+
+/// The rewards paid to a validator and its delegators at the end of an era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonValidatorRewards {
+    /// The public key of the validator.
+    pub public_key: PublicKey,
+    /// The amount paid to the validator itself.
+    pub amount: U512,
+    /// The amounts paid to the validator's delegators.
+    pub delegators: Vec<JsonDelegatorReward>,
+}
+
+impl JsonValidatorRewards {
+    /// Groups the seigniorage allocations of `era_info` by validator, ordered by public key.
+    fn from_era_info(era_info: &EraInfo) -> Vec<Self> {
+        let mut rewards: BTreeMap<PublicKey, JsonValidatorRewards> = BTreeMap::new();
+        for allocation in era_info.seigniorage_allocations() {
+            match allocation {
+                SeigniorageAllocation::Validator {
+                    validator_public_key,
+                    amount,
+                } => {
+                    rewards
+                        .entry(validator_public_key.clone())
+                        .or_insert_with(|| JsonValidatorRewards::new(validator_public_key.clone()))
+                        .amount += *amount;
+                }
+                SeigniorageAllocation::Delegator {
+                    delegator_public_key,
+                    validator_public_key,
+                    amount,
+                } => {
+                    rewards
+                        .entry(validator_public_key.clone())
+                        .or_insert_with(|| JsonValidatorRewards::new(validator_public_key.clone()))
+                        .delegators
+                        .push(JsonDelegatorReward {
+                            public_key: delegator_public_key.clone(),
+                            amount: *amount,
+                        });
+                }
+            }
+        }
+        rewards.into_values().collect()
+    }
+
+    fn new(public_key: PublicKey) -> Self {
+        JsonValidatorRewards {
+            public_key,
+            amount: U512::zero(),
+            delegators: vec![],
+        }
+    }
+}
+
+/// Returns the sum of all seigniorage allocations of `era_info`.
+fn total_rewards(era_info: &EraInfo) -> U512 {
+    era_info
+        .seigniorage_allocations()
+        .iter()
+        .fold(U512::zero(), |total, allocation| {
+            total + *allocation.amount()
+        })
+}
+
+/// Result for "chain_get_era_summary" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraSummaryResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The summarized era.
+    pub era_id: EraId,
+    /// The switch block concluding the era.
+    pub switch_block: JsonSwitchBlockSummary,
+    /// The total amount of rewards paid at the end of the era.
+    pub total_rewards: U512,
+    /// The rewards paid to each validator and its delegators, ordered by validator public key.
+    pub validator_rewards: Vec<JsonValidatorRewards>,
+    /// The validators found to have equivocated during the era.
+    pub equivocators: Vec<PublicKey>,
+    /// The validators which did not produce any unit during the era.
+    pub inactive_validators: Vec<PublicKey>,
+}
+
+impl DocExample for GetEraSummaryResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_SUMMARY_RESULT
+    }
+}
+
+/// "chain_get_era_summary" RPC.
+pub struct GetEraSummary {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetEraSummary {
+    const METHOD: &'static str = "chain_get_era_summary";
+    type OptionalRequestParams = GetEraSummaryParams;
+    type ResponseResult = GetEraSummaryResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        let era_id = match maybe_params {
+            Some(params) => params.era_id,
+            None => latest_completed_era(effect_builder).await?,
+        };
+
+        let header = match effect_builder
+            .get_switch_block_header_at_era_id_from_storage(era_id)
+            .await
+        {
+            Some(header) => header,
+            None => {
+                return Err(common::missing_block_or_state_root_error(
+                    effect_builder,
+                    ErrorCode::NoSuchBlock,
+                    format!("switch block of {} not stored on this node", era_id),
+                )
+                .await)
+            }
+        };
+        let era_report = match header.era_end() {
+            Some(era_end) => era_end.era_report(),
+            None => {
+                let error_msg = format!("block {} is not a switch block", header.hash());
+                info!("{}", error_msg);
+                return Err(Error::new(ReservedErrorCode::InternalError, error_msg));
+            }
+        };
+
+        let (stored_value, _proofs) = state::run_query(
+            effect_builder,
+            *header.state_root_hash(),
+            Key::EraInfo(era_id),
+            vec![],
+        )
+        .await?;
+        let era_info = match stored_value {
+            DomainStoredValue::EraInfo(era_info) => era_info,
+            _ => {
+                let error_msg = format!("era info of {} has unexpected type", era_id);
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::QueryFailed, error_msg));
+            }
+        };
+
+        Ok(Self::ResponseResult {
+            api_version,
+            era_id,
+            switch_block: JsonSwitchBlockSummary::new(&header),
+            total_rewards: total_rewards(&era_info),
+            validator_rewards: JsonValidatorRewards::from_era_info(&era_info),
+            equivocators: era_report.equivocators.clone(),
+            inactive_validators: era_report.inactive_validators.clone(),
+        })
+    }
+}
+
+/// Returns the most recent era whose switch block has been stored.
+async fn latest_completed_era<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> Result<EraId, Error> {
+    let header = match effect_builder.get_highest_block_header_from_storage().await {
+        Some(header) => header,
+        None => {
+            return Err(common::missing_block_or_state_root_error(
+                effect_builder,
+                ReservedErrorCode::InternalError,
+                "failed to get highest block".to_string(),
+            )
+            .await)
+        }
+    };
+    if header.is_switch_block() {
+        return Ok(header.era_id());
+    }
+    header.era_id().checked_sub(1).ok_or_else(|| {
+        let error_msg = "no era has been completed yet".to_string();
+        info!("{}", error_msg);
+        Error::new(ErrorCode::NoSuchBlock, error_msg)
+    })
+}
+
+/// Returns the header and finality signatures of the switch block of `era_id`.
+async fn get_signed_switch_block<REv: ReactorEventT>(
+    era_id: EraId,
+    effect_builder: EffectBuilder<REv>,
+) -> Result<SignedBlockHeader, Error> {
+    let header = match effect_builder
+        .get_switch_block_header_at_era_id_from_storage(era_id)
+        .await
+    {
+        Some(header) => header,
+        None => {
+            return Err(common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::NoSuchBlock,
+                format!("switch block of {} not stored on this node", era_id),
+            )
+            .await)
+        }
+    };
+    match effect_builder
+        .get_signatures_from_storage(header.hash())
+        .await
+    {
+        Some(signatures) => Ok(SignedBlockHeader::new(header, signatures)),
+        None => Err(common::missing_block_or_state_root_error(
+            effect_builder,
+            ErrorCode::NoSuchBlock,
+            format!(
+                "finality signatures of switch block of {} not stored on this node",
+                era_id
+            ),
+        )
+        .await),
+    }
+}
+
 pub(super) async fn get_block_with_metadata<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     only_from_available_block_range: bool,
@@ -13,7 +13,7 @@ use crate::{
     types::{json_compatibility::StoredValue, Block, BlockHash},
 };
 
-pub(super) static ERA_SUMMARY: Lazy<EraSummary> = Lazy::new(|| {
+pub(super) static ERA_INFO: Lazy<EraInfo> = Lazy::new(|| {
     let delegator_amount = U512::from(1000);
     let validator_amount = U512::from(2000);
     let delegator_public_key =
@@ -35,13 +35,14 @@ pub(super) static ERA_SUMMARY: Lazy<EraSummary> = Lazy::new(|| {
     let seigniorage_allocations = vec![delegator, validator];
     let mut era_info = EraInfo::new();
     *era_info.seigniorage_allocations_mut() = seigniorage_allocations;
-    EraSummary {
-        block_hash: *Block::doc_example().hash(),
-        era_id: EraId::from(42),
-        stored_value: StoredValue::EraInfo(era_info),
-        state_root_hash: *Block::doc_example().header().state_root_hash(),
-        merkle_proof: MERKLE_PROOF.clone(),
-    }
+    era_info
+});
+pub(super) static ERA_SUMMARY: Lazy<EraSummary> = Lazy::new(|| EraSummary {
+    block_hash: *Block::doc_example().hash(),
+    era_id: EraId::from(42),
+    stored_value: StoredValue::EraInfo(ERA_INFO.clone()),
+    state_root_hash: *Block::doc_example().header().state_root_hash(),
+    merkle_proof: MERKLE_PROOF.clone(),
 });
 
 /// The summary of an era
@@ -16,12 +16,18 @@ use serde_json::{json, Value};
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+    account::{AddDeployApprovals, PutDeploy, PutPartialDeploy},
+    chain::{
+        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetLeaderSchedule,
+        GetLightClientProof, GetStateRootHash, GetWasmConfig, RebroadcastFinalitySignatures,
+    },
+    info::{
+        GetChainspec, GetDeploy, GetEvidence, GetPeers, GetPendingDeploys, GetStatus,
+        GetValidatorChanges,
+    },
     state::{
         GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        QueryBalanceDetails, QueryGlobalState,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -67,6 +73,13 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<PutPartialDeploy>(
+        "receives a Deploy which is held by the node until it is approved by enough keys",
+    );
+    schema.push_with_params::<AddDeployApprovals>(
+        "adds approvals to a Deploy received via `account_put_partial_deploy`, executing it once \
+        it is approved by enough keys",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
@@ -76,13 +89,23 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_params::<QueryBalance>(
         "query for a balance using a purse identifier and a state identifier",
     );
+    schema.push_with_params::<QueryBalanceDetails>(
+        "query for the total and available balance of a purse and the holds on it, along with the \
+        state root hash and block used",
+    );
     schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
+    schema.push_without_params::<GetEvidence>(
+        "returns the persisted equivocation evidence, by faulty validator",
+    );
     schema.push_without_params::<GetChainspec>(
         "returns the raw bytes of the chainspec.toml, genesis accounts.toml, and \
-        global_state.toml files",
+        global_state.toml files, along with the parsed chainspec and its hash",
+    );
+    schema.push_without_params::<GetPendingDeploys>(
+        "returns the deploys waiting to be proposed in a Block, if enabled in the node's config",
     );
     schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
     schema.push_with_optional_params::<GetBlockTransfers>(
@@ -99,6 +122,25 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
     );
+    schema.push_with_params::<GetLightClientProof>(
+        "returns a Block's header and finality signatures, the switch Blocks back to a trusted \
+        era, and merkle proofs of the requested keys",
+    );
+    schema.push_with_params::<RebroadcastFinalitySignatures>(
+        "broadcasts the known finality signatures of a Block to the node's peers again",
+    );
+    schema.push_without_params::<GetLeaderSchedule>(
+        "returns the leader of each round of the current era",
+    );
+    schema.push_with_optional_params::<GetWasmConfig>(
+        "returns the Wasm config, including the gas cost tables, applying to either a specific \
+        block (by height or hash), or the most recently added block",
+    );
+    schema.push_with_optional_params::<GetEraSummary>(
+        "returns the switch block, the rewards paid to each validator and its delegators, and the \
+        equivocators and inactive validators of either a specific era, or the most recently \
+        completed era",
+    );
     schema.push_with_optional_params::<GetAuctionInfo>(
         "returns the bids and validators as of either a specific block (by height or hash), or \
         the most recently added block",
@@ -35,6 +35,26 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The requested Deploy is not pending further approvals.
+    NoSuchPendingDeploy = -32013,
+    /// The given trusted era is later than the era of the requested Block.
+    InvalidTrustedEra = -32014,
+    /// The query followed a circular reference in global state.
+    QueryCircularReference = -32015,
+    /// The query exceeded the maximum query depth.
+    QueryDepthLimitReached = -32016,
+    /// The finality signatures of the requested Block were rebroadcast too recently.
+    RebroadcastRateLimited = -32017,
+    /// Too many requests for the same method are being processed already.
+    TooManyRequestsForMethod = -32018,
+    /// The node is not running consensus, so there is no leader schedule.
+    NoLeaderSchedule = -32019,
+    /// The view could not be called, or failed.
+    FailedToCallView = -32020,
+    /// The deploy has already been executed.
+    ReplayedDeploy = -32021,
+    /// The requested execution results or transfers have been pruned from this node's storage.
+    ExecutionResultsPruned = -32022,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +79,20 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::NoSuchPendingDeploy => (error_code as i64, "No such pending deploy"),
+            ErrorCode::InvalidTrustedEra => (error_code as i64, "Invalid trusted era"),
+            ErrorCode::QueryCircularReference => {
+                (error_code as i64, "Query found a circular reference")
+            }
+            ErrorCode::QueryDepthLimitReached => (error_code as i64, "Query depth limit reached"),
+            ErrorCode::RebroadcastRateLimited => (error_code as i64, "Rebroadcast rate limited"),
+            ErrorCode::TooManyRequestsForMethod => {
+                (error_code as i64, "Too many requests for method")
+            }
+            ErrorCode::NoLeaderSchedule => (error_code as i64, "No leader schedule"),
+            ErrorCode::FailedToCallView => (error_code as i64, "Failed to call view"),
+            ErrorCode::ReplayedDeploy => (error_code as i64, "Deploy already executed"),
+            ErrorCode::ExecutionResultsPruned => (error_code as i64, "Execution results pruned"),
         }
     }
 }
@@ -9,20 +9,22 @@ use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use serde_json::json;
+use tracing::{error, info};
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_hashing::Digest;
+use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::{block_proposer::PendingDeployStatus, consensus::ValidatorChange},
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
-        Block, BlockHash, BlockHashAndHeight, ChainspecRawBytes, Deploy, DeployHash,
+        Block, BlockHash, BlockHashAndHeight, Chainspec, ChainspecRawBytes, Deploy, DeployHash,
         DeployMetadataExt, GetStatusResult, PeersMap,
     },
 };
@@ -53,9 +55,33 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_EVIDENCE_RESULT: Lazy<GetEvidenceResult> = Lazy::new(|| GetEvidenceResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    evidence: vec![JsonEvidence {
+        public_key: PublicKey::doc_example().clone(),
+        era_id: EraId::new(1),
+    }],
+});
 static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     chainspec_bytes: ChainspecRawBytes::new(vec![42, 42].into(), None, None),
+    chainspec: json!({ "network": { "name": "casper-example" } }),
+    chainspec_hash: Digest::from([42; Digest::LENGTH]),
+});
+static GET_PENDING_DEPLOYS_RESULT: Lazy<GetPendingDeploysResult> = Lazy::new(|| {
+    let deploy = Deploy::doc_example();
+    GetPendingDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![JsonPendingDeploy {
+            deploy_hash: *deploy.id(),
+            is_transfer: deploy.session().is_transfer(),
+            account: deploy.header().account().clone(),
+            received: deploy.header().timestamp(),
+            age: TimeDiff::from(60_000),
+            expires: deploy.header().expires(),
+            dependencies_resolved: true,
+        }],
+    }
 });
 
 /// Params for "info_get_deploy" RPC request.
@@ -162,6 +188,18 @@ impl RpcWithParams for GetDeploy {
                 None,
             ),
             DeployMetadataExt::BlockInfo(block_hash_and_height) => {
+                let pruned_below = effect_builder
+                    .get_execution_results_pruned_below_from_storage()
+                    .await;
+                if block_hash_and_height.block_height < pruned_below {
+                    let message = format!(
+                        "execution results of {} in block {} have been pruned, only those of \
+                         blocks from height {} are retained",
+                        params.deploy_hash, block_hash_and_height.block_hash, pruned_below
+                    );
+                    info!("{}", message);
+                    return Err(Error::new(ErrorCode::ExecutionResultsPruned, message));
+                }
                 (Vec::new(), Some(block_hash_and_height))
             }
             DeployMetadataExt::Empty => (Vec::new(), None),
@@ -348,6 +386,64 @@ impl RpcWithoutParams for GetValidatorChanges {
     }
 }
 
+/// Persisted evidence of a validator's equivocation.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonEvidence {
+    /// The public key of the faulty validator.
+    public_key: PublicKey,
+    /// The era in which the validator equivocated.
+    era_id: EraId,
+}
+
+/// Result for the "info_get_evidence" RPC.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEvidenceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The persisted equivocation evidence, by faulty validator.
+    pub evidence: Vec<JsonEvidence>,
+}
+
+impl GetEvidenceResult {
+    pub(crate) fn new(api_version: ProtocolVersion, evidence: BTreeMap<PublicKey, EraId>) -> Self {
+        let evidence = evidence
+            .into_iter()
+            .map(|(public_key, era_id)| JsonEvidence { public_key, era_id })
+            .collect();
+        GetEvidenceResult {
+            api_version,
+            evidence,
+        }
+    }
+}
+
+impl DocExample for GetEvidenceResult {
+    fn doc_example() -> &'static Self {
+        &*GET_EVIDENCE_RESULT
+    }
+}
+
+/// "info_get_evidence" RPC.
+pub struct GetEvidence {}
+
+#[async_trait]
+impl RpcWithoutParams for GetEvidence {
+    const METHOD: &'static str = "info_get_evidence";
+    type ResponseResult = GetEvidenceResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let evidence = effect_builder.get_consensus_evidence().await;
+        let result = Self::ResponseResult::new(api_version, evidence);
+        Ok(result)
+    }
+}
+
 /// Result for the "info_get_chainspec" RPC.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetChainspecResult {
@@ -356,13 +452,28 @@ pub struct GetChainspecResult {
     pub api_version: ProtocolVersion,
     /// The chainspec file bytes.
     pub chainspec_bytes: ChainspecRawBytes,
+    /// The chainspec as parsed by the node, including the settings taken from the genesis accounts
+    /// and global state files.
+    pub chainspec: serde_json::Value,
+    /// The hash of the parsed chainspec, as exchanged in the network handshake.
+    pub chainspec_hash: Digest,
 }
 
 impl GetChainspecResult {
-    pub(crate) fn new(api_version: ProtocolVersion, chainspec_bytes: ChainspecRawBytes) -> Self {
+    pub(crate) fn new(
+        api_version: ProtocolVersion,
+        chainspec_bytes: ChainspecRawBytes,
+        chainspec: &Chainspec,
+    ) -> Self {
+        let parsed_chainspec = serde_json::to_value(chainspec).unwrap_or_else(|error| {
+            error!(%error, "failed to convert chainspec to JSON");
+            serde_json::Value::Null
+        });
         Self {
             api_version,
             chainspec_bytes,
+            chainspec: parsed_chainspec,
+            chainspec_hash: chainspec.hash(),
         }
     }
 }
@@ -386,7 +497,87 @@ impl RpcWithoutParams for GetChainspec {
         api_version: ProtocolVersion,
     ) -> Result<Self::ResponseResult, Error> {
         let chainspec_bytes = effect_builder.get_chainspec_raw_bytes().await;
-        let result = Self::ResponseResult::new(api_version, (*chainspec_bytes).clone());
+        let chainspec = effect_builder.get_chainspec().await;
+        let result = Self::ResponseResult::new(api_version, (*chainspec_bytes).clone(), &chainspec);
         Ok(result)
     }
 }
+
+/// The status of a deploy waiting in the node's buffer to be proposed in a block.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonPendingDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Whether the deploy is a native transfer.
+    pub is_transfer: bool,
+    /// The account which created the deploy.
+    pub account: PublicKey,
+    /// When the node added the deploy to its buffer.
+    pub received: Timestamp,
+    /// How long the deploy has been in the buffer.
+    pub age: TimeDiff,
+    /// When the deploy expires.
+    pub expires: Timestamp,
+    /// Whether all of the deploy's dependencies have been finalized.
+    pub dependencies_resolved: bool,
+}
+
+impl JsonPendingDeploy {
+    fn new(status: PendingDeployStatus, now: Timestamp) -> Self {
+        JsonPendingDeploy {
+            deploy_hash: status.deploy_hash,
+            is_transfer: status.is_transfer,
+            account: status.account,
+            received: status.received,
+            age: now.saturating_diff(status.received),
+            expires: status.expires,
+            dependencies_resolved: status.dependencies_resolved,
+        }
+    }
+}
+
+/// Result for the "info_get_pending_deploys" RPC.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys and transfers waiting to be proposed, oldest first.
+    pub deploys: Vec<JsonPendingDeploy>,
+}
+
+impl DocExample for GetPendingDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_pending_deploys" RPC.
+///
+/// Only available if enabled via `enable_operator_rpcs` in the node's config.
+pub struct GetPendingDeploys {}
+
+#[async_trait]
+impl RpcWithoutParams for GetPendingDeploys {
+    const METHOD: &'static str = "info_get_pending_deploys";
+    type ResponseResult = GetPendingDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let now = Timestamp::now();
+        let deploys = effect_builder
+            .get_pending_deploys()
+            .await
+            .into_iter()
+            .map(|status| JsonPendingDeploy::new(status, now))
+            .collect();
+        Ok(Self::ResponseResult {
+            api_version,
+            deploys,
+        })
+    }
+}
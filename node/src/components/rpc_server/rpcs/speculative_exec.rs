@@ -149,6 +149,7 @@ impl RpcWithParams for SpeculativeExec {
                     | EngineStateError::Mint(_)
                     | EngineStateError::InvalidKeyVariant
                     | EngineStateError::ProtocolUpgrade(_)
+                    | EngineStateError::Migration(_)
                     | EngineStateError::CommitError(_)
                     | EngineStateError::MissingSystemContractRegistry
                     | EngineStateError::MissingSystemContractHash(_)
@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
 use casper_execution_engine::{
-    core::engine_state::{BalanceResult, GetBidsResult, QueryResult},
+    core::engine_state::{BalanceResult, GetBidsResult, QueryResult, ViewRequest, ViewResult},
     storage::trie::merkle_proof::TrieMerkleProof,
 };
 use casper_hashing::Digest;
@@ -20,8 +20,8 @@ use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    runtime_args, CLValue, ContractHash, Key, ProtocolVersion, PublicKey, RuntimeArgs, SecretKey,
+    StoredValue as DomainStoredValue, URef, U512,
 };
 
 use crate::{
@@ -35,7 +35,7 @@ use crate::{
     },
     types::{
         json_compatibility::{Account as JsonAccount, AuctionState, StoredValue},
-        Block, BlockHash, JsonBlockHeader,
+        Block, BlockHash, BlockHeader, JsonBlockHeader,
     },
 };
 
@@ -127,6 +127,35 @@ static QUERY_BALANCE_RESULT: Lazy<QueryBalanceResult> = Lazy::new(|| QueryBalanc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     balance: U512::from(123_456),
 });
+static QUERY_BALANCE_DETAILS_PARAMS: Lazy<QueryBalanceDetailsParams> =
+    Lazy::new(|| QueryBalanceDetailsParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        purse_identifier: PurseIdentifier::MainPurseUnderAccountHash(AccountHash::new([9u8; 32])),
+    });
+static QUERY_BALANCE_DETAILS_RESULT: Lazy<QueryBalanceDetailsResult> =
+    Lazy::new(|| QueryBalanceDetailsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        total_balance: U512::from(123_456),
+        available_balance: U512::from(123_456),
+        holds: vec![],
+        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        block_hash: Some(*Block::doc_example().hash()),
+        block_height: Some(Block::doc_example().header().height()),
+    });
+static CALL_VIEW_PARAMS: Lazy<CallViewParams> = Lazy::new(|| CallViewParams {
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    contract_hash: ContractHash::new([7u8; 32]),
+    entry_point: "get_price".to_string(),
+    args: runtime_args! { "quantity" => 5u64 },
+});
+static CALL_VIEW_RESULT: Lazy<CallViewResult> = Lazy::new(|| CallViewResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    block_hash: *Block::doc_example().hash(),
+    value: CLValue::from_t(U512::from(123_456)).unwrap(),
+    cost: U512::from(45_120),
+});
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -909,83 +938,329 @@ impl RpcWithParams for QueryBalance {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
-        let state_root_hash = match params.state_identifier {
-            None => match effect_builder.get_highest_block_header_from_storage().await {
-                None => {
-                    return Err(Error::new(
-                        ErrorCode::NoSuchBlock,
-                        "query-balance failed to retrieve highest block header",
-                    ))
-                }
-                Some(block_header) => *block_header.state_root_hash(),
-            },
-            Some(state_identifier) => {
-                let (state_root_hash, _) =
-                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
-                        .await?;
-                state_root_hash
-            }
+        let (state_root_hash, _) =
+            get_balance_query_state(effect_builder, params.state_identifier).await?;
+        let purse_uref =
+            get_purse_uref(effect_builder, state_root_hash, params.purse_identifier).await?;
+        let balance_value = get_purse_balance(effect_builder, state_root_hash, purse_uref).await?;
+
+        let result = Self::ResponseResult {
+            api_version,
+            balance: balance_value,
         };
+        Ok(result)
+    }
+}
 
-        let purse_uref = match params.purse_identifier {
-            PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
-                let account = get_account(
-                    effect_builder,
-                    state_root_hash,
-                    account_public_key.to_account_hash(),
-                )
-                .await?;
-                account.main_purse()
-            }
-            PurseIdentifier::MainPurseUnderAccountHash(account_hash) => {
-                let account = get_account(effect_builder, state_root_hash, account_hash).await?;
-                account.main_purse()
-            }
-            PurseIdentifier::PurseUref(purse_uref) => purse_uref,
+/// Params for "query_balance_details" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBalanceDetailsParams {
+    /// The state identifier used for the query, if none is passed
+    /// the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The identifier to obtain the purse corresponding to balance query.
+    pub purse_identifier: PurseIdentifier,
+}
+
+impl DocExample for QueryBalanceDetailsParams {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BALANCE_DETAILS_PARAMS
+    }
+}
+
+/// A hold on part of a purse's balance, making it unavailable for transfers.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceHold {
+    /// The held amount in motes.
+    pub amount: U512,
+}
+
+/// Result for "query_balance_details" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBalanceDetailsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The total balance of the purse in motes.
+    pub total_balance: U512,
+    /// The part of the total balance not subject to any hold, in motes.
+    pub available_balance: U512,
+    /// The holds on the purse's balance.
+    ///
+    /// The mint does not place holds on purses yet, so this is currently always empty.
+    pub holds: Vec<BalanceHold>,
+    /// The state root hash the balance was read from.
+    pub state_root_hash: Digest,
+    /// The hash of the block with that state root hash, unless queried by state root hash.
+    pub block_hash: Option<BlockHash>,
+    /// The height of the block with that state root hash, unless queried by state root hash.
+    pub block_height: Option<u64>,
+}
+
+impl DocExample for QueryBalanceDetailsResult {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BALANCE_DETAILS_RESULT
+    }
+}
+
+/// "query_balance_details" RPC.
+pub struct QueryBalanceDetails {}
+
+#[async_trait]
+impl RpcWithParams for QueryBalanceDetails {
+    const METHOD: &'static str = "query_balance_details";
+    type RequestParams = QueryBalanceDetailsParams;
+    type ResponseResult = QueryBalanceDetailsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (state_root_hash, maybe_block_header) =
+            get_balance_query_state(effect_builder, params.state_identifier).await?;
+        let purse_uref =
+            get_purse_uref(effect_builder, state_root_hash, params.purse_identifier).await?;
+        let total_balance = get_purse_balance(effect_builder, state_root_hash, purse_uref).await?;
+
+        let holds: Vec<BalanceHold> = vec![];
+        let held = holds
+            .iter()
+            .fold(U512::zero(), |held, hold| held.saturating_add(hold.amount));
+
+        let result = Self::ResponseResult {
+            api_version,
+            total_balance,
+            available_balance: total_balance.saturating_sub(held),
+            holds,
+            state_root_hash,
+            block_hash: maybe_block_header.as_ref().map(BlockHeader::hash),
+            block_height: maybe_block_header.as_ref().map(BlockHeader::height),
         };
+        Ok(result)
+    }
+}
 
-        // Get the balance.
-        let balance_result = effect_builder
+/// Returns the state root hash to query a balance in, and the header of its block if known.
+///
+/// Uses the highest block if no state identifier is given.
+async fn get_balance_query_state<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    maybe_state_identifier: Option<GlobalStateIdentifier>,
+) -> Result<(Digest, Option<BlockHeader>), Error> {
+    match maybe_state_identifier {
+        None => match effect_builder.get_highest_block_header_from_storage().await {
+            None => Err(Error::new(
+                ErrorCode::NoSuchBlock,
+                "query-balance failed to retrieve highest block header",
+            )),
+            Some(block_header) => Ok((*block_header.state_root_hash(), Some(block_header))),
+        },
+        Some(state_identifier) => {
+            get_state_root_hash_and_optional_block_header(effect_builder, state_identifier).await
+        }
+    }
+}
+
+/// Returns the purse identified by `purse_identifier`.
+async fn get_purse_uref<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_identifier: PurseIdentifier,
+) -> Result<URef, Error> {
+    let purse_uref = match purse_identifier {
+        PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
+            let account = get_account(
+                effect_builder,
+                state_root_hash,
+                account_public_key.to_account_hash(),
+            )
+            .await?;
+            account.main_purse()
+        }
+        PurseIdentifier::MainPurseUnderAccountHash(account_hash) => {
+            let account = get_account(effect_builder, state_root_hash, account_hash).await?;
+            account.main_purse()
+        }
+        PurseIdentifier::PurseUref(purse_uref) => purse_uref,
+    };
+    Ok(purse_uref)
+}
+
+/// Returns the balance of `purse_uref` in motes.
+async fn get_purse_balance<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_uref: URef,
+) -> Result<U512, Error> {
+    let balance_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetBalance {
+                state_root_hash,
+                purse_uref,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    match balance_result {
+        Ok(BalanceResult::Success { motes, .. }) => Ok(motes),
+        Ok(BalanceResult::RootNotFound) => {
+            info!(
+                %state_root_hash,
+                %purse_uref,
+                "query-balance failed: root not found"
+            );
+            Err(Error::new(
+                ErrorCode::FailedToGetBalance,
+                format!(
+                    "root hash {} not found when querying for purse {}",
+                    state_root_hash, purse_uref
+                ),
+            ))
+        }
+        Err(error) => {
+            info!("query-balance failed to execute: {}", error);
+            Err(Error::new(
+                ErrorCode::GetBalanceFailedToExecute,
+                error.to_string(),
+            ))
+        }
+    }
+}
+
+/// Params for "state_call_view" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CallViewParams {
+    /// The block on top of which to call the view, if none is passed the tip of the chain will be
+    /// used.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The hash of the contract.
+    pub contract_hash: ContractHash,
+    /// The name of the view entry point.
+    pub entry_point: String,
+    /// The arguments to call the view with.
+    #[serde(default)]
+    pub args: RuntimeArgs,
+}
+
+impl DocExample for CallViewParams {
+    fn doc_example() -> &'static Self {
+        &*CALL_VIEW_PARAMS
+    }
+}
+
+/// Result for "state_call_view" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CallViewResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the view was called.
+    pub block_hash: BlockHash,
+    /// The value returned by the view.
+    pub value: CLValue,
+    /// The gas used by the view.
+    pub cost: U512,
+}
+
+impl DocExample for CallViewResult {
+    fn doc_example() -> &'static Self {
+        &*CALL_VIEW_RESULT
+    }
+}
+
+/// "state_call_view" RPC.
+pub struct CallView {}
+
+#[async_trait]
+impl RpcWithParams for CallView {
+    const METHOD: &'static str = "state_call_view";
+    type RequestParams = CallViewParams;
+    type ResponseResult = CallViewResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let CallViewParams {
+            block_identifier: maybe_block_id,
+            contract_hash,
+            entry_point,
+            args,
+        } = params;
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let view_request = ViewRequest::new(
+            *block.header().state_root_hash(),
+            block.header().protocol_version(),
+            block.header().timestamp().millis(),
+            block.header().height(),
+            block.header().era_id(),
+            contract_hash,
+            entry_point,
+            args,
+        );
+
+        let result = effect_builder
             .make_request(
-                |responder| RpcRequest::GetBalance {
-                    state_root_hash,
-                    purse_uref,
+                |responder| RpcRequest::CallView {
+                    view_request: Box::new(view_request),
                     responder,
                 },
                 QueueKind::Api,
             )
             .await;
 
-        let balance_value = match balance_result {
-            Ok(BalanceResult::Success { motes, .. }) => motes,
-            Ok(BalanceResult::RootNotFound) => {
-                info!(
-                    %state_root_hash,
-                    %purse_uref,
-                    "query-balance failed: root not found"
-                );
-                return Err(Error::new(
-                    ErrorCode::FailedToGetBalance,
-                    format!(
-                        "root hash {} not found when querying for purse {}",
-                        state_root_hash, purse_uref
-                    ),
-                ));
+        let error_msg = match result {
+            Ok(ViewResult::Success { value, cost }) => {
+                let result = Self::ResponseResult {
+                    api_version,
+                    block_hash,
+                    value,
+                    cost: cost.value(),
+                };
+                return Ok(result);
+            }
+            Ok(ViewResult::RootNotFound) => {
+                return Err(Error::new(ErrorCode::NoSuchStateRoot, ""));
             }
+            Ok(ViewResult::ContractNotFound) => format!("no contract under {}", contract_hash),
+            Ok(ViewResult::EntryPointNotFound(entry_point)) => {
+                format!("{} has no entry point {}", contract_hash, entry_point)
+            }
+            Ok(ViewResult::NotAView(entry_point)) => {
+                format!(
+                    "entry point {} of {} is not a view",
+                    entry_point, contract_hash
+                )
+            }
+            Ok(ViewResult::Failure { error, .. }) => format!("view failed: {}", error),
             Err(error) => {
-                info!("query-balance failed to execute: {}", error);
+                info!("state-call-view failed to execute: {}", error);
                 return Err(Error::new(
-                    ErrorCode::GetBalanceFailedToExecute,
+                    ReservedErrorCode::InternalError,
                     error.to_string(),
                 ));
             }
         };
-
-        let result = Self::ResponseResult {
-            api_version,
-            balance: balance_value,
-        };
-        Ok(result)
+        info!("{}", error_msg);
+        Err(Error::new(ErrorCode::FailedToCallView, error_msg))
     }
 }
 
@@ -1097,6 +1372,17 @@ pub(super) async fn run_query<REv: ReactorEventT>(
             .await;
             Err(error)
         }
+        Ok(QueryResult::CircularReference(message)) => {
+            info!(%message, "query failed: circular reference");
+            Err(Error::new(ErrorCode::QueryCircularReference, message))
+        }
+        Ok(QueryResult::DepthLimit { depth }) => {
+            info!(%depth, "query failed: depth limit reached");
+            Err(Error::new(
+                ErrorCode::QueryDepthLimitReached,
+                format!("query depth limit of {} reached", depth),
+            ))
+        }
         Ok(query_result) => {
             info!(?query_result, "query failed");
             Err(Error::new(
@@ -1140,6 +1426,18 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     state_identifier: GlobalStateIdentifier,
 ) -> Result<(Digest, Option<JsonBlockHeader>), Error> {
+    let (state_root_hash, maybe_block_header) =
+        get_state_root_hash_and_optional_block_header(effect_builder, state_identifier).await?;
+    Ok((
+        state_root_hash,
+        maybe_block_header.map(JsonBlockHeader::from),
+    ))
+}
+
+async fn get_state_root_hash_and_optional_block_header<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_identifier: GlobalStateIdentifier,
+) -> Result<(Digest, Option<BlockHeader>), Error> {
     // This RPC request is restricted by the block availability index.
     let only_from_available_block_range = true;
     match state_identifier {
@@ -1153,10 +1451,7 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                         format!("failed to retrieve specified block header {}", block_hash);
                     Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
                 }
-                Some(block_header) => {
-                    let json_block_header = JsonBlockHeader::from(block_header.clone());
-                    Ok((*block_header.state_root_hash(), Some(json_block_header)))
-                }
+                Some(block_header) => Ok((*block_header.state_root_hash(), Some(block_header))),
             }
         }
         GlobalStateIdentifier::BlockHeight(block_height) => {
@@ -1172,10 +1467,7 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                         format!("failed to retrieve block header at height {}", block_height);
                     Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
                 }
-                Some(block_header) => {
-                    let json_block_header = JsonBlockHeader::from(block_header.clone());
-                    Ok((*block_header.state_root_hash(), Some(json_block_header)))
-                }
+                Some(block_header) => Ok((*block_header.state_root_hash(), Some(block_header))),
             }
         }
         GlobalStateIdentifier::StateRootHash(state_root_hash) => Ok((state_root_hash, None)),
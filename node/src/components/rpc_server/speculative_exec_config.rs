@@ -1,6 +1,9 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use super::config::RequestLimitsConfig;
+use crate::utils::http_server::{CorsConfig, TlsConfig};
+
 /// Default binding address for the speculative execution RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -23,6 +26,15 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Additional limits applied to incoming requests.
+    #[serde(default)]
+    pub request_limits: RequestLimitsConfig,
+    /// TLS settings.
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// CORS policy.
+    #[serde(default)]
+    pub cors: CorsConfig,
 }
 
 impl Config {
@@ -33,6 +45,9 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            request_limits: RequestLimitsConfig::default(),
+            tls: TlsConfig::default(),
+            cors: CorsConfig::default(),
         }
     }
 }
@@ -1,12 +1,13 @@
-use hyper::server::{conn::AddrIncoming, Builder};
+use std::sync::Arc;
 
 use casper_json_rpc::RequestHandlersBuilder;
 use casper_types::ProtocolVersion;
 
-use super::ReactorEventT;
+use super::{request_limiter::RequestLimiter, ReactorEventT};
 use crate::{
     effect::EffectBuilder,
-    rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    rpcs::{speculative_exec::SpeculativeExec, state::CallView, RpcWithParams},
+    utils::http_server::HttpIncoming,
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -16,19 +17,24 @@ pub const SPECULATIVE_EXEC_SERVER_NAME: &str = "speculative execution";
 
 /// Run the speculative execution server.
 pub(super) async fn run<REv: ReactorEventT>(
-    builder: Builder<AddrIncoming>,
+    incoming: HttpIncoming,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
+    limiter: Arc<RequestLimiter>,
+    cors: warp::cors::Builder,
     qps_limit: u64,
     max_body_bytes: u32,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExec::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    CallView::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
     let handlers = handlers.build();
 
     super::rpcs::run(
-        builder,
+        incoming,
         handlers,
+        limiter,
+        cors,
         qps_limit,
         max_body_bytes,
         SPECULATIVE_EXEC_API_PATH,
@@ -23,18 +23,24 @@
 //! Nodes gossip their public listening addresses periodically, and will try to establish and
 //! maintain an outgoing connection to any new address learned.
 
+mod access_control;
+mod attestation;
+mod backoff_state;
 mod bincode_format;
 mod chain_info;
+mod clock_drift;
 mod config;
 mod counting_format;
+mod diversity;
 mod error;
 mod event;
-mod gossiped_address;
 mod limiter;
 mod message;
 mod message_pack_format;
 mod metrics;
+mod nat;
 mod outgoing;
+mod peer_record;
 mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
@@ -45,11 +51,11 @@ use std::{
     convert::Infallible,
     fmt::{self, Debug, Display, Formatter},
     io,
-    net::{SocketAddr, TcpListener},
+    net::{IpAddr, SocketAddr, TcpListener},
     result,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Weak,
+        Arc, RwLock, Weak,
     },
     time::{Duration, Instant},
 };
@@ -74,32 +80,39 @@ use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
-use casper_types::{EraId, PublicKey};
+use casper_types::{EraId, PublicKey, TimeDiff, Timestamp};
 
-pub(crate) use self::{
-    bincode_format::BincodeFormat,
-    config::Config,
-    error::Error,
-    event::Event,
-    gossiped_address::GossipedAddress,
-    message::{EstimatorWeights, FromIncoming, Message, MessageKind, Payload},
-};
 use self::{
+    access_control::AllowList,
     chain_info::ChainInfo,
+    clock_drift::ClockOffsets,
     config::IdentityConfig,
     counting_format::{ConnectionId, CountingFormat, Role},
+    diversity::DiversityLimits,
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
     limiter::Limiter,
     message::ConsensusKeyPair,
     metrics::Metrics,
+    nat::{NatError, ObservedAddresses, PortMapping},
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    peer_record::{PeerRecordError, MAX_PEER_RECORD_ADDRESSES},
     symmetry::ConnectionSymmetry,
     tasks::{MessageQueueItem, NetworkContext},
 };
+pub(crate) use self::{
+    attestation::NodeAttestation,
+    bincode_format::BincodeFormat,
+    config::Config,
+    diversity::DiversityStats,
+    error::Error,
+    event::Event,
+    message::{EstimatorWeights, FromIncoming, Message, MessageKind, Payload},
+    peer_record::PeerRecord,
+};
 
 use crate::{
-    components::{consensus, Component},
+    components::{consensus, Component, HealthCheck},
     effect::{
         announcements::{
             BlocklistAnnouncement, ChainSynchronizerAnnouncement, ContractRuntimeAnnouncement,
@@ -112,7 +125,7 @@ use crate::{
         self, validate_cert_with_authority, LoadCertError, LoadSecretKeyError, TlsCert,
         ValidationError,
     },
-    types::NodeId,
+    types::{Health, NodeId},
     utils::{self, display_error, Source, WithDir},
     NodeRng,
 };
@@ -132,6 +145,12 @@ const BASE_RECONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
 /// Interval during which to perform outgoing manager housekeeping.
 const OUTGOING_MANAGER_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
 
+/// How far in the future a received peer record may be timestamped, to allow for clock skew.
+const MAX_PEER_RECORD_CLOCK_SKEW: TimeDiff = TimeDiff::from_seconds(30);
+
+/// Minimum delay before renewing a port mapping or retrying a failed one.
+const MIN_PORT_MAPPING_RENEWAL_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
@@ -164,6 +183,10 @@ where
     /// Tracks nodes that have announced themselves as nodes that are syncing.
     syncing_nodes: HashSet<NodeId>,
 
+    /// Tracks nodes that have announced they are about to disconnect, e.g. because they are
+    /// draining.  They are no longer picked for gossiping or offered as fully connected peers.
+    disconnecting_nodes: HashSet<NodeId>,
+
     /// Channel signaling a shutdown of the small network.
     // Note: This channel is closed when `SmallNetwork` is dropped, signalling the receivers that
     // they should cease operation.
@@ -200,6 +223,31 @@ where
 
     /// The era that is considered the active era by the small network component.
     active_era: EraId,
+
+    /// Whether the node is draining, in which case we no longer advertise our address.
+    is_draining: bool,
+
+    /// Address the listener is bound to.
+    local_addr: SocketAddr,
+    /// Mapping of our listening port on the local gateway, if NAT traversal succeeded.
+    port_mapping: Option<PortMapping>,
+    /// IP addresses peers observed our connections to originate from.
+    #[data_size(skip)]
+    observed_addresses: ObservedAddresses,
+    /// Offsets of our clock from the clocks of peers we are connected to.
+    #[data_size(skip)]
+    clock_offsets: ClockOffsets,
+    /// Whether our clock was last found to drift too far from the connected peers' clocks.
+    is_clock_drifting: bool,
+
+    /// Further public addresses advertised after our public address.
+    additional_public_addrs: Vec<SocketAddr>,
+    /// The most recent valid peer record received for each node.
+    peer_records: HashMap<NodeId, PeerRecord>,
+    /// The peer record we last gossiped, renewed once our addresses change or it gets old.
+    our_record: Option<PeerRecord>,
+    /// The reconnection backoff of unreachable addresses last written to disk.
+    backoff_states: BTreeMap<SocketAddr, u8>,
 }
 
 impl<REv, P> SmallNetwork<REv, P>
@@ -262,19 +310,50 @@ where
                 ))
             };
 
-        let outgoing_manager = OutgoingManager::with_metrics(
+        let mut outgoing_manager = OutgoingManager::with_metrics(
             OutgoingConfig {
                 retry_attempts: RECONNECTION_ATTEMPTS,
                 base_timeout: BASE_RECONNECTION_TIMEOUT,
+                jitter: cfg.reconnect_backoff.jitter,
+                jitter_seed: rand::random(),
                 unblock_after: cfg.blocklist_retain_duration.into(),
                 sweep_timeout: cfg.max_addr_pending_time.into(),
+                diversity: DiversityLimits::from_config(&cfg.peer_diversity)?,
             },
             net_metrics.create_outgoing_metrics(),
         );
 
+        // Retained backoff only delays reconnecting, so failing to load it is not fatal.
+        let mut backoff_states = BTreeMap::new();
+        if let Some(path) = &cfg.reconnect_backoff.state_path {
+            match backoff_state::load(path) {
+                Ok(loaded) => backoff_states = loaded,
+                Err(error) => warn!(
+                    path = %path.display(),
+                    err = display_error(&error),
+                    "could not load reconnection backoff, ignoring"
+                ),
+            }
+        }
+        outgoing_manager.restore_backoff_states(backoff_states.clone());
+
         let mut public_addr =
             utils::resolve_address(&cfg.public_address).map_err(Error::ResolveAddr)?;
 
+        if cfg.additional_public_addresses.len() >= MAX_PEER_RECORD_ADDRESSES {
+            warn!(
+                count = cfg.additional_public_addresses.len(),
+                "too many additional public addresses, only the first {} will be advertised",
+                MAX_PEER_RECORD_ADDRESSES - 1
+            );
+        }
+        let additional_public_addrs = cfg
+            .additional_public_addresses
+            .iter()
+            .take(MAX_PEER_RECORD_ADDRESSES - 1)
+            .map(|address| utils::resolve_address(address).map_err(Error::ResolveAddr))
+            .collect::<Result<Vec<_>>>()?;
+
         // We can now create a listener.
         let bind_address = utils::resolve_address(&cfg.bind_address).map_err(Error::ResolveAddr)?;
         let listener = TcpListener::bind(bind_address)
@@ -299,7 +378,7 @@ where
             })
             .transpose()
             .map_err(Error::LoadConsensusKeys)?
-            .map(|(secret_key, public_key)| ConsensusKeyPair::new(secret_key, public_key));
+            .map(|(signer, public_key)| ConsensusKeyPair::new(signer, public_key));
 
         // Set the demand max from configuration, regarding `0` as "unlimited".
         let demand_max = if cfg.max_in_flight_demands == 0 {
@@ -328,6 +407,15 @@ where
             None => None,
         };
 
+        // Load the allow list if running in permissioned mode.
+        let allow_list = if cfg.access_control.enabled {
+            Some(RwLock::new(AllowList::load(
+                &cfg.access_control.allow_list,
+            )?))
+        } else {
+            None
+        };
+
         let chain_info = chain_info_source.into();
         let protocol_version = chain_info.protocol_version;
         let context = Arc::new(NetworkContext {
@@ -338,7 +426,7 @@ where
             secret_key: small_network_identity.secret_key,
             net_metrics: Arc::downgrade(&net_metrics),
             chain_info,
-            public_addr,
+            public_addr: RwLock::new(public_addr),
             consensus_keys,
             handshake_timeout: cfg.handshake_timeout,
             payload_weights: cfg.estimator_weights.clone(),
@@ -347,6 +435,7 @@ where
             tarpit_chance: cfg.tarpit_chance,
             max_in_flight_demands: demand_max,
             is_syncing: AtomicBool::new(true),
+            allow_list,
         });
 
         // Run the server task.
@@ -372,6 +461,7 @@ where
             outgoing_manager,
             connection_symmetries: HashMap::new(),
             syncing_nodes: HashSet::new(),
+            disconnecting_nodes: HashSet::new(),
             shutdown_sender: Some(server_shutdown_sender),
             close_incoming_sender: Some(close_incoming_sender),
             close_incoming_receiver,
@@ -381,6 +471,16 @@ where
             incoming_limiter,
             // We start with an empty set of validators for era 0 and expect to be updated.
             active_era: EraId::new(0),
+            is_draining: false,
+            local_addr,
+            port_mapping: None,
+            observed_addresses: ObservedAddresses::default(),
+            clock_offsets: ClockOffsets::default(),
+            is_clock_drifting: false,
+            additional_public_addrs,
+            peer_records: HashMap::new(),
+            our_record: None,
+            backoff_states,
         };
 
         let effect_builder = EffectBuilder::new(event_queue);
@@ -408,9 +508,239 @@ where
                 .event(|_| Event::SweepOutgoing),
         );
 
+        if component.cfg.nat_traversal.enabled {
+            effects.extend(component.map_port());
+        }
+
+        if component.cfg.access_control.enabled {
+            effects.extend(
+                effect_builder
+                    .set_timeout(component.cfg.access_control.reload_interval.into())
+                    .event(|_| Event::ReloadAllowList),
+            );
+        }
+
         Ok((component, effects))
     }
 
+    /// Attempts to map our listening port on the local gateway.
+    fn map_port(&self) -> Effects<Event<P>> {
+        nat::map_port(self.cfg.nat_traversal.clone(), self.local_addr.port()).event(|result| {
+            Event::PortMapped {
+                result: Box::new(result),
+            }
+        })
+    }
+
+    /// Records a new or renewed port mapping, scheduling its renewal.
+    fn handle_port_mapped(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        result: result::Result<PortMapping, NatError>,
+    ) -> Effects<Event<P>> {
+        let renew_after = match result {
+            Ok(mapping) => {
+                info!(%mapping, "mapped listening port on gateway");
+                let renew_after = mapping.lifetime / 2;
+                self.port_mapping = Some(mapping);
+                self.advertise_verified_mapping();
+                renew_after
+            }
+            Err(error) => {
+                warn!(
+                    err = display_error(&error),
+                    public_addr = %self.context.public_addr(),
+                    "could not map listening port on gateway"
+                );
+                self.port_mapping = None;
+                self.cfg.nat_traversal.mapping_lifetime.into()
+            }
+        };
+
+        effect_builder
+            .set_timeout(renew_after.max(MIN_PORT_MAPPING_RENEWAL_INTERVAL))
+            .event(|_| Event::RenewPortMapping)
+    }
+
+    /// Reloads the allow list, disconnecting all incoming connections if it changed.
+    ///
+    /// Since we only ever read from incoming connections, closing them ensures no peer removed from
+    /// the allow list can send us any further messages. Permitted peers will reconnect.
+    fn reload_allow_list(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<P>> {
+        let path = &self.cfg.access_control.allow_list;
+        match AllowList::load(path) {
+            Ok(reloaded) => {
+                if let Some(allow_list) = &self.context.allow_list {
+                    let mut allow_list = allow_list.write().expect("allow list lock poisoned");
+                    if !allow_list.is_same_as(&reloaded) {
+                        info!(path = %path.display(), "allow list changed");
+                        *allow_list = reloaded;
+                        drop(allow_list);
+                        self.close_incoming_connections();
+                    }
+                }
+            }
+            Err(error) => {
+                warn!(
+                    path = %path.display(),
+                    err = display_error(&error),
+                    "could not reload allow list, keeping the previous one"
+                );
+            }
+        }
+
+        effect_builder
+            .set_timeout(self.cfg.access_control.reload_interval.into())
+            .event(|_| Event::ReloadAllowList)
+    }
+
+    /// Learns the addresses of a valid peer record received via gossip, unless the record is
+    /// outdated or meant for a different protocol version.
+    fn handle_peer_record(&mut self, peer_record: PeerRecord) -> Effects<Event<P>> {
+        let node_id = peer_record.node_id();
+        if node_id == self.context.our_id {
+            return Effects::new();
+        }
+        let protocol_version = self.context.chain_info.protocol_version;
+        if peer_record.protocol_version() != protocol_version {
+            debug!(
+                %peer_record,
+                %protocol_version,
+                "ignoring peer record of other protocol version"
+            );
+            return Effects::new();
+        }
+        let now = Timestamp::now();
+        if peer_record.timestamp() > now + MAX_PEER_RECORD_CLOCK_SKEW {
+            debug!(%peer_record, "ignoring peer record from the future");
+            return Effects::new();
+        }
+        if now.saturating_diff(peer_record.timestamp()) > self.cfg.peer_record_max_age {
+            debug!(%peer_record, "ignoring expired peer record");
+            return Effects::new();
+        }
+        if let Some(known_record) = self.peer_records.get(&node_id) {
+            if known_record.timestamp() >= peer_record.timestamp() {
+                return Effects::new();
+            }
+        }
+        self.peer_records.insert(node_id, peer_record);
+
+        let now = Instant::now();
+        let requests: Vec<_> = peer_record
+            .addresses()
+            .filter_map(|addr| self.outgoing_manager.learn_addr(addr, false, now))
+            .collect();
+        self.process_dial_requests(requests)
+    }
+
+    /// Forgets all peer records older than the configured maximum age.
+    fn prune_peer_records(&mut self) {
+        let now = Timestamp::now();
+        let max_age = self.cfg.peer_record_max_age;
+        self.peer_records
+            .retain(|_, peer_record| now.saturating_diff(peer_record.timestamp()) <= max_age);
+    }
+
+    /// Returns our peer record, creating a new one if our addresses changed or the previous one is
+    /// half way to expiring.
+    fn our_record(&mut self) -> result::Result<PeerRecord, PeerRecordError> {
+        let mut addresses = vec![self.context.public_addr()];
+        addresses.extend(self.additional_public_addrs.iter().copied());
+
+        let now = Timestamp::now();
+        if let Some(our_record) = self.our_record {
+            if our_record.addresses().eq(addresses.iter().copied())
+                && now.saturating_diff(our_record.timestamp()) < self.cfg.peer_record_max_age / 2
+            {
+                return Ok(our_record);
+            }
+        }
+
+        let our_record = PeerRecord::new(
+            self.context.our_id,
+            &addresses,
+            now,
+            self.context.chain_info.protocol_version,
+            &self.context.secret_key,
+        )?;
+        self.our_record = Some(our_record);
+        Ok(our_record)
+    }
+
+    /// Records that `peer_id` observed our connection to originate from `observed_ip`.
+    fn record_observed_ip(&mut self, peer_id: NodeId, observed_ip: IpAddr) {
+        let confirmations = self.observed_addresses.record(peer_id, observed_ip);
+        let public_addr = self.context.public_addr();
+        if self.port_mapping.is_none()
+            && observed_ip != public_addr.ip()
+            && confirmations == self.cfg.nat_traversal.required_confirmations as usize
+        {
+            warn!(
+                %observed_ip,
+                %public_addr,
+                confirmations,
+                "peers observe us at a different IP than our public address, which is likely \
+                misconfigured"
+            );
+        }
+        self.advertise_verified_mapping();
+    }
+
+    /// Re-estimates the drift of our clock from the connected peers' clocks, warning once it
+    /// exceeds the configured maximum.
+    fn update_clock_drift(&mut self) {
+        let config = &self.cfg.clock_drift;
+        let offset = match self.clock_offsets.estimate(config.min_peers as usize) {
+            Some(offset) => offset,
+            None => return,
+        };
+        self.net_metrics.clock_offset.set(offset);
+
+        let max_offset = config.max_offset;
+        let is_drifting = offset.unsigned_abs() > max_offset.millis();
+        if is_drifting && !self.is_clock_drifting {
+            warn!(
+                offset_ms = offset,
+                %max_offset,
+                "local clock drifts from the connected peers' clocks, check time synchronization"
+            );
+        } else if !is_drifting && self.is_clock_drifting {
+            info!(
+                offset_ms = offset,
+                "local clock is back in sync with the connected peers' clocks"
+            );
+        }
+        self.is_clock_drifting = is_drifting;
+    }
+
+    /// Starts advertising the mapped address once enough distinct peers have confirmed its IP.
+    fn advertise_verified_mapping(&self) {
+        let external_addr = match self.port_mapping {
+            Some(ref mapping) => mapping.external_addr,
+            None => return,
+        };
+        if self.context.public_addr() == external_addr {
+            return;
+        }
+
+        let confirmations = self.observed_addresses.confirmations(external_addr.ip());
+        if confirmations >= self.cfg.nat_traversal.required_confirmations as usize {
+            info!(
+                %external_addr,
+                confirmations,
+                "peers confirmed mapped address, advertising it as our public address"
+            );
+            self.context.set_public_addr(external_addr);
+        } else {
+            debug!(
+                %external_addr,
+                confirmations,
+                "waiting for peers to confirm mapped address"
+            );
+        }
+    }
+
     fn close_incoming_connections(&mut self) {
         info!("disconnecting incoming connections");
         let (close_incoming_sender, close_incoming_receiver) = watch::channel(());
@@ -437,7 +767,9 @@ where
         let peer_ids = self
             .outgoing_manager
             .connected_peers()
-            .filter(|peer_id| !exclude.contains(peer_id))
+            .filter(|peer_id| {
+                !exclude.contains(peer_id) && !self.disconnecting_nodes.contains(peer_id)
+            })
             .choose_multiple(rng, count);
 
         if peer_ids.len() != count {
@@ -644,6 +976,9 @@ where
             | ConnectionError::HandshakeRecv(_)
             | ConnectionError::IncompatibleVersion(_) => false,
 
+            // The peer may be permitted once the allow list is reloaded.
+            ConnectionError::PeerNotAllowed(_) => false,
+
             // These errors are potential bugs on our side.
             ConnectionError::HandshakeSenderCrashed(_)
             | ConnectionError::FailedToReuniteHandshakeSinkAndStream
@@ -715,9 +1050,21 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                observed_ip,
+                clock_offset,
             } => {
                 info!("new outgoing connection established");
 
+                if let Some(observed_ip) = observed_ip {
+                    self.record_observed_ip(peer_id, observed_ip);
+                }
+
+                if let Some(clock_offset) = clock_offset {
+                    debug!(clock_offset_ms = clock_offset, "measured clock offset");
+                    self.clock_offsets.record(peer_id, clock_offset);
+                    self.update_clock_drift();
+                }
+
                 let (sender, receiver) = mpsc::unbounded_channel();
                 let handle = OutgoingHandle { sender, peer_addr };
 
@@ -767,8 +1114,7 @@ where
         peer_id: NodeId,
         peer_addr: SocketAddr,
     ) -> Effects<Event<P>> {
-        let requests = self
-            .outgoing_manager
+        self.outgoing_manager
             .handle_connection_drop(peer_addr, Instant::now());
 
         self.connection_symmetries
@@ -776,7 +1122,35 @@ where
             .or_default()
             .unmark_outgoing(Instant::now());
 
-        self.process_dial_requests(requests)
+        self.clock_offsets.remove(&peer_id);
+        self.update_clock_drift();
+
+        // Should the peer reconnect, it will be back from whatever made it disconnect.
+        self.disconnecting_nodes.remove(&peer_id);
+
+        Effects::new()
+    }
+
+    /// Writes the backoff of consistently unreachable addresses to disk, if configured and changed.
+    fn store_backoff_states(&mut self) {
+        let path = match &self.cfg.reconnect_backoff.state_path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let backoff_states = self.outgoing_manager.backoff_states();
+        if backoff_states == self.backoff_states {
+            return;
+        }
+
+        if let Err(error) = backoff_state::store(path, &backoff_states) {
+            warn!(
+                path = %path.display(),
+                err = display_error(&error),
+                "could not store reconnection backoff"
+            );
+        }
+        self.backoff_states = backoff_states;
     }
 
     /// Processes a set of `DialRequest`s, updating the component and emitting needed effects.
@@ -831,6 +1205,11 @@ where
             Message::Payload(payload) => {
                 effect_builder.announce_incoming(peer_id, payload).ignore()
             }
+            Message::Disconnecting => {
+                info!("peer announced it is about to disconnect");
+                self.disconnecting_nodes.insert(peer_id);
+                Effects::new()
+            }
         })
     }
 
@@ -881,6 +1260,17 @@ where
     pub(crate) fn node_id(&self) -> NodeId {
         self.context.our_id
     }
+
+    /// Puts the networking component into drain mode.
+    ///
+    /// All connected peers are told that we are about to disconnect, and our public address is no
+    /// longer gossiped, so peers stop learning about a node that is about to disappear. Existing
+    /// connections are kept open until the node exits.
+    pub(crate) fn begin_drain(&mut self) {
+        info!("announcing imminent disconnect to peers, node is draining");
+        self.broadcast_message(Arc::new(Message::Disconnecting));
+        self.is_draining = true;
+    }
 }
 
 impl<REv, P> Finalize for SmallNetwork<REv, P>
@@ -915,7 +1305,7 @@ impl<REv, P> Component<REv> for SmallNetwork<REv, P>
 where
     REv: ReactorEvent
         + From<Event<P>>
-        + From<BeginGossipRequest<GossipedAddress>>
+        + From<BeginGossipRequest<PeerRecord>>
         + FromIncoming<P>
         + From<StorageRequest>
         + From<NetworkRequest<P>>,
@@ -1010,6 +1400,7 @@ where
                         .filter_map(|(node_id, sym)| {
                             matches!(sym, ConnectionSymmetry::Symmetric { .. }).then(|| *node_id)
                         })
+                        .filter(|node_id| !self.disconnecting_nodes.contains(node_id))
                         .collect();
 
                     symmetric_peers.shuffle(rng);
@@ -1023,22 +1414,37 @@ where
                         .filter_map(|(node_id, sym)| {
                             matches!(sym, ConnectionSymmetry::Symmetric { .. }).then(|| *node_id)
                         })
-                        .filter(|node_id| !self.syncing_nodes.contains(node_id))
+                        .filter(|node_id| {
+                            !self.syncing_nodes.contains(node_id)
+                                && !self.disconnecting_nodes.contains(node_id)
+                        })
                         .collect();
 
                     symmetric_validator_peers.shuffle(rng);
 
                     responder.respond(symmetric_validator_peers).ignore()
                 }
+                NetworkInfoRequest::DiversityStats { responder } => responder
+                    .respond(self.outgoing_manager.diversity_stats())
+                    .ignore(),
+                NetworkInfoRequest::Attest { data, responder } => {
+                    let our_id = self.context.our_id;
+                    let secret_key = self.context.secret_key.clone();
+                    let consensus_keys = self.context.consensus_keys.clone();
+                    async move {
+                        let attestation = NodeAttestation::create(
+                            our_id,
+                            &secret_key,
+                            consensus_keys.as_ref(),
+                            &data,
+                        )
+                        .await;
+                        responder.respond(attestation).await
+                    }
+                    .ignore()
+                }
             },
-            Event::PeerAddressReceived(gossiped_address) => {
-                let requests = self.outgoing_manager.learn_addr(
-                    gossiped_address.into(),
-                    false,
-                    Instant::now(),
-                );
-                self.process_dial_requests(requests)
-            }
+            Event::PeerRecordReceived(peer_record) => self.handle_peer_record(peer_record),
             Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted(peer_id)) => {
                 // TODO: We do not have a proper by-node-ID blocklist, but rather only block the
                 // current outgoing address of a peer.
@@ -1101,11 +1507,19 @@ where
             }
 
             Event::GossipOurAddress => {
-                let our_address = GossipedAddress::new(self.context.public_addr);
+                if self.is_draining {
+                    return Effects::new();
+                }
 
-                let mut effects = effect_builder
-                    .begin_gossip(our_address, Source::Ourself)
-                    .ignore();
+                let mut effects = match self.our_record() {
+                    Ok(our_record) => effect_builder
+                        .begin_gossip(our_record, Source::Ourself)
+                        .ignore(),
+                    Err(error) => {
+                        warn!(%error, "could not create our peer record");
+                        Effects::new()
+                    }
+                };
                 effects.extend(
                     effect_builder
                         .set_timeout(self.cfg.gossip_interval.into())
@@ -1116,6 +1530,8 @@ where
             Event::SweepOutgoing => {
                 let now = Instant::now();
                 let requests = self.outgoing_manager.perform_housekeeping(now);
+                self.prune_peer_records();
+                self.store_backoff_states();
 
                 let mut effects = self.process_dial_requests(requests);
 
@@ -1132,7 +1548,40 @@ where
                 self.close_incoming_connections();
                 Effects::new()
             }
+            Event::PortMapped { result } => self.handle_port_mapped(effect_builder, *result),
+            Event::RenewPortMapping => self.map_port(),
+            Event::ReloadAllowList => self.reload_allow_list(effect_builder),
+        }
+    }
+}
+
+impl<REv, P> HealthCheck for SmallNetwork<REv, P>
+where
+    P: Payload + 'static,
+    REv: ReactorEvent
+        + From<Event<P>>
+        + FromIncoming<P>
+        + From<StorageRequest>
+        + From<NetworkRequest<P>>,
+{
+    const NAME: &'static str = "network";
+
+    fn health(&self) -> Health {
+        let mut health = Health::ready();
+        if self
+            .server_join_handle
+            .as_ref()
+            .map_or(true, JoinHandle::is_finished)
+        {
+            health.fail("not accepting incoming connections");
+        }
+        if self.peers().is_empty() {
+            health.degrade("no connected peers");
+        }
+        if self.is_clock_drifting {
+            health.degrade("local clock drifts from the connected peers' clocks");
         }
+        health
     }
 }
 
@@ -1231,6 +1680,7 @@ pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDel
 fn full_transport<P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
+    peer_id: NodeId,
     framed: FramedTransport,
     role: Role,
 ) -> FullTransport<P>
@@ -1240,7 +1690,13 @@ where
 {
     tokio_serde::Framed::new(
         framed,
-        CountingFormat::new(metrics, connection_id, role, BincodeFormat::default()),
+        CountingFormat::new(
+            metrics,
+            connection_id,
+            peer_id,
+            role,
+            BincodeFormat::default(),
+        ),
     )
 }
 
@@ -1263,7 +1719,7 @@ where
         // otherwise.
         f.debug_struct("SmallNetwork")
             .field("our_id", &self.context.our_id)
-            .field("public_addr", &self.context.public_addr)
+            .field("public_addr", &self.context.public_addr())
             .finish()
     }
 }
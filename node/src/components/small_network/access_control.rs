@@ -0,0 +1,234 @@
+//! Allow-listed network access.
+//!
+//! In permissioned mode, a peer is only admitted if its certificate is signed by one of the
+//! certificate authorities in the allow list, or if its node ID is listed explicitly. The check is
+//! performed right after the TLS handshake, before any message is exchanged.
+
+use std::{
+    collections::BTreeSet,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+
+use openssl::x509::X509;
+use serde::Deserialize;
+use thiserror::Error;
+
+use casper_types::file_utils::{self, ReadFileError};
+
+use crate::{
+    tls::{self, CertFingerprint, KeyFingerprint, LoadCertError, TlsCert, ValidationError},
+    types::NodeId,
+};
+
+/// The allow list file, as read from disk.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllowListFile {
+    /// Paths to certificate authorities, relative to the allow list.
+    #[serde(default)]
+    ca_certificates: Vec<PathBuf>,
+    /// Hex-encoded node IDs.
+    #[serde(default)]
+    node_ids: Vec<String>,
+}
+
+/// Error loading the allow list.
+#[derive(Debug, Error)]
+pub enum AllowListError {
+    /// Failed to read the allow list.
+    #[error(transparent)]
+    ReadFile(#[from] ReadFileError),
+    /// Failed to parse the allow list.
+    #[error("could not parse allow list: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// Failed to load a certificate authority.
+    #[error("could not load certificate authority {}: {source}", path.display())]
+    LoadCertificate {
+        path: PathBuf,
+        #[source]
+        source: LoadCertError,
+    },
+    /// Failed to compute the fingerprint of a certificate authority.
+    #[error("invalid certificate authority {}: {source}", path.display())]
+    InvalidCertificate {
+        path: PathBuf,
+        #[source]
+        source: ValidationError,
+    },
+    /// A listed node ID is not a valid hex-encoded fingerprint.
+    #[error("invalid node ID {0:?}")]
+    InvalidNodeId(String),
+}
+
+/// The peers permitted to connect in permissioned mode.
+pub(crate) struct AllowList {
+    /// Certificate authorities whose signed certificates are admitted.
+    authorities: Vec<X509>,
+    /// Fingerprints of `authorities`, used to detect changes.
+    authority_fingerprints: BTreeSet<CertFingerprint>,
+    /// Node IDs admitted regardless of their certificate's issuer.
+    node_ids: BTreeSet<NodeId>,
+}
+
+impl AllowList {
+    /// Loads the allow list from `path`.
+    pub(super) fn load(path: &Path) -> Result<Self, AllowListError> {
+        let bytes = file_utils::read_file(path)?;
+        let file: AllowListFile = toml::from_slice(&bytes)?;
+        let root = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut authorities = Vec::new();
+        let mut authority_fingerprints = BTreeSet::new();
+        for ca_path in file.ca_certificates {
+            let ca_path = root.join(ca_path);
+            let authority =
+                tls::load_cert(&ca_path).map_err(|source| AllowListError::LoadCertificate {
+                    path: ca_path.clone(),
+                    source,
+                })?;
+            let fingerprint = tls::cert_fingerprint(&authority).map_err(|source| {
+                AllowListError::InvalidCertificate {
+                    path: ca_path.clone(),
+                    source,
+                }
+            })?;
+            authority_fingerprints.insert(fingerprint);
+            authorities.push(authority);
+        }
+
+        let node_ids = file
+            .node_ids
+            .into_iter()
+            .map(|node_id| parse_node_id(&node_id).ok_or(AllowListError::InvalidNodeId(node_id)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(AllowList {
+            authorities,
+            authority_fingerprints,
+            node_ids,
+        })
+    }
+
+    /// Validates `peer_cert` against the listed certificate authorities, returning the validated
+    /// certificate if any of them signed it.
+    pub(super) fn validate_with_authorities(&self, peer_cert: &X509) -> Option<TlsCert> {
+        self.authorities.iter().find_map(|authority| {
+            tls::validate_cert_with_authority(peer_cert.clone(), authority).ok()
+        })
+    }
+
+    /// Returns whether `node_id` is listed explicitly.
+    pub(super) fn contains(&self, node_id: &NodeId) -> bool {
+        self.node_ids.contains(node_id)
+    }
+
+    /// Returns whether `other` admits the same peers as this allow list.
+    pub(super) fn is_same_as(&self, other: &AllowList) -> bool {
+        self.authority_fingerprints == other.authority_fingerprints
+            && self.node_ids == other.node_ids
+    }
+}
+
+/// Parses a hex-encoded node ID, as found in the `/status` endpoint.
+fn parse_node_id(node_id: &str) -> Option<NodeId> {
+    let bytes = base16::decode(node_id.trim()).ok()?;
+    let fingerprint = <[u8; KeyFingerprint::LENGTH]>::try_from(bytes.as_slice()).ok()?;
+    Some(NodeId::from(KeyFingerprint::from(fingerprint)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write_allow_list(dir: &Path, contents: &str) -> PathBuf {
+        let path = dir.join("allowed_peers.toml");
+        fs::write(&path, contents).expect("should write allow list");
+        path
+    }
+
+    fn generate_cert() -> TlsCert {
+        let (cert, _secret_key) = tls::generate_node_cert().expect("should generate cert");
+        tls::validate_self_signed_cert(cert).expect("should be valid")
+    }
+
+    #[test]
+    fn should_admit_listed_node_ids() {
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        let listed = NodeId::from(generate_cert().public_key_fingerprint());
+        let unlisted = NodeId::from(generate_cert().public_key_fingerprint());
+        let path = write_allow_list(
+            tempdir.path(),
+            &format!(
+                "node_ids = ['{}']",
+                base16::encode_lower(listed.hash_bytes())
+            ),
+        );
+
+        let allow_list = AllowList::load(&path).expect("should load allow list");
+        assert!(allow_list.contains(&listed));
+        assert!(!allow_list.contains(&unlisted));
+    }
+
+    #[test]
+    fn should_admit_certificates_signed_by_listed_authorities() {
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        // A self-signed certificate is its own authority.
+        let authority = generate_cert();
+        let other = generate_cert();
+        fs::write(
+            tempdir.path().join("ca.pem"),
+            authority.as_x509().to_pem().expect("should encode cert"),
+        )
+        .expect("should write cert");
+        let path = write_allow_list(tempdir.path(), "ca_certificates = ['ca.pem']");
+
+        let allow_list = AllowList::load(&path).expect("should load allow list");
+        assert!(allow_list
+            .validate_with_authorities(authority.as_x509())
+            .is_some());
+        assert!(allow_list
+            .validate_with_authorities(other.as_x509())
+            .is_none());
+        assert!(!allow_list.contains(&NodeId::from(authority.public_key_fingerprint())));
+    }
+
+    #[test]
+    fn should_detect_changes() {
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        let node_id = base16::encode_lower(
+            NodeId::from(generate_cert().public_key_fingerprint()).hash_bytes(),
+        );
+        let path = write_allow_list(tempdir.path(), "");
+        let empty = AllowList::load(&path).expect("should load allow list");
+        assert!(empty.is_same_as(&AllowList::load(&path).expect("should load allow list")));
+
+        write_allow_list(tempdir.path(), &format!("node_ids = ['{}']", node_id));
+        let changed = AllowList::load(&path).expect("should load allow list");
+        assert!(!empty.is_same_as(&changed));
+    }
+
+    #[test]
+    fn should_reject_invalid_allow_lists() {
+        let tempdir = tempfile::tempdir().expect("should create tempdir");
+        let path = write_allow_list(tempdir.path(), "node_ids = ['not-hex']");
+        assert!(matches!(
+            AllowList::load(&path),
+            Err(AllowListError::InvalidNodeId(_))
+        ));
+
+        let path = write_allow_list(tempdir.path(), "ca_certificates = ['missing.pem']");
+        assert!(matches!(
+            AllowList::load(&path),
+            Err(AllowListError::LoadCertificate { .. })
+        ));
+
+        let path = write_allow_list(tempdir.path(), "unknown = 1");
+        assert!(matches!(
+            AllowList::load(&path),
+            Err(AllowListError::Parse(_))
+        ));
+    }
+}
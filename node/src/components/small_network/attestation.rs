@@ -0,0 +1,175 @@
+//! Attestations of arbitrary data by this node's identity.
+//!
+//! These allow external parties, e.g. monitoring infrastructure, to verify that a response was
+//! produced by the node with a given ID and, if it is a validator, by the holder of its validator
+//! key, rather than by an impostor relaying or forging it.
+
+use openssl::pkey::{PKeyRef, Private};
+use tracing::warn;
+
+use casper_types::{PublicKey, Signature};
+
+use super::message::ConsensusKeyPair;
+use crate::{
+    tls::{CompressedPublicKey, RawSignature},
+    types::NodeId,
+};
+
+/// Signatures over some data by this node's TLS key and, if it has one, its validator key.
+#[derive(Clone, Debug)]
+pub(crate) struct NodeAttestation {
+    /// The ID of the attesting node.
+    pub(crate) node_id: NodeId,
+    /// The public TLS key of the node, whose fingerprint is the node ID.
+    pub(crate) node_public_key: CompressedPublicKey,
+    /// The signature of the data by the node's TLS key.
+    pub(crate) node_signature: RawSignature,
+    /// The validator key of the node and its signature of the data, if the node has one.
+    pub(crate) validator_signature: Option<(PublicKey, Signature)>,
+}
+
+impl NodeAttestation {
+    /// Signs `data` with the node's TLS key and, if given, its consensus keys.
+    ///
+    /// Returns `None` if the TLS key fails to produce a signature. A failure to sign with the
+    /// consensus keys is logged and only omits the validator signature.
+    pub(super) async fn create(
+        node_id: NodeId,
+        secret_key: &PKeyRef<Private>,
+        consensus_keys: Option<&ConsensusKeyPair>,
+        data: &[u8],
+    ) -> Option<Self> {
+        let node_public_key = CompressedPublicKey::from_secret_key(secret_key)
+            .map_err(|error| warn!(%error, "failed to derive public key for attestation"))
+            .ok()?;
+        let node_signature = RawSignature::create(secret_key, data)
+            .map_err(|error| warn!(%error, "failed to sign attestation"))
+            .ok()?;
+        let validator_signature = match consensus_keys {
+            Some(keys) => keys
+                .sign(data)
+                .await
+                .map(|signature| (keys.public_key().clone(), signature)),
+            None => None,
+        };
+
+        Some(NodeAttestation {
+            node_id,
+            node_public_key,
+            node_signature,
+            validator_signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use thiserror::Error;
+
+    use casper_types::{crypto, SecretKey};
+
+    use super::*;
+    use crate::tls::{self, ValidationError};
+
+    /// An error verifying a node attestation.
+    #[derive(Debug, Error)]
+    enum AttestationError {
+        /// The node's public key doesn't match the node ID.
+        #[error("attestation claims to be by {node_id} but its key belongs to {signer}")]
+        WrongSigner {
+            /// The node ID claimed by the attestation.
+            node_id: NodeId,
+            /// The node ID of the key included in the attestation.
+            signer: NodeId,
+        },
+        /// The signature by the node's TLS key is invalid.
+        #[error("invalid node signature: {0}")]
+        InvalidNodeSignature(#[source] ValidationError),
+        /// The signature by the validator key is invalid.
+        #[error("invalid validator signature: {0}")]
+        InvalidValidatorSignature(#[source] crypto::Error),
+    }
+
+    impl NodeAttestation {
+        /// Verifies both signatures of `data`, and that the key matches the node ID.
+        fn verify(&self, data: &[u8]) -> Result<(), AttestationError> {
+            let signer = NodeId::from(self.node_public_key.fingerprint());
+            if signer != self.node_id {
+                return Err(AttestationError::WrongSigner {
+                    node_id: self.node_id,
+                    signer,
+                });
+            }
+            self.node_signature
+                .verify(&self.node_public_key, data)
+                .map_err(AttestationError::InvalidNodeSignature)?;
+            if let Some((public_key, signature)) = &self.validator_signature {
+                crypto::verify(data, signature, public_key)
+                    .map_err(AttestationError::InvalidValidatorSignature)?;
+            }
+            Ok(())
+        }
+    }
+
+    async fn new_attestation(
+        data: &[u8],
+        consensus_keys: Option<&ConsensusKeyPair>,
+    ) -> NodeAttestation {
+        let (cert, secret_key) = tls::generate_node_cert().unwrap();
+        let tls_cert = tls::validate_self_signed_cert(cert).unwrap();
+        let node_id = NodeId::from(tls_cert.public_key_fingerprint());
+        NodeAttestation::create(node_id, &secret_key, consensus_keys, data)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_verify_attestation() {
+        let data = b"status";
+        let attestation = new_attestation(data, None).await;
+        assert!(attestation.validator_signature.is_none());
+        attestation
+            .verify(data)
+            .expect("attestation should be valid");
+
+        assert!(matches!(
+            attestation.verify(b"other status"),
+            Err(AttestationError::InvalidNodeSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_verify_validator_signature() {
+        let secret_key = SecretKey::ed25519_from_bytes([42; SecretKey::ED25519_LENGTH]).unwrap();
+        let public_key = PublicKey::from(&secret_key);
+        let consensus_keys = ConsensusKeyPair::new(Arc::new(secret_key).into(), public_key.clone());
+
+        let data = b"status";
+        let attestation = new_attestation(data, Some(&consensus_keys)).await;
+        assert_eq!(
+            attestation.validator_signature.as_ref().map(|(key, _)| key),
+            Some(&public_key)
+        );
+        attestation
+            .verify(data)
+            .expect("attestation should be valid");
+    }
+
+    #[tokio::test]
+    async fn should_reject_attestation_claiming_other_node() {
+        let data = b"status";
+        let attestation = new_attestation(data, None).await;
+        let other_attestation = new_attestation(data, None).await;
+
+        let spoofed_attestation = NodeAttestation {
+            node_id: other_attestation.node_id,
+            ..attestation
+        };
+        assert!(matches!(
+            spoofed_attestation.verify(data),
+            Err(AttestationError::WrongSigner { .. })
+        ));
+    }
+}
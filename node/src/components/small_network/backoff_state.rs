@@ -0,0 +1,107 @@
+//! Retained reconnection backoff.
+//!
+//! The number of failed connection attempts of consistently unreachable addresses is written to
+//! disk, so that a restarted node does not immediately dial all of them again.
+
+use std::{collections::BTreeMap, fs, io, net::SocketAddr, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::file_utils::{self, ReadFileError};
+
+/// The backoff state of a single address, as written to disk.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct BackoffState {
+    /// The unreachable address.
+    addr: SocketAddr,
+    /// Number of consecutive failed connection attempts.
+    failures: u8,
+}
+
+/// Error loading or storing the backoff states.
+#[derive(Debug, Error)]
+pub(super) enum BackoffStateError {
+    /// Failed to read the backoff states.
+    #[error(transparent)]
+    ReadFile(#[from] ReadFileError),
+    /// Failed to parse the backoff states.
+    #[error("could not parse backoff states: {0}")]
+    Parse(#[source] serde_json::Error),
+    /// Failed to serialize the backoff states.
+    #[error("could not serialize backoff states: {0}")]
+    Serialize(#[source] serde_json::Error),
+    /// Failed to write the backoff states.
+    #[error("could not write backoff states: {0}")]
+    WriteFile(#[source] io::Error),
+}
+
+/// Loads the backoff states from `path`.
+///
+/// A missing file is not an error, but yields no backoff states.
+pub(super) fn load<P: AsRef<Path>>(path: P) -> Result<BTreeMap<SocketAddr, u8>, BackoffStateError> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = file_utils::read_file(path)?;
+    let states: Vec<BackoffState> =
+        serde_json::from_slice(&contents).map_err(BackoffStateError::Parse)?;
+    Ok(states
+        .into_iter()
+        .map(|state| (state.addr, state.failures))
+        .collect())
+}
+
+/// Stores the backoff states at `path`, replacing any previously stored ones.
+pub(super) fn store<P: AsRef<Path>>(
+    path: P,
+    backoff_states: &BTreeMap<SocketAddr, u8>,
+) -> Result<(), BackoffStateError> {
+    let states: Vec<BackoffState> = backoff_states
+        .iter()
+        .map(|(addr, failures)| BackoffState {
+            addr: *addr,
+            failures: *failures,
+        })
+        .collect();
+    let contents = serde_json::to_vec_pretty(&states).map_err(BackoffStateError::Serialize)?;
+    fs::write(path, contents).map_err(BackoffStateError::WriteFile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_states_roundtrip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("backoff_states.json");
+
+        // No states were stored yet.
+        assert!(load(&path).unwrap().is_empty());
+
+        let backoff_states: BTreeMap<SocketAddr, u8> = vec![
+            ("1.2.3.4:1234".parse().unwrap(), 3),
+            ("[2001:db8::1]:34553".parse().unwrap(), 8),
+        ]
+        .into_iter()
+        .collect();
+        store(&path, &backoff_states).unwrap();
+        assert_eq!(load(&path).unwrap(), backoff_states);
+
+        store(&path, &BTreeMap::new()).unwrap();
+        assert!(load(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_backoff_states_are_rejected() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("backoff_states.json");
+        fs::write(&path, r#"[{"addr": "not an address", "failures": 3}]"#).unwrap();
+
+        assert!(matches!(load(&path), Err(BackoffStateError::Parse(_))));
+    }
+}
@@ -3,17 +3,13 @@
 // TODO: This module and `ChainId` should disappear in its entirety and the actual chainspec be made
 // available.
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use casper_hashing::Digest;
-use casper_types::ProtocolVersion;
+use casper_types::{ProtocolVersion, Timestamp};
 use datasize::DataSize;
 
-use super::{
-    counting_format::ConnectionId,
-    message::{ConsensusCertificate, ConsensusKeyPair},
-    Message,
-};
+use super::{message::ConsensusCertificate, Message};
 use crate::types::Chainspec;
 
 /// Data retained from the chainspec by the small networking component.
@@ -49,18 +45,19 @@ impl ChainInfo {
     pub(super) fn create_handshake<P>(
         &self,
         public_addr: SocketAddr,
-        consensus_keys: Option<&ConsensusKeyPair>,
-        connection_id: ConnectionId,
+        consensus_certificate: Option<ConsensusCertificate>,
         is_syncing: bool,
+        observed_ip: Option<IpAddr>,
     ) -> Message<P> {
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_addr,
             protocol_version: self.protocol_version,
-            consensus_certificate: consensus_keys
-                .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
+            consensus_certificate,
             is_syncing,
             chainspec_hash: Some(self.chainspec_hash),
+            observed_ip,
+            timestamp: Some(Timestamp::now()),
         }
     }
 }
@@ -0,0 +1,111 @@
+//! Estimation of our clock's drift relative to connected peers.
+//!
+//! Every handshake carries the sender's local time. Comparing it to the midpoint between sending
+//! our own handshake and receiving the peer's yields a rough offset of our clock from the peer's,
+//! accurate to about half the round trip time. The median offset across peers is used as the
+//! estimate of our clock's drift, so that a few peers with wrong clocks cannot raise an alarm.
+
+use std::collections::BTreeMap;
+
+use casper_types::Timestamp;
+
+use crate::types::NodeId;
+
+/// Returns the offset in milliseconds of our clock from the clock of a peer which sent
+/// `remote_timestamp`, given the local times we sent our handshake and received the peer's.
+///
+/// A positive offset indicates our clock is ahead of the peer's.
+pub(super) fn measure_offset(
+    remote_timestamp: Timestamp,
+    sent_at: Timestamp,
+    received_at: Timestamp,
+) -> i64 {
+    let midpoint = sent_at.millis() + received_at.millis().saturating_sub(sent_at.millis()) / 2;
+    midpoint as i64 - remote_timestamp.millis() as i64
+}
+
+/// Tracks the clock offsets measured against connected peers.
+#[derive(Debug, Default)]
+pub(super) struct ClockOffsets {
+    offsets: BTreeMap<NodeId, i64>,
+}
+
+impl ClockOffsets {
+    /// Records the offset in milliseconds of our clock from `peer_id`'s, replacing any earlier one.
+    pub(super) fn record(&mut self, peer_id: NodeId, offset: i64) {
+        self.offsets.insert(peer_id, offset);
+    }
+
+    /// Forgets the offset measured against `peer_id`.
+    pub(super) fn remove(&mut self, peer_id: &NodeId) {
+        self.offsets.remove(peer_id);
+    }
+
+    /// Returns the median offset in milliseconds of our clock from the peers', or `None` if fewer
+    /// than `min_peers` offsets are known.
+    pub(super) fn estimate(&self, min_peers: usize) -> Option<i64> {
+        if self.offsets.is_empty() || self.offsets.len() < min_peers {
+            return None;
+        }
+        let mut offsets: Vec<i64> = self.offsets.values().copied().collect();
+        offsets.sort_unstable();
+        let middle = offsets.len() / 2;
+        if offsets.len() % 2 == 0 {
+            Some((offsets[middle - 1] + offsets[middle]) / 2)
+        } else {
+            Some(offsets[middle])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_measure_offset_from_midpoint() {
+        let sent_at = Timestamp::from(10_000);
+        let received_at = Timestamp::from(10_200);
+        assert_eq!(
+            measure_offset(Timestamp::from(10_100), sent_at, received_at),
+            0
+        );
+        assert_eq!(
+            measure_offset(Timestamp::from(4_100), sent_at, received_at),
+            6_000
+        );
+        assert_eq!(
+            measure_offset(Timestamp::from(16_100), sent_at, received_at),
+            -6_000
+        );
+    }
+
+    #[test]
+    fn should_estimate_median_offset() {
+        let mut rng = TestRng::new();
+        let peers: Vec<NodeId> = (0..4).map(|_| NodeId::random(&mut rng)).collect();
+
+        let mut offsets = ClockOffsets::default();
+        offsets.record(peers[0], 6_000);
+        offsets.record(peers[1], 5_000);
+        assert_eq!(offsets.estimate(3), None);
+
+        // A single peer with a wrong clock does not skew the estimate.
+        offsets.record(peers[2], -90_000);
+        assert_eq!(offsets.estimate(3), Some(5_000));
+
+        offsets.record(peers[3], 7_000);
+        assert_eq!(offsets.estimate(3), Some(5_500));
+
+        // Newer measurements replace older ones.
+        offsets.record(peers[2], 7_000);
+        assert_eq!(offsets.estimate(3), Some(6_500));
+
+        offsets.remove(&peers[3]);
+        offsets.remove(&peers[2]);
+        assert_eq!(offsets.estimate(3), None);
+        assert_eq!(offsets.estimate(0), Some(5_500));
+    }
+}
@@ -21,6 +21,9 @@ const DEFAULT_PUBLIC_ADDRESS: &str = "127.0.0.1:0";
 /// Default interval for gossiping network addresses.
 const DEFAULT_GOSSIP_INTERVAL: TimeDiff = TimeDiff::from_seconds(30);
 
+/// Default maximum age of a gossiped peer record.
+const DEFAULT_PEER_RECORD_MAX_AGE: TimeDiff = TimeDiff::from_seconds(600);
+
 /// Default delay until initial round of address gossiping starts.
 const DEFAULT_INITIAL_GOSSIP_DELAY: TimeDiff = TimeDiff::from_seconds(5);
 
@@ -30,14 +33,46 @@ const DEFAULT_MAX_ADDR_PENDING_TIME: TimeDiff = TimeDiff::from_seconds(60);
 /// Default timeout during which the handshake needs to be completed.
 const DEFAULT_HANDSHAKE_TIMEOUT: TimeDiff = TimeDiff::from_seconds(20);
 
+/// Default lifetime requested for a port mapping on the gateway.
+const DEFAULT_NAT_MAPPING_LIFETIME: TimeDiff = TimeDiff::from_seconds(7200);
+
+/// Default timeout for a single request to the gateway.
+const DEFAULT_NAT_REQUEST_TIMEOUT: TimeDiff = TimeDiff::from_seconds(3);
+
+/// Default number of distinct peers that need to confirm our mapped address.
+const DEFAULT_NAT_REQUIRED_CONFIRMATIONS: u16 = 3;
+
+/// Default path of the allow list used in permissioned mode.
+const DEFAULT_ALLOW_LIST_PATH: &str = "allowed_peers.toml";
+
+/// Default interval at which the allow list is reloaded.
+const DEFAULT_ALLOW_LIST_RELOAD_INTERVAL: TimeDiff = TimeDiff::from_seconds(60);
+
+/// Default maximum fraction of outgoing connections into a single network group.
+const DEFAULT_MAX_GROUP_FRACTION: f32 = 0.25;
+
+/// Default number of outgoing connections into a network group which is always permitted.
+const DEFAULT_MIN_GROUP_ALLOWANCE: u16 = 2;
+
+/// Default maximum fraction of each reconnection delay added as jitter.
+const DEFAULT_RECONNECT_JITTER: f32 = 0.25;
+
+/// Default maximum offset of our clock from the connected peers' clocks.
+const DEFAULT_MAX_CLOCK_OFFSET: TimeDiff = TimeDiff::from_seconds(5);
+
+/// Default number of peers whose clocks need to be known to estimate our clock's drift.
+const DEFAULT_CLOCK_DRIFT_MIN_PEERS: u16 = 3;
+
 // Default values for networking configuration:
 impl Default for Config {
     fn default() -> Self {
         Config {
             bind_address: DEFAULT_BIND_ADDRESS.to_string(),
             public_address: DEFAULT_PUBLIC_ADDRESS.to_string(),
+            additional_public_addresses: Vec::new(),
             known_addresses: Vec::new(),
             gossip_interval: DEFAULT_GOSSIP_INTERVAL,
+            peer_record_max_age: DEFAULT_PEER_RECORD_MAX_AGE,
             initial_gossip_delay: DEFAULT_INITIAL_GOSSIP_DELAY,
             max_addr_pending_time: DEFAULT_MAX_ADDR_PENDING_TIME,
             handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
@@ -51,6 +86,66 @@ impl Default for Config {
             max_in_flight_demands: 50,
             blocklist_retain_duration: TimeDiff::from_seconds(600),
             identity: None,
+            nat_traversal: NatTraversalConfig::default(),
+            access_control: AccessControlConfig::default(),
+            peer_diversity: PeerDiversityConfig::default(),
+            reconnect_backoff: ReconnectBackoffConfig::default(),
+            clock_drift: ClockDriftConfig::default(),
+        }
+    }
+}
+
+fn default_peer_record_max_age() -> TimeDiff {
+    DEFAULT_PEER_RECORD_MAX_AGE
+}
+
+impl Default for AccessControlConfig {
+    fn default() -> Self {
+        AccessControlConfig {
+            enabled: false,
+            allow_list: PathBuf::from(DEFAULT_ALLOW_LIST_PATH),
+            reload_interval: DEFAULT_ALLOW_LIST_RELOAD_INTERVAL,
+        }
+    }
+}
+
+impl Default for PeerDiversityConfig {
+    fn default() -> Self {
+        PeerDiversityConfig {
+            enabled: false,
+            max_group_fraction: DEFAULT_MAX_GROUP_FRACTION,
+            min_group_allowance: DEFAULT_MIN_GROUP_ALLOWANCE,
+            prefix_map: None,
+        }
+    }
+}
+
+impl Default for ReconnectBackoffConfig {
+    fn default() -> Self {
+        ReconnectBackoffConfig {
+            jitter: DEFAULT_RECONNECT_JITTER,
+            state_path: None,
+        }
+    }
+}
+
+impl Default for ClockDriftConfig {
+    fn default() -> Self {
+        ClockDriftConfig {
+            max_offset: DEFAULT_MAX_CLOCK_OFFSET,
+            min_peers: DEFAULT_CLOCK_DRIFT_MIN_PEERS,
+        }
+    }
+}
+
+impl Default for NatTraversalConfig {
+    fn default() -> Self {
+        NatTraversalConfig {
+            enabled: false,
+            gateway: None,
+            mapping_lifetime: DEFAULT_NAT_MAPPING_LIFETIME,
+            request_timeout: DEFAULT_NAT_REQUEST_TIMEOUT,
+            required_confirmations: DEFAULT_NAT_REQUIRED_CONFIRMATIONS,
         }
     }
 }
@@ -68,6 +163,81 @@ pub struct IdentityConfig {
     pub ca_certificate: PathBuf,
 }
 
+/// NAT traversal configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct NatTraversalConfig {
+    /// Whether to map the bound port on the local gateway using NAT-PMP or UPnP.
+    ///
+    /// The mapped address is only advertised once enough peers have confirmed it.
+    pub enabled: bool,
+    /// IP address of the gateway. Detected from the default route if unspecified.
+    pub gateway: Option<String>,
+    /// Lifetime requested for the port mapping. The mapping is renewed at half its lifetime.
+    pub mapping_lifetime: TimeDiff,
+    /// Timeout for a single request to the gateway.
+    pub request_timeout: TimeDiff,
+    /// Number of distinct peers which need to observe us at the mapped IP before it is advertised.
+    pub required_confirmations: u16,
+}
+
+/// Access control configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct AccessControlConfig {
+    /// Whether only peers permitted by the allow list may connect.
+    pub enabled: bool,
+    /// Path to the allow list, listing permitted node IDs and certificate authorities.
+    pub allow_list: PathBuf,
+    /// Interval at which the allow list is reloaded.
+    pub reload_interval: TimeDiff,
+}
+
+/// Peer diversity configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct PeerDiversityConfig {
+    /// Whether outgoing connections into a single network group are limited.
+    pub enabled: bool,
+    /// Maximum fraction of outgoing connections into a single network group, between 0.0 and 1.0.
+    pub max_group_fraction: f32,
+    /// Number of outgoing connections into a network group which is permitted regardless of
+    /// `max_group_fraction`.
+    pub min_group_allowance: u16,
+    /// Path to a prefix map assigning IP prefixes to autonomous systems.
+    ///
+    /// Addresses not covered by it are grouped by their /16 (IPv4) or /32 (IPv6) subnet.
+    pub prefix_map: Option<PathBuf>,
+}
+
+/// Reconnection backoff configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct ReconnectBackoffConfig {
+    /// Maximum fraction of each reconnection delay added as random jitter, between 0.0 and 1.0.
+    pub jitter: f32,
+    /// Path to a file retaining the backoff of consistently unreachable addresses across restarts.
+    ///
+    /// The backoff is not retained if unspecified.
+    pub state_path: Option<PathBuf>,
+}
+
+/// Clock drift detection configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct ClockDriftConfig {
+    /// Maximum offset of our clock from the median of the connected peers' clocks before the node
+    /// is reported as unhealthy.
+    pub max_offset: TimeDiff,
+    /// Minimum number of connected peers whose clocks are known before the drift is estimated.
+    pub min_peers: u16,
+}
+
 /// Small network configuration.
 #[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -79,10 +249,19 @@ pub struct Config {
     ///
     /// If the port is specified as `0`, it will be replaced with the actually bound port.
     pub public_address: String,
+    /// Further publicly advertised addresses the node can be reached at.
+    ///
+    /// Advertised after the public address, in the given order.  At most three are advertised.
+    #[serde(default)]
+    pub additional_public_addresses: Vec<String>,
     /// Known address of a node on the network used for joining.
     pub known_addresses: Vec<String>,
     /// Interval in milliseconds used for gossiping.
     pub gossip_interval: TimeDiff,
+    /// Maximum age of a gossiped peer record.  Older records are ignored, and our own record is
+    /// renewed once half as old.
+    #[serde(default = "default_peer_record_max_age")]
+    pub peer_record_max_age: TimeDiff,
     /// Initial delay before the first round of gossip.
     pub initial_gossip_delay: TimeDiff,
     /// Maximum allowed time for an address to be kept in the pending set.
@@ -112,6 +291,21 @@ pub struct Config {
     /// An identity will be automatically generated when starting up a node if this option is
     /// unspecified.
     pub identity: Option<IdentityConfig>,
+    /// NAT traversal configuration.
+    #[serde(default)]
+    pub nat_traversal: NatTraversalConfig,
+    /// Access control configuration.
+    #[serde(default)]
+    pub access_control: AccessControlConfig,
+    /// Peer diversity configuration.
+    #[serde(default)]
+    pub peer_diversity: PeerDiversityConfig,
+    /// Reconnection backoff configuration.
+    #[serde(default)]
+    pub reconnect_backoff: ReconnectBackoffConfig,
+    /// Clock drift detection configuration.
+    #[serde(default)]
+    pub clock_drift: ClockDriftConfig,
 }
 
 #[cfg(test)]
@@ -25,7 +25,7 @@ use tracing::{trace, warn};
 
 use casper_hashing::Digest;
 
-use super::{tls::KeyFingerprint, Message, Metrics, Payload};
+use super::{metrics::PeerTraffic, tls::KeyFingerprint, Message, Metrics, Payload};
 use crate::{types::NodeId, utils};
 
 /// Lazily-evaluated network message ID generator.
@@ -42,9 +42,9 @@ impl Display for TraceId {
 
 /// A metric-updating serializer/deserializer wrapper for network messages.
 ///
-/// Classifies each message given and updates the `NetworkingMetrics` accordingly. Also emits a
-/// TRACE-level message to the `net_out` and `net_in` target with a per-message unique hash when
-/// a message is sent or received.
+/// Classifies each message given and updates the `NetworkingMetrics` accordingly, both in total
+/// and for the peer connected to. Also emits a TRACE-level message to the `net_out` and `net_in`
+/// target with a per-message unique hash when a message is sent or received.
 #[pin_project]
 #[derive(Debug)]
 pub struct CountingFormat<F> {
@@ -61,6 +61,8 @@ pub struct CountingFormat<F> {
     role: Role,
     /// Metrics to update.
     metrics: Weak<Metrics>,
+    /// Traffic accounting of the peer connected to.
+    peer_traffic: Option<PeerTraffic>,
 }
 
 impl<F> CountingFormat<F> {
@@ -69,10 +71,12 @@ impl<F> CountingFormat<F> {
     pub(super) fn new(
         metrics: Weak<Metrics>,
         connection_id: ConnectionId,
+        peer_id: NodeId,
         role: Role,
         inner: F,
     ) -> Self {
         Self {
+            peer_traffic: Metrics::record_peer_traffic(&metrics, peer_id),
             metrics,
             connection_id,
             out_count: 0,
@@ -99,6 +103,9 @@ where
         let msg_size = serialized.len() as u64;
         let msg_kind = item.classify();
         Metrics::record_payload_out(this.metrics, msg_kind, msg_size);
+        if let Some(peer_traffic) = this.peer_traffic {
+            peer_traffic.record_out(msg_kind, msg_size);
+        }
 
         let trace_id = this
             .connection_id
@@ -131,6 +138,9 @@ where
         let deserialized = F::deserialize(projection, src)?;
         let msg_kind = deserialized.classify();
         Metrics::record_payload_in(this.metrics, msg_kind, msg_size);
+        if let Some(peer_traffic) = this.peer_traffic {
+            peer_traffic.record_in(msg_kind, msg_size);
+        }
 
         let trace_id = this
             .connection_id
@@ -0,0 +1,333 @@
+//! Peer diversity enforcement.
+//!
+//! To make eclipse attacks harder, outgoing connections into any single network group are limited
+//! to a fraction of all outgoing connections. The network group of an address is the autonomous
+//! system announcing it if the address is covered by the operator-provided prefix map, or its /16
+//! (IPv4) or /32 (IPv6) subnet otherwise. Loopback, private and link-local addresses belong to no
+//! group and are never limited.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+};
+
+use datasize::DataSize;
+use serde::Serialize;
+use thiserror::Error;
+
+use casper_types::file_utils::{self, ReadFileError};
+
+use super::config::PeerDiversityConfig;
+
+/// Error loading the prefix map.
+#[derive(Debug, Error)]
+pub enum PrefixMapError {
+    /// Failed to read the prefix map.
+    #[error(transparent)]
+    ReadFile(#[from] ReadFileError),
+    /// The prefix map is not valid UTF-8.
+    #[error("prefix map {} is not valid UTF-8", path.display())]
+    NotUtf8 { path: PathBuf },
+    /// A line is not of the form `<ip>/<prefix length> <ASN>`.
+    #[error("invalid line {line_number} in prefix map: {line:?}")]
+    InvalidLine { line_number: usize, line: String },
+}
+
+/// The network group an address belongs to.
+#[derive(Clone, Copy, DataSize, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) enum NetworkGroup {
+    /// An autonomous system, by number.
+    Asn(u32),
+    /// An IPv4 /16 subnet, by its first two octets.
+    Ipv4Subnet([u8; 2]),
+    /// An IPv6 /32 subnet, by its first two segments.
+    Ipv6Subnet([u16; 2]),
+}
+
+impl Display for NetworkGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkGroup::Asn(asn) => write!(f, "AS{}", asn),
+            NetworkGroup::Ipv4Subnet([a, b]) => write!(f, "{}.{}.0.0/16", a, b),
+            NetworkGroup::Ipv6Subnet([a, b]) => write!(f, "{:x}:{:x}::/32", a, b),
+        }
+    }
+}
+
+/// A map from IP prefixes to the autonomous systems announcing them.
+#[derive(DataSize, Debug, Default)]
+pub(crate) struct PrefixMap {
+    /// The ASN of every prefix, by prefix length, longest first, and masked address.
+    prefixes: BTreeMap<Reverse<u8>, HashMap<IpAddr, u32>>,
+}
+
+impl PrefixMap {
+    /// Loads the prefix map from `path`.
+    ///
+    /// Every line holds a prefix and its ASN, e.g. `203.0.113.0/24 64496`. Empty lines and lines
+    /// starting with `#` are ignored.
+    pub(super) fn load(path: &Path) -> Result<Self, PrefixMapError> {
+        let bytes = file_utils::read_file(path)?;
+        let contents = String::from_utf8(bytes).map_err(|_| PrefixMapError::NotUtf8 {
+            path: path.to_path_buf(),
+        })?;
+        Self::parse(&contents)
+    }
+
+    /// Parses the contents of a prefix map.
+    fn parse(contents: &str) -> Result<Self, PrefixMapError> {
+        let mut prefix_map = PrefixMap::default();
+        for (index, line) in contents.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let invalid_line = || PrefixMapError::InvalidLine {
+                line_number: index + 1,
+                line: line.to_string(),
+            };
+            let (prefix, asn) = parse_line(trimmed).ok_or_else(invalid_line)?;
+            let (addr, prefix_len) = prefix;
+            prefix_map
+                .prefixes
+                .entry(Reverse(prefix_len))
+                .or_default()
+                .insert(mask(addr, prefix_len), asn);
+        }
+        Ok(prefix_map)
+    }
+
+    /// Returns the ASN of the longest prefix covering `addr`, if any.
+    fn lookup(&self, addr: IpAddr) -> Option<u32> {
+        self.prefixes
+            .iter()
+            .find_map(|(Reverse(prefix_len), asns)| {
+                let max_len = match addr {
+                    IpAddr::V4(_) => 32,
+                    IpAddr::V6(_) => 128,
+                };
+                if *prefix_len > max_len {
+                    return None;
+                }
+                asns.get(&mask(addr, *prefix_len)).copied()
+            })
+    }
+}
+
+/// Parses a line of the form `<ip>/<prefix length> <ASN>`.
+fn parse_line(line: &str) -> Option<((IpAddr, u8), u32)> {
+    let mut parts = line.split_whitespace();
+    let (addr, prefix_len) = parts.next()?.split_once('/')?;
+    let asn = parts.next()?.trim_start_matches("AS").parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let addr: IpAddr = addr.parse().ok()?;
+    let prefix_len: u8 = prefix_len.parse().ok()?;
+    let max_len = if addr.is_ipv4() { 32 } else { 128 };
+    if prefix_len > max_len {
+        return None;
+    }
+    Some(((addr, prefix_len), asn))
+}
+
+/// Clears all bits of `addr` after the first `prefix_len` ones.
+fn mask(addr: IpAddr, prefix_len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(addr) => {
+            let bits = u32::from(addr)
+                .checked_shr(32 - prefix_len as u32)
+                .and_then(|bits| bits.checked_shl(32 - prefix_len as u32))
+                .unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(bits))
+        }
+        IpAddr::V6(addr) => {
+            let bits = u128::from(addr)
+                .checked_shr(128 - prefix_len as u32)
+                .and_then(|bits| bits.checked_shl(128 - prefix_len as u32))
+                .unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(bits))
+        }
+    }
+}
+
+/// Converts IPv4-mapped IPv6 addresses to IPv4 addresses.
+fn unmap(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6_addr) => v6_addr.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(addr),
+        IpAddr::V4(_) => addr,
+    }
+}
+
+/// Returns whether `addr` is not publicly routable, so that it belongs to no network group.
+fn is_local(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => {
+            addr.is_loopback() || addr.is_private() || addr.is_link_local() || addr.is_unspecified()
+        }
+        IpAddr::V6(addr) => {
+            let first_segment = addr.segments()[0];
+            addr.is_loopback()
+                || addr.is_unspecified()
+                // Unique local addresses, fc00::/7.
+                || first_segment & 0xfe00 == 0xfc00
+                // Unicast link-local addresses, fe80::/10.
+                || first_segment & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// Limits on the outgoing connections into a single network group.
+#[derive(DataSize, Debug)]
+pub(crate) struct DiversityLimits {
+    /// Maximum fraction of outgoing connections into a single network group.
+    max_group_fraction: f32,
+    /// Number of outgoing connections into a network group which is always permitted.
+    min_group_allowance: usize,
+    /// Map assigning IP prefixes to autonomous systems.
+    prefix_map: PrefixMap,
+}
+
+impl DiversityLimits {
+    /// Creates the limits from the configuration, loading the prefix map if one is configured.
+    ///
+    /// Returns `None` if peer diversity is not enforced.
+    pub(super) fn from_config(cfg: &PeerDiversityConfig) -> Result<Option<Self>, PrefixMapError> {
+        if !cfg.enabled {
+            return Ok(None);
+        }
+        let prefix_map = match cfg.prefix_map {
+            Some(ref path) => PrefixMap::load(path)?,
+            None => PrefixMap::default(),
+        };
+        Ok(Some(DiversityLimits {
+            max_group_fraction: cfg.max_group_fraction,
+            min_group_allowance: cfg.min_group_allowance as usize,
+            prefix_map,
+        }))
+    }
+
+    /// Returns the network group of `addr`, or `None` if it is exempt from the limits.
+    pub(crate) fn group(&self, addr: IpAddr) -> Option<NetworkGroup> {
+        let addr = unmap(addr);
+        if is_local(addr) {
+            return None;
+        }
+        match self.prefix_map.lookup(addr) {
+            Some(asn) => Some(NetworkGroup::Asn(asn)),
+            None => Self::default_group(addr),
+        }
+    }
+
+    /// Returns the subnet of `addr`, or `None` if it is not publicly routable.
+    ///
+    /// This is the network group of addresses not covered by a prefix map.
+    pub(crate) fn default_group(addr: IpAddr) -> Option<NetworkGroup> {
+        let group = match unmap(addr) {
+            addr if is_local(addr) => return None,
+            IpAddr::V4(addr) => {
+                let [a, b, _, _] = addr.octets();
+                NetworkGroup::Ipv4Subnet([a, b])
+            }
+            IpAddr::V6(addr) => {
+                let segments = addr.segments();
+                NetworkGroup::Ipv6Subnet([segments[0], segments[1]])
+            }
+        };
+        Some(group)
+    }
+
+    /// Returns whether another outgoing connection into a group may be added, given that there
+    /// are `group_count` connections into the group out of `total_count` connections.
+    pub(crate) fn permits(&self, group_count: usize, total_count: usize) -> bool {
+        let fraction_limit = (self.max_group_fraction * (total_count + 1) as f32).floor() as usize;
+        group_count < fraction_limit.max(self.min_group_allowance)
+    }
+}
+
+/// Statistics on the network groups of outgoing connections.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(crate) struct DiversityStats {
+    /// Whether the limits are enforced.
+    pub(crate) enforced: bool,
+    /// Number of outgoing connections, connected or connecting.
+    pub(crate) total_outgoing: usize,
+    /// Number of outgoing connections exempt from the limits.
+    pub(crate) exempt_outgoing: usize,
+    /// Number of outgoing connections, by network group.
+    pub(crate) outgoing_by_group: BTreeMap<String, usize>,
+    /// Number of learned addresses not dialed because their network group is saturated.
+    pub(crate) deferred: usize,
+}
+
+impl Display for DiversityStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "enforced: {}, outgoing: {}, exempt: {}, deferred: {}",
+            self.enforced, self.total_outgoing, self.exempt_outgoing, self.deferred
+        )?;
+        for (group, count) in &self.outgoing_by_group {
+            writeln!(f, "{}: {}", group, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(prefix_map: &str) -> DiversityLimits {
+        DiversityLimits {
+            max_group_fraction: 0.25,
+            min_group_allowance: 2,
+            prefix_map: PrefixMap::parse(prefix_map).unwrap(),
+        }
+    }
+
+    #[test]
+    fn should_group_addresses() {
+        let limits =
+            limits("# comment\n203.0.0.0/8 64496\n203.0.113.0/24 AS64497\n2001:db8::/32 64498\n");
+        let group = |addr: &str| limits.group(addr.parse().unwrap());
+
+        assert_eq!(group("203.0.113.7"), Some(NetworkGroup::Asn(64497)));
+        assert_eq!(group("203.0.114.7"), Some(NetworkGroup::Asn(64496)));
+        assert_eq!(
+            group("198.51.100.1"),
+            Some(NetworkGroup::Ipv4Subnet([198, 51]))
+        );
+        assert_eq!(
+            group("::ffff:198.51.100.1"),
+            Some(NetworkGroup::Ipv4Subnet([198, 51]))
+        );
+        assert_eq!(group("2001:db8::1"), Some(NetworkGroup::Asn(64498)));
+        assert_eq!(
+            group("2001:db9::1"),
+            Some(NetworkGroup::Ipv6Subnet([0x2001, 0xdb9]))
+        );
+        assert_eq!(group("127.0.0.1"), None);
+        assert_eq!(group("10.1.2.3"), None);
+        assert_eq!(group("fe80::1"), None);
+
+        assert!(PrefixMap::parse("203.0.113.0/33 64496").is_err());
+        assert!(PrefixMap::parse("203.0.113.0 64496").is_err());
+    }
+
+    #[test]
+    fn should_permit_allowance_then_fraction() {
+        let limits = limits("");
+        // The allowance is always permitted.
+        assert!(limits.permits(0, 0));
+        assert!(limits.permits(1, 1));
+        assert!(!limits.permits(2, 2));
+        // Beyond it, a group may hold a quarter of all connections.
+        assert!(!limits.permits(2, 10));
+        assert!(limits.permits(2, 11));
+        assert!(!limits.permits(3, 11));
+    }
+}
@@ -7,8 +7,10 @@ use openssl::{error::ErrorStack, ssl};
 use serde::Serialize;
 use thiserror::Error;
 
+use super::{access_control::AllowListError, diversity::PrefixMapError};
 use crate::{
     tls::{LoadCertError, ValidationError},
+    types::NodeId,
     utils::{LoadError, Loadable, ResolveAddressError},
 };
 
@@ -84,6 +86,20 @@ pub enum Error {
         #[source]
         ValidationError,
     ),
+    /// Failed to load the allow list.
+    #[error("failed to load allow list: {0}")]
+    AllowList(
+        #[serde(skip_serializing)]
+        #[from]
+        AllowListError,
+    ),
+    /// Failed to load the prefix map used to enforce peer diversity.
+    #[error("failed to load prefix map: {0}")]
+    PrefixMap(
+        #[serde(skip_serializing)]
+        #[from]
+        PrefixMapError,
+    ),
 }
 
 // Manual implementation for `DataSize` - the type contains too many FFI variants that are hard to
@@ -145,6 +161,9 @@ pub enum ConnectionError {
     /// TLS validation error.
     #[error("TLS validation error of peer certificate")]
     PeerCertificateInvalid(#[source] ValidationError),
+    /// Peer is not permitted by the allow list.
+    #[error("peer {0} is not on the allow list")]
+    PeerNotAllowed(NodeId),
     /// Failed to send handshake.
     #[error("handshake send failed")]
     HandshakeSend(
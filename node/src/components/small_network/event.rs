@@ -1,7 +1,7 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
     io, mem,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
 };
 
@@ -12,7 +12,11 @@ use serde::Serialize;
 use static_assertions::const_assert;
 use tracing::Span;
 
-use super::{error::ConnectionError, FullTransport, GossipedAddress, Message, NodeId};
+use super::{
+    error::ConnectionError,
+    nat::{NatError, PortMapping},
+    FullTransport, Message, NodeId, PeerRecord,
+};
 use crate::{
     effect::{
         announcements::{
@@ -84,12 +88,24 @@ pub(crate) enum Event<P> {
     /// The node should gossip its own public listening address.
     GossipOurAddress,
 
-    /// We received a peer's public listening address via gossip.
-    PeerAddressReceived(GossipedAddress),
+    /// We received a valid peer record via gossip.
+    PeerRecordReceived(PeerRecord),
 
     /// Housekeeping for the outgoing manager.
     SweepOutgoing,
 
+    /// An attempt to map our listening port on the local gateway has completed.
+    PortMapped {
+        #[serde(skip_serializing)]
+        result: Box<Result<PortMapping, NatError>>,
+    },
+
+    /// The port mapping on the local gateway should be renewed.
+    RenewPortMapping,
+
+    /// The allow list should be reloaded.
+    ReloadAllowList,
+
     /// Blocklist announcement.
     #[from]
     BlocklistAnnouncement(BlocklistAnnouncement),
@@ -138,8 +154,8 @@ impl<P: Display> Display for Event<P> {
             Event::NetworkRequest { req } => write!(f, "request: {}", req),
             Event::NetworkInfoRequest { req } => write!(f, "request: {}", req),
             Event::GossipOurAddress => write!(f, "gossip our address"),
-            Event::PeerAddressReceived(gossiped_address) => {
-                write!(f, "received gossiped peer address {}", gossiped_address)
+            Event::PeerRecordReceived(peer_record) => {
+                write!(f, "received gossiped {}", peer_record)
             }
             Event::BlocklistAnnouncement(ann) => {
                 write!(f, "handling blocklist announcement: {}", ann)
@@ -150,6 +166,12 @@ impl<P: Display> Display for Event<P> {
             Event::SweepOutgoing => {
                 write!(f, "sweep outgoing connections")
             }
+            Event::PortMapped { result } => match result.as_ref() {
+                Ok(mapping) => write!(f, "port mapped: {}", mapping),
+                Err(error) => write!(f, "port mapping failed: {}", error),
+            },
+            Event::RenewPortMapping => write!(f, "renew port mapping"),
+            Event::ReloadAllowList => write!(f, "reload allow list"),
             Event::ChainSynchronizerAnnouncement(ann) => {
                 write!(f, "handling chain synchronizer announcement: {}", ann)
             }
@@ -263,6 +285,10 @@ pub(crate) enum OutgoingConnection<P> {
         sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
         /// Holds the information whether the remote node is syncing.
         is_syncing: bool,
+        /// Our IP address as observed by the peer, if it reported one.
+        observed_ip: Option<IpAddr>,
+        /// Offset of our clock from the peer's in milliseconds, if it sent a timestamp.
+        clock_offset: Option<i64>,
     },
 }
 
@@ -284,6 +310,8 @@ impl<P> Display for OutgoingConnection<P> {
                 peer_consensus_public_key,
                 sink: _,
                 is_syncing,
+                observed_ip: _,
+                clock_offset: _,
             } => {
                 write!(
                     f,
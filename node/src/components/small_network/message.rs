@@ -1,13 +1,12 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
-    net::SocketAddr,
-    sync::Arc,
+    net::{IpAddr, SocketAddr},
 };
 
 use casper_hashing::Digest;
+use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, Signature, Timestamp};
 #[cfg(test)]
-use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{testing::TestRng, SecretKey};
 use datasize::DataSize;
 use futures::future::BoxFuture;
 use serde::{
@@ -15,7 +14,12 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use crate::{effect::EffectBuilder, types::NodeId, utils::opt_display::OptDisplay};
+use tracing::warn;
+
+use crate::{
+    components::consensus::Signer, effect::EffectBuilder, types::NodeId,
+    utils::opt_display::OptDisplay,
+};
 
 use super::counting_format::ConnectionId;
 
@@ -45,8 +49,21 @@ pub(crate) enum Message<P> {
         /// Hash of the chainspec the node is running.
         #[serde(default)]
         chainspec_hash: Option<Digest>,
+        /// The IP address the receiving node's connection was observed to originate from, if the
+        /// sender accepted the connection.
+        #[serde(default)]
+        observed_ip: Option<IpAddr>,
+        /// The sender's local time when creating the handshake.
+        #[serde(default)]
+        timestamp: Option<Timestamp>,
     },
     Payload(P),
+    /// Announces that the sender is about to disconnect from the network, e.g. because it is
+    /// draining.
+    ///
+    /// Peers running a version without this message fail to decode it and close the connection,
+    /// which the sender is about to do anyway.
+    Disconnecting,
 }
 
 impl<P: Payload> Message<P> {
@@ -54,7 +71,7 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn classify(&self) -> MessageKind {
         match self {
-            Message::Handshake { .. } => MessageKind::Protocol,
+            Message::Handshake { .. } | Message::Disconnecting => MessageKind::Protocol,
             Message::Payload(payload) => payload.classify(),
         }
     }
@@ -63,7 +80,7 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn is_low_priority(&self) -> bool {
         match self {
-            Message::Handshake { .. } => false,
+            Message::Handshake { .. } | Message::Disconnecting => false,
             Message::Payload(payload) => payload.is_low_priority(),
         }
     }
@@ -72,7 +89,7 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn payload_incoming_resource_estimate(&self, weights: &EstimatorWeights) -> u32 {
         match self {
-            Message::Handshake { .. } => 0,
+            Message::Handshake { .. } | Message::Disconnecting => 0,
             Message::Payload(payload) => payload.incoming_resource_estimate(weights),
         }
     }
@@ -81,7 +98,7 @@ impl<P: Payload> Message<P> {
     #[inline]
     pub(super) fn payload_is_unsafe_for_syncing_nodes(&self) -> bool {
         match self {
-            Message::Handshake { .. } => false,
+            Message::Handshake { .. } | Message::Disconnecting => false,
             Message::Payload(payload) => payload.is_unsafe_for_syncing_peers(),
         }
     }
@@ -98,7 +115,7 @@ impl<P: Payload> Message<P> {
         REv: FromIncoming<P> + Send,
     {
         match self {
-            Message::Handshake { .. } => Err(self),
+            Message::Handshake { .. } | Message::Disconnecting => Err(self),
             Message::Payload(payload) => {
                 // Note: For now, the wrapping/unwrapp of the payload is a bit unfortunate here.
                 REv::try_demand_from_incoming(effect_builder, sender, payload)
@@ -109,23 +126,30 @@ impl<P: Payload> Message<P> {
 }
 
 /// A pair of secret keys used by consensus.
+#[derive(Clone)]
 pub(super) struct ConsensusKeyPair {
-    secret_key: Arc<SecretKey>,
+    signer: Signer,
     public_key: PublicKey,
 }
 
 impl ConsensusKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(super) fn new(signer: Signer, public_key: PublicKey) -> Self {
+        Self { signer, public_key }
+    }
+
+    /// Returns the public key of this keypair.
+    pub(super) fn public_key(&self) -> &PublicKey {
+        &self.public_key
     }
 
     /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    pub(super) async fn sign(&self, value: &[u8]) -> Option<Signature> {
+        self.signer
+            .sign(value.to_vec(), self.public_key.clone(), None)
+            .await
+            .map_err(|error| warn!(%error, "failed to sign with consensus key"))
+            .ok()
     }
 }
 
@@ -142,12 +166,17 @@ pub(crate) struct ConsensusCertificate {
 
 impl ConsensusCertificate {
     /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &ConsensusKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
+    ///
+    /// Returns `None` if the key pair failed to produce a signature.
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        key_pair: &ConsensusKeyPair,
+    ) -> Option<Self> {
+        let signature = key_pair.sign(connection_id.as_bytes()).await?;
+        Some(ConsensusCertificate {
             public_key: key_pair.public_key.clone(),
             signature,
-        }
+        })
     }
 
     /// Validates a certificate, returning a `PublicKey` if valid.
@@ -161,10 +190,15 @@ impl ConsensusCertificate {
     fn random(rng: &mut TestRng) -> Self {
         let secret_key = SecretKey::random(rng);
         let public_key = PublicKey::from(&secret_key);
-        ConsensusCertificate::create(
-            ConnectionId::random(rng),
-            &ConsensusKeyPair::new(Arc::new(secret_key), public_key),
-        )
+        let signature = crypto::sign(
+            ConnectionId::random(rng).as_bytes(),
+            &secret_key,
+            &public_key,
+        );
+        ConsensusCertificate {
+            public_key,
+            signature,
+        }
     }
 }
 
@@ -265,19 +299,24 @@ impl<P: Display> Display for Message<P> {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                observed_ip,
+                timestamp,
             } => {
                 write!(
                     f,
-                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}",
+                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}, observed_ip: {}, timestamp: {}",
                     network_name,
                     public_addr,
                     protocol_version,
                     OptDisplay::new(consensus_certificate.as_ref(), "none"),
                     is_syncing,
-                    OptDisplay::new(chainspec_hash.as_ref(), "none")
+                    OptDisplay::new(chainspec_hash.as_ref(), "none"),
+                    OptDisplay::new(observed_ip.as_ref(), "none"),
+                    OptDisplay::new(timestamp.as_ref(), "none")
                 )
             }
             Message::Payload(payload) => write!(f, "payload: {}", payload),
+            Message::Disconnecting => write!(f, "disconnecting"),
         }
     }
 }
@@ -305,6 +344,21 @@ pub(crate) enum MessageKind {
     Other,
 }
 
+impl MessageKind {
+    /// All message kinds, in declaration order, so that `kind as usize` indexes this array.
+    pub(crate) const ALL: [MessageKind; 9] = [
+        MessageKind::Protocol,
+        MessageKind::Consensus,
+        MessageKind::DeployGossip,
+        MessageKind::AddressGossip,
+        MessageKind::DeployTransfer,
+        MessageKind::FinalizedApprovalsTransfer,
+        MessageKind::BlockTransfer,
+        MessageKind::TrieTransfer,
+        MessageKind::Other,
+    ];
+}
+
 impl Display for MessageKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -562,6 +616,8 @@ mod tests {
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
             is_syncing: false,
             chainspec_hash: Some(Digest::hash("example-chainspec")),
+            observed_ip: Some([87, 65, 43, 21].into()),
+            timestamp: Some(Timestamp::from(1_600_000_000_000)),
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -580,6 +636,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn current_handshake_roundtrips_observed_ip() {
+        let handshake = Message::<protocol::Message>::Handshake {
+            network_name: "example-handshake".to_string(),
+            public_addr: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::from_parts(5, 6, 7),
+            consensus_certificate: None,
+            is_syncing: false,
+            chainspec_hash: Some(Digest::hash("example-chainspec")),
+            observed_ip: Some([87, 65, 43, 21].into()),
+            timestamp: Some(Timestamp::from(1_600_000_000_000)),
+        };
+
+        let roundtripped: Message<protocol::Message> = roundtrip_message(&handshake);
+
+        match roundtripped {
+            Message::Handshake {
+                observed_ip,
+                timestamp,
+                ..
+            } => {
+                assert_eq!(observed_ip, Some([87, 65, 43, 21].into()));
+                assert_eq!(timestamp, Some(Timestamp::from(1_600_000_000_000)));
+            }
+            Message::Payload(_) | Message::Disconnecting => {
+                panic!("did not expect handshake to deserialize to payload")
+            }
+        }
+    }
+
     #[test]
     fn current_handshake_decodes_from_v1_0_0() {
         let legacy_handshake = V1_0_0_Message::Handshake {
@@ -597,15 +683,19 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                observed_ip,
+                timestamp,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(observed_ip.is_none());
+                assert!(timestamp.is_none())
             }
-            Message::Payload(_) => {
+            Message::Payload(_) | Message::Disconnecting => {
                 panic!("did not expect modern handshake to deserialize to payload")
             }
         }
@@ -623,6 +713,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                observed_ip,
+                timestamp,
             } => {
                 assert!(!is_syncing);
                 assert_eq!(network_name, "serialization-test");
@@ -630,9 +722,11 @@ mod tests {
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(observed_ip.is_none());
+                assert!(timestamp.is_none())
             }
-            Message::Payload(_) => {
+            Message::Payload(_) | Message::Disconnecting => {
                 panic!("did not expect modern handshake to deserialize to payload")
             }
         }
@@ -650,6 +744,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                observed_ip,
+                timestamp,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
@@ -676,9 +772,11 @@ mod tests {
                     .unwrap()
                 );
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(observed_ip.is_none());
+                assert!(timestamp.is_none())
             }
-            Message::Payload(_) => {
+            Message::Payload(_) | Message::Disconnecting => {
                 panic!("did not expect modern handshake to deserialize to payload")
             }
         }
@@ -696,6 +794,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                observed_ip,
+                timestamp,
             } => {
                 assert!(!is_syncing);
                 assert_eq!(network_name, "example-handshake");
@@ -722,9 +822,11 @@ mod tests {
                     .unwrap()
                 );
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(observed_ip.is_none());
+                assert!(timestamp.is_none())
             }
-            Message::Payload(_) => {
+            Message::Payload(_) | Message::Disconnecting => {
                 panic!("did not expect modern handshake to deserialize to payload")
             }
         }
@@ -753,4 +855,11 @@ mod tests {
     fn bincode_roundtrip_certificate() {
         roundtrip_certificate(false)
     }
+
+    #[test]
+    fn message_kinds_are_indexed_by_discriminant() {
+        for (index, kind) in MessageKind::ALL.iter().enumerate() {
+            assert_eq!(*kind as usize, index, "{} is out of order", kind);
+        }
+    }
 }
@@ -1,10 +1,19 @@
-use std::sync::Weak;
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    sync::{Mutex, Weak},
+};
 
-use prometheus::{Counter, IntCounter, IntGauge, Registry};
+use prometheus::{Counter, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
 use tracing::debug;
 
 use super::{outgoing::OutgoingMetrics, MessageKind};
-use crate::unregister_metric;
+use crate::{types::NodeId, unregister_metric};
+
+/// Label value of outgoing traffic.
+const DIRECTION_OUT: &str = "out";
+/// Label value of incoming traffic.
+const DIRECTION_IN: &str = "in";
 
 /// Network-type agnostic networking metrics.
 #[derive(Debug)]
@@ -19,6 +28,8 @@ pub(super) struct Metrics {
     pub(super) queued_messages: IntGauge,
     /// Number of connected peers.
     pub(super) peers: IntGauge,
+    /// Estimated offset of our clock from the connected peers' clocks in milliseconds.
+    pub(super) clock_offset: IntGauge,
 
     /// Count of outgoing messages that are protocol overhead.
     pub(super) out_count_protocol: IntCounter,
@@ -68,6 +79,8 @@ pub(super) struct Metrics {
     pub(super) out_state_blocked: IntGauge,
     /// Number of outgoing connections in loopback state.
     pub(super) out_state_loopback: IntGauge,
+    /// Number of outgoing connections in waiting state, by number of failed attempts.
+    pub(super) out_backoff_addresses: IntGaugeVec,
 
     /// Volume in bytes of incoming messages that are protocol overhead.
     pub(super) in_bytes_protocol: IntCounter,
@@ -117,6 +130,9 @@ pub(super) struct Metrics {
     /// Total time spent delaying incoming traffic from non-validators due to limiter, in seconds.
     pub(super) accumulated_incoming_limiter_delay: Counter,
 
+    /// Traffic of each connected peer.
+    peer_traffic: PeerTrafficMetrics,
+
     /// Registry instance.
     registry: Registry,
 }
@@ -137,6 +153,10 @@ impl Metrics {
             "number of messages waiting to be sent out",
         )?;
         let peers = IntGauge::new("peers", "number of connected peers")?;
+        let clock_offset = IntGauge::new(
+            "net_clock_offset_ms",
+            "estimated offset of the local clock from the connected peers' clocks in milliseconds",
+        )?;
 
         let out_count_protocol = IntCounter::new(
             "net_out_count_protocol",
@@ -232,6 +252,13 @@ impl Metrics {
             "out_state_loopback",
             "number of connections in the loopback state",
         )?;
+        let out_backoff_addresses = IntGaugeVec::new(
+            Opts::new(
+                "out_backoff_addresses",
+                "number of connections in the waiting state, by number of failed attempts",
+            ),
+            &["failures"],
+        )?;
 
         let in_count_protocol = IntCounter::new(
             "net_in_count_protocol",
@@ -330,6 +357,7 @@ impl Metrics {
         registry.register(Box::new(open_connections.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
         registry.register(Box::new(peers.clone()))?;
+        registry.register(Box::new(clock_offset.clone()))?;
 
         registry.register(Box::new(out_count_protocol.clone()))?;
         registry.register(Box::new(out_count_consensus.clone()))?;
@@ -356,6 +384,7 @@ impl Metrics {
         registry.register(Box::new(out_state_connected.clone()))?;
         registry.register(Box::new(out_state_blocked.clone()))?;
         registry.register(Box::new(out_state_loopback.clone()))?;
+        registry.register(Box::new(out_backoff_addresses.clone()))?;
 
         registry.register(Box::new(in_count_protocol.clone()))?;
         registry.register(Box::new(in_count_consensus.clone()))?;
@@ -383,12 +412,16 @@ impl Metrics {
         registry.register(Box::new(accumulated_outgoing_limiter_delay.clone()))?;
         registry.register(Box::new(accumulated_incoming_limiter_delay.clone()))?;
 
+        let peer_traffic = PeerTrafficMetrics::new()?;
+        registry.register(Box::new(peer_traffic.bytes.clone()))?;
+
         Ok(Metrics {
             broadcast_requests,
             direct_message_requests,
             open_connections,
             queued_messages,
             peers,
+            clock_offset,
             out_count_protocol,
             out_count_consensus,
             out_count_deploy_gossip,
@@ -412,6 +445,7 @@ impl Metrics {
             out_state_connected,
             out_state_blocked,
             out_state_loopback,
+            out_backoff_addresses,
             in_count_protocol,
             in_count_consensus,
             in_count_deploy_gossip,
@@ -434,6 +468,7 @@ impl Metrics {
             requests_for_trie_finished,
             accumulated_outgoing_limiter_delay,
             accumulated_incoming_limiter_delay,
+            peer_traffic,
             registry: registry.clone(),
         })
     }
@@ -530,6 +565,24 @@ impl Metrics {
         }
     }
 
+    /// Starts recording the traffic of a connection to `peer`.
+    ///
+    /// The peer's series are removed once the recorders of all its connections have been dropped.
+    pub(super) fn record_peer_traffic(this: &Weak<Self>, peer: NodeId) -> Option<PeerTraffic> {
+        if let Some(metrics) = this.upgrade() {
+            let (bytes_out, bytes_in) = metrics.peer_traffic.acquire(peer);
+            Some(PeerTraffic {
+                peer,
+                bytes_out,
+                bytes_in,
+                metrics: this.clone(),
+            })
+        } else {
+            debug!("not recording metrics, component already shut down");
+            None
+        }
+    }
+
     /// Creates a set of outgoing metrics that is connected to this set of metrics.
     pub(super) fn create_outgoing_metrics(&self) -> OutgoingMetrics {
         OutgoingMetrics {
@@ -538,6 +591,7 @@ impl Metrics {
             out_state_connected: self.out_state_connected.clone(),
             out_state_blocked: self.out_state_blocked.clone(),
             out_state_loopback: self.out_state_loopback.clone(),
+            out_backoff_addresses: self.out_backoff_addresses.clone(),
         }
     }
 
@@ -567,6 +621,7 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.open_connections);
         unregister_metric!(self.registry, self.queued_messages);
         unregister_metric!(self.registry, self.peers);
+        unregister_metric!(self.registry, self.clock_offset);
 
         unregister_metric!(self.registry, self.out_count_protocol);
         unregister_metric!(self.registry, self.out_count_consensus);
@@ -593,6 +648,7 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.out_state_connected);
         unregister_metric!(self.registry, self.out_state_blocked);
         unregister_metric!(self.registry, self.out_state_loopback);
+        unregister_metric!(self.registry, self.out_backoff_addresses);
 
         unregister_metric!(self.registry, self.in_count_protocol);
         unregister_metric!(self.registry, self.in_count_consensus);
@@ -619,5 +675,126 @@ impl Drop for Metrics {
 
         unregister_metric!(self.registry, self.accumulated_outgoing_limiter_delay);
         unregister_metric!(self.registry, self.accumulated_incoming_limiter_delay);
+
+        unregister_metric!(self.registry, self.peer_traffic.bytes);
+    }
+}
+
+/// Traffic of each connected peer, by direction and message kind.
+///
+/// Series are labeled by peer, so they are only kept while the peer is connected, to avoid
+/// accumulating series of every node ever connected to.
+struct PeerTrafficMetrics {
+    /// Volume in bytes of messages exchanged with each connected peer.
+    bytes: IntCounterVec,
+    /// Number of connections to each peer whose traffic is being recorded.
+    connections: Mutex<HashMap<NodeId, usize>>,
+}
+
+impl PeerTrafficMetrics {
+    fn new() -> Result<Self, prometheus::Error> {
+        let bytes = IntCounterVec::new(
+            Opts::new(
+                "net_peer_bytes",
+                "volume in bytes of messages exchanged with a connected peer, by direction and \
+                 message kind",
+            ),
+            &["direction", "peer", "kind"],
+        )?;
+        Ok(PeerTrafficMetrics {
+            bytes,
+            connections: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Registers another connection to `peer`, returning its outgoing and incoming byte counters,
+    /// indexed by message kind.
+    fn acquire(&self, peer: NodeId) -> (Vec<IntCounter>, Vec<IntCounter>) {
+        // The series are created and removed under the lock, so that a connection established
+        // while another one to the same peer is closing never ends up with orphaned counters.
+        let mut connections = self.connections.lock().expect("lock poisoned");
+        *connections.entry(peer).or_default() += 1;
+
+        let peer_label = peer.to_string();
+        let counters = |direction: &str| -> Vec<IntCounter> {
+            MessageKind::ALL
+                .iter()
+                .map(|kind| {
+                    self.bytes
+                        .with_label_values(&[direction, &peer_label, &kind.to_string()])
+                })
+                .collect()
+        };
+        (counters(DIRECTION_OUT), counters(DIRECTION_IN))
+    }
+
+    /// Unregisters a connection to `peer`, removing its series if it was the last one.
+    fn release(&self, peer: NodeId) {
+        let mut connections = self.connections.lock().expect("lock poisoned");
+        match connections.get_mut(&peer) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                connections.remove(&peer);
+                let peer_label = peer.to_string();
+                for direction in [DIRECTION_OUT, DIRECTION_IN] {
+                    for kind in MessageKind::ALL.iter() {
+                        let _ = self.bytes.remove_label_values(&[
+                            direction,
+                            &peer_label,
+                            &kind.to_string(),
+                        ]);
+                    }
+                }
+            }
+            None => debug!(%peer, "no traffic recorded for peer"),
+        }
+    }
+}
+
+impl Debug for PeerTrafficMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeerTrafficMetrics")
+            .field("connections", &self.connections)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Records the traffic of a single connection to a peer.
+pub(super) struct PeerTraffic {
+    /// The peer connected to.
+    peer: NodeId,
+    /// Volume in bytes of outgoing messages, indexed by message kind.
+    bytes_out: Vec<IntCounter>,
+    /// Volume in bytes of incoming messages, indexed by message kind.
+    bytes_in: Vec<IntCounter>,
+    /// The metrics the counters belong to.
+    metrics: Weak<Metrics>,
+}
+
+impl PeerTraffic {
+    /// Records an outgoing payload.
+    pub(super) fn record_out(&self, kind: MessageKind, size: u64) {
+        self.bytes_out[kind as usize].inc_by(size);
+    }
+
+    /// Records an incoming payload.
+    pub(super) fn record_in(&self, kind: MessageKind, size: u64) {
+        self.bytes_in[kind as usize].inc_by(size);
+    }
+}
+
+impl Drop for PeerTraffic {
+    fn drop(&mut self) {
+        if let Some(metrics) = self.metrics.upgrade() {
+            metrics.peer_traffic.release(self.peer);
+        }
+    }
+}
+
+impl Debug for PeerTraffic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeerTraffic")
+            .field("peer", &self.peer)
+            .finish_non_exhaustive()
     }
 }
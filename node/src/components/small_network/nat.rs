@@ -0,0 +1,635 @@
+//! NAT traversal.
+//!
+//! Nodes run from home are usually behind a NAT gateway, requiring a port forwarding to be set up
+//! by hand and the gateway's external IP to be configured as the public address. When enabled,
+//! the node instead asks the gateway to map its listening port, first via NAT-PMP
+//! ([RFC 6886](https://tools.ietf.org/html/rfc6886)), falling back to UPnP IGD.
+//!
+//! The external address reported by the gateway is not trusted outright, as the gateway may itself
+//! be behind another NAT. Instead, peers accepting our connections report the IP they observe us
+//! connecting from during the handshake, and the mapped address is only advertised once enough
+//! distinct peers agree with it.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use futures::Future;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+use tracing::debug;
+
+use super::config::NatTraversalConfig;
+use crate::types::NodeId;
+
+/// Port the NAT-PMP service listens on at the gateway.
+const NAT_PMP_PORT: u16 = 5351;
+
+/// NAT-PMP opcode requesting the external address.
+const NAT_PMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+
+/// NAT-PMP opcode requesting a TCP mapping.
+const NAT_PMP_OP_MAP_TCP: u8 = 2;
+
+/// Address UPnP devices are discovered on via SSDP.
+const SSDP_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// Device type of a UPnP internet gateway device.
+const IGD_DEVICE_TYPE: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// UPnP services able to create port mappings.
+const UPNP_SERVICE_TYPES: [&str; 2] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// Description attached to port mappings created via UPnP.
+const UPNP_MAPPING_DESCRIPTION: &str = "casper-node";
+
+/// Path of the kernel routing table, used to detect the default gateway.
+const ROUTE_TABLE_PATH: &str = "/proc/net/route";
+
+/// Protocol used to create a port mapping.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq)]
+pub(crate) enum MappingProtocol {
+    NatPmp,
+    Upnp,
+}
+
+impl Display for MappingProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingProtocol::NatPmp => f.write_str("NAT-PMP"),
+            MappingProtocol::Upnp => f.write_str("UPnP"),
+        }
+    }
+}
+
+/// A mapping of our listening port on the local gateway.
+#[derive(Clone, DataSize, Debug)]
+pub(crate) struct PortMapping {
+    /// External address of the mapping, as reported by the gateway.
+    pub(super) external_addr: SocketAddr,
+    /// Protocol the mapping was created with.
+    pub(super) protocol: MappingProtocol,
+    /// Lifetime of the mapping granted by the gateway.
+    pub(super) lifetime: Duration,
+}
+
+impl Display for PortMapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} via {} for {}s",
+            self.external_addr,
+            self.protocol,
+            self.lifetime.as_secs()
+        )
+    }
+}
+
+/// Error mapping a port on the local gateway.
+#[derive(Debug, Error)]
+pub(crate) enum NatError {
+    /// No gateway was configured and none could be detected.
+    #[error("could not detect the default gateway, please configure it")]
+    NoGateway,
+    /// The configured gateway is not an IPv4 address.
+    #[error("gateway {0} is not an IPv4 address")]
+    InvalidGateway(String),
+    /// Communicating with the gateway failed.
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    /// The gateway did not respond in time.
+    #[error("request to gateway timed out")]
+    Timeout,
+    /// The gateway rejected a NAT-PMP request.
+    #[error("gateway returned NAT-PMP result code {0}")]
+    NatPmpResult(u16),
+    /// No UPnP internet gateway device responded to discovery.
+    #[error("no UPnP internet gateway device found")]
+    NoIgd,
+    /// The gateway rejected a UPnP request.
+    #[error("UPnP request failed: {0}")]
+    UpnpRequest(String),
+    /// The gateway sent a response that could not be understood.
+    #[error("invalid response from gateway: {0}")]
+    InvalidResponse(&'static str),
+    /// Neither protocol succeeded in mapping the port.
+    #[error("NAT-PMP failed: {nat_pmp}; UPnP failed: {upnp}")]
+    AllFailed {
+        nat_pmp: Box<NatError>,
+        upnp: Box<NatError>,
+    },
+}
+
+/// Tracks the IP addresses peers observed our connections to originate from.
+#[derive(Debug, Default)]
+pub(super) struct ObservedAddresses {
+    observations: BTreeMap<NodeId, IpAddr>,
+}
+
+impl ObservedAddresses {
+    /// Records that `peer_id` observed us connecting from `ip`, replacing its earlier observation.
+    ///
+    /// Returns the number of distinct peers which observed us at `ip`.
+    pub(super) fn record(&mut self, peer_id: NodeId, ip: IpAddr) -> usize {
+        self.observations.insert(peer_id, ip);
+        self.confirmations(ip)
+    }
+
+    /// Returns the number of distinct peers which observed us at `ip`.
+    pub(super) fn confirmations(&self, ip: IpAddr) -> usize {
+        self.observations
+            .values()
+            .filter(|observed| **observed == ip)
+            .count()
+    }
+}
+
+/// Maps `port` on the local gateway, trying NAT-PMP first and falling back to UPnP.
+pub(super) async fn map_port(
+    config: NatTraversalConfig,
+    port: u16,
+) -> Result<PortMapping, NatError> {
+    let gateway = gateway_ip(&config)?;
+    let lifetime = Duration::from(config.mapping_lifetime);
+    let timeout = Duration::from(config.request_timeout);
+
+    let nat_pmp_error = match map_port_nat_pmp(gateway, port, lifetime, timeout).await {
+        Ok(mapping) => return Ok(mapping),
+        Err(error) => error,
+    };
+    debug!(%gateway, %nat_pmp_error, "NAT-PMP port mapping failed, trying UPnP");
+
+    map_port_upnp(gateway, port, lifetime, timeout)
+        .await
+        .map_err(|upnp_error| NatError::AllFailed {
+            nat_pmp: Box::new(nat_pmp_error),
+            upnp: Box::new(upnp_error),
+        })
+}
+
+/// Returns the configured gateway, or the gateway of the default route if none is configured.
+fn gateway_ip(config: &NatTraversalConfig) -> Result<Ipv4Addr, NatError> {
+    match &config.gateway {
+        Some(gateway) => gateway
+            .parse()
+            .map_err(|_| NatError::InvalidGateway(gateway.clone())),
+        None => fs::read_to_string(ROUTE_TABLE_PATH)
+            .ok()
+            .and_then(|route_table| parse_default_gateway(&route_table))
+            .ok_or(NatError::NoGateway),
+    }
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`.
+fn parse_default_gateway(route_table: &str) -> Option<Ipv4Addr> {
+    /// Flag marking a route via a gateway.
+    const RTF_GATEWAY: u16 = 0x2;
+
+    route_table.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let destination = *fields.get(1)?;
+        // Addresses are printed as hexadecimal numbers in host byte order.
+        let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+        let flags = u16::from_str_radix(fields.get(3)?, 16).ok()?;
+        (destination == "00000000" && flags & RTF_GATEWAY != 0)
+            .then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+/// Runs an IO operation against the gateway, failing if it takes longer than `timeout`.
+async fn with_timeout<F, T>(timeout: Duration, future: F) -> Result<T, NatError>
+where
+    F: Future<Output = io::Result<T>>,
+{
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_elapsed| NatError::Timeout)?
+        .map_err(NatError::Io)
+}
+
+/// Maps `port` for TCP via NAT-PMP.
+async fn map_port_nat_pmp(
+    gateway: Ipv4Addr,
+    port: u16,
+    lifetime: Duration,
+    timeout: Duration,
+) -> Result<PortMapping, NatError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((gateway, NAT_PMP_PORT)).await?;
+
+    let response = nat_pmp_request(&socket, &[0, NAT_PMP_OP_EXTERNAL_ADDRESS], timeout).await?;
+    let external_ip = parse_nat_pmp_external_address(&response)?;
+
+    let request = encode_nat_pmp_mapping_request(port, lifetime);
+    let response = nat_pmp_request(&socket, &request, timeout).await?;
+    let (external_port, granted_lifetime) = parse_nat_pmp_mapping(&response, port)?;
+
+    Ok(PortMapping {
+        external_addr: SocketAddr::new(external_ip.into(), external_port),
+        protocol: MappingProtocol::NatPmp,
+        lifetime: Duration::from_secs(granted_lifetime.into()),
+    })
+}
+
+/// Sends a NAT-PMP request on the connected `socket` and waits for the response.
+async fn nat_pmp_request(
+    socket: &UdpSocket,
+    request: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, NatError> {
+    socket.send(request).await?;
+    let mut buffer = [0; 16];
+    let length = with_timeout(timeout, socket.recv(&mut buffer)).await?;
+    Ok(buffer[..length].to_vec())
+}
+
+/// Encodes a request to map `port` for TCP to the same external port.
+fn encode_nat_pmp_mapping_request(port: u16, lifetime: Duration) -> [u8; 12] {
+    let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+    let mut request = [0; 12];
+    request[1] = NAT_PMP_OP_MAP_TCP;
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+/// Checks the header of a NAT-PMP response to a request with the given `opcode`.
+fn check_nat_pmp_response(response: &[u8], opcode: u8, length: usize) -> Result<(), NatError> {
+    if response.len() < length {
+        return Err(NatError::InvalidResponse("truncated NAT-PMP response"));
+    }
+    // Responses carry the request's opcode with the high bit set.
+    if response[0] != 0 || response[1] != opcode | 0x80 {
+        return Err(NatError::InvalidResponse("unexpected NAT-PMP response"));
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(()),
+        result_code => Err(NatError::NatPmpResult(result_code)),
+    }
+}
+
+/// Parses the external IP from a NAT-PMP external address response.
+fn parse_nat_pmp_external_address(response: &[u8]) -> Result<Ipv4Addr, NatError> {
+    check_nat_pmp_response(response, NAT_PMP_OP_EXTERNAL_ADDRESS, 12)?;
+    Ok(Ipv4Addr::new(
+        response[8],
+        response[9],
+        response[10],
+        response[11],
+    ))
+}
+
+/// Parses the external port and granted lifetime in seconds from a NAT-PMP mapping response.
+fn parse_nat_pmp_mapping(response: &[u8], port: u16) -> Result<(u16, u32), NatError> {
+    check_nat_pmp_response(response, NAT_PMP_OP_MAP_TCP, 16)?;
+    if u16::from_be_bytes([response[8], response[9]]) != port {
+        return Err(NatError::InvalidResponse(
+            "NAT-PMP mapping for a different port",
+        ));
+    }
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+    Ok((external_port, lifetime))
+}
+
+/// Maps `port` for TCP via UPnP, using the same external port.
+async fn map_port_upnp(
+    gateway: Ipv4Addr,
+    port: u16,
+    lifetime: Duration,
+    timeout: Duration,
+) -> Result<PortMapping, NatError> {
+    let location = discover_igd(timeout).await?;
+    let (host, description_path) = split_url(&location)?;
+    let description = http_request(
+        host,
+        &format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+            description_path, host
+        ),
+        timeout,
+    )
+    .await?;
+    let (service_type, control_url) = parse_control_url(&description).ok_or(NatError::NoIgd)?;
+    let (control_host, control_path) = if control_url.starts_with("http://") {
+        split_url(control_url)?
+    } else {
+        (host, control_url)
+    };
+
+    let local_ip = local_ip(gateway).await?;
+    let lease_duration = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+    soap_request(
+        control_host,
+        control_path,
+        service_type,
+        "AddPortMapping",
+        &[
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", port.to_string()),
+            ("NewProtocol", "TCP".to_string()),
+            ("NewInternalPort", port.to_string()),
+            ("NewInternalClient", local_ip.to_string()),
+            ("NewEnabled", "1".to_string()),
+            (
+                "NewPortMappingDescription",
+                UPNP_MAPPING_DESCRIPTION.to_string(),
+            ),
+            ("NewLeaseDuration", lease_duration.to_string()),
+        ],
+        timeout,
+    )
+    .await?;
+
+    let response = soap_request(
+        control_host,
+        control_path,
+        service_type,
+        "GetExternalIPAddress",
+        &[],
+        timeout,
+    )
+    .await?;
+    let external_ip: IpAddr = tag_value(&response, "NewExternalIPAddress")
+        .and_then(|ip| ip.parse().ok())
+        .ok_or(NatError::InvalidResponse("missing external IP address"))?;
+
+    Ok(PortMapping {
+        external_addr: SocketAddr::new(external_ip, port),
+        protocol: MappingProtocol::Upnp,
+        lifetime,
+    })
+}
+
+/// Returns the local IP address used to reach `gateway`.
+async fn local_ip(gateway: Ipv4Addr) -> Result<IpAddr, NatError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    // Connecting a UDP socket sends nothing, but selects the interface to reach the gateway on.
+    socket.connect((gateway, NAT_PMP_PORT)).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Discovers an internet gateway device via SSDP, returning the URL of its description.
+async fn discover_igd(timeout: Duration) -> Result<String, NatError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}:{}\r\nST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n",
+        SSDP_ADDR.0, SSDP_ADDR.1, IGD_DEVICE_TYPE
+    );
+    socket.send_to(request.as_bytes(), SSDP_ADDR).await?;
+
+    let mut buffer = [0; 2048];
+    let (length, _) = match with_timeout(timeout, socket.recv_from(&mut buffer)).await {
+        Ok(received) => received,
+        Err(NatError::Timeout) => return Err(NatError::NoIgd),
+        Err(error) => return Err(error),
+    };
+    let response = str::from_utf8(&buffer[..length])
+        .map_err(|_| NatError::InvalidResponse("SSDP response is not valid UTF-8"))?;
+    parse_ssdp_location(response)
+        .map(ToString::to_string)
+        .ok_or(NatError::InvalidResponse("SSDP response without location"))
+}
+
+/// Parses the `LOCATION` header from an SSDP response.
+fn parse_ssdp_location(response: &str) -> Option<&str> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim())
+    })
+}
+
+/// Splits an `http://host:port/path` URL into its host and path.
+fn split_url(url: &str) -> Result<(&str, &str), NatError> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or(NatError::InvalidResponse("unsupported UPnP device URL"))?;
+    Ok(match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    })
+}
+
+/// Returns the type and control URL of the first service in a device description able to create
+/// port mappings.
+fn parse_control_url(description: &str) -> Option<(&'static str, &str)> {
+    description.split("<service>").skip(1).find_map(|service| {
+        let service_type = tag_value(service, "serviceType")?;
+        let service_type = UPNP_SERVICE_TYPES
+            .iter()
+            .find(|known| **known == service_type)?;
+        Some((*service_type, tag_value(service, "controlURL")?))
+    })
+}
+
+/// Returns the trimmed text of the first `tag` element in `xml`.
+fn tag_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find("</")?;
+    Some(xml[start..end].trim())
+}
+
+/// Invokes `action` on a UPnP service, returning the response body.
+async fn soap_request(
+    host: &str,
+    path: &str,
+    service_type: &str,
+    action: &str,
+    arguments: &[(&str, String)],
+    timeout: Duration,
+) -> Result<String, NatError> {
+    let arguments: String = arguments
+        .iter()
+        .map(|(name, value)| format!("<{0}>{1}</{0}>", name, value))
+        .collect();
+    let body = format!(
+        "<?xml version=\"1.0\"?>\r\n\
+        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+        s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+        <s:Body><u:{0} xmlns:u=\"{1}\">{2}</u:{0}></s:Body></s:Envelope>",
+        action, service_type, arguments
+    );
+    let request = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: text/xml; charset=\"utf-8\"\r\n\
+        Content-Length: {}\r\nSOAPAction: \"{}#{}\"\r\n\r\n{}",
+        path,
+        host,
+        body.len(),
+        service_type,
+        action,
+        body
+    );
+    http_request(host, &request, timeout).await
+}
+
+/// Sends a raw HTTP/1.0 `request` to `host`, returning the body of a successful response.
+async fn http_request(host: &str, request: &str, timeout: Duration) -> Result<String, NatError> {
+    let response = with_timeout(timeout, async {
+        let mut stream = TcpStream::connect(host).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        Ok(response)
+    })
+    .await?;
+    parse_http_response(&String::from_utf8_lossy(&response))
+}
+
+/// Returns the body of an HTTP response, or the UPnP error it describes.
+fn parse_http_response(response: &str) -> Result<String, NatError> {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or(NatError::InvalidResponse("malformed HTTP response"))?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|status_line| status_line.split_whitespace().nth(1))
+        .unwrap_or_default();
+    if status == "200" {
+        Ok(body.to_string())
+    } else {
+        let description = tag_value(body, "errorDescription").unwrap_or(status);
+        Err(NatError::UpnpRequest(description.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_default_gateway() {
+        let route_table = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0
+";
+        assert_eq!(
+            parse_default_gateway(route_table),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        assert_eq!(
+            parse_default_gateway(route_table.lines().next().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn should_encode_nat_pmp_mapping_request() {
+        assert_eq!(
+            encode_nat_pmp_mapping_request(34553, Duration::from_secs(7200)),
+            [0, 2, 0, 0, 0x86, 0xf9, 0x86, 0xf9, 0, 0, 0x1c, 0x20]
+        );
+    }
+
+    #[test]
+    fn should_parse_nat_pmp_responses() {
+        let external_address = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            parse_nat_pmp_external_address(&external_address).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 7)
+        );
+
+        let mapping = [
+            0, 130, 0, 0, 0, 0, 0, 1, 0x86, 0xf9, 0x86, 0xfa, 0, 0, 0x0e, 0x10,
+        ];
+        assert_eq!(
+            parse_nat_pmp_mapping(&mapping, 34553).unwrap(),
+            (34554, 3600)
+        );
+        assert!(matches!(
+            parse_nat_pmp_mapping(&mapping, 1234),
+            Err(NatError::InvalidResponse(_))
+        ));
+
+        let refused = [0, 130, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(matches!(
+            parse_nat_pmp_mapping(&refused, 34553),
+            Err(NatError::NatPmpResult(2))
+        ));
+        assert!(matches!(
+            parse_nat_pmp_external_address(&refused[..4]),
+            Err(NatError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn should_parse_upnp_responses() {
+        let ssdp_response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+            Location: http://192.168.1.1:5000/rootDesc.xml\r\nST: upnp:rootdevice\r\n\r\n";
+        let location = parse_ssdp_location(ssdp_response).unwrap();
+        assert_eq!(location, "http://192.168.1.1:5000/rootDesc.xml");
+        assert_eq!(
+            split_url(location).unwrap(),
+            ("192.168.1.1:5000", "/rootDesc.xml")
+        );
+
+        let description = "<root><device><serviceList>\
+            <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+            <controlURL>/ctl/L3F</controlURL></service>\
+            <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+            <controlURL> /ctl/IPConn </controlURL></service>\
+            </serviceList></device></root>";
+        assert_eq!(
+            parse_control_url(description),
+            Some((
+                "urn:schemas-upnp-org:service:WANIPConnection:1",
+                "/ctl/IPConn"
+            ))
+        );
+
+        let response = "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\n\r\n<s:Envelope><s:Body>\
+            <u:GetExternalIPAddressResponse><NewExternalIPAddress>203.0.113.7\
+            </NewExternalIPAddress></u:GetExternalIPAddressResponse></s:Body></s:Envelope>";
+        let body = parse_http_response(response).unwrap();
+        assert_eq!(
+            tag_value(&body, "NewExternalIPAddress"),
+            Some("203.0.113.7")
+        );
+
+        let fault = "HTTP/1.1 500 Internal Server Error\r\n\r\n<s:Envelope><s:Body><s:Fault>\
+            <detail><UPnPError><errorCode>718</errorCode>\
+            <errorDescription>ConflictInMappingEntry</errorDescription></UPnPError></detail>\
+            </s:Fault></s:Body></s:Envelope>";
+        assert!(matches!(
+            parse_http_response(fault),
+            Err(NatError::UpnpRequest(description)) if description == "ConflictInMappingEntry"
+        ));
+    }
+
+    #[test]
+    fn should_count_distinct_confirmations() {
+        let mut rng = TestRng::new();
+        let peer_1 = NodeId::random(&mut rng);
+        let peer_2 = NodeId::random(&mut rng);
+        let mapped_ip: IpAddr = [203, 0, 113, 7].into();
+        let other_ip: IpAddr = [198, 51, 100, 1].into();
+
+        let mut observed = ObservedAddresses::default();
+        assert_eq!(observed.record(peer_1, mapped_ip), 1);
+        // Repeated observations by the same peer don't count twice.
+        assert_eq!(observed.record(peer_1, mapped_ip), 1);
+        assert_eq!(observed.record(peer_2, other_ip), 1);
+        assert_eq!(observed.record(peer_2, mapped_ip), 2);
+        assert_eq!(observed.confirmations(other_ip), 0);
+    }
+}
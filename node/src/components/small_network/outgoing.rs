@@ -12,6 +12,12 @@
 //!   connection to the given address, only giving up if retry thresholds are exceeded, after which
 //!   it will be forgotten.
 //! * `block_addr` and `redeem_addr` can be used to maintain a `SocketAddr`-keyed block list.
+//! * If peer diversity limits are configured, addresses in network groups already holding their
+//!   share of outgoing connections are deferred by `learn_addr` and only dialed by a later
+//!   `perform_housekeeping` call once the limits permit it.
+//! * The backoff of consistently unreachable addresses can be retrieved via `backoff_states` and
+//!   handed to a new `OutgoingManager` via `restore_backoff_states`, so that they are not dialed
+//!   immediately after a restart.
 //! * `OutgoingManager` maintains an internal routing table. The `get_route` function can be used to
 //!   retrieve a "route" (typically a `sync::channel` accepting network messages) to a remote peer
 //!   by `NodeId`.
@@ -73,6 +79,14 @@
 //! └───────┴─────────────────────────────────────────────────┘
 //! ```
 //!
+//! # Backoff
+//!
+//! Reconnection attempts are delayed exponentially by the number of failed attempts so far. A
+//! dropped connection briefly passes through the `Waiting` state as well, and is redialed after the
+//! base timeout. Each delay is extended by a jitter derived from a per-node seed, so that nodes
+//! which lost their connections at the same time, e.g. due to a network blip, do not all reconnect
+//! in lockstep.
+//!
 //! # Timeouts/safety
 //!
 //! The `sweep` transition for connections usually does not happen during normal operations. Three
@@ -93,9 +107,13 @@
 #![allow(clippy::redundant_clone)]
 
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{
+        hash_map::{DefaultHasher, Entry},
+        BTreeMap, HashMap, HashSet,
+    },
     error::Error,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     mem,
     net::SocketAddr,
     time::{Duration, Instant},
@@ -103,10 +121,18 @@ use std::{
 
 use datasize::DataSize;
 
-use prometheus::IntGauge;
+use prometheus::{IntGauge, IntGaugeVec};
 use tracing::{debug, error_span, field::Empty, info, trace, warn, Span};
 
-use super::{display_error, NodeId};
+use super::{
+    display_error,
+    diversity::{DiversityLimits, DiversityStats},
+    NodeId,
+};
+
+/// Number of consecutive failed attempts after which an address is considered consistently
+/// unreachable, and its backoff state is retained across restarts.
+const MIN_RETAINED_BACKOFF_FAILURES: u8 = 3;
 
 /// An outgoing connection/address in various states.
 #[derive(DataSize, Debug)]
@@ -141,7 +167,8 @@ where
         failures_so_far: u8,
         /// The most recent connection error.
         ///
-        /// If not given, the connection was put into a `Waiting` state due to a sweep timeout.
+        /// If not given, the connection was put into a `Waiting` state due to a sweep timeout, a
+        /// dropped connection or a backoff state restored from a previous run.
         error: Option<E>,
         /// The precise moment when the last connection attempt failed.
         last_failure: Instant,
@@ -264,19 +291,36 @@ pub struct OutgoingConfig {
     pub(crate) retry_attempts: u8,
     /// The basic time slot for exponential backoff when reconnecting.
     pub(crate) base_timeout: Duration,
+    /// Maximum fraction of each backoff time added as jitter, between 0.0 and 1.0.
+    pub(crate) jitter: f32,
+    /// Seed from which the jitter of each backoff time is derived.
+    ///
+    /// Should be chosen at random, so that the jitter differs between nodes.
+    pub(crate) jitter_seed: u64,
     /// Time until an outgoing address is unblocked.
     pub(crate) unblock_after: Duration,
     /// Safety timeout, after which a connection is no longer expected to finish dialing.
     pub(crate) sweep_timeout: Duration,
+    /// Limits on the outgoing connections into a single network group, if enforced.
+    pub(crate) diversity: Option<DiversityLimits>,
 }
 
 impl OutgoingConfig {
-    /// Calculates the backoff time.
+    /// Calculates the backoff time for reconnecting to `addr`.
     ///
     /// `failed_attempts` (n) is the number of previous attempts *before* the current failure (thus
-    /// starting at 0). The backoff time will be double for each attempt.
-    fn calc_backoff(&self, failed_attempts: u8) -> Duration {
-        2u32.pow(failed_attempts as u32) * self.base_timeout
+    /// starting at 0). The backoff time will be double for each attempt, extended by a jitter of up
+    /// to `jitter` times itself, which is derived from the seed, the address and the attempt.
+    fn calc_backoff(&self, addr: SocketAddr, failed_attempts: u8) -> Duration {
+        let backoff = 2u32.pow(failed_attempts as u32) * self.base_timeout;
+        if self.jitter <= 0.0 {
+            return backoff;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        (self.jitter_seed, addr, failed_attempts).hash(&mut hasher);
+        let fraction = hasher.finish() as f64 / u64::MAX as f64;
+        backoff + backoff.mul_f64(self.jitter.min(1.0) as f64 * fraction)
     }
 }
 
@@ -298,6 +342,11 @@ where
     /// Contains a mapping from node IDs to connected socket addresses. A missing entry means that
     /// the destination is not connected.
     routes: HashMap<NodeId, SocketAddr>,
+    /// Learned addresses not yet dialed because their network group is saturated.
+    deferred: HashSet<SocketAddr>,
+    /// Number of failed attempts of addresses found unreachable during a previous run, applied
+    /// once the address is learned.
+    restored_backoffs: HashMap<SocketAddr, u8>,
     /// A set of outgoing metrics.
     #[data_size(skip)]
     metrics: OutgoingMetrics,
@@ -316,6 +365,8 @@ pub(super) struct OutgoingMetrics {
     pub(super) out_state_blocked: IntGauge,
     /// Number of outgoing connections in loopback state.
     pub(super) out_state_loopback: IntGauge,
+    /// Number of outgoing connections in waiting state, by number of failed attempts.
+    pub(super) out_backoff_addresses: IntGaugeVec,
 }
 
 // Note: We only implement `Default` here for use in testing with `OutgoingManager::new`.
@@ -338,6 +389,11 @@ impl Default for OutgoingMetrics {
             out_state_blocked: IntGauge::new("out_state_blocked", "internal out_state_blocked")
                 .unwrap(),
             out_state_loopback: IntGauge::new("out_state_loopback", "internal loopback").unwrap(),
+            out_backoff_addresses: IntGaugeVec::new(
+                prometheus::Opts::new("out_backoff_addresses", "internal out_backoff_addresses"),
+                &["failures"],
+            )
+            .unwrap(),
         }
     }
 }
@@ -360,6 +416,8 @@ where
             config,
             outgoing: Default::default(),
             routes: Default::default(),
+            deferred: Default::default(),
+            restored_backoffs: Default::default(),
             metrics,
         }
     }
@@ -463,7 +521,15 @@ where
             Some(OutgoingState::Connected { .. }) => self.metrics.out_state_connected.dec(),
             Some(OutgoingState::Connecting { .. }) => self.metrics.out_state_connecting.dec(),
             Some(OutgoingState::Loopback) => self.metrics.out_state_loopback.dec(),
-            Some(OutgoingState::Waiting { .. }) => self.metrics.out_state_waiting.dec(),
+            Some(OutgoingState::Waiting {
+                failures_so_far, ..
+            }) => {
+                self.metrics.out_state_waiting.dec();
+                self.metrics
+                    .out_backoff_addresses
+                    .with_label_values(&[&failures_so_far.to_string()])
+                    .dec();
+            }
             None => {
                 // Nothing to do, there was no previous state.
             }
@@ -474,7 +540,15 @@ where
             OutgoingState::Connected { .. } => self.metrics.out_state_connected.inc(),
             OutgoingState::Connecting { .. } => self.metrics.out_state_connecting.inc(),
             OutgoingState::Loopback => self.metrics.out_state_loopback.inc(),
-            OutgoingState::Waiting { .. } => self.metrics.out_state_waiting.inc(),
+            OutgoingState::Waiting {
+                failures_so_far, ..
+            } => {
+                self.metrics.out_state_waiting.inc();
+                self.metrics
+                    .out_backoff_addresses
+                    .with_label_values(&[&failures_so_far.to_string()])
+                    .inc();
+            }
         }
 
         new_outgoing
@@ -504,12 +578,83 @@ where
         self.routes.keys().cloned()
     }
 
+    /// Iterates over the addresses of all outgoing connections which are connected or being
+    /// (re)connected.
+    fn active_addrs(&'_ self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.outgoing
+            .iter()
+            .filter(|(_, outgoing)| {
+                matches!(
+                    outgoing.state,
+                    OutgoingState::Connecting { .. }
+                        | OutgoingState::Waiting { .. }
+                        | OutgoingState::Connected { .. }
+                )
+            })
+            .map(|(addr, _)| *addr)
+    }
+
+    /// Checks whether dialing `addr` keeps the outgoing connections within the peer diversity
+    /// limits.
+    fn diversity_permits(&self, addr: SocketAddr) -> bool {
+        let limits = match self.config.diversity {
+            Some(ref limits) => limits,
+            None => return true,
+        };
+        let group = match limits.group(addr.ip()) {
+            Some(group) => group,
+            None => return true,
+        };
+
+        let mut total_count = 0;
+        let mut group_count = 0;
+        for active_addr in self.active_addrs() {
+            total_count += 1;
+            if limits.group(active_addr.ip()) == Some(group) {
+                group_count += 1;
+            }
+        }
+
+        limits.permits(group_count, total_count)
+    }
+
+    /// Returns statistics on the network groups of the outgoing connections.
+    pub(crate) fn diversity_stats(&self) -> DiversityStats {
+        let mut stats = DiversityStats {
+            enforced: self.config.diversity.is_some(),
+            deferred: self.deferred.len(),
+            ..Default::default()
+        };
+        let mut outgoing_by_group = BTreeMap::new();
+
+        for addr in self.active_addrs() {
+            stats.total_outgoing += 1;
+            let group = match self.config.diversity {
+                Some(ref limits) => limits.group(addr.ip()),
+                None => DiversityLimits::default_group(addr.ip()),
+            };
+            match group {
+                Some(group) => *outgoing_by_group.entry(group).or_insert(0) += 1,
+                None => stats.exempt_outgoing += 1,
+            }
+        }
+
+        stats.outgoing_by_group = outgoing_by_group
+            .into_iter()
+            .map(|(group, count)| (group.to_string(), count))
+            .collect();
+        stats
+    }
+
     /// Notify about a potentially new address that has been discovered.
     ///
-    /// Immediately triggers the connection process to said address if it was not known before.
+    /// Immediately triggers the connection process to said address if it was not known before,
+    /// unless it was found unreachable during a previous run, in which case it is dialed once its
+    /// restored backoff has passed.
     ///
     /// A connection marked `unforgettable` will never be evicted but reset instead when it exceeds
-    /// the retry limit.
+    /// the retry limit. It is also exempt from the peer diversity limits, while any other address
+    /// in a saturated network group is deferred until the limits permit dialing it.
     pub(crate) fn learn_addr(
         &mut self,
         addr: SocketAddr,
@@ -517,26 +662,50 @@ where
         now: Instant,
     ) -> Option<DialRequest<H>> {
         let span = make_span(addr, self.outgoing.get(&addr));
+        let defer =
+            !unforgettable && !self.outgoing.contains_key(&addr) && !self.diversity_permits(addr);
         span.clone()
             .in_scope(move || match self.outgoing.entry(addr) {
                 Entry::Occupied(_) => {
                     debug!("ignoring already known address");
                     None
                 }
+                Entry::Vacant(_vacant) if defer => {
+                    if self.deferred.insert(addr) {
+                        info!("deferring newly learned address, network group saturated");
+                    }
+                    None
+                }
                 Entry::Vacant(_vacant) => {
-                    info!("connecting to newly learned address");
-                    let outgoing = self.change_outgoing_state(
-                        addr,
-                        OutgoingState::Connecting {
-                            failures_so_far: 0,
-                            since: now,
-                        },
-                    );
+                    let restored_backoff = self.restored_backoffs.remove(&addr);
+                    let new_state = match restored_backoff {
+                        Some(failures_so_far) => {
+                            info!(
+                                attempts = failures_so_far,
+                                "delaying newly learned address, unreachable during previous run"
+                            );
+                            OutgoingState::Waiting {
+                                failures_so_far,
+                                error: None,
+                                last_failure: now,
+                            }
+                        }
+                        None => {
+                            info!("connecting to newly learned address");
+                            OutgoingState::Connecting {
+                                failures_so_far: 0,
+                                since: now,
+                            }
+                        }
+                    };
+                    let outgoing = self.change_outgoing_state(addr, new_state);
                     if outgoing.is_unforgettable != unforgettable {
                         outgoing.is_unforgettable = unforgettable;
                         debug!(unforgettable, "marked");
                     }
-                    Some(DialRequest::Dial { addr, span })
+                    restored_backoff
+                        .is_none()
+                        .then(|| DialRequest::Dial { addr, span })
                 }
             })
     }
@@ -654,7 +823,7 @@ where
                         }
                     } else {
                         // The address has not exceeded the limit, so check if it is due.
-                        let due = last_failure + self.config.calc_backoff(failures_so_far);
+                        let due = last_failure + self.config.calc_backoff(addr, failures_so_far);
                         if now >= due {
                             debug!(attempts = failures_so_far, "address reconnecting");
 
@@ -716,7 +885,7 @@ where
         });
 
         // Reconnect all others.
-        to_reconnect
+        let mut dial_requests: Vec<_> = to_reconnect
             .into_iter()
             .map(|(addr, failures_so_far)| {
                 let span = make_span(addr, self.outgoing.get(&addr));
@@ -733,7 +902,36 @@ where
 
                 DialRequest::Dial { addr, span }
             })
-            .collect()
+            .collect();
+
+        // Dial deferred addresses whose network group is no longer saturated.
+        let deferred: Vec<_> = self.deferred.iter().copied().collect();
+        for addr in deferred {
+            if self.outgoing.contains_key(&addr) {
+                // The address has been learned as unforgettable or blocked in the meantime.
+                self.deferred.remove(&addr);
+                continue;
+            }
+            if !self.diversity_permits(addr) {
+                continue;
+            }
+            self.deferred.remove(&addr);
+
+            let span = make_span::<H, E>(addr, None);
+            span.clone().in_scope(|| {
+                info!("connecting to deferred address");
+                self.change_outgoing_state(
+                    addr,
+                    OutgoingState::Connecting {
+                        failures_so_far: 0,
+                        since: now,
+                    },
+                )
+            });
+            dial_requests.push(DialRequest::Dial { addr, span });
+        }
+
+        dial_requests
     }
 
     /// Handles the outcome of a dialing attempt.
@@ -826,15 +1024,12 @@ where
 
     /// Notifies the connection manager about a dropped connection.
     ///
-    /// This will usually result in an immediate reconnection.
-    pub(crate) fn handle_connection_drop(
-        &mut self,
-        addr: SocketAddr,
-        now: Instant,
-    ) -> Option<DialRequest<H>> {
+    /// This will usually result in a reconnection on the first `perform_housekeeping` call after
+    /// the base timeout, extended by its jitter, has passed.
+    pub(crate) fn handle_connection_drop(&mut self, addr: SocketAddr, now: Instant) {
         let span = make_span(addr, self.outgoing.get(&addr));
 
-        span.clone().in_scope(move || {
+        span.in_scope(move || {
             if let Some(outgoing) = self.outgoing.get(&addr) {
                 match outgoing.state {
                     OutgoingState::Waiting { .. }
@@ -843,31 +1038,52 @@ where
                         // We should, under normal circumstances, not receive drop notifications for
                         // any of these. Connection failures are handled by the dialer.
                         warn!("unexpected drop notification");
-                        None
                     }
                     OutgoingState::Connected { .. } => {
-                        // Drop the handle, immediately initiate a reconnection.
+                        // Drop the handle and wait before reconnecting, to avoid all peers
+                        // reconnecting at once after a network-wide disruption.
                         self.change_outgoing_state(
                             addr,
-                            OutgoingState::Connecting {
+                            OutgoingState::Waiting {
                                 failures_so_far: 0,
-                                since: now,
+                                error: None,
+                                last_failure: now,
                             },
                         );
-                        Some(DialRequest::Dial { addr, span })
                     }
                     OutgoingState::Blocked { .. } => {
                         // Blocked addresses ignore connection drops.
                         debug!("received drop notification for blocked connection");
-                        None
                     }
                 }
             } else {
                 warn!("received connection drop notification for unknown connection");
-                None
             }
         })
     }
+
+    /// Returns the number of failed attempts of all consistently unreachable addresses, which
+    /// should be retained across restarts.
+    pub(crate) fn backoff_states(&self) -> BTreeMap<SocketAddr, u8> {
+        self.outgoing
+            .iter()
+            .filter_map(|(addr, outgoing)| match outgoing.state {
+                OutgoingState::Waiting {
+                    failures_so_far, ..
+                } if failures_so_far >= MIN_RETAINED_BACKOFF_FAILURES => {
+                    Some((*addr, failures_so_far.min(self.config.retry_attempts)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Restores the backoff states retained from a previous run.
+    ///
+    /// Each restored address will only be dialed once its backoff has passed after it is learned.
+    pub(crate) fn restore_backoff_states(&mut self, backoff_states: BTreeMap<SocketAddr, u8>) {
+        self.restored_backoffs.extend(backoff_states);
+    }
 }
 
 #[cfg(test)]
@@ -877,7 +1093,10 @@ mod tests {
     use datasize::DataSize;
     use thiserror::Error;
 
-    use super::{DialOutcome, DialRequest, NodeId, OutgoingConfig, OutgoingManager};
+    use super::{
+        super::{config::PeerDiversityConfig, diversity::DiversityLimits},
+        DialOutcome, DialRequest, NodeId, OutgoingConfig, OutgoingManager,
+    };
     use crate::testing::{init_logging, test_clock::TestClock};
 
     /// Error for test dialer.
@@ -894,8 +1113,11 @@ mod tests {
         OutgoingConfig {
             retry_attempts: 3,
             base_timeout: Duration::from_secs(1),
+            jitter: 0.0,
+            jitter_seed: 0,
             unblock_after: Duration::from_secs(60),
             sweep_timeout: Duration::from_secs(45),
+            diversity: None,
         }
     }
 
@@ -992,24 +1214,135 @@ mod tests {
         assert_eq!(manager.get_route(id_a), Some(&99));
         assert_eq!(manager.get_addr(id_a), Some(addr_a));
 
-        // Time passes, and our connection drops. Reconnecting should be immediate.
+        // Time passes, and our connection drops. Reconnecting should happen after the base timeout.
         assert!(manager.perform_housekeeping(clock.now()).is_empty());
         clock.advance_time(20_000);
-        assert!(dials(
-            addr_a,
-            &manager.handle_connection_drop(addr_a, clock.now())
-        ));
-        assert_eq!(manager.metrics().out_state_connecting.get(), 1);
-        assert_eq!(manager.metrics().out_state_waiting.get(), 0);
+        manager.handle_connection_drop(addr_a, clock.now());
+        assert_eq!(manager.metrics().out_state_connecting.get(), 0);
+        assert_eq!(manager.metrics().out_state_waiting.get(), 1);
+        assert_eq!(
+            manager
+                .metrics()
+                .out_backoff_addresses
+                .with_label_values(&["0"])
+                .get(),
+            1
+        );
 
         // The route should have been cleared.
         assert!(manager.get_route(id_a).is_none());
         assert!(manager.get_addr(id_a).is_none());
 
+        assert!(manager.perform_housekeeping(clock.now()).is_empty());
+        clock.advance_time(1_000);
+        assert!(dials(addr_a, &manager.perform_housekeeping(clock.now())));
+        assert_eq!(manager.metrics().out_state_connecting.get(), 1);
+        assert_eq!(manager.metrics().out_state_waiting.get(), 0);
+
         // Reconnection is already in progress, so we do not expect another request on housekeeping.
         assert!(manager.perform_housekeeping(clock.now()).is_empty());
     }
 
+    #[test]
+    fn backoff_jitter_is_bounded() {
+        let config = OutgoingConfig {
+            jitter: 0.5,
+            jitter_seed: 12345,
+            ..test_config()
+        };
+
+        let backoffs: HashSet<Duration> = (1..=50)
+            .map(|octet| {
+                let addr = SocketAddr::from(([10, 0, 0, octet], 1234));
+                let backoff = config.calc_backoff(addr, 2);
+                assert!(backoff >= Duration::from_secs(4));
+                assert!(backoff <= Duration::from_secs(6));
+
+                // The jitter is deterministic for a given seed, address and attempt.
+                assert_eq!(backoff, config.calc_backoff(addr, 2));
+                backoff
+            })
+            .collect();
+
+        // Addresses should not all be retried at the same time.
+        assert!(backoffs.len() > 1);
+
+        // Without jitter, backoff times are exact.
+        let addr: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        assert_eq!(test_config().calc_backoff(addr, 3), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn backoff_states_are_restored() {
+        init_logging();
+
+        let mut clock = TestClock::new();
+
+        let addr_a: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        let addr_b: SocketAddr = "5.6.7.8:5678".parse().unwrap();
+
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(OutgoingConfig {
+            retry_attempts: 5,
+            ..test_config()
+        });
+        assert!(dials(
+            addr_a,
+            &manager.learn_addr(addr_a, false, clock.now())
+        ));
+        assert!(dials(
+            addr_b,
+            &manager.learn_addr(addr_b, false, clock.now())
+        ));
+
+        // Address `addr_a` fails repeatedly, while `addr_b` fails only once.
+        for attempt in 1..=3 {
+            assert!(manager
+                .handle_dial_outcome(DialOutcome::Failed {
+                    addr: addr_a,
+                    error: TestDialerError { id: attempt },
+                    when: clock.now(),
+                })
+                .is_none());
+            if attempt == 1 {
+                assert!(manager
+                    .handle_dial_outcome(DialOutcome::Failed {
+                        addr: addr_b,
+                        error: TestDialerError { id: 100 },
+                        when: clock.now(),
+                    })
+                    .is_none());
+            }
+            if attempt < 3 {
+                clock.advance_time(2u64.pow(attempt) * 1_000);
+                assert!(dials(addr_a, &manager.perform_housekeeping(clock.now())));
+            }
+        }
+
+        // Only the consistently unreachable address is retained.
+        let backoff_states = manager.backoff_states();
+        assert_eq!(backoff_states.len(), 1);
+        assert_eq!(backoff_states.get(&addr_a), Some(&3));
+
+        // After a restart, the restored address is not dialed right away.
+        let mut clock = TestClock::new();
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(OutgoingConfig {
+            retry_attempts: 5,
+            ..test_config()
+        });
+        manager.restore_backoff_states(backoff_states);
+        assert!(manager.learn_addr(addr_a, false, clock.now()).is_none());
+        assert!(dials(
+            addr_b,
+            &manager.learn_addr(addr_b, false, clock.now())
+        ));
+        assert_eq!(manager.metrics().out_state_waiting.get(), 1);
+
+        clock.advance_time(7_000);
+        assert!(manager.perform_housekeeping(clock.now()).is_empty());
+        clock.advance_time(1_000);
+        assert!(dials(addr_a, &manager.perform_housekeeping(clock.now())));
+    }
+
     #[test]
     fn connections_forgotten_after_too_many_tries() {
         init_logging();
@@ -1422,4 +1755,62 @@ mod tests {
         assert!(manager.perform_housekeeping(clock.now()).is_empty());
         assert!(manager.is_blocked(addr_a));
     }
+
+    #[test]
+    fn saturated_network_groups_are_deferred() {
+        init_logging();
+
+        let mut clock = TestClock::new();
+
+        let diversity = DiversityLimits::from_config(&PeerDiversityConfig {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(OutgoingConfig {
+            diversity,
+            ..test_config()
+        });
+
+        // The first two addresses in 1.2.0.0/16 are covered by the minimum group allowance.
+        let addr_a: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        let addr_b: SocketAddr = "1.2.5.6:1234".parse().unwrap();
+        let addr_c: SocketAddr = "1.2.7.8:1234".parse().unwrap();
+        let addr_d: SocketAddr = "1.2.9.10:1234".parse().unwrap();
+        assert!(dials(
+            addr_a,
+            &manager.learn_addr(addr_a, false, clock.now())
+        ));
+        assert!(dials(
+            addr_b,
+            &manager.learn_addr(addr_b, false, clock.now())
+        ));
+
+        // A third one is deferred, unless unforgettable.
+        assert!(manager.learn_addr(addr_c, false, clock.now()).is_none());
+        assert!(dials(
+            addr_d,
+            &manager.learn_addr(addr_d, true, clock.now())
+        ));
+        assert_eq!(manager.diversity_stats().deferred, 1);
+        assert_eq!(manager.diversity_stats().outgoing_by_group["1.2.0.0/16"], 3);
+
+        // Other subnets and local addresses are not affected.
+        let local_addr: SocketAddr = "127.0.0.1:1234".parse().unwrap();
+        assert!(dials(
+            local_addr,
+            &manager.learn_addr(local_addr, false, clock.now())
+        ));
+        for octet in 3..14 {
+            let addr = SocketAddr::from(([1, octet, 0, 1], 1234));
+            assert!(dials(addr, &manager.learn_addr(addr, false, clock.now())));
+        }
+        assert_eq!(manager.diversity_stats().exempt_outgoing, 1);
+
+        // With 15 outgoing connections, a quarter of 16 permits a fourth connection into the group.
+        clock.advance_time(1_000);
+        assert!(dials(addr_c, &manager.perform_housekeeping(clock.now())));
+        assert_eq!(manager.diversity_stats().deferred, 0);
+        assert!(manager.perform_housekeeping(clock.now()).is_empty());
+    }
 }
@@ -0,0 +1,266 @@
+//! Signed peer records, gossiped to advertise the addresses a node can be reached at.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    net::SocketAddr,
+};
+
+use datasize::DataSize;
+use itertools::Itertools;
+use openssl::{
+    error::ErrorStack,
+    pkey::{PKeyRef, Private},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::{ProtocolVersion, Timestamp};
+
+use crate::{
+    tls::{CompressedPublicKey, RawSignature, ValidationError},
+    types::{Item, NodeId, Tag},
+};
+
+/// Maximum number of addresses a single peer record can list.
+pub(crate) const MAX_PEER_RECORD_ADDRESSES: usize = 4;
+
+/// An error creating or validating a peer record.
+#[derive(Debug, Error)]
+pub(crate) enum PeerRecordError {
+    /// The record doesn't list any address.
+    #[error("peer record lists no address")]
+    NoAddresses,
+    /// The signed part of the record could not be serialized.
+    #[error("failed to serialize peer record: {0}")]
+    Serialization(#[source] bincode::Error),
+    /// The signing key could not be used.
+    #[error("failed to sign peer record: {0}")]
+    KeyUnusable(#[source] ValidationError),
+    /// The record could not be signed.
+    #[error("failed to sign peer record: {0}")]
+    Signing(#[source] ErrorStack),
+    /// The signature doesn't match the record.
+    #[error("invalid peer record signature: {0}")]
+    InvalidSignature(#[source] ValidationError),
+    /// The record was signed by a key other than the one of the node it describes.
+    #[error("peer record of {node_id} signed by {signer}")]
+    WrongSigner {
+        /// The node described by the record.
+        node_id: NodeId,
+        /// The node whose key signed the record.
+        signer: NodeId,
+    },
+}
+
+/// A record of the addresses a node can be reached at, signed with the node's TLS key.
+///
+/// Since the ID of a node is the fingerprint of its TLS key, any node can check that a record was
+/// created by the node it describes, even if it never connected to it.
+#[derive(
+    Copy, Clone, DataSize, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug,
+)]
+pub struct PeerRecord {
+    /// The node the record describes.
+    node_id: NodeId,
+    /// The addresses of the node, in order of preference, unused slots being `None`.
+    #[data_size(skip)]
+    addresses: [Option<SocketAddr>; MAX_PEER_RECORD_ADDRESSES],
+    /// The time the record was created.
+    timestamp: Timestamp,
+    /// The protocol version the node was running when creating the record.
+    protocol_version: ProtocolVersion,
+    /// The public key of the node, whose fingerprint is the node ID.
+    #[data_size(skip)]
+    public_key: CompressedPublicKey,
+    /// The signature of all the fields above.
+    #[data_size(skip)]
+    signature: RawSignature,
+}
+
+impl PeerRecord {
+    /// Creates a new record of the given addresses, signed with `secret_key`.
+    ///
+    /// Only the first `MAX_PEER_RECORD_ADDRESSES` addresses are included.
+    pub(super) fn new(
+        node_id: NodeId,
+        addresses: &[SocketAddr],
+        timestamp: Timestamp,
+        protocol_version: ProtocolVersion,
+        secret_key: &PKeyRef<Private>,
+    ) -> Result<Self, PeerRecordError> {
+        if addresses.is_empty() {
+            return Err(PeerRecordError::NoAddresses);
+        }
+        let mut address_slots = [None; MAX_PEER_RECORD_ADDRESSES];
+        for (slot, address) in address_slots.iter_mut().zip(addresses) {
+            *slot = Some(*address);
+        }
+
+        let public_key = CompressedPublicKey::from_secret_key(secret_key)
+            .map_err(PeerRecordError::KeyUnusable)?;
+        let signed_data = signed_data(node_id, &address_slots, timestamp, protocol_version)?;
+        let signature =
+            RawSignature::create(secret_key, &signed_data).map_err(PeerRecordError::Signing)?;
+
+        Ok(PeerRecord {
+            node_id,
+            addresses: address_slots,
+            timestamp,
+            protocol_version,
+            public_key,
+            signature,
+        })
+    }
+
+    /// Returns the ID of the node the record describes.
+    pub(crate) fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Returns the addresses of the node.
+    pub(crate) fn addresses(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.addresses.iter().flatten().copied()
+    }
+
+    /// Returns the time the record was created.
+    pub(crate) fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Returns the protocol version the node was running when creating the record.
+    pub(crate) fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+}
+
+/// Returns the data signed by the creator of a peer record.
+fn signed_data(
+    node_id: NodeId,
+    addresses: &[Option<SocketAddr>; MAX_PEER_RECORD_ADDRESSES],
+    timestamp: Timestamp,
+    protocol_version: ProtocolVersion,
+) -> Result<Vec<u8>, PeerRecordError> {
+    bincode::serialize(&(node_id, addresses, timestamp, protocol_version))
+        .map_err(PeerRecordError::Serialization)
+}
+
+impl Display for PeerRecord {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "peer-record {} [{}] at {}",
+            self.node_id,
+            self.addresses().join(", "),
+            self.timestamp
+        )
+    }
+}
+
+impl Item for PeerRecord {
+    type Id = PeerRecord;
+    type ValidationError = PeerRecordError;
+    const TAG: Tag = Tag::GossipedAddress;
+    const ID_IS_COMPLETE_ITEM: bool = true;
+
+    fn validate(&self) -> Result<(), Self::ValidationError> {
+        if self.addresses().next().is_none() {
+            return Err(PeerRecordError::NoAddresses);
+        }
+        let signer = NodeId::from(self.public_key.fingerprint());
+        if signer != self.node_id {
+            return Err(PeerRecordError::WrongSigner {
+                node_id: self.node_id,
+                signer,
+            });
+        }
+        let signed_data = signed_data(
+            self.node_id,
+            &self.addresses,
+            self.timestamp,
+            self.protocol_version,
+        )?;
+        self.signature
+            .verify(&self.public_key, &signed_data)
+            .map_err(PeerRecordError::InvalidSignature)
+    }
+
+    fn validate_id(id: &Self::Id) -> Result<(), Self::ValidationError> {
+        id.validate()
+    }
+
+    fn id(&self) -> Self::Id {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls;
+
+    fn new_record(addresses: &[SocketAddr]) -> (PeerRecord, NodeId) {
+        let (cert, secret_key) = tls::generate_node_cert().unwrap();
+        let tls_cert = tls::validate_self_signed_cert(cert).unwrap();
+        let node_id = NodeId::from(tls_cert.public_key_fingerprint());
+        let record = PeerRecord::new(
+            node_id,
+            addresses,
+            Timestamp::from(1_000),
+            ProtocolVersion::V1_0_0,
+            &secret_key,
+        )
+        .unwrap();
+        (record, node_id)
+    }
+
+    #[test]
+    fn should_validate_signed_record() {
+        let addresses: Vec<SocketAddr> = (1..=5)
+            .map(|port| SocketAddr::from(([127, 0, 0, 1], port)))
+            .collect();
+        let (record, node_id) = new_record(&addresses);
+
+        assert_eq!(record.node_id(), node_id);
+        assert_eq!(
+            record.addresses().collect::<Vec<_>>(),
+            addresses[..MAX_PEER_RECORD_ADDRESSES]
+        );
+        record.validate().expect("record should be valid");
+
+        let serialized = bincode::serialize(&record).unwrap();
+        let deserialized: PeerRecord = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, record);
+        deserialized
+            .validate()
+            .expect("record should still be valid");
+    }
+
+    #[test]
+    fn should_reject_tampered_record() {
+        let (mut record, _) = new_record(&[SocketAddr::from(([127, 0, 0, 1], 34553))]);
+
+        record.addresses[0] = Some(SocketAddr::from(([10, 0, 0, 1], 34553)));
+        assert!(matches!(
+            record.validate(),
+            Err(PeerRecordError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_record_signed_by_other_node() {
+        let address = SocketAddr::from(([127, 0, 0, 1], 34553));
+        let (record, _) = new_record(&[address]);
+        let (other_record, other_node_id) = new_record(&[address]);
+
+        // Claim the other node's identity, keeping our own key and signature.
+        let spoofed_record = PeerRecord {
+            node_id: other_node_id,
+            ..record
+        };
+        assert!(matches!(
+            spoofed_record.validate(),
+            Err(PeerRecordError::WrongSigner { .. })
+        ));
+        assert!(other_record.validate().is_ok());
+    }
+}
@@ -4,11 +4,11 @@ use std::{
     error::Error as StdError,
     fmt::Display,
     io,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Weak,
+        Arc, RwLock, Weak,
     },
     time::Duration,
 };
@@ -39,16 +39,18 @@ use tracing::{
     info, trace, warn, Instrument, Span,
 };
 
-use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
+use casper_types::{ProtocolVersion, PublicKey, TimeDiff, Timestamp};
 
 use super::{
+    access_control::AllowList,
     chain_info::ChainInfo,
+    clock_drift,
     counting_format::{ConnectionId, Role},
     error::{ConnectionError, IoError},
     event::{IncomingConnection, OutgoingConnection},
     full_transport,
     limiter::LimiterHandle,
-    message::ConsensusKeyPair,
+    message::{ConsensusCertificate, ConsensusKeyPair},
     message_pack_format::MessagePackFormat,
     EstimatorWeights, Event, FramedTransport, FullTransport, Message, Metrics, Payload, Transport,
 };
@@ -77,6 +79,10 @@ struct HandshakeOutcome {
     peer_consensus_public_key: Option<PublicKey>,
     /// Holds the information whether the remote node is syncing.
     is_peer_syncing: bool,
+    /// Our IP address as observed by the peer, if it reported one.
+    observed_ip: Option<IpAddr>,
+    /// Offset of our clock from the peer's in milliseconds, if it sent a timestamp.
+    clock_offset: Option<i64>,
 }
 
 /// Low-level TLS connection function.
@@ -115,9 +121,7 @@ where
         .peer_certificate()
         .ok_or(ConnectionError::NoPeerCertificate)?;
 
-    let validated_peer_cert = context
-        .validate_peer_cert(peer_cert)
-        .map_err(ConnectionError::PeerCertificateInvalid)?;
+    let validated_peer_cert = context.admit_peer_cert(peer_cert)?;
 
     let peer_id = NodeId::from(validated_peer_cert.public_key_fingerprint());
 
@@ -153,12 +157,15 @@ where
     let framed_transport = framed_transport(transport, context.chain_info.maximum_net_message_size);
 
     // Negotiate the handshake, concluding the incoming connection process.
-    match negotiate_handshake::<P, _>(&context, framed_transport, connection_id).await {
+    // We dialed the peer, so we cannot observe anything about its address worth reporting.
+    match negotiate_handshake::<P, _>(&context, framed_transport, connection_id, None).await {
         Ok(HandshakeOutcome {
             framed_transport,
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            observed_ip,
+            clock_offset,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
@@ -173,6 +180,7 @@ where
             let full_transport = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
+                peer_id,
                 framed_transport,
                 Role::Dialer,
             );
@@ -184,6 +192,8 @@ where
                 peer_consensus_public_key,
                 sink,
                 is_syncing,
+                observed_ip,
+                clock_offset,
             }
         }
         Err(error) => OutgoingConnection::Failed {
@@ -214,7 +224,9 @@ where
     /// Chain info extract from chainspec.
     pub(super) chain_info: ChainInfo,
     /// Our own public listening address.
-    pub(super) public_addr: SocketAddr,
+    ///
+    /// May be replaced at runtime once NAT traversal has verified a mapped address.
+    pub(super) public_addr: RwLock<SocketAddr>,
     /// Optional set of consensus keys, to identify as a validator during handshake.
     pub(super) consensus_keys: Option<ConsensusKeyPair>,
     /// Timeout for handshake completion.
@@ -231,15 +243,63 @@ where
     pub(super) max_in_flight_demands: usize,
     /// Flag indicating whether this node is syncing.
     pub(super) is_syncing: AtomicBool,
+    /// The peers permitted to connect, if running in permissioned mode.
+    pub(super) allow_list: Option<RwLock<AllowList>>,
 }
 
 impl<REv> NetworkContext<REv> {
+    /// Returns our current public listening address.
+    pub(super) fn public_addr(&self) -> SocketAddr {
+        *self
+            .public_addr
+            .read()
+            .expect("public address lock poisoned")
+    }
+
+    /// Replaces our public listening address, used in all subsequent handshakes.
+    pub(super) fn set_public_addr(&self, public_addr: SocketAddr) {
+        *self
+            .public_addr
+            .write()
+            .expect("public address lock poisoned") = public_addr;
+    }
+
     pub(crate) fn validate_peer_cert(&self, peer_cert: X509) -> Result<TlsCert, ValidationError> {
         match &self.network_ca {
             Some(ca_cert) => tls::validate_cert_with_authority(peer_cert, ca_cert),
             None => tls::validate_self_signed_cert(peer_cert),
         }
     }
+
+    /// Validates the peer's certificate and, in permissioned mode, checks it against the allow
+    /// list.
+    ///
+    /// Certificates signed by a listed authority are admitted, as are certificates otherwise valid
+    /// whose node ID is listed.
+    fn admit_peer_cert(&self, peer_cert: X509) -> Result<TlsCert, ConnectionError> {
+        let allow_list = match &self.allow_list {
+            Some(allow_list) => allow_list.read().expect("allow list lock poisoned"),
+            None => {
+                return self
+                    .validate_peer_cert(peer_cert)
+                    .map_err(ConnectionError::PeerCertificateInvalid)
+            }
+        };
+
+        if let Some(validated_peer_cert) = allow_list.validate_with_authorities(&peer_cert) {
+            return Ok(validated_peer_cert);
+        }
+
+        let validated_peer_cert = self
+            .validate_peer_cert(peer_cert)
+            .map_err(ConnectionError::PeerCertificateInvalid)?;
+        let peer_id = NodeId::from(validated_peer_cert.public_key_fingerprint());
+        if allow_list.contains(&peer_id) {
+            Ok(validated_peer_cert)
+        } else {
+            Err(ConnectionError::PeerNotAllowed(peer_id))
+        }
+    }
 }
 
 /// Handles an incoming connection.
@@ -278,12 +338,22 @@ where
     let framed_transport = framed_transport(transport, context.chain_info.maximum_net_message_size);
 
     // Negotiate the handshake, concluding the incoming connection process.
-    match negotiate_handshake::<P, _>(&context, framed_transport, connection_id).await {
+    // Report the address the peer connected from, allowing it to verify its public address.
+    match negotiate_handshake::<P, _>(
+        &context,
+        framed_transport,
+        connection_id,
+        Some(peer_addr.ip()),
+    )
+    .await
+    {
         Ok(HandshakeOutcome {
             framed_transport,
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: _,
+            observed_ip: _,
+            clock_offset: _,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
@@ -293,6 +363,7 @@ where
             let full_transport = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
+                peer_id,
                 framed_transport,
                 Role::Listener,
             );
@@ -340,9 +411,7 @@ pub(super) async fn server_setup_tls<REv>(
         .peer_certificate()
         .ok_or(ConnectionError::NoPeerCertificate)?;
 
-    let validated_peer_cert = context
-        .validate_peer_cert(peer_cert)
-        .map_err(ConnectionError::PeerCertificateInvalid)?;
+    let validated_peer_cert = context.admit_peer_cert(peer_cert)?;
 
     Ok((
         NodeId::from(validated_peer_cert.public_key_fingerprint()),
@@ -380,10 +449,13 @@ where
 }
 
 /// Negotiates a handshake between two peers.
+///
+/// `observed_ip` is the address the peer's connection originates from, reported back to it.
 async fn negotiate_handshake<P, REv>(
     context: &NetworkContext<REv>,
     framed: FramedTransport,
     connection_id: ConnectionId,
+    observed_ip: Option<IpAddr>,
 ) -> Result<HandshakeOutcome, ConnectionError>
 where
     P: Payload,
@@ -391,11 +463,16 @@ where
     let mut encoder = MessagePackFormat;
 
     // Manually encode a handshake.
+    let consensus_certificate = match context.consensus_keys.as_ref() {
+        Some(key_pair) => ConsensusCertificate::create(connection_id, key_pair).await,
+        None => None,
+    };
+    let sent_at = Timestamp::now();
     let handshake_message = context.chain_info.create_handshake::<P>(
-        context.public_addr,
-        context.consensus_keys.as_ref(),
-        connection_id,
+        context.public_addr(),
+        consensus_certificate,
         context.is_syncing.load(Ordering::SeqCst),
+        observed_ip,
     );
 
     let serialized_handshake_message = Pin::new(&mut encoder)
@@ -417,6 +494,7 @@ where
     let remote_message_raw = io_opt_timeout(context.handshake_timeout.into(), stream.next())
         .await
         .map_err(ConnectionError::HandshakeRecv)?;
+    let received_at = Timestamp::now();
 
     // Ensure the handshake was sent correctly.
     let sink = handshake_send
@@ -435,6 +513,8 @@ where
         consensus_certificate,
         is_syncing,
         chainspec_hash,
+        observed_ip: our_observed_ip,
+        timestamp,
     } = remote_message
     {
         debug!(%protocol_version, "handshake received");
@@ -493,6 +573,9 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            observed_ip: our_observed_ip,
+            clock_offset: timestamp
+                .map(|timestamp| clock_drift::measure_offset(timestamp, sent_at, received_at)),
         })
     } else {
         // Received a non-handshake, this is an error.
@@ -16,8 +16,8 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
 use super::{
-    chain_info::ChainInfo, Config, Event as SmallNetworkEvent, FromIncoming, GossipedAddress,
-    MessageKind, Payload, SmallNetwork,
+    chain_info::ChainInfo, Config, Event as SmallNetworkEvent, FromIncoming, MessageKind, Payload,
+    PeerRecord, SmallNetwork,
 };
 use crate::{
     components::{
@@ -51,17 +51,17 @@ enum Event {
     #[from]
     SmallNet(#[serde(skip_serializing)] SmallNetworkEvent<Message>),
     #[from]
-    AddressGossiper(#[serde(skip_serializing)] gossiper::Event<GossipedAddress>),
+    AddressGossiper(#[serde(skip_serializing)] gossiper::Event<PeerRecord>),
     #[from]
     NetworkRequest(#[serde(skip_serializing)] NetworkRequest<Message>),
     #[from]
     ControlAnnouncement(ControlAnnouncement),
     #[from]
-    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<PeerRecord>),
     #[from]
-    BeginAddressGossipRequest(BeginGossipRequest<GossipedAddress>),
+    BeginAddressGossipRequest(BeginGossipRequest<PeerRecord>),
     /// An incoming network message with an address gossiper protocol message.
-    AddressGossiperIncoming(GossiperIncoming<GossipedAddress>),
+    AddressGossiperIncoming(GossiperIncoming<PeerRecord>),
 }
 
 impl ReactorEvent for Event {
@@ -82,8 +82,8 @@ impl ReactorEvent for Event {
     }
 }
 
-impl From<NetworkRequest<gossiper::Message<GossipedAddress>>> for Event {
-    fn from(request: NetworkRequest<gossiper::Message<GossipedAddress>>) -> Self {
+impl From<NetworkRequest<gossiper::Message<PeerRecord>>> for Event {
+    fn from(request: NetworkRequest<gossiper::Message<PeerRecord>>) -> Self {
         Event::NetworkRequest(request.map_payload(Message::from))
     }
 }
@@ -139,7 +139,7 @@ impl Display for Event {
 #[derive(Clone, Debug, Deserialize, Serialize, From)]
 enum Message {
     #[from]
-    AddressGossiper(gossiper::Message<GossipedAddress>),
+    AddressGossiper(gossiper::Message<PeerRecord>),
 }
 
 impl Display for Message {
@@ -171,7 +171,7 @@ impl Payload for Message {
 #[derive(Debug)]
 struct TestReactor {
     net: SmallNetwork<Event, Message>,
-    address_gossiper: Gossiper<GossipedAddress, Event>,
+    address_gossiper: Gossiper<PeerRecord, Event>,
 }
 
 impl Reactor for TestReactor {
@@ -231,13 +231,13 @@ impl Reactor for TestReactor {
                 unreachable!("unhandled control announcement: {}", ctrl_ann)
             }
             Event::AddressGossiperAnnouncement(GossiperAnnouncement::NewCompleteItem(
-                gossiped_address,
+                peer_record,
             )) => reactor::wrap_effects(
                 Event::SmallNet,
                 self.net.handle_event(
                     effect_builder,
                     rng,
-                    SmallNetworkEvent::PeerAddressReceived(gossiped_address),
+                    SmallNetworkEvent::PeerRecordReceived(peer_record),
                 ),
             ),
 
@@ -30,8 +30,11 @@
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
 
+mod compression;
 pub(crate) mod disjoint_sequences;
+mod disk_budget;
 mod error;
+mod legacy;
 mod lmdb_ext;
 mod object_pool;
 #[cfg(test)]
@@ -41,10 +44,10 @@ mod tests;
 use std::collections::BTreeSet;
 use std::{
     borrow::Cow,
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     fmt::{self, Display, Formatter},
-    fs, mem,
+    fs, io, mem,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -65,13 +68,13 @@ use tracing::{debug, error, info, warn};
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::{FromBytes, ToBytes},
-    EraId, ExecutionResult, ProtocolVersion, TimeDiff, Transfer, Transform,
+    EraId, ExecutionResult, ProtocolVersion, TimeDiff, Timestamp, Transfer, Transform,
 };
 
 // The reactor! macro needs this in the fetcher tests
 pub(crate) use crate::effect::requests::StorageRequest;
 use crate::{
-    components::{fetcher::FetchedOrNotFound, Component},
+    components::{fetcher::FetchedOrNotFound, Component, HealthCheck},
     effect::{
         incoming::{NetRequest, NetRequestIncoming},
         requests::{MarkBlockCompletedRequest, NetworkRequest, StateStoreRequest},
@@ -81,15 +84,18 @@ use crate::{
     protocol::Message,
     reactor::ReactorEvent,
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockBody, BlockHash, BlockHashAndHeight,
-        BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId,
-        BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployMetadata, DeployMetadataExt,
-        DeployWithFinalizedApprovals, FinalizedApprovals, FinalizedApprovalsWithId, Item, NodeId,
+        AvailableBlockRange, Block, BlockAndDeploys, BlockBody, BlockBodyOrChunk,
+        BlockBodyOrChunkId, BlockHash, BlockHashAndHeight, BlockHeader, BlockHeaderWithMetadata,
+        BlockHeadersBatch, BlockHeadersBatchId, BlockSignatures, BlockWithMetadata, Deploy,
+        DeployHash, DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals,
+        FinalizedApprovals, FinalizedApprovalsWithId, Health, Item, NodeId,
     },
     utils::{display_error, WithDir},
     NodeRng,
 };
+use compression::Compression;
 use disjoint_sequences::{DisjointSequences, Sequence};
+use disk_budget::{BudgetAction, DiskBudget, DiskBudgetConfig};
 pub use error::FatalStorageError;
 use error::GetRequestError;
 use lmdb_ext::{LmdbExtError, TransactionExt, WriteTransactionExt};
@@ -114,9 +120,31 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 8;
+const MAX_DB_COUNT: u32 = 9;
+/// Free space on the storage volume below which storage reports itself as degraded.
+const LOW_DISK_SPACE: u64 = 10 * GIB as u64;
+/// Free space on the storage volume below which storage reports itself as failed.
+const CRITICAL_DISK_SPACE: u64 = GIB as u64;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the compression format the stored deploys and block bodies were migrated to is
+/// stored.
+const COMPRESSION_FORMAT_STORAGE_KEY: &[u8] = b"compression_format_version";
+/// Key under which the height below which the execution results of blocks have been pruned is
+/// stored.
+const EXECUTION_RESULTS_PRUNED_BELOW_STORAGE_KEY: &[u8] = b"execution_results_pruned_below";
+/// Number of values compressed per transaction when migrating to compressed storage.
+const COMPRESSION_MIGRATION_BATCH_SIZE: usize = 1024;
+/// Default zstd compression level.
+const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+/// Default size in bytes from which serialized values are compressed.
+const DEFAULT_COMPRESSION_THRESHOLD: usize = 1024;
+/// Default time for which executed deploys are still recognized as replays after expiring.
+const DEFAULT_REPLAY_PROTECTION_WINDOW: TimeDiff = TimeDiff::from_seconds(3600);
+/// Filename of the marker which exists in the storage folder while the node is running.
+///
+/// If it is present on startup, the previous run of the node did not shut down cleanly.
+const UNCLEAN_SHUTDOWN_MARKER_FILENAME: &str = "unclean_shutdown";
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -170,6 +198,9 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The replay index, mapping the hashes of executed deploys to their expiry.
+    #[data_size(skip)]
+    replay_index_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -184,6 +215,17 @@ pub struct Storage {
     ///
     /// Keyed by serialized item ID, contains the serialized item.
     serialized_item_pool: ObjectPool<Box<[u8]>>,
+    /// Whether the unclean shutdown marker was found on startup.
+    unclean_shutdown_detected: bool,
+    /// Compression applied to deploys and block bodies when writing them, if enabled.
+    compression: Option<Compression>,
+    /// The disk usage budget and the latest measurement.
+    disk_budget: DiskBudget,
+    /// The number of most recent complete eras whose execution results are retained, or zero to
+    /// retain them indefinitely.
+    execution_results_retention_eras: u64,
+    /// Execution results and transfers of blocks below this height have been pruned.
+    execution_results_pruned_below: u64,
 }
 
 /// A storage component event.
@@ -269,6 +311,28 @@ where
     }
 }
 
+impl HealthCheck for Storage {
+    const NAME: &'static str = "storage";
+
+    fn health(&self) -> Health {
+        let mut health = Health::ready();
+        match fs2::available_space(&self.root) {
+            Ok(available) if available < CRITICAL_DISK_SPACE => {
+                health.fail(format!("only {} bytes of disk space available", available))
+            }
+            Ok(available) if available < LOW_DISK_SPACE => {
+                health.degrade(format!("only {} bytes of disk space available", available))
+            }
+            Ok(_) => (),
+            Err(error) => health.degrade(format!("could not get available disk space: {}", error)),
+        }
+        if let Some(degradation) = self.disk_budget.degradation() {
+            health.degrade(degradation);
+        }
+        health
+    }
+}
+
 impl Storage {
     /// Creates a new storage component.
     #[allow(clippy::too_many_arguments)]
@@ -296,27 +360,25 @@ impl Storage {
 
         root = network_subdir;
 
+        let shutdown_marker = root.join(UNCLEAN_SHUTDOWN_MARKER_FILENAME);
+        let unclean_shutdown_detected = shutdown_marker.exists();
+        if unclean_shutdown_detected {
+            warn!(
+                marker = %shutdown_marker.display(),
+                "previous run of the node did not shut down cleanly"
+            );
+        }
+
         // Calculate the upper bound for the memory map that is potentially used.
         let total_size = config
             .max_block_store_size
             .saturating_add(config.max_deploy_store_size)
             .saturating_add(config.max_deploy_metadata_store_size);
 
+        disk_budget::compact_if_requested(&root, total_size)?;
+
         // Creates the environment and databases.
-        let env = Environment::new()
-            .set_flags(
-                OS_FLAGS
-                // We manage our own directory.
-                | EnvironmentFlags::NO_SUB_DIR
-                // Disable thread local storage, strongly suggested for operation with tokio.
-                | EnvironmentFlags::NO_TLS
-                // Disable read-ahead. Our data is not stored/read in sequence that would benefit from the read-ahead.
-                | EnvironmentFlags::NO_READAHEAD,
-            )
-            .set_max_readers(MAX_TRANSACTIONS)
-            .set_max_dbs(MAX_DB_COUNT)
-            .set_map_size(total_size)
-            .open(&root.join(STORAGE_DB_FILENAME))?;
+        let env = open_environment(&root.join(STORAGE_DB_FILENAME), total_size)?;
 
         let block_header_db = env.create_db(Some("block_header"), DatabaseFlags::empty())?;
         let block_metadata_db = env.create_db(Some("block_metadata"), DatabaseFlags::empty())?;
@@ -327,6 +389,7 @@ impl Storage {
         let finalized_approvals_db =
             env.create_db(Some("finalized_approvals"), DatabaseFlags::empty())?;
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let replay_index_db = env.create_db(Some("replay_index"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -401,6 +464,13 @@ impl Storage {
 
         initialize_block_metadata_db(&env, &block_metadata_db, &deleted_block_hashes_raw)?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_deploy_hashes)?;
+        let pruned = prune_replay_index(
+            &env,
+            replay_index_db,
+            config.replay_protection_window,
+            Timestamp::now(),
+        )?;
+        debug!(%pruned, "pruned replay index");
 
         let mut component = Self {
             root,
@@ -413,14 +483,31 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            replay_index_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
             completed_blocks: Default::default(),
             enable_mem_deduplication: config.enable_mem_deduplication,
             serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
+            unclean_shutdown_detected,
+            compression: config
+                .enable_compression
+                .then(|| Compression::new(config.compression_level, config.compression_threshold)),
+            disk_budget: DiskBudget::new(config.disk_budget.clone()),
+            execution_results_retention_eras: config.execution_results_retention_eras,
+            execution_results_pruned_below: 0,
         };
 
+        if let Some(raw) = component
+            .read_state_store(&Cow::Borrowed(EXECUTION_RESULTS_PRUNED_BELOW_STORAGE_KEY))?
+        {
+            let (pruned_below, _) = u64::from_bytes(&raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.execution_results_pruned_below = pruned_below;
+            component.disk_budget.set_pruned_below(pruned_below);
+        }
+
         match component.read_state_store(&Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY))? {
             Some(raw) => {
                 let (mut sequences, _) = DisjointSequences::from_vec(raw)
@@ -454,9 +541,43 @@ impl Storage {
             }
         }
 
+        if let Some(compression) = component.compression {
+            component.compress_stored_values(compression)?;
+        }
+
+        fs::write(&shutdown_marker, [])
+            .map_err(|err| FatalStorageError::ShutdownMarker(shutdown_marker, err))?;
+
         Ok(component)
     }
 
+    /// Compresses the deploys and block bodies stored uncompressed, unless this has already been
+    /// done for the current compression format.
+    ///
+    /// Values are compressed in batches, each in its own transaction, so an interrupted migration
+    /// simply resumes on the next startup.
+    fn compress_stored_values(&self, compression: Compression) -> Result<(), FatalStorageError> {
+        let format_version = vec![compression::FORMAT_VERSION];
+        let migrated_format_version = self.read_state_store(&COMPRESSION_FORMAT_STORAGE_KEY)?;
+        if migrated_format_version.as_ref() == Some(&format_version) {
+            return Ok(());
+        }
+
+        for (name, db) in [
+            ("deploys", self.deploy_db),
+            ("block bodies", self.block_body_db),
+        ] {
+            info!(%name, "compressing stored values");
+            let compressed = compress_db_values(&self.env, db, compression)?;
+            info!(%name, %compressed, "stored values compressed");
+        }
+
+        self.write_state_store(
+            Cow::Borrowed(COMPRESSION_FORMAT_STORAGE_KEY),
+            &format_version,
+        )
+    }
+
     /// Handles a state store request.
     fn handle_state_store_request<REv>(
         &self,
@@ -521,6 +642,92 @@ impl Storage {
         &self.root
     }
 
+    /// Forces all data written to the storage databases to be flushed to disk.
+    pub(crate) fn flush(&self) -> Result<(), FatalStorageError> {
+        self.env.sync(true)?;
+        Ok(())
+    }
+
+    /// Returns whether the unclean shutdown marker was present when storage was opened.
+    pub(crate) fn unclean_shutdown_detected(&self) -> bool {
+        self.unclean_shutdown_detected
+    }
+
+    /// Flushes storage to disk and removes the unclean shutdown marker.
+    ///
+    /// Should be called as the very last step of an orderly shutdown.
+    pub(crate) fn mark_clean_shutdown(&self) -> Result<(), FatalStorageError> {
+        self.flush()?;
+        let shutdown_marker = self.root.join(UNCLEAN_SHUTDOWN_MARKER_FILENAME);
+        match fs::remove_file(&shutdown_marker) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(FatalStorageError::ShutdownMarker(shutdown_marker, err)),
+        }
+    }
+
+    /// Returns the header of the highest complete block, after verifying that it is consistent
+    /// with the storage indices.
+    ///
+    /// If the highest block marked as complete is missing its header or body, or its header is not
+    /// stored under its hash, it is no longer considered complete and the next highest complete
+    /// block is checked instead.
+    pub(crate) fn verify_highest_complete_block(
+        &mut self,
+    ) -> Result<Option<BlockHeader>, FatalStorageError> {
+        while let Some(height) = self.completed_blocks.highest_sequence().map(Sequence::high) {
+            match self.read_complete_block_header(height)? {
+                Some(block_header) => return Ok(Some(block_header)),
+                None => {
+                    warn!(
+                        height,
+                        "highest complete block is inconsistent, marking incomplete"
+                    );
+                    self.unmark_complete_blocks_from(height)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Marks all blocks at `height` and above as incomplete, persisting the change.
+    pub(crate) fn unmark_complete_blocks_from(
+        &mut self,
+        height: u64,
+    ) -> Result<(), FatalStorageError> {
+        match height.checked_sub(1) {
+            Some(max_height) => self.completed_blocks.truncate(max_height),
+            None => self.completed_blocks = Default::default(),
+        }
+        self.persist_completed_blocks()
+    }
+
+    /// Reads the header of the block at the given height, returning `None` if the header is not
+    /// in the height index, is not stored under its hash, or has no body.
+    fn read_complete_block_header(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockHeader>, FatalStorageError> {
+        let block_hash = match self.block_height_index.get(&height) {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let block_header = match self.get_single_block_header(&mut txn, block_hash) {
+            Ok(Some(block_header)) => block_header,
+            Ok(None) | Err(FatalStorageError::BlockHeaderNotStoredUnderItsHash { .. }) => {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        if block_header.height() != height
+            || get_body_for_block_header(&mut txn, &block_header, self.block_body_db)?.is_none()
+        {
+            return Ok(None);
+        }
+        Ok(Some(block_header))
+    }
+
     fn handle_net_request_incoming<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -672,6 +879,19 @@ impl Storage {
 
                 let opt_item = self.read_block_signatures(&item_id)?;
 
+                Ok(self.update_pool_and_send(
+                    effect_builder,
+                    incoming.sender,
+                    serialized_id,
+                    item_id,
+                    opt_item,
+                )?)
+            }
+            NetRequest::BlockBodyOrChunk(ref serialized_id) => {
+                let item_id = decode_item_id::<BlockBodyOrChunk>(serialized_id)?;
+
+                let opt_item = self.read_block_body_or_chunk(&item_id)?;
+
                 Ok(self.update_pool_and_send(
                     effect_builder,
                     incoming.sender,
@@ -723,6 +943,15 @@ impl Storage {
                     .respond(self.get_block_header_by_deploy_hash(&mut txn, deploy_hash)?)
                     .ignore()
             }
+            StorageRequest::IsReplayedDeploy {
+                deploy_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let expiry: Option<Timestamp> =
+                    txn.get_value(self.replay_index_db, &deploy_hash)?;
+                responder.respond(expiry.is_some()).ignore()
+            }
             StorageRequest::GetBlockHeader {
                 block_hash,
                 only_from_available_block_range,
@@ -769,65 +998,19 @@ impl Storage {
                     )
                     .ignore()
             }
-            StorageRequest::PutExecutionResults {
-                block_hash,
+            StorageRequest::PutExecutedBlock {
+                block,
                 execution_results,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
-
-                let mut transfers: Vec<Transfer> = vec![];
-
-                for (deploy_hash, execution_result) in execution_results {
-                    let mut metadata = self
-                        .get_deploy_metadata(&mut txn, &deploy_hash)?
-                        .unwrap_or_default();
-
-                    // If we have a previous execution result, we can continue if it is the same.
-                    if let Some(prev) = metadata.execution_results.get(&block_hash) {
-                        if prev == &execution_result {
-                            continue;
-                        } else {
-                            debug!(%deploy_hash, %block_hash, "different execution result");
-                        }
-                    }
-
-                    if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
-                        for transform_entry in effect.transforms {
-                            if let Transform::WriteTransfer(transfer) = transform_entry.transform {
-                                transfers.push(transfer);
-                            }
-                        }
-                    }
-
-                    // TODO: this is currently done like this because rpc get_deploy returns the
-                    // data, but the organization of deploy, block_hash, and
-                    // execution_result is incorrectly represented. it should be
-                    // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
-                    // result (aka deploy_metadata in this context).
-
-                    // Update metadata and write back to db.
-                    metadata
-                        .execution_results
-                        .insert(*block_hash, execution_result);
-                    let was_written =
-                        txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
-                    if !was_written {
-                        error!(?block_hash, ?deploy_hash, "failed to write deploy metadata");
-                        debug_assert!(was_written);
-                    }
-                }
-
-                let was_written =
-                    txn.put_value(self.transfer_db, &*block_hash, &transfers, true)?;
-                if !was_written {
-                    error!(?block_hash, "failed to write transfers");
-                    debug_assert!(was_written);
-                }
-
-                txn.commit()?;
-                responder.respond(()).ignore()
+                let wrote = self.write_executed_block(&*block, execution_results)?;
+                self.enforce_execution_results_retention(&*block)?;
+                self.enforce_disk_budget()?;
+                responder.respond(wrote).ignore()
             }
+            StorageRequest::GetExecutionResultsPrunedBelow { responder } => responder
+                .respond(self.execution_results_pruned_below)
+                .ignore(),
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
@@ -1154,7 +1337,8 @@ impl Storage {
     /// Put a single deploy into storage.
     pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
         let mut txn = self.env.begin_rw_txn()?;
-        let outcome = txn.put_value(self.deploy_db, deploy.id(), deploy, false)?;
+        let outcome =
+            txn.put_value_compressed(self.deploy_db, deploy.id(), deploy, false, self.compression)?;
         txn.commit()?;
         Ok(outcome)
     }
@@ -1170,14 +1354,23 @@ impl Storage {
 
         block.verify()?;
         let deploy_db = self.deploy_db;
+        let compression = self.compression;
+        let replay_index_db = self.replay_index_db;
         let (wrote, mut txn) = self.write_validated_block(block)?;
         if !wrote {
             return Err(FatalStorageError::FailedToOverwriteBlock);
         }
 
         for deploy in deploys {
-            let _ = txn.put_value(deploy_db, deploy.id(), deploy, false)?;
+            let _ = txn.put_value_compressed(deploy_db, deploy.id(), deploy, false, compression)?;
         }
+        write_replay_index(
+            &mut txn,
+            replay_index_db,
+            deploys
+                .iter()
+                .map(|deploy| (*deploy.id(), deploy.header().expires())),
+        )?;
         txn.commit()?;
 
         Ok(())
@@ -1254,6 +1447,228 @@ impl Storage {
         Ok((true, txn))
     }
 
+    /// Writes a block and the execution results of its deploys to storage in a single transaction,
+    /// updating indices as necessary.
+    ///
+    /// Either the block, the deploys' metadata and the block's transfers are all written, or none of
+    /// them are.  Returns `Ok(true)` if they have been successfully written, `Ok(false)` if the block
+    /// couldn't be written because a part of it already existed, and `Err(_)` if there was an error.
+    fn write_executed_block(
+        &mut self,
+        block: &Block,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<bool, FatalStorageError> {
+        block.verify()?;
+        let deploy_db = self.deploy_db;
+        let deploy_metadata_db = self.deploy_metadata_db;
+        let transfer_db = self.transfer_db;
+        let replay_index_db = self.replay_index_db;
+        let (wrote, mut txn) = self.write_validated_block(block)?;
+        if !wrote {
+            return Ok(false);
+        }
+        let mut expiries = Vec::with_capacity(execution_results.len());
+        for deploy_hash in execution_results.keys() {
+            match txn.get_value::<_, Deploy>(deploy_db, deploy_hash)? {
+                Some(deploy) => expiries.push((*deploy_hash, deploy.header().expires())),
+                None => warn!(%deploy_hash, "executed deploy not stored, not indexed for replays"),
+            }
+        }
+        write_replay_index(&mut txn, replay_index_db, expiries)?;
+        write_execution_results(
+            &mut txn,
+            deploy_metadata_db,
+            transfer_db,
+            block.hash(),
+            execution_results,
+        )?;
+        txn.commit()?;
+        Ok(true)
+    }
+
+    /// Returns the hashes of the deploys and transfers included in the given block which have no
+    /// execution result stored for it.
+    pub(crate) fn read_missing_execution_results(
+        &self,
+        block: &Block,
+    ) -> Result<Vec<DeployHash>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut missing = vec![];
+        for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+            let has_result = self
+                .get_deploy_metadata(&mut txn, deploy_hash)?
+                .map_or(false, |metadata| {
+                    metadata.execution_results.contains_key(block.hash())
+                });
+            if !has_result {
+                missing.push(*deploy_hash);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Retrieves the deploys included in the given block along with their finalized approvals,
+    /// or `None` if any of them is missing.
+    pub(crate) fn read_block_deploys_with_finalized_approvals(
+        &self,
+        block: &Block,
+    ) -> Result<Option<(Vec<Deploy>, Vec<Deploy>)>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut read = |deploy_hashes: &[DeployHash]| {
+            self.get_deploys_with_finalized_approvals(&mut txn, deploy_hashes)
+                .map(|deploys| {
+                    deploys
+                        .into_iter()
+                        .map(|maybe_deploy| {
+                            maybe_deploy.map(DeployWithFinalizedApprovals::into_naive)
+                        })
+                        .collect::<Option<Vec<_>>>()
+                })
+        };
+        let deploys = read(block.deploy_hashes())?;
+        let transfers = read(block.transfer_hashes())?;
+        Ok(deploys.zip(transfers))
+    }
+
+    /// Writes regenerated execution results of the deploys included in the given block.
+    ///
+    /// Execution results already stored for the block are left untouched, so only missing ones are
+    /// filled in.  The block's transfers are rewritten from the given execution results, which must
+    /// therefore cover all deploys of the block.  Returns the number of execution results written.
+    pub(crate) fn write_backfilled_execution_results(
+        &mut self,
+        block: &Block,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<usize, FatalStorageError> {
+        let block_hash = block.hash();
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut transfers = vec![];
+        let mut written = 0;
+        for (deploy_hash, execution_result) in execution_results {
+            collect_transfers(&execution_result, &mut transfers);
+            let mut metadata: DeployMetadata = txn
+                .get_value(self.deploy_metadata_db, &deploy_hash)?
+                .unwrap_or_default();
+            if let Some(stored) = metadata.execution_results.get(block_hash) {
+                if *stored != execution_result {
+                    warn!(
+                        %deploy_hash, %block_hash,
+                        "regenerated execution result differs from stored one, keeping stored one"
+                    );
+                }
+                continue;
+            }
+            metadata
+                .execution_results
+                .insert(*block_hash, execution_result);
+            let _ = txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
+            written += 1;
+        }
+        let _ = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
+        txn.commit()?;
+        Ok(written)
+    }
+
+    /// Prunes the execution results of blocks older than the configured number of eras, if the
+    /// given block is a switch block.
+    ///
+    /// The execution results of the blocks in the configured number of most recent complete eras,
+    /// the last of which is concluded by the given switch block, are retained.
+    fn enforce_execution_results_retention(
+        &mut self,
+        block: &Block,
+    ) -> Result<(), FatalStorageError> {
+        if self.execution_results_retention_eras == 0 || !block.header().is_switch_block() {
+            return Ok(());
+        }
+        let oldest_retained_era = block
+            .header()
+            .era_id()
+            .saturating_sub(self.execution_results_retention_eras - 1);
+        // The oldest retained era starts right after the switch block of the era before it.
+        let previous_switch_block_hash = match oldest_retained_era
+            .checked_sub(1)
+            .and_then(|era_id| self.switch_block_era_id_index.get(&era_id))
+        {
+            Some(block_hash) => *block_hash,
+            None => return Ok(()),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let cutoff = match self.get_single_block_header(&mut txn, &previous_switch_block_hash)? {
+            Some(block_header) => block_header.height() + 1,
+            None => return Ok(()),
+        };
+        drop(txn);
+        let pruned = self.prune_execution_results_below(cutoff)?;
+        if pruned > 0 {
+            info!(
+                %oldest_retained_era, %cutoff, %pruned,
+                "pruned execution results of blocks in eras beyond the retention"
+            );
+        }
+        Ok(())
+    }
+
+    /// Prunes the execution results and transfers of all blocks below `height` which have not
+    /// been pruned yet, returning the number of removed execution results.
+    fn prune_execution_results_below(&mut self, height: u64) -> Result<usize, FatalStorageError> {
+        if height <= self.execution_results_pruned_below {
+            return Ok(0);
+        }
+        let pruned = disk_budget::prune_execution_results(
+            &self.env,
+            self.deploy_metadata_db,
+            self.transfer_db,
+            &self.block_height_index,
+            &self.deploy_hash_index,
+            self.execution_results_pruned_below..height,
+        )?;
+        self.execution_results_pruned_below = height;
+        self.disk_budget.set_pruned_below(height);
+        let serialized = height
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        self.write_state_store(
+            Cow::Borrowed(EXECUTION_RESULTS_PRUNED_BELOW_STORAGE_KEY),
+            &serialized,
+        )?;
+        Ok(pruned)
+    }
+
+    /// Measures the disk usage if due, and takes the actions the disk budget calls for.
+    ///
+    /// Returns an error if the budget is exhausted.
+    fn enforce_disk_budget(&mut self) -> Result<(), FatalStorageError> {
+        let now = Timestamp::now();
+        if !self.disk_budget.is_check_due(now) {
+            return Ok(());
+        }
+        let usage = disk_budget::disk_usage(&self.root)
+            .map_err(|err| FatalStorageError::DiskUsage(self.root.clone(), err))?;
+        let action = self.disk_budget.record(now, usage);
+        if action >= BudgetAction::Prune {
+            let maybe_heights = self
+                .block_height_index
+                .keys()
+                .next_back()
+                .and_then(|highest| self.disk_budget.heights_to_prune(*highest));
+            if let Some(heights) = maybe_heights {
+                let pruned = self.prune_execution_results_below(heights.end)?;
+                info!(?heights, %pruned, "pruned execution results to stay within disk budget");
+            }
+        }
+        if action >= BudgetAction::Compact {
+            self.disk_budget.request_compaction(&self.root);
+        }
+        if action == BudgetAction::Halt {
+            return Err(FatalStorageError::DiskBudgetExhausted {
+                usage,
+                budget: self.disk_budget.max_disk_usage(),
+            });
+        }
+        Ok(())
+    }
+
     /// Get the switch block header for a specified [`EraID`].
     pub(crate) fn read_switch_block_header_by_era_id(
         &self,
@@ -1601,8 +2016,13 @@ impl Storage {
         block_body_hash: &Digest,
         block_body: &BlockBody,
     ) -> Result<bool, LmdbExtError> {
-        txn.put_value(self.block_body_db, block_body_hash, block_body, true)
-            .map_err(Into::into)
+        txn.put_value_compressed(
+            self.block_body_db,
+            block_body_hash,
+            block_body,
+            true,
+            self.compression,
+        )
     }
 
     /// Retrieves a block header by hash.
@@ -1714,6 +2134,24 @@ impl Storage {
         self.get_block_signatures(&mut txn, block_hash)
     }
 
+    /// Retrieves the body of the block with the given hash if it is small enough, or the
+    /// requested chunk of it.
+    fn read_block_body_or_chunk(
+        &self,
+        id: &BlockBodyOrChunkId,
+    ) -> Result<Option<BlockBodyOrChunk>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let block_header = match self.get_single_block_header(&mut txn, id.block_hash())? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        let maybe_block_body =
+            get_body_for_block_header(&mut txn, &block_header, self.block_body_db)?;
+        Ok(maybe_block_body.and_then(|block_body| {
+            BlockBodyOrChunk::new(*id.block_hash(), &block_body, id.index())
+        }))
+    }
+
     /// Directly returns a deploy from internal store.
     pub fn read_deploy_by_hash(
         &self,
@@ -1938,6 +2376,131 @@ fn insert_to_deploy_index(
     Ok(())
 }
 
+/// Records the given deploy hashes and expiries in the replay index, as part of the given
+/// transaction.
+fn write_replay_index<I>(
+    txn: &mut RwTransaction,
+    replay_index_db: Database,
+    expiries: I,
+) -> Result<(), FatalStorageError>
+where
+    I: IntoIterator<Item = (DeployHash, Timestamp)>,
+{
+    for (deploy_hash, expiry) in expiries {
+        let _ = txn.put_value(replay_index_db, &deploy_hash, &expiry, true)?;
+    }
+    Ok(())
+}
+
+/// Opens the storage database environment at `path`, with a memory map of `map_size` bytes.
+fn open_environment(path: &Path, map_size: usize) -> Result<Environment, FatalStorageError> {
+    let env = Environment::new()
+        .set_flags(
+            OS_FLAGS
+            // We manage our own directory.
+            | EnvironmentFlags::NO_SUB_DIR
+            // Disable thread local storage, strongly suggested for operation with tokio.
+            | EnvironmentFlags::NO_TLS
+            // Disable read-ahead. Our data is not stored/read in sequence that would benefit from the read-ahead.
+            | EnvironmentFlags::NO_READAHEAD,
+        )
+        .set_max_readers(MAX_TRANSACTIONS)
+        .set_max_dbs(MAX_DB_COUNT)
+        .set_map_size(map_size)
+        .open(path)?;
+    Ok(env)
+}
+
+/// Removes the deploys which expired more than `replay_protection_window` before `now` from the
+/// replay index, returning the number of removed entries.
+///
+/// Such deploys can no longer be included in a block, so there is no need to remember them.
+fn prune_replay_index(
+    env: &Environment,
+    replay_index_db: Database,
+    replay_protection_window: TimeDiff,
+    now: Timestamp,
+) -> Result<usize, FatalStorageError> {
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(replay_index_db)?;
+    let mut pruned = 0;
+    for (_, raw_val) in cursor.iter() {
+        let expiry: Timestamp = lmdb_ext::deserialize(raw_val)?;
+        if expiry.saturating_add(replay_protection_window) < now {
+            cursor.del(WriteFlags::empty())?;
+            pruned += 1;
+        }
+    }
+    drop(cursor);
+    txn.commit()?;
+    Ok(pruned)
+}
+
+/// Appends the transfers made by a deploy with the given execution result to `transfers`.
+fn collect_transfers(execution_result: &ExecutionResult, transfers: &mut Vec<Transfer>) {
+    if let ExecutionResult::Success { effect, .. } = execution_result {
+        for transform_entry in &effect.transforms {
+            if let Transform::WriteTransfer(transfer) = &transform_entry.transform {
+                transfers.push(transfer.clone());
+            }
+        }
+    }
+}
+
+/// Writes the execution results of the deploys in the given block, and the transfers they
+/// made, as part of the given transaction.
+///
+/// Storing the same execution result for a deploy/block combination multiple times is a no-op.
+fn write_execution_results(
+    txn: &mut RwTransaction,
+    deploy_metadata_db: Database,
+    transfer_db: Database,
+    block_hash: &BlockHash,
+    execution_results: HashMap<DeployHash, ExecutionResult>,
+) -> Result<(), FatalStorageError> {
+    let mut transfers: Vec<Transfer> = vec![];
+
+    for (deploy_hash, execution_result) in execution_results {
+        let mut metadata: DeployMetadata = txn
+            .get_value(deploy_metadata_db, &deploy_hash)?
+            .unwrap_or_default();
+
+        // If we have a previous execution result, we can continue if it is the same.
+        if let Some(prev) = metadata.execution_results.get(block_hash) {
+            if prev == &execution_result {
+                continue;
+            } else {
+                debug!(%deploy_hash, %block_hash, "different execution result");
+            }
+        }
+
+        collect_transfers(&execution_result, &mut transfers);
+
+        // TODO: this is currently done like this because rpc get_deploy returns the
+        // data, but the organization of deploy, block_hash, and
+        // execution_result is incorrectly represented. it should be
+        // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
+        // result (aka deploy_metadata in this context).
+
+        // Update metadata and write back to db.
+        metadata
+            .execution_results
+            .insert(*block_hash, execution_result);
+        let was_written = txn.put_value(deploy_metadata_db, &deploy_hash, &metadata, true)?;
+        if !was_written {
+            error!(?block_hash, ?deploy_hash, "failed to write deploy metadata");
+            debug_assert!(was_written);
+        }
+    }
+
+    let was_written = txn.put_value(transfer_db, block_hash, &transfers, true)?;
+    if !was_written {
+        error!(?block_hash, "failed to write transfers");
+        debug_assert!(was_written);
+    }
+    Ok(())
+}
+
 fn should_move_storage_files_to_network_subdir(
     root: &Path,
     file_names: &[&str],
@@ -2025,6 +2588,26 @@ pub struct Config {
     enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     mem_pool_prune_interval: u16,
+    /// Whether deploys and block bodies are compressed when written.
+    ///
+    /// Values stored uncompressed are compressed once on startup when this is enabled. Compressed
+    /// values remain readable if it is disabled again.
+    enable_compression: bool,
+    /// The zstd compression level, from 1 (fastest) to 22 (smallest).
+    compression_level: i32,
+    /// Serialized values smaller than this many bytes are stored uncompressed.
+    compression_threshold: usize,
+    /// How long executed deploys are still rejected as replays after they expired.
+    ///
+    /// Deploys which expired earlier are removed from the replay index on startup.
+    replay_protection_window: TimeDiff,
+    /// The disk usage budget of the storage folder.
+    disk_budget: DiskBudgetConfig,
+    /// The number of most recent complete eras whose execution results and transfers are retained.
+    ///
+    /// Execution results of blocks in older eras are pruned at the end of each era, while the
+    /// blocks and deploys themselves are kept.  Zero retains execution results indefinitely.
+    execution_results_retention_eras: u64,
 }
 
 impl Default for Config {
@@ -2038,6 +2621,12 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            enable_compression: false,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            compression_threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            replay_protection_window: DEFAULT_REPLAY_PROTECTION_WINDOW,
+            disk_budget: DiskBudgetConfig::default(),
+            execution_results_retention_eras: 0,
         }
     }
 }
@@ -2238,6 +2827,44 @@ fn initialize_block_body_db(
     Ok(())
 }
 
+/// Compresses all values in `db` which are not compressed yet, returning how many were.
+///
+/// Values which don't shrink or are below the compression threshold are left as they are.
+fn compress_db_values(
+    env: &Environment,
+    db: Database,
+    compression: Compression,
+) -> Result<usize, FatalStorageError> {
+    let uncompressed_keys: Vec<Vec<u8>> = {
+        let txn = env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(db)?;
+        let keys = cursor
+            .iter()
+            .filter(|(_, raw_val)| !compression::is_compressed(raw_val))
+            .map(|(raw_key, _)| raw_key.to_vec())
+            .collect();
+        drop(cursor);
+        keys
+    };
+
+    let mut compressed_count = 0;
+    for batch in uncompressed_keys.chunks(COMPRESSION_MIGRATION_BATCH_SIZE) {
+        let mut txn = env.begin_rw_txn()?;
+        for raw_key in batch {
+            let raw_val = txn.get(db, raw_key)?.to_vec();
+            let compressed = compression
+                .compress(raw_val)
+                .map_err(|err| LmdbExtError::Other(Box::new(err)))?;
+            if compression::is_compressed(&compressed) {
+                txn.put(db, raw_key, &compressed, WriteFlags::empty())?;
+                compressed_count += 1;
+            }
+        }
+        txn.commit()?;
+    }
+    Ok(compressed_count)
+}
+
 /// Retrieves the block body for the given block header.
 fn get_body_for_block_header<Tx: Transaction>(
     txn: &mut Tx,
@@ -0,0 +1,97 @@
+//! Optional compression of large stored values.
+//!
+//! Compressed values are prefixed with magic bytes and a format version, which set them apart from
+//! values stored uncompressed, be it by earlier versions of the node or with compression disabled.
+//! Reading is therefore transparent: values carrying the prefix are decompressed, all others are
+//! used as they are. Whether to compress only matters when writing.
+
+use std::{borrow::Cow, io};
+
+use datasize::DataSize;
+use thiserror::Error;
+
+/// Prefix of every compressed value.
+///
+/// Like the `UnbondingPurse` magic bytes, these are random, so that a serialized value starting
+/// with them by accident is practically impossible.
+const MAGIC_BYTES: &[u8] = &[87, 203, 14, 162, 240, 55, 129, 6];
+
+/// The current compression format version, indicating that a zstd frame follows the header.
+pub(super) const FORMAT_VERSION: u8 = 1;
+
+/// Length of the magic bytes and format version preceding the compressed data.
+const HEADER_LENGTH: usize = MAGIC_BYTES.len() + 1;
+
+/// Error compressing or decompressing a stored value.
+#[derive(Debug, Error)]
+pub(super) enum CompressionError {
+    /// The value was compressed in a format unknown to this version of the node.
+    #[error("unsupported compression format version {0}")]
+    UnsupportedVersion(u8),
+    /// The value could not be compressed.
+    #[error("failed to compress value: {0}")]
+    Compress(#[source] io::Error),
+    /// The value could not be decompressed.
+    #[error("failed to decompress value: {0}")]
+    Decompress(#[source] io::Error),
+}
+
+/// Compression settings for values written to storage.
+#[derive(Copy, Clone, DataSize, Debug)]
+pub(super) struct Compression {
+    /// The zstd compression level.
+    level: i32,
+    /// Serialized values smaller than this many bytes are stored uncompressed.
+    threshold: usize,
+}
+
+impl Compression {
+    /// Creates new compression settings.
+    pub(super) fn new(level: i32, threshold: usize) -> Self {
+        Compression { level, threshold }
+    }
+
+    /// Returns the bytes to store for the serialized `value`.
+    ///
+    /// These are compressed if `value` reaches the size threshold and compressing actually makes it
+    /// smaller, otherwise `value` is returned unchanged.
+    pub(super) fn compress(&self, value: Vec<u8>) -> Result<Vec<u8>, CompressionError> {
+        if value.len() < self.threshold {
+            return Ok(value);
+        }
+
+        let mut compressed = Vec::with_capacity(HEADER_LENGTH + value.len() / 2);
+        compressed.extend_from_slice(MAGIC_BYTES);
+        compressed.push(FORMAT_VERSION);
+        zstd::stream::copy_encode(value.as_slice(), &mut compressed, self.level)
+            .map_err(CompressionError::Compress)?;
+
+        if compressed.len() < value.len() {
+            Ok(compressed)
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+/// Returns whether `raw` is a compressed value.
+pub(super) fn is_compressed(raw: &[u8]) -> bool {
+    raw.starts_with(MAGIC_BYTES)
+}
+
+/// Decompresses `raw` if it is a compressed value, otherwise returns it unchanged.
+pub(super) fn decompress(raw: &[u8]) -> Result<Cow<[u8]>, CompressionError> {
+    if !is_compressed(raw) {
+        return Ok(Cow::Borrowed(raw));
+    }
+
+    match raw.get(MAGIC_BYTES.len()) {
+        Some(&FORMAT_VERSION) => zstd::stream::decode_all(&raw[HEADER_LENGTH..])
+            .map(Cow::Owned)
+            .map_err(CompressionError::Decompress),
+        Some(&version) => Err(CompressionError::UnsupportedVersion(version)),
+        None => Err(CompressionError::Decompress(
+            io::ErrorKind::UnexpectedEof.into(),
+        )),
+    }
+}
@@ -0,0 +1,456 @@
+//! Disk usage budget.
+//!
+//! If a budget is configured, storage measures the space taken up by the files in its folder, which
+//! include the global state, after storing executed blocks. As usage approaches the budget, it takes
+//! increasingly drastic actions, each level including the ones below it:
+//!
+//! 1. It logs a warning and reports itself as degraded.
+//! 2. It prunes the execution results and transfers of all but the most recent blocks.
+//! 3. It requests the storage database to be compacted on the next start. LMDB never shrinks its
+//!    files, but pages freed by pruning are reused, and compacting copies only the pages in use.
+//! 4. Once the budget is exhausted, it shuts the node down, rather than letting it crash when the
+//!    disk is full in the middle of a write.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    ops::Range,
+    path::Path,
+};
+
+use datasize::DataSize;
+use lmdb::{Cursor, Database, DatabaseFlags, Environment, Transaction, WriteFlags};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use casper_types::{TimeDiff, Timestamp};
+
+use super::{
+    lmdb_ext::{TransactionExt, WriteTransactionExt},
+    open_environment, FatalStorageError, STORAGE_DB_FILENAME,
+};
+use crate::types::{BlockHash, BlockHashAndHeight, DeployHash, DeployMetadata};
+
+/// Filename of the marker requesting the storage database to be compacted on the next start.
+const COMPACTION_MARKER_FILENAME: &str = "compact_on_startup";
+/// Filename of the compacted copy of the storage database while it is being written.
+const COMPACTED_DB_FILENAME: &str = "storage.lmdb.compacted";
+/// Number of records copied per write transaction when compacting.
+const COMPACTION_BATCH_SIZE: usize = 10_000;
+
+/// Default interval between two disk usage measurements.
+const DEFAULT_CHECK_INTERVAL: TimeDiff = TimeDiff::from_seconds(60);
+/// Default number of most recent blocks whose execution results are never pruned.
+const DEFAULT_RETAINED_EXECUTION_RESULTS: u64 = 100_000;
+
+/// Disk budget configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiskBudgetConfig {
+    /// The number of bytes the files in the storage folder may take up in total.
+    ///
+    /// Zero disables the budget.
+    pub max_disk_usage: u64,
+    /// The minimum interval between two disk usage measurements.
+    pub check_interval: TimeDiff,
+    /// The percentage of the budget from which a warning is logged and storage reports itself as
+    /// degraded.
+    pub alert_percent: u8,
+    /// The percentage of the budget from which old execution results are pruned.
+    pub prune_percent: u8,
+    /// The percentage of the budget from which the storage database is compacted on the next
+    /// start.
+    pub compact_percent: u8,
+    /// The number of most recent blocks whose execution results are never pruned.
+    pub retained_execution_results: u64,
+}
+
+impl Default for DiskBudgetConfig {
+    fn default() -> Self {
+        DiskBudgetConfig {
+            max_disk_usage: 0,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            alert_percent: 80,
+            prune_percent: 90,
+            compact_percent: 95,
+            retained_execution_results: DEFAULT_RETAINED_EXECUTION_RESULTS,
+        }
+    }
+}
+
+/// The actions called for by the disk usage, from least to most urgent.
+#[derive(Clone, Copy, DataSize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum BudgetAction {
+    /// Usage is well within the budget.
+    None,
+    /// Warn about the usage.
+    Alert,
+    /// Prune old execution results.
+    Prune,
+    /// Compact the database on the next start.
+    Compact,
+    /// The budget is exhausted: shut down.
+    Halt,
+}
+
+impl DiskBudgetConfig {
+    /// Returns whether a budget is configured.
+    fn is_enabled(&self) -> bool {
+        self.max_disk_usage > 0
+    }
+
+    /// Returns the most urgent action called for by using `usage` bytes.
+    fn action(&self, usage: u64) -> BudgetAction {
+        if !self.is_enabled() {
+            return BudgetAction::None;
+        }
+        if usage >= self.max_disk_usage {
+            return BudgetAction::Halt;
+        }
+        let percent = u128::from(usage) * 100 / u128::from(self.max_disk_usage);
+        if percent >= u128::from(self.compact_percent) {
+            BudgetAction::Compact
+        } else if percent >= u128::from(self.prune_percent) {
+            BudgetAction::Prune
+        } else if percent >= u128::from(self.alert_percent) {
+            BudgetAction::Alert
+        } else {
+            BudgetAction::None
+        }
+    }
+}
+
+/// Keeps track of the disk usage measurements.
+#[derive(DataSize, Debug)]
+pub(super) struct DiskBudget {
+    config: DiskBudgetConfig,
+    /// The time and result of the latest measurement.
+    last_check: Option<(Timestamp, u64)>,
+    /// The action called for by the latest measurement.
+    last_action: BudgetAction,
+    /// Execution results of blocks below this height have been pruned since the node started.
+    pruned_below: u64,
+    /// Whether compaction on the next start has been requested.
+    compaction_requested: bool,
+}
+
+impl DiskBudget {
+    /// Creates a new disk budget.
+    pub(super) fn new(config: DiskBudgetConfig) -> Self {
+        DiskBudget {
+            config,
+            last_check: None,
+            last_action: BudgetAction::None,
+            pruned_below: 0,
+            compaction_requested: false,
+        }
+    }
+
+    /// Returns the budget in bytes, which is zero if disabled.
+    pub(super) fn max_disk_usage(&self) -> u64 {
+        self.config.max_disk_usage
+    }
+
+    /// Returns whether disk usage should be measured again at `now`.
+    pub(super) fn is_check_due(&self, now: Timestamp) -> bool {
+        self.config.is_enabled()
+            && self.last_check.map_or(true, |(checked_at, _)| {
+                now.saturating_diff(checked_at) >= self.config.check_interval
+            })
+    }
+
+    /// Records a measurement of `usage` bytes at `now`, and returns the action it calls for.
+    pub(super) fn record(&mut self, now: Timestamp, usage: u64) -> BudgetAction {
+        let action = self.config.action(usage);
+        if action >= BudgetAction::Alert {
+            warn!(
+                %usage,
+                budget = %self.config.max_disk_usage,
+                ?action,
+                "storage disk usage approaching budget"
+            );
+        } else if self.last_action >= BudgetAction::Alert {
+            info!(%usage, budget = %self.config.max_disk_usage, "storage disk usage back to normal");
+        }
+        self.last_check = Some((now, usage));
+        self.last_action = action;
+        action
+    }
+
+    /// Returns the range of block heights whose execution results should be pruned, given the
+    /// highest stored block, or `None` if there are none.
+    pub(super) fn heights_to_prune(&self, highest_block_height: u64) -> Option<Range<u64>> {
+        let cutoff = highest_block_height.saturating_sub(self.config.retained_execution_results);
+        (cutoff > self.pruned_below).then(|| self.pruned_below..cutoff)
+    }
+
+    /// Records that the execution results of blocks below `height` have been pruned.
+    pub(super) fn set_pruned_below(&mut self, height: u64) {
+        self.pruned_below = self.pruned_below.max(height);
+    }
+
+    /// Requests the storage database in `root` to be compacted on the next start.
+    pub(super) fn request_compaction(&mut self, root: &Path) {
+        if self.compaction_requested {
+            return;
+        }
+        let marker = root.join(COMPACTION_MARKER_FILENAME);
+        match fs::write(&marker, b"") {
+            Ok(()) => {
+                warn!("storage database will be compacted on the next start");
+                self.compaction_requested = true;
+            }
+            Err(error) => warn!(
+                %error,
+                marker = %marker.display(),
+                "failed to request compaction of the storage database"
+            ),
+        }
+    }
+
+    /// Returns a description of the problem if the latest measurement called for any action.
+    pub(super) fn degradation(&self) -> Option<String> {
+        match self.last_check {
+            Some((_, usage)) if self.last_action >= BudgetAction::Alert => Some(format!(
+                "storage uses {} of its {} bytes disk budget",
+                usage, self.config.max_disk_usage
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the total size of the files in `dir` and its subfolders.
+pub(super) fn disk_usage(dir: &Path) -> io::Result<u64> {
+    let mut usage = 0u64;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // Doesn't follow symlinks, so nothing is counted twice.
+        let metadata = entry.metadata()?;
+        let size = if metadata.is_dir() {
+            disk_usage(&entry.path())?
+        } else {
+            metadata.len()
+        };
+        usage = usage.saturating_add(size);
+    }
+    Ok(usage)
+}
+
+/// Removes the execution results and transfers of the blocks with a height in `heights`,
+/// returning the number of removed execution results.
+pub(super) fn prune_execution_results(
+    env: &Environment,
+    deploy_metadata_db: Database,
+    transfer_db: Database,
+    block_height_index: &BTreeMap<u64, BlockHash>,
+    deploy_hash_index: &BTreeMap<DeployHash, BlockHashAndHeight>,
+    heights: Range<u64>,
+) -> Result<usize, FatalStorageError> {
+    let pruned_blocks: HashSet<BlockHash> = block_height_index
+        .range(heights.clone())
+        .map(|(_, block_hash)| *block_hash)
+        .collect();
+    if pruned_blocks.is_empty() {
+        return Ok(0);
+    }
+
+    let mut txn = env.begin_rw_txn()?;
+    let mut pruned = 0;
+    for (deploy_hash, block_hash_and_height) in deploy_hash_index {
+        if !heights.contains(&block_hash_and_height.block_height) {
+            continue;
+        }
+        let mut metadata: DeployMetadata = match txn.get_value(deploy_metadata_db, deploy_hash)? {
+            Some(metadata) => metadata,
+            None => continue,
+        };
+        let count = metadata.execution_results.len();
+        metadata
+            .execution_results
+            .retain(|block_hash, _| !pruned_blocks.contains(block_hash));
+        if metadata.execution_results.len() == count {
+            continue;
+        }
+        pruned += count - metadata.execution_results.len();
+        if metadata.execution_results.is_empty() {
+            txn.del(deploy_metadata_db, deploy_hash, None)?;
+        } else {
+            let _ = txn.put_value(deploy_metadata_db, deploy_hash, &metadata, true)?;
+        }
+    }
+    for block_hash in &pruned_blocks {
+        match txn.del(transfer_db, block_hash, None) {
+            Ok(()) | Err(lmdb::Error::NotFound) => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    txn.commit()?;
+    Ok(pruned)
+}
+
+/// Compacts the storage database in `root` if compaction was requested while the node was last
+/// running.
+///
+/// This must be called before the database is opened. Compaction is skipped and retried on the
+/// next start if there isn't enough disk space for a copy of the database.
+pub(super) fn compact_if_requested(root: &Path, map_size: usize) -> Result<(), FatalStorageError> {
+    let marker = root.join(COMPACTION_MARKER_FILENAME);
+    if !marker.exists() {
+        return Ok(());
+    }
+    let db_path = root.join(STORAGE_DB_FILENAME);
+    let compacted_path = root.join(COMPACTED_DB_FILENAME);
+    let compacted_lock_path = root.join(format!("{}-lock", COMPACTED_DB_FILENAME));
+    for leftover in [&compacted_path, &compacted_lock_path] {
+        match fs::remove_file(leftover) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(FatalStorageError::Compaction(leftover.clone(), err)),
+        }
+    }
+
+    let size_before = fs::metadata(&db_path)
+        .map_err(|err| FatalStorageError::Compaction(db_path.clone(), err))?
+        .len();
+    match fs2::available_space(root) {
+        Ok(available) if available > size_before => (),
+        Ok(available) => {
+            warn!(
+                %available,
+                required = %size_before,
+                "not enough disk space to compact the storage database"
+            );
+            return Ok(());
+        }
+        Err(error) => {
+            warn!(%error, "could not get available disk space, not compacting");
+            return Ok(());
+        }
+    }
+
+    info!(size = %size_before, "compacting storage database");
+    {
+        let source = open_environment(&db_path, map_size)?;
+        let dest = open_environment(&compacted_path, map_size)?;
+        copy_databases(&source, &dest)?;
+        dest.sync(true)?;
+    }
+    fs::rename(&compacted_path, &db_path).map_err(|original_error| {
+        FatalStorageError::UnableToMoveFile {
+            source_path: compacted_path.clone(),
+            dest_path: db_path.clone(),
+            original_error,
+        }
+    })?;
+    if let Err(error) = fs::remove_file(&compacted_lock_path) {
+        warn!(%error, "failed to remove lock file of the compacted database");
+    }
+    fs::remove_file(&marker).map_err(|err| FatalStorageError::Compaction(marker.clone(), err))?;
+
+    let size_after = fs::metadata(&db_path)
+        .map_err(|err| FatalStorageError::Compaction(db_path.clone(), err))?
+        .len();
+    info!(before = %size_before, after = %size_after, "compacted storage database");
+    Ok(())
+}
+
+/// Copies all named databases of `source` to the empty environment `dest`.
+///
+/// All storage databases are created without flags, so the copies are as well.
+fn copy_databases(source: &Environment, dest: &Environment) -> Result<(), FatalStorageError> {
+    // The keys of the unnamed main database are the names of the named databases.
+    let main_db = source.open_db(None)?;
+    let names = {
+        let txn = source.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(main_db)?;
+        cursor
+            .iter()
+            .map(|(name, _)| String::from_utf8_lossy(name).into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut dbs = Vec::with_capacity(names.len());
+    for name in &names {
+        let source_db = source.open_db(Some(name))?;
+        let dest_db = dest.create_db(Some(name), DatabaseFlags::empty())?;
+        dbs.push((source_db, dest_db));
+    }
+
+    let source_txn = source.begin_ro_txn()?;
+    let mut dest_txn = dest.begin_rw_txn()?;
+    let mut batch_len = 0;
+    for (source_db, dest_db) in dbs {
+        let mut cursor = source_txn.open_ro_cursor(source_db)?;
+        for (key, value) in cursor.iter() {
+            // The cursor yields the keys in order, so they can be appended.
+            dest_txn.put(dest_db, &key, &value, WriteFlags::APPEND)?;
+            batch_len += 1;
+            if batch_len == COMPACTION_BATCH_SIZE {
+                dest_txn.commit()?;
+                dest_txn = dest.begin_rw_txn()?;
+                batch_len = 0;
+            }
+        }
+    }
+    dest_txn.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_escalate_with_usage() {
+        let config = DiskBudgetConfig {
+            max_disk_usage: 1000,
+            ..Default::default()
+        };
+        assert_eq!(config.action(0), BudgetAction::None);
+        assert_eq!(config.action(799), BudgetAction::None);
+        assert_eq!(config.action(800), BudgetAction::Alert);
+        assert_eq!(config.action(900), BudgetAction::Prune);
+        assert_eq!(config.action(950), BudgetAction::Compact);
+        assert_eq!(config.action(1000), BudgetAction::Halt);
+
+        let disabled = DiskBudgetConfig::default();
+        assert_eq!(disabled.action(u64::MAX), BudgetAction::None);
+    }
+
+    #[test]
+    fn prunes_each_height_once() {
+        let mut budget = DiskBudget::new(DiskBudgetConfig {
+            max_disk_usage: 1000,
+            retained_execution_results: 10,
+            ..Default::default()
+        });
+        assert_eq!(budget.heights_to_prune(5), None);
+        assert_eq!(budget.heights_to_prune(25), Some(0..15));
+        budget.set_pruned_below(15);
+        assert_eq!(budget.heights_to_prune(25), None);
+        assert_eq!(budget.heights_to_prune(30), Some(15..20));
+    }
+
+    #[test]
+    fn checks_are_rate_limited() {
+        let mut budget = DiskBudget::new(DiskBudgetConfig {
+            max_disk_usage: 1000,
+            ..Default::default()
+        });
+        let now = Timestamp::from(1_000_000);
+        assert!(budget.is_check_due(now));
+        assert_eq!(budget.record(now, 850), BudgetAction::Alert);
+        assert!(budget.degradation().is_some());
+        assert!(!budget.is_check_due(now + TimeDiff::from_seconds(1)));
+        assert!(budget.is_check_due(now + DEFAULT_CHECK_INTERVAL));
+        assert_eq!(
+            budget.record(now + DEFAULT_CHECK_INTERVAL, 10),
+            BudgetAction::None
+        );
+        assert!(budget.degradation().is_none());
+
+        let disabled = DiskBudget::new(DiskBudgetConfig::default());
+        assert!(!disabled.is_check_due(now));
+    }
+}
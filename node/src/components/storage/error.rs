@@ -148,6 +148,23 @@ pub enum FatalStorageError {
     /// `ToBytes` deserialization failure of an item that should never fail to serialize.
     #[error("unexpected deserialization failure: {0}")]
     UnexpectedDeserializationFailure(bytesrepr::Error),
+    /// Failure to create or remove the unclean shutdown marker file.
+    #[error("failed to update unclean shutdown marker `{}`: {}", .0.display(), .1)]
+    ShutdownMarker(PathBuf, io::Error),
+    /// Failure to measure the disk usage of the storage folder.
+    #[error("failed to measure disk usage of `{}`: {}", .0.display(), .1)]
+    DiskUsage(PathBuf, io::Error),
+    /// The storage folder exhausted its disk budget.
+    #[error("storage uses {usage} bytes, exhausting its disk budget of {budget} bytes")]
+    DiskBudgetExhausted {
+        /// The number of bytes used by the storage folder.
+        usage: u64,
+        /// The disk budget in bytes.
+        budget: u64,
+    },
+    /// Filesystem error while compacting the storage database.
+    #[error("failed to compact storage database at `{}`: {}", .0.display(), .1)]
+    Compaction(PathBuf, io::Error),
 }
 
 // We wholesale wrap lmdb errors and treat them as internal errors here.
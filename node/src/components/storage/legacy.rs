@@ -0,0 +1,275 @@
+//! Types as stored by previous versions of the node.
+//!
+//! Values of these types are deserialized from raw bytes written before the layout of the current
+//! types changed, then converted into the current types.  See
+//! [`deserialize_internal`](super::lmdb_ext::deserialize_internal) for how legacy values are told
+//! apart from current ones.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use casper_types::{
+    account::AccountHash,
+    system::auction::{Bid, DelegationRate, Delegator, EraInfo, UnbondingPurse, VestingSchedule},
+    CLValue, DeployHash, DeployInfo, ExecutionEffect, ExecutionResult, NamedKey, Operation,
+    PublicKey, Transfer, TransferAddr, Transform, TransformEntry, URef, U128, U256, U512,
+};
+
+use crate::types::{BlockHash, DeployMetadata};
+
+/// A [`Transfer`] without a memo.
+#[derive(Deserialize)]
+pub(super) struct LegacyTransfer {
+    deploy_hash: DeployHash,
+    from: AccountHash,
+    to: Option<AccountHash>,
+    source: URef,
+    target: URef,
+    amount: U512,
+    gas: U512,
+    id: Option<u64>,
+}
+
+impl From<LegacyTransfer> for Transfer {
+    fn from(legacy: LegacyTransfer) -> Self {
+        Transfer::new(
+            legacy.deploy_hash,
+            legacy.from,
+            legacy.to,
+            legacy.source,
+            legacy.target,
+            legacy.amount,
+            legacy.gas,
+            legacy.id,
+            None,
+        )
+    }
+}
+
+/// A [`Delegator`] without unclaimed rewards.
+#[derive(Deserialize)]
+struct LegacyDelegator {
+    delegator_public_key: PublicKey,
+    staked_amount: U512,
+    bonding_purse: URef,
+    validator_public_key: PublicKey,
+    vesting_schedule: Option<VestingSchedule>,
+}
+
+impl From<LegacyDelegator> for Delegator {
+    fn from(legacy: LegacyDelegator) -> Self {
+        match legacy.vesting_schedule {
+            Some(vesting_schedule) => {
+                let mut delegator = Delegator::locked(
+                    legacy.delegator_public_key,
+                    legacy.staked_amount,
+                    legacy.bonding_purse,
+                    legacy.validator_public_key,
+                    vesting_schedule.initial_release_timestamp_millis(),
+                );
+                if let Some(current) = delegator.vesting_schedule_mut() {
+                    *current = vesting_schedule;
+                }
+                delegator
+            }
+            None => Delegator::unlocked(
+                legacy.delegator_public_key,
+                legacy.staked_amount,
+                legacy.bonding_purse,
+                legacy.validator_public_key,
+            ),
+        }
+    }
+}
+
+/// A [`Bid`] holding legacy delegators.
+#[derive(Deserialize)]
+struct LegacyBid {
+    validator_public_key: PublicKey,
+    bonding_purse: URef,
+    staked_amount: U512,
+    delegation_rate: DelegationRate,
+    vesting_schedule: Option<VestingSchedule>,
+    delegators: BTreeMap<PublicKey, LegacyDelegator>,
+    inactive: bool,
+}
+
+impl From<LegacyBid> for Bid {
+    fn from(legacy: LegacyBid) -> Self {
+        let mut bid = match legacy.vesting_schedule {
+            Some(vesting_schedule) => {
+                let mut bid = Bid::locked(
+                    legacy.validator_public_key,
+                    legacy.bonding_purse,
+                    legacy.staked_amount,
+                    legacy.delegation_rate,
+                    vesting_schedule.initial_release_timestamp_millis(),
+                );
+                if let Some(current) = bid.vesting_schedule_mut() {
+                    *current = vesting_schedule;
+                }
+                bid
+            }
+            None => Bid::unlocked(
+                legacy.validator_public_key,
+                legacy.bonding_purse,
+                legacy.staked_amount,
+                legacy.delegation_rate,
+            ),
+        };
+        bid.delegators_mut().extend(
+            legacy
+                .delegators
+                .into_iter()
+                .map(|(public_key, delegator)| (public_key, delegator.into())),
+        );
+        if legacy.inactive {
+            bid.deactivate();
+        }
+        bid
+    }
+}
+
+/// A [`DeployMetadata`] holding execution results with legacy transfers and bids.
+#[derive(Deserialize)]
+pub(super) struct LegacyDeployMetadata {
+    execution_results: HashMap<BlockHash, LegacyExecutionResult>,
+}
+
+impl From<LegacyDeployMetadata> for DeployMetadata {
+    fn from(legacy: LegacyDeployMetadata) -> Self {
+        DeployMetadata {
+            execution_results: legacy
+                .execution_results
+                .into_iter()
+                .map(|(block_hash, execution_result)| (block_hash, execution_result.into()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum LegacyExecutionResult {
+    Failure {
+        effect: LegacyExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
+    },
+    Success {
+        effect: LegacyExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+    },
+}
+
+impl From<LegacyExecutionResult> for ExecutionResult {
+    fn from(legacy: LegacyExecutionResult) -> Self {
+        match legacy {
+            LegacyExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => ExecutionResult::Failure {
+                effect: effect.into(),
+                transfers,
+                cost,
+                error_message,
+            },
+            LegacyExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+            } => ExecutionResult::Success {
+                effect: effect.into(),
+                transfers,
+                cost,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyExecutionEffect {
+    operations: Vec<Operation>,
+    transforms: Vec<LegacyTransformEntry>,
+}
+
+impl From<LegacyExecutionEffect> for ExecutionEffect {
+    fn from(legacy: LegacyExecutionEffect) -> Self {
+        ExecutionEffect {
+            operations: legacy.operations,
+            transforms: legacy
+                .transforms
+                .into_iter()
+                .map(|entry| TransformEntry {
+                    key: entry.key,
+                    transform: entry.transform.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyTransformEntry {
+    key: String,
+    transform: LegacyTransform,
+}
+
+/// A [`Transform`] whose variants are in the same order as the current ones, only differing in the
+/// types whose layout changed.
+#[derive(Deserialize)]
+enum LegacyTransform {
+    Identity,
+    WriteCLValue(CLValue),
+    WriteAccount(AccountHash),
+    WriteContractWasm,
+    WriteContract,
+    WriteContractPackage,
+    WriteDeployInfo(DeployInfo),
+    WriteEraInfo(EraInfo),
+    WriteTransfer(LegacyTransfer),
+    WriteBid(Box<LegacyBid>),
+    WriteWithdraw(Vec<UnbondingPurse>),
+    AddInt32(i32),
+    AddUInt64(u64),
+    AddUInt128(U128),
+    AddUInt256(U256),
+    AddUInt512(U512),
+    AddKeys(Vec<NamedKey>),
+    Failure(String),
+    Prune,
+}
+
+impl From<LegacyTransform> for Transform {
+    fn from(legacy: LegacyTransform) -> Self {
+        match legacy {
+            LegacyTransform::Identity => Transform::Identity,
+            LegacyTransform::WriteCLValue(value) => Transform::WriteCLValue(value),
+            LegacyTransform::WriteAccount(account_hash) => Transform::WriteAccount(account_hash),
+            LegacyTransform::WriteContractWasm => Transform::WriteContractWasm,
+            LegacyTransform::WriteContract => Transform::WriteContract,
+            LegacyTransform::WriteContractPackage => Transform::WriteContractPackage,
+            LegacyTransform::WriteDeployInfo(deploy_info) => {
+                Transform::WriteDeployInfo(deploy_info)
+            }
+            LegacyTransform::WriteEraInfo(era_info) => Transform::WriteEraInfo(era_info),
+            LegacyTransform::WriteTransfer(transfer) => Transform::WriteTransfer(transfer.into()),
+            LegacyTransform::WriteBid(bid) => Transform::WriteBid(Box::new((*bid).into())),
+            LegacyTransform::WriteWithdraw(unbonding_purses) => {
+                Transform::WriteWithdraw(unbonding_purses)
+            }
+            LegacyTransform::AddInt32(value) => Transform::AddInt32(value),
+            LegacyTransform::AddUInt64(value) => Transform::AddUInt64(value),
+            LegacyTransform::AddUInt128(value) => Transform::AddUInt128(value),
+            LegacyTransform::AddUInt256(value) => Transform::AddUInt256(value),
+            LegacyTransform::AddUInt512(value) => Transform::AddUInt512(value),
+            LegacyTransform::AddKeys(named_keys) => Transform::AddKeys(named_keys),
+            LegacyTransform::Failure(error) => Transform::Failure(error),
+            LegacyTransform::Prune => Transform::Prune,
+        }
+    }
+}
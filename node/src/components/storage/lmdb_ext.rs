@@ -9,8 +9,15 @@
 //!
 //! Serialization errors are unified into a generic, type erased `std` error to allow for easy
 //! interchange of the serialization format if desired.
+//!
+//! Values written with [`WriteTransactionExt::put_value_compressed`] may be compressed, see the
+//! [`compression`](super::compression) module; [`TransactionExt::get_value`] decompresses them
+//! transparently.
 
-use std::any::TypeId;
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+};
 
 use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
 use serde::{de::DeserializeOwned, Serialize};
@@ -19,9 +26,21 @@ use thiserror::Error;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     system::auction::UnbondingPurse,
+    Transfer,
+};
+
+use super::{
+    compression::{self, Compression},
+    legacy::{LegacyDeployMetadata, LegacyTransfer},
 };
+use crate::types::DeployMetadata;
 
 const UNBONDING_PURSE_V2_MAGIC_BYTES: &[u8] = &[121, 17, 133, 179, 91, 63, 69, 222];
+/// Prefix of transfers serialized since transfer memos were introduced.
+const TRANSFERS_V2_MAGIC_BYTES: &[u8] = &[254, 200, 86, 81, 119, 241, 197, 113];
+/// Prefix of deploy metadata serialized since transfer memos and delegators' unclaimed rewards were
+/// introduced.
+const DEPLOY_METADATA_V2_MAGIC_BYTES: &[u8] = &[180, 37, 129, 11, 60, 162, 200, 128];
 
 /// Error wrapper for lower-level storage errors.
 ///
@@ -116,6 +135,21 @@ pub(super) trait WriteTransactionExt {
         overwrite: bool,
     ) -> Result<bool, LmdbExtError>;
 
+    /// Helper function to write a value to a database, compressing it according to `compression` if
+    /// given.
+    ///
+    /// Returns `true` if the value has actually been written, `false` if the key already existed.
+    ///
+    /// Setting `overwrite` to true will cause the value to always be written instead.
+    fn put_value_compressed<K: AsRef<[u8]>, V: 'static + Serialize>(
+        &mut self,
+        db: Database,
+        key: &K,
+        value: &V,
+        overwrite: bool,
+        compression: Option<Compression>,
+    ) -> Result<bool, LmdbExtError>;
+
     /// Helper function to write a value to a database using the `bytesrepr` `ToBytes`/`FromBytes`
     /// serialization.
     ///
@@ -143,7 +177,7 @@ where
     ) -> Result<Option<V>, LmdbExtError> {
         match self.get(db, key) {
             // Deserialization failures are likely due to storage corruption.
-            Ok(raw) => deserialize_internal(raw),
+            Ok(raw) => deserialize_internal(&decompress(raw)?),
             Err(lmdb::Error::NotFound) => Ok(None),
             Err(err) => Err(err.into()),
         }
@@ -168,11 +202,18 @@ where
 /// In case the `value` is of the `UnbondingPurse` type it uses the specialized
 /// function to provide compatibility with the legacy version of the `UnbondingPurse` struct.
 /// See [`serialize_unbonding_purse`] for more details.
+///
+/// Transfers and deploy metadata are prefixed with magic bytes likewise, as their layout changed
+/// when transfer memos and delegators' unclaimed rewards were introduced.
 pub(crate) fn serialize_internal<V: 'static + Serialize>(
     value: &V,
 ) -> Result<Vec<u8>, LmdbExtError> {
     let buffer = if TypeId::of::<UnbondingPurse>() == TypeId::of::<V>() {
         serialize_unbonding_purse(value)?
+    } else if TypeId::of::<Vec<Transfer>>() == TypeId::of::<V>() {
+        serialize_with_magic_bytes(TRANSFERS_V2_MAGIC_BYTES, value)?
+    } else if TypeId::of::<DeployMetadata>() == TypeId::of::<V>() {
+        serialize_with_magic_bytes(DEPLOY_METADATA_V2_MAGIC_BYTES, value)?
     } else {
         serialize(value)?
     };
@@ -183,11 +224,30 @@ pub(crate) fn serialize_internal<V: 'static + Serialize>(
 /// In case the expected object is of the `UnbondingPurse` type it uses the specialized
 /// function to provide compatibility with the legacy version of the `UnbondingPurse` struct.
 /// See [`deserialize_unbonding_purse`] for more details.
+///
+/// Transfers and deploy metadata without magic bytes are deserialized as their
+/// [`legacy`](super::legacy) versions and converted.
 pub(crate) fn deserialize_internal<V: 'static + DeserializeOwned>(
     raw: &[u8],
 ) -> Result<Option<V>, LmdbExtError> {
     if TypeId::of::<UnbondingPurse>() == TypeId::of::<V>() {
         deserialize_unbonding_purse(raw).map(Some)
+    } else if TypeId::of::<Vec<Transfer>>() == TypeId::of::<V>() {
+        deserialize_with_magic_bytes(
+            TRANSFERS_V2_MAGIC_BYTES,
+            raw,
+            |legacy: Vec<LegacyTransfer>| -> Vec<Transfer> {
+                legacy.into_iter().map(Transfer::from).collect()
+            },
+        )
+        .map(Some)
+    } else if TypeId::of::<DeployMetadata>() == TypeId::of::<V>() {
+        deserialize_with_magic_bytes(
+            DEPLOY_METADATA_V2_MAGIC_BYTES,
+            raw,
+            |legacy: LegacyDeployMetadata| DeployMetadata::from(legacy),
+        )
+        .map(Some)
     } else {
         deserialize(raw).map(Some)
     }
@@ -202,19 +262,24 @@ impl WriteTransactionExt for RwTransaction<'_> {
         overwrite: bool,
     ) -> Result<bool, LmdbExtError> {
         let buffer = serialize_internal(value)?;
+        put_buffer(self, db, key, &buffer, overwrite)
+    }
 
-        let flags = if overwrite {
-            WriteFlags::empty()
-        } else {
-            WriteFlags::NO_OVERWRITE
-        };
-
-        match self.put(db, key, &buffer, flags) {
-            Ok(()) => Ok(true),
-            // If we did not add the value due to it already existing, just return `false`.
-            Err(lmdb::Error::KeyExist) => Ok(false),
-            Err(err) => Err(err.into()),
+    fn put_value_compressed<K: AsRef<[u8]>, V: 'static + Serialize>(
+        &mut self,
+        db: Database,
+        key: &K,
+        value: &V,
+        overwrite: bool,
+        compression: Option<Compression>,
+    ) -> Result<bool, LmdbExtError> {
+        let mut buffer = serialize_internal(value)?;
+        if let Some(compression) = compression {
+            buffer = compression
+                .compress(buffer)
+                .map_err(|err| LmdbExtError::Other(Box::new(err)))?;
         }
+        put_buffer(self, db, key, &buffer, overwrite)
     }
 
     fn put_value_bytesrepr<K: AsRef<[u8]>, V: ToBytes>(
@@ -225,22 +290,43 @@ impl WriteTransactionExt for RwTransaction<'_> {
         overwrite: bool,
     ) -> Result<bool, LmdbExtError> {
         let buffer = serialize_bytesrepr(value)?;
+        put_buffer(self, db, key, &buffer, overwrite)
+    }
+}
 
-        let flags = if overwrite {
-            WriteFlags::empty()
-        } else {
-            WriteFlags::NO_OVERWRITE
-        };
+/// Writes an already serialized value to a database.
+///
+/// Returns `true` if the value has actually been written, `false` if the key already existed.
+fn put_buffer<K: AsRef<[u8]>>(
+    txn: &mut RwTransaction<'_>,
+    db: Database,
+    key: &K,
+    buffer: &[u8],
+    overwrite: bool,
+) -> Result<bool, LmdbExtError> {
+    let flags = if overwrite {
+        WriteFlags::empty()
+    } else {
+        WriteFlags::NO_OVERWRITE
+    };
 
-        match self.put(db, key, &buffer, flags) {
-            Ok(()) => Ok(true),
-            // If we did not add the value due to it already existing, just return `false`.
-            Err(lmdb::Error::KeyExist) => Ok(false),
-            Err(err) => Err(err.into()),
-        }
+    match txn.put(db, key, &buffer, flags) {
+        Ok(()) => Ok(true),
+        // If we did not add the value due to it already existing, just return `false`.
+        Err(lmdb::Error::KeyExist) => Ok(false),
+        Err(err) => Err(err.into()),
     }
 }
 
+/// Decompresses a raw value if it is compressed.
+///
+/// Values compressed in an unknown format are considered corrupted, like any other data written by
+/// an incompatible version of this software.
+#[inline(always)]
+pub(super) fn decompress(raw: &[u8]) -> Result<Cow<[u8]>, LmdbExtError> {
+    compression::decompress(raw).map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))
+}
+
 /// Deserializes from a buffer.
 #[inline(always)]
 pub(super) fn deserialize<T: DeserializeOwned>(raw: &[u8]) -> Result<T, LmdbExtError> {
@@ -271,6 +357,42 @@ pub(super) fn deserialize_unbonding_purse<T: DeserializeOwned>(
     }
 }
 
+/// Deserializes a value of type `C` serialized with [`serialize_with_magic_bytes`], or, if `raw`
+/// does not start with the magic bytes, a legacy value of type `L`, converted with `convert`.
+///
+/// The value is returned as `V`, the type requested by the caller, which must be `C`.
+fn deserialize_with_magic_bytes<V, C, L>(
+    magic_bytes: &[u8],
+    raw: &[u8],
+    convert: impl FnOnce(L) -> C,
+) -> Result<V, LmdbExtError>
+where
+    V: 'static,
+    C: 'static + DeserializeOwned,
+    L: DeserializeOwned,
+{
+    let value: C = match raw.strip_prefix(magic_bytes) {
+        Some(current) => deserialize(current)?,
+        None => convert(deserialize(raw)?),
+    };
+    let value: Box<dyn Any> = Box::new(value);
+    value
+        .downcast()
+        .map(|value| *value)
+        .map_err(|_| LmdbExtError::Other("deserialized value of unexpected type".into()))
+}
+
+/// Serializes into a buffer prefixed with the given magic bytes, which tell the value apart from
+/// its legacy version.
+fn serialize_with_magic_bytes<T: Serialize>(
+    magic_bytes: &[u8],
+    value: &T,
+) -> Result<Vec<u8>, LmdbExtError> {
+    let mut serialized = magic_bytes.to_vec();
+    serialized.extend(serialize(value)?);
+    Ok(serialized)
+}
+
 /// Serializes into a buffer.
 #[inline(always)]
 pub(super) fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, LmdbExtError> {
@@ -11,13 +11,18 @@ use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use casper_types::{
-    system::auction::UnbondingPurse, testing::TestRng, AccessRights, EraId, ExecutionResult,
-    ProtocolVersion, PublicKey, SecretKey, URef, U512,
+    account::AccountHash,
+    system::auction::{Bid, Delegator, UnbondingPurse, VestingSchedule},
+    testing::TestRng,
+    AccessRights, EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, SecretKey,
+    TimeDiff, Transfer, TransferMemo, Transform, TransformEntry, URef, U512,
 };
 
 use super::{
-    move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage,
+    compression::{self, Compression},
+    disk_budget, move_storage_files_to_network_subdir, prune_replay_index,
+    should_move_storage_files_to_network_subdir, write_execution_results, Config, Sequence,
+    Storage, COMPRESSION_FORMAT_STORAGE_KEY,
 };
 use crate::{
     effect::{requests::StorageRequest, Multiple},
@@ -42,6 +47,12 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        enable_compression: false,
+        compression_level: 3,
+        compression_threshold: 1024,
+        replay_protection_window: TimeDiff::from_seconds(3600),
+        disk_budget: Default::default(),
+        execution_results_retention_eras: 0,
     }
 }
 
@@ -280,6 +291,22 @@ fn put_deploy(
     response
 }
 
+fn is_replayed_deploy(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::IsReplayedDeploy {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 fn insert_to_deploy_index(
     storage: &mut Storage,
     deploy: Deploy,
@@ -291,22 +318,53 @@ fn insert_to_deploy_index(
         .is_none()
 }
 
-/// Stores execution results in a storage component.
-fn put_execution_results(
+/// Requests the height below which execution results have been pruned from a storage component.
+fn get_execution_results_pruned_below(
     harness: &mut ComponentHarness<UnitTestEvent>,
     storage: &mut Storage,
+) -> u64 {
+    let response = harness.send_request(storage, |responder| {
+        StorageRequest::GetExecutionResultsPrunedBelow { responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Stores execution results in a storage component, without storing the block they belong to.
+fn put_execution_results(
+    storage: &mut Storage,
     block_hash: BlockHash,
     execution_results: HashMap<DeployHash, ExecutionResult>,
 ) {
-    harness.send_request(storage, move |responder| {
-        StorageRequest::PutExecutionResults {
-            block_hash: Box::new(block_hash),
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    write_execution_results(
+        &mut txn,
+        storage.deploy_metadata_db,
+        storage.transfer_db,
+        &block_hash,
+        execution_results,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+}
+
+/// Stores a block together with the execution results of its deploys in a storage component.
+fn put_executed_block(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block: Box<Block>,
+    execution_results: HashMap<DeployHash, ExecutionResult>,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutExecutedBlock {
+            block,
             execution_results,
             responder,
         }
         .into()
     });
     assert!(harness.is_idle());
+    response
 }
 
 #[test]
@@ -653,7 +711,7 @@ fn store_execution_results_for_two_blocks() {
     let first_result: ExecutionResult = harness.rng.gen();
     let mut first_results = HashMap::new();
     first_results.insert(*deploy.id(), first_result.clone());
-    put_execution_results(&mut harness, &mut storage, block_hash_a, first_results);
+    put_execution_results(&mut storage, block_hash_a, first_results);
 
     // Retrieve and check if correct.
     let (first_deploy, first_metadata) =
@@ -673,7 +731,7 @@ fn store_execution_results_for_two_blocks() {
     let second_result: ExecutionResult = harness.rng.gen();
     let mut second_results = HashMap::new();
     second_results.insert(*deploy.id(), second_result.clone());
-    put_execution_results(&mut harness, &mut storage, block_hash_b, second_results);
+    put_execution_results(&mut storage, block_hash_b, second_results);
 
     // Retrieve the deploy again, should now contain both.
     let (second_deploy, second_metadata) =
@@ -763,7 +821,7 @@ fn store_random_execution_results() {
         assert_eq!(block_results.len(), unique_count + shared_deploys.len());
 
         // Now we can submit the block's execution results.
-        put_execution_results(harness, storage, *block_hash, block_results);
+        put_execution_results(storage, *block_hash, block_results);
     }
 
     setup_block(
@@ -814,10 +872,10 @@ fn store_execution_results_twice_for_same_block_deploy_pair() {
     let mut exec_result_2 = HashMap::new();
     exec_result_2.insert(deploy_hash, harness.rng.gen());
 
-    put_execution_results(&mut harness, &mut storage, block_hash, exec_result_1);
+    put_execution_results(&mut storage, block_hash, exec_result_1);
 
     // Storing a second execution result for the same deploy on the same block should panic.
-    put_execution_results(&mut harness, &mut storage, block_hash, exec_result_2);
+    put_execution_results(&mut storage, block_hash, exec_result_2);
 }
 
 #[test]
@@ -831,10 +889,10 @@ fn store_identical_execution_results() {
     let mut exec_result = HashMap::new();
     exec_result.insert(deploy_hash, harness.rng.gen());
 
-    put_execution_results(&mut harness, &mut storage, block_hash, exec_result.clone());
+    put_execution_results(&mut storage, block_hash, exec_result.clone());
 
     // We should be fine storing the exact same result twice.
-    put_execution_results(&mut harness, &mut storage, block_hash, exec_result);
+    put_execution_results(&mut storage, block_hash, exec_result);
 }
 
 /// Example state used in storage.
@@ -844,6 +902,45 @@ struct StateData {
     b: i32,
 }
 
+#[test]
+fn should_store_block_and_execution_results_together() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random(&mut harness.rng);
+    let deploy = Deploy::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+
+    let execution_result: ExecutionResult = harness.rng.gen();
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.id(), execution_result.clone());
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        Box::new(block.clone()),
+        execution_results,
+    ));
+
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()),
+        Some(block.clone())
+    );
+    assert_eq!(
+        get_block_at_height(&mut storage, block.height()),
+        Some(block.clone())
+    );
+    let (_, metadata) = get_naive_deploy_and_metadata(&mut harness, &mut storage, *deploy.id())
+        .expect("missing deploy we stored earlier");
+    let mut expected_results = HashMap::new();
+    expected_results.insert(*block.hash(), execution_result);
+    assert_eq!(
+        metadata,
+        DeployMetadata {
+            execution_results: expected_results
+        }
+    );
+}
+
 #[test]
 fn test_legacy_interface() {
     let mut harness = ComponentHarness::default();
@@ -876,14 +973,14 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     let deploy = Deploy::random(&mut harness.rng);
     let execution_result: ExecutionResult = harness.rng.gen();
     put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
-    put_block(
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.id(), execution_result.clone());
+    assert!(put_executed_block(
         &mut harness,
         &mut storage,
         Box::new(block.disable_switch_block().clone()),
-    );
-    let mut execution_results = HashMap::new();
-    execution_results.insert(*deploy.id(), execution_result.clone());
-    put_execution_results(&mut harness, &mut storage, *block.hash(), execution_results);
+        execution_results,
+    ));
     assert_eq!(
         get_block_at_height(&mut storage, block_height).expect("block not indexed properly"),
         block
@@ -921,6 +1018,272 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn should_remember_executed_deploys_until_replay_protection_window_passes() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let mut block = Block::random(&mut harness.rng);
+    let deploy = Deploy::random(&mut harness.rng);
+    let other_deploy = Deploy::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+    put_deploy(&mut harness, &mut storage, Box::new(other_deploy.clone()));
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.id(), harness.rng.gen());
+    assert!(put_executed_block(
+        &mut harness,
+        &mut storage,
+        Box::new(block.disable_switch_block().clone()),
+        execution_results,
+    ));
+    assert!(is_replayed_deploy(&mut harness, &mut storage, *deploy.id()));
+    assert!(!is_replayed_deploy(
+        &mut harness,
+        &mut storage,
+        *other_deploy.id()
+    ));
+
+    // The replay index survives a restart.
+    drop(storage);
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    assert!(is_replayed_deploy(&mut harness, &mut storage, *deploy.id()));
+
+    // Entries are only pruned once the window after the deploy's expiry has passed.
+    let window = TimeDiff::from_seconds(60);
+    let expiry = deploy.header().expires();
+    let pruned = prune_replay_index(
+        &storage.env,
+        storage.replay_index_db,
+        window,
+        expiry.saturating_add(TimeDiff::from_seconds(30)),
+    )
+    .expect("should prune replay index");
+    assert_eq!(pruned, 0);
+    assert!(is_replayed_deploy(&mut harness, &mut storage, *deploy.id()));
+
+    let pruned = prune_replay_index(
+        &storage.env,
+        storage.replay_index_db,
+        window,
+        expiry.saturating_add(TimeDiff::from_seconds(90)),
+    )
+    .expect("should prune replay index");
+    assert_eq!(pruned, 1);
+    assert!(!is_replayed_deploy(
+        &mut harness,
+        &mut storage,
+        *deploy.id()
+    ));
+}
+
+#[test]
+fn should_prune_execution_results_of_old_blocks() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let mut blocks_and_deploys = vec![];
+    for height in 1..=2 {
+        let deploy = Deploy::random(&mut harness.rng);
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+        let block = Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::new(1),
+            height,
+            ProtocolVersion::from_parts(1, 0, 0),
+            false,
+            iter::once(&deploy),
+        );
+        let mut execution_results = HashMap::new();
+        execution_results.insert(*deploy.id(), harness.rng.gen());
+        assert!(put_executed_block(
+            &mut harness,
+            &mut storage,
+            Box::new(block.clone()),
+            execution_results,
+        ));
+        blocks_and_deploys.push((block, deploy));
+    }
+
+    let pruned = disk_budget::prune_execution_results(
+        &storage.env,
+        storage.deploy_metadata_db,
+        storage.transfer_db,
+        &storage.block_height_index,
+        &storage.deploy_hash_index,
+        0..2,
+    )
+    .expect("should prune execution results");
+    assert_eq!(pruned, 1);
+
+    // The pruned deploy is still known to be included in its block.
+    let (old_block, old_deploy) = &blocks_and_deploys[0];
+    let (_, metadata) = get_naive_deploy_and_metadata(&mut harness, &mut storage, *old_deploy.id())
+        .expect("missing deploy we stored earlier");
+    assert_eq!(
+        metadata,
+        DeployMetadataExt::BlockInfo(BlockHashAndHeight::new(*old_block.hash(), 1))
+    );
+    let (new_block, new_deploy) = &blocks_and_deploys[1];
+    let (_, metadata) = get_naive_deploy_and_metadata(&mut harness, &mut storage, *new_deploy.id())
+        .expect("missing deploy we stored earlier");
+    match metadata {
+        DeployMetadataExt::Metadata(metadata) => {
+            assert!(metadata.execution_results.contains_key(new_block.hash()))
+        }
+        other => panic!("unexpected metadata {:?}", other),
+    }
+}
+
+#[test]
+fn should_retain_execution_results_of_configured_number_of_eras() {
+    let mut harness = ComponentHarness::default();
+    let mut cfg = new_config(&harness);
+    cfg.execution_results_retention_eras = 1;
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg.clone()),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        "test",
+    )
+    .expect("could not create storage component");
+
+    // Two blocks in each of eras 1 and 2, the second of which is a switch block.
+    let mut blocks_and_deploys = vec![];
+    for height in 1..=4 {
+        let deploy = Deploy::random(&mut harness.rng);
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+        let block = Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::new((height + 1) / 2),
+            height,
+            ProtocolVersion::from_parts(1, 0, 0),
+            height % 2 == 0,
+            iter::once(&deploy),
+        );
+        let mut execution_results = HashMap::new();
+        execution_results.insert(*deploy.id(), harness.rng.gen());
+        assert!(put_executed_block(
+            &mut harness,
+            &mut storage,
+            Box::new(block.clone()),
+            execution_results,
+        ));
+        blocks_and_deploys.push((block, deploy));
+    }
+
+    // Only the execution results of era 2 are retained.
+    assert_eq!(
+        get_execution_results_pruned_below(&mut harness, &mut storage),
+        3
+    );
+    for (block, deploy) in &blocks_and_deploys {
+        let (_, metadata) = get_naive_deploy_and_metadata(&mut harness, &mut storage, *deploy.id())
+            .expect("missing deploy we stored earlier");
+        if block.height() < 3 {
+            assert_eq!(
+                metadata,
+                DeployMetadataExt::BlockInfo(BlockHashAndHeight::new(
+                    *block.hash(),
+                    block.height()
+                ))
+            );
+        } else {
+            assert!(matches!(metadata, DeployMetadataExt::Metadata(_)));
+        }
+    }
+
+    // The height below which execution results have been pruned survives a restart.
+    drop(storage);
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        "test",
+    )
+    .expect("could not reopen storage component");
+    assert_eq!(
+        get_execution_results_pruned_below(&mut harness, &mut storage),
+        3
+    );
+}
+
+#[test]
+fn should_backfill_missing_execution_results() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploys: Vec<Deploy> = (0..2).map(|_| Deploy::random(&mut harness.rng)).collect();
+    for deploy in &deploys {
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+    }
+    let block = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::new(1),
+        1,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        deploys.iter(),
+    );
+    assert!(put_block(
+        &mut harness,
+        &mut storage,
+        Box::new(block.clone())
+    ));
+
+    // Only the first deploy's execution result is stored.
+    let stored_result: ExecutionResult = harness.rng.gen();
+    let mut stored_results = HashMap::new();
+    stored_results.insert(*deploys[0].id(), stored_result.clone());
+    put_execution_results(&mut storage, *block.hash(), stored_results);
+    assert_eq!(
+        storage.read_missing_execution_results(&block).unwrap(),
+        vec![*deploys[1].id()]
+    );
+
+    let (block_deploys, block_transfers) = storage
+        .read_block_deploys_with_finalized_approvals(&block)
+        .unwrap()
+        .expect("should read deploys of block");
+    assert_eq!(block_deploys, deploys);
+    assert!(block_transfers.is_empty());
+
+    // Only the missing result is written, the stored one is kept.
+    let backfilled_result: ExecutionResult = harness.rng.gen();
+    let mut regenerated_results = HashMap::new();
+    regenerated_results.insert(*deploys[0].id(), harness.rng.gen());
+    regenerated_results.insert(*deploys[1].id(), backfilled_result.clone());
+    let written = storage
+        .write_backfilled_execution_results(&block, regenerated_results)
+        .unwrap();
+    assert_eq!(written, 1);
+    assert!(storage
+        .read_missing_execution_results(&block)
+        .unwrap()
+        .is_empty());
+
+    for (deploy, expected_result) in deploys.iter().zip([stored_result, backfilled_result]) {
+        let (_, metadata) = get_naive_deploy_and_metadata(&mut harness, &mut storage, *deploy.id())
+            .expect("missing deploy we stored earlier");
+        match metadata {
+            DeployMetadataExt::Metadata(metadata) => assert_eq!(
+                metadata.execution_results.get(block.hash()),
+                Some(&expected_result)
+            ),
+            other => panic!("unexpected metadata {:?}", other),
+        }
+    }
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
@@ -975,12 +1338,7 @@ fn should_hard_reset() {
         put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
         let mut exec_results = HashMap::new();
         exec_results.insert(*deploy.id(), execution_result);
-        put_execution_results(
-            &mut harness,
-            &mut storage,
-            *block_hash,
-            exec_results.clone(),
-        );
+        put_execution_results(&mut storage, *block_hash, exec_results.clone());
         deploys.push(deploy);
         execution_results.push(exec_results);
     }
@@ -1260,6 +1618,62 @@ fn should_restrict_returned_blocks() {
         .expect("should return block failed"));
 }
 
+#[test]
+fn should_detect_unclean_shutdown() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+    assert!(!storage.unclean_shutdown_detected());
+    drop(storage);
+
+    // Reopening without marking a clean shutdown should detect the marker.
+    let storage = storage_fixture(&harness);
+    assert!(storage.unclean_shutdown_detected());
+    storage
+        .mark_clean_shutdown()
+        .expect("should mark clean shutdown");
+    drop(storage);
+
+    let storage = storage_fixture(&harness);
+    assert!(!storage.unclean_shutdown_detected());
+}
+
+#[test]
+fn should_unmark_inconsistent_highest_complete_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(1),
+        4,
+        ProtocolVersion::from_parts(1, 5, 0),
+        false,
+        None,
+    );
+    storage.write_block(&block).unwrap();
+    storage.completed_blocks.insert(4);
+    // Block 5 is marked complete, but is not actually in storage.
+    storage.completed_blocks.insert(5);
+
+    let highest_complete_block_header = storage
+        .verify_highest_complete_block()
+        .expect("should verify highest complete block")
+        .expect("should have a complete block");
+    assert_eq!(highest_complete_block_header, *block.header());
+    assert_eq!(
+        storage.completed_blocks.highest_sequence().map(Sequence::high),
+        Some(4)
+    );
+
+    storage
+        .unmark_complete_blocks_from(4)
+        .expect("should unmark blocks");
+    assert!(storage
+        .verify_highest_complete_block()
+        .expect("should verify highest complete block")
+        .is_none());
+}
+
 #[test]
 fn should_get_block_header_by_height() {
     let mut harness = ComponentHarness::default();
@@ -1332,3 +1746,244 @@ fn unbonding_purse_serialization_roundtrip() {
     // Explicitly assert that the `new_validator` is not `None`
     assert!(deserialized.new_validator().is_some())
 }
+
+/// The id of the transfers whose memos [`remove_transfer_memos`] removes.
+const LEGACY_TRANSFER_ID: u64 = 0x1122_3344_5566_7788;
+
+/// Returns a transfer without memo, identified by [`LEGACY_TRANSFER_ID`].
+fn legacy_transfer() -> Transfer {
+    Transfer::new(
+        casper_types::DeployHash::new([1; 32]),
+        AccountHash::new([2; 32]),
+        None,
+        URef::new([3; 32], AccessRights::READ_ADD_WRITE),
+        URef::new([4; 32], AccessRights::READ_ADD_WRITE),
+        U512::from(5),
+        U512::from(6),
+        Some(LEGACY_TRANSFER_ID),
+        None,
+    )
+}
+
+/// Removes the `count` bytes following each occurrence of `marker` in `serialized`.
+fn remove_bytes_following(serialized: Vec<u8>, marker: &[u8], count: usize) -> Vec<u8> {
+    let mut legacy = Vec::new();
+    let mut remainder = &serialized[..];
+    while let Some(position) = remainder
+        .windows(marker.len())
+        .position(|window| window == marker)
+    {
+        let end = position + marker.len();
+        legacy.extend(&remainder[..end]);
+        remainder = &remainder[end + count..];
+    }
+    legacy.extend(remainder);
+    assert_ne!(legacy, serialized, "should have removed bytes");
+    legacy
+}
+
+/// Turns the `bincode` serialization of a value holding transfers from [`legacy_transfer`] into the
+/// serialization written before transfer memos were introduced, by removing the `None` memos
+/// following their ids.
+fn remove_transfer_memos(serialized: Vec<u8>) -> Vec<u8> {
+    let id = bincode::serialize(&Some(LEGACY_TRANSFER_ID)).unwrap();
+    let memo = bincode::serialize(&Option::<TransferMemo>::None).unwrap();
+    remove_bytes_following(serialized, &id, memo.len())
+}
+
+#[test]
+fn should_read_legacy_transfers() {
+    let transfers = vec![legacy_transfer(), legacy_transfer()];
+    let legacy_bytes = remove_transfer_memos(bincode::serialize(&transfers).unwrap());
+
+    let deserialized: Vec<Transfer> = deserialize_internal(&legacy_bytes)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+    assert_eq!(deserialized, transfers);
+}
+
+#[test]
+fn transfers_serialization_roundtrip() {
+    let mut transfer = legacy_transfer();
+    transfer.memo = Some(TransferMemo::new(vec![7; 8].into()).unwrap());
+    let transfers = vec![transfer, legacy_transfer()];
+
+    let serialized = serialize_internal(&transfers).expect("serialization");
+    let deserialized: Vec<Transfer> = deserialize_internal(&serialized)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+    assert_eq!(deserialized, transfers);
+}
+
+#[test]
+fn should_read_legacy_deploy_metadata() {
+    let execution_result = ExecutionResult::Success {
+        effect: ExecutionEffect::new(vec![
+            TransformEntry {
+                key: "transfer".to_string(),
+                transform: Transform::WriteTransfer(legacy_transfer()),
+            },
+            TransformEntry {
+                key: "balance".to_string(),
+                transform: Transform::AddUInt512(U512::one()),
+            },
+        ]),
+        transfers: vec![],
+        cost: U512::from(8),
+    };
+    let mut deploy_metadata = DeployMetadata::default();
+    deploy_metadata
+        .execution_results
+        .insert(BlockHash::new([9; 32].into()), execution_result);
+    let legacy_bytes = remove_transfer_memos(bincode::serialize(&deploy_metadata).unwrap());
+
+    let deserialized: DeployMetadata = deserialize_internal(&legacy_bytes)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+    assert_eq!(deserialized, deploy_metadata);
+
+    let serialized = serialize_internal(&deploy_metadata).expect("serialization");
+    let deserialized: DeployMetadata = deserialize_internal(&serialized)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+    assert_eq!(deserialized, deploy_metadata);
+}
+
+#[test]
+fn should_read_legacy_deploy_metadata_with_bids() {
+    let mut rng = TestRng::new();
+    let validator_public_key = PublicKey::random(&mut rng);
+    let delegator_public_key = PublicKey::random(&mut rng);
+    let mut bid = Bid::locked(
+        validator_public_key.clone(),
+        URef::new([1; 32], AccessRights::READ_ADD_WRITE),
+        U512::from(2),
+        3,
+        4,
+    );
+    bid.delegators_mut().insert(
+        delegator_public_key.clone(),
+        Delegator::unlocked(
+            delegator_public_key,
+            U512::from(5),
+            URef::new([6; 32], AccessRights::READ_ADD_WRITE),
+            validator_public_key.clone(),
+        ),
+    );
+    bid.deactivate();
+    let execution_result = ExecutionResult::Success {
+        effect: ExecutionEffect::new(vec![TransformEntry {
+            key: "bid".to_string(),
+            transform: Transform::WriteBid(Box::new(bid)),
+        }]),
+        transfers: vec![],
+        cost: U512::from(7),
+    };
+    let mut deploy_metadata = DeployMetadata::default();
+    deploy_metadata
+        .execution_results
+        .insert(BlockHash::new([8; 32].into()), execution_result);
+
+    // Delegators stored before unclaimed rewards were introduced end with their vesting schedule.
+    let delegator_end =
+        bincode::serialize(&(validator_public_key, Option::<VestingSchedule>::None)).unwrap();
+    let unclaimed_rewards = bincode::serialize(&U512::zero()).unwrap();
+    let legacy_bytes = remove_bytes_following(
+        bincode::serialize(&deploy_metadata).unwrap(),
+        &delegator_end,
+        unclaimed_rewards.len(),
+    );
+
+    let deserialized: DeployMetadata = deserialize_internal(&legacy_bytes)
+        .expect("should deserialize w/o error")
+        .expect("should be Some");
+    assert_eq!(deserialized, deploy_metadata);
+}
+
+#[test]
+fn compression_roundtrip() {
+    let compression = Compression::new(3, 1024);
+
+    let small = vec![7; 1023];
+    let stored = compression
+        .compress(small.clone())
+        .expect("should compress");
+    assert!(!compression::is_compressed(&stored));
+    assert_eq!(stored, small);
+
+    let large = vec![7; 4096];
+    let stored = compression
+        .compress(large.clone())
+        .expect("should compress");
+    assert!(compression::is_compressed(&stored));
+    assert!(stored.len() < large.len());
+    assert_eq!(
+        compression::decompress(&stored).expect("should decompress"),
+        large.as_slice()
+    );
+
+    // Values compressed by a future version of the node must not be misread.
+    let mut unknown_format = stored;
+    unknown_format[8] = compression::FORMAT_VERSION + 1;
+    assert!(compression::decompress(&unknown_format).is_err());
+}
+
+#[test]
+fn should_compress_stored_values_on_startup() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random(&mut harness.rng);
+    let deploy = Deploy::random(&mut harness.rng);
+    assert!(put_deploy(
+        &mut harness,
+        &mut storage,
+        Box::new(deploy.clone())
+    ));
+    assert!(put_block(
+        &mut harness,
+        &mut storage,
+        Box::new(block.clone())
+    ));
+    drop(storage);
+
+    // Reopening with compression enabled migrates the values stored so far.
+    let compressed_config = Config {
+        enable_compression: true,
+        compression_threshold: 0,
+        ..new_config(&harness)
+    };
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), compressed_config),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        "test",
+    )
+    .expect("could not create storage component with compression");
+    assert_eq!(
+        storage
+            .read_state_store(&COMPRESSION_FORMAT_STORAGE_KEY)
+            .expect("should read state store"),
+        Some(vec![compression::FORMAT_VERSION])
+    );
+    assert_eq!(
+        get_naive_deploys(&mut harness, &mut storage, smallvec![*deploy.id()]),
+        vec![Some(deploy.clone())]
+    );
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()),
+        Some(block.clone())
+    );
+    drop(storage);
+
+    // Disabling compression again keeps the compressed values readable.
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        get_naive_deploys(&mut harness, &mut storage, smallvec![*deploy.id()]),
+        vec![Some(deploy)]
+    );
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()),
+        Some(block)
+    );
+}
@@ -0,0 +1,399 @@
+//! Telemetry component.
+//!
+//! When enabled, the telemetry component traces every deploy through its lifecycle on this node,
+//! and periodically exports the spans to an OpenTelemetry collector using OTLP over HTTP with JSON
+//! encoding.
+//!
+//! All the spans of a deploy belong to a single trace whose ID is derived from the deploy hash, so
+//! the spans exported by different nodes for the same deploy are linked together.  The trace
+//! consists of a root `deploy` span, from the time the deploy was first seen until it was executed
+//! or expired, and of one child span per stage reached, each starting with the root span and ending
+//! when the stage was reached.
+//!
+//! Failing to export spans is logged, but doesn't affect the node.
+
+mod config;
+mod otlp;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use hyper::{
+    client::HttpConnector,
+    header::{HeaderValue, CONTENT_TYPE},
+    Body, Client, Method, Request, Uri,
+};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use casper_hashing::Digest;
+use casper_types::Timestamp;
+
+use super::Component;
+use crate::{
+    effect::{EffectBuilder, EffectExt, Effects},
+    types::{DeployHash, NodeRng},
+};
+pub(crate) use config::Config;
+use otlp::{ExportTraceServiceRequest, KeyValue, Span};
+
+/// Name of the root span of a deploy's trace.
+const ROOT_SPAN_NAME: &str = "deploy";
+
+/// A stage of the lifecycle of a deploy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum Stage {
+    /// The deploy was accepted by the deploy acceptor.
+    Accepted,
+    /// This node finished gossiping the deploy.
+    Gossiped,
+    /// A block containing the deploy was proposed.
+    Proposed,
+    /// A block containing the deploy was finalized by consensus.
+    Finalized,
+    /// A block containing the deploy was executed.
+    Executed,
+    /// The deploy expired without being executed.
+    Expired,
+}
+
+impl Stage {
+    /// Returns the name of the span ending when the stage is reached.
+    fn span_name(self) -> &'static str {
+        match self {
+            Stage::Accepted => "deploy.accepted",
+            Stage::Gossiped => "deploy.gossiped",
+            Stage::Proposed => "deploy.proposed",
+            Stage::Finalized => "deploy.finalized",
+            Stage::Executed => "deploy.executed",
+            Stage::Expired => "deploy.expired",
+        }
+    }
+
+    /// Returns `true` if the deploy isn't traced any further once the stage is reached.
+    fn is_final(self) -> bool {
+        matches!(self, Stage::Executed | Stage::Expired)
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Accepted => "accepted",
+            Stage::Gossiped => "gossiped",
+            Stage::Proposed => "proposed",
+            Stage::Finalized => "finalized",
+            Stage::Executed => "executed",
+            Stage::Expired => "expired",
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// A telemetry initialization error.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// The configured endpoint isn't a valid URL.
+    #[error("invalid telemetry endpoint {endpoint}: {source}")]
+    InvalidEndpoint {
+        endpoint: String,
+        source: hyper::http::uri::InvalidUri,
+    },
+}
+
+/// Telemetry event.
+#[derive(Debug, Serialize)]
+pub(crate) enum Event {
+    /// Deploys reached a stage of their lifecycle.
+    StageReached {
+        deploy_hashes: Vec<DeployHash>,
+        stage: Stage,
+        timestamp: Timestamp,
+    },
+    /// The spans collected since the last export should be exported.
+    Export,
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::StageReached {
+                deploy_hashes,
+                stage,
+                ..
+            } => write!(formatter, "{} deploys {}", deploy_hashes.len(), stage),
+            Event::Export => write!(formatter, "export spans"),
+        }
+    }
+}
+
+/// Keeps track of the deploys being traced, and of the spans completed but not exported yet.
+#[derive(Debug, DataSize)]
+struct Tracer {
+    /// The time each deploy being traced was first seen.
+    started: HashMap<DeployHash, Timestamp>,
+    /// Spans waiting to be exported.
+    #[data_size(skip)]
+    queued_spans: Vec<Span>,
+    /// Number of spans dropped since the last export, as the queue was full.
+    dropped_spans: usize,
+    max_traced_deploys: usize,
+    max_queued_spans: usize,
+}
+
+impl Tracer {
+    fn new(max_traced_deploys: usize, max_queued_spans: usize) -> Self {
+        Tracer {
+            started: HashMap::new(),
+            queued_spans: Vec::new(),
+            dropped_spans: 0,
+            max_traced_deploys,
+            max_queued_spans,
+        }
+    }
+
+    /// Records that the deploy reached `stage` at `timestamp`, queueing the completed spans.
+    fn record(&mut self, deploy_hash: DeployHash, stage: Stage, timestamp: Timestamp) {
+        let start = match self.started.get(&deploy_hash) {
+            Some(start) => *start,
+            None if stage.is_final() => {
+                // Not worth starting a trace which would end right away.
+                return;
+            }
+            None if self.started.len() >= self.max_traced_deploys => {
+                debug!(%deploy_hash, "too many deploys traced, not tracing deploy");
+                return;
+            }
+            None => {
+                self.started.insert(deploy_hash, timestamp);
+                timestamp
+            }
+        };
+        // The proposal time is the block's timestamp, which can precede the time this node first
+        // saw the deploy.
+        let start = start.min(timestamp);
+
+        let trace_id = trace_id(&deploy_hash);
+        let root_span_id = root_span_id(&deploy_hash);
+        let span = Span::new(
+            trace_id.clone(),
+            stage_span_id(&deploy_hash, stage),
+            root_span_id.clone(),
+            stage.span_name().to_string(),
+            start,
+            timestamp,
+            vec![KeyValue::new("deploy_hash", hex_deploy_hash(&deploy_hash))],
+        );
+        self.queue(span);
+
+        if stage.is_final() {
+            self.started.remove(&deploy_hash);
+            let root_span = Span::new(
+                trace_id,
+                root_span_id,
+                String::new(),
+                ROOT_SPAN_NAME.to_string(),
+                start,
+                timestamp,
+                vec![
+                    KeyValue::new("deploy_hash", hex_deploy_hash(&deploy_hash)),
+                    KeyValue::new("outcome", stage),
+                ],
+            );
+            self.queue(root_span);
+        }
+    }
+
+    fn queue(&mut self, span: Span) {
+        if self.queued_spans.len() >= self.max_queued_spans {
+            self.dropped_spans += 1;
+            return;
+        }
+        self.queued_spans.push(span);
+    }
+
+    /// Takes all the spans waiting to be exported.
+    fn take_spans(&mut self) -> Vec<Span> {
+        if self.dropped_spans > 0 {
+            warn!(
+                dropped_spans = self.dropped_spans,
+                "telemetry span queue full, dropped spans"
+            );
+            self.dropped_spans = 0;
+        }
+        std::mem::take(&mut self.queued_spans)
+    }
+}
+
+/// Returns the hex encoded deploy hash.
+fn hex_deploy_hash(deploy_hash: &DeployHash) -> String {
+    base16::encode_lower(&deploy_hash.inner().value())
+}
+
+/// Returns the trace ID of a deploy, i.e. the first 16 bytes of its hash.
+fn trace_id(deploy_hash: &DeployHash) -> String {
+    base16::encode_lower(&deploy_hash.inner().value()[..16])
+}
+
+/// Returns the ID of the root span of a deploy's trace, i.e. the next 8 bytes of its hash.
+fn root_span_id(deploy_hash: &DeployHash) -> String {
+    base16::encode_lower(&deploy_hash.inner().value()[16..24])
+}
+
+/// Returns the ID of the span ending when a deploy reached `stage`.
+fn stage_span_id(deploy_hash: &DeployHash, stage: Stage) -> String {
+    let mut data = deploy_hash.inner().value().to_vec();
+    data.extend_from_slice(stage.span_name().as_bytes());
+    base16::encode_lower(&Digest::hash(data).value()[..8])
+}
+
+/// Telemetry component.
+#[derive(Debug, DataSize)]
+pub(crate) struct Telemetry {
+    config: Config,
+    tracer: Tracer,
+    /// The parsed endpoint, `None` if telemetry is disabled.
+    #[data_size(skip)]
+    endpoint: Option<Uri>,
+    #[data_size(skip)]
+    client: Client<HttpConnector>,
+}
+
+impl Telemetry {
+    /// Creates a new telemetry component, scheduling the first export if enabled.
+    pub(crate) fn new<REv>(
+        config: Config,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Result<(Self, Effects<Event>), Error>
+    where
+        REv: Send,
+    {
+        let tracer = Tracer::new(config.max_traced_deploys, config.max_queued_spans);
+        let mut effects = Effects::new();
+        let endpoint = if config.enabled {
+            let endpoint = config
+                .endpoint
+                .parse()
+                .map_err(|source| Error::InvalidEndpoint {
+                    endpoint: config.endpoint.clone(),
+                    source,
+                })?;
+            effects.extend(schedule_export(
+                effect_builder,
+                config.export_interval.into(),
+            ));
+            debug!(endpoint = %config.endpoint, "telemetry enabled");
+            Some(endpoint)
+        } else {
+            debug!("telemetry disabled");
+            None
+        };
+        let telemetry = Telemetry {
+            config,
+            tracer,
+            endpoint,
+            client: Client::new(),
+        };
+        Ok((telemetry, effects))
+    }
+
+    /// Returns an effect exporting all the queued spans, if any.
+    fn export(&mut self) -> Effects<Event> {
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => return Effects::new(),
+        };
+        let spans = self.tracer.take_spans();
+        if spans.is_empty() {
+            return Effects::new();
+        }
+        let span_count = spans.len();
+        let body = match serde_json::to_vec(&ExportTraceServiceRequest::new(
+            &self.config.service_name,
+            spans,
+        )) {
+            Ok(body) => body,
+            Err(error) => {
+                warn!(%error, "could not serialize spans");
+                return Effects::new();
+            }
+        };
+        let mut request = Request::new(Body::from(body));
+        *request.method_mut() = Method::POST;
+        *request.uri_mut() = endpoint;
+        request
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let client = self.client.clone();
+        let timeout = self.config.export_timeout.into();
+        async move {
+            match tokio::time::timeout(timeout, client.request(request)).await {
+                Ok(Ok(response)) if response.status().is_success() => {
+                    debug!(span_count, "exported spans");
+                }
+                Ok(Ok(response)) => {
+                    warn!(status = %response.status(), span_count, "collector rejected spans");
+                }
+                Ok(Err(error)) => warn!(%error, span_count, "could not export spans"),
+                Err(_) => warn!(span_count, "timed out exporting spans"),
+            }
+        }
+        .ignore()
+    }
+}
+
+fn schedule_export<REv: Send>(
+    effect_builder: EffectBuilder<REv>,
+    interval: Duration,
+) -> Effects<Event> {
+    effect_builder
+        .set_timeout(interval)
+        .event(|_| Event::Export)
+}
+
+impl<REv> Component<REv> for Telemetry
+where
+    REv: Send,
+{
+    type Event = Event;
+
+    type ConstructionError = Error;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Event,
+    ) -> Effects<Event> {
+        match event {
+            Event::StageReached {
+                deploy_hashes,
+                stage,
+                timestamp,
+            } => {
+                if self.config.enabled {
+                    for deploy_hash in deploy_hashes {
+                        self.tracer.record(deploy_hash, stage, timestamp);
+                    }
+                }
+                Effects::new()
+            }
+            Event::Export => {
+                let mut effects = self.export();
+                effects.extend(schedule_export(
+                    effect_builder,
+                    self.config.export_interval.into(),
+                ));
+                effects
+            }
+        }
+    }
+}
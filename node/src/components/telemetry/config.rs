@@ -0,0 +1,59 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+/// Default OTLP/HTTP endpoint traces are exported to.
+const DEFAULT_ENDPOINT: &str = "http://127.0.0.1:4318/v1/traces";
+
+/// Default name of the service the spans are reported for.
+const DEFAULT_SERVICE_NAME: &str = "casper-node";
+
+/// Default interval between two exports.
+const DEFAULT_EXPORT_INTERVAL: &str = "5s";
+
+/// Default timeout of a single export.
+const DEFAULT_EXPORT_TIMEOUT: &str = "10s";
+
+/// Default maximum number of deploys traced at the same time.
+const DEFAULT_MAX_TRACED_DEPLOYS: usize = 10_000;
+
+/// Default maximum number of spans waiting to be exported.
+const DEFAULT_MAX_QUEUED_SPANS: usize = 20_000;
+
+/// Telemetry configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Whether or not spans of the deploy lifecycle are exported.
+    pub(crate) enabled: bool,
+    /// URL of the OTLP/HTTP traces endpoint of a collector.
+    pub(crate) endpoint: String,
+    /// Name of the service the spans are reported for.
+    pub(crate) service_name: String,
+    /// Interval between two exports of the spans collected in the meantime.
+    pub(crate) export_interval: TimeDiff,
+    /// Timeout of a single export.
+    pub(crate) export_timeout: TimeDiff,
+    /// Maximum number of deploys traced at the same time.  Deploys seen once the limit is reached
+    /// aren't traced.
+    pub(crate) max_traced_deploys: usize,
+    /// Maximum number of spans waiting to be exported.  Spans completed once the limit is reached
+    /// are dropped.
+    pub(crate) max_queued_spans: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enabled: false,
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            export_interval: DEFAULT_EXPORT_INTERVAL.parse().unwrap(),
+            export_timeout: DEFAULT_EXPORT_TIMEOUT.parse().unwrap(),
+            max_traced_deploys: DEFAULT_MAX_TRACED_DEPLOYS,
+            max_queued_spans: DEFAULT_MAX_QUEUED_SPANS,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! The subset of the OTLP/JSON trace export request used by the telemetry component.
+//!
+//! See the `opentelemetry-proto` definitions of `ExportTraceServiceRequest` for the full format.
+
+use serde::Serialize;
+
+use casper_types::Timestamp;
+
+/// Name of the instrumentation scope reporting the spans.
+const SCOPE_NAME: &str = "casper-node/deploy-lifecycle";
+
+/// `SPAN_KIND_INTERNAL`: the span represents an internal operation of the node.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// A trace export request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ExportTraceServiceRequest {
+    resource_spans: Vec<ResourceSpans>,
+}
+
+impl ExportTraceServiceRequest {
+    /// Creates a request exporting `spans` on behalf of `service_name`.
+    pub(super) fn new(service_name: &str, spans: Vec<Span>) -> Self {
+        ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Resource {
+                    attributes: vec![KeyValue::new("service.name", service_name)],
+                },
+                scope_spans: vec![ScopeSpans {
+                    scope: InstrumentationScope {
+                        name: SCOPE_NAME.to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                    },
+                    spans,
+                }],
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Resource,
+    scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeSpans {
+    scope: InstrumentationScope,
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Serialize)]
+struct InstrumentationScope {
+    name: String,
+    version: String,
+}
+
+/// A completed span.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Span {
+    /// Hex encoded 16 byte trace ID.
+    pub(super) trace_id: String,
+    /// Hex encoded 8 byte span ID.
+    pub(super) span_id: String,
+    /// Hex encoded 8 byte span ID of the parent span, empty for a root span.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub(super) parent_span_id: String,
+    pub(super) name: String,
+    kind: u8,
+    /// Start time in nanoseconds since the Unix epoch, as a string since it exceeds 2^53.
+    start_time_unix_nano: String,
+    /// End time in nanoseconds since the Unix epoch.
+    end_time_unix_nano: String,
+    pub(super) attributes: Vec<KeyValue>,
+}
+
+impl Span {
+    /// Creates a new span.
+    pub(super) fn new(
+        trace_id: String,
+        span_id: String,
+        parent_span_id: String,
+        name: String,
+        start: Timestamp,
+        end: Timestamp,
+        attributes: Vec<KeyValue>,
+    ) -> Self {
+        Span {
+            trace_id,
+            span_id,
+            parent_span_id,
+            name,
+            kind: SPAN_KIND_INTERNAL,
+            start_time_unix_nano: unix_nanos(start),
+            end_time_unix_nano: unix_nanos(end),
+            attributes,
+        }
+    }
+}
+
+/// A string-valued attribute.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(super) struct KeyValue {
+    key: String,
+    value: AnyValue,
+}
+
+impl KeyValue {
+    pub(super) fn new(key: &str, value: impl ToString) -> Self {
+        KeyValue {
+            key: key.to_string(),
+            value: AnyValue {
+                string_value: value.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnyValue {
+    string_value: String,
+}
+
+fn unix_nanos(timestamp: Timestamp) -> String {
+    (u128::from(timestamp.millis()) * 1_000_000).to_string()
+}
@@ -0,0 +1,102 @@
+use super::*;
+
+fn span_names(spans: &[Span]) -> Vec<&str> {
+    spans.iter().map(|span| span.name.as_str()).collect()
+}
+
+#[test]
+fn should_trace_deploy_until_executed() {
+    let mut rng = crate::new_rng();
+    let deploy_hash = DeployHash::random(&mut rng);
+    let mut tracer = Tracer::new(10, 10);
+
+    tracer.record(deploy_hash, Stage::Accepted, Timestamp::from(1_000));
+    tracer.record(deploy_hash, Stage::Gossiped, Timestamp::from(1_100));
+    // The block was proposed before the deploy was gossiped to this node.
+    tracer.record(deploy_hash, Stage::Proposed, Timestamp::from(900));
+    tracer.record(deploy_hash, Stage::Finalized, Timestamp::from(1_200));
+    tracer.record(deploy_hash, Stage::Executed, Timestamp::from(1_300));
+
+    let spans = tracer.take_spans();
+    assert_eq!(
+        span_names(&spans),
+        vec![
+            "deploy.accepted",
+            "deploy.gossiped",
+            "deploy.proposed",
+            "deploy.finalized",
+            "deploy.executed",
+            ROOT_SPAN_NAME,
+        ]
+    );
+
+    let root_span = spans.last().unwrap();
+    assert!(root_span.parent_span_id.is_empty());
+    assert_eq!(root_span.span_id, root_span_id(&deploy_hash));
+    for span in &spans[..spans.len() - 1] {
+        assert_eq!(span.trace_id, trace_id(&deploy_hash));
+        assert_eq!(span.parent_span_id, root_span.span_id);
+        assert_ne!(span.span_id, root_span.span_id);
+    }
+
+    // The deploy is no longer traced, and the queue is empty.
+    assert!(tracer.started.is_empty());
+    assert!(tracer.take_spans().is_empty());
+}
+
+#[test]
+fn should_serialize_spans_as_otlp_json() {
+    let mut rng = crate::new_rng();
+    let deploy_hash = DeployHash::random(&mut rng);
+    let mut tracer = Tracer::new(10, 10);
+
+    tracer.record(deploy_hash, Stage::Accepted, Timestamp::from(1_000));
+    tracer.record(deploy_hash, Stage::Expired, Timestamp::from(2_000));
+
+    let request = ExportTraceServiceRequest::new("test-node", tracer.take_spans());
+    let json = serde_json::to_value(&request).unwrap();
+
+    let resource_spans = &json["resourceSpans"][0];
+    assert_eq!(
+        resource_spans["resource"]["attributes"][0]["value"]["stringValue"],
+        "test-node"
+    );
+    let spans = resource_spans["scopeSpans"][0]["spans"].as_array().unwrap();
+    assert_eq!(spans.len(), 3);
+    let root_span = &spans[2];
+    assert_eq!(root_span["traceId"], trace_id(&deploy_hash));
+    assert_eq!(root_span["startTimeUnixNano"], "1000000000");
+    assert_eq!(root_span["endTimeUnixNano"], "2000000000");
+    assert!(root_span.get("parentSpanId").is_none());
+    assert_eq!(
+        root_span["attributes"][1]["value"]["stringValue"],
+        "expired"
+    );
+}
+
+#[test]
+fn should_limit_traced_deploys_and_queued_spans() {
+    let mut rng = crate::new_rng();
+    let mut tracer = Tracer::new(2, 2);
+
+    let deploy_hashes: Vec<_> = (0..3).map(|_| DeployHash::random(&mut rng)).collect();
+    for deploy_hash in &deploy_hashes {
+        tracer.record(*deploy_hash, Stage::Accepted, Timestamp::from(1_000));
+    }
+    assert_eq!(tracer.started.len(), 2);
+    assert!(!tracer.started.contains_key(&deploy_hashes[2]));
+
+    // A deploy which wasn't traced doesn't start a trace once executed.
+    tracer.record(deploy_hashes[2], Stage::Executed, Timestamp::from(2_000));
+    assert_eq!(tracer.take_spans().len(), 2);
+
+    // Executing a traced deploy completes two spans, but the root span doesn't fit in the queue.
+    tracer.record(deploy_hashes[0], Stage::Gossiped, Timestamp::from(1_500));
+    tracer.record(deploy_hashes[0], Stage::Executed, Timestamp::from(2_000));
+    assert_eq!(tracer.dropped_spans, 1);
+    assert_eq!(
+        span_names(&tracer.take_spans()),
+        vec!["deploy.gossiped", "deploy.executed"]
+    );
+    assert_eq!(tracer.dropped_spans, 0);
+}
@@ -98,12 +98,12 @@ pub(crate) mod requests;
 use std::{
     any::type_name,
     borrow::Cow,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
     mem,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use datasize::DataSize;
@@ -118,9 +118,9 @@ use casper_execution_engine::{
     core::engine_state::{
         self, era_validators::GetEraValidatorsError, genesis::GenesisSuccess, BalanceRequest,
         BalanceResult, GetBidsRequest, GetBidsResult, QueryRequest, QueryResult, UpgradeConfig,
-        UpgradeSuccess,
+        UpgradeSuccess, ViewRequest, ViewResult,
     },
-    shared::execution_journal::ExecutionJournal,
+    shared::{execution_journal::ExecutionJournal, wasm_config::WasmConfig},
     storage::trie::{TrieOrChunk, TrieOrChunkId},
 };
 use casper_hashing::Digest;
@@ -132,25 +132,27 @@ use casper_types::{
 
 use crate::{
     components::{
+        block_proposer::PendingDeployStatus,
         block_validator::ValidatingBlock,
         chainspec_loader::NextUpgrade,
-        consensus::{BlockContext, ClContext, EraDump, ValidatorChange},
+        consensus::{BlockContext, ClContext, EraDump, LeaderSchedule, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
         },
         deploy_acceptor,
         fetcher::FetchResult,
-        small_network::FromIncoming,
+        small_network::{DiversityStats, FromIncoming, NodeAttestation},
     },
     contract_runtime::SpeculativeExecutionState,
     effect::announcements::ChainSynchronizerAnnouncement,
     reactor::{EventQueueHandle, QueueKind},
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
+        Approval, AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
         BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockPayload,
         BlockSignatures, BlockWithMetadata, Chainspec, ChainspecInfo, ChainspecRawBytes, Deploy,
         DeployHash, DeployHeader, DeployMetadataExt, DeployWithFinalizedApprovals,
-        FinalitySignature, FinalizedApprovals, FinalizedBlock, Item, NodeId, NodeState,
+        FinalitySignature, FinalizedApprovals, FinalizedBlock, HealthReport, Item, NodeId,
+        NodeState,
     },
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
 };
@@ -164,8 +166,8 @@ use diagnostics_port::DumpConsensusStateRequest;
 use requests::{
     BeginGossipRequest, BlockPayloadRequest, BlockProposerRequest, BlockValidationRequest,
     ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, FetcherRequest,
-    MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
-    NodeStateRequest, StateStoreRequest, StorageRequest,
+    HealthRequest, LinearChainRequest, MarkBlockCompletedRequest, MetricsRequest,
+    NetworkInfoRequest, NetworkRequest, NodeStateRequest, StateStoreRequest, StorageRequest,
 };
 
 /// A resource that will never be available, thus trying to acquire it will wait forever.
@@ -636,10 +638,13 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Sets a timeout.
+    ///
+    /// Uses tokio's clock, which can be paused and advanced manually in tests, see
+    /// `utils::clock`.
     pub(crate) async fn set_timeout(self, timeout: Duration) -> Duration {
-        let then = Instant::now();
+        let then = time::Instant::now();
         time::sleep(timeout).await;
-        Instant::now() - then
+        time::Instant::now() - then
     }
 
     /// Retrieve a snapshot of the nodes current metrics formatted as string.
@@ -778,6 +783,30 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets statistics on the network groups of outgoing connections.
+    pub(crate) async fn get_network_diversity_stats(self) -> DiversityStats
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::DiversityStats { responder },
+            QueueKind::Control,
+        )
+        .await
+    }
+
+    /// Signs `data` with the node's network identity and, if it is a validator, its validator key.
+    pub(crate) async fn attest_with_node_identity(self, data: Vec<u8>) -> Option<NodeAttestation>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::Attest { data, responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Announces which deploys have expired.
     pub(crate) async fn announce_expired_deploys(self, hashes: Vec<DeployHash>)
     where
@@ -857,6 +886,43 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces that the HTTP API server has received a deploy which may lack approvals.
+    pub(crate) async fn announce_partial_deploy_received(
+        self,
+        deploy: Box<Deploy>,
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
+    ) where
+        REv: From<RpcServerAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                RpcServerAnnouncement::PartialDeployReceived { deploy, responder },
+                QueueKind::Api,
+            )
+            .await;
+    }
+
+    /// Announces that the HTTP API server has received approvals for a partial deploy.
+    pub(crate) async fn announce_deploy_approvals_received(
+        self,
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
+    ) where
+        REv: From<RpcServerAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                RpcServerAnnouncement::DeployApprovalsReceived {
+                    deploy_hash,
+                    approvals,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+    }
+
     /// Announces that a deploy not previously stored has now been accepted and stored.
     pub(crate) fn announce_new_deploy_accepted(
         self,
@@ -1186,6 +1252,35 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the height below which the execution results and transfers of blocks have been
+    /// pruned from storage.
+    pub(crate) async fn get_execution_results_pruned_below_from_storage(self) -> u64
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetExecutionResultsPrunedBelow { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether the given deploy has already been executed and is still remembered as such
+    /// by the replay index in storage.
+    pub(crate) async fn is_replayed_deploy(self, deploy_hash: DeployHash) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsReplayedDeploy {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the highest block.
     pub(crate) async fn get_highest_block_from_storage(self) -> Option<Block>
     where
@@ -1342,18 +1437,19 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Stores the given execution results for the deploys in the given block in the linear block
-    /// store.
-    pub(crate) async fn put_execution_results_to_storage(
+    /// Puts the given block and the execution results of its deploys into the linear block store
+    /// atomically.
+    pub(crate) async fn put_executed_block_to_storage(
         self,
-        block_hash: BlockHash,
+        block: Box<Block>,
         execution_results: HashMap<DeployHash, ExecutionResult>,
-    ) where
+    ) -> bool
+    where
         REv: From<StorageRequest>,
     {
         self.make_request(
-            |responder| StorageRequest::PutExecutionResults {
-                block_hash: Box::new(block_hash),
+            |responder| StorageRequest::PutExecutedBlock {
+                block,
                 execution_results,
                 responder,
             },
@@ -1747,6 +1843,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Runs the upgrade process on the contract runtime without committing its effects.
+    pub(crate) async fn dry_run_upgrade(
+        self,
+        upgrade_config: Box<UpgradeConfig>,
+    ) -> Result<UpgradeSuccess, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::DryRunUpgrade {
+                upgrade_config,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested chainspec info from the chainspec loader.
     pub(crate) async fn get_chainspec_info(self) -> ChainspecInfo
     where
@@ -1763,6 +1877,14 @@ impl<REv> EffectBuilder<REv> {
         self.make_request(NodeStateRequest, QueueKind::Api).await
     }
 
+    /// Retrieves the health of the node's major components.
+    pub(crate) async fn get_health_report(self) -> HealthReport
+    where
+        REv: From<HealthRequest> + Send,
+    {
+        self.make_request(HealthRequest, QueueKind::Api).await
+    }
+
     /// Retrieves finalized blocks with timestamps no older than the maximum deploy TTL.
     ///
     /// These blocks contain all deploy and transfer hashes that are known to be finalized but
@@ -1854,6 +1976,39 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a view entry point be called by the Contract Runtime component.
+    pub(crate) async fn call_view(
+        self,
+        view_request: ViewRequest,
+    ) -> Result<ViewResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::CallView {
+                view_request: Box::new(view_request),
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Returns the Wasm config applying to the given protocol version.
+    pub(crate) async fn get_wasm_config(self, protocol_version: ProtocolVersion) -> WasmConfig
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetWasmConfig {
+                protocol_version,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Retrieves an `Account` from global state if present.
     pub(crate) async fn get_account_from_global_state(
         self,
@@ -2067,6 +2222,55 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns the era of the persisted equivocation evidence, by faulty validator.
+    pub(crate) async fn get_consensus_evidence(self) -> BTreeMap<PublicKey, EraId>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::Evidence, QueueKind::Regular)
+            .await
+    }
+
+    /// Returns the leader schedule of the current era, if consensus is running.
+    pub(crate) async fn get_consensus_leader_schedule(
+        self,
+    ) -> Option<(EraId, LeaderSchedule<ClContext>)>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::LeaderSchedule, QueueKind::Regular)
+            .await
+    }
+
+    /// Returns the status of all deploys in the block proposer's buffer.
+    pub(crate) async fn get_pending_deploys(self) -> Vec<PendingDeployStatus>
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(BlockProposerRequest::GetPendingDeploys, QueueKind::Regular)
+            .await
+    }
+
+    /// Broadcasts the known finality signatures of a block to all peers again.
+    ///
+    /// Returns the number of signatures broadcast, or `None` if the request was rate limited.
+    pub(crate) async fn rebroadcast_finality_signatures(
+        self,
+        block_hash: BlockHash,
+    ) -> Option<usize>
+    where
+        REv: From<LinearChainRequest>,
+    {
+        self.make_request(
+            |responder| LinearChainRequest::RebroadcastFinalitySignatures {
+                block_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Dump consensus state for a specific era, using the supplied function to serialize the
     /// output.
     pub(crate) async fn diagnostics_port_dump_consensus_state(
@@ -2103,6 +2307,16 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the reactor to enter drain mode.
+    pub(crate) async fn diagnostics_port_request_drain(self)
+    where
+        REv: From<ControlAnnouncement>,
+    {
+        self.event_queue
+            .schedule(ControlAnnouncement::DrainRequested, QueueKind::Control)
+            .await
+    }
+
     /// Get the bytes for the chainspec file and genesis_accounts
     /// and global_state bytes if the files are present.
     pub(crate) async fn get_chainspec_raw_bytes(self) -> Arc<ChainspecRawBytes>
@@ -2116,6 +2330,15 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the chainspec the node is running with, as parsed from the chainspec files.
+    pub(crate) async fn get_chainspec(self) -> Arc<Chainspec>
+    where
+        REv: From<ChainspecLoaderRequest> + Send,
+    {
+        self.make_request(ChainspecLoaderRequest::GetChainspec, QueueKind::Regular)
+            .await
+    }
+
     /// Stores a set of given finalized approvals in storage.
     ///
     /// Any previously stored finalized approvals for the given hash are quietly overwritten
@@ -4,7 +4,7 @@
 //! module documentation for details.
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug, Display, Formatter},
     fs::File,
 };
@@ -20,7 +20,8 @@ use crate::{
     },
     effect::Responder,
     types::{
-        Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item, NodeId,
+        Approval, Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item,
+        NodeId,
     },
     utils::Source,
 };
@@ -55,6 +56,9 @@ pub(crate) enum ControlAnnouncement {
         /// Responder called when the dump has been finished.
         finished: Responder<()>,
     },
+    /// The operator requested the node to drain: stop taking on new work, finish everything in
+    /// flight and exit.
+    DrainRequested,
 }
 
 /// Queue dump format with handler.
@@ -88,6 +92,7 @@ impl Debug for ControlAnnouncement {
                 .field("msg", msg)
                 .finish(),
             Self::QueueDumpRequest { .. } => f.debug_struct("QueueDump").finish_non_exhaustive(),
+            Self::DrainRequested => f.write_str("DrainRequested"),
         }
     }
 }
@@ -101,6 +106,9 @@ impl Display for ControlAnnouncement {
             ControlAnnouncement::QueueDumpRequest { .. } => {
                 write!(f, "dump event queue")
             }
+            ControlAnnouncement::DrainRequested => {
+                write!(f, "drain requested")
+            }
         }
     }
 }
@@ -108,6 +116,7 @@ impl Display for ControlAnnouncement {
 /// An RPC API server announcement.
 #[derive(Debug, Serialize)]
 #[must_use]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum RpcServerAnnouncement {
     /// A new deploy received.
     DeployReceived {
@@ -116,6 +125,22 @@ pub(crate) enum RpcServerAnnouncement {
         /// A client responder in the case where a client submits a deploy.
         responder: Option<Responder<Result<(), Error>>>,
     },
+    /// A new deploy received, which may still lack approvals required to authorize it.
+    PartialDeployReceived {
+        /// The received deploy.
+        deploy: Box<Deploy>,
+        /// The client responder.
+        responder: Responder<Result<(), Error>>,
+    },
+    /// Additional approvals received for a deploy previously received as partial.
+    DeployApprovalsReceived {
+        /// The hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// The received approvals.
+        approvals: BTreeSet<Approval>,
+        /// The client responder.
+        responder: Responder<Result<(), Error>>,
+    },
 }
 
 impl Display for RpcServerAnnouncement {
@@ -124,6 +149,19 @@ impl Display for RpcServerAnnouncement {
             RpcServerAnnouncement::DeployReceived { deploy, .. } => {
                 write!(formatter, "api server received {}", deploy.id())
             }
+            RpcServerAnnouncement::PartialDeployReceived { deploy, .. } => {
+                write!(formatter, "api server received partial {}", deploy.id())
+            }
+            RpcServerAnnouncement::DeployApprovalsReceived {
+                deploy_hash,
+                approvals,
+                ..
+            } => write!(
+                formatter,
+                "api server received {} approvals for {}",
+                approvals.len(),
+                deploy_hash
+            ),
         }
     }
 }
@@ -105,6 +105,8 @@ pub(crate) enum NetRequest {
     BlockHeadersBatch(Vec<u8>),
     /// Request for finality signatures for a block.
     FinalitySignatures(Vec<u8>),
+    /// Request for a block body or a chunk of it.
+    BlockBodyOrChunk(Vec<u8>),
 }
 
 impl Display for NetRequest {
@@ -124,6 +126,7 @@ impl Display for NetRequest {
             NetRequest::BlockAndDeploys(_) => f.write_str("request for a block and its deploys"),
             NetRequest::BlockHeadersBatch(_) => f.write_str("request for block headers batch"),
             NetRequest::FinalitySignatures(_) => f.write_str("request for finality signatures"),
+            NetRequest::BlockBodyOrChunk(_) => f.write_str("request for block body or chunk"),
         }
     }
 }
@@ -146,6 +149,7 @@ impl NetRequest {
             NetRequest::BlockAndDeploys(ref id) => id,
             NetRequest::BlockHeadersBatch(ref id) => id,
             NetRequest::FinalitySignatures(ref id) => id,
+            NetRequest::BlockBodyOrChunk(ref id) => id,
         };
         let mut unique_id = Vec::with_capacity(id.len() + 1);
         unique_id.push(self.tag() as u8);
@@ -169,6 +173,7 @@ impl NetRequest {
             NetRequest::BlockAndDeploys(_) => Tag::BlockAndDeploysByHash,
             NetRequest::BlockHeadersBatch(_) => Tag::BlockHeaderBatch,
             NetRequest::FinalitySignatures(_) => Tag::FinalitySignaturesByHash,
+            NetRequest::BlockBodyOrChunk(_) => Tag::BlockBodyOrChunk,
         }
     }
 }
@@ -208,6 +213,8 @@ pub(crate) enum NetResponse {
     BlockHeadersBatch(Arc<[u8]>),
     /// Response of finality signatures.
     FinalitySignatures(Arc<[u8]>),
+    /// Response of a block body or a chunk of it.
+    BlockBodyOrChunk(Arc<[u8]>),
 }
 
 // `NetResponse` uses `Arcs`, so we count all data as 0.
@@ -238,6 +245,7 @@ impl Display for NetResponse {
             NetResponse::BlockAndDeploys(_) => f.write_str("response, block and deploys"),
             NetResponse::BlockHeadersBatch(_) => f.write_str("response for block-headers-batch"),
             NetResponse::FinalitySignatures(_) => f.write_str("response for finality signatures"),
+            NetResponse::BlockBodyOrChunk(_) => f.write_str("response, block body or chunk"),
         }
     }
 }
@@ -5,7 +5,7 @@
 
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
     sync::Arc,
@@ -24,8 +24,9 @@ use casper_execution_engine::{
         genesis::GenesisSuccess,
         get_bids::{GetBidsRequest, GetBidsResult},
         query::{QueryRequest, QueryResult},
-        UpgradeConfig, UpgradeSuccess,
+        UpgradeConfig, UpgradeSuccess, ViewRequest, ViewResult,
     },
+    shared::wasm_config::WasmConfig,
     storage::trie::{TrieOrChunk, TrieOrChunkId},
 };
 use casper_hashing::Digest;
@@ -36,23 +37,25 @@ use casper_types::{
 
 use crate::{
     components::{
+        block_proposer::PendingDeployStatus,
         block_validator::ValidatingBlock,
-        consensus::{BlockContext, ClContext, ValidatorChange},
+        consensus::{BlockContext, ClContext, LeaderSchedule, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
         },
         deploy_acceptor::Error,
         fetcher::FetchResult,
+        small_network::{DiversityStats, NodeAttestation},
     },
     contract_runtime::SpeculativeExecutionState,
     effect::{AutoClosingResponder, Responder},
     rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
+        Approval, AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
         BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockPayload,
         BlockSignatures, BlockWithMetadata, Chainspec, ChainspecInfo, ChainspecRawBytes, Deploy,
         DeployHash, DeployMetadataExt, DeployWithFinalizedApprovals, FinalizedApprovals,
-        FinalizedBlock, Item, NodeId, NodeState, StatusFeed,
+        FinalizedBlock, HealthReport, Item, NodeId, NodeState, StatusFeed,
     },
     utils::{DisplayIter, Source},
 };
@@ -205,6 +208,18 @@ pub(crate) enum NetworkInfoRequest {
         /// Responder to be called with all connected non-syncing peers in random order.
         responder: Responder<Vec<NodeId>>,
     },
+    /// Get statistics on the network groups of outgoing connections.
+    DiversityStats {
+        /// Responder to be called with the peer diversity statistics.
+        responder: Responder<DiversityStats>,
+    },
+    /// Sign the given data with the node's identity.
+    Attest {
+        /// The data to sign.
+        data: Vec<u8>,
+        /// Responder to be called with the attestation, or `None` if signing failed.
+        responder: Responder<Option<NodeAttestation>>,
+    },
 }
 
 impl Display for NetworkInfoRequest {
@@ -219,6 +234,12 @@ impl Display for NetworkInfoRequest {
             NetworkInfoRequest::FullyConnectedNonSyncingPeers { responder: _ } => {
                 write!(formatter, "get fully connected non-syncing peers")
             }
+            NetworkInfoRequest::DiversityStats { responder: _ } => {
+                write!(formatter, "get peer diversity statistics")
+            }
+            NetworkInfoRequest::Attest { data, responder: _ } => {
+                write!(formatter, "attest {} bytes of data", data.len())
+            }
         }
     }
 }
@@ -311,6 +332,19 @@ pub(crate) enum StorageRequest {
         /// Responder.
         responder: Responder<Option<BlockHeader>>,
     },
+    /// Retrieve the height below which the execution results and transfers of blocks have been
+    /// pruned.
+    GetExecutionResultsPrunedBelow {
+        /// Responder.
+        responder: Responder<u64>,
+    },
+    /// Check whether the deploy has been executed and could not be included in a block again.
+    IsReplayedDeploy {
+        /// Hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Responder, responded to with `true` if the deploy is a replay.
+        responder: Responder<bool>,
+    },
     /// Retrieve block header with given hash.
     GetBlockHeader {
         /// Hash of block to get header of.
@@ -370,20 +404,21 @@ pub(crate) enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Vec<Block>>,
     },
-    /// Store execution results for a set of deploys of a single block.
+    /// Store given block together with the execution results of its deploys.
     ///
-    /// Will return a fatal error if there are already execution results known for a specific
-    /// deploy/block combination and a different result is inserted.
+    /// The block, the updated deploy metadata, the block's transfers and the index updates are
+    /// written in a single transaction, so either all of them are persisted or none are.
     ///
     /// Inserting the same block/deploy combination multiple times with the same execution results
     /// is not an error and will silently be ignored.
-    PutExecutionResults {
-        /// Hash of block.
-        block_hash: Box<BlockHash>,
+    PutExecutedBlock {
+        /// Block to be stored.
+        block: Box<Block>,
         /// Mapping of deploys to execution results of the block.
         execution_results: HashMap<DeployHash, ExecutionResult>,
-        /// Responder to call when done storing.
-        responder: Responder<()>,
+        /// Responder to call with the result.  Returns true if the block and execution results
+        /// were stored on this attempt or false if the block was previously stored.
+        responder: Responder<bool>,
     },
     /// Retrieve deploy and its metadata.
     GetDeployAndMetadata {
@@ -518,6 +553,9 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockHeaderForDeploy { deploy_hash, .. } => {
                 write!(formatter, "get block header for deploy {}", deploy_hash)
             }
+            StorageRequest::IsReplayedDeploy { deploy_hash, .. } => {
+                write!(formatter, "is replayed deploy {}", deploy_hash)
+            }
             StorageRequest::GetBlockHeader { block_hash, .. } => {
                 write!(formatter, "get {}", block_hash)
             }
@@ -530,12 +568,15 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers for {}", block_hash)
             }
+            StorageRequest::GetExecutionResultsPrunedBelow { .. } => {
+                write!(formatter, "get execution results pruning height")
+            }
             StorageRequest::PutDeploy { deploy, .. } => write!(formatter, "put {}", deploy),
             StorageRequest::GetDeploys { deploy_hashes, .. } => {
                 write!(formatter, "get {}", DisplayIter::new(deploy_hashes.iter()))
             }
-            StorageRequest::PutExecutionResults { block_hash, .. } => {
-                write!(formatter, "put execution results for {}", block_hash)
+            StorageRequest::PutExecutedBlock { block, .. } => {
+                write!(formatter, "put executed {}", block)
             }
             StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
                 write!(formatter, "get deploy and metadata for {}", deploy_hash)
@@ -695,6 +736,8 @@ pub(crate) struct BlockPayloadRequest {
 pub(crate) enum BlockProposerRequest {
     /// Request a list of deploys to propose in a new block.
     RequestBlockPayload(BlockPayloadRequest),
+    /// Request the status of all deploys in the buffer.
+    GetPendingDeploys(Responder<Vec<PendingDeployStatus>>),
 }
 
 impl Display for BlockProposerRequest {
@@ -713,6 +756,7 @@ impl Display for BlockProposerRequest {
                 context.height(),
                 next_finalized
             ),
+            BlockProposerRequest::GetPendingDeploys(_) => write!(formatter, "get pending deploys"),
         }
     }
 }
@@ -731,6 +775,22 @@ pub(crate) enum RpcRequest {
         /// Responder to call.
         responder: Responder<Result<(), Error>>,
     },
+    /// Submit a deploy which may not yet carry enough approvals, to be held until it does.
+    SubmitPartialDeploy {
+        /// The partially approved deploy.
+        deploy: Box<Deploy>,
+        /// Responder to call.
+        responder: Responder<Result<(), Error>>,
+    },
+    /// Add approvals to a deploy previously submitted via `SubmitPartialDeploy`.
+    AddDeployApprovals {
+        /// The hash of the pending deploy.
+        deploy_hash: DeployHash,
+        /// The approvals to add.
+        approvals: BTreeSet<Approval>,
+        /// Responder to call.
+        responder: Responder<Result<(), Error>>,
+    },
     /// If `maybe_identifier` is `Some`, return the specified block if it exists, else `None`.  If
     /// `maybe_identifier` is `None`, return the latest block.
     GetBlock {
@@ -820,12 +880,39 @@ pub(crate) enum RpcRequest {
         /// Responder.
         responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
     },
+    /// Calls a view entry point, returning the value it returns.
+    CallView {
+        /// The view to call, and the state on top of which to call it.
+        view_request: Box<ViewRequest>,
+        /// Responder to call with the result.
+        responder: Responder<Result<ViewResult, engine_state::Error>>,
+    },
+    /// Returns the Wasm config applying to the given protocol version.
+    GetWasmConfig {
+        /// The protocol version.
+        protocol_version: ProtocolVersion,
+        /// Responder to call with the result.
+        responder: Responder<WasmConfig>,
+    },
 }
 
 impl Display for RpcRequest {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             RpcRequest::SubmitDeploy { deploy, .. } => write!(formatter, "submit {}", *deploy),
+            RpcRequest::SubmitPartialDeploy { deploy, .. } => {
+                write!(formatter, "submit partial {}", *deploy)
+            }
+            RpcRequest::AddDeployApprovals {
+                deploy_hash,
+                approvals,
+                ..
+            } => write!(
+                formatter,
+                "add {} approvals to {}",
+                approvals.len(),
+                deploy_hash
+            ),
             RpcRequest::GetBlock {
                 maybe_id: Some(BlockIdentifier::Hash(hash)),
                 ..
@@ -881,6 +968,15 @@ impl Display for RpcRequest {
                 write!(formatter, "get available block range")
             }
             RpcRequest::SpeculativeDeployExecute { .. } => write!(formatter, "execute deploy"),
+            RpcRequest::CallView { view_request, .. } => write!(
+                formatter,
+                "call view {} of {}",
+                view_request.entry_point_name(),
+                view_request.contract_hash()
+            ),
+            RpcRequest::GetWasmConfig {
+                protocol_version, ..
+            } => write!(formatter, "get wasm config of {}", protocol_version),
         }
     }
 }
@@ -949,6 +1045,14 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the upgrade result.
         responder: Responder<Result<UpgradeSuccess, engine_state::Error>>,
     },
+    /// A request to run upgrade without committing its effects to global state.
+    DryRunUpgrade {
+        /// Upgrade config.
+        #[serde(skip_serializing)]
+        upgrade_config: Box<UpgradeConfig>,
+        /// Responder to call with the upgrade result.
+        responder: Responder<Result<UpgradeSuccess, engine_state::Error>>,
+    },
     /// A query request.
     Query {
         /// Query request.
@@ -957,6 +1061,21 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the query result.
         responder: Responder<Result<QueryResult, engine_state::Error>>,
     },
+    /// A request to call a view entry point.
+    CallView {
+        /// View request.
+        #[serde(skip_serializing)]
+        view_request: Box<ViewRequest>,
+        /// Responder to call with the view result.
+        responder: Responder<Result<ViewResult, engine_state::Error>>,
+    },
+    /// A request for the Wasm config applying to a protocol version.
+    GetWasmConfig {
+        /// The protocol version.
+        protocol_version: ProtocolVersion,
+        /// Responder to call with the Wasm config.
+        responder: Responder<WasmConfig>,
+    },
     /// A balance request.
     GetBalance {
         /// Balance request.
@@ -1075,10 +1194,22 @@ impl Display for ContractRuntimeRequest {
                 write!(formatter, "upgrade request: {:?}", upgrade_config)
             }
 
+            ContractRuntimeRequest::DryRunUpgrade { upgrade_config, .. } => {
+                write!(formatter, "dry run upgrade request: {:?}", upgrade_config)
+            }
+
             ContractRuntimeRequest::Query { query_request, .. } => {
                 write!(formatter, "query request: {:?}", query_request)
             }
 
+            ContractRuntimeRequest::CallView { view_request, .. } => {
+                write!(formatter, "view request: {:?}", view_request)
+            }
+
+            ContractRuntimeRequest::GetWasmConfig {
+                protocol_version, ..
+            } => write!(formatter, "get wasm config of {}", protocol_version),
+
             ContractRuntimeRequest::GetBalance {
                 balance_request, ..
             } => write!(formatter, "balance request: {:?}", balance_request),
@@ -1185,6 +1316,37 @@ pub(crate) enum ConsensusRequest {
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
     /// Request for a list of validator status changes, by public key.
     ValidatorChanges(Responder<BTreeMap<PublicKey, Vec<(EraId, ValidatorChange)>>>),
+    /// Request for the persisted equivocation evidence: the era of the evidence, by faulty
+    /// validator.
+    Evidence(Responder<BTreeMap<PublicKey, EraId>>),
+    /// Request for the leader schedule of the current era.
+    LeaderSchedule(Responder<Option<(EraId, LeaderSchedule<ClContext>)>>),
+}
+
+/// LinearChain component requests.
+#[derive(DataSize, Debug)]
+#[must_use]
+pub(crate) enum LinearChainRequest {
+    /// Request to broadcast the known finality signatures of a block to all peers again.
+    ///
+    /// Responds with the number of signatures broadcast, or `None` if the request was rate
+    /// limited.
+    RebroadcastFinalitySignatures {
+        /// The hash of the block whose signatures should be broadcast.
+        block_hash: BlockHash,
+        /// Responder to call with the result.
+        responder: Responder<Option<usize>>,
+    },
+}
+
+impl Display for LinearChainRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LinearChainRequest::RebroadcastFinalitySignatures { block_hash, .. } => {
+                write!(f, "rebroadcast finality signatures of {}", block_hash)
+            }
+        }
+    }
 }
 
 /// ChainspecLoader component requests.
@@ -1196,6 +1358,8 @@ pub(crate) enum ChainspecLoaderRequest {
     /// Request for the chainspec file bytes with the genesis_accounts and global_state bytes, if
     /// they are present.
     GetChainspecRawBytes(Responder<Arc<ChainspecRawBytes>>),
+    /// Request for the parsed chainspec.
+    GetChainspec(Responder<Arc<Chainspec>>),
 }
 
 impl Display for ChainspecLoaderRequest {
@@ -1203,6 +1367,7 @@ impl Display for ChainspecLoaderRequest {
         match self {
             ChainspecLoaderRequest::GetChainspecInfo(_) => write!(f, "get chainspec info"),
             ChainspecLoaderRequest::GetChainspecRawBytes(_) => write!(f, "get chainspec raw bytes"),
+            ChainspecLoaderRequest::GetChainspec(_) => write!(f, "get chainspec"),
         }
     }
 }
@@ -1216,3 +1381,13 @@ impl Display for NodeStateRequest {
         write!(f, "node state request")
     }
 }
+
+/// A request for the health of the node's major components, answered by the reactor.
+#[derive(Debug, Serialize)]
+pub(crate) struct HealthRequest(pub(crate) Responder<HealthReport>);
+
+impl Display for HealthRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "health request")
+    }
+}
@@ -43,13 +43,19 @@ pub use components::{
 };
 pub use utils::WithDir;
 
-use std::sync::{atomic::AtomicUsize, Arc};
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize},
+    Arc,
+};
 
 use ansi_term::Color::Red;
 use once_cell::sync::Lazy;
 #[cfg(not(test))]
 use rand::SeedableRng;
-use signal_hook::{consts::TERM_SIGNALS, flag};
+use signal_hook::{
+    consts::{signal::SIGUSR1, TERM_SIGNALS},
+    flag,
+};
 
 pub(crate) use components::{
     block_proposer::Config as BlockProposerConfig,
@@ -59,9 +65,11 @@ pub(crate) use components::{
     event_stream_server::Config as EventStreamServerConfig,
     fetcher::Config as FetcherConfig,
     gossiper::Config as GossipConfig,
+    indexer::Config as IndexerConfig,
     rest_server::Config as RestServerConfig,
     rpc_server::{Config as RpcServerConfig, SpeculativeExecConfig},
     small_network::Config as SmallNetworkConfig,
+    telemetry::Config as TelemetryConfig,
 };
 pub(crate) use types::NodeRng;
 
@@ -100,6 +108,12 @@ pub(crate) static VERSION_STRING: Lazy<String> = Lazy::new(|| version_string(fal
 pub(crate) static TERMINATION_REQUESTED: Lazy<Arc<AtomicUsize>> =
     Lazy::new(|| Arc::new(AtomicUsize::new(0)));
 
+/// Global value that indicates the currently running reactor should enter drain mode if set.
+///
+/// Set by sending `SIGUSR1` to the process, reset once the request has been handed to the reactor.
+pub(crate) static DRAIN_REQUESTED: Lazy<Arc<AtomicBool>> =
+    Lazy::new(|| Arc::new(AtomicBool::new(false)));
+
 /// Setup UNIX signal hooks for current application.
 pub(crate) fn setup_signal_hooks() {
     for signal in TERM_SIGNALS {
@@ -110,6 +124,8 @@ pub(crate) fn setup_signal_hooks() {
         )
         .unwrap_or_else(|error| panic!("failed to register signal {}: {}", signal, error));
     }
+    flag::register(SIGUSR1, Arc::clone(&*DRAIN_REQUESTED))
+        .unwrap_or_else(|error| panic!("failed to register signal {}: {}", SIGUSR1, error));
 }
 
 /// Constructs a new `NodeRng`.
@@ -16,7 +16,7 @@ use crate::{
         consensus,
         fetcher::FetchedOrNotFound,
         gossiper,
-        small_network::{EstimatorWeights, FromIncoming, GossipedAddress, MessageKind, Payload},
+        small_network::{EstimatorWeights, FromIncoming, MessageKind, Payload, PeerRecord},
     },
     effect::{
         incoming::{
@@ -40,7 +40,7 @@ pub(crate) enum Message {
     DeployGossiper(gossiper::Message<Deploy>),
     /// Address gossiper component message.
     #[from]
-    AddressGossiper(gossiper::Message<GossipedAddress>),
+    AddressGossiper(gossiper::Message<PeerRecord>),
     /// Request to get an item from a peer.
     GetRequest {
         /// The type tag of the requested item.
@@ -81,6 +81,7 @@ impl Payload for Message {
                     Tag::BlockAndDeploysByHash => MessageKind::BlockTransfer,
                     Tag::BlockHeaderBatch => MessageKind::BlockTransfer,
                     Tag::FinalitySignaturesByHash => MessageKind::BlockTransfer,
+                    Tag::BlockBodyOrChunk => MessageKind::BlockTransfer,
                 }
             }
             Message::FinalitySignature(_) => MessageKind::Consensus,
@@ -119,6 +120,7 @@ impl Payload for Message {
                 Tag::BlockAndDeploysByHash => weights.block_requests,
                 Tag::BlockHeaderBatch => weights.block_requests,
                 Tag::FinalitySignaturesByHash => weights.block_requests,
+                Tag::BlockBodyOrChunk => weights.block_requests,
             },
             Message::GetResponse { tag, .. } => match tag {
                 Tag::Deploy => weights.deploy_responses,
@@ -132,6 +134,7 @@ impl Payload for Message {
                 Tag::BlockAndDeploysByHash => weights.block_requests,
                 Tag::BlockHeaderBatch => weights.block_responses,
                 Tag::FinalitySignaturesByHash => weights.block_responses,
+                Tag::BlockBodyOrChunk => weights.block_responses,
             },
             Message::FinalitySignature(_) => weights.finality_signatures,
         }
@@ -211,8 +214,8 @@ impl Display for Message {
         match self {
             Message::Consensus(consensus) => write!(f, "Consensus::{}", consensus),
             Message::DeployGossiper(deploy) => write!(f, "DeployGossiper::{}", deploy),
-            Message::AddressGossiper(gossiped_address) => {
-                write!(f, "AddressGossiper::({})", gossiped_address)
+            Message::AddressGossiper(peer_record) => {
+                write!(f, "AddressGossiper::({})", peer_record)
             }
             Message::GetRequest { tag, serialized_id } => {
                 write!(f, "GetRequest({}-{:10})", tag, HexFmt(serialized_id))
@@ -232,7 +235,7 @@ impl<REv> FromIncoming<Message> for REv
 where
     REv: From<ConsensusMessageIncoming>
         + From<GossiperIncoming<Deploy>>
-        + From<GossiperIncoming<GossipedAddress>>
+        + From<GossiperIncoming<PeerRecord>>
         + From<NetRequestIncoming>
         + From<NetResponseIncoming>
         + From<TrieRequestIncoming>
@@ -303,6 +306,11 @@ where
                     message: NetRequest::FinalitySignatures(serialized_id),
                 }
                 .into(),
+                Tag::BlockBodyOrChunk => NetRequestIncoming {
+                    sender,
+                    message: NetRequest::BlockBodyOrChunk(serialized_id),
+                }
+                .into(),
             },
             Message::GetResponse {
                 tag,
@@ -363,6 +371,11 @@ where
                     message: NetResponse::FinalitySignatures(serialized_item),
                 }
                 .into(),
+                Tag::BlockBodyOrChunk => NetResponseIncoming {
+                    sender,
+                    message: NetResponse::BlockBodyOrChunk(serialized_item),
+                }
+                .into(),
             },
             Message::FinalitySignature(message) => {
                 FinalitySignatureIncoming { sender, message }.into()
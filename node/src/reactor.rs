@@ -28,10 +28,12 @@
 //! manner using [`Runner::crank`] or indefinitely using [`Runner::run`].
 
 mod event_queue_metrics;
+mod health_metrics;
 pub(crate) mod initializer;
 pub(crate) mod joiner;
 pub(crate) mod participating;
 mod queue_kind;
+pub(crate) mod watchdog;
 
 #[cfg(test)]
 use std::sync::Arc;
@@ -40,7 +42,7 @@ use std::{
     collections::HashMap,
     env,
     fmt::{Debug, Display},
-    io::Write,
+    io::{self, Write},
     mem,
     num::NonZeroU64,
     str::FromStr,
@@ -53,7 +55,7 @@ use futures::{future::BoxFuture, FutureExt};
 use once_cell::sync::Lazy;
 use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
 use quanta::{Clock, IntoNanoseconds};
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
 use stats_alloc::{Stats, INSTRUMENTED_SYSTEM};
 use tokio::time::{Duration, Instant};
@@ -68,24 +70,25 @@ use crate::{
         Effect, EffectBuilder, EffectExt, Effects,
     },
     types::{
-        Block, BlockAndDeploys, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockSignatures, BlockWithMetadata, Deploy, DeployHash, ExitCode, FinalizedApprovalsWithId,
-        Item, NodeId,
+        Block, BlockAndDeploys, BlockBodyOrChunk, BlockHeader, BlockHeaderWithMetadata,
+        BlockHeadersBatch, BlockSignatures, BlockWithMetadata, Deploy, DeployHash, ExitCode,
+        FinalizedApprovalsWithId, Item, NodeId,
     },
     unregister_metric,
     utils::{
         self,
         rlimit::{Limit, OpenFiles, ResourceLimit},
-        SharedFlag, Source, WeightedRoundRobin,
+        SharedFlag, Source, WeightedRoundRobin, WithDir,
     },
-    NodeRng, TERMINATION_REQUESTED,
+    NodeRng, DRAIN_REQUESTED, TERMINATION_REQUESTED,
 };
 #[cfg(test)]
 use crate::{
     reactor::initializer::Reactor as InitializerReactor,
     types::{Chainspec, ChainspecRawBytes},
 };
-pub(crate) use queue_kind::QueueKind;
+pub(crate) use queue_kind::{OverflowPolicy, QueueKind};
+use watchdog::{Config as WatchdogConfig, Watchdog};
 
 /// Default threshold for when an event is considered slow.  Can be overridden by setting the env
 /// var `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
@@ -293,6 +296,19 @@ pub(crate) trait Reactor: Sized {
     /// dispatch events.  The running process may stop or may keep running with a new reactor.
     fn maybe_exit(&self) -> Option<ReactorExit>;
 
+    /// Puts the reactor into drain mode.
+    ///
+    /// A draining reactor stops taking on new work, finishes what is already in flight and then
+    /// indicates through `maybe_exit` that the process should exit with `ExitCode::Drained`.
+    /// Reactors that do not support draining ignore the request.
+    fn drain(&mut self, _effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        warn!(
+            reactor = any::type_name::<Self>(),
+            "ignoring drain request, not supported by reactor"
+        );
+        Effects::new()
+    }
+
     /// Instructs the reactor to update performance metrics, if any.
     fn update_metrics(&mut self, _event_queue_handle: EventQueueHandle<Self::Event>) {}
 }
@@ -315,6 +331,16 @@ pub(crate) trait ReactorEvent: Send + Debug + From<ControlAnnouncement> + 'stati
     fn description(&self) -> &'static str {
         "anonymous event"
     }
+
+    /// Returns the queue the event is meant to be scheduled on.
+    fn queue_kind(&self) -> QueueKind {
+        QueueKind::default()
+    }
+
+    /// Returns how the event is treated if its queue is congested.
+    fn overflow_policy(&self) -> OverflowPolicy {
+        OverflowPolicy::for_queue(self.queue_kind())
+    }
 }
 
 /// A drop-like trait for `async` compatible drop-and-wait.
@@ -375,6 +401,9 @@ where
 
     /// Flag indicating the reactor is being shut down.
     is_shutting_down: SharedFlag,
+
+    /// Watchdog detecting stalls of the reactor, if started.
+    watchdog: Option<Watchdog>,
 }
 
 /// Metric data for the Runner
@@ -390,6 +419,8 @@ struct RunnerMetrics {
     consumed_ram_bytes: IntGauge,
     /// Total system RAM in bytes, as reported by sys-info.
     total_ram_bytes: IntGauge,
+    /// Total number of stalls detected by the watchdog.
+    reactor_stalls: IntCounter,
     /// Handle to the metrics registry, in case we need to unregister.
     registry: Registry,
 }
@@ -436,12 +467,17 @@ impl RunnerMetrics {
         let consumed_ram_bytes =
             IntGauge::new("consumed_ram_bytes", "total consumed ram in bytes")?;
         let total_ram_bytes = IntGauge::new("total_ram_bytes", "total system ram in bytes")?;
+        let reactor_stalls = IntCounter::new(
+            "reactor_stalls",
+            "running total count of reactor stalls detected by the watchdog",
+        )?;
 
         registry.register(Box::new(events.clone()))?;
         registry.register(Box::new(event_dispatch_duration.clone()))?;
         registry.register(Box::new(allocated_ram_bytes.clone()))?;
         registry.register(Box::new(consumed_ram_bytes.clone()))?;
         registry.register(Box::new(total_ram_bytes.clone()))?;
+        registry.register(Box::new(reactor_stalls.clone()))?;
 
         Ok(RunnerMetrics {
             events,
@@ -450,6 +486,7 @@ impl RunnerMetrics {
             allocated_ram_bytes,
             consumed_ram_bytes,
             total_ram_bytes,
+            reactor_stalls,
         })
     }
 }
@@ -461,6 +498,7 @@ impl Drop for RunnerMetrics {
         unregister_metric!(self.registry, self.allocated_ram_bytes);
         unregister_metric!(self.registry, self.consumed_ram_bytes);
         unregister_metric!(self.registry, self.total_ram_bytes);
+        unregister_metric!(self.registry, self.reactor_stalls);
     }
 }
 
@@ -514,9 +552,37 @@ where
             event_metrics_threshold: 1000,
             clock: Clock::new(),
             is_shutting_down,
+            watchdog: None,
         })
     }
 
+    /// Starts a watchdog detecting stalls of the reactor, unless disabled in the configuration.
+    pub(crate) fn start_watchdog(&mut self, cfg: &WithDir<WatchdogConfig>) -> io::Result<()> {
+        let config = cfg.value();
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let scheduler = self.scheduler;
+        self.watchdog = Some(Watchdog::spawn(
+            config,
+            cfg.with_dir(config.dump_path.clone()),
+            self.metrics.reactor_stalls.clone(),
+            move || scheduler.event_queues_counts().values().sum(),
+            move |mut writer| {
+                futures::executor::block_on(scheduler.dump(move |queue_dump| {
+                    if let Err(error) = serde_json::to_writer_pretty(&mut writer, queue_dump)
+                        .map_err(io::Error::from)
+                        .and_then(|_| writer.flush())
+                    {
+                        warn!(%error, "failed to write event queue dump");
+                    }
+                }))
+            },
+        )?);
+        Ok(())
+    }
+
     /// Processes a single event on the event queue.
     ///
     /// Returns `false` if processing should stop.
@@ -565,6 +631,9 @@ where
         }
 
         // Dispatch the event, then execute the resulting effect.
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.dispatch_started(event_desc);
+        }
         let start = self.clock.start();
 
         let (effects, keep_going) = if event.as_control().is_some() {
@@ -584,6 +653,10 @@ where
                     error!(%file, %line, %msg, "fatal error via control announcement");
                     (Default::default(), false)
                 }
+                Some(ControlAnnouncement::DrainRequested) => {
+                    info!("drain requested via control announcement");
+                    (self.reactor.drain(effect_builder), true)
+                }
                 Some(ControlAnnouncement::QueueDumpRequest {
                     dump_format,
                     finished,
@@ -640,6 +713,9 @@ where
         };
 
         let end = self.clock.end();
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.dispatch_finished();
+        }
 
         // Warn if processing took a long time, record to histogram.
         let delta = self.clock.delta(start, end);
@@ -700,6 +776,16 @@ where
         loop {
             match TERMINATION_REQUESTED.load(Ordering::SeqCst) as i32 {
                 0 => {
+                    if DRAIN_REQUESTED.swap(false, Ordering::SeqCst) {
+                        info!("drain requested via signal");
+                        let effect_builder = EffectBuilder::new(EventQueueHandle::new(
+                            self.scheduler,
+                            self.is_shutting_down,
+                        ));
+                        let effects = self.reactor.drain(effect_builder);
+                        process_effects(None, self.scheduler, effects).await;
+                    }
+
                     if let Some(reactor_exit) = self.reactor.maybe_exit() {
                         self.is_shutting_down.set();
 
@@ -727,6 +813,9 @@ where
                                         // Maybe return an error instead, something like "reactor is
                                         // shutting down"?
                                     }
+                                    ControlAnnouncement::DrainRequested => {
+                                        // We are exiting anyway, so there is nothing to drain.
+                                    }
                                 }
                             } else {
                                 debug!(?ancestor, %event, "found non-control announcement while draining queue")
@@ -868,6 +957,7 @@ impl Runner<InitializerReactor> {
             event_metrics_threshold: 1000,
             clock: Clock::new(),
             is_shutting_down,
+            watchdog: None,
         })
     }
 }
@@ -922,6 +1012,60 @@ where
         .collect()
 }
 
+/// Applies the overrides of a component's config given in the environment variable `env_var`, if
+/// it is set.
+///
+/// The variable holds a TOML document, e.g. `CL_FETCHER_TEST_CONFIG='get_from_peer_timeout =
+/// "10sec"'`, whose values replace the corresponding ones of `config`. Called by reactors generated
+/// using the `reactor!` macro for components with a `config` attribute.
+///
+/// Panics if the overrides cannot be parsed or do not fit the config.
+pub(crate) fn apply_config_override<T>(config: T, env_var: &str) -> T
+where
+    T: Serialize + DeserializeOwned,
+{
+    let overrides_str = match env::var(env_var) {
+        Ok(overrides_str) => overrides_str,
+        Err(_) => return config,
+    };
+    let overrides: toml::Value = toml::from_str(&overrides_str).unwrap_or_else(|error| {
+        panic!(
+            "can't parse env var {}={} as TOML: {}",
+            env_var, overrides_str, error
+        )
+    });
+    let mut merged = toml::Value::try_from(config).unwrap_or_else(|error| {
+        panic!(
+            "can't serialize config to apply env var {}: {}",
+            env_var, error
+        )
+    });
+    merge_toml_values(&mut merged, overrides);
+    merged.try_into().unwrap_or_else(|error| {
+        panic!(
+            "can't apply env var {}={} to config: {}",
+            env_var, overrides_str, error
+        )
+    })
+}
+
+/// Replaces the values in `base` by those in `overrides`, merging tables recursively.
+fn merge_toml_values(base: &mut toml::Value, overrides: toml::Value) {
+    match (base, overrides) {
+        (toml::Value::Table(base), toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_toml_values(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
 fn handle_fetch_response<R, I>(
     reactor: &mut R,
     effect_builder: EffectBuilder<<R as Reactor>::Event>,
@@ -969,6 +1113,7 @@ where
         + From<fetcher::Event<BlockAndDeploys>>
         + From<fetcher::Event<BlockHeadersBatch>>
         + From<fetcher::Event<BlockSignatures>>
+        + From<fetcher::Event<BlockBodyOrChunk>>
         + From<fetcher::Event<Deploy>>
         + From<BlocklistAnnouncement>,
 {
@@ -1083,5 +1228,57 @@ where
                 serialized_item,
             )
         }
+        NetResponse::BlockBodyOrChunk(ref serialized_item) => {
+            handle_fetch_response::<R, BlockBodyOrChunk>(
+                reactor,
+                effect_builder,
+                rng,
+                sender,
+                serialized_item,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_merge_toml_values() {
+        let mut base: toml::Value = toml::from_str(
+            r#"
+            timeout = "3sec"
+            retries = 5
+
+            [limits]
+            peers = 10
+            bytes = 1024
+            "#,
+        )
+        .unwrap();
+        let overrides: toml::Value = toml::from_str(
+            r#"
+            timeout = "10sec"
+
+            [limits]
+            peers = 20
+            "#,
+        )
+        .unwrap();
+        let expected: toml::Value = toml::from_str(
+            r#"
+            timeout = "10sec"
+            retries = 5
+
+            [limits]
+            peers = 20
+            bytes = 1024
+            "#,
+        )
+        .unwrap();
+
+        merge_toml_values(&mut base, overrides);
+        assert_eq!(base, expected);
     }
 }
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use prometheus::{self, IntGauge, Registry};
+use tracing::{error, warn};
+
+use crate::{types::HealthReport, unregister_metric};
+
+/// Metrics for the health of the node and of its major components.
+///
+/// Each gauge is 0 while ready, 1 while degraded and 2 once failed.
+#[derive(Debug)]
+pub(super) struct HealthMetrics {
+    /// Health of the node as a whole, i.e. of its least healthy component.
+    node_health: IntGauge,
+    /// Per component gauges, by component name.
+    component_health: HashMap<&'static str, IntGauge>,
+    /// Instance of registry to unregister from when being dropped.
+    registry: Registry,
+}
+
+impl HealthMetrics {
+    /// Initializes health metrics for the components with the given names.
+    pub(super) fn new(
+        registry: Registry,
+        component_names: &[&'static str],
+    ) -> Result<Self, prometheus::Error> {
+        let node_health = IntGauge::new(
+            "health",
+            "health of the node: 0 if ready, 1 if degraded, 2 if failed",
+        )?;
+        registry.register(Box::new(node_health.clone()))?;
+
+        let mut component_health = HashMap::new();
+        for name in component_names {
+            let gauge = IntGauge::new(
+                format!("health_{}", name),
+                format!(
+                    "health of the {}: 0 if ready, 1 if degraded, 2 if failed",
+                    name
+                ),
+            )?;
+            registry.register(Box::new(gauge.clone()))?;
+            let result = component_health.insert(*name, gauge);
+            assert!(result.is_none(), "Map keys should not be overwritten.");
+        }
+
+        Ok(HealthMetrics {
+            node_health,
+            component_health,
+            registry,
+        })
+    }
+
+    /// Updates the gauges from the given report.
+    pub(super) fn record(&self, report: &HealthReport) {
+        self.node_health.set(report.status.as_gauge_value());
+        for (name, health) in &report.components {
+            match self.component_health.get(name.as_str()) {
+                Some(gauge) => gauge.set(health.status.as_gauge_value()),
+                None => warn!(component = %name, "no health gauge for component"),
+            }
+        }
+    }
+}
+
+impl Drop for HealthMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.node_health);
+        self.component_health.iter().for_each(|(name, gauge)| {
+            self.registry
+                .unregister(Box::new(gauge.clone()))
+                .unwrap_or_else(|_| error!("unregistering {} failed: was not registered", name))
+        });
+    }
+}
@@ -1,6 +1,9 @@
 //! Reactor used to initialize a node.
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
 
 use datasize::DataSize;
 use derive_more::From;
@@ -8,9 +11,10 @@ use prometheus::Registry;
 use reactor::ReactorEvent;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
 use casper_execution_engine::core::engine_state;
+use casper_hashing::Digest;
 
 use crate::{
     components::{
@@ -174,6 +178,22 @@ pub(crate) enum Error {
     /// An execution engine state error.
     #[error(transparent)]
     EngineState(#[from] engine_state::Error),
+
+    /// Global state is missing the state root of a complete block, even after repairing storage.
+    #[error(
+        "global state is missing state root {state_root_hash} of complete block at height \
+         {block_height}; the data in {} is likely corrupted, remove it and restart the node to \
+         resync",
+        .storage_path.display()
+    )]
+    MissingGlobalState {
+        /// Height of the block whose global state is missing.
+        block_height: u64,
+        /// The missing state root hash.
+        state_root_hash: Digest,
+        /// Path to the storage folder.
+        storage_path: PathBuf,
+    },
 }
 
 /// Initializer node reactor.
@@ -196,7 +216,7 @@ impl Reactor {
         let hard_reset_to_start_of_era = chainspec_loader.hard_reset_to_start_of_era();
 
         let storage_config = config.map_ref(|cfg| cfg.storage.clone());
-        let storage = Storage::new(
+        let mut storage = Storage::new(
             &storage_config,
             hard_reset_to_start_of_era,
             chainspec_loader.chainspec().protocol_config.version,
@@ -227,9 +247,25 @@ impl Reactor {
                 .core_config
                 .vesting_schedule_period
                 .millis(),
+            chainspec_loader
+                .chainspec()
+                .core_config
+                .allow_authorization_contracts,
+            chainspec_loader
+                .chainspec()
+                .core_config
+                .authorization_contract_gas_limit,
+            chainspec_loader
+                .chainspec()
+                .core_config
+                .max_unbondings_per_step,
             registry,
         )?;
 
+        if storage.unclean_shutdown_detected() {
+            check_integrity(&mut storage, &contract_runtime)?;
+        }
+
         let effects = reactor::wrap_effects(Event::Chainspec, chainspec_effects);
 
         let network_config = config.map_ref(|config| config.network.clone());
@@ -246,6 +282,42 @@ impl Reactor {
     }
 }
 
+/// Verifies that the highest complete block in storage is consistent with the storage indices and
+/// global state, repairing storage where possible.
+///
+/// A block whose global state is missing is marked incomplete so that it gets synced again. If the
+/// next highest complete block has no global state either, the node refuses to start.
+fn check_integrity(storage: &mut Storage, contract_runtime: &ContractRuntime) -> Result<(), Error> {
+    info!("checking storage integrity after unclean shutdown");
+    let mut repaired = false;
+    while let Some(block_header) = storage.verify_highest_complete_block()? {
+        let state_root_hash = *block_header.state_root_hash();
+        if contract_runtime.state_root_exists(state_root_hash)? {
+            info!(
+                height = block_header.height(),
+                "storage integrity check passed"
+            );
+            return Ok(());
+        }
+        if repaired {
+            return Err(Error::MissingGlobalState {
+                block_height: block_header.height(),
+                state_root_hash,
+                storage_path: storage.root_path().to_path_buf(),
+            });
+        }
+        warn!(
+            height = block_header.height(),
+            %state_root_hash,
+            "global state missing for highest complete block, marking it incomplete"
+        );
+        storage.unmark_complete_blocks_from(block_header.height())?;
+        repaired = true;
+    }
+    info!("no complete blocks in storage, skipping global state check");
+    Ok(())
+}
+
 #[cfg(test)]
 impl Reactor {
     /// Inspect storage.
@@ -34,9 +34,9 @@ use crate::{
         gossiper::{self, Gossiper},
         metrics::Metrics,
         rest_server::{self, RestServer},
-        small_network::{self, GossipedAddress, SmallNetwork, SmallNetworkIdentity},
+        small_network::{self, PeerRecord, SmallNetwork, SmallNetworkIdentity},
         storage::{self, Storage},
-        Component,
+        Component, HealthCheck,
     },
     contract_runtime,
     effect::{
@@ -53,8 +53,9 @@ use crate::{
         },
         requests::{
             BeginGossipRequest, ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
-            FetcherRequest, MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest,
-            NetworkRequest, NodeStateRequest, RestRequest, StorageRequest,
+            FetcherRequest, HealthRequest, LinearChainRequest, MarkBlockCompletedRequest,
+            MetricsRequest, NetworkInfoRequest, NetworkRequest, NodeStateRequest, RestRequest,
+            StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
@@ -62,13 +63,15 @@ use crate::{
     reactor::{
         self,
         event_queue_metrics::EventQueueMetrics,
+        health_metrics::HealthMetrics,
         initializer,
         participating::{self, Error, ParticipatingInitConfig},
         EventQueueHandle, Finalize, ReactorExit,
     },
     types::{
-        Block, BlockAndDeploys, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockSignatures, BlockWithMetadata, Deploy, ExitCode, FinalizedApprovalsWithId,
+        Block, BlockAndDeploys, BlockBodyOrChunk, BlockHeader, BlockHeaderWithMetadata,
+        BlockHeadersBatch, BlockSignatures, BlockWithMetadata, Deploy, ExitCode,
+        FinalizedApprovalsWithId, HealthReport,
     },
     utils::WithDir,
     NodeRng,
@@ -94,6 +97,8 @@ pub(crate) enum JoinerEvent {
     #[from]
     MetricsRequest(#[serde(skip_serializing)] MetricsRequest),
     #[from]
+    HealthRequest(#[serde(skip_serializing)] HealthRequest),
+    #[from]
     ChainspecLoader(#[serde(skip_serializing)] chainspec_loader::Event),
     #[from]
     ChainspecLoaderRequest(#[serde(skip_serializing)] ChainspecLoaderRequest),
@@ -122,9 +127,11 @@ pub(crate) enum JoinerEvent {
     #[from]
     FinalitySignaturesFetcher(#[serde(skip_serializing)] fetcher::Event<BlockSignatures>),
     #[from]
+    BlockBodyOrChunkFetcher(#[serde(skip_serializing)] fetcher::Event<BlockBodyOrChunk>),
+    #[from]
     DeployAcceptor(#[serde(skip_serializing)] deploy_acceptor::Event),
     #[from]
-    AddressGossiper(gossiper::Event<GossipedAddress>),
+    AddressGossiper(gossiper::Event<PeerRecord>),
     #[from]
     DeployGossiper(#[serde(skip_serializing)] gossiper::Event<Deploy>),
     #[from]
@@ -160,7 +167,9 @@ pub(crate) enum JoinerEvent {
     #[from]
     FinalitySignaturesFetcherRequest(#[serde(skip_serializing)] FetcherRequest<BlockSignatures>),
     #[from]
-    BeginAddressGossipRequest(BeginGossipRequest<GossipedAddress>),
+    BlockBodyOrChunkFetcherRequest(#[serde(skip_serializing)] FetcherRequest<BlockBodyOrChunk>),
+    #[from]
+    BeginAddressGossipRequest(BeginGossipRequest<PeerRecord>),
     #[from]
     ContractRuntimeRequest(ContractRuntimeRequest),
     #[from]
@@ -170,7 +179,7 @@ pub(crate) enum JoinerEvent {
     #[from]
     ContractRuntimeAnnouncement(#[serde(skip_serializing)] ContractRuntimeAnnouncement),
     #[from]
-    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<PeerRecord>),
     #[from]
     DeployAcceptorAnnouncement(#[serde(skip_serializing)] DeployAcceptorAnnouncement),
     #[from]
@@ -184,11 +193,13 @@ pub(crate) enum JoinerEvent {
     #[from]
     ConsensusRequest(#[serde(skip_serializing)] ConsensusRequest),
     #[from]
+    LinearChainRequest(#[serde(skip_serializing)] LinearChainRequest),
+    #[from]
     ConsensusMessageIncoming(ConsensusMessageIncoming),
     #[from]
     DeployGossiperIncoming(GossiperIncoming<Deploy>),
     #[from]
-    AddressGossiperIncoming(GossiperIncoming<GossipedAddress>),
+    AddressGossiperIncoming(GossiperIncoming<PeerRecord>),
     #[from]
     NetRequestIncoming(NetRequestIncoming),
     #[from]
@@ -230,6 +241,7 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::RestServer(_) => "RestServer",
             JoinerEvent::EventStreamServer(_) => "EventStreamServer",
             JoinerEvent::MetricsRequest(_) => "MetricsRequest",
+            JoinerEvent::HealthRequest(_) => "HealthRequest",
             JoinerEvent::ChainspecLoader(_) => "ChainspecLoader",
             JoinerEvent::ChainspecLoaderRequest(_) => "ChainspecLoaderRequest",
             JoinerEvent::ChainSynchronizerRequest(_) => "ChainSynchronizerRequest",
@@ -241,6 +253,7 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::TrieOrChunkFetcher(_) => "TrieOrChunkFetcher",
             JoinerEvent::BlockHeadersBatchFetcher(_) => "BlockHeadersBatchFetcher",
             JoinerEvent::FinalitySignaturesFetcher(_) => "FinalitySignaturesFetcher",
+            JoinerEvent::BlockBodyOrChunkFetcher(_) => "BlockBodyOrChunkFetcher",
             JoinerEvent::DeployAcceptor(_) => "DeployAcceptor",
             JoinerEvent::ContractRuntime(_) => "ContractRuntime",
             JoinerEvent::AddressGossiper(_) => "AddressGossiper",
@@ -258,6 +271,7 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::LinearChainAnnouncement(_) => "LinearChainAnnouncement",
             JoinerEvent::ChainspecLoaderAnnouncement(_) => "ChainspecLoaderAnnouncement",
             JoinerEvent::ConsensusRequest(_) => "ConsensusRequest",
+            JoinerEvent::LinearChainRequest(_) => "LinearChainRequest",
             JoinerEvent::BlockHeaderFetcher(_) => "BlockHeaderFetcher",
             JoinerEvent::BlockHeaderByHeightFetcher(_) => "BlockHeaderByHeightFetcher",
             JoinerEvent::BlockHeaderFetcherRequest(_) => "BlockHeaderFetcherRequest",
@@ -284,6 +298,7 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::DeployGossiperAnnouncement(_) => "DeployGossiperAnnouncement",
             JoinerEvent::BlockHeadersBatchFetcherRequest(_) => "BlockHeadersBatchFetcherRequest",
             JoinerEvent::FinalitySignaturesFetcherRequest(_) => "FinalitySignaturesFetcherRequest",
+            JoinerEvent::BlockBodyOrChunkFetcherRequest(_) => "BlockBodyOrChunkFetcherRequest",
             JoinerEvent::ChainSynchronizerAnnouncement(_) => "ChainSynchronizerAnnouncement",
         }
     }
@@ -295,8 +310,8 @@ impl From<NetworkRequest<Message>> for JoinerEvent {
     }
 }
 
-impl From<NetworkRequest<gossiper::Message<GossipedAddress>>> for JoinerEvent {
-    fn from(request: NetworkRequest<gossiper::Message<GossipedAddress>>) -> Self {
+impl From<NetworkRequest<gossiper::Message<PeerRecord>>> for JoinerEvent {
+    fn from(request: NetworkRequest<gossiper::Message<PeerRecord>>) -> Self {
         JoinerEvent::SmallNetwork(small_network::Event::from(
             request.map_payload(Message::from),
         ))
@@ -331,6 +346,7 @@ impl Display for JoinerEvent {
             JoinerEvent::RestServer(event) => write!(f, "rest server: {}", event),
             JoinerEvent::EventStreamServer(event) => write!(f, "event stream server: {}", event),
             JoinerEvent::MetricsRequest(req) => write!(f, "metrics request: {}", req),
+            JoinerEvent::HealthRequest(req) => write!(f, "{}", req),
             JoinerEvent::ChainspecLoader(event) => write!(f, "chainspec loader: {}", event),
             JoinerEvent::ChainspecLoaderRequest(req) => {
                 write!(f, "chainspec loader request: {}", req)
@@ -393,6 +409,7 @@ impl Display for JoinerEvent {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
             JoinerEvent::ConsensusRequest(req) => write!(f, "consensus request: {:?}", req),
+            JoinerEvent::LinearChainRequest(req) => write!(f, "linear chain request: {}", req),
             JoinerEvent::BlockHeaderFetcher(block_header) => {
                 write!(f, "block header fetcher event: {}", block_header)
             }
@@ -446,6 +463,12 @@ impl Display for JoinerEvent {
             JoinerEvent::FinalitySignaturesFetcherRequest(inner) => {
                 write!(f, "finality signatures fetch request: {}", inner)
             }
+            JoinerEvent::BlockBodyOrChunkFetcher(inner) => {
+                write!(f, "block body or chunk fetcher event: {}", inner)
+            }
+            JoinerEvent::BlockBodyOrChunkFetcherRequest(inner) => {
+                write!(f, "block body or chunk fetch request: {}", inner)
+            }
             JoinerEvent::ChainSynchronizerAnnouncement(ann) => {
                 write!(f, "chain synchronizer announcement: {}", ann)
             }
@@ -459,7 +482,7 @@ pub(crate) struct Reactor {
     root: PathBuf,
     metrics: Metrics,
     small_network: SmallNetwork<JoinerEvent, Message>,
-    address_gossiper: Gossiper<GossipedAddress, JoinerEvent>,
+    address_gossiper: Gossiper<PeerRecord, JoinerEvent>,
     config: participating::Config,
     chainspec_loader: ChainspecLoader,
     storage: Storage,
@@ -476,11 +499,14 @@ pub(crate) struct Reactor {
     block_header_by_hash_fetcher: Fetcher<BlockHeader>,
     block_headers_batch_fetcher: Fetcher<BlockHeadersBatch>,
     finality_signatures_fetcher: Fetcher<BlockSignatures>,
+    block_body_or_chunk_fetcher: Fetcher<BlockBodyOrChunk>,
     #[data_size(skip)]
     deploy_acceptor: DeployAcceptor,
     #[data_size(skip)]
     event_queue_metrics: EventQueueMetrics,
     #[data_size(skip)]
+    health_metrics: HealthMetrics,
+    #[data_size(skip)]
     rest_server: RestServer,
     #[data_size(skip)]
     event_stream_server: EventStreamServer,
@@ -525,6 +551,15 @@ impl reactor::Reactor for Reactor {
 
         let event_queue_metrics = EventQueueMetrics::new(registry.clone(), event_queue)?;
 
+        let health_metrics = HealthMetrics::new(
+            registry.clone(),
+            &[
+                Storage::NAME,
+                SmallNetwork::<JoinerEvent, Message>::NAME,
+                ContractRuntime::NAME,
+            ],
+        )?;
+
         let metrics = Metrics::new(registry.clone());
 
         let chainspec = chainspec_loader.chainspec().as_ref();
@@ -567,14 +602,14 @@ impl reactor::Reactor for Reactor {
 
         let protocol_version = &chainspec_loader.chainspec().protocol_config.version;
         let rest_server = RestServer::new(
-            config.rest_server.clone(),
+            WithDir::new(&root, config.rest_server.clone()),
             effect_builder,
             *protocol_version,
             node_startup_instant,
         )?;
 
         let event_stream_server = EventStreamServer::new(
-            config.event_stream_server.clone(),
+            WithDir::new(&root, config.event_stream_server.clone()),
             storage.root_path().to_path_buf(),
             *protocol_version,
         )?;
@@ -595,6 +630,7 @@ impl reactor::Reactor for Reactor {
         let block_and_deploys_fetcher = fetcher_builder.build("block_and_deploys")?;
         let block_headers_batch_fetcher = fetcher_builder.build("block_headers_batch")?;
         let finality_signatures_fetcher = fetcher_builder.build("finality_signatures")?;
+        let block_body_or_chunk_fetcher = fetcher_builder.build("block_body_or_chunk")?;
 
         let trie_or_chunk_fetcher = fetcher_builder.build("trie_or_chunk")?;
 
@@ -630,11 +666,13 @@ impl reactor::Reactor for Reactor {
                 block_header_by_hash_fetcher,
                 block_headers_batch_fetcher,
                 finality_signatures_fetcher,
+                block_body_or_chunk_fetcher,
                 block_header_and_finality_signatures_by_height_fetcher,
                 block_and_deploys_fetcher,
                 trie_or_chunk_fetcher,
                 deploy_acceptor,
                 event_queue_metrics,
+                health_metrics,
                 rest_server,
                 event_stream_server,
                 memory_metrics,
@@ -759,6 +797,11 @@ impl reactor::Reactor for Reactor {
                 self.finality_signatures_fetcher
                     .handle_event(effect_builder, rng, event),
             ),
+            JoinerEvent::BlockBodyOrChunkFetcher(event) => reactor::wrap_effects(
+                JoinerEvent::BlockBodyOrChunkFetcher,
+                self.block_body_or_chunk_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
             JoinerEvent::DeployFetcherRequest(request) => self.dispatch_event(
                 effect_builder,
                 rng,
@@ -829,10 +872,10 @@ impl reactor::Reactor for Reactor {
                     .handle_event(effect_builder, rng, event),
             ),
             JoinerEvent::AddressGossiperAnnouncement(GossiperAnnouncement::NewCompleteItem(
-                gossiped_address,
+                peer_record,
             )) => {
                 let reactor_event = JoinerEvent::SmallNetwork(
-                    small_network::Event::PeerAddressReceived(gossiped_address),
+                    small_network::Event::PeerRecordReceived(peer_record),
                 );
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
@@ -879,6 +922,9 @@ impl reactor::Reactor for Reactor {
                 JoinerEvent::MetricsRequest,
                 self.metrics.handle_event(effect_builder, rng, req),
             ),
+            JoinerEvent::HealthRequest(HealthRequest(responder)) => {
+                responder.respond(self.health_report()).ignore()
+            }
             JoinerEvent::ChainspecLoader(event) => reactor::wrap_effects(
                 JoinerEvent::ChainspecLoader,
                 self.chainspec_loader
@@ -917,6 +963,20 @@ impl reactor::Reactor for Reactor {
                 // no consensus, respond with empty map
                 responder.respond(BTreeMap::new()).ignore()
             }
+            JoinerEvent::ConsensusRequest(ConsensusRequest::Evidence(responder)) => {
+                // no consensus, respond with empty map
+                responder.respond(BTreeMap::new()).ignore()
+            }
+            JoinerEvent::ConsensusRequest(ConsensusRequest::LeaderSchedule(responder)) => {
+                // no consensus, respond with None
+                responder.respond(None).ignore()
+            }
+            JoinerEvent::LinearChainRequest(
+                LinearChainRequest::RebroadcastFinalitySignatures { responder, .. },
+            ) => {
+                // no linear chain, so no signatures to rebroadcast
+                responder.respond(Some(0)).ignore()
+            }
             JoinerEvent::BlockHeaderByHeightFetcher(event) => reactor::wrap_effects(
                 JoinerEvent::BlockHeaderByHeightFetcher,
                 self.block_header_and_finality_signatures_by_height_fetcher
@@ -1012,6 +1072,11 @@ impl reactor::Reactor for Reactor {
                 rng,
                 JoinerEvent::FinalitySignaturesFetcher(request.into()),
             ),
+            JoinerEvent::BlockBodyOrChunkFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                JoinerEvent::BlockBodyOrChunkFetcher(request.into()),
+            ),
         }
     }
 
@@ -1030,10 +1095,23 @@ impl reactor::Reactor for Reactor {
         self.memory_metrics.estimate(self);
         self.event_queue_metrics
             .record_event_queue_counts(&event_queue_handle);
+        self.health_metrics.record(&self.health_report());
     }
 }
 
 impl Reactor {
+    /// Returns the health of the node's major components.
+    fn health_report(&self) -> HealthReport {
+        HealthReport::new(vec![
+            (Storage::NAME, self.storage.health()),
+            (
+                SmallNetwork::<JoinerEvent, Message>::NAME,
+                self.small_network.health(),
+            ),
+            (ContractRuntime::NAME, self.contract_runtime.health()),
+        ])
+    }
+
     /// Deconstructs the reactor into config useful for creating a Validator reactor. Shuts down
     /// the network, closing all incoming and outgoing connections, and frees up the listening
     /// socket.
@@ -37,13 +37,15 @@ use crate::{
         event_stream_server::{self, EventStreamServer},
         fetcher::{self, Fetcher, FetcherBuilder},
         gossiper::{self, Gossiper},
+        indexer::{self, Indexer},
         linear_chain::{self, LinearChainComponent},
         metrics::Metrics,
         rest_server::{self, RestServer},
         rpc_server::{self, RpcServer},
-        small_network::{self, GossipedAddress, SmallNetwork, SmallNetworkIdentity},
+        small_network::{self, PeerRecord, SmallNetwork, SmallNetworkIdentity},
         storage::{self, Storage},
-        Component,
+        telemetry::{self, Stage, Telemetry},
+        Component, HealthCheck,
     },
     contract_runtime,
     effect::{
@@ -62,20 +64,24 @@ use crate::{
         requests::{
             BeginGossipRequest, BlockProposerRequest, BlockValidationRequest,
             ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, FetcherRequest,
-            MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
-            NodeStateRequest, RestRequest, RpcRequest, StateStoreRequest, StorageRequest,
+            HealthRequest, LinearChainRequest, MarkBlockCompletedRequest, MetricsRequest,
+            NetworkInfoRequest, NetworkRequest, NodeStateRequest, RestRequest, RpcRequest,
+            StateStoreRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects,
     },
     fatal,
     protocol::Message,
-    reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle, ReactorExit},
+    reactor::{
+        self, event_queue_metrics::EventQueueMetrics, health_metrics::HealthMetrics,
+        EventQueueHandle, ReactorExit,
+    },
     types::{
-        Block, BlockAndDeploys, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockSignatures, BlockWithMetadata, Deploy, ExitCode, FinalitySignature,
-        FinalizedApprovalsWithId,
+        Block, BlockAndDeploys, BlockBodyOrChunk, BlockHeader, BlockHeaderWithMetadata,
+        BlockHeadersBatch, BlockSignatures, BlockWithMetadata, Deploy, ExitCode,
+        FinalizedApprovalsWithId, HealthReport,
     },
-    utils::{Source, WithDir},
+    utils::{self, Source, WithDir},
     NodeRng,
 };
 #[cfg(test)]
@@ -117,7 +123,7 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     DeployGossiper(#[serde(skip_serializing)] gossiper::Event<Deploy>),
     #[from]
-    AddressGossiper(gossiper::Event<GossipedAddress>),
+    AddressGossiper(gossiper::Event<PeerRecord>),
     #[from]
     BlockValidator(#[serde(skip_serializing)] block_validator::Event),
     #[from]
@@ -125,6 +131,10 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     DiagnosticsPort(diagnostics_port::Event),
     #[from]
+    Indexer(#[serde(skip_serializing)] indexer::Event),
+    #[from]
+    Telemetry(#[serde(skip_serializing)] telemetry::Event),
+    #[from]
     ContractRuntime(contract_runtime::Event),
     #[from]
     BlockFetcher(#[serde(skip_serializing)] fetcher::Event<Block>),
@@ -144,6 +154,8 @@ pub(crate) enum ParticipatingEvent {
     BlockHeadersBatchFetcher(#[serde(skip_serializing)] fetcher::Event<BlockHeadersBatch>),
     #[from]
     FinalitySignaturesFetcher(#[serde(skip_serializing)] fetcher::Event<BlockSignatures>),
+    #[from]
+    BlockBodyOrChunkFetcher(#[serde(skip_serializing)] fetcher::Event<BlockBodyOrChunk>),
 
     // Requests
     #[from]
@@ -179,19 +191,23 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     FinalitySignaturesFetcherRequest(#[serde(skip_serializing)] FetcherRequest<BlockSignatures>),
     #[from]
+    BlockBodyOrChunkFetcherRequest(#[serde(skip_serializing)] FetcherRequest<BlockBodyOrChunk>),
+    #[from]
     BlockProposerRequest(#[serde(skip_serializing)] BlockProposerRequest),
     #[from]
     BlockValidatorRequest(#[serde(skip_serializing)] BlockValidationRequest),
     #[from]
     MetricsRequest(#[serde(skip_serializing)] MetricsRequest),
     #[from]
+    HealthRequest(#[serde(skip_serializing)] HealthRequest),
+    #[from]
     ChainspecLoaderRequest(#[serde(skip_serializing)] ChainspecLoaderRequest),
     #[from]
     StorageRequest(#[serde(skip_serializing)] StorageRequest),
     #[from]
     MarkBlockCompletedRequest(MarkBlockCompletedRequest),
     #[from]
-    BeginAddressGossipRequest(BeginGossipRequest<GossipedAddress>),
+    BeginAddressGossipRequest(BeginGossipRequest<PeerRecord>),
     #[from]
     StateStoreRequest(StateStoreRequest),
     #[from]
@@ -211,7 +227,7 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     DeployGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<Deploy>),
     #[from]
-    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<GossipedAddress>),
+    AddressGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<PeerRecord>),
     #[from]
     LinearChainAnnouncement(#[serde(skip_serializing)] LinearChainAnnouncement),
     #[from]
@@ -225,7 +241,7 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     DeployGossiperIncoming(GossiperIncoming<Deploy>),
     #[from]
-    AddressGossiperIncoming(GossiperIncoming<GossipedAddress>),
+    AddressGossiperIncoming(GossiperIncoming<PeerRecord>),
     #[from]
     NetRequestIncoming(NetRequestIncoming),
     #[from]
@@ -288,7 +304,10 @@ impl ReactorEvent for ParticipatingEvent {
             ParticipatingEvent::FinalizedApprovalsFetcher(_) => "FinalizedApprovalsFetcher",
             ParticipatingEvent::BlockHeadersBatchFetcher(_) => "BlockHeadersBatchFetcher",
             ParticipatingEvent::FinalitySignaturesFetcher(_) => "FinalitySignaturesFetcher",
+            ParticipatingEvent::BlockBodyOrChunkFetcher(_) => "BlockBodyOrChunkFetcher",
             ParticipatingEvent::DiagnosticsPort(_) => "DiagnosticsPort",
+            ParticipatingEvent::Indexer(_) => "Indexer",
+            ParticipatingEvent::Telemetry(_) => "Telemetry",
             ParticipatingEvent::NetworkRequest(_) => "NetworkRequest",
             ParticipatingEvent::NetworkInfoRequest(_) => "NetworkInfoRequest",
             ParticipatingEvent::BlockFetcherRequest(_) => "BlockFetcherRequest",
@@ -309,9 +328,13 @@ impl ReactorEvent for ParticipatingEvent {
             ParticipatingEvent::FinalitySignaturesFetcherRequest(_) => {
                 "FinalitySignaturesFetcherRequest"
             }
+            ParticipatingEvent::BlockBodyOrChunkFetcherRequest(_) => {
+                "BlockBodyOrChunkFetcherRequest"
+            }
             ParticipatingEvent::BlockProposerRequest(_) => "BlockProposerRequest",
             ParticipatingEvent::BlockValidatorRequest(_) => "BlockValidatorRequest",
             ParticipatingEvent::MetricsRequest(_) => "MetricsRequest",
+            ParticipatingEvent::HealthRequest(_) => "HealthRequest",
             ParticipatingEvent::ChainspecLoaderRequest(_) => "ChainspecLoaderRequest",
             ParticipatingEvent::StorageRequest(_) => "StorageRequest",
             ParticipatingEvent::MarkBlockCompletedRequest(_) => "MarkBlockCompletedRequest",
@@ -368,8 +391,8 @@ impl From<NetworkRequest<gossiper::Message<Deploy>>> for ParticipatingEvent {
     }
 }
 
-impl From<NetworkRequest<gossiper::Message<GossipedAddress>>> for ParticipatingEvent {
-    fn from(request: NetworkRequest<gossiper::Message<GossipedAddress>>) -> Self {
+impl From<NetworkRequest<gossiper::Message<PeerRecord>>> for ParticipatingEvent {
+    fn from(request: NetworkRequest<gossiper::Message<PeerRecord>>) -> Self {
         ParticipatingEvent::NetworkRequest(request.map_payload(Message::from))
     }
 }
@@ -380,6 +403,12 @@ impl From<ConsensusRequest> for ParticipatingEvent {
     }
 }
 
+impl From<LinearChainRequest> for ParticipatingEvent {
+    fn from(request: LinearChainRequest) -> Self {
+        ParticipatingEvent::LinearChain(linear_chain::Event::Request(request))
+    }
+}
+
 impl Display for ParticipatingEvent {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -430,7 +459,12 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::FinalitySignaturesFetcher(event) => {
                 write!(f, "finality signatures fetcher: {}", event)
             }
+            ParticipatingEvent::BlockBodyOrChunkFetcher(event) => {
+                write!(f, "block body or chunk fetcher: {}", event)
+            }
             ParticipatingEvent::DiagnosticsPort(event) => write!(f, "diagnostics port: {}", event),
+            ParticipatingEvent::Indexer(event) => write!(f, "indexer: {}", event),
+            ParticipatingEvent::Telemetry(event) => write!(f, "telemetry: {}", event),
             ParticipatingEvent::ChainSynchronizerRequest(req) => {
                 write!(f, "chain synchronizer request: {}", req)
             }
@@ -476,6 +510,9 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::FinalitySignaturesFetcherRequest(request) => {
                 write!(f, "finality signatures fetcher request: {}", request)
             }
+            ParticipatingEvent::BlockBodyOrChunkFetcherRequest(request) => {
+                write!(f, "block body or chunk fetcher request: {}", request)
+            }
             ParticipatingEvent::BeginAddressGossipRequest(request) => {
                 write!(f, "begin address gossip request: {}", request)
             }
@@ -486,6 +523,7 @@ impl Display for ParticipatingEvent {
                 write!(f, "block validator request: {}", req)
             }
             ParticipatingEvent::MetricsRequest(req) => write!(f, "metrics request: {}", req),
+            ParticipatingEvent::HealthRequest(req) => write!(f, "{}", req),
             ParticipatingEvent::ControlAnnouncement(ctrl_ann) => write!(f, "control: {}", ctrl_ann),
             ParticipatingEvent::DumpConsensusStateRequest(req) => {
                 write!(f, "dump consensus state: {}", req)
@@ -575,7 +613,7 @@ impl Debug for ParticipatingInitConfig {
 pub(crate) struct Reactor {
     metrics: Metrics,
     small_network: SmallNetwork<ParticipatingEvent, Message>,
-    address_gossiper: Gossiper<GossipedAddress, ParticipatingEvent>,
+    address_gossiper: Gossiper<PeerRecord, ParticipatingEvent>,
     storage: Storage,
     contract_runtime: ContractRuntime,
     rpc_server: RpcServer,
@@ -600,12 +638,17 @@ pub(crate) struct Reactor {
     finalized_approvals_fetcher: Fetcher<FinalizedApprovalsWithId>,
     block_headers_batch_fetcher: Fetcher<BlockHeadersBatch>,
     finality_signatures_fetcher: Fetcher<BlockSignatures>,
+    block_body_or_chunk_fetcher: Fetcher<BlockBodyOrChunk>,
     diagnostics_port: DiagnosticsPort,
+    indexer: Indexer,
+    telemetry: Telemetry,
     // Non-components.
     #[data_size(skip)] // Never allocates heap data.
     memory_metrics: MemoryMetrics,
     #[data_size(skip)]
     event_queue_metrics: EventQueueMetrics,
+    #[data_size(skip)]
+    health_metrics: HealthMetrics,
 }
 
 #[cfg(test)]
@@ -643,7 +686,7 @@ impl reactor::Reactor for Reactor {
         let ParticipatingInitConfig {
             root,
             config,
-            chainspec_loader,
+            mut chainspec_loader,
             storage,
             mut contract_runtime,
             joining_outcome,
@@ -653,7 +696,8 @@ impl reactor::Reactor for Reactor {
             node_startup_instant,
         } = config;
 
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root)?;
+        let (our_signer, our_public_key) = config.consensus.load_keys(&root)?;
+        let our_successor_keys = config.consensus.load_successor_keys(&root)?;
 
         let effect_builder = EffectBuilder::new(event_queue);
         let mut effects = Effects::new();
@@ -706,7 +750,7 @@ impl reactor::Reactor for Reactor {
                         );
                     }
 
-                    let secret_key = our_secret_key.clone();
+                    let signer = our_signer.clone();
                     let public_key = our_public_key.clone();
                     let block_hash = *block.hash();
                     effects.extend(
@@ -732,12 +776,20 @@ impl reactor::Reactor for Reactor {
                             // We're responsible for signing the new block if we're in the provided
                             // list.
                             if validator_weights.contains_key(&public_key) {
-                                let signature = FinalitySignature::new(
-                                    block_hash,
-                                    current_era_id,
-                                    &secret_key,
-                                    public_key.clone(),
-                                );
+                                let signature = match signer
+                                    .finality_signature(
+                                        block_hash,
+                                        current_era_id,
+                                        public_key.clone(),
+                                    )
+                                    .await
+                                {
+                                    Ok(signature) => signature,
+                                    Err(error) => {
+                                        error!(%error, "failed to sign immediate switch block");
+                                        return;
+                                    }
+                                };
 
                                 effect_builder
                                     .announce_created_finality_signature(signature.clone())
@@ -763,6 +815,16 @@ impl reactor::Reactor for Reactor {
 
         let event_queue_metrics = EventQueueMetrics::new(registry.clone(), event_queue)?;
 
+        let health_metrics = HealthMetrics::new(
+            registry.clone(),
+            &[
+                Storage::NAME,
+                SmallNetwork::<ParticipatingEvent, Message>::NAME,
+                EraSupervisor::NAME,
+                ContractRuntime::NAME,
+            ],
+        )?;
+
         let metrics = Metrics::new(registry.clone());
 
         let (diagnostics_port, diagnostics_port_effects) = DiagnosticsPort::new(
@@ -770,23 +832,30 @@ impl reactor::Reactor for Reactor {
             event_queue,
         )?;
 
+        let indexer = Indexer::new(&WithDir::new(&root, config.indexer.clone()))?;
+
         let effect_builder = EffectBuilder::new(event_queue);
 
+        let (telemetry, telemetry_effects) =
+            Telemetry::new(config.telemetry.clone(), effect_builder)?;
+
         let address_gossiper =
             Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
 
+        chainspec_loader.enable_upgrade_dry_run(config.node.upgrade_dry_run_lead_time, registry)?;
         let chainspec = chainspec_loader.chainspec();
 
         let protocol_version = chainspec.protocol_config.version;
         let rpc_server = RpcServer::new(
-            config.rpc_server.clone(),
-            config.speculative_exec_server.clone(),
+            WithDir::new(&root, config.rpc_server.clone()),
+            WithDir::new(&root, config.speculative_exec_server.clone()),
             effect_builder,
             protocol_version,
             node_startup_instant,
+            registry,
         )?;
         let rest_server = RestServer::new(
-            config.rest_server.clone(),
+            WithDir::new(&root, config.rest_server.clone()),
             effect_builder,
             protocol_version,
             node_startup_instant,
@@ -800,12 +869,16 @@ impl reactor::Reactor for Reactor {
 
         let deploy_acceptor = DeployAcceptor::new(chainspec_loader.chainspec(), registry)?;
         let deploy_fetcher = fetcher_builder.build("deploy")?;
-        let deploy_gossiper = Gossiper::new_for_partial_items(
+        let mut deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
             config.gossip,
             gossiper::get_deploy_from_storage::<Deploy, ParticipatingEvent>,
             registry,
         )?;
+        effects.extend(reactor::wrap_effects(
+            ParticipatingEvent::DeployGossiper,
+            deploy_gossiper.persist_table("deploy_gossiper", effect_builder),
+        ));
 
         let (block_proposer, block_proposer_effects) = BlockProposer::new(
             registry.clone(),
@@ -832,13 +905,18 @@ impl reactor::Reactor for Reactor {
             ParticipatingEvent::DiagnosticsPort,
             diagnostics_port_effects,
         ));
+        effects.extend(reactor::wrap_effects(
+            ParticipatingEvent::Telemetry,
+            telemetry_effects,
+        ));
 
         let next_upgrade_activation_point = chainspec_loader.next_upgrade_activation_point();
         let (consensus, init_consensus_effects) = EraSupervisor::new(
             highest_block_header.next_block_era_id(),
             storage.root_path(),
-            our_secret_key,
+            our_signer,
             our_public_key,
+            our_successor_keys,
             config.consensus,
             effect_builder,
             chainspec.clone(),
@@ -890,6 +968,7 @@ impl reactor::Reactor for Reactor {
         let finalized_approvals_fetcher = fetcher_builder.build("finalized_approvals")?;
         let block_headers_batch_fetcher = fetcher_builder.build("block_headers_batch")?;
         let finality_signatures_fetcher = fetcher_builder.build("finality_signatures")?;
+        let block_body_or_chunk_fetcher = fetcher_builder.build("block_body_or_chunk")?;
 
         effects.extend(reactor::wrap_effects(
             ParticipatingEvent::SmallNetwork,
@@ -928,9 +1007,13 @@ impl reactor::Reactor for Reactor {
                 finalized_approvals_fetcher,
                 block_headers_batch_fetcher,
                 finality_signatures_fetcher,
+                block_body_or_chunk_fetcher,
                 diagnostics_port,
+                indexer,
+                telemetry,
                 memory_metrics,
                 event_queue_metrics,
+                health_metrics,
             },
             effects,
         ))
@@ -1060,11 +1143,24 @@ impl reactor::Reactor for Reactor {
                 self.finality_signatures_fetcher
                     .handle_event(effect_builder, rng, event),
             ),
+            ParticipatingEvent::BlockBodyOrChunkFetcher(event) => reactor::wrap_effects(
+                ParticipatingEvent::BlockBodyOrChunkFetcher,
+                self.block_body_or_chunk_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
             ParticipatingEvent::DiagnosticsPort(event) => reactor::wrap_effects(
                 ParticipatingEvent::DiagnosticsPort,
                 self.diagnostics_port
                     .handle_event(effect_builder, rng, event),
             ),
+            ParticipatingEvent::Indexer(event) => reactor::wrap_effects(
+                ParticipatingEvent::Indexer,
+                self.indexer.handle_event(effect_builder, rng, event),
+            ),
+            ParticipatingEvent::Telemetry(event) => reactor::wrap_effects(
+                ParticipatingEvent::Telemetry,
+                self.telemetry.handle_event(effect_builder, rng, event),
+            ),
 
             // Requests:
             ParticipatingEvent::ChainSynchronizerRequest(request) => reactor::wrap_effects(
@@ -1132,6 +1228,11 @@ impl reactor::Reactor for Reactor {
                 self.finality_signatures_fetcher
                     .handle_event(effect_builder, rng, request.into()),
             ),
+            ParticipatingEvent::BlockBodyOrChunkFetcherRequest(request) => reactor::wrap_effects(
+                ParticipatingEvent::BlockBodyOrChunkFetcher,
+                self.block_body_or_chunk_fetcher
+                    .handle_event(effect_builder, rng, request.into()),
+            ),
             ParticipatingEvent::BlockProposerRequest(req) => self.dispatch_event(
                 effect_builder,
                 rng,
@@ -1146,6 +1247,9 @@ impl reactor::Reactor for Reactor {
                 ParticipatingEvent::MetricsRequest,
                 self.metrics.handle_event(effect_builder, rng, req),
             ),
+            ParticipatingEvent::HealthRequest(HealthRequest(responder)) => {
+                responder.respond(self.health_report()).ignore()
+            }
             ParticipatingEvent::ChainspecLoaderRequest(req) => self.dispatch_event(
                 effect_builder,
                 rng,
@@ -1192,6 +1296,11 @@ impl reactor::Reactor for Reactor {
                     ParticipatingEvent::DeployAcceptor(event),
                 )
             }
+            ParticipatingEvent::RpcServerAnnouncement(ann) => self.dispatch_event(
+                effect_builder,
+                rng,
+                ParticipatingEvent::DeployAcceptor(ann.into()),
+            ),
             ParticipatingEvent::DeployAcceptorAnnouncement(
                 DeployAcceptorAnnouncement::AcceptedNewDeploy { deploy, source },
             ) => {
@@ -1224,6 +1333,17 @@ impl reactor::Reactor for Reactor {
                     ParticipatingEvent::DeployGossiper(event),
                 ));
 
+                let event = telemetry::Event::StageReached {
+                    deploy_hashes: vec![*deploy.id()],
+                    stage: Stage::Accepted,
+                    timestamp: utils::clock::now(),
+                };
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    ParticipatingEvent::Telemetry(event),
+                ));
+
                 let event = event_stream_server::Event::DeployAccepted(deploy.clone());
                 effects.extend(self.dispatch_event(
                     effect_builder,
@@ -1252,10 +1372,30 @@ impl reactor::Reactor for Reactor {
             ParticipatingEvent::ConsensusAnnouncement(consensus_announcement) => {
                 match consensus_announcement {
                     ConsensusAnnouncement::Finalized(block) => {
+                        let deploy_hashes: Vec<_> = block
+                            .deploy_hashes()
+                            .chain(block.transfer_hashes())
+                            .copied()
+                            .collect();
+                        let mut effects = Effects::new();
+                        for (stage, timestamp) in [
+                            (Stage::Proposed, block.timestamp()),
+                            (Stage::Finalized, utils::clock::now()),
+                        ] {
+                            let reactor_event =
+                                ParticipatingEvent::Telemetry(telemetry::Event::StageReached {
+                                    deploy_hashes: deploy_hashes.clone(),
+                                    stage,
+                                    timestamp,
+                                });
+                            effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                        }
+
                         let reactor_event = ParticipatingEvent::BlockProposer(
                             block_proposer::Event::FinalizedBlock(block),
                         );
-                        self.dispatch_event(effect_builder, rng, reactor_event)
+                        effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                        effects
                     }
                     ConsensusAnnouncement::CreatedFinalitySignature(fs) => self.dispatch_event(
                         effect_builder,
@@ -1289,6 +1429,24 @@ impl reactor::Reactor for Reactor {
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
 
+                // send to telemetry
+                let reactor_event = ParticipatingEvent::Telemetry(telemetry::Event::StageReached {
+                    deploy_hashes: execution_results
+                        .iter()
+                        .map(|(deploy_hash, _, _)| *deploy_hash)
+                        .collect(),
+                    stage: Stage::Executed,
+                    timestamp: utils::clock::now(),
+                });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
+                // send to indexer
+                let reactor_event = ParticipatingEvent::Indexer(indexer::Event::BlockExecuted {
+                    block: block.clone(),
+                    execution_results: execution_results.clone(),
+                });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
                 // send to linear chain
                 let reactor_event =
                     ParticipatingEvent::LinearChain(linear_chain::Event::NewLinearChainBlock {
@@ -1321,12 +1479,24 @@ impl reactor::Reactor for Reactor {
                     execution_effect,
                 },
             ) => {
+                let mut effects = Effects::new();
+
+                // send to indexer
+                let reactor_event = ParticipatingEvent::Indexer(indexer::Event::StepCommitted {
+                    era_id,
+                    execution_effect: execution_effect.clone(),
+                });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
+                // send to event stream
                 let reactor_event =
                     ParticipatingEvent::EventStreamServer(event_stream_server::Event::Step {
                         era_id,
                         execution_effect,
                     });
-                self.dispatch_event(effect_builder, rng, reactor_event)
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
+                effects
             }
             ParticipatingEvent::DeployGossiperAnnouncement(
                 GossiperAnnouncement::NewCompleteItem(gossiped_deploy_id),
@@ -1335,19 +1505,20 @@ impl reactor::Reactor for Reactor {
                 Effects::new()
             }
             ParticipatingEvent::DeployGossiperAnnouncement(
-                GossiperAnnouncement::FinishedGossiping(_gossiped_deploy_id),
+                GossiperAnnouncement::FinishedGossiping(gossiped_deploy_id),
             ) => {
-                // let reactor_event =
-                //     ParticipatingEvent::BlockProposer(block_proposer::Event::
-                // BufferDeploy(gossiped_deploy_id));
-                // self.dispatch_event(effect_builder, rng, reactor_event)
-                Effects::new()
+                let reactor_event = ParticipatingEvent::Telemetry(telemetry::Event::StageReached {
+                    deploy_hashes: vec![gossiped_deploy_id],
+                    stage: Stage::Gossiped,
+                    timestamp: utils::clock::now(),
+                });
+                self.dispatch_event(effect_builder, rng, reactor_event)
             }
             ParticipatingEvent::AddressGossiperAnnouncement(
-                GossiperAnnouncement::NewCompleteItem(gossiped_address),
+                GossiperAnnouncement::NewCompleteItem(peer_record),
             ) => {
                 let reactor_event = ParticipatingEvent::SmallNetwork(
-                    small_network::Event::PeerAddressReceived(gossiped_address),
+                    small_network::Event::PeerRecordReceived(peer_record),
                 );
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
@@ -1376,10 +1547,18 @@ impl reactor::Reactor for Reactor {
             ParticipatingEvent::BlockProposerAnnouncement(
                 BlockProposerAnnouncement::DeploysExpired(hashes),
             ) => {
+                let reactor_event = ParticipatingEvent::Telemetry(telemetry::Event::StageReached {
+                    deploy_hashes: hashes.clone(),
+                    stage: Stage::Expired,
+                    timestamp: utils::clock::now(),
+                });
+                let mut effects = self.dispatch_event(effect_builder, rng, reactor_event);
+
                 let reactor_event = ParticipatingEvent::EventStreamServer(
                     event_stream_server::Event::DeploysExpired(hashes),
                 );
-                self.dispatch_event(effect_builder, rng, reactor_event)
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                effects
             }
             ParticipatingEvent::LinearChainAnnouncement(
                 LinearChainAnnouncement::NewFinalitySignature(fs),
@@ -1486,13 +1665,48 @@ impl reactor::Reactor for Reactor {
     fn update_metrics(&mut self, event_queue_handle: EventQueueHandle<Self::Event>) {
         self.memory_metrics.estimate(self);
         self.event_queue_metrics
-            .record_event_queue_counts(&event_queue_handle)
+            .record_event_queue_counts(&event_queue_handle);
+        self.health_metrics.record(&self.health_report());
     }
 
     fn maybe_exit(&self) -> Option<ReactorExit> {
-        self.linear_chain
-            .stop_for_upgrade()
-            .then(|| ReactorExit::ProcessShouldExit(ExitCode::Success))
+        if self.linear_chain.stop_for_upgrade() {
+            Some(ReactorExit::ProcessShouldExit(ExitCode::Success))
+        } else if self.consensus.is_drained() {
+            info!("drain complete");
+            Some(ReactorExit::ProcessShouldExit(ExitCode::Drained))
+        } else {
+            None
+        }
+    }
+
+    fn drain(&mut self, _effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        info!("entering drain mode");
+        self.consensus.begin_drain();
+        self.deploy_acceptor.begin_drain();
+        self.small_network.begin_drain();
+        Effects::new()
+    }
+}
+
+impl Reactor {
+    /// Flushes the node's storage to disk and marks the shutdown as clean.
+    pub(crate) fn shutdown_storage(&self) -> Result<(), storage::FatalStorageError> {
+        self.storage.mark_clean_shutdown()
+    }
+
+    /// Returns the health of the node's major components.
+    fn health_report(&self) -> HealthReport {
+        HealthReport::new(vec![
+            (Storage::NAME, self.storage.health()),
+            (
+                SmallNetwork::<ParticipatingEvent, Message>::NAME,
+                self.small_network.health(),
+            ),
+            (EraSupervisor::NAME, self.consensus.health()),
+            (ContractRuntime::NAME, self.contract_runtime.health()),
+            (Indexer::NAME, self.indexer.health()),
+        ])
     }
 }
 
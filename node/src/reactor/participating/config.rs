@@ -2,10 +2,10 @@ use datasize::DataSize;
 use serde::Deserialize;
 
 use crate::{
-    logging::LoggingConfig, types::NodeConfig, BlockProposerConfig, ConsensusConfig,
-    ContractRuntimeConfig, DiagnosticsPortConfig, EventStreamServerConfig, FetcherConfig,
-    GossipConfig, RestServerConfig, RpcServerConfig, SmallNetworkConfig, SpeculativeExecConfig,
-    StorageConfig,
+    logging::LoggingConfig, reactor::watchdog::Config as WatchdogConfig, types::NodeConfig,
+    BlockProposerConfig, ConsensusConfig, ContractRuntimeConfig, DiagnosticsPortConfig,
+    EventStreamServerConfig, FetcherConfig, GossipConfig, IndexerConfig, RestServerConfig,
+    RpcServerConfig, SmallNetworkConfig, SpeculativeExecConfig, StorageConfig, TelemetryConfig,
 };
 
 /// Root configuration.
@@ -42,4 +42,13 @@ pub(crate) struct Config {
     pub(crate) block_proposer: BlockProposerConfig,
     /// Diagnostics port configuration.
     pub(crate) diagnostics_port: DiagnosticsPortConfig,
+    /// Indexer configuration.
+    #[serde(default)]
+    pub(crate) indexer: IndexerConfig,
+    /// Telemetry configuration.
+    #[serde(default)]
+    pub(crate) telemetry: TelemetryConfig,
+    /// Watchdog configuration.
+    #[serde(default)]
+    pub(crate) watchdog: WatchdogConfig,
 }
@@ -3,7 +3,7 @@ use thiserror::Error;
 use crate::{
     components::{
         chain_synchronizer, contract_runtime, contract_runtime::BlockExecutionError,
-        diagnostics_port, small_network, storage,
+        diagnostics_port, indexer, rpc_server, small_network, storage, telemetry,
     },
     utils::{ListeningError, LoadError},
 };
@@ -25,6 +25,10 @@ pub(crate) enum Error {
     #[error("http server listening error: {0}")]
     HttpServerListening(#[from] ListeningError),
 
+    /// `RpcServer` component error.
+    #[error("rpc server error: {0}")]
+    RpcServer(#[from] rpc_server::Error),
+
     /// `Storage` component error.
     #[error("storage error: {0}")]
     Storage(#[from] storage::FatalStorageError),
@@ -61,6 +65,14 @@ pub(crate) enum Error {
     #[error("diagnostics port: {0}")]
     DiagnosticsPort(#[from] diagnostics_port::Error),
 
+    /// `Indexer` component error.
+    #[error("indexer: {0}")]
+    Indexer(#[from] indexer::Error),
+
+    /// `Telemetry` component error.
+    #[error("telemetry: {0}")]
+    Telemetry(#[from] telemetry::Error),
+
     /// Error while loading the signing key pair.
     #[error("signing key pair load error: {0}")]
     LoadSigningKeyPair(#[from] LoadError<CryptoError>),
@@ -96,3 +96,31 @@ impl QueueKind {
         }
     }
 }
+
+/// Behavior of an event's queue once it is congested.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize)]
+pub enum OverflowPolicy {
+    /// Events are never dropped, the queue grows instead.
+    ///
+    /// Required for events whose loss would stall the node, e.g. consensus messages.
+    NeverDrop,
+    /// The oldest events of the same kind are dropped first.
+    ///
+    /// Suitable for events that are superseded by newer ones, e.g. gossip.
+    DropOldest,
+}
+
+impl OverflowPolicy {
+    /// Returns the overflow policy of events on a specific queue that do not declare one.
+    pub(crate) fn for_queue(queue: QueueKind) -> Self {
+        match queue {
+            QueueKind::NetworkLowPriority => OverflowPolicy::DropOldest,
+            QueueKind::Control
+            | QueueKind::NetworkIncoming
+            | QueueKind::NetworkDemand
+            | QueueKind::Network
+            | QueueKind::Regular
+            | QueueKind::Api => OverflowPolicy::NeverDrop,
+        }
+    }
+}
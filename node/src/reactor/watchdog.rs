@@ -0,0 +1,312 @@
+//! Watchdog detecting a stalled reactor.
+//!
+//! The reactor dispatches events one at a time, so a component which never returns from handling
+//! an event stalls the entire node, which then silently falls behind the network. The watchdog
+//! runs on a dedicated thread, independent of the tokio runtime, and checks the reactor's progress
+//! every second. The reactor is considered stalled if a single event has been dispatching for
+//! longer than the stall timeout, or if events are waiting in the queues while none have been
+//! dispatched for as long.
+//!
+//! On a stall, the watchdog raises an alert by logging an error and increasing a metric. If
+//! configured to abort, it then dumps the event queues and panics, which aborts the process so that
+//! its supervisor can restart it.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use prometheus::IntCounter;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use casper_types::TimeDiff;
+
+/// Interval at which the watchdog checks on the reactor.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time to wait for the event queues to be dumped before aborting regardless.
+const DUMP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time after which the reactor is considered stalled.
+const DEFAULT_STALL_TIMEOUT: TimeDiff = TimeDiff::from_seconds(300);
+
+/// Default path of the event queue dump written before aborting.
+const DEFAULT_DUMP_PATH: &str = "stall_queue_dump.json";
+
+/// Action taken by the watchdog on a stall.
+#[derive(Clone, Copy, DataSize, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StallAction {
+    /// Log an error and increase the `reactor_stalls` metric.
+    Alert,
+    /// Alert, then dump the event queues and abort the process.
+    Abort,
+}
+
+/// Watchdog configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Whether the watchdog is enabled.
+    pub enabled: bool,
+    /// Time after which the reactor is considered stalled.
+    pub stall_timeout: TimeDiff,
+    /// Action taken on a stall.
+    pub action: StallAction,
+    /// Path of the event queue dump written before aborting.
+    pub dump_path: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enabled: false,
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
+            action: StallAction::Alert,
+            dump_path: PathBuf::from(DEFAULT_DUMP_PATH),
+        }
+    }
+}
+
+/// Progress of the reactor, as reported to the watchdog.
+#[derive(Debug)]
+struct Progress {
+    /// Description and start of the event currently being dispatched, if any.
+    dispatching: Option<(&'static str, Instant)>,
+    /// Time the reactor last started or finished dispatching an event.
+    last_progress: Instant,
+}
+
+/// A detected stall.
+#[derive(Debug, Eq, PartialEq)]
+enum Stall {
+    /// A single event has been dispatching for too long.
+    Dispatch {
+        event: &'static str,
+        duration: Duration,
+    },
+    /// Events are queued, but none have been dispatched for too long.
+    Queue { duration: Duration },
+}
+
+impl Progress {
+    /// Checks whether the reactor is stalled at `now`, given the number of queued events.
+    fn check(&self, now: Instant, stall_timeout: Duration, queued_events: usize) -> Option<Stall> {
+        if let Some((event, since)) = self.dispatching {
+            let duration = now.saturating_duration_since(since);
+            return (duration > stall_timeout).then(|| Stall::Dispatch { event, duration });
+        }
+        let duration = now.saturating_duration_since(self.last_progress);
+        (queued_events > 0 && duration > stall_timeout).then(|| Stall::Queue { duration })
+    }
+}
+
+/// Handle to the watchdog, used by the reactor runner to report its progress.
+///
+/// Stops the watchdog thread when dropped.
+#[derive(Debug)]
+pub(super) struct Watchdog {
+    /// Progress shared with the watchdog thread.
+    progress: Arc<Mutex<Progress>>,
+    /// Flag telling the watchdog thread to stop.
+    stop: Arc<AtomicBool>,
+    /// The watchdog thread.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Spawns the watchdog thread.
+    ///
+    /// `queued_events` returns the number of events waiting in the queues, `dump_queues` writes
+    /// the event queues to the given file.
+    pub(super) fn spawn<Q, D>(
+        config: &Config,
+        dump_path: PathBuf,
+        stalls: IntCounter,
+        queued_events: Q,
+        dump_queues: D,
+    ) -> io::Result<Self>
+    where
+        Q: Fn() -> usize + Send + 'static,
+        D: FnOnce(BufWriter<File>) + Send + 'static,
+    {
+        let progress = Arc::new(Mutex::new(Progress {
+            dispatching: None,
+            last_progress: Instant::now(),
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let stall_timeout = config.stall_timeout.into();
+        let action = config.action;
+        let thread_progress = Arc::clone(&progress);
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::Builder::new()
+            .name("watchdog".to_string())
+            .spawn(move || {
+                let mut stalled = false;
+                while !thread_stop.load(Ordering::SeqCst) {
+                    thread::park_timeout(CHECK_INTERVAL);
+                    if thread_stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let stall = thread_progress
+                        .lock()
+                        .expect("watchdog progress lock poisoned")
+                        .check(Instant::now(), stall_timeout, queued_events());
+                    match stall {
+                        Some(stall) if !stalled => {
+                            stalled = true;
+                            stalls.inc();
+                            alert(&stall);
+                            if action == StallAction::Abort {
+                                dump_and_abort(&dump_path, dump_queues);
+                            }
+                        }
+                        None if stalled => {
+                            stalled = false;
+                            info!("reactor resumed dispatching events");
+                        }
+                        _ => {}
+                    }
+                }
+            })?;
+
+        Ok(Watchdog {
+            progress,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Reports that the reactor started dispatching an event.
+    pub(super) fn dispatch_started(&self, event: &'static str) {
+        let now = Instant::now();
+        let mut progress = self
+            .progress
+            .lock()
+            .expect("watchdog progress lock poisoned");
+        progress.dispatching = Some((event, now));
+        progress.last_progress = now;
+    }
+
+    /// Reports that the reactor finished dispatching an event.
+    pub(super) fn dispatch_finished(&self) {
+        let mut progress = self
+            .progress
+            .lock()
+            .expect("watchdog progress lock poisoned");
+        progress.dispatching = None;
+        progress.last_progress = Instant::now();
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            if thread.join().is_err() {
+                error!("watchdog thread panicked");
+            }
+        }
+    }
+}
+
+/// Logs an error describing the stall.
+fn alert(stall: &Stall) {
+    match stall {
+        Stall::Dispatch { event, duration } => error!(
+            %event,
+            stalled_for = ?duration,
+            "reactor stalled: event has not finished dispatching"
+        ),
+        Stall::Queue { duration } => error!(
+            stalled_for = ?duration,
+            "reactor stalled: events are queued, but none are being dispatched"
+        ),
+    }
+}
+
+/// Dumps the event queues to `dump_path`, then panics.
+///
+/// The dump is written from a separate thread, since it needs to lock the queues, which the
+/// stalled reactor might be holding. If it does not finish in time, the watchdog panics anyway.
+fn dump_and_abort<D>(dump_path: &Path, dump_queues: D) -> !
+where
+    D: FnOnce(BufWriter<File>) + Send + 'static,
+{
+    match File::create(dump_path) {
+        Ok(file) => {
+            let (sender, receiver) = mpsc::channel();
+            let writer = BufWriter::new(file);
+            let spawned = thread::Builder::new()
+                .name("watchdog-dump".to_string())
+                .spawn(move || {
+                    dump_queues(writer);
+                    let _ = sender.send(());
+                });
+            match spawned {
+                Ok(_) => match receiver.recv_timeout(DUMP_TIMEOUT) {
+                    Ok(()) => info!(dump_path = %dump_path.display(), "dumped event queues"),
+                    Err(_) => warn!("timed out dumping event queues"),
+                },
+                Err(error) => warn!(%error, "failed to spawn thread dumping event queues"),
+            }
+        }
+        Err(error) => {
+            warn!(%error, dump_path = %dump_path.display(), "failed to create event queue dump")
+        }
+    }
+
+    panic!("reactor stalled, aborting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_stalls() {
+        let timeout = Duration::from_secs(60);
+        let start = Instant::now();
+        let later = start + Duration::from_secs(61);
+
+        // An idle reactor with empty queues is never stalled.
+        let mut progress = Progress {
+            dispatching: None,
+            last_progress: start,
+        };
+        assert_eq!(progress.check(later, timeout, 0), None);
+
+        // Queued events must be dispatched within the timeout.
+        assert_eq!(progress.check(start, timeout, 3), None);
+        assert_eq!(
+            progress.check(later, timeout, 3),
+            Some(Stall::Queue {
+                duration: Duration::from_secs(61)
+            })
+        );
+
+        // A single event must be dispatched within the timeout, regardless of the queues.
+        progress.dispatching = Some(("consensus", start));
+        assert_eq!(progress.check(start, timeout, 3), None);
+        assert_eq!(
+            progress.check(later, timeout, 0),
+            Some(Stall::Dispatch {
+                event: "consensus",
+                duration: Duration::from_secs(61)
+            })
+        );
+    }
+}
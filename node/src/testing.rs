@@ -3,6 +3,7 @@
 //! Contains various parts and components to aid writing tests and simulations using the
 //! `casper-node` library.
 
+pub(crate) mod cluster;
 mod condition_check_reactor;
 pub(crate) mod fake_deploy_acceptor;
 pub(crate) mod filter_reactor;
@@ -285,6 +286,9 @@ impl<REv: 'static> ComponentHarness<REv> {
                         ControlAnnouncement::QueueDumpRequest { .. } => {
                             panic!("queue dumps are not supported in the test harness")
                         }
+                        ControlAnnouncement::DrainRequested => {
+                            panic!("draining is not supported in the test harness")
+                        }
                     }
                 } else {
                     debug!(?ev, "ignoring event while looking for a fatal")
@@ -347,6 +351,8 @@ impl ReactorEvent for UnitTestEvent {
 }
 
 /// Helper function to simulate the passage of time.
+///
+/// Fires all tokio timers due within `duration` and moves `utils::clock::now` forward accordingly.
 pub(crate) async fn advance_time(duration: time::Duration) {
     tokio::time::pause();
     tokio::time::advance(duration).await;
@@ -0,0 +1,173 @@
+//! A cluster of full node reactors running inside a single process.
+//!
+//! Builds on top of [`Network`], but cranks its nodes in a fixed order and only advances time once
+//! every node is idle, making multi-node tests reproducible for a given random number generator.
+//! Additionally exposes assertions over the storages and linear chains of all nodes.
+
+use std::time::Duration;
+
+use casper_types::testing::TestRng;
+use fake_instant::FakeClock as Instant;
+use serde::Serialize;
+use tokio::time;
+use tracing::debug;
+
+use super::network::{Network, NetworkedReactor, Nodes};
+use crate::{
+    components::storage::Storage,
+    reactor::Reactor,
+    types::{BlockHash, NodeId},
+};
+
+/// Time by which the clock of an idle cluster is advanced before cranking again.
+const IDLE_TIME_STEP: Duration = Duration::from_millis(10);
+
+/// A reactor that can be part of a cluster.
+pub(crate) trait ClusterReactor: Reactor + NetworkedReactor {
+    /// Returns the storage of the node, if it has been instantiated already.
+    fn storage(&self) -> Option<&Storage>;
+}
+
+/// A deterministic cluster of test reactors.
+///
+/// Nodes are cranked round by round, in the order of their node IDs. Time, both tokio's and the
+/// `FakeClock`'s, is only advanced if no node processed an event during a round.
+pub(crate) struct Cluster<R: Reactor + NetworkedReactor> {
+    /// The underlying network of nodes.
+    network: Network<R>,
+    /// IDs of all nodes, in cranking order.
+    node_ids: Vec<NodeId>,
+}
+
+impl<R> From<Network<R>> for Cluster<R>
+where
+    R: Reactor + NetworkedReactor,
+    R::Event: Serialize,
+    R::Error: From<prometheus::Error>,
+{
+    fn from(network: Network<R>) -> Self {
+        let mut node_ids: Vec<_> = network.nodes().keys().copied().collect();
+        node_ids.sort();
+
+        Cluster { network, node_ids }
+    }
+}
+
+impl<R> Cluster<R>
+where
+    R: Reactor + NetworkedReactor,
+    R::Event: Serialize,
+    R::Error: From<prometheus::Error> + From<R::Error>,
+{
+    /// Cranks every node once, in order, returning the number of events processed.
+    async fn crank_round(&mut self, rng: &mut TestRng) -> usize {
+        let mut event_count = 0;
+        for node_id in &self.node_ids {
+            event_count += self.network.crank(node_id, rng).await;
+        }
+        event_count
+    }
+
+    /// Advances the clock of the cluster by `duration`.
+    async fn advance_time(&mut self, duration: Duration) {
+        Instant::advance_time(duration.as_millis() as u64);
+        time::sleep(duration).await;
+    }
+
+    /// Cranks the cluster until `condition` is true.
+    ///
+    /// # Panics
+    ///
+    /// If the `condition` is not reached inside of `within`, panics.
+    pub(crate) async fn run_until<F>(&mut self, rng: &mut TestRng, condition: F, within: Duration)
+    where
+        F: Fn(&Nodes<R>) -> bool,
+    {
+        time::timeout(within, self.run_until_indefinitely(rng, condition))
+            .await
+            .unwrap_or_else(|_| panic!("cluster did not meet condition within {:?}", within))
+    }
+
+    async fn run_until_indefinitely<F>(&mut self, rng: &mut TestRng, condition: F)
+    where
+        F: Fn(&Nodes<R>) -> bool,
+    {
+        loop {
+            if condition(self.network.nodes()) {
+                debug!("cluster met condition");
+                break;
+            }
+
+            if self.crank_round(rng).await == 0 {
+                self.advance_time(IDLE_TIME_STEP).await;
+            }
+        }
+    }
+}
+
+impl<R> Cluster<R>
+where
+    R: ClusterReactor,
+    R::Event: Serialize,
+    R::Error: From<prometheus::Error>,
+{
+    /// Returns the storages of all nodes that have instantiated one, in cranking order.
+    fn storages(&self) -> Vec<(NodeId, &Storage)> {
+        self.node_ids
+            .iter()
+            .filter_map(|node_id| {
+                let runner = self.network.nodes().get(node_id)?;
+                let storage = runner.reactor().inner().storage()?;
+                Some((*node_id, storage))
+            })
+            .collect()
+    }
+
+    /// Returns the highest block height stored by every node, or `None` if any node has no blocks.
+    pub(crate) fn highest_common_height(&self) -> Option<u64> {
+        self.storages()
+            .into_iter()
+            .map(|(_, storage)| {
+                storage
+                    .read_highest_block_header()
+                    .expect("could not read highest block header")
+                    .map(|header| header.height())
+            })
+            .min()
+            .flatten()
+    }
+
+    /// Asserts that all nodes stored the same linear chain up to their highest common height.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two nodes stored different blocks at the same height, or if a node is missing a
+    /// block below its highest one.
+    pub(crate) fn assert_linear_chains_agree(&self) {
+        let highest_common_height = match self.highest_common_height() {
+            Some(height) => height,
+            None => return,
+        };
+
+        for height in 0..=highest_common_height {
+            let mut expected: Option<(NodeId, BlockHash)> = None;
+
+            for (node_id, storage) in self.storages() {
+                let block_hash = storage
+                    .read_block_header_by_height(height)
+                    .expect("could not read block header")
+                    .unwrap_or_else(|| panic!("{} is missing block at height {}", node_id, height))
+                    .hash();
+
+                match expected {
+                    None => expected = Some((node_id, block_hash)),
+                    Some((expected_node_id, expected_block_hash)) => assert_eq!(
+                        expected_block_hash, block_hash,
+                        "{} and {} disagree on block at height {}",
+                        expected_node_id, node_id, height
+                    ),
+                }
+            }
+        }
+    }
+}
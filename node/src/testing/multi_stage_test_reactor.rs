@@ -24,7 +24,7 @@ use crate::{
         participating::{ParticipatingInitConfig, Reactor as ParticipatingReactor},
         wrap_effects, EventQueueHandle, QueueKind, Reactor, ReactorEvent, ReactorExit, Scheduler,
     },
-    testing::network::NetworkedReactor,
+    testing::{cluster::ClusterReactor, network::NetworkedReactor},
     types::{Chainspec, ChainspecRawBytes, NodeId},
     utils::{self, WithDir, RESOURCES_PATH},
     NodeRng,
@@ -561,3 +561,9 @@ impl NetworkedReactor for MultiStageTestReactor {
         }
     }
 }
+
+impl ClusterReactor for MultiStageTestReactor {
+    fn storage(&self) -> Option<&Storage> {
+        MultiStageTestReactor::storage(self)
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
     reactor::participating,
     testing::{
         self,
+        cluster::Cluster,
         multi_stage_test_reactor::{InitializerReactorConfigWithChainspec, CONFIG_DIR},
         network::{Network, Nodes},
         MultiStageTestReactor,
@@ -284,6 +285,28 @@ async fn run_participating_network() {
     }
 }
 
+#[tokio::test]
+async fn run_participating_cluster() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+
+    const NETWORK_SIZE: usize = 3;
+    let chain = TestChain::new(NETWORK_SIZE, &mut rng).await;
+    let mut cluster = Cluster::from(chain.network);
+
+    info!("Waiting for Era 2 to end");
+    cluster
+        .run_until(&mut rng, has_passed_by_era(2), Duration::from_secs(600))
+        .await;
+
+    assert!(
+        cluster.highest_common_height().is_some(),
+        "all nodes should have stored blocks"
+    );
+    cluster.assert_linear_chains_agree();
+}
+
 #[tokio::test]
 async fn run_equivocator_network() {
     // Test that we won't panic if a node equivocates
@@ -17,7 +17,10 @@
 //! * creation and validation of self-signed certificates
 //!   ([`generate_node_cert`](fn.generate_node_cert.html)),
 //! * signing and verification of arbitrary values using keys from certificates
-//!   ([`Signature`](struct.Signature.html), [`Signed`](struct.Signed.html)), and
+//!   ([`Signature`](struct.Signature.html), [`Signed`](struct.Signed.html)),
+//! * signing and verification of small values by nodes not connected to each other
+//!   ([`CompressedPublicKey`](struct.CompressedPublicKey.html),
+//!   [`RawSignature`](struct.RawSignature.html)), and
 //! * `serde` support for certificates ([`x509_serde`](x509_serde/index.html))
 
 use std::{
@@ -38,7 +41,8 @@ use nid::Nid;
 use openssl::{
     asn1::{Asn1Integer, Asn1IntegerRef, Asn1Time},
     bn::{BigNum, BigNumContext},
-    ec::{self, EcKey},
+    ec::{self, EcKey, EcPoint},
+    ecdsa::EcdsaSig,
     error::ErrorStack,
     hash::{DigestBytes, MessageDigest},
     nid,
@@ -60,7 +64,7 @@ use thiserror::Error;
 mod big_array {
     use serde_big_array::big_array;
 
-    big_array! { BigArray; }
+    big_array! { BigArray; +67, 132, }
 }
 
 /// The chosen signature algorithm (**ECDSA  with SHA512**).
@@ -72,6 +76,12 @@ const SIGNATURE_CURVE: Nid = Nid::SECP521R1;
 /// The chosen signature algorithm (**SHA512**).
 const SIGNATURE_DIGEST: Nid = Nid::SHA512;
 
+/// Length of a public key on `SIGNATURE_CURVE` in compressed form.
+const COMPRESSED_PUBLIC_KEY_LENGTH: usize = 67;
+
+/// Length of each of the `r` and `s` values of an ECDSA signature on `SIGNATURE_CURVE`.
+const SIGNATURE_SCALAR_LENGTH: usize = 66;
+
 /// OpenSSL result type alias.
 ///
 /// Many functions rely solely on `openssl` functions and return this kind of result.
@@ -189,6 +199,132 @@ impl Debug for Signature {
     }
 }
 
+/// Public key in compressed form.
+///
+/// Unlike a certificate, it is small enough to accompany every signed value, allowing the signature
+/// to be verified by nodes which never connected to the signer.
+#[derive(Copy, Clone, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) struct CompressedPublicKey(
+    #[serde(with = "big_array::BigArray")] [u8; COMPRESSED_PUBLIC_KEY_LENGTH],
+);
+
+impl CompressedPublicKey {
+    /// Returns the public key of the given secret key.
+    pub(crate) fn from_secret_key(secret_key: &PKeyRef<Private>) -> Result<Self, ValidationError> {
+        let ec_key = secret_key
+            .ec_key()
+            .map_err(ValidationError::CouldNotExtractEcKey)?;
+        let mut big_num_context =
+            BigNumContext::new().map_err(ValidationError::BigNumContextNotAvailable)?;
+        let buf = ec_key
+            .public_key()
+            .to_bytes(
+                ec::EcGroup::from_curve_name(SIGNATURE_CURVE)
+                    .expect("broken constant SIGNATURE_CURVE")
+                    .as_ref(),
+                ec::PointConversionForm::COMPRESSED,
+                &mut big_num_context,
+            )
+            .map_err(ValidationError::PublicKeyEncodingFailed)?;
+        let bytes = buf
+            .as_slice()
+            .try_into()
+            .map_err(|_| ValidationError::WrongCurve)?;
+        Ok(CompressedPublicKey(bytes))
+    }
+
+    /// Returns the compressed encoding of the public key.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the fingerprint of the public key, matching the one of a certificate for the key.
+    pub(crate) fn fingerprint(&self) -> KeyFingerprint {
+        KeyFingerprint(Sha512::new(&self.0))
+    }
+
+    /// Decodes the public key.
+    fn decode(&self) -> Result<EcKey<Public>, ValidationError> {
+        let group =
+            ec::EcGroup::from_curve_name(SIGNATURE_CURVE).expect("broken constant SIGNATURE_CURVE");
+        let mut big_num_context =
+            BigNumContext::new().map_err(ValidationError::BigNumContextNotAvailable)?;
+        let point = EcPoint::from_bytes(&group, &self.0, &mut big_num_context)
+            .map_err(ValidationError::KeyFailsCheck)?;
+        let ec_key = EcKey::from_public_key(&group, &point)
+            .map_err(ValidationError::CouldNotExtractEcKey)?;
+        ec_key.check_key().map_err(ValidationError::KeyFailsCheck)?;
+        Ok(ec_key)
+    }
+}
+
+impl Debug for CompressedPublicKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CompressedPublicKey({:10})", HexFmt(&self.0[..]))
+    }
+}
+
+/// Fixed-size ECDSA signature over the SHA512 hash of the signed data.
+///
+/// Consists of the `r` and `s` values of the signature, each padded to the same length.
+#[derive(Copy, Clone, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) struct RawSignature(
+    #[serde(with = "big_array::BigArray")] [u8; 2 * SIGNATURE_SCALAR_LENGTH],
+);
+
+impl RawSignature {
+    /// Signs `data` using the given secret key.
+    pub(crate) fn create(secret_key: &PKeyRef<Private>, data: &[u8]) -> SslResult<Self> {
+        let ec_key = secret_key.ec_key()?;
+        let signature = EcdsaSig::sign(Sha512::new(data).bytes(), &ec_key)?;
+
+        let r = signature
+            .r()
+            .to_vec_padded(SIGNATURE_SCALAR_LENGTH as i32)?;
+        let s = signature
+            .s()
+            .to_vec_padded(SIGNATURE_SCALAR_LENGTH as i32)?;
+        let mut bytes = [0; 2 * SIGNATURE_SCALAR_LENGTH];
+        bytes[..SIGNATURE_SCALAR_LENGTH].copy_from_slice(&r);
+        bytes[SIGNATURE_SCALAR_LENGTH..].copy_from_slice(&s);
+        Ok(RawSignature(bytes))
+    }
+
+    /// Returns the `r` and `s` values of the signature, concatenated.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Verifies the signature of `data` against the given public key.
+    pub(crate) fn verify(
+        &self,
+        public_key: &CompressedPublicKey,
+        data: &[u8],
+    ) -> Result<(), ValidationError> {
+        let ec_key = public_key.decode()?;
+        let r = BigNum::from_slice(&self.0[..SIGNATURE_SCALAR_LENGTH])
+            .map_err(ValidationError::FailedToValidateSignature)?;
+        let s = BigNum::from_slice(&self.0[SIGNATURE_SCALAR_LENGTH..])
+            .map_err(ValidationError::FailedToValidateSignature)?;
+        let signature = EcdsaSig::from_private_components(r, s)
+            .map_err(ValidationError::FailedToValidateSignature)?;
+        if signature
+            .verify(Sha512::new(data).bytes(), &ec_key)
+            .map_err(ValidationError::FailedToValidateSignature)?
+        {
+            Ok(())
+        } else {
+            Err(ValidationError::InvalidSignature)
+        }
+    }
+}
+
+impl Debug for RawSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "RawSignature({:10})", HexFmt(&self.0[..]))
+    }
+}
+
 /// TLS certificate.
 ///
 /// Thin wrapper around `X509` enabling things like Serde serialization and fingerprint caching.
@@ -903,6 +1039,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn raw_signature_roundtrip() {
+        let (cert, secret_key) = generate_node_cert().expect("failed to generate key, cert pair");
+        let tls_cert = validate_self_signed_cert(cert).expect("generated cert is not valid");
+
+        let public_key =
+            CompressedPublicKey::from_secret_key(&secret_key).expect("failed to encode public key");
+        assert_eq!(public_key.fingerprint(), tls_cert.public_key_fingerprint());
+
+        let signature = RawSignature::create(&secret_key, b"peer record").expect("failed to sign");
+        signature
+            .verify(&public_key, b"peer record")
+            .expect("signature should be valid");
+
+        let error = signature
+            .verify(&public_key, b"other record")
+            .expect_err("signature should not be valid for other data");
+        assert!(matches!(error, ValidationError::InvalidSignature));
+
+        let other_secret_key = generate_private_key().expect("failed to generate private key");
+        let other_public_key = CompressedPublicKey::from_secret_key(&other_secret_key)
+            .expect("failed to encode public key");
+        let error = signature
+            .verify(&other_public_key, b"peer record")
+            .expect_err("signature should not be valid for other key");
+        assert!(matches!(error, ValidationError::InvalidSignature));
+    }
+
     fn make_ca_signed_cert(private_key: PKey<Private>, ca_private_key: PKey<Private>) -> X509 {
         let mut builder = X509Builder::new().unwrap();
         builder.set_version(2).unwrap();
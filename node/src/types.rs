@@ -7,6 +7,7 @@ pub mod chainspec;
 mod deploy;
 pub mod error;
 mod exit_code;
+mod health;
 mod item;
 pub mod json_compatibility;
 mod node_config;
@@ -22,30 +23,34 @@ use rand_chacha::ChaCha20Rng;
 
 pub use available_block_range::AvailableBlockRange;
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
+    json_compatibility::{JsonBlock, JsonBlockHeader, JsonProof},
     Block, BlockAndDeploys, BlockBody, BlockHash, BlockHeader, BlockSignatures, FinalitySignature,
     FinalizedBlock,
 };
 pub(crate) use block::{
-    BlockHashAndHeight, BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId,
-    BlockPayload, BlockWithMetadata,
+    BlockBodyOrChunk, BlockBodyOrChunkId, BlockHashAndHeight, BlockHeaderWithMetadata,
+    BlockHeadersBatch, BlockHeadersBatchId, BlockPayload, BlockWithMetadata,
 };
 pub use chainspec::Chainspec;
 pub(crate) use chainspec::{ActivationPoint, ChainspecRawBytes};
 pub use datasize::DataSize;
 pub use deploy::{
-    Approval, Deploy, DeployConfigurationFailure, DeployHash, DeployHeader, DeployMetadata,
-    DeployMetadataExt, DeployOrTransferHash, DeployWithApprovals, DeployWithFinalizedApprovals,
-    Error as DeployError, ExcessiveSizeError as ExcessiveSizeDeployError, FinalizedApprovals,
-    FinalizedApprovalsWithId,
+    Approval, ApprovalsHash, Deploy, DeployConfigurationFailure, DeployHash, DeployHeader,
+    DeployMetadata, DeployMetadataExt, DeployOrTransferHash, DeployValidationReport,
+    DeployWithApprovals, DeployWithFinalizedApprovals, Error as DeployError,
+    ExcessiveSizeError as ExcessiveSizeDeployError, FinalizedApprovals, FinalizedApprovalsWithId,
 };
 pub use error::BlockValidationError;
 pub use exit_code::ExitCode;
+pub use health::{Health, HealthReport, HealthStatus};
 pub(crate) use item::{Item, Tag};
-pub use node_config::NodeConfig;
+pub use node_config::{NodeConfig, NodeMode};
 pub(crate) use node_id::NodeId;
 pub use peers_map::PeersMap;
-pub use status_feed::{ChainspecInfo, GetStatusResult, NodeState, StatusFeed};
+pub use status_feed::{
+    ChainspecInfo, GetSignedStatusResult, GetStatusResult, NodeState, SignedStatusPayload,
+    StatusFeed,
+};
 
 /// An object-safe RNG trait that requires a cryptographically strong random number generator.
 pub trait CryptoRngCore: CryptoRng + RngCore {}
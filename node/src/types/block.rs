@@ -20,7 +20,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use casper_hashing::Digest;
+use casper_hashing::{ChunkWithProof, ChunkWithProofVerificationError, Digest};
 #[cfg(any(feature = "testing", test))]
 use casper_types::testing::TestRng;
 use casper_types::{
@@ -1296,6 +1296,146 @@ impl FromBytes for BlockBody {
     }
 }
 
+/// The ID of a [`BlockBodyOrChunk`]: the hash of the block and the index of the chunk.
+///
+/// Block bodies small enough to be sent whole only have the index 0.
+#[derive(DataSize, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub(crate) struct BlockBodyOrChunkId {
+    block_hash: BlockHash,
+    index: u64,
+}
+
+impl BlockBodyOrChunkId {
+    /// Creates the ID of the chunk with the given index of the block's body.
+    pub(crate) fn new(block_hash: BlockHash, index: u64) -> Self {
+        BlockBodyOrChunkId { block_hash, index }
+    }
+
+    /// Returns the hash of the block.
+    pub(crate) fn block_hash(&self) -> &BlockHash {
+        &self.block_hash
+    }
+
+    /// Returns the index of the chunk.
+    pub(crate) fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl Display for BlockBodyOrChunkId {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "body chunk {} of block {}",
+            self.index, self.block_hash
+        )
+    }
+}
+
+/// A block body, or a chunk of its serialized bytes with a Merkle proof if the body is too large
+/// to be sent in a single message.
+///
+/// The chunks can only be verified against each other: The reassembled body needs to be checked
+/// against the block header's body hash.
+#[derive(DataSize, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum BlockBodyOrChunk {
+    /// The whole block body.
+    BlockBody {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The block body.
+        body: Box<BlockBody>,
+    },
+    /// A chunk of the serialized block body.
+    ChunkWithProof {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The chunk with the proof of its inclusion in the serialized body.
+        chunk_with_proof: ChunkWithProof,
+    },
+}
+
+impl BlockBodyOrChunk {
+    /// Block bodies with more serialized bytes than this are sent in chunks of this size.
+    #[cfg(test)]
+    pub(crate) const CHUNK_SIZE_BYTES: usize = 100;
+
+    /// Block bodies with more serialized bytes than this are sent in chunks of this size.
+    #[cfg(not(test))]
+    pub(crate) const CHUNK_SIZE_BYTES: usize = 1024 * 1024;
+
+    /// Returns the whole body if it is small enough, or the chunk with the given index otherwise.
+    ///
+    /// Returns `None` if there is no chunk with that index.
+    pub(crate) fn new(block_hash: BlockHash, body: &BlockBody, index: u64) -> Option<Self> {
+        let serialized_body = body
+            .to_bytes()
+            .unwrap_or_else(|error| panic!("should serialize block body: {}", error));
+        if serialized_body.len() <= Self::CHUNK_SIZE_BYTES {
+            return (index == 0).then(|| BlockBodyOrChunk::BlockBody {
+                block_hash,
+                body: Box::new(body.clone()),
+            });
+        }
+        let chunk_with_proof =
+            ChunkWithProof::new_with_chunk_size(&serialized_body, index, Self::CHUNK_SIZE_BYTES)
+                .ok()?;
+        Some(BlockBodyOrChunk::ChunkWithProof {
+            block_hash,
+            chunk_with_proof,
+        })
+    }
+}
+
+impl Display for BlockBodyOrChunk {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            BlockBodyOrChunk::BlockBody { block_hash, .. } => {
+                write!(formatter, "body of block {}", block_hash)
+            }
+            BlockBodyOrChunk::ChunkWithProof {
+                block_hash,
+                chunk_with_proof,
+            } => write!(
+                formatter,
+                "body chunk {} of {} of block {}",
+                chunk_with_proof.proof().index(),
+                chunk_with_proof.proof().count(),
+                block_hash
+            ),
+        }
+    }
+}
+
+impl Item for BlockBodyOrChunk {
+    type Id = BlockBodyOrChunkId;
+    type ValidationError = ChunkWithProofVerificationError;
+
+    const TAG: Tag = Tag::BlockBodyOrChunk;
+    const ID_IS_COMPLETE_ITEM: bool = false;
+
+    fn validate(&self) -> Result<(), Self::ValidationError> {
+        match self {
+            BlockBodyOrChunk::BlockBody { .. } => Ok(()),
+            BlockBodyOrChunk::ChunkWithProof {
+                chunk_with_proof, ..
+            } => chunk_with_proof.verify(),
+        }
+    }
+
+    fn id(&self) -> Self::Id {
+        match self {
+            BlockBodyOrChunk::BlockBody { block_hash, .. } => {
+                BlockBodyOrChunkId::new(*block_hash, 0)
+            }
+            BlockBodyOrChunk::ChunkWithProof {
+                block_hash,
+                chunk_with_proof,
+            } => BlockBodyOrChunkId::new(*block_hash, chunk_with_proof.proof().index()),
+        }
+    }
+}
+
 /// A storage representation of finality signatures with the associated block hash.
 #[derive(Clone, Debug, PartialOrd, Ord, Hash, Serialize, Deserialize, DataSize, Eq, PartialEq)]
 pub struct BlockSignatures {
@@ -2094,8 +2234,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -2107,12 +2246,17 @@ impl FinalitySignature {
 
     /// Verifies whether the signature is correct.
     pub fn verify(&self) -> Result<(), crypto::Error> {
-        // NOTE: This needs to be in sync with the `new` constructor.
-        let mut bytes = self.block_hash.inner().into_vec();
-        bytes.extend_from_slice(&self.era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
         crypto::verify(bytes, &self.signature, &self.public_key)
     }
 
+    /// Returns the data a finality signature for the given block and era is computed over.
+    pub(crate) fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     /// Returns a random `FinalitySignature` for the provided `block_hash` and `era_id`.
     #[cfg(any(feature = "testing", test))]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
@@ -2802,4 +2946,44 @@ mod tests {
             BlockHeadersBatch::validate(&invalid_highest_batch, &batch_id, &trusted,)
         );
     }
+
+    #[test]
+    fn block_body_or_chunk_reassembles_large_body() {
+        let mut rng = TestRng::new();
+        let block_hash = BlockHash::random(&mut rng);
+
+        let small_body = BlockBody::new(PublicKey::random(&mut rng), vec![], vec![]);
+        assert!(matches!(
+            BlockBodyOrChunk::new(block_hash, &small_body, 0),
+            Some(BlockBodyOrChunk::BlockBody { .. })
+        ));
+        assert!(BlockBodyOrChunk::new(block_hash, &small_body, 1).is_none());
+
+        let deploy_hashes = iter::repeat_with(|| DeployHash::random(&mut rng))
+            .take(10)
+            .collect();
+        let large_body = BlockBody::new(PublicKey::random(&mut rng), deploy_hashes, vec![]);
+        let mut serialized_body = vec![];
+        for index in 0.. {
+            match BlockBodyOrChunk::new(block_hash, &large_body, index) {
+                Some(item @ BlockBodyOrChunk::ChunkWithProof { .. }) => {
+                    assert!(item.validate().is_ok());
+                    assert_eq!(item.id(), BlockBodyOrChunkId::new(block_hash, index));
+                    if let BlockBodyOrChunk::ChunkWithProof {
+                        chunk_with_proof, ..
+                    } = item
+                    {
+                        serialized_body.extend(chunk_with_proof.into_chunk().iter());
+                    }
+                }
+                Some(BlockBodyOrChunk::BlockBody { .. }) => panic!("body should be chunked"),
+                None => {
+                    assert!(index > 1);
+                    break;
+                }
+            }
+        }
+        let reassembled: BlockBody = bytesrepr::deserialize(serialized_body).unwrap();
+        assert_eq!(reassembled, large_body);
+    }
 }
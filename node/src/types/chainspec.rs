@@ -4,8 +4,10 @@
 mod accounts_config;
 mod activation_point;
 mod chainspec_raw_bytes;
+mod check;
 mod core_config;
 mod deploy_config;
+mod emergency;
 mod error;
 mod global_state_update;
 mod highway_config;
@@ -35,10 +37,12 @@ use casper_types::{
 
 #[cfg(test)]
 pub(crate) use self::accounts_config::{AccountConfig, ValidatorConfig};
+pub(crate) use self::check::{check_chainspec, check_upgrade};
 pub use self::error::Error;
 pub(crate) use self::{
     accounts_config::AccountsConfig, activation_point::ActivationPoint,
     chainspec_raw_bytes::ChainspecRawBytes, core_config::CoreConfig, deploy_config::DeployConfig,
+    emergency::{EmergencyRestart, EmergencyRestartError},
     global_state_update::GlobalStateUpdate, highway_config::HighwayConfig,
     network_config::NetworkConfig, protocol_config::ProtocolConfig,
 };
@@ -215,6 +219,7 @@ impl From<&Chainspec> for ExecConfig {
                 .activation_point
                 .genesis_timestamp()
                 .map_or(0, |timestamp| timestamp.millis()),
+            chainspec.core_config.native_token_issuers(),
         )
     }
 }
@@ -228,6 +233,8 @@ mod tests {
 
     use casper_execution_engine::shared::{
         host_function_costs::{HostFunction, HostFunctionCosts},
+        memory_costs::MemoryCosts,
+        module_limits::ModuleLimits,
         opcode_costs::OpcodeCosts,
         storage_costs::StorageCosts,
         wasm_config::WasmConfig,
@@ -282,6 +289,9 @@ mod tests {
             print: HostFunction::new(123, [0, 1]),
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
             random_bytes: HostFunction::new(123, [0, 1]),
+            remove_contract: HostFunction::new(142, [0, 1, 2, 3, 4, 5]),
+            get_block_context: HostFunction::new(143, [0]),
+            narrow_uref: HostFunction::new(144, [0, 1, 2, 3]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -289,12 +299,18 @@ mod tests {
             19, // max_stack_height
             EXPECTED_GENESIS_COSTS,
             EXPECTED_GENESIS_STORAGE_COSTS,
+            EXPECTED_GENESIS_MEMORY_COSTS,
             *EXPECTED_GENESIS_HOST_FUNCTION_COSTS,
+            EXPECTED_GENESIS_MODULE_LIMITS,
         )
     });
 
     const EXPECTED_GENESIS_STORAGE_COSTS: StorageCosts = StorageCosts::new(101);
 
+    const EXPECTED_GENESIS_MEMORY_COSTS: MemoryCosts = MemoryCosts::new(102, 103);
+
+    const EXPECTED_GENESIS_MODULE_LIMITS: ModuleLimits = ModuleLimits::new(true, 30, 31, 32, 33);
+
     const EXPECTED_GENESIS_COSTS: OpcodeCosts = OpcodeCosts {
         bit: 13,
         add: 14,
@@ -0,0 +1,342 @@
+//! Consistency checks of a chainspec which go beyond what is enforced when parsing it, and
+//! compatibility checks of a chainspec against the one of the previous protocol version.
+
+use std::fmt::{self, Display, Formatter};
+
+use toml::Value;
+
+use casper_types::TimeDiff;
+
+use super::{parse_toml::TomlChainspec, ActivationPoint, Chainspec, ChainspecRawBytes};
+
+/// The problems found while checking a chainspec.
+#[derive(Default, Debug)]
+pub(crate) struct CheckReport {
+    /// Problems which would cause the node to fail or misbehave.
+    pub(crate) errors: Vec<String>,
+    /// Settings which are likely mistakes, but would not prevent the network from running.
+    pub(crate) warnings: Vec<String>,
+}
+
+impl CheckReport {
+    /// Returns `true` if no errors were found.
+    pub(crate) fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors.push(message);
+    }
+
+    fn warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    /// Adds the problems found by another check.
+    pub(crate) fn extend(&mut self, other: CheckReport) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+}
+
+impl Display for CheckReport {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(formatter, "error: {}", error)?;
+        }
+        for warning in &self.warnings {
+            writeln!(formatter, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the cross-field invariants of the given chainspec, and that the TOML file it was parsed
+/// from doesn't contain any settings which are silently ignored.
+pub(crate) fn check_chainspec(
+    chainspec: &Chainspec,
+    chainspec_raw_bytes: &ChainspecRawBytes,
+) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    if !chainspec.is_valid() {
+        report.error("chainspec failed validation; see the log output for details".to_string());
+    }
+
+    let core_config = &chainspec.core_config;
+    let highway_config = &chainspec.highway_config;
+    let min_round_length = highway_config.min_round_length();
+    let max_round_length = TimeDiff::from(
+        1u64.checked_shl(u32::from(highway_config.maximum_round_exponent))
+            .unwrap_or(u64::MAX),
+    );
+    if core_config.era_duration.millis() == 0 && core_config.minimum_era_height == 0 {
+        report.error(
+            "[core] era_duration and minimum_era_height are both zero, so eras would never contain \
+            any blocks"
+                .to_string(),
+        );
+    } else if core_config.era_duration.millis() > 0 {
+        let min_era_length = min_round_length
+            .millis()
+            .saturating_mul(core_config.minimum_era_height);
+        if core_config.era_duration.millis() < min_era_length {
+            report.error(format!(
+                "[core] era_duration ({}) is shorter than minimum_era_height ({}) blocks at the \
+                minimum round length ({}), so it can never be reached",
+                core_config.era_duration, core_config.minimum_era_height, min_round_length
+            ));
+        }
+        if core_config.era_duration < max_round_length {
+            report.warning(format!(
+                "[core] era_duration ({}) is shorter than the maximum round length ({})",
+                core_config.era_duration, max_round_length
+            ));
+        }
+    }
+
+    let deploy_config = &chainspec.deploy_config;
+    if deploy_config.max_deploy_size > deploy_config.max_block_size {
+        report.error(format!(
+            "[deploys] max_deploy_size ({}) exceeds max_block_size ({})",
+            deploy_config.max_deploy_size, deploy_config.max_block_size
+        ));
+    }
+    if deploy_config.max_block_size > chainspec.network_config.maximum_net_message_size {
+        report.error(format!(
+            "[deploys] max_block_size ({}) exceeds [network] maximum_net_message_size ({}), so \
+            full blocks could not be gossiped",
+            deploy_config.max_block_size, chainspec.network_config.maximum_net_message_size
+        ));
+    }
+
+    match chainspec.protocol_config.activation_point {
+        ActivationPoint::Genesis(_) => {
+            if chainspec.protocol_config.global_state_update.is_some() {
+                report.error(
+                    "a global state update is only applied at an upgrade, but the activation point \
+                    is a genesis timestamp"
+                        .to_string(),
+                );
+            }
+            if chainspec.protocol_config.emergency_checkpoint.is_some() {
+                report.error(
+                    "[protocol] emergency_checkpoint is only used in an emergency restart, but the \
+                    activation point is a genesis timestamp"
+                        .to_string(),
+                );
+            }
+            if chainspec
+                .network_config
+                .accounts_config
+                .accounts()
+                .is_empty()
+            {
+                report.error("genesis requires at least one account in accounts.toml".to_string());
+            }
+        }
+        ActivationPoint::EraId(era_id) => {
+            if era_id.is_genesis() {
+                report.error(
+                    "[protocol] activation_point must be a genesis timestamp or an era ID greater \
+                    than 0"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    report.extend(check_unused_keys(chainspec_raw_bytes.chainspec_bytes()));
+    report
+}
+
+/// Checks the given chainspec for changes relative to the chainspec of the previous protocol
+/// version which are not allowed in an upgrade.
+pub(crate) fn check_upgrade(previous: &Chainspec, chainspec: &Chainspec) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    if chainspec.network_config.name != previous.network_config.name {
+        report.error(format!(
+            "[network] name changed from {} to {}",
+            previous.network_config.name, chainspec.network_config.name
+        ));
+    }
+
+    if chainspec.protocol_version() <= previous.protocol_version() {
+        report.error(format!(
+            "[protocol] version {} is not greater than the previous version {}",
+            chainspec.protocol_version(),
+            previous.protocol_version()
+        ));
+    }
+
+    match (
+        previous.protocol_config.activation_point,
+        chainspec.protocol_config.activation_point,
+    ) {
+        (_, ActivationPoint::Genesis(_)) => report.error(
+            "[protocol] activation_point must be an era ID in an upgrade, not a genesis timestamp"
+                .to_string(),
+        ),
+        (ActivationPoint::EraId(previous_era_id), ActivationPoint::EraId(era_id))
+            if era_id <= previous_era_id =>
+        {
+            report.error(format!(
+                "[protocol] activation_point {} is not after the previous activation point {}",
+                era_id, previous_era_id
+            ))
+        }
+        _ => (),
+    }
+
+    if chainspec.network_config.accounts_config != previous.network_config.accounts_config
+        && !chainspec
+            .network_config
+            .accounts_config
+            .accounts()
+            .is_empty()
+    {
+        report.warning(
+            "accounts.toml differs from the previous version; genesis accounts are only used at \
+            genesis"
+                .to_string(),
+        );
+    }
+
+    report
+}
+
+/// Returns warnings about settings in the chainspec TOML file which are not used when parsing it,
+/// e.g. misspelled entries of a cost table.
+fn check_unused_keys(chainspec_bytes: &[u8]) -> CheckReport {
+    let mut report = CheckReport::default();
+    let raw: Value = match toml::from_slice(chainspec_bytes) {
+        Ok(raw) => raw,
+        Err(error) => {
+            report.error(format!("failed to parse chainspec: {}", error));
+            return report;
+        }
+    };
+    let parsed = match raw.clone().try_into::<TomlChainspec>() {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            report.error(format!("failed to parse chainspec: {}", error));
+            return report;
+        }
+    };
+    let reserialized = match Value::try_from(&parsed) {
+        Ok(reserialized) => reserialized,
+        Err(error) => {
+            report.error(format!("failed to serialize chainspec: {}", error));
+            return report;
+        }
+    };
+
+    let mut unknown_keys = vec![];
+    collect_unknown_keys(&raw, &reserialized, &mut vec![], &mut unknown_keys);
+    for path in unknown_keys {
+        // Keys which are aliases of known settings are not in the re-serialized chainspec either,
+        // so only report those the chainspec doesn't depend on.
+        let mut without_key = raw.clone();
+        remove_key(&mut without_key, &path);
+        if without_key.try_into::<TomlChainspec>().ok().as_ref() == Some(&parsed) {
+            report.warning(format!("unknown setting {} is ignored", path.join(".")));
+        }
+    }
+    report
+}
+
+/// Collects the paths of all keys present in `raw` but not in `known`.
+fn collect_unknown_keys(
+    raw: &Value,
+    known: &Value,
+    path: &mut Vec<String>,
+    unknown_keys: &mut Vec<Vec<String>>,
+) {
+    if let (Value::Table(raw_table), Value::Table(known_table)) = (raw, known) {
+        for (key, raw_value) in raw_table {
+            path.push(key.clone());
+            match known_table.get(key) {
+                Some(known_value) => {
+                    collect_unknown_keys(raw_value, known_value, path, unknown_keys)
+                }
+                None => unknown_keys.push(path.clone()),
+            }
+            path.pop();
+        }
+    }
+}
+
+/// Removes the key at the given path.
+fn remove_key(value: &mut Value, path: &[String]) {
+    if let (Value::Table(table), Some((key, rest))) = (value, path.split_first()) {
+        if rest.is_empty() {
+            table.remove(key);
+        } else if let Some(child) = table.get_mut(key) {
+            remove_key(child, rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{EraId, ProtocolVersion};
+
+    use super::*;
+    use crate::utils::Loadable;
+
+    fn production_chainspec() -> (Chainspec, ChainspecRawBytes) {
+        <(Chainspec, ChainspecRawBytes)>::from_resources("production")
+    }
+
+    #[test]
+    fn bundled_production_chainspec_should_pass() {
+        let (chainspec, chainspec_raw_bytes) = production_chainspec();
+        let report = check_chainspec(&chainspec, &chainspec_raw_bytes);
+        assert!(report.errors.is_empty(), "{}", report);
+        assert!(report.warnings.is_empty(), "{}", report);
+    }
+
+    #[test]
+    fn should_reject_unreachable_era_duration() {
+        let (mut chainspec, chainspec_raw_bytes) = production_chainspec();
+        chainspec.core_config.era_duration = TimeDiff::from_seconds(60);
+        let report = check_chainspec(&chainspec, &chainspec_raw_bytes);
+        assert_eq!(report.errors.len(), 1, "{}", report);
+        assert!(report.errors[0].contains("era_duration"));
+    }
+
+    #[test]
+    fn should_warn_about_unknown_cost_table_entries() {
+        let (chainspec, chainspec_raw_bytes) = production_chainspec();
+        let mut raw: Value = toml::from_slice(chainspec_raw_bytes.chainspec_bytes()).unwrap();
+        raw["wasm"]["opcode_costs"]
+            .as_table_mut()
+            .unwrap()
+            .insert("multiply".to_string(), Value::Integer(240));
+        let raw_bytes = ChainspecRawBytes::new(toml::to_vec(&raw).unwrap().into(), None, None);
+
+        let report = check_chainspec(&chainspec, &raw_bytes);
+        assert!(report.errors.is_empty(), "{}", report);
+        assert_eq!(
+            report.warnings,
+            vec!["unknown setting wasm.opcode_costs.multiply is ignored".to_string()]
+        );
+    }
+
+    #[test]
+    fn should_reject_illegal_upgrade() {
+        let (previous, _) = production_chainspec();
+        let (mut chainspec, _) = production_chainspec();
+        assert_eq!(check_upgrade(&previous, &chainspec).errors.len(), 2);
+
+        chainspec.protocol_config.version = ProtocolVersion::from_parts(2, 0, 0);
+        chainspec.protocol_config.activation_point = ActivationPoint::EraId(EraId::new(4000));
+        assert!(check_upgrade(&previous, &chainspec).is_ok());
+
+        chainspec.network_config.name = "other-network".to_string();
+        let report = check_upgrade(&previous, &chainspec);
+        assert_eq!(report.errors.len(), 1, "{}", report);
+        assert!(report.errors[0].contains("name"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use datasize::DataSize;
 use num::rational::Ratio;
 #[cfg(test)]
@@ -7,11 +9,12 @@ use serde::{Deserialize, Serialize};
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
+    account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes},
-    TimeDiff,
+    PublicKey, TimeDiff,
 };
 
-#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[derive(Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct CoreConfig {
@@ -39,6 +42,26 @@ pub struct CoreConfig {
     pub(crate) minimum_delegation_amount: u64,
     /// Enables strict arguments checking when calling a contract.
     pub(crate) strict_argument_checking: bool,
+    /// Allows accounts to delegate the authorization of their deploys to a contract.
+    pub(crate) allow_authorization_contracts: bool,
+    /// Maximum amount of gas an authorization contract can use to authorize a deploy.
+    pub(crate) authorization_contract_gas_limit: u64,
+    /// Maximum number of matured unbonding purses paid out per era-end step.
+    pub(crate) max_unbondings_per_step: u32,
+    /// Native tokens held in the mint alongside motes, mapping the symbol of each token to the
+    /// public key of its issuer.
+    pub(crate) native_tokens: BTreeMap<String, PublicKey>,
+}
+
+impl CoreConfig {
+    /// Returns the native tokens, mapping the symbol of each token to the account hash of its
+    /// issuer.
+    pub(crate) fn native_token_issuers(&self) -> BTreeMap<String, AccountHash> {
+        self.native_tokens
+            .iter()
+            .map(|(token, issuer)| (token.clone(), issuer.to_account_hash()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -60,6 +83,12 @@ impl CoreConfig {
         let max_runtime_call_stack_height = rng.gen();
         let minimum_delegation_amount = rng.gen::<u32>() as u64;
         let strict_argument_checking = rng.gen();
+        let allow_authorization_contracts = rng.gen();
+        let authorization_contract_gas_limit = rng.gen::<u32>() as u64;
+        let max_unbondings_per_step = rng.gen();
+        let native_tokens = (0..rng.gen_range(0..3))
+            .map(|_| (rng.gen::<u32>().to_string(), PublicKey::random(rng)))
+            .collect();
 
         CoreConfig {
             era_duration,
@@ -74,6 +103,10 @@ impl CoreConfig {
             max_runtime_call_stack_height,
             minimum_delegation_amount,
             strict_argument_checking,
+            allow_authorization_contracts,
+            authorization_contract_gas_limit,
+            max_unbondings_per_step,
+            native_tokens,
         }
     }
 }
@@ -93,6 +126,10 @@ impl ToBytes for CoreConfig {
         buffer.extend(self.max_runtime_call_stack_height.to_bytes()?);
         buffer.extend(self.minimum_delegation_amount.to_bytes()?);
         buffer.extend(self.strict_argument_checking.to_bytes()?);
+        buffer.extend(self.allow_authorization_contracts.to_bytes()?);
+        buffer.extend(self.authorization_contract_gas_limit.to_bytes()?);
+        buffer.extend(self.max_unbondings_per_step.to_bytes()?);
+        buffer.extend(self.native_tokens.to_bytes()?);
         Ok(buffer)
     }
 
@@ -109,6 +146,10 @@ impl ToBytes for CoreConfig {
             + self.max_runtime_call_stack_height.serialized_length()
             + self.minimum_delegation_amount.serialized_length()
             + self.strict_argument_checking.serialized_length()
+            + self.allow_authorization_contracts.serialized_length()
+            + self.authorization_contract_gas_limit.serialized_length()
+            + self.max_unbondings_per_step.serialized_length()
+            + self.native_tokens.serialized_length()
     }
 }
 
@@ -126,6 +167,10 @@ impl FromBytes for CoreConfig {
         let (max_runtime_call_stack_height, remainder) = u32::from_bytes(remainder)?;
         let (minimum_delegation_amount, remainder) = u64::from_bytes(remainder)?;
         let (strict_argument_checking, remainder) = bool::from_bytes(remainder)?;
+        let (allow_authorization_contracts, remainder) = bool::from_bytes(remainder)?;
+        let (authorization_contract_gas_limit, remainder) = u64::from_bytes(remainder)?;
+        let (max_unbondings_per_step, remainder) = u32::from_bytes(remainder)?;
+        let (native_tokens, remainder) = BTreeMap::<String, PublicKey>::from_bytes(remainder)?;
         let config = CoreConfig {
             era_duration,
             minimum_era_height,
@@ -139,6 +184,10 @@ impl FromBytes for CoreConfig {
             max_runtime_call_stack_height,
             minimum_delegation_amount,
             strict_argument_checking,
+            allow_authorization_contracts,
+            authorization_contract_gas_limit,
+            max_unbondings_per_step,
+            native_tokens,
         };
         Ok((config, remainder))
     }
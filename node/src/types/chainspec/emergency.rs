@@ -0,0 +1,407 @@
+//! Emergency restarts.
+//!
+//! An emergency restart is an upgrade which the validators agreed on off-chain, by social
+//! consensus, usually because the network stopped finalizing blocks.  It typically replaces the
+//! validator set via a global state update, so the validators of the new protocol version cannot
+//! be derived from the chain itself, and blocks finalized by the previous validators after the
+//! restart point must not be trusted.
+//!
+//! The data flow through the node is as follows:
+//!
+//! 1. Detection: a chainspec whose activation point is an era ID and which contains a global state
+//!    update or an `emergency_checkpoint` describes an emergency restart; see
+//!    [`EmergencyRestart::from_chainspec`].
+//! 2. Checkpoint: the `[protocol] emergency_checkpoint` setting holds the hash of the switch block
+//!    immediately preceding the activation point, as agreed by social consensus.  On startup the
+//!    chainspec loader checks the highest stored block against it before running the upgrade, so
+//!    the immediate switch block is only ever created on top of the checkpoint.
+//! 3. Override: the immediate switch block created by the upgrade carries the overridden
+//!    validator set.  The chainspec loader verifies it with
+//!    [`EmergencyRestart::check_immediate_switch_block`] before storing it.
+//! 4. Conflicting blocks: the chain synchronizer checks the trusted block header and every block
+//!    fetched from peers with [`EmergencyRestart::check_block_header`], refusing blocks of the
+//!    abandoned chain beyond the checkpoint.
+//!
+//! The linear chain picks up the overridden validators when verifying the signatures of the
+//! blocks after the restart; see `linear_chain::era_validator_weights_for_block`.
+
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_types::{EraId, ProtocolVersion};
+
+use super::{ActivationPoint, Chainspec};
+use crate::types::{BlockHash, BlockHeader};
+
+/// An error caused by a block conflicting with an emergency restart.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum EmergencyRestartError {
+    /// The last block before the activation point is not the checkpoint.
+    #[error(
+        "last block {block_hash} before the emergency restart at era {activation_era_id} is not \
+        the checkpoint {checkpoint}"
+    )]
+    NotCheckpoint {
+        /// The era in which the emergency restart takes effect.
+        activation_era_id: EraId,
+        /// The hash of the agreed-upon last block before the restart.
+        checkpoint: BlockHash,
+        /// The hash of the conflicting block.
+        block_hash: BlockHash,
+    },
+    /// A block of an earlier protocol version was created at or after the activation point, i.e.
+    /// on the abandoned chain.
+    #[error(
+        "block {block_hash} of version {protocol_version} in era {era_id} is past the emergency \
+        restart at era {activation_era_id}"
+    )]
+    PastCheckpoint {
+        /// The era in which the emergency restart takes effect.
+        activation_era_id: EraId,
+        /// The hash of the conflicting block.
+        block_hash: BlockHash,
+        /// The era of the conflicting block.
+        era_id: EraId,
+        /// The protocol version of the conflicting block.
+        protocol_version: ProtocolVersion,
+    },
+    /// The first block of the restart is not a child of the checkpoint.
+    #[error(
+        "block {block_hash} in era {activation_era_id} has parent {parent_hash} instead of the \
+        checkpoint {checkpoint}"
+    )]
+    NotChildOfCheckpoint {
+        /// The era in which the emergency restart takes effect.
+        activation_era_id: EraId,
+        /// The hash of the agreed-upon last block before the restart.
+        checkpoint: BlockHash,
+        /// The hash of the conflicting block.
+        block_hash: BlockHash,
+        /// The parent hash of the conflicting block.
+        parent_hash: BlockHash,
+    },
+    /// The immediate switch block doesn't have the expected era, version or validators.
+    #[error("invalid immediate switch block {block_hash}: {reason}")]
+    InvalidImmediateSwitchBlock {
+        /// The hash of the immediate switch block.
+        block_hash: BlockHash,
+        /// What is wrong with the immediate switch block.
+        reason: &'static str,
+    },
+}
+
+/// The parameters of an emergency restart, taken from the chainspec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct EmergencyRestart {
+    /// The protocol version the network restarts with.
+    protocol_version: ProtocolVersion,
+    /// The era in which the restart takes effect.
+    activation_era_id: EraId,
+    /// The hash of the last block before the restart, if configured.
+    checkpoint: Option<BlockHash>,
+}
+
+impl EmergencyRestart {
+    /// Returns the emergency restart described by the given chainspec, or `None` if it doesn't
+    /// describe one.
+    pub(crate) fn from_chainspec(chainspec: &Chainspec) -> Option<Self> {
+        let protocol_config = &chainspec.protocol_config;
+        let activation_era_id = match protocol_config.activation_point {
+            ActivationPoint::EraId(era_id) => era_id,
+            ActivationPoint::Genesis(_) => return None,
+        };
+        if protocol_config.global_state_update.is_none()
+            && protocol_config.emergency_checkpoint.is_none()
+        {
+            return None;
+        }
+        Some(EmergencyRestart {
+            protocol_version: protocol_config.version,
+            activation_era_id,
+            checkpoint: protocol_config.emergency_checkpoint,
+        })
+    }
+
+    /// Returns the hash of the last block before the restart, if configured.
+    pub(crate) fn checkpoint(&self) -> Option<BlockHash> {
+        self.checkpoint
+    }
+
+    /// Logs the detected emergency restart, warning if there is no checkpoint to check blocks
+    /// against.
+    pub(crate) fn log(&self) {
+        match self.checkpoint {
+            Some(checkpoint) => info!(
+                activation_era_id = %self.activation_era_id,
+                %checkpoint,
+                "emergency restart detected"
+            ),
+            None => warn!(
+                activation_era_id = %self.activation_era_id,
+                "emergency restart detected without an emergency_checkpoint in the chainspec; \
+                blocks will not be checked against the agreed-upon chain"
+            ),
+        }
+    }
+
+    /// Checks that the given block header doesn't conflict with the checkpoint.
+    ///
+    /// Always succeeds if no checkpoint is configured.
+    pub(crate) fn check_block_header(
+        &self,
+        block_header: &BlockHeader,
+    ) -> Result<(), EmergencyRestartError> {
+        let checkpoint = match self.checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(()),
+        };
+        let block_hash = block_header.hash();
+        if block_header.protocol_version() < self.protocol_version {
+            if block_header.is_switch_block()
+                && block_header.next_block_era_id() == self.activation_era_id
+                && block_hash != checkpoint
+            {
+                return Err(EmergencyRestartError::NotCheckpoint {
+                    activation_era_id: self.activation_era_id,
+                    checkpoint,
+                    block_hash,
+                });
+            }
+            if block_header.era_id() >= self.activation_era_id {
+                return Err(EmergencyRestartError::PastCheckpoint {
+                    activation_era_id: self.activation_era_id,
+                    block_hash,
+                    era_id: block_header.era_id(),
+                    protocol_version: block_header.protocol_version(),
+                });
+            }
+        } else if block_header.era_id() == self.activation_era_id
+            && *block_header.parent_hash() != checkpoint
+        {
+            // The immediate switch block is the only block in the activation era.
+            return Err(EmergencyRestartError::NotChildOfCheckpoint {
+                activation_era_id: self.activation_era_id,
+                checkpoint,
+                block_hash,
+                parent_hash: *block_header.parent_hash(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the immediate switch block created by the upgrade, which carries the overridden
+    /// validator set.
+    pub(crate) fn check_immediate_switch_block(
+        &self,
+        block_header: &BlockHeader,
+    ) -> Result<(), EmergencyRestartError> {
+        let invalid = |reason| EmergencyRestartError::InvalidImmediateSwitchBlock {
+            block_hash: block_header.hash(),
+            reason,
+        };
+        if block_header.protocol_version() != self.protocol_version {
+            return Err(invalid("wrong protocol version"));
+        }
+        if block_header.era_id() != self.activation_era_id {
+            return Err(invalid("not in the activation era"));
+        }
+        match block_header.next_era_validator_weights() {
+            None => return Err(invalid("not a switch block")),
+            Some(validator_weights) if validator_weights.is_empty() => {
+                return Err(invalid("no validators"))
+            }
+            Some(validator_weights) => info!(
+                next_era_validators = ?validator_weights.keys().collect::<Vec<_>>(),
+                "validators after emergency restart"
+            ),
+        }
+        self.check_block_header(block_header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use casper_hashing::Digest;
+    use casper_types::{testing::TestRng, PublicKey, SemVer, Timestamp, U512};
+
+    use super::{super::GlobalStateUpdate, *};
+    use crate::{
+        components::consensus::EraReport,
+        types::{Block, BlockPayload, ChainspecRawBytes, FinalizedBlock},
+        utils::Loadable,
+    };
+
+    const OLD_VERSION: ProtocolVersion = ProtocolVersion::V1_0_0;
+    const NEW_VERSION: ProtocolVersion = ProtocolVersion::new(SemVer::new(2, 0, 0));
+    const PREVIOUS_ERA: EraId = EraId::new(4);
+    const ACTIVATION_ERA: EraId = EraId::new(5);
+
+    /// Returns a switch block with the given parent, era and version.
+    fn switch_block(
+        rng: &mut TestRng,
+        parent_hash: BlockHash,
+        era_id: EraId,
+        protocol_version: ProtocolVersion,
+    ) -> Block {
+        let finalized_block = FinalizedBlock::new(
+            BlockPayload::default(),
+            Some(EraReport::default()),
+            Timestamp::now(),
+            era_id,
+            era_id.value() * 10,
+            PublicKey::System,
+        );
+        let mut validator_weights = BTreeMap::new();
+        validator_weights.insert(PublicKey::random(rng), U512::from(100));
+        Block::new(
+            parent_hash,
+            Digest::hash(b"parent seed"),
+            Digest::hash(b"state root hash"),
+            finalized_block,
+            Some(validator_weights),
+            protocol_version,
+        )
+        .unwrap()
+    }
+
+    fn new_emergency_restart(checkpoint: Option<BlockHash>) -> EmergencyRestart {
+        EmergencyRestart {
+            protocol_version: NEW_VERSION,
+            activation_era_id: ACTIVATION_ERA,
+            checkpoint,
+        }
+    }
+
+    #[test]
+    fn should_detect_emergency_restart() {
+        let mut rng = crate::new_rng();
+        let (mut chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        assert_eq!(EmergencyRestart::from_chainspec(&chainspec), None);
+
+        // A global state update at genesis is not an emergency restart.
+        chainspec.protocol_config.global_state_update = Some(GlobalStateUpdate::random(&mut rng));
+        assert_eq!(EmergencyRestart::from_chainspec(&chainspec), None);
+
+        chainspec.protocol_config.activation_point = ActivationPoint::EraId(ACTIVATION_ERA);
+        let emergency_restart = EmergencyRestart::from_chainspec(&chainspec).unwrap();
+        assert_eq!(emergency_restart.checkpoint(), None);
+
+        let checkpoint = BlockHash::random(&mut rng);
+        chainspec.protocol_config.global_state_update = None;
+        chainspec.protocol_config.emergency_checkpoint = Some(checkpoint);
+        let emergency_restart = EmergencyRestart::from_chainspec(&chainspec).unwrap();
+        assert_eq!(emergency_restart.checkpoint(), Some(checkpoint));
+    }
+
+    #[test]
+    fn should_accept_blocks_of_agreed_chain() {
+        let mut rng = crate::new_rng();
+        let earlier_block = switch_block(
+            &mut rng,
+            BlockHash::random(&mut rng),
+            EraId::new(3),
+            OLD_VERSION,
+        );
+        let checkpoint_block =
+            switch_block(&mut rng, *earlier_block.hash(), PREVIOUS_ERA, OLD_VERSION);
+        let immediate_switch_block = switch_block(
+            &mut rng,
+            *checkpoint_block.hash(),
+            ACTIVATION_ERA,
+            NEW_VERSION,
+        );
+        let later_block = switch_block(
+            &mut rng,
+            *immediate_switch_block.hash(),
+            ACTIVATION_ERA.successor(),
+            NEW_VERSION,
+        );
+
+        let emergency_restart = new_emergency_restart(Some(*checkpoint_block.hash()));
+        for block in [
+            &earlier_block,
+            &checkpoint_block,
+            &immediate_switch_block,
+            &later_block,
+        ] {
+            assert_eq!(emergency_restart.check_block_header(block.header()), Ok(()));
+        }
+        assert_eq!(
+            emergency_restart.check_immediate_switch_block(immediate_switch_block.header()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_refuse_blocks_conflicting_with_checkpoint() {
+        let mut rng = crate::new_rng();
+        let checkpoint = BlockHash::random(&mut rng);
+        let emergency_restart = new_emergency_restart(Some(checkpoint));
+
+        // A different last block before the activation point.
+        let other_last_block = switch_block(
+            &mut rng,
+            BlockHash::random(&mut rng),
+            PREVIOUS_ERA,
+            OLD_VERSION,
+        );
+        assert!(matches!(
+            emergency_restart.check_block_header(other_last_block.header()),
+            Err(EmergencyRestartError::NotCheckpoint { .. })
+        ));
+
+        // A block of the abandoned chain, finalized by the previous validators.
+        let abandoned_block = switch_block(&mut rng, checkpoint, ACTIVATION_ERA, OLD_VERSION);
+        assert!(matches!(
+            emergency_restart.check_block_header(abandoned_block.header()),
+            Err(EmergencyRestartError::PastCheckpoint { .. })
+        ));
+
+        // An immediate switch block on top of a different block.
+        let other_switch_block = switch_block(
+            &mut rng,
+            *other_last_block.hash(),
+            ACTIVATION_ERA,
+            NEW_VERSION,
+        );
+        assert!(matches!(
+            emergency_restart.check_block_header(other_switch_block.header()),
+            Err(EmergencyRestartError::NotChildOfCheckpoint { .. })
+        ));
+        assert!(matches!(
+            emergency_restart.check_immediate_switch_block(other_switch_block.header()),
+            Err(EmergencyRestartError::NotChildOfCheckpoint { .. })
+        ));
+
+        // Without a checkpoint, nothing is refused.
+        let emergency_restart = new_emergency_restart(None);
+        for block in [&other_last_block, &abandoned_block, &other_switch_block] {
+            assert_eq!(emergency_restart.check_block_header(block.header()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn should_refuse_invalid_immediate_switch_block() {
+        let mut rng = crate::new_rng();
+        let checkpoint = BlockHash::random(&mut rng);
+        let emergency_restart = new_emergency_restart(Some(checkpoint));
+
+        let wrong_version = switch_block(&mut rng, checkpoint, ACTIVATION_ERA, OLD_VERSION);
+        let wrong_era = switch_block(
+            &mut rng,
+            checkpoint,
+            ACTIVATION_ERA.successor(),
+            NEW_VERSION,
+        );
+        let not_switch_block =
+            Block::random_with_specifics(&mut rng, ACTIVATION_ERA, 50, NEW_VERSION, false, None);
+        for block in [&wrong_version, &wrong_era, &not_switch_block] {
+            assert!(matches!(
+                emergency_restart.check_immediate_switch_block(block.header()),
+                Err(EmergencyRestartError::InvalidImmediateSwitchBlock { .. })
+            ));
+        }
+    }
+}
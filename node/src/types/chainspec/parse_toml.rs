@@ -18,6 +18,7 @@ use super::{
     Chainspec, ChainspecRawBytes, CoreConfig, DeployConfig, Error, GlobalStateUpdate,
     HighwayConfig, NetworkConfig, ProtocolConfig,
 };
+use crate::types::BlockHash;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -34,6 +35,8 @@ struct TomlProtocol {
     version: ProtocolVersion,
     hard_reset: bool,
     activation_point: ActivationPoint,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    emergency_checkpoint: Option<BlockHash>,
 }
 
 /// A chainspec configuration as laid out in the TOML-encoded configuration file.
@@ -56,12 +59,13 @@ impl From<&Chainspec> for TomlChainspec {
             version: chainspec.protocol_config.version,
             hard_reset: chainspec.protocol_config.hard_reset,
             activation_point: chainspec.protocol_config.activation_point,
+            emergency_checkpoint: chainspec.protocol_config.emergency_checkpoint,
         };
         let network = TomlNetwork {
             name: chainspec.network_config.name.clone(),
             maximum_net_message_size: chainspec.network_config.maximum_net_message_size,
         };
-        let core = chainspec.core_config;
+        let core = chainspec.core_config.clone();
         let deploys = chainspec.deploy_config;
         let highway = chainspec.highway_config;
         let wasm = chainspec.wasm_config;
@@ -112,6 +116,7 @@ pub(super) fn parse_toml<P: AsRef<Path>>(
         hard_reset: toml_chainspec.protocol.hard_reset,
         activation_point: toml_chainspec.protocol.activation_point,
         global_state_update,
+        emergency_checkpoint: toml_chainspec.protocol.emergency_checkpoint,
     };
 
     let chainspec = Chainspec {
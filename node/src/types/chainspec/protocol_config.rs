@@ -16,7 +16,7 @@ use casper_types::{
 };
 
 use super::{ActivationPoint, GlobalStateUpdate};
-use crate::types::BlockHeader;
+use crate::types::{BlockHash, BlockHeader};
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, DataSize, Debug)]
 pub struct ProtocolConfig {
@@ -27,6 +27,10 @@ pub struct ProtocolConfig {
     pub(crate) hard_reset: bool,
     /// This protocol config applies starting at the era specified in the activation point.
     pub(crate) activation_point: ActivationPoint,
+    /// The hash of the switch block immediately preceding the activation point, agreed upon by
+    /// social consensus for an emergency restart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) emergency_checkpoint: Option<BlockHash>,
     /// Any arbitrary updates we might want to make to the global state at the start of the era
     /// specified in the activation point.
     pub(crate) global_state_update: Option<GlobalStateUpdate>,
@@ -78,6 +82,7 @@ impl ProtocolConfig {
             hard_reset: rng.gen(),
             activation_point,
             global_state_update: None,
+            emergency_checkpoint: rng.gen::<bool>().then(|| BlockHash::random(rng)),
         }
     }
 }
@@ -89,6 +94,7 @@ impl ToBytes for ProtocolConfig {
         buffer.extend(self.hard_reset.to_bytes()?);
         buffer.extend(self.activation_point.to_bytes()?);
         buffer.extend(self.global_state_update.to_bytes()?);
+        buffer.extend(self.emergency_checkpoint.to_bytes()?);
         Ok(buffer)
     }
 
@@ -97,6 +103,7 @@ impl ToBytes for ProtocolConfig {
             + self.hard_reset.serialized_length()
             + self.activation_point.serialized_length()
             + self.global_state_update.serialized_length()
+            + self.emergency_checkpoint.serialized_length()
     }
 }
 
@@ -108,11 +115,13 @@ impl FromBytes for ProtocolConfig {
         let (hard_reset, remainder) = bool::from_bytes(remainder)?;
         let (activation_point, remainder) = ActivationPoint::from_bytes(remainder)?;
         let (global_state_update, remainder) = Option::<GlobalStateUpdate>::from_bytes(remainder)?;
+        let (emergency_checkpoint, remainder) = Option::<BlockHash>::from_bytes(remainder)?;
         let protocol_config = ProtocolConfig {
             version,
             hard_reset,
             activation_point,
             global_state_update,
+            emergency_checkpoint,
         };
         Ok((protocol_config, remainder))
     }
@@ -186,6 +195,7 @@ mod tests {
             hard_reset: false,
             activation_point: ActivationPoint::EraId(upgrade_era),
             global_state_update: None,
+            emergency_checkpoint: None,
         };
 
         // The block before this protocol version: a switch block with previous era and version.
@@ -1,6 +1,8 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
+mod validation;
+
 use std::{
     array::TryFromSliceError,
     cmp,
@@ -20,7 +22,7 @@ use rand::{Rng, RngCore};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tracing::{info, warn};
+use tracing::warn;
 
 #[cfg(test)]
 use casper_execution_engine::core::engine_state::MAX_PAYMENT;
@@ -47,6 +49,7 @@ use crate::{
     types::chainspec::DeployConfig,
     utils::{ds, DisplayIter},
 };
+pub use validation::DeployValidationReport;
 
 static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
     let payment_args = runtime_args! {
@@ -589,6 +592,48 @@ impl FromBytes for Approval {
     }
 }
 
+/// The cryptographic hash of a set of approvals.
+///
+/// The approvals are hashed in their canonical form, i.e. sorted and without duplicates, as held in
+/// a `BTreeSet`, so that the same approvals always hash the same regardless of the order in which
+/// they were received.  Since the deploy hash doesn't cover the approvals, this is what tells apart
+/// two differing sets of approvals for the same deploy.
+///
+/// It is only computed locally, to decide whether a peer's finalized approvals are worth
+/// re-executing a block with while syncing, and to build a block's approvals root hash.  Storage,
+/// gossip and block payloads still identify deploys by their deploy hash alone, and it is never
+/// sent to peers.
+#[derive(
+    Copy, Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize, Debug,
+)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalsHash(Digest);
+
+impl ApprovalsHash {
+    /// Computes the hash of the given set of approvals.
+    pub fn compute(approvals: &BTreeSet<Approval>) -> Result<Self, bytesrepr::Error> {
+        let bytes = approvals.to_bytes()?;
+        Ok(ApprovalsHash(Digest::hash(bytes)))
+    }
+
+    /// Returns the wrapped inner hash.
+    pub fn inner(&self) -> &Digest {
+        &self.0
+    }
+}
+
+impl From<ApprovalsHash> for Digest {
+    fn from(approvals_hash: ApprovalsHash) -> Self {
+        approvals_hash.0
+    }
+}
+
+impl Display for ApprovalsHash {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "approvals-hash({})", self.0)
+    }
+}
+
 /// The hash of a deploy (or transfer) together with signatures approving it for execution.
 #[derive(Clone, DataSize, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DeployWithApprovals {
@@ -616,6 +661,11 @@ impl DeployWithApprovals {
     pub fn approvals(&self) -> &BTreeSet<Approval> {
         &self.approvals
     }
+
+    /// Returns the hash of the approvals.
+    pub fn approvals_hash(&self) -> Result<ApprovalsHash, bytesrepr::Error> {
+        ApprovalsHash::compute(&self.approvals)
+    }
 }
 
 impl From<&Deploy> for DeployWithApprovals {
@@ -641,6 +691,11 @@ impl FinalizedApprovals {
     pub fn into_inner(self) -> BTreeSet<Approval> {
         self.0
     }
+
+    /// Returns the hash of the approvals.
+    pub fn approvals_hash(&self) -> Result<ApprovalsHash, bytesrepr::Error> {
+        ApprovalsHash::compute(&self.0)
+    }
 }
 
 impl AsRef<BTreeSet<Approval>> for FinalizedApprovals {
@@ -667,6 +722,11 @@ impl FinalizedApprovalsWithId {
     pub fn into_inner(self) -> BTreeSet<Approval> {
         self.approvals.into_inner()
     }
+
+    /// Returns the hash of the approvals.
+    pub fn approvals_hash(&self) -> Result<ApprovalsHash, bytesrepr::Error> {
+        self.approvals.approvals_hash()
+    }
 }
 
 /// Error type containing the error message passed from `crypto::verify`
@@ -871,11 +931,24 @@ impl Deploy {
         &self.approvals
     }
 
+    /// Returns the hash of the `Approval`s for this deploy.
+    pub fn approvals_hash(&self) -> Result<ApprovalsHash, bytesrepr::Error> {
+        ApprovalsHash::compute(&self.approvals)
+    }
+
     /// Replaces the set of approvals attached to this deploy.
     pub fn replace_approvals(&mut self, approvals: BTreeSet<Approval>) {
         self.approvals = approvals;
     }
 
+    /// Adds the given approvals to the set of approvals attached to this deploy.
+    ///
+    /// Since the approvals change, the cached result of `is_valid` is discarded.
+    pub(crate) fn add_approvals<I: IntoIterator<Item = Approval>>(&mut self, approvals: I) {
+        self.approvals.extend(approvals);
+        self.is_valid = OnceCell::new();
+    }
+
     /// Returns the hash of this deploy wrapped in `DeployOrTransferHash`.
     pub fn deploy_or_transfer_hash(&self) -> DeployOrTransferHash {
         if self.session.is_transfer() {
@@ -965,142 +1038,8 @@ impl Deploy {
         config: &DeployConfig,
         max_associated_keys: u32,
     ) -> Result<(), DeployConfigurationFailure> {
-        self.is_valid_size(config.max_deploy_size)?;
-
-        let header = self.header();
-        if header.chain_name() != chain_name {
-            info!(
-                deploy_hash = %self.id(),
-                deploy_header = %header,
-                chain_name = %header.chain_name(),
-                "invalid chain identifier"
-            );
-            return Err(DeployConfigurationFailure::InvalidChainName {
-                expected: chain_name.to_string(),
-                got: header.chain_name().to_string(),
-            });
-        }
-
-        if header.dependencies().len() > config.max_dependencies as usize {
-            info!(
-                deploy_hash = %self.id(),
-                deploy_header = %header,
-                max_dependencies = %config.max_dependencies,
-                "deploy dependency ceiling exceeded"
-            );
-            return Err(DeployConfigurationFailure::ExcessiveDependencies {
-                max_dependencies: config.max_dependencies,
-                got: header.dependencies().len(),
-            });
-        }
-
-        if header.ttl() > config.max_ttl {
-            info!(
-                deploy_hash = %self.id(),
-                deploy_header = %header,
-                max_ttl = %config.max_ttl,
-                "deploy ttl excessive"
-            );
-            return Err(DeployConfigurationFailure::ExcessiveTimeToLive {
-                max_ttl: config.max_ttl,
-                got: header.ttl(),
-            });
-        }
-
-        if self.approvals.len() > max_associated_keys as usize {
-            info!(
-                deploy_hash = %self.id(),
-                number_of_associated_keys = %self.approvals.len(),
-                max_associated_keys = %max_associated_keys,
-                "number of associated keys exceeds the maximum limit"
-            );
-            return Err(DeployConfigurationFailure::ExcessiveApprovals {
-                got: self.approvals.len() as u32,
-                max_associated_keys,
-            });
-        }
-
-        // Transfers have a fixed cost and won't blow the block gas limit.
-        // Other deploys can, therefore, statically check the payment amount
-        // associated with the deploy.
-        if !self.session().is_transfer() {
-            let value = self
-                .payment()
-                .args()
-                .get(ARG_AMOUNT)
-                .ok_or(DeployConfigurationFailure::MissingPaymentAmount)?;
-            let payment_amount = value
-                .clone()
-                .into_t::<U512>()
-                .map_err(|_| DeployConfigurationFailure::FailedToParsePaymentAmount)?;
-            if payment_amount > U512::from(config.block_gas_limit) {
-                info!(
-                    amount = %payment_amount,
-                    block_gas_limit = %config.block_gas_limit, "payment amount exceeds block gas limit"
-                );
-                return Err(DeployConfigurationFailure::ExceededBlockGasLimit {
-                    block_gas_limit: config.block_gas_limit,
-                    got: payment_amount,
-                });
-            }
-        }
-
-        let payment_args_length = self.payment().args().serialized_length();
-        if payment_args_length > config.payment_args_max_length as usize {
-            info!(
-                payment_args_length,
-                payment_args_max_length = config.payment_args_max_length,
-                "payment args excessive"
-            );
-            return Err(DeployConfigurationFailure::ExcessivePaymentArgsLength {
-                max_length: config.payment_args_max_length as usize,
-                got: payment_args_length,
-            });
-        }
-
-        let session_args_length = self.session().args().serialized_length();
-        if session_args_length > config.session_args_max_length as usize {
-            info!(
-                session_args_length,
-                session_args_max_length = config.session_args_max_length,
-                "session args excessive"
-            );
-            return Err(DeployConfigurationFailure::ExcessiveSessionArgsLength {
-                max_length: config.session_args_max_length as usize,
-                got: session_args_length,
-            });
-        }
-
-        if self.session().is_transfer() {
-            let item = self.session().clone();
-            let attempted = item
-                .args()
-                .get(ARG_AMOUNT)
-                .ok_or_else(|| {
-                    info!("missing transfer 'amount' runtime argument");
-                    DeployConfigurationFailure::MissingTransferAmount
-                })?
-                .clone()
-                .into_t::<U512>()
-                .map_err(|_| {
-                    info!("failed to parse transfer 'amount' runtime argument as a U512");
-                    DeployConfigurationFailure::FailedToParseTransferAmount
-                })?;
-            let minimum = U512::from(config.native_transfer_minimum_motes);
-            if attempted < minimum {
-                info!(
-                    minimum = %config.native_transfer_minimum_motes,
-                    amount = %attempted,
-                    "insufficient transfer amount"
-                );
-                return Err(DeployConfigurationFailure::InsufficientTransferAmount {
-                    minimum,
-                    attempted,
-                });
-            }
-        }
-
-        Ok(())
+        self.check_config_compliance(chain_name, config, max_associated_keys)
+            .into_result()
     }
 }
 
@@ -1581,24 +1520,7 @@ fn serialize_body(payment: &ExecutableDeployItem, session: &ExecutableDeployItem
 // Computationally expensive validity check for a given deploy instance, including
 // asymmetric_key signing verification.
 fn validate_deploy(deploy: &Deploy) -> Result<(), DeployConfigurationFailure> {
-    if deploy.approvals.is_empty() {
-        warn!(?deploy, "deploy has no approvals");
-        return Err(DeployConfigurationFailure::EmptyApprovals);
-    }
-
-    deploy.has_valid_hash()?;
-
-    for (index, approval) in deploy.approvals.iter().enumerate() {
-        if let Err(error) = crypto::verify(&deploy.hash, &approval.signature, &approval.signer) {
-            warn!(?deploy, "failed to verify approval {}: {}", index, error);
-            return Err(DeployConfigurationFailure::InvalidApproval {
-                index,
-                error_msg: error.to_string(),
-            });
-        }
-    }
-
-    Ok(())
+    deploy.check_cryptography().into_result()
 }
 
 impl Item for Deploy {
@@ -1778,6 +1700,56 @@ mod tests {
         assert_eq!(deploy, deserialized);
     }
 
+    #[test]
+    fn approvals_hash_should_be_canonical() {
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::random(&mut rng);
+        let approvals: Vec<_> =
+            iter::repeat_with(|| Approval::create(deploy.id(), &SecretKey::random(&mut rng)))
+                .take(3)
+                .collect();
+
+        // The order in which approvals are received and any duplicates don't affect the hash.
+        let in_order: BTreeSet<_> = approvals.iter().cloned().collect();
+        let reordered_with_duplicate: BTreeSet<_> = approvals
+            .iter()
+            .rev()
+            .chain(approvals.first())
+            .cloned()
+            .collect();
+        let approvals_hash = ApprovalsHash::compute(&in_order).unwrap();
+        assert_eq!(
+            ApprovalsHash::compute(&reordered_with_duplicate).unwrap(),
+            approvals_hash
+        );
+
+        // Differing sets of approvals for the same deploy are told apart.
+        let mut deploy_with_approvals = deploy.clone();
+        deploy_with_approvals.replace_approvals(in_order.clone());
+        assert_eq!(*deploy_with_approvals.id(), *deploy.id());
+        assert_eq!(
+            deploy_with_approvals.approvals_hash().unwrap(),
+            approvals_hash
+        );
+        assert_ne!(deploy.approvals_hash().unwrap(), approvals_hash);
+
+        let subset: BTreeSet<_> = approvals.into_iter().skip(1).collect();
+        assert_ne!(ApprovalsHash::compute(&subset).unwrap(), approvals_hash);
+
+        let finalized_approvals =
+            FinalizedApprovalsWithId::new(*deploy.id(), FinalizedApprovals::new(in_order));
+        assert_eq!(
+            finalized_approvals.approvals_hash().unwrap(),
+            approvals_hash
+        );
+        assert_eq!(
+            DeployWithApprovals::from(&deploy_with_approvals)
+                .approvals_hash()
+                .unwrap(),
+            approvals_hash
+        );
+    }
+
     #[test]
     fn bytesrepr_roundtrip() {
         let mut rng = crate::new_rng();
@@ -2044,6 +2016,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_report_all_config_failures() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig::default();
+
+        let ttl = deploy_config.max_ttl + TimeDiff::from(Duration::from_secs(1));
+        let dependency_count = usize::from(deploy_config.max_dependencies + 1);
+
+        let deploy = create_deploy(&mut rng, ttl, dependency_count, "net-2");
+
+        let report =
+            deploy.check_config_compliance(chain_name, &deploy_config, DEFAULT_MAX_ASSOCIATED_KEYS);
+        assert_eq!(
+            report.failures(),
+            [
+                DeployConfigurationFailure::InvalidChainName {
+                    expected: chain_name.to_string(),
+                    got: "net-2".to_string(),
+                },
+                DeployConfigurationFailure::ExcessiveDependencies {
+                    max_dependencies: deploy_config.max_dependencies,
+                    got: dependency_count,
+                },
+                DeployConfigurationFailure::ExcessiveTimeToLive {
+                    max_ttl: deploy_config.max_ttl,
+                    got: ttl,
+                },
+            ]
+        );
+        assert_eq!(
+            deploy.is_config_compliant(chain_name, &deploy_config, DEFAULT_MAX_ASSOCIATED_KEYS),
+            Err(report.failures()[0].clone())
+        );
+    }
+
     #[test]
     fn not_acceptable_due_to_missing_payment_amount() {
         let mut rng = crate::new_rng();
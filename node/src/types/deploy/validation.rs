@@ -0,0 +1,251 @@
+//! Validation of deploys, shared by the deploy acceptor and the RPCs submitting deploys.
+//!
+//! Unlike the checks returning the first failure, these run every check and collect all failures
+//! into a [`DeployValidationReport`], so that clients learn everything wrong with a deploy at once.
+
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{bytesrepr::ToBytes, crypto, system::standard_payment::ARG_AMOUNT, U512};
+
+use super::{serialize_body, serialize_header, Deploy, DeployConfigurationFailure, DeployHash};
+use crate::types::chainspec::DeployConfig;
+
+/// A machine-readable report of all the validation checks a deploy failed.
+#[derive(Clone, Default, Eq, PartialEq, Debug, Serialize)]
+pub struct DeployValidationReport {
+    /// The checks the deploy failed, in the order they were run.
+    failures: Vec<DeployConfigurationFailure>,
+}
+
+impl DeployValidationReport {
+    /// Returns `true` if the deploy passed all checks.
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the checks the deploy failed, in the order they were run.
+    pub fn failures(&self) -> &[DeployConfigurationFailure] {
+        &self.failures
+    }
+
+    /// Returns `Ok` if the deploy passed all checks, or the first failure otherwise.
+    pub(crate) fn into_result(self) -> Result<(), DeployConfigurationFailure> {
+        match self.failures.into_iter().next() {
+            None => Ok(()),
+            Some(failure) => Err(failure),
+        }
+    }
+
+    fn push(&mut self, failure: DeployConfigurationFailure) {
+        self.failures.push(failure);
+    }
+}
+
+impl From<DeployConfigurationFailure> for DeployValidationReport {
+    fn from(failure: DeployConfigurationFailure) -> Self {
+        DeployValidationReport {
+            failures: vec![failure],
+        }
+    }
+}
+
+impl Display for DeployValidationReport {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.failures.iter().join("; "))
+    }
+}
+
+impl Deploy {
+    /// Checks that the deploy complies with the chain name and the configured limits, reporting
+    /// every check it fails.
+    pub(crate) fn check_config_compliance(
+        &self,
+        chain_name: &str,
+        config: &DeployConfig,
+        max_associated_keys: u32,
+    ) -> DeployValidationReport {
+        let mut report = DeployValidationReport::default();
+
+        if let Err(error) = self.is_valid_size(config.max_deploy_size) {
+            report.push(error.into());
+        }
+
+        let header = self.header();
+        if header.chain_name() != chain_name {
+            info!(
+                deploy_hash = %self.id(),
+                deploy_header = %header,
+                chain_name = %header.chain_name(),
+                "invalid chain identifier"
+            );
+            report.push(DeployConfigurationFailure::InvalidChainName {
+                expected: chain_name.to_string(),
+                got: header.chain_name().to_string(),
+            });
+        }
+
+        if header.dependencies().len() > config.max_dependencies as usize {
+            info!(
+                deploy_hash = %self.id(),
+                deploy_header = %header,
+                max_dependencies = %config.max_dependencies,
+                "deploy dependency ceiling exceeded"
+            );
+            report.push(DeployConfigurationFailure::ExcessiveDependencies {
+                max_dependencies: config.max_dependencies,
+                got: header.dependencies().len(),
+            });
+        }
+
+        if header.ttl() > config.max_ttl {
+            info!(
+                deploy_hash = %self.id(),
+                deploy_header = %header,
+                max_ttl = %config.max_ttl,
+                "deploy ttl excessive"
+            );
+            report.push(DeployConfigurationFailure::ExcessiveTimeToLive {
+                max_ttl: config.max_ttl,
+                got: header.ttl(),
+            });
+        }
+
+        if self.approvals.len() > max_associated_keys as usize {
+            info!(
+                deploy_hash = %self.id(),
+                number_of_associated_keys = %self.approvals.len(),
+                max_associated_keys = %max_associated_keys,
+                "number of associated keys exceeds the maximum limit"
+            );
+            report.push(DeployConfigurationFailure::ExcessiveApprovals {
+                got: self.approvals.len() as u32,
+                max_associated_keys,
+            });
+        }
+
+        // Transfers have a fixed cost and won't blow the block gas limit.
+        // Other deploys can, therefore, statically check the payment amount
+        // associated with the deploy.
+        if !self.session().is_transfer() {
+            match self.payment().args().get(ARG_AMOUNT) {
+                None => report.push(DeployConfigurationFailure::MissingPaymentAmount),
+                Some(value) => match value.clone().into_t::<U512>() {
+                    Err(_) => report.push(DeployConfigurationFailure::FailedToParsePaymentAmount),
+                    Ok(payment_amount) if payment_amount > U512::from(config.block_gas_limit) => {
+                        info!(
+                            amount = %payment_amount,
+                            block_gas_limit = %config.block_gas_limit,
+                            "payment amount exceeds block gas limit"
+                        );
+                        report.push(DeployConfigurationFailure::ExceededBlockGasLimit {
+                            block_gas_limit: config.block_gas_limit,
+                            got: payment_amount,
+                        });
+                    }
+                    Ok(_) => {}
+                },
+            }
+        }
+
+        let payment_args_length = self.payment().args().serialized_length();
+        if payment_args_length > config.payment_args_max_length as usize {
+            info!(
+                payment_args_length,
+                payment_args_max_length = config.payment_args_max_length,
+                "payment args excessive"
+            );
+            report.push(DeployConfigurationFailure::ExcessivePaymentArgsLength {
+                max_length: config.payment_args_max_length as usize,
+                got: payment_args_length,
+            });
+        }
+
+        let session_args_length = self.session().args().serialized_length();
+        if session_args_length > config.session_args_max_length as usize {
+            info!(
+                session_args_length,
+                session_args_max_length = config.session_args_max_length,
+                "session args excessive"
+            );
+            report.push(DeployConfigurationFailure::ExcessiveSessionArgsLength {
+                max_length: config.session_args_max_length as usize,
+                got: session_args_length,
+            });
+        }
+
+        if self.session().is_transfer() {
+            match self.session().args().get(ARG_AMOUNT) {
+                None => {
+                    info!("missing transfer 'amount' runtime argument");
+                    report.push(DeployConfigurationFailure::MissingTransferAmount);
+                }
+                Some(value) => match value.clone().into_t::<U512>() {
+                    Err(_) => {
+                        info!("failed to parse transfer 'amount' runtime argument as a U512");
+                        report.push(DeployConfigurationFailure::FailedToParseTransferAmount);
+                    }
+                    Ok(attempted) => {
+                        let minimum = U512::from(config.native_transfer_minimum_motes);
+                        if attempted < minimum {
+                            info!(
+                                minimum = %config.native_transfer_minimum_motes,
+                                amount = %attempted,
+                                "insufficient transfer amount"
+                            );
+                            report.push(DeployConfigurationFailure::InsufficientTransferAmount {
+                                minimum,
+                                attempted,
+                            });
+                        }
+                    }
+                },
+            }
+        }
+
+        report
+    }
+
+    /// Checks the deploy's hashes and approvals, reporting every check it fails, including each
+    /// invalid approval.
+    ///
+    /// This is computationally expensive, as it verifies the signature of every approval.
+    pub(crate) fn check_cryptography(&self) -> DeployValidationReport {
+        let mut report = DeployValidationReport::default();
+
+        if self.approvals.is_empty() {
+            warn!(deploy = ?self, "deploy has no approvals");
+            report.push(DeployConfigurationFailure::EmptyApprovals);
+        }
+
+        let serialized_body = serialize_body(&self.payment, &self.session);
+        let body_hash = Digest::hash(&serialized_body);
+        if body_hash != self.header.body_hash {
+            warn!(deploy = ?self, ?body_hash, "invalid deploy body hash");
+            report.push(DeployConfigurationFailure::InvalidBodyHash);
+        }
+
+        let serialized_header = serialize_header(&self.header);
+        let hash = DeployHash::new(Digest::hash(&serialized_header));
+        if hash != self.hash {
+            warn!(deploy = ?self, ?hash, "invalid deploy hash");
+            report.push(DeployConfigurationFailure::InvalidDeployHash);
+        }
+
+        for (index, approval) in self.approvals.iter().enumerate() {
+            if let Err(error) = crypto::verify(&self.hash, &approval.signature, &approval.signer) {
+                warn!(deploy = ?self, "failed to verify approval {}: {}", index, error);
+                report.push(DeployConfigurationFailure::InvalidApproval {
+                    index,
+                    error_msg: error.to_string(),
+                });
+            }
+        }
+
+        report
+    }
+}
@@ -23,6 +23,10 @@ pub enum ExitCode {
     /// It is no longer used, but we keep it here to avoid it being reassigned to other features.
     #[doc(hidden)]
     DowngradeVersion = 102,
+    /// The process should exit with `103`.  The node was put into drain mode by the operator and
+    /// has finished executing all blocks it finalized before the drain was requested.  The
+    /// launcher should not restart the node.
+    Drained = 103,
     /// The exit code Rust uses by default when interrupted via an `INT` signal.
     SigInt = SIGNAL_OFFSET + SIGINT as u8,
     /// The exit code Rust uses by default when interrupted via a `QUIT` signal.
@@ -0,0 +1,122 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use std::collections::BTreeMap;
+
+use datasize::DataSize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How healthy a component, or the node as a whole, is.
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, DataSize, Debug, Deserialize, Serialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Operating normally.
+    Ready,
+    /// Operating, but impaired.
+    Degraded,
+    /// Not operating, unlikely to recover without a restart.
+    Failed,
+}
+
+impl HealthStatus {
+    /// Returns the status as a gauge value: 0 for ready, 1 for degraded and 2 for failed.
+    pub(crate) fn as_gauge_value(self) -> i64 {
+        self as i64
+    }
+}
+
+/// The health of a single component.
+#[derive(Clone, PartialEq, Eq, DataSize, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Health {
+    /// The status of the component.
+    pub status: HealthStatus,
+    /// The reasons the component isn't ready, if any.
+    pub reasons: Vec<String>,
+}
+
+impl Health {
+    /// Returns the health of a component operating normally.
+    pub(crate) fn ready() -> Self {
+        Health {
+            status: HealthStatus::Ready,
+            reasons: Vec::new(),
+        }
+    }
+
+    /// Marks the component as at least degraded, for the given reason.
+    pub(crate) fn degrade(&mut self, reason: impl Into<String>) {
+        self.worsen(HealthStatus::Degraded, reason.into());
+    }
+
+    /// Marks the component as failed, for the given reason.
+    pub(crate) fn fail(&mut self, reason: impl Into<String>) {
+        self.worsen(HealthStatus::Failed, reason.into());
+    }
+
+    fn worsen(&mut self, status: HealthStatus, reason: String) {
+        self.status = self.status.max(status);
+        self.reasons.push(reason);
+    }
+}
+
+/// The health of the node, aggregated from the health of its major components.
+#[derive(Clone, PartialEq, Eq, DataSize, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HealthReport {
+    /// The worst status of any component.
+    pub status: HealthStatus,
+    /// The health of each component, by name.
+    pub components: BTreeMap<String, Health>,
+}
+
+impl HealthReport {
+    /// Aggregates the health of the given components.
+    pub(crate) fn new<'a, I>(components: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, Health)>,
+    {
+        let components: BTreeMap<String, Health> = components
+            .into_iter()
+            .map(|(name, health)| (name.to_string(), health))
+            .collect();
+        let status = components
+            .values()
+            .map(|health| health.status)
+            .max()
+            .unwrap_or(HealthStatus::Ready);
+        HealthReport { status, components }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_worst_component_status() {
+        let mut degraded = Health::ready();
+        degraded.degrade("no connected peers");
+        let mut failed = Health::ready();
+        failed.fail("listener exited");
+        failed.degrade("no connected peers");
+        assert_eq!(failed.status, HealthStatus::Failed);
+        assert_eq!(failed.reasons.len(), 2);
+
+        let report = HealthReport::new(vec![("storage", Health::ready())]);
+        assert_eq!(report.status, HealthStatus::Ready);
+
+        let report = HealthReport::new(vec![
+            ("storage", Health::ready()),
+            ("network", degraded.clone()),
+        ]);
+        assert_eq!(report.status, HealthStatus::Degraded);
+
+        let report = HealthReport::new(vec![("network", degraded), ("consensus", failed)]);
+        assert_eq!(report.status, HealthStatus::Failed);
+        assert_eq!(report.status.as_gauge_value(), 2);
+    }
+}
@@ -37,7 +37,7 @@ pub enum Tag {
     FinalizedApprovals,
     /// A block.
     Block,
-    /// A gossiped public listening address.
+    /// A gossiped peer record, listing the public listening addresses of a node.
     GossipedAddress,
     /// A block requested by its height in the linear chain.
     BlockAndMetadataByHeight,
@@ -53,6 +53,8 @@ pub enum Tag {
     BlockHeaderBatch,
     /// Finality signatures for a block requested by the block's hash.
     FinalitySignaturesByHash,
+    /// A block body or chunk of it, requested by the block's hash and the chunk's index.
+    BlockBodyOrChunk,
 }
 
 /// A trait which allows an implementing type to be used by the gossiper and fetcher components, and
@@ -73,6 +75,15 @@ pub(crate) trait Item:
     /// Checks cryptographic validity of the item, and returns an error if invalid.
     fn validate(&self) -> Result<(), Self::ValidationError>;
 
+    /// Checks cryptographic validity of an item ID received from a peer, and returns an error if
+    /// invalid.
+    ///
+    /// Only relevant where `ID_IS_COMPLETE_ITEM` is true, as the ID then carries all the data of the
+    /// item.
+    fn validate_id(_id: &Self::Id) -> Result<(), Self::ValidationError> {
+        Ok(())
+    }
+
     /// The ID of the specific item.
     fn id(&self) -> Self::Id;
 }
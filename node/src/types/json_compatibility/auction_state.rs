@@ -93,6 +93,7 @@ pub struct JsonDelegator {
     staked_amount: U512,
     bonding_purse: URef,
     delegatee: PublicKey,
+    unclaimed_rewards: U512,
 }
 
 /// An entry in a founding validator map representing a bid.
@@ -120,6 +121,7 @@ impl From<Bid> for JsonBid {
                 staked_amount: *delegator.staked_amount(),
                 bonding_purse: *delegator.bonding_purse(),
                 delegatee: delegator.validator_public_key().clone(),
+                unclaimed_rewards: *delegator.unclaimed_rewards(),
             });
         }
         JsonBid {
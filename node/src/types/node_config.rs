@@ -1,5 +1,8 @@
+use std::fmt::{self, Display, Formatter};
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use toml::Value;
 
 use crate::types::BlockHash;
 
@@ -14,6 +17,86 @@ const DEFAULT_MAX_SYNC_FETCH_ATTEMPTS: u32 = 5;
 const DEFAULT_PEER_REDEMPTION_INTERVAL: u32 = 10_000;
 const DEFAULT_RETRY_INTERVAL: &str = "100ms";
 
+/// Number of server-sent events buffered by the event stream server of a validator.
+const VALIDATOR_EVENT_STREAM_BUFFER_LENGTH: i64 = 5000;
+/// Number of server-sent events buffered by the event stream server of an archive node.
+const ARCHIVE_EVENT_STREAM_BUFFER_LENGTH: i64 = 50_000;
+
+/// The role a node is run in.
+///
+/// Selecting a mode applies a profile of settings to several components so that they are
+/// configured coherently, and enables startup checks of the resources the mode requires.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeMode {
+    /// A validator, keeping recent history only, without an index, and buffering few events for
+    /// event stream clients.
+    Validator,
+    /// An RPC or archive node, synchronizing all history back to genesis, indexing it, and
+    /// buffering many events for event stream clients.
+    Archive,
+    /// A light node, keeping recent history only and running neither the indexer, the event
+    /// stream server nor the speculative execution server.
+    Light,
+}
+
+impl NodeMode {
+    /// Returns the settings applied by this mode, as `(section, key, value)` triples.
+    pub(crate) fn profile(self) -> Vec<(&'static str, &'static str, Value)> {
+        match self {
+            NodeMode::Validator => vec![
+                ("node", "sync_to_genesis", Value::Boolean(false)),
+                ("indexer", "enabled", Value::Boolean(false)),
+                ("event_stream_server", "enable_server", Value::Boolean(true)),
+                (
+                    "event_stream_server",
+                    "event_stream_buffer_length",
+                    Value::Integer(VALIDATOR_EVENT_STREAM_BUFFER_LENGTH),
+                ),
+                (
+                    "speculative_exec_server",
+                    "enable_server",
+                    Value::Boolean(false),
+                ),
+            ],
+            NodeMode::Archive => vec![
+                ("node", "sync_to_genesis", Value::Boolean(true)),
+                ("indexer", "enabled", Value::Boolean(true)),
+                ("event_stream_server", "enable_server", Value::Boolean(true)),
+                (
+                    "event_stream_server",
+                    "event_stream_buffer_length",
+                    Value::Integer(ARCHIVE_EVENT_STREAM_BUFFER_LENGTH),
+                ),
+            ],
+            NodeMode::Light => vec![
+                ("node", "sync_to_genesis", Value::Boolean(false)),
+                ("indexer", "enabled", Value::Boolean(false)),
+                (
+                    "event_stream_server",
+                    "enable_server",
+                    Value::Boolean(false),
+                ),
+                (
+                    "speculative_exec_server",
+                    "enable_server",
+                    Value::Boolean(false),
+                ),
+            ],
+        }
+    }
+}
+
+impl Display for NodeMode {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeMode::Validator => write!(formatter, "validator"),
+            NodeMode::Archive => write!(formatter, "archive"),
+            NodeMode::Light => write!(formatter, "light"),
+        }
+    }
+}
+
 /// Node fast-sync configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize, Clone)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -45,6 +128,18 @@ pub struct NodeConfig {
     /// Whether to run in sync-to-genesis mode which captures all data (blocks, deploys
     /// and global state) back to genesis.
     pub sync_to_genesis: bool,
+
+    /// If set, the upgrade to the next staged protocol version is tried out against a copy of the
+    /// global state this long before its estimated activation, without committing it.
+    pub upgrade_dry_run_lead_time: Option<TimeDiff>,
+
+    /// The role the node is run in. If set, the settings of the mode's profile take precedence
+    /// over the rest of the config file, and the resources required by it are checked at startup.
+    pub mode: Option<NodeMode>,
+
+    /// Whether to skip the startup checks of the resources required by the node's mode.
+    #[serde(default)]
+    pub skip_preflight_checks: bool,
 }
 
 impl Default for NodeConfig {
@@ -58,6 +153,9 @@ impl Default for NodeConfig {
             retry_interval: DEFAULT_RETRY_INTERVAL.parse().unwrap(),
             sync_peer_redemption_interval: DEFAULT_PEER_REDEMPTION_INTERVAL,
             sync_to_genesis: false,
+            upgrade_dry_run_lead_time: None,
+            mode: None,
+            skip_preflight_checks: false,
         }
     }
 }
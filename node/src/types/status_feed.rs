@@ -13,15 +13,16 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use casper_hashing::Digest;
-use casper_types::{EraId, ProtocolVersion, PublicKey, TimeDiff, Timestamp};
+use casper_types::{EraId, ProtocolVersion, PublicKey, Signature, TimeDiff, Timestamp};
 
 use crate::{
     components::{
         chain_synchronizer::Progress,
         chainspec_loader::NextUpgrade,
         rpc_server::rpcs::docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
+        small_network::NodeAttestation,
     },
-    types::{ActivationPoint, Block, BlockHash, NodeId, PeersMap},
+    types::{ActivationPoint, Block, BlockHash, Health, HealthReport, NodeId, PeersMap},
 };
 
 static CHAINSPEC_INFO: Lazy<ChainspecInfo> = Lazy::new(|| {
@@ -49,6 +50,13 @@ static GET_STATUS_RESULT: Lazy<GetStatusResult> = Lazy::new(|| {
         version: crate::VERSION_STRING.as_str(),
         node_uptime: Duration::from_secs(13),
         node_state: NodeState::Participating,
+        health: HealthReport::new(vec![
+            ("consensus", Health::ready()),
+            ("contract_runtime", Health::ready()),
+            ("indexer", Health::ready()),
+            ("network", Health::ready()),
+            ("storage", Health::ready()),
+        ]),
     };
     GetStatusResult::new(status_feed, DOCS_EXAMPLE_PROTOCOL_VERSION)
 });
@@ -110,6 +118,8 @@ pub struct StatusFeed {
     pub node_uptime: Duration,
     /// The current state of node.
     pub node_state: NodeState,
+    /// The health of the node and its major components.
+    pub health: HealthReport,
 }
 
 impl StatusFeed {
@@ -120,6 +130,7 @@ impl StatusFeed {
         consensus_status: Option<(PublicKey, Option<TimeDiff>)>,
         node_uptime: Duration,
         node_state: NodeState,
+        health: HealthReport,
     ) -> Self {
         let (our_public_signing_key, round_length) = match consensus_status {
             Some((public_key, round_length)) => (Some(public_key), round_length),
@@ -134,6 +145,7 @@ impl StatusFeed {
             version: crate::VERSION_STRING.as_str(),
             node_uptime,
             node_state,
+            health,
         }
     }
 }
@@ -191,6 +203,8 @@ pub struct GetStatusResult {
     pub uptime: TimeDiff,
     /// The current state of node.
     pub node_state: NodeState,
+    /// The health of the node and its major components.
+    pub health: HealthReport,
 }
 
 impl GetStatusResult {
@@ -207,6 +221,7 @@ impl GetStatusResult {
             next_upgrade: status_feed.chainspec_info.next_upgrade,
             uptime: status_feed.node_uptime.into(),
             node_state: status_feed.node_state,
+            health: status_feed.health,
             #[cfg(not(test))]
             build_version: crate::VERSION_STRING.clone(),
 
@@ -217,6 +232,55 @@ impl GetStatusResult {
     }
 }
 
+/// The part of a "signed-status" response covered by the signatures.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SignedStatusPayload {
+    /// The time at which the status was signed.
+    pub timestamp: Timestamp,
+    /// The status of the node.
+    pub status: GetStatusResult,
+}
+
+/// Result for the REST "signed-status" response.
+///
+/// Both signatures cover exactly the UTF-8 bytes of `payload`. The node signature is an ECDSA
+/// signature over the SHA512 hash of the payload, made with the node's TLS key; the SHA512 hash of
+/// `node_public_key` is the node ID.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GetSignedStatusResult {
+    /// The JSON-encoded `SignedStatusPayload`.
+    pub payload: String,
+    /// The ID of the node.
+    pub node_id: NodeId,
+    /// The hex-encoded compressed public TLS key of the node.
+    pub node_public_key: String,
+    /// The hex-encoded signature of the payload by the node's TLS key.
+    pub node_signature: String,
+    /// The validator key of the node, if it has one.
+    pub validator_public_key: Option<PublicKey>,
+    /// The signature of the payload by the validator key, if the node has one.
+    pub validator_signature: Option<Signature>,
+}
+
+impl GetSignedStatusResult {
+    pub(crate) fn new(payload: String, attestation: NodeAttestation) -> Self {
+        let (validator_public_key, validator_signature) = match attestation.validator_signature {
+            Some((public_key, signature)) => (Some(public_key), Some(signature)),
+            None => (None, None),
+        };
+        GetSignedStatusResult {
+            payload,
+            node_id: attestation.node_id,
+            node_public_key: base16::encode_lower(attestation.node_public_key.as_bytes()),
+            node_signature: base16::encode_lower(attestation.node_signature.as_bytes()),
+            validator_public_key,
+            validator_signature,
+        }
+    }
+}
+
 impl DocExample for GetStatusResult {
     fn doc_example() -> &'static Self {
         &*GET_STATUS_RESULT
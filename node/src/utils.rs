@@ -1,11 +1,14 @@
 //! Various functions that are not limited to a particular module, but are too small to warrant
 //! being factored out into standalone crates.
 
+pub(crate) mod clock;
 mod display_error;
 pub(crate) mod ds;
 mod external;
 pub(crate) mod fmt_limit;
+pub(crate) mod http_server;
 pub(crate) mod opt_display;
+pub(crate) mod preflight;
 pub(crate) mod rlimit;
 pub(crate) mod round_robin;
 pub(crate) mod umask;
@@ -27,7 +30,6 @@ use std::{
 };
 
 use datasize::DataSize;
-use hyper::server::{conn::AddrIncoming, Builder, Server};
 #[cfg(test)]
 use once_cell::sync::Lazy;
 use prometheus::{self, Histogram, HistogramOpts, Registry};
@@ -131,21 +133,38 @@ pub(crate) enum ListeningError {
         /// The failure reason.
         error: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    /// Failed to load the TLS certificate chain or private key.
+    #[error("failed to set up tls using {cert_path:?} and {key_path:?}: {error}")]
+    Tls {
+        /// Path to the certificate chain.
+        cert_path: PathBuf,
+        /// Path to the private key.
+        key_path: PathBuf,
+        /// The failure reason.
+        error: openssl::error::ErrorStack,
+    },
+
+    /// Failed to register the handler reloading the TLS certificate.
+    #[error("failed to register SIGHUP handler: {0}")]
+    RegisterSignal(io::Error),
+
+    /// The configured CORS policy is invalid.
+    #[error("invalid cors policy: {0}")]
+    InvalidCorsPolicy(String),
 }
 
-pub(crate) fn start_listening(address: &str) -> Result<Builder<AddrIncoming>, ListeningError> {
+/// Starts listening on `address`, terminating TLS if enabled in `tls`.
+pub(crate) fn start_listening(
+    address: &str,
+    tls: WithDir<&http_server::TlsConfig>,
+) -> Result<http_server::HttpIncoming, ListeningError> {
     let address = resolve_address(address).map_err(|error| {
         warn!(%error, %address, "failed to start HTTP server, cannot parse address");
         ListeningError::ResolveAddress(error)
     })?;
 
-    Server::try_bind(&address).map_err(|error| {
-        warn!(%error, %address, "failed to start HTTP server");
-        ListeningError::Listen {
-            address,
-            error: Box::new(error),
-        }
-    })
+    http_server::bind(address, tls)
 }
 
 /// Moves a value to the heap and then forgets about, leaving only a static reference behind.
@@ -0,0 +1,59 @@
+//! The clock used by components.
+//!
+//! Components should obtain the current time through [`now`] rather than calling
+//! `Timestamp::now()` directly, and wait using `EffectBuilder::set_timeout`, which is backed by
+//! tokio's timers.
+//!
+//! In tests, both follow tokio's clock: once it is paused, time only passes when advanced manually
+//! (see `testing::advance_time`), which fires due timers and moves [`now`] forward by the same
+//! amount. Timeout-related tests thus run instantly and deterministically.
+
+#[cfg(test)]
+use once_cell::sync::Lazy;
+#[cfg(test)]
+use tokio::time::Instant;
+
+#[cfg(test)]
+use casper_types::TimeDiff;
+use casper_types::Timestamp;
+
+/// The wall clock time and tokio instant at which the simulated clock was first read.
+#[cfg(test)]
+static EPOCH: Lazy<(Timestamp, Instant)> = Lazy::new(|| (Timestamp::now(), Instant::now()));
+
+/// Returns the current time.
+#[cfg(not(test))]
+pub(crate) fn now() -> Timestamp {
+    Timestamp::now()
+}
+
+/// Returns the current simulated time.
+///
+/// Starts out at the wall clock time when first read, then follows tokio's clock.
+#[cfg(test)]
+pub(crate) fn now() -> Timestamp {
+    let (epoch_timestamp, epoch_instant) = *EPOCH;
+    let elapsed = Instant::now().saturating_duration_since(epoch_instant);
+    epoch_timestamp + TimeDiff::from(elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use casper_types::TimeDiff;
+
+    use super::now;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn should_follow_advanced_time() {
+        let before = now();
+
+        testing::advance_time(Duration::from_secs(3_600)).await;
+
+        let elapsed = now().saturating_diff(before);
+        assert!(elapsed >= TimeDiff::from_seconds(3_600));
+        assert!(elapsed < TimeDiff::from_seconds(3_660));
+    }
+}
@@ -22,7 +22,7 @@ use thiserror::Error;
 
 use casper_types::{
     file_utils::{read_file, ReadFileError},
-    SecretKey,
+    PublicKey, SecretKey,
 };
 
 use crate::tls::{self, LoadCertError, LoadSecretKeyError};
@@ -176,6 +176,14 @@ impl Loadable for Arc<SecretKey> {
     }
 }
 
+impl Loadable for PublicKey {
+    type Error = crypto::ErrorExt;
+
+    fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Self::Error> {
+        PublicKey::from_file(path)
+    }
+}
+
 impl Loadable for Vec<u8> {
     type Error = ReadFileError;
 
@@ -0,0 +1,514 @@
+//! Settings and listeners shared by the node's HTTP servers.
+//!
+//! Each HTTP server can optionally terminate TLS itself, in which case the certificate and
+//! private key are reloaded from disk upon receiving `SIGHUP`, and exposes a configurable CORS
+//! policy, allowing the servers to be accessed directly from browsers.
+
+use std::{
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use http::{
+    header::HeaderName,
+    uri::{Authority, Scheme},
+};
+use hyper::server::{
+    accept::Accept,
+    conn::{AddrIncoming, AddrStream},
+};
+use openssl::{
+    error::ErrorStack,
+    ssl::{Ssl, SslAcceptor, SslFiletype, SslMethod},
+};
+use serde::{Deserialize, Serialize};
+use signal_hook::{consts::signal::SIGHUP, flag, low_level, SigId};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time,
+};
+use tokio_openssl::SslStream;
+use tracing::{debug, info, warn};
+
+use super::{ListeningError, WithDir};
+
+/// Value of `allowed_origins` allowing requests from any origin.
+const ANY_ORIGIN: &str = "*";
+
+/// Time after which a client that has not completed the TLS handshake is disconnected.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS configuration of an HTTP server.
+#[derive(Clone, DataSize, Debug, Default, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(default, deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Whether the server accepts TLS connections only, instead of plain HTTP ones.
+    pub enabled: bool,
+    /// Path to the PEM-encoded certificate chain, relative to the config file.
+    pub cert_path: PathBuf,
+    /// Path to the PEM-encoded private key, relative to the config file.
+    pub key_path: PathBuf,
+}
+
+/// CORS policy of an HTTP server.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests, e.g. "https://example.com".
+    ///
+    /// A single "*" allows any origin, an empty list disallows all cross-origin requests.
+    pub allowed_origins: Vec<String>,
+    /// Request headers allowed in cross-origin requests, in addition to the ones required by the
+    /// server itself.
+    pub allowed_headers: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: vec![ANY_ORIGIN.to_string()],
+            allowed_headers: vec![],
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Creates a warp CORS filter builder enforcing this policy.
+    pub(crate) fn to_builder(&self) -> Result<warp::cors::Builder, ListeningError> {
+        let mut builder = warp::cors();
+
+        let allow_any_origin = self
+            .allowed_origins
+            .iter()
+            .any(|origin| origin == ANY_ORIGIN);
+        if allow_any_origin {
+            if self.allowed_origins.len() > 1 {
+                return Err(ListeningError::InvalidCorsPolicy(format!(
+                    "'{}' cannot be combined with other allowed origins",
+                    ANY_ORIGIN
+                )));
+            }
+            builder = builder.allow_any_origin();
+        } else {
+            // Warp panics on invalid origins, so we check them upfront.
+            for origin in &self.allowed_origins {
+                if !is_valid_origin(origin) {
+                    return Err(ListeningError::InvalidCorsPolicy(format!(
+                        "invalid origin '{}'",
+                        origin
+                    )));
+                }
+            }
+            builder = builder.allow_origins(self.allowed_origins.iter().map(String::as_str));
+        }
+
+        for header in &self.allowed_headers {
+            let header = HeaderName::from_str(header).map_err(|_| {
+                ListeningError::InvalidCorsPolicy(format!("invalid header '{}'", header))
+            })?;
+            builder = builder.allow_header(header);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Checks whether `origin` is of the form "scheme://host[:port]".
+fn is_valid_origin(origin: &str) -> bool {
+    match origin.split_once("://") {
+        Some((scheme, authority)) => {
+            Scheme::from_str(scheme).is_ok()
+                && Authority::from_str(authority).is_ok()
+                && !authority.contains('@')
+        }
+        None => false,
+    }
+}
+
+/// Binds an HTTP server's listener, terminating TLS if enabled in `tls`.
+pub(crate) fn bind(
+    address: SocketAddr,
+    tls: WithDir<&TlsConfig>,
+) -> Result<HttpIncoming, ListeningError> {
+    let incoming = AddrIncoming::bind(&address).map_err(|error| {
+        warn!(%error, %address, "failed to start HTTP server");
+        ListeningError::Listen {
+            address,
+            error: Box::new(error),
+        }
+    })?;
+
+    let tls = if tls.value().enabled {
+        let cert_path = tls.with_dir(tls.value().cert_path.clone());
+        let key_path = tls.with_dir(tls.value().key_path.clone());
+        Some(TlsTerminator::new(cert_path, key_path)?)
+    } else {
+        None
+    };
+
+    Ok(HttpIncoming { incoming, tls })
+}
+
+/// Incoming connections of an HTTP server, optionally with TLS terminated.
+pub(crate) struct HttpIncoming {
+    /// The incoming TCP connections.
+    incoming: AddrIncoming,
+    /// The TLS terminator, if TLS is enabled.
+    tls: Option<TlsTerminator>,
+}
+
+impl HttpIncoming {
+    /// Returns the local address the server is bound to.
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.incoming.local_addr()
+    }
+}
+
+impl Accept for HttpIncoming {
+    type Conn = HttpConnection;
+    type Error = io::Error;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let this = self.get_mut();
+
+        let tls = match this.tls.as_mut() {
+            Some(tls) => tls,
+            None => {
+                return Pin::new(&mut this.incoming)
+                    .poll_accept(cx)
+                    .map_ok(HttpConnection::Plain)
+            }
+        };
+
+        // Start the handshake for every new connection, so that slow clients do not hold up
+        // others.
+        loop {
+            match Pin::new(&mut this.incoming).poll_accept(cx) {
+                Poll::Ready(Some(Ok(stream))) => tls.start_handshake(stream),
+                Poll::Ready(Some(Err(error))) => return Poll::Ready(Some(Err(error))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => break,
+            }
+        }
+
+        loop {
+            match tls.handshakes.poll_next_unpin(cx) {
+                Poll::Ready(Some((_, Ok(stream)))) => {
+                    return Poll::Ready(Some(Ok(HttpConnection::Tls(stream))))
+                }
+                Poll::Ready(Some((remote_addr, Err(error)))) => {
+                    debug!(%remote_addr, %error, "TLS handshake with HTTP client failed");
+                }
+                // No handshakes pending, we will be woken up by the next incoming connection.
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// An error completing the TLS handshake with a client.
+#[derive(Debug, Error)]
+enum HandshakeError {
+    /// Failed to set up the TLS stream.
+    #[error("failed to initialize TLS: {0}")]
+    Initialization(#[from] ErrorStack),
+    /// The handshake failed.
+    #[error("TLS handshake failed: {0}")]
+    Handshake(#[from] openssl::ssl::Error),
+    /// The client did not complete the handshake in time.
+    #[error("TLS handshake timed out")]
+    TimedOut,
+}
+
+/// The outcome of a TLS handshake with the client at the given address.
+type HandshakeResult = (SocketAddr, Result<SslStream<AddrStream>, HandshakeError>);
+
+/// Terminates TLS on incoming connections.
+struct TlsTerminator {
+    /// Path to the PEM-encoded certificate chain.
+    cert_path: PathBuf,
+    /// Path to the PEM-encoded private key.
+    key_path: PathBuf,
+    /// The acceptor used for new connections.
+    acceptor: SslAcceptor,
+    /// Flag set by the `SIGHUP` handler.
+    reload_requested: Arc<AtomicBool>,
+    /// ID of the `SIGHUP` handler, unregistered on drop.
+    signal_id: SigId,
+    /// Handshakes in progress.
+    handshakes: FuturesUnordered<BoxFuture<'static, HandshakeResult>>,
+}
+
+impl TlsTerminator {
+    /// Creates a new TLS terminator, loading the certificate chain and private key.
+    fn new(cert_path: PathBuf, key_path: PathBuf) -> Result<Self, ListeningError> {
+        let acceptor = load_acceptor(&cert_path, &key_path).map_err(|error| {
+            warn!(%error, ?cert_path, ?key_path, "failed to set up TLS for HTTP server");
+            ListeningError::Tls {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                error,
+            }
+        })?;
+
+        let reload_requested = Arc::new(AtomicBool::new(false));
+        let signal_id = flag::register(SIGHUP, Arc::clone(&reload_requested))
+            .map_err(ListeningError::RegisterSignal)?;
+
+        Ok(TlsTerminator {
+            cert_path,
+            key_path,
+            acceptor,
+            reload_requested,
+            signal_id,
+            handshakes: FuturesUnordered::new(),
+        })
+    }
+
+    /// Starts the TLS handshake with a newly connected client.
+    fn start_handshake(&mut self, stream: AddrStream) {
+        if self.reload_requested.swap(false, Ordering::SeqCst) {
+            self.reload();
+        }
+
+        let remote_addr = stream.remote_addr();
+        let ssl = Ssl::new(self.acceptor.context());
+        let handshake = async move {
+            let mut tls_stream = SslStream::new(ssl?, stream)?;
+            time::timeout(TLS_HANDSHAKE_TIMEOUT, Pin::new(&mut tls_stream).accept())
+                .await
+                .map_err(|_elapsed| HandshakeError::TimedOut)??;
+            Ok::<_, HandshakeError>(tls_stream)
+        };
+        self.handshakes
+            .push(handshake.map(move |result| (remote_addr, result)).boxed());
+    }
+
+    /// Reloads the certificate chain and private key, keeping the current ones on failure.
+    fn reload(&mut self) {
+        match load_acceptor(&self.cert_path, &self.key_path) {
+            Ok(acceptor) => {
+                info!(cert_path = ?self.cert_path, "reloaded TLS certificate of HTTP server");
+                self.acceptor = acceptor;
+            }
+            Err(error) => {
+                warn!(
+                    %error,
+                    cert_path = ?self.cert_path,
+                    "failed to reload TLS certificate of HTTP server, keeping the current one"
+                );
+            }
+        }
+    }
+}
+
+impl Drop for TlsTerminator {
+    fn drop(&mut self) {
+        low_level::unregister(self.signal_id);
+    }
+}
+
+/// Creates a TLS acceptor suitable for browsers from the given PEM files.
+fn load_acceptor(cert_path: &Path, key_path: &Path) -> Result<SslAcceptor, ErrorStack> {
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+    builder.set_certificate_chain_file(cert_path)?;
+    builder.set_private_key_file(key_path, SslFiletype::PEM)?;
+    builder.check_private_key()?;
+    Ok(builder.build())
+}
+
+/// A connection accepted by an HTTP server.
+pub(crate) enum HttpConnection {
+    /// A plain HTTP connection.
+    Plain(AddrStream),
+    /// A connection with TLS terminated.
+    Tls(SslStream<AddrStream>),
+}
+
+impl HttpConnection {
+    /// Returns the address of the connected client.
+    pub(crate) fn remote_addr(&self) -> SocketAddr {
+        match self {
+            HttpConnection::Plain(stream) => stream.remote_addr(),
+            HttpConnection::Tls(stream) => stream.get_ref().remote_addr(),
+        }
+    }
+}
+
+impl AsyncRead for HttpConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpConnection::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            HttpConnection::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for HttpConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            HttpConnection::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            HttpConnection::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpConnection::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            HttpConnection::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            HttpConnection::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            HttpConnection::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::tls;
+
+    #[test]
+    fn should_accept_valid_cors_policies() {
+        assert!(CorsConfig::default().to_builder().is_ok());
+
+        let cors = CorsConfig {
+            allowed_origins: vec![
+                "https://example.com".to_string(),
+                "http://localhost:8080".to_string(),
+            ],
+            allowed_headers: vec!["x-api-key".to_string()],
+        };
+        assert!(cors.to_builder().is_ok());
+
+        let cors = CorsConfig {
+            allowed_origins: vec![],
+            allowed_headers: vec![],
+        };
+        assert!(cors.to_builder().is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_cors_policies() {
+        let invalid_origins = [
+            vec!["example.com"],
+            vec!["https://"],
+            vec!["https://user@example.com"],
+            vec!["*", "https://example.com"],
+        ];
+        for origins in invalid_origins {
+            let cors = CorsConfig {
+                allowed_origins: origins.iter().map(ToString::to_string).collect(),
+                allowed_headers: vec![],
+            };
+            assert!(
+                matches!(cors.to_builder(), Err(ListeningError::InvalidCorsPolicy(_))),
+                "{:?} should be rejected",
+                origins
+            );
+        }
+
+        let cors = CorsConfig {
+            allowed_headers: vec!["not a header".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            cors.to_builder(),
+            Err(ListeningError::InvalidCorsPolicy(_))
+        ));
+    }
+
+    /// Writes a freshly generated certificate and private key to `dir`.
+    fn write_cert_and_key(dir: &Path) {
+        let (cert, key) = tls::generate_node_cert().unwrap();
+        fs::write(dir.join("cert.pem"), cert.to_pem().unwrap()).unwrap();
+        fs::write(dir.join("key.pem"), key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+    }
+
+    fn tls_config() -> TlsConfig {
+        TlsConfig {
+            enabled: true,
+            cert_path: "cert.pem".into(),
+            key_path: "key.pem".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_bind_with_missing_certificate() {
+        let dir = TempDir::new().unwrap();
+        let address = "127.0.0.1:0".parse().unwrap();
+
+        let result = bind(address, WithDir::new(dir.path(), &tls_config()));
+        assert!(matches!(result, Err(ListeningError::Tls { .. })));
+    }
+
+    #[tokio::test]
+    async fn should_terminate_tls() {
+        let dir = TempDir::new().unwrap();
+        write_cert_and_key(dir.path());
+        let address = "127.0.0.1:0".parse().unwrap();
+
+        let mut incoming = bind(address, WithDir::new(dir.path(), &tls_config())).unwrap();
+        let local_addr = incoming.local_addr();
+
+        let client = tokio::spawn(async move {
+            let mut connector = openssl::ssl::SslConnector::builder(SslMethod::tls_client())
+                .unwrap()
+                .build()
+                .configure()
+                .unwrap();
+            connector.set_verify_hostname(false);
+            connector.set_verify(openssl::ssl::SslVerifyMode::NONE);
+            let ssl = connector.into_ssl("localhost").unwrap();
+
+            let stream = TcpStream::connect(local_addr).await.unwrap();
+            let mut tls_stream = SslStream::new(ssl, stream).unwrap();
+            Pin::new(&mut tls_stream).connect().await.unwrap();
+            tls_stream
+        });
+
+        let connection = futures::future::poll_fn(|cx| Pin::new(&mut incoming).poll_accept(cx))
+            .await
+            .expect("incoming should not end")
+            .expect("should accept connection");
+        assert!(matches!(connection, HttpConnection::Tls(_)));
+        assert_eq!(
+            connection.remote_addr(),
+            client.await.unwrap().get_ref().local_addr().unwrap()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Startup checks of the resources required by the node's mode.
+//!
+//! These are only run if a [`NodeMode`] is configured, and catch misconfigured hosts before the
+//! node starts joining the network, rather than once it runs out of disk space or file handles.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use super::rlimit::{Limit, OpenFiles, ResourceLimit};
+use crate::types::NodeMode;
+
+/// One gibibyte.
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// The resources a node running in a particular mode requires.
+#[derive(Copy, Clone, Debug)]
+struct Requirements {
+    /// Minimum free space on the storage volume, in bytes.
+    disk_space: u64,
+    /// Minimum hard limit for open files.
+    open_files: Limit,
+    /// Whether an unsynchronized system clock fails the checks rather than just being reported.
+    clock_sync: bool,
+}
+
+impl Requirements {
+    fn of(mode: NodeMode) -> Self {
+        match mode {
+            NodeMode::Validator => Requirements {
+                disk_space: 100 * GIB,
+                open_files: 64_000,
+                clock_sync: true,
+            },
+            NodeMode::Archive => Requirements {
+                disk_space: 500 * GIB,
+                open_files: 64_000,
+                clock_sync: false,
+            },
+            NodeMode::Light => Requirements {
+                disk_space: 20 * GIB,
+                open_files: 4_096,
+                clock_sync: false,
+            },
+        }
+    }
+}
+
+/// A failed preflight check.
+#[derive(Debug, Error)]
+pub(crate) enum PreflightFailure {
+    /// Not enough free space on the storage volume.
+    #[error(
+        "only {available} bytes of disk space available at {}, {mode} mode requires {required}",
+        path.display()
+    )]
+    DiskSpace {
+        mode: NodeMode,
+        path: PathBuf,
+        available: u64,
+        required: u64,
+    },
+    /// The free space on the storage volume could not be determined.
+    #[error("could not determine available disk space at {}: {error}", path.display())]
+    UnknownDiskSpace {
+        path: PathBuf,
+        #[source]
+        error: io::Error,
+    },
+    /// The open files limit is too low.
+    #[error("open files hard limit is {limit}, {mode} mode requires {required}")]
+    OpenFiles {
+        mode: NodeMode,
+        limit: Limit,
+        required: Limit,
+    },
+    /// The open files limit could not be determined.
+    #[error("could not determine open files limit: {0}")]
+    UnknownOpenFiles(#[source] io::Error),
+    /// The system clock is not synchronized.
+    #[error("system clock is not synchronized, which {mode} mode requires")]
+    ClockNotSynchronized { mode: NodeMode },
+}
+
+/// Checks that the host provides the resources required by `mode`, returning all failed checks.
+///
+/// `storage_path` is the configured storage directory, which need not exist yet.
+pub(crate) fn run_preflight_checks(mode: NodeMode, storage_path: &Path) -> Vec<PreflightFailure> {
+    let requirements = Requirements::of(mode);
+    let mut failures = Vec::new();
+
+    // The storage directory is only created later, so we check the volume of its nearest existing
+    // ancestor.
+    let existing_path = storage_path
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or(storage_path);
+    match fs2::available_space(existing_path) {
+        Ok(available) if available < requirements.disk_space => {
+            failures.push(PreflightFailure::DiskSpace {
+                mode,
+                path: storage_path.to_path_buf(),
+                available,
+                required: requirements.disk_space,
+            })
+        }
+        Ok(available) => debug!(%available, "sufficient disk space available"),
+        Err(error) => failures.push(PreflightFailure::UnknownDiskSpace {
+            path: storage_path.to_path_buf(),
+            error,
+        }),
+    }
+
+    // The soft limit is raised towards the hard limit at startup, so only the latter matters.
+    match ResourceLimit::<OpenFiles>::get() {
+        Ok(limit) if limit.max() < requirements.open_files => {
+            failures.push(PreflightFailure::OpenFiles {
+                mode,
+                limit: limit.max(),
+                required: requirements.open_files,
+            })
+        }
+        Ok(limit) => debug!(?limit, "sufficient open files limit"),
+        Err(error) => failures.push(PreflightFailure::UnknownOpenFiles(error)),
+    }
+
+    match clock_synchronized() {
+        Some(true) => debug!("system clock is synchronized"),
+        Some(false) if requirements.clock_sync => {
+            failures.push(PreflightFailure::ClockNotSynchronized { mode })
+        }
+        Some(false) => warn!("system clock is not synchronized"),
+        None => info!("could not determine whether the system clock is synchronized"),
+    }
+
+    failures
+}
+
+/// Returns whether the kernel considers the system clock synchronized, if it can be determined.
+#[cfg(target_os = "linux")]
+fn clock_synchronized() -> Option<bool> {
+    let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+    // With `modes` being zero, this only reads the clock state.
+    match unsafe { libc::adjtimex(&mut timex) } {
+        -1 => None,
+        libc::TIME_ERROR => Some(false),
+        _ => Some(true),
+    }
+}
+
+/// Returns whether the kernel considers the system clock synchronized, if it can be determined.
+#[cfg(not(target_os = "linux"))]
+fn clock_synchronized() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_check_nearest_existing_ancestor_of_storage_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().join("does").join("not").join("exist");
+
+        let failures = run_preflight_checks(NodeMode::Light, &storage_path);
+        assert!(!failures
+            .iter()
+            .any(|failure| matches!(failure, PreflightFailure::UnknownDiskSpace { .. })));
+    }
+}
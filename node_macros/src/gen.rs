@@ -1,19 +1,21 @@
 use crate::{
-    parse::{ReactorDefinition, Target},
+    parse::{QueuePolicy, ReactorDefinition, Target, METRICS_ERROR_CODE},
     util::suffix_ident,
 };
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::Ident;
 
 /// Generates the top level reactor `struct`.
 ///
-/// Will generate a field for each component to be used.
+/// Will generate a field for each component to be used. Fields are declared in reverse construction
+/// order, so that dropping the reactor drops every component before its dependencies.
 pub(crate) fn generate_reactor(def: &ReactorDefinition) -> TokenStream {
     let reactor_ident = def.reactor_ident();
 
     let mut reactor_fields = Vec::new();
 
-    for component in def.components() {
+    for component in def.components_in_construction_order().rev() {
         let field_name = component.field_ident();
         let full_type = component.full_component_type();
 
@@ -40,7 +42,11 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
     let mut display_variants = Vec::new();
     let mut error_display_variants = Vec::new();
     let mut error_source_variants = Vec::new();
+    let mut error_debug_variants = Vec::new();
+    let mut error_code_variants = Vec::new();
     let mut from_impls = Vec::new();
+    let mut queue_kind_variants = Vec::new();
+    let mut overflow_policy_variants = Vec::new();
 
     for component in def.components() {
         let variant_ident = component.variant_ident();
@@ -48,28 +54,54 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
         let full_error_type = component.full_error_type(quote!(#event_ident));
         let field_name = component.field_ident().to_string();
 
-        let event_variant_doc = format!("Event from `{}` component", field_name);
-        event_variants.push(quote!(
-            #[doc = #event_variant_doc]
-            #variant_ident(#full_event_type)));
-
         let error_variant_doc = format!("Error constructing `{}` component", field_name);
         error_variants.push(quote!(
             #[doc = #error_variant_doc]
             #variant_ident(#full_error_type)));
 
-        display_variants.push(quote!(
-            #event_ident::#variant_ident(inner) => write!(f, "{}: {}", #field_name, inner)
-        ));
-
         error_display_variants.push(quote!(
-            #error_ident::#variant_ident(inner) => write!(f, "{}: {}", #field_name, inner)
+            #error_ident::#variant_ident(_) => write!(f, "failed to construct {}", #field_name)
         ));
 
         error_source_variants.push(quote!(
             #error_ident::#variant_ident(inner) => Some(inner)
         ));
 
+        let error_code = def.error_code(component);
+        error_debug_variants.push(quote!(
+            #error_ident::#variant_ident(inner) => f
+                .debug_struct(stringify!(#error_ident))
+                .field("component", &#field_name)
+                .field("code", &#error_code)
+                .field("error", inner)
+                .finish()
+        ));
+
+        error_code_variants.push(quote!(
+            #error_ident::#variant_ident(_) => #error_code
+        ));
+
+        // Event sources never receive events, so their events need no variant.
+        if component.is_event_source() {
+            continue;
+        }
+
+        let event_variant_doc = format!("Event from `{}` component", field_name);
+        event_variants.push(quote!(
+            #[doc = #event_variant_doc]
+            #variant_ident(#full_event_type)));
+
+        display_variants.push(quote!(
+            #event_ident::#variant_ident(inner) => write!(f, "{}: {}", #field_name, inner)
+        ));
+
+        push_queue_policy_variants(
+            &mut queue_kind_variants,
+            &mut overflow_policy_variants,
+            quote!(#event_ident::#variant_ident(_)),
+            component.queue_policy(),
+        );
+
         from_impls.push(quote!(
             impl From<#full_event_type> for #event_ident {
                 fn from(event: #full_event_type) -> Self {
@@ -96,6 +128,13 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
            #event_ident::#variant_ident(inner) => ::std::fmt::Display::fmt(inner, f)
         ));
 
+        push_queue_policy_variants(
+            &mut queue_kind_variants,
+            &mut overflow_policy_variants,
+            quote!(#event_ident::#variant_ident(_)),
+            request.queue_policy(),
+        );
+
         from_impls.push(quote!(
             impl From<#full_request_type> for #event_ident {
                 fn from(request: #full_request_type) -> Self {
@@ -118,6 +157,13 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
            #event_ident::#variant_ident(inner) => ::std::fmt::Display::fmt(inner, f)
         ));
 
+        push_queue_policy_variants(
+            &mut queue_kind_variants,
+            &mut overflow_policy_variants,
+            quote!(#event_ident::#variant_ident(_)),
+            announcement.queue_policy(),
+        );
+
         from_impls.push(quote!(
             impl From<#full_announcement_type> for #event_ident {
                 fn from(announcement: #full_announcement_type) -> Self {
@@ -129,6 +175,7 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
 
     let event_docs = format!("Events of `{}` reactor.", reactor_ident);
     let error_docs = format!("Construction errors of `{}` reactor.", reactor_ident);
+    let metrics_error_code = METRICS_ERROR_CODE;
 
     quote!(
         #[doc = #event_docs]
@@ -154,10 +201,21 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
                     None
                 }
             }
+
+            fn queue_kind(&self) -> crate::reactor::QueueKind {
+                match self {
+                    #(#queue_kind_variants,)*
+                }
+            }
+
+            fn overflow_policy(&self) -> crate::reactor::OverflowPolicy {
+                match self {
+                    #(#overflow_policy_variants,)*
+                }
+            }
         }
 
         #[doc = #error_docs]
-        #[derive(Debug)]
         pub(crate) enum #error_ident {
             #(#error_variants,)*
             /// Failure to initialize metrics.
@@ -176,7 +234,32 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 match self {
                     #(#error_display_variants,)*
-                    #error_ident::MetricsInitialization(err) => write!(f, "metrics_initialization: {}", err),
+                    #error_ident::MetricsInitialization(_) => write!(f, "failed to initialize metrics"),
+                }
+            }
+        }
+
+        impl std::fmt::Debug for #error_ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #(#error_debug_variants,)*
+                    #error_ident::MetricsInitialization(inner) => f
+                        .debug_struct(stringify!(#error_ident))
+                        .field("component", &"metrics")
+                        .field("code", &#metrics_error_code)
+                        .field("error", inner)
+                        .finish(),
+                }
+            }
+        }
+
+        impl #error_ident {
+            /// Returns the stable numeric code of the error, usable as a process exit code.
+            #[allow(dead_code)]
+            pub(crate) fn code(&self) -> u8 {
+                match self {
+                    #(#error_code_variants,)*
+                    #error_ident::MetricsInitialization(_) => #metrics_error_code,
                 }
             }
         }
@@ -200,6 +283,32 @@ pub(crate) fn generate_reactor_types(def: &ReactorDefinition) -> TokenStream {
     )
 }
 
+/// Adds the match arms mapping an event variant to its queue kind and overflow policy.
+///
+/// Variants without a declared queue are scheduled on the default queue with its default policy.
+fn push_queue_policy_variants(
+    queue_kind_variants: &mut Vec<TokenStream>,
+    overflow_policy_variants: &mut Vec<TokenStream>,
+    pattern: TokenStream,
+    queue_policy: Option<&QueuePolicy>,
+) {
+    let (queue_kind, overflow_policy) = match queue_policy {
+        Some(queue_policy) => (
+            queue_policy.full_queue_kind(),
+            queue_policy.full_overflow_policy(),
+        ),
+        None => (
+            quote!(crate::reactor::QueueKind::Regular),
+            quote!(crate::reactor::OverflowPolicy::for_queue(
+                crate::reactor::QueueKind::Regular
+            )),
+        ),
+    };
+
+    queue_kind_variants.push(quote!(#pattern => #queue_kind));
+    overflow_policy_variants.push(quote!(#pattern => #overflow_policy));
+}
+
 /// Generates the reactor implementation itself.
 pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
     let reactor_ident = def.reactor_ident();
@@ -209,8 +318,11 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
 
     let mut dispatches = Vec::new();
 
-    // Generate dispatches for component events.
-    for component in def.components() {
+    // Generate dispatches for component events. Event sources have none.
+    for component in def
+        .components()
+        .filter(|component| !component.is_event_source())
+    {
         let variant_ident = component.variant_ident();
         let full_component_type = component.full_component_type();
         let field_ident = component.field_ident();
@@ -247,6 +359,16 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
                     },
                 ));
             }
+            Target::Dest(ref dest) if def.component(dest).is_event_source() => {
+                let warning = event_source_route_warning(dest, &request_variant_ident);
+                dispatches.push(quote!(
+                    #event_ident::#request_variant_ident(request) => {
+                        #warning
+                        drop(request);
+                        Default::default()
+                    },
+                ));
+            }
             Target::Dest(ref dest) => {
                 let dest_component_type = def.component(dest).full_component_type();
                 let dest_variant_ident = def.component(dest).variant_ident();
@@ -292,6 +414,12 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
                                announcement);
                     ));
                 }
+                Target::Dest(ref dest) if def.component(dest).is_event_source() => {
+                    announcement_dispatches.push(event_source_route_warning(
+                        dest,
+                        &announcement_variant_ident,
+                    ));
+                }
                 Target::Dest(ref dest) => {
                     let dest_component_type = def.component(dest).full_component_type();
                     let dest_variant_ident = def.component(dest).variant_ident();
@@ -320,6 +448,7 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
         }
 
         dispatches.push(quote!(
+            #[allow(unused_variables)]
             #event_ident::#announcement_variant_ident(announcement) => {
                 let mut announcement_effects = crate::effect::Multiple::new();
 
@@ -333,37 +462,80 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
     let mut component_instantiations = Vec::new();
     let mut component_fields = Vec::new();
 
-    for cdef in def.components() {
+    for cdef in def.components_in_construction_order() {
         let field_ident = cdef.field_ident();
         let component_type = cdef.full_component_type();
         let variant_ident = cdef.variant_ident();
 
         let constructor_args = cdef.component_arguments();
 
+        // Dependencies are passed to the constructor by reference, shadowing the owned values.
+        let dependencies = cdef.dependencies();
+
+        // A declared config is merged from its default and the overrides in its environment
+        // variable, and passed to the constructor as `config`.
+        let config = cdef.config().map(|config_def| {
+            let config_type = config_def.config_type();
+            let default_value = config_def.default_value();
+            let env_override = config_def.env_var().map(|env_var| {
+                quote!(
+                    let config = crate::reactor::apply_config_override(config, #env_var);
+                )
+            });
+            quote!(
+                let config: #config_type = #default_value;
+                #env_override
+            )
+        });
+
         let suffix = if cdef.is_infallible() {
             quote!()
         } else {
             quote!(.map_err(#error_ident::#variant_ident)?)
         };
 
+        // Event sources have no event variant, their effects must not produce any events.
+        let wrap = if cdef.is_event_source() {
+            let panic_message = format!("event source `{}` produced an event", field_ident);
+            quote!(|_event| -> #event_ident { panic!(#panic_message) })
+        } else {
+            quote!(#event_ident::#variant_ident)
+        };
+
         if cdef.has_effects() {
             component_instantiations.push(quote!(
-                let (#field_ident, effects) = #component_type::new(#(#constructor_args),*)
-                    #suffix;
-                let wrapped_effects: crate::effect::Effects<#event_ident> = crate::reactor::wrap_effects(#event_ident::#variant_ident, effects);
+                let (#field_ident, effects) = {
+                    #(#[allow(unused_variables)] let #dependencies = &#dependencies;)*
+                    #config
+                    #component_type::new(#(#constructor_args),*)
+                        #suffix
+                };
+                let wrapped_effects: crate::effect::Effects<#event_ident> = crate::reactor::wrap_effects(#wrap, effects);
 
                 all_effects.extend(wrapped_effects.into_iter());
             ));
         } else {
             component_instantiations.push(quote!(
-                let #field_ident = #component_type::new(#(#constructor_args),*)
-                    #suffix;
+                let #field_ident = {
+                    #(#[allow(unused_variables)] let #dependencies = &#dependencies;)*
+                    #config
+                    #component_type::new(#(#constructor_args),*)
+                        #suffix
+                };
             ));
         }
 
         component_fields.push(quote!(#field_ident));
     }
 
+    // Only bind the reactor mutably if there are hooks to call on it.
+    let init_hooks: Vec<_> = def.init_hooks().collect();
+    let reactor_binding = if init_hooks.is_empty() {
+        quote!(reactor)
+    } else {
+        quote!(mut reactor)
+    };
+
     quote!(
         #[allow(unreachable_code)]
         impl crate::reactor::Reactor for #reactor_ident {
@@ -392,14 +564,17 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
 
                 let effect_builder = crate::effect::EffectBuilder::new(event_queue);
 
-                // Instantiate each component.
+                // Instantiate each component after its dependencies.
                 #(#component_instantiations)*
 
                 // Assign component fields during reactor construction.
-                let reactor = #reactor_ident {
+                let #reactor_binding = #reactor_ident {
                     #(#component_fields,)*
                 };
 
+                // Run the initialization hooks, now that all components are available.
+                #(all_effects.extend(reactor.#init_hooks(effect_builder, rng).into_iter());)*
+
                 // To avoid unused warnings.
                 let _ = effect_builder;
 
@@ -410,3 +585,180 @@ pub(crate) fn generate_reactor_impl(def: &ReactorDefinition) -> TokenStream {
         }
     )
 }
+
+/// Generates a statement which discards an event routed to an event source, raising a deprecation
+/// warning at the route, since the event source does not handle any events.
+fn event_source_route_warning(dest: &Ident, routed: &Ident) -> TokenStream {
+    let marker_ident = Ident::new(&format!("{}_is_an_event_source", dest), dest.span());
+    let note = format!(
+        "`{}` is an event source and does not handle events, routed `{}` events are discarded",
+        dest, routed
+    );
+
+    quote_spanned!(dest.span()=>
+        #[deprecated(note = #note)]
+        #[allow(non_upper_case_globals)]
+        const #marker_ident: () = ();
+        #[allow(clippy::let_unit_value)]
+        let _ = #marker_ident;
+    )
+}
+
+/// Generates the `Finalize` implementation of the reactor.
+///
+/// Components are finalized in reverse construction order, i.e. every component is shut down before
+/// the components it depends on. Components marked `has_finalizer` are awaited, all others are
+/// dropped.
+///
+/// Only generated if at least one component has a finalizer, as the reactor is destructured and
+/// thus cannot implement `Drop`. Otherwise, the field order of the reactor ensures components are
+/// dropped in reverse construction order.
+pub(crate) fn generate_reactor_finalize(def: &ReactorDefinition) -> TokenStream {
+    let reactor_ident = def.reactor_ident();
+
+    let components: Vec<_> = def.components_in_construction_order().collect();
+    if !components.iter().any(|cdef| cdef.has_finalizer()) {
+        return quote!();
+    }
+
+    let field_idents: Vec<_> = components.iter().map(|cdef| cdef.field_ident()).collect();
+
+    let mut finalizations = Vec::new();
+    for cdef in components.iter().rev() {
+        let field_ident = cdef.field_ident();
+
+        if cdef.has_finalizer() {
+            finalizations.push(quote!(
+                crate::reactor::Finalize::finalize(#field_ident).await;
+            ));
+        } else {
+            finalizations.push(quote!(
+                drop(#field_ident);
+            ));
+        }
+    }
+
+    quote!(
+        impl crate::reactor::Finalize for #reactor_ident {
+            fn finalize(self) -> futures::future::BoxFuture<'static, ()> {
+                use futures::FutureExt;
+
+                let #reactor_ident { #(#field_idents),* } = self;
+
+                async move {
+                    #(#finalizations)*
+                }
+                .boxed()
+            }
+        }
+    )
+}
+
+/// Generates the handoff to the reactor this reactor transitions into, if one is declared.
+///
+/// `into_handoff` moves the retained components into a `NameOfReactorHandoff` struct and shuts down
+/// all other components in reverse construction order, like the `Finalize` implementation. Events
+/// still queued can be converted into the target reactor's events using `TryFrom`, which succeeds
+/// for events of retained components and forwarded requests and announcements, and returns all
+/// other events unchanged as its error.
+pub(crate) fn generate_reactor_transition(def: &ReactorDefinition) -> TokenStream {
+    let transition = match def.transition() {
+        Some(transition) => transition,
+        None => return quote!(),
+    };
+
+    let reactor_ident = def.reactor_ident();
+    let event_ident = def.event_ident();
+    let handoff_ident = suffix_ident(&reactor_ident, "Handoff");
+    let target_event_ident = transition.target_event_ident();
+
+    let components: Vec<_> = def.components_in_construction_order().collect();
+    let field_idents: Vec<_> = components.iter().map(|cdef| cdef.field_ident()).collect();
+
+    let mut handoff_fields = Vec::new();
+    let mut retained_fields = Vec::new();
+    let mut conversions = Vec::new();
+
+    for cdef in components.iter().filter(|cdef| transition.retains(cdef)) {
+        let field_ident = cdef.field_ident();
+        let full_type = cdef.full_component_type();
+        let variant_ident = cdef.variant_ident();
+
+        let field_doc = format!("The `{}` component.", field_ident);
+        handoff_fields.push(quote!(
+            #[doc = #field_doc]
+            pub(crate) #field_ident: #full_type));
+        retained_fields.push(quote!(#field_ident));
+
+        if cdef.is_event_source() {
+            continue;
+        }
+        conversions.push(quote!(
+            #event_ident::#variant_ident(event) => Ok(#target_event_ident::#variant_ident(event))
+        ));
+    }
+
+    for variant_ident in transition.forwarded() {
+        conversions.push(quote!(
+            #event_ident::#variant_ident(event) => Ok(#target_event_ident::#variant_ident(event))
+        ));
+    }
+
+    let mut shutdowns = Vec::new();
+    for cdef in components
+        .iter()
+        .rev()
+        .filter(|cdef| !transition.retains(cdef))
+    {
+        let field_ident = cdef.field_ident();
+
+        if cdef.has_finalizer() {
+            shutdowns.push(quote!(
+                crate::reactor::Finalize::finalize(#field_ident).await;
+            ));
+        } else {
+            shutdowns.push(quote!(
+                drop(#field_ident);
+            ));
+        }
+    }
+
+    let handoff_docs = format!(
+        "Components of `{}` reactor handed over to the reactor it transitions into.",
+        reactor_ident
+    );
+
+    quote!(
+        #[doc = #handoff_docs]
+        #[derive(Debug)]
+        pub(crate) struct #handoff_ident {
+            #(#handoff_fields,)*
+        }
+
+        impl #reactor_ident {
+            /// Shuts down all components that are not handed over, in reverse construction order,
+            /// and returns the others.
+            pub(crate) async fn into_handoff(self) -> #handoff_ident {
+                let #reactor_ident { #(#field_idents),* } = self;
+
+                #(#shutdowns)*
+
+                #handoff_ident {
+                    #(#retained_fields,)*
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<#event_ident> for #target_event_ident {
+            type Error = #event_ident;
+
+            #[allow(unreachable_patterns)]
+            fn try_from(event: #event_ident) -> Result<Self, Self::Error> {
+                match event {
+                    #(#conversions,)*
+                    event => Err(event),
+                }
+            }
+        }
+    )
+}
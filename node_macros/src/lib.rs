@@ -31,6 +31,8 @@ pub fn reactor(input: TokenStream) -> TokenStream {
     output.extend(gen::generate_reactor(&def));
     output.extend(gen::generate_reactor_types(&def));
     output.extend(gen::generate_reactor_impl(&def));
+    output.extend(gen::generate_reactor_finalize(&def));
+    output.extend(gen::generate_reactor_transition(&def));
 
     output.into()
 }
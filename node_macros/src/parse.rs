@@ -5,6 +5,7 @@
 //! methods in this representation.
 
 use std::{
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt::{self, Debug, Formatter},
 };
@@ -16,12 +17,23 @@ use syn::{
     braced, bracketed, parenthesized,
     parse::{Parse, ParseStream, Result},
     punctuated::Punctuated,
-    Expr, Ident, ItemType, Path, Token, Type,
+    Attribute, Expr, Ident, ItemType, LitInt, LitStr, Path, Token, Type,
 };
 
 use crate::{rust_type::RustType, util::to_ident};
 use proc_macro2::TokenStream;
 
+/// Error code of the reactor's metrics initialization error.
+pub(crate) const METRICS_ERROR_CODE: u8 = 1;
+
+/// Error code of the first component without a declared error code.  Undeclared codes are assigned
+/// in declaration order.
+const FIRST_DEFAULT_ERROR_CODE: u8 = 10;
+
+/// Upper bound of error codes.  Higher exit codes are reserved for the node's `ExitCode`s and
+/// termination signals.
+const MAX_ERROR_CODE: u8 = 99;
+
 #[derive(Debug)]
 pub(crate) struct ReactorDefinition {
     /// Identifier of the reactor type.
@@ -39,6 +51,13 @@ pub(crate) struct ReactorDefinition {
     /// Example: "net" maps to `crate::components::small_net::SmallNet<NodeId>`.
     components: IndexMap<Ident, ComponentDefinition>,
 
+    /// Order in which components are constructed, such that every component comes after all of its
+    /// dependencies. Components are finalized in reverse order.
+    construction_order: Vec<Ident>,
+
+    /// Error code of each component's construction error.
+    error_codes: IndexMap<Ident, u8>,
+
     /// Overrides for events of components.
     ///
     /// Example: "net" may have an event type that differs from
@@ -50,6 +69,12 @@ pub(crate) struct ReactorDefinition {
 
     /// List of announcement routing directives.
     announcements: Vec<AnnouncementDefinition>,
+
+    /// Reactor methods called once all components are constructed, in declaration order.
+    init_hooks: Vec<Ident>,
+
+    /// The reactor this reactor transitions into, if any.
+    transition: Option<TransitionDefinition>,
 }
 
 impl ReactorDefinition {
@@ -82,6 +107,33 @@ impl ReactorDefinition {
         self.components.values()
     }
 
+    /// Returns an iterator over all component definitions in construction order.
+    ///
+    /// Every component is preceded by the components it `needs`, otherwise the declaration order
+    /// is kept.
+    pub(crate) fn components_in_construction_order(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &ComponentDefinition> {
+        self.construction_order
+            .iter()
+            .map(move |ident| &self.components[ident])
+    }
+
+    /// Returns the code of the component's construction error.
+    pub(crate) fn error_code(&self, component: &ComponentDefinition) -> u8 {
+        self.error_codes[component.field_ident()]
+    }
+
+    /// Returns an iterator over the reactor methods to call after construction.
+    pub(crate) fn init_hooks(&self) -> impl Iterator<Item = &Ident> {
+        self.init_hooks.iter()
+    }
+
+    /// Returns the reactor this reactor transitions into, if any.
+    pub(crate) fn transition(&self) -> Option<&TransitionDefinition> {
+        self.transition.as_ref()
+    }
+
     /// Returns the configuration type.
     pub(crate) fn config_type(&self) -> &RustType {
         &self.config_type
@@ -123,6 +175,10 @@ impl ReactorDefinition {
                 .try_into()
                 .expect("could not convert hardcoded `ControlAnnouncement` to `RustType`"),
             targets: vec![Target::Panic],
+            queue_policy: Some(QueuePolicy {
+                queue_kind: to_ident("Control"),
+                overflow_policy: Some(to_ident("never_drop")),
+            }),
         })
     }
 }
@@ -184,6 +240,34 @@ impl Parse for ReactorDefinition {
             .into_iter()
             .collect();
 
+        // Optional initialization hooks.
+        let init_hooks: Vec<_> = if content.peek(kw::init) {
+            let init_content;
+            let _: kw::init = content.parse()?;
+            let _: Token!(:) = content.parse()?;
+            braced!(init_content in content);
+            init_content
+                .parse_terminated::<Ident, Token!(;)>(|input| {
+                    let _: Token!(fn) = input.parse()?;
+                    input.parse()
+                })?
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Optional transition into another reactor.
+        let transition = if content.peek(kw::transition) {
+            let transition_content;
+            let _: kw::transition = content.parse()?;
+            let _: Token!(:) = content.parse()?;
+            braced!(transition_content in content);
+            Some(transition_content.parse::<TransitionDefinition>()?)
+        } else {
+            None
+        };
+
         // We can now perform some rudimentary checks. Component keys are converted to strings, so
         // rid them of their span information.
         let component_keys: IndexSet<_> =
@@ -201,6 +285,18 @@ impl Parse for ReactorDefinition {
                     format!("An event entry points to a non-existing component: {}", key),
                 ));
             }
+            if components
+                .values()
+                .any(|cdef| cdef.is_event_source && cdef.name == key.to_string())
+            {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "An event entry points to an event source, which has no events: {}",
+                        key
+                    ),
+                ));
+            }
         }
 
         // Ensure that requests are not routed to non-existing events.
@@ -234,18 +330,139 @@ impl Parse for ReactorDefinition {
             }
         }
 
+        if let Some(ref transition) = transition {
+            transition.check(&components, &requests, &announcements)?;
+        }
+
+        let construction_order = construction_order(&components)?;
+        let error_codes = error_codes(&components)?;
+
         Ok(ReactorDefinition {
             reactor_type_ident,
             config_type: RustType::try_from(config.ty.as_ref().clone())
                 .map_err(|err| syn::parse::Error::new_spanned(config.ty, err))?,
             components,
+            construction_order,
+            error_codes,
             events,
             requests,
             announcements,
+            init_hooks,
+            transition,
         })
     }
 }
 
+/// Computes the order in which components must be constructed to satisfy their dependencies.
+///
+/// Components whose dependencies are satisfied are constructed in declaration order. Returns an
+/// error if a dependency does not exist or dependencies are circular.
+fn construction_order(components: &IndexMap<Ident, ComponentDefinition>) -> Result<Vec<Ident>> {
+    // As with other checks, compare idents by their string representation, ignoring their span.
+    let component_keys: IndexSet<_> = components.keys().map(|ident| ident.to_string()).collect();
+
+    for cdef in components.values() {
+        for dependency in cdef.dependencies() {
+            if !component_keys.contains(&dependency.to_string()) {
+                return Err(syn::Error::new_spanned(
+                    dependency,
+                    format!("A component needs a non-existing component: {}", dependency),
+                ));
+            }
+        }
+    }
+
+    let mut order: Vec<Ident> = Vec::with_capacity(components.len());
+    let mut constructed: IndexSet<String> = IndexSet::new();
+
+    while order.len() < components.len() {
+        let next = components.values().find(|cdef| {
+            !constructed.contains(&cdef.name.to_string())
+                && cdef
+                    .dependencies()
+                    .iter()
+                    .all(|dependency| constructed.contains(&dependency.to_string()))
+        });
+
+        match next {
+            Some(cdef) => {
+                constructed.insert(cdef.name.to_string());
+                order.push(cdef.name.clone());
+            }
+            None => {
+                // Every remaining component waits on another one, so there must be a cycle.
+                let blocked = components
+                    .values()
+                    .find(|cdef| !constructed.contains(&cdef.name.to_string()))
+                    .expect("at least one component must be left");
+                return Err(syn::Error::new_spanned(
+                    &blocked.name,
+                    format!(
+                        "Circular dependency between components involving: {}",
+                        blocked.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Assigns an error code to each component's construction error.
+///
+/// Components without a declared code are numbered in declaration order. Returns an error if a code
+/// is out of range or used more than once.
+fn error_codes(components: &IndexMap<Ident, ComponentDefinition>) -> Result<IndexMap<Ident, u8>> {
+    let mut error_codes = IndexMap::new();
+    let mut components_by_code: HashMap<u8, &Ident> = HashMap::new();
+
+    for (index, cdef) in components.values().enumerate() {
+        let code = match cdef.error_code {
+            Some(ref lit) => {
+                let code: u8 = lit.base10_parse()?;
+                if code <= METRICS_ERROR_CODE || code > MAX_ERROR_CODE {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!(
+                            "Error code must be between {} and {}",
+                            METRICS_ERROR_CODE + 1,
+                            MAX_ERROR_CODE
+                        ),
+                    ));
+                }
+                code
+            }
+            None => match u8::try_from(index)
+                .ok()
+                .and_then(|index| FIRST_DEFAULT_ERROR_CODE.checked_add(index))
+                .filter(|code| *code <= MAX_ERROR_CODE)
+            {
+                Some(code) => code,
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        &cdef.name,
+                        "Too many components to number their error codes, declare `error_code`",
+                    ))
+                }
+            },
+        };
+
+        if let Some(other) = components_by_code.insert(code, &cdef.name) {
+            return Err(syn::Error::new_spanned(
+                &cdef.name,
+                format!(
+                    "Error code {} is already used by component: {}",
+                    code, other
+                ),
+            ));
+        }
+        error_codes.insert(cdef.name.clone(), code);
+    }
+
+    Ok(error_codes)
+}
+
 /// A definition of a component.
 pub(crate) struct ComponentDefinition {
     /// The attribute-style name of the component, e.g. `net`.
@@ -258,6 +475,19 @@ pub(crate) struct ComponentDefinition {
     has_effects: bool,
     /// Whether or not the component's `new` function returns a component instead of a `Result`.
     is_infallible: bool,
+    /// Whether or not the component implements `Finalize` and must be finalized on shutdown.
+    has_finalizer: bool,
+    /// Components that must be constructed before this one, passed to its constructor by
+    /// reference.
+    dependencies: Vec<Ident>,
+    /// Queue declared for the component's events, if any.
+    queue_policy: Option<QueuePolicy>,
+    /// Error code declared for the component's construction error, if any.
+    error_code: Option<LitInt>,
+    /// Whether or not the component only produces events and never handles any.
+    is_event_source: bool,
+    /// Config declared for the component, if any.
+    config: Option<ConfigDefinition>,
 }
 
 impl ComponentDefinition {
@@ -306,6 +536,32 @@ impl ComponentDefinition {
     pub(crate) fn is_infallible(&self) -> bool {
         self.is_infallible
     }
+
+    /// Returns whether the component must be finalized on shutdown.
+    pub(crate) fn has_finalizer(&self) -> bool {
+        self.has_finalizer
+    }
+
+    /// Returns the components this component needs to be constructed.
+    pub(crate) fn dependencies(&self) -> &[Ident] {
+        self.dependencies.as_slice()
+    }
+
+    /// Returns the queue declared for the component's events.
+    pub(crate) fn queue_policy(&self) -> Option<&QueuePolicy> {
+        self.queue_policy.as_ref()
+    }
+
+    /// Returns whether the component only produces events, in which case no event variant is
+    /// generated for it and nothing is dispatched to it.
+    pub(crate) fn is_event_source(&self) -> bool {
+        self.is_event_source
+    }
+
+    /// Returns the config declared for the component.
+    pub(crate) fn config(&self) -> Option<&ConfigDefinition> {
+        self.config.as_ref()
+    }
 }
 
 impl Debug for ComponentDefinition {
@@ -314,12 +570,86 @@ impl Debug for ComponentDefinition {
             .field("name", &self.name.to_string())
             .field("component_type", &self.component_type)
             .field("component_arguments", &"TODO: fmtargs")
+            .field(
+                "dependencies",
+                &self
+                    .dependencies
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+            )
+            .field("queue_policy", &self.queue_policy)
+            .field(
+                "error_code",
+                &self.error_code.as_ref().map(ToString::to_string),
+            )
+            .field("is_event_source", &self.is_event_source)
+            .field("config", &self.config)
             .finish()
     }
 }
 
 impl Parse for ComponentDefinition {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut queue_policy = None;
+        let mut error_code = None;
+        let mut is_event_source = false;
+        let mut config = None;
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path.is_ident("queue") {
+                if is_event_source {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "An event source has no events to queue",
+                    ));
+                }
+                QueuePolicy::parse_attribute(attr, &mut queue_policy)?;
+            } else if attr.path.is_ident("event_source") {
+                if is_event_source {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "Duplicate `event_source` attribute",
+                    ));
+                }
+                if queue_policy.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "An event source has no events to queue",
+                    ));
+                }
+                if !attr.tokens.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        attr.tokens,
+                        "The `event_source` attribute takes no arguments",
+                    ));
+                }
+                is_event_source = true;
+            } else if attr.path.is_ident("error_code") {
+                if error_code.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "Duplicate `error_code` attribute",
+                    ));
+                }
+                error_code = Some(attr.parse_args::<LitInt>()?);
+            } else if attr.path.is_ident("config") {
+                if config.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "Duplicate `config` attribute",
+                    ));
+                }
+                config = Some(attr.parse_args::<ConfigDefinition>()?);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    attr.path,
+                    "Unsupported attribute, expected `queue`, `error_code`, `event_source` or \
+                     `config`",
+                ));
+            }
+        }
+
         // Parse left hand side and type def.
         let name: Ident = input.parse()?;
         let _: Token!(=) = input.parse()?;
@@ -338,6 +668,13 @@ impl Parse for ComponentDefinition {
             false
         };
 
+        let has_finalizer = if input.peek(kw::has_finalizer) {
+            let _: kw::has_finalizer = input.parse()?;
+            true
+        } else {
+            false
+        };
+
         let ty: Path = input.parse()?;
 
         // Parse arguments
@@ -345,12 +682,99 @@ impl Parse for ComponentDefinition {
         parenthesized!(content in input);
 
         let args: Punctuated<Expr, Token!(,)> = content.parse_terminated(Expr::parse)?;
+
+        // Parse optional dependencies.
+        let dependencies = if input.peek(kw::needs) {
+            let _: kw::needs = input.parse()?;
+            let deps_content;
+            bracketed!(deps_content in input);
+            let deps: Punctuated<Ident, Token!(,)> = deps_content.parse_terminated(Ident::parse)?;
+            deps.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+
         Ok(ComponentDefinition {
             name,
             component_type: RustType::new(ty),
             component_arguments: args.into_iter().collect(),
             has_effects,
             is_infallible,
+            has_finalizer,
+            dependencies,
+            queue_policy,
+            error_code,
+            is_event_source,
+            config,
+        })
+    }
+}
+
+/// A config declaration for a component.
+///
+/// Declared using an attribute, e.g. `#[config(fetcher::Config = .., env = "CL_FETCHER_CONFIG")]`.
+/// Both the default value and the environment variable are optional.
+#[derive(Debug)]
+pub(crate) struct ConfigDefinition {
+    /// Type of the config.
+    config_type: Type,
+    /// Expression evaluating to the default config, if not `Default::default()`.
+    default: Option<Expr>,
+    /// Environment variable holding overrides of the default config, if any.
+    env_var: Option<LitStr>,
+}
+
+impl ConfigDefinition {
+    /// Returns the type of the config.
+    pub(crate) fn config_type(&self) -> &Type {
+        &self.config_type
+    }
+
+    /// Returns the expression evaluating to the default config.
+    pub(crate) fn default_value(&self) -> TokenStream {
+        match self.default {
+            Some(ref default) => quote!(#default),
+            None => quote!(::std::default::Default::default()),
+        }
+    }
+
+    /// Returns the environment variable holding overrides of the default config.
+    pub(crate) fn env_var(&self) -> Option<&LitStr> {
+        self.env_var.as_ref()
+    }
+}
+
+impl Parse for ConfigDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let config_type = input.parse()?;
+
+        let default = if input.peek(Token!(=)) {
+            let _: Token!(=) = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let env_var = if input.peek(Token!(,)) {
+            let _: Token!(,) = input.parse()?;
+            let _: kw::env = input.parse()?;
+            let _: Token!(=) = input.parse()?;
+            let env_var: LitStr = input.parse()?;
+            if env_var.value().is_empty() {
+                return Err(syn::Error::new_spanned(
+                    env_var,
+                    "Environment variable name must not be empty",
+                ));
+            }
+            Some(env_var)
+        } else {
+            None
+        };
+
+        Ok(ConfigDefinition {
+            config_type,
+            default,
+            env_var,
         })
     }
 }
@@ -385,6 +809,7 @@ impl Parse for EventDefinition {
 pub(crate) struct RequestDefinition {
     pub(crate) request_type: RustType,
     pub(crate) target: Target,
+    pub(crate) queue_policy: Option<QueuePolicy>,
 }
 
 impl RequestDefinition {
@@ -404,6 +829,11 @@ impl RequestDefinition {
         &self.target
     }
 
+    /// Returns the queue declared for the request.
+    pub(crate) fn queue_policy(&self) -> Option<&QueuePolicy> {
+        self.queue_policy.as_ref()
+    }
+
     /// Returns the full path for a request.
     pub(crate) fn full_request_type(&self) -> TokenStream {
         let request_type = self.request_type();
@@ -419,6 +849,8 @@ impl RequestDefinition {
 
 impl Parse for RequestDefinition {
     fn parse(input: ParseStream) -> Result<Self> {
+        let queue_policy = QueuePolicy::parse_attributes(input)?;
+
         let request_type = RustType::new(input.parse()?);
         let _: Token!(->) = input.parse()?;
 
@@ -427,6 +859,7 @@ impl Parse for RequestDefinition {
         Ok(RequestDefinition {
             request_type,
             target,
+            queue_policy,
         })
     }
 }
@@ -436,6 +869,7 @@ impl Parse for RequestDefinition {
 pub(crate) struct AnnouncementDefinition {
     pub(crate) announcement_type: RustType,
     pub(crate) targets: Vec<Target>,
+    pub(crate) queue_policy: Option<QueuePolicy>,
 }
 
 impl AnnouncementDefinition {
@@ -461,6 +895,11 @@ impl AnnouncementDefinition {
         self.targets.iter()
     }
 
+    /// Returns the queue declared for the announcement.
+    pub(crate) fn queue_policy(&self) -> Option<&QueuePolicy> {
+        self.queue_policy.as_ref()
+    }
+
     /// Returns an ident identifying the announcement that is suitable for a variant, e.g.
     /// `NetworkAnnouncement`.
     pub(crate) fn variant_ident(&self) -> Ident {
@@ -470,6 +909,8 @@ impl AnnouncementDefinition {
 
 impl Parse for AnnouncementDefinition {
     fn parse(input: ParseStream) -> Result<Self> {
+        let queue_policy = QueuePolicy::parse_attributes(input)?;
+
         let announcement_type = RustType::new(input.parse()?);
         let _: Token!(->) = input.parse()?;
 
@@ -483,6 +924,223 @@ impl Parse for AnnouncementDefinition {
         Ok(AnnouncementDefinition {
             announcement_type,
             targets,
+            queue_policy,
+        })
+    }
+}
+
+/// A queue declaration for the events of a component, request or announcement.
+///
+/// Declared using an attribute, e.g. `#[queue(NetworkLowPriority, drop_oldest)]`. The overflow
+/// policy is optional.
+#[derive(Debug)]
+pub(crate) struct QueuePolicy {
+    /// Variant of `crate::reactor::QueueKind`, e.g. `NetworkLowPriority`.
+    queue_kind: Ident,
+    /// Snake-cased variant of `crate::reactor::OverflowPolicy`, e.g. `drop_oldest`.
+    overflow_policy: Option<Ident>,
+}
+
+impl QueuePolicy {
+    /// Parses the outer attributes of a definition, returning the queue declaration if present.
+    ///
+    /// Returns an error on any other attribute or on more than one queue declaration.
+    fn parse_attributes(input: ParseStream) -> Result<Option<Self>> {
+        let mut queue_policy = None;
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if !attr.path.is_ident("queue") {
+                return Err(syn::Error::new_spanned(
+                    attr.path,
+                    "Unsupported attribute, expected `queue`",
+                ));
+            }
+
+            Self::parse_attribute(attr, &mut queue_policy)?;
+        }
+
+        Ok(queue_policy)
+    }
+
+    /// Parses a `queue` attribute into `queue_policy`.
+    ///
+    /// Returns an error if a queue has already been declared.
+    fn parse_attribute(attr: Attribute, queue_policy: &mut Option<Self>) -> Result<()> {
+        if queue_policy.is_some() {
+            return Err(syn::Error::new_spanned(attr, "Duplicate `queue` attribute"));
+        }
+
+        *queue_policy = Some(attr.parse_args::<QueuePolicy>()?);
+        Ok(())
+    }
+
+    /// Returns the full path of the queue kind, e.g. `crate::reactor::QueueKind::Network`.
+    pub(crate) fn full_queue_kind(&self) -> TokenStream {
+        let queue_kind = &self.queue_kind;
+        quote!(crate::reactor::QueueKind::#queue_kind)
+    }
+
+    /// Returns the full path of the overflow policy, e.g.
+    /// `crate::reactor::OverflowPolicy::DropOldest`.
+    ///
+    /// If no overflow policy was declared, the default policy of the queue is used.
+    pub(crate) fn full_overflow_policy(&self) -> TokenStream {
+        match self.overflow_policy {
+            Some(ref policy) => {
+                // Keep the span, so that unknown policies are reported at the attribute.
+                let variant_ident = Ident::new(&to_pascal_case(&policy.to_string()), policy.span());
+                quote!(crate::reactor::OverflowPolicy::#variant_ident)
+            }
+            None => {
+                let queue_kind = self.full_queue_kind();
+                quote!(crate::reactor::OverflowPolicy::for_queue(#queue_kind))
+            }
+        }
+    }
+}
+
+impl Parse for QueuePolicy {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let queue_kind = input.parse()?;
+
+        let overflow_policy = if input.peek(Token!(,)) {
+            let _: Token!(,) = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(QueuePolicy {
+            queue_kind,
+            overflow_policy,
+        })
+    }
+}
+
+/// A declaration that the reactor transitions into another one, e.g. the joiner into the
+/// participating reactor.
+#[derive(Debug)]
+pub(crate) struct TransitionDefinition {
+    /// Identifier of the reactor type transitioned into, whose event type is assumed to be named
+    /// after it, e.g. `OtherReactorEvent`.
+    target_reactor: Ident,
+    /// Components handed over to the target reactor.
+    retained: Vec<Ident>,
+    /// Request and announcement variants whose queued events are carried over.
+    forwarded: Vec<Ident>,
+}
+
+impl TransitionDefinition {
+    /// Returns the identifier of the target reactor's event type, e.g. `OtherReactorEvent`.
+    pub(crate) fn target_event_ident(&self) -> Ident {
+        to_ident(&format!("{}Event", self.target_reactor))
+    }
+
+    /// Returns whether the component is handed over to the target reactor.
+    pub(crate) fn retains(&self, component: &ComponentDefinition) -> bool {
+        let name = component.field_ident().to_string();
+        self.retained.iter().any(|ident| *ident == name)
+    }
+
+    /// Returns an iterator over the request and announcement variants that are carried over.
+    pub(crate) fn forwarded(&self) -> impl Iterator<Item = &Ident> {
+        self.forwarded.iter()
+    }
+
+    /// Ensures that retained components and forwarded events exist, and that retained components
+    /// only need components which are retained as well.
+    fn check(
+        &self,
+        components: &IndexMap<Ident, ComponentDefinition>,
+        requests: &[RequestDefinition],
+        announcements: &[AnnouncementDefinition],
+    ) -> Result<()> {
+        let retained_keys: IndexSet<_> = self.retained.iter().map(ToString::to_string).collect();
+
+        for key in &self.retained {
+            let cdef = components
+                .values()
+                .find(|cdef| cdef.name == key.to_string())
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        key,
+                        format!("A retained component does not exist: {}", key),
+                    )
+                })?;
+            if let Some(dependency) = cdef
+                .dependencies()
+                .iter()
+                .find(|dependency| !retained_keys.contains(&dependency.to_string()))
+            {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "A retained component needs a component that is not retained: {}",
+                        dependency
+                    ),
+                ));
+            }
+        }
+
+        let event_keys: IndexSet<_> = requests
+            .iter()
+            .map(RequestDefinition::variant_ident)
+            .chain(
+                announcements
+                    .iter()
+                    .map(AnnouncementDefinition::variant_ident),
+            )
+            .map(|ident| ident.to_string())
+            .collect();
+        for key in &self.forwarded {
+            if !event_keys.contains(&key.to_string()) {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "A forwarded event is not a routed request or announcement: {}",
+                        key
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Parse for TransitionDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: kw::into = input.parse()?;
+        let _: Token!(:) = input.parse()?;
+        let target_reactor: Ident = input.parse()?;
+        let _: Token!(;) = input.parse()?;
+
+        let _: kw::retain = input.parse()?;
+        let _: Token!(:) = input.parse()?;
+        let retain_content;
+        bracketed!(retain_content in input);
+        let retained: Punctuated<Ident, Token!(,)> =
+            retain_content.parse_terminated(Ident::parse)?;
+        let _: Token!(;) = input.parse()?;
+
+        // Forwarding events is optional.
+        let forwarded = if input.peek(kw::forward) {
+            let _: kw::forward = input.parse()?;
+            let _: Token!(:) = input.parse()?;
+            let forward_content;
+            bracketed!(forward_content in input);
+            let forwarded: Punctuated<Ident, Token!(,)> =
+                forward_content.parse_terminated(Ident::parse)?;
+            let _: Token!(;) = input.parse()?;
+            forwarded.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(TransitionDefinition {
+            target_reactor,
+            retained: retained.into_iter().collect(),
+            forwarded,
         })
     }
 }
@@ -547,6 +1205,14 @@ mod kw {
     syn::custom_keyword!(events);
     syn::custom_keyword!(requests);
     syn::custom_keyword!(announcements);
+    syn::custom_keyword!(init);
     syn::custom_keyword!(infallible);
     syn::custom_keyword!(has_effects);
+    syn::custom_keyword!(has_finalizer);
+    syn::custom_keyword!(needs);
+    syn::custom_keyword!(transition);
+    syn::custom_keyword!(into);
+    syn::custom_keyword!(retain);
+    syn::custom_keyword!(forward);
+    syn::custom_keyword!(env);
 }
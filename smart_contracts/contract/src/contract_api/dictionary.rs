@@ -0,0 +1,193 @@
+//! Typed access to dictionaries.
+//!
+//! [`Dictionary`] wraps the seed [`URef`] of a dictionary together with the types of its keys and
+//! values, so that contract code doesn't have to derive item keys or convert values by hand:
+//!
+//! ```no_run
+//! use casper_contract::contract_api::{dictionary::Dictionary, runtime};
+//! use casper_types::{account::AccountHash, U512};
+//!
+//! let owner: AccountHash = runtime::get_named_arg("owner");
+//! let balances: Dictionary<AccountHash, U512> = Dictionary::named("balances");
+//! let balance = balances.get(&owner).unwrap_or_default();
+//! balances.set(&owner, balance + 1);
+//! ```
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    ApiError, CLTyped, ContractHash, ContractPackageHash, Key, PublicKey, URef,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, U128, U256, U512,
+};
+use hex_fmt::HexFmt;
+
+use crate::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+/// A type which can be used as the key of a [`Dictionary`].
+///
+/// The default item key is the lowercase hex encoding of the Blake2b hash of the serialized key,
+/// which is always within [`DICTIONARY_ITEM_KEY_MAX_LENGTH`].  Types defined by a contract only
+/// need an empty impl to opt in:
+///
+/// ```
+/// use casper_contract::contract_api::dictionary::DictionaryKey;
+/// # use casper_types::bytesrepr::{self, ToBytes};
+///
+/// # struct TokenId(u64);
+/// # impl ToBytes for TokenId {
+/// #     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> { self.0.to_bytes() }
+/// #     fn serialized_length(&self) -> usize { self.0.serialized_length() }
+/// # }
+/// impl DictionaryKey for TokenId {}
+/// ```
+///
+/// Strings, integers and account hashes override this to use a readable item key, so that their
+/// entries can be queried off-chain without hashing.
+pub trait DictionaryKey: ToBytes {
+    /// Returns the item key under which the value for `self` is stored.
+    fn to_dictionary_item_key(&self) -> String {
+        hashed_item_key(&self.to_bytes().unwrap_or_revert())
+    }
+}
+
+/// Returns the lowercase hex encoding of the Blake2b hash of `bytes`.
+fn hashed_item_key(bytes: &[u8]) -> String {
+    format!("{}", HexFmt(runtime::blake2b(bytes)))
+}
+
+impl DictionaryKey for str {
+    /// Returns `self` if it is within [`DICTIONARY_ITEM_KEY_MAX_LENGTH`], or its hash otherwise.
+    fn to_dictionary_item_key(&self) -> String {
+        if self.len() <= DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            self.to_string()
+        } else {
+            hashed_item_key(self.as_bytes())
+        }
+    }
+}
+
+impl DictionaryKey for String {
+    /// Returns `self` if it is within [`DICTIONARY_ITEM_KEY_MAX_LENGTH`], or its hash otherwise.
+    fn to_dictionary_item_key(&self) -> String {
+        self.as_str().to_dictionary_item_key()
+    }
+}
+
+macro_rules! impl_dictionary_key_for_number {
+    ($($type:ty),*) => {
+        $(
+            impl DictionaryKey for $type {
+                /// Returns the decimal representation of `self`.
+                fn to_dictionary_item_key(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_dictionary_key_for_number!(u8, u32, u64, i32, i64, U128, U256, U512);
+
+impl DictionaryKey for AccountHash {
+    /// Returns the lowercase hex encoding of the account hash.
+    fn to_dictionary_item_key(&self) -> String {
+        format!("{}", HexFmt(self.value()))
+    }
+}
+
+impl DictionaryKey for ContractHash {}
+
+impl DictionaryKey for ContractPackageHash {}
+
+impl DictionaryKey for Key {}
+
+impl DictionaryKey for URef {}
+
+impl DictionaryKey for PublicKey {}
+
+/// A dictionary whose values of type `V` are stored under keys of type `K`.
+///
+/// Reads return `None` for missing keys, and revert if the stored value is not of type `V`.
+#[derive(Debug)]
+pub struct Dictionary<K, V> {
+    seed_uref: URef,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<K: DictionaryKey, V: CLTyped + FromBytes + ToBytes> Dictionary<K, V> {
+    /// Creates a new dictionary and puts its seed [`URef`] under `name` in the named keys of the
+    /// current context.
+    ///
+    /// Returns [`ApiError::InvalidArgument`] if `name` is empty or already in use.
+    pub fn new(name: &str) -> Result<Self, ApiError> {
+        storage::new_dictionary(name).map(Self::from_seed_uref)
+    }
+
+    /// Returns the dictionary whose seed [`URef`] is stored under `name` in the named keys of the
+    /// current context, reverting if there is no such named key.
+    pub fn named(name: &str) -> Self {
+        Self::from_seed_uref(storage::get_named_uref(name))
+    }
+
+    /// Returns the dictionary accessed by `seed_uref`.
+    pub fn from_seed_uref(seed_uref: URef) -> Self {
+        Dictionary {
+            seed_uref,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the seed [`URef`] of the dictionary.
+    pub fn seed_uref(&self) -> URef {
+        self.seed_uref
+    }
+
+    /// Returns the value stored under `key`, or `None` if there is none.
+    pub fn get(&self, key: &K) -> Option<V> {
+        storage::dictionary_get(self.seed_uref, &key.to_dictionary_item_key()).unwrap_or_revert()
+    }
+
+    /// Returns the values stored under each of `keys`, in the same order.
+    pub fn get_many<'a, I>(&self, keys: I) -> Vec<Option<V>>
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        keys.into_iter().map(|key| self.get(key)).collect()
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Stores `value` under `key`, replacing any previous value.
+    pub fn set(&self, key: &K, value: V) {
+        storage::dictionary_put(self.seed_uref, &key.to_dictionary_item_key(), value)
+    }
+
+    /// Stores the value returned by `f` under `key`, passing it the current value.
+    pub fn update<F: FnOnce(Option<V>) -> V>(&self, key: &K, f: F) {
+        let item_key = key.to_dictionary_item_key();
+        let current = storage::dictionary_get(self.seed_uref, &item_key).unwrap_or_revert();
+        storage::dictionary_put(self.seed_uref, &item_key, f(current))
+    }
+}
+
+impl<K, V> Clone for Dictionary<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Dictionary<K, V> {}
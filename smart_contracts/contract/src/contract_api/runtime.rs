@@ -9,8 +9,10 @@ use casper_types::{
     bytesrepr::{self, FromBytes},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
-    ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
-    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    AccessRights, ApiError, BlockContext, BlockTime, CLTyped, CLValue, ContractHash,
+    ContractPackageHash, Key, Phase, RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH,
+    BLOCKTIME_SERIALIZED_LENGTH, BLOCK_CONTEXT_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    UREF_SERIALIZED_LENGTH,
 };
 
 use crate::{contract_api, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
@@ -207,6 +209,20 @@ pub fn get_blocktime() -> BlockTime {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Returns the [`BlockContext`] of the block this deploy is included in.
+pub fn get_block_context() -> BlockContext {
+    let dest_non_null_ptr = contract_api::alloc_bytes(BLOCK_CONTEXT_SERIALIZED_LENGTH);
+    let bytes = unsafe {
+        ext_ffi::casper_get_block_context(dest_non_null_ptr.as_ptr());
+        Vec::from_raw_parts(
+            dest_non_null_ptr.as_ptr(),
+            BLOCK_CONTEXT_SERIALIZED_LENGTH,
+            BLOCK_CONTEXT_SERIALIZED_LENGTH,
+        )
+    };
+    bytesrepr::deserialize(bytes).unwrap_or_revert()
+}
+
 /// Returns the current [`Phase`].
 pub fn get_phase() -> Phase {
     let dest_non_null_ptr = contract_api::alloc_bytes(PHASE_SERIALIZED_LENGTH);
@@ -331,6 +347,34 @@ pub fn is_valid_uref(uref: URef) -> bool {
     result != 0
 }
 
+/// Returns a copy of `uref` with its access rights narrowed to `access_rights`, e.g. a read-only
+/// alias to pass on to another contract without granting it write access.
+///
+/// Returns [`ApiError::NoAccessRights`] if `access_rights` is empty, and
+/// [`ApiError::PermissionDenied`] if it is not a subset of the access rights of `uref`. Causes a
+/// trap if `uref` is not valid in the current context.
+pub fn narrow_uref(uref: URef, access_rights: AccessRights) -> Result<URef, ApiError> {
+    let (uref_ptr, uref_size, _bytes) = contract_api::to_ptr(uref);
+    let dest_non_null_ptr = contract_api::alloc_bytes(UREF_SERIALIZED_LENGTH);
+    let ret = unsafe {
+        ext_ffi::casper_narrow_uref(
+            uref_ptr,
+            uref_size,
+            access_rights.bits(),
+            dest_non_null_ptr.as_ptr(),
+        )
+    };
+    let bytes = unsafe {
+        Vec::from_raw_parts(
+            dest_non_null_ptr.as_ptr(),
+            UREF_SERIALIZED_LENGTH,
+            UREF_SERIALIZED_LENGTH,
+        )
+    };
+    api_error::result_from(ret)?;
+    Ok(bytesrepr::deserialize(bytes).unwrap_or_revert())
+}
+
 /// Returns a 32-byte BLAKE2b digest
 pub fn blake2b<T: AsRef<[u8]>>(input: T) -> [u8; BLAKE2B_DIGEST_LENGTH] {
     let mut ret = [0; BLAKE2B_DIGEST_LENGTH];
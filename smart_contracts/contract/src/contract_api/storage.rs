@@ -341,6 +341,36 @@ pub fn disable_contract_version(
     api_error::result_from(result)
 }
 
+/// Removes a disabled version of a contract from the global state, along with at most `max_keys`
+/// of the keys created by the contract, i.e. its [`URef`]s and the items it added to dictionaries.
+///
+/// Returns the number of created keys still to be removed. The contract itself, including its named
+/// keys and Wasm, is only removed along with the last of them, so this should be called until it
+/// returns zero. The cost of a call grows with `max_keys`.
+pub fn remove_contract(
+    contract_package_hash: ContractPackageHash,
+    contract_hash: ContractHash,
+    max_keys: u32,
+) -> Result<u32, ApiError> {
+    let (contract_package_hash_ptr, contract_package_hash_size, _bytes1) =
+        contract_api::to_ptr(contract_package_hash);
+    let (contract_hash_ptr, contract_hash_size, _bytes2) = contract_api::to_ptr(contract_hash);
+    let mut remaining = 0_u32;
+
+    let result = unsafe {
+        ext_ffi::casper_remove_contract(
+            contract_package_hash_ptr,
+            contract_package_hash_size,
+            contract_hash_ptr,
+            contract_hash_size,
+            max_keys,
+            &mut remaining as *mut u32,
+        )
+    };
+
+    api_error::result_from(result).map(|_| remaining)
+}
+
 /// Creates new [`URef`] that represents a seed for a dictionary partition of the global state and
 /// puts it under named keys.
 pub fn new_dictionary(dictionary_name: &str) -> Result<URef, ApiError> {
@@ -451,7 +481,7 @@ pub fn dictionary_read<T: CLTyped + FromBytes>(dictionary_key: Key) -> Result<Op
     Ok(Some(bytesrepr::deserialize(value_bytes)?))
 }
 
-fn get_named_uref(name: &str) -> URef {
+pub(crate) fn get_named_uref(name: &str) -> URef {
     match runtime::get_key(name).unwrap_or_revert_with(ApiError::GetKey) {
         Key::URef(uref) => uref,
         _ => revert(ApiError::UnexpectedKeyVariant),
@@ -5,13 +5,13 @@ use core::mem::MaybeUninit;
 
 use casper_types::{
     account::AccountHash,
-    api_error, bytesrepr,
+    api_error, bytesrepr, runtime_args,
     system::{
         auction::{self, EraInfo},
-        SystemContractType,
+        mint, SystemContractType,
     },
-    ApiError, ContractHash, EraId, HashAddr, PublicKey, TransferResult, TransferredTo, URef, U512,
-    UREF_SERIALIZED_LENGTH,
+    ApiError, ContractHash, EraId, HashAddr, PublicKey, RuntimeArgs, TransferResult, TransferredTo,
+    URef, U512, UREF_SERIALIZED_LENGTH,
 };
 
 use crate::{
@@ -222,6 +222,49 @@ pub fn transfer_from_purse_to_purse(
     api_error::result_from(result)
 }
 
+/// Mints `amount` of the native token `token` into the `target` purse.  Only the issuer of the
+/// token declared in the chainspec may mint it.
+pub fn mint_token(token: &str, target: URef, amount: U512) -> Result<(), ApiError> {
+    let args = runtime_args! {
+        mint::ARG_TOKEN => token,
+        mint::ARG_TARGET => target,
+        mint::ARG_AMOUNT => amount,
+    };
+    let result: Result<(), mint::Error> =
+        runtime::call_contract(get_mint(), mint::METHOD_MINT_TOKEN, args);
+    result.map_err(ApiError::from)
+}
+
+/// Transfers `amount` of the native token `token` from `source` purse to `target` purse.  If
+/// `target` does not exist the transfer fails.
+pub fn transfer_token_from_purse_to_purse(
+    token: &str,
+    source: URef,
+    target: URef,
+    amount: U512,
+) -> Result<(), ApiError> {
+    let args = runtime_args! {
+        mint::ARG_TOKEN => token,
+        mint::ARG_SOURCE => source,
+        mint::ARG_TARGET => target,
+        mint::ARG_AMOUNT => amount,
+    };
+    let result: Result<(), mint::Error> =
+        runtime::call_contract(get_mint(), mint::METHOD_TRANSFER_TOKEN, args);
+    result.map_err(ApiError::from)
+}
+
+/// Returns the balance of the native token `token` held by the given purse.
+pub fn get_token_balance(token: &str, purse: URef) -> Result<U512, ApiError> {
+    let args = runtime_args! {
+        mint::ARG_TOKEN => token,
+        mint::ARG_PURSE => purse,
+    };
+    let result: Result<U512, mint::Error> =
+        runtime::call_contract(get_mint(), mint::METHOD_TOKEN_BALANCE, args);
+    result.map_err(ApiError::from)
+}
+
 /// Records a transfer.  Can only be called from within the mint contract.
 /// Needed to support system contract-based execution.
 #[doc(hidden)]
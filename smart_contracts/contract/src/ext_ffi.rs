@@ -2,6 +2,14 @@
 //!
 //! Generally should not be used directly.  See the [`contract_api`](crate::contract_api) for
 //! high-level bindings suitable for writing smart contracts.
+//!
+//! When the `mock-host` feature is enabled, these are replaced by functions operating on the
+//! in-memory state of the `mock_host` module.
+
+#[cfg(feature = "mock-host")]
+pub use crate::mock_host::ffi::*;
+
+#[cfg(not(feature = "mock-host"))]
 extern "C" {
     /// The bytes in the span of wasm memory from `key_ptr` to `key_ptr + key_size` must correspond
     /// to a valid global state key, otherwise the function will fail. If the key is de-serialized
@@ -126,6 +134,27 @@ extern "C" {
     /// memory from offset `value_ptr` to `value_ptr + value_size` cannot be de-serialized as
     /// type `Value`.
     pub fn casper_is_valid_uref(uref_ptr: *const u8, uref_size: usize) -> i32;
+    /// This function writes a copy of the given `URef` with its access rights narrowed to
+    /// `access_rights` to `dest_ptr`, e.g. a read-only alias of a `URef` to pass on to another
+    /// contract. The `URef` must be valid in the current context, otherwise this function will
+    /// cause a `Trap`. It is up to the caller to ensure there are [`UREF_SERIALIZED_LENGTH`] bytes
+    /// allocated at `dest_ptr`. Returns standard error code.
+    ///
+    /// [`UREF_SERIALIZED_LENGTH`]: casper_types::UREF_SERIALIZED_LENGTH
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the `URef` to narrow
+    /// * `uref_size` - size of the `URef` (in bytes)
+    /// * `access_rights` - access rights of the narrowed `URef`, which must be a non-empty subset
+    ///   of the access rights of the `URef`
+    /// * `dest_ptr` - pointer in wasm memory where to write the narrowed `URef`
+    pub fn casper_narrow_uref(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        access_rights: u8,
+        dest_ptr: *mut u8,
+    ) -> i32;
     /// This function attempts to add the given public key as an associated key to
     /// the current account. Presently only 32-byte keys are supported; it is up
     /// to the caller to ensure that the 32-bytes starting from offset
@@ -240,6 +269,18 @@ extern "C" {
     ///
     /// * `dest_ptr` - pointer in wasm memory where to write the result
     pub fn casper_get_blocktime(dest_ptr: *const u8);
+    /// This function gets the context of the block this deploy is included in: its timestamp,
+    /// height, era id and protocol version, serialized as a [`BlockContext`]. It is up to the
+    /// caller to ensure there are [`BLOCK_CONTEXT_SERIALIZED_LENGTH`] bytes allocated at
+    /// `dest_ptr`, otherwise data corruption in the wasm memory may occur.
+    ///
+    /// [`BlockContext`]: casper_types::BlockContext
+    /// [`BLOCK_CONTEXT_SERIALIZED_LENGTH`]: casper_types::BLOCK_CONTEXT_SERIALIZED_LENGTH
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_block_context(dest_ptr: *const u8);
     /// This function uses the mint contract to create a new, empty purse. If the
     /// call is successful then the `URef` (in serialized form) is written
     /// to the indicated place in wasm memory. It is up to the caller to ensure at
@@ -548,6 +589,28 @@ extern "C" {
         contract_hash_ptr: *const u8,
         contract_hash_size: usize,
     ) -> i32;
+    /// Removes a disabled contract of a contract package, along with at most `max_keys` of the keys
+    /// it created. Returns non-zero standard error for a failure, otherwise a zero indicates
+    /// success.
+    ///
+    /// The contract itself is only removed once all of the keys it created have been removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_package_hash_ptr` - pointer to serialized contract package hash.
+    /// * `contract_package_hash_size` - size of contract package hash in serialized form.
+    /// * `contract_hash_ptr` - pointer to serialized contract hash.
+    /// * `contract_hash_size` - size of contract hash in serialized form.
+    /// * `max_keys` - maximum number of created keys to remove.
+    /// * `remaining_ptr` - pointer to the number of created keys still to be removed.
+    pub fn casper_remove_contract(
+        contract_package_hash_ptr: *const u8,
+        contract_package_hash_size: usize,
+        contract_hash_ptr: *const u8,
+        contract_hash_size: usize,
+        max_keys: u32,
+        remaining_ptr: *mut u32,
+    ) -> i32;
     /// Calls a contract by its hash. Requires entry point name that has to be present on a
     /// specified contract, and serialized named arguments. Returns a standard error code in
     /// case of failure, otherwise a successful execution returns zero. Bytes returned from contract
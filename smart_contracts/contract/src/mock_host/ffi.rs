@@ -0,0 +1,745 @@
+//! Replacements for the host-side functions declared in [`ext_ffi`](crate::ext_ffi), implemented
+//! against the state of the mock host of the current thread.
+//!
+//! The safety requirements of each function are those of its counterpart in `ext_ffi`: pointers
+//! must be valid for reads or writes of the given sizes.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{panic, ptr, slice};
+
+use casper_types::{
+    account::AccountHash,
+    api_error,
+    bytesrepr::{self, FromBytes, ToBytes},
+    system::CallStackElement,
+    AccessRights, ApiError, CLType, CLValue, Key, URef, BLAKE2B_DIGEST_LENGTH, U128, U256, U512,
+};
+
+use super::{with_state, Return, Revert, State};
+
+macro_rules! unsupported {
+    ($function:literal) => {
+        panic!(concat!(
+            "`",
+            $function,
+            "` is not supported by the mock host"
+        ))
+    };
+}
+
+unsafe fn bytes_from_mem<'a>(ptr: *const u8, size: usize) -> &'a [u8] {
+    if size == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, size)
+    }
+}
+
+unsafe fn t_from_mem<T: FromBytes>(ptr: *const u8, size: usize) -> T {
+    bytesrepr::deserialize(bytes_from_mem(ptr, size).to_vec())
+        .expect("should deserialize value passed to the mock host")
+}
+
+unsafe fn string_from_mem(ptr: *const u8, size: usize) -> String {
+    String::from_utf8(bytes_from_mem(ptr, size).to_vec())
+        .expect("should pass valid UTF-8 to the mock host")
+}
+
+unsafe fn write_to_mem(dest_ptr: *mut u8, bytes: &[u8]) {
+    ptr::copy_nonoverlapping(bytes.as_ptr(), dest_ptr, bytes.len());
+}
+
+fn serialize<T: ToBytes>(value: &T) -> Vec<u8> {
+    value.to_bytes().expect("should serialize")
+}
+
+unsafe fn t_to_mem<T: ToBytes>(dest_ptr: *mut u8, value: &T) {
+    write_to_mem(dest_ptr, &serialize(value));
+}
+
+/// Sets the host buffer to `bytes` and writes their size to `output_size`.
+unsafe fn set_host_buffer(state: &mut State, bytes: Vec<u8>, output_size: *mut usize) -> i32 {
+    match state.set_host_buffer(bytes) {
+        Ok(size) => {
+            *output_size = size;
+            0
+        }
+        Err(error) => api_error::i32_from(Err(error)),
+    }
+}
+
+/// Adds `addend` to `current` with the semantics of the host, i.e. wrapping on overflow.
+fn add(current: &CLValue, addend: &CLValue) -> CLValue {
+    macro_rules! wrapping_add {
+        ($type:ty) => {{
+            let current: $type = current.clone().into_t().expect("should be the stored type");
+            let addend: $type = addend.clone().into_t().expect("should add the stored type");
+            CLValue::from_t(current.overflowing_add(addend).0).expect("should create CLValue")
+        }};
+    }
+
+    if current.cl_type() != addend.cl_type() {
+        panic!(
+            "cannot add {:?} to {:?}",
+            addend.cl_type(),
+            current.cl_type()
+        );
+    }
+    match current.cl_type() {
+        CLType::I32 => wrapping_add!(i32),
+        CLType::I64 => wrapping_add!(i64),
+        CLType::U8 => wrapping_add!(u8),
+        CLType::U32 => wrapping_add!(u32),
+        CLType::U64 => wrapping_add!(u64),
+        CLType::U128 => wrapping_add!(U128),
+        CLType::U256 => wrapping_add!(U256),
+        CLType::U512 => wrapping_add!(U512),
+        other => panic!("adding to {:?} is not supported by the mock host", other),
+    }
+}
+
+/// Reads the value under a key into the host buffer.
+pub unsafe fn casper_read_value(
+    key_ptr: *const u8,
+    key_size: usize,
+    output_size: *mut usize,
+) -> i32 {
+    let key: Key = t_from_mem(key_ptr, key_size);
+    with_state(|state| match state.read(&key).cloned() {
+        Some(value) => set_host_buffer(state, value.inner_bytes().clone(), output_size),
+        None => api_error::i32_from(Err(ApiError::ValueNotFound)),
+    })
+}
+
+/// Writes a value under a key.
+pub unsafe fn casper_write(
+    key_ptr: *const u8,
+    key_size: usize,
+    value_ptr: *const u8,
+    value_size: usize,
+) {
+    let key: Key = t_from_mem(key_ptr, key_size);
+    let value: CLValue = t_from_mem(value_ptr, value_size);
+    with_state(|state| state.write(key, value));
+}
+
+/// Adds a value to the one under a key.
+pub unsafe fn casper_add(
+    key_ptr: *const u8,
+    key_size: usize,
+    value_ptr: *const u8,
+    value_size: usize,
+) {
+    let key: Key = t_from_mem(key_ptr, key_size);
+    let addend: CLValue = t_from_mem(value_ptr, value_size);
+    with_state(|state| {
+        let current = state.read(&key).expect("should add to an existing value");
+        let sum = add(current, &addend);
+        state.write(key, sum);
+    });
+}
+
+/// Creates a new `URef` initialized to a value.
+pub unsafe fn casper_new_uref(uref_ptr: *mut u8, value_ptr: *const u8, value_size: usize) {
+    let value: CLValue = t_from_mem(value_ptr, value_size);
+    let uref = with_state(|state| state.new_uref(value));
+    t_to_mem(uref_ptr, &uref);
+}
+
+/// Loads the authorization keys into the host buffer.
+pub unsafe fn casper_load_authorization_keys(
+    total_keys: *mut usize,
+    result_size: *mut usize,
+) -> i32 {
+    with_state(|state| {
+        *total_keys = state.authorization_keys.len();
+        let bytes = serialize(&state.authorization_keys.clone());
+        set_host_buffer(state, bytes, result_size)
+    })
+}
+
+/// Loads the named keys into the host buffer.
+pub unsafe fn casper_load_named_keys(total_keys: *mut usize, result_size: *mut usize) -> i32 {
+    with_state(|state| {
+        *total_keys = state.named_keys.len();
+        if state.named_keys.is_empty() {
+            *result_size = 0;
+            return 0;
+        }
+        let bytes = serialize(&state.named_keys.clone());
+        set_host_buffer(state, bytes, result_size)
+    })
+}
+
+/// Unwinds out of the entry point with a return value.
+pub unsafe fn casper_ret(value_ptr: *const u8, value_size: usize) -> ! {
+    let value: CLValue = t_from_mem(value_ptr, value_size);
+    panic::resume_unwind(Box::new(Return(value)))
+}
+
+/// Writes the key under a name.
+pub unsafe fn casper_get_key(
+    name_ptr: *const u8,
+    name_size: usize,
+    output_ptr: *mut u8,
+    output_size: usize,
+    bytes_written_ptr: *mut usize,
+) -> i32 {
+    let name: String = t_from_mem(name_ptr, name_size);
+    let key = match with_state(|state| state.named_keys.get(&name).copied()) {
+        Some(key) => key,
+        None => return api_error::i32_from(Err(ApiError::MissingKey)),
+    };
+    let key_bytes = key.to_bytes().expect("should serialize");
+    if key_bytes.len() > output_size {
+        return api_error::i32_from(Err(ApiError::BufferTooSmall));
+    }
+    write_to_mem(output_ptr, &key_bytes);
+    *bytes_written_ptr = key_bytes.len();
+    0
+}
+
+/// Returns `0` if there is a key under a name.
+pub unsafe fn casper_has_key(name_ptr: *const u8, name_size: usize) -> i32 {
+    let name: String = t_from_mem(name_ptr, name_size);
+    if with_state(|state| state.named_keys.contains_key(&name)) {
+        0
+    } else {
+        1
+    }
+}
+
+/// Puts a key under a name.
+pub unsafe fn casper_put_key(
+    name_ptr: *const u8,
+    name_size: usize,
+    key_ptr: *const u8,
+    key_size: usize,
+) {
+    let name: String = t_from_mem(name_ptr, name_size);
+    let key: Key = t_from_mem(key_ptr, key_size);
+    with_state(|state| state.named_keys.insert(name, key));
+}
+
+/// Removes the key under a name.
+pub unsafe fn casper_remove_key(name_ptr: *const u8, name_size: usize) {
+    let name: String = t_from_mem(name_ptr, name_size);
+    with_state(|state| state.named_keys.remove(&name));
+}
+
+/// Unwinds out of the entry point with an error.
+pub unsafe fn casper_revert(status: u32) -> ! {
+    panic::resume_unwind(Box::new(Revert(status)))
+}
+
+/// Returns a non-zero value if the address of a `URef` is known to the mock host.
+pub unsafe fn casper_is_valid_uref(uref_ptr: *const u8, uref_size: usize) -> i32 {
+    let uref: URef = t_from_mem(uref_ptr, uref_size);
+    with_state(|state| {
+        let is_valid = state
+            .values
+            .contains_key(&Key::URef(uref.remove_access_rights()))
+            || state.balances.contains_key(&uref.addr());
+        is_valid as i32
+    })
+}
+
+/// Writes a copy of a `URef` with narrowed access rights.
+///
+/// Like `casper_is_valid_uref`, only checks that the address of the `URef` is known to the mock
+/// host, not its access rights.
+pub unsafe fn casper_narrow_uref(
+    uref_ptr: *const u8,
+    uref_size: usize,
+    access_rights: u8,
+    dest_ptr: *mut u8,
+) -> i32 {
+    if casper_is_valid_uref(uref_ptr, uref_size) == 0 {
+        panic!("forged reference passed to `casper_narrow_uref`");
+    }
+    let uref: URef = t_from_mem(uref_ptr, uref_size);
+    let result = match AccessRights::from_bits(access_rights) {
+        None => Err(ApiError::InvalidArgument),
+        Some(access_rights) if access_rights.is_none() => Err(ApiError::NoAccessRights),
+        Some(access_rights) if !uref.access_rights().contains(access_rights) => {
+            Err(ApiError::PermissionDenied)
+        }
+        Some(access_rights) => {
+            t_to_mem(dest_ptr, &uref.with_access_rights(access_rights));
+            Ok(())
+        }
+    };
+    api_error::i32_from(result)
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_add_associated_key(
+    _account_hash_ptr: *const u8,
+    _account_hash_size: usize,
+    _weight: i32,
+) -> i32 {
+    unsupported!("casper_add_associated_key")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_remove_associated_key(
+    _account_hash_ptr: *const u8,
+    _account_hash_size: usize,
+) -> i32 {
+    unsupported!("casper_remove_associated_key")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_update_associated_key(
+    _account_hash_ptr: *const u8,
+    _account_hash_size: usize,
+    _weight: i32,
+) -> i32 {
+    unsupported!("casper_update_associated_key")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_set_action_threshold(_permission_level: u32, _threshold: u32) -> i32 {
+    unsupported!("casper_set_action_threshold")
+}
+
+/// Loads the caller into the host buffer.
+pub unsafe fn casper_get_caller(output_size: *mut usize) -> i32 {
+    with_state(|state| {
+        let bytes = serialize(&state.caller);
+        set_host_buffer(state, bytes, output_size)
+    })
+}
+
+/// Writes the block time.
+pub unsafe fn casper_get_blocktime(dest_ptr: *const u8) {
+    let blocktime = with_state(|state| state.block_context.block_time());
+    t_to_mem(dest_ptr as *mut u8, &blocktime);
+}
+
+/// Writes the block context.
+pub unsafe fn casper_get_block_context(dest_ptr: *const u8) {
+    let block_context = with_state(|state| state.block_context);
+    t_to_mem(dest_ptr as *mut u8, &block_context);
+}
+
+/// Creates a new purse with a zero balance.
+pub unsafe fn casper_create_purse(purse_ptr: *const u8, _purse_size: usize) -> i32 {
+    let purse = with_state(|state| state.new_purse(U512::zero()));
+    t_to_mem(purse_ptr as *mut u8, &purse);
+    0
+}
+
+/// Transfers from the caller's main purse to the main purse of an account, creating it if needed.
+pub unsafe fn casper_transfer_to_account(
+    target_ptr: *const u8,
+    target_size: usize,
+    amount_ptr: *const u8,
+    amount_size: usize,
+    _id_ptr: *const u8,
+    _id_size: usize,
+    result_ptr: *const i32,
+) -> i32 {
+    let target: AccountHash = t_from_mem(target_ptr, target_size);
+    let amount: U512 = t_from_mem(amount_ptr, amount_size);
+    with_state(|state| {
+        let main_purse = state.main_purse();
+        match state.transfer_to_account(main_purse, target, amount) {
+            Ok(transferred_to) => {
+                *(result_ptr as *mut i32) = transferred_to;
+                0
+            }
+            Err(error) => api_error::i32_from(Err(error)),
+        }
+    })
+}
+
+/// Transfers from a purse to the main purse of an account, creating it if needed.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_transfer_from_purse_to_account(
+    source_ptr: *const u8,
+    source_size: usize,
+    target_ptr: *const u8,
+    target_size: usize,
+    amount_ptr: *const u8,
+    amount_size: usize,
+    _id_ptr: *const u8,
+    _id_size: usize,
+    result_ptr: *const i32,
+) -> i32 {
+    let source: URef = t_from_mem(source_ptr, source_size);
+    let target: AccountHash = t_from_mem(target_ptr, target_size);
+    let amount: U512 = t_from_mem(amount_ptr, amount_size);
+    with_state(
+        |state| match state.transfer_to_account(source, target, amount) {
+            Ok(transferred_to) => {
+                *(result_ptr as *mut i32) = transferred_to;
+                0
+            }
+            Err(error) => api_error::i32_from(Err(error)),
+        },
+    )
+}
+
+/// Transfers between two purses.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_transfer_from_purse_to_purse(
+    source_ptr: *const u8,
+    source_size: usize,
+    target_ptr: *const u8,
+    target_size: usize,
+    amount_ptr: *const u8,
+    amount_size: usize,
+    _id_ptr: *const u8,
+    _id_size: usize,
+) -> i32 {
+    let source: URef = t_from_mem(source_ptr, source_size);
+    let target: URef = t_from_mem(target_ptr, target_size);
+    let amount: U512 = t_from_mem(amount_ptr, amount_size);
+    api_error::i32_from(with_state(|state| state.transfer(source, target, amount)))
+}
+
+/// Not supported by the mock host.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_record_transfer(
+    _maybe_to_ptr: *const u8,
+    _maybe_to_size: usize,
+    _source_ptr: *const u8,
+    _source_size: usize,
+    _target_ptr: *const u8,
+    _target_size: usize,
+    _amount_ptr: *const u8,
+    _amount_size: usize,
+    _id_ptr: *const u8,
+    _id_size: usize,
+) -> i32 {
+    unsupported!("casper_record_transfer")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_record_era_info(
+    _era_id_ptr: *const u8,
+    _era_id_size: usize,
+    _era_info_ptr: *const u8,
+    _era_info_size: usize,
+) -> i32 {
+    unsupported!("casper_record_era_info")
+}
+
+/// Loads the balance of a purse into the host buffer.
+pub unsafe fn casper_get_balance(
+    purse_ptr: *const u8,
+    purse_size: usize,
+    result_size: *mut usize,
+) -> i32 {
+    let purse: URef = t_from_mem(purse_ptr, purse_size);
+    with_state(|state| match state.balances.get(&purse.addr()).copied() {
+        Some(balance) => {
+            let bytes = serialize(&balance);
+            set_host_buffer(state, bytes, result_size)
+        }
+        None => api_error::i32_from(Err(ApiError::InvalidPurse)),
+    })
+}
+
+/// Writes the execution phase.
+pub unsafe fn casper_get_phase(dest_ptr: *mut u8) {
+    let phase = with_state(|state| state.phase);
+    t_to_mem(dest_ptr, &phase);
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_get_system_contract(
+    _system_contract_index: u32,
+    _dest_ptr: *mut u8,
+    _dest_size: usize,
+) -> i32 {
+    unsupported!("casper_get_system_contract")
+}
+
+/// Writes the caller's main purse.
+pub unsafe fn casper_get_main_purse(dest_ptr: *mut u8) {
+    let main_purse = with_state(|state| state.main_purse());
+    t_to_mem(dest_ptr, &main_purse);
+}
+
+/// Moves the contents of the host buffer into memory.
+pub unsafe fn casper_read_host_buffer(
+    dest_ptr: *mut u8,
+    dest_size: usize,
+    bytes_written: *mut usize,
+) -> i32 {
+    with_state(|state| {
+        let bytes = match state.host_buffer.take() {
+            Some(bytes) => bytes,
+            None => return api_error::i32_from(Err(ApiError::HostBufferEmpty)),
+        };
+        if bytes.len() > dest_size {
+            state.host_buffer = Some(bytes);
+            return api_error::i32_from(Err(ApiError::BufferTooSmall));
+        }
+        write_to_mem(dest_ptr, &bytes);
+        *bytes_written = bytes.len();
+        0
+    })
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_create_contract_package_at_hash(
+    _hash_addr_ptr: *mut u8,
+    _access_addr_ptr: *mut u8,
+    _is_locked: bool,
+) {
+    unsupported!("casper_create_contract_package_at_hash")
+}
+
+/// Not supported by the mock host.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_create_contract_user_group(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _label_ptr: *const u8,
+    _label_size: usize,
+    _num_new_urefs: u8,
+    _existing_urefs_ptr: *const u8,
+    _existing_urefs_size: usize,
+    _output_size_ptr: *mut usize,
+) -> i32 {
+    unsupported!("casper_create_contract_user_group")
+}
+
+/// Not supported by the mock host.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_add_contract_version(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _version_ptr: *const u32,
+    _entry_points_ptr: *const u8,
+    _entry_points_size: usize,
+    _named_keys_ptr: *const u8,
+    _named_keys_size: usize,
+    _output_ptr: *mut u8,
+    _output_size: usize,
+    _bytes_written_ptr: *mut usize,
+) -> i32 {
+    unsupported!("casper_add_contract_version")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_disable_contract_version(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _contract_hash_ptr: *const u8,
+    _contract_hash_size: usize,
+) -> i32 {
+    unsupported!("casper_disable_contract_version")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_remove_contract(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _contract_hash_ptr: *const u8,
+    _contract_hash_size: usize,
+    _max_keys: u32,
+    _remaining_ptr: *mut u32,
+) -> i32 {
+    unsupported!("casper_remove_contract")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_call_contract(
+    _contract_hash_ptr: *const u8,
+    _contract_hash_size: usize,
+    _entry_point_name_ptr: *const u8,
+    _entry_point_name_size: usize,
+    _runtime_args_ptr: *const u8,
+    _runtime_args_size: usize,
+    _result_size: *mut usize,
+) -> i32 {
+    unsupported!("casper_call_contract")
+}
+
+/// Not supported by the mock host.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn casper_call_versioned_contract(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _contract_version_ptr: *const u8,
+    _contract_version_size: usize,
+    _entry_point_name_ptr: *const u8,
+    _entry_point_name_size: usize,
+    _runtime_args_ptr: *const u8,
+    _runtime_args_size: usize,
+    _result_size: *mut usize,
+) -> i32 {
+    unsupported!("casper_call_versioned_contract")
+}
+
+/// Writes the size of a runtime argument.
+pub unsafe fn casper_get_named_arg_size(
+    name_ptr: *const u8,
+    name_size: usize,
+    dest_size: *mut usize,
+) -> i32 {
+    let name = string_from_mem(name_ptr, name_size);
+    match with_state(|state| state.args.get(&name).map(|arg| arg.inner_bytes().len())) {
+        Some(size) => {
+            *dest_size = size;
+            0
+        }
+        None => api_error::i32_from(Err(ApiError::MissingArgument)),
+    }
+}
+
+/// Writes the bytes of a runtime argument.
+pub unsafe fn casper_get_named_arg(
+    name_ptr: *const u8,
+    name_size: usize,
+    dest_ptr: *mut u8,
+    dest_size: usize,
+) -> i32 {
+    let name = string_from_mem(name_ptr, name_size);
+    let arg = match with_state(|state| state.args.get(&name).cloned()) {
+        Some(arg) => arg,
+        None => return api_error::i32_from(Err(ApiError::MissingArgument)),
+    };
+    if arg.inner_bytes().len() > dest_size {
+        return api_error::i32_from(Err(ApiError::OutOfMemory));
+    }
+    write_to_mem(dest_ptr, arg.inner_bytes());
+    0
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_remove_contract_user_group(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _label_ptr: *const u8,
+    _label_size: usize,
+) -> i32 {
+    unsupported!("casper_remove_contract_user_group")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_provision_contract_user_group_uref(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _label_ptr: *const u8,
+    _label_size: usize,
+    _value_size_ptr: *const usize,
+) -> i32 {
+    unsupported!("casper_provision_contract_user_group_uref")
+}
+
+/// Not supported by the mock host.
+pub unsafe fn casper_remove_contract_user_group_urefs(
+    _contract_package_hash_ptr: *const u8,
+    _contract_package_hash_size: usize,
+    _label_ptr: *const u8,
+    _label_size: usize,
+    _urefs_ptr: *const u8,
+    _urefs_size: usize,
+) -> i32 {
+    unsupported!("casper_remove_contract_user_group_urefs")
+}
+
+/// Writes the Blake2b hash of the given bytes.
+pub unsafe fn casper_blake2b(
+    in_ptr: *const u8,
+    in_size: usize,
+    out_ptr: *mut u8,
+    out_size: usize,
+) -> i32 {
+    if out_size != BLAKE2B_DIGEST_LENGTH {
+        return api_error::i32_from(Err(ApiError::BufferTooSmall));
+    }
+    write_to_mem(
+        out_ptr,
+        &casper_types::blake2b(bytes_from_mem(in_ptr, in_size)),
+    );
+    0
+}
+
+/// Loads a call stack consisting of the caller's session code into the host buffer.
+pub unsafe fn casper_load_call_stack(
+    call_stack_len_ptr: *mut usize,
+    result_size_ptr: *mut usize,
+) -> i32 {
+    with_state(|state| {
+        let call_stack = vec![CallStackElement::session(state.caller)];
+        *call_stack_len_ptr = call_stack.len();
+        let bytes = serialize(&call_stack);
+        set_host_buffer(state, bytes, result_size_ptr)
+    })
+}
+
+/// Prints text to the standard output.
+#[cfg(feature = "test-support")]
+pub unsafe fn casper_print(text_ptr: *const u8, text_size: usize) {
+    println!("{}", string_from_mem(text_ptr, text_size));
+}
+
+/// Creates a new dictionary and loads its seed `URef` into the host buffer.
+pub unsafe fn casper_new_dictionary(output_size_ptr: *mut usize) -> i32 {
+    with_state(|state| {
+        if state.host_buffer.is_some() {
+            return api_error::i32_from(Err(ApiError::HostBufferFull));
+        }
+        let seed_uref = state.new_uref(CLValue::unit());
+        let bytes = serialize(&seed_uref);
+        set_host_buffer(state, bytes, output_size_ptr)
+    })
+}
+
+/// Loads the value under an item key of a dictionary into the host buffer.
+pub unsafe fn casper_dictionary_get(
+    uref_ptr: *const u8,
+    uref_size: usize,
+    key_bytes_ptr: *const u8,
+    key_bytes_size: usize,
+    output_size: *mut usize,
+) -> i32 {
+    let seed_uref: URef = t_from_mem(uref_ptr, uref_size);
+    let item_key = bytes_from_mem(key_bytes_ptr, key_bytes_size);
+    let dictionary_key = Key::dictionary(seed_uref, item_key);
+    with_state(|state| match state.read(&dictionary_key).cloned() {
+        Some(value) => set_host_buffer(state, value.inner_bytes().clone(), output_size),
+        None => api_error::i32_from(Err(ApiError::ValueNotFound)),
+    })
+}
+
+/// Loads the value under a `Key::Dictionary` into the host buffer.
+pub unsafe fn casper_dictionary_read(
+    key_ptr: *const u8,
+    key_size: usize,
+    output_size: *mut usize,
+) -> i32 {
+    casper_read_value(key_ptr, key_size, output_size)
+}
+
+/// Writes a value under an item key of a dictionary.
+pub unsafe fn casper_dictionary_put(
+    uref_ptr: *const u8,
+    uref_size: usize,
+    key_ptr: *const u8,
+    key_size: usize,
+    value_ptr: *const u8,
+    value_size: usize,
+) -> i32 {
+    let seed_uref: URef = t_from_mem(uref_ptr, uref_size);
+    let item_key = bytes_from_mem(key_ptr, key_size);
+    let value: CLValue = t_from_mem(value_ptr, value_size);
+    with_state(|state| state.write(Key::dictionary(seed_uref, item_key), value));
+    0
+}
+
+/// Writes deterministic pseudo-random bytes.
+pub unsafe fn casper_random_bytes(out_ptr: *mut u8, out_size: usize) -> i32 {
+    let mut bytes = Vec::with_capacity(out_size);
+    while bytes.len() < out_size {
+        bytes.extend_from_slice(&with_state(State::new_address));
+    }
+    write_to_mem(out_ptr, &bytes[..out_size]);
+    0
+}
@@ -0,0 +1,459 @@
+//! An in-process mock of the host, allowing contract logic to be unit-tested natively.
+//!
+//! When the `mock-host` feature is enabled, the functions in [`ext_ffi`](crate::ext_ffi) are
+//! implemented in Rust against an in-memory state rather than being imported from the host.  The
+//! state is thread-local, so each test gets its own host when run by the default test harness.
+//!
+//! The mock host supports named keys, reading and writing [`URef`]s, dictionaries, purses and
+//! transfers, runtime arguments and the caller context.  Calls to other contracts, contract
+//! packages, associated keys and the system contracts are not supported and panic.  Access rights
+//! are not checked and no gas is charged, so tests requiring either should use
+//! `casper-engine-test-support` instead.
+//!
+//! ```
+//! use casper_contract::{
+//!     contract_api::{runtime, storage},
+//!     mock_host::{self, MockHost},
+//! };
+//! use casper_types::{runtime_args, ApiError, RuntimeArgs};
+//!
+//! fn call() {
+//!     let value: u64 = runtime::get_named_arg("value");
+//!     if value == 0 {
+//!         runtime::revert(ApiError::User(1));
+//!     }
+//!     runtime::put_key("value", storage::new_uref(value).into());
+//! }
+//!
+//! MockHost::new()
+//!     .with_args(runtime_args! { "value" => 0u64 })
+//!     .install();
+//! assert_eq!(mock_host::call(call), Err(ApiError::User(1)));
+//! assert!(mock_host::named_keys().is_empty());
+//!
+//! mock_host::set_args(runtime_args! { "value" => 7u64 });
+//! assert_eq!(mock_host::call(call), Ok(None));
+//! let key = mock_host::named_keys()["value"];
+//! assert_eq!(mock_host::read::<u64>(key), Some(7));
+//! ```
+
+pub(crate) mod ffi;
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    panic::{self, AssertUnwindSafe},
+};
+
+use casper_types::{
+    account::AccountHash, bytesrepr::FromBytes, contracts::NamedKeys, system::mint, AccessRights,
+    ApiError, BlockContext, BlockTime, CLTyped, CLValue, Key, Phase, RuntimeArgs, TransferredTo,
+    URef, URefAddr, U512,
+};
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(MockHost::new().state);
+}
+
+/// The panic payload used to unwind out of an entry point which reverted.
+struct Revert(u32);
+
+/// The panic payload used to unwind out of an entry point which returned a value.
+struct Return(CLValue);
+
+/// The in-memory state of the mock host.
+#[derive(Clone, Debug)]
+struct State {
+    caller: AccountHash,
+    block_context: BlockContext,
+    phase: Phase,
+    args: RuntimeArgs,
+    named_keys: NamedKeys,
+    authorization_keys: BTreeSet<AccountHash>,
+    main_purses: BTreeMap<AccountHash, URef>,
+    values: BTreeMap<Key, CLValue>,
+    balances: BTreeMap<URefAddr, U512>,
+    host_buffer: Option<Vec<u8>>,
+    address_count: u64,
+}
+
+impl State {
+    fn new_address(&mut self) -> [u8; 32] {
+        self.address_count += 1;
+        casper_types::blake2b(self.address_count.to_le_bytes())
+    }
+
+    fn new_uref(&mut self, value: CLValue) -> URef {
+        let uref = URef::new(self.new_address(), AccessRights::READ_ADD_WRITE);
+        self.values
+            .insert(Key::URef(uref.remove_access_rights()), value);
+        uref
+    }
+
+    fn new_purse(&mut self, balance: U512) -> URef {
+        let purse = URef::new(self.new_address(), AccessRights::READ_ADD_WRITE);
+        self.balances.insert(purse.addr(), balance);
+        purse
+    }
+
+    fn main_purse(&self) -> URef {
+        self.main_purses[&self.caller]
+    }
+
+    fn read(&self, key: &Key) -> Option<&CLValue> {
+        self.values.get(&key.normalize())
+    }
+
+    fn write(&mut self, key: Key, value: CLValue) {
+        self.values.insert(key.normalize(), value);
+    }
+
+    fn set_host_buffer(&mut self, bytes: Vec<u8>) -> Result<usize, ApiError> {
+        if self.host_buffer.is_some() {
+            return Err(ApiError::HostBufferFull);
+        }
+        let size = bytes.len();
+        self.host_buffer = Some(bytes);
+        Ok(size)
+    }
+
+    fn transfer(&mut self, source: URef, target: URef, amount: U512) -> Result<(), ApiError> {
+        if !self.balances.contains_key(&target.addr()) {
+            return Err(ApiError::InvalidPurse);
+        }
+        let source_balance = self
+            .balances
+            .get_mut(&source.addr())
+            .ok_or(ApiError::InvalidPurse)?;
+        *source_balance = source_balance
+            .checked_sub(amount)
+            .ok_or_else(|| ApiError::from(mint::Error::InsufficientFunds))?;
+        *self.balances.entry(target.addr()).or_default() += amount;
+        Ok(())
+    }
+
+    fn transfer_to_account(
+        &mut self,
+        source: URef,
+        target: AccountHash,
+        amount: U512,
+    ) -> Result<i32, ApiError> {
+        let (target_purse, transferred_to) = match self.main_purses.get(&target) {
+            Some(main_purse) => (*main_purse, TransferredTo::ExistingAccount),
+            None => {
+                let main_purse = self.new_purse(U512::zero());
+                self.main_purses.insert(target, main_purse);
+                (main_purse, TransferredTo::NewAccount)
+            }
+        };
+        self.transfer(source, target_purse, amount)?;
+        Ok(transferred_to as i32)
+    }
+}
+
+fn with_state<T, F: FnOnce(&mut State) -> T>(f: F) -> T {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// A builder for the state of the mock host of the current thread.
+#[derive(Clone, Debug)]
+pub struct MockHost {
+    state: State,
+}
+
+impl MockHost {
+    /// Returns a builder for a mock host whose caller has an empty main purse and no named keys,
+    /// running session code at block time `0` without any runtime arguments.
+    pub fn new() -> Self {
+        let caller = AccountHash::default();
+        let mut state = State {
+            caller,
+            block_context: BlockContext::default(),
+            phase: Phase::Session,
+            args: RuntimeArgs::new(),
+            named_keys: NamedKeys::new(),
+            authorization_keys: BTreeSet::new(),
+            main_purses: BTreeMap::new(),
+            values: BTreeMap::new(),
+            balances: BTreeMap::new(),
+            host_buffer: None,
+            address_count: 0,
+        };
+        let main_purse = state.new_purse(U512::zero());
+        state.main_purses.insert(caller, main_purse);
+        state.authorization_keys.insert(caller);
+        MockHost { state }
+    }
+
+    /// Sets the account calling the contract, which is also its only authorization key.
+    pub fn with_caller(mut self, caller: AccountHash) -> Self {
+        let main_purse = self.state.main_purse();
+        self.state.main_purses.remove(&self.state.caller);
+        self.state.main_purses.insert(caller, main_purse);
+        self.state.authorization_keys = BTreeSet::from([caller]);
+        self.state.caller = caller;
+        self
+    }
+
+    /// Sets the block time.
+    pub fn with_blocktime(mut self, blocktime: BlockTime) -> Self {
+        self.state.block_context = BlockContext::new(
+            blocktime,
+            self.state.block_context.block_height(),
+            self.state.block_context.era_id(),
+            self.state.block_context.protocol_version(),
+        );
+        self
+    }
+
+    /// Sets the block context, including the block time.
+    pub fn with_block_context(mut self, block_context: BlockContext) -> Self {
+        self.state.block_context = block_context;
+        self
+    }
+
+    /// Sets the execution phase.
+    pub fn with_phase(mut self, phase: Phase) -> Self {
+        self.state.phase = phase;
+        self
+    }
+
+    /// Sets the runtime arguments.
+    pub fn with_args(mut self, args: RuntimeArgs) -> Self {
+        self.state.args = args;
+        self
+    }
+
+    /// Adds a named key to the context of the contract.
+    pub fn with_named_key(mut self, name: &str, key: Key) -> Self {
+        self.state.named_keys.insert(name.into(), key);
+        self
+    }
+
+    /// Sets the balance of the caller's main purse.
+    pub fn with_main_purse_balance(mut self, balance: U512) -> Self {
+        let main_purse = self.state.main_purse();
+        self.state.balances.insert(main_purse.addr(), balance);
+        self
+    }
+
+    /// Replaces the state of the mock host of the current thread.
+    pub fn install(self) {
+        with_state(|state| *state = self.state);
+    }
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        MockHost::new()
+    }
+}
+
+/// Runs `entry_point` against the mock host of the current thread.
+///
+/// Returns the value passed to [`runtime::ret`](crate::contract_api::runtime::ret) if any, or the
+/// error passed to [`runtime::revert`](crate::contract_api::runtime::revert), in which case all
+/// changes made by `entry_point` are discarded.  Other panics are propagated.
+pub fn call<F: FnOnce()>(entry_point: F) -> Result<Option<CLValue>, ApiError> {
+    let snapshot = with_state(|state| state.clone());
+    let result = panic::catch_unwind(AssertUnwindSafe(entry_point));
+    with_state(|state| state.host_buffer = None);
+    match result {
+        Ok(()) => Ok(None),
+        Err(payload) => {
+            let payload = match payload.downcast::<Return>() {
+                Ok(value) => return Ok(Some(value.0)),
+                Err(payload) => payload,
+            };
+            match payload.downcast::<Revert>() {
+                Ok(status) => {
+                    with_state(|state| *state = snapshot);
+                    Err(ApiError::from(status.0))
+                }
+                Err(payload) => panic::resume_unwind(payload),
+            }
+        }
+    }
+}
+
+/// Replaces the runtime arguments for subsequent calls.
+pub fn set_args(args: RuntimeArgs) {
+    with_state(|state| state.args = args);
+}
+
+/// Returns the named keys of the context of the contract.
+pub fn named_keys() -> NamedKeys {
+    with_state(|state| state.named_keys.clone())
+}
+
+/// Returns the value stored under `key`, e.g. a [`URef`] or a dictionary item, or `None` if there
+/// is no such value or it is not of type `T`.
+pub fn read<T: CLTyped + FromBytes>(key: Key) -> Option<T> {
+    with_state(|state| state.read(&key).cloned())?.into_t().ok()
+}
+
+/// Returns the balance of `purse`, or `None` if there is no such purse.
+pub fn purse_balance(purse: URef) -> Option<U512> {
+    with_state(|state| state.balances.get(&purse.addr()).copied())
+}
+
+/// Returns the balance of the main purse of `account_hash`, or `None` if the account doesn't
+/// exist.
+pub fn account_balance(account_hash: AccountHash) -> Option<U512> {
+    with_state(|state| {
+        let main_purse = state.main_purses.get(&account_hash)?;
+        state.balances.get(&main_purse.addr()).copied()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{
+        runtime_args, system::mint, ApiError, EraId, Key, ProtocolVersion, RuntimeArgs, U512,
+    };
+
+    use super::*;
+    use crate::contract_api::{account, dictionary::Dictionary, runtime, storage, system};
+
+    const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1; 32]);
+
+    #[test]
+    fn should_store_dictionary_items() {
+        MockHost::new().install();
+        let result = call(|| {
+            let dictionary: Dictionary<String, u64> = Dictionary::new("dictionary").unwrap();
+            dictionary.set(&"a".to_string(), 1);
+            dictionary.update(&"a".to_string(), |value| value.unwrap() + 1);
+            assert_eq!(
+                dictionary.get_many(&["a".to_string(), "b".to_string()]),
+                vec![Some(2), None]
+            );
+        });
+        assert_eq!(result, Ok(None));
+
+        let seed_uref = named_keys()["dictionary"].into_uref().unwrap();
+        assert_eq!(read::<u64>(Key::dictionary(seed_uref, b"a")), Some(2));
+    }
+
+    #[test]
+    fn should_discard_changes_on_revert() {
+        MockHost::new().install();
+        let result = call(|| {
+            let uref = storage::new_uref(1u64);
+            runtime::put_key("uref", uref.into());
+            runtime::revert(ApiError::User(0));
+        });
+        assert_eq!(result, Err(ApiError::User(0)));
+        assert!(named_keys().is_empty());
+    }
+
+    #[test]
+    fn should_return_value() {
+        MockHost::new()
+            .with_args(runtime_args! { "value" => 7u64 })
+            .install();
+        let result = call(|| {
+            let value: u64 = runtime::get_named_arg("value");
+            runtime::ret(CLValue::from_t(value * 2).unwrap())
+        });
+        assert_eq!(result, Ok(Some(CLValue::from_t(14u64).unwrap())));
+    }
+
+    #[test]
+    fn should_transfer_between_purses_and_accounts() {
+        MockHost::new()
+            .with_main_purse_balance(U512::from(100))
+            .install();
+        call(|| {
+            let purse = system::create_purse();
+            runtime::put_key("purse", purse.into());
+            system::transfer_from_purse_to_purse(
+                account::get_main_purse(),
+                purse,
+                U512::from(30),
+                None,
+            )
+            .unwrap();
+            assert_eq!(
+                system::transfer_to_account(ACCOUNT_1_ADDR, U512::from(50), None),
+                Ok(TransferredTo::NewAccount)
+            );
+            assert_eq!(
+                system::transfer_to_account(ACCOUNT_1_ADDR, U512::from(50), None),
+                Err(ApiError::from(mint::Error::InsufficientFunds))
+            );
+            assert_eq!(system::get_balance(), Some(U512::from(20)));
+        })
+        .unwrap();
+
+        let purse = named_keys()["purse"].into_uref().unwrap();
+        assert_eq!(purse_balance(purse), Some(U512::from(30)));
+        assert_eq!(account_balance(ACCOUNT_1_ADDR), Some(U512::from(50)));
+    }
+
+    #[test]
+    fn should_provide_caller_context() {
+        let blocktime = BlockTime::new(1_000);
+        MockHost::new()
+            .with_caller(ACCOUNT_1_ADDR)
+            .with_blocktime(blocktime)
+            .with_phase(Phase::Payment)
+            .install();
+        call(|| {
+            assert_eq!(runtime::get_caller(), ACCOUNT_1_ADDR);
+            assert_eq!(runtime::get_blocktime(), blocktime);
+            assert_eq!(runtime::get_phase(), Phase::Payment);
+            assert_eq!(
+                runtime::list_authorization_keys(),
+                BTreeSet::from([ACCOUNT_1_ADDR])
+            );
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn should_provide_block_context() {
+        let block_context = BlockContext::new(
+            BlockTime::new(1_000),
+            10,
+            EraId::new(2),
+            ProtocolVersion::V1_0_0,
+        );
+        MockHost::new()
+            .with_block_context(block_context)
+            .with_blocktime(BlockTime::new(2_000))
+            .install();
+        call(|| {
+            let expected = BlockContext::new(
+                BlockTime::new(2_000),
+                10,
+                EraId::new(2),
+                ProtocolVersion::V1_0_0,
+            );
+            assert_eq!(runtime::get_block_context(), expected);
+            assert_eq!(runtime::get_blocktime(), BlockTime::new(2_000));
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn should_narrow_uref() {
+        MockHost::new().install();
+        call(|| {
+            let uref = storage::new_uref(1u64);
+            let read_only = runtime::narrow_uref(uref, AccessRights::READ).unwrap();
+            assert_eq!(read_only, uref.into_read());
+            assert_eq!(storage::read::<u64>(read_only), Ok(Some(1)));
+
+            assert_eq!(
+                runtime::narrow_uref(read_only, AccessRights::READ_WRITE),
+                Err(ApiError::PermissionDenied)
+            );
+            assert_eq!(
+                runtime::narrow_uref(uref, AccessRights::NONE),
+                Err(ApiError::NoAccessRights)
+            );
+        })
+        .unwrap();
+    }
+}
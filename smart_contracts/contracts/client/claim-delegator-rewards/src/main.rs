@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::{runtime, system};
+use casper_types::{runtime_args, system::auction, PublicKey, RuntimeArgs, U512};
+
+fn claim_delegator_rewards(delegator: PublicKey, validator: PublicKey) {
+    let contract_hash = system::get_auction();
+    let args = runtime_args! {
+        auction::ARG_DELEGATOR => delegator,
+        auction::ARG_VALIDATOR => validator,
+    };
+    let _amount: U512 =
+        runtime::call_contract(contract_hash, auction::METHOD_CLAIM_DELEGATOR_REWARDS, args);
+}
+
+// Claim delegator rewards contract.
+//
+// Accepts a delegator's public key and the public key of the validator the rewards were earned
+// with.  The claimed rewards are paid out to the delegator once the unbonding delay has passed.
+#[no_mangle]
+pub extern "C" fn call() {
+    let delegator = runtime::get_named_arg(auction::ARG_DELEGATOR);
+    let validator = runtime::get_named_arg(auction::ARG_VALIDATOR);
+    claim_delegator_rewards(delegator, validator);
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::{runtime, system};
+use casper_types::{runtime_args, system::auction, PublicKey, RuntimeArgs};
+
+fn register_successor_key(validator_public_key: PublicKey, successor_public_key: PublicKey) {
+    let contract_hash = system::get_auction();
+    let args = runtime_args! {
+        auction::ARG_VALIDATOR_PUBLIC_KEY => validator_public_key,
+        auction::ARG_SUCCESSOR_PUBLIC_KEY => successor_public_key,
+    };
+    runtime::call_contract::<()>(contract_hash, auction::METHOD_REGISTER_SUCCESSOR_KEY, args);
+}
+
+// Accepts a validator's public key and the key it should sign with from the next era onwards.
+// Issues a register-successor-key request to the auction contract.
+#[no_mangle]
+pub extern "C" fn call() {
+    let validator_public_key: PublicKey = runtime::get_named_arg(auction::ARG_VALIDATOR_PUBLIC_KEY);
+    let successor_public_key: PublicKey = runtime::get_named_arg(auction::ARG_SUCCESSOR_PUBLIC_KEY);
+    register_successor_key(validator_public_key, successor_public_key);
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::runtime;
+use casper_types::{BlockContext, BlockTime, EraId};
+
+const ARG_KNOWN_BLOCK_TIME: &str = "known_block_time";
+const ARG_KNOWN_BLOCK_HEIGHT: &str = "known_block_height";
+const ARG_KNOWN_ERA_ID: &str = "known_era_id";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let known_block_time: u64 = runtime::get_named_arg(ARG_KNOWN_BLOCK_TIME);
+    let known_block_height: u64 = runtime::get_named_arg(ARG_KNOWN_BLOCK_HEIGHT);
+    let known_era_id: EraId = runtime::get_named_arg(ARG_KNOWN_ERA_ID);
+    let block_context: BlockContext = runtime::get_block_context();
+
+    assert_eq!(
+        block_context.block_time(),
+        BlockTime::new(known_block_time),
+        "actual block time not known block time"
+    );
+    assert_eq!(
+        block_context.block_time(),
+        runtime::get_blocktime(),
+        "block context time not block time"
+    );
+    assert_eq!(
+        block_context.block_height(),
+        known_block_height,
+        "actual block height not known block height"
+    );
+    assert_eq!(
+        block_context.era_id(),
+        known_era_id,
+        "actual era id not known era id"
+    );
+}
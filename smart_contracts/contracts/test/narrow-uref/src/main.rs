@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{AccessRights, ApiError};
+
+const ARG_WRITE_THROUGH_ALIAS: &str = "write_through_alias";
+const READ_ONLY_KEY: &str = "read_only";
+const INITIAL_VALUE: u64 = 42;
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let write_through_alias: bool = runtime::get_named_arg(ARG_WRITE_THROUGH_ALIAS);
+
+    let uref = storage::new_uref(INITIAL_VALUE);
+    let read_only = runtime::narrow_uref(uref, AccessRights::READ).unwrap_or_revert();
+    assert_eq!(
+        read_only.access_rights(),
+        AccessRights::READ,
+        "alias should be read-only"
+    );
+    assert_eq!(
+        storage::read_or_revert::<u64>(read_only),
+        INITIAL_VALUE,
+        "alias should read the value of the uref"
+    );
+    assert_eq!(
+        runtime::narrow_uref(read_only, AccessRights::READ_WRITE),
+        Err(ApiError::PermissionDenied),
+        "alias should not be widened"
+    );
+    runtime::put_key(READ_ONLY_KEY, read_only.into());
+
+    if write_through_alias {
+        // Traps, since the alias has no write access.
+        storage::write(read_only, INITIAL_VALUE + 1);
+    }
+}
@@ -0,0 +1,63 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
+use casper_contract::{
+    contract_api::{dictionary::Dictionary, runtime},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{account::AccountHash, Key, U512};
+
+const ARG_CREDITS: &str = "credits";
+const BALANCES_DICTIONARY_NAME: &str = "balances";
+const OWNERS_DICTIONARY_NAME: &str = "owners";
+const LABELS_DICTIONARY_NAME: &str = "labels";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let credits: Vec<(AccountHash, U512)> = runtime::get_named_arg(ARG_CREDITS);
+
+    let balances: Dictionary<AccountHash, U512> =
+        Dictionary::new(BALANCES_DICTIONARY_NAME).unwrap_or_revert();
+    let owners: Dictionary<Key, AccountHash> =
+        Dictionary::new(OWNERS_DICTIONARY_NAME).unwrap_or_revert();
+    let labels: Dictionary<String, String> =
+        Dictionary::new(LABELS_DICTIONARY_NAME).unwrap_or_revert();
+
+    for (account_hash, amount) in &credits {
+        balances.update(account_hash, |balance| {
+            balance.unwrap_or_default() + *amount
+        });
+        owners.set(&Key::Account(*account_hash), *account_hash);
+    }
+
+    let account_hashes: Vec<AccountHash> = credits
+        .iter()
+        .map(|(account_hash, _amount)| *account_hash)
+        .collect();
+    for (account_hash, balance) in account_hashes
+        .iter()
+        .zip(balances.get_many(&account_hashes))
+    {
+        let expected_balance: U512 = credits
+            .iter()
+            .filter(|(credited, _amount)| credited == account_hash)
+            .map(|(_credited, amount)| *amount)
+            .fold(U512::zero(), |total, amount| total + amount);
+        assert_eq!(balance, Some(expected_balance));
+        assert_eq!(
+            owners.get(&Key::Account(*account_hash)),
+            Some(*account_hash)
+        );
+    }
+    assert!(!balances.contains_key(&AccountHash::new([u8::MAX; 32])));
+
+    // Item keys longer than the maximum length are hashed.
+    let long_label: String = "label".repeat(100);
+    labels.set(&long_label, long_label.clone());
+    let labels: Dictionary<String, String> = Dictionary::named(LABELS_DICTIONARY_NAME);
+    assert_eq!(labels.get(&long_label), Some(long_label));
+}
@@ -668,6 +668,7 @@ fn sample_transfer() -> Transfer {
         U512::MAX,
         U512::from_dec_str("123123123123").unwrap(),
         Some(1u64),
+        None,
     )
 }
 
@@ -30,9 +30,27 @@ pub use self::{
 use crate::{
     bytesrepr::{self, FromBytes, ToBytes},
     contracts::NamedKeys,
-    crypto, AccessRights, ContextAccessRights, Key, URef, BLAKE2B_DIGEST_LENGTH,
+    crypto, AccessRights, ContextAccessRights, HashAddr, Key, URef, BLAKE2B_DIGEST_LENGTH,
 };
 
+/// Name of the named key under which an account designates its authorization contract.
+///
+/// If the chainspec allows authorization contracts, a deploy sent from an account holding a
+/// [`Key::Hash`] of a contract under this name is authorized by calling that contract's
+/// [`AUTHORIZATION_ENTRY_POINT`] instead of checking the weights of the account's associated keys.
+/// The deploy still has to be approved by associated keys of the account only.
+pub const AUTHORIZATION_CONTRACT_KEY: &str = "authorization_contract";
+/// Name of the entry point of an authorization contract.
+///
+/// It is called with the [`ARG_AUTHORIZATION_KEYS`] and [`ARG_DEPLOY_HASH`] arguments and is
+/// expected to return `true` if the deploy is authorized.
+pub const AUTHORIZATION_ENTRY_POINT: &str = "authorize";
+/// Name of the argument holding the account hashes of the keys which approved the deploy, as a
+/// `Vec<AccountHash>`.
+pub const ARG_AUTHORIZATION_KEYS: &str = "authorization_keys";
+/// Name of the argument holding the hash of the deploy being authorized, as a `[u8; 32]`.
+pub const ARG_DEPLOY_HASH: &str = "deploy_hash";
+
 /// Represents an Account in the global state.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -246,6 +264,14 @@ impl Account {
         total_weight >= *self.action_thresholds().deployment()
     }
 
+    /// Returns the address of the account's authorization contract, if it designated one.
+    pub fn authorization_contract(&self) -> Option<HashAddr> {
+        match self.named_keys.get(AUTHORIZATION_CONTRACT_KEY) {
+            Some(Key::Hash(contract_hash)) => Some(*contract_hash),
+            _ => None,
+        }
+    }
+
     /// Checks whether the sum of the weights of all authorization keys is
     /// greater or equal to key management threshold.
     pub fn can_manage_keys_with(&self, authorization_keys: &BTreeSet<AccountHash>) -> bool {
@@ -748,6 +774,28 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn account_authorization_contract() {
+        let mut account = Account::create(
+            AccountHash::new([0u8; 32]),
+            NamedKeys::new(),
+            URef::new([0u8; 32], AccessRights::READ_ADD_WRITE),
+        );
+        assert_eq!(account.authorization_contract(), None);
+
+        // Only a contract hash designates an authorization contract.
+        account.named_keys_mut().insert(
+            AUTHORIZATION_CONTRACT_KEY.to_string(),
+            Key::URef(URef::new([1u8; 32], AccessRights::READ)),
+        );
+        assert_eq!(account.authorization_contract(), None);
+
+        account
+            .named_keys_mut()
+            .insert(AUTHORIZATION_CONTRACT_KEY.to_string(), Key::Hash([2u8; 32]));
+        assert_eq!(account.authorization_contract(), Some([2u8; 32]));
+    }
+
     #[test]
     fn set_action_threshold_higher_than_total_weight() {
         let identity_key = AccountHash::new([1u8; 32]);
@@ -0,0 +1,111 @@
+use alloc::vec::Vec;
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes, U64_SERIALIZED_LENGTH},
+    BlockTime, EraId, ProtocolVersion, BLOCKTIME_SERIALIZED_LENGTH, SEM_VER_SERIALIZED_LENGTH,
+};
+
+/// The number of bytes in a serialized [`BlockContext`].
+pub const BLOCK_CONTEXT_SERIALIZED_LENGTH: usize = BLOCKTIME_SERIALIZED_LENGTH
+    + U64_SERIALIZED_LENGTH
+    + U64_SERIALIZED_LENGTH
+    + SEM_VER_SERIALIZED_LENGTH;
+
+/// Information about the block in which a contract is executing.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct BlockContext {
+    block_time: BlockTime,
+    block_height: u64,
+    era_id: EraId,
+    protocol_version: ProtocolVersion,
+}
+
+impl BlockContext {
+    /// Constructs a `BlockContext`.
+    pub fn new(
+        block_time: BlockTime,
+        block_height: u64,
+        era_id: EraId,
+        protocol_version: ProtocolVersion,
+    ) -> Self {
+        BlockContext {
+            block_time,
+            block_height,
+            era_id,
+            protocol_version,
+        }
+    }
+
+    /// Returns the block time.
+    pub fn block_time(&self) -> BlockTime {
+        self.block_time
+    }
+
+    /// Returns the height of the block.
+    pub fn block_height(&self) -> u64 {
+        self.block_height
+    }
+
+    /// Returns the ID of the era the block belongs to.
+    pub fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    /// Returns the protocol version the block is executed with.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+}
+
+impl ToBytes for BlockContext {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        BLOCK_CONTEXT_SERIALIZED_LENGTH
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.block_time.write_bytes(writer)?;
+        self.block_height.write_bytes(writer)?;
+        self.era_id.write_bytes(writer)?;
+        self.protocol_version.write_bytes(writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for BlockContext {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_time, rem) = BlockTime::from_bytes(bytes)?;
+        let (block_height, rem) = u64::from_bytes(rem)?;
+        let (era_id, rem) = EraId::from_bytes(rem)?;
+        let (protocol_version, rem) = ProtocolVersion::from_bytes(rem)?;
+        Ok((
+            BlockContext::new(block_time, block_height, era_id, protocol_version),
+            rem,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytesrepr_roundtrip() {
+        let block_context = BlockContext::new(
+            BlockTime::new(1_600_000_000_000),
+            42,
+            EraId::new(7),
+            ProtocolVersion::from_parts(1, 4, 5),
+        );
+        bytesrepr::test_serialization_roundtrip(&block_context);
+        assert_eq!(
+            block_context.to_bytes().unwrap().len(),
+            BLOCK_CONTEXT_SERIALIZED_LENGTH
+        );
+    }
+}
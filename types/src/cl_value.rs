@@ -16,8 +16,11 @@ use crate::{
     checksummed_hex, CLType, CLTyped,
 };
 
+mod inspect;
 mod jsonrepr;
 
+pub use inspect::{CLValueNode, CLValueTree};
+
 /// Error while converting a [`CLValue`] into a given type.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -132,6 +135,19 @@ impl CLValue {
         self.bytes.inner_bytes()
     }
 
+    /// Returns a structured, human-readable rendering of the underlying value.
+    ///
+    /// Blobs of bytes, i.e. values of type `Any` or `List<U8>`, are decoded on a best-effort basis
+    /// as a serialized `CLValue` or string.
+    pub fn inspect(&self) -> CLValueTree {
+        CLValueTree::new(self)
+    }
+
+    /// Returns a human-readable rendering of the underlying value on a single line.
+    pub fn to_compact_string(&self) -> String {
+        self.inspect().to_compact_string()
+    }
+
     /// Returns the length of the `Vec<u8>` yielded after calling `self.to_bytes()`.
     ///
     /// Note, this method doesn't actually serialize `self`, and hence is relatively cheap.
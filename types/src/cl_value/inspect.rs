@@ -0,0 +1,570 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Display, Formatter};
+
+use crate::{
+    bytesrepr::{
+        Bytes, FromBytes, OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG,
+    },
+    cl_type::CL_TYPE_RECURSION_DEPTH,
+    AsymmetricType, CLType, CLValue, Key, PublicKey, URef, U128, U256, U512,
+};
+
+/// A structured, human-readable rendering of a [`CLValue`], as returned by [`CLValue::inspect`].
+///
+/// Rendering never fails: bytes which can't be parsed as the expected type are kept as
+/// [`CLValueNode::Invalid`].  The `Display` implementation renders the value as an indented tree
+/// over several lines, while [`CLValueTree::to_compact_string`] renders it on a single line.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CLValueTree {
+    cl_type: String,
+    node: CLValueNode,
+}
+
+/// The contents of a [`CLValueTree`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CLValueNode {
+    /// A primitive value, byte array, key or public key, rendered in full.
+    Leaf(String),
+    /// An `Option`.
+    Option(Option<Box<CLValueTree>>),
+    /// A `Result`.
+    Result(Result<Box<CLValueTree>, Box<CLValueTree>>),
+    /// A list.
+    List(Vec<CLValueTree>),
+    /// The entries of a map, in order.
+    Map(Vec<(CLValueTree, CLValueTree)>),
+    /// A tuple.
+    Tuple(Vec<CLValueTree>),
+    /// An opaque blob of bytes, i.e. a value of type `Any` or `List<U8>`.
+    Blob {
+        /// The raw bytes.
+        bytes: Vec<u8>,
+        /// The bytes decoded as a serialized `CLValue` or string, if they parse as either.
+        decoded: Option<Box<CLValueTree>>,
+    },
+    /// Bytes which could not be parsed as the expected type.
+    Invalid(Vec<u8>),
+}
+
+impl CLValueTree {
+    pub(super) fn new(cl_value: &CLValue) -> Self {
+        match parse(0, cl_value.cl_type(), cl_value.inner_bytes()) {
+            Ok((tree, remainder)) if remainder.is_empty() => tree,
+            Ok(_) => CLValueTree::invalid(cl_value.cl_type(), cl_value.inner_bytes()),
+            Err(tree) => tree,
+        }
+    }
+
+    fn from_node(cl_type: &CLType, node: CLValueNode) -> Self {
+        CLValueTree {
+            cl_type: type_name(cl_type),
+            node,
+        }
+    }
+
+    fn invalid(cl_type: &CLType, bytes: &[u8]) -> Self {
+        CLValueTree::from_node(cl_type, CLValueNode::Invalid(bytes.to_vec()))
+    }
+
+    /// Returns the rendered type of the value, e.g. `Map<String, List<U512>>`.
+    pub fn cl_type(&self) -> &str {
+        &self.cl_type
+    }
+
+    /// Returns the contents of the value.
+    pub fn node(&self) -> &CLValueNode {
+        &self.node
+    }
+
+    /// Returns `true` if all the bytes of the value could be parsed as its type.
+    pub fn is_valid(&self) -> bool {
+        match &self.node {
+            CLValueNode::Leaf(_) | CLValueNode::Option(None) | CLValueNode::Blob { .. } => true,
+            CLValueNode::Option(Some(inner))
+            | CLValueNode::Result(Ok(inner))
+            | CLValueNode::Result(Err(inner)) => inner.is_valid(),
+            CLValueNode::List(items) | CLValueNode::Tuple(items) => {
+                items.iter().all(CLValueTree::is_valid)
+            }
+            CLValueNode::Map(entries) => entries
+                .iter()
+                .all(|(key, value)| key.is_valid() && value.is_valid()),
+            CLValueNode::Invalid(_) => false,
+        }
+    }
+
+    /// Returns the value rendered on a single line, e.g. `{"a": [1, 2], "b": []}`.
+    pub fn to_compact_string(&self) -> String {
+        Compact(self).to_string()
+    }
+
+    fn write_tree(
+        &self,
+        formatter: &mut Formatter,
+        label: Option<&str>,
+        indent: usize,
+    ) -> fmt::Result {
+        write!(formatter, "{:indent$}", "", indent = indent)?;
+        if let Some(label) = label {
+            write!(formatter, "{}: ", label)?;
+        }
+        write!(formatter, "{}", self.cl_type)?;
+
+        let (summary, children): (Option<String>, Vec<(String, &CLValueTree)>) = match &self.node {
+            CLValueNode::Leaf(value) => (Some(value.clone()), vec![]),
+            CLValueNode::Option(None) => (Some("None".to_string()), vec![]),
+            CLValueNode::Option(Some(inner)) => (None, vec![("Some".to_string(), &**inner)]),
+            CLValueNode::Result(Ok(inner)) => (None, vec![("Ok".to_string(), &**inner)]),
+            CLValueNode::Result(Err(inner)) => (None, vec![("Err".to_string(), &**inner)]),
+            CLValueNode::List(items) if items.is_empty() => (Some("[]".to_string()), vec![]),
+            CLValueNode::List(items) => (
+                None,
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (format!("[{}]", index), item))
+                    .collect(),
+            ),
+            CLValueNode::Map(entries) if entries.is_empty() => (Some("{}".to_string()), vec![]),
+            CLValueNode::Map(entries) => (
+                None,
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.to_compact_string(), value))
+                    .collect(),
+            ),
+            CLValueNode::Tuple(items) => (
+                None,
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (index.to_string(), item))
+                    .collect(),
+            ),
+            CLValueNode::Blob { bytes, decoded } => (
+                Some(format!(
+                    "{} ({} bytes)",
+                    base16::encode_lower(bytes),
+                    bytes.len()
+                )),
+                decoded
+                    .iter()
+                    .map(|decoded| ("decoded".to_string(), &**decoded))
+                    .collect(),
+            ),
+            CLValueNode::Invalid(bytes) => (
+                Some(format!("<invalid: {}>", base16::encode_lower(bytes))),
+                vec![],
+            ),
+        };
+
+        if let Some(summary) = summary {
+            write!(formatter, " = {}", summary)?;
+        }
+        for (label, child) in children {
+            writeln!(formatter)?;
+            child.write_tree(formatter, Some(&label), indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CLValueTree {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.write_tree(formatter, None, 0)
+    }
+}
+
+/// Renders a [`CLValueTree`] on a single line.
+struct Compact<'a>(&'a CLValueTree);
+
+impl<'a> Display for Compact<'a> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match &self.0.node {
+            CLValueNode::Leaf(value) => write!(formatter, "{}", value),
+            CLValueNode::Option(None) => write!(formatter, "None"),
+            CLValueNode::Option(Some(inner)) => write!(formatter, "Some({})", Compact(inner)),
+            CLValueNode::Result(Ok(inner)) => write!(formatter, "Ok({})", Compact(inner)),
+            CLValueNode::Result(Err(inner)) => write!(formatter, "Err({})", Compact(inner)),
+            CLValueNode::List(items) => {
+                write!(formatter, "[")?;
+                write_separated(formatter, items.iter().map(Compact))?;
+                write!(formatter, "]")
+            }
+            CLValueNode::Map(entries) => {
+                write!(formatter, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ", ")?;
+                    }
+                    write!(formatter, "{}: {}", Compact(key), Compact(value))?;
+                }
+                write!(formatter, "}}")
+            }
+            CLValueNode::Tuple(items) => {
+                write!(formatter, "(")?;
+                write_separated(formatter, items.iter().map(Compact))?;
+                if items.len() == 1 {
+                    write!(formatter, ",")?;
+                }
+                write!(formatter, ")")
+            }
+            CLValueNode::Blob {
+                decoded: Some(decoded),
+                ..
+            } => write!(formatter, "{}", Compact(decoded)),
+            CLValueNode::Blob { bytes, .. } => write!(formatter, "{}", base16::encode_lower(bytes)),
+            CLValueNode::Invalid(bytes) => {
+                write!(formatter, "<invalid: {}>", base16::encode_lower(bytes))
+            }
+        }
+    }
+}
+
+fn write_separated<T: Display>(
+    formatter: &mut Formatter,
+    items: impl Iterator<Item = T>,
+) -> fmt::Result {
+    for (index, item) in items.enumerate() {
+        if index > 0 {
+            write!(formatter, ", ")?;
+        }
+        write!(formatter, "{}", item)?;
+    }
+    Ok(())
+}
+
+/// The outcome of parsing a single value from the front of a byte slice.
+///
+/// On failure, the partially-parsed value is returned, holding the unparsable bytes as
+/// [`CLValueNode::Invalid`], and all remaining bytes are considered consumed.
+type Parsed<'a> = Result<(CLValueTree, &'a [u8]), CLValueTree>;
+
+fn parse<'a>(depth: u8, cl_type: &CLType, bytes: &'a [u8]) -> Parsed<'a> {
+    if depth >= CL_TYPE_RECURSION_DEPTH {
+        return Err(CLValueTree::invalid(cl_type, bytes));
+    }
+    let depth = depth + 1;
+
+    match cl_type {
+        CLType::Bool => parse_leaf(cl_type, bytes, |value: &bool| value.to_string()),
+        CLType::I32 => parse_leaf(cl_type, bytes, |value: &i32| value.to_string()),
+        CLType::I64 => parse_leaf(cl_type, bytes, |value: &i64| value.to_string()),
+        CLType::U8 => parse_leaf(cl_type, bytes, |value: &u8| value.to_string()),
+        CLType::U32 => parse_leaf(cl_type, bytes, |value: &u32| value.to_string()),
+        CLType::U64 => parse_leaf(cl_type, bytes, |value: &u64| value.to_string()),
+        CLType::U128 => parse_leaf(cl_type, bytes, |value: &U128| value.to_string()),
+        CLType::U256 => parse_leaf(cl_type, bytes, |value: &U256| value.to_string()),
+        CLType::U512 => parse_leaf(cl_type, bytes, |value: &U512| value.to_string()),
+        CLType::Unit => parse_leaf(cl_type, bytes, |_: &()| "()".to_string()),
+        CLType::String => parse_leaf(cl_type, bytes, |value: &String| format!("{:?}", value)),
+        CLType::Key => parse_leaf(cl_type, bytes, |key: &Key| key.to_formatted_string()),
+        CLType::URef => parse_leaf(cl_type, bytes, |uref: &URef| uref.to_formatted_string()),
+        CLType::PublicKey => {
+            parse_leaf(cl_type, bytes, |public_key: &PublicKey| public_key.to_hex())
+        }
+        CLType::Option(inner_cl_type) => match u8::from_bytes(bytes) {
+            Ok((OPTION_NONE_TAG, remainder)) => Ok((
+                CLValueTree::from_node(cl_type, CLValueNode::Option(None)),
+                remainder,
+            )),
+            Ok((OPTION_SOME_TAG, remainder)) => {
+                wrap(cl_type, parse(depth, inner_cl_type, remainder), |inner| {
+                    CLValueNode::Option(Some(Box::new(inner)))
+                })
+            }
+            _ => Err(CLValueTree::invalid(cl_type, bytes)),
+        },
+        CLType::List(inner_cl_type) if **inner_cl_type == CLType::U8 => {
+            match Bytes::from_bytes(bytes) {
+                Ok((blob_bytes, remainder)) => {
+                    Ok((blob(depth, cl_type, blob_bytes.into()), remainder))
+                }
+                Err(_) => Err(CLValueTree::invalid(cl_type, bytes)),
+            }
+        }
+        CLType::List(inner_cl_type) => match u32::from_bytes(bytes) {
+            Ok((count, remainder)) => {
+                let item_cl_types = (0..count).map(|_| &**inner_cl_type);
+                match parse_sequence(depth, item_cl_types, remainder) {
+                    Ok((items, remainder)) => Ok((
+                        CLValueTree::from_node(cl_type, CLValueNode::List(items)),
+                        remainder,
+                    )),
+                    Err(items) => Err(CLValueTree::from_node(cl_type, CLValueNode::List(items))),
+                }
+            }
+            Err(_) => Err(CLValueTree::invalid(cl_type, bytes)),
+        },
+        CLType::ByteArray(length) => {
+            if bytes.len() < *length as usize {
+                return Err(CLValueTree::invalid(cl_type, bytes));
+            }
+            let (array, remainder) = bytes.split_at(*length as usize);
+            Ok((
+                CLValueTree::from_node(cl_type, CLValueNode::Leaf(base16::encode_lower(array))),
+                remainder,
+            ))
+        }
+        CLType::Result { ok, err } => match u8::from_bytes(bytes) {
+            Ok((RESULT_OK_TAG, remainder)) => wrap(cl_type, parse(depth, ok, remainder), |inner| {
+                CLValueNode::Result(Ok(Box::new(inner)))
+            }),
+            Ok((RESULT_ERR_TAG, remainder)) => {
+                wrap(cl_type, parse(depth, err, remainder), |inner| {
+                    CLValueNode::Result(Err(Box::new(inner)))
+                })
+            }
+            _ => Err(CLValueTree::invalid(cl_type, bytes)),
+        },
+        CLType::Map { key, value } => {
+            let (count, mut stream) = match u32::from_bytes(bytes) {
+                Ok(parsed) => parsed,
+                Err(_) => return Err(CLValueTree::invalid(cl_type, bytes)),
+            };
+            let mut entries = Vec::new();
+            for _ in 0..count {
+                let (entry_key, remainder) = match parse(depth, key, stream) {
+                    Ok(parsed) => parsed,
+                    Err(entry_key) => {
+                        entries.push((entry_key, CLValueTree::invalid(value, &[])));
+                        return Err(CLValueTree::from_node(cl_type, CLValueNode::Map(entries)));
+                    }
+                };
+                match parse(depth, value, remainder) {
+                    Ok((entry_value, remainder)) => {
+                        entries.push((entry_key, entry_value));
+                        stream = remainder;
+                    }
+                    Err(entry_value) => {
+                        entries.push((entry_key, entry_value));
+                        return Err(CLValueTree::from_node(cl_type, CLValueNode::Map(entries)));
+                    }
+                }
+            }
+            Ok((
+                CLValueTree::from_node(cl_type, CLValueNode::Map(entries)),
+                stream,
+            ))
+        }
+        CLType::Tuple1(item_cl_types) => parse_tuple(depth, cl_type, item_cl_types, bytes),
+        CLType::Tuple2(item_cl_types) => parse_tuple(depth, cl_type, item_cl_types, bytes),
+        CLType::Tuple3(item_cl_types) => parse_tuple(depth, cl_type, item_cl_types, bytes),
+        // A value of type `Any` spans all the remaining bytes.
+        CLType::Any => Ok((blob(depth, cl_type, bytes.to_vec()), &[])),
+    }
+}
+
+fn parse_leaf<'a, T: FromBytes, F: Fn(&T) -> String>(
+    cl_type: &CLType,
+    bytes: &'a [u8],
+    render: F,
+) -> Parsed<'a> {
+    match T::from_bytes(bytes) {
+        Ok((value, remainder)) => Ok((
+            CLValueTree::from_node(cl_type, CLValueNode::Leaf(render(&value))),
+            remainder,
+        )),
+        Err(_) => Err(CLValueTree::invalid(cl_type, bytes)),
+    }
+}
+
+/// Wraps the outcome of parsing the single value nested in a value of type `cl_type`.
+fn wrap<'a, F: FnOnce(CLValueTree) -> CLValueNode>(
+    cl_type: &CLType,
+    parsed: Parsed<'a>,
+    node: F,
+) -> Parsed<'a> {
+    match parsed {
+        Ok((inner, remainder)) => Ok((CLValueTree::from_node(cl_type, node(inner)), remainder)),
+        Err(inner) => Err(CLValueTree::from_node(cl_type, node(inner))),
+    }
+}
+
+/// Parses consecutive values of the given types, stopping at the first one which can't be parsed.
+fn parse_sequence<'a, 'b>(
+    depth: u8,
+    cl_types: impl Iterator<Item = &'b CLType>,
+    mut bytes: &'a [u8],
+) -> Result<(Vec<CLValueTree>, &'a [u8]), Vec<CLValueTree>> {
+    let mut items = Vec::new();
+    for cl_type in cl_types {
+        match parse(depth, cl_type, bytes) {
+            Ok((item, remainder)) => {
+                items.push(item);
+                bytes = remainder;
+            }
+            Err(item) => {
+                items.push(item);
+                return Err(items);
+            }
+        }
+    }
+    Ok((items, bytes))
+}
+
+fn parse_tuple<'a>(
+    depth: u8,
+    cl_type: &CLType,
+    item_cl_types: &[Box<CLType>],
+    bytes: &'a [u8],
+) -> Parsed<'a> {
+    match parse_sequence(depth, item_cl_types.iter().map(|item| &**item), bytes) {
+        Ok((items, remainder)) => Ok((
+            CLValueTree::from_node(cl_type, CLValueNode::Tuple(items)),
+            remainder,
+        )),
+        Err(items) => Err(CLValueTree::from_node(cl_type, CLValueNode::Tuple(items))),
+    }
+}
+
+/// Renders `bytes` as a blob, decoding them on a best-effort basis.
+fn blob(depth: u8, cl_type: &CLType, bytes: Vec<u8>) -> CLValueTree {
+    let decoded = decode_blob(depth, &bytes).map(Box::new);
+    CLValueTree::from_node(cl_type, CLValueNode::Blob { bytes, decoded })
+}
+
+/// Decodes `bytes` as a serialized `CLValue`, or failing that as a serialized string.
+///
+/// Returns `None` unless the bytes are entirely consumed by a valid value.
+fn decode_blob(depth: u8, bytes: &[u8]) -> Option<CLValueTree> {
+    if let Ok((cl_value, remainder)) = CLValue::from_bytes(bytes) {
+        if remainder.is_empty() {
+            if let Ok((tree, remainder)) = parse(depth, cl_value.cl_type(), cl_value.inner_bytes())
+            {
+                if remainder.is_empty() && tree.is_valid() {
+                    return Some(tree);
+                }
+            }
+        }
+    }
+    match String::from_bytes(bytes) {
+        Ok((string, remainder)) if remainder.is_empty() => Some(CLValueTree::from_node(
+            &CLType::String,
+            CLValueNode::Leaf(format!("{:?}", string)),
+        )),
+        _ => None,
+    }
+}
+
+/// Returns the name of `cl_type` as used in Rust-like notation, e.g. `Option<List<U8>>`.
+fn type_name(cl_type: &CLType) -> String {
+    match cl_type {
+        CLType::Option(inner) => format!("Option<{}>", type_name(inner)),
+        CLType::List(inner) => format!("List<{}>", type_name(inner)),
+        CLType::ByteArray(length) => format!("ByteArray<{}>", length),
+        CLType::Result { ok, err } => format!("Result<{}, {}>", type_name(ok), type_name(err)),
+        CLType::Map { key, value } => format!("Map<{}, {}>", type_name(key), type_name(value)),
+        CLType::Tuple1([t1]) => format!("({},)", type_name(t1)),
+        CLType::Tuple2([t1, t2]) => format!("({}, {})", type_name(t1), type_name(t2)),
+        CLType::Tuple3([t1, t2, t3]) => {
+            format!("({}, {}, {})", type_name(t1), type_name(t2), type_name(t3))
+        }
+        primitive => format!("{:?}", primitive),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use proptest::{prop_assert, proptest};
+
+    use super::*;
+    use crate::{bytesrepr::ToBytes, gens, AccessRights};
+
+    #[test]
+    fn should_render_nested_values_compactly() {
+        let mut map = BTreeMap::new();
+        map.insert(String::from("a"), vec![1_u64, 2]);
+        map.insert(String::from("b"), vec![]);
+        let cl_value = CLValue::from_t(map).unwrap();
+        assert_eq!(cl_value.to_compact_string(), r#"{"a": [1, 2], "b": []}"#);
+
+        let cl_value = CLValue::from_t((
+            Some(U512::from(7)),
+            Result::<(), String>::Err(String::from("oops")),
+            URef::new([0; 32], AccessRights::READ),
+        ))
+        .unwrap();
+        assert_eq!(
+            cl_value.to_compact_string(),
+            format!(
+                r#"(Some(7), Err("oops"), {})"#,
+                URef::new([0; 32], AccessRights::READ).to_formatted_string()
+            )
+        );
+
+        let cl_value = CLValue::from_t((Option::<bool>::None,)).unwrap();
+        assert_eq!(cl_value.to_compact_string(), "(None,)");
+    }
+
+    #[test]
+    fn should_render_tree() {
+        let cl_value = CLValue::from_t((vec![true], Some(String::from("x")))).unwrap();
+        let expected = "\
+(List<Bool>, Option<String>)
+  0: List<Bool>
+    [0]: Bool = true
+  1: Option<String>
+    Some: String = \"x\"";
+        assert_eq!(cl_value.inspect().to_string(), expected);
+    }
+
+    #[test]
+    fn should_decode_nested_blobs() {
+        // A `CLValue` serialized into a value of type `Any`.
+        let nested = CLValue::from_t(vec![1_i32, 2]).unwrap();
+        let cl_value = CLValue::from_components(CLType::Any, nested.to_bytes().unwrap());
+        let tree = cl_value.inspect();
+        match tree.node() {
+            CLValueNode::Blob {
+                decoded: Some(decoded),
+                ..
+            } => assert_eq!(decoded.cl_type(), "List<I32>"),
+            node => panic!("unexpected node {:?}", node),
+        }
+        assert_eq!(tree.to_compact_string(), "[1, 2]");
+
+        // A string serialized into bytes.
+        let bytes = Bytes::from(String::from("hello").to_bytes().unwrap());
+        let cl_value = CLValue::from_t(bytes).unwrap();
+        assert_eq!(cl_value.inspect().cl_type(), "List<U8>");
+        assert_eq!(cl_value.to_compact_string(), r#""hello""#);
+
+        // Opaque bytes are left undecoded.
+        let cl_value = CLValue::from_t(Bytes::from(vec![0xff_u8, 0xfe])).unwrap();
+        assert_eq!(cl_value.to_compact_string(), "fffe");
+        assert!(cl_value.inspect().to_string().ends_with("= fffe (2 bytes)"));
+    }
+
+    #[test]
+    fn should_keep_partially_parsed_values() {
+        // A list claiming three items, but only holding one.
+        let mut bytes = 3_u32.to_bytes().unwrap();
+        bytes.extend(5_u64.to_bytes().unwrap());
+        bytes.push(1);
+        let cl_value = CLValue::from_components(CLType::List(Box::new(CLType::U64)), bytes);
+
+        let tree = cl_value.inspect();
+        assert!(!tree.is_valid());
+        assert_eq!(tree.to_compact_string(), "[5, <invalid: 01>]");
+
+        // Trailing bytes invalidate the whole value.
+        let cl_value = CLValue::from_components(CLType::Bool, vec![1, 0]);
+        assert_eq!(cl_value.to_compact_string(), "<invalid: 0100>");
+    }
+
+    proptest! {
+        #[test]
+        fn generated_cl_values_should_be_valid(cl_value in gens::any_cl_value_arb()) {
+            prop_assert!(cl_value.inspect().is_valid(), "{:?}", cl_value);
+        }
+    }
+}
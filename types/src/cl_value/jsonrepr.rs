@@ -121,8 +121,10 @@ fn simple_type_to_json<T: FromBytes + Serialize>(bytes: &[u8]) -> Option<(Value,
 
 #[cfg(test)]
 mod tests {
+    use proptest::{prop_assert, proptest};
+
     use super::*;
-    use crate::{bytesrepr::ToBytes, AsymmetricType, CLTyped, SecretKey};
+    use crate::{bytesrepr::ToBytes, gens, AsymmetricType, CLTyped, SecretKey};
     use alloc::collections::BTreeMap;
 
     fn test_value<T: ToBytes + Serialize + Clone + CLTyped>(value: T) {
@@ -269,4 +271,14 @@ mod tests {
             assert!(cl_value_to_json(&cl_value).is_none());
         }
     }
+
+    proptest! {
+        #[test]
+        fn generated_cl_values_should_be_parseable(cl_value in gens::any_cl_value_arb()) {
+            // The bytes of a `CLValue` of type `Any` are opaque.
+            if *cl_value.cl_type() != CLType::Any {
+                prop_assert!(cl_value_to_json(&cl_value).is_some(), "{:?}", cl_value);
+            }
+        }
+    }
 }
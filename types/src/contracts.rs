@@ -106,6 +106,18 @@ pub enum Error {
     /// assert_eq!(9, Error::URefAlreadyExists as u8);
     /// ```
     URefAlreadyExists = 9,
+    /// Attempted to remove a contract which is not disabled.
+    /// ```
+    /// # use casper_types::contracts::Error;
+    /// assert_eq!(10, Error::ContractNotDisabled as u8);
+    /// ```
+    ContractNotDisabled = 10,
+    /// Attempted to remove a contract which is currently being executed.
+    /// ```
+    /// # use casper_types::contracts::Error;
+    /// assert_eq!(11, Error::ContractInUse as u8);
+    /// ```
+    ContractInUse = 11,
 }
 
 impl TryFrom<u8> for Error {
@@ -122,6 +134,8 @@ impl TryFrom<u8> for Error {
             v if v == Self::UnableToRemoveURef as u8 => Self::UnableToRemoveURef,
             v if v == Self::GroupInUse as u8 => Self::GroupInUse,
             v if v == Self::URefAlreadyExists as u8 => Self::URefAlreadyExists,
+            v if v == Self::ContractNotDisabled as u8 => Self::ContractNotDisabled,
+            v if v == Self::ContractInUse as u8 => Self::ContractInUse,
             _ => return Err(()),
         };
         Ok(error)
@@ -1254,6 +1268,8 @@ pub enum EntryPointType {
     Session = 0,
     /// Runs within contract's context
     Contract = 1,
+    /// Runs within contract's context, without modifying global state
+    View = 2,
 }
 
 impl ToBytes for EntryPointType {
@@ -1277,6 +1293,7 @@ impl FromBytes for EntryPointType {
         match value {
             0 => Ok((EntryPointType::Session, bytes)),
             1 => Ok((EntryPointType::Contract, bytes)),
+            2 => Ok((EntryPointType::View, bytes)),
             _ => Err(bytesrepr::Error::Formatting),
         }
     }
@@ -94,6 +94,7 @@ enum TransformTag {
     AddUInt512 = 15,
     AddKeys = 16,
     Failure = 17,
+    Prune = 18,
 }
 
 impl TryFrom<u8> for TransformTag {
@@ -546,6 +547,8 @@ pub enum Transform {
     AddKeys(Vec<NamedKey>),
     /// A failed transformation, containing an error message.
     Failure(String),
+    /// Removes the value from global state.
+    Prune,
 }
 
 impl Transform {
@@ -569,6 +572,7 @@ impl Transform {
             Transform::AddUInt512(_) => TransformTag::AddUInt512,
             Transform::AddKeys(_) => TransformTag::AddKeys,
             Transform::Failure(_) => TransformTag::Failure,
+            Transform::Prune => TransformTag::Prune,
         }
     }
 }
@@ -625,6 +629,7 @@ impl ToBytes for Transform {
             Transform::Failure(value) => {
                 buffer.extend(value.to_bytes()?);
             }
+            Transform::Prune => {}
         }
         Ok(buffer)
     }
@@ -646,7 +651,8 @@ impl ToBytes for Transform {
             Transform::Identity
             | Transform::WriteContractWasm
             | Transform::WriteContract
-            | Transform::WriteContractPackage => 0,
+            | Transform::WriteContractPackage
+            | Transform::Prune => 0,
             Transform::WriteBid(value) => value.serialized_length(),
             Transform::WriteWithdraw(value) => value.serialized_length(),
         };
@@ -719,6 +725,7 @@ impl FromBytes for Transform {
                     <Vec<UnbondingPurse> as FromBytes>::from_bytes(remainder)?;
                 Ok((Transform::WriteWithdraw(unbonding_purses), remainder))
             }
+            TransformTag::Prune => Ok((Transform::Prune, remainder)),
         }
     }
 }
@@ -726,7 +733,7 @@ impl FromBytes for Transform {
 impl Distribution<Transform> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Transform {
         // TODO - include WriteDeployInfo and WriteTransfer as options
-        match rng.gen_range(0..13) {
+        match rng.gen_range(0..14) {
             0 => Transform::Identity,
             1 => Transform::WriteCLValue(CLValue::from_t(true).unwrap()),
             2 => Transform::WriteAccount(AccountHash::new(rng.gen())),
@@ -749,6 +756,7 @@ impl Distribution<Transform> for Standard {
                 Transform::AddKeys(named_keys)
             }
             12 => Transform::Failure(rng.gen::<u64>().to_string()),
+            13 => Transform::Prune,
             _ => unreachable!(),
         }
     }
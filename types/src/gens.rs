@@ -2,7 +2,8 @@
 //! [`Proptest`](https://crates.io/crates/proptest).
 #![allow(missing_docs)]
 
-use alloc::{boxed::Box, string::String, vec};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::iter;
 
 use proptest::{
     array, bits, bool,
@@ -14,10 +15,12 @@ use proptest::{
 
 use crate::{
     account::{gens::account_arb, AccountHash, Weight},
+    bytesrepr::{Bytes, ToBytes, OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG},
+    cl_type::CL_TYPE_RECURSION_DEPTH,
     contracts::{
         ContractPackageStatus, ContractVersions, DisabledVersions, Groups, NamedKeys, Parameters,
     },
-    crypto::gens::public_key_arb_no_system,
+    crypto::gens::{public_key_arb, public_key_arb_no_system},
     system::auction::{
         gens::era_info_arb, Bid, DelegationRate, Delegator, UnbondingPurse, WithdrawPurse,
         DELEGATION_RATE_DENOMINATOR,
@@ -283,6 +286,7 @@ pub fn entry_point_type_arb() -> impl Strategy<Value = EntryPointType> {
     prop_oneof![
         Just(EntryPointType::Session),
         Just(EntryPointType::Contract),
+        Just(EntryPointType::View),
     ]
 }
 
@@ -528,3 +532,305 @@ pub fn stored_value_arb() -> impl Strategy<Value = StoredValue> {
             StoredValue::Unbonding(_) => stored_value,
         })
 }
+
+/// The number of wrapping types which can be nested around a simple `CLType` such that it can
+/// still be deserialized.
+const MAX_CL_TYPE_NESTING: usize = CL_TYPE_RECURSION_DEPTH as usize - 1;
+
+/// The length of the collections generated by [`large_cl_value_arb`].
+pub const LARGE_COLLECTION_LEN: usize = u16::MAX as usize + 1;
+
+/// Generates every variant of `Key`.
+///
+/// Unlike [`key_arb`], this includes the variants which don't hold an address, so generated keys
+/// are frequently equal.
+pub fn any_key_arb() -> impl Strategy<Value = Key> {
+    prop_oneof![
+        key_arb(),
+        Just(Key::SystemContractRegistry),
+        account_hash_arb().prop_map(Key::Unbond),
+        Just(Key::ChainspecRegistry),
+        any::<u64>().prop_map(|block_height| Key::BlockEffectsRootHash { block_height }),
+        any::<u64>().prop_map(|block_height| Key::DeployApprovalsRootHash { block_height }),
+        (u8_slice_32(), u8_slice_32()).prop_map(|(contract_addr, entry_addr)| {
+            Key::ContractStorageIndex {
+                contract_addr,
+                entry_addr,
+            }
+        }),
+        u8_slice_32().prop_map(Key::NativeTokenBalance),
+    ]
+    .prop_map(|key| {
+        // The following match statement is here only to make sure we don't forget to update the
+        // generator when a new variant is added.
+        match key {
+            Key::Account(_)
+            | Key::Hash(_)
+            | Key::URef(_)
+            | Key::Transfer(_)
+            | Key::DeployInfo(_)
+            | Key::EraInfo(_)
+            | Key::Balance(_)
+            | Key::Bid(_)
+            | Key::Withdraw(_)
+            | Key::Dictionary(_)
+            | Key::SystemContractRegistry
+            | Key::Unbond(_)
+            | Key::ChainspecRegistry
+            | Key::BlockEffectsRootHash { .. }
+            | Key::DeployApprovalsRootHash { .. }
+            | Key::ContractStorageIndex { .. }
+            | Key::NativeTokenBalance(_) => key,
+        }
+    })
+}
+
+/// Generates every `CLType` which has no inner types.
+fn any_simple_cl_type_arb() -> impl Strategy<Value = CLType> {
+    prop_oneof![
+        cl_simple_type_arb(),
+        Just(CLType::PublicKey),
+        (0..64u32).prop_map(CLType::ByteArray),
+    ]
+}
+
+/// Generates a simple `CLType` wrapped in up to the maximum number of `Option`, `List` and
+/// `Tuple1` types which can be deserialized.
+pub fn deeply_nested_cl_type_arb() -> impl Strategy<Value = CLType> {
+    (
+        any_simple_cl_type_arb(),
+        collection::vec(0..3u8, 0..=MAX_CL_TYPE_NESTING),
+    )
+        .prop_map(|(simple_type, wrappers)| {
+            wrappers
+                .into_iter()
+                .fold(simple_type, |cl_type, wrapper| match wrapper {
+                    0 => CLType::Option(Box::new(cl_type)),
+                    1 => CLType::List(Box::new(cl_type)),
+                    _ => CLType::Tuple1([Box::new(cl_type)]),
+                })
+        })
+}
+
+/// Generates every variant of `CLType`, including deeply nested ones.
+pub fn any_cl_type_arb() -> impl Strategy<Value = CLType> {
+    let nested = any_simple_cl_type_arb().prop_recursive(4, 16, 8, |element| {
+        prop_oneof![
+            element.clone(),
+            element
+                .clone()
+                .prop_map(|cl_type| CLType::Option(Box::new(cl_type))),
+            element
+                .clone()
+                .prop_map(|cl_type| CLType::List(Box::new(cl_type))),
+            (element.clone(), element.clone()).prop_map(|(ok, err)| CLType::Result {
+                ok: Box::new(ok),
+                err: Box::new(err)
+            }),
+            (element.clone(), element.clone()).prop_map(|(key, value)| CLType::Map {
+                key: Box::new(key),
+                value: Box::new(value)
+            }),
+            element
+                .clone()
+                .prop_map(|cl_type| CLType::Tuple1([Box::new(cl_type)])),
+            (element.clone(), element.clone()).prop_map(|(cl_type1, cl_type2)| CLType::Tuple2([
+                Box::new(cl_type1),
+                Box::new(cl_type2)
+            ])),
+            (element.clone(), element.clone(), element).prop_map(
+                |(cl_type1, cl_type2, cl_type3)| CLType::Tuple3([
+                    Box::new(cl_type1),
+                    Box::new(cl_type2),
+                    Box::new(cl_type3)
+                ])
+            ),
+        ]
+    });
+    prop_oneof![
+        8 => nested,
+        1 => deeply_nested_cl_type_arb(),
+        1 => Just(CLType::Any),
+    ]
+}
+
+fn to_bytes_arb<T: ToBytes>(
+    strategy: impl Strategy<Value = T> + 'static,
+) -> BoxedStrategy<Vec<u8>> {
+    strategy
+        .prop_map(|value| value.into_bytes().expect("should serialize"))
+        .boxed()
+}
+
+/// Generates the serialized form of a value of the given `CLType`.
+///
+/// The number of elements of collections decreases with the nesting depth, so that deeply nested
+/// values stay small.
+fn cl_value_bytes_arb(cl_type: &CLType, depth: usize) -> BoxedStrategy<Vec<u8>> {
+    let max_len = if depth < 3 { 8 } else { 2 };
+    let depth = depth + 1;
+    match cl_type {
+        CLType::Bool => to_bytes_arb(any::<bool>()),
+        CLType::I32 => to_bytes_arb(any::<i32>()),
+        CLType::I64 => to_bytes_arb(any::<i64>()),
+        CLType::U8 => to_bytes_arb(any::<u8>()),
+        CLType::U32 => to_bytes_arb(any::<u32>()),
+        CLType::U64 => to_bytes_arb(any::<u64>()),
+        CLType::U128 => to_bytes_arb(u128_arb()),
+        CLType::U256 => to_bytes_arb(u256_arb()),
+        CLType::U512 => to_bytes_arb(u512_arb()),
+        CLType::Unit => Just(Vec::new()).boxed(),
+        CLType::String => to_bytes_arb(".*".prop_map(String::from)),
+        CLType::Key => to_bytes_arb(any_key_arb()),
+        CLType::URef => to_bytes_arb(uref_arb()),
+        CLType::PublicKey => to_bytes_arb(public_key_arb()),
+        CLType::Option(inner) => option::of(cl_value_bytes_arb(inner, depth))
+            .prop_map(|maybe_bytes| match maybe_bytes {
+                None => vec![OPTION_NONE_TAG],
+                Some(bytes) => iter::once(OPTION_SOME_TAG).chain(bytes).collect(),
+            })
+            .boxed(),
+        CLType::List(inner) => collection::vec(cl_value_bytes_arb(inner, depth), 0..=max_len)
+            .prop_map(|elements| {
+                let mut bytes = (elements.len() as u32)
+                    .into_bytes()
+                    .expect("should serialize");
+                elements
+                    .into_iter()
+                    .for_each(|element| bytes.extend(element));
+                bytes
+            })
+            .boxed(),
+        CLType::ByteArray(len) => collection::vec(any::<u8>(), *len as usize).boxed(),
+        CLType::Result { ok, err } => result::maybe_err(
+            cl_value_bytes_arb(ok, depth),
+            cl_value_bytes_arb(err, depth),
+        )
+        .prop_map(|result| match result {
+            Ok(bytes) => iter::once(RESULT_OK_TAG).chain(bytes).collect(),
+            Err(bytes) => iter::once(RESULT_ERR_TAG).chain(bytes).collect(),
+        })
+        .boxed(),
+        CLType::Map { key, value } => collection::btree_map(
+            cl_value_bytes_arb(key, depth),
+            cl_value_bytes_arb(value, depth),
+            0..=max_len,
+        )
+        .prop_map(|entries| {
+            let mut bytes = (entries.len() as u32)
+                .into_bytes()
+                .expect("should serialize");
+            entries.into_iter().for_each(|(key, value)| {
+                bytes.extend(key);
+                bytes.extend(value);
+            });
+            bytes
+        })
+        .boxed(),
+        CLType::Tuple1([cl_type1]) => cl_value_bytes_arb(cl_type1, depth),
+        CLType::Tuple2([cl_type1, cl_type2]) => (
+            cl_value_bytes_arb(cl_type1, depth),
+            cl_value_bytes_arb(cl_type2, depth),
+        )
+            .prop_map(|(bytes1, bytes2)| bytes1.into_iter().chain(bytes2).collect())
+            .boxed(),
+        CLType::Tuple3([cl_type1, cl_type2, cl_type3]) => (
+            cl_value_bytes_arb(cl_type1, depth),
+            cl_value_bytes_arb(cl_type2, depth),
+            cl_value_bytes_arb(cl_type3, depth),
+        )
+            .prop_map(|(bytes1, bytes2, bytes3)| {
+                bytes1.into_iter().chain(bytes2).chain(bytes3).collect()
+            })
+            .boxed(),
+        // There is no canonical serialization of `Any`; any bytes are accepted.
+        CLType::Any => collection::vec(any::<u8>(), 0..64).boxed(),
+    }
+}
+
+/// Generates `CLValue`s of every `CLType`, including deeply nested ones, whose bytes are a valid
+/// serialization of a value of that type.
+pub fn any_cl_value_arb() -> impl Strategy<Value = CLValue> {
+    any_cl_type_arb().prop_flat_map(|cl_type| {
+        cl_value_bytes_arb(&cl_type, 0)
+            .prop_map(move |bytes| CLValue::from_components(cl_type.clone(), bytes))
+    })
+}
+
+/// Generates `CLValue`s holding collections of [`LARGE_COLLECTION_LEN`] elements.
+pub fn large_cl_value_arb() -> impl Strategy<Value = CLValue> {
+    prop_oneof![
+        collection::vec(any::<u8>(), LARGE_COLLECTION_LEN)
+            .prop_map(|x| CLValue::from_t(Bytes::from(x)).expect("should create CLValue")),
+        collection::vec(any::<u64>(), LARGE_COLLECTION_LEN)
+            .prop_map(|x| CLValue::from_t(x).expect("should create CLValue")),
+        collection::btree_map(any::<u32>(), any::<bool>(), LARGE_COLLECTION_LEN)
+            .prop_map(|x| CLValue::from_t(x).expect("should create CLValue")),
+        collection::vec(".{0,8}", LARGE_COLLECTION_LEN)
+            .prop_map(|x: Vec<String>| CLValue::from_t(x).expect("should create CLValue")),
+    ]
+}
+
+/// Generates every variant of `StoredValue`, including ones holding deeply nested `CLValue`s.
+pub fn any_stored_value_arb() -> impl Strategy<Value = StoredValue> {
+    prop_oneof![
+        stored_value_arb(),
+        any_cl_value_arb().prop_map(StoredValue::CLValue),
+    ]
+}
+
+/// Generates `StoredValue`s holding collections of [`LARGE_COLLECTION_LEN`] elements.
+pub fn large_stored_value_arb() -> impl Strategy<Value = StoredValue> {
+    prop_oneof![
+        large_cl_value_arb().prop_map(StoredValue::CLValue),
+        collection::vec(any::<u8>(), LARGE_COLLECTION_LEN)
+            .prop_map(|bytes| StoredValue::ContractWasm(ContractWasm::new(bytes))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prelude::ProptestConfig, proptest};
+
+    use super::*;
+    use crate::bytesrepr::{self, FromBytes};
+
+    proptest! {
+        #[test]
+        fn any_key_should_roundtrip(key in any_key_arb()) {
+            bytesrepr::test_serialization_roundtrip(&key);
+        }
+
+        #[test]
+        fn any_cl_type_should_roundtrip(cl_type in any_cl_type_arb()) {
+            // `CLType` doesn't implement `ToBytes`, so can't use `test_serialization_roundtrip`.
+            let mut serialized = vec![];
+            cl_type.append_bytes(&mut serialized).expect("should serialize");
+            assert_eq!(serialized.len(), cl_type.serialized_length());
+            let (deserialized, remainder) =
+                CLType::from_bytes(&serialized).expect("should deserialize");
+            assert!(remainder.is_empty());
+            assert_eq!(deserialized, cl_type);
+        }
+
+        #[test]
+        fn any_cl_value_should_roundtrip(cl_value in any_cl_value_arb()) {
+            bytesrepr::test_serialization_roundtrip(&cl_value);
+        }
+
+        #[test]
+        fn any_stored_value_should_roundtrip(stored_value in any_stored_value_arb()) {
+            bytesrepr::test_serialization_roundtrip(&stored_value);
+        }
+    }
+
+    proptest! {
+        // Generating large collections is slow, so fewer cases are run.
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn large_stored_value_should_roundtrip(stored_value in large_stored_value_arb()) {
+            bytesrepr::test_serialization_roundtrip(&stored_value);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! JSON Schemas of the types exposed by the node's JSON-RPC and REST APIs.
+//!
+//! Client SDKs can generate code from these schemas, and compare them between releases to detect
+//! breaking changes to the JSON representation of the types.
+
+use alloc::string::ToString;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{Metadata, RootSchema, SchemaObject},
+};
+
+use crate::{
+    account::AccountHash,
+    contracts::ContractPackageStatus,
+    system::auction::{
+        Bid, Delegator, EraInfo, SeigniorageAllocation, UnbondingPurse, WithdrawPurse,
+    },
+    CLType, CLValue, ContractHash, ContractPackageHash, ContractWasmHash, DeployHash, DeployInfo,
+    EntryPoint, EntryPointAccess, EntryPointType, EraId, ExecutionEffect, ExecutionResult, Group,
+    Key, NamedArg, NamedKey, OpKind, Operation, Parameter, ProtocolVersion, PublicKey, RuntimeArgs,
+    Signature, TimeDiff, Timestamp, Transfer, TransferAddr, TransferMemo, Transform,
+    TransformEntry, URef, U128, U256, U512,
+};
+
+/// The title of the schema returned by [`json_schema`].
+pub const JSON_SCHEMA_TITLE: &str = "casper-types";
+
+/// Returns a schema whose definitions describe the JSON representation of every type of this
+/// crate exposed by the node's APIs, along with the types they refer to.
+///
+/// The definitions are keyed by type name, and refer to one another via `#/definitions/<name>`.
+pub fn json_schema() -> RootSchema {
+    let mut gen = SchemaGenerator::default();
+
+    gen.subschema_for::<AccountHash>();
+    gen.subschema_for::<Bid>();
+    gen.subschema_for::<CLType>();
+    gen.subschema_for::<CLValue>();
+    gen.subschema_for::<ContractHash>();
+    gen.subschema_for::<ContractPackageHash>();
+    gen.subschema_for::<ContractPackageStatus>();
+    gen.subschema_for::<ContractWasmHash>();
+    gen.subschema_for::<Delegator>();
+    gen.subschema_for::<DeployHash>();
+    gen.subschema_for::<DeployInfo>();
+    gen.subschema_for::<EntryPoint>();
+    gen.subschema_for::<EntryPointAccess>();
+    gen.subschema_for::<EntryPointType>();
+    gen.subschema_for::<EraId>();
+    gen.subschema_for::<EraInfo>();
+    gen.subschema_for::<ExecutionEffect>();
+    gen.subschema_for::<ExecutionResult>();
+    gen.subschema_for::<Group>();
+    gen.subschema_for::<Key>();
+    gen.subschema_for::<NamedArg>();
+    gen.subschema_for::<NamedKey>();
+    gen.subschema_for::<OpKind>();
+    gen.subschema_for::<Operation>();
+    gen.subschema_for::<Parameter>();
+    gen.subschema_for::<ProtocolVersion>();
+    gen.subschema_for::<PublicKey>();
+    gen.subschema_for::<RuntimeArgs>();
+    gen.subschema_for::<SeigniorageAllocation>();
+    gen.subschema_for::<Signature>();
+    gen.subschema_for::<TimeDiff>();
+    gen.subschema_for::<Timestamp>();
+    gen.subschema_for::<Transfer>();
+    gen.subschema_for::<TransferAddr>();
+    gen.subschema_for::<TransferMemo>();
+    gen.subschema_for::<Transform>();
+    gen.subschema_for::<TransformEntry>();
+    gen.subschema_for::<U128>();
+    gen.subschema_for::<U256>();
+    gen.subschema_for::<U512>();
+    gen.subschema_for::<URef>();
+    gen.subschema_for::<UnbondingPurse>();
+    gen.subschema_for::<WithdrawPurse>();
+
+    let definitions = gen.take_definitions();
+    RootSchema {
+        meta_schema: gen.settings().meta_schema.clone(),
+        schema: SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some(JSON_SCHEMA_TITLE.to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        definitions,
+    }
+}
@@ -22,6 +22,8 @@ use rand::{
     distributions::{Distribution, Standard},
     Rng,
 };
+#[cfg(feature = "json-schema")]
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
@@ -47,6 +49,8 @@ const SYSTEM_CONTRACT_REGISTRY_PREFIX: &str = "system-contract-registry-";
 const CHAINSPEC_REGISTRY_PREFIX: &str = "chainspec-registry-";
 const BLOCK_EFFECTS_ROOT_HASH_PREFIX: &str = "block-effects-root-hash-";
 const DEPLOY_APPROVALS_ROOT_HASH_REGISTRY_PREFIX: &str = "deploy-approvals-root-hash-";
+const CONTRACT_STORAGE_INDEX_PREFIX: &str = "contract-storage-index-";
+const NATIVE_TOKEN_BALANCE_PREFIX: &str = "native-token-balance-";
 
 /// The number of bytes in a Blake2b hash
 pub const BLAKE2B_DIGEST_LENGTH: usize = 32;
@@ -82,6 +86,10 @@ const KEY_CHAINSPEC_REGISTRY_SERIALIZED_LENGTH: usize =
 const KEY_BLOCK_EFFECTS_ROOT_HASH_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + U64_SERIALIZED_LENGTH;
 const KEY_DEPLOY_APPROVALS_ROOT_HASH_SERIALIZED_LENGTH: usize =
     KEY_ID_SERIALIZED_LENGTH + U64_SERIALIZED_LENGTH;
+const KEY_CONTRACT_STORAGE_INDEX_SERIALIZED_LENGTH: usize =
+    KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH + KEY_HASH_LENGTH;
+const KEY_NATIVE_TOKEN_BALANCE_SERIALIZED_LENGTH: usize =
+    KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH;
 
 /// An alias for [`Key`]s hash variant.
 pub type HashAddr = [u8; KEY_HASH_LENGTH];
@@ -108,6 +116,8 @@ pub enum KeyTag {
     ChainspecRegistry = 12,
     BlockEffectsRootHash = 13,
     DeployApprovalsRootHash = 14,
+    ContractStorageIndex = 15,
+    NativeTokenBalance = 16,
 }
 
 /// The type under which data (e.g. [`CLValue`](crate::CLValue)s, smart contracts, user accounts)
@@ -155,6 +165,16 @@ pub enum Key {
         /// The height of the block whose deploy approvals are hashed here.
         block_height: u64,
     },
+    /// A `Key` under which a single key created by a contract is indexed, so that it can be removed
+    /// along with the contract.  All the entries of one contract share the same serialized prefix.
+    ContractStorageIndex {
+        /// The address of the contract which created the indexed key.
+        contract_addr: HashAddr,
+        /// The hash of the serialized indexed key.
+        entry_addr: HashAddr,
+    },
+    /// A `Key` under which we store the balance of a native token held by a purse.
+    NativeTokenBalance(HashAddr),
 }
 
 /// Errors produced when converting a `String` into a `Key`.
@@ -191,6 +211,10 @@ pub enum FromStrError {
     BlockEffectsRootHash(String),
     /// DeployApprovalsRootHash parse error.
     DeployApprovalsRootHash(String),
+    /// ContractStorageIndex parse error.
+    ContractStorageIndex(String),
+    /// NativeTokenBalance parse error.
+    NativeTokenBalance(String),
     /// Unknown prefix.
     UnknownPrefix,
 }
@@ -257,6 +281,12 @@ impl Display for FromStrError {
                     error
                 )
             }
+            FromStrError::ContractStorageIndex(error) => {
+                write!(f, "contract-storage-index-key from string error: {}", error)
+            }
+            FromStrError::NativeTokenBalance(error) => {
+                write!(f, "native-token-balance-key from string error: {}", error)
+            }
             FromStrError::UnknownPrefix => write!(f, "unknown prefix for key"),
         }
     }
@@ -282,6 +312,8 @@ impl Key {
             Key::ChainspecRegistry => String::from("Key::ChainspecRegistry"),
             Key::BlockEffectsRootHash { .. } => String::from("Key::BlockEffectsRootHash"),
             Key::DeployApprovalsRootHash { .. } => String::from("Key::DeployApprovalsRootHash"),
+            Key::ContractStorageIndex { .. } => String::from("Key::ContractStorageIndex"),
+            Key::NativeTokenBalance(_) => String::from("Key::NativeTokenBalance"),
         }
     }
 
@@ -360,6 +392,24 @@ impl Key {
                     DEPLOY_APPROVALS_ROOT_HASH_REGISTRY_PREFIX, block_height
                 )
             }
+            Key::ContractStorageIndex {
+                contract_addr,
+                entry_addr,
+            } => {
+                format!(
+                    "{}{}-{}",
+                    CONTRACT_STORAGE_INDEX_PREFIX,
+                    base16::encode_lower(&contract_addr),
+                    base16::encode_lower(&entry_addr)
+                )
+            }
+            Key::NativeTokenBalance(addr) => {
+                format!(
+                    "{}{}",
+                    NATIVE_TOKEN_BALANCE_PREFIX,
+                    base16::encode_lower(&addr)
+                )
+            }
         }
     }
 
@@ -483,6 +533,30 @@ impl Key {
             return Ok(Key::BlockEffectsRootHash { block_height });
         }
 
+        if let Some(hex) = input.strip_prefix(CONTRACT_STORAGE_INDEX_PREFIX) {
+            let (contract_hex, entry_hex) = hex.split_once('-').ok_or_else(|| {
+                FromStrError::ContractStorageIndex("missing entry address".to_string())
+            })?;
+            let parse_addr = |hex: &str| {
+                let addr = checksummed_hex::decode(hex)
+                    .map_err(|error| FromStrError::ContractStorageIndex(error.to_string()))?;
+                HashAddr::try_from(addr.as_ref())
+                    .map_err(|error| FromStrError::ContractStorageIndex(error.to_string()))
+            };
+            return Ok(Key::ContractStorageIndex {
+                contract_addr: parse_addr(contract_hex)?,
+                entry_addr: parse_addr(entry_hex)?,
+            });
+        }
+
+        if let Some(hex) = input.strip_prefix(NATIVE_TOKEN_BALANCE_PREFIX) {
+            let addr = checksummed_hex::decode(hex)
+                .map_err(|error| FromStrError::NativeTokenBalance(error.to_string()))?;
+            let hash_addr = HashAddr::try_from(addr.as_ref())
+                .map_err(|error| FromStrError::NativeTokenBalance(error.to_string()))?;
+            return Ok(Key::NativeTokenBalance(hash_addr));
+        }
+
         Err(FromStrError::UnknownPrefix)
     }
 
@@ -584,6 +658,30 @@ impl Key {
         }
         false
     }
+
+    /// Creates a new [`Key::ContractStorageIndex`] variant under which `created_key`, created by
+    /// the contract with the given `contract_hash`, is indexed.
+    pub fn contract_storage_index(contract_hash: ContractHash, created_key: &Key) -> Key {
+        // NOTE: Expect below is safe because the length passed is supported.
+        let mut hasher = VarBlake2b::new(BLAKE2B_DIGEST_LENGTH).expect("should create hasher");
+        // NOTE: Serializing a `Key` cannot fail.
+        hasher.update(created_key.to_bytes().unwrap_or_default());
+        let mut entry_addr = HashAddr::default();
+        hasher.finalize_variable(|hash| entry_addr.clone_from_slice(hash));
+        Key::ContractStorageIndex {
+            contract_addr: contract_hash.value(),
+            entry_addr,
+        }
+    }
+
+    /// Returns the serialized prefix shared by all the [`Key::ContractStorageIndex`] entries of
+    /// the contract with the given `contract_hash`.
+    pub fn contract_storage_index_prefix(contract_hash: ContractHash) -> Vec<u8> {
+        let mut prefix = Vec::with_capacity(KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH);
+        prefix.push(KeyTag::ContractStorageIndex as u8);
+        prefix.extend_from_slice(&contract_hash.value());
+        prefix
+    }
 }
 
 impl Display for Key {
@@ -624,6 +722,20 @@ impl Display for Key {
             Key::DeployApprovalsRootHash { block_height } => {
                 write!(f, "Key::DeployApprovalsRootHash({})", block_height)
             }
+            Key::ContractStorageIndex {
+                contract_addr,
+                entry_addr,
+            } => {
+                write!(
+                    f,
+                    "Key::ContractStorageIndex({}-{})",
+                    base16::encode_lower(contract_addr),
+                    base16::encode_lower(entry_addr)
+                )
+            }
+            Key::NativeTokenBalance(addr) => {
+                write!(f, "Key::NativeTokenBalance({})", base16::encode_lower(addr))
+            }
         }
     }
 }
@@ -652,6 +764,8 @@ impl Tagged<KeyTag> for Key {
             Key::ChainspecRegistry => KeyTag::ChainspecRegistry,
             Key::BlockEffectsRootHash { .. } => KeyTag::BlockEffectsRootHash,
             Key::DeployApprovalsRootHash { .. } => KeyTag::DeployApprovalsRootHash,
+            Key::ContractStorageIndex { .. } => KeyTag::ContractStorageIndex,
+            Key::NativeTokenBalance(_) => KeyTag::NativeTokenBalance,
         }
     }
 }
@@ -747,6 +861,16 @@ impl ToBytes for Key {
             Key::DeployApprovalsRootHash { block_height } => {
                 result.append(&mut block_height.to_bytes()?)
             }
+            Key::ContractStorageIndex {
+                contract_addr,
+                entry_addr,
+            } => {
+                result.append(&mut contract_addr.to_bytes()?);
+                result.append(&mut entry_addr.to_bytes()?);
+            }
+            Key::NativeTokenBalance(addr) => {
+                result.append(&mut addr.to_bytes()?);
+            }
         }
         Ok(result)
     }
@@ -770,6 +894,8 @@ impl ToBytes for Key {
             Key::ChainspecRegistry => KEY_CHAINSPEC_REGISTRY_SERIALIZED_LENGTH,
             Key::BlockEffectsRootHash { .. } => KEY_BLOCK_EFFECTS_ROOT_HASH_LENGTH,
             Key::DeployApprovalsRootHash { .. } => KEY_DEPLOY_APPROVALS_ROOT_HASH_SERIALIZED_LENGTH,
+            Key::ContractStorageIndex { .. } => KEY_CONTRACT_STORAGE_INDEX_SERIALIZED_LENGTH,
+            Key::NativeTokenBalance(_) => KEY_NATIVE_TOKEN_BALANCE_SERIALIZED_LENGTH,
         }
     }
 
@@ -791,6 +917,14 @@ impl ToBytes for Key {
             Key::ChainspecRegistry => CHAINSPEC_REGISTRY_KEY_BYTES.write_bytes(writer),
             Key::BlockEffectsRootHash { block_height } => block_height.write_bytes(writer),
             Key::DeployApprovalsRootHash { block_height } => block_height.write_bytes(writer),
+            Key::ContractStorageIndex {
+                contract_addr,
+                entry_addr,
+            } => {
+                contract_addr.write_bytes(writer)?;
+                entry_addr.write_bytes(writer)
+            }
+            Key::NativeTokenBalance(addr) => addr.write_bytes(writer),
         }
     }
 }
@@ -859,6 +993,21 @@ impl FromBytes for Key {
                 let (block_height, rem) = u64::from_bytes(remainder)?;
                 Ok((Key::DeployApprovalsRootHash { block_height }, rem))
             }
+            tag if tag == KeyTag::ContractStorageIndex as u8 => {
+                let (contract_addr, rem) = HashAddr::from_bytes(remainder)?;
+                let (entry_addr, rem) = HashAddr::from_bytes(rem)?;
+                Ok((
+                    Key::ContractStorageIndex {
+                        contract_addr,
+                        entry_addr,
+                    },
+                    rem,
+                ))
+            }
+            tag if tag == KeyTag::NativeTokenBalance as u8 => {
+                let (addr, rem) = HashAddr::from_bytes(remainder)?;
+                Ok((Key::NativeTokenBalance(addr), rem))
+            }
             _ => Err(Error::Formatting),
         }
     }
@@ -884,12 +1033,14 @@ fn please_add_to_distribution_impl(key: Key) {
         Key::ChainspecRegistry => unimplemented!(),
         Key::BlockEffectsRootHash { .. } => unimplemented!(),
         Key::DeployApprovalsRootHash { .. } => unimplemented!(),
+        Key::ContractStorageIndex { .. } => unimplemented!(),
+        Key::NativeTokenBalance(_) => unimplemented!(),
     }
 }
 
 impl Distribution<Key> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Key {
-        match rng.gen_range(0..=14) {
+        match rng.gen_range(0..=16) {
             0 => Key::Account(rng.gen()),
             1 => Key::Hash(rng.gen()),
             2 => Key::URef(rng.gen()),
@@ -909,6 +1060,11 @@ impl Distribution<Key> for Standard {
             14 => Key::DeployApprovalsRootHash {
                 block_height: rng.gen(),
             },
+            15 => Key::ContractStorageIndex {
+                contract_addr: rng.gen(),
+                entry_addr: rng.gen(),
+            },
+            16 => Key::NativeTokenBalance(rng.gen()),
             _ => unreachable!(),
         }
     }
@@ -934,6 +1090,8 @@ mod serde_helpers {
         ChainspecRegistry(String),
         BlockEffectsRootHash(String),
         DeployApprovalsRootHash(String),
+        ContractStorageIndex(String),
+        NativeTokenBalance(String),
     }
 
     impl From<&Key> for HumanReadable {
@@ -961,6 +1119,10 @@ mod serde_helpers {
                 Key::DeployApprovalsRootHash { .. } => {
                     HumanReadable::DeployApprovalsRootHash(formatted_string)
                 }
+                Key::ContractStorageIndex { .. } => {
+                    HumanReadable::ContractStorageIndex(formatted_string)
+                }
+                Key::NativeTokenBalance(_) => HumanReadable::NativeTokenBalance(formatted_string),
             }
         }
     }
@@ -984,7 +1146,9 @@ mod serde_helpers {
                 | HumanReadable::SystemContractRegistry(formatted_string)
                 | HumanReadable::ChainspecRegistry(formatted_string)
                 | HumanReadable::BlockEffectsRootHash(formatted_string)
-                | HumanReadable::DeployApprovalsRootHash(formatted_string) => {
+                | HumanReadable::DeployApprovalsRootHash(formatted_string)
+                | HumanReadable::ContractStorageIndex(formatted_string)
+                | HumanReadable::NativeTokenBalance(formatted_string) => {
                     Key::from_formatted_str(&formatted_string)
                 }
             }
@@ -1008,6 +1172,8 @@ mod serde_helpers {
         ChainspecRegistry,
         BlockEffectsRootHash { block_height: u64 },
         DeployApprovalsRootHash { block_height: u64 },
+        ContractStorageIndex(&'a HashAddr, &'a HashAddr),
+        NativeTokenBalance(&'a HashAddr),
     }
 
     impl<'a> From<&'a Key> for BinarySerHelper<'a> {
@@ -1036,6 +1202,11 @@ mod serde_helpers {
                         block_height: *block_height,
                     }
                 }
+                Key::ContractStorageIndex {
+                    contract_addr,
+                    entry_addr,
+                } => BinarySerHelper::ContractStorageIndex(contract_addr, entry_addr),
+                Key::NativeTokenBalance(addr) => BinarySerHelper::NativeTokenBalance(addr),
             }
         }
     }
@@ -1057,6 +1228,8 @@ mod serde_helpers {
         ChainspecRegistry,
         BlockEffectsRootHash { block_height: u64 },
         DeployApprovalsRootHash { block_height: u64 },
+        ContractStorageIndex(HashAddr, HashAddr),
+        NativeTokenBalance(HashAddr),
     }
 
     impl From<BinaryDeserHelper> for Key {
@@ -1081,11 +1254,36 @@ mod serde_helpers {
                 BinaryDeserHelper::DeployApprovalsRootHash { block_height } => {
                     Key::DeployApprovalsRootHash { block_height }
                 }
+                BinaryDeserHelper::ContractStorageIndex(contract_addr, entry_addr) => {
+                    Key::ContractStorageIndex {
+                        contract_addr,
+                        entry_addr,
+                    }
+                }
+                BinaryDeserHelper::NativeTokenBalance(addr) => Key::NativeTokenBalance(addr),
             }
         }
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl JsonSchema for Key {
+    fn schema_name() -> String {
+        String::from("Key")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let schema = gen.subschema_for::<String>();
+        let mut schema_object = schema.into_object();
+        schema_object.metadata().description = Some(
+            "The key as a formatted string, under which data (e.g. `CLValue`s, smart contracts, \
+            user accounts) are stored in global state."
+                .to_string(),
+        );
+        schema_object.into()
+    }
+}
+
 impl Serialize for Key {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         if serializer.is_human_readable() {
@@ -1142,7 +1340,12 @@ mod tests {
     const DEPLOY_APPROVALS_ROOT_HASH_KEY: Key = Key::DeployApprovalsRootHash {
         block_height: BLOCK_HEIGHT,
     };
-    const KEYS: [Key; 15] = [
+    const CONTRACT_STORAGE_INDEX_KEY: Key = Key::ContractStorageIndex {
+        contract_addr: [42; 32],
+        entry_addr: [42; 32],
+    };
+    const NATIVE_TOKEN_BALANCE_KEY: Key = Key::NativeTokenBalance([42; 32]);
+    const KEYS: [Key; 17] = [
         ACCOUNT_KEY,
         HASH_KEY,
         UREF_KEY,
@@ -1158,6 +1361,8 @@ mod tests {
         UNBOND_KEY,
         BLOCK_EFFECTS_ROOT_HASH_KEY,
         DEPLOY_APPROVALS_ROOT_HASH_KEY,
+        CONTRACT_STORAGE_INDEX_KEY,
+        NATIVE_TOKEN_BALANCE_KEY,
     ];
     const HEX_STRING: &str = "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a";
 
@@ -1271,6 +1476,14 @@ mod tests {
             format!("{}", DEPLOY_APPROVALS_ROOT_HASH_KEY),
             format!("Key::DeployApprovalsRootHash({})", BLOCK_HEIGHT,)
         );
+        assert_eq!(
+            format!("{}", CONTRACT_STORAGE_INDEX_KEY),
+            format!("Key::ContractStorageIndex({}-{})", HEX_STRING, HEX_STRING)
+        );
+        assert_eq!(
+            format!("{}", NATIVE_TOKEN_BALANCE_KEY),
+            format!("Key::NativeTokenBalance({})", HEX_STRING)
+        );
     }
 
     #[test]
@@ -1399,6 +1612,14 @@ mod tests {
             .unwrap_err()
             .to_string()
             .starts_with("block-effects-root-hash-key from string error: "));
+        assert!(Key::from_formatted_str(CONTRACT_STORAGE_INDEX_PREFIX)
+            .unwrap_err()
+            .to_string()
+            .starts_with("contract-storage-index-key from string error: "));
+        assert!(Key::from_formatted_str(NATIVE_TOKEN_BALANCE_PREFIX)
+            .unwrap_err()
+            .to_string()
+            .starts_with("native-token-balance-key from string error: "));
 
         let invalid_prefix = "a-0000000000000000000000000000000000000000000000000000000000000000";
         assert_eq!(
@@ -1454,6 +1675,14 @@ mod tests {
                 r#"{{"DeployApprovalsRootHash":"deploy-approvals-root-hash-{}"}}"#,
                 BLOCK_HEIGHT
             ),
+            format!(
+                r#"{{"ContractStorageIndex":"contract-storage-index-{}-{}"}}"#,
+                HEX_STRING, HEX_STRING
+            ),
+            format!(
+                r#"{{"NativeTokenBalance":"native-token-balance-{}"}}"#,
+                HEX_STRING
+            ),
         ];
 
         assert_eq!(
@@ -1509,5 +1738,26 @@ mod tests {
         round_trip(&Key::DeployApprovalsRootHash {
             block_height: BLOCK_HEIGHT,
         });
+        round_trip(&Key::ContractStorageIndex {
+            contract_addr: zeros,
+            entry_addr: zeros,
+        });
+        round_trip(&Key::NativeTokenBalance(zeros));
+    }
+
+    #[test]
+    fn contract_storage_index_entries_should_share_contract_prefix() {
+        let contract_hash = ContractHash::new([42; 32]);
+        let prefix = Key::contract_storage_index_prefix(contract_hash);
+
+        let first = Key::contract_storage_index(contract_hash, &HASH_KEY);
+        let second = Key::contract_storage_index(contract_hash, &UREF_KEY);
+        assert_ne!(first, second);
+        for entry in [first, second] {
+            assert!(entry.to_bytes().unwrap().starts_with(&prefix));
+        }
+
+        let other_contract = Key::contract_storage_index(ContractHash::new([43; 32]), &HASH_KEY);
+        assert!(!other_contract.to_bytes().unwrap().starts_with(&prefix));
     }
 }
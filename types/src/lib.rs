@@ -24,6 +24,7 @@ extern crate alloc;
 mod access_rights;
 pub mod account;
 pub mod api_error;
+mod block_context;
 mod block_time;
 pub mod bytesrepr;
 pub mod checksummed_hex;
@@ -41,6 +42,8 @@ mod gas;
 #[cfg(any(feature = "testing", test))]
 pub mod gens;
 mod json_pretty_printer;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
 mod key;
 mod motes;
 mod named_key;
@@ -64,9 +67,10 @@ pub use access_rights::{
 };
 #[doc(inline)]
 pub use api_error::ApiError;
+pub use block_context::{BlockContext, BLOCK_CONTEXT_SERIALIZED_LENGTH};
 pub use block_time::{BlockTime, BLOCKTIME_SERIALIZED_LENGTH};
 pub use cl_type::{named_key_type, CLType, CLTyped};
-pub use cl_value::{CLTypeMismatch, CLValue, CLValueError};
+pub use cl_value::{CLTypeMismatch, CLValue, CLValueError, CLValueNode, CLValueTree};
 pub use contract_wasm::{ContractWasm, ContractWasmHash};
 #[doc(inline)]
 pub use contracts::{
@@ -97,8 +101,8 @@ pub use stored_value::{StoredValue, TypeMismatch as StoredValueTypeMismatch};
 pub use tagged::Tagged;
 pub use timestamp::{TimeDiff, Timestamp};
 pub use transfer::{
-    DeployHash, FromStrError as TransferFromStrError, Transfer, TransferAddr, DEPLOY_HASH_LENGTH,
-    TRANSFER_ADDR_LENGTH,
+    DeployHash, FromStrError as TransferFromStrError, Transfer, TransferAddr, TransferMemo,
+    TransferMemoTooLong, DEPLOY_HASH_LENGTH, TRANSFER_ADDR_LENGTH, TRANSFER_MEMO_MAX_LENGTH,
 };
 pub use transfer_result::{TransferResult, TransferredTo};
 pub use uref::{
@@ -11,7 +11,7 @@ mod withdraw_purse;
 
 use alloc::{collections::BTreeMap, vec::Vec};
 
-pub use bid::{Bid, VESTING_SCHEDULE_LENGTH_MILLIS};
+pub use bid::{Bid, VestingSchedule, VESTING_SCHEDULE_LENGTH_MILLIS};
 pub use constants::*;
 pub use delegator::Delegator;
 pub use entry_points::auction_entry_points;
@@ -46,6 +46,10 @@ pub type SeigniorageRecipients = BTreeMap<PublicKey, SeigniorageRecipient>;
 /// Snapshot of `SeigniorageRecipients` for a given era.
 pub type SeigniorageRecipientsSnapshot = BTreeMap<EraId, SeigniorageRecipients>;
 
+/// Validators mapped to the public keys they sign consensus messages with, if different from their
+/// own.
+pub type SigningKeys = BTreeMap<PublicKey, PublicKey>;
+
 /// Validators and delegators mapped to their unbonding purses.
 pub type UnbondingPurses = BTreeMap<AccountHash, Vec<UnbondingPurse>>;
 
@@ -314,6 +314,18 @@ impl CLTyped for Bid {
     }
 }
 
+impl Bid {
+    /// Returns the non-zero unclaimed rewards of the delegators, which are serialized after the
+    /// rest of the bid.
+    fn delegator_unclaimed_rewards(&self) -> BTreeMap<PublicKey, U512> {
+        self.delegators
+            .iter()
+            .filter(|(_, delegator)| !delegator.unclaimed_rewards().is_zero())
+            .map(|(public_key, delegator)| (public_key.clone(), *delegator.unclaimed_rewards()))
+            .collect()
+    }
+}
+
 impl ToBytes for Bid {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut result = bytesrepr::allocate_buffer(self)?;
@@ -324,6 +336,8 @@ impl ToBytes for Bid {
         self.vesting_schedule.write_bytes(&mut result)?;
         self.delegators().write_bytes(&mut result)?;
         self.inactive.write_bytes(&mut result)?;
+        self.delegator_unclaimed_rewards()
+            .write_bytes(&mut result)?;
         Ok(result)
     }
 
@@ -335,6 +349,7 @@ impl ToBytes for Bid {
             + self.vesting_schedule.serialized_length()
             + self.delegators.serialized_length()
             + self.inactive.serialized_length()
+            + self.delegator_unclaimed_rewards().serialized_length()
     }
 
     fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
@@ -345,6 +360,7 @@ impl ToBytes for Bid {
         self.vesting_schedule.write_bytes(writer)?;
         self.delegators().write_bytes(writer)?;
         self.inactive.write_bytes(writer)?;
+        self.delegator_unclaimed_rewards().write_bytes(writer)?;
         Ok(())
     }
 }
@@ -356,8 +372,21 @@ impl FromBytes for Bid {
         let (staked_amount, bytes) = FromBytes::from_bytes(bytes)?;
         let (delegation_rate, bytes) = FromBytes::from_bytes(bytes)?;
         let (vesting_schedule, bytes) = FromBytes::from_bytes(bytes)?;
-        let (delegators, bytes) = FromBytes::from_bytes(bytes)?;
+        let (mut delegators, bytes): (BTreeMap<PublicKey, Delegator>, _) =
+            FromBytes::from_bytes(bytes)?;
         let (inactive, bytes) = FromBytes::from_bytes(bytes)?;
+        // Bids stored before delegator rewards were introduced end here.
+        let (delegator_unclaimed_rewards, bytes) = if bytes.is_empty() {
+            (BTreeMap::new(), bytes)
+        } else {
+            BTreeMap::<PublicKey, U512>::from_bytes(bytes)?
+        };
+        for (public_key, unclaimed_rewards) in delegator_unclaimed_rewards {
+            delegators
+                .get_mut(&public_key)
+                .ok_or(bytesrepr::Error::Formatting)?
+                .set_unclaimed_rewards(unclaimed_rewards);
+        }
         Ok((
             Bid {
                 validator_public_key,
@@ -375,10 +404,10 @@ impl FromBytes for Bid {
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeMap;
+    use alloc::{collections::BTreeMap, vec::Vec};
 
     use crate::{
-        bytesrepr,
+        bytesrepr::{self, ToBytes},
         system::auction::{bid::VestingSchedule, Bid, DelegationRate, Delegator},
         AccessRights, PublicKey, SecretKey, URef, U512,
     };
@@ -402,6 +431,84 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&founding_validator);
     }
 
+    #[test]
+    fn should_roundtrip_delegator_unclaimed_rewards() {
+        let validator_public_key = PublicKey::from(
+            &SecretKey::ed25519_from_bytes([0u8; SecretKey::ED25519_LENGTH]).unwrap(),
+        );
+        let mut bid = Bid::unlocked(
+            validator_public_key.clone(),
+            URef::new([42; 32], AccessRights::READ_ADD_WRITE),
+            U512::one(),
+            DelegationRate::max_value(),
+        );
+        for (seed, unclaimed_rewards) in [(1u8, 0u64), (2, 7)] {
+            let delegator_public_key = PublicKey::from(
+                &SecretKey::ed25519_from_bytes([seed; SecretKey::ED25519_LENGTH]).unwrap(),
+            );
+            let mut delegator = Delegator::unlocked(
+                delegator_public_key.clone(),
+                U512::from(10),
+                URef::new([seed; 32], AccessRights::READ_ADD_WRITE),
+                validator_public_key.clone(),
+            );
+            delegator
+                .add_unclaimed_rewards(U512::from(unclaimed_rewards))
+                .unwrap();
+            bid.delegators_mut().insert(delegator_public_key, delegator);
+        }
+        bytesrepr::test_serialization_roundtrip(&bid);
+    }
+
+    #[test]
+    fn should_deserialize_bid_without_delegator_unclaimed_rewards() {
+        let validator_public_key = PublicKey::from(
+            &SecretKey::ed25519_from_bytes([0u8; SecretKey::ED25519_LENGTH]).unwrap(),
+        );
+        let delegator_public_key = PublicKey::from(
+            &SecretKey::ed25519_from_bytes([1u8; SecretKey::ED25519_LENGTH]).unwrap(),
+        );
+        let bonding_purse = URef::new([42; 32], AccessRights::READ_ADD_WRITE);
+        let delegator_bonding_purse = URef::new([43; 32], AccessRights::READ_ADD_WRITE);
+        let delegation_rate = DelegationRate::max_value();
+
+        // A bid as serialized before delegator rewards were introduced.
+        let mut delegator_bytes = Vec::new();
+        delegator_bytes.extend(delegator_public_key.to_bytes().unwrap());
+        delegator_bytes.extend(U512::from(10).to_bytes().unwrap());
+        delegator_bytes.extend(delegator_bonding_purse.to_bytes().unwrap());
+        delegator_bytes.extend(validator_public_key.to_bytes().unwrap());
+        delegator_bytes.extend(Option::<VestingSchedule>::None.to_bytes().unwrap());
+        let mut legacy_bytes = Vec::new();
+        legacy_bytes.extend(validator_public_key.to_bytes().unwrap());
+        legacy_bytes.extend(bonding_purse.to_bytes().unwrap());
+        legacy_bytes.extend(U512::one().to_bytes().unwrap());
+        legacy_bytes.extend(delegation_rate.to_bytes().unwrap());
+        legacy_bytes.extend(Option::<VestingSchedule>::None.to_bytes().unwrap());
+        legacy_bytes.extend(1u32.to_bytes().unwrap());
+        legacy_bytes.extend(delegator_public_key.to_bytes().unwrap());
+        legacy_bytes.extend(delegator_bytes);
+        legacy_bytes.extend(false.to_bytes().unwrap());
+
+        let mut expected = Bid::unlocked(
+            validator_public_key.clone(),
+            bonding_purse,
+            U512::one(),
+            delegation_rate,
+        );
+        expected.delegators_mut().insert(
+            delegator_public_key.clone(),
+            Delegator::unlocked(
+                delegator_public_key,
+                U512::from(10),
+                delegator_bonding_purse,
+                validator_public_key,
+            ),
+        );
+        let bid: Bid = bytesrepr::deserialize(legacy_bytes).unwrap();
+        assert_eq!(bid, expected);
+    }
+
     #[test]
     fn should_immediately_initialize_unlock_amounts() {
         const TIMESTAMP_MILLIS: u64 = 0;
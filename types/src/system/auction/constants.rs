@@ -58,6 +58,8 @@ pub const ARG_UNBONDING_DELAY: &str = "unbonding_delay";
 pub const ARG_ERA_END_TIMESTAMP_MILLIS: &str = "era_end_timestamp_millis";
 /// Named constant for `evicted_validators`;
 pub const ARG_EVICTED_VALIDATORS: &str = "evicted_validators";
+/// Named constant for `successor_public_key`.
+pub const ARG_SUCCESSOR_PUBLIC_KEY: &str = "successor_public_key";
 
 /// Named constant for method `get_era_validators`.
 pub const METHOD_GET_ERA_VALIDATORS: &str = "get_era_validators";
@@ -81,6 +83,10 @@ pub const METHOD_DISTRIBUTE: &str = "distribute";
 pub const METHOD_READ_ERA_ID: &str = "read_era_id";
 /// Named constant for method `activate_bid`.
 pub const METHOD_ACTIVATE_BID: &str = "activate_bid";
+/// Named constant for method `register_successor_key`.
+pub const METHOD_REGISTER_SUCCESSOR_KEY: &str = "register_successor_key";
+/// Named constant for method `claim_delegator_rewards`.
+pub const METHOD_CLAIM_DELEGATOR_REWARDS: &str = "claim_delegator_rewards";
 
 /// Storage for `EraId`.
 pub const ERA_ID_KEY: &str = "era_id";
@@ -96,3 +102,7 @@ pub const AUCTION_DELAY_KEY: &str = "auction_delay";
 pub const LOCKED_FUNDS_PERIOD_KEY: &str = "locked_funds_period";
 /// Unbonding delay expressed in eras.
 pub const UNBONDING_DELAY_KEY: &str = "unbonding_delay";
+/// Storage for the signing keys validators use in the current era, keyed by validator public key.
+pub const SIGNING_KEYS_KEY: &str = "signing_keys";
+/// Storage for successor signing keys which take effect at the next era boundary.
+pub const SUCCESSOR_KEYS_KEY: &str = "successor_keys";
@@ -26,6 +26,7 @@ pub struct Delegator {
     bonding_purse: URef,
     validator_public_key: PublicKey,
     vesting_schedule: Option<VestingSchedule>,
+    unclaimed_rewards: U512,
 }
 
 impl Delegator {
@@ -43,6 +44,7 @@ impl Delegator {
             bonding_purse,
             validator_public_key,
             vesting_schedule,
+            unclaimed_rewards: U512::zero(),
         }
     }
 
@@ -61,6 +63,7 @@ impl Delegator {
             bonding_purse,
             validator_public_key,
             vesting_schedule,
+            unclaimed_rewards: U512::zero(),
         }
     }
 
@@ -136,6 +139,37 @@ impl Delegator {
         Ok(updated_staked_amount)
     }
 
+    /// Returns the rewards accrued to the delegator which have not been claimed yet.
+    ///
+    /// Unclaimed rewards are held in the validator's bonding purse until claimed, and do not
+    /// count towards the delegator's stake.
+    pub fn unclaimed_rewards(&self) -> &U512 {
+        &self.unclaimed_rewards
+    }
+
+    /// Increases the unclaimed rewards of the delegator.
+    pub fn add_unclaimed_rewards(&mut self, amount: U512) -> Result<U512, Error> {
+        let updated_unclaimed_rewards = self
+            .unclaimed_rewards
+            .checked_add(amount)
+            .ok_or(Error::InvalidAmount)?;
+
+        self.unclaimed_rewards = updated_unclaimed_rewards;
+
+        Ok(updated_unclaimed_rewards)
+    }
+
+    /// Resets the unclaimed rewards of the delegator to zero and returns the previous amount.
+    pub fn take_unclaimed_rewards(&mut self) -> U512 {
+        core::mem::take(&mut self.unclaimed_rewards)
+    }
+
+    /// Sets the unclaimed rewards of the delegator, as deserialized by the [`Bid`](super::Bid)
+    /// holding it.
+    pub(super) fn set_unclaimed_rewards(&mut self, unclaimed_rewards: U512) {
+        self.unclaimed_rewards = unclaimed_rewards;
+    }
+
     /// Returns a reference to the vesting schedule of the provided
     /// delegator bid.  `None` if a non-genesis validator.
     pub fn vesting_schedule(&self) -> Option<&VestingSchedule> {
@@ -155,6 +189,9 @@ impl CLTyped for Delegator {
     }
 }
 
+/// The unclaimed rewards are not part of the serialized delegator.  They are serialized by the
+/// [`Bid`](super::Bid) holding the delegator instead, so that bids stored before delegator rewards
+/// were introduced remain readable.
 impl ToBytes for Delegator {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut buffer = bytesrepr::allocate_buffer(self)?;
@@ -198,6 +235,7 @@ impl FromBytes for Delegator {
                 bonding_purse,
                 validator_public_key,
                 vesting_schedule,
+                unclaimed_rewards: U512::zero(),
             },
             bytes,
         ))
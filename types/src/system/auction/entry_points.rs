@@ -4,9 +4,10 @@ use crate::{
     system::auction::{
         DelegationRate, ValidatorWeights, ARG_AMOUNT, ARG_DELEGATION_RATE, ARG_DELEGATOR,
         ARG_ERA_END_TIMESTAMP_MILLIS, ARG_NEW_VALIDATOR, ARG_PUBLIC_KEY, ARG_REWARD_FACTORS,
-        ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY, METHOD_ACTIVATE_BID, METHOD_ADD_BID,
-        METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_GET_ERA_VALIDATORS, METHOD_READ_ERA_ID,
-        METHOD_REDELEGATE, METHOD_RUN_AUCTION, METHOD_SLASH, METHOD_UNDELEGATE,
+        ARG_SUCCESSOR_PUBLIC_KEY, ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY, METHOD_ACTIVATE_BID,
+        METHOD_ADD_BID, METHOD_CLAIM_DELEGATOR_REWARDS, METHOD_DELEGATE, METHOD_DISTRIBUTE,
+        METHOD_GET_ERA_VALIDATORS, METHOD_READ_ERA_ID, METHOD_REDELEGATE,
+        METHOD_REGISTER_SUCCESSOR_KEY, METHOD_RUN_AUCTION, METHOD_SLASH, METHOD_UNDELEGATE,
         METHOD_WITHDRAW_BID,
     },
     CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
@@ -142,5 +143,29 @@ pub fn auction_entry_points() -> EntryPoints {
     );
     entry_points.add_entry_point(entry_point);
 
+    let entry_point = EntryPoint::new(
+        METHOD_REGISTER_SUCCESSOR_KEY,
+        vec![
+            Parameter::new(ARG_VALIDATOR_PUBLIC_KEY, CLType::PublicKey),
+            Parameter::new(ARG_SUCCESSOR_PUBLIC_KEY, CLType::PublicKey),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_CLAIM_DELEGATOR_REWARDS,
+        vec![
+            Parameter::new(ARG_DELEGATOR, PublicKey::cl_type()),
+            Parameter::new(ARG_VALIDATOR, PublicKey::cl_type()),
+        ],
+        U512::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
     entry_points
 }
@@ -308,6 +308,12 @@ pub enum Error {
     /// assert_eq!(46, Error::RuntimeStack as u8);
     /// ```
     RuntimeStack = 46,
+    /// The requested signing key is already in use by another validator.
+    /// ```
+    /// # use casper_types::system::auction::Error;
+    /// assert_eq!(47, Error::SigningKeyInUse as u8);
+    /// ```
+    SigningKeyInUse = 47,
 }
 
 impl Display for Error {
@@ -360,6 +366,7 @@ impl Display for Error {
             Error::GlobalDelegatorCapacityReached => formatter.write_str("The global delegator capacity has been reached"),
             Error::DelegationAmountTooSmall => formatter.write_str("The delegated amount is below the minimum allowed"),
             Error::RuntimeStack => formatter.write_str("Runtime stack error"),
+            Error::SigningKeyInUse => formatter.write_str("The signing key is already in use by another validator"),
         }
     }
 }
@@ -438,6 +445,7 @@ impl TryFrom<u8> for Error {
             }
             d if d == Error::DelegationAmountTooSmall as u8 => Ok(Error::DelegationAmountTooSmall),
             d if d == Error::RuntimeStack as u8 => Ok(Error::RuntimeStack),
+            d if d == Error::SigningKeyInUse as u8 => Ok(Error::SigningKeyInUse),
             _ => Err(TryFromU8ForError(())),
         }
     }
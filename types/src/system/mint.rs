@@ -3,6 +3,22 @@ mod constants;
 mod entry_points;
 mod error;
 
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
 pub use constants::*;
 pub use entry_points::mint_entry_points;
 pub use error::Error;
+
+use crate::{account::AccountHash, crypto, Key, URef, U512};
+
+/// The registry of native tokens held in the mint, stored under [`NATIVE_TOKENS_KEY`], mapping the
+/// symbol of each token to its issuer and total supply.
+pub type NativeTokens = BTreeMap<String, (AccountHash, U512)>;
+
+/// Returns the key under which the balance of the native token `token` held by `purse` is stored.
+pub fn native_token_balance_key(token: &str, purse: URef) -> Key {
+    let mut preimage = Vec::with_capacity(token.len() + purse.addr().len());
+    preimage.extend_from_slice(token.as_bytes());
+    preimage.extend_from_slice(&purse.addr());
+    Key::NativeTokenBalance(crypto::blake2b(preimage))
+}
@@ -4,6 +4,8 @@ pub const ARG_PURSE: &str = "purse";
 pub const ARG_AMOUNT: &str = "amount";
 /// Named constant for `id`.
 pub const ARG_ID: &str = "id";
+/// Named constant for `memo`.
+pub const ARG_MEMO: &str = "memo";
 /// Named constant for `to`.
 pub const ARG_TO: &str = "to";
 /// Named constant for `source`.
@@ -12,6 +14,8 @@ pub const ARG_SOURCE: &str = "source";
 pub const ARG_TARGET: &str = "target";
 /// Named constant for `round_seigniorage_rate` used in installer.
 pub const ARG_ROUND_SEIGNIORAGE_RATE: &str = "round_seigniorage_rate";
+/// Named constant for `token`.
+pub const ARG_TOKEN: &str = "token";
 
 /// Named constant for method `mint`.
 pub const METHOD_MINT: &str = "mint";
@@ -27,6 +31,12 @@ pub const METHOD_TRANSFER: &str = "transfer";
 pub const METHOD_READ_BASE_ROUND_REWARD: &str = "read_base_round_reward";
 /// Named constant for method `mint_into_existing_purse`.
 pub const METHOD_MINT_INTO_EXISTING_PURSE: &str = "mint_into_existing_purse";
+/// Named constant for method `mint_token`.
+pub const METHOD_MINT_TOKEN: &str = "mint_token";
+/// Named constant for method `transfer_token`.
+pub const METHOD_TRANSFER_TOKEN: &str = "transfer_token";
+/// Named constant for method `token_balance`.
+pub const METHOD_TOKEN_BALANCE: &str = "token_balance";
 
 /// Storage for mint contract hash.
 pub const HASH_KEY: &str = "mint_hash";
@@ -38,3 +48,5 @@ pub const BASE_ROUND_REWARD_KEY: &str = "mint_base_round_reward";
 pub const TOTAL_SUPPLY_KEY: &str = "total_supply";
 /// Storage for mint round seigniorage rate.
 pub const ROUND_SEIGNIORAGE_RATE_KEY: &str = "round_seigniorage_rate";
+/// Storage for the registry of native tokens.
+pub const NATIVE_TOKENS_KEY: &str = "native_tokens";
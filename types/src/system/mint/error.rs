@@ -148,6 +148,19 @@ pub enum Error {
     /// assert_eq!(21, Error::UnapprovedSpendingAmount as u8);
     UnapprovedSpendingAmount = 21,
 
+    /// The native token is not registered with the mint.
+    /// ```
+    /// # use casper_types::system::mint::Error;
+    /// assert_eq!(22, Error::UnknownToken as u8);
+    /// ```
+    UnknownToken = 22,
+    /// Only the issuer of a native token may mint it.
+    /// ```
+    /// # use casper_types::system::mint::Error;
+    /// assert_eq!(23, Error::InvalidTokenIssuer as u8);
+    /// ```
+    InvalidTokenIssuer = 23,
+
     #[cfg(test)]
     #[doc(hidden)]
     Sentinel,
@@ -200,6 +213,8 @@ impl TryFrom<u8> for Error {
             d if d == Error::GasLimit as u8 => Ok(Error::GasLimit),
             d if d == Error::InvalidContext as u8 => Ok(Error::InvalidContext),
             d if d == Error::UnapprovedSpendingAmount as u8 => Ok(Error::UnapprovedSpendingAmount),
+            d if d == Error::UnknownToken as u8 => Ok(Error::UnknownToken),
+            d if d == Error::InvalidTokenIssuer as u8 => Ok(Error::InvalidTokenIssuer),
             _ => Err(TryFromU8ForError(())),
         }
     }
@@ -257,6 +272,8 @@ impl Display for Error {
             Error::GasLimit => formatter.write_str("GasLimit"),
             Error::InvalidContext => formatter.write_str("Invalid context"),
             Error::UnapprovedSpendingAmount => formatter.write_str("Unapproved spending amount"),
+            Error::UnknownToken => formatter.write_str("Unknown native token"),
+            Error::InvalidTokenIssuer => formatter.write_str("Invalid native token issuer"),
             #[cfg(test)]
             Error::Sentinel => formatter.write_str("Sentinel error"),
         }
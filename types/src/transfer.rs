@@ -20,7 +20,7 @@ use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Seria
 
 use crate::{
     account::AccountHash,
-    bytesrepr::{self, FromBytes, ToBytes},
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
     checksummed_hex, CLType, CLTyped, URef, U512,
 };
 
@@ -29,6 +29,8 @@ pub const DEPLOY_HASH_LENGTH: usize = 32;
 /// The length of a transfer address.
 pub const TRANSFER_ADDR_LENGTH: usize = 32;
 pub(super) const TRANSFER_ADDR_FORMATTED_STRING_PREFIX: &str = "transfer-";
+/// The maximum length in bytes of a transfer memo.
+pub const TRANSFER_MEMO_MAX_LENGTH: usize = 64;
 
 /// A newtype wrapping a <code>[u8; [DEPLOY_HASH_LENGTH]]</code> which is the raw bytes of the
 /// deploy hash.
@@ -125,8 +127,109 @@ impl Distribution<DeployHash> for Standard {
     }
 }
 
+/// Error returned when constructing a [`TransferMemo`] longer than [`TRANSFER_MEMO_MAX_LENGTH`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct TransferMemoTooLong {
+    /// The length in bytes of the rejected memo.
+    pub length: usize,
+}
+
+impl Display for TransferMemoTooLong {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "transfer memo of {} bytes exceeds the maximum of {} bytes",
+            self.length, TRANSFER_MEMO_MAX_LENGTH
+        )
+    }
+}
+
+/// A user-defined memo attached to a transfer, e.g. to let the recipient match it to a deposit.
+///
+/// Unlike the numeric transfer id, a memo holds arbitrary bytes, up to
+/// [`TRANSFER_MEMO_MAX_LENGTH`] of them.
+#[derive(Default, PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Debug, Serialize)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+pub struct TransferMemo(Bytes);
+
+impl TransferMemo {
+    /// Constructs a new `TransferMemo`, or returns an error if `bytes` is longer than
+    /// [`TRANSFER_MEMO_MAX_LENGTH`].
+    pub fn new(bytes: Bytes) -> Result<Self, TransferMemoTooLong> {
+        if bytes.len() > TRANSFER_MEMO_MAX_LENGTH {
+            return Err(TransferMemoTooLong {
+                length: bytes.len(),
+            });
+        }
+        Ok(TransferMemo(bytes))
+    }
+
+    /// Returns the raw bytes of the memo.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl TryFrom<Bytes> for TransferMemo {
+    type Error = TransferMemoTooLong;
+
+    fn try_from(bytes: Bytes) -> Result<Self, Self::Error> {
+        TransferMemo::new(bytes)
+    }
+}
+
+#[cfg(feature = "json-schema")]
+impl JsonSchema for TransferMemo {
+    fn schema_name() -> String {
+        String::from("TransferMemo")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let schema = gen.subschema_for::<String>();
+        let mut schema_object = schema.into_object();
+        schema_object.metadata().description =
+            Some("Hex-encoded transfer memo of at most 64 bytes.".to_string());
+        schema_object.into()
+    }
+}
+
+impl<'de> Deserialize<'de> for TransferMemo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Bytes::deserialize(deserializer)?;
+        TransferMemo::new(bytes).map_err(SerdeError::custom)
+    }
+}
+
+impl CLTyped for TransferMemo {
+    fn cl_type() -> CLType {
+        Bytes::cl_type()
+    }
+}
+
+impl ToBytes for TransferMemo {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.0.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.0.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.0.write_bytes(writer)
+    }
+}
+
+impl FromBytes for TransferMemo {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (bytes, remainder) = Bytes::from_bytes(bytes)?;
+        let memo = TransferMemo::new(bytes).map_err(|_| bytesrepr::Error::Formatting)?;
+        Ok((memo, remainder))
+    }
+}
+
 /// Represents a transfer from one purse to another
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(deny_unknown_fields)]
@@ -147,6 +250,8 @@ pub struct Transfer {
     pub gas: U512,
     /// User-defined id
     pub id: Option<u64>,
+    /// User-defined memo
+    pub memo: Option<TransferMemo>,
 }
 
 impl Transfer {
@@ -161,6 +266,7 @@ impl Transfer {
         amount: U512,
         gas: U512,
         id: Option<u64>,
+        memo: Option<TransferMemo>,
     ) -> Self {
         Transfer {
             deploy_hash,
@@ -171,6 +277,7 @@ impl Transfer {
             amount,
             gas,
             id,
+            memo,
         }
     }
 }
@@ -185,6 +292,12 @@ impl FromBytes for Transfer {
         let (amount, rem) = U512::from_bytes(rem)?;
         let (gas, rem) = U512::from_bytes(rem)?;
         let (id, rem) = <Option<u64>>::from_bytes(rem)?;
+        // Transfers stored before memos were introduced end here.
+        let (memo, rem) = if rem.is_empty() {
+            (None, rem)
+        } else {
+            <Option<TransferMemo>>::from_bytes(rem)?
+        };
         Ok((
             Transfer {
                 deploy_hash,
@@ -195,6 +308,7 @@ impl FromBytes for Transfer {
                 amount,
                 gas,
                 id,
+                memo,
             },
             rem,
         ))
@@ -212,6 +326,7 @@ impl ToBytes for Transfer {
         self.amount.write_bytes(&mut result)?;
         self.gas.write_bytes(&mut result)?;
         self.id.write_bytes(&mut result)?;
+        self.memo.write_bytes(&mut result)?;
         Ok(result)
     }
 
@@ -224,6 +339,7 @@ impl ToBytes for Transfer {
             + self.amount.serialized_length()
             + self.gas.serialized_length()
             + self.id.serialized_length()
+            + self.memo.serialized_length()
     }
 
     fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
@@ -235,6 +351,7 @@ impl ToBytes for Transfer {
         self.amount.write_bytes(writer)?;
         self.gas.write_bytes(writer)?;
         self.id.write_bytes(writer)?;
+        self.memo.write_bytes(writer)?;
         Ok(())
     }
 }
@@ -411,14 +528,24 @@ impl Distribution<TransferAddr> for Standard {
 /// Generators for [`Transfer`]
 #[cfg(any(feature = "testing", test))]
 pub mod gens {
-    use proptest::prelude::{prop::option, Arbitrary, Strategy};
+    use proptest::{
+        collection::vec,
+        prelude::{prop::option, Arbitrary, Strategy},
+    };
 
+    use super::{TransferMemo, TRANSFER_MEMO_MAX_LENGTH};
     use crate::{
         deploy_info::gens::{account_hash_arb, deploy_hash_arb},
         gens::{u512_arb, uref_arb},
         Transfer,
     };
 
+    /// Creates an arbitrary [`TransferMemo`]
+    pub fn transfer_memo_arb() -> impl Strategy<Value = TransferMemo> {
+        vec(<u8>::arbitrary(), 0..=TRANSFER_MEMO_MAX_LENGTH)
+            .prop_map(|bytes| TransferMemo::new(bytes.into()).unwrap())
+    }
+
     /// Creates an arbitrary [`Transfer`]
     pub fn transfer_arb() -> impl Strategy<Value = Transfer> {
         (
@@ -430,9 +557,10 @@ pub mod gens {
             u512_arb(),
             u512_arb(),
             option::of(<u64>::arbitrary()),
+            option::of(transfer_memo_arb()),
         )
-            .prop_map(|(deploy_hash, from, to, source, target, amount, gas, id)| {
-                Transfer {
+            .prop_map(
+                |(deploy_hash, from, to, source, target, amount, gas, id, memo)| Transfer {
                     deploy_hash,
                     from,
                     to,
@@ -441,8 +569,9 @@ pub mod gens {
                     amount,
                     gas,
                     id,
-                }
-            })
+                    memo,
+                },
+            )
     }
 }
 
@@ -450,7 +579,7 @@ pub mod gens {
 mod tests {
     use proptest::prelude::*;
 
-    use crate::bytesrepr;
+    use crate::{bytesrepr, AccessRights, StoredValue};
 
     use super::*;
 
@@ -488,6 +617,71 @@ mod tests {
         assert!(TransferAddr::from_formatted_str(invalid_hex).is_err());
     }
 
+    #[test]
+    fn should_deserialize_transfer_without_memo() {
+        let transfer = Transfer::new(
+            DeployHash::new([1; 32]),
+            AccountHash::new([2; 32]),
+            Some(AccountHash::new([3; 32])),
+            URef::new([4; 32], AccessRights::READ_ADD_WRITE),
+            URef::new([5; 32], AccessRights::READ_ADD_WRITE),
+            U512::from(6),
+            U512::from(7),
+            Some(8),
+            None,
+        );
+
+        // A transfer as serialized before memos were introduced.
+        let mut legacy_bytes = Vec::new();
+        legacy_bytes.extend(transfer.deploy_hash.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.from.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.to.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.source.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.target.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.amount.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.gas.to_bytes().unwrap());
+        legacy_bytes.extend(transfer.id.to_bytes().unwrap());
+
+        assert_eq!(
+            bytesrepr::deserialize::<Transfer>(legacy_bytes.clone()).unwrap(),
+            transfer
+        );
+
+        // The same transfer stored in global state, keeping the stored value's tag byte.
+        let mut legacy_stored_value = StoredValue::Transfer(transfer.clone()).to_bytes().unwrap();
+        legacy_stored_value.truncate(1);
+        legacy_stored_value.extend(legacy_bytes);
+        assert_eq!(
+            bytesrepr::deserialize::<StoredValue>(legacy_stored_value).unwrap(),
+            StoredValue::Transfer(transfer)
+        );
+    }
+
+    #[test]
+    fn transfer_memo_should_enforce_max_length() {
+        let memo = TransferMemo::new(vec![1; TRANSFER_MEMO_MAX_LENGTH].into()).unwrap();
+        let json_string = serde_json::to_string(&memo).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TransferMemo>(&json_string).unwrap(),
+            memo
+        );
+
+        let too_long = Bytes::from(vec![1; TRANSFER_MEMO_MAX_LENGTH + 1]);
+        assert_eq!(
+            TransferMemo::new(too_long.clone()),
+            Err(TransferMemoTooLong {
+                length: TRANSFER_MEMO_MAX_LENGTH + 1
+            })
+        );
+        let serialized = too_long.to_bytes().unwrap();
+        assert_eq!(
+            TransferMemo::from_bytes(&serialized).unwrap_err(),
+            bytesrepr::Error::Formatting
+        );
+        let json_string = serde_json::to_string(&too_long).unwrap();
+        assert!(serde_json::from_str::<TransferMemo>(&json_string).is_err());
+    }
+
     #[test]
     fn transfer_addr_serde_roundtrip() {
         let transfer_address = TransferAddr([255; 32]);
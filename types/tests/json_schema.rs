@@ -0,0 +1,61 @@
+#![cfg(feature = "json-schema")]
+
+use std::{fmt::Debug, fs};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use casper_types::{
+    account::AccountHash,
+    json_schema::{self, JSON_SCHEMA_TITLE},
+    AccessRights, DeployHash, Key, TransferAddr, URef, U512,
+};
+
+fn expected_schema() -> Value {
+    let schema_path = format!(
+        "{}/../resources/test/casper_types_schema.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let expected_schema = fs::read_to_string(&schema_path).unwrap();
+    serde_json::from_str(&expected_schema).unwrap()
+}
+
+#[test]
+fn json_schema_should_match_fixture() {
+    let actual_schema = serde_json::to_value(&json_schema::json_schema()).unwrap();
+    assert_eq!(
+        actual_schema,
+        expected_schema(),
+        "schema does not match, actual:\n{}",
+        serde_json::to_string_pretty(&actual_schema).unwrap()
+    );
+    assert_eq!(actual_schema["title"], JSON_SCHEMA_TITLE);
+}
+
+/// Asserts that `value` is encoded as the JSON type declared by its schema definition, and that
+/// it roundtrips through both the human-readable and the binary serde formats.
+fn assert_serde_duality<T>(definition_name: &str, value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let schema = expected_schema();
+    let definition = &schema["definitions"][definition_name];
+    assert_eq!(definition["type"], "string", "{}", definition_name);
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert!(json.is_string(), "{} encoded as {}", definition_name, json);
+    assert_eq!(serde_json::from_value::<T>(json).unwrap(), value);
+
+    let bytes = bincode::serialize(&value).unwrap();
+    assert_eq!(bincode::deserialize::<T>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn string_encoded_types_should_roundtrip_in_both_serde_formats() {
+    assert_serde_duality("AccountHash", AccountHash::new([1; 32]));
+    assert_serde_duality("DeployHash", DeployHash::new([2; 32]));
+    assert_serde_duality("Key", Key::Hash([3; 32]));
+    assert_serde_duality("TransferAddr", TransferAddr::new([4; 32]));
+    assert_serde_duality("URef", URef::new([5; 32], AccessRights::READ_ADD_WRITE));
+    assert_serde_duality("U512", U512::from(6_000_000_000_u64));
+}
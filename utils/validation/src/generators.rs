@@ -67,6 +67,7 @@ pub fn make_abi_test_fixtures() -> Result<TestFixtures, Error> {
         U512::from(15_000_000_000u64),
         U512::from(2_500_000_000u64),
         Some(1),
+        None,
     );
     let deploy_info = DeployInfo::new(
         DeployHash::new([55; 32]),
@@ -183,7 +184,7 @@ pub fn make_abi_test_fixtures() -> Result<TestFixtures, Error> {
 
         transform.insert(
             "WriteTransfer".to_string(),
-            ABITestCase::from_inputs(vec![Transform::WriteTransfer(transfer).into()])?,
+            ABITestCase::from_inputs(vec![Transform::WriteTransfer(transfer.clone()).into()])?,
         );
 
         transform.insert(